@@ -325,6 +325,7 @@ async fn build_local_code_index(
         providers: app_config.get_llm_provider_config(),
         store_path: None,
         hook_registry: None,
+        offline: app_config.is_offline(),
     })
     .await
     .map_err(|e| format!("Failed to create agent client: {}", e))?;
@@ -696,6 +697,7 @@ async fn execute_code_index_update(
                 providers: app_config.get_llm_provider_config(),
                 store_path: None,
                 hook_registry: None,
+                offline: app_config.is_offline(),
             })
             .await
             .map_err(|e| format!("Failed to create agent client: {}", e))?;
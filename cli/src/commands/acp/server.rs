@@ -127,6 +127,7 @@ impl StakpakAcpAgent {
                 providers: config.get_llm_provider_config(),
                 store_path: None,
                 hook_registry: None,
+                offline: config.is_offline(),
             })
             .await
             .map_err(|e| format!("Failed to create agent client: {}", e))?;
@@ -1129,6 +1130,7 @@ impl StakpakAcpAgent {
                 profile_name: Some(config.profile_name.clone()),
                 config_path: Some(config.config_path.clone()),
                 model: config.subagent_model(),
+                local_endpoint: config.offline_endpoint().map(str::to_string),
             },
             ..McpInitConfig::default()
         };
@@ -1647,6 +1649,7 @@ impl acp::Agent for StakpakAcpAgent {
                     providers: config.get_llm_provider_config(),
                     hook_registry: None,
                     store_path: None,
+                    offline: config.is_offline(),
                 })
                 .await
                 .map_err(|e| {
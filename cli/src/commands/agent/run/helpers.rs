@@ -1,5 +1,5 @@
 use stakpak_shared::models::integrations::openai::{
-    ChatMessage, FunctionDefinition, MessageContent, Role, Tool, ToolCallResult,
+    ChatMessage, FunctionDefinition, MessageContent, Role, Tool, ToolCall, ToolCallResult,
 };
 use uuid::Uuid;
 
@@ -127,6 +127,7 @@ pub fn user_message(user_input: String) -> ChatMessage {
         tool_calls: None,
         tool_call_id: None,
         usage: None,
+        created_at: Some(chrono::Utc::now().timestamp_millis()),
         ..Default::default()
     }
 }
@@ -151,10 +152,34 @@ pub fn tool_result(tool_call_id: String, result: String) -> ChatMessage {
         tool_calls: None,
         tool_call_id: Some(tool_call_id),
         usage: None,
+        created_at: Some(chrono::Utc::now().timestamp_millis()),
         ..Default::default()
     }
 }
 
+/// Put ahead of the result of a tool call the user edited before approving it
+pub const USER_EDITED_TOOL_CALL_NOTE: &str = "[The user edited this tool call before approving it. \
+The arguments in the tool call are the ones that ran.]";
+
+/// Bring the assistant message's copy of `tool_call` in line with the version
+/// the user approved, which differs when they edited it in the approval bar.
+/// Returns whether the arguments changed.
+pub fn sync_approved_tool_call(messages: &mut [ChatMessage], tool_call: &ToolCall) -> bool {
+    let proposed = messages
+        .iter_mut()
+        .rev()
+        .filter(|message| message.role == Role::Assistant)
+        .flat_map(|message| message.tool_calls.iter_mut().flatten())
+        .find(|call| call.id == tool_call.id);
+    match proposed {
+        Some(call) if call.function.arguments != tool_call.function.arguments => {
+            call.function.arguments = tool_call.function.arguments.clone();
+            true
+        }
+        _ => false,
+    }
+}
+
 pub fn tool_call_history_string(tool_calls: &[ToolCallResult]) -> Option<String> {
     if tool_calls.is_empty() {
         return None;
@@ -248,6 +273,36 @@ mod tests {
         assert!(instructions.contains("updated:"));
     }
 
+    #[test]
+    fn test_sync_approved_tool_call_records_user_edits() {
+        let proposed = ToolCall {
+            id: "call_1".to_string(),
+            r#type: "function".to_string(),
+            function: stakpak_shared::models::integrations::openai::FunctionCall {
+                name: "str_replace".to_string(),
+                arguments: r#"{"path":"a.rs","old_str":"x","new_str":"y"}"#.to_string(),
+            },
+            metadata: None,
+        };
+        let mut messages = vec![ChatMessage {
+            role: Role::Assistant,
+            tool_calls: Some(vec![proposed.clone()]),
+            ..Default::default()
+        }];
+
+        assert!(!sync_approved_tool_call(&mut messages, &proposed));
+
+        let mut edited = proposed.clone();
+        edited.function.arguments = r#"{"path":"a.rs","old_str":"x","new_str":"z"}"#.to_string();
+        assert!(sync_approved_tool_call(&mut messages, &edited));
+        assert_eq!(
+            messages[0].tool_calls.as_ref().unwrap()[0]
+                .function
+                .arguments,
+            edited.function.arguments
+        );
+    }
+
     #[test]
     fn test_build_plan_mode_instructions_ends_with_user_request_marker() {
         let instructions = build_plan_mode_instructions();
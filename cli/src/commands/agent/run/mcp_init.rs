@@ -13,6 +13,7 @@ use crate::config::AppConfig;
 use crate::utils::network;
 use stakpak_api::local::skills::default_skill_directories;
 use stakpak_mcp_client::McpClient;
+use stakpak_mcp_config::{McpApprovalRules, RESERVED_SERVER_NAMES};
 use stakpak_mcp_proxy::client::{ClientPoolConfig, ServerConfig};
 use stakpak_mcp_proxy::server::start_proxy_server;
 use stakpak_mcp_server::{
@@ -160,6 +161,9 @@ async fn start_mcp_server(
             subagent_config,
             server_tls_config: None,
             task_manager_handle,
+            extra_tools: Vec::new(),
+            http_transport: Default::default(),
+            auth_token: None,
         };
 
         // Signal that we're about to start
@@ -235,12 +239,14 @@ fn build_proxy_config(
     );
 
     // Load external servers from config file (skip mcp_servers with reserved names)
+    let mut approvals = McpApprovalRules::default();
     if let Ok(config_path) = stakpak_mcp_config::find_config_file() {
         match load_external_servers(&config_path) {
-            Ok(external_servers) => {
+            Ok(external) => {
+                approvals = external.approvals;
                 let mut loaded_servers = 0;
-                for (name, config) in external_servers {
-                    if name == "stakpak" || name == "paks" || name == "aap" {
+                for (name, config) in external.servers {
+                    if RESERVED_SERVER_NAMES.contains(&name.as_str()) {
                         tracing::warn!(
                             "Skipping external MCP server {} (reserved for stakpak's internal use)",
                             name
@@ -262,14 +268,14 @@ fn build_proxy_config(
         }
     }
 
-    ClientPoolConfig::with_servers(servers)
+    ClientPoolConfig::with_servers(servers).with_approvals(approvals)
 }
 
-/// Load external MCP servers from a config file (TOML or JSON).
-fn load_external_servers(config_path: &str) -> Result<HashMap<String, ServerConfig>, String> {
+/// Load external MCP servers and their approval policies from a config file
+/// (TOML or JSON).
+fn load_external_servers(config_path: &str) -> Result<ClientPoolConfig, String> {
     let config = stakpak_mcp_config::load_config(config_path.as_ref())?;
-    let pool_config = ClientPoolConfig::from(config);
-    Ok(pool_config.servers)
+    Ok(ClientPoolConfig::from(config))
 }
 
 /// Start the proxy server
@@ -7,7 +7,9 @@ pub mod pause;
 pub mod profile_switch;
 pub mod renderer;
 pub mod stream;
+pub mod tabs;
 pub mod tooling;
+pub mod transcript;
 pub mod tui;
 
 pub use mode_async::{RunAsyncConfig, run_async};
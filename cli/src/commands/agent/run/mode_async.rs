@@ -7,12 +7,16 @@ use crate::commands::agent::run::mcp_init::{McpInitConfig, initialize_mcp_server
 use crate::commands::agent::run::pause::{
     AsyncOutcome, ResumeInput, build_resume_hint, detect_pending_tool_calls, write_pause_manifest,
 };
-use crate::commands::agent::run::renderer::{OutputFormat, OutputRenderer};
+use crate::commands::agent::run::renderer::{
+    OutputFormat, OutputRenderer, RunEvent, ToolResultStatus, tool_arguments_value,
+};
 use crate::commands::agent::run::tooling::run_tool_call;
 use crate::config::AppConfig;
 use crate::utils::agent_context::AgentContext;
 use stakpak_api::{AgentClient, AgentClientConfig, AgentProvider, Model, SessionStorage};
+use stakpak_mcp_config::{McpApprovalAction, McpApprovalRules};
 use stakpak_mcp_server::EnabledToolsConfig;
+use stakpak_shared::error::StakpakError;
 use stakpak_shared::local_store::LocalStore;
 use stakpak_shared::models::async_manifest::{AsyncManifest, PauseReason, PendingToolCall};
 use stakpak_shared::models::integrations::openai::{ChatMessage, MessageContent, Role};
@@ -49,6 +53,8 @@ pub struct RunAsyncConfig {
     pub plan_new: bool,
     /// When true, respect auto-approve config and pause when tools require approval.
     pub pause_on_approval: bool,
+    /// When true, reject tool calls the auto-approve config doesn't allow and keep going.
+    pub reject_on_approval: bool,
     /// Resume input (tool decisions or text prompt) when resuming from a paused checkpoint.
     pub resume_input: Option<ResumeInput>,
     /// Auto-approve tool overrides from profile config.
@@ -73,6 +79,8 @@ struct AsyncAutoApproveConfig {
     enabled: bool,
     default_policy: AsyncApprovePolicy,
     tools: HashMap<String, AsyncApprovePolicy>,
+    /// Approval policies of external MCP servers, checked before `tools`.
+    mcp_approvals: McpApprovalRules,
 }
 
 impl AsyncAutoApproveConfig {
@@ -157,14 +165,26 @@ impl AsyncAutoApproveConfig {
             enabled: true,
             default_policy: AsyncApprovePolicy::Prompt,
             tools,
+            mcp_approvals: McpApprovalRules::load(),
         }
     }
 
-    fn get_policy(&self, tool_name: &str) -> &AsyncApprovePolicy {
+    fn get_policy(&self, tool_name: &str) -> AsyncApprovePolicy {
+        if let Some(action) = self.mcp_approvals.action_for(tool_name) {
+            return match action {
+                McpApprovalAction::Approve => AsyncApprovePolicy::Auto,
+                McpApprovalAction::Ask => AsyncApprovePolicy::Prompt,
+                McpApprovalAction::Deny => AsyncApprovePolicy::Never,
+            };
+        }
+
         // strip_tool_name handles MCP prefix stripping, "()" removal, and
         // backward compatibility mapping (e.g., read_rulebook → load_skill).
         let canonical = strip_tool_name(tool_name);
-        self.tools.get(canonical).unwrap_or(&self.default_policy)
+        self.tools
+            .get(canonical)
+            .unwrap_or(&self.default_policy)
+            .clone()
     }
 
     fn should_auto_approve(&self, tool_name: &str) -> bool {
@@ -182,7 +202,18 @@ impl AsyncAutoApproveConfig {
     }
 }
 
-pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<AsyncOutcome, String> {
+fn tool_result_status(is_error: Option<bool>) -> ToolResultStatus {
+    if is_error == Some(true) {
+        ToolResultStatus::Error
+    } else {
+        ToolResultStatus::Success
+    }
+}
+
+pub async fn run_async(
+    ctx: AppConfig,
+    mut config: RunAsyncConfig,
+) -> Result<AsyncOutcome, StakpakError> {
     let start_time = Instant::now();
     let mut llm_response_time = std::time::Duration::new(0, 0);
     let mut chat_messages: Vec<ChatMessage> = Vec::new();
@@ -190,8 +221,8 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
     let renderer = OutputRenderer::new(config.output_format.clone(), config.verbose);
     let secret_manager = SecretManager::new(config.redact_secrets, config.privacy_mode);
 
-    // Build auto-approve config if pause_on_approval is enabled
-    let auto_approve = if config.pause_on_approval {
+    // Build auto-approve config if pause_on_approval or reject_on_approval is enabled
+    let auto_approve = if config.pause_on_approval || config.reject_on_approval {
         Some(AsyncAutoApproveConfig::new(
             config.auto_approve_tools.as_ref(),
         ))
@@ -217,10 +248,13 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
             profile_name: Some(ctx.profile_name.clone()),
             config_path: Some(ctx.config_path.clone()),
             model: ctx.subagent_model(),
+            local_endpoint: ctx.offline_endpoint().map(str::to_string),
         },
         ..McpInitConfig::default()
     };
-    let mcp_init_result = initialize_mcp_server_and_tools(&ctx, mcp_init_config, None).await?;
+    let mcp_init_result = initialize_mcp_server_and_tools(&ctx, mcp_init_config, None)
+        .await
+        .map_err(StakpakError::tool_execution)?;
     let mcp_client = mcp_init_result.client;
     let mcp_tools = mcp_init_result.mcp_tools;
     let server_shutdown_tx = mcp_init_result.server_shutdown_tx;
@@ -231,7 +265,9 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
 
     // Build unified AgentClient config
     let providers = ctx.get_llm_provider_config();
-    let mut client_config = AgentClientConfig::new().with_providers(providers);
+    let mut client_config = AgentClientConfig::new()
+        .with_providers(providers)
+        .with_offline(ctx.is_offline());
 
     if let Some(api_key) = ctx.get_stakpak_api_key() {
         client_config = client_config.with_stakpak(
@@ -241,7 +277,7 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
 
     let client = AgentClient::new(client_config)
         .await
-        .map_err(|e| format!("Failed to create client: {}", e))?;
+        .map_err(|e| StakpakError::config(format!("Failed to create client: {}", e)))?;
 
     // Resolve short model names (e.g., "GLM-5") against the provider's model catalog.
     config.model = super::helpers::resolve_model_from_provider(config.model, &client).await;
@@ -255,12 +291,17 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
     if let Some(session_id_str) = config.session_id {
         let checkpoint_start = Instant::now();
         let session_uuid = Uuid::parse_str(&session_id_str)
-            .map_err(|_| format!("Invalid session ID: {}", session_id_str))?;
+            .map_err(|_| StakpakError::config(format!("Invalid session ID: {}", session_id_str)))?;
 
         let checkpoint = client
             .get_active_checkpoint(session_uuid)
             .await
-            .map_err(|e| format!("Failed to get active checkpoint for session: {}", e))?;
+            .map_err(|e| {
+                StakpakError::internal(format!(
+                    "Failed to get active checkpoint for session: {}",
+                    e
+                ))
+            })?;
 
         current_session_id = Some(checkpoint.session_id);
         current_checkpoint_id = Some(checkpoint.id);
@@ -276,8 +317,9 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
         let checkpoint_start = Instant::now();
 
         // Parse checkpoint UUID
-        let checkpoint_uuid = Uuid::parse_str(checkpoint_id_str.as_str())
-            .map_err(|_| format!("Invalid checkpoint ID: {}", checkpoint_id_str))?;
+        let checkpoint_uuid = Uuid::parse_str(checkpoint_id_str.as_str()).map_err(|_| {
+            StakpakError::config(format!("Invalid checkpoint ID: {}", checkpoint_id_str))
+        })?;
 
         // Get checkpoint with session info
         match client.get_checkpoint(checkpoint_uuid).await {
@@ -294,7 +336,10 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                 chat_messages.extend(checkpoint.state.messages);
             }
             Err(e) => {
-                return Err(format!("Failed to get checkpoint: {}", e));
+                return Err(StakpakError::internal(format!(
+                    "Failed to get checkpoint: {}",
+                    e
+                )));
             }
         }
 
@@ -320,6 +365,15 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
             );
 
             for tool_call in &pending_tool_calls {
+                print!(
+                    "{}",
+                    renderer.render_event(&RunEvent::ToolCall {
+                        step: 0,
+                        id: &tool_call.id,
+                        name: &tool_call.function.name,
+                        arguments: tool_arguments_value(&tool_call.function.arguments),
+                    })
+                );
                 if resume_input.is_approved(&tool_call.id) {
                     // Execute approved tool
                     print!(
@@ -358,6 +412,15 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                                 tool_call.function.name
                             );
                             print!("{}", renderer.render_error(&error_msg));
+                            print!(
+                                "{}",
+                                renderer.render_event(&RunEvent::ToolResult {
+                                    id: &tool_call.id,
+                                    name: &tool_call.function.name,
+                                    status: ToolResultStatus::Error,
+                                    output: &error_msg,
+                                })
+                            );
                             chat_messages.push(tool_result(tool_call.id.clone(), error_msg));
                             continue;
                         }
@@ -375,8 +438,26 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                             .join("\n");
 
                         print!("{}", renderer.render_tool_result(&result_content));
+                        print!(
+                            "{}",
+                            renderer.render_event(&RunEvent::ToolResult {
+                                id: &tool_call.id,
+                                name: &tool_call.function.name,
+                                status: tool_result_status(result.is_error),
+                                output: &result_content,
+                            })
+                        );
                         chat_messages.push(tool_result(tool_call.id.clone(), result_content));
                     } else {
+                        print!(
+                            "{}",
+                            renderer.render_event(&RunEvent::ToolResult {
+                                id: &tool_call.id,
+                                name: &tool_call.function.name,
+                                status: ToolResultStatus::Error,
+                                output: "No result",
+                            })
+                        );
                         chat_messages
                             .push(tool_result(tool_call.id.clone(), "No result".to_string()));
                     }
@@ -389,6 +470,15 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                             tool_call.function.name, tool_call.id
                         ))
                     );
+                    print!(
+                        "{}",
+                        renderer.render_event(&RunEvent::ToolResult {
+                            id: &tool_call.id,
+                            name: &tool_call.function.name,
+                            status: ToolResultStatus::Rejected,
+                            output: "TOOL_CALL_REJECTED",
+                        })
+                    );
                     chat_messages.push(tool_result(
                         tool_call.id.clone(),
                         "TOOL_CALL_REJECTED".to_string(),
@@ -466,10 +556,17 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
     // If --plan-feedback, validate the file exists and read it
     if let Some(ref feedback_path) = config.plan_feedback {
         if !std::path::Path::new(feedback_path).exists() {
-            return Err(format!("Plan feedback file not found: {}", feedback_path));
+            return Err(StakpakError::config(format!(
+                "Plan feedback file not found: {}",
+                feedback_path
+            )));
         }
-        let feedback_text = std::fs::read_to_string(feedback_path)
-            .map_err(|e| format!("Failed to read feedback file '{}': {}", feedback_path, e))?;
+        let feedback_text = std::fs::read_to_string(feedback_path).map_err(|e| {
+            StakpakError::config(format!(
+                "Failed to read feedback file '{}': {}",
+                feedback_path, e
+            ))
+        })?;
         let feedback_msg = if !plan_instructions_injected {
             let instructions = build_plan_mode_instructions();
             format!("{instructions}\n\n{feedback_text}")
@@ -483,6 +580,12 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
 
     print!("{}", renderer.render_info("Starting execution..."));
     print!("{}", renderer.render_section_break());
+    print!(
+        "{}",
+        renderer.render_event(&RunEvent::RunStarted {
+            model: &config.model.id,
+        })
+    );
 
     loop {
         step += 1;
@@ -508,7 +611,7 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                 current_metadata.clone(),
             )
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(StakpakError::model)?;
         llm_response_time += llm_start.elapsed();
 
         // Accumulate token usage
@@ -586,6 +689,13 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
             && !content_str.trim().is_empty()
         {
             print!("{}", renderer.render_assistant_message(content_str, false));
+            print!(
+                "{}",
+                renderer.render_event(&RunEvent::AssistantMessage {
+                    step,
+                    content: content_str,
+                })
+            );
         }
 
         // Check if there are tool calls to execute
@@ -600,7 +710,9 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
             }
 
             // Check if pause_on_approval is enabled and any tools require approval
-            if let Some(ref auto_approve_config) = auto_approve {
+            if config.pause_on_approval
+                && let Some(ref auto_approve_config) = auto_approve
+            {
                 let tool_names: Vec<&str> = tool_calls
                     .iter()
                     .map(|tc| tc.function.name.as_str())
@@ -649,6 +761,12 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                     {
                         println!("{}", json);
                     }
+                    print!(
+                        "{}",
+                        renderer.render_event(&RunEvent::Result {
+                            manifest: &manifest,
+                        })
+                    );
 
                     print!(
                         "{}",
@@ -681,6 +799,42 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                         tool_calls.len(),
                     )
                 );
+                print!(
+                    "{}",
+                    renderer.render_event(&RunEvent::ToolCall {
+                        step,
+                        id: &tool_call.id,
+                        name: &tool_call.function.name,
+                        arguments: tool_arguments_value(&tool_call.function.arguments),
+                    })
+                );
+
+                if config.reject_on_approval
+                    && let Some(ref auto_approve_config) = auto_approve
+                    && !auto_approve_config.should_auto_approve(&tool_call.function.name)
+                {
+                    print!(
+                        "{}",
+                        renderer.render_info(&format!(
+                            "Rejected tool call: {} ({}) - requires approval",
+                            tool_call.function.name, tool_call.id
+                        ))
+                    );
+                    print!(
+                        "{}",
+                        renderer.render_event(&RunEvent::ToolResult {
+                            id: &tool_call.id,
+                            name: &tool_call.function.name,
+                            status: ToolResultStatus::Rejected,
+                            output: "TOOL_CALL_REJECTED",
+                        })
+                    );
+                    chat_messages.push(tool_result(
+                        tool_call.id.clone(),
+                        "TOOL_CALL_REJECTED".to_string(),
+                    ));
+                    continue;
+                }
 
                 // Add timeout for tool execution
                 let tool_execution = async {
@@ -709,6 +863,15 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                             tool_call.function.name
                         );
                         print!("{}", renderer.render_error(&error_msg));
+                        print!(
+                            "{}",
+                            renderer.render_event(&RunEvent::ToolResult {
+                                id: &tool_call.id,
+                                name: &tool_call.function.name,
+                                status: ToolResultStatus::Error,
+                                output: &error_msg,
+                            })
+                        );
                         chat_messages.push(tool_result(tool_call.id.clone(), error_msg));
                         continue;
                     }
@@ -727,6 +890,15 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
 
                     // Print tool result
                     print!("{}", renderer.render_tool_result(&result_content));
+                    print!(
+                        "{}",
+                        renderer.render_event(&RunEvent::ToolResult {
+                            id: &tool_call.id,
+                            name: &tool_call.function.name,
+                            status: tool_result_status(result.is_error),
+                            output: &result_content,
+                        })
+                    );
 
                     chat_messages.push(tool_result(tool_call.id.clone(), result_content.clone()));
                 } else {
@@ -737,6 +909,15 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
                             tool_call.function.name
                         ))
                     );
+                    print!(
+                        "{}",
+                        renderer.render_event(&RunEvent::ToolResult {
+                            id: &tool_call.id,
+                            name: &tool_call.function.name,
+                            status: ToolResultStatus::Error,
+                            output: "No result",
+                        })
+                    );
                 }
             }
         } else {
@@ -866,8 +1047,8 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
         )
     );
 
-    // Output JSON completion manifest if in JSON mode
-    if config.output_format == OutputFormat::Json {
+    // Output the completion manifest in JSON and JSONL modes
+    if config.output_format != OutputFormat::Text {
         let manifest = AsyncManifest {
             outcome: "completed".to_string(),
             checkpoint_id: checkpoint_id_str.clone(),
@@ -880,7 +1061,14 @@ pub async fn run_async(ctx: AppConfig, mut config: RunAsyncConfig) -> Result<Asy
             pause_reason: None,
             resume_hint: None,
         };
-        if let Ok(json) = serde_json::to_string_pretty(&manifest) {
+        if config.output_format == OutputFormat::Jsonl {
+            print!(
+                "{}",
+                renderer.render_event(&RunEvent::Result {
+                    manifest: &manifest,
+                })
+            );
+        } else if let Ok(json) = serde_json::to_string_pretty(&manifest) {
             println!("{}", json);
         }
     } else {
@@ -1044,4 +1232,61 @@ mod tests {
             "run_command remains prompt-only after canonicalization"
         );
     }
+
+    #[test]
+    fn jsonl_events_are_single_tagged_lines() {
+        let renderer = OutputRenderer::new(OutputFormat::Jsonl, false);
+        let line = renderer.render_event(&RunEvent::ToolCall {
+            step: 2,
+            id: "call_1",
+            name: "stakpak__run_command",
+            arguments: tool_arguments_value(r#"{"command":"ls"}"#),
+        });
+        assert!(line.ends_with('\n') && line.matches('\n').count() == 1);
+        let event: serde_json::Value = serde_json::from_str(&line).expect("event json");
+        assert_eq!(event["type"], "tool_call");
+        assert_eq!(event["arguments"]["command"], "ls");
+
+        let manifest = AsyncManifest {
+            outcome: "completed".to_string(),
+            checkpoint_id: None,
+            session_id: None,
+            model: "m".to_string(),
+            agent_message: Some("done".to_string()),
+            steps: 3,
+            total_steps: 3,
+            usage: LLMTokenUsage::default(),
+            pause_reason: None,
+            resume_hint: None,
+        };
+        let event: serde_json::Value =
+            serde_json::from_str(&renderer.render_event(&RunEvent::Result {
+                manifest: &manifest,
+            }))
+            .expect("event json");
+        assert_eq!(event["type"], "result");
+        assert_eq!(event["outcome"], "completed");
+        assert_eq!(event["agent_message"], "done");
+
+        let error = StakpakError::auth("bad key");
+        let event: serde_json::Value =
+            serde_json::from_str(&renderer.render_event(&RunEvent::error(&error)))
+                .expect("event json");
+        assert_eq!(event["type"], "error");
+        assert_eq!(event["code"], "auth");
+        assert_eq!(event["message"], "bad key");
+        assert_eq!(event["hint"], error.hint());
+
+        let text = OutputRenderer::new(OutputFormat::Text, true);
+        assert!(text.render_event(&RunEvent::error(&error)).is_empty());
+        assert_eq!(
+            renderer.render_event(&RunEvent::ToolResult {
+                id: "call_1",
+                name: "view",
+                status: tool_result_status(Some(true)),
+                output: "",
+            }),
+            "{\"type\":\"tool_result\",\"id\":\"call_1\",\"name\":\"view\",\"status\":\"error\",\"output\":\"\"}\n"
+        );
+    }
 }
@@ -4,26 +4,30 @@ use crate::commands::agent::run::checkpoint::{
     get_checkpoint_messages, resume_session_from_checkpoint,
 };
 use crate::commands::agent::run::helpers::{
-    build_plan_mode_instructions, build_resume_command, extract_last_checkpoint_id,
-    is_first_non_system_message, refresh_billing_info, tool_call_history_string, tool_result,
-    user_message,
+    USER_EDITED_TOOL_CALL_NOTE, build_plan_mode_instructions, build_resume_command,
+    extract_last_checkpoint_id, is_first_non_system_message, refresh_billing_info,
+    sync_approved_tool_call, tool_call_history_string, tool_result, user_message,
 };
 use crate::commands::agent::run::mcp_init;
 use crate::commands::agent::run::renderer::{OutputFormat, OutputRenderer};
 use crate::commands::agent::run::stream::process_responses_stream;
+use crate::commands::agent::run::tabs::{TabSession, TabSessions};
 use crate::commands::agent::run::tooling::{list_sessions, run_tool_call};
+use crate::commands::agent::run::transcript;
 use crate::commands::agent::run::tui::{send_input_event, send_tool_call};
 use crate::commands::warden;
 use crate::config::AppConfig;
 use crate::utils::agent_context::AgentContext;
 use crate::utils::check_update::get_latest_cli_version;
 use crate::utils::cli_colors::CliColors;
+use crate::utils::discovery::{BackgroundDiscovery, DiscoveryStatus, ProbeRegistry};
 use reqwest::header::HeaderMap;
 use stakpak_api::local::skills::{default_skill_directories, discover_skills};
 use stakpak_api::models::{ApiStreamError, Skill};
 use stakpak_api::{AgentClient, AgentClientConfig, AgentProvider, Model};
 
 use stakpak_mcp_server::EnabledToolsConfig;
+use stakpak_shared::error::StakpakError;
 use stakpak_shared::models::integrations::mcp::CallToolResultExt;
 use stakpak_shared::models::integrations::openai::{
     ChatMessage, MessageContent, Role, ToolCall, ToolCallResultStatus,
@@ -204,6 +208,14 @@ pub struct RunInteractiveConfig {
     pub send_init_prompt_on_start: bool,
     /// Theme override: None = auto-detect, Some(theme) = use specified theme
     pub theme: Option<stakpak_tui::services::detect_term::Theme>,
+    /// `--theme` color scheme, overriding `[settings.theme] scheme`
+    pub color_scheme: Option<String>,
+    /// Autosaved TUI state from `--restore-last`, applied to the first TUI only
+    pub restore_snapshot: Option<stakpak_tui::services::autosave::SessionSnapshot>,
+    /// Discovery probes run in the background at session start
+    pub discovery: ProbeRegistry,
+    /// Reuse cached discovery results younger than this instead of rescanning
+    pub discovery_cache_max_age: Option<std::time::Duration>,
 }
 
 #[allow(unused_assignments)] // plan_mode_active: written in PlanModeActivated, read in later phases
@@ -213,6 +225,16 @@ pub async fn run_interactive(
 ) -> Result<(), String> {
     // Initialize theme detection before starting TUI
     stakpak_tui::services::detect_term::init_theme(config.theme);
+    let mut theme_config = ctx.theme.clone().unwrap_or_default();
+    if let Some(scheme) = config.color_scheme.take() {
+        theme_config.scheme = Some(scheme);
+    }
+    stakpak_tui::services::theme::init(&theme_config)
+        .map_err(|e| format!("Invalid theme settings: {}", e))?;
+    stakpak_tui::services::notifications::init(&ctx.notifications.clone().unwrap_or_default())
+        .map_err(|e| format!("Invalid notification settings: {}", e))?;
+    // `/accessibility` can still flip this for the rest of the session
+    stakpak_tui::services::accessibility::set_enabled(ctx.accessibility.unwrap_or(false));
 
     // Outer loop for profile switching
     'profile_switch_loop: loop {
@@ -224,6 +246,7 @@ pub async fn run_interactive(
         let mut plan_mode_active = false;
         let mut plan_instructions_injected = false;
         let mut should_refresh_skills_on_next_message = false;
+        let mut discovery_injected = false;
         let mut total_session_usage = LLMTokenUsage {
             prompt_tokens: 0,
             completion_tokens: 0,
@@ -289,21 +312,32 @@ pub async fn run_interactive(
         let recent_models_for_tui = ctx.recent_models.clone();
 
         // Use init prompt (loaded at module level as const).
-        // Always run discovery probes so both `stakpak init` and `/init` get pre-calculated analysis results.
-        let init_prompt_content_for_tui = {
-            let discovery_output = crate::utils::discovery::run_all().await;
-            if discovery_output.is_empty() {
-                Some(INIT_PROMPT.to_string())
-            } else {
-                Some(format!(
-                    "{}\n\n<discovery_results>\n{}</discovery_results>",
-                    INIT_PROMPT,
-                    discovery_output.trim()
-                ))
-            }
-        };
+        let init_prompt_content_for_tui = Some(INIT_PROMPT.to_string());
+
+        // Run discovery probes in the background so the TUI starts immediately. The
+        // results are spliced into the first user message sent after they are ready;
+        // `stakpak init` holds its prompt until discovery finishes or is skipped.
+        let input_tx_for_discovery = input_tx.clone();
+        let discovery = BackgroundDiscovery::spawn(
+            config.discovery.clone(),
+            config.discovery_cache_max_age,
+            move |status| match status {
+                // Progress updates may be dropped if the TUI is busy; the finish event may not.
+                DiscoveryStatus::Running { completed, total } => {
+                    let _ = input_tx_for_discovery
+                        .try_send(InputEvent::DiscoveryProgress { completed, total });
+                }
+                DiscoveryStatus::Finished => {
+                    let input_tx = input_tx_for_discovery.clone();
+                    tokio::spawn(async move {
+                        let _ = input_tx.send(InputEvent::DiscoveryFinished).await;
+                    });
+                }
+            },
+        );
 
         let send_init_prompt_on_start = config.send_init_prompt_on_start;
+        let restore_snapshot_for_tui = config.restore_snapshot.take();
 
         let banner_message = if agent_context
             .as_ref()
@@ -342,6 +376,7 @@ pub async fn run_interactive(
                 recent_models_for_tui,
                 banner_message,
                 task_manager_handle_for_tui,
+                restore_snapshot_for_tui,
             )
             .await
             .map_err(|e| e.to_string())
@@ -376,10 +411,13 @@ pub async fn run_interactive(
         let client_handle: tokio::task::JoinHandle<ClientTaskResult> = tokio::spawn(async move {
             let mut current_session_id: Option<Uuid> = None;
             let mut current_metadata: Option<serde_json::Value> = None;
+            let mut tab_sessions = TabSessions::default();
 
             // Build unified AgentClient config
             let providers = ctx_clone.get_llm_provider_config();
-            let mut client_config = AgentClientConfig::new().with_providers(providers);
+            let mut client_config = AgentClientConfig::new()
+                .with_providers(providers)
+                .with_offline(ctx_clone.is_offline());
 
             if let Some(ref key) = api_key_for_client {
                 client_config = client_config.with_stakpak(
@@ -407,6 +445,7 @@ pub async fn run_interactive(
                     profile_name: Some(ctx_clone.profile_name.clone()),
                     config_path: Some(ctx_clone.config_path.clone()),
                     model: ctx_clone.subagent_model(),
+                    local_endpoint: ctx_clone.offline_endpoint().map(str::to_string),
                 },
                 task_manager_handle: Some(task_manager_handle_for_mcp),
             };
@@ -657,6 +696,20 @@ pub async fn run_interactive(
                             user_input
                         };
 
+                        // Splice the startup discovery results into the conversation
+                        // once they are ready.
+                        let user_input = match discovery.output() {
+                            Some(output) if !discovery_injected => {
+                                discovery_injected = true;
+                                format!(
+                                    "{}\n\n<discovery_results>\n{}</discovery_results>",
+                                    user_input,
+                                    output.trim()
+                                )
+                            }
+                            _ => user_input,
+                        };
+
                         let redacted_user_input =
                             secret_manager.redact_and_store_secrets(&user_input, None);
 
@@ -711,7 +764,7 @@ pub async fn run_interactive(
                         // Capture telemetry when not using Stakpak API (local mode)
                         if !has_stakpak_key
                             && let Some(ref anonymous_id) = ctx_clone.anonymous_id
-                            && ctx_clone.collect_telemetry.unwrap_or(true)
+                            && ctx_clone.telemetry_enabled()
                         {
                             capture_event(
                                 anonymous_id,
@@ -795,6 +848,9 @@ pub async fn run_interactive(
                             continue;
                         }
 
+                        // The user may have edited a file change before approving it
+                        let edited_by_user = sync_approved_tool_call(&mut messages, &tool_call);
+
                         send_input_event(
                             &input_tx,
                             InputEvent::StartLoadingOperation(LoadingOperation::ToolExecution),
@@ -867,10 +923,16 @@ pub async fn run_interactive(
                                         content_parts.join("\n")
                                     };
 
-                                    messages.push(tool_result(
-                                        tool_call.clone().id,
-                                        result_content.clone(),
-                                    ));
+                                    let history_content = if edited_by_user {
+                                        format!(
+                                            "{}\n{}",
+                                            USER_EDITED_TOOL_CALL_NOTE, result_content
+                                        )
+                                    } else {
+                                        result_content.clone()
+                                    };
+                                    messages
+                                        .push(tool_result(tool_call.clone().id, history_content));
 
                                     send_input_event(
                                         &input_tx,
@@ -952,6 +1014,7 @@ pub async fn run_interactive(
                         // Clear the current session and start fresh
                         current_session_id = None;
                         messages.clear();
+                        discovery_injected = false;
                         total_session_usage = LLMTokenUsage {
                             prompt_tokens: 0,
                             completion_tokens: 0,
@@ -1317,7 +1380,7 @@ pub async fn run_interactive(
                     }
                     OutputEvent::CommandCalled(command_name) => {
                         if let Some(ref anonymous_id) = ctx_clone.anonymous_id
-                            && ctx_clone.collect_telemetry.unwrap_or(true)
+                            && ctx_clone.telemetry_enabled()
                         {
                             capture_event(
                                 anonymous_id,
@@ -1377,6 +1440,75 @@ pub async fn run_interactive(
                         }
                         continue;
                     }
+                    OutputEvent::CancelDiscovery => {
+                        discovery.cancel();
+                        continue;
+                    }
+                    OutputEvent::ExportTranscript(format) => {
+                        let event = match transcript::export_transcript(
+                            &messages,
+                            format,
+                            current_session_id,
+                            &secret_manager,
+                        ) {
+                            Ok(path) => InputEvent::TranscriptExported(path),
+                            Err(e) => InputEvent::Error(e),
+                        };
+                        send_input_event(&input_tx, event).await?;
+                        continue;
+                    }
+                    OutputEvent::SwitchTab(tab_id) => {
+                        let current = TabSession {
+                            session_id: current_session_id.take(),
+                            metadata: current_metadata.take(),
+                            messages: std::mem::take(&mut messages),
+                            tools_queue: std::mem::take(&mut tools_queue),
+                            plan_mode_active,
+                            plan_instructions_injected,
+                            should_refresh_skills_on_next_message,
+                            discovery_injected,
+                            total_session_usage: std::mem::take(&mut total_session_usage),
+                        };
+                        let next = tab_sessions.switch(tab_id, current);
+                        current_session_id = next.session_id;
+                        current_metadata = next.metadata;
+                        messages = next.messages;
+                        tools_queue = next.tools_queue;
+                        plan_mode_active = next.plan_mode_active;
+                        plan_instructions_injected = next.plan_instructions_injected;
+                        should_refresh_skills_on_next_message =
+                            next.should_refresh_skills_on_next_message;
+                        discovery_injected = next.discovery_injected;
+                        total_session_usage = next.total_session_usage;
+                        continue;
+                    }
+                    OutputEvent::CloseTab(tab_id) => {
+                        tab_sessions.close(tab_id);
+                        continue;
+                    }
+                    OutputEvent::ApplyTemplate(template) => {
+                        let addendum = match template.system_prompt_addendum() {
+                            Ok(addendum) => addendum,
+                            Err(e) => {
+                                send_input_event(&input_tx, InputEvent::Error(e)).await?;
+                                continue;
+                            }
+                        };
+                        if !addendum.is_empty() {
+                            // Keep system messages ahead of the conversation.
+                            let insert_at = messages
+                                .iter()
+                                .take_while(|message| message.role == Role::System)
+                                .count();
+                            messages.insert(insert_at, system_message(addendum));
+                        }
+                        if let Some(template_model) = &template.model {
+                            model = ctx_clone.get_default_model(Some(template_model));
+                            send_input_event(&input_tx, InputEvent::StreamModel(model.clone()))
+                                .await?;
+                        }
+                        continue;
+                    }
                 }
 
                 // Skip sending to API if there are pending tool calls without tool_results
@@ -1428,8 +1560,12 @@ pub async fn run_interactive(
                                 InputEvent::EndLoadingOperation(LoadingOperation::StreamProcessing),
                             )
                             .await?;
-                            send_input_event(&input_tx, InputEvent::Error(error_msg.clone()))
-                                .await?;
+                            // The model request itself failed
+                            send_input_event(
+                                &input_tx,
+                                InputEvent::Failure(StakpakError::model(error_msg.clone())),
+                            )
+                            .await?;
                             break Err(ApiStreamError::Unknown(error_msg));
                         }
                     };
@@ -1495,8 +1631,14 @@ pub async fn run_interactive(
                                     ),
                                 )
                                 .await?;
-                                send_input_event(&input_tx, InputEvent::Error(format!("{:?}", e)))
-                                    .await?;
+                                send_input_event(
+                                    &input_tx,
+                                    InputEvent::Failure(StakpakError::new(
+                                        e.kind(),
+                                        format!("{:?}", e),
+                                    )),
+                                )
+                                .await?;
                                 break Err(e);
                             }
                         }
@@ -1505,7 +1647,11 @@ pub async fn run_interactive(
 
                 match response_result {
                     Ok(response) => {
-                        messages.push(response.choices[0].message.clone());
+                        let mut assistant_message = response.choices[0].message.clone();
+                        assistant_message
+                            .created_at
+                            .get_or_insert_with(|| chrono::Utc::now().timestamp_millis());
+                        messages.push(assistant_message);
 
                         if let Some(session_id) = response
                             .metadata
@@ -1653,7 +1799,9 @@ pub async fn run_interactive(
 
             // Fetch and filter rulebooks for the new profile
             let providers = new_config.get_llm_provider_config();
-            let mut new_client_config = AgentClientConfig::new().with_providers(providers);
+            let mut new_client_config = AgentClientConfig::new()
+                .with_providers(providers)
+                .with_offline(new_config.is_offline());
 
             if let Some(api_key) = new_config.get_stakpak_api_key() {
                 new_client_config = new_client_config.with_stakpak(
@@ -1721,7 +1869,9 @@ pub async fn run_interactive(
         // Normal exit - no profile switch requested
         // Display final stats and session info
         let providers = ctx.get_llm_provider_config();
-        let mut final_client_config = AgentClientConfig::new().with_providers(providers);
+        let mut final_client_config = AgentClientConfig::new()
+            .with_providers(providers)
+            .with_offline(ctx.is_offline());
 
         if let Some(api_key) = ctx.get_stakpak_api_key() {
             final_client_config = final_client_config.with_stakpak(
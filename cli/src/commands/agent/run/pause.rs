@@ -1,3 +1,4 @@
+use stakpak_shared::error::StakpakError;
 use stakpak_shared::local_store::LocalStore;
 use stakpak_shared::models::async_manifest::{AsyncManifest, PauseReason};
 use stakpak_shared::models::integrations::openai::{ChatMessage, Role, ToolCall};
@@ -25,7 +26,7 @@ pub enum AsyncOutcome {
         agent_message: Option<String>,
     },
     /// Agent failed.
-    Failed { error: String },
+    Failed { error: StakpakError },
 }
 
 /// Resume input provided via CLI flags when resuming from a paused checkpoint.
@@ -43,6 +43,7 @@ pub async fn validate_profile_switch(
             providers: new_config.get_llm_provider_config(),
             store_path: None,
             hook_registry: None,
+            offline: new_config.is_offline(),
         })
         .await
         .map_err(|e| format!("Failed to create agent client: {}", e))?;
@@ -1,6 +1,9 @@
 use crossterm::style::Stylize;
+use serde::Serialize;
 use serde_json::Value;
 use stakpak_api::storage::{SessionStats, ToolUsageStats};
+use stakpak_shared::error::StakpakError;
+use stakpak_shared::models::async_manifest::AsyncManifest;
 use stakpak_shared::models::{integrations::openai::ChatMessage, llm::LLMTokenUsage};
 use std::fmt;
 
@@ -9,6 +12,8 @@ use crate::utils::cli_colors::crossterm_colors;
 #[derive(Debug, Clone, PartialEq)]
 pub enum OutputFormat {
     Json,
+    /// One JSON event per line as the run progresses
+    Jsonl,
     Text,
 }
 
@@ -16,6 +21,7 @@ impl fmt::Display for OutputFormat {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Jsonl => write!(f, "jsonl"),
             OutputFormat::Text => write!(f, "text"),
         }
     }
@@ -27,15 +33,77 @@ impl std::str::FromStr for OutputFormat {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "json" => Ok(OutputFormat::Json),
+            "jsonl" => Ok(OutputFormat::Jsonl),
             "text" => Ok(OutputFormat::Text),
             _ => Err(format!(
-                "Invalid output format: {}. Valid values are 'json' or 'text'",
+                "Invalid output format: {}. Valid values are 'json', 'jsonl' or 'text'",
                 s
             )),
         }
     }
 }
 
+/// A step of a run, written as one JSON line in `jsonl` output.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RunEvent<'a> {
+    RunStarted {
+        model: &'a str,
+    },
+    AssistantMessage {
+        step: usize,
+        content: &'a str,
+    },
+    ToolCall {
+        step: usize,
+        id: &'a str,
+        name: &'a str,
+        arguments: Value,
+    },
+    ToolResult {
+        id: &'a str,
+        name: &'a str,
+        status: ToolResultStatus,
+        output: &'a str,
+    },
+    /// The run finished or paused; `outcome` tells which.
+    Result {
+        #[serde(flatten)]
+        manifest: &'a AsyncManifest,
+    },
+    /// A failed run; `code` is the stable [`ErrorKind`] code.
+    ///
+    /// [`ErrorKind`]: stakpak_shared::error::ErrorKind
+    Error {
+        code: &'static str,
+        message: &'a str,
+        hint: &'static str,
+    },
+}
+
+impl<'a> RunEvent<'a> {
+    pub fn error(error: &'a StakpakError) -> Self {
+        RunEvent::Error {
+            code: error.code(),
+            message: &error.message,
+            hint: error.hint(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolResultStatus {
+    Success,
+    Error,
+    Rejected,
+}
+
+/// Tool call arguments as JSON, or the raw string when they don't parse.
+pub fn tool_arguments_value(arguments: &str) -> Value {
+    serde_json::from_str(arguments).unwrap_or_else(|_| Value::String(arguments.to_string()))
+}
+
 pub struct OutputRenderer {
     format: OutputFormat,
     verbose: bool,
@@ -46,6 +114,16 @@ impl OutputRenderer {
         Self { format, verbose }
     }
 
+    /// A JSON line for `event` in `jsonl` output; nothing otherwise.
+    pub fn render_event(&self, event: &RunEvent<'_>) -> String {
+        match self.format {
+            OutputFormat::Jsonl => serde_json::to_string(event)
+                .map(|line| format!("{}\n", line))
+                .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
     // Generic rendering functions
 
     pub fn render_title(&self, title: &str) -> String {
@@ -218,27 +296,28 @@ impl OutputRenderer {
 
     pub fn render_warning(&self, message: &str) -> String {
         match self.format {
-            OutputFormat::Json => String::new(),
+            OutputFormat::Json | OutputFormat::Jsonl => String::new(),
             OutputFormat::Text => format!("[warning] {}\n", message),
         }
     }
 
     pub fn render_error(&self, message: &str) -> String {
         match self.format {
-            OutputFormat::Json => String::new(),
+            OutputFormat::Json | OutputFormat::Jsonl => String::new(),
             OutputFormat::Text => format!("[error] {}\n", message),
         }
     }
 
     pub fn render_stat_line(&self, label: &str, value: &str) -> String {
         match self.format {
-            OutputFormat::Json => String::new(),
+            OutputFormat::Json | OutputFormat::Jsonl => String::new(),
             OutputFormat::Text => self.render_info(&format!("{}: {}", label, value)),
         }
     }
 
     pub fn render_final_completion(&self, messages: &[ChatMessage]) -> String {
         match self.format {
+            OutputFormat::Jsonl => String::new(),
             OutputFormat::Json => {
                 if self.verbose {
                     serde_json::to_string_pretty(messages).unwrap_or_default()
@@ -425,6 +504,7 @@ impl OutputRenderer {
 
     pub fn render_session_stats(&self, stats: &SessionStats) -> String {
         match &self.format {
+            OutputFormat::Jsonl => String::new(),
             OutputFormat::Json => {
                 serde_json::to_string_pretty(stats).unwrap_or_else(|_| "{}".to_string())
             }
@@ -597,6 +677,7 @@ impl OutputRenderer {
         model_name: Option<&str>,
     ) -> String {
         match &self.format {
+            OutputFormat::Jsonl => String::new(),
             OutputFormat::Json => {
                 serde_json::to_string_pretty(usage).unwrap_or_else(|_| "{}".to_string())
             }
@@ -0,0 +1,80 @@
+//! Per-tab session state for the TUI's session tabs.
+//!
+//! The interactive loop works on one session at a time. When the TUI
+//! switches tabs, the loop's per-session state is parked under the id of the
+//! tab being left and the target tab's state (or a fresh session's) takes
+//! its place.
+
+use stakpak_shared::models::integrations::openai::{ChatMessage, ToolCall};
+use stakpak_shared::models::llm::LLMTokenUsage;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// State the interactive loop keeps for one session.
+#[derive(Default)]
+pub struct TabSession {
+    pub session_id: Option<Uuid>,
+    pub metadata: Option<serde_json::Value>,
+    pub messages: Vec<ChatMessage>,
+    pub tools_queue: Vec<ToolCall>,
+    pub plan_mode_active: bool,
+    pub plan_instructions_injected: bool,
+    pub should_refresh_skills_on_next_message: bool,
+    pub discovery_injected: bool,
+    pub total_session_usage: LLMTokenUsage,
+}
+
+/// Sessions of the tabs that are not active.
+#[derive(Default)]
+pub struct TabSessions {
+    /// Id of the tab whose state the loop holds; the first tab is 0
+    active: u64,
+    parked: HashMap<u64, TabSession>,
+}
+
+impl TabSessions {
+    /// Park `current` under the active tab and return the state of tab `id`,
+    /// fresh when the tab is new.
+    pub fn switch(&mut self, id: u64, current: TabSession) -> TabSession {
+        self.parked.insert(self.active, current);
+        self.active = id;
+        self.parked.remove(&id).unwrap_or_default()
+    }
+
+    /// Forget a closed tab.
+    pub fn close(&mut self, id: u64) {
+        self.parked.remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: Uuid) -> TabSession {
+        TabSession {
+            session_id: Some(id),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_switch_parks_and_restores_sessions() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let mut tabs = TabSessions::default();
+
+        // Opening tab 1 gives a fresh session
+        let fresh = tabs.switch(1, session(first));
+        assert!(fresh.session_id.is_none());
+
+        let restored = tabs.switch(0, session(second));
+        assert_eq!(restored.session_id, Some(first));
+
+        let restored = tabs.switch(1, restored);
+        assert_eq!(restored.session_id, Some(second));
+
+        tabs.close(0);
+        assert!(tabs.switch(0, restored).session_id.is_none());
+    }
+}
@@ -0,0 +1,271 @@
+//! Session transcript export for `/export` and Ctrl+S.
+//!
+//! Writes the conversation held by the interactive loop (user and assistant
+//! messages, tool calls and their results) to a markdown or JSON file under
+//! `.stakpak/session/transcripts/`. System prompts are left out. Every text
+//! is passed through the session's secret manager first, so a transcript
+//! never holds more than the session's redaction settings allow.
+
+use chrono::{DateTime, Local, TimeZone};
+use serde::Serialize;
+use stakpak_shared::local_store::LocalStore;
+use stakpak_shared::models::integrations::openai::{ChatMessage, Role};
+use stakpak_shared::secret_manager::SecretManager;
+use stakpak_tui::TranscriptFormat;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+struct Transcript {
+    session_id: Option<String>,
+    exported_at: String,
+    messages: Vec<TranscriptMessage>,
+}
+
+#[derive(Debug, Serialize)]
+struct TranscriptMessage {
+    role: &'static str,
+    /// RFC 3339, absent when the message carries no timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp: Option<String>,
+    content: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<TranscriptToolCall>,
+    /// Tool call this message is the result of
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<String>,
+    /// Name of the tool that produced this result
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TranscriptToolCall {
+    id: String,
+    name: String,
+    arguments: serde_json::Value,
+}
+
+/// Write the transcript and return the path of the file.
+pub fn export_transcript(
+    messages: &[ChatMessage],
+    format: TranscriptFormat,
+    session_id: Option<Uuid>,
+    secret_manager: &SecretManager,
+) -> Result<String, String> {
+    let now = Local::now();
+    let transcript = build_transcript(messages, session_id, now, |text| {
+        secret_manager.redact_and_store_secrets(text, None)
+    });
+    let contents = match format {
+        TranscriptFormat::Markdown => render_markdown(&transcript),
+        TranscriptFormat::Json => serde_json::to_string_pretty(&transcript)
+            .map_err(|e| format!("Failed to serialize transcript: {}", e))?,
+    };
+    let file_name = format!(
+        "transcripts/transcript-{}.{}",
+        now.format("%Y%m%d-%H%M%S"),
+        format.extension()
+    );
+    LocalStore::write_session_data(&file_name, &contents)
+}
+
+fn build_transcript(
+    messages: &[ChatMessage],
+    session_id: Option<Uuid>,
+    now: DateTime<Local>,
+    redact: impl Fn(&str) -> String,
+) -> Transcript {
+    // Tool results only carry the call id; look the tool name up from the call
+    let tool_names: HashMap<&str, &str> = messages
+        .iter()
+        .flat_map(|message| message.tool_calls.iter().flatten())
+        .map(|call| (call.id.as_str(), call.function.name.as_str()))
+        .collect();
+
+    let messages = messages
+        .iter()
+        .filter_map(|message| {
+            let role = match message.role {
+                Role::System | Role::Developer => return None,
+                Role::User => "user",
+                Role::Assistant => "assistant",
+                Role::Tool => "tool",
+            };
+            let content = message
+                .content
+                .as_ref()
+                .map(|content| redact(&content.to_string()))
+                .unwrap_or_default();
+            let tool_calls = message
+                .tool_calls
+                .iter()
+                .flatten()
+                .map(|call| {
+                    let arguments = redact(&call.function.arguments);
+                    TranscriptToolCall {
+                        id: call.id.clone(),
+                        name: call.function.name.clone(),
+                        arguments: serde_json::from_str(&arguments)
+                            .unwrap_or(serde_json::Value::String(arguments)),
+                    }
+                })
+                .collect();
+            Some(TranscriptMessage {
+                role,
+                timestamp: message
+                    .created_at
+                    .and_then(|millis| Local.timestamp_millis_opt(millis).single())
+                    .map(|time| time.to_rfc3339()),
+                content,
+                tool_calls,
+                tool_call_id: message.tool_call_id.clone(),
+                tool_name: message
+                    .tool_call_id
+                    .as_deref()
+                    .and_then(|id| tool_names.get(id))
+                    .map(|name| name.to_string()),
+            })
+        })
+        .collect();
+
+    Transcript {
+        session_id: session_id.map(|id| id.to_string()),
+        exported_at: now.to_rfc3339(),
+        messages,
+    }
+}
+
+fn render_markdown(transcript: &Transcript) -> String {
+    let mut out = String::from("# Session transcript\n\n");
+    if let Some(session_id) = &transcript.session_id {
+        out.push_str(&format!("- Session: `{}`\n", session_id));
+    }
+    out.push_str(&format!("- Exported: {}\n", transcript.exported_at));
+
+    for message in &transcript.messages {
+        let heading = match (message.role, &message.tool_name) {
+            ("tool", Some(name)) => format!("Tool result: {}", name),
+            ("tool", None) => "Tool result".to_string(),
+            ("user", _) => "User".to_string(),
+            _ => "Assistant".to_string(),
+        };
+        out.push_str(&format!("\n## {}", heading));
+        if let Some(timestamp) = &message.timestamp {
+            out.push_str(&format!(" · {}", timestamp));
+        }
+        out.push_str("\n\n");
+
+        if message.role == "tool" {
+            out.push_str(&fenced(&message.content, ""));
+        } else if !message.content.trim().is_empty() {
+            out.push_str(message.content.trim());
+            out.push('\n');
+        }
+
+        for call in &message.tool_calls {
+            out.push_str(&format!(
+                "\n### Tool call: {} (`{}`)\n\n",
+                call.name, call.id
+            ));
+            let arguments = serde_json::to_string_pretty(&call.arguments)
+                .unwrap_or_else(|_| call.arguments.to_string());
+            out.push_str(&fenced(&arguments, "json"));
+        }
+    }
+    out
+}
+
+/// Code block around `text`, with a fence longer than any backtick run inside
+fn fenced(text: &str, language: &str) -> String {
+    let longest_run = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{}{}\n{}\n{}\n", fence, language, text.trim_end(), fence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stakpak_shared::models::integrations::openai::{FunctionCall, MessageContent, ToolCall};
+
+    fn message(role: Role, content: &str) -> ChatMessage {
+        ChatMessage {
+            role,
+            content: Some(MessageContent::String(content.to_string())),
+            ..Default::default()
+        }
+    }
+
+    fn sample_messages() -> Vec<ChatMessage> {
+        let mut assistant = message(Role::Assistant, "Checking the pods.");
+        assistant.tool_calls = Some(vec![ToolCall {
+            id: "call_1".to_string(),
+            r#type: "function".to_string(),
+            function: FunctionCall {
+                name: "run_command".to_string(),
+                arguments: r#"{"command":"kubectl get pods"}"#.to_string(),
+            },
+            metadata: None,
+        }]);
+        let mut result = message(Role::Tool, "api-7f9 Running\n```\nnested fence\n```");
+        result.tool_call_id = Some("call_1".to_string());
+        let mut user = message(Role::User, "Why is the api pod restarting?");
+        user.created_at = Some(1_700_000_000_000);
+
+        vec![
+            message(Role::System, "You are a helpful agent."),
+            user,
+            assistant,
+            result,
+        ]
+    }
+
+    #[test]
+    fn test_transcript_skips_system_and_names_tool_results() {
+        let transcript = build_transcript(&sample_messages(), None, Local::now(), str::to_string);
+        let roles: Vec<&str> = transcript.messages.iter().map(|m| m.role).collect();
+        assert_eq!(roles, vec!["user", "assistant", "tool"]);
+        assert!(transcript.messages[0].timestamp.is_some());
+        assert_eq!(
+            transcript.messages[1].tool_calls[0].arguments["command"],
+            "kubectl get pods"
+        );
+        assert_eq!(
+            transcript.messages[2].tool_name.as_deref(),
+            Some("run_command")
+        );
+    }
+
+    #[test]
+    fn test_transcript_redacts_content_and_arguments() {
+        let transcript = build_transcript(&sample_messages(), None, Local::now(), |text| {
+            text.replace("kubectl", "[REDACTED]")
+        });
+        let json = serde_json::to_string(&transcript).expect("serialize");
+        assert!(!json.contains("kubectl"));
+        assert!(json.contains("[REDACTED] get pods"));
+    }
+
+    #[test]
+    fn test_markdown_fences_tool_output() {
+        let session_id = Uuid::nil();
+        let transcript = build_transcript(
+            &sample_messages(),
+            Some(session_id),
+            Local::now(),
+            str::to_string,
+        );
+        let markdown = render_markdown(&transcript);
+        assert!(markdown.contains(&format!("- Session: `{}`", session_id)));
+        assert!(markdown.contains("## User · "));
+        assert!(markdown.contains("### Tool call: run_command (`call_1`)"));
+        assert!(markdown.contains("## Tool result: run_command"));
+        // Tool output with its own fence gets a longer one around it
+        assert!(markdown.contains("````\napi-7f9 Running\n```\nnested fence\n```\n````"));
+        assert!(!markdown.contains("helpful agent"));
+    }
+}
@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use stakpak_api::AgentProvider;
+use stakpak_shared::context_rules::ContextRulesConfig;
 use stakpak_shared::utils::normalize_optional_string;
 
 use crate::{
@@ -21,6 +22,7 @@ use crate::{
 
 mod presenter;
 mod probes;
+mod schedule_wizard;
 
 use self::probes::{
     AutopilotProbeContext, ProbeMode, RealProbeEnvironment, run_autopilot_probes,
@@ -55,6 +57,10 @@ pub struct StartArgs {
     #[arg(long, default_value_t = false)]
     pub foreground: bool,
 
+    /// Fork into the background without installing an OS service
+    #[arg(long, default_value_t = false, conflicts_with = "foreground")]
+    pub daemon: bool,
+
     /// Do not prompt; require env vars / pre-existing config for setup
     #[arg(long, default_value_t = false)]
     pub non_interactive: bool,
@@ -74,7 +80,7 @@ pub struct StopArgs {
 #[derive(Subcommand, PartialEq, Debug, Clone)]
 pub enum AutopilotCommands {
     /// Start autopilot and install as system service (runs setup on first use)
-    #[command(name = "up")]
+    #[command(name = "up", visible_alias = "start")]
     Up {
         #[command(flatten)]
         args: StartArgs,
@@ -85,7 +91,7 @@ pub enum AutopilotCommands {
     },
 
     /// Stop autopilot and remove system service
-    #[command(name = "down")]
+    #[command(name = "down", visible_alias = "stop")]
     Down {
         #[command(flatten)]
         args: StopArgs,
@@ -117,86 +123,103 @@ pub enum AutopilotCommands {
         component: Option<String>,
     },
 
-    /// Restart autopilot (reload config)
-    Restart,
+    /// Query schedule run history
+    Runs {
+        /// Only show runs of this schedule
+        #[arg(long)]
+        schedule: Option<String>,
 
-    /// Manage scheduled tasks
-    #[command(subcommand)]
-    Schedule(AutopilotScheduleCommands),
+        /// Only show runs with this status (running, completed, failed, skipped, timed_out, paused)
+        #[arg(long)]
+        status: Option<crate::commands::watch::RunStatus>,
 
-    /// Manage messaging channels (Slack, Telegram, Discord)
-    #[command(subcommand)]
-    Channel(AutopilotChannelCommands),
+        /// Only show runs started within a duration (e.g. 2h, 7d) or since an RFC 3339 timestamp / YYYY-MM-DD date
+        #[arg(long, value_parser = crate::commands::watch::commands::history::parse_since)]
+        since: Option<chrono::DateTime<chrono::Utc>>,
 
-    /// Run preflight checks for autopilot setup/runtime
-    Doctor,
-}
+        /// Number of runs to show initially
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: u32,
 
-#[derive(Subcommand, PartialEq, Debug, Clone)]
-pub enum AutopilotScheduleCommands {
-    /// List all schedules
-    List,
+        /// Keep running and print runs as they start and finish
+        #[arg(short = 'f', long)]
+        follow: bool,
 
-    /// Add a schedule
-    Add {
+        /// Emit machine-readable JSON (one object per line with --follow)
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+
+    /// Run a schedule now; with --dry-run, run its check and print the
+    /// assembled prompt and spawn configuration without spawning the agent
+    Run {
         /// Schedule name
         name: String,
 
-        /// Cron expression
-        #[arg(long)]
-        cron: String,
-
-        /// Prompt to run on trigger
+        /// Preview the run without queuing it or spawning the agent
         #[arg(long)]
-        prompt: String,
+        dry_run: bool,
+    },
 
-        /// Check script path
-        #[arg(long)]
-        check: Option<String>,
+    /// Approve or deny the pending tool calls of a paused run
+    Resume {
+        /// Run ID
+        run_id: i64,
 
-        /// When to trigger after check (default: failure)
-        #[arg(long)]
-        trigger_on: Option<ScheduleTriggerOn>,
+        /// Approve the pending tool calls
+        #[arg(long, conflicts_with = "deny", required_unless_present = "deny")]
+        approve: bool,
 
-        // /// Working directory for this schedule
-        // #[arg(long)]
-        // workdir: Option<String>,
-        /// Max agent turns for this schedule
+        /// Deny the pending tool calls; the agent continues without them
         #[arg(long)]
-        max_turns: Option<usize>,
+        deny: bool,
+    },
 
-        /// Deprecated alias for --max-turns
-        #[arg(long, hide = true)]
-        max_steps: Option<usize>,
+    /// Restart autopilot (reload config)
+    Restart,
 
-        /// Notification channel override (slack, telegram, discord)
-        #[arg(long)]
-        notify_channel: Option<String>,
+    /// Generate and install a systemd unit (Linux) or launchd agent (macOS)
+    ///
+    /// The service runs the current binary with the active profile and config.
+    /// `stakpak up` starts an installed service as-is; `stakpak down` removes it.
+    InstallService {
+        /// Pass an environment variable to the service: NAME copies its current
+        /// value, NAME=VALUE sets it explicitly (repeatable)
+        #[arg(long = "env", value_name = "NAME[=VALUE]")]
+        env: Vec<String>,
+
+        /// When the service manager restarts autopilot
+        #[arg(long, value_enum, default_value_t = ServiceRestartPolicy::OnFailure)]
+        restart: ServiceRestartPolicy,
+
+        /// Print the generated service definition instead of installing it
+        #[arg(long, default_value_t = false)]
+        print: bool,
 
-        /// Notification target override (Slack channel/ID, Telegram chat ID, Discord channel ID)
-        #[arg(long)]
-        notify_target: Option<String>,
+        /// Start (or restart) the service after installing it
+        #[arg(long, default_value_t = false)]
+        start: bool,
+    },
 
-        /// Deprecated alias for --notify-target
-        #[arg(long, hide = true)]
-        channel: Option<String>,
+    /// Manage scheduled tasks
+    #[command(subcommand)]
+    Schedule(AutopilotScheduleCommands),
 
-        /// Profile from config.toml used for this schedule's sessions
-        #[arg(long)]
-        profile: Option<String>,
+    /// Manage messaging channels (Slack, Telegram, Discord, WhatsApp, Matrix, Rocket.Chat)
+    #[command(subcommand)]
+    Channel(AutopilotChannelCommands),
 
-        /// Require approval before acting
-        #[arg(long, default_value_t = false)]
-        pause_on_approval: bool,
+    /// Run preflight checks for autopilot setup/runtime
+    Doctor,
+}
 
-        /// Run agent tool calls inside a sandboxed warden container
-        #[arg(long, default_value_t = false)]
-        sandbox: bool,
+#[derive(Subcommand, PartialEq, Debug, Clone)]
+pub enum AutopilotScheduleCommands {
+    /// List all schedules
+    List,
 
-        /// Enable immediately
-        #[arg(long, default_value_t = true)]
-        enabled: bool,
-    },
+    /// Add a schedule
+    Add(Box<ScheduleAddArgs>),
 
     /// Remove a schedule
     Remove { name: String },
@@ -227,10 +250,33 @@ pub enum AutopilotScheduleCommands {
         dry_run: bool,
     },
 
-    /// Show details of a specific run
+    /// Preview a schedule without recording a run: next fire times, one run
+    /// of its check with the trigger decision, and the assembled prompt
+    Test {
+        /// Schedule name
+        name: String,
+
+        /// Number of upcoming fire times to list
+        #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..=100))]
+        count: u32,
+    },
+
+    /// Show details of a specific run with a timeline of its check, agent
+    /// and tool calls
+    ///
+    /// The timeline is built from the run record and, while autopilot is
+    /// running, the agent session's events still held by the server.
+    #[command(visible_alias = "inspect")]
     Show {
         /// Run ID
         id: i64,
+
+        #[command(flatten)]
+        server: crate::commands::gateway::GatewayServerArgs,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Clean up stale runs and optionally prune old history
@@ -241,6 +287,74 @@ pub enum AutopilotScheduleCommands {
     },
 }
 
+/// Flags for `stakpak autopilot schedule add`.
+#[derive(Args, PartialEq, Debug, Clone)]
+pub struct ScheduleAddArgs {
+    /// Schedule name
+    #[arg(required_unless_present = "interactive")]
+    pub name: Option<String>,
+
+    /// Cron expression
+    #[arg(long, required_unless_present = "interactive")]
+    pub cron: Option<String>,
+
+    /// Prompt to run on trigger
+    #[arg(long, required_unless_present = "interactive")]
+    pub prompt: Option<String>,
+
+    /// Check script path
+    #[arg(long)]
+    pub check: Option<String>,
+
+    /// When to trigger after check (default: failure)
+    #[arg(long)]
+    pub trigger_on: Option<ScheduleTriggerOn>,
+
+    // /// Working directory for this schedule
+    // #[arg(long)]
+    // workdir: Option<String>,
+    /// Max agent turns for this schedule
+    #[arg(long)]
+    pub max_turns: Option<usize>,
+
+    /// Deprecated alias for --max-turns
+    #[arg(long, hide = true)]
+    pub max_steps: Option<usize>,
+
+    /// Notification channel override (slack, telegram, discord)
+    #[arg(long)]
+    pub notify_channel: Option<String>,
+
+    /// Notification target override (Slack channel/ID, Telegram chat ID, Discord channel ID)
+    #[arg(long)]
+    pub notify_target: Option<String>,
+
+    /// Deprecated alias for --notify-target
+    #[arg(long, hide = true)]
+    pub channel: Option<String>,
+
+    /// Profile from config.toml used for this schedule's sessions
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Require approval before acting
+    #[arg(long, default_value_t = false)]
+    pub pause_on_approval: bool,
+
+    /// Run agent tool calls inside a sandboxed warden container
+    #[arg(long, default_value_t = false)]
+    pub sandbox: bool,
+
+    /// Enable immediately
+    #[arg(long, default_value_t = true)]
+    pub enabled: bool,
+
+    /// Prompt for any field not given as a flag, previewing the cron
+    /// expression before saving
+    #[arg(long, short = 'i', default_value_t = false)]
+    pub interactive: bool,
+}
+
 #[derive(Subcommand, PartialEq, Debug, Clone)]
 pub enum AutopilotChannelCommands {
     /// List all channels
@@ -248,14 +362,14 @@ pub enum AutopilotChannelCommands {
 
     /// Add a channel
     #[command(
-        after_long_help = "HOW TO GET TOKENS:\n\n  Slack (requires both --bot-token and --app-token):\n\n    RECOMMENDED: Use the app manifest for quick setup:\n    1. Go to https://api.slack.com/apps → Create New App → From an app manifest\n    2. Paste the manifest from: https://github.com/stakpak/agent/blob/main/libs/gateway/src/channels/slack-manifest.yaml\n    3. Basic Information → App-Level Tokens → generate token with connections:write scope (xapp-...)\n    4. Install to Workspace → copy Bot User OAuth Token (xoxb-...)\n\n    Manual setup (if you already have an app):\n    1. Create app at https://api.slack.com/apps\n    2. Enable Socket Mode → generate app-level token (xapp-...) with connections:write scope\n    3. OAuth & Permissions → add Bot Token Scopes:\n       app_mentions:read, channels:history, channels:read, chat:write,\n       groups:history, groups:read, im:history, im:read,\n       mpim:history, mpim:read, reactions:read, reactions:write\n    4. Event Subscriptions → subscribe to bot events:\n       message.channels, message.groups, message.im, app_mention\n    5. Interactivity & Shortcuts → enable\n    6. Install to Workspace → copy Bot User OAuth Token (xoxb-...)\n\n  Telegram:\n    1. Message @BotFather on Telegram\n    2. Send /newbot → choose name and username (must end in 'bot')\n    3. Copy the bot token (format: 123456789:ABCdef...)\n\n  Discord:\n    1. Create app at https://discord.com/developers/applications\n    2. Bot tab → copy the bot token\n    3. OAuth2 → enable bot scope and required permissions\n\n  Optional default notification target:\n    --target sets [notifications].channel/target for watch alerts\n    Example: --target \"#engineering\" (Slack)\n"
+        after_long_help = "HOW TO GET TOKENS:\n\n  Slack (requires both --bot-token and --app-token):\n\n    RECOMMENDED: Use the app manifest for quick setup:\n    1. Go to https://api.slack.com/apps → Create New App → From an app manifest\n    2. Paste the manifest from: https://github.com/stakpak/agent/blob/main/libs/gateway/src/channels/slack-manifest.yaml\n    3. Basic Information → App-Level Tokens → generate token with connections:write scope (xapp-...)\n    4. Install to Workspace → copy Bot User OAuth Token (xoxb-...)\n\n    Manual setup (if you already have an app):\n    1. Create app at https://api.slack.com/apps\n    2. Enable Socket Mode → generate app-level token (xapp-...) with connections:write scope\n    3. OAuth & Permissions → add Bot Token Scopes:\n       app_mentions:read, channels:history, channels:read, chat:write,\n       groups:history, groups:read, im:history, im:read,\n       mpim:history, mpim:read, reactions:read, reactions:write\n    4. Event Subscriptions → subscribe to bot events:\n       message.channels, message.groups, message.im, app_mention\n    5. Interactivity & Shortcuts → enable\n    6. Install to Workspace → copy Bot User OAuth Token (xoxb-...)\n\n  Telegram:\n    1. Message @BotFather on Telegram\n    2. Send /newbot → choose name and username (must end in 'bot')\n    3. Copy the bot token (format: 123456789:ABCdef...)\n\n  Discord:\n    1. Create app at https://discord.com/developers/applications\n    2. Bot tab → copy the bot token\n    3. OAuth2 → enable bot scope and required permissions\n\n  WhatsApp (Cloud API; --token, --phone-number-id, --verify-token and --app-secret):\n    1. Create a Business app at https://developers.facebook.com/apps and add WhatsApp\n    2. WhatsApp → API Setup → copy the phone number ID\n    3. Business settings → System users → generate a token with\n       whatsapp_business_messaging (use it as --token)\n    4. WhatsApp → Configuration → Webhook: callback URL https://<your-host>/whatsapp/webhook,\n       verify token = --verify-token; subscribe to the messages field\n    5. App settings → Basic → App secret (--app-secret); unsigned webhooks are rejected\n\n  Matrix (--homeserver and --token):\n    1. Register a user for the bot on your homeserver\n    2. Element → Settings → Help & About → Access Token (use it as --token),\n       or log in with POST /_matrix/client/v3/login\n    3. Invite the bot to rooms; pass --auto-join to accept invites, --room to limit rooms\n    4. Encrypted rooms are not supported yet; use unencrypted rooms\n\n  Rocket.Chat (--server-url, --user-id and --token):\n    1. Create a user with the bot role for the agent\n    2. Log in as it → My Account → Personal Access Tokens → add a token\n    3. Copy the token (--token) and the user ID shown with it (--user-id)\n    4. Add the bot to the channels it should answer in\n\n  Optional default notification target:\n    --target sets [notifications].channel/target for watch alerts\n    Example: --target \"#engineering\" (Slack)\n"
     )]
     Add {
-        /// Channel type (slack, telegram, discord)
+        /// Channel type (slack, telegram, discord, whatsapp, matrix, rocketchat)
         #[arg(value_enum)]
         channel_type: ChannelType,
 
-        /// Bot token (Telegram bot token, Discord bot token)
+        /// Bot token (Telegram bot token, Discord bot token, WhatsApp or Matrix access token, Rocket.Chat personal access token)
         #[arg(long)]
         token: Option<String>,
 
@@ -267,7 +381,16 @@ pub enum AutopilotChannelCommands {
         #[arg(long)]
         app_token: Option<String>,
 
-        /// Default notification target (Slack channel/ID, Telegram chat ID, Discord channel ID)
+        #[command(flatten)]
+        whatsapp: Box<WhatsAppChannelArgs>,
+
+        #[command(flatten)]
+        matrix: Box<MatrixChannelArgs>,
+
+        #[command(flatten)]
+        rocketchat: Box<RocketChatChannelArgs>,
+
+        /// Default notification target (Slack channel/ID, Telegram chat ID, Discord channel ID, WhatsApp phone number, Matrix or Rocket.Chat room ID)
         #[arg(long)]
         target: Option<String>,
 
@@ -278,7 +401,7 @@ pub enum AutopilotChannelCommands {
 
     /// Remove a channel
     Remove {
-        /// Channel type (slack, telegram, discord)
+        /// Channel type (slack, telegram, discord, whatsapp, matrix, rocketchat)
         #[arg(value_enum)]
         channel_type: ChannelType,
     },
@@ -287,6 +410,50 @@ pub enum AutopilotChannelCommands {
     Test,
 }
 
+/// WhatsApp Cloud API settings for `channel add whatsapp`.
+#[derive(Args, Debug, Clone, Default, PartialEq)]
+pub struct WhatsAppChannelArgs {
+    /// WhatsApp phone number ID
+    #[arg(long)]
+    pub phone_number_id: Option<String>,
+
+    /// WhatsApp webhook verify token
+    #[arg(long)]
+    pub verify_token: Option<String>,
+
+    /// WhatsApp app secret, required to check webhook signatures
+    #[arg(long)]
+    pub app_secret: Option<String>,
+}
+
+/// Matrix settings for `channel add matrix`.
+#[derive(Args, Debug, Clone, Default, PartialEq)]
+pub struct MatrixChannelArgs {
+    /// Matrix homeserver URL, e.g. https://matrix.example.org
+    #[arg(long)]
+    pub homeserver: Option<String>,
+
+    /// Matrix room ID to listen in (repeatable; default: all joined rooms)
+    #[arg(long = "room")]
+    pub rooms: Vec<String>,
+
+    /// Accept Matrix room invites automatically
+    #[arg(long, default_value_t = false)]
+    pub auto_join: bool,
+}
+
+/// Rocket.Chat settings for `channel add rocketchat`.
+#[derive(Args, Debug, Clone, Default, PartialEq)]
+pub struct RocketChatChannelArgs {
+    /// Rocket.Chat server URL, e.g. https://chat.example.org
+    #[arg(long)]
+    pub server_url: Option<String>,
+
+    /// Rocket.Chat user ID of the bot, shown with its personal access token
+    #[arg(long)]
+    pub user_id: Option<String>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ScheduleTriggerOn {
@@ -296,6 +463,28 @@ pub enum ScheduleTriggerOn {
     Any,
 }
 
+/// Restart behavior written into generated service definitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum ServiceRestartPolicy {
+    /// Restart only after a crash or non-zero exit
+    #[default]
+    OnFailure,
+    /// Always restart, including after a clean exit
+    Always,
+    /// Never restart automatically
+    Never,
+}
+
+impl ServiceRestartPolicy {
+    fn systemd_value(&self) -> &'static str {
+        match self {
+            ServiceRestartPolicy::OnFailure => "on-failure",
+            ServiceRestartPolicy::Always => "always",
+            ServiceRestartPolicy::Never => "no",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
 #[serde(rename_all = "snake_case")]
 pub enum ChannelType {
@@ -303,6 +492,8 @@ pub enum ChannelType {
     Telegram,
     Discord,
     Whatsapp,
+    Matrix,
+    Rocketchat,
     Webhook,
 }
 
@@ -319,10 +510,15 @@ impl AutopilotCommands {
                         model: args.model,
                         auto_approve_all: args.auto_approve_all,
                         foreground: args.foreground,
+                        daemon: args.daemon,
                         from_service,
                         non_interactive: args.non_interactive,
                         force: args.force,
                         sandbox_mode: stakpak_server::SandboxMode::default(),
+                        hibernate_idle_minutes: default_hibernate_idle_minutes(),
+                        machine_profile: false,
+                        memory: false,
+                        context_rules: Default::default(),
                     },
                 )
                 .await
@@ -336,7 +532,37 @@ impl AutopilotCommands {
                 lines,
                 component,
             } => logs_autopilot(follow, lines, component).await,
+            AutopilotCommands::Runs {
+                schedule,
+                status,
+                since,
+                limit,
+                follow,
+                json,
+            } => {
+                crate::commands::watch::commands::history::show_runs(
+                    crate::commands::watch::commands::history::RunsOptions {
+                        schedule,
+                        status,
+                        since,
+                        limit,
+                        follow,
+                        json,
+                    },
+                )
+                .await
+            }
+            AutopilotCommands::Run { name, dry_run } => trigger_schedule(&name, dry_run).await,
+            AutopilotCommands::Resume {
+                run_id, approve, ..
+            } => crate::commands::watch::commands::schedule::resume_run(run_id, approve).await,
             AutopilotCommands::Restart => restart_autopilot().await,
+            AutopilotCommands::InstallService {
+                env,
+                restart,
+                print,
+                start,
+            } => install_service_command(&config, &env, restart, print, start),
             AutopilotCommands::Schedule(command) => run_schedule_command(command, &config).await,
             AutopilotCommands::Channel(command) => run_channel_command(command, &config).await,
             AutopilotCommands::Doctor => doctor_autopilot(&config).await,
@@ -352,10 +578,15 @@ struct StartOptions {
     model: Option<String>,
     auto_approve_all: bool,
     foreground: bool,
+    daemon: bool,
     from_service: bool,
     non_interactive: bool,
     force: bool,
     sandbox_mode: stakpak_server::SandboxMode,
+    hibernate_idle_minutes: u64,
+    machine_profile: bool,
+    memory: bool,
+    context_rules: ContextRulesConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -382,15 +613,40 @@ struct AutopilotServerConfig {
     /// at startup and reuses it; "ephemeral" spawns a new container per session.
     #[serde(default)]
     sandbox_mode: stakpak_server::SandboxMode,
+    /// Hibernate sessions after this many idle minutes: their in-memory
+    /// event logs are compressed to disk until the next message. 0 disables.
+    #[serde(default = "default_hibernate_idle_minutes")]
+    hibernate_idle_minutes: u64,
+    /// Add discovery probe results (repos, cloud accounts, ports, ...) to
+    /// the context of new sessions, rescanning every hour.
+    #[serde(default)]
+    machine_profile: bool,
+    /// Let agents save facts about a workspace (hosts, account IDs, past
+    /// incidents) and recall them into later sessions there.
+    #[serde(default)]
+    memory: bool,
+    /// `[server.context]` include/exclude patterns (gitignore syntax) for
+    /// project context, on top of `.stakpakignore` files.
+    #[serde(default, skip_serializing_if = "ContextRulesConfig::is_empty")]
+    context: ContextRulesConfig,
+}
+
+/// How often `machine_profile` reruns the discovery probes.
+const MACHINE_PROFILE_REFRESH: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+fn default_hibernate_idle_minutes() -> u64 {
+    30
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct AutopilotScheduleConfig {
     name: String,
     cron: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
     prompt: String,
     #[serde(default)]
-    check: Option<String>,
+    check: Option<crate::commands::watch::CheckSpec>,
     #[serde(default)]
     trigger_on: Option<ScheduleTriggerOn>,
     // #[serde(default)]
@@ -428,6 +684,10 @@ impl Default for AutopilotServerConfig {
             model: None,
             auto_approve_all: false,
             sandbox_mode: stakpak_server::SandboxMode::default(),
+            hibernate_idle_minutes: default_hibernate_idle_minutes(),
+            machine_profile: false,
+            memory: false,
+            context: Default::default(),
         }
     }
 }
@@ -557,6 +817,8 @@ impl std::fmt::Display for ChannelType {
             ChannelType::Telegram => write!(f, "telegram"),
             ChannelType::Discord => write!(f, "discord"),
             ChannelType::Whatsapp => write!(f, "whatsapp"),
+            ChannelType::Matrix => write!(f, "matrix"),
+            ChannelType::Rocketchat => write!(f, "rocketchat"),
             ChannelType::Webhook => write!(f, "webhook"),
         }
     }
@@ -631,6 +893,10 @@ impl StartOptions {
         self.model = server.model.clone();
         self.auto_approve_all = server.auto_approve_all;
         self.sandbox_mode = server.sandbox_mode.clone();
+        self.hibernate_idle_minutes = server.hibernate_idle_minutes;
+        self.machine_profile = server.machine_profile;
+        self.memory = server.memory;
+        self.context_rules = server.context.clone();
         self
     }
 
@@ -660,6 +926,10 @@ impl AutopilotServerConfig {
             // Only override if the user explicitly passed --auto-approve-all.
             auto_approve_all: options.auto_approve_all || existing.auto_approve_all,
             sandbox_mode: existing.sandbox_mode,
+            hibernate_idle_minutes: existing.hibernate_idle_minutes,
+            machine_profile: existing.machine_profile,
+            memory: existing.memory,
+            context: existing.context,
         }
     }
 }
@@ -714,6 +984,8 @@ struct SchedulerStatusJson {
     running: bool,
     pid: Option<i64>,
     stale_pid: bool,
+    last_heartbeat: Option<String>,
+    heartbeat_age_seconds: Option<i64>,
     db_path: Option<String>,
     error: Option<String>,
     recent_runs: Vec<ScheduleRunSummaryJson>,
@@ -876,10 +1148,27 @@ async fn start_autopilot(config: &mut AppConfig, options: StartOptions) -> Resul
         let discord_token = std::env::var("DISCORD_BOT_TOKEN").ok();
         let slack_bot_token = std::env::var("SLACK_BOT_TOKEN").ok();
         let slack_app_token = std::env::var("SLACK_APP_TOKEN").ok();
+        let whatsapp_access_token = std::env::var("WHATSAPP_ACCESS_TOKEN").ok();
+        let whatsapp_phone_number_id = std::env::var("WHATSAPP_PHONE_NUMBER_ID").ok();
+        let whatsapp_verify_token = std::env::var("WHATSAPP_VERIFY_TOKEN").ok();
+        let whatsapp_app_secret = std::env::var("WHATSAPP_APP_SECRET").ok();
+        let matrix_homeserver = std::env::var("MATRIX_HOMESERVER").ok();
+        let matrix_access_token = std::env::var("MATRIX_ACCESS_TOKEN").ok();
+        let rocketchat_url = std::env::var("ROCKETCHAT_URL").ok();
+        let rocketchat_user_id = std::env::var("ROCKETCHAT_USER_ID").ok();
+        let rocketchat_auth_token = std::env::var("ROCKETCHAT_AUTH_TOKEN").ok();
 
         let has_env_channels = telegram_token.is_some()
             || discord_token.is_some()
-            || (slack_bot_token.is_some() && slack_app_token.is_some());
+            || (slack_bot_token.is_some() && slack_app_token.is_some())
+            || (whatsapp_access_token.is_some()
+                && whatsapp_phone_number_id.is_some()
+                && whatsapp_verify_token.is_some()
+                && whatsapp_app_secret.is_some())
+            || (matrix_homeserver.is_some() && matrix_access_token.is_some())
+            || (rocketchat_url.is_some()
+                && rocketchat_user_id.is_some()
+                && rocketchat_auth_token.is_some());
 
         if has_env_channels {
             let mut gateway_config = stakpak_gateway::GatewayConfig::load(
@@ -895,6 +1184,7 @@ async fn start_autopilot(config: &mut AppConfig, options: StartOptions) -> Resul
                     model: None,
                     auto_approve: None,
                     profile: Some(config.profile_name.clone()),
+                    templates: Default::default(),
                 });
             }
             if let Some(token) = discord_token {
@@ -904,6 +1194,8 @@ async fn start_autopilot(config: &mut AppConfig, options: StartOptions) -> Resul
                     model: None,
                     auto_approve: None,
                     profile: Some(config.profile_name.clone()),
+                    templates: Default::default(),
+                    cancel_reaction: None,
                 });
             }
             if let (Some(bot_token), Some(app_token)) = (slack_bot_token, slack_app_token) {
@@ -913,8 +1205,65 @@ async fn start_autopilot(config: &mut AppConfig, options: StartOptions) -> Resul
                     model: None,
                     auto_approve: None,
                     profile: Some(config.profile_name.clone()),
+                    table_rendering: Default::default(),
+                    block_format: Default::default(),
+                    mentions: Default::default(),
+                    templates: Default::default(),
+                    cancel_reaction: None,
+                });
+            }
+            if let (
+                Some(access_token),
+                Some(phone_number_id),
+                Some(verify_token),
+                Some(app_secret),
+            ) = (
+                whatsapp_access_token,
+                whatsapp_phone_number_id,
+                whatsapp_verify_token,
+                whatsapp_app_secret,
+            ) {
+                gateway_config.channels.whatsapp = Some(stakpak_gateway::config::WhatsAppConfig {
+                    access_token,
+                    phone_number_id,
+                    verify_token,
+                    app_secret,
+                    listen: stakpak_gateway::channels::whatsapp::DEFAULT_WHATSAPP_LISTEN
+                        .to_string(),
+                    template: None,
+                    model: None,
+                    auto_approve: None,
+                    profile: Some(config.profile_name.clone()),
+                    templates: Default::default(),
+                });
+            }
+            if let (Some(homeserver), Some(access_token)) = (matrix_homeserver, matrix_access_token)
+            {
+                gateway_config.channels.matrix = Some(stakpak_gateway::config::MatrixConfig {
+                    homeserver,
+                    access_token,
+                    rooms: Vec::new(),
+                    auto_join: false,
+                    model: None,
+                    auto_approve: None,
+                    profile: Some(config.profile_name.clone()),
+                    templates: Default::default(),
                 });
             }
+            if let (Some(server_url), Some(user_id), Some(auth_token)) =
+                (rocketchat_url, rocketchat_user_id, rocketchat_auth_token)
+            {
+                gateway_config.channels.rocketchat =
+                    Some(stakpak_gateway::config::RocketChatConfig {
+                        server_url,
+                        user_id,
+                        auth_token,
+                        model: None,
+                        auto_approve: None,
+                        profile: Some(config.profile_name.clone()),
+                        templates: Default::default(),
+                    });
+            }
 
             gateway_config
                 .save(autopilot_config_path.as_path())
@@ -927,7 +1276,9 @@ async fn start_autopilot(config: &mut AppConfig, options: StartOptions) -> Resul
             );
         } else if !options.non_interactive {
             println!();
-            println!("Channels let autopilot talk to you on Slack, Telegram, or Discord.");
+            println!(
+                "Channels let autopilot talk to you on Slack, Telegram, Discord, WhatsApp, Matrix, or Rocket.Chat."
+            );
             println!("You can add them now or later with: stakpak autopilot channel add");
             println!();
             println!("  Slack quick setup: use the app manifest at");
@@ -977,8 +1328,8 @@ async fn start_autopilot(config: &mut AppConfig, options: StartOptions) -> Resul
 
     run_startup_preflight(config, &effective_server.listen).await?;
 
-    if !autopilot_service_installed() {
-        install_autopilot_service(config)?;
+    if !effective_options.daemon && !autopilot_service_installed() {
+        install_autopilot_service(config, &ServiceUnitOptions::default())?;
         println!("✓ Installed autopilot service");
     }
 
@@ -991,7 +1342,12 @@ async fn start_autopilot(config: &mut AppConfig, options: StartOptions) -> Resul
 
     let expects_sandbox = effective_server.sandbox_mode == stakpak_server::SandboxMode::Persistent;
 
-    start_autopilot_service()?;
+    let mut daemon_process = if effective_options.daemon {
+        Some(spawn_autopilot_daemon(config)?)
+    } else {
+        start_autopilot_service()?;
+        None
+    };
 
     // Wait for the server (and persistent sandbox if configured) to be ready
     // before printing the status summary. This ensures `stakpak up` only returns
@@ -1013,6 +1369,20 @@ async fn start_autopilot(config: &mut AppConfig, options: StartOptions) -> Resul
         }
         tokio::time::sleep(poll_interval).await;
 
+        // A daemon that dies during startup will never become healthy; report
+        // it right away instead of waiting out the full timeout.
+        if let Some(process) = daemon_process.as_mut()
+            && let Ok(Some(status)) = process.try_wait()
+        {
+            print!("\r\x1b[2K");
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+            return Err(format!(
+                "Autopilot daemon exited during startup ({}). See {}",
+                status,
+                autopilot_log_dir().join("stderr.log").display()
+            ));
+        }
+
         // Determine current phase for display
         let phase = match reqwest::get(&health_url).await {
             Ok(resp) => match resp.json::<serde_json::Value>().await {
@@ -1093,6 +1463,10 @@ async fn start_autopilot(config: &mut AppConfig, options: StartOptions) -> Resul
     println!();
     println!("  Autopilot is running.");
     println!();
+    if let Some(process) = daemon_process.as_ref() {
+        println!("  Daemon      PID {}", process.id());
+        println!("  Logs        {}", autopilot_log_dir().display());
+    }
     println!("  Server      http://{}", effective_server.listen);
     println!(
         "  Tools       {}",
@@ -1130,13 +1504,14 @@ async fn start_foreground_runtime(
     // Each runtime gets its own log file under ~/.stakpak/autopilot/logs/.
     // Guards must be held for the lifetime of the runtime to ensure logs are flushed.
     let log_dir = autopilot_log_dir();
-    std::fs::create_dir_all(&log_dir)
-        .map_err(|e| format!("Failed to create autopilot log directory: {}", e))?;
-
-    // TODO: add log rotation (daily or size-based) via tracing_appender::rolling::daily()
-    let scheduler_appender = tracing_appender::rolling::never(&log_dir, "scheduler.log");
-    let server_appender = tracing_appender::rolling::never(&log_dir, "server.log");
-    let gateway_appender = tracing_appender::rolling::never(&log_dir, "gateway.log");
+    let rotation_policy = autopilot_log_rotation_policy();
+    let open_log = |file_name: &str| {
+        crate::commands::watch::RotatingFileWriter::open(&log_dir, file_name, rotation_policy)
+            .map_err(|e| format!("Failed to open autopilot log {}: {}", file_name, e))
+    };
+    let scheduler_appender = open_log("scheduler.log")?;
+    let server_appender = open_log("server.log")?;
+    let gateway_appender = open_log("gateway.log")?;
 
     let (scheduler_nb, _scheduler_guard) = tracing_appender::non_blocking(scheduler_appender);
     let (server_nb, _server_guard) = tracing_appender::non_blocking(server_appender);
@@ -1207,7 +1582,14 @@ async fn start_foreground_runtime(
     let runtime_client = crate::commands::build_agent_client(config).await?;
     let storage = runtime_client.session_storage().clone();
 
-    let events = Arc::new(stakpak_server::EventLog::new(4096));
+    let hibernate_after = (options.hibernate_idle_minutes > 0)
+        .then(|| std::time::Duration::from_secs(options.hibernate_idle_minutes * 60));
+    let events = stakpak_server::EventLog::new(4096);
+    let events = Arc::new(if hibernate_after.is_some() {
+        events.with_hibernation(Arc::new(stakpak_server::HibernationStore::default_local()))
+    } else {
+        events
+    });
     let idempotency = Arc::new(stakpak_server::IdempotencyStore::new(
         std::time::Duration::from_secs(24 * 60 * 60),
     ));
@@ -1225,6 +1607,10 @@ async fn start_foreground_runtime(
         config.auto_approve.as_ref(),
         options.auto_approve_all,
     );
+    let approval_rules =
+        load_gateway_config_allowing_no_channels(AutopilotConfigFile::path().as_path())
+            .map(|gateway_cfg| gateway_cfg.gateway.approval_rules)
+            .unwrap_or_default();
 
     let requested_model_from_catalog = requested_model.as_deref().and_then(|name| {
         if let Some((provider, id)) = name.split_once('/') {
@@ -1283,6 +1669,7 @@ async fn start_foreground_runtime(
             profile_name: Some(config.profile_name.clone()),
             config_path: Some(config.config_path.clone()),
             model: config.subagent_model(),
+            local_endpoint: config.offline_endpoint().map(str::to_string),
         },
         ..crate::commands::agent::run::mcp_init::McpInitConfig::default()
     };
@@ -1337,17 +1724,40 @@ async fn start_foreground_runtime(
         inference,
         models,
         default_model,
-        resolved_tool_policy.clone(),
+        apply_mcp_approvals_to_server_policy(
+            apply_approval_rules_to_server_policy(resolved_tool_policy.clone(), &approval_rules),
+            &stakpak_mcp_config::McpApprovalRules::load(),
+        ),
     )
     .with_base_system_prompt(Some(DEFAULT_SYSTEM_PROMPT.trim().to_string()))
+    .with_hibernation(hibernate_after)
     .with_project_dir(startup_project_dir)
     .with_skills(startup_remote_skills)
+    .with_context_rules(options.context_rules.clone())
     .with_mcp(
         mcp_init_result.client,
         mcp_tools,
         Some(mcp_init_result.server_shutdown_tx),
         Some(mcp_init_result.proxy_shutdown_tx),
     );
+    let app_state = if options.machine_profile {
+        let registry = crate::utils::discovery::ProbeRegistry::from_config(
+            &config.discovery.clone().unwrap_or_default(),
+        )?;
+        app_state.with_machine_profile(Arc::new(
+            crate::utils::discovery::BackgroundMachineProfile::spawn(
+                registry,
+                MACHINE_PROFILE_REFRESH,
+            ),
+        ))
+    } else {
+        app_state
+    };
+    let app_state = if options.memory {
+        app_state.with_memory(stakpak_server::MemoryStore::default_local())
+    } else {
+        app_state
+    };
 
     // --- 1b. Sandbox configuration (warden + container image) ---
     let warden_path = crate::commands::warden::get_warden_plugin_path().await;
@@ -1413,11 +1823,11 @@ async fn start_foreground_runtime(
     );
 
     let gateway_runtime = if gateway_cfg.has_channels() {
-        match stakpak_gateway::Gateway::new_with_profile_overrides(
-            gateway_cfg,
-            gateway_profile_overrides,
-        )
-        .await
+        match stakpak_gateway::Gateway::builder(gateway_cfg)
+            .profile_overrides(gateway_profile_overrides)
+            .schedule_control(Arc::new(AutopilotScheduleControl::new(config_path.clone())))
+            .build()
+            .await
         {
             Ok(gw) => Some(Arc::new(gw)),
             Err(e) => {
@@ -1462,6 +1872,8 @@ async fn start_foreground_runtime(
         }
     });
 
+    let hibernation_task = app_state.spawn_hibernation_sweeper();
+
     let shutdown_state = app_state.clone();
     let shutdown_refresh_tx = refresh_shutdown_tx.clone();
     let server_model_id = app_state
@@ -1531,6 +1943,18 @@ async fn start_foreground_runtime(
         "  Tools       {}",
         describe_tool_policy(&resolved_tool_policy)
     );
+    if options.hibernate_idle_minutes > 0 {
+        println!(
+            "  Hibernate   idle sessions after {}m",
+            options.hibernate_idle_minutes
+        );
+    }
+    if options.machine_profile {
+        println!("  Machine     profile in session context, rescanned hourly");
+    }
+    if options.memory {
+        println!("  Memory      workspace memories in ~/.stakpak/server/memory");
+    }
 
     // --- Shutdown handler ---
     let shutdown = async move {
@@ -1591,6 +2015,10 @@ async fn start_foreground_runtime(
     }
     let _ = refresh_task.await;
 
+    if let Some(task) = hibernation_task {
+        task.abort();
+    }
+
     // Abort the schedule task
     schedule_task.abort();
     let _ = schedule_task.await;
@@ -1693,6 +2121,7 @@ impl stakpak_gateway::dispatcher::RunOverrideResolver for ProfileRunOverrideReso
                 .map(stakpak_gateway::client::AutoApproveOverride::AllowList),
             system_prompt: resolved.system_prompt,
             max_turns: resolved.max_turns,
+            env: None,
         };
 
         if overrides.is_empty() {
@@ -1703,6 +2132,77 @@ impl stakpak_gateway::dispatcher::RunOverrideResolver for ProfileRunOverrideReso
     }
 }
 
+/// Carries out `/autopilot` chat commands against `autopilot.toml` and the
+/// schedule database, like `stakpak autopilot schedule` does.
+#[derive(Debug, Clone)]
+struct AutopilotScheduleControl {
+    config_path: PathBuf,
+}
+
+impl AutopilotScheduleControl {
+    fn new(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
+    async fn open_schedule_db() -> Result<crate::commands::watch::ScheduleDb, String> {
+        let config = crate::commands::watch::ScheduleConfig::load_default()
+            .map_err(|e| format!("Failed to load watch config: {}", e))?;
+        crate::commands::watch::ScheduleDb::open(&config.watch)
+            .await
+            .map_err(|e| format!("Failed to open database: {}", e))
+    }
+}
+
+#[async_trait::async_trait]
+impl stakpak_gateway::ScheduleControl for AutopilotScheduleControl {
+    async fn list(&self) -> Result<Vec<stakpak_gateway::ScheduleSummary>, String> {
+        let config = AutopilotConfigFile::load_or_default_async().await?;
+        let db = Self::open_schedule_db().await.ok();
+
+        let mut summaries = Vec::with_capacity(config.schedules.len());
+        for schedule in config.schedules {
+            let last_status = match &db {
+                Some(db) => db
+                    .list_runs(&crate::commands::watch::ListRunsFilter {
+                        schedule_name: Some(schedule.name.clone()),
+                        limit: Some(1),
+                        ..Default::default()
+                    })
+                    .await
+                    .ok()
+                    .and_then(|runs| runs.first().map(|run| run.status.to_string())),
+                None => None,
+            };
+            summaries.push(stakpak_gateway::ScheduleSummary {
+                name: schedule.name,
+                cron: schedule.cron,
+                enabled: schedule.enabled,
+                last_status,
+            });
+        }
+        Ok(summaries)
+    }
+
+    async fn run(&self, name: &str) -> Result<(), String> {
+        let config = AutopilotConfigFile::load_or_default_async().await?;
+        if config.find_schedule(name).is_none() {
+            return Err(format!("Schedule '{}' not found", name));
+        }
+        Self::open_schedule_db()
+            .await?
+            .insert_pending_schedule(name)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to queue schedule: {}", e))
+    }
+
+    async fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), String> {
+        set_schedule_enabled_at_path(self.config_path.as_path(), name, enabled)?;
+        signal_scheduler_reload().await;
+        Ok(())
+    }
+}
+
 fn gateway_channel_profiles_with_default(
     channels: &stakpak_gateway::config::ChannelConfigs,
     default_profile: &str,
@@ -1812,6 +2312,56 @@ fn apply_gateway_policy_from_resolved_tools(
     }
 }
 
+/// Fold `[gateway] approval_rules` into the server policy, so the server
+/// denies what they deny and asks about what they hold for approval instead
+/// of approving it itself. `None` already asks about everything.
+fn apply_approval_rules_to_server_policy(
+    policy: stakpak_server::ToolApprovalPolicy,
+    rules: &stakpak_gateway::ApprovalRules,
+) -> stakpak_server::ToolApprovalPolicy {
+    if rules.is_empty() {
+        return policy;
+    }
+    let overrides = rules.scope_rules(
+        stakpak_server::ToolApprovalAction::Approve,
+        stakpak_server::ToolApprovalAction::Ask,
+        stakpak_server::ToolApprovalAction::Deny,
+    );
+    fold_overrides_into_server_policy(policy, overrides)
+}
+
+/// Fold the approval policies of external MCP servers (`approval` in
+/// `mcp.toml`) into the server policy, the same way as `[gateway]
+/// approval_rules`.
+fn apply_mcp_approvals_to_server_policy(
+    policy: stakpak_server::ToolApprovalPolicy,
+    approvals: &stakpak_mcp_config::McpApprovalRules,
+) -> stakpak_server::ToolApprovalPolicy {
+    if approvals.is_empty() {
+        return policy;
+    }
+    let overrides = approvals.scope_rules(
+        stakpak_server::ToolApprovalAction::Approve,
+        stakpak_server::ToolApprovalAction::Ask,
+        stakpak_server::ToolApprovalAction::Deny,
+    );
+    fold_overrides_into_server_policy(policy, overrides)
+}
+
+fn fold_overrides_into_server_policy(
+    policy: stakpak_server::ToolApprovalPolicy,
+    overrides: HashMap<String, stakpak_server::ToolApprovalAction>,
+) -> stakpak_server::ToolApprovalPolicy {
+    match policy {
+        stakpak_server::ToolApprovalPolicy::All => stakpak_server::ToolApprovalPolicy::Custom {
+            rules: overrides,
+            default: stakpak_server::ToolApprovalAction::Approve,
+        },
+        stakpak_server::ToolApprovalPolicy::None => stakpak_server::ToolApprovalPolicy::None,
+        custom => custom.with_overrides(overrides),
+    }
+}
+
 fn describe_tool_policy(policy: &stakpak_server::ToolApprovalPolicy) -> String {
     match policy {
         stakpak_server::ToolApprovalPolicy::All => "all tools (auto-approve all)".to_string(),
@@ -1997,54 +2547,94 @@ async fn restart_autopilot() -> Result<(), String> {
     Ok(())
 }
 
+/// Fire a schedule once, or preview the run with `dry_run`.
+async fn trigger_schedule(name: &str, dry_run: bool) -> Result<(), String> {
+    // Validate the schedule exists in config
+    let config = AutopilotConfigFile::load_or_default_async().await?;
+    if config.find_schedule(name).is_none() {
+        return Err(format!("Schedule '{}' not found", name));
+    }
+    // Delegate to the watch module's fire_schedule
+    match crate::commands::watch::commands::schedule::fire_schedule(name, dry_run).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.contains("not found") || e.contains("not running") => Err(format!(
+            "Cannot trigger '{}': autopilot is not running. Start it with: stakpak up",
+            name
+        )),
+        Err(e) => Err(e),
+    }
+}
+
 async fn run_schedule_command(
     command: AutopilotScheduleCommands,
     config: &AppConfig,
 ) -> Result<(), String> {
     match command {
         AutopilotScheduleCommands::List => list_schedules().await,
-        AutopilotScheduleCommands::Add {
-            name,
-            cron,
-            prompt,
-            check,
-            trigger_on,
-            // workdir,
-            max_turns,
-            max_steps,
-            notify_channel,
-            notify_target,
-            channel,
-            profile,
-            pause_on_approval,
-            sandbox,
-            enabled,
-        } => {
-            let profile = normalize_optional_string(profile);
-            if let Some(profile_name) = profile.as_deref() {
-                validate_profile_reference(profile_name, config)?;
-            }
-            let notify_target = resolve_schedule_notify_target(notify_target, channel)?;
-            let max_turns = resolve_schedule_max_turns(max_turns, max_steps)?;
-
-            let schedule = AutopilotScheduleConfig {
-                name: name.clone(),
+        AutopilotScheduleCommands::Add(args) => {
+            let ScheduleAddArgs {
+                name,
                 cron,
                 prompt,
                 check,
                 trigger_on,
                 // workdir,
                 max_turns,
-                notify_channel: normalize_optional_string(notify_channel),
+                max_steps,
+                notify_channel,
                 notify_target,
-                legacy_notify_chat_id: None,
-                legacy_channel: None,
+                channel,
                 profile,
                 pause_on_approval,
                 sandbox,
                 enabled,
+                interactive,
+            } = *args;
+            let profile = normalize_optional_string(profile);
+            if let Some(profile_name) = profile.as_deref() {
+                validate_profile_reference(profile_name, config)?;
+            }
+            let notify_target = resolve_schedule_notify_target(notify_target, channel)?;
+            let max_turns = resolve_schedule_max_turns(max_turns, max_steps)?;
+
+            let schedule = AutopilotScheduleConfig {
+                name: name.unwrap_or_default(),
+                cron: cron.unwrap_or_default(),
+                timezone: None,
+                prompt: prompt.unwrap_or_default(),
+                check: check.map(crate::commands::watch::CheckSpec::Script),
+                trigger_on,
+                // workdir,
+                max_turns,
+                notify_channel: normalize_optional_string(notify_channel),
+                notify_target,
+                legacy_notify_chat_id: None,
+                legacy_channel: None,
+                profile,
+                pause_on_approval,
+                sandbox,
+                enabled,
+            };
+            let schedule = if interactive {
+                match schedule_wizard::run_schedule_wizard(
+                    schedule,
+                    AutopilotConfigFile::path().as_path(),
+                )? {
+                    Some(schedule) => schedule,
+                    None => {
+                        println!("Schedule not added");
+                        return Ok(());
+                    }
+                }
+            } else {
+                schedule
             };
-            let check_path = schedule.check.clone();
+            let name = schedule.name.clone();
+            let check_path = schedule
+                .check
+                .as_ref()
+                .and_then(|check| check.script())
+                .map(str::to_string);
             add_schedule_to_path(AutopilotConfigFile::path().as_path(), schedule)?;
 
             let signaled = signal_scheduler_reload().await;
@@ -2081,35 +2671,21 @@ async fn run_schedule_command(
             Ok(())
         }
         AutopilotScheduleCommands::Trigger { name, dry_run } => {
-            // Validate the schedule exists in config
-            let config = AutopilotConfigFile::load_or_default_async().await?;
-            if config.find_schedule(&name).is_none() {
-                return Err(format!("Schedule '{}' not found", name));
-            }
-            // Delegate to the watch module's fire_schedule
-            match crate::commands::watch::commands::schedule::fire_schedule(&name, dry_run).await {
-                Ok(()) => Ok(()),
-                Err(e) if e.contains("not found") || e.contains("not running") => Err(format!(
-                    "Cannot trigger '{}': autopilot is not running. Start it with: stakpak up",
-                    name
-                )),
-                Err(e) => Err(e),
-            }
+            trigger_schedule(&name, dry_run).await
+        }
+        AutopilotScheduleCommands::Test { name, count } => {
+            crate::commands::watch::commands::schedule::test_schedule(&name, count as usize).await
         }
         AutopilotScheduleCommands::History { name, limit } => {
             crate::commands::watch::commands::history::show_history(Some(&name), Some(limit)).await
         }
-        AutopilotScheduleCommands::Show { id } => {
-            crate::commands::watch::commands::history::show_run(id).await
+        AutopilotScheduleCommands::Show { id, server, json } => {
+            crate::commands::watch::commands::history::show_run(id, &server, json).await
         }
         AutopilotScheduleCommands::Clean { older_than_days } => {
             let config = crate::commands::watch::ScheduleConfig::load_default()
                 .map_err(|e| format!("Failed to load watch config: {}", e))?;
-            let db_path = config.db_path();
-            let db_path_str = db_path
-                .to_str()
-                .ok_or_else(|| "Invalid database path".to_string())?;
-            let db = crate::commands::watch::ScheduleDb::new(db_path_str)
+            let db = crate::commands::watch::ScheduleDb::open(&config.watch)
                 .await
                 .map_err(|e| format!("Failed to open database: {}", e))?;
 
@@ -2199,12 +2775,16 @@ fn validate_profile_reference(profile_name: &str, config: &AppConfig) -> Result<
     ))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn add_channel_with_optional_target(
     config_path: &Path,
     channel_type: ChannelType,
     token: Option<String>,
     bot_token: Option<String>,
     app_token: Option<String>,
+    whatsapp: WhatsAppChannelArgs,
+    matrix: MatrixChannelArgs,
+    rocketchat: RocketChatChannelArgs,
     target: Option<String>,
     profile: Option<String>,
 ) -> Result<Option<String>, String> {
@@ -2292,6 +2872,151 @@ fn add_channel_with_optional_target(
                 }
                 channels.insert("slack".to_string(), toml::Value::Table(slack));
             }
+            ChannelType::Whatsapp => {
+                let raw_token = token.or_else(|| std::env::var("WHATSAPP_ACCESS_TOKEN").ok()).ok_or(
+                    "WhatsApp access token required. Use --token or set WHATSAPP_ACCESS_TOKEN\n\n  To get a token: Meta Business settings → System users → generate a token with whatsapp_business_messaging",
+                )?;
+                let raw_phone_number_id = whatsapp
+                    .phone_number_id
+                    .or_else(|| std::env::var("WHATSAPP_PHONE_NUMBER_ID").ok())
+                    .ok_or(
+                        "WhatsApp phone number ID required. Use --phone-number-id or set WHATSAPP_PHONE_NUMBER_ID\n\n  Find it at https://developers.facebook.com/apps → your app → WhatsApp → API Setup",
+                    )?;
+                let raw_verify_token = whatsapp
+                    .verify_token
+                    .or_else(|| std::env::var("WHATSAPP_VERIFY_TOKEN").ok())
+                    .ok_or(
+                        "WhatsApp verify token required. Use --verify-token or set WHATSAPP_VERIFY_TOKEN\n\n  Pick any secret string and enter the same value in WhatsApp → Configuration → Webhook",
+                    )?;
+                let raw_app_secret = whatsapp
+                    .app_secret
+                    .or_else(|| std::env::var("WHATSAPP_APP_SECRET").ok())
+                    .ok_or(
+                        "WhatsApp app secret required. Use --app-secret or set WHATSAPP_APP_SECRET\n\n  Find it at https://developers.facebook.com/apps → your app → App settings → Basic → App secret",
+                    )?;
+                let tok = require_non_empty_token(
+                    raw_token,
+                    "WhatsApp access token cannot be empty. Use --token or set WHATSAPP_ACCESS_TOKEN",
+                )?;
+                let phone_number_id = require_non_empty_token(
+                    raw_phone_number_id,
+                    "WhatsApp phone number ID cannot be empty. Use --phone-number-id or set WHATSAPP_PHONE_NUMBER_ID",
+                )?;
+                let verify_token = require_non_empty_token(
+                    raw_verify_token,
+                    "WhatsApp verify token cannot be empty. Use --verify-token or set WHATSAPP_VERIFY_TOKEN",
+                )?;
+                let app_secret = require_non_empty_token(
+                    raw_app_secret,
+                    "WhatsApp app secret cannot be empty. Use --app-secret or set WHATSAPP_APP_SECRET",
+                )?;
+
+                let mut whatsapp_table = toml::value::Table::new();
+                whatsapp_table.insert("access_token".to_string(), toml::Value::String(tok));
+                whatsapp_table.insert(
+                    "phone_number_id".to_string(),
+                    toml::Value::String(phone_number_id),
+                );
+                whatsapp_table.insert(
+                    "verify_token".to_string(),
+                    toml::Value::String(verify_token),
+                );
+                whatsapp_table.insert("app_secret".to_string(), toml::Value::String(app_secret));
+                if let Some(profile_name) = normalized_profile.as_ref() {
+                    whatsapp_table.insert(
+                        "profile".to_string(),
+                        toml::Value::String(profile_name.clone()),
+                    );
+                }
+                channels.insert("whatsapp".to_string(), toml::Value::Table(whatsapp_table));
+            }
+            ChannelType::Matrix => {
+                let raw_homeserver = matrix
+                    .homeserver
+                    .or_else(|| std::env::var("MATRIX_HOMESERVER").ok())
+                    .ok_or(
+                        "Matrix homeserver required. Use --homeserver or set MATRIX_HOMESERVER\n\n  Example: --homeserver https://matrix.example.org",
+                    )?;
+                let raw_token = token.or_else(|| std::env::var("MATRIX_ACCESS_TOKEN").ok()).ok_or(
+                    "Matrix access token required. Use --token or set MATRIX_ACCESS_TOKEN\n\n  To get a token: log in as the bot in Element → Settings → Help & About → Access Token",
+                )?;
+                let homeserver = require_non_empty_token(
+                    raw_homeserver,
+                    "Matrix homeserver cannot be empty. Use --homeserver or set MATRIX_HOMESERVER",
+                )?;
+                let tok = require_non_empty_token(
+                    raw_token,
+                    "Matrix access token cannot be empty. Use --token or set MATRIX_ACCESS_TOKEN",
+                )?;
+
+                let mut matrix_table = toml::value::Table::new();
+                matrix_table.insert("homeserver".to_string(), toml::Value::String(homeserver));
+                matrix_table.insert("access_token".to_string(), toml::Value::String(tok));
+                let rooms = matrix
+                    .rooms
+                    .into_iter()
+                    .filter_map(|room| normalize_optional_string(Some(room)))
+                    .map(toml::Value::String)
+                    .collect::<Vec<_>>();
+                if !rooms.is_empty() {
+                    matrix_table.insert("rooms".to_string(), toml::Value::Array(rooms));
+                }
+                matrix_table.insert(
+                    "auto_join".to_string(),
+                    toml::Value::Boolean(matrix.auto_join),
+                );
+                if let Some(profile_name) = normalized_profile.as_ref() {
+                    matrix_table.insert(
+                        "profile".to_string(),
+                        toml::Value::String(profile_name.clone()),
+                    );
+                }
+                channels.insert("matrix".to_string(), toml::Value::Table(matrix_table));
+            }
+            ChannelType::Rocketchat => {
+                let raw_server_url = rocketchat
+                    .server_url
+                    .or_else(|| std::env::var("ROCKETCHAT_URL").ok())
+                    .ok_or(
+                        "Rocket.Chat server URL required. Use --server-url or set ROCKETCHAT_URL\n\n  Example: --server-url https://chat.example.org",
+                    )?;
+                let raw_user_id = rocketchat
+                    .user_id
+                    .or_else(|| std::env::var("ROCKETCHAT_USER_ID").ok())
+                    .ok_or(
+                        "Rocket.Chat user ID required. Use --user-id or set ROCKETCHAT_USER_ID\n\n  It is shown with the token under My Account → Personal Access Tokens",
+                    )?;
+                let raw_token = token.or_else(|| std::env::var("ROCKETCHAT_AUTH_TOKEN").ok()).ok_or(
+                    "Rocket.Chat token required. Use --token or set ROCKETCHAT_AUTH_TOKEN\n\n  To get a token: log in as the bot → My Account → Personal Access Tokens → add",
+                )?;
+                let server_url = require_non_empty_token(
+                    raw_server_url,
+                    "Rocket.Chat server URL cannot be empty. Use --server-url or set ROCKETCHAT_URL",
+                )?;
+                let user_id = require_non_empty_token(
+                    raw_user_id,
+                    "Rocket.Chat user ID cannot be empty. Use --user-id or set ROCKETCHAT_USER_ID",
+                )?;
+                let tok = require_non_empty_token(
+                    raw_token,
+                    "Rocket.Chat token cannot be empty. Use --token or set ROCKETCHAT_AUTH_TOKEN",
+                )?;
+
+                let mut rocketchat_table = toml::value::Table::new();
+                rocketchat_table.insert("server_url".to_string(), toml::Value::String(server_url));
+                rocketchat_table.insert("user_id".to_string(), toml::Value::String(user_id));
+                rocketchat_table.insert("auth_token".to_string(), toml::Value::String(tok));
+                if let Some(profile_name) = normalized_profile.as_ref() {
+                    rocketchat_table.insert(
+                        "profile".to_string(),
+                        toml::Value::String(profile_name.clone()),
+                    );
+                }
+                channels.insert(
+                    "rocketchat".to_string(),
+                    toml::Value::Table(rocketchat_table),
+                );
+            }
             _ => return Err(format!("{:?} is not supported yet", channel_type)),
         }
     }
@@ -2316,6 +3041,9 @@ fn remove_channel(config_path: &Path, channel_type: ChannelType) -> Result<(), S
         ChannelType::Telegram => config.channels.telegram = None,
         ChannelType::Discord => config.channels.discord = None,
         ChannelType::Slack => config.channels.slack = None,
+        ChannelType::Whatsapp => config.channels.whatsapp = None,
+        ChannelType::Matrix => config.channels.matrix = None,
+        ChannelType::Rocketchat => config.channels.rocketchat = None,
         _ => return Err(format!("{:?} is not supported yet", channel_type)),
     }
 
@@ -2354,6 +3082,15 @@ async fn run_channel_command(
             if config.channels.slack.is_some() {
                 println!("{:<15} configured", "slack");
             }
+            if config.channels.whatsapp.is_some() {
+                println!("{:<15} configured", "whatsapp");
+            }
+            if config.channels.matrix.is_some() {
+                println!("{:<15} configured", "matrix");
+            }
+            if config.channels.rocketchat.is_some() {
+                println!("{:<15} configured", "rocketchat");
+            }
             Ok(())
         }
         AutopilotChannelCommands::Add {
@@ -2361,6 +3098,9 @@ async fn run_channel_command(
             token,
             bot_token,
             app_token,
+            whatsapp,
+            matrix,
+            rocketchat,
             target,
             profile,
         } => {
@@ -2384,6 +3124,9 @@ async fn run_channel_command(
                 token,
                 bot_token,
                 app_token,
+                *whatsapp,
+                *matrix,
+                *rocketchat,
                 target,
                 requested_profile,
             )?;
@@ -2481,6 +3224,12 @@ fn validate_schedule(schedule: &AutopilotScheduleConfig) -> Result<(), String> {
     Cron::from_str(&schedule.cron)
         .map_err(|e| format!("Invalid cron expression '{}': {}", schedule.cron, e))?;
 
+    if let Some(timezone) = schedule.timezone.as_deref()
+        && timezone.parse::<chrono_tz::Tz>().is_err()
+    {
+        return Err(format!("Unknown time zone '{}'", timezone));
+    }
+
     if schedule.prompt.trim().is_empty() {
         return Err("Schedule prompt cannot be empty".to_string());
     }
@@ -2491,7 +3240,13 @@ fn validate_schedule(schedule: &AutopilotScheduleConfig) -> Result<(), String> {
         return Err("Schedule profile cannot be empty".to_string());
     }
 
-    if let Some(check_path) = schedule.check.as_deref() {
+    if let Some(crate::commands::watch::CheckSpec::Probe(probe)) = &schedule.check {
+        probe
+            .validate()
+            .map_err(|e| format!("Invalid check for schedule '{}': {}", schedule.name, e))?;
+    }
+
+    if let Some(check_path) = schedule.check.as_ref().and_then(|check| check.script()) {
         let expanded = crate::commands::watch::config::expand_tilde(check_path);
         let expanded_str = expanded.to_string_lossy();
 
@@ -2649,8 +3404,13 @@ fn build_schedule_toml_table(schedule: &AutopilotScheduleConfig) -> toml::value:
         toml::Value::String(schedule.prompt.clone()),
     );
 
-    if let Some(check) = schedule.check.as_ref() {
-        table.insert("check".to_string(), toml::Value::String(check.clone()));
+    // Scripts serialize as a string and probes as a table; neither can fail.
+    if let Some(check) = schedule
+        .check
+        .as_ref()
+        .and_then(|check| toml::Value::try_from(check).ok())
+    {
+        table.insert("check".to_string(), check);
     }
     if let Some(trigger_on) = schedule.trigger_on {
         table.insert(
@@ -2789,12 +3549,11 @@ fn print_schedule_mutation_feedback(name: &str, action: &str, signaled: bool) {
 }
 
 async fn signal_scheduler_reload() -> bool {
-    let db_path = match autopilot_db_path() {
-        Ok(path) => path,
-        Err(_) => return false,
+    let Ok(config) = crate::commands::watch::ScheduleConfig::load_default() else {
+        return false;
     };
 
-    let db = match crate::commands::watch::ScheduleDb::new(&db_path).await {
+    let db = match crate::commands::watch::ScheduleDb::open(&config.watch).await {
         Ok(db) => db,
         Err(_) => return false,
     };
@@ -2802,17 +3561,6 @@ async fn signal_scheduler_reload() -> bool {
     db.request_config_reload().await.is_ok()
 }
 
-fn autopilot_db_path() -> Result<String, String> {
-    let config = crate::commands::watch::ScheduleConfig::load_default()
-        .map_err(|error| format!("Failed to load watch config: {}", error))?;
-    let db_path = config.db_path();
-
-    db_path
-        .to_str()
-        .map(|value| value.to_string())
-        .ok_or_else(|| "Invalid db path".to_string())
-}
-
 #[derive(Debug, Clone)]
 struct NotificationDefaults {
     channel: String,
@@ -3105,6 +3853,8 @@ async fn status_autopilot(
             } else {
                 "installed (inactive)"
             }
+        } else if scheduler.running {
+            "not installed (running as daemon)"
         } else {
             "not installed"
         }
@@ -3146,8 +3896,21 @@ async fn status_autopilot(
     if !config_exists {
         println!("  Scheduler       not configured (run: stakpak up)");
     } else if scheduler.config_valid {
-        let sched_state = if scheduler.running {
-            format!("✓ running (pid {})", scheduler.pid.unwrap_or_default())
+        let heartbeat_age = scheduler.heartbeat_age_seconds.unwrap_or_default();
+        let sched_state = if scheduler.running
+            && heartbeat_age > crate::commands::watch::commands::HEARTBEAT_STALE_SECONDS
+        {
+            format!(
+                "⚠ unresponsive (pid {}, last heartbeat {}s ago)",
+                scheduler.pid.unwrap_or_default(),
+                heartbeat_age
+            )
+        } else if scheduler.running {
+            format!(
+                "✓ running (pid {}, heartbeat {}s ago)",
+                scheduler.pid.unwrap_or_default(),
+                heartbeat_age
+            )
         } else if scheduler.stale_pid {
             format!("⚠ stale (pid {})", scheduler.pid.unwrap_or_default())
         } else {
@@ -3231,7 +3994,8 @@ async fn status_autopilot(
 fn tail_log_files(files: &[PathBuf], follow: bool, lines: Option<u32>) -> Result<(), String> {
     let mut cmd = std::process::Command::new("tail");
     if follow {
-        cmd.arg("-f");
+        // Follow by name so tail picks up the new file after a log rotation.
+        cmd.arg("-F");
     }
     if let Some(n) = lines {
         cmd.arg("-n").arg(n.to_string());
@@ -3289,8 +4053,9 @@ async fn logs_autopilot(
 
     match detect_platform() {
         Platform::Linux => {
-            // If a component filter is set, use tail on the specific file instead of journalctl
-            if component.is_some() {
+            // If a component filter is set, or autopilot runs as a daemon rather than a
+            // systemd unit, tail the log files instead of reading journalctl
+            if component.is_some() || !autopilot_service_installed() {
                 tail_log_files(&log_files, follow, lines)?;
             } else {
                 let mut cmd = std::process::Command::new("journalctl");
@@ -3513,6 +4278,89 @@ async fn write_default_autopilot_config(path: &Path, force: bool) -> Result<(),
     Ok(())
 }
 
+/// Loopback base URL of the local autopilot server, from `autopilot.toml`.
+pub(crate) fn local_server_base_url() -> Result<String, String> {
+    Ok(loopback_base_url_from_bind(
+        &AutopilotConfigFile::load_or_default()?.server.listen,
+    ))
+}
+
+/// Schedule names and cron expressions from `autopilot.toml`, in file order.
+pub(crate) fn local_schedule_names() -> Result<Vec<(String, String)>, String> {
+    Ok(AutopilotConfigFile::load_or_default()?
+        .schedules
+        .into_iter()
+        .map(|schedule| (schedule.name, schedule.cron))
+        .collect())
+}
+
+/// Tool approval policies `stakpak up` would start with right now.
+pub(crate) struct LocalApprovalPolicies {
+    /// Server policy for runs without an `auto_approve` override.
+    pub server: stakpak_server::ToolApprovalPolicy,
+    pub gateway: stakpak_gateway::approval::ApprovalPolicy,
+    /// Configured channels, in gateway order.
+    pub channels: Vec<String>,
+}
+
+/// Resolve approval policies from the profile and `autopilot.toml` the same
+/// way `stakpak up` does, without starting anything.
+pub(crate) fn local_approval_policies(config: &AppConfig) -> Result<LocalApprovalPolicies, String> {
+    let autopilot_config = AutopilotConfigFile::load_or_default()?;
+    let server = resolve_server_tool_policy(
+        config.allowed_tools.as_ref(),
+        config.auto_approve.as_ref(),
+        autopilot_config.server.auto_approve_all,
+    );
+
+    let mut gateway_cfg =
+        load_gateway_config_allowing_no_channels(AutopilotConfigFile::path().as_path())?;
+    apply_gateway_policy_from_resolved_tools(&mut gateway_cfg, &server);
+    let server = apply_approval_rules_to_server_policy(server, &gateway_cfg.gateway.approval_rules);
+    let server =
+        apply_mcp_approvals_to_server_policy(server, &stakpak_mcp_config::McpApprovalRules::load());
+
+    let gateway = stakpak_gateway::approval::ApprovalPolicy::from_config(&gateway_cfg)
+        .with_profile_resolution(
+            gateway_channel_profiles_with_default(&gateway_cfg.channels, &config.profile_name),
+            Arc::new(ProfileRunOverrideResolver::new(config.config_path.clone())),
+        );
+
+    Ok(LocalApprovalPolicies {
+        server,
+        gateway,
+        channels: gateway_cfg
+            .enabled_channels()
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+/// Channels configured in `autopilot.toml`, built as `stakpak up` builds them.
+pub(crate) fn local_gateway_channels()
+-> Result<HashMap<String, Arc<dyn stakpak_gateway::Channel>>, String> {
+    let config = load_gateway_config_allowing_no_channels(AutopilotConfigFile::path().as_path())?;
+    stakpak_gateway::build_channels(&config).map_err(|e| format!("Failed to build channels: {e}"))
+}
+
+/// The gateway store `stakpak up` writes to.
+pub(crate) fn local_gateway_store_path() -> Result<PathBuf, String> {
+    stakpak_gateway::GatewayConfig::load_unvalidated(
+        AutopilotConfigFile::path().as_path(),
+        &stakpak_gateway::GatewayCliFlags::default(),
+    )
+    .map(|config| config.gateway.store_path)
+    .map_err(|e| format!("Failed to load channel config: {e}"))
+}
+
+/// The `[notifications]` default as `(channel, target)`, when one is set.
+pub(crate) fn default_notification_target() -> Option<(String, String)> {
+    let defaults = load_notification_defaults(AutopilotConfigFile::path().as_path()).ok()?;
+    let target = defaults.resolved_target()?.to_string();
+    Some((defaults.channel, target))
+}
+
 fn loopback_base_url_from_bind(bind: &str) -> String {
     match bind.parse::<SocketAddr>() {
         Ok(addr) => {
@@ -3547,31 +4395,21 @@ async fn collect_scheduler_status(recent_runs: Option<u32>) -> SchedulerStatusJs
 
     let config_valid = config_path.exists();
 
-    // Watch runtime uses ~/.stakpak/autopilot/autopilot.db regardless of config format
-    let db_path = dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join(".stakpak/autopilot/autopilot.db");
-    let db_path_str = db_path.to_string_lossy().to_string();
-
-    let db = match db_path.to_str() {
-        Some(path) => match crate::commands::watch::ScheduleDb::new(path).await {
-            Ok(db) => db,
-            Err(error) => {
-                return SchedulerStatusJson {
-                    expected_enabled: true,
-                    config_path: config_path.display().to_string(),
-                    config_valid,
-                    trigger_count: schedule_count,
-                    running: false,
-                    pid: None,
-                    stale_pid: false,
-                    db_path: Some(db_path_str),
-                    error: Some(error.to_string()),
-                    recent_runs: Vec::new(),
-                };
-            }
-        },
-        None => {
+    // Watch runtime reads its database settings from [watch], falling back to
+    // ~/.stakpak/autopilot/autopilot.db when the config cannot be loaded.
+    let settings = crate::commands::watch::ScheduleConfig::load_default()
+        .map(|config| config.watch)
+        .unwrap_or_default();
+
+    let db = match crate::commands::watch::ScheduleDb::open(&settings).await {
+        Ok(db) => db,
+        Err(error) => {
+            let db_path_str = match &settings.database_url {
+                Some(url) => crate::commands::watch::describe_database_url(url),
+                None => crate::commands::watch::expand_tilde(&settings.db_path)
+                    .display()
+                    .to_string(),
+            };
             return SchedulerStatusJson {
                 expected_enabled: true,
                 config_path: config_path.display().to_string(),
@@ -3580,33 +4418,40 @@ async fn collect_scheduler_status(recent_runs: Option<u32>) -> SchedulerStatusJs
                 running: false,
                 pid: None,
                 stale_pid: false,
+                last_heartbeat: None,
+                heartbeat_age_seconds: None,
                 db_path: Some(db_path_str),
-                error: Some("Invalid scheduler database path".to_string()),
+                error: Some(error.to_string()),
                 recent_runs: Vec::new(),
             };
         }
     };
+    let db_path_str = db.location();
 
     let scheduler_state = db.get_autopilot_state().await.ok().flatten();
 
-    let (running, stale_pid, pid) = if let Some(state) = scheduler_state {
+    let (running, stale_pid, pid, last_heartbeat) = if let Some(state) = scheduler_state {
         let pid = state.pid;
         let running = u32::try_from(pid)
             .ok()
             .map(crate::commands::watch::is_process_running)
             .unwrap_or(false);
-        (running, !running, Some(pid))
+        (running, !running, Some(pid), Some(state.last_heartbeat))
     } else {
-        (false, false, None)
+        (false, false, None, None)
     };
+    let heartbeat_age_seconds = last_heartbeat.map(|heartbeat| {
+        Utc::now()
+            .signed_duration_since(heartbeat)
+            .num_seconds()
+            .max(0)
+    });
 
     let recent_runs = if let Some(limit) = recent_runs.filter(|limit| *limit > 0) {
         match db
             .list_runs(&crate::commands::watch::ListRunsFilter {
-                schedule_name: None,
-                status: None,
                 limit: Some(limit),
-                offset: None,
+                ..Default::default()
             })
             .await
         {
@@ -3635,6 +4480,8 @@ async fn collect_scheduler_status(recent_runs: Option<u32>) -> SchedulerStatusJs
         running,
         pid,
         stale_pid,
+        last_heartbeat: last_heartbeat.map(|heartbeat| heartbeat.to_rfc3339()),
+        heartbeat_age_seconds,
         db_path: Some(db_path_str),
         error: None,
         recent_runs,
@@ -3652,7 +4499,11 @@ fn build_schedule_statuses(
             cron: schedule.cron.clone(),
             enabled: schedule.enabled,
             sandbox: schedule.sandbox,
-            next_run: next_run_for_cron(&schedule.cron, schedule.enabled),
+            next_run: next_run_for_cron(
+                &schedule.cron,
+                schedule.timezone.as_deref(),
+                schedule.enabled,
+            ),
             notification: resolve_schedule_notification_status(schedule, notification_defaults),
         })
         .collect()
@@ -3822,13 +4673,17 @@ fn build_single_channel_status(
     }
 }
 
-fn next_run_for_cron(cron: &str, enabled: bool) -> Option<String> {
+fn next_run_for_cron(cron: &str, timezone: Option<&str>, enabled: bool) -> Option<String> {
     if !enabled {
         return None;
     }
 
     let expression = Cron::from_str(cron).ok()?;
-    let next = expression.find_next_occurrence(&Utc::now(), false).ok()?;
+    let timezone: chrono_tz::Tz = timezone.unwrap_or("UTC").parse().ok()?;
+    let next = expression
+        .find_next_occurrence(&Utc::now().with_timezone(&timezone), false)
+        .ok()?
+        .with_timezone(&Utc);
     Some(next.format("%Y-%m-%d %H:%M").to_string())
 }
 
@@ -3979,12 +4834,80 @@ fn detect_platform() -> Platform {
 const AUTOPILOT_SYSTEMD_SERVICE: &str = "stakpak-autopilot";
 const AUTOPILOT_LAUNCHD_LABEL: &str = "dev.stakpak.autopilot";
 
+/// Log directory from the `[watch]` settings, falling back to the default
+/// location when the scheduler config cannot be loaded.
 fn autopilot_log_dir() -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join(".stakpak")
-        .join("autopilot")
-        .join("logs")
+    match crate::commands::watch::ScheduleConfig::load_default() {
+        Ok(config) => config.log_dir(),
+        Err(_) => dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".stakpak")
+            .join("autopilot")
+            .join("logs"),
+    }
+}
+
+fn autopilot_log_rotation_policy() -> crate::commands::watch::LogRotationPolicy {
+    crate::commands::watch::ScheduleConfig::load_default()
+        .map(|config| config.watch.log_rotation_policy())
+        .unwrap_or_else(|_| {
+            crate::commands::watch::config::ScheduleSettings::default().log_rotation_policy()
+        })
+}
+
+/// Re-exec the current binary as a detached autopilot runtime.
+///
+/// The child runs the same `up --foreground --from-service` command the OS
+/// service units use, in its own session so it survives the terminal closing.
+/// Its stdout/stderr go to the same `stdout.log`/`stderr.log` files.
+fn spawn_autopilot_daemon(config: &AppConfig) -> Result<std::process::Child, String> {
+    let binary = std::env::current_exe()
+        .map_err(|e| format!("Failed to resolve stakpak binary path: {}", e))?;
+
+    let log_dir = autopilot_log_dir();
+    std::fs::create_dir_all(&log_dir)
+        .map_err(|e| format!("Failed to create autopilot log directory: {}", e))?;
+    let open_output = |file_name: &str| {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_dir.join(file_name))
+            .map_err(|e| format!("Failed to open autopilot log {}: {}", file_name, e))
+    };
+    let stdout = open_output("stdout.log")?;
+    let stderr = open_output("stderr.log")?;
+
+    let mut command = std::process::Command::new(binary);
+    if !config.profile_name.is_empty() {
+        command.arg("--profile").arg(&config.profile_name);
+    }
+    if !config.config_path.is_empty() {
+        command.arg("--config").arg(&config.config_path);
+    }
+    command
+        .args(["autopilot", "up", "--foreground", "--from-service"])
+        .current_dir(dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")))
+        .stdin(std::process::Stdio::null())
+        .stdout(stdout)
+        .stderr(stderr);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // SAFETY: setsid is async-signal-safe and touches no parent state.
+        unsafe {
+            command.pre_exec(|| {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+
+    command
+        .spawn()
+        .map_err(|e| format!("Failed to start autopilot daemon: {}", e))
 }
 
 fn autopilot_service_path() -> PathBuf {
@@ -4028,10 +4951,204 @@ pub(crate) fn is_autopilot_running() -> Option<u32> {
     }
 }
 
-fn install_autopilot_service(config: &AppConfig) -> Result<(), String> {
+const SYSTEMD_DEFAULT_PATH: &str = "/usr/local/bin:/usr/bin:/bin";
+const LAUNCHD_DEFAULT_PATH: &str = "/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin";
+
+/// Settings for generated service definitions beyond binary, profile and config.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ServiceUnitOptions {
+    /// Extra environment variables; these override the default HOME/PATH.
+    env: Vec<(String, String)>,
+    restart: ServiceRestartPolicy,
+}
+
+/// Parse an `--env` value: `NAME` copies the variable from the current
+/// environment, `NAME=VALUE` sets it explicitly.
+fn parse_service_env(spec: &str) -> Result<(String, String), String> {
+    let (name, value) = match spec.split_once('=') {
+        Some((name, value)) => (name.to_string(), value.to_string()),
+        None => {
+            let value = std::env::var(spec).map_err(|_| {
+                format!(
+                    "Environment variable {} is not set (use --env {}=VALUE to set it explicitly)",
+                    spec, spec
+                )
+            })?;
+            (spec.to_string(), value)
+        }
+    };
+
+    let valid_name = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid_name {
+        return Err(format!("Invalid environment variable name '{}'", name));
+    }
+    if value.contains('\n') || value.contains('\r') {
+        return Err(format!(
+            "Environment variable {} contains a newline, which service definitions cannot hold",
+            name
+        ));
+    }
+    Ok((name, value))
+}
+
+/// Command line the service manager runs: the current binary with the active
+/// profile and config, in foreground service mode.
+fn service_exec_parts(config: &AppConfig) -> Result<Vec<String>, String> {
+    let binary = std::env::current_exe()
+        .map_err(|e| format!("Failed to resolve stakpak binary path: {}", e))?;
+
+    let mut exec_parts = vec![binary.display().to_string()];
+    if !config.profile_name.is_empty() {
+        exec_parts.push("--profile".to_string());
+        exec_parts.push(config.profile_name.clone());
+    }
+    if !config.config_path.is_empty() {
+        exec_parts.push("--config".to_string());
+        exec_parts.push(config.config_path.clone());
+    }
+    exec_parts.extend([
+        "autopilot".to_string(),
+        "up".to_string(),
+        "--foreground".to_string(),
+        "--from-service".to_string(),
+    ]);
+    Ok(exec_parts)
+}
+
+/// HOME and PATH defaults followed by user-supplied variables, with later
+/// entries replacing earlier ones of the same name.
+fn service_environment(
+    home: &Path,
+    default_path: &str,
+    options: &ServiceUnitOptions,
+) -> Vec<(String, String)> {
+    let mut environment = vec![
+        ("HOME".to_string(), home.display().to_string()),
+        ("PATH".to_string(), default_path.to_string()),
+    ];
+    for (name, value) in &options.env {
+        match environment
+            .iter_mut()
+            .find(|(existing, _)| existing == name)
+        {
+            Some(entry) => entry.1.clone_from(value),
+            None => environment.push((name.clone(), value.clone())),
+        }
+    }
+    environment
+}
+
+/// Write a service definition. Definitions carrying passthrough environment
+/// variables may hold secrets, so they are made owner-readable only.
+fn write_service_definition(
+    path: &Path,
+    content: &str,
+    options: &ServiceUnitOptions,
+) -> std::io::Result<()> {
+    std::fs::write(path, content)?;
+    #[cfg(unix)]
+    if !options.env.is_empty() {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+fn install_service_command(
+    config: &AppConfig,
+    env: &[String],
+    restart: ServiceRestartPolicy,
+    print: bool,
+    start: bool,
+) -> Result<(), String> {
+    let options = ServiceUnitOptions {
+        env: env
+            .iter()
+            .map(|spec| parse_service_env(spec))
+            .collect::<Result<_, _>>()?,
+        restart,
+    };
+
+    if print {
+        let exec_parts = service_exec_parts(config)?;
+        let log_dir = autopilot_log_dir();
+        let definition = match detect_platform() {
+            Platform::Linux => build_systemd_unit(&exec_parts, &log_dir, &options),
+            Platform::MacOS => build_launchd_plist(&exec_parts, &log_dir, &options),
+            Platform::Windows => {
+                return Err("Windows autopilot service is not yet supported".to_string());
+            }
+            Platform::Unknown => {
+                return Err("Unsupported platform for autopilot service".to_string());
+            }
+        };
+        print!("{}", definition);
+        return Ok(());
+    }
+
+    let reinstall = autopilot_service_installed();
+    let was_active = reinstall && autopilot_service_active();
+    install_autopilot_service(config, &options)?;
+
+    println!(
+        "✓ {} autopilot service: {}",
+        if reinstall {
+            "Reinstalled"
+        } else {
+            "Installed"
+        },
+        autopilot_service_path().display()
+    );
+    println!("  Profile     {}", config.profile_name);
+    let restart_label = restart
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default();
+    println!("  Restart     {}", restart_label);
+    if !options.env.is_empty() {
+        let names: Vec<&str> = options.env.iter().map(|(name, _)| name.as_str()).collect();
+        println!("  Env         {}", names.join(", "));
+        println!("  Note        environment values are stored in plain text (file mode 0600)");
+    }
+
+    if start {
+        if was_active {
+            stop_autopilot_service()?;
+        }
+        // launchd keeps the previously loaded plist until it is unloaded.
+        if reinstall && detect_platform() == Platform::MacOS {
+            let _ = std::process::Command::new("launchctl")
+                .args([
+                    "unload",
+                    autopilot_service_path().to_string_lossy().as_ref(),
+                ])
+                .status();
+        }
+        start_autopilot_service()?;
+        println!("✓ Autopilot service started");
+    } else if was_active {
+        println!();
+        println!("  The running service still uses the previous definition.");
+        println!("  Apply now   stakpak autopilot install-service --start");
+    } else {
+        println!();
+        println!("  Start       stakpak up");
+    }
+
+    Ok(())
+}
+
+fn install_autopilot_service(
+    config: &AppConfig,
+    options: &ServiceUnitOptions,
+) -> Result<(), String> {
     match detect_platform() {
-        Platform::Linux => install_systemd_service(config),
-        Platform::MacOS => install_launchd_service(config),
+        Platform::Linux => install_systemd_service(config, options),
+        Platform::MacOS => install_launchd_service(config, options),
         Platform::Windows => Err("Windows autopilot service is not yet supported".to_string()),
         Platform::Unknown => Err("Unsupported platform for autopilot service".to_string()),
     }
@@ -4133,9 +5250,7 @@ pub(crate) fn autopilot_service_active() -> bool {
     }
 }
 
-fn install_systemd_service(config: &AppConfig) -> Result<(), String> {
-    let binary = std::env::current_exe()
-        .map_err(|e| format!("Failed to resolve stakpak binary path: {}", e))?;
+fn install_systemd_service(config: &AppConfig, options: &ServiceUnitOptions) -> Result<(), String> {
     let service_path = autopilot_service_path();
 
     if let Some(parent) = service_path.parent() {
@@ -4147,52 +5262,56 @@ fn install_systemd_service(config: &AppConfig) -> Result<(), String> {
     std::fs::create_dir_all(&log_dir)
         .map_err(|e| format!("Failed to create autopilot log directory: {}", e))?;
 
+    let unit = build_systemd_unit(&service_exec_parts(config)?, &log_dir, options);
+    write_service_definition(&service_path, &unit, options)
+        .map_err(|e| format!("Failed to write systemd service file: {}", e))?;
+
+    run_command(
+        "systemctl",
+        &["--user", "daemon-reload"],
+        "Failed to reload systemd",
+    )?;
+    run_command(
+        "systemctl",
+        &["--user", "enable", AUTOPILOT_SYSTEMD_SERVICE],
+        "Failed to enable autopilot service",
+    )?;
+
+    Ok(())
+}
+
+fn build_systemd_unit(
+    exec_parts: &[String],
+    log_dir: &Path,
+    options: &ServiceUnitOptions,
+) -> String {
     let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let exec_cmd = shell_join(exec_parts);
+    let (exec_start, no_new_privileges) = build_systemd_exec_start(&exec_cmd);
 
-    let mut exec_parts = vec![binary.display().to_string()];
-    if !config.profile_name.is_empty() {
-        exec_parts.push("--profile".to_string());
-        exec_parts.push(config.profile_name.clone());
-    }
-    if !config.config_path.is_empty() {
-        exec_parts.push("--config".to_string());
-        exec_parts.push(config.config_path.clone());
-    }
-    exec_parts.extend([
-        "autopilot".to_string(),
-        "up".to_string(),
-        "--foreground".to_string(),
-        "--from-service".to_string(),
-    ]);
-
-    let exec_cmd = shell_join(&exec_parts);
-    let (exec_start, no_new_privileges) = build_systemd_exec_start(&exec_cmd);
+    let environment: String = service_environment(&home, SYSTEMD_DEFAULT_PATH, options)
+        .iter()
+        .map(|(name, value)| format!("Environment=\"{}\"\n", systemd_escape_env(name, value)))
+        .collect();
 
-    let unit = format!(
-        "[Unit]\nDescription=Stakpak Autopilot Runtime\nAfter=network.target\n\n[Service]\nType=simple\nExecStart={}\nRestart=on-failure\nRestartSec=5\nWorkingDirectory={}\nEnvironment=HOME={}\nEnvironment=PATH=/usr/local/bin:/usr/bin:/bin\nStandardOutput=append:{}/stdout.log\nStandardError=append:{}/stderr.log\nNoNewPrivileges={}\n\n[Install]\nWantedBy=default.target\n",
+    format!(
+        "[Unit]\nDescription=Stakpak Autopilot Runtime\nAfter=network.target\n\n[Service]\nType=simple\nExecStart={}\nRestart={}\nRestartSec=5\nWorkingDirectory={}\n{}StandardOutput=append:{}/stdout.log\nStandardError=append:{}/stderr.log\nNoNewPrivileges={}\n\n[Install]\nWantedBy=default.target\n",
         exec_start,
+        options.restart.systemd_value(),
         home.display(),
-        home.display(),
+        environment,
         log_dir.display(),
         log_dir.display(),
         no_new_privileges,
-    );
-
-    std::fs::write(&service_path, unit)
-        .map_err(|e| format!("Failed to write systemd service file: {}", e))?;
-
-    run_command(
-        "systemctl",
-        &["--user", "daemon-reload"],
-        "Failed to reload systemd",
-    )?;
-    run_command(
-        "systemctl",
-        &["--user", "enable", AUTOPILOT_SYSTEMD_SERVICE],
-        "Failed to enable autopilot service",
-    )?;
+    )
+}
 
-    Ok(())
+/// Escape a `NAME=value` pair for a double-quoted systemd `Environment=` line.
+fn systemd_escape_env(name: &str, value: &str) -> String {
+    format!("{}={}", name, value)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
 }
 
 /// Build the `ExecStart=` value for the systemd unit file.
@@ -4227,9 +5346,7 @@ fn uninstall_systemd_service() -> Result<(), String> {
     Ok(())
 }
 
-fn install_launchd_service(config: &AppConfig) -> Result<(), String> {
-    let binary = std::env::current_exe()
-        .map_err(|e| format!("Failed to resolve stakpak binary path: {}", e))?;
+fn install_launchd_service(config: &AppConfig, options: &ServiceUnitOptions) -> Result<(), String> {
     let plist_path = autopilot_service_path();
 
     if let Some(parent) = plist_path.parent() {
@@ -4241,31 +5358,43 @@ fn install_launchd_service(config: &AppConfig) -> Result<(), String> {
     std::fs::create_dir_all(&log_dir)
         .map_err(|e| format!("Failed to create autopilot log directory: {}", e))?;
 
+    let plist = build_launchd_plist(&service_exec_parts(config)?, &log_dir, options);
+    write_service_definition(&plist_path, &plist, options)
+        .map_err(|e| format!("Failed to write launchd plist: {}", e))?;
+
+    Ok(())
+}
+
+fn build_launchd_plist(
+    exec_parts: &[String],
+    log_dir: &Path,
+    options: &ServiceUnitOptions,
+) -> String {
     let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
 
-    let mut args = Vec::new();
-    if !config.profile_name.is_empty() {
-        args.push("<string>--profile</string>".to_string());
-        args.push(format!(
-            "<string>{}</string>",
-            xml_escape(&config.profile_name)
-        ));
-    }
-    if !config.config_path.is_empty() {
-        args.push("<string>--config</string>".to_string());
-        args.push(format!(
-            "<string>{}</string>",
-            xml_escape(&config.config_path)
-        ));
-    }
-    args.extend([
-        "<string>autopilot</string>".to_string(),
-        "<string>up</string>".to_string(),
-        "<string>--foreground</string>".to_string(),
-        "<string>--from-service</string>".to_string(),
-    ]);
+    let args: Vec<String> = exec_parts
+        .iter()
+        .map(|part| format!("<string>{}</string>", xml_escape(part)))
+        .collect();
+    let environment: Vec<String> = service_environment(&home, LAUNCHD_DEFAULT_PATH, options)
+        .iter()
+        .map(|(name, value)| {
+            format!(
+                "<key>{}</key>\n        <string>{}</string>",
+                xml_escape(name),
+                xml_escape(value)
+            )
+        })
+        .collect();
+    let keep_alive = match options.restart {
+        ServiceRestartPolicy::OnFailure => {
+            "<dict>\n        <key>SuccessfulExit</key>\n        <false/>\n    </dict>"
+        }
+        ServiceRestartPolicy::Always => "<true/>",
+        ServiceRestartPolicy::Never => "<false/>",
+    };
 
-    let plist = format!(
+    format!(
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
 <plist version="1.0">
@@ -4274,16 +5403,12 @@ fn install_launchd_service(config: &AppConfig) -> Result<(), String> {
     <string>{}</string>
     <key>ProgramArguments</key>
     <array>
-        <string>{}</string>
         {}
     </array>
     <key>RunAtLoad</key>
     <true/>
     <key>KeepAlive</key>
-    <dict>
-        <key>SuccessfulExit</key>
-        <false/>
-    </dict>
+    {}
     <key>WorkingDirectory</key>
     <string>{}</string>
     <key>StandardOutPath</key>
@@ -4292,27 +5417,19 @@ fn install_launchd_service(config: &AppConfig) -> Result<(), String> {
     <string>{}/stderr.log</string>
     <key>EnvironmentVariables</key>
     <dict>
-        <key>HOME</key>
-        <string>{}</string>
-        <key>PATH</key>
-        <string>/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin</string>
+        {}
     </dict>
 </dict>
 </plist>
 "#,
         AUTOPILOT_LAUNCHD_LABEL,
-        xml_escape(&binary.display().to_string()),
         args.join("\n        "),
+        keep_alive,
         xml_escape(&home.display().to_string()),
         xml_escape(&log_dir.display().to_string()),
         xml_escape(&log_dir.display().to_string()),
-        xml_escape(&home.display().to_string()),
-    );
-
-    std::fs::write(&plist_path, plist)
-        .map_err(|e| format!("Failed to write launchd plist: {}", e))?;
-
-    Ok(())
+        environment.join("\n        "),
+    )
 }
 
 fn uninstall_launchd_service() -> Result<(), String> {
@@ -4420,7 +5537,13 @@ mod tests {
             anonymous_id: None,
             collect_telemetry: None,
             editor: None,
+            accessibility: None,
+            theme: None,
+            notifications: None,
+            discovery: None,
+            redaction: None,
             recent_models: Vec::new(),
+            local_endpoint: None,
         }
     }
 
@@ -4468,6 +5591,7 @@ mod tests {
         AutopilotScheduleConfig {
             name: name.to_string(),
             cron: "*/5 * * * *".to_string(),
+            timezone: None,
             prompt: "Check infra".to_string(),
             check: None,
             trigger_on: Some(ScheduleTriggerOn::Failure),
@@ -4543,7 +5667,9 @@ mod tests {
         let mut schedule = sample_schedule("missing-check");
         let missing = temp_file_path("autopilot-missing-check-script");
         let _ = std::fs::remove_file(&missing);
-        schedule.check = Some(missing.to_string_lossy().to_string());
+        schedule.check = Some(crate::commands::watch::CheckSpec::Script(
+            missing.to_string_lossy().to_string(),
+        ));
 
         let result = add_schedule_in_config(&mut config, schedule);
         assert!(result.is_err());
@@ -4563,7 +5689,9 @@ mod tests {
             std::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755))
                 .expect("set executable permission");
         }
-        schedule.check = Some(script_path.to_string_lossy().to_string());
+        schedule.check = Some(crate::commands::watch::CheckSpec::Script(
+            script_path.to_string_lossy().to_string(),
+        ));
 
         let result = add_schedule_in_config(&mut config, schedule);
         assert!(result.is_ok());
@@ -4652,6 +5780,62 @@ app_token = "xapp-old"
         let _ = std::fs::remove_file(path);
     }
 
+    #[test]
+    fn server_context_rules_load_into_start_options_and_survive_save() {
+        let path = temp_file_path("autopilot-context-rules");
+        let write_result = std::fs::write(
+            &path,
+            r##"
+[server]
+listen = "127.0.0.1:4096"
+
+[server.context]
+include = ["vendor/acme/AGENTS.md"]
+exclude = ["vendor/", "*.lock", "gen/"]
+"##,
+        );
+        assert!(write_result.is_ok());
+
+        let loaded = match AutopilotConfigFile::load_from_path(&path) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        assert_eq!(loaded.server.context.include, vec!["vendor/acme/AGENTS.md"]);
+        assert_eq!(
+            loaded.server.context.exclude,
+            vec!["vendor/", "*.lock", "gen/"]
+        );
+
+        let options = StartOptions {
+            bind: default_server_listen(),
+            show_token: false,
+            no_auth: false,
+            model: None,
+            auto_approve_all: false,
+            foreground: false,
+            daemon: false,
+            from_service: false,
+            non_interactive: true,
+            force: false,
+            sandbox_mode: stakpak_server::SandboxMode::default(),
+            hibernate_idle_minutes: default_hibernate_idle_minutes(),
+            machine_profile: false,
+            memory: false,
+            context_rules: ContextRulesConfig::default(),
+        }
+        .with_server_config(&loaded.server);
+        assert_eq!(options.context_rules, loaded.server.context);
+
+        assert!(loaded.save_to_path(&path).is_ok());
+        let reloaded = match AutopilotConfigFile::load_from_path(&path) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to reload config: {error}"),
+        };
+        assert_eq!(reloaded.server.context, loaded.server.context);
+
+        let _ = std::fs::remove_file(path);
+    }
+
     #[test]
     fn set_default_notification_target_merges_without_overwrite() {
         let path = temp_file_path("autopilot-notification-target");
@@ -4716,6 +5900,9 @@ prompt = "Check system health"
             None,
             Some("xoxb-test".to_string()),
             Some("xapp-test".to_string()),
+            WhatsAppChannelArgs::default(),
+            MatrixChannelArgs::default(),
+            RocketChatChannelArgs::default(),
             Some("#eng".to_string()),
             None,
         );
@@ -4764,6 +5951,9 @@ prompt = "Check system health"
             None,
             Some("xoxb-test".to_string()),
             Some("xapp-test".to_string()),
+            WhatsAppChannelArgs::default(),
+            MatrixChannelArgs::default(),
+            RocketChatChannelArgs::default(),
             Some("   ".to_string()),
             None,
         );
@@ -4793,6 +5983,9 @@ prompt = "Check system health"
             Some("   ".to_string()),
             None,
             None,
+            WhatsAppChannelArgs::default(),
+            MatrixChannelArgs::default(),
+            RocketChatChannelArgs::default(),
             None,
             None,
         );
@@ -4804,6 +5997,9 @@ prompt = "Check system health"
             Some("   ".to_string()),
             None,
             None,
+            WhatsAppChannelArgs::default(),
+            MatrixChannelArgs::default(),
+            RocketChatChannelArgs::default(),
             None,
             None,
         );
@@ -4815,6 +6011,9 @@ prompt = "Check system health"
             None,
             Some("   ".to_string()),
             Some("xapp-test".to_string()),
+            WhatsAppChannelArgs::default(),
+            MatrixChannelArgs::default(),
+            RocketChatChannelArgs::default(),
             None,
             None,
         );
@@ -4826,6 +6025,9 @@ prompt = "Check system health"
             None,
             Some("xoxb-test".to_string()),
             Some("   ".to_string()),
+            WhatsAppChannelArgs::default(),
+            MatrixChannelArgs::default(),
+            RocketChatChannelArgs::default(),
             None,
             None,
         );
@@ -4834,6 +6036,204 @@ prompt = "Check system health"
         let _ = std::fs::remove_file(path);
     }
 
+    #[test]
+    fn channel_add_whatsapp_writes_cloud_api_settings() {
+        let path = temp_file_path("autopilot-channel-add-whatsapp");
+
+        let missing_verify_token = add_channel_with_optional_target(
+            path.as_path(),
+            ChannelType::Whatsapp,
+            Some("EAAG-token".to_string()),
+            None,
+            None,
+            WhatsAppChannelArgs {
+                phone_number_id: Some("1234567890".to_string()),
+                verify_token: Some("   ".to_string()),
+                app_secret: None,
+            },
+            MatrixChannelArgs::default(),
+            RocketChatChannelArgs::default(),
+            None,
+            None,
+        );
+        assert!(missing_verify_token.is_err());
+
+        let empty_app_secret = add_channel_with_optional_target(
+            path.as_path(),
+            ChannelType::Whatsapp,
+            Some("EAAG-token".to_string()),
+            None,
+            None,
+            WhatsAppChannelArgs {
+                phone_number_id: Some("1234567890".to_string()),
+                verify_token: Some("verify-me".to_string()),
+                app_secret: Some("   ".to_string()),
+            },
+            MatrixChannelArgs::default(),
+            RocketChatChannelArgs::default(),
+            None,
+            None,
+        );
+        assert!(empty_app_secret.is_err());
+
+        let add_result = add_channel_with_optional_target(
+            path.as_path(),
+            ChannelType::Whatsapp,
+            Some("EAAG-token".to_string()),
+            None,
+            None,
+            WhatsAppChannelArgs {
+                phone_number_id: Some("1234567890".to_string()),
+                verify_token: Some("verify-me".to_string()),
+                app_secret: Some("app-secret".to_string()),
+            },
+            MatrixChannelArgs::default(),
+            RocketChatChannelArgs::default(),
+            Some("4915112345678".to_string()),
+            Some("field-ops".to_string()),
+        );
+        assert!(add_result.is_ok());
+
+        let config = match stakpak_gateway::GatewayConfig::load(
+            path.as_path(),
+            &stakpak_gateway::GatewayCliFlags::default(),
+        ) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        let whatsapp = match config.channels.whatsapp {
+            Some(value) => value,
+            None => panic!("whatsapp channel missing"),
+        };
+        assert_eq!(whatsapp.access_token, "EAAG-token");
+        assert_eq!(whatsapp.phone_number_id, "1234567890");
+        assert_eq!(whatsapp.verify_token, "verify-me");
+        assert_eq!(whatsapp.app_secret, "app-secret");
+        assert_eq!(whatsapp.profile.as_deref(), Some("field-ops"));
+
+        let reloaded = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(reloaded.contains("channel = \"whatsapp\""));
+        assert!(reloaded.contains("target = \"4915112345678\""));
+
+        assert!(remove_channel(path.as_path(), ChannelType::Whatsapp).is_ok());
+        let reloaded = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(!reloaded.contains("[channels.whatsapp]"));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn channel_add_matrix_writes_homeserver_and_rooms() {
+        let path = temp_file_path("autopilot-channel-add-matrix");
+
+        let add_result = add_channel_with_optional_target(
+            path.as_path(),
+            ChannelType::Matrix,
+            Some("syt_token".to_string()),
+            None,
+            None,
+            WhatsAppChannelArgs::default(),
+            MatrixChannelArgs {
+                homeserver: Some("https://matrix.example.org".to_string()),
+                rooms: vec!["!ops:example.org".to_string(), " ".to_string()],
+                auto_join: true,
+            },
+            RocketChatChannelArgs::default(),
+            Some("!ops:example.org".to_string()),
+            None,
+        );
+        assert!(add_result.is_ok());
+
+        let config = match stakpak_gateway::GatewayConfig::load(
+            path.as_path(),
+            &stakpak_gateway::GatewayCliFlags::default(),
+        ) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        let matrix = match config.channels.matrix {
+            Some(value) => value,
+            None => panic!("matrix channel missing"),
+        };
+        assert_eq!(matrix.homeserver, "https://matrix.example.org");
+        assert_eq!(matrix.access_token, "syt_token");
+        assert_eq!(matrix.rooms, vec!["!ops:example.org"]);
+        assert!(matrix.auto_join);
+
+        let reloaded = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(reloaded.contains("channel = \"matrix\""));
+
+        assert!(remove_channel(path.as_path(), ChannelType::Matrix).is_ok());
+        let reloaded = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(!reloaded.contains("[channels.matrix]"));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn channel_add_rocketchat_writes_server_and_credentials() {
+        let path = temp_file_path("autopilot-channel-add-rocketchat");
+
+        let missing_user_id = add_channel_with_optional_target(
+            path.as_path(),
+            ChannelType::Rocketchat,
+            Some("pat-token".to_string()),
+            None,
+            None,
+            WhatsAppChannelArgs::default(),
+            MatrixChannelArgs::default(),
+            RocketChatChannelArgs {
+                server_url: Some("https://chat.example.org".to_string()),
+                user_id: Some(" ".to_string()),
+            },
+            None,
+            None,
+        );
+        assert!(missing_user_id.is_err());
+
+        let add_result = add_channel_with_optional_target(
+            path.as_path(),
+            ChannelType::Rocketchat,
+            Some("pat-token".to_string()),
+            None,
+            None,
+            WhatsAppChannelArgs::default(),
+            MatrixChannelArgs::default(),
+            RocketChatChannelArgs {
+                server_url: Some("https://chat.example.org".to_string()),
+                user_id: Some("botuserid".to_string()),
+            },
+            Some("GENERAL".to_string()),
+            Some("ops".to_string()),
+        );
+        assert!(add_result.is_ok());
+
+        let config = match stakpak_gateway::GatewayConfig::load(
+            path.as_path(),
+            &stakpak_gateway::GatewayCliFlags::default(),
+        ) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        let rocketchat = match config.channels.rocketchat {
+            Some(value) => value,
+            None => panic!("rocketchat channel missing"),
+        };
+        assert_eq!(rocketchat.server_url, "https://chat.example.org");
+        assert_eq!(rocketchat.user_id, "botuserid");
+        assert_eq!(rocketchat.auth_token, "pat-token");
+        assert_eq!(rocketchat.profile.as_deref(), Some("ops"));
+
+        let reloaded = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(reloaded.contains("channel = \"rocketchat\""));
+
+        assert!(remove_channel(path.as_path(), ChannelType::Rocketchat).is_ok());
+        let reloaded = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(!reloaded.contains("[channels.rocketchat]"));
+
+        let _ = std::fs::remove_file(path);
+    }
+
     #[test]
     fn channel_remove_recovers_from_invalid_channel_config() {
         let path = temp_file_path("autopilot-channel-remove-invalid");
@@ -4870,6 +6270,7 @@ app_token = "xapp-test"
         let schedule = AutopilotScheduleConfig {
             name: "demo".to_string(),
             cron: "*/5 * * * *".to_string(),
+            timezone: None,
             prompt: "hello".to_string(),
             check: None,
             trigger_on: Some(ScheduleTriggerOn::Failure),
@@ -5076,6 +6477,30 @@ prompt = "Existing"
         assert!(!help.contains("--channel <"));
     }
 
+    #[test]
+    fn schedule_add_requires_fields_unless_interactive() {
+        let command =
+            AutopilotScheduleCommands::augment_subcommands(clap::Command::new("schedule"));
+
+        assert!(
+            command
+                .clone()
+                .try_get_matches_from(["schedule", "add", "nightly", "--prompt", "Check"])
+                .is_err()
+        );
+        assert!(
+            command
+                .clone()
+                .try_get_matches_from(["schedule", "add", "--interactive"])
+                .is_ok()
+        );
+        assert!(
+            command
+                .try_get_matches_from(["schedule", "add", "nightly", "-i", "--cron", "0 2 * * *"])
+                .is_ok()
+        );
+    }
+
     #[test]
     fn schedule_crud_preserves_runtime_and_unknown_fields() {
         let path = temp_file_path("autopilot-schedule-preserve-fields");
@@ -5249,6 +6674,9 @@ api_key = "default-key"
                 token: None,
                 bot_token: None,
                 app_token: None,
+                whatsapp: Box::default(),
+                matrix: Box::default(),
+                rocketchat: Box::default(),
                 target: None,
                 profile: None,
             },
@@ -5538,6 +6966,11 @@ max_turns = 12
             model: None,
             auto_approve: None,
             profile: Some("ops".to_string()),
+            table_rendering: Default::default(),
+            block_format: Default::default(),
+            mentions: Default::default(),
+            templates: Default::default(),
+            cancel_reaction: None,
         });
 
         let profiles = gateway_cfg.channels.profiles_map();
@@ -5554,6 +6987,11 @@ max_turns = 12
             model: None,
             auto_approve: None,
             profile: None,
+            table_rendering: Default::default(),
+            block_format: Default::default(),
+            mentions: Default::default(),
+            templates: Default::default(),
+            cancel_reaction: None,
         });
 
         let profiles = gateway_channel_profiles_with_default(&gateway_cfg.channels, "default");
@@ -5569,6 +7007,11 @@ max_turns = 12
             model: None,
             auto_approve: None,
             profile: Some("ops".to_string()),
+            table_rendering: Default::default(),
+            block_format: Default::default(),
+            mentions: Default::default(),
+            templates: Default::default(),
+            cancel_reaction: None,
         });
 
         let profiles = gateway_channel_profiles_with_default(&gateway_cfg.channels, "default");
@@ -5609,6 +7052,97 @@ max_turns = 12
         );
     }
 
+    #[test]
+    fn test_approval_rules_fold_into_server_policy() {
+        let rules = stakpak_gateway::ApprovalRules {
+            allow: vec!["kubectl get *".to_string()],
+            require_approval: vec!["terraform apply".to_string()],
+            deny: vec!["rm -rf *".to_string()],
+        };
+        let command = |command: &str| serde_json::json!({ "command": command });
+
+        let all =
+            apply_approval_rules_to_server_policy(stakpak_server::ToolApprovalPolicy::All, &rules);
+        assert_eq!(
+            all.action_for("run_command", Some(&command("terraform apply"))),
+            stakpak_server::ToolApprovalAction::Ask
+        );
+        assert_eq!(
+            all.action_for("run_command", Some(&command("rm -rf /"))),
+            stakpak_server::ToolApprovalAction::Deny
+        );
+        assert_eq!(
+            all.action_for("create", None),
+            stakpak_server::ToolApprovalAction::Approve
+        );
+
+        let custom = apply_approval_rules_to_server_policy(
+            resolve_server_tool_policy(None, None, false),
+            &rules,
+        );
+        assert_eq!(
+            custom.action_for("run_command", Some(&command("kubectl get pods"))),
+            stakpak_server::ToolApprovalAction::Approve
+        );
+        assert_eq!(
+            custom.action_for("view", None),
+            stakpak_server::ToolApprovalAction::Approve
+        );
+
+        assert_eq!(
+            apply_approval_rules_to_server_policy(stakpak_server::ToolApprovalPolicy::None, &rules),
+            stakpak_server::ToolApprovalPolicy::None
+        );
+    }
+
+    #[test]
+    fn test_mcp_server_approvals_fold_into_server_policy() {
+        let approvals = stakpak_mcp_config::McpApprovalRules::new(
+            [(
+                "postgres".to_string(),
+                stakpak_mcp_config::McpServerApproval {
+                    default: Some(stakpak_mcp_config::McpApprovalAction::Ask),
+                    tools: [(
+                        "drop_table".to_string(),
+                        stakpak_mcp_config::McpApprovalAction::Deny,
+                    )]
+                    .into(),
+                },
+            )]
+            .into(),
+        );
+
+        let all = apply_mcp_approvals_to_server_policy(
+            stakpak_server::ToolApprovalPolicy::All,
+            &approvals,
+        );
+        assert_eq!(
+            all.action_for("postgres__query", None),
+            stakpak_server::ToolApprovalAction::Ask
+        );
+        assert_eq!(
+            all.action_for("postgres__drop_table", None),
+            stakpak_server::ToolApprovalAction::Deny
+        );
+        assert_eq!(
+            all.action_for("stakpak__create", None),
+            stakpak_server::ToolApprovalAction::Approve
+        );
+
+        let custom = apply_mcp_approvals_to_server_policy(
+            resolve_server_tool_policy(None, None, false),
+            &approvals,
+        );
+        assert_eq!(
+            custom.action_for("postgres__view", None),
+            stakpak_server::ToolApprovalAction::Ask
+        );
+        assert_eq!(
+            custom.action_for("stakpak__view", None),
+            stakpak_server::ToolApprovalAction::Approve
+        );
+    }
+
     #[test]
     fn test_gateway_gets_allow_all_when_auto_approve_all() {
         let policy = resolve_server_tool_policy(None, None, true);
@@ -5665,6 +7199,8 @@ max_turns = 12
                 running: true,
                 pid: Some(123),
                 stale_pid: false,
+                last_heartbeat: Some("2026-01-01T00:00:05Z".to_string()),
+                heartbeat_age_seconds: Some(5),
                 db_path: Some("/tmp/autopilot.db".to_string()),
                 error: None,
                 recent_runs: vec![ScheduleRunSummaryJson {
@@ -5935,6 +7471,83 @@ max_turns = 12
         args
     }
 
+    fn sample_exec_parts() -> Vec<String> {
+        [
+            "/usr/local/bin/stakpak",
+            "--profile",
+            "ops",
+            "autopilot",
+            "up",
+            "--foreground",
+            "--from-service",
+        ]
+        .iter()
+        .map(|part| part.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn parse_service_env_accepts_explicit_values_and_rejects_bad_names() {
+        assert_eq!(
+            parse_service_env("AWS_PROFILE=prod=east"),
+            Ok(("AWS_PROFILE".to_string(), "prod=east".to_string()))
+        );
+        assert!(parse_service_env("1BAD=value").is_err());
+        assert!(parse_service_env("BAD-NAME=value").is_err());
+        assert!(parse_service_env("MULTILINE=a\nb").is_err());
+        assert!(parse_service_env("STAKPAK_TEST_SURELY_UNSET_VARIABLE").is_err());
+    }
+
+    #[test]
+    fn systemd_unit_includes_env_passthrough_and_restart_policy() {
+        let options = ServiceUnitOptions {
+            env: vec![
+                ("PATH".to_string(), "/opt/bin:/usr/bin".to_string()),
+                ("API_TOKEN".to_string(), "a\"b%c".to_string()),
+            ],
+            restart: ServiceRestartPolicy::Always,
+        };
+        let unit = build_systemd_unit(&sample_exec_parts(), Path::new("/logs"), &options);
+
+        assert!(unit.contains(
+            "ExecStart=/usr/local/bin/stakpak --profile ops autopilot up --foreground --from-service\n"
+        ));
+        assert!(unit.contains("Restart=always\n"));
+        assert!(unit.contains("Environment=\"PATH=/opt/bin:/usr/bin\"\n"));
+        assert!(!unit.contains(SYSTEMD_DEFAULT_PATH));
+        assert!(unit.contains("Environment=\"API_TOKEN=a\\\"b%%c\"\n"));
+        assert!(unit.contains("StandardOutput=append:/logs/stdout.log\n"));
+
+        let default_unit = build_systemd_unit(
+            &sample_exec_parts(),
+            Path::new("/logs"),
+            &ServiceUnitOptions::default(),
+        );
+        assert!(default_unit.contains("Restart=on-failure\n"));
+        assert!(default_unit.contains(&format!("Environment=\"PATH={}\"", SYSTEMD_DEFAULT_PATH)));
+    }
+
+    #[test]
+    fn launchd_plist_escapes_env_and_maps_restart_policy() {
+        let options = ServiceUnitOptions {
+            env: vec![("GREETING".to_string(), "a<b&c".to_string())],
+            restart: ServiceRestartPolicy::Never,
+        };
+        let plist = build_launchd_plist(&sample_exec_parts(), Path::new("/logs"), &options);
+
+        assert!(plist.contains("<string>--profile</string>\n        <string>ops</string>"));
+        assert!(plist.contains("<key>GREETING</key>\n        <string>a&lt;b&amp;c</string>"));
+        assert!(plist.contains(&format!("<string>{}</string>", LAUNCHD_DEFAULT_PATH)));
+        assert!(plist.contains("<key>KeepAlive</key>\n    <false/>"));
+
+        let on_failure = build_launchd_plist(
+            &sample_exec_parts(),
+            Path::new("/logs"),
+            &ServiceUnitOptions::default(),
+        );
+        assert!(on_failure.contains("<key>SuccessfulExit</key>\n        <false/>"));
+    }
+
     #[test]
     fn build_systemd_exec_start_ignores_docker_group_wrapper_and_keeps_hardening() {
         let exec_cmd = "/usr/local/bin/stakpak autopilot up --foreground";
@@ -1075,7 +1075,13 @@ mod tests {
             anonymous_id: None,
             collect_telemetry: None,
             editor: None,
+            accessibility: None,
+            theme: None,
+            notifications: None,
+            discovery: None,
+            redaction: None,
             recent_models: Vec::new(),
+            local_endpoint: None,
         }
     }
 
@@ -0,0 +1,373 @@
+//! `stakpak autopilot schedule add --interactive`: prompts for each schedule
+//! field not given as a flag, previews the cron expression, and hands the
+//! schedule back to be written like any other `schedule add`.
+
+use std::io::IsTerminal;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use croner::Cron;
+
+use super::{
+    AutopilotScheduleConfig, ScheduleTriggerOn, build_schedule_toml_table,
+    load_gateway_config_allowing_no_channels, load_toml_root_table, schedule_name_from_value,
+};
+use crate::onboarding::menu::{prompt_text, prompt_yes_no, select_option_no_header};
+use crate::onboarding::navigation::NavResult;
+use crate::onboarding::styled_output::{
+    render_config_preview, render_error, render_info, render_title, render_warning,
+};
+
+/// Fire times shown when previewing a cron expression.
+const PREVIEW_FIRES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Name,
+    Cron,
+    Prompt,
+    Check,
+    Notifications,
+    Confirm,
+}
+
+/// A cron expression in words plus its next fire times.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CronPreview {
+    description: String,
+    next_fires: Vec<DateTime<Utc>>,
+}
+
+/// Walk through the fields of `schedule` left empty by the command line.
+/// Returns `None` when the user cancels or declines to save.
+pub(super) fn run_schedule_wizard(
+    mut schedule: AutopilotScheduleConfig,
+    config_path: &Path,
+) -> Result<Option<AutopilotScheduleConfig>, String> {
+    if !std::io::stdin().is_terminal() {
+        return Err("--interactive needs a terminal; pass --cron and --prompt instead".to_string());
+    }
+
+    let existing = existing_schedule_names(config_path)?;
+    let channels: Vec<String> = load_gateway_config_allowing_no_channels(config_path)
+        .map(|config| {
+            config
+                .enabled_channels()
+                .into_iter()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let steps = wizard_steps(&schedule);
+    render_title("New autopilot schedule");
+    render_info("Press Esc to go back a step.");
+
+    let mut index = 0;
+    while let Some(step) = steps.get(index).copied() {
+        let result = match step {
+            Step::Name => prompt_name(&existing),
+            Step::Cron => prompt_cron(schedule.timezone.as_deref()),
+            Step::Prompt => prompt_text("Prompt to run when the schedule fires", true),
+            Step::Check => prompt_check(),
+            Step::Notifications => prompt_notifications(&channels),
+            Step::Confirm => match confirm(&schedule) {
+                NavResult::Forward(save) => return Ok(save.then_some(schedule)),
+                NavResult::Back => NavResult::Back,
+                NavResult::Cancel => NavResult::Cancel,
+            },
+        };
+
+        match result {
+            NavResult::Forward(value) => {
+                match (step, value) {
+                    (Step::Name, Some(name)) => schedule.name = name,
+                    (Step::Cron, Some(cron)) => schedule.cron = cron,
+                    (Step::Prompt, Some(prompt)) => schedule.prompt = prompt,
+                    (Step::Check, Some(check)) => {
+                        let trigger_on = match schedule.trigger_on {
+                            Some(trigger_on) => trigger_on,
+                            None => match prompt_trigger_on() {
+                                NavResult::Forward(trigger_on) => trigger_on,
+                                NavResult::Back => continue,
+                                NavResult::Cancel => return Ok(None),
+                            },
+                        };
+                        schedule.check = Some(crate::commands::watch::CheckSpec::Script(check));
+                        schedule.trigger_on = Some(trigger_on);
+                    }
+                    (Step::Check, None) => schedule.check = None,
+                    (Step::Notifications, channel) => {
+                        schedule.notify_target = None;
+                        schedule.notify_channel = channel;
+                        if schedule.notify_channel.is_some() {
+                            match prompt_text(
+                                "Notification target (blank for the channel default)",
+                                false,
+                            ) {
+                                NavResult::Forward(target) => schedule.notify_target = target,
+                                NavResult::Back => continue,
+                                NavResult::Cancel => return Ok(None),
+                            }
+                        }
+                    }
+                    (Step::Confirm, _) => unreachable!("confirm returns before this match"),
+                    // A required prompt re-asks until it gets a value, and
+                    // name/cron validation repeats its own step.
+                    (Step::Name | Step::Cron | Step::Prompt, None) => continue,
+                }
+                index += 1;
+            }
+            NavResult::Back => index = index.saturating_sub(1),
+            NavResult::Cancel => return Ok(None),
+        }
+    }
+
+    Ok(Some(schedule))
+}
+
+/// The steps to ask, skipping fields already given as flags.
+fn wizard_steps(schedule: &AutopilotScheduleConfig) -> Vec<Step> {
+    let mut steps = Vec::new();
+    if schedule.name.trim().is_empty() {
+        steps.push(Step::Name);
+    }
+    if schedule.cron.trim().is_empty() {
+        steps.push(Step::Cron);
+    }
+    if schedule.prompt.trim().is_empty() {
+        steps.push(Step::Prompt);
+    }
+    if schedule.check.is_none() {
+        steps.push(Step::Check);
+    }
+    if schedule.notify_channel.is_none() && schedule.notify_target.is_none() {
+        steps.push(Step::Notifications);
+    }
+    steps.push(Step::Confirm);
+    steps
+}
+
+fn existing_schedule_names(config_path: &Path) -> Result<Vec<String>, String> {
+    let root = load_toml_root_table(config_path)?;
+    Ok(root
+        .get("schedules")
+        .and_then(toml::Value::as_array)
+        .map(|schedules| {
+            schedules
+                .iter()
+                .filter_map(schedule_name_from_value)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+fn prompt_name(existing: &[String]) -> NavResult<Option<String>> {
+    let result = prompt_text("Schedule name", true);
+    if let NavResult::Forward(Some(name)) = &result {
+        if name == crate::commands::watch::RELOAD_SENTINEL {
+            render_error(&format!("Schedule name '{}' is reserved", name));
+            return NavResult::Forward(None);
+        }
+        if existing.iter().any(|existing| existing == name) {
+            render_error(&format!("Schedule '{}' already exists", name));
+            return NavResult::Forward(None);
+        }
+    }
+    result
+}
+
+/// Ask for a cron expression until one parses and the user accepts its
+/// preview.
+fn prompt_cron(timezone: Option<&str>) -> NavResult<Option<String>> {
+    let cron = match prompt_text("Cron expression (e.g. 0 9 * * 1-5)", true) {
+        NavResult::Forward(Some(cron)) => cron,
+        other => return other,
+    };
+    let timezone: chrono_tz::Tz = timezone
+        .and_then(|timezone| timezone.parse().ok())
+        .unwrap_or(chrono_tz::Tz::UTC);
+
+    let preview = match cron_preview(&cron, timezone, Utc::now(), PREVIEW_FIRES) {
+        Ok(preview) => preview,
+        Err(error) => {
+            render_error(&error);
+            return NavResult::Forward(None);
+        }
+    };
+    render_info(&preview.description);
+    for fire in &preview.next_fires {
+        print!(
+            "    {}\r\n",
+            fire.with_timezone(&timezone).format("%a %Y-%m-%d %H:%M %Z")
+        );
+    }
+
+    match prompt_yes_no("Use this schedule?", true) {
+        NavResult::Forward(Some(false)) | NavResult::Back => NavResult::Forward(None),
+        NavResult::Forward(_) => NavResult::Forward(Some(cron)),
+        NavResult::Cancel => NavResult::Cancel,
+    }
+}
+
+fn cron_preview(
+    cron: &str,
+    timezone: chrono_tz::Tz,
+    after: DateTime<Utc>,
+    count: usize,
+) -> Result<CronPreview, String> {
+    let expression =
+        Cron::from_str(cron).map_err(|e| format!("Invalid cron expression '{}': {}", cron, e))?;
+    let next_fires: Vec<DateTime<Utc>> = expression
+        .iter_after(after.with_timezone(&timezone))
+        .take(count)
+        .map(|fire| fire.with_timezone(&Utc))
+        .collect();
+    if next_fires.is_empty() {
+        return Err(format!("Cron expression '{}' never fires", cron));
+    }
+
+    Ok(CronPreview {
+        description: expression.describe(),
+        next_fires,
+    })
+}
+
+fn prompt_check() -> NavResult<Option<String>> {
+    let result = prompt_text("Check script path (blank to run on every firing)", false);
+    if let NavResult::Forward(Some(path)) = &result
+        && !crate::commands::watch::config::expand_tilde(path).exists()
+    {
+        render_warning(&format!(
+            "'{}' does not exist yet; the schedule will fail its check until it does",
+            path
+        ));
+    }
+    result
+}
+
+fn prompt_trigger_on() -> NavResult<ScheduleTriggerOn> {
+    render_info("Run the agent when the check...");
+    select_option_no_header(
+        &[
+            (ScheduleTriggerOn::Failure, "fails (non-zero exit)", true),
+            (ScheduleTriggerOn::Success, "succeeds (exit 0)", false),
+            (ScheduleTriggerOn::Any, "finishes either way", false),
+        ],
+        true,
+    )
+}
+
+fn prompt_notifications(channels: &[String]) -> NavResult<Option<String>> {
+    if channels.is_empty() {
+        render_info("No channels configured; results use the default notification route.");
+        return NavResult::Forward(None);
+    }
+
+    render_info("Send results to...");
+    let mut options: Vec<(Option<String>, &str, bool)> =
+        vec![(None, "the default notification route", true)];
+    options.extend(
+        channels
+            .iter()
+            .map(|channel| (Some(channel.clone()), channel.as_str(), false)),
+    );
+    select_option_no_header(&options, true)
+}
+
+fn confirm(schedule: &AutopilotScheduleConfig) -> NavResult<bool> {
+    let mut root = toml::value::Table::new();
+    root.insert(
+        "schedules".to_string(),
+        toml::Value::Array(vec![toml::Value::Table(build_schedule_toml_table(
+            schedule,
+        ))]),
+    );
+    render_config_preview(&toml::to_string(&root).unwrap_or_default());
+
+    match prompt_yes_no("Save this schedule?", true) {
+        NavResult::Forward(save) => NavResult::Forward(save.unwrap_or(true)),
+        NavResult::Back => NavResult::Back,
+        NavResult::Cancel => NavResult::Cancel,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn schedule() -> AutopilotScheduleConfig {
+        AutopilotScheduleConfig {
+            name: String::new(),
+            cron: String::new(),
+            timezone: None,
+            prompt: String::new(),
+            check: None,
+            trigger_on: None,
+            max_turns: None,
+            notify_channel: None,
+            notify_target: None,
+            legacy_notify_chat_id: None,
+            legacy_channel: None,
+            profile: None,
+            pause_on_approval: false,
+            sandbox: false,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn wizard_skips_fields_given_as_flags() {
+        assert_eq!(
+            wizard_steps(&schedule()),
+            vec![
+                Step::Name,
+                Step::Cron,
+                Step::Prompt,
+                Step::Check,
+                Step::Notifications,
+                Step::Confirm,
+            ]
+        );
+
+        let mut partial = schedule();
+        partial.name = "nightly".to_string();
+        partial.cron = "0 2 * * *".to_string();
+        partial.notify_channel = Some("slack".to_string());
+        assert_eq!(
+            wizard_steps(&partial),
+            vec![Step::Prompt, Step::Check, Step::Confirm]
+        );
+    }
+
+    #[test]
+    fn cron_preview_lists_next_fires_in_the_schedule_time_zone() {
+        let after = Utc.with_ymd_and_hms(2026, 3, 6, 12, 0, 0).unwrap();
+        let preview = cron_preview("0 9 * * 1-5", chrono_tz::Tz::UTC, after, 3).expect("preview");
+        assert_eq!(
+            preview.next_fires,
+            vec![
+                Utc.with_ymd_and_hms(2026, 3, 9, 9, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 3, 10, 9, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 3, 11, 9, 0, 0).unwrap(),
+            ]
+        );
+        assert!(!preview.description.is_empty());
+
+        let berlin =
+            cron_preview("0 9 * * *", chrono_tz::Europe::Berlin, after, 1).expect("preview");
+        assert_eq!(
+            berlin.next_fires,
+            vec![Utc.with_ymd_and_hms(2026, 3, 7, 8, 0, 0).unwrap()]
+        );
+
+        assert!(
+            cron_preview("not a cron", chrono_tz::Tz::UTC, after, 5)
+                .expect_err("invalid cron")
+                .contains("Invalid cron expression")
+        );
+    }
+}
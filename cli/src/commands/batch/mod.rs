@@ -0,0 +1,455 @@
+//! `stakpak batch` — run a queue of prompts from a file.
+//!
+//! Each prompt runs as its own `stakpak --async` child process, so it gets a
+//! fresh session and its own working directory. Output for every prompt is
+//! written to `<output-dir>/<id>/` and a `summary.json` is written once the
+//! batch finishes.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use serde::Serialize;
+use stakpak_shared::models::async_manifest::AsyncManifest;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::commands::agent::run::pause::EXIT_CODE_PAUSED;
+use crate::config::AppConfig;
+
+pub mod spec;
+
+use spec::{BatchSpec, ResolvedPrompt};
+
+#[derive(Subcommand, PartialEq)]
+pub enum BatchCommands {
+    /// Run every prompt in a batch file, each in its own session.
+    Run {
+        /// Path to the batch file (YAML)
+        file: PathBuf,
+
+        /// Directory for per-prompt results (default: ./batch-results/<file>-<timestamp>)
+        #[arg(short = 'o', long)]
+        output_dir: Option<PathBuf>,
+
+        /// Maximum prompts to run at once (overrides `parallelism` in the file)
+        #[arg(short = 'j', long)]
+        parallel: Option<usize>,
+
+        /// Validate the file and print the resolved prompts without running them
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
+}
+
+impl BatchCommands {
+    pub async fn run(self, config: AppConfig) -> Result<(), String> {
+        match self {
+            BatchCommands::Run {
+                file,
+                output_dir,
+                parallel,
+                dry_run,
+            } => run_batch(&config, &file, output_dir, parallel, dry_run).await,
+        }
+    }
+}
+
+/// Final state of a single prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PromptStatus {
+    Completed,
+    Paused,
+    Failed,
+    TimedOut,
+}
+
+impl std::fmt::Display for PromptStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromptStatus::Completed => write!(f, "completed"),
+            PromptStatus::Paused => write!(f, "paused"),
+            PromptStatus::Failed => write!(f, "failed"),
+            PromptStatus::TimedOut => write!(f, "timed out"),
+        }
+    }
+}
+
+/// Per-prompt record written to `result.json` and collected into `summary.json`.
+#[derive(Debug, Clone, Serialize)]
+struct PromptResult {
+    id: String,
+    status: PromptStatus,
+    exit_code: Option<i32>,
+    session_id: Option<String>,
+    workdir: Option<String>,
+    started_at: DateTime<Utc>,
+    finished_at: DateTime<Utc>,
+    duration_secs: u64,
+    error: Option<String>,
+}
+
+/// Everything a child process needs besides the prompt itself.
+#[derive(Debug, Clone)]
+struct ChildContext {
+    executable: PathBuf,
+    config_path: Option<String>,
+    default_profile: String,
+    output_dir: PathBuf,
+}
+
+async fn run_batch(
+    config: &AppConfig,
+    file: &Path,
+    output_dir: Option<PathBuf>,
+    parallel: Option<usize>,
+    dry_run: bool,
+) -> Result<(), String> {
+    let spec = BatchSpec::load(file)?;
+    let base_dir = file
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let prompts = spec.resolve(&base_dir)?;
+
+    let parallelism = parallel.or(spec.parallelism).unwrap_or(1);
+    if parallelism == 0 {
+        return Err("Parallelism must be at least 1".to_string());
+    }
+
+    if dry_run {
+        print_plan(&prompts, parallelism);
+        return Ok(());
+    }
+
+    let output_dir = output_dir.unwrap_or_else(|| default_output_dir(file));
+    std::fs::create_dir_all(&output_dir).map_err(|e| {
+        format!(
+            "Failed to create output directory '{}': {}",
+            output_dir.display(),
+            e
+        )
+    })?;
+
+    let context = Arc::new(ChildContext {
+        executable: std::env::current_exe()
+            .map_err(|e| format!("Failed to locate stakpak executable: {}", e))?,
+        config_path: (!config.config_path.is_empty()).then(|| config.config_path.clone()),
+        default_profile: config.profile_name.clone(),
+        output_dir: output_dir.clone(),
+    });
+
+    let total = prompts.len();
+    println!(
+        "Running {} prompt(s) with parallelism {} → {}",
+        total,
+        parallelism,
+        output_dir.display()
+    );
+
+    let semaphore = Arc::new(Semaphore::new(parallelism));
+    let mut tasks = JoinSet::new();
+    for prompt in prompts {
+        let semaphore = Arc::clone(&semaphore);
+        let context = Arc::clone(&context);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            run_prompt(&context, prompt).await
+        });
+    }
+
+    let mut results = Vec::with_capacity(total);
+    while let Some(joined) = tasks.join_next().await {
+        let result = joined.map_err(|e| format!("Batch task failed: {}", e))?;
+        println!(
+            "[{}/{}] {:<24} {} ({})",
+            results.len() + 1,
+            total,
+            result.id,
+            format_status(result.status),
+            format_duration(result.duration_secs)
+        );
+        if let Some(error) = &result.error {
+            println!("       \x1b[31m{}\x1b[0m", error);
+        }
+        results.push(result);
+    }
+    results.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let summary_path = output_dir.join("summary.json");
+    write_json(&summary_path, &results)?;
+
+    let count = |status: PromptStatus| results.iter().filter(|r| r.status == status).count();
+    let failed = count(PromptStatus::Failed) + count(PromptStatus::TimedOut);
+    println!();
+    println!(
+        "Done: {} completed, {} paused, {} failed. Summary: {}",
+        count(PromptStatus::Completed),
+        count(PromptStatus::Paused),
+        failed,
+        summary_path.display()
+    );
+
+    if failed > 0 {
+        return Err(format!("{} of {} prompt(s) failed", failed, total));
+    }
+    Ok(())
+}
+
+async fn run_prompt(context: &ChildContext, prompt: ResolvedPrompt) -> PromptResult {
+    let started_at = Utc::now();
+    let started = Instant::now();
+    let prompt_dir = context.output_dir.join(&prompt.id);
+
+    let outcome = execute_prompt(context, &prompt, &prompt_dir).await;
+    let (status, exit_code, error) = match outcome {
+        Ok(Some(code)) if code == 0 => (PromptStatus::Completed, Some(code), None),
+        Ok(Some(code)) if code == EXIT_CODE_PAUSED => (PromptStatus::Paused, Some(code), None),
+        Ok(Some(code)) => (
+            PromptStatus::Failed,
+            Some(code),
+            Some(format!("exited with code {} (see stderr.log)", code)),
+        ),
+        Ok(None) => (
+            PromptStatus::TimedOut,
+            None,
+            Some(format!(
+                "timed out after {}",
+                format_duration(prompt.timeout.as_secs())
+            )),
+        ),
+        Err(e) => (PromptStatus::Failed, None, Some(e)),
+    };
+
+    let session_id = std::fs::read_to_string(prompt_dir.join("stdout.log"))
+        .ok()
+        .and_then(|stdout| AsyncManifest::try_parse(&stdout))
+        .and_then(|manifest| manifest.session_id);
+
+    let result = PromptResult {
+        id: prompt.id.clone(),
+        status,
+        exit_code,
+        session_id,
+        workdir: prompt.workdir.as_ref().map(|dir| dir.display().to_string()),
+        started_at,
+        finished_at: Utc::now(),
+        duration_secs: started.elapsed().as_secs(),
+        error,
+    };
+
+    if let Err(e) = write_json(&prompt_dir.join("result.json"), &result) {
+        tracing::warn!(prompt = %prompt.id, error = %e, "Failed to write batch result");
+    }
+    result
+}
+
+/// Spawn the child process for one prompt. Returns the exit code, or `None`
+/// when the prompt timed out and was killed.
+async fn execute_prompt(
+    context: &ChildContext,
+    prompt: &ResolvedPrompt,
+    prompt_dir: &Path,
+) -> Result<Option<i32>, String> {
+    std::fs::create_dir_all(prompt_dir).map_err(|e| {
+        format!(
+            "Failed to create result directory '{}': {}",
+            prompt_dir.display(),
+            e
+        )
+    })?;
+
+    let prompt_file = prompt_dir.join("prompt.md");
+    std::fs::write(&prompt_file, &prompt.prompt)
+        .map_err(|e| format!("Failed to write prompt file: {}", e))?;
+    let stdout = std::fs::File::create(prompt_dir.join("stdout.log"))
+        .map_err(|e| format!("Failed to create stdout.log: {}", e))?;
+    let stderr = std::fs::File::create(prompt_dir.join("stderr.log"))
+        .map_err(|e| format!("Failed to create stderr.log: {}", e))?;
+
+    let mut command = tokio::process::Command::new(&context.executable);
+    command
+        .args(child_args(context, prompt, &prompt_file))
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(stdout))
+        .stderr(Stdio::from(stderr))
+        .kill_on_drop(true);
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to start agent: {}", e))?;
+
+    match tokio::time::timeout(prompt.timeout, child.wait()).await {
+        Ok(status) => {
+            let status = status.map_err(|e| format!("Failed to wait for agent: {}", e))?;
+            Ok(Some(status.code().unwrap_or(-1)))
+        }
+        Err(_) => {
+            let _ = child.kill().await;
+            Ok(None)
+        }
+    }
+}
+
+fn child_args(context: &ChildContext, prompt: &ResolvedPrompt, prompt_file: &Path) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(config_path) = &context.config_path {
+        args.extend(["--config".to_string(), config_path.clone()]);
+    }
+    args.extend([
+        "--profile".to_string(),
+        prompt
+            .profile
+            .clone()
+            .unwrap_or_else(|| context.default_profile.clone()),
+        "--async".to_string(),
+        "--output".to_string(),
+        "json".to_string(),
+        "--prompt-file".to_string(),
+        prompt_file.display().to_string(),
+    ]);
+    if let Some(workdir) = &prompt.workdir {
+        args.extend(["--workdir".to_string(), workdir.display().to_string()]);
+    }
+    if let Some(model) = &prompt.model {
+        args.extend(["--model".to_string(), model.clone()]);
+    }
+    if let Some(max_steps) = prompt.max_steps {
+        args.extend(["--max-steps".to_string(), max_steps.to_string()]);
+    }
+    if let Some(system_prompt_file) = &prompt.system_prompt_file {
+        args.extend([
+            "--system-prompt-file".to_string(),
+            system_prompt_file.display().to_string(),
+        ]);
+    }
+    for tool in prompt.allowed_tools.iter().flatten() {
+        args.extend(["--tool".to_string(), tool.clone()]);
+    }
+    if prompt.pause_on_approval {
+        args.push("--pause-on-approval".to_string());
+    }
+    args
+}
+
+fn print_plan(prompts: &[ResolvedPrompt], parallelism: usize) {
+    println!(
+        "{} prompt(s), parallelism {}:\n",
+        prompts.len(),
+        parallelism
+    );
+    for prompt in prompts {
+        let first_line = prompt.prompt.lines().next().unwrap_or_default();
+        println!("  \x1b[1m{}\x1b[0m", prompt.id);
+        println!("    Prompt:  {}", truncate(first_line, 70));
+        if let Some(workdir) = &prompt.workdir {
+            println!("    Workdir: {}", workdir.display());
+        }
+        if let Some(profile) = &prompt.profile {
+            println!("    Profile: {}", profile);
+        }
+        if let Some(model) = &prompt.model {
+            println!("    Model:   {}", model);
+        }
+        println!("    Timeout: {}", format_duration(prompt.timeout.as_secs()));
+    }
+}
+
+fn default_output_dir(file: &Path) -> PathBuf {
+    let stem = file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "batch".to_string());
+    PathBuf::from("batch-results").join(format!("{}-{}", stem, Utc::now().format("%Y%m%d-%H%M%S")))
+}
+
+fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    std::fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn format_status(status: PromptStatus) -> String {
+    match status {
+        PromptStatus::Completed => "\x1b[32mcompleted\x1b[0m".to_string(),
+        PromptStatus::Paused => "\x1b[33mpaused\x1b[0m".to_string(),
+        PromptStatus::Failed => "\x1b[31mfailed\x1b[0m".to_string(),
+        PromptStatus::TimedOut => "\x1b[31mtimed out\x1b[0m".to_string(),
+    }
+}
+
+fn format_duration(total_secs: u64) -> String {
+    let duration = Duration::from_secs(total_secs);
+    let minutes = duration.as_secs() / 60;
+    let seconds = duration.as_secs() % 60;
+    if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+fn truncate(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt() -> ResolvedPrompt {
+        ResolvedPrompt {
+            id: "repo-a".to_string(),
+            prompt: "Update CI".to_string(),
+            profile: None,
+            model: Some("claude-haiku-4-5".to_string()),
+            max_steps: Some(10),
+            workdir: Some(PathBuf::from("/src/repo-a")),
+            system_prompt_file: None,
+            allowed_tools: Some(vec!["view".to_string(), "str_replace".to_string()]),
+            timeout: Duration::from_secs(60),
+            pause_on_approval: true,
+        }
+    }
+
+    #[test]
+    fn test_child_args_carry_resolved_settings() {
+        let context = ChildContext {
+            executable: PathBuf::from("stakpak"),
+            config_path: Some("/etc/stakpak.toml".to_string()),
+            default_profile: "default".to_string(),
+            output_dir: PathBuf::from("/out"),
+        };
+
+        let args = child_args(&context, &prompt(), Path::new("/out/repo-a/prompt.md"));
+        let joined = args.join(" ");
+
+        assert!(joined.starts_with("--config /etc/stakpak.toml --profile default --async"));
+        assert!(joined.contains("--prompt-file /out/repo-a/prompt.md"));
+        assert!(joined.contains("--workdir /src/repo-a"));
+        assert!(joined.contains("--model claude-haiku-4-5"));
+        assert!(joined.contains("--max-steps 10"));
+        assert!(joined.contains("--tool view --tool str_replace"));
+        assert!(joined.ends_with("--pause-on-approval"));
+    }
+
+    #[test]
+    fn test_default_output_dir_uses_file_stem() {
+        let dir = default_output_dir(Path::new("migrations/ci-update.yaml"));
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        assert!(dir.starts_with("batch-results"));
+        assert!(name.starts_with("ci-update-"));
+    }
+}
@@ -0,0 +1,366 @@
+//! Batch file parsing and per-prompt setting resolution.
+//!
+//! A batch file is YAML with shared `defaults` and a list of `prompts`:
+//!
+//! ```yaml
+//! parallelism: 4
+//! defaults:
+//!   profile: default
+//!   max_steps: 40
+//!   timeout: 30m
+//! prompts:
+//!   - id: repo-a
+//!     workdir: ~/src/repo-a
+//!     prompt: Update the CI config to use the new runner image
+//!   - id: repo-b
+//!     workdir: ~/src/repo-b
+//!     prompt_file: prompts/ci-update.md
+//! ```
+//!
+//! Relative paths are resolved against the directory containing the batch file.
+
+use crate::commands::watch::expand_tilde;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default per-prompt timeout when neither the prompt nor `defaults` set one.
+pub const DEFAULT_PROMPT_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Settings shared by every prompt unless the prompt overrides them.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchSettings {
+    /// Config profile used for the session.
+    pub profile: Option<String>,
+    /// Model override.
+    pub model: Option<String>,
+    /// Maximum agent steps.
+    pub max_steps: Option<usize>,
+    /// Working directory the agent runs in.
+    pub workdir: Option<String>,
+    /// File whose contents replace the system prompt.
+    pub system_prompt_file: Option<String>,
+    /// Restrict the agent to these tools.
+    pub allowed_tools: Option<Vec<String>>,
+    /// Kill the session after this long (e.g. `30m`).
+    #[serde(default, with = "humantime_serde")]
+    pub timeout: Option<Duration>,
+    /// Pause instead of auto-approving tools that require approval.
+    pub pause_on_approval: Option<bool>,
+}
+
+impl BatchSettings {
+    fn or(&self, defaults: &BatchSettings) -> BatchSettings {
+        BatchSettings {
+            profile: self.profile.clone().or_else(|| defaults.profile.clone()),
+            model: self.model.clone().or_else(|| defaults.model.clone()),
+            max_steps: self.max_steps.or(defaults.max_steps),
+            workdir: self.workdir.clone().or_else(|| defaults.workdir.clone()),
+            system_prompt_file: self
+                .system_prompt_file
+                .clone()
+                .or_else(|| defaults.system_prompt_file.clone()),
+            allowed_tools: self
+                .allowed_tools
+                .clone()
+                .or_else(|| defaults.allowed_tools.clone()),
+            timeout: self.timeout.or(defaults.timeout),
+            pause_on_approval: self.pause_on_approval.or(defaults.pause_on_approval),
+        }
+    }
+}
+
+/// One entry of `prompts`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchPrompt {
+    /// Identifier used for the result directory. Defaults to `prompt-NNN`;
+    /// may not start with `.` or be `summary.json`.
+    pub id: Option<String>,
+    /// Inline prompt text.
+    pub prompt: Option<String>,
+    /// File containing the prompt text.
+    pub prompt_file: Option<String>,
+    pub profile: Option<String>,
+    pub model: Option<String>,
+    pub max_steps: Option<usize>,
+    pub workdir: Option<String>,
+    pub system_prompt_file: Option<String>,
+    pub allowed_tools: Option<Vec<String>>,
+    #[serde(default, with = "humantime_serde")]
+    pub timeout: Option<Duration>,
+    pub pause_on_approval: Option<bool>,
+}
+
+impl BatchPrompt {
+    /// Settings set on this prompt, before falling back to `defaults`.
+    fn settings(&self) -> BatchSettings {
+        BatchSettings {
+            profile: self.profile.clone(),
+            model: self.model.clone(),
+            max_steps: self.max_steps,
+            workdir: self.workdir.clone(),
+            system_prompt_file: self.system_prompt_file.clone(),
+            allowed_tools: self.allowed_tools.clone(),
+            timeout: self.timeout,
+            pause_on_approval: self.pause_on_approval,
+        }
+    }
+}
+
+/// Top-level batch file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchSpec {
+    /// How many prompts may run at once. Defaults to 1 (sequential).
+    pub parallelism: Option<usize>,
+    #[serde(default)]
+    pub defaults: BatchSettings,
+    pub prompts: Vec<BatchPrompt>,
+}
+
+/// A prompt with all settings resolved and paths made absolute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedPrompt {
+    pub id: String,
+    pub prompt: String,
+    pub profile: Option<String>,
+    pub model: Option<String>,
+    pub max_steps: Option<usize>,
+    pub workdir: Option<PathBuf>,
+    pub system_prompt_file: Option<PathBuf>,
+    pub allowed_tools: Option<Vec<String>>,
+    pub timeout: Duration,
+    pub pause_on_approval: bool,
+}
+
+impl BatchSpec {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read batch file '{}': {}", path.display(), e))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        serde_yaml::from_str(content).map_err(|e| format!("Invalid batch file: {}", e))
+    }
+
+    /// Resolve every prompt against `defaults`, reading prompt files and
+    /// resolving relative paths against `base_dir`.
+    pub fn resolve(&self, base_dir: &Path) -> Result<Vec<ResolvedPrompt>, String> {
+        if self.prompts.is_empty() {
+            return Err("Batch file has no prompts".to_string());
+        }
+
+        let mut seen_ids = HashSet::new();
+        let mut resolved = Vec::with_capacity(self.prompts.len());
+
+        for (index, entry) in self.prompts.iter().enumerate() {
+            let id = match entry.id.as_deref().map(str::trim) {
+                Some(id) if !id.is_empty() => sanitize_id(id)?,
+                _ => format!("prompt-{:03}", index + 1),
+            };
+            if !seen_ids.insert(id.clone()) {
+                return Err(format!("Duplicate prompt id '{}'", id));
+            }
+
+            let prompt = match (&entry.prompt, &entry.prompt_file) {
+                (Some(_), Some(_)) => {
+                    return Err(format!(
+                        "Prompt '{}' sets both 'prompt' and 'prompt_file'",
+                        id
+                    ));
+                }
+                (Some(text), None) => text.trim().to_string(),
+                (None, Some(file)) => {
+                    let path = resolve_path(base_dir, file);
+                    std::fs::read_to_string(&path)
+                        .map_err(|e| {
+                            format!(
+                                "Prompt '{}': failed to read prompt_file '{}': {}",
+                                id,
+                                path.display(),
+                                e
+                            )
+                        })?
+                        .trim()
+                        .to_string()
+                }
+                (None, None) => {
+                    return Err(format!(
+                        "Prompt '{}' needs either 'prompt' or 'prompt_file'",
+                        id
+                    ));
+                }
+            };
+            if prompt.is_empty() {
+                return Err(format!("Prompt '{}' is empty", id));
+            }
+
+            let settings = entry.settings().or(&self.defaults);
+            resolved.push(ResolvedPrompt {
+                id,
+                prompt,
+                profile: settings.profile,
+                model: settings.model,
+                max_steps: settings.max_steps,
+                workdir: settings
+                    .workdir
+                    .as_deref()
+                    .map(|dir| resolve_path(base_dir, dir)),
+                system_prompt_file: settings
+                    .system_prompt_file
+                    .as_deref()
+                    .map(|file| resolve_path(base_dir, file)),
+                allowed_tools: settings.allowed_tools,
+                timeout: settings.timeout.unwrap_or(DEFAULT_PROMPT_TIMEOUT),
+                pause_on_approval: settings.pause_on_approval.unwrap_or(false),
+            });
+        }
+
+        Ok(resolved)
+    }
+}
+
+fn resolve_path(base_dir: &Path, value: &str) -> PathBuf {
+    let expanded = expand_tilde(value);
+    if expanded.is_absolute() {
+        expanded
+    } else {
+        base_dir.join(expanded)
+    }
+}
+
+/// File the batch writes next to the per-prompt directories.
+const RESERVED_IDS: &[&str] = &["summary.json"];
+
+/// Keep ids usable as directory names inside the output directory: `..`
+/// and other dot-leading ids would escape it or hide, and reserved names
+/// would collide with the batch's own files.
+fn sanitize_id(id: &str) -> Result<String, String> {
+    let sanitized: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    if sanitized.is_empty() || sanitized.starts_with('.') {
+        return Err(format!(
+            "Prompt id '{}' must not be empty or start with '.'",
+            id
+        ));
+    }
+    if RESERVED_IDS
+        .iter()
+        .any(|reserved| sanitized.eq_ignore_ascii_case(reserved))
+    {
+        return Err(format!("Prompt id '{}' is reserved", id));
+    }
+    Ok(sanitized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_settings_override_defaults() {
+        let spec = BatchSpec::parse(
+            r#"
+defaults:
+  profile: ops
+  max_steps: 20
+  timeout: 10m
+prompts:
+  - id: repo-a
+    workdir: /src/repo-a
+    prompt: Update CI
+  - prompt: Update docs
+    profile: docs
+    timeout: 5m
+"#,
+        )
+        .expect("batch file should parse");
+
+        let resolved = spec.resolve(Path::new("/batches")).expect("should resolve");
+        assert_eq!(resolved.len(), 2);
+
+        assert_eq!(resolved[0].id, "repo-a");
+        assert_eq!(resolved[0].profile.as_deref(), Some("ops"));
+        assert_eq!(resolved[0].workdir, Some(PathBuf::from("/src/repo-a")));
+        assert_eq!(resolved[0].timeout, Duration::from_secs(600));
+
+        assert_eq!(resolved[1].id, "prompt-002");
+        assert_eq!(resolved[1].profile.as_deref(), Some("docs"));
+        assert_eq!(resolved[1].max_steps, Some(20));
+        assert_eq!(resolved[1].timeout, Duration::from_secs(300));
+    }
+
+    #[test]
+    fn test_relative_paths_resolve_against_batch_dir() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("ci.md"), "  Bump the runner image  \n")
+            .expect("write prompt file");
+
+        let spec = BatchSpec::parse(
+            r#"
+prompts:
+  - id: "repo a"
+    workdir: repos/a
+    prompt_file: ci.md
+"#,
+        )
+        .expect("batch file should parse");
+
+        let resolved = spec.resolve(dir.path()).expect("should resolve");
+        assert_eq!(resolved[0].id, "repo-a");
+        assert_eq!(resolved[0].prompt, "Bump the runner image");
+        assert_eq!(resolved[0].workdir, Some(dir.path().join("repos/a")));
+        assert_eq!(resolved[0].timeout, DEFAULT_PROMPT_TIMEOUT);
+    }
+
+    #[test]
+    fn test_invalid_prompts_are_rejected() {
+        let duplicate = BatchSpec::parse(
+            r#"
+prompts:
+  - id: a
+    prompt: one
+  - id: a
+    prompt: two
+"#,
+        )
+        .expect("batch file should parse");
+        assert!(duplicate.resolve(Path::new("/tmp")).is_err());
+
+        let missing = BatchSpec::parse(
+            r#"
+prompts:
+  - id: a
+"#,
+        )
+        .expect("batch file should parse");
+        assert!(missing.resolve(Path::new("/tmp")).is_err());
+
+        for id in ["..", ".", ".hidden", "summary.json", "Summary.JSON"] {
+            let spec = BatchSpec::parse(&format!("prompts:\n  - id: '{id}'\n    prompt: one\n"))
+                .expect("batch file should parse");
+            assert!(spec.resolve(Path::new("/tmp")).is_err(), "{id}");
+        }
+        assert_eq!(
+            sanitize_id("deploy v1.2/eu"),
+            Ok("deploy-v1.2-eu".to_string())
+        );
+
+        assert!(BatchSpec::parse("prompts: []\nunknown: 1\n").is_err());
+        let empty = BatchSpec::parse("prompts: []\n").expect("batch file should parse");
+        assert!(empty.resolve(Path::new("/tmp")).is_err());
+    }
+}
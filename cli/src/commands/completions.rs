@@ -0,0 +1,246 @@
+//! Shell completion scripts.
+//!
+//! `stakpak completions <shell>` prints the clap-generated script. For bash,
+//! zsh and fish it appends a small wrapper that hands the command line to the
+//! hidden `stakpak complete-words` helper first: when the word under the
+//! cursor is a schedule name or a session ID the helper prints candidates
+//! (one per line, `value<TAB>description`) and exits 0, otherwise it exits 1
+//! and the static completion runs as usual. The helper is dispatched from
+//! `main` before clap parses the command line, since the generators list
+//! hidden subcommands too.
+
+use std::io::Write;
+
+use clap::{Arg, Command, CommandFactory};
+use clap_complete::Shell;
+
+/// First argument that runs the helper: `stakpak complete-words -- <words>`
+pub const HELPER: &str = "complete-words";
+
+/// Recent sessions offered for a session ID
+const SESSION_CANDIDATES: u32 = 50;
+
+const BASH_DYNAMIC: &str = r#"
+_stakpak_dynamic() {
+    local candidates line
+    if candidates="$(stakpak complete-words -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null)"; then
+        COMPREPLY=()
+        while IFS= read -r line; do
+            [[ -n "$line" ]] && COMPREPLY+=("${line%%$'\t'*}")
+        done <<< "$candidates"
+        return 0
+    fi
+    _stakpak "$@"
+}
+
+complete -F _stakpak_dynamic -o bashdefault -o default stakpak
+"#;
+
+const ZSH_DYNAMIC: &str = r#"
+_stakpak_dynamic() {
+    local output
+    local -a candidates
+    if output="$(stakpak complete-words -- "${(@)words[2,CURRENT]}" 2>/dev/null)"; then
+        [[ -n "$output" ]] || return 1
+        candidates=("${(@f)output}")
+        candidates=("${(@)candidates//:/\\:}")
+        candidates=("${(@)candidates//$'\t'/:}")
+        _describe 'value' candidates
+        return
+    fi
+    _stakpak "$@"
+}
+
+compdef _stakpak_dynamic stakpak
+"#;
+
+const FISH_DYNAMIC: &str = r#"
+function __stakpak_dynamic
+    stakpak complete-words -- (commandline -opc)[2..-1] (commandline -ct) 2>/dev/null
+end
+
+complete -c stakpak -f -n '__stakpak_dynamic >/dev/null' -a '(__stakpak_dynamic)'
+"#;
+
+/// Values the helper can complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynamicValue {
+    Schedule,
+    Session,
+}
+
+/// Write the completion script for `shell` to `out`.
+pub fn write_script(shell: Shell, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut buffer = Vec::new();
+    clap_complete::generate(shell, &mut crate::Cli::command(), "stakpak", &mut buffer);
+    out.write_all(&buffer)?;
+    let dynamic = match shell {
+        Shell::Bash => BASH_DYNAMIC,
+        Shell::Zsh => ZSH_DYNAMIC,
+        Shell::Fish => FISH_DYNAMIC,
+        _ => return Ok(()),
+    };
+    out.write_all(dynamic.as_bytes())
+}
+
+/// The value the last of `words` (the arguments after `stakpak`, ending
+/// with the word being completed) stands for, when it is dynamic.
+pub fn dynamic_value(words: &[String]) -> Option<DynamicValue> {
+    let (current, before) = words.split_last()?;
+    let root = crate::Cli::command();
+    let mut commands: Vec<&Command> = vec![&root];
+    let mut path: Vec<&str> = Vec::new();
+    let mut positionals = 0;
+    let mut pending_value: Option<&Arg> = None;
+
+    for word in before {
+        if pending_value.take().is_some() {
+            continue;
+        }
+        if word == "--" {
+            return None;
+        }
+        if let Some(long) = word.strip_prefix("--") {
+            if !long.contains('=') {
+                pending_value = find_flag(&commands, |arg| arg.get_long() == Some(long));
+            }
+        } else if let Some(short) = word.strip_prefix('-').filter(|s| !s.is_empty()) {
+            let mut chars = short.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                pending_value = find_flag(&commands, |arg| arg.get_short() == Some(c));
+            }
+        } else if let Some(sub) = commands
+            .last()
+            .and_then(|command| command.find_subcommand(word))
+        {
+            path.push(sub.get_name());
+            commands.push(sub);
+            positionals = 0;
+        } else {
+            positionals += 1;
+        }
+    }
+
+    let arg = match pending_value {
+        Some(arg) => arg,
+        None if current.starts_with('-') => return None,
+        None => commands.last()?.get_positionals().nth(positionals)?,
+    };
+    value_for(&path, arg.get_id().as_str())
+}
+
+/// Flags are looked up in the innermost subcommand first, then outwards.
+fn find_flag<'a>(commands: &[&'a Command], matches: impl Fn(&Arg) -> bool) -> Option<&'a Arg> {
+    commands
+        .iter()
+        .rev()
+        .find_map(|command| command.get_arguments().find(|arg| matches(arg)))
+        .filter(|arg| arg.get_action().takes_values())
+}
+
+fn value_for(path: &[&str], arg_id: &str) -> Option<DynamicValue> {
+    match (path, arg_id) {
+        ([], "session_id") | (["sessions", "show" | "checkpoints" | "resume"], "id") => {
+            Some(DynamicValue::Session)
+        }
+        (
+            [
+                "autopilot",
+                "schedule",
+                "remove" | "enable" | "disable" | "history" | "trigger" | "test",
+            ],
+            "name",
+        )
+        | (["autopilot", "run"], "name")
+        | (["autopilot", "runs"], "schedule") => Some(DynamicValue::Schedule),
+        _ => None,
+    }
+}
+
+/// `stakpak complete-words`: print candidates for the last word and return true,
+/// or return false when the word isn't a schedule name or session ID.
+pub async fn run_helper(words: &[String]) -> bool {
+    let Some(value) = dynamic_value(words) else {
+        return false;
+    };
+    let candidates = match value {
+        DynamicValue::Schedule => crate::commands::autopilot::local_schedule_names(),
+        DynamicValue::Session => {
+            crate::commands::sessions::local_session_ids(SESSION_CANDIDATES).await
+        }
+    }
+    .unwrap_or_default();
+
+    let prefix = words.last().map(String::as_str).unwrap_or_default();
+    let mut stdout = std::io::stdout().lock();
+    for (candidate, description) in candidates {
+        if candidate.starts_with(prefix) {
+            let description = description.replace(['\t', '\n', '\r'], " ");
+            let _ = writeln!(stdout, "{}\t{}", candidate, description);
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        let mut words: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        if line.ends_with(' ') {
+            words.push(String::new());
+        }
+        words
+    }
+
+    #[test]
+    fn test_dynamic_value_by_position() {
+        assert_eq!(
+            dynamic_value(&words("autopilot schedule trigger da")),
+            Some(DynamicValue::Schedule)
+        );
+        assert_eq!(
+            dynamic_value(&words("autopilot runs --schedule ")),
+            Some(DynamicValue::Schedule)
+        );
+        assert_eq!(
+            dynamic_value(&words("session show ")),
+            Some(DynamicValue::Session)
+        );
+        assert_eq!(
+            dynamic_value(&words("sessions resume ")),
+            Some(DynamicValue::Session)
+        );
+        assert_eq!(
+            dynamic_value(&words("--profile work -s ")),
+            Some(DynamicValue::Session)
+        );
+
+        // Flag values and earlier positionals move the cursor past the name
+        assert_eq!(
+            dynamic_value(&words("autopilot schedule history nightly --limit ")),
+            None
+        );
+        assert_eq!(dynamic_value(&words("autopilot schedule show ")), None);
+        assert_eq!(
+            dynamic_value(&words("autopilot schedule trigger nightly ")),
+            None
+        );
+        assert_eq!(dynamic_value(&words("sessions show --")), None);
+        assert_eq!(dynamic_value(&words("autopilot sch")), None);
+    }
+
+    #[test]
+    fn test_scripts_call_the_helper() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let mut script = Vec::new();
+            write_script(shell, &mut script).expect("write script");
+            let script = String::from_utf8(script).expect("utf-8 script");
+            assert!(script.contains("stakpak complete-words --"), "{shell}");
+            assert!(script.contains("autopilot"), "{shell}");
+            // Only the wrapper mentions the helper; it is never offered
+            assert_eq!(script.matches(HELPER).count(), 1, "{shell}");
+        }
+    }
+}
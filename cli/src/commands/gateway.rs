@@ -0,0 +1,1554 @@
+//! `stakpak gateway` — inspect the gateway of a running autopilot server.
+//!
+//! `events` re-fetches a channel session's run event stream through the
+//! authenticated `GET /v1/gateway/sessions/{id}/events` endpoint, starting
+//! from the cursor the gateway stored for it, so "the bot stopped answering"
+//! reports can be debugged without access to the gateway database.
+//!
+//! `policy test` evaluates tool calls against the approval policies the
+//! current profile and `autopilot.toml` resolve to, so a policy change can be
+//! checked against real calls before autopilot is restarted with it.
+//!
+//! `sessions` lists which chat threads map to which agent sessions and
+//! unlinks or relinks a mapping through `/v1/gateway/mappings`, for fixing a
+//! thread stuck on a deleted or broken session without wiping the store.
+//!
+//! `audit` reads the approval audit log from the local gateway store, as a
+//! table or as CSV/JSON for compliance exports. It opens the store file
+//! directly, so it works while autopilot is stopped.
+//!
+//! `bench` posts a burst of messages through each configured channel and
+//! reports send latency and how often the provider throttled, to tune send
+//! pacing against real rate limits.
+
+use crate::commands::autopilot::{
+    LocalApprovalPolicies, default_notification_target, local_approval_policies,
+    local_gateway_channels, local_gateway_store_path,
+};
+use crate::config::AppConfig;
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use stakpak_gateway::api::{
+    GatewayApiError, GatewayRelinkRequest, GatewayReplayedEvent, GatewaySessionEventsResponse,
+    GatewaySessionItem, GatewaySessionsResponse,
+};
+use stakpak_gateway::approval::{ApprovalSource, GatewayApprovalAction};
+use stakpak_gateway::audit::{ApprovalAuditFilter, ApprovalAuditRecord};
+use stakpak_gateway::bench::{BenchOptions, ChannelBenchReport, bench_channel};
+use stakpak_gateway::targeting::ChannelTarget;
+use stakpak_server::{ToolApprovalAction, resolve_tool_approval_override};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_EVENT_LIMIT: usize = 200;
+const MAX_PAYLOAD_CHARS: usize = 160;
+const MAX_ARGS_CHARS: usize = 60;
+const DEFAULT_BENCH_MESSAGES: usize = 10;
+const DEFAULT_MAPPING_LIMIT: usize = 100;
+const DEFAULT_AUDIT_LIMIT: usize = 1_000;
+
+#[derive(Subcommand, PartialEq)]
+pub enum GatewayCommands {
+    /// Replay and print a session's run events
+    ///
+    /// Starts after the session's stored cursor unless `--since` is given.
+    /// Only events still held by the server's replay buffer are returned.
+    Events {
+        /// Server session id the gateway routes to
+        session_id: String,
+
+        /// Replay events after this event id (default: the stored session cursor)
+        #[arg(long, value_name = "CURSOR")]
+        since: Option<u64>,
+
+        /// Maximum number of events to fetch
+        #[arg(long, default_value_t = DEFAULT_EVENT_LIMIT)]
+        limit: usize,
+
+        /// Server base URL (default: the local autopilot server)
+        #[arg(long)]
+        url: Option<String>,
+
+        /// Gateway bearer token, as printed by `stakpak up --show-token`
+        #[arg(long, env = "STAKPAK_GATEWAY_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Inspect and repair chat thread to session mappings
+    #[command(subcommand)]
+    Sessions(GatewaySessionsCommands),
+
+    /// Dry-run tool calls against the approval policy
+    #[command(subcommand)]
+    Policy(GatewayPolicyCommands),
+
+    /// Query or export the approval audit log
+    ///
+    /// Lists every tool call the gateway approved or denied, most recent
+    /// first: who decided (a chat user, the approval mode, or the gateway
+    /// itself), in which channel, the tool and a SHA-256 of its arguments.
+    Audit {
+        /// Only decisions since this time: a duration (7d, 12h), RFC 3339 timestamp or YYYY-MM-DD
+        #[arg(long, value_parser = crate::commands::watch::commands::history::parse_since)]
+        since: Option<DateTime<Utc>>,
+
+        /// Only decisions in this channel
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Only decisions for this session id
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Only decisions for this tool name
+        #[arg(long)]
+        tool: Option<String>,
+
+        /// Maximum number of decisions to return
+        #[arg(long, default_value_t = DEFAULT_AUDIT_LIMIT)]
+        limit: usize,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = AuditFormat::Table)]
+        format: AuditFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(long, short, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+
+    /// Measure send latency and rate limiting per channel
+    ///
+    /// Tests each channel, then posts `--messages` messages to its target and
+    /// times every send. Channels run in parallel; one without a target is
+    /// only tested. Targets default to the `[notifications]` target.
+    Bench {
+        /// Messages to send per channel
+        #[arg(long, default_value_t = DEFAULT_BENCH_MESSAGES)]
+        messages: usize,
+
+        /// Only bench these channels (repeatable)
+        #[arg(long = "channel", value_name = "CHANNEL")]
+        channels: Vec<String>,
+
+        /// Where to send, e.g. telegram=123456, slack=C0123, discord=987 (repeatable)
+        #[arg(long = "target", value_name = "CHANNEL=TARGET")]
+        targets: Vec<String>,
+
+        /// Pause between sends on a channel; 0 sends back to back
+        #[arg(long, default_value_t = 0)]
+        interval_ms: u64,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AuditFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+/// Where to reach the gateway API.
+#[derive(Args, PartialEq, Debug, Clone)]
+pub struct GatewayServerArgs {
+    /// Server base URL (default: the local autopilot server)
+    #[arg(long)]
+    url: Option<String>,
+
+    /// Gateway bearer token, as printed by `stakpak up --show-token`
+    #[arg(long, env = "STAKPAK_GATEWAY_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+}
+
+#[derive(Subcommand, PartialEq)]
+pub enum GatewaySessionsCommands {
+    /// List routing keys and the sessions they map to, most recent first
+    List {
+        /// Only mappings of this channel
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Only mappings to this session id
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Maximum number of mappings to list
+        #[arg(long, default_value_t = DEFAULT_MAPPING_LIMIT)]
+        limit: usize,
+
+        #[command(flatten)]
+        server: GatewayServerArgs,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show one mapping and its delivery target
+    Show {
+        /// Routing key, as printed by `list`
+        routing_key: String,
+
+        #[command(flatten)]
+        server: GatewayServerArgs,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Forget a mapping; the next message in the thread starts a new session
+    ///
+    /// The session itself is kept. Refused while the session has a run in
+    /// progress.
+    Unlink {
+        /// Routing key, as printed by `list`
+        routing_key: String,
+
+        #[command(flatten)]
+        server: GatewayServerArgs,
+    },
+
+    /// Point a mapping at another existing session
+    ///
+    /// The thread keeps its delivery target; its next message continues
+    /// `session_id`. Refused while the current session has a run in progress.
+    Relink {
+        /// Routing key, as printed by `list`
+        routing_key: String,
+
+        /// Server session id to route the thread to
+        session_id: String,
+
+        #[command(flatten)]
+        server: GatewayServerArgs,
+    },
+}
+
+#[derive(Subcommand, PartialEq)]
+pub enum GatewayPolicyCommands {
+    /// Show which rule a tool call matches and whether it would be approved
+    ///
+    /// Evaluates the policy the current profile and autopilot.toml resolve
+    /// to, for every configured channel unless `--channel` is given.
+    Test {
+        /// Tool name, e.g. run_command
+        #[arg(long, required_unless_present = "file", conflicts_with = "file")]
+        tool: Option<String>,
+
+        /// Tool arguments as a JSON object, e.g. '{"command":"git status"}'
+        #[arg(long, value_name = "JSON", requires = "tool")]
+        args: Option<String>,
+
+        /// JSONL file of tool calls (`{"name": ..., "arguments": {...}}` per line), or - for stdin
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+
+        /// Only evaluate for this channel
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+impl GatewayCommands {
+    pub async fn run(self, config: AppConfig) -> Result<(), String> {
+        match self {
+            GatewayCommands::Events {
+                session_id,
+                since,
+                limit,
+                url,
+                token,
+                json,
+            } => {
+                let base_url = gateway_base_url(url)?;
+                let mut query = vec![("limit", limit.to_string())];
+                if let Some(since) = since {
+                    query.push(("since", since.to_string()));
+                }
+                let body = call_gateway(
+                    &base_url,
+                    token.as_deref(),
+                    reqwest::Method::GET,
+                    &["sessions", &session_id, "events"],
+                    &query,
+                    None,
+                )
+                .await?;
+                if json {
+                    println!("{}", body);
+                    return Ok(());
+                }
+                let response: GatewaySessionEventsResponse = serde_json::from_str(&body)
+                    .map_err(|e| format!("Invalid response from gateway: {}", e))?;
+                print!("{}", render_session_events(&response));
+                Ok(())
+            }
+            GatewayCommands::Sessions(command) => command.run().await,
+            GatewayCommands::Audit {
+                since,
+                channel,
+                session,
+                tool,
+                limit,
+                format,
+                output,
+            } => {
+                let filter = ApprovalAuditFilter {
+                    since: since.map(|since| since.timestamp_millis()),
+                    until: None,
+                    channel,
+                    session_id: session,
+                    tool_name: tool,
+                };
+                let records =
+                    read_approval_audit(&local_gateway_store_path()?, &filter, limit).await?;
+                let rendered = match format {
+                    AuditFormat::Table => render_audit_table(&records),
+                    AuditFormat::Csv => render_audit_csv(&records),
+                    AuditFormat::Json => {
+                        let mut body = serde_json::to_string_pretty(&records)
+                            .map_err(|e| format!("Failed to serialize audit log: {}", e))?;
+                        body.push('\n');
+                        body
+                    }
+                };
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, rendered)
+                            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                        eprintln!("Wrote {} decision(s) to {}", records.len(), path.display());
+                    }
+                    None => print!("{}", rendered),
+                }
+                Ok(())
+            }
+            GatewayCommands::Policy(GatewayPolicyCommands::Test {
+                tool,
+                args,
+                file,
+                channel,
+                json,
+            }) => {
+                let calls = match (tool, file) {
+                    (Some(name), _) => {
+                        let arguments = match args {
+                            Some(args) => serde_json::from_str(&args)
+                                .map_err(|e| format!("--args is not valid JSON: {}", e))?,
+                            None => serde_json::json!({}),
+                        };
+                        vec![PolicyToolCall {
+                            name,
+                            arguments,
+                            channel: None,
+                        }]
+                    }
+                    (None, Some(path)) => read_tool_calls(&path)?,
+                    (None, None) => return Err("Pass --tool or --file".to_string()),
+                };
+
+                let policies = local_approval_policies(&config)?;
+                let channels = match channel {
+                    Some(channel) => vec![channel],
+                    None => policies.channels.clone(),
+                };
+                let evaluations = evaluate_tool_calls(&policies, &channels, &calls);
+                if json {
+                    let body = serde_json::to_string_pretty(&evaluations)
+                        .map_err(|e| format!("Failed to serialize evaluations: {}", e))?;
+                    println!("{}", body);
+                } else {
+                    print!("{}", render_evaluations(&evaluations));
+                }
+                Ok(())
+            }
+            GatewayCommands::Bench {
+                messages,
+                channels,
+                targets,
+                interval_ms,
+                json,
+            } => {
+                let mut targets = parse_bench_targets(&targets)?;
+                if let Some((channel, target)) = default_notification_target()
+                    && !targets.contains_key(&channel)
+                    && let Ok(target) = bench_target(&channel, &target)
+                {
+                    targets.insert(channel, target);
+                }
+
+                let mut available = local_gateway_channels()?;
+                if available.is_empty() {
+                    return Err("No channels configured. Add one first: stakpak autopilot channel add slack --bot-token X --app-token Y".to_string());
+                }
+                let mut selected: Vec<String> = if channels.is_empty() {
+                    available.keys().cloned().collect()
+                } else {
+                    channels
+                };
+                selected.sort();
+                selected.dedup();
+
+                let options = BenchOptions {
+                    messages,
+                    interval: std::time::Duration::from_millis(interval_ms),
+                };
+                let mut benches = Vec::new();
+                for name in selected {
+                    let channel = available
+                        .remove(&name)
+                        .ok_or_else(|| format!("Channel {} is not configured", name))?;
+                    let target = targets.get(&name).cloned();
+                    if let Some(target) = &target
+                        && messages > 0
+                        && !json
+                    {
+                        eprintln!(
+                            "Sending {} message(s) to {} {}",
+                            messages,
+                            name,
+                            target.target_key()
+                        );
+                    }
+                    let options = options.clone();
+                    benches.push(async move {
+                        bench_channel(channel, &name, target.as_ref(), &options).await
+                    });
+                }
+                let reports = futures_util::future::join_all(benches).await;
+
+                if json {
+                    let body = serde_json::to_string_pretty(&reports)
+                        .map_err(|e| format!("Failed to serialize bench reports: {}", e))?;
+                    println!("{}", body);
+                } else {
+                    print!("{}", render_bench_reports(&reports));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl GatewaySessionsCommands {
+    async fn run(self) -> Result<(), String> {
+        match self {
+            GatewaySessionsCommands::List {
+                channel,
+                session,
+                limit,
+                server,
+                json,
+            } => {
+                let base_url = gateway_base_url(server.url)?;
+                let mut query = vec![("limit", limit.to_string())];
+                if let Some(channel) = channel {
+                    query.push(("channel", channel));
+                }
+                if let Some(session) = session {
+                    query.push(("session_id", session));
+                }
+                let body = call_gateway(
+                    &base_url,
+                    server.token.as_deref(),
+                    reqwest::Method::GET,
+                    &["mappings"],
+                    &query,
+                    None,
+                )
+                .await?;
+                if json {
+                    println!("{}", body);
+                    return Ok(());
+                }
+                let response: GatewaySessionsResponse = serde_json::from_str(&body)
+                    .map_err(|e| format!("Invalid response from gateway: {}", e))?;
+                print!("{}", render_mappings(&response.sessions));
+                Ok(())
+            }
+            GatewaySessionsCommands::Show {
+                routing_key,
+                server,
+                json,
+            } => {
+                let base_url = gateway_base_url(server.url)?;
+                let body = call_gateway(
+                    &base_url,
+                    server.token.as_deref(),
+                    reqwest::Method::GET,
+                    &["mappings", &routing_key],
+                    &[],
+                    None,
+                )
+                .await?;
+                if json {
+                    println!("{}", body);
+                    return Ok(());
+                }
+                let mapping: GatewaySessionItem = serde_json::from_str(&body)
+                    .map_err(|e| format!("Invalid response from gateway: {}", e))?;
+                print!("{}", render_mapping(&mapping));
+                Ok(())
+            }
+            GatewaySessionsCommands::Unlink {
+                routing_key,
+                server,
+            } => {
+                let base_url = gateway_base_url(server.url)?;
+                let body = call_gateway(
+                    &base_url,
+                    server.token.as_deref(),
+                    reqwest::Method::DELETE,
+                    &["mappings", &routing_key],
+                    &[],
+                    None,
+                )
+                .await?;
+                let mapping: GatewaySessionItem = serde_json::from_str(&body)
+                    .map_err(|e| format!("Invalid response from gateway: {}", e))?;
+                println!(
+                    "Unlinked {} from session {}; its next message starts a new session.",
+                    mapping.routing_key, mapping.session_id
+                );
+                Ok(())
+            }
+            GatewaySessionsCommands::Relink {
+                routing_key,
+                session_id,
+                server,
+            } => {
+                let base_url = gateway_base_url(server.url)?;
+                let request = serde_json::to_value(GatewayRelinkRequest { session_id })
+                    .map_err(|e| format!("Failed to serialize request: {}", e))?;
+                let body = call_gateway(
+                    &base_url,
+                    server.token.as_deref(),
+                    reqwest::Method::PUT,
+                    &["mappings", &routing_key],
+                    &[],
+                    Some(request),
+                )
+                .await?;
+                let mapping: GatewaySessionItem = serde_json::from_str(&body)
+                    .map_err(|e| format!("Invalid response from gateway: {}", e))?;
+                println!(
+                    "Relinked {} to session {}.",
+                    mapping.routing_key, mapping.session_id
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A tool call to dry-run, as read from `--file`.
+#[derive(Debug, Clone, Deserialize)]
+struct PolicyToolCall {
+    #[serde(alias = "tool")]
+    name: String,
+    /// Object, or a JSON-encoded string as providers send them.
+    #[serde(default, alias = "args")]
+    arguments: serde_json::Value,
+    /// Evaluate this call for one channel only.
+    #[serde(default)]
+    channel: Option<String>,
+}
+
+/// Which layer settled a tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PolicyLayer {
+    /// The server's tool policy approved or denied it outright.
+    Server,
+    /// The server asked; the gateway's approval mode decided.
+    Gateway,
+}
+
+/// Where the server policy for a run came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ServerPolicySource {
+    /// The profile's `allowed_tools` / `auto_approve` at startup.
+    Default,
+    /// The run's `auto_approve` override from the channel or its profile.
+    RunOverride,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PolicyOutcome {
+    Approve,
+    Reject,
+    /// Prompts in the channel.
+    Ask,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PolicyEvaluation {
+    /// `None` when no channel is configured and the gateway defaults apply.
+    channel: Option<String>,
+    tool: String,
+    arguments: serde_json::Value,
+    outcome: PolicyOutcome,
+    layer: PolicyLayer,
+    /// `server` layer only: which server policy was consulted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    server_policy: Option<ServerPolicySource>,
+    /// `gateway` layer only: where the approval mode came from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gateway_policy: Option<ApprovalSource>,
+    /// Matched rule key or allowlist entry; `None` when a default or mode applied.
+    rule: Option<String>,
+    /// Human-readable explanation of the decision.
+    reason: String,
+}
+
+fn read_tool_calls(path: &Path) -> Result<Vec<PolicyToolCall>, String> {
+    let reader: Box<dyn BufRead> = if path.as_os_str() == "-" {
+        Box::new(std::io::BufReader::new(std::io::stdin()))
+    } else {
+        let file = std::fs::File::open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Box::new(std::io::BufReader::new(file))
+    };
+    parse_tool_calls(reader)
+}
+
+fn parse_tool_calls(reader: impl BufRead) -> Result<Vec<PolicyToolCall>, String> {
+    let mut calls = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read tool calls: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut call: PolicyToolCall = serde_json::from_str(&line)
+            .map_err(|e| format!("Line {}: invalid tool call: {}", index + 1, e))?;
+        if let Some(encoded) = call.arguments.as_str() {
+            call.arguments = serde_json::from_str(encoded)
+                .map_err(|e| format!("Line {}: arguments are not valid JSON: {}", index + 1, e))?;
+        }
+        calls.push(call);
+    }
+    Ok(calls)
+}
+
+fn evaluate_tool_calls(
+    policies: &LocalApprovalPolicies,
+    channels: &[String],
+    calls: &[PolicyToolCall],
+) -> Vec<PolicyEvaluation> {
+    let mut evaluations = Vec::new();
+    for call in calls {
+        match &call.channel {
+            Some(channel) => evaluations.push(evaluate_tool_call(policies, Some(channel), call)),
+            None if channels.is_empty() => {
+                evaluations.push(evaluate_tool_call(policies, None, call));
+            }
+            None => evaluations.extend(
+                channels
+                    .iter()
+                    .map(|channel| evaluate_tool_call(policies, Some(channel), call)),
+            ),
+        }
+    }
+    evaluations
+}
+
+/// Mirror how a run decides: the server's policy first, then — for calls
+/// it asks about — the gateway's `approval_rules` and approval mode for the
+/// channel.
+fn evaluate_tool_call(
+    policies: &LocalApprovalPolicies,
+    channel: Option<&str>,
+    call: &PolicyToolCall,
+) -> PolicyEvaluation {
+    let channel_name = channel.unwrap_or_default();
+    let run_overrides = policies.gateway.run_overrides(channel_name);
+    let auto_approve = run_overrides
+        .as_ref()
+        .and_then(|overrides| overrides.auto_approve.as_ref());
+    let server_source = if auto_approve.is_some() {
+        ServerPolicySource::RunOverride
+    } else {
+        ServerPolicySource::Default
+    };
+    let server_policy = resolve_tool_approval_override(auto_approve, &policies.server);
+    let server_match = server_policy.explain(&call.name, Some(&call.arguments));
+
+    let mut evaluation = PolicyEvaluation {
+        channel: channel.map(str::to_string),
+        tool: call.name.clone(),
+        arguments: call.arguments.clone(),
+        outcome: PolicyOutcome::Ask,
+        layer: PolicyLayer::Server,
+        server_policy: Some(server_source),
+        gateway_policy: None,
+        rule: server_match.rule.clone(),
+        reason: String::new(),
+    };
+    let server_rule = match (&server_match.rule, server_source) {
+        (Some(rule), ServerPolicySource::Default) => format!("server rule `{}`", rule),
+        (Some(rule), ServerPolicySource::RunOverride) => {
+            format!("run override allows `{}`", rule)
+        }
+        (None, ServerPolicySource::Default) => "server default".to_string(),
+        (None, ServerPolicySource::RunOverride) => "run override default".to_string(),
+    };
+
+    match server_match.action {
+        ToolApprovalAction::Approve => {
+            evaluation.outcome = PolicyOutcome::Approve;
+            evaluation.reason = format!("{} approves", server_rule);
+            return evaluation;
+        }
+        ToolApprovalAction::Deny => {
+            evaluation.outcome = PolicyOutcome::Reject;
+            evaluation.reason = format!("{} denies", server_rule);
+            return evaluation;
+        }
+        ToolApprovalAction::Ask => {}
+    }
+
+    let approval = policies
+        .gateway
+        .run_approval(channel_name, run_overrides.as_ref());
+    let source = match approval.source {
+        ApprovalSource::RunOverride => "run override",
+        ApprovalSource::Channel => "channel",
+        ApprovalSource::Gateway => "gateway",
+    };
+    let decision = approval.decide(&call.name, &call.arguments);
+    let action = decision.action;
+    evaluation.layer = PolicyLayer::Gateway;
+    evaluation.server_policy = None;
+    evaluation.gateway_policy = Some(approval.source.clone());
+    evaluation.outcome = match action {
+        GatewayApprovalAction::Accept => PolicyOutcome::Approve,
+        GatewayApprovalAction::Reject => PolicyOutcome::Reject,
+        GatewayApprovalAction::Ask => PolicyOutcome::Ask,
+    };
+    if let Some(pattern) = decision.rule {
+        let verdict = match action {
+            GatewayApprovalAction::Accept => "approves",
+            GatewayApprovalAction::Reject => "rejects",
+            GatewayApprovalAction::Ask => "prompts in channel",
+        };
+        evaluation.reason = format!(
+            "{} asks; approval_rules `{}` {}",
+            server_rule, pattern, verdict
+        );
+        evaluation.rule = Some(pattern);
+        return evaluation;
+    }
+
+    let allowlist_entry = approval.allowlist_match(&call.name).map(str::to_string);
+    evaluation.reason = match (action, &allowlist_entry) {
+        (GatewayApprovalAction::Accept, Some(entry)) => {
+            format!(
+                "{} asks; {} allowlist entry `{}` approves",
+                server_rule, source, entry
+            )
+        }
+        (GatewayApprovalAction::Accept, None) => {
+            format!(
+                "{} asks; {} approval_mode=allow_all approves",
+                server_rule, source
+            )
+        }
+        (GatewayApprovalAction::Reject, _) => {
+            format!(
+                "{} asks; {} approval_mode=deny_all rejects",
+                server_rule, source
+            )
+        }
+        (GatewayApprovalAction::Ask, _) => {
+            format!(
+                "{} asks; not on the {} allowlist, prompts in channel",
+                server_rule, source
+            )
+        }
+    };
+    evaluation.rule = allowlist_entry;
+    evaluation
+}
+
+fn render_evaluations(evaluations: &[PolicyEvaluation]) -> String {
+    let mut out = String::new();
+    let (mut approve, mut reject, mut ask) = (0, 0, 0);
+    for evaluation in evaluations {
+        let outcome = match evaluation.outcome {
+            PolicyOutcome::Approve => {
+                approve += 1;
+                "approve"
+            }
+            PolicyOutcome::Reject => {
+                reject += 1;
+                "reject"
+            }
+            PolicyOutcome::Ask => {
+                ask += 1;
+                "ask"
+            }
+        };
+        let mut call = evaluation.tool.clone();
+        if evaluation
+            .arguments
+            .as_object()
+            .is_some_and(|args| !args.is_empty())
+        {
+            call.push(' ');
+            call.push_str(&truncate_chars(
+                &evaluation.arguments.to_string(),
+                MAX_ARGS_CHARS,
+            ));
+        }
+        out.push_str(&format!(
+            "{:<10} {:<7} {}\n           {}\n",
+            evaluation.channel.as_deref().unwrap_or("(default)"),
+            outcome,
+            call,
+            evaluation.reason
+        ));
+    }
+    out.push_str(&format!(
+        "\n{} evaluation(s): {} approve, {} reject, {} ask\n",
+        evaluations.len(),
+        approve,
+        reject,
+        ask
+    ));
+    out
+}
+
+/// Parse `--target CHANNEL=TARGET` values.
+fn parse_bench_targets(values: &[String]) -> Result<HashMap<String, ChannelTarget>, String> {
+    let mut targets = HashMap::new();
+    for value in values {
+        let (channel, target) = value
+            .split_once('=')
+            .ok_or_else(|| format!("--target {} must look like CHANNEL=TARGET", value))?;
+        targets.insert(channel.to_string(), bench_target(channel, target)?);
+    }
+    Ok(targets)
+}
+
+/// Map a bare target id to the channel's target shape, as schedule
+/// notifications do.
+fn bench_target(channel: &str, target: &str) -> Result<ChannelTarget, String> {
+    let value = match channel {
+        "discord" => serde_json::json!({ "channel_id": target }),
+        "slack" => serde_json::json!({ "channel": target }),
+        "whatsapp" => serde_json::json!({ "to": target }),
+        "matrix" | "rocketchat" => serde_json::json!({ "room_id": target }),
+        _ => serde_json::json!({ "chat_id": target }),
+    };
+    ChannelTarget::parse(channel, &value)
+        .map_err(|e| format!("Invalid target for {}: {}", channel, e))
+}
+
+fn render_bench_reports(reports: &[ChannelBenchReport]) -> String {
+    let mut out = format!(
+        "{:<10} {:>7} {:>6} {:>7} {:>7} {:>7} {:>6} {:>9} {:>9}\n",
+        "CHANNEL", "TEST", "SENT", "P50", "P95", "MAX", "FAILED", "THROTTLED", "MSG/S"
+    );
+    let mut notes = Vec::new();
+    for report in reports {
+        let ms = |value: Option<u64>| value.map_or("-".to_string(), |v| format!("{}ms", v));
+        let sent = report.samples.len() - report.failed;
+        out.push_str(&format!(
+            "{:<10} {:>7} {:>6} {:>7} {:>7} {:>7} {:>6} {:>9} {:>9}\n",
+            report.channel,
+            ms(report.test_ms),
+            sent,
+            ms(report.latency.map(|l| l.p50_ms)),
+            ms(report.latency.map(|l| l.p95_ms)),
+            ms(report.latency.map(|l| l.max_ms)),
+            report.failed,
+            report.throttled,
+            report
+                .sends_per_second
+                .map_or("-".to_string(), |rate| format!("{:.2}", rate)),
+        ));
+
+        if let Some(error) = &report.test_error {
+            notes.push(format!("{}: test failed: {}", report.channel, error));
+        } else if report.target.is_none() {
+            notes.push(format!(
+                "{}: no target; pass --target {}=TARGET to bench sends",
+                report.channel, report.channel
+            ));
+        }
+        if let Some(error) = report.samples.iter().find_map(|s| s.error.as_ref()) {
+            notes.push(format!("{}: first send error: {}", report.channel, error));
+        }
+        if report.throttled > 0 {
+            notes.push(format!(
+                "{}: {} send(s) throttled; space sends at least {} apart",
+                report.channel,
+                report.throttled,
+                report
+                    .sends_per_second
+                    .map_or("-".to_string(), |rate| format!("{:.0}ms", 1000.0 / rate))
+            ));
+        }
+    }
+    if !notes.is_empty() {
+        out.push('\n');
+        for note in notes {
+            out.push_str(&note);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// An empty list when the gateway never ran, without creating the store.
+async fn read_approval_audit(
+    store_path: &Path,
+    filter: &ApprovalAuditFilter,
+    limit: usize,
+) -> Result<Vec<ApprovalAuditRecord>, String> {
+    if !store_path.exists() {
+        return Ok(Vec::new());
+    }
+    let store = stakpak_gateway::GatewayStore::open(store_path)
+        .await
+        .map_err(|e| format!("Failed to open gateway store: {}", e))?;
+    store
+        .list_approval_audit(filter, limit)
+        .await
+        .map_err(|e| format!("Failed to read approval audit log: {}", e))
+}
+
+const AUDIT_CSV_HEADER: &str = "id,decided_at,channel,session_id,run_id,tool_call_id,tool_name,arguments_hash,decision,source,decided_by,reason";
+
+fn render_audit_csv(records: &[ApprovalAuditRecord]) -> String {
+    let mut out = format!("{}\n", AUDIT_CSV_HEADER);
+    for record in records {
+        let fields = [
+            record.id.to_string(),
+            format_rfc3339_millis(record.decided_at),
+            record.channel.clone(),
+            record.session_id.clone(),
+            record.run_id.clone(),
+            record.tool_call_id.clone(),
+            record.tool_name.clone(),
+            record.arguments_hash.clone(),
+            record.decision.as_str().to_string(),
+            record.source.as_str().to_string(),
+            record.decided_by.clone(),
+            record.reason.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quote a CSV field when it holds a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_audit_table(records: &[ApprovalAuditRecord]) -> String {
+    if records.is_empty() {
+        return "No approval decisions recorded.\n".to_string();
+    }
+    let mut out = format!(
+        "{:<20} {:<9} {:<8} {:<24} {:<28} {:<12} {}\n",
+        "TIME", "CHANNEL", "DECISION", "BY", "TOOL", "ARGS", "SESSION"
+    );
+    for record in records {
+        let decided_by = match record.source {
+            stakpak_gateway::AuditSource::User => record.decided_by.clone(),
+            source => format!("{} ({})", record.decided_by, source.as_str()),
+        };
+        out.push_str(&format!(
+            "{:<20} {:<9} {:<8} {:<24} {:<28} {:<12} {}\n",
+            format_millis(record.decided_at),
+            record.channel,
+            record.decision.as_str(),
+            decided_by,
+            record.tool_name,
+            record.arguments_hash.chars().take(12).collect::<String>(),
+            record.session_id
+        ));
+    }
+    out
+}
+
+fn format_rfc3339_millis(millis: i64) -> String {
+    DateTime::from_timestamp_millis(millis)
+        .map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        .unwrap_or_default()
+}
+
+/// Replay a server session's events after `since`, oldest first.
+pub(crate) async fn fetch_session_events(
+    server: &GatewayServerArgs,
+    session_id: &str,
+    since: u64,
+    limit: usize,
+) -> Result<GatewaySessionEventsResponse, String> {
+    let base_url = gateway_base_url(server.url.clone())?;
+    let body = call_gateway(
+        &base_url,
+        server.token.as_deref(),
+        reqwest::Method::GET,
+        &["sessions", session_id, "events"],
+        &[("since", since.to_string()), ("limit", limit.to_string())],
+        None,
+    )
+    .await?;
+    serde_json::from_str(&body).map_err(|e| format!("Invalid response from gateway: {}", e))
+}
+
+fn gateway_base_url(url: Option<String>) -> Result<String, String> {
+    match url {
+        Some(url) => Ok(url.trim_end_matches('/').to_string()),
+        None => crate::commands::autopilot::local_server_base_url(),
+    }
+}
+
+/// Call `/v1/gateway/<path>` and return the response body. Path segments are
+/// percent-encoded, since routing keys can hold any character.
+async fn call_gateway(
+    base_url: &str,
+    token: Option<&str>,
+    method: reqwest::Method,
+    path: &[&str],
+    query: &[(&str, String)],
+    body: Option<serde_json::Value>,
+) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .connect_timeout(std::time::Duration::from_secs(5))
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let url = gateway_url(base_url, path)?;
+    let mut request = client.request(method, url).query(query);
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+    if let Some(token) = token.map(str::trim).filter(|token| !token.is_empty()) {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach gateway at {}: {}", base_url, e))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read gateway response: {}", e))?;
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(
+            "Gateway rejected the token; pass --token or set STAKPAK_GATEWAY_TOKEN".to_string(),
+        );
+    }
+    if !status.is_success() {
+        if let Ok(error) = serde_json::from_str::<GatewayApiError>(&body) {
+            return Err(error.message);
+        }
+        return Err(format!("Gateway returned {}: {}", status, body));
+    }
+    Ok(body)
+}
+
+fn gateway_url(base_url: &str, path: &[&str]) -> Result<reqwest::Url, String> {
+    let mut url = reqwest::Url::parse(base_url)
+        .map_err(|e| format!("Invalid gateway URL {}: {}", base_url, e))?;
+    url.path_segments_mut()
+        .map_err(|_| format!("Invalid gateway URL {}", base_url))?
+        .pop_if_empty()
+        .extend(["v1", "gateway"])
+        .extend(path);
+    Ok(url)
+}
+
+fn render_mappings(mappings: &[GatewaySessionItem]) -> String {
+    if mappings.is_empty() {
+        return "No session mappings.\n".to_string();
+    }
+    let mut out = format!(
+        "{:<40} {:<36} {:<9} {:<20} {}\n",
+        "ROUTING KEY", "SESSION", "CHANNEL", "UPDATED", "TITLE"
+    );
+    for mapping in mappings {
+        let session = if mapping.active {
+            format!("{} *", mapping.session_id)
+        } else {
+            mapping.session_id.clone()
+        };
+        out.push_str(&format!(
+            "{:<40} {:<36} {:<9} {:<20} {}\n",
+            mapping.routing_key,
+            session,
+            mapping.channel,
+            format_millis(mapping.updated_at),
+            mapping.title
+        ));
+    }
+    if mappings.iter().any(|mapping| mapping.active) {
+        out.push_str("\n* run in progress\n");
+    }
+    out
+}
+
+fn render_mapping(mapping: &GatewaySessionItem) -> String {
+    let mut out = format!("Routing key {}\n", mapping.routing_key);
+    out.push_str(&format!("  session:  {}\n", mapping.session_id));
+    out.push_str(&format!(
+        "  run:      {}\n",
+        if mapping.active {
+            "in progress"
+        } else {
+            "idle"
+        }
+    ));
+    out.push_str(&format!("  title:    {}\n", mapping.title));
+    out.push_str(&format!("  channel:  {}\n", mapping.channel));
+    out.push_str(&format!("  target:   {}\n", mapping.target_key));
+    if !mapping.peer_id.is_empty() {
+        out.push_str(&format!("  peer:     {}\n", mapping.peer_id));
+    }
+    out.push_str(&format!(
+        "  created:  {}\n",
+        format_millis(mapping.created_at)
+    ));
+    out.push_str(&format!(
+        "  updated:  {}\n",
+        format_millis(mapping.updated_at)
+    ));
+    out
+}
+
+fn format_millis(millis: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis).map_or_else(
+        || "-".to_string(),
+        |time| time.format("%Y-%m-%d %H:%M:%S").to_string(),
+    )
+}
+
+fn render_session_events(response: &GatewaySessionEventsResponse) -> String {
+    let mut out = format!("Session {}\n", response.session_id);
+    out.push_str(&format!(
+        "  stored cursor: {}\n",
+        format_cursor(response.cursors.session)
+    ));
+    for run in &response.cursors.runs {
+        out.push_str(&format!(
+            "  run {} (session {}): cursor {}\n",
+            run.run_id,
+            run.run_session_id,
+            format_cursor(run.cursor)
+        ));
+    }
+    out.push('\n');
+
+    if response.events.is_empty() {
+        out.push_str(&format!("No events after #{}.\n", response.since));
+        return out;
+    }
+    for event in &response.events {
+        out.push_str(&format_event(event));
+        out.push('\n');
+    }
+    if let Some(next) = response.next_cursor {
+        out.push_str(&format!(
+            "\n{} event(s). Continue with --since {}\n",
+            response.events.len(),
+            next
+        ));
+    }
+    out
+}
+
+fn format_cursor(cursor: Option<u64>) -> String {
+    cursor.map_or_else(|| "none".to_string(), |cursor| format!("#{}", cursor))
+}
+
+/// One line per event: `#id timestamp variant [run] payload`.
+fn format_event(event: &GatewayReplayedEvent) -> String {
+    let id = event
+        .id
+        .map_or_else(|| "#-".to_string(), |id| format!("#{}", id));
+    let timestamp = event
+        .data
+        .get("timestamp")
+        .and_then(|value| value.as_str())
+        .unwrap_or("-");
+
+    // Envelopes carry the payload as `{"event": {"Variant": payload}}`.
+    let (variant, payload) = match event
+        .data
+        .get("event")
+        .and_then(|value| value.as_object())
+        .and_then(|object| object.iter().next())
+    {
+        Some((variant, payload)) => (variant.as_str(), payload.to_string()),
+        None => (event.event_type.as_str(), event.data.to_string()),
+    };
+
+    let mut line = format!("{} {} {}", id, timestamp, variant);
+    if let Some(run_id) = event.run_id.as_deref() {
+        line.push_str(&format!(" [run {}]", run_id));
+    }
+    if payload != "null" && payload != "{}" {
+        line.push(' ');
+        line.push_str(&truncate_chars(&payload, MAX_PAYLOAD_CHARS));
+    }
+    line
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stakpak_gateway::ApprovalMode;
+    use stakpak_gateway::api::{GatewayRunCursor, GatewaySessionCursors};
+    use stakpak_gateway::approval::ApprovalPolicy;
+    use stakpak_gateway::config::ChannelOverrides;
+    use stakpak_server::ToolApprovalPolicy;
+
+    fn call(name: &str, arguments: serde_json::Value) -> PolicyToolCall {
+        PolicyToolCall {
+            name: name.to_string(),
+            arguments,
+            channel: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_tool_calls_accepts_aliases_and_encoded_arguments() {
+        let input = concat!(
+            "{\"name\": \"view\", \"arguments\": {\"path\": \"README.md\"}}\n",
+            "\n",
+            "{\"tool\": \"run_command\", \"args\": \"{\\\"command\\\": \\\"ls\\\"}\", \"channel\": \"slack\"}\n",
+        );
+        let calls = parse_tool_calls(input.as_bytes()).expect("parse tool calls");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1].name, "run_command");
+        assert_eq!(calls[1].arguments, serde_json::json!({"command": "ls"}));
+        assert_eq!(calls[1].channel.as_deref(), Some("slack"));
+
+        let error = parse_tool_calls("{\"name\": 1}\n".as_bytes()).expect_err("invalid line");
+        assert!(error.starts_with("Line 1:"));
+    }
+
+    #[test]
+    fn test_gateway_url_encodes_routing_keys() {
+        let url = gateway_url(
+            "http://127.0.0.1:4096/",
+            &["mappings", "slack:thread:C1/1700000000.1"],
+        )
+        .expect("url");
+        assert_eq!(
+            url.as_str(),
+            "http://127.0.0.1:4096/v1/gateway/mappings/slack:thread:C1%2F1700000000.1"
+        );
+    }
+
+    #[test]
+    fn test_render_audit_csv_quotes_fields() {
+        let record = ApprovalAuditRecord {
+            id: 7,
+            decided_at: 0,
+            channel: "slack".to_string(),
+            session_id: "s1".to_string(),
+            run_id: "r1".to_string(),
+            tool_call_id: "tc1".to_string(),
+            tool_name: "run_command".to_string(),
+            arguments_hash: "ab".repeat(32),
+            decision: stakpak_gateway::AuditDecision::Denied,
+            source: stakpak_gateway::AuditSource::System,
+            decided_by: "gateway".to_string(),
+            reason: Some("Cancelled, said \"stop\"".to_string()),
+        };
+
+        let csv = render_audit_csv(&[record]);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(AUDIT_CSV_HEADER));
+        assert_eq!(
+            lines.next(),
+            Some(
+                format!(
+                    "7,1970-01-01T00:00:00.000Z,slack,s1,r1,tc1,run_command,{},denied,system,gateway,\"Cancelled, said \"\"stop\"\"\"",
+                    "ab".repeat(32)
+                )
+                .as_str()
+            )
+        );
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn test_render_mappings_marks_active_runs() {
+        let mapping = |routing_key: &str, active: bool| GatewaySessionItem {
+            routing_key: routing_key.to_string(),
+            session_id: "3f2a".to_string(),
+            channel: "slack".to_string(),
+            target_key: "slack:channel:C1".to_string(),
+            title: "ops".to_string(),
+            peer_id: "U1".to_string(),
+            chat_type: None,
+            created_at: 0,
+            updated_at: 0,
+            active,
+        };
+        assert_eq!(render_mappings(&[]), "No session mappings.\n");
+
+        let rendered = render_mappings(&[mapping("main", false), mapping("dm:U2", true)]);
+        assert!(rendered.starts_with("ROUTING KEY"));
+        assert_eq!(rendered.matches("3f2a *").count(), 1);
+        assert!(rendered.ends_with("* run in progress\n"));
+    }
+
+    #[test]
+    fn test_evaluate_tool_call_walks_server_then_gateway_policy() {
+        let policies = LocalApprovalPolicies {
+            server: ToolApprovalPolicy::Custom {
+                rules: HashMap::from([
+                    ("view".to_string(), ToolApprovalAction::Approve),
+                    ("run_command::rm".to_string(), ToolApprovalAction::Deny),
+                ]),
+                default: ToolApprovalAction::Ask,
+            },
+            gateway: ApprovalPolicy::new(
+                ApprovalMode::DenyAll,
+                Vec::new(),
+                HashMap::from([(
+                    "slack".to_string(),
+                    ChannelOverrides {
+                        approval_mode: Some(ApprovalMode::Allowlist),
+                        ..ChannelOverrides::default()
+                    },
+                )]),
+            ),
+            channels: vec!["slack".to_string(), "telegram".to_string()],
+        };
+        let calls = vec![
+            call("stakpak__view", serde_json::json!({"path": "README.md"})),
+            call(
+                "run_command",
+                serde_json::json!({"command": "rm -rf /tmp/x"}),
+            ),
+            call("create", serde_json::json!({})),
+        ];
+
+        let evaluations = evaluate_tool_calls(&policies, &policies.channels, &calls);
+        assert_eq!(evaluations.len(), 6);
+
+        let view = &evaluations[0];
+        assert_eq!(view.outcome, PolicyOutcome::Approve);
+        assert_eq!(view.layer, PolicyLayer::Server);
+        assert_eq!(view.rule.as_deref(), Some("view"));
+
+        let rm = &evaluations[2];
+        assert_eq!(rm.outcome, PolicyOutcome::Reject);
+        assert_eq!(rm.rule.as_deref(), Some("run_command::rm"));
+        assert_eq!(rm.reason, "server rule `run_command::rm` denies");
+
+        let create_slack = &evaluations[4];
+        assert_eq!(create_slack.channel.as_deref(), Some("slack"));
+        assert_eq!(create_slack.outcome, PolicyOutcome::Ask);
+        assert_eq!(create_slack.layer, PolicyLayer::Gateway);
+        assert_eq!(create_slack.gateway_policy, Some(ApprovalSource::Channel));
+
+        let create_telegram = &evaluations[5];
+        assert_eq!(create_telegram.outcome, PolicyOutcome::Reject);
+        assert_eq!(
+            create_telegram.reason,
+            "server default asks; gateway approval_mode=deny_all rejects"
+        );
+
+        let rendered = render_evaluations(&evaluations);
+        assert!(rendered.contains("6 evaluation(s): 2 approve, 3 reject, 1 ask"));
+    }
+
+    #[test]
+    fn test_evaluate_tool_call_applies_channel_allowlist_as_run_override() {
+        let policies = LocalApprovalPolicies {
+            server: ToolApprovalPolicy::All,
+            gateway: ApprovalPolicy::new(
+                ApprovalMode::AllowAll,
+                Vec::new(),
+                HashMap::from([(
+                    "discord".to_string(),
+                    ChannelOverrides {
+                        approval_mode: Some(ApprovalMode::Allowlist),
+                        approval_allowlist: Some(vec!["search_docs".to_string()]),
+                        ..ChannelOverrides::default()
+                    },
+                )]),
+            ),
+            channels: vec!["discord".to_string()],
+        };
+
+        let docs = evaluate_tool_call(
+            &policies,
+            Some("discord"),
+            &call("search_docs", serde_json::json!({})),
+        );
+        assert_eq!(docs.outcome, PolicyOutcome::Approve);
+        assert_eq!(docs.server_policy, Some(ServerPolicySource::RunOverride));
+        assert_eq!(docs.reason, "run override allows `search_docs` approves");
+
+        let command = evaluate_tool_call(
+            &policies,
+            Some("discord"),
+            &call("run_command", serde_json::json!({"command": "ls"})),
+        );
+        assert_eq!(command.outcome, PolicyOutcome::Ask);
+        assert_eq!(command.gateway_policy, Some(ApprovalSource::RunOverride));
+
+        let default =
+            evaluate_tool_call(&policies, None, &call("run_command", serde_json::json!({})));
+        assert_eq!(default.outcome, PolicyOutcome::Approve);
+        assert_eq!(default.reason, "server default approves");
+    }
+
+    #[test]
+    fn test_render_session_events_shows_cursors_and_events() {
+        let response = GatewaySessionEventsResponse {
+            session_id: "session-1".to_string(),
+            since: 4,
+            next_cursor: Some(6),
+            cursors: GatewaySessionCursors {
+                session: Some(4),
+                runs: vec![GatewayRunCursor {
+                    run_id: "run-1".to_string(),
+                    run_session_id: "session-1".to_string(),
+                    cursor: None,
+                }],
+            },
+            events: vec![
+                GatewayReplayedEvent {
+                    id: Some(5),
+                    event_type: "run.started".to_string(),
+                    run_id: Some("run-1".to_string()),
+                    data: serde_json::json!({
+                        "timestamp": "2026-10-14T12:00:00Z",
+                        "event": {"RunStarted": {"run_id": "run-1"}},
+                    }),
+                },
+                GatewayReplayedEvent {
+                    id: Some(6),
+                    event_type: "gap_detected".to_string(),
+                    run_id: None,
+                    data: serde_json::Value::String("x".repeat(200)),
+                },
+            ],
+        };
+
+        let rendered = render_session_events(&response);
+        assert!(rendered.contains("stored cursor: #4"));
+        assert!(rendered.contains("run run-1 (session session-1): cursor none"));
+        assert!(
+            rendered
+                .contains("#5 2026-10-14T12:00:00Z RunStarted [run run-1] {\"run_id\":\"run-1\"}")
+        );
+        assert!(rendered.contains("#6 - gap_detected \""));
+        assert!(rendered.contains('…'));
+        assert!(rendered.contains("Continue with --since 6"));
+    }
+
+    #[test]
+    fn bench_targets_map_to_channel_shapes() {
+        let targets = parse_bench_targets(&[
+            "telegram=123456".to_string(),
+            "slack=C0123".to_string(),
+            "discord=987".to_string(),
+            "whatsapp=4915112345678".to_string(),
+            "matrix=!ops:example.org".to_string(),
+            "rocketchat=GENERAL".to_string(),
+        ])
+        .expect("targets");
+        assert_eq!(targets["telegram"].target_key(), "telegram:chat:123456");
+        assert_eq!(targets["slack"].target_key(), "slack:channel:C0123");
+        assert_eq!(targets["discord"].target_key(), "discord:channel:987");
+        assert_eq!(
+            targets["whatsapp"].target_key(),
+            "whatsapp:chat:4915112345678"
+        );
+        assert_eq!(
+            targets["matrix"].target_key(),
+            "matrix:room:!ops:example.org"
+        );
+        assert_eq!(
+            targets["rocketchat"].target_key(),
+            "rocketchat:room:GENERAL"
+        );
+
+        assert!(parse_bench_targets(&["telegram".to_string()]).is_err());
+        assert!(parse_bench_targets(&["signal=1".to_string()]).is_err());
+    }
+
+    #[test]
+    fn render_bench_reports_flags_throttling_and_missing_targets() {
+        let rendered = render_bench_reports(&[
+            ChannelBenchReport {
+                channel: "telegram".to_string(),
+                target: Some("telegram:chat:1".to_string()),
+                test_ms: Some(80),
+                test_error: None,
+                samples: Vec::new(),
+                latency: Some(stakpak_gateway::bench::LatencySummary {
+                    min_ms: 90,
+                    p50_ms: 110,
+                    p95_ms: 2_000,
+                    max_ms: 2_000,
+                }),
+                failed: 0,
+                throttled: 1,
+                sends_per_second: Some(2.0),
+            },
+            ChannelBenchReport {
+                channel: "slack".to_string(),
+                target: None,
+                test_ms: Some(40),
+                test_error: None,
+                samples: Vec::new(),
+                latency: None,
+                failed: 0,
+                throttled: 0,
+                sends_per_second: None,
+            },
+        ]);
+        assert!(rendered.contains("110ms"));
+        assert!(
+            rendered.contains("telegram: 1 send(s) throttled; space sends at least 500ms apart")
+        );
+        assert!(rendered.contains("slack: no target; pass --target slack=TARGET"));
+    }
+}
@@ -6,11 +6,12 @@ use stakpak_mcp_server::ToolMode;
 use crate::config::AppConfig;
 
 use stakpak_mcp_config::{
-    McpServerEntry, add_server, find_config_file, load_config, remove_server, resolve_config_path,
-    save_config, set_server_disabled,
+    McpApprovalAction, McpServerApproval, McpServerEntry, add_server, find_config_file,
+    load_config, remove_server, resolve_config_path, save_config, set_server_disabled,
 };
 
 pub mod proxy;
+pub mod serve;
 pub mod server;
 
 #[derive(Subcommand, PartialEq)]
@@ -33,6 +34,25 @@ pub enum McpCommands {
         #[arg(long = "disable-mcp-mtls", default_value_t = false)]
         disable_mcp_mtls: bool,
     },
+    /// Serve Stakpak's tools to MCP clients such as IDEs and other agents
+    Serve {
+        /// Transport to serve over
+        #[arg(long, value_enum, default_value_t = serve::ServeTransport::Stdio)]
+        transport: serve::ServeTransport,
+
+        /// Address to listen on with `--transport http|sse` (default: a free loopback port)
+        #[arg(long)]
+        bind: Option<String>,
+
+        /// Bearer token HTTP clients must send. Required for non-loopback
+        /// binds; generated and printed when omitted on loopback
+        #[arg(long, env = "STAKPAK_MCP_SERVE_TOKEN", hide_env_values = true)]
+        auth_token: Option<String>,
+
+        /// Tool mode to use (local, remote, combined)
+        #[arg(long, short = 'm', default_value_t = ToolMode::Combined)]
+        tool_mode: ToolMode,
+    },
     /// Start the MCP proxy server (reads config from file, connects to external MCP servers)
     Proxy {
         /// Config file path
@@ -80,6 +100,11 @@ pub enum McpCommands {
         #[arg(long)]
         disabled: Option<bool>,
 
+        /// Approval for this server's tools: approve, ask or deny (default:
+        /// the session's approval settings)
+        #[arg(long, value_parser = parse_approval_action)]
+        approval: Option<McpApprovalAction>,
+
         /// Config file path
         #[arg(long = "config-file")]
         config_file: Option<String>,
@@ -150,6 +175,12 @@ impl McpCommands {
                 )
                 .await
             }
+            McpCommands::Serve {
+                transport,
+                bind,
+                auth_token,
+                tool_mode,
+            } => serve::run_serve(config, transport, bind, auth_token, tool_mode).await,
             McpCommands::Proxy {
                 config_file,
                 disable_secret_redaction,
@@ -170,14 +201,22 @@ impl McpCommands {
                 headers,
                 json,
                 disabled,
+                approval,
                 config_file,
             } => {
+                let approval = McpServerApproval {
+                    default: approval,
+                    ..McpServerApproval::default()
+                };
                 let entry = if let Some(json_str) = json {
                     let mut entry = serde_json::from_str::<McpServerEntry>(&json_str)
                         .map_err(|e| format!("Invalid JSON config: {e}"))?;
                     if let Some(disabled) = disabled {
                         entry.set_disabled(disabled);
                     }
+                    if !approval.is_empty() {
+                        entry.set_approval(approval);
+                    }
                     entry
                 } else if let Some(url) = url {
                     let headers = parse_key_values(&headers)?;
@@ -189,6 +228,7 @@ impl McpCommands {
                             Some(headers)
                         },
                         disabled: disabled.unwrap_or(false),
+                        approval,
                     }
                 } else if let Some(command) = command {
                     let env = parse_key_values(&envs)?;
@@ -197,6 +237,7 @@ impl McpCommands {
                         args,
                         env: if env.is_empty() { None } else { Some(env) },
                         disabled: disabled.unwrap_or(false),
+                        approval,
                     }
                 } else {
                     return Err(
@@ -296,6 +337,11 @@ impl McpCommands {
     }
 }
 
+fn parse_approval_action(value: &str) -> Result<McpApprovalAction, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| format!("Invalid approval '{value}'. Expected approve, ask or deny."))
+}
+
 /// Parse KEY=VALUE pairs from a vec of strings.
 fn parse_key_values(pairs: &[String]) -> Result<HashMap<String, String>, String> {
     let mut map = HashMap::new();
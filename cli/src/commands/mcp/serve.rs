@@ -0,0 +1,385 @@
+//! `stakpak mcp serve`: the agent's tools as an MCP server for IDEs and
+//! other agents, over stdio, streamable HTTP or legacy SSE.
+//!
+//! The HTTP transports run without TLS and hand out shell access, so every
+//! request must carry a bearer token. A loopback bind without `--auth-token`
+//! gets a generated one; any other bind refuses to start without a token.
+//!
+//! On top of the tool mode's tools (commands, files, tasks, docs, skills and
+//! subagents) it serves environment discovery and the session plan. Nothing
+//! is written to stdout except MCP messages, so stdio clients can spawn it
+//! directly.
+
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+
+use clap::ValueEnum;
+use futures_util::FutureExt;
+use rmcp::ErrorData;
+use rmcp::handler::server::router::tool::ToolRoute;
+use rmcp::model::{CallToolResult, Content, JsonObject, Tool};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use stakpak_api::local::skills::default_skill_directories;
+use stakpak_mcp_server::{
+    EnabledToolsConfig, HttpTransport, MCPServerConfig, SubagentConfig, ToolContainer, ToolMode,
+    start_server, start_server_stdio,
+};
+use stakpak_tui::services::plan::{PlanStatus, plan_file_path, read_plan_file, set_plan_status};
+
+use crate::config::{AppConfig, DiscoveryConfig};
+use crate::utils::discovery::{ProbeRegistry, run_report};
+use crate::{commands::get_client, utils::network};
+
+/// Where the session plan lives, relative to the working directory.
+const PLAN_SESSION_DIR: &str = ".stakpak/session";
+
+/// Probes run at once by the discovery tool. The caller is waiting on the
+/// result, so this runs wider than background discovery.
+const DISCOVERY_MAX_CONCURRENT_PROBES: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ServeTransport {
+    /// MCP over stdin/stdout, for clients that spawn the server
+    Stdio,
+    /// Streamable HTTP at `/mcp`, without TLS
+    Http,
+    /// Legacy HTTP+SSE at `/sse` and `/message`, without TLS
+    Sse,
+}
+
+impl ServeTransport {
+    fn http_transport(self) -> Option<HttpTransport> {
+        match self {
+            ServeTransport::Stdio => None,
+            ServeTransport::Http => Some(HttpTransport::StreamableHttp),
+            ServeTransport::Sse => Some(HttpTransport::Sse),
+        }
+    }
+
+    fn url_path(self) -> &'static str {
+        match self {
+            ServeTransport::Stdio => "",
+            ServeTransport::Http => "/mcp",
+            ServeTransport::Sse => stakpak_mcp_server::sse::SSE_PATH,
+        }
+    }
+}
+
+/// Serve the agent's tools over MCP until the client disconnects or the
+/// process is interrupted.
+pub async fn run_serve(
+    config: AppConfig,
+    transport: ServeTransport,
+    bind: Option<String>,
+    auth_token: Option<String>,
+    tool_mode: ToolMode,
+) -> Result<(), String> {
+    let auth_token = auth_token
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty());
+
+    let (bind_address, listener, auth_token) = match (transport.http_transport(), bind) {
+        (None, _) => (String::new(), None, None),
+        (Some(_), Some(addr)) => {
+            let auth_token = resolve_auth_token(&addr, auth_token)?;
+            let listener = tokio::net::TcpListener::bind(&addr)
+                .await
+                .map_err(|e| format!("Failed to bind MCP server to {addr}: {e}"))?;
+            (addr, Some(listener), Some(auth_token))
+        }
+        (Some(_), None) => {
+            let (addr, listener) = network::find_available_loopback_address_with_listener().await?;
+            let auth_token = resolve_auth_token(&addr, auth_token)?;
+            (addr, Some(listener), Some(auth_token))
+        }
+    };
+
+    let server_config = MCPServerConfig {
+        client: Some(get_client(&config).await?),
+        enabled_tools: EnabledToolsConfig { slack: false },
+        tool_mode,
+        enable_subagents: true,
+        bind_address: bind_address.clone(),
+        certificate_chain: Arc::new(None),
+        skill_directories: default_skill_directories(),
+        subagent_config: SubagentConfig {
+            profile_name: Some(config.profile_name.clone()),
+            config_path: Some(config.config_path.clone()),
+            model: config.subagent_model(),
+            local_endpoint: config.offline_endpoint().map(str::to_string),
+        },
+        server_tls_config: None,
+        task_manager_handle: None,
+        extra_tools: serve_tools(config.discovery.clone().unwrap_or_default()),
+        http_transport: transport.http_transport().unwrap_or_default(),
+        auth_token: auth_token.clone(),
+    };
+
+    match transport {
+        ServeTransport::Stdio => start_server_stdio(server_config, None).await,
+        ServeTransport::Http | ServeTransport::Sse => {
+            eprintln!(
+                "MCP server started at http://{}{}",
+                bind_address,
+                transport.url_path()
+            );
+            if let Some(token) = &auth_token {
+                eprintln!("Send `Authorization: Bearer {token}` with every request");
+            }
+            start_server(server_config, listener, None).await
+        }
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// The token HTTP clients must present. Loopback binds get a generated one
+/// when none is given; other binds are refused without one, since the server
+/// runs commands for whoever can reach it.
+fn resolve_auth_token(bind: &str, auth_token: Option<String>) -> Result<String, String> {
+    match auth_token {
+        Some(token) => Ok(token),
+        None if is_loopback_bind(bind) => Ok(uuid::Uuid::new_v4().simple().to_string()),
+        None => Err(format!(
+            "Refusing to serve MCP tools on non-loopback address {bind} without --auth-token \
+             (or STAKPAK_MCP_SERVE_TOKEN)"
+        )),
+    }
+}
+
+fn is_loopback_bind(bind: &str) -> bool {
+    if let Ok(addr) = bind.parse::<SocketAddr>() {
+        return addr.ip().is_loopback();
+    }
+    bind.rsplit_once(':')
+        .is_some_and(|(host, _)| host.eq_ignore_ascii_case("localhost"))
+}
+
+/// The discovery and plan tools served alongside the tool mode's tools.
+fn serve_tools(discovery: DiscoveryConfig) -> Vec<ToolRoute<ToolContainer>> {
+    let discovery = Arc::new(discovery);
+    vec![
+        ToolRoute::new_dyn(
+            Tool::new(
+                "discover_environment",
+                "Scan this machine and project: platform, toolchains, containers, Kubernetes, cloud accounts, IaC, services and more. Returns a markdown report. Pass `probes` to run only those probe ids.",
+                schema(json!({
+                    "type": "object",
+                    "properties": {
+                        "probes": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Probe ids to run, in order (default: all configured probes)"
+                        }
+                    }
+                })),
+            ),
+            move |ctx| {
+                let discovery = discovery.clone();
+                async move {
+                    let request: DiscoverRequest = parse_arguments(ctx.arguments)?;
+                    Ok(discover(&discovery, request.probes).await)
+                }
+                .boxed()
+            },
+        ),
+        ToolRoute::new_dyn(
+            Tool::new(
+                "get_plan",
+                "Read the session plan (.stakpak/session/plan.md): its title, status, version and body.",
+                schema(json!({ "type": "object", "properties": {} })),
+            ),
+            |_ctx| async move { Ok(get_plan(Path::new(PLAN_SESSION_DIR))) }.boxed(),
+        ),
+        ToolRoute::new_dyn(
+            Tool::new(
+                "set_plan_status",
+                "Set the session plan's status, e.g. approve a plan that is pending review.",
+                schema(json!({
+                    "type": "object",
+                    "properties": {
+                        "status": {
+                            "type": "string",
+                            "enum": ["drafting", "pending_review", "approved"]
+                        }
+                    },
+                    "required": ["status"]
+                })),
+            ),
+            |ctx| {
+                async move {
+                    let request: SetPlanStatusRequest = parse_arguments(ctx.arguments)?;
+                    Ok(update_plan_status(
+                        Path::new(PLAN_SESSION_DIR),
+                        request.status,
+                    ))
+                }
+                .boxed()
+            },
+        ),
+    ]
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscoverRequest {
+    #[serde(default)]
+    probes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetPlanStatusRequest {
+    status: PlanStatus,
+}
+
+fn schema(value: serde_json::Value) -> JsonObject {
+    match value {
+        serde_json::Value::Object(object) => object,
+        _ => JsonObject::new(),
+    }
+}
+
+fn parse_arguments<T: DeserializeOwned>(arguments: Option<JsonObject>) -> Result<T, ErrorData> {
+    serde_json::from_value(serde_json::Value::Object(arguments.unwrap_or_default()))
+        .map_err(|e| ErrorData::invalid_params(e.to_string(), None))
+}
+
+async fn discover(config: &DiscoveryConfig, probes: Option<Vec<String>>) -> CallToolResult {
+    let config = config.clone().with_cli_overrides(probes, Vec::new());
+    let registry = match ProbeRegistry::from_config(&config) {
+        Ok(registry) => registry,
+        Err(error) => {
+            return CallToolResult::error(vec![
+                Content::text("INVALID_PROBES"),
+                Content::text(error),
+            ]);
+        }
+    };
+
+    let report = run_report(&registry, DISCOVERY_MAX_CONCURRENT_PROBES, |_, _| {}).await;
+    CallToolResult::success(vec![Content::text(report.to_markdown())])
+}
+
+fn get_plan(session_dir: &Path) -> CallToolResult {
+    let Some((metadata, content)) = read_plan_file(session_dir) else {
+        return CallToolResult::error(vec![
+            Content::text("NO_PLAN"),
+            Content::text(format!(
+                "No plan with valid front matter at {}",
+                plan_file_path(session_dir).display()
+            )),
+        ]);
+    };
+
+    let plan = json!({
+        "title": metadata.title,
+        "status": metadata.status,
+        "version": metadata.version,
+        "created": metadata.created,
+        "updated": metadata.updated,
+        "body": stakpak_tui::services::plan::extract_plan_body(&content),
+    });
+    CallToolResult::success(vec![Content::text(plan.to_string())])
+}
+
+fn update_plan_status(session_dir: &Path, status: PlanStatus) -> CallToolResult {
+    let path = plan_file_path(session_dir);
+    let updated = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| set_plan_status(&content, status));
+    let Some(updated) = updated else {
+        return CallToolResult::error(vec![
+            Content::text("NO_PLAN"),
+            Content::text(format!(
+                "No plan with valid front matter at {}",
+                path.display()
+            )),
+        ]);
+    };
+
+    match std::fs::write(&path, updated) {
+        Ok(()) => {
+            CallToolResult::success(vec![Content::text(format!("Plan status set to {status}"))])
+        }
+        Err(e) => CallToolResult::error(vec![
+            Content::text("PLAN_WRITE_ERROR"),
+            Content::text(format!("Failed to write {}: {}", path.display(), e)),
+        ]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = "---\ntitle: Rotate keys\nstatus: pending_review\n---\n\n## Steps\n";
+
+    fn text(result: &CallToolResult) -> String {
+        result
+            .content
+            .iter()
+            .filter_map(|content| content.as_text().map(|text| text.text.clone()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn plan_tools_read_and_approve_the_session_plan() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert_eq!(get_plan(dir.path()).is_error, Some(true));
+
+        std::fs::write(plan_file_path(dir.path()), PLAN).expect("write plan");
+        let plan: serde_json::Value =
+            serde_json::from_str(&text(&get_plan(dir.path()))).expect("plan json");
+        assert_eq!(plan["title"], "Rotate keys");
+        assert_eq!(plan["status"], "pending_review");
+        assert_eq!(plan["body"], "\n## Steps\n");
+
+        let result = update_plan_status(dir.path(), PlanStatus::Approved);
+        assert_eq!(result.is_error, Some(false));
+        let plan: serde_json::Value =
+            serde_json::from_str(&text(&get_plan(dir.path()))).expect("plan json");
+        assert_eq!(plan["status"], "approved");
+    }
+
+    #[test]
+    fn loopback_binds_are_recognised() {
+        assert!(is_loopback_bind("127.0.0.1:8420"));
+        assert!(is_loopback_bind("[::1]:8420"));
+        assert!(is_loopback_bind("localhost:8420"));
+        assert!(!is_loopback_bind("0.0.0.0:8420"));
+        assert!(!is_loopback_bind("[::]:8420"));
+        assert!(!is_loopback_bind("192.168.1.10:8420"));
+        assert!(!is_loopback_bind("example.com:8420"));
+    }
+
+    #[test]
+    fn non_loopback_bind_requires_a_token() {
+        assert!(resolve_auth_token("0.0.0.0:8420", None).is_err());
+        assert_eq!(
+            resolve_auth_token("0.0.0.0:8420", Some("secret".to_string())),
+            Ok("secret".to_string())
+        );
+
+        let generated = resolve_auth_token("127.0.0.1:8420", None).expect("generated token");
+        assert_eq!(generated.len(), 32);
+    }
+
+    #[test]
+    fn serve_tools_are_named_and_validate_arguments() {
+        let names: Vec<String> = serve_tools(DiscoveryConfig::default())
+            .iter()
+            .map(|route| route.name().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["discover_environment", "get_plan", "set_plan_status"]
+        );
+
+        let status: Result<SetPlanStatusRequest, _> =
+            parse_arguments(Some(schema(json!({ "status": "approved" }))));
+        assert_eq!(status.expect("valid").status, PlanStatus::Approved);
+        assert!(parse_arguments::<SetPlanStatusRequest>(None).is_err());
+    }
+}
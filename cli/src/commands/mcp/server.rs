@@ -114,9 +114,13 @@ pub async fn run_server(
                 profile_name: Some(config.profile_name.clone()),
                 config_path: Some(config.config_path.clone()),
                 model: config.subagent_model(),
+                local_endpoint: config.offline_endpoint().map(str::to_string),
             },
             server_tls_config,
             task_manager_handle: None,
+            extra_tools: Vec::new(),
+            http_transport: Default::default(),
+            auth_token: None,
         },
         Some(listener),
         None,
@@ -3,7 +3,7 @@ use std::process::Command;
 use std::sync::Arc;
 
 use crate::config::AppConfig;
-use clap::{CommandFactory, Subcommand};
+use clap::Subcommand;
 use serde::{Deserialize, Serialize};
 use stakpak_api::{AgentClient, AgentClientConfig, AgentProvider, StakpakConfig};
 
@@ -13,9 +13,14 @@ pub mod ak;
 pub mod auth;
 pub mod auto_update;
 pub mod autopilot;
+pub mod batch;
 pub mod board;
 pub mod browser;
+pub mod completions;
+pub mod gateway;
 pub mod mcp;
+pub mod retention;
+pub mod run;
 pub mod sessions;
 pub mod warden;
 pub mod watch;
@@ -24,6 +29,7 @@ use autopilot::{StartArgs, StopArgs};
 
 pub use auth::AuthCommands;
 pub use autopilot::AutopilotCommands;
+pub use batch::BatchCommands;
 pub use mcp::McpCommands;
 pub use sessions::SessionsCommands;
 
@@ -149,6 +155,9 @@ pub enum Commands {
     /// Analyze your infrastructure setup
     Init,
 
+    /// Run the agent headless on a prompt and stream JSON events (for CI)
+    Run(run::RunArgs),
+
     /// MCP commands
     #[command(subcommand)]
     Mcp(McpCommands),
@@ -204,10 +213,22 @@ pub enum Commands {
     #[command(subcommand)]
     Autopilot(AutopilotCommands),
 
+    /// Run a file of prompts, each in its own session
+    #[command(subcommand)]
+    Batch(BatchCommands),
+
+    /// Inspect the gateway of a running autopilot server
+    #[command(subcommand)]
+    Gateway(gateway::GatewayCommands),
+
     /// List and inspect past sessions
     #[command(subcommand, alias = "session")]
     Sessions(SessionsCommands),
 
+    /// Inspect and apply the local data retention policy
+    #[command(subcommand)]
+    Retention(retention::RetentionCommands),
+
     /// Start autopilot — auto-configures on first run (alias: stakpak autopilot up)
     Up {
         #[command(flatten)]
@@ -223,7 +244,9 @@ pub enum Commands {
     /// Generate shell completion scripts
     ///
     /// Prints a completion script for the given shell to stdout. Source or
-    /// install it according to your shell's documentation.
+    /// install it according to your shell's documentation. The bash, zsh and
+    /// fish scripts also complete schedule names and local session IDs.
+    #[command(name = "completions", alias = "completion")]
     Completion {
         /// Shell to generate completions for
         #[arg(value_enum)]
@@ -246,6 +269,7 @@ async fn build_agent_client(config: &AppConfig) -> Result<AgentClient, String> {
         providers,
         store_path: None,
         hook_registry: None,
+        offline: config.is_offline(),
     })
     .await
     .map_err(|e| format!("Failed to create agent client: {}", e))
@@ -278,6 +302,8 @@ impl Commands {
                 | Commands::Acp { .. }
                 | Commands::Auth(_)
                 | Commands::Autopilot(_)
+                | Commands::Gateway(_)
+                | Commands::Retention(_)
                 | Commands::Up { .. }
                 | Commands::Down { .. }
                 | Commands::Ak(_)
@@ -496,6 +522,10 @@ impl Commands {
                 // Handled in main: starts interactive session with init prompt sent on start
                 unreachable!("stakpak init is handled before Commands::run()")
             }
+            Commands::Run(_) => {
+                // Handled in main: folded into the top-level flags and run in async mode
+                unreachable!("stakpak run is handled before Commands::run()")
+            }
             Commands::Version => {
                 println!(
                     "stakpak v{} (https://github.com/stakpak/agent)",
@@ -532,9 +562,18 @@ impl Commands {
             Commands::Autopilot(autopilot_command) => {
                 autopilot_command.run(config).await?;
             }
+            Commands::Batch(batch_command) => {
+                batch_command.run(config).await?;
+            }
+            Commands::Gateway(gateway_command) => {
+                gateway_command.run(config).await?;
+            }
             Commands::Sessions(sessions_command) => {
                 sessions_command.run(config).await?;
             }
+            Commands::Retention(retention_command) => {
+                retention_command.run().await?;
+            }
             Commands::Up { args } => {
                 AutopilotCommands::Up {
                     args,
@@ -606,12 +645,8 @@ impl Commands {
                 }
             }
             Commands::Completion { shell } => {
-                clap_complete::generate(
-                    shell,
-                    &mut crate::Cli::command(),
-                    "stakpak",
-                    &mut std::io::stdout(),
-                );
+                completions::write_script(shell, &mut std::io::stdout())
+                    .map_err(|e| format!("Failed to write completion script: {}", e))?;
             }
         }
         Ok(())
@@ -795,6 +830,7 @@ mod tests {
             model: None,
             auto_approve_all: false,
             foreground: false,
+            daemon: false,
             non_interactive: false,
             force: false,
         };
@@ -0,0 +1,180 @@
+//! `stakpak retention` — inspect and apply the local data retention policy.
+//!
+//! The policy is the `[retention]` section of `autopilot.toml`; see
+//! [`crate::commands::watch::retention`] for what each category covers.
+//! A running autopilot applies it hourly, so `apply` is only needed to
+//! clean up right away or on machines where autopilot is not running.
+
+use crate::commands::watch::ScheduleConfig;
+use crate::commands::watch::config::{STAKPAK_AUTOPILOT_CONFIG_PATH, expand_tilde};
+use crate::commands::watch::retention::{
+    self, CategoryCleanup, CategoryRetention, CategoryStatus, RetentionCategory, RetentionTargets,
+};
+use chrono::Utc;
+use clap::Subcommand;
+
+#[derive(Subcommand, PartialEq)]
+pub enum RetentionCommands {
+    /// Show how much local data each category holds and what the policy would remove
+    Status {
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Remove local data that exceeds the retention policy now
+    Apply {
+        /// Only apply the policy to this category (repeatable)
+        #[arg(long = "category", value_enum, value_name = "CATEGORY")]
+        categories: Vec<RetentionCategory>,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+impl RetentionCommands {
+    pub async fn run(self) -> Result<(), String> {
+        let config_path = expand_tilde(STAKPAK_AUTOPILOT_CONFIG_PATH);
+        let config = if config_path.exists() {
+            ScheduleConfig::load(&config_path)
+                .map_err(|e| format!("Failed to load autopilot config: {}", e))?
+        } else {
+            ScheduleConfig::parse("").map_err(|e| format!("Invalid default config: {}", e))?
+        };
+        let targets = RetentionTargets::resolve(&config, &config_path);
+
+        match self {
+            RetentionCommands::Status { json } => {
+                let statuses = retention::status(&config.retention, &targets, Utc::now()).await;
+                if json {
+                    let body = serde_json::to_string_pretty(&statuses)
+                        .map_err(|e| format!("Failed to serialize status: {}", e))?;
+                    println!("{}", body);
+                } else {
+                    print!("{}", render_status(&statuses));
+                }
+                Ok(())
+            }
+            RetentionCommands::Apply { categories, json } => {
+                let categories = if categories.is_empty() {
+                    RetentionCategory::ALL.to_vec()
+                } else {
+                    categories
+                };
+                let cleanups =
+                    retention::apply(&config.retention, &targets, &categories, Utc::now()).await;
+                if json {
+                    let body = serde_json::to_string_pretty(&cleanups)
+                        .map_err(|e| format!("Failed to serialize cleanup: {}", e))?;
+                    println!("{}", body);
+                } else {
+                    print!("{}", render_cleanups(&config.retention, &cleanups));
+                }
+
+                let failed: Vec<String> = cleanups
+                    .iter()
+                    .filter(|cleanup| cleanup.error.is_some())
+                    .map(|cleanup| cleanup.category.to_string())
+                    .collect();
+                if failed.is_empty() {
+                    Ok(())
+                } else {
+                    Err(format!("Retention failed for: {}", failed.join(", ")))
+                }
+            }
+        }
+    }
+}
+
+fn render_status(statuses: &[CategoryStatus]) -> String {
+    let mut out = format!(
+        "{:<10} {:>8} {:>10} {:<11} {:<10} {:<9} {}\n",
+        "CATEGORY", "ENTRIES", "SIZE", "OLDEST", "MAX AGE", "MAX SIZE", "EXPIRED"
+    );
+    for status in statuses {
+        if let Some(error) = &status.error {
+            out.push_str(&format!("{:<10} error: {}\n", status.category, error));
+            continue;
+        }
+        let (max_age, max_size) = render_policy(&status.policy);
+        let expired = if status.policy.is_enabled() {
+            format!(
+                "{} ({})",
+                status.expired_entries,
+                format_bytes(status.expired_bytes)
+            )
+        } else {
+            "-".to_string()
+        };
+        out.push_str(&format!(
+            "{:<10} {:>8} {:>10} {:<11} {:<10} {:<9} {}\n",
+            status.category,
+            status.entries,
+            format_bytes(status.bytes),
+            status
+                .oldest
+                .map(|oldest| oldest.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            max_age,
+            max_size,
+            expired
+        ));
+    }
+    out
+}
+
+fn render_cleanups(
+    settings: &retention::RetentionSettings,
+    cleanups: &[CategoryCleanup],
+) -> String {
+    let mut out = String::new();
+    for cleanup in cleanups {
+        let line = match &cleanup.error {
+            Some(error) => format!("✗ {}: {}", cleanup.category, error),
+            None if !settings.policy(cleanup.category).is_enabled() => {
+                format!("- {}: no policy configured", cleanup.category)
+            }
+            None => format!(
+                "✓ {}: removed {} entr{} ({})",
+                cleanup.category,
+                cleanup.removed,
+                if cleanup.removed == 1 { "y" } else { "ies" },
+                format_bytes(cleanup.freed_bytes)
+            ),
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+fn render_policy(policy: &CategoryRetention) -> (String, String) {
+    let max_age = policy
+        .max_age
+        .filter(|age| !age.is_zero())
+        .map(|age| humantime::format_duration(age).to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let max_size = policy
+        .max_size_mb
+        .filter(|mb| *mb > 0)
+        .map(|mb| format!("{} MB", mb))
+        .unwrap_or_else(|| "-".to_string());
+    (max_age, max_size)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
@@ -0,0 +1,122 @@
+//! `stakpak run`: headless agent runs for CI pipelines and scripts.
+//!
+//! The prompt comes from the argument, `--prompt-file` or stdin. The agent
+//! runs to completion in async mode and, by default, writes one JSON event
+//! per line to stdout: tool calls, tool results, assistant messages and a
+//! final `result` event. Exit codes are 0 when the run completes, 10 when it
+//! pauses for approval and 1 when it fails.
+
+use std::io::{IsTerminal, Read};
+
+use clap::{Args, ValueEnum};
+
+use crate::commands::agent::run::OutputFormat;
+
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct RunArgs {
+    /// Prompt to run; omit it or pass `-` to read the prompt from stdin
+    pub prompt: Option<String>,
+
+    /// Read the prompt from a file
+    #[arg(long = "prompt-file", conflicts_with = "prompt")]
+    pub prompt_file: Option<String>,
+
+    /// What to do with tool calls the auto-approve policy doesn't allow
+    #[arg(long = "approval", value_enum, default_value_t = RunApprovalMode::Policy)]
+    pub approval: RunApprovalMode,
+
+    /// Output format: jsonl, json or text
+    #[arg(short = 'o', long = "output", default_value_t = OutputFormat::Jsonl)]
+    pub output_format: OutputFormat,
+
+    /// Maximum number of steps the agent can take (default: 50)
+    #[arg(short = 'm', long = "max-steps")]
+    pub max_steps: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RunApprovalMode {
+    /// Run every tool call
+    Auto,
+    /// Run tool calls the auto-approve policy allows and reject the rest
+    Policy,
+    /// Stop with exit code 10 when a tool call needs approval
+    Pause,
+}
+
+impl RunArgs {
+    /// The prompt from the argument, the prompt file or stdin.
+    pub fn read_prompt(&self) -> Result<String, String> {
+        let stdin = std::io::stdin();
+        let piped = !stdin.is_terminal();
+        resolve_prompt(
+            self.prompt.as_deref(),
+            self.prompt_file.as_deref(),
+            piped.then_some(stdin),
+        )
+    }
+}
+
+fn resolve_prompt(
+    prompt: Option<&str>,
+    prompt_file: Option<&str>,
+    stdin: Option<impl Read>,
+) -> Result<String, String> {
+    let prompt = match (prompt, prompt_file) {
+        (_, Some(path)) => std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read prompt file '{}': {}", path, e))?,
+        (Some(prompt), None) if prompt != "-" => prompt.to_string(),
+        _ => {
+            let Some(mut stdin) = stdin else {
+                return Err(
+                    "No prompt given: pass it as an argument, with --prompt-file, or on stdin"
+                        .to_string(),
+                );
+            };
+            let mut prompt = String::new();
+            stdin
+                .read_to_string(&mut prompt)
+                .map_err(|e| format!("Failed to read prompt from stdin: {}", e))?;
+            prompt
+        }
+    };
+
+    let prompt = prompt.trim();
+    if prompt.is_empty() {
+        return Err("The prompt is empty".to_string());
+    }
+    Ok(prompt.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_comes_from_argument_file_or_stdin() {
+        let no_stdin: Option<&[u8]> = None;
+        assert_eq!(
+            resolve_prompt(Some("fix the build"), None, no_stdin),
+            Ok("fix the build".to_string())
+        );
+        assert_eq!(
+            resolve_prompt(Some("-"), None, Some(&b"  from stdin\n"[..])),
+            Ok("from stdin".to_string())
+        );
+        assert_eq!(
+            resolve_prompt(None, None, Some(&b"piped"[..])),
+            Ok("piped".to_string())
+        );
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("prompt.md");
+        std::fs::write(&path, "from a file\n").expect("write prompt");
+        assert_eq!(
+            resolve_prompt(None, path.to_str(), no_stdin),
+            Ok("from a file".to_string())
+        );
+
+        assert!(resolve_prompt(None, None, no_stdin).is_err());
+        assert!(resolve_prompt(Some("-"), None, Some(&b"  \n"[..])).is_err());
+    }
+}
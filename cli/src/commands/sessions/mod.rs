@@ -1,4 +1,4 @@
-//! `stakpak sessions` — list and inspect past sessions.
+//! `stakpak sessions` — list, inspect and resume past sessions.
 //!
 //! Exposes the `SessionStorage` trait through an agent-friendly CLI with
 //! explicit `--json` output. Uses `build_agent_client(&config)` so it works
@@ -9,7 +9,10 @@ use std::io::Write;
 use std::sync::Arc;
 
 use clap::Subcommand;
-use stakpak_api::{AgentClient, ListSessionsQuery, SessionStorage, StakpakConfig, StorageError};
+use stakpak_api::{
+    AgentClient, ListCheckpointsQuery, ListSessionsQuery, SessionStorage, StakpakConfig,
+    StorageError,
+};
 use uuid::Uuid;
 
 use crate::config::AppConfig;
@@ -21,7 +24,9 @@ pub mod output;
 mod tests;
 
 use messages::{RoleFilter, filter_messages};
-use output::{OutputMode, ShowRenderOptions, render_error, render_list, render_show};
+use output::{
+    OutputMode, ShowRenderOptions, render_checkpoints, render_error, render_list, render_show,
+};
 
 const DEFAULT_LIST_LIMIT: u32 = 20;
 
@@ -71,6 +76,45 @@ pub enum SessionsCommands {
         #[arg(long)]
         json: bool,
     },
+
+    /// List a session's checkpoints, oldest first.
+    ///
+    /// Uses the Stakpak API when the active profile has an API key, otherwise the local SQLite store; honours `--profile`.
+    Checkpoints {
+        /// Full session UUID
+        id: String,
+
+        /// Maximum number of checkpoints to return
+        #[arg(long, default_value_t = 100)]
+        limit: u32,
+
+        /// Skip the first N checkpoints
+        #[arg(long, default_value_t = 0)]
+        offset: u32,
+
+        /// Output machine-readable JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Reopen a session in the TUI, at its active checkpoint or an earlier one.
+    Resume {
+        /// Full session UUID
+        id: String,
+
+        /// Full UUID of the checkpoint to resume from (see `stakpak sessions checkpoints`)
+        #[arg(long)]
+        checkpoint: Option<String>,
+    },
+}
+
+/// Where `stakpak sessions resume` reopens the TUI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResumeTarget {
+    /// The session's active checkpoint.
+    Session(Uuid),
+    /// A specific checkpoint of the session.
+    Checkpoint(Uuid),
 }
 
 impl SessionsCommands {
@@ -96,6 +140,19 @@ impl SessionsCommands {
                 let limit = if limit == 0 { None } else { Some(limit) };
                 run_show(&config, &id, role.as_deref(), limit, offset, mode).await
             }
+            SessionsCommands::Checkpoints {
+                id,
+                limit,
+                offset,
+                json,
+            } => {
+                let mode = OutputMode::from_flag(json);
+                run_checkpoints(&config, &id, limit, offset, mode).await
+            }
+            SessionsCommands::Resume { .. } => {
+                // Handled in main: opens the TUI on the resolved session or checkpoint
+                unreachable!("stakpak sessions resume is handled before Commands::run()")
+            }
         }
     }
 }
@@ -108,6 +165,25 @@ async fn build_storage(config: &AppConfig) -> Result<Arc<dyn SessionStorage>, St
     AgentClient::build_session_storage(stakpak, None, Some(config.profile_name.clone())).await
 }
 
+/// IDs and titles of the most recent sessions in the local SQLite store,
+/// without creating the store when there is none yet.
+pub(crate) async fn local_session_ids(limit: u32) -> Result<Vec<(String, String)>, String> {
+    let store_path = stakpak_api::client::default_store_path();
+    if !std::path::Path::new(&store_path).exists() {
+        return Ok(Vec::new());
+    }
+    let client = AgentClient::build_session_storage(None, Some(store_path), None).await?;
+    let result = client
+        .list_sessions(&ListSessionsQuery::new().with_limit(limit))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result
+        .sessions
+        .into_iter()
+        .map(|session| (session.id.to_string(), session.title))
+        .collect())
+}
+
 pub(crate) async fn list_sessions_output(
     client: Arc<dyn SessionStorage>,
     search: Option<String>,
@@ -185,14 +261,7 @@ async fn run_show(
     offset: u32,
     mode: OutputMode,
 ) -> Result<(), String> {
-    let session_id = match Uuid::parse_str(id_str) {
-        Ok(id) => id,
-        Err(_) => {
-            let msg = format!("invalid session id '{}': expected a full UUID", id_str);
-            emit_error(&msg, "invalid_argument", mode);
-            std::process::exit(2);
-        }
-    };
+    let session_id = parse_session_id(id_str, mode);
 
     let role_filter = match role {
         Some(r) => match r.parse::<RoleFilter>() {
@@ -226,6 +295,105 @@ async fn run_show(
     }
 }
 
+pub(crate) async fn checkpoints_output(
+    client: Arc<dyn SessionStorage>,
+    session_id: Uuid,
+    limit: u32,
+    offset: u32,
+    mode: OutputMode,
+) -> Result<String, StorageError> {
+    let session = client.get_session(session_id).await?;
+    let query = ListCheckpointsQuery {
+        limit: Some(limit),
+        offset: Some(offset),
+        include_state: Some(false),
+    };
+    let result = client.list_checkpoints(session_id, &query).await?;
+    let backend = client.backend_info();
+    Ok(render_checkpoints(
+        &session,
+        &result.checkpoints,
+        &backend,
+        mode,
+    ))
+}
+
+async fn run_checkpoints(
+    config: &AppConfig,
+    id_str: &str,
+    limit: u32,
+    offset: u32,
+    mode: OutputMode,
+) -> Result<(), String> {
+    let session_id = parse_session_id(id_str, mode);
+    let client = build_storage(config).await?;
+
+    match checkpoints_output(client, session_id, limit, offset, mode).await {
+        Ok(rendered) => {
+            emit_stdout(&rendered);
+            Ok(())
+        }
+        Err(e) => exit_with_storage_error(e, mode),
+    }
+}
+
+/// Check that the session exists and, when given, that the checkpoint
+/// belongs to it.
+pub(crate) async fn resolve_resume_target(
+    client: Arc<dyn SessionStorage>,
+    session_id: &str,
+    checkpoint_id: Option<&str>,
+) -> Result<ResumeTarget, String> {
+    let session_id = Uuid::parse_str(session_id)
+        .map_err(|_| format!("invalid session id '{}': expected a full UUID", session_id))?;
+    let session = client
+        .get_session(session_id)
+        .await
+        .map_err(|e| format!("session {}: {}", session_id, e))?;
+
+    let Some(checkpoint_id) = checkpoint_id else {
+        return Ok(ResumeTarget::Session(session.id));
+    };
+    let checkpoint_id = Uuid::parse_str(checkpoint_id).map_err(|_| {
+        format!(
+            "invalid checkpoint id '{}': expected a full UUID",
+            checkpoint_id
+        )
+    })?;
+    let checkpoint = client
+        .get_checkpoint(checkpoint_id)
+        .await
+        .map_err(|e| format!("checkpoint {}: {}", checkpoint_id, e))?;
+    if checkpoint.session_id != session.id {
+        return Err(format!(
+            "checkpoint {} belongs to session {}, not {}",
+            checkpoint_id, checkpoint.session_id, session.id
+        ));
+    }
+    Ok(ResumeTarget::Checkpoint(checkpoint.id))
+}
+
+/// Resolve `stakpak sessions resume` against the profile's session store.
+pub async fn resume_target(
+    config: &AppConfig,
+    session_id: &str,
+    checkpoint_id: Option<&str>,
+) -> Result<ResumeTarget, String> {
+    let client = build_storage(config).await?;
+    resolve_resume_target(client, session_id, checkpoint_id).await
+}
+
+fn parse_session_id(id_str: &str, mode: OutputMode) -> Uuid {
+    match Uuid::parse_str(id_str) {
+        Ok(id) => id,
+        Err(_) => {
+            let msg = format!("invalid session id '{}': expected a full UUID", id_str);
+            emit_error(&msg, "invalid_argument", mode);
+            std::process::exit(2);
+        }
+    }
+}
+
 fn emit_stdout(rendered: &str) {
     if rendered.ends_with('\n') {
         print!("{}", rendered);
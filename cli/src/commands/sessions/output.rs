@@ -2,10 +2,11 @@
 
 use serde::Serialize;
 use stakpak_api::{
-    BackendInfo, BackendKind, Session, SessionStatus, SessionSummary, SessionVisibility,
+    BackendInfo, BackendKind, CheckpointSummary, Session, SessionStatus, SessionSummary,
+    SessionVisibility,
 };
 use stakpak_shared::models::integrations::openai::ChatMessage;
-use stakpak_shared::utils::sanitize_text_output;
+use stakpak_shared::utils::{redact_text_output, sanitize_text_output};
 
 /// Output format for session commands.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -230,7 +231,7 @@ fn render_show_human(
     for (i, m) in messages.iter().enumerate() {
         out.push_str(&format!("\n[{}] {}\n", i + 1, m.role));
         if let Some(content) = &m.content {
-            let text = sanitize_text_output(&content.to_string());
+            let text = redact_text_output(&content.to_string());
             for line in text.lines() {
                 out.push_str("    ");
                 out.push_str(line);
@@ -275,6 +276,98 @@ fn render_show_human(
     out
 }
 
+// =============================================================================
+// Checkpoints output
+// =============================================================================
+
+#[derive(Debug, Serialize)]
+struct CheckpointsOutput<'a> {
+    backend: &'a BackendInfo,
+    session_id: uuid::Uuid,
+    title: &'a str,
+    active_checkpoint_id: Option<uuid::Uuid>,
+    checkpoints: &'a [CheckpointSummary],
+}
+
+pub fn render_checkpoints(
+    session: &Session,
+    checkpoints: &[CheckpointSummary],
+    backend: &BackendInfo,
+    mode: OutputMode,
+) -> String {
+    let active = session.active_checkpoint.as_ref().map(|c| c.id);
+    match mode {
+        OutputMode::Json => {
+            let out = CheckpointsOutput {
+                backend,
+                session_id: session.id,
+                title: &session.title,
+                active_checkpoint_id: active,
+                checkpoints,
+            };
+            serde_json::to_string_pretty(&out).unwrap_or_else(|_| "{}".to_string())
+        }
+        OutputMode::Human => render_checkpoints_human(session, checkpoints, active, backend),
+    }
+}
+
+fn render_checkpoints_human(
+    session: &Session,
+    checkpoints: &[CheckpointSummary],
+    active: Option<uuid::Uuid>,
+    backend: &BackendInfo,
+) -> String {
+    let mut out = format!("{}\n", render_backend_header(backend));
+    let sanitized_title = sanitize_text_output(&session.title);
+    out.push_str(&format!(
+        "Session: {} ({})\n\n",
+        session.id,
+        collapse_whitespace(&sanitized_title)
+    ));
+    if checkpoints.is_empty() {
+        out.push_str("No checkpoints found.");
+        return out;
+    }
+
+    let id_w = 36;
+    let msgs_w = 5;
+    out.push_str(&format!(
+        "  {:<id_w$}  {:>msgs_w$}  CREATED\n",
+        "ID",
+        "MSGS",
+        id_w = id_w,
+        msgs_w = msgs_w,
+    ));
+    out.push_str(&format!(
+        "  {}  {}  {}\n",
+        "-".repeat(id_w),
+        "-".repeat(msgs_w),
+        "-".repeat(19),
+    ));
+    for checkpoint in checkpoints {
+        let marker = if Some(checkpoint.id) == active {
+            '*'
+        } else {
+            ' '
+        };
+        out.push_str(&format!(
+            "{} {:<id_w$}  {:>msgs_w$}  {}\n",
+            marker,
+            checkpoint.id,
+            checkpoint.message_count,
+            checkpoint.created_at.format("%Y-%m-%d %H:%M:%S"),
+            id_w = id_w,
+            msgs_w = msgs_w,
+        ));
+    }
+
+    out.push_str(&format!(
+        "\n* active checkpoint\nResume at a checkpoint: stakpak sessions resume {} --checkpoint <id>\n",
+        session.id
+    ));
+    out
+}
+
 // =============================================================================
 // Errors
 // =============================================================================
@@ -328,7 +421,7 @@ fn format_tool_call_arguments(arguments: &str) -> String {
         Ok(value) => serde_json::to_string_pretty(&value).unwrap_or_else(|_| arguments.to_string()),
         Err(_) => arguments.to_string(),
     };
-    sanitize_text_output(&rendered)
+    redact_text_output(&rendered)
 }
 
 fn collapse_whitespace(s: &str) -> String {
@@ -5,16 +5,16 @@
 use std::sync::Arc;
 
 use stakpak_api::{
-    BackendInfo, Checkpoint, CheckpointState, ListSessionsQuery, LocalStorage, Session,
-    SessionStatus, SessionStorage, SessionVisibility,
+    BackendInfo, Checkpoint, CheckpointState, CreateCheckpointRequest, ListSessionsQuery,
+    LocalStorage, Session, SessionStatus, SessionStorage, SessionVisibility,
     StorageCreateSessionRequest as CreateSessionRequest, StorageError,
 };
 use stakpak_shared::models::integrations::openai::{ChatMessage, MessageContent, Role};
 use uuid::Uuid;
 
-use super::classify_storage_error;
 use super::messages::{RoleFilter, filter_messages};
 use super::output::{self, OutputMode, ShowRenderOptions, render_error};
+use super::{ResumeTarget, classify_storage_error, resolve_resume_target};
 
 async fn in_memory_storage() -> LocalStorage {
     LocalStorage::new(":memory:")
@@ -1055,6 +1055,121 @@ fn invalid_role_string_is_rejected_by_role_filter() {
     assert!(err.contains("user"));
 }
 
+// =============================================================================
+// `stakpak sessions checkpoints` / `stakpak sessions resume`
+// =============================================================================
+
+#[tokio::test]
+async fn sessions_checkpoints_lists_checkpoints_and_marks_the_active_one() {
+    let storage: Arc<dyn SessionStorage> = Arc::new(in_memory_storage().await);
+    let created = storage
+        .create_session(&CreateSessionRequest::new(
+            "checkpoints",
+            vec![msg(Role::User, "hello")],
+        ))
+        .await
+        .unwrap();
+    let latest = storage
+        .create_checkpoint(
+            created.session_id,
+            &CreateCheckpointRequest::new(vec![
+                msg(Role::User, "hello"),
+                msg(Role::Assistant, "hi"),
+            ])
+            .with_parent(created.checkpoint.id),
+        )
+        .await
+        .unwrap();
+
+    let json = super::checkpoints_output(
+        storage.clone(),
+        created.session_id,
+        100,
+        0,
+        OutputMode::Json,
+    )
+    .await
+    .expect("checkpoints output should succeed");
+    let value: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+    let ids: Vec<&str> = value["checkpoints"]
+        .as_array()
+        .expect("checkpoints array")
+        .iter()
+        .filter_map(|checkpoint| checkpoint["id"].as_str())
+        .collect();
+    let first = created.checkpoint.id.to_string();
+    let second = latest.id.to_string();
+    assert_eq!(ids, vec![first.as_str(), second.as_str()]);
+    assert_eq!(
+        value["active_checkpoint_id"].as_str(),
+        Some(second.as_str())
+    );
+
+    let human = super::checkpoints_output(storage, created.session_id, 100, 0, OutputMode::Human)
+        .await
+        .expect("checkpoints output should succeed");
+    assert!(human.contains(&format!("* {}", latest.id)));
+    assert!(human.contains(&format!("  {}", created.checkpoint.id)));
+    assert!(human.contains(&format!(
+        "stakpak sessions resume {} --checkpoint <id>",
+        created.session_id
+    )));
+}
+
+#[tokio::test]
+async fn sessions_resume_checks_the_checkpoint_belongs_to_the_session() {
+    let storage: Arc<dyn SessionStorage> = Arc::new(in_memory_storage().await);
+    let first = storage
+        .create_session(&CreateSessionRequest::new(
+            "one",
+            vec![msg(Role::User, "a")],
+        ))
+        .await
+        .unwrap();
+    let second = storage
+        .create_session(&CreateSessionRequest::new(
+            "two",
+            vec![msg(Role::User, "b")],
+        ))
+        .await
+        .unwrap();
+    let session = first.session_id.to_string();
+
+    assert_eq!(
+        resolve_resume_target(storage.clone(), &session, None).await,
+        Ok(ResumeTarget::Session(first.session_id))
+    );
+    assert_eq!(
+        resolve_resume_target(
+            storage.clone(),
+            &session,
+            Some(&first.checkpoint.id.to_string())
+        )
+        .await,
+        Ok(ResumeTarget::Checkpoint(first.checkpoint.id))
+    );
+
+    let foreign = resolve_resume_target(
+        storage.clone(),
+        &session,
+        Some(&second.checkpoint.id.to_string()),
+    )
+    .await
+    .expect_err("checkpoint of another session");
+    assert!(foreign.contains("belongs to session"), "{foreign}");
+
+    assert!(
+        resolve_resume_target(storage.clone(), "abc", None)
+            .await
+            .is_err()
+    );
+    assert!(
+        resolve_resume_target(storage, &Uuid::new_v4().to_string(), None)
+            .await
+            .is_err()
+    );
+}
+
 // =============================================================================
 // Branch coverage for `classify_storage_error`
 // =============================================================================
@@ -2,13 +2,16 @@
 //!
 //! Spawns the stakpak agent via the co-hosted agent server API.
 
+use super::budget::estimate_cost;
+use super::redaction::{RedactionPattern, redact};
 use crate::commands::agent::run::pause::EXIT_CODE_PAUSED;
 use stakpak_gateway::client::{
-    CallerContextInput, ClientError, RunOverrides, SendMessageOptions, StakpakClient,
-    ToolDecisionAction, ToolDecisionInput,
+    CallerContextInput, ClientError, CreateSessionOptions, EventStream, RunOverrides,
+    SendMessageOptions, StakpakClient, TokenUsage, ToolDecisionAction, ToolDecisionInput,
 };
-use stakpak_shared::models::async_manifest::PauseReason;
-use std::collections::{HashMap, HashSet};
+use stakpak_shared::error::ErrorKind;
+use stakpak_shared::models::async_manifest::{PauseReason, PendingToolCall};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
@@ -33,6 +36,12 @@ pub struct AgentResult {
     pub stdout: String,
     /// Combined stderr output from the agent.
     pub stderr: String,
+    /// Paths of files the agent wrote through approved file-editing tool calls.
+    pub written_files: Vec<String>,
+    /// Tokens used across all turns of the run.
+    pub usage: TokenUsage,
+    /// Estimated cost of `usage`, when the model's pricing is known.
+    pub estimated_cost: Option<f64>,
 }
 
 impl AgentResult {
@@ -56,8 +65,21 @@ impl AgentResult {
 /// Errors that can occur during agent spawning.
 #[derive(Debug, thiserror::Error)]
 pub enum AgentError {
+    /// The schedule's agent environment could not be prepared.
     #[error("Failed to spawn agent: {0}")]
     SpawnError(String),
+    /// The agent server failed or rejected a request.
+    #[error("Failed to spawn agent: Server API error: {0}")]
+    Server(#[from] ClientError),
+}
+
+impl AgentError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            AgentError::SpawnError(_) => ErrorKind::Config,
+            AgentError::Server(error) => error.kind(),
+        }
+    }
 }
 
 /// Connection details for the co-hosted agent server.
@@ -88,6 +110,10 @@ pub struct SpawnConfig {
     pub timeout: Duration,
     /// Working directory for the agent (optional).
     pub workdir: Option<String>,
+    /// Extra environment variables for commands the agent runs.
+    pub env: BTreeMap<String, String>,
+    /// Tags set on the server session, e.g. [`schedule_session_tag`].
+    pub tags: Vec<String>,
     /// Enable Slack tools (experimental).
     pub enable_slack_tools: bool,
     /// Enable subagents.
@@ -104,6 +130,93 @@ pub struct SpawnConfig {
     pub overrides: Option<RunOverrides>,
     /// Agent server connection.
     pub server: AgentServerConnection,
+    /// Patterns redacted from agent errors before they are logged.
+    pub redact_patterns: Vec<RedactionPattern>,
+}
+
+/// Answer to the tool calls a paused run is waiting on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResumeDecision {
+    Approve,
+    Deny,
+}
+
+/// Configuration for resuming a paused run.
+#[derive(Debug, Clone)]
+pub struct ResumeConfig {
+    /// Server session the paused run belongs to.
+    pub session_id: String,
+    /// Decision to submit, or `None` to wait for one submitted elsewhere
+    /// (an approval in chat, or [`resolve_paused_run`]).
+    pub decision: Option<ResumeDecision>,
+    /// Maximum time to wait for the run to pause again or finish.
+    pub timeout: Duration,
+    /// Pause again when further tools require approval.
+    pub pause_on_approval: bool,
+    /// Tools this run can auto-approve. Empty means allow all tools.
+    pub allowed_tools: HashSet<String>,
+    /// Model the run uses, for cost estimates.
+    pub model: Option<String>,
+    /// Agent server connection.
+    pub server: AgentServerConnection,
+    /// Patterns redacted from agent errors before they are logged.
+    pub redact_patterns: Vec<RedactionPattern>,
+}
+
+/// Configuration for adopting a run still in flight in an existing session.
+#[derive(Debug, Clone)]
+pub struct AdoptConfig {
+    /// Server session the run belongs to.
+    pub session_id: String,
+    /// The run in flight.
+    pub run_id: String,
+    /// Maximum time to wait for the run to finish or pause.
+    pub timeout: Duration,
+    /// Pause when tools require approval instead of auto-approving.
+    pub pause_on_approval: bool,
+    /// Tools this run can auto-approve. Empty means allow all tools.
+    pub allowed_tools: HashSet<String>,
+    /// Model the run uses, for cost estimates.
+    pub model: Option<String>,
+    /// Agent server connection.
+    pub server: AgentServerConnection,
+    /// Patterns redacted from agent errors before they are logged.
+    pub redact_patterns: Vec<RedactionPattern>,
+}
+
+impl AdoptConfig {
+    /// Adopt `outstanding` with the settings a fresh spawn would have used.
+    pub fn from_spawn(spawn: &SpawnConfig, outstanding: OutstandingSession) -> Self {
+        Self {
+            session_id: outstanding.session_id,
+            run_id: outstanding.run_id,
+            timeout: spawn.timeout,
+            pause_on_approval: spawn.pause_on_approval,
+            allowed_tools: spawn.allowed_tools.clone(),
+            model: effective_model(spawn),
+            server: spawn.server.clone(),
+            redact_patterns: spawn.redact_patterns.clone(),
+        }
+    }
+}
+
+/// A session tagged for a schedule whose run is still in flight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutstandingSession {
+    pub session_id: String,
+    pub run_id: String,
+}
+
+/// Tag set on every server session spawned for `schedule`.
+pub fn schedule_session_tag(schedule: &str) -> String {
+    format!("autopilot-schedule:{schedule}")
+}
+
+/// How the event loop handles a run's tool calls.
+struct RunPolicy<'a> {
+    pause_on_approval: bool,
+    allowed_tools: &'a HashSet<String>,
+    redact_patterns: &'a [RedactionPattern],
 }
 
 /// Spawn the stakpak agent via the co-hosted agent server API.
@@ -121,34 +234,249 @@ pub async fn spawn_agent(config: SpawnConfig) -> Result<AgentResult, AgentError>
         "Spawning agent via server API"
     );
 
+    // Usage is tracked outside the session future so a timed-out run still
+    // reports the tokens it used.
+    let mut usage = TokenUsage::default();
     let result = tokio::time::timeout(config.timeout, async {
-        run_server_session(&client, server, &config).await
+        run_server_session(&client, &config, &mut usage).await
     })
     .await;
+    let estimated_cost = estimate_cost(effective_model(&config).as_deref(), &usage);
 
     match result {
-        Ok(Ok(agent_result)) => Ok(agent_result),
-        Ok(Err(e)) => Err(AgentError::SpawnError(format!("Server API error: {}", e))),
+        Ok(Ok(agent_result)) => Ok(AgentResult {
+            usage,
+            estimated_cost,
+            ..agent_result
+        }),
+        Ok(Err(e)) => Err(AgentError::Server(e)),
         Err(_) => {
             warn!(
                 timeout_secs = config.timeout.as_secs(),
                 "Agent timed out (server API)"
             );
-            Ok(AgentResult {
-                exit_code: None,
-                session_id: None,
-                checkpoint_id: None,
-                timed_out: true,
-                paused: false,
-                pause_reason: None,
-                resume_hint: None,
-                stdout: String::new(),
-                stderr: String::new(),
-            })
+            Ok(timed_out_result(None, usage, estimated_cost))
+        }
+    }
+}
+
+/// Resume a paused run: submit `config.decision` for its pending tool calls
+/// (or wait for a decision made elsewhere) and drain events until the run
+/// completes, errors, pauses again or times out.
+pub async fn resume_agent(config: ResumeConfig) -> Result<AgentResult, AgentError> {
+    let server = &config.server;
+    let client = StakpakClient::new(server.url.clone(), server.token.clone());
+
+    debug!(
+        session_id = %config.session_id,
+        decision = ?config.decision,
+        timeout_secs = config.timeout.as_secs(),
+        "Resuming paused agent run"
+    );
+
+    let mut usage = TokenUsage::default();
+    let result = tokio::time::timeout(config.timeout, async {
+        resume_server_session(&client, &config, &mut usage).await
+    })
+    .await;
+    let estimated_cost = estimate_cost(config.model.as_deref(), &usage);
+
+    match result {
+        Ok(Ok(agent_result)) => Ok(AgentResult {
+            usage,
+            estimated_cost,
+            ..agent_result
+        }),
+        Ok(Err(e)) => Err(AgentError::Server(e)),
+        Err(_) => {
+            warn!(
+                session_id = %config.session_id,
+                timeout_secs = config.timeout.as_secs(),
+                "Resumed agent run timed out"
+            );
+            Ok(timed_out_result(
+                Some(config.session_id.clone()),
+                usage,
+                estimated_cost,
+            ))
         }
     }
 }
 
+/// Find a session tagged with `tag` whose run is still in flight, skipping
+/// `tracked` sessions that a run record already follows.
+pub async fn find_outstanding_session(
+    server: &AgentServerConnection,
+    tag: &str,
+    tracked: &HashSet<String>,
+) -> Result<Option<OutstandingSession>, AgentError> {
+    let client = StakpakClient::new(server.url.clone(), server.token.clone());
+    let sessions = client.list_sessions_tagged(tag).await?;
+
+    Ok(sessions.sessions.into_iter().find_map(|session| {
+        let session_id = session.id.to_string();
+        if tracked.contains(&session_id) {
+            return None;
+        }
+        session.active_run_id().map(|run_id| OutstandingSession {
+            session_id,
+            run_id: run_id.to_string(),
+        })
+    }))
+}
+
+/// Follow a run that is still in flight in an existing session, e.g. one
+/// left behind by an autopilot that crashed, until it completes, errors,
+/// pauses or times out.
+pub async fn adopt_agent(config: AdoptConfig) -> Result<AgentResult, AgentError> {
+    let server = &config.server;
+    let client = StakpakClient::new(server.url.clone(), server.token.clone());
+
+    debug!(
+        session_id = %config.session_id,
+        run_id = %config.run_id,
+        timeout_secs = config.timeout.as_secs(),
+        "Adopting in-flight agent run"
+    );
+
+    let mut usage = TokenUsage::default();
+    let result = tokio::time::timeout(config.timeout, async {
+        adopt_server_session(&client, &config, &mut usage).await
+    })
+    .await;
+    let estimated_cost = estimate_cost(config.model.as_deref(), &usage);
+
+    match result {
+        Ok(Ok(agent_result)) => Ok(AgentResult {
+            usage,
+            estimated_cost,
+            ..agent_result
+        }),
+        Ok(Err(e)) => Err(AgentError::Server(e)),
+        Err(_) => {
+            warn!(
+                session_id = %config.session_id,
+                timeout_secs = config.timeout.as_secs(),
+                "Adopted agent run timed out"
+            );
+            Ok(timed_out_result(
+                Some(config.session_id.clone()),
+                usage,
+                estimated_cost,
+            ))
+        }
+    }
+}
+
+/// Submit `decision` for the tool calls a paused run is waiting on without
+/// following the run; a task already waiting on it picks up the events.
+/// Returns how many tool calls were resolved.
+pub async fn resolve_paused_run(
+    server: &AgentServerConnection,
+    session_id: &str,
+    decision: ResumeDecision,
+) -> Result<usize, AgentError> {
+    let client = StakpakClient::new(server.url.clone(), server.token.clone());
+    let (run_id, tool_calls) = pending_approval(&client, session_id).await?;
+    client
+        .resolve_tools(session_id, &run_id, resume_decisions(&tool_calls, decision))
+        .await?;
+    Ok(tool_calls.len())
+}
+
+/// Result for a run that hit its timeout.
+fn timed_out_result(
+    session_id: Option<String>,
+    usage: TokenUsage,
+    estimated_cost: Option<f64>,
+) -> AgentResult {
+    AgentResult {
+        exit_code: None,
+        session_id,
+        checkpoint_id: None,
+        timed_out: true,
+        paused: false,
+        pause_reason: None,
+        resume_hint: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        written_files: Vec::new(),
+        usage,
+        estimated_cost,
+    }
+}
+
+/// The run a session is waiting on and its pending tool calls.
+async fn pending_approval(
+    client: &StakpakClient,
+    session_id: &str,
+) -> Result<(String, Vec<PendingToolCall>), ClientError> {
+    let pending = client.pending_tools(session_id).await?;
+    match pending.run_id {
+        Some(run_id) if !pending.tool_calls.is_empty() => Ok((
+            run_id.to_string(),
+            pending
+                .tool_calls
+                .iter()
+                .map(|tool_call| PendingToolCall {
+                    id: tool_call.id.clone(),
+                    name: tool_call.name.clone(),
+                    arguments: tool_call.arguments.clone(),
+                })
+                .collect(),
+        )),
+        _ => Err(ClientError::InvalidRequest(format!(
+            "session {} is not waiting for a tool approval",
+            session_id
+        ))),
+    }
+}
+
+fn resume_decisions(
+    tool_calls: &[PendingToolCall],
+    decision: ResumeDecision,
+) -> HashMap<String, ToolDecisionInput> {
+    tool_calls
+        .iter()
+        .map(|tool_call| {
+            let input = match decision {
+                ResumeDecision::Approve => ToolDecisionInput {
+                    action: ToolDecisionAction::Accept,
+                    content: None,
+                },
+                ResumeDecision::Deny => ToolDecisionInput {
+                    action: ToolDecisionAction::Reject,
+                    content: Some("Denied by operator".to_string()),
+                },
+            };
+            (tool_call.id.clone(), input)
+        })
+        .collect()
+}
+
+/// Run overrides sent with the message, with the spawn env layered on top.
+fn effective_overrides(config: &SpawnConfig) -> Option<RunOverrides> {
+    if config.env.is_empty() {
+        return config.overrides.clone();
+    }
+
+    let mut overrides = config.overrides.clone().unwrap_or_default();
+    overrides
+        .env
+        .get_or_insert_with(BTreeMap::new)
+        .extend(config.env.clone());
+    Some(overrides)
+}
+
+/// Model the run is sent with: the profile override, else the server default.
+fn effective_model(config: &SpawnConfig) -> Option<String> {
+    config
+        .overrides
+        .as_ref()
+        .and_then(|overrides| overrides.model.clone())
+        .or_else(|| config.server.model.clone())
+}
+
 fn build_tool_decisions(
     tool_calls: &[(String, String)],
     allowed_tools: &HashSet<String>,
@@ -177,14 +505,35 @@ fn build_tool_decisions(
         .collect()
 }
 
+/// Tools whose `path` argument names a file the agent writes.
+const FILE_WRITE_TOOLS: &[&str] = &["create", "str_replace"];
+
+/// Path of the file written by a tool call, if it is a file-writing tool.
+fn written_file_path(tool_name: &str, arguments: &serde_json::Value) -> Option<String> {
+    let normalized = stakpak_server::strip_tool_prefix(tool_name);
+    if !FILE_WRITE_TOOLS.contains(&normalized) {
+        return None;
+    }
+    arguments
+        .get("path")
+        .and_then(|path| path.as_str())
+        .map(str::to_string)
+}
+
 /// Execute a full server session: create → send message → drain events.
 async fn run_server_session(
     client: &StakpakClient,
-    server: &AgentServerConnection,
     config: &SpawnConfig,
+    usage: &mut TokenUsage,
 ) -> Result<AgentResult, ClientError> {
     let session = client
-        .create_session(&format!("autopilot: {}", config.profile))
+        .create_session_with(
+            &format!("autopilot: {}", config.profile),
+            CreateSessionOptions {
+                cwd: config.workdir.clone(),
+                tags: config.tags.clone(),
+            },
+        )
         .await?;
     let session_id = session.id.to_string();
 
@@ -196,14 +545,10 @@ async fn run_server_session(
     };
 
     let opts = SendMessageOptions {
-        model: config
-            .overrides
-            .as_ref()
-            .and_then(|overrides| overrides.model.clone())
-            .or_else(|| server.model.clone()),
+        model: effective_model(config),
         sandbox: if config.sandbox { Some(true) } else { None },
         context: config.caller_context.clone(),
-        overrides: config.overrides.clone(),
+        overrides: effective_overrides(config),
         ..Default::default()
     };
 
@@ -213,7 +558,176 @@ async fn run_server_session(
     let run_id = send_resp.run_id.to_string();
 
     let mut event_stream = client.subscribe_events(&session_id, None).await?;
+    let policy = RunPolicy {
+        pause_on_approval: config.pause_on_approval,
+        allowed_tools: &config.allowed_tools,
+        redact_patterns: &config.redact_patterns,
+    };
+
+    drain_run_events(
+        client,
+        &mut event_stream,
+        &session_id,
+        &run_id,
+        &policy,
+        usage,
+        Vec::new(),
+    )
+    .await
+}
 
+/// Resume a paused run in an existing server session.
+async fn resume_server_session(
+    client: &StakpakClient,
+    config: &ResumeConfig,
+    usage: &mut TokenUsage,
+) -> Result<AgentResult, ClientError> {
+    let session_id = config.session_id.as_str();
+
+    // Subscribe before deciding so no event of the resumed run is missed.
+    let mut event_stream = client.subscribe_events(session_id, None).await?;
+    let (run_id, tool_calls) = pending_approval(client, session_id).await?;
+
+    let mut written_files = Vec::new();
+    if let Some(decision) = config.decision {
+        if decision == ResumeDecision::Approve {
+            for tool_call in &tool_calls {
+                if let Some(path) = written_file_path(&tool_call.name, &tool_call.arguments)
+                    && !written_files.contains(&path)
+                {
+                    written_files.push(path);
+                }
+            }
+        }
+        client
+            .resolve_tools(session_id, &run_id, resume_decisions(&tool_calls, decision))
+            .await?;
+    }
+
+    let policy = RunPolicy {
+        pause_on_approval: config.pause_on_approval,
+        allowed_tools: &config.allowed_tools,
+        redact_patterns: &config.redact_patterns,
+    };
+
+    drain_run_events(
+        client,
+        &mut event_stream,
+        session_id,
+        &run_id,
+        &policy,
+        usage,
+        written_files,
+    )
+    .await
+}
+
+/// Follow an in-flight run in an existing server session.
+async fn adopt_server_session(
+    client: &StakpakClient,
+    config: &AdoptConfig,
+    usage: &mut TokenUsage,
+) -> Result<AgentResult, ClientError> {
+    let session_id = config.session_id.as_str();
+
+    // Subscribe before re-checking the run so its completion is not missed.
+    let mut event_stream = client.subscribe_events(session_id, None).await?;
+    let session = client.get_session(session_id).await?;
+    let still_running = session
+        .active_run_id()
+        .is_some_and(|run_id| run_id.to_string() == config.run_id);
+    if !still_running {
+        info!(session_id = %session_id, "Adopted run finished before it could be followed");
+        return Ok(AgentResult {
+            exit_code: Some(0),
+            session_id: Some(session_id.to_string()),
+            checkpoint_id: None,
+            timed_out: false,
+            paused: false,
+            pause_reason: None,
+            resume_hint: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            written_files: Vec::new(),
+            usage: TokenUsage::default(),
+            estimated_cost: None,
+        });
+    }
+
+    // Tool calls proposed before we subscribed will not be replayed, so a
+    // run already waiting on them is handled here.
+    let mut written_files = Vec::new();
+    if let Ok((run_id, tool_calls)) = pending_approval(client, session_id).await
+        && run_id == config.run_id
+    {
+        if config.pause_on_approval {
+            return Ok(AgentResult {
+                exit_code: Some(EXIT_CODE_PAUSED),
+                session_id: Some(session_id.to_string()),
+                checkpoint_id: None,
+                timed_out: false,
+                paused: true,
+                pause_reason: Some(PauseReason::ToolApprovalRequired {
+                    pending_tool_calls: tool_calls,
+                }),
+                resume_hint: None,
+                stdout: String::new(),
+                stderr: String::new(),
+                written_files,
+                usage: TokenUsage::default(),
+                estimated_cost: None,
+            });
+        }
+
+        let named: Vec<(String, String)> = tool_calls
+            .iter()
+            .map(|tool_call| (tool_call.id.clone(), tool_call.name.clone()))
+            .collect();
+        let decisions = build_tool_decisions(&named, &config.allowed_tools);
+        for tool_call in &tool_calls {
+            let accepted = decisions
+                .get(&tool_call.id)
+                .is_some_and(|decision| matches!(decision.action, ToolDecisionAction::Accept));
+            if accepted
+                && let Some(path) = written_file_path(&tool_call.name, &tool_call.arguments)
+                && !written_files.contains(&path)
+            {
+                written_files.push(path);
+            }
+        }
+        client
+            .resolve_tools(session_id, &config.run_id, decisions)
+            .await?;
+    }
+
+    let policy = RunPolicy {
+        pause_on_approval: config.pause_on_approval,
+        allowed_tools: &config.allowed_tools,
+        redact_patterns: &config.redact_patterns,
+    };
+
+    drain_run_events(
+        client,
+        &mut event_stream,
+        session_id,
+        &config.run_id,
+        &policy,
+        usage,
+        written_files,
+    )
+    .await
+}
+
+/// Drain a run's events until it completes, errors or pauses for approval.
+async fn drain_run_events(
+    client: &StakpakClient,
+    event_stream: &mut EventStream,
+    session_id: &str,
+    run_id: &str,
+    policy: &RunPolicy<'_>,
+    usage: &mut TokenUsage,
+    mut written_files: Vec<String>,
+) -> Result<AgentResult, ClientError> {
     let mut agent_message = String::new();
     let mut paused = false;
     let mut pause_reason: Option<PauseReason> = None;
@@ -227,21 +741,24 @@ async fn run_server_session(
             agent_message.push_str(&delta);
         }
 
+        if let Some(report) = event.as_usage_report() {
+            usage.prompt_tokens += report.usage.prompt_tokens;
+            usage.completion_tokens += report.usage.completion_tokens;
+            usage.total_tokens += report.usage.total_tokens;
+        }
+
         if let Some(proposed) = event.as_tool_calls_proposed() {
-            if config.pause_on_approval {
+            if policy.pause_on_approval {
                 paused = true;
-                let pending: Vec<stakpak_shared::models::async_manifest::PendingToolCall> =
-                    proposed
-                        .tool_calls
-                        .iter()
-                        .map(
-                            |tc| stakpak_shared::models::async_manifest::PendingToolCall {
-                                id: tc.id.clone(),
-                                name: tc.name.clone(),
-                                arguments: tc.arguments.clone(),
-                            },
-                        )
-                        .collect();
+                let pending: Vec<PendingToolCall> = proposed
+                    .tool_calls
+                    .iter()
+                    .map(|tc| PendingToolCall {
+                        id: tc.id.clone(),
+                        name: tc.name.clone(),
+                        arguments: tc.arguments.clone(),
+                    })
+                    .collect();
                 pause_reason = Some(PauseReason::ToolApprovalRequired {
                     pending_tool_calls: pending,
                 });
@@ -253,19 +770,33 @@ async fn run_server_session(
                 .iter()
                 .map(|tool_call| (tool_call.id.clone(), tool_call.name.clone()))
                 .collect();
-            let decisions = build_tool_decisions(&tool_calls, &config.allowed_tools);
-            client
-                .resolve_tools(&session_id, &run_id, decisions)
-                .await?;
+            let decisions = build_tool_decisions(&tool_calls, policy.allowed_tools);
+            for tool_call in &proposed.tool_calls {
+                let accepted = decisions
+                    .get(&tool_call.id)
+                    .is_some_and(|decision| matches!(decision.action, ToolDecisionAction::Accept));
+                if accepted
+                    && let Some(path) = written_file_path(&tool_call.name, &tool_call.arguments)
+                    && !written_files.contains(&path)
+                {
+                    written_files.push(path);
+                }
+            }
+            client.resolve_tools(session_id, run_id, decisions).await?;
         }
 
         if event.as_run_completed().is_some() || event.as_run_error().is_some() {
             if let Some(err) = event.as_run_error() {
                 let error_msg = err.error.unwrap_or_else(|| "unknown error".to_string());
-                info!(session_id = %session_id, error = %error_msg, "Agent run error");
+                info!(
+                    session_id = %session_id,
+                    error = %redact(&error_msg, policy.redact_patterns),
+                    "Agent run error"
+                );
                 return Ok(AgentResult {
-                    exit_code: Some(1),
-                    session_id: Some(session_id),
+                    // The same code the CLI exits with for this kind of error
+                    exit_code: Some(err.kind.exit_code()),
+                    session_id: Some(session_id.to_string()),
                     checkpoint_id: None,
                     timed_out: false,
                     paused: false,
@@ -273,6 +804,9 @@ async fn run_server_session(
                     resume_hint: None,
                     stdout: agent_message,
                     stderr: error_msg,
+                    written_files,
+                    usage: TokenUsage::default(),
+                    estimated_cost: None,
                 });
             }
             break;
@@ -291,7 +825,7 @@ async fn run_server_session(
         } else {
             Some(0)
         },
-        session_id: Some(session_id),
+        session_id: Some(session_id.to_string()),
         checkpoint_id: None,
         timed_out: false,
         paused,
@@ -299,6 +833,9 @@ async fn run_server_session(
         resume_hint: None,
         stdout: agent_message,
         stderr: String::new(),
+        written_files,
+        usage: TokenUsage::default(),
+        estimated_cost: None,
     })
 }
 
@@ -356,6 +893,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_written_file_path_only_for_file_tools() {
+        let arguments = serde_json::json!({ "path": "/tmp/report.md", "file_text": "hi" });
+        assert_eq!(
+            written_file_path("stakpak__create", &arguments).as_deref(),
+            Some("/tmp/report.md")
+        );
+        assert_eq!(
+            written_file_path("str_replace", &arguments).as_deref(),
+            Some("/tmp/report.md")
+        );
+        assert!(written_file_path("stakpak__view", &arguments).is_none());
+        assert!(written_file_path("create", &serde_json::json!({})).is_none());
+    }
+
     #[test]
     fn test_agent_result_success() {
         let result = AgentResult {
@@ -368,6 +920,9 @@ mod tests {
             resume_hint: None,
             stdout: String::new(),
             stderr: String::new(),
+            written_files: Vec::new(),
+            usage: TokenUsage::default(),
+            estimated_cost: None,
         };
 
         assert!(result.success());
@@ -387,6 +942,9 @@ mod tests {
             resume_hint: None,
             stdout: String::new(),
             stderr: "Error occurred".to_string(),
+            written_files: Vec::new(),
+            usage: TokenUsage::default(),
+            estimated_cost: None,
         };
 
         assert!(!result.success());
@@ -406,6 +964,9 @@ mod tests {
             resume_hint: None,
             stdout: String::new(),
             stderr: String::new(),
+            written_files: Vec::new(),
+            usage: TokenUsage::default(),
+            estimated_cost: None,
         };
 
         assert!(!result.success());
@@ -427,6 +988,9 @@ mod tests {
             resume_hint: Some("stakpak -c test-checkpoint --approve-all".to_string()),
             stdout: String::new(),
             stderr: String::new(),
+            written_files: Vec::new(),
+            usage: TokenUsage::default(),
+            estimated_cost: None,
         };
 
         assert!(!result.success());
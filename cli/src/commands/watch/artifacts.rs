@@ -0,0 +1,350 @@
+//! Per-run artifact storage and retention.
+//!
+//! Every run that wakes the agent gets its own `run-<id>/` directory under the
+//! configured artifacts dir, holding the full check and agent output plus
+//! copies of files the agent wrote. The database only keeps a truncated
+//! preview, so this directory is what to look at when debugging a run.
+//! [`RetentionPolicy::enforce`] keeps the directory bounded by age and size.
+
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, warn};
+
+use super::executor::CheckResult;
+
+pub const CHECK_STDOUT_FILE: &str = "check_stdout.log";
+pub const CHECK_STDERR_FILE: &str = "check_stderr.log";
+pub const AGENT_STDOUT_FILE: &str = "agent_stdout.log";
+pub const AGENT_STDERR_FILE: &str = "agent_stderr.log";
+/// Subdirectory holding copies of files the agent wrote, mirroring their original paths.
+pub const FILES_DIR: &str = "files";
+
+/// Files larger than this are not copied into the artifacts directory.
+const MAX_COLLECTED_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+const RUN_DIR_PREFIX: &str = "run-";
+
+/// Directory for a single run's artifacts.
+#[derive(Debug, Clone)]
+pub struct RunArtifacts {
+    dir: PathBuf,
+}
+
+impl RunArtifacts {
+    /// Create (or reuse) the artifacts directory for `run_id` under `root`.
+    pub fn create(root: &Path, run_id: i64) -> io::Result<Self> {
+        let dir = root.join(format!("{}{}", RUN_DIR_PREFIX, run_id));
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Store the full check script output.
+    pub fn write_check_output(&self, result: &CheckResult) -> io::Result<()> {
+        write_if_not_empty(&self.dir.join(CHECK_STDOUT_FILE), &result.stdout)?;
+        write_if_not_empty(&self.dir.join(CHECK_STDERR_FILE), &result.stderr)
+    }
+
+    /// Store the full agent output.
+    pub fn write_agent_output(&self, stdout: &str, stderr: &str) -> io::Result<()> {
+        write_if_not_empty(&self.dir.join(AGENT_STDOUT_FILE), stdout)?;
+        write_if_not_empty(&self.dir.join(AGENT_STDERR_FILE), stderr)
+    }
+
+    /// Copy files the agent wrote into `files/`, keeping their original path
+    /// layout. Missing, oversized or unreadable files are skipped with a
+    /// warning. Returns how many files were copied.
+    pub fn collect_files<S: AsRef<str>>(&self, paths: &[S]) -> usize {
+        let files_dir = self.dir.join(FILES_DIR);
+        let mut copied = 0;
+
+        for path in paths {
+            let source = Path::new(path.as_ref());
+            let metadata = match fs::metadata(source) {
+                Ok(metadata) if metadata.is_file() => metadata,
+                Ok(_) => continue,
+                Err(e) => {
+                    debug!(path = %source.display(), error = %e, "Agent-written file no longer exists");
+                    continue;
+                }
+            };
+            if metadata.len() > MAX_COLLECTED_FILE_BYTES {
+                warn!(
+                    path = %source.display(),
+                    size = metadata.len(),
+                    "Skipping oversized agent-written file"
+                );
+                continue;
+            }
+
+            let destination = files_dir.join(relative_artifact_path(source));
+            let result = destination
+                .parent()
+                .map(fs::create_dir_all)
+                .transpose()
+                .and_then(|_| fs::copy(source, &destination));
+            match result {
+                Ok(_) => copied += 1,
+                Err(e) => {
+                    warn!(path = %source.display(), error = %e, "Failed to copy agent-written file")
+                }
+            }
+        }
+
+        copied
+    }
+}
+
+fn write_if_not_empty(path: &Path, content: &str) -> io::Result<()> {
+    if content.is_empty() {
+        return Ok(());
+    }
+    fs::write(path, content)
+}
+
+/// Map an absolute or relative source path to a path inside `files/`, dropping
+/// root, prefix and `..` components so copies cannot escape the directory.
+fn relative_artifact_path(source: &Path) -> PathBuf {
+    source
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Limits applied to the artifacts directory. `None` disables a limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub max_age: Option<Duration>,
+    pub max_total_bytes: Option<u64>,
+}
+
+impl RetentionPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.max_age.is_some() || self.max_total_bytes.is_some()
+    }
+
+    /// Delete run directories under `root` that exceed the policy.
+    ///
+    /// Runs older than `max_age` are removed first, then the oldest remaining
+    /// runs until the total size fits `max_total_bytes`. The most recent run is
+    /// never removed by the size limit so an active run is not deleted mid-write.
+    pub fn enforce(&self, root: &Path, now: SystemTime) -> io::Result<CleanupReport> {
+        let mut report = CleanupReport::default();
+        if !self.is_enabled() || !root.exists() {
+            return Ok(report);
+        }
+
+        let mut runs = list_run_dirs(root)?;
+        runs.sort_by_key(|run| run.modified);
+
+        if let Some(max_age) = self.max_age {
+            let mut kept = Vec::with_capacity(runs.len());
+            for run in runs {
+                let age = now.duration_since(run.modified).unwrap_or_default();
+                if age > max_age {
+                    remove_run_dir(&run, &mut report);
+                } else {
+                    kept.push(run);
+                }
+            }
+            runs = kept;
+        }
+
+        if let Some(max_total_bytes) = self.max_total_bytes {
+            let mut total: u64 = runs.iter().map(|run| run.size).sum();
+            let removable = runs.len().saturating_sub(1);
+            for run in runs.iter().take(removable) {
+                if total <= max_total_bytes {
+                    break;
+                }
+                total = total.saturating_sub(run.size);
+                remove_run_dir(run, &mut report);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// What a retention pass removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CleanupReport {
+    pub removed_runs: usize,
+    pub freed_bytes: u64,
+}
+
+#[derive(Debug)]
+struct RunDir {
+    path: PathBuf,
+    modified: SystemTime,
+    size: u64,
+}
+
+fn list_run_dirs(root: &Path) -> io::Result<Vec<RunDir>> {
+    let mut runs = Vec::new();
+    for entry in fs::read_dir(root)? {
+        let entry = entry?;
+        let is_run_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false)
+            && entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(RUN_DIR_PREFIX);
+        if !is_run_dir {
+            continue;
+        }
+        let path = entry.path();
+        let (size, modified) = dir_usage(&path)?;
+        runs.push(RunDir {
+            path,
+            modified,
+            size,
+        });
+    }
+    Ok(runs)
+}
+
+/// Total size and most recent modification time of everything under `dir`.
+pub(super) fn dir_usage(dir: &Path) -> io::Result<(u64, SystemTime)> {
+    let mut size = 0;
+    let mut modified = fs::metadata(dir)?.modified()?;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let (entry_size, entry_modified) = if metadata.is_dir() {
+            dir_usage(&entry.path())?
+        } else {
+            (metadata.len(), metadata.modified()?)
+        };
+        size += entry_size;
+        modified = modified.max(entry_modified);
+    }
+    Ok((size, modified))
+}
+
+fn remove_run_dir(run: &RunDir, report: &mut CleanupReport) {
+    match fs::remove_dir_all(&run.path) {
+        Ok(()) => {
+            report.removed_runs += 1;
+            report.freed_bytes += run.size;
+        }
+        Err(e) => warn!(path = %run.path.display(), error = %e, "Failed to remove run artifacts"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_mtime(path: &Path, time: SystemTime) {
+        let file = fs::File::options()
+            .write(true)
+            .open(path)
+            .expect("open artifact file");
+        file.set_modified(time).expect("set mtime");
+    }
+
+    fn run_with_file(root: &Path, run_id: i64, bytes: usize, modified: SystemTime) -> PathBuf {
+        let artifacts = RunArtifacts::create(root, run_id).expect("create run dir");
+        let file = artifacts.dir().join(AGENT_STDOUT_FILE);
+        fs::write(&file, vec![b'x'; bytes]).expect("write output");
+        set_mtime(&file, modified);
+        // Directory mtime is bumped by the write; pin it to the same time.
+        fs::File::open(artifacts.dir())
+            .and_then(|dir| dir.set_modified(modified))
+            .expect("set dir mtime");
+        artifacts.dir().to_path_buf()
+    }
+
+    #[test]
+    fn test_stores_full_output_and_agent_files() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let workspace = tempfile::tempdir().expect("tempdir");
+        let written = workspace.path().join("reports/summary.md");
+        fs::create_dir_all(written.parent().expect("parent")).expect("mkdir");
+        fs::write(&written, "# Summary").expect("write report");
+
+        let artifacts = RunArtifacts::create(root.path(), 7).expect("create run dir");
+        let long_output = "line\n".repeat(50_000);
+        artifacts
+            .write_agent_output(&long_output, "")
+            .expect("write output");
+        let written_str = written.display().to_string();
+        let missing = workspace.path().join("gone.txt").display().to_string();
+        let copied = artifacts.collect_files(&[written_str, missing]);
+
+        assert_eq!(copied, 1);
+        assert_eq!(
+            fs::read_to_string(artifacts.dir().join(AGENT_STDOUT_FILE)).expect("read stdout"),
+            long_output
+        );
+        assert!(!artifacts.dir().join(AGENT_STDERR_FILE).exists());
+        let copy = artifacts
+            .dir()
+            .join(FILES_DIR)
+            .join(relative_artifact_path(&written));
+        assert_eq!(fs::read_to_string(copy).expect("read copy"), "# Summary");
+    }
+
+    #[test]
+    fn test_relative_artifact_path_cannot_escape() {
+        assert_eq!(
+            relative_artifact_path(Path::new("/etc/../tmp/out.txt")),
+            PathBuf::from("etc/tmp/out.txt")
+        );
+        assert_eq!(
+            relative_artifact_path(Path::new("../../secret")),
+            PathBuf::from("secret")
+        );
+    }
+
+    #[test]
+    fn test_retention_removes_old_runs_then_oldest_over_size() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 60 * 60);
+
+        let expired = run_with_file(root.path(), 1, 100, now - day * 40);
+        let oldest = run_with_file(root.path(), 2, 600, now - day * 3);
+        let middle = run_with_file(root.path(), 3, 600, now - day * 2);
+        let newest = run_with_file(root.path(), 4, 600, now - day);
+        fs::create_dir_all(root.path().join("not-a-run")).expect("mkdir");
+
+        let policy = RetentionPolicy {
+            max_age: Some(day * 30),
+            max_total_bytes: Some(1_300),
+        };
+        let report = policy.enforce(root.path(), now).expect("enforce");
+
+        assert_eq!(report.removed_runs, 2);
+        assert_eq!(report.freed_bytes, 700);
+        assert!(!expired.exists());
+        assert!(!oldest.exists());
+        assert!(middle.exists());
+        assert!(newest.exists());
+        assert!(root.path().join("not-a-run").exists());
+    }
+
+    #[test]
+    fn test_retention_keeps_most_recent_run() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let now = SystemTime::now();
+        let only = run_with_file(root.path(), 1, 5_000, now);
+
+        let policy = RetentionPolicy {
+            max_age: None,
+            max_total_bytes: Some(10),
+        };
+        let report = policy.enforce(root.path(), now).expect("enforce");
+
+        assert_eq!(report, CleanupReport::default());
+        assert!(only.exists());
+    }
+}
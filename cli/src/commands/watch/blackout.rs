@@ -0,0 +1,336 @@
+//! Maintenance blackout windows during which schedules do not fire.
+//!
+//! Windows are written as strings, either recurring or a fixed range:
+//! - `"Sat 00:00-06:00 UTC"`: every Saturday from midnight to 6am UTC
+//! - `"Mon-Fri 22:00-02:00 +02:00"`: weeknights; an end before the start runs past midnight
+//! - `"Sat,Sun 00:00-24:00"` or `"03:00-04:00"`: weekends / every day, UTC when no zone is given
+//! - `"2026-12-24..2026-12-26"` or `"2026-12-24 18:00..2027-01-02 09:00 UTC"`: a change freeze;
+//!   a date without a time covers that whole day
+//!
+//! Only fixed zones are supported: `UTC`, `Z` or an offset such as `+05:30`.
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    TimeZone, Utc, Weekday,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// A parsed blackout window, serialized back as the string it was written as.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct BlackoutWindow {
+    spec: String,
+    kind: WindowKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WindowKind {
+    Weekly {
+        /// Days the window starts on, indexed from Monday.
+        days: [bool; 7],
+        start: NaiveTime,
+        end: NaiveTime,
+        offset: FixedOffset,
+    },
+    Range {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+}
+
+impl BlackoutWindow {
+    /// Whether `at` falls inside the window.
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        match &self.kind {
+            WindowKind::Weekly {
+                days,
+                start,
+                end,
+                offset,
+            } => {
+                let local = at.with_timezone(offset);
+                let time = local.time();
+                let today = days[local.weekday().num_days_from_monday() as usize];
+                let yesterday = days[local.weekday().pred().num_days_from_monday() as usize];
+                if start == end {
+                    today
+                } else if start < end {
+                    today && time >= *start && time < *end
+                } else {
+                    (today && time >= *start) || (yesterday && time < *end)
+                }
+            }
+            WindowKind::Range { start, end } => at >= *start && at < *end,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.spec
+    }
+}
+
+impl std::fmt::Display for BlackoutWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.spec)
+    }
+}
+
+impl FromStr for BlackoutWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spec = s.trim();
+        let invalid = |reason: &str| format!("invalid blackout window '{}': {}", spec, reason);
+
+        let kind = if let Some((start, end)) = spec.split_once("..") {
+            parse_range(start, end).map_err(|reason| invalid(&reason))?
+        } else {
+            parse_weekly(spec).map_err(|reason| invalid(&reason))?
+        };
+
+        Ok(Self {
+            spec: spec.to_string(),
+            kind,
+        })
+    }
+}
+
+impl TryFrom<String> for BlackoutWindow {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<BlackoutWindow> for String {
+    fn from(window: BlackoutWindow) -> Self {
+        window.spec
+    }
+}
+
+/// First window in `windows` containing `at`.
+pub fn active_window(windows: &[BlackoutWindow], at: DateTime<Utc>) -> Option<&BlackoutWindow> {
+    windows.iter().find(|window| window.contains(at))
+}
+
+/// Split a trailing time zone token off `tokens`, defaulting to UTC.
+fn take_offset(tokens: &mut Vec<&str>) -> Result<FixedOffset, String> {
+    let Some(last) = tokens.last() else {
+        return Ok(utc_offset());
+    };
+    if last.eq_ignore_ascii_case("utc") || *last == "Z" {
+        tokens.pop();
+        return Ok(utc_offset());
+    }
+    if last.starts_with('+') || last.starts_with('-') {
+        let offset = parse_offset(last)?;
+        tokens.pop();
+        return Ok(offset);
+    }
+    Ok(utc_offset())
+}
+
+fn utc_offset() -> FixedOffset {
+    Utc.fix()
+}
+
+fn parse_offset(value: &str) -> Result<FixedOffset, String> {
+    let (sign, rest) = match value.chars().next() {
+        Some('+') => (1, value.trim_start_matches('+')),
+        Some('-') => (-1, value.trim_start_matches('-')),
+        _ => return Err(format!("unknown time zone '{}'", value)),
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i32 = hours
+        .parse()
+        .map_err(|_| format!("invalid UTC offset '{}'", value))?;
+    let minutes: i32 = minutes
+        .parse()
+        .map_err(|_| format!("invalid UTC offset '{}'", value))?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+        .ok_or_else(|| format!("UTC offset '{}' is out of range", value))
+}
+
+fn parse_weekly(spec: &str) -> Result<WindowKind, String> {
+    let mut tokens: Vec<&str> = spec.split_whitespace().collect();
+    let offset = take_offset(&mut tokens)?;
+
+    let (days, times) = match tokens.as_slice() {
+        [times] => ([true; 7], *times),
+        [days, times] => (parse_days(days)?, *times),
+        _ => return Err("expected '[days] HH:MM-HH:MM [zone]'".to_string()),
+    };
+
+    let (start, end) = times
+        .split_once('-')
+        .ok_or_else(|| format!("expected a time range like 00:00-06:00, got '{}'", times))?;
+
+    Ok(WindowKind::Weekly {
+        days,
+        start: parse_time(start)?,
+        end: parse_time(end)?,
+        offset,
+    })
+}
+
+/// Parse `Sat`, `Mon-Fri` or a comma-separated list of either.
+fn parse_days(value: &str) -> Result<[bool; 7], String> {
+    let mut days = [false; 7];
+    for part in value.split(',') {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (parse_weekday(first)?, parse_weekday(last)?),
+            None => {
+                let day = parse_weekday(part)?;
+                (day, day)
+            }
+        };
+        let mut day = first;
+        loop {
+            days[day.num_days_from_monday() as usize] = true;
+            if day == last {
+                break;
+            }
+            day = day.succ();
+        }
+    }
+    Ok(days)
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    value
+        .trim()
+        .parse::<Weekday>()
+        .map_err(|_| format!("unknown day '{}'", value.trim()))
+}
+
+/// `HH:MM`, with `24:00` accepted as the end of the day.
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    let value = value.trim();
+    if value == "24:00" {
+        return Ok(NaiveTime::MIN);
+    }
+    NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| format!("invalid time '{}'", value))
+}
+
+fn parse_range(start: &str, end: &str) -> Result<WindowKind, String> {
+    let mut end_tokens: Vec<&str> = end.split_whitespace().collect();
+    let offset = take_offset(&mut end_tokens)?;
+
+    let start = parse_range_bound(start, offset, false)?;
+    let end = parse_range_bound(&end_tokens.join(" "), offset, true)?;
+    if end <= start {
+        return Err("range end must be after its start".to_string());
+    }
+    Ok(WindowKind::Range { start, end })
+}
+
+/// `YYYY-MM-DD HH:MM` or `YYYY-MM-DD`. A bare end date includes that whole day.
+fn parse_range_bound(
+    value: &str,
+    offset: FixedOffset,
+    is_end: bool,
+) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    let local = match NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M") {
+        Ok(datetime) => datetime,
+        Err(_) => {
+            let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| format!("invalid date '{}'", value))?;
+            let date = if is_end {
+                date + Duration::days(1)
+            } else {
+                date
+            };
+            date.and_time(NaiveTime::MIN)
+        }
+    };
+    offset
+        .from_local_datetime(&local)
+        .single()
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .ok_or_else(|| format!("invalid date '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .expect("valid timestamp")
+            .with_timezone(&Utc)
+    }
+
+    fn window(spec: &str) -> BlackoutWindow {
+        spec.parse().expect("valid blackout window")
+    }
+
+    #[test]
+    fn test_weekly_window_matches_day_and_time() {
+        // 2026-10-17 is a Saturday.
+        let saturday_morning = window("Sat 00:00-06:00 UTC");
+        assert!(saturday_morning.contains(at("2026-10-17T00:00:00Z")));
+        assert!(saturday_morning.contains(at("2026-10-17T05:59:00Z")));
+        assert!(!saturday_morning.contains(at("2026-10-17T06:00:00Z")));
+        assert!(!saturday_morning.contains(at("2026-10-18T01:00:00Z")));
+
+        let weekends = window("Sat,Sun 00:00-24:00");
+        assert!(weekends.contains(at("2026-10-18T23:30:00Z")));
+        assert!(!weekends.contains(at("2026-10-19T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_overnight_window_with_offset() {
+        // 22:00-02:00 at +02:00 starting Friday 2026-10-16.
+        let weeknights = window("Mon-Fri 22:00-02:00 +02:00");
+        assert!(weeknights.contains(at("2026-10-16T20:30:00Z")));
+        assert!(weeknights.contains(at("2026-10-16T23:59:00Z")));
+        assert!(!weeknights.contains(at("2026-10-17T00:00:00Z")));
+        // Saturday night is not a weeknight.
+        assert!(!weeknights.contains(at("2026-10-17T20:30:00Z")));
+        // Monday 22:00 local.
+        assert!(weeknights.contains(at("2026-10-19T20:00:00Z")));
+    }
+
+    #[test]
+    fn test_date_range_window() {
+        let freeze = window("2026-12-24..2026-12-26");
+        assert!(!freeze.contains(at("2026-12-23T23:59:00Z")));
+        assert!(freeze.contains(at("2026-12-24T00:00:00Z")));
+        assert!(freeze.contains(at("2026-12-26T23:59:00Z")));
+        assert!(!freeze.contains(at("2026-12-27T00:00:00Z")));
+
+        let timed = window("2026-12-24 18:00..2027-01-02 09:00 -05:00");
+        assert!(!timed.contains(at("2026-12-24T22:59:00Z")));
+        assert!(timed.contains(at("2026-12-24T23:00:00Z")));
+        assert!(!timed.contains(at("2027-01-02T14:00:00Z")));
+    }
+
+    #[test]
+    fn test_invalid_windows_are_rejected() {
+        for spec in [
+            "Someday 00:00-06:00",
+            "Sat 25:00-06:00",
+            "Sat 00:00 06:00",
+            "Sat 00:00-06:00 Europe/Berlin",
+            "2026-12-26..2026-12-24",
+            "",
+        ] {
+            assert!(spec.parse::<BlackoutWindow>().is_err(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn test_serializes_as_original_string() {
+        let windows: Vec<BlackoutWindow> =
+            serde_json::from_str(r#"["Sat 00:00-06:00 UTC"]"#).expect("deserialize");
+        assert_eq!(windows[0].as_str(), "Sat 00:00-06:00 UTC");
+        assert_eq!(
+            serde_json::to_string(&windows).expect("serialize"),
+            r#"["Sat 00:00-06:00 UTC"]"#
+        );
+        assert!(serde_json::from_str::<Vec<BlackoutWindow>>(r#"["Sat"]"#).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! Daily token and cost caps for autopilot runs.
+//!
+//! Budgets are set globally under `[watch.budget]` and per schedule under
+//! `[schedules.budget]`. Usage is summed from the runs recorded in the
+//! schedule database since midnight UTC. Costs are estimated from the model
+//! catalog's list prices, so runs on models without known pricing only count
+//! toward token caps. Runs delegated to an interactive gateway session are
+//! not metered.
+
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use stakpak_gateway::client::TokenUsage;
+
+/// Daily limits on agent usage. `None` disables a limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetSettings {
+    /// Maximum prompt + completion tokens per day.
+    #[serde(default)]
+    pub max_tokens_per_day: Option<u64>,
+
+    /// Maximum estimated cost per day, in USD.
+    #[serde(default)]
+    pub max_cost_per_day: Option<f64>,
+}
+
+impl BudgetSettings {
+    pub fn is_enabled(&self) -> bool {
+        self.max_tokens_per_day.is_some() || self.max_cost_per_day.is_some()
+    }
+
+    /// Describe the first limit `usage` has reached, if any.
+    pub fn exceeded(&self, usage: &BudgetUsage) -> Option<String> {
+        if let Some(max_tokens) = self.max_tokens_per_day
+            && usage.tokens >= max_tokens
+        {
+            return Some(format!(
+                "daily token budget reached ({}/{} tokens)",
+                usage.tokens, max_tokens
+            ));
+        }
+        if let Some(max_cost) = self.max_cost_per_day
+            && usage.cost >= max_cost
+        {
+            return Some(format!(
+                "daily cost budget reached (${:.2}/${:.2})",
+                usage.cost, max_cost
+            ));
+        }
+        None
+    }
+}
+
+/// Tokens and estimated cost recorded against a budget.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BudgetUsage {
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+/// Midnight UTC of the day containing `now`; budgets reset at this time.
+pub fn budget_day_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.date_naive().and_time(NaiveTime::MIN).and_utc()
+}
+
+/// Estimated USD cost of `usage` on `model`, `None` when its pricing is unknown.
+pub fn estimate_cost(model: Option<&str>, usage: &TokenUsage) -> Option<f64> {
+    let cost = stakpak_api::find_model(model?, false)?.cost?;
+    Some(cost.calculate(usage.prompt_tokens, usage.completion_tokens))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exceeded_reports_first_reached_limit() {
+        let budget = BudgetSettings {
+            max_tokens_per_day: Some(10_000),
+            max_cost_per_day: Some(5.0),
+        };
+
+        assert_eq!(
+            budget.exceeded(&BudgetUsage {
+                tokens: 9_999,
+                cost: 4.99
+            }),
+            None
+        );
+        assert_eq!(
+            budget.exceeded(&BudgetUsage {
+                tokens: 10_000,
+                cost: 1.0
+            }),
+            Some("daily token budget reached (10000/10000 tokens)".to_string())
+        );
+        assert_eq!(
+            budget.exceeded(&BudgetUsage {
+                tokens: 100,
+                cost: 5.125
+            }),
+            Some("daily cost budget reached ($5.12/$5.00)".to_string())
+        );
+        assert!(!BudgetSettings::default().is_enabled());
+    }
+
+    #[test]
+    fn test_budget_day_starts_at_midnight_utc() {
+        let now = DateTime::parse_from_rfc3339("2026-10-14T23:59:30+00:00")
+            .expect("valid timestamp")
+            .with_timezone(&Utc);
+        assert_eq!(
+            budget_day_start(now).to_rfc3339(),
+            "2026-10-14T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_estimate_cost_requires_known_model() {
+        let usage = TokenUsage {
+            prompt_tokens: 1_000_000,
+            completion_tokens: 0,
+            total_tokens: 1_000_000,
+        };
+        assert_eq!(estimate_cost(None, &usage), None);
+        assert_eq!(estimate_cost(Some("not-a-real-model"), &usage), None);
+    }
+}
@@ -1,7 +1,297 @@
 //! Autopilot history command - show run history.
 
-use crate::commands::watch::{ListRunsFilter, RunStatus, ScheduleConfig, ScheduleDb};
-use chrono::{DateTime, Utc};
+use crate::commands::gateway::{GatewayServerArgs, fetch_session_events};
+use crate::commands::watch::{
+    CheckOutput, ListRunsFilter, RunStatus, ScheduleConfig, ScheduleDb, ScheduleRun,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use stakpak_gateway::api::GatewayReplayedEvent;
+use stakpak_shared::error::StakpakError;
+use stakpak_shared::models::async_manifest::{AsyncManifest, PauseReason};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often `runs --follow` polls the database for new runs and status changes.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Most agent session events replayed for a run's timeline.
+const TIMELINE_EVENT_LIMIT: usize = 2_000;
+
+/// Options for the `autopilot runs` command.
+#[derive(Debug, Clone, Default)]
+pub struct RunsOptions {
+    pub schedule: Option<String>,
+    pub status: Option<RunStatus>,
+    pub since: Option<DateTime<Utc>>,
+    pub limit: u32,
+    pub follow: bool,
+    pub json: bool,
+}
+
+/// Machine-readable run record for `runs --json`.
+#[derive(Debug, Serialize)]
+struct RunJson {
+    id: i64,
+    schedule: String,
+    status: String,
+    started_at: String,
+    finished_at: Option<String>,
+    duration_secs: Option<i64>,
+    check_exit_code: Option<i32>,
+    agent_session_id: Option<String>,
+    artifacts_dir: Option<String>,
+    total_tokens: Option<u64>,
+    estimated_cost: Option<f64>,
+    error_message: Option<String>,
+    /// Stable code of a failed run's error, e.g. `auth`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_code: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_hint: Option<&'static str>,
+}
+
+/// Machine-readable run with its timeline for `schedule show --json`.
+#[derive(Debug, Serialize)]
+struct RunInspectJson {
+    #[serde(flatten)]
+    run: RunJson,
+    timeline: Vec<TimelineEntry>,
+    /// Why the agent session's events could not be fetched; the timeline
+    /// then only holds what the run record knows.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    events_error: Option<String>,
+}
+
+/// One step of a run: its check, the agent and its tool calls, pauses and
+/// the outcome.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct TimelineEntry {
+    /// When the step happened, if the record or event says.
+    at: Option<DateTime<Utc>>,
+    kind: TimelineKind,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum TimelineKind {
+    CheckStarted,
+    CheckFinished,
+    AgentSpawned,
+    AgentResumed,
+    ToolProposed,
+    ToolExecuted,
+    ToolFailed,
+    ToolRejected,
+    Paused,
+    AgentCompleted,
+    AgentFailed,
+    RunFinished,
+}
+
+impl TimelineKind {
+    fn label(self) -> &'static str {
+        match self {
+            TimelineKind::CheckStarted => "check started",
+            TimelineKind::CheckFinished => "check finished",
+            TimelineKind::AgentSpawned => "agent spawned",
+            TimelineKind::AgentResumed => "agent resumed",
+            TimelineKind::ToolProposed => "tool proposed",
+            TimelineKind::ToolExecuted => "tool executed",
+            TimelineKind::ToolFailed => "tool failed",
+            TimelineKind::ToolRejected => "tool rejected",
+            TimelineKind::Paused => "paused",
+            TimelineKind::AgentCompleted => "agent completed",
+            TimelineKind::AgentFailed => "agent failed",
+            TimelineKind::RunFinished => "run finished",
+        }
+    }
+}
+
+impl From<&ScheduleRun> for RunJson {
+    fn from(run: &ScheduleRun) -> Self {
+        let error = run.error();
+        Self {
+            id: run.id,
+            schedule: run.schedule_name.clone(),
+            status: run.status.to_string(),
+            started_at: run.started_at.to_rfc3339(),
+            finished_at: run.finished_at.map(|dt| dt.to_rfc3339()),
+            duration_secs: run
+                .finished_at
+                .map(|finished| (finished - run.started_at).num_seconds()),
+            check_exit_code: run.check_exit_code,
+            agent_session_id: run.agent_session_id.clone(),
+            artifacts_dir: run.artifacts_dir.clone(),
+            total_tokens: run.total_tokens,
+            estimated_cost: run.estimated_cost,
+            error_message: run.error_text(),
+            error_code: error.as_ref().map(StakpakError::code),
+            error_hint: error.as_ref().map(StakpakError::hint),
+        }
+    }
+}
+
+/// Parse a `--since` value: a relative duration (`2h`, `30m`, `7d`), an RFC 3339
+/// timestamp, or a `YYYY-MM-DD` date (midnight UTC).
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_since_at(value, Utc::now())
+}
+
+fn parse_since_at(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+
+    if let Ok(duration) = humantime::parse_duration(value) {
+        let duration = chrono::Duration::from_std(duration)
+            .map_err(|_| format!("--since duration '{}' is too large", value))?;
+        return Ok(now - duration);
+    }
+
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        && let Some(midnight) = date.and_hms_opt(0, 0, 0)
+    {
+        return Ok(midnight.and_utc());
+    }
+
+    Err(format!(
+        "Invalid --since value '{}': expected a duration (e.g. 2h, 30m), RFC 3339 timestamp, or YYYY-MM-DD date",
+        value
+    ))
+}
+
+/// Query run history with filters, optionally tailing new runs.
+pub async fn show_runs(options: RunsOptions) -> Result<(), String> {
+    let config = ScheduleConfig::load_default()
+        .map_err(|e| format!("Failed to load watch config: {}", e))?;
+
+    let db = ScheduleDb::open(&config.watch)
+        .await
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+
+    let filter = ListRunsFilter {
+        schedule_name: options.schedule.clone(),
+        status: options.status,
+        since: options.since,
+        limit: Some(options.limit),
+        ..Default::default()
+    };
+
+    let runs = db
+        .list_runs(&filter)
+        .await
+        .map_err(|e| format!("Failed to list runs: {}", e))?;
+
+    if !options.follow {
+        if options.json {
+            let json: Vec<RunJson> = runs.iter().map(RunJson::from).collect();
+            let text = serde_json::to_string_pretty(&json)
+                .map_err(|e| format!("Failed to serialize runs: {}", e))?;
+            println!("{}", text);
+        } else if runs.is_empty() {
+            println!("No runs found.");
+        } else {
+            print_runs_header();
+            for run in &runs {
+                print_run_row(run, &config);
+            }
+        }
+        return Ok(());
+    }
+
+    follow_runs(&db, &config, &options, runs).await
+}
+
+/// Print the initial page oldest-first, then poll for new runs and status
+/// transitions until interrupted.
+async fn follow_runs(
+    db: &ScheduleDb,
+    config: &ScheduleConfig,
+    options: &RunsOptions,
+    initial: Vec<ScheduleRun>,
+) -> Result<(), String> {
+    let emit = |run: &ScheduleRun| {
+        if options.json {
+            if let Ok(line) = serde_json::to_string(&RunJson::from(run)) {
+                println!("{}", line);
+            }
+        } else {
+            print_run_row(run, config);
+        }
+    };
+    let matches_status =
+        |run: &ScheduleRun| options.status.is_none_or(|status| run.status == status);
+
+    if !options.json {
+        print_runs_header();
+    }
+
+    // Runs that have not reached a terminal status yet, with their last seen status.
+    let mut tracked: HashMap<i64, RunStatus> = HashMap::new();
+    let mut last_id = initial.iter().map(|run| run.id).max().unwrap_or(0);
+
+    for run in initial.iter().rev() {
+        emit(run);
+        if !is_terminal(run.status) {
+            tracked.insert(run.id, run.status);
+        }
+    }
+
+    // New runs are fetched without the status filter: they start as `running`
+    // and are emitted once they reach a matching status.
+    let new_runs_filter = |after_id: i64| ListRunsFilter {
+        schedule_name: options.schedule.clone(),
+        since: options.since,
+        after_id: Some(after_id),
+        ..Default::default()
+    };
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = tokio::time::sleep(FOLLOW_POLL_INTERVAL) => {}
+        }
+
+        let new_runs = db
+            .list_runs(&new_runs_filter(last_id))
+            .await
+            .map_err(|e| format!("Failed to list runs: {}", e))?;
+
+        for run in new_runs.iter().rev() {
+            last_id = last_id.max(run.id);
+            if matches_status(run) {
+                emit(run);
+            }
+            if !is_terminal(run.status) {
+                tracked.insert(run.id, run.status);
+            }
+        }
+
+        let ids: Vec<i64> = tracked.keys().copied().collect();
+        for id in ids {
+            let Ok(run) = db.get_run(id).await else {
+                tracked.remove(&id);
+                continue;
+            };
+            if tracked.get(&id) == Some(&run.status) {
+                continue;
+            }
+            if matches_status(&run) {
+                emit(&run);
+            }
+            if is_terminal(run.status) {
+                tracked.remove(&id);
+            } else {
+                tracked.insert(id, run.status);
+            }
+        }
+    }
+}
 
 /// Show run history for all schedules or a specific schedule.
 pub async fn show_history(schedule_name: Option<&str>, limit: Option<u32>) -> Result<(), String> {
@@ -10,21 +300,15 @@ pub async fn show_history(schedule_name: Option<&str>, limit: Option<u32>) -> Re
         .map_err(|e| format!("Failed to load watch config: {}", e))?;
 
     // Connect to database
-    let db_path = config.db_path();
-    let db_path_str = db_path
-        .to_str()
-        .ok_or_else(|| "Invalid database path".to_string())?;
-
-    let db = ScheduleDb::new(db_path_str)
+    let db = ScheduleDb::open(&config.watch)
         .await
         .map_err(|e| format!("Failed to open database: {}", e))?;
 
     // Build filter
     let filter = ListRunsFilter {
         schedule_name: schedule_name.map(|s| s.to_string()),
-        status: None,
         limit: Some(limit.unwrap_or(20)),
-        offset: None,
+        ..Default::default()
     };
 
     // Get runs
@@ -49,67 +333,22 @@ pub async fn show_history(schedule_name: Option<&str>, limit: Option<u32>) -> Re
         println!("Run history ({} runs):\n", runs.len());
     }
 
-    println!(
-        "{:<6} {:<20} {:<20} {:<12} {:<8} {:<20} SESSION",
-        "ID", "SCHEDULE", "STARTED", "STATUS", "SANDBOX", "FINISHED"
-    );
-    println!("{}", "-".repeat(108));
-
-    for run in runs {
-        let status_str = format_status(&run.status);
-        let finished_str = run
-            .finished_at
-            .map(|dt| format_datetime(&dt))
-            .unwrap_or_else(|| "-".to_string());
-        let session_str = run
-            .agent_session_id
-            .as_ref()
-            .map(|s| truncate(s, 20))
-            .unwrap_or_else(|| "-".to_string());
-
-        // Look up the schedule config to determine if sandbox was enabled
-        let sandbox_enabled = config
-            .schedules
-            .iter()
-            .find(|s| s.name == run.schedule_name)
-            .map(|s| s.effective_sandbox(&config.defaults))
-            .unwrap_or(false);
-
-        println!(
-            "{:<6} {:<20} {:<20} {:<12} {:<8} {:<20} {}",
-            run.id,
-            truncate(&run.schedule_name, 20),
-            format_datetime(&run.started_at),
-            status_str,
-            if sandbox_enabled { "yes" } else { "no" },
-            finished_str,
-            session_str
-        );
-
-        // Show error message if failed
-        if (run.status == RunStatus::Failed || run.status == RunStatus::TimedOut)
-            && let Some(error) = &run.error_message
-        {
-            println!("       \x1b[31mError: {}\x1b[0m", truncate(error, 80));
-        }
+    print_runs_header();
+    for run in &runs {
+        print_run_row(run, &config);
     }
 
     Ok(())
 }
 
-/// Show detailed information about a specific run.
-pub async fn show_run(run_id: i64) -> Result<(), String> {
+/// Show detailed information about a specific run and its timeline.
+pub async fn show_run(run_id: i64, server: &GatewayServerArgs, json: bool) -> Result<(), String> {
     // Load configuration
     let config = ScheduleConfig::load_default()
         .map_err(|e| format!("Failed to load watch config: {}", e))?;
 
     // Connect to database
-    let db_path = config.db_path();
-    let db_path_str = db_path
-        .to_str()
-        .ok_or_else(|| "Invalid database path".to_string())?;
-
-    let db = ScheduleDb::new(db_path_str)
+    let db = ScheduleDb::open(&config.watch)
         .await
         .map_err(|e| format!("Failed to open database: {}", e))?;
 
@@ -119,6 +358,24 @@ pub async fn show_run(run_id: i64) -> Result<(), String> {
         .await
         .map_err(|e| format!("Failed to get run: {}", e))?;
 
+    let (events, events_error) = match run_session_events(&run, server).await {
+        Ok(events) => (events, None),
+        Err(error) => (Vec::new(), Some(error)),
+    };
+    let timeline = run_timeline(&run, &events);
+
+    if json {
+        let inspect = RunInspectJson {
+            run: RunJson::from(&run),
+            timeline,
+            events_error,
+        };
+        let text = serde_json::to_string_pretty(&inspect)
+            .map_err(|e| format!("Failed to serialize run: {}", e))?;
+        println!("{}", text);
+        return Ok(());
+    }
+
     // Print run details
     println!("\x1b[1mRun #{}\x1b[0m", run.id);
     println!();
@@ -139,6 +396,8 @@ pub async fn show_run(run_id: i64) -> Result<(), String> {
         println!("Duration:   {}", format_duration(duration.num_seconds()));
     }
 
+    print_timeline(&timeline, events_error.as_deref());
+
     // Check script results
     if run.check_exit_code.is_some() || run.check_timed_out {
         println!();
@@ -158,8 +417,23 @@ pub async fn show_run(run_id: i64) -> Result<(), String> {
         if run.check_timed_out {
             println!("  Result: \x1b[31mtimed out\x1b[0m");
         } else if let Some(code) = run.check_exit_code {
-            let should_trigger = trigger_on.should_trigger(code);
-            let result_str = if should_trigger {
+            let output_trigger = run
+                .check_stdout
+                .as_deref()
+                .and_then(CheckOutput::parse)
+                .and_then(|output| output.trigger);
+            let should_trigger = output_trigger.unwrap_or_else(|| trigger_on.should_trigger(code));
+            let result_str = if let Some(trigger) = output_trigger {
+                let (color, outcome) = if trigger {
+                    ("32", "triggered")
+                } else {
+                    ("33", "skipped")
+                };
+                format!(
+                    "\x1b[{}m{} (check output set trigger={})\x1b[0m",
+                    color, outcome, trigger
+                )
+            } else if should_trigger {
                 format!(
                     "\x1b[32mtriggered (exit {} matches trigger_on={})\x1b[0m",
                     code, trigger_on
@@ -208,14 +482,22 @@ pub async fn show_run(run_id: i64) -> Result<(), String> {
         if let Some(checkpoint_id) = &run.agent_last_checkpoint_id {
             println!("  Checkpoint:   {}", checkpoint_id);
         }
+        if let Some(usage) = usage_str(&run) {
+            println!("  Usage:        {}", usage);
+        }
+        if let Some(artifacts_dir) = &run.artifacts_dir {
+            if std::path::Path::new(artifacts_dir).exists() {
+                println!("  Artifacts:    {}", artifacts_dir);
+            } else {
+                println!("  Artifacts:    {} \x1b[2m(pruned)\x1b[0m", artifacts_dir);
+            }
+        }
 
         // Try to parse agent output as AsyncManifest for human-friendly display
         if let Some(stdout) = &run.agent_stdout
             && !stdout.trim().is_empty()
         {
-            if let Some(manifest) =
-                stakpak_shared::models::async_manifest::AsyncManifest::try_parse(stdout)
-            {
+            if let Some(manifest) = AsyncManifest::try_parse(stdout) {
                 // Outcome
                 let outcome_display = match manifest.outcome.as_str() {
                     "completed" => "\x1b[32mcompleted\x1b[0m".to_string(),
@@ -257,13 +539,13 @@ pub async fn show_run(run_id: i64) -> Result<(), String> {
                     println!();
                     println!("\x1b[1mPause Reason\x1b[0m");
                     match pause_reason {
-                        stakpak_shared::models::async_manifest::PauseReason::ToolApprovalRequired { pending_tool_calls } => {
+                        PauseReason::ToolApprovalRequired { pending_tool_calls } => {
                             println!("  Waiting for tool approval:");
                             for tc in pending_tool_calls {
                                 println!("    • {} ({})", tc.name, tc.id);
                             }
                         }
-                        stakpak_shared::models::async_manifest::PauseReason::InputRequired => {
+                        PauseReason::InputRequired => {
                             println!("  Waiting for user input");
                         }
                     }
@@ -318,10 +600,18 @@ pub async fn show_run(run_id: i64) -> Result<(), String> {
         }
     }
 
-    // Error message
-    if let Some(error) = &run.error_message {
+    // Error message with its code and hint, or why the run was skipped
+    if let Some(error) = run.error() {
         println!();
-        println!("\x1b[31mError: {}\x1b[0m", error);
+        println!("\x1b[31mError: {} [{}]\x1b[0m", error.message, error.code());
+        println!("Hint: {}", error.hint());
+    } else if let Some(message) = &run.error_message {
+        println!();
+        if run.status == RunStatus::Skipped {
+            println!("Skipped: {}", message);
+        } else {
+            println!("\x1b[31mError: {}\x1b[0m", message);
+        }
     }
 
     // Show resume hint if applicable
@@ -338,6 +628,272 @@ pub async fn show_run(run_id: i64) -> Result<(), String> {
     Ok(())
 }
 
+/// Events of the run's agent session, replayed from the autopilot server.
+/// Runs that never woke the agent have none.
+async fn run_session_events(
+    run: &ScheduleRun,
+    server: &GatewayServerArgs,
+) -> Result<Vec<GatewayReplayedEvent>, String> {
+    let Some(session_id) = run.agent_session_id.as_deref() else {
+        return Ok(Vec::new());
+    };
+    fetch_session_events(server, session_id, 0, TIMELINE_EVENT_LIMIT)
+        .await
+        .map(|response| response.events)
+}
+
+/// Build a run's timeline from its record and its agent session's events.
+/// Without events, the agent part falls back to what the record holds.
+fn run_timeline(run: &ScheduleRun, events: &[GatewayReplayedEvent]) -> Vec<TimelineEntry> {
+    let entry = |at, kind, detail: String| TimelineEntry { at, kind, detail };
+    let mut timeline = Vec::new();
+
+    if run.check_timed_out {
+        timeline.push(entry(
+            Some(run.started_at),
+            TimelineKind::CheckStarted,
+            String::new(),
+        ));
+        timeline.push(entry(
+            None,
+            TimelineKind::CheckFinished,
+            "timed out".to_string(),
+        ));
+    } else if let Some(code) = run.check_exit_code {
+        timeline.push(entry(
+            Some(run.started_at),
+            TimelineKind::CheckStarted,
+            String::new(),
+        ));
+        timeline.push(entry(
+            None,
+            TimelineKind::CheckFinished,
+            format!("exit {}", code),
+        ));
+    }
+
+    let mut spawned = None;
+    if run.agent_woken {
+        spawned = Some(timeline.len());
+        let detail = run
+            .agent_session_id
+            .as_ref()
+            .map(|session_id| format!("session {}", session_id))
+            .unwrap_or_default();
+        timeline.push(entry(None, TimelineKind::AgentSpawned, detail));
+    }
+
+    let mut paused = false;
+    for event in events {
+        // Envelopes carry the payload as `{"event": {"Variant": payload}}`.
+        let Some((variant, payload)) = event
+            .data
+            .get("event")
+            .and_then(|value| value.as_object())
+            .and_then(|object| object.iter().next())
+        else {
+            continue;
+        };
+        let at = event
+            .data
+            .get("timestamp")
+            .and_then(|value| value.as_str())
+            .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+            .map(|timestamp| timestamp.with_timezone(&Utc));
+        let field = |name: &str| {
+            payload
+                .get(name)
+                .and_then(|value| value.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        match variant.as_str() {
+            "RunStarted" => match spawned.take() {
+                Some(index) => {
+                    if let Some(spawned) = timeline.get_mut(index) {
+                        spawned.at = at;
+                    }
+                }
+                None => timeline.push(entry(at, TimelineKind::AgentResumed, String::new())),
+            },
+            "ToolCallsProposed" => {
+                let calls = payload
+                    .get("tool_calls")
+                    .and_then(|value| value.as_array())
+                    .into_iter()
+                    .flatten();
+                for call in calls {
+                    let name = call
+                        .get("name")
+                        .and_then(|value| value.as_str())
+                        .unwrap_or_default();
+                    timeline.push(entry(at, TimelineKind::ToolProposed, name.to_string()));
+                }
+            }
+            "WaitingForToolApproval" => {
+                let pending = payload
+                    .get("pending_tool_call_ids")
+                    .and_then(|value| value.as_array())
+                    .map_or(0, Vec::len);
+                paused = true;
+                timeline.push(entry(
+                    at,
+                    TimelineKind::Paused,
+                    format!("waiting for approval of {} tool call(s)", pending),
+                ));
+            }
+            "ToolExecutionCompleted" => {
+                let failed = payload
+                    .get("is_error")
+                    .and_then(|value| value.as_bool())
+                    .unwrap_or(false);
+                let kind = if failed {
+                    TimelineKind::ToolFailed
+                } else {
+                    TimelineKind::ToolExecuted
+                };
+                timeline.push(entry(at, kind, field("tool_name")));
+            }
+            "ToolRejected" => timeline.push(entry(
+                at,
+                TimelineKind::ToolRejected,
+                format!("{}: {}", field("tool_name"), field("reason")),
+            )),
+            "RunCompleted" => {
+                let turns = payload
+                    .get("total_turns")
+                    .and_then(|value| value.as_u64())
+                    .unwrap_or(0);
+                timeline.push(entry(
+                    at,
+                    TimelineKind::AgentCompleted,
+                    format!("{} after {} turn(s)", field("stop_reason"), turns),
+                ));
+            }
+            "RunError" => timeline.push(entry(at, TimelineKind::AgentFailed, field("error"))),
+            _ => {}
+        }
+    }
+
+    // Without session events, the agent's manifest still says why it paused.
+    if run.status == RunStatus::Paused && !paused {
+        let detail = run
+            .agent_stdout
+            .as_deref()
+            .and_then(AsyncManifest::try_parse)
+            .and_then(|manifest| manifest.pause_reason)
+            .map(|reason| match reason {
+                PauseReason::ToolApprovalRequired { pending_tool_calls } => format!(
+                    "waiting for approval of {} tool call(s)",
+                    pending_tool_calls.len()
+                ),
+                PauseReason::InputRequired => "waiting for input".to_string(),
+            })
+            .unwrap_or_default();
+        timeline.push(entry(None, TimelineKind::Paused, detail));
+    }
+
+    if let Some(finished_at) = run.finished_at {
+        let detail = match run.error_text() {
+            Some(error) => format!("{}: {}", run.status, error),
+            None => run.status.to_string(),
+        };
+        timeline.push(entry(Some(finished_at), TimelineKind::RunFinished, detail));
+    }
+
+    timeline
+}
+
+fn print_timeline(timeline: &[TimelineEntry], events_error: Option<&str>) {
+    if timeline.is_empty() {
+        return;
+    }
+    println!();
+    println!("\x1b[1mTimeline\x1b[0m");
+    for entry in timeline {
+        let time = entry
+            .at
+            .map(|at| at.format("%H:%M:%S").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "  {:<8}  {:<15}  {}",
+            time,
+            entry.kind.label(),
+            truncate(&entry.detail, 100)
+        );
+    }
+    if let Some(error) = events_error {
+        println!(
+            "  \x1b[2m(agent session events unavailable: {})\x1b[0m",
+            error
+        );
+    }
+}
+
+fn is_terminal(status: RunStatus) -> bool {
+    !matches!(status, RunStatus::Running | RunStatus::Paused)
+}
+
+fn print_runs_header() {
+    println!(
+        "{:<6} {:<20} {:<20} {:<12} {:<8} {:<20} {:<24} SESSION",
+        "ID", "SCHEDULE", "STARTED", "STATUS", "SANDBOX", "FINISHED", "USAGE"
+    );
+    println!("{}", "-".repeat(133));
+}
+
+fn print_run_row(run: &ScheduleRun, config: &ScheduleConfig) {
+    let finished_str = run
+        .finished_at
+        .map(|dt| format_datetime(&dt))
+        .unwrap_or_else(|| "-".to_string());
+    let session_str = run
+        .agent_session_id
+        .as_ref()
+        .map(|s| truncate(s, 20))
+        .unwrap_or_else(|| "-".to_string());
+
+    // Look up the schedule config to determine if sandbox was enabled
+    let sandbox_enabled = config
+        .schedules
+        .iter()
+        .find(|s| s.name == run.schedule_name)
+        .map(|s| s.effective_sandbox(&config.defaults))
+        .unwrap_or(false);
+
+    println!(
+        "{:<6} {:<20} {:<20} {:<12} {:<8} {:<20} {:<24} {}",
+        run.id,
+        truncate(&run.schedule_name, 20),
+        format_datetime(&run.started_at),
+        format_status(&run.status),
+        if sandbox_enabled { "yes" } else { "no" },
+        finished_str,
+        usage_str(run).unwrap_or_else(|| "-".to_string()),
+        session_str
+    );
+
+    // Show error message if failed
+    if (run.status == RunStatus::Failed || run.status == RunStatus::TimedOut)
+        && let Some(error) = run.error_text()
+    {
+        println!("       \x1b[31mError: {}\x1b[0m", truncate(&error, 80));
+    }
+
+    if run.status == RunStatus::Skipped
+        && let Some(reason) = &run.error_message
+    {
+        println!("       \x1b[90m{}\x1b[0m", truncate(reason, 80));
+    }
+}
+
+/// Tokens and estimated cost of a run's agent, if it reported any.
+fn usage_str(run: &ScheduleRun) -> Option<String> {
+    run.total_tokens
+        .map(|tokens| stakpak_gateway::client::format_usage(tokens, run.estimated_cost))
+}
+
 /// Format a datetime for display.
 fn format_datetime(dt: &DateTime<Utc>) -> String {
     dt.format("%Y-%m-%d %H:%M:%S").to_string()
@@ -382,3 +938,161 @@ fn truncate(s: &str, max_len: usize) -> String {
         format!("{}...", truncated)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-10T12:00:00Z")
+            .expect("valid timestamp")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_since_relative_duration() {
+        let since = parse_since_at("2h", now()).expect("should parse duration");
+        assert_eq!(since.to_rfc3339(), "2026-03-10T10:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_since_timestamp_and_date() {
+        let since =
+            parse_since_at("2026-03-01T08:30:00+02:00", now()).expect("should parse timestamp");
+        assert_eq!(since.to_rfc3339(), "2026-03-01T06:30:00+00:00");
+
+        let since = parse_since_at("2026-03-01", now()).expect("should parse date");
+        assert_eq!(since.to_rfc3339(), "2026-03-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_since_rejects_garbage() {
+        assert!(parse_since_at("yesterday-ish", now()).is_err());
+    }
+
+    fn event(
+        id: u64,
+        timestamp: &str,
+        variant: &str,
+        payload: serde_json::Value,
+    ) -> GatewayReplayedEvent {
+        GatewayReplayedEvent {
+            id: Some(id),
+            event_type: "agent_event".to_string(),
+            run_id: Some("run-1".to_string()),
+            data: serde_json::json!({
+                "id": id,
+                "session_id": "session-1",
+                "timestamp": timestamp,
+                "event": { variant: payload },
+            }),
+        }
+    }
+
+    fn woken_run() -> ScheduleRun {
+        ScheduleRun {
+            id: 7,
+            schedule_name: "disk-check".to_string(),
+            started_at: now(),
+            finished_at: Some(now() + chrono::Duration::seconds(95)),
+            check_exit_code: Some(1),
+            check_stdout: None,
+            check_stderr: None,
+            check_timed_out: false,
+            agent_woken: true,
+            interactive_delegated: false,
+            agent_session_id: Some("session-1".to_string()),
+            agent_last_checkpoint_id: None,
+            agent_stdout: None,
+            agent_stderr: None,
+            artifacts_dir: None,
+            total_tokens: None,
+            estimated_cost: None,
+            status: RunStatus::Completed,
+            error_message: None,
+            created_at: now(),
+        }
+    }
+
+    #[test]
+    fn test_run_timeline_merges_record_and_session_events() {
+        let events = vec![
+            event(
+                1,
+                "2026-03-10T12:00:03Z",
+                "RunStarted",
+                serde_json::json!({"run_id": "run-1"}),
+            ),
+            event(
+                2,
+                "2026-03-10T12:00:05Z",
+                "TextDelta",
+                serde_json::json!({"delta": "hi"}),
+            ),
+            event(
+                3,
+                "2026-03-10T12:00:06Z",
+                "ToolCallsProposed",
+                serde_json::json!({"tool_calls": [{"id": "c1", "name": "run_command", "arguments": {}}]}),
+            ),
+            event(
+                4,
+                "2026-03-10T12:00:09Z",
+                "ToolExecutionCompleted",
+                serde_json::json!({"tool_call_id": "c1", "tool_name": "run_command", "result": "ok", "is_error": false}),
+            ),
+            event(
+                5,
+                "2026-03-10T12:01:30Z",
+                "RunCompleted",
+                serde_json::json!({"total_turns": 2, "stop_reason": "completed"}),
+            ),
+        ];
+
+        let timeline = run_timeline(&woken_run(), &events);
+        let steps: Vec<(TimelineKind, &str)> = timeline
+            .iter()
+            .map(|entry| (entry.kind, entry.detail.as_str()))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (TimelineKind::CheckStarted, ""),
+                (TimelineKind::CheckFinished, "exit 1"),
+                (TimelineKind::AgentSpawned, "session session-1"),
+                (TimelineKind::ToolProposed, "run_command"),
+                (TimelineKind::ToolExecuted, "run_command"),
+                (TimelineKind::AgentCompleted, "completed after 2 turn(s)"),
+                (TimelineKind::RunFinished, "completed"),
+            ]
+        );
+        assert_eq!(
+            timeline[2].at.map(|at| at.to_rfc3339()).as_deref(),
+            Some("2026-03-10T12:00:03+00:00")
+        );
+
+        let json = serde_json::to_value(&timeline[3]).expect("serialize entry");
+        assert_eq!(json["kind"], "tool_proposed");
+    }
+
+    #[test]
+    fn test_run_timeline_without_events_uses_the_record() {
+        let mut run = woken_run();
+        run.status = RunStatus::Paused;
+        run.finished_at = None;
+
+        let kinds: Vec<TimelineKind> = run_timeline(&run, &[])
+            .iter()
+            .map(|entry| entry.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TimelineKind::CheckStarted,
+                TimelineKind::CheckFinished,
+                TimelineKind::AgentSpawned,
+                TimelineKind::Paused,
+            ]
+        );
+    }
+}
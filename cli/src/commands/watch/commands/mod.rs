@@ -4,4 +4,4 @@ pub mod history;
 mod run;
 pub mod schedule;
 
-pub use run::run_scheduler;
+pub use run::{HEARTBEAT_STALE_SECONDS, run_scheduler};
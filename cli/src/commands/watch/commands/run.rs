@@ -8,35 +8,59 @@
 //! 5. Runs the scheduler loop
 //! 6. Handles graceful shutdown on SIGTERM/SIGINT
 
-use crate::commands::watch::db::RELOAD_SENTINEL;
+use crate::commands::watch::agent::{
+    AdoptConfig, AgentError, AgentResult, OutstandingSession, ResumeConfig, ResumeDecision,
+    adopt_agent, find_outstanding_session, resolve_paused_run, resume_agent, schedule_session_tag,
+};
+use crate::commands::watch::artifacts::RunArtifacts;
+use crate::commands::watch::budget::budget_day_start;
+use crate::commands::watch::concurrency::RunLimiter;
+use crate::commands::watch::config::ConcurrencyLimitPolicy;
+use crate::commands::watch::db::{PendingResume, RELOAD_SENTINEL};
+use crate::commands::watch::health::{HealthState, spawn_health_server};
+use crate::commands::watch::notification_template::TemplateContext;
 use crate::commands::watch::reconciler::{
     RegisteredSchedule, ScheduleSnapshot, reconcile_schedules,
 };
+use crate::commands::watch::retention::{self, RetentionCategory, RetentionTargets};
+use crate::commands::watch::webhook::{RunWebhookPayload, send_run_webhook};
 use crate::commands::watch::{
-    AgentServerConnection, INTERACTIVE_DELEGATED_NOTE, InteractionMode, ListRunsFilter, RunStatus,
-    ScheduleConfig, ScheduleDb, Scheduler, SpawnConfig, assemble_prompt,
-    build_schedule_caller_context, is_process_running, run_check_script, spawn_agent,
+    AgentServerConnection, CheckResult, INTERACTIVE_DELEGATED_NOTE, InteractionMode,
+    ListRunsFilter, RunStatus, ScheduleConfig, ScheduleDb, Scheduler, SpawnConfig, assemble_prompt,
+    build_schedule_caller_context, is_process_running, render_dry_run, run_check, spawn_agent,
 };
 use chrono::{DateTime, Utc};
-use croner::Cron;
 use serde::Deserialize;
 use stakpak_gateway::client::{AutoApproveOverride, RunOverrides};
+use stakpak_shared::error::{ErrorKind, StakpakError};
+use stakpak_shared::models::async_manifest::PauseReason;
+use stakpak_shared::models::gateway_api::{
+    ApprovalOptions, CallerContextInput, GatewayApiError, GatewaySendRequest, GatewaySendResponse,
+    InteractiveOptions, NotificationContext,
+};
 use stakpak_shared::utils::sanitize_text_output;
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
-use std::str::FromStr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, OnceLock};
 use std::time::{Duration, SystemTime};
 use tokio::signal;
 use tokio::sync::{Mutex, RwLock, mpsc};
 use tracing::{error, info, warn};
 
-const HEARTBEAT_STALE_SECONDS: i64 = 120;
+pub const HEARTBEAT_STALE_SECONDS: i64 = 120;
 const HEARTBEAT_UPDATE_INTERVAL_SECONDS: u64 = 30;
 const INTERACTIVE_STATUS_POLL_INTERVAL_SECONDS: u64 = 15;
 const INTERACTIVE_MAX_RUN_AGE_HOURS: i64 = 24;
 const INTERACTIVE_RUN_MAX_AGE_GRACE_SECONDS: i64 = 60 * 60;
 const MAX_GATEWAY_CHECK_OUTPUT_CHARS: usize = 4_000;
+const ARTIFACT_CLEANUP_INTERVAL_SECONDS: u64 = 60 * 60;
+/// How long a paused run waits for an approval decision.
+const PAUSED_RUN_APPROVAL_WAIT_SECONDS: u64 = 24 * 60 * 60;
+/// Error message prefix of runs skipped because a budget was exhausted.
+const BUDGET_SKIP_PREFIX: &str = "Budget exceeded";
+/// Error message of runs skipped because the schedule has `dry_run = true`.
+const DRY_RUN_NOTE: &str = "Dry run: agent not spawned";
 
 static WATCH_HTTP_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
 
@@ -44,12 +68,12 @@ static WATCH_HTTP_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
 ///
 /// This function blocks until the autopilot service receives a shutdown signal (SIGTERM/SIGINT).
 /// Scheduled agents run via the co-hosted agent server API.
-pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String> {
+pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), StakpakError> {
     print_banner();
 
     // Load and validate configuration
-    let mut config =
-        ScheduleConfig::load_default().map_err(|e| format!("Failed to load config: {}", e))?;
+    let mut config = ScheduleConfig::load_default()
+        .map_err(|e| StakpakError::config(format!("Failed to load config: {}", e)))?;
 
     // Inject the runtime-generated gateway token so the scheduler can
     // authenticate against the co-hosted gateway API (/v1/gateway/send).
@@ -64,8 +88,9 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
     // Initialize database directory
     let db_path = config.db_path();
     if let Some(parent) = db_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create database directory: {}", e))?;
+        std::fs::create_dir_all(parent).map_err(|e| {
+            StakpakError::internal(format!("Failed to create database directory: {}", e))
+        })?;
     }
 
     // Check for existing autopilot service via PID file
@@ -74,34 +99,31 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
         .unwrap_or(std::path::Path::new("."))
         .join("autopilot.pid");
     if let Some(existing_pid) = check_existing_autopilot(&pid_file) {
-        return Err(format!(
+        return Err(StakpakError::internal(format!(
             "Another autopilot instance is already running (PID {}). \
              Stop it first with 'kill {}' or remove the stale PID file at {}",
             existing_pid,
             existing_pid,
             pid_file.display()
-        ));
+        )));
     }
 
     // Write PID file
     let pid = std::process::id();
     std::fs::write(&pid_file, pid.to_string())
-        .map_err(|e| format!("Failed to write PID file: {}", e))?;
+        .map_err(|e| StakpakError::internal(format!("Failed to write PID file: {}", e)))?;
 
     // Ensure PID file is cleaned up on exit
     let pid_file_cleanup = pid_file.clone();
 
-    let db_path_str = db_path
-        .to_str()
-        .ok_or_else(|| "Invalid database path".to_string())?;
-
-    let db = ScheduleDb::new(db_path_str)
-        .await
-        .map_err(|e| format!("Failed to initialize database: {}", e))?;
+    let db = ScheduleDb::open(&config.watch).await.map_err(|e| {
+        let _ = std::fs::remove_file(&pid_file_cleanup);
+        StakpakError::internal(format!("Failed to initialize database: {}", e))
+    })?;
 
     let persisted_state = db.get_autopilot_state().await.map_err(|e| {
         let _ = std::fs::remove_file(&pid_file_cleanup);
-        format!("Failed to read existing autopilot state: {}", e)
+        StakpakError::internal(format!("Failed to read existing autopilot state: {}", e))
     })?;
 
     if let Err(message) = validate_prior_scheduler_state(persisted_state, Utc::now()) {
@@ -122,22 +144,22 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
         }
         Err(e) => {
             let _ = std::fs::remove_file(&pid_file_cleanup);
-            return Err(format!(
+            return Err(StakpakError::internal(format!(
                 "Failed to clean stale runs at startup (refusing to continue): {}",
                 e
-            ));
+            )));
         }
     }
 
     // Set autopilot state
     db.set_autopilot_state(pid as i64)
         .await
-        .map_err(|e| format!("Failed to set autopilot state: {}", e))?;
+        .map_err(|e| StakpakError::internal(format!("Failed to set autopilot state: {}", e)))?;
 
-    info!(pid = pid, db_path = %db_path.display(), "Autopilot state initialized");
+    info!(pid = pid, database = %db.location(), "Autopilot state initialized");
 
     // Print config summary
-    print_config_summary(&config, pid as i64);
+    print_config_summary(&config, pid as i64, &db.location());
 
     // Keep configuration in shared state so pollers can read reloaded schedules.
     let config_state = Arc::new(RwLock::new(Arc::new(config.clone())));
@@ -145,7 +167,7 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
     // Create scheduler (returns scheduler and event receiver)
     let (mut scheduler, mut event_rx) = Scheduler::new()
         .await
-        .map_err(|e| format!("Failed to create scheduler: {}", e))?;
+        .map_err(|e| StakpakError::internal(format!("Failed to create scheduler: {}", e)))?;
 
     // Register enabled schedules and collect info for display + reconciliation snapshot.
     let mut registered_schedules = Vec::new();
@@ -181,7 +203,7 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
     scheduler
         .start()
         .await
-        .map_err(|e| format!("Failed to start scheduler: {}", e))?;
+        .map_err(|e| StakpakError::internal(format!("Failed to start scheduler: {}", e)))?;
 
     info!("Scheduler started, waiting for schedules...");
     print_schedules_table(&registered_schedules);
@@ -191,11 +213,31 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
 
     let db = Arc::new(db);
     let server = Arc::new(server);
+    let limiter = RunLimiter::new();
+    let waiters = PausedRunWaiters::default();
+
+    // Serve liveness/readiness probes when configured.
+    let scheduler_ready = Arc::new(AtomicBool::new(true));
+    let health_server = match config.watch.health_listen {
+        Some(addr) => Some(
+            spawn_health_server(
+                addr,
+                HealthState {
+                    db: Arc::clone(&db),
+                    scheduler: Arc::clone(&scheduler),
+                    snapshot: Arc::clone(&schedule_snapshot),
+                    ready: Arc::clone(&scheduler_ready),
+                },
+            )
+            .await?,
+        ),
+        None => None,
+    };
 
     let config_path = crate::commands::watch::config::expand_tilde(
         crate::commands::watch::config::STAKPAK_AUTOPILOT_CONFIG_PATH,
     );
-    let initial_config_mtime = file_mtime(&config_path).await;
+    let initial_config_fingerprint = config_fingerprint(&config_path).await;
 
     // Shutdown signal bridge.
     let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
@@ -219,6 +261,25 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
         }
     });
 
+    // Spawn artifact and data retention cleanup (first pass runs immediately).
+    let config_cleanup = Arc::clone(&config_state);
+    let config_path_cleanup = config_path.clone();
+    let artifact_cleanup = tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(ARTIFACT_CLEANUP_INTERVAL_SECONDS));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+            let config = {
+                let cfg = config_cleanup.read().await;
+                Arc::clone(&cfg)
+            };
+            enforce_artifact_retention(config.as_ref()).await;
+            enforce_data_retention(config.as_ref(), &config_path_cleanup).await;
+        }
+    });
+
     // Spawn pending schedule poller for manual schedule fires + config hot-reload signals.
     let db_clone2 = Arc::clone(&db);
     let config_clone2 = Arc::clone(&config_state);
@@ -226,9 +287,11 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
     let snapshot_clone2 = Arc::clone(&schedule_snapshot);
     let config_path_clone = config_path.clone();
     let server_clone2 = Arc::clone(&server);
+    let limiter_clone2 = limiter.clone();
+    let waiters_clone2 = waiters.clone();
     let pending_poller = tokio::spawn(async move {
         let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
-        let mut last_mtime = initial_config_mtime;
+        let mut last_fingerprint = initial_config_fingerprint;
         let mut mtime_check_counter: u8 = 0;
 
         loop {
@@ -263,6 +326,8 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
                             let db = Arc::clone(&db_clone2);
                             let config = config_for_event;
                             let server = Arc::clone(&server_clone2);
+                            let limiter = limiter_clone2.clone();
+                            let waiters = waiters_clone2.clone();
 
                             tokio::spawn(async move {
                                 info!(schedule = %schedule.name, "Manual schedule fired");
@@ -272,6 +337,8 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
                                     config.as_ref(),
                                     &schedule,
                                     &server,
+                                    &limiter,
+                                    &waiters,
                                     true,
                                 )
                                 .await
@@ -301,7 +368,7 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
                         )
                         .await;
                         if success {
-                            last_mtime = file_mtime(&config_path_clone).await;
+                            last_fingerprint = config_fingerprint(&config_path_clone).await;
                         }
                     }
                 }
@@ -310,18 +377,49 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
                 }
             }
 
+            match db_clone2.pop_pending_resumes().await {
+                Ok(resumes) => {
+                    for request in resumes {
+                        let db = Arc::clone(&db_clone2);
+                        let config = {
+                            let cfg = config_clone2.read().await;
+                            Arc::clone(&cfg)
+                        };
+                        let server = Arc::clone(&server_clone2);
+                        let waiters = waiters_clone2.clone();
+                        tokio::spawn(async move {
+                            let run_id = request.run_id;
+                            if let Err(e) = handle_resume_request(
+                                &db,
+                                config.as_ref(),
+                                &server,
+                                &waiters,
+                                request,
+                            )
+                            .await
+                            {
+                                warn!(run_id, error = %e, "Failed to resume paused run");
+                            }
+                        });
+                    }
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed to poll pending resumes");
+                }
+            }
+
             mtime_check_counter = mtime_check_counter.saturating_add(1);
             if mtime_check_counter >= 5 {
                 mtime_check_counter = 0;
-                let current_mtime = file_mtime(&config_path_clone).await;
-
-                if current_mtime != last_mtime {
-                    // Always advance last_mtime so permanent rejections (e.g.
-                    // db_path changed) don't re-trigger every 5 seconds. If
-                    // the file is edited again the mtime will change once more
-                    // and we will retry.
-                    last_mtime = current_mtime;
-                    info!("Config file mtime changed, reloading schedules");
+                let current_fingerprint = config_fingerprint(&config_path_clone).await;
+
+                if current_fingerprint != last_fingerprint {
+                    // Always advance last_fingerprint so permanent rejections
+                    // (e.g. db_path changed) don't re-trigger every 5 seconds.
+                    // If a file is edited again its mtime will change once
+                    // more and we will retry.
+                    last_fingerprint = current_fingerprint;
+                    info!("Config files changed, reloading schedules");
                     trigger_config_reload(
                         &scheduler_clone2,
                         &config_clone2,
@@ -374,8 +472,10 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
                             Arc::clone(&cfg)
                         };
                         let server = Arc::clone(&server);
+                        let limiter = limiter.clone();
+                        let waiters = waiters.clone();
                         tokio::spawn(async move {
-                            if let Err(e) = handle_schedule_event(&db, config.as_ref(), &event.schedule, &server, false).await {
+                            if let Err(e) = handle_schedule_event(&db, config.as_ref(), &event.schedule, &server, &limiter, &waiters, false).await {
                                 error!(schedule = %event.schedule.name, error = %e, "Failed to handle schedule event");
                             }
                         });
@@ -392,6 +492,7 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
     println!();
     println!("\x1b[33mShutdown signal received, stopping autopilot service...\x1b[0m");
     info!("Shutdown signal received, stopping autopilot service...");
+    scheduler_ready.store(false, Ordering::Relaxed);
 
     {
         let mut scheduler_guard = scheduler.lock().await;
@@ -401,8 +502,12 @@ pub async fn run_scheduler(server: AgentServerConnection) -> Result<(), String>
     }
 
     heartbeat_updater.abort();
+    artifact_cleanup.abort();
     pending_poller.abort();
     interactive_status_poller.abort();
+    if let Some(health_server) = health_server {
+        health_server.abort();
+    }
 
     // Clear autopilot state
     if let Err(e) = db.clear_autopilot_state().await {
@@ -454,9 +559,12 @@ where
     // for the ephemeral token generated at `stakpak up` time.
     new_config.apply_runtime_gateway_credentials(&server.url, &server.token);
 
-    let current_db_path = {
+    let (current_db_path, current_database_url) = {
         let config_guard = config.read().await;
-        config_guard.db_path()
+        (
+            config_guard.db_path(),
+            config_guard.watch.database_url.clone(),
+        )
     };
 
     if new_config.db_path() != current_db_path {
@@ -468,6 +576,11 @@ where
         return false;
     }
 
+    if new_config.watch.database_url != current_database_url {
+        warn!("Ignoring hot-reload because database_url changed; restart required");
+        return false;
+    }
+
     // NOTE: snapshot is cloned before acquiring the scheduler lock. This is
     // safe because this function is only called from the single pending_poller
     // task — no concurrent caller can mutate the snapshot between the clone
@@ -506,6 +619,17 @@ where
     true
 }
 
+/// Modification times of the config file and each `schedules.d` file, so
+/// adding, editing or removing any of them triggers a reload.
+async fn config_fingerprint(config_path: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut fingerprint = Vec::new();
+    for path in crate::commands::watch::config::config_source_files(config_path) {
+        let mtime = file_mtime(&path).await;
+        fingerprint.push((path, mtime));
+    }
+    fingerprint
+}
+
 async fn file_mtime(path: &Path) -> Option<SystemTime> {
     tokio::fs::metadata(path)
         .await
@@ -516,7 +640,7 @@ async fn file_mtime(path: &Path) -> Option<SystemTime> {
 fn validate_prior_scheduler_state(
     state: Option<crate::commands::watch::db::SchedulerState>,
     now: DateTime<Utc>,
-) -> Result<(), String> {
+) -> Result<(), StakpakError> {
     let Some(state) = state else {
         return Ok(());
     };
@@ -535,16 +659,116 @@ fn validate_prior_scheduler_state(
         .max(0);
 
     if heartbeat_age_seconds <= HEARTBEAT_STALE_SECONDS {
-        return Err(format!(
+        return Err(StakpakError::internal(format!(
             "Another autopilot instance appears active (PID {}, heartbeat {}s ago). Stop it first.",
             state.pid, heartbeat_age_seconds
-        ));
+        )));
     }
 
-    Err(format!(
+    Err(StakpakError::internal(format!(
         "Autopilot state PID {} is still running but heartbeat is stale ({}s). Refusing startup to avoid marking active runs as failed.",
         state.pid, heartbeat_age_seconds
-    ))
+    )))
+}
+
+/// Create the artifacts directory for a run that is about to wake the agent and
+/// store the full check output there. Artifact failures never fail the run.
+async fn prepare_run_artifacts(
+    db: &ScheduleDb,
+    config: &ScheduleConfig,
+    run_id: i64,
+    check_result: Option<&CheckResult>,
+) -> Option<RunArtifacts> {
+    let artifacts = match RunArtifacts::create(&config.artifacts_dir(), run_id) {
+        Ok(artifacts) => artifacts,
+        Err(e) => {
+            warn!(run_id, error = %e, "Failed to create run artifacts directory");
+            return None;
+        }
+    };
+
+    if let Some(check_result) = check_result
+        && let Err(e) = artifacts.write_check_output(check_result)
+    {
+        warn!(run_id, error = %e, "Failed to write check output artifacts");
+    }
+
+    let dir = artifacts.dir().display().to_string();
+    if let Err(e) = db.update_run_artifacts_dir(run_id, &dir).await {
+        warn!(run_id, error = %e, "Failed to record run artifacts directory");
+    }
+
+    Some(artifacts)
+}
+
+/// Store the full agent output and copies of files the agent wrote.
+fn store_agent_artifacts(
+    artifacts: &RunArtifacts,
+    schedule: &crate::commands::watch::Schedule,
+    result: &crate::commands::watch::agent::AgentResult,
+) {
+    if let Err(e) = artifacts.write_agent_output(&result.stdout, &result.stderr) {
+        warn!(schedule = %schedule.name, error = %e, "Failed to write agent output artifacts");
+    }
+    let copied = artifacts.collect_files(&result.written_files);
+    if copied > 0 {
+        info!(
+            schedule = %schedule.name,
+            files = copied,
+            dir = %artifacts.dir().display(),
+            "Stored agent-written files"
+        );
+    }
+}
+
+/// Apply the configured retention policy to the artifacts directory.
+async fn enforce_artifact_retention(config: &ScheduleConfig) {
+    let policy = config.watch.artifact_retention();
+    if !policy.is_enabled() {
+        return;
+    }
+
+    let root = config.artifacts_dir();
+    let result =
+        tokio::task::spawn_blocking(move || policy.enforce(&root, SystemTime::now())).await;
+    match result {
+        Ok(Ok(report)) if report.removed_runs > 0 => {
+            info!(
+                removed_runs = report.removed_runs,
+                freed_bytes = report.freed_bytes,
+                "Pruned run artifacts"
+            );
+        }
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => warn!(error = %e, "Failed to enforce artifact retention"),
+        Err(e) => warn!(error = %e, "Artifact retention task failed"),
+    }
+}
+
+async fn enforce_data_retention(config: &ScheduleConfig, config_path: &Path) {
+    if !config.retention.is_enabled() {
+        return;
+    }
+
+    let targets = RetentionTargets::resolve(config, config_path);
+    let categories: Vec<RetentionCategory> = RetentionCategory::ALL
+        .into_iter()
+        .filter(|category| config.retention.policy(*category).is_enabled())
+        .collect();
+    for cleanup in retention::apply(&config.retention, &targets, &categories, Utc::now()).await {
+        match cleanup.error {
+            Some(e) => {
+                warn!(category = %cleanup.category, error = %e, "Failed to enforce retention")
+            }
+            None if cleanup.removed > 0 => info!(
+                category = %cleanup.category,
+                removed = cleanup.removed,
+                freed_bytes = cleanup.freed_bytes,
+                "Pruned local data"
+            ),
+            None => {}
+        }
+    }
 }
 
 /// Handle a schedule event by running the check script and spawning the agent if needed.
@@ -553,8 +777,92 @@ async fn handle_schedule_event(
     config: &ScheduleConfig,
     schedule: &crate::commands::watch::Schedule,
     server: &AgentServerConnection,
+    limiter: &RunLimiter,
+    waiters: &PausedRunWaiters,
     manual: bool,
-) -> Result<(), String> {
+) -> Result<(), StakpakError> {
+    // Maintenance windows suppress scheduled fires; a manual fire is an
+    // explicit request and still runs.
+    if !manual && let Some(window) = schedule.active_blackout(&config.watch, Utc::now()) {
+        info!(schedule = %schedule.name, window = %window, "Skipping: blackout window");
+        print_event(
+            "skip",
+            &schedule.name,
+            &format!("Skipped (blackout window '{}')", window),
+        );
+        let run_id = db
+            .insert_run(&schedule.name)
+            .await
+            .map_err(|e| StakpakError::internal(format!("Failed to insert run: {}", e)))?;
+        db.update_run_finished(
+            run_id,
+            RunStatus::Skipped,
+            Some(&format!("Blackout window '{}'", window)),
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| StakpakError::internal(format!("Failed to update run status: {}", e)))?;
+        return Ok(());
+    }
+
+    if let Some(reason) = exhausted_budget(db, config, schedule).await {
+        info!(schedule = %schedule.name, reason = %reason, "Skipping: budget exceeded");
+        print_event("skip", &schedule.name, &format!("Skipped ({})", reason));
+        // Notify on the first skip of the day only, not on every fire.
+        let already_notified = db
+            .has_skipped_run_since(
+                &schedule.name,
+                BUDGET_SKIP_PREFIX,
+                budget_day_start(Utc::now()),
+            )
+            .await
+            .unwrap_or(false);
+        let run_id = db
+            .insert_run(&schedule.name)
+            .await
+            .map_err(|e| StakpakError::internal(format!("Failed to insert run: {}", e)))?;
+        db.update_run_finished(
+            run_id,
+            RunStatus::Skipped,
+            Some(&format!("{}: {}", BUDGET_SKIP_PREFIX, reason)),
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| StakpakError::internal(format!("Failed to update run status: {}", e)))?;
+        if !already_notified {
+            send_budget_notification(config, schedule, run_id, &reason).await;
+        }
+        return Ok(());
+    }
+
+    // Claim the schedule across every autopilot sharing the database, held
+    // until the run finishes or pauses. Local databases always grant it.
+    let schedule_lock = match db.try_lock_schedule(&schedule.name).await {
+        Ok(Some(lock)) => Some(lock),
+        Ok(None) => {
+            info!(
+                schedule = %schedule.name,
+                "Skipping: running on another autopilot"
+            );
+            print_event(
+                "skip",
+                &schedule.name,
+                "Skipped (running on another autopilot)",
+            );
+            return Ok(());
+        }
+        Err(e) => {
+            warn!(
+                schedule = %schedule.name,
+                error = %e,
+                "Failed to claim schedule, proceeding anyway"
+            );
+            None
+        }
+    };
+
     // Singleton guard: skip if this schedule already has a running run
     match db.has_running_run(&schedule.name).await {
         Ok(true) => {
@@ -579,6 +887,26 @@ async fn handle_schedule_event(
         }
     }
 
+    // Global / group concurrency limits. The permit is held until the agent
+    // run finishes or pauses, covering both the check script and the agent run.
+    let permit = match limiter.try_acquire(schedule, &config.watch) {
+        Ok(permit) => Ok(permit),
+        Err(limit) if config.watch.on_concurrency_limit == ConcurrencyLimitPolicy::Queue => {
+            info!(schedule = %schedule.name, reason = %limit, "Queueing run until a slot frees up");
+            print_event("queue", &schedule.name, &format!("Queued ({})", limit));
+            limiter.acquire(schedule, &config.watch).await
+        }
+        Err(limit) => Err(limit),
+    };
+    let permit = match permit {
+        Ok(permit) => permit,
+        Err(limit) => {
+            info!(schedule = %schedule.name, reason = %limit, "Skipping: concurrency limit reached");
+            print_event("skip", &schedule.name, &format!("Skipped ({})", limit));
+            return Ok(());
+        }
+    };
+
     info!(schedule = %schedule.name, "Schedule fired");
     print_event("fire", &schedule.name, "Schedule fired");
 
@@ -586,37 +914,38 @@ async fn handle_schedule_event(
     let run_id = db
         .insert_run(&schedule.name)
         .await
-        .map_err(|e| format!("Failed to insert run: {}", e))?;
+        .map_err(|e| StakpakError::internal(format!("Failed to insert run: {}", e)))?;
 
     // Run check script if defined
-    let check_result = if let Some(check_path) = &schedule.check {
-        let expanded_path = crate::commands::watch::config::expand_tilde(check_path);
+    let check_result = if let Some(check) = &schedule.check {
         let timeout = schedule.effective_check_timeout(&config.defaults);
 
         info!(
             schedule = %schedule.name,
-            check_script = %expanded_path.display(),
-            "Running check script"
+            check = %check,
+            "Running check"
         );
         print_event(
             "check",
             &schedule.name,
-            &format!("Running check: {}", expanded_path.display()),
+            &format!("Running check: {}", check),
         );
 
-        match run_check_script(&expanded_path, timeout).await {
+        match run_check(check, timeout).await {
             Ok(result) => {
                 // Update run with check result
                 let exit_code = result.exit_code.unwrap_or(-1);
                 db.update_run_check_result(
                     run_id,
                     exit_code,
-                    &result.stdout,
-                    &result.stderr,
+                    &schedule.redact(&result.stdout),
+                    &schedule.redact(&result.stderr),
                     result.timed_out,
                 )
                 .await
-                .map_err(|e| format!("Failed to update check result: {}", e))?;
+                .map_err(|e| {
+                    StakpakError::internal(format!("Failed to update check result: {}", e))
+                })?;
 
                 if result.timed_out {
                     warn!(schedule = %schedule.name, "Check script timed out");
@@ -624,19 +953,21 @@ async fn handle_schedule_event(
                     db.update_run_finished(
                         run_id,
                         RunStatus::Failed,
-                        Some("Check script timed out"),
+                        Some(&StakpakError::tool_execution("Check script timed out").to_record()),
                         None,
                         None,
                     )
                     .await
-                    .map_err(|e| format!("Failed to update run status: {}", e))?;
+                    .map_err(|e| {
+                        StakpakError::internal(format!("Failed to update run status: {}", e))
+                    })?;
                     return Ok(());
                 }
 
                 // Determine if we should trigger based on trigger_on setting
                 let exit_code = result.exit_code.unwrap_or(-1);
                 let trigger_on = schedule.effective_trigger_on(&config.defaults);
-                let should_trigger = trigger_on.should_trigger(exit_code);
+                let should_trigger = result.should_trigger(trigger_on);
 
                 if !should_trigger {
                     info!(
@@ -645,17 +976,20 @@ async fn handle_schedule_event(
                         trigger_on = %trigger_on,
                         "Check script did not meet trigger condition"
                     );
-                    print_event(
-                        "skip",
-                        &schedule.name,
-                        &format!(
-                            "Skipped (exit {} does not match trigger_on={})",
+                    let reason = if result.output().and_then(|output| output.trigger).is_some() {
+                        "check output set trigger=false".to_string()
+                    } else {
+                        format!(
+                            "exit {} does not match trigger_on={}",
                             exit_code, trigger_on
-                        ),
-                    );
+                        )
+                    };
+                    print_event("skip", &schedule.name, &format!("Skipped ({})", reason));
                     db.update_run_finished(run_id, RunStatus::Skipped, None, None, None)
                         .await
-                        .map_err(|e| format!("Failed to update run status: {}", e))?;
+                        .map_err(|e| {
+                            StakpakError::internal(format!("Failed to update run status: {}", e))
+                        })?;
                     return Ok(());
                 }
 
@@ -674,12 +1008,17 @@ async fn handle_schedule_event(
                 db.update_run_finished(
                     run_id,
                     RunStatus::Failed,
-                    Some(&format!("Check script error: {}", e)),
+                    Some(
+                        &StakpakError::tool_execution(format!("Check script error: {}", e))
+                            .to_record(),
+                    ),
                     None,
                     None,
                 )
                 .await
-                .map_err(|e| format!("Failed to update run status: {}", e))?;
+                .map_err(|e| {
+                    StakpakError::internal(format!("Failed to update run status: {}", e))
+                })?;
                 return Ok(());
             }
         }
@@ -690,6 +1029,27 @@ async fn handle_schedule_event(
     // Assemble prompt + structured caller context
     let prompt = assemble_prompt(schedule, check_result.as_ref());
     let caller_context = build_schedule_caller_context(schedule, check_result.as_ref());
+    // The agent sees the original check output; everything stored or sent
+    // elsewhere gets the schedule's redaction patterns applied.
+    let stored_check_result = check_result
+        .as_ref()
+        .map(|result| redacted_check_result(schedule, result));
+
+    if schedule.dry_run {
+        let report = render_dry_run(schedule, &config.defaults, stored_check_result.as_ref());
+        info!(schedule = %schedule.name, "Dry run: agent not spawned\n{}", report);
+        print_event("skip", &schedule.name, "Dry run (agent not spawned)");
+        db.update_run_finished(
+            run_id,
+            RunStatus::Skipped,
+            Some(DRY_RUN_NOTE),
+            Some(&report),
+            None,
+        )
+        .await
+        .map_err(|e| StakpakError::internal(format!("Failed to update run status: {}", e)))?;
+        return Ok(());
+    }
 
     if schedule.interaction == InteractionMode::Interactive {
         match try_start_interactive_session(
@@ -704,7 +1064,12 @@ async fn handle_schedule_event(
             Ok(Some(session_id)) => {
                 db.update_run_interactive_started(run_id, &session_id, INTERACTIVE_DELEGATED_NOTE)
                     .await
-                    .map_err(|e| format!("Failed to persist interactive session id: {}", e))?;
+                    .map_err(|e| {
+                        StakpakError::internal(format!(
+                            "Failed to persist interactive session id: {}",
+                            e
+                        ))
+                    })?;
 
                 print_event(
                     "done",
@@ -732,17 +1097,26 @@ async fn handle_schedule_event(
     info!(schedule = %schedule.name, "Waking agent");
     print_event("agent", &schedule.name, "Spawning agent...");
 
+    let artifacts = prepare_run_artifacts(db, config, run_id, stored_check_result.as_ref()).await;
+
     let profile_name = schedule.effective_profile(&config.defaults).to_string();
     let (profile_overrides, profile_allowed_tools) =
         resolve_schedule_profile_overrides(&profile_name, server);
     let run_overrides = apply_schedule_run_overrides(profile_overrides, schedule);
 
-    // Spawn agent
+    // Spawn agent. The env file is re-read here so a file that became
+    // unreadable since the config was loaded fails the run rather than
+    // starting the agent without its credentials.
+    let agent_env = schedule.agent_env();
     let spawn_config = SpawnConfig {
         prompt,
         profile: profile_name,
         timeout: schedule.effective_timeout(&config.defaults),
-        workdir: None,
+        workdir: schedule
+            .workdir_path()
+            .map(|path| path.to_string_lossy().into_owned()),
+        env: agent_env.clone().unwrap_or_default(),
+        tags: vec![schedule_session_tag(&schedule.name)],
         enable_slack_tools: schedule.effective_enable_slack_tools(&config.defaults),
         enable_subagents: schedule.effective_enable_subagents(&config.defaults),
         pause_on_approval: schedule.effective_pause_on_approval(&config.defaults),
@@ -752,76 +1126,44 @@ async fn handle_schedule_event(
             .unwrap_or_else(|| server.default_allowed_tools.clone()),
         overrides: run_overrides,
         server: server.clone(),
+        redact_patterns: schedule.redact.clone(),
     };
 
-    match spawn_agent(spawn_config).await {
-        Ok(result) => {
-            // Update run with agent session info
-            if let Some(session_id) = &result.session_id {
-                db.update_run_agent_started(run_id, session_id)
-                    .await
-                    .map_err(|e| format!("Failed to update agent session: {}", e))?;
-            }
-
-            if let Some(checkpoint_id) = &result.checkpoint_id {
-                db.update_run_checkpoint(run_id, checkpoint_id)
-                    .await
-                    .map_err(|e| format!("Failed to update checkpoint: {}", e))?;
-            }
-
-            // Determine final status and print event
-            let (status, error_msg) = if result.timed_out {
-                print_event("timeout", &schedule.name, "Agent timed out");
-                (RunStatus::TimedOut, Some("Agent timed out".to_string()))
-            } else if result.is_paused() {
-                let resume_hint = result
-                    .resume_hint
-                    .as_deref()
-                    .unwrap_or("stakpak autopilot schedule inspect <run_id>");
-                print_event(
-                    "pause",
-                    &schedule.name,
-                    &format!("Agent paused - resume with: {}", resume_hint),
-                );
-                (RunStatus::Paused, None)
-            } else if result.success() {
-                print_event("done", &schedule.name, "Agent completed successfully");
-                (RunStatus::Completed, None)
-            } else {
-                print_event(
-                    "fail",
-                    &schedule.name,
-                    &format!("Agent failed (exit {:?})", result.exit_code),
-                );
-                (
-                    RunStatus::Failed,
-                    Some(format!("Agent exited with code {:?}", result.exit_code)),
-                )
-            };
-
-            // Store agent output (truncate if too large, respecting unicode boundaries)
-            let stdout = if result.stdout.is_empty() {
-                None
-            } else {
-                Some(truncate_string(&result.stdout, 100_000))
-            };
-            let stderr = if result.stderr.is_empty() {
-                None
-            } else {
-                Some(truncate_string(&result.stderr, 100_000))
-            };
+    // An agent from an earlier run may still be working, e.g. after crash
+    // recovery marked its run failed. Follow it rather than spawning a second
+    // agent to remediate the same problem.
+    let spawn_result = if let Some(outstanding) = outstanding_session(db, schedule, server).await {
+        info!(
+            schedule = %schedule.name,
+            session_id = %outstanding.session_id,
+            "Adopting in-flight agent session instead of spawning"
+        );
+        print_event(
+            "agent",
+            &schedule.name,
+            &format!("Adopting running session {}", outstanding.session_id),
+        );
+        adopt_agent(AdoptConfig::from_spawn(&spawn_config, outstanding)).await
+    } else {
+        match agent_env {
+            Ok(_) => spawn_agent(spawn_config).await,
+            Err(message) => Err(AgentError::SpawnError(message)),
+        }
+    };
 
-            db.update_run_finished(
+    match spawn_result {
+        Ok(result) => {
+            let result = redacted_agent_result(schedule, result);
+            let status = record_agent_result(
+                db,
+                config,
+                schedule,
                 run_id,
-                status,
-                error_msg.as_deref(),
-                stdout.as_deref(),
-                stderr.as_deref(),
+                &result,
+                artifacts.as_ref(),
+                stored_check_result.as_ref(),
             )
-            .await
-            .map_err(|e| format!("Failed to update run status: {}", e))?;
-
-            maybe_send_notification(config, schedule, &result, check_result.as_ref(), None).await;
+            .await?;
 
             info!(
                 schedule = %schedule.name,
@@ -830,27 +1172,56 @@ async fn handle_schedule_event(
                 paused = result.is_paused(),
                 "Agent completed"
             );
+
+            // Follow the paused run until a decision arrives from chat or
+            // `stakpak autopilot resume`, without holding a run slot.
+            if status == RunStatus::Paused
+                && let Some(session_id) = result.session_id.clone()
+            {
+                drop(permit);
+                drop(schedule_lock);
+                follow_paused_run(
+                    db,
+                    config,
+                    schedule,
+                    server,
+                    waiters,
+                    PausedRun { run_id, session_id },
+                    None,
+                )
+                .await;
+            }
         }
         Err(e) => {
+            let kind = e.kind();
+            let e = schedule.redact(&e.to_string());
             error!(schedule = %schedule.name, error = %e, "Failed to spawn agent");
             print_event(
                 "fail",
                 &schedule.name,
                 &format!("Failed to spawn agent: {}", e),
             );
+            if let Some(artifacts) = &artifacts
+                && let Err(write_error) =
+                    artifacts.write_agent_output("", &format!("Failed to spawn agent: {}", e))
+            {
+                warn!(schedule = %schedule.name, error = %write_error, "Failed to write run artifacts");
+            }
             db.update_run_finished(
                 run_id,
                 RunStatus::Failed,
-                Some(&format!("Failed to spawn agent: {}", e)),
+                Some(&StakpakError::new(kind, format!("Failed to spawn agent: {}", e)).to_record()),
                 None,
                 None,
             )
             .await
-            .map_err(|e| format!("Failed to update run status: {}", e))?;
+            .map_err(|e| StakpakError::internal(format!("Failed to update run status: {}", e)))?;
+            spawn_run_webhook(db, schedule, run_id).await;
 
             maybe_send_notification(
                 config,
                 schedule,
+                run_id,
                 &crate::commands::watch::agent::AgentResult {
                     exit_code: Some(1),
                     session_id: None,
@@ -861,8 +1232,11 @@ async fn handle_schedule_event(
                     resume_hint: None,
                     stdout: String::new(),
                     stderr: format!("Failed to spawn agent: {}", e),
+                    written_files: Vec::new(),
+                    usage: Default::default(),
+                    estimated_cost: None,
                 },
-                check_result.as_ref(),
+                stored_check_result.as_ref(),
                 Some(&format!("Failed to spawn agent: {}", e)),
             )
             .await;
@@ -872,6 +1246,603 @@ async fn handle_schedule_event(
     Ok(())
 }
 
+/// Persist an agent result on its run, print the outcome and notify.
+/// Returns the status the run was recorded with.
+async fn record_agent_result(
+    db: &ScheduleDb,
+    config: &ScheduleConfig,
+    schedule: &crate::commands::watch::Schedule,
+    run_id: i64,
+    result: &AgentResult,
+    artifacts: Option<&RunArtifacts>,
+    check_result: Option<&CheckResult>,
+) -> Result<RunStatus, StakpakError> {
+    // Update run with agent session info
+    if let Some(session_id) = &result.session_id {
+        db.update_run_agent_started(run_id, session_id)
+            .await
+            .map_err(|e| {
+                StakpakError::internal(format!("Failed to update agent session: {}", e))
+            })?;
+    }
+
+    if let Some(checkpoint_id) = &result.checkpoint_id {
+        db.update_run_checkpoint(run_id, checkpoint_id)
+            .await
+            .map_err(|e| StakpakError::internal(format!("Failed to update checkpoint: {}", e)))?;
+    }
+
+    let tokens = result.usage.prompt_tokens + result.usage.completion_tokens;
+    if let Err(e) = db
+        .add_run_usage(run_id, tokens, result.estimated_cost)
+        .await
+    {
+        warn!(schedule = %schedule.name, error = %e, "Failed to record run usage");
+    }
+
+    // Determine final status and print event
+    let (status, error_msg) = if result.timed_out {
+        print_event("timeout", &schedule.name, "Agent timed out");
+        (RunStatus::TimedOut, Some("Agent timed out".to_string()))
+    } else if result.is_paused() {
+        let resume_hint = result
+            .resume_hint
+            .clone()
+            .unwrap_or_else(|| format!("stakpak autopilot resume {} --approve", run_id));
+        print_event(
+            "pause",
+            &schedule.name,
+            &format!("Agent paused - resume with: {}", resume_hint),
+        );
+        (RunStatus::Paused, None)
+    } else if result.success() {
+        print_event("done", &schedule.name, "Agent completed successfully");
+        (RunStatus::Completed, None)
+    } else {
+        print_event(
+            "fail",
+            &schedule.name,
+            &format!("Agent failed (exit {:?})", result.exit_code),
+        );
+        // The agent's exit code tells which kind of error stopped it
+        let kind = result
+            .exit_code
+            .and_then(ErrorKind::from_exit_code)
+            .unwrap_or(ErrorKind::Internal);
+        (
+            RunStatus::Failed,
+            Some(
+                StakpakError::new(
+                    kind,
+                    format!("Agent exited with code {:?}", result.exit_code),
+                )
+                .to_record(),
+            ),
+        )
+    };
+
+    if let Some(artifacts) = artifacts {
+        store_agent_artifacts(artifacts, schedule, result);
+    }
+
+    // Store an output preview (truncate if too large, respecting unicode
+    // boundaries); the full output lives in the run's artifacts directory.
+    let stdout = if result.stdout.is_empty() {
+        None
+    } else {
+        Some(truncate_string(&result.stdout, 100_000))
+    };
+    let stderr = if result.stderr.is_empty() {
+        None
+    } else {
+        Some(truncate_string(&result.stderr, 100_000))
+    };
+
+    db.update_run_finished(
+        run_id,
+        status,
+        error_msg.as_deref(),
+        stdout.as_deref(),
+        stderr.as_deref(),
+    )
+    .await
+    .map_err(|e| StakpakError::internal(format!("Failed to update run status: {}", e)))?;
+
+    if status == RunStatus::Paused {
+        send_pause_notification(config, schedule, run_id, result).await;
+    } else {
+        maybe_send_notification(config, schedule, run_id, result, check_result, None).await;
+        spawn_run_webhook(db, schedule, run_id).await;
+    }
+
+    Ok(status)
+}
+
+/// POST the finished run's summary to the schedule's `on_complete_webhook`
+/// in the background. Delivery failures are logged and never fail the run.
+async fn spawn_run_webhook(
+    db: &ScheduleDb,
+    schedule: &crate::commands::watch::Schedule,
+    run_id: i64,
+) {
+    let Some(url) = schedule.on_complete_webhook.clone() else {
+        return;
+    };
+    let payload = match db.get_run(run_id).await {
+        Ok(run) => RunWebhookPayload::from_run(&run),
+        Err(e) => {
+            warn!(schedule = %schedule.name, run_id, error = %e, "Failed to load run for webhook");
+            return;
+        }
+    };
+    let client = match watch_http_client() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!(schedule = %schedule.name, run_id, error = %e, "Failed to create webhook client");
+            return;
+        }
+    };
+    let schedule_name = schedule.name.clone();
+    tokio::spawn(async move {
+        if let Err(e) = send_run_webhook(client, &url, &payload).await {
+            warn!(schedule = %schedule_name, run_id, error = %e, "Failed to deliver run webhook");
+        }
+    });
+}
+
+/// Paused runs with a task following them until they finish.
+#[derive(Debug, Clone, Default)]
+struct PausedRunWaiters {
+    runs: Arc<std::sync::Mutex<HashSet<i64>>>,
+}
+
+impl PausedRunWaiters {
+    /// Claim `run_id`; `false` when another task already follows it.
+    fn register(&self, run_id: i64) -> bool {
+        self.lock().insert(run_id)
+    }
+
+    fn unregister(&self, run_id: i64) {
+        self.lock().remove(&run_id);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashSet<i64>> {
+        self.runs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A paused run and the server session it waits in.
+#[derive(Debug, Clone)]
+struct PausedRun {
+    run_id: i64,
+    session_id: String,
+}
+
+/// Follow a paused run until it finishes, recording each pause and the final
+/// result. `decision` answers the current pause; without one the run waits for
+/// a decision made in chat or by a later `stakpak autopilot resume`. When
+/// another task already follows the run, the decision is handed to it.
+async fn follow_paused_run(
+    db: &ScheduleDb,
+    config: &ScheduleConfig,
+    schedule: &crate::commands::watch::Schedule,
+    server: &AgentServerConnection,
+    waiters: &PausedRunWaiters,
+    paused: PausedRun,
+    mut decision: Option<ResumeDecision>,
+) {
+    if !waiters.register(paused.run_id) {
+        if let Some(decision) = decision
+            && let Err(e) = resolve_paused_run(server, &paused.session_id, decision).await
+        {
+            warn!(schedule = %schedule.name, run_id = paused.run_id, error = %e, "Failed to resolve paused run");
+        }
+        return;
+    }
+
+    loop {
+        let decided = decision.is_some();
+        let resume = resume_config(
+            config,
+            schedule,
+            server,
+            &paused.session_id,
+            decision.take(),
+        );
+        let previous_stdout = db
+            .get_run(paused.run_id)
+            .await
+            .ok()
+            .and_then(|run| run.agent_stdout)
+            .unwrap_or_default();
+
+        let result = match resume_agent(resume).await {
+            Ok(result) => redacted_agent_result(schedule, result),
+            Err(e) => {
+                let kind = e.kind();
+                let e = schedule.redact(&e.to_string());
+                warn!(schedule = %schedule.name, run_id = paused.run_id, error = %e, "Failed to resume paused run");
+                // A run still waiting for a decision stays paused; one that was
+                // explicitly resumed and cannot continue has failed.
+                if decided {
+                    print_event(
+                        "fail",
+                        &schedule.name,
+                        &format!("Failed to resume run: {}", e),
+                    );
+                    if let Err(e) = db
+                        .update_run_finished(
+                            paused.run_id,
+                            RunStatus::Failed,
+                            Some(
+                                &StakpakError::new(
+                                    kind,
+                                    format!("Failed to resume paused run: {}", e),
+                                )
+                                .to_record(),
+                            ),
+                            None,
+                            None,
+                        )
+                        .await
+                    {
+                        warn!(run_id = paused.run_id, error = %e, "Failed to update run status");
+                    }
+                }
+                break;
+            }
+        };
+        let result = AgentResult {
+            stdout: join_agent_output(&previous_stdout, &result.stdout),
+            ..result
+        };
+
+        let artifacts = RunArtifacts::create(&config.artifacts_dir(), paused.run_id)
+            .inspect_err(|e| warn!(run_id = paused.run_id, error = %e, "Failed to open run artifacts directory"))
+            .ok();
+        match record_agent_result(
+            db,
+            config,
+            schedule,
+            paused.run_id,
+            &result,
+            artifacts.as_ref(),
+            None,
+        )
+        .await
+        {
+            Ok(RunStatus::Paused) => continue,
+            Ok(_) => break,
+            Err(e) => {
+                warn!(schedule = %schedule.name, run_id = paused.run_id, error = %e, "Failed to record resumed run");
+                break;
+            }
+        }
+    }
+
+    waiters.unregister(paused.run_id);
+}
+
+/// Apply a `stakpak autopilot resume` request to a paused run.
+async fn handle_resume_request(
+    db: &ScheduleDb,
+    config: &ScheduleConfig,
+    server: &AgentServerConnection,
+    waiters: &PausedRunWaiters,
+    request: PendingResume,
+) -> Result<(), StakpakError> {
+    let run = db.get_run(request.run_id).await.map_err(|e| {
+        StakpakError::internal(format!("Failed to load run {}: {}", request.run_id, e))
+    })?;
+    let Some(session_id) = run.agent_session_id.clone() else {
+        return Err(StakpakError::internal(format!(
+            "Run {} has no agent session",
+            run.id
+        )));
+    };
+    let Some(schedule) = config
+        .schedules
+        .iter()
+        .find(|schedule| schedule.name == run.schedule_name)
+        .cloned()
+    else {
+        return Err(StakpakError::internal(format!(
+            "Schedule '{}' of run {} is no longer configured",
+            run.schedule_name, run.id
+        )));
+    };
+
+    // Claiming the run also rejects duplicate requests for the same pause.
+    let claimed = db
+        .update_run_resumed(run.id)
+        .await
+        .map_err(|e| StakpakError::internal(format!("Failed to update run status: {}", e)))?;
+    if !claimed {
+        return Err(StakpakError::internal(format!(
+            "Run {} is not paused (status: {})",
+            run.id, run.status
+        )));
+    }
+
+    let decision = if request.approve {
+        ResumeDecision::Approve
+    } else {
+        ResumeDecision::Deny
+    };
+    print_event(
+        "resume",
+        &schedule.name,
+        &format!(
+            "Run {} resumed ({})",
+            run.id,
+            if request.approve {
+                "approved"
+            } else {
+                "denied"
+            }
+        ),
+    );
+
+    follow_paused_run(
+        db,
+        config,
+        &schedule,
+        server,
+        waiters,
+        PausedRun {
+            run_id: run.id,
+            session_id,
+        },
+        Some(decision),
+    )
+    .await;
+    Ok(())
+}
+
+fn resume_config(
+    config: &ScheduleConfig,
+    schedule: &crate::commands::watch::Schedule,
+    server: &AgentServerConnection,
+    session_id: &str,
+    decision: Option<ResumeDecision>,
+) -> ResumeConfig {
+    let profile_name = schedule.effective_profile(&config.defaults).to_string();
+    let (profile_overrides, profile_allowed_tools) =
+        resolve_schedule_profile_overrides(&profile_name, server);
+    let run_overrides = apply_schedule_run_overrides(profile_overrides, schedule);
+    let run_timeout = schedule.effective_timeout(&config.defaults);
+
+    ResumeConfig {
+        session_id: session_id.to_string(),
+        decision,
+        // Without a decision, the wait for one counts toward the timeout too.
+        timeout: if decision.is_some() {
+            run_timeout
+        } else {
+            run_timeout + Duration::from_secs(PAUSED_RUN_APPROVAL_WAIT_SECONDS)
+        },
+        pause_on_approval: schedule.effective_pause_on_approval(&config.defaults),
+        allowed_tools: profile_allowed_tools
+            .unwrap_or_else(|| server.default_allowed_tools.clone()),
+        model: run_overrides
+            .and_then(|overrides| overrides.model)
+            .or_else(|| server.model.clone()),
+        server: server.clone(),
+        redact_patterns: schedule.redact.clone(),
+    }
+}
+
+/// Output recorded before a pause followed by the output after resuming.
+fn join_agent_output(before: &str, after: &str) -> String {
+    match (before.is_empty(), after.is_empty()) {
+        (true, _) => after.to_string(),
+        (false, true) => before.to_string(),
+        (false, false) => format!("{}\n\n{}", before, after),
+    }
+}
+
+/// The schedule or global daily budget `schedule` has used up, if any.
+async fn exhausted_budget(
+    db: &ScheduleDb,
+    config: &ScheduleConfig,
+    schedule: &crate::commands::watch::Schedule,
+) -> Option<String> {
+    let since = budget_day_start(Utc::now());
+    let scopes = [
+        (Some(schedule.name.as_str()), &schedule.budget, "schedule"),
+        (None, &config.watch.budget, "global"),
+    ];
+    for (name, budget, scope) in scopes {
+        if !budget.is_enabled() {
+            continue;
+        }
+        match db.usage_since(name, since).await {
+            Ok(usage) => {
+                if let Some(reason) = budget.exceeded(&usage) {
+                    return Some(format!("{} {}", scope, reason));
+                }
+            }
+            Err(e) => {
+                warn!(schedule = %schedule.name, error = %e, "Failed to read budget usage, proceeding anyway");
+            }
+        }
+    }
+    None
+}
+
+async fn send_budget_notification(
+    config: &ScheduleConfig,
+    schedule: &crate::commands::watch::Schedule,
+    run_id: i64,
+    reason: &str,
+) {
+    let Some(notifications) = &config.notifications else {
+        return;
+    };
+    if !notifications.should_notify(schedule, false) {
+        return;
+    }
+    let deliveries = schedule.effective_deliveries(notifications);
+    if deliveries.is_empty() {
+        warn!(schedule = %schedule.name, "Notification enabled but delivery target is missing");
+        return;
+    }
+
+    let run_url = run_url(notifications, run_id);
+    let text = match schedule.effective_notify_template(notifications) {
+        Some(template) => template.render(&TemplateContext {
+            schedule: &schedule.name,
+            status: "skipped",
+            summary: reason,
+            check_output: "",
+            run_url: run_url.as_deref().unwrap_or_default(),
+        }),
+        None => format!(
+            "💸 Schedule **{}** skipped until tomorrow (UTC): {}",
+            schedule.name, reason
+        ),
+    };
+    let context = NotificationContext {
+        schedule: Some(schedule.name.clone()),
+        summary: Some(reason.to_string()),
+        status: Some("skipped".to_string()),
+        run_url,
+        ..Default::default()
+    };
+
+    for delivery in &deliveries {
+        let mut payload =
+            GatewaySendRequest::new(&delivery.channel, build_gateway_target(delivery), &text);
+        payload.context = Some(context.clone());
+
+        if let Err(error) = post_gateway_send(notifications, &payload).await {
+            warn!(
+                schedule = %schedule.name,
+                channel = %delivery.channel,
+                error = %error,
+                "Failed to send budget notification"
+            );
+        }
+    }
+}
+
+/// Tell the schedule's delivery routes that a run is waiting for approval.
+/// Pauses always notify, regardless of `notify_on`. The first route also gets
+/// allow/deny buttons for the pending tool calls.
+async fn send_pause_notification(
+    config: &ScheduleConfig,
+    schedule: &crate::commands::watch::Schedule,
+    run_id: i64,
+    result: &AgentResult,
+) {
+    let Some(notifications) = &config.notifications else {
+        return;
+    };
+    let deliveries = schedule.effective_deliveries(notifications);
+    if deliveries.is_empty() {
+        return;
+    }
+
+    let summary = schedule.redact(&format_pause_summary(result.pause_reason.as_ref(), run_id));
+    let run_url = run_url(notifications, run_id);
+    let text = match schedule.effective_notify_template(notifications) {
+        Some(template) => template.render(&TemplateContext {
+            schedule: &schedule.name,
+            status: "paused",
+            summary: &summary,
+            check_output: "",
+            run_url: run_url.as_deref().unwrap_or_default(),
+        }),
+        None => format!("⏸️ Schedule **{}** paused\n\n{}", schedule.name, summary),
+    };
+    let context = NotificationContext {
+        schedule: Some(schedule.name.clone()),
+        summary: Some(summary),
+        status: Some("paused".to_string()),
+        run_url,
+        ..Default::default()
+    };
+
+    for (index, delivery) in deliveries.iter().enumerate() {
+        let mut payload =
+            GatewaySendRequest::new(&delivery.channel, build_gateway_target(delivery), &text);
+        payload.context = Some(context.clone());
+        payload.approval = result
+            .session_id
+            .clone()
+            .filter(|_| index == 0)
+            .map(|session_id| ApprovalOptions { session_id });
+
+        let mut sent = post_gateway_send(notifications, &payload).await;
+        if sent.is_err() && payload.approval.is_some() {
+            // Still deliver the notice when the gateway cannot post buttons.
+            payload.approval = None;
+            sent = post_gateway_send(notifications, &payload).await;
+        }
+        if let Err(error) = sent {
+            warn!(
+                schedule = %schedule.name,
+                channel = %delivery.channel,
+                error = %error,
+                "Failed to send pause notification"
+            );
+        }
+    }
+}
+
+/// What a paused run is waiting on and how to resume it.
+fn format_pause_summary(reason: Option<&PauseReason>, run_id: i64) -> String {
+    let mut lines = Vec::new();
+    match reason {
+        Some(PauseReason::ToolApprovalRequired { pending_tool_calls }) => {
+            lines.push(format!(
+                "Waiting for approval of {} tool call(s):",
+                pending_tool_calls.len()
+            ));
+            for tool_call in pending_tool_calls {
+                lines.push(format!(
+                    "- {}: {}",
+                    stakpak_server::strip_tool_prefix(&tool_call.name),
+                    truncate_string(&tool_call.arguments.to_string(), 200)
+                ));
+            }
+        }
+        Some(PauseReason::InputRequired) | None => lines.push("Waiting for input.".to_string()),
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "Resume with `stakpak autopilot resume {} --approve` or `--deny`.",
+        run_id
+    ));
+    lines.join("\n")
+}
+
+/// Copy of a check result with the schedule's redaction patterns applied.
+fn redacted_check_result(
+    schedule: &crate::commands::watch::Schedule,
+    result: &CheckResult,
+) -> CheckResult {
+    CheckResult {
+        stdout: schedule.redact(&result.stdout),
+        stderr: schedule.redact(&result.stderr),
+        ..result.clone()
+    }
+}
+
+/// Agent result with the schedule's redaction patterns applied to its output.
+fn redacted_agent_result(
+    schedule: &crate::commands::watch::Schedule,
+    result: crate::commands::watch::agent::AgentResult,
+) -> crate::commands::watch::agent::AgentResult {
+    crate::commands::watch::agent::AgentResult {
+        stdout: schedule.redact(&result.stdout),
+        stderr: schedule.redact(&result.stderr),
+        ..result
+    }
+}
+
 fn resolve_schedule_profile_overrides(
     profile_name: &str,
     server: &AgentServerConnection,
@@ -902,6 +1873,7 @@ fn resolve_schedule_profile_overrides(
         auto_approve: normalized_auto_approve,
         system_prompt: resolved.system_prompt,
         max_turns: resolved.max_turns,
+        env: None,
     };
 
     let overrides = if overrides.is_empty() {
@@ -913,6 +1885,43 @@ fn resolve_schedule_profile_overrides(
     (overrides, normalized_allowed_tools)
 }
 
+/// A session spawned for `schedule` whose run is still in flight but which
+/// no running or paused run record follows.
+async fn outstanding_session(
+    db: &ScheduleDb,
+    schedule: &crate::commands::watch::Schedule,
+    server: &AgentServerConnection,
+) -> Option<OutstandingSession> {
+    let runs = match db
+        .list_runs(&ListRunsFilter {
+            schedule_name: Some(schedule.name.clone()),
+            limit: Some(100),
+            ..Default::default()
+        })
+        .await
+    {
+        Ok(runs) => runs,
+        Err(error) => {
+            // Without the run records a followed session could be adopted twice.
+            warn!(schedule = %schedule.name, error = %error, "Failed to list runs; not adopting sessions");
+            return None;
+        }
+    };
+    let tracked: HashSet<String> = runs
+        .into_iter()
+        .filter(|run| matches!(run.status, RunStatus::Running | RunStatus::Paused))
+        .filter_map(|run| run.agent_session_id)
+        .collect();
+
+    match find_outstanding_session(server, &schedule_session_tag(&schedule.name), &tracked).await {
+        Ok(outstanding) => outstanding,
+        Err(error) => {
+            warn!(schedule = %schedule.name, error = %error, "Failed to look up outstanding agent sessions");
+            None
+        }
+    }
+}
+
 fn apply_schedule_run_overrides(
     profile_overrides: Option<RunOverrides>,
     schedule: &crate::commands::watch::Schedule,
@@ -946,12 +1955,6 @@ fn normalize_allowed_tools(tools: Vec<String>) -> HashSet<String> {
     normalize_tool_list(tools).into_iter().collect()
 }
 
-#[derive(Debug, Deserialize)]
-struct GatewaySendResponse {
-    #[serde(default)]
-    session_id: Option<String>,
-}
-
 #[derive(Debug, Deserialize)]
 struct GatewaySessionStatusResponse {
     active: bool,
@@ -963,34 +1966,41 @@ async fn try_start_interactive_session(
     check_result: Option<&crate::commands::watch::CheckResult>,
     prompt: &str,
     manual: bool,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, StakpakError> {
     let Some(notifications) = &config.notifications else {
         return Ok(None);
     };
 
-    let Some(delivery) = schedule.effective_delivery(notifications) else {
+    // An interactive session lives in a single chat, the first route.
+    let Some(delivery) = schedule
+        .effective_deliveries(notifications)
+        .into_iter()
+        .next()
+    else {
         return Ok(None);
     };
 
     let caller_context = build_interactive_caller_context(schedule, check_result);
-    let check_output = normalized_check_output(check_result);
+    let check_output = normalized_check_output(check_result).map(|output| schedule.redact(&output));
     let trigger_text = format_trigger_text(&schedule.name, check_result, manual);
-    let payload = serde_json::json!({
-        "channel": delivery.channel,
-        "target": build_gateway_target(&delivery),
-        "text": trigger_text,
-        "context": {
-            "schedule": schedule.name,
-            "check_output": check_output,
-            "status": "triggered"
-        },
-        "interactive": {
-            "prompt": prompt,
-            "caller_context": caller_context,
-            "sandbox": schedule.effective_sandbox(&config.defaults),
-            "timeout": schedule.effective_timeout(&config.defaults).as_secs(),
-            "title": schedule.name,
-        }
+    let mut payload = GatewaySendRequest::new(
+        &delivery.channel,
+        build_gateway_target(&delivery),
+        trigger_text,
+    );
+    payload.context = Some(NotificationContext {
+        schedule: Some(schedule.name.clone()),
+        check_output,
+        status: Some("triggered".to_string()),
+        ..Default::default()
+    });
+    payload.interactive = Some(InteractiveOptions {
+        prompt: prompt.to_string(),
+        caller_context,
+        model: None,
+        sandbox: Some(schedule.effective_sandbox(&config.defaults)),
+        timeout: Some(schedule.effective_timeout(&config.defaults).as_secs()),
+        title: Some(schedule.name.clone()),
     });
 
     match post_gateway_send(notifications, &payload).await {
@@ -1024,26 +2034,25 @@ fn format_trigger_text(
 fn build_interactive_caller_context(
     schedule: &crate::commands::watch::Schedule,
     check_result: Option<&crate::commands::watch::CheckResult>,
-) -> Vec<serde_json::Value> {
-    let mut items = vec![serde_json::json!({
-        "name": "schedule",
-        "priority": "high",
-        "content": format!("Schedule '{}' fired", schedule.name),
-    })];
+) -> Vec<CallerContextInput> {
+    let item = |name: &str, content: String| CallerContextInput {
+        name: name.to_string(),
+        content,
+        priority: Some("high".to_string()),
+    };
+    let mut items = vec![item(
+        "schedule",
+        format!("Schedule '{}' fired", schedule.name),
+    )];
 
     if let Some(check_result) = check_result {
-        items.push(serde_json::json!({
-            "name": "check_exit_code",
-            "priority": "high",
-            "content": check_result.exit_code.unwrap_or(-1).to_string(),
-        }));
+        items.push(item(
+            "check_exit_code",
+            check_result.exit_code.unwrap_or(-1).to_string(),
+        ));
 
         if let Some(check_output) = normalized_check_output(Some(check_result)) {
-            items.push(serde_json::json!({
-                "name": "check_output",
-                "priority": "high",
-                "content": check_output,
-            }));
+            items.push(item("check_output", check_output));
         }
     }
 
@@ -1059,7 +2068,7 @@ fn normalized_check_output(
         .map(|value| truncate_string(&value, MAX_GATEWAY_CHECK_OUTPUT_CHARS))
 }
 
-fn watch_http_client() -> Result<&'static reqwest::Client, String> {
+fn watch_http_client() -> Result<&'static reqwest::Client, StakpakError> {
     if let Some(client) = WATCH_HTTP_CLIENT.get() {
         return Ok(client);
     }
@@ -1068,19 +2077,24 @@ fn watch_http_client() -> Result<&'static reqwest::Client, String> {
         .connect_timeout(Duration::from_secs(3))
         .timeout(Duration::from_secs(10))
         .build()
-        .map_err(|error| format!("failed to create gateway HTTP client: {}", error))?;
+        .map_err(|error| {
+            StakpakError::internal(format!("failed to create gateway HTTP client: {}", error))
+        })?;
 
     let _ = WATCH_HTTP_CLIENT.set(client);
 
     WATCH_HTTP_CLIENT
         .get()
-        .ok_or_else(|| "failed to initialize watch HTTP client".to_string())
+        .ok_or_else(|| StakpakError::internal("failed to initialize watch HTTP client"))
 }
 
 async fn post_gateway_send(
     notifications: &crate::commands::watch::config::NotificationConfig,
-    payload: &serde_json::Value,
-) -> Result<GatewaySendResponse, String> {
+    payload: &GatewaySendRequest,
+) -> Result<GatewaySendResponse, StakpakError> {
+    payload
+        .validate()
+        .map_err(|error| StakpakError::config(format!("invalid gateway request: {}", error)))?;
     let client = watch_http_client()?;
 
     let mut request = client.post(format!("{}/v1/gateway/send", notifications.gateway_url));
@@ -1091,31 +2105,44 @@ async fn post_gateway_send(
         request = request.bearer_auth(token);
     }
 
-    let response = request
-        .json(payload)
-        .send()
-        .await
-        .map_err(|error| format!("gateway send request failed: {}", error))?;
+    let response = request.json(payload).send().await.map_err(|error| {
+        StakpakError::network(format!("gateway send request failed: {}", error))
+    })?;
 
     let status = response.status();
     if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
-        return Err(format!(
-            "gateway send request returned {}: {}",
-            status, body
+        let detail = serde_json::from_str::<GatewayApiError>(&body)
+            .map(|error| error.to_string())
+            .unwrap_or(body);
+        return Err(StakpakError::new(
+            gateway_status_kind(status),
+            format!("gateway send request returned {}: {}", status, detail),
         ));
     }
 
     response
         .json::<GatewaySendResponse>()
         .await
-        .map_err(|error| format!("failed to decode gateway response: {}", error))
+        .map_err(|error| {
+            StakpakError::internal(format!("failed to decode gateway response: {}", error))
+        })
+}
+
+/// The kind of error a gateway API response status stands for.
+fn gateway_status_kind(status: reqwest::StatusCode) -> ErrorKind {
+    match status.as_u16() {
+        401 | 403 => ErrorKind::Auth,
+        429 => ErrorKind::RateLimit,
+        400 | 422 => ErrorKind::Config,
+        _ => ErrorKind::Internal,
+    }
 }
 
 async fn get_gateway_session_status(
     notifications: &crate::commands::watch::config::NotificationConfig,
     session_id: &str,
-) -> Result<Option<GatewaySessionStatusResponse>, String> {
+) -> Result<Option<GatewaySessionStatusResponse>, StakpakError> {
     let client = watch_http_client()?;
     let mut request = client.get(format!(
         "{}/v1/gateway/sessions/{}",
@@ -1128,10 +2155,9 @@ async fn get_gateway_session_status(
         request = request.bearer_auth(token);
     }
 
-    let response = request
-        .send()
-        .await
-        .map_err(|error| format!("gateway session status request failed: {}", error))?;
+    let response = request.send().await.map_err(|error| {
+        StakpakError::network(format!("gateway session status request failed: {}", error))
+    })?;
 
     let status = response.status();
     if status == reqwest::StatusCode::NOT_FOUND {
@@ -1140,9 +2166,12 @@ async fn get_gateway_session_status(
 
     if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
-        return Err(format!(
-            "gateway session status request returned {}: {}",
-            status, body
+        return Err(StakpakError::new(
+            gateway_status_kind(status),
+            format!(
+                "gateway session status request returned {}: {}",
+                status, body
+            ),
         ));
     }
 
@@ -1151,17 +2180,17 @@ async fn get_gateway_session_status(
         .await
         .map(Some)
         .map_err(|error| {
-            format!(
+            StakpakError::internal(format!(
                 "failed to decode gateway session status response: {}",
                 error
-            )
+            ))
         })
 }
 
 async fn reconcile_interactive_runs(
     db: &ScheduleDb,
     config: &ScheduleConfig,
-) -> Result<(), String> {
+) -> Result<(), StakpakError> {
     let Some(notifications) = &config.notifications else {
         return Ok(());
     };
@@ -1178,10 +2207,9 @@ async fn reconcile_interactive_runs(
             ..Default::default()
         };
 
-        let page = db
-            .list_runs(&filter)
-            .await
-            .map_err(|error| format!("failed to list running runs: {}", error))?;
+        let page = db.list_runs(&filter).await.map_err(|error| {
+            StakpakError::internal(format!("failed to list running runs: {}", error))
+        })?;
 
         if page.is_empty() {
             break;
@@ -1227,7 +2255,10 @@ async fn reconcile_interactive_runs(
                 )
                 .await
                 .map_err(|error| {
-                    format!("failed to finalize expired interactive run: {}", error)
+                    StakpakError::internal(format!(
+                        "failed to finalize expired interactive run: {}",
+                        error
+                    ))
                 })?;
             }
             Ok(Some(_status)) => {
@@ -1239,19 +2270,27 @@ async fn reconcile_interactive_runs(
                     None,
                 )
                 .await
-                .map_err(|error| format!("failed to finalize interactive run: {}", error))?;
+                .map_err(|error| {
+                    StakpakError::internal(format!("failed to finalize interactive run: {}", error))
+                })?;
             }
             Ok(None) => {
                 db.update_run_finished(
                     run.id,
                     RunStatus::Failed,
-                    Some("Interactive gateway session not found"),
+                    Some(
+                        &StakpakError::internal("Interactive gateway session not found")
+                            .to_record(),
+                    ),
                     None,
                     None,
                 )
                 .await
                 .map_err(|error| {
-                    format!("failed to finalize missing interactive run: {}", error)
+                    StakpakError::internal(format!(
+                        "failed to finalize missing interactive run: {}",
+                        error
+                    ))
                 })?;
             }
             Err(error) if run_expired => {
@@ -1271,10 +2310,10 @@ async fn reconcile_interactive_runs(
                 )
                 .await
                 .map_err(|db_error| {
-                    format!(
+                    StakpakError::internal(format!(
                         "failed to finalize expired interactive run after status errors: {}",
                         db_error
-                    )
+                    ))
                 })?;
             }
             Err(error) => {
@@ -1312,6 +2351,7 @@ fn interactive_run_max_age(config: &ScheduleConfig, schedule_name: &str) -> chro
 async fn maybe_send_notification(
     config: &ScheduleConfig,
     schedule: &crate::commands::watch::Schedule,
+    run_id: i64,
     result: &crate::commands::watch::agent::AgentResult,
     check_result: Option<&crate::commands::watch::CheckResult>,
     error_override: Option<&str>,
@@ -1325,41 +2365,70 @@ async fn maybe_send_notification(
         return;
     }
 
-    let Some(delivery) = schedule.effective_delivery(notifications) else {
+    let deliveries = schedule.effective_deliveries(notifications);
+    if deliveries.is_empty() {
         warn!(schedule = %schedule.name, "Notification enabled but delivery target is missing");
         return;
-    };
+    }
 
-    let text = format_notification(schedule, result, check_result, error_override);
+    let status = if success { "completed" } else { "failed" };
+    let summary = extract_summary(result, error_override);
     let check_output = normalized_check_output(check_result);
-    let context = serde_json::json!({
-        "schedule": schedule.name,
-        "summary": extract_summary(result, error_override),
-        "check_output": check_output,
-        "status": if success { "completed" } else { "failed" },
-    });
+    let run_url = run_url(notifications, run_id);
+    let text = match schedule.effective_notify_template(notifications) {
+        Some(template) => template.render(&TemplateContext {
+            schedule: &schedule.name,
+            status,
+            summary: &summary,
+            check_output: check_output.as_deref().unwrap_or_default(),
+            run_url: run_url.as_deref().unwrap_or_default(),
+        }),
+        None => format_notification(schedule, result, check_result, error_override),
+    };
+    let context = NotificationContext {
+        schedule: Some(schedule.name.clone()),
+        summary: Some(summary),
+        check_output,
+        status: Some(status.to_string()),
+        run_url,
+        ..Default::default()
+    };
 
-    let payload = serde_json::json!({
-        "channel": delivery.channel,
-        "target": build_gateway_target(&delivery),
-        "text": text,
-        "context": context,
-    });
+    for delivery in &deliveries {
+        let mut payload =
+            GatewaySendRequest::new(&delivery.channel, build_gateway_target(delivery), &text);
+        payload.context = Some(context.clone());
 
-    if let Err(error) = post_gateway_send(notifications, &payload).await {
-        warn!(
-            schedule = %schedule.name,
-            error = %error,
-            "Failed to send watch notification"
-        );
+        if let Err(error) = post_gateway_send(notifications, &payload).await {
+            warn!(
+                schedule = %schedule.name,
+                channel = %delivery.channel,
+                error = %error,
+                "Failed to send watch notification"
+            );
+        }
     }
 }
 
+/// Link to a run on the configured dashboard, if `run_url_base` is set.
+fn run_url(
+    notifications: &crate::commands::watch::config::NotificationConfig,
+    run_id: i64,
+) -> Option<String> {
+    let base = notifications.run_url_base.as_deref()?.trim();
+    if base.is_empty() {
+        return None;
+    }
+    Some(format!("{}/{}", base.trim_end_matches('/'), run_id))
+}
+
 fn build_gateway_target(delivery: &crate::commands::watch::DeliveryConfig) -> serde_json::Value {
     match delivery.channel.as_str() {
         "telegram" => serde_json::json!({ "chat_id": delivery.target }),
         "discord" => serde_json::json!({ "channel_id": delivery.target }),
         "slack" => serde_json::json!({ "channel": delivery.target }),
+        "whatsapp" => serde_json::json!({ "to": delivery.target }),
+        "matrix" | "rocketchat" => serde_json::json!({ "room_id": delivery.target }),
         _ => serde_json::json!({ "chat_id": delivery.target }),
     }
 }
@@ -1506,16 +2575,26 @@ fn print_banner() {
 }
 
 /// Print configuration summary.
-fn print_config_summary(config: &ScheduleConfig, pid: i64) {
+fn print_config_summary(config: &ScheduleConfig, pid: i64, database: &str) {
     println!("\x1b[1mConfiguration:\x1b[0m");
     println!("  PID:        {}", pid);
-    println!("  Database:   {}", config.db_path().display());
+    println!("  Database:   {}", database);
     println!("  Log dir:    {}", config.log_dir().display());
+    println!("  Artifacts:  {}", config.artifacts_dir().display());
     println!("  Profile:    {}", config.defaults.profile);
     println!(
         "  Timeout:    {}",
         humantime::format_duration(config.defaults.timeout)
     );
+    if let Some(max_parallel_runs) = config.watch.max_parallel_runs {
+        println!(
+            "  Parallel:   {} max ({} when full)",
+            max_parallel_runs, config.watch.on_concurrency_limit
+        );
+    }
+    if let Some(addr) = config.watch.health_listen {
+        println!("  Health:     http://{}/healthz", addr);
+    }
     println!();
 }
 
@@ -1532,7 +2611,8 @@ fn print_schedules_table(schedules: &[crate::commands::watch::Schedule]) {
     println!("  {}", "-".repeat(66));
 
     for trigger in schedules {
-        let next_run = calculate_next_run(&trigger.cron)
+        let next_run = trigger
+            .next_occurrence(Utc::now())
             .map(|dt| format_relative_time(&dt))
             .unwrap_or_else(|| "invalid".to_string());
 
@@ -1551,12 +2631,6 @@ fn print_schedules_table(schedules: &[crate::commands::watch::Schedule]) {
     println!();
 }
 
-/// Calculate the next run time for a cron expression.
-fn calculate_next_run(schedule: &str) -> Option<DateTime<Utc>> {
-    let cron = Cron::from_str(schedule).ok()?;
-    cron.find_next_occurrence(&Utc::now(), false).ok()
-}
-
 /// Format datetime as relative time (e.g., "in 5m 30s").
 fn format_relative_time(dt: &DateTime<Utc>) -> String {
     let now = Utc::now();
@@ -1605,6 +2679,8 @@ fn print_event(event_type: &str, trigger_name: &str, message: &str) {
         "timeout" => ("\x1b[31m", "TO"),
         "clean" => ("\x1b[34m", "RC"),
         "reload" => ("\x1b[34m", "RL"),
+        "queue" => ("\x1b[2m", "QU"),
+        "resume" => ("\x1b[33m", ">|"),
         _ => ("\x1b[0m", ".."),
     };
     println!(
@@ -1616,9 +2692,10 @@ fn print_event(event_type: &str, trigger_name: &str, message: &str) {
 #[cfg(test)]
 mod tests {
     use super::{
-        MAX_GATEWAY_CHECK_OUTPUT_CHARS, apply_schedule_run_overrides, build_gateway_target,
-        build_interactive_caller_context, interactive_run_max_age, normalized_check_output,
-        resolve_schedule_profile_overrides, trigger_config_reload_with_loader,
+        MAX_GATEWAY_CHECK_OUTPUT_CHARS, PauseReason, apply_schedule_run_overrides,
+        build_gateway_target, build_interactive_caller_context, format_pause_summary,
+        interactive_run_max_age, join_agent_output, normalized_check_output,
+        resolve_schedule_profile_overrides, run_url, trigger_config_reload_with_loader,
         validate_prior_scheduler_state,
     };
     use crate::commands::watch::config::{ScheduleDefaults, ScheduleSettings};
@@ -1727,7 +2804,8 @@ mod tests {
         let result = validate_prior_scheduler_state(Some(state), now);
         assert!(result.is_err());
 
-        let message = result.expect_err("expected active-instance guard error");
+        let error = result.expect_err("expected active-instance guard error");
+        let message = error.message;
         assert!(
             message.contains("appears active"),
             "unexpected guard message: {message}"
@@ -2086,12 +3164,25 @@ mod tests {
             enable_subagents: None,
             pause_on_approval: None,
             sandbox: None,
+            workdir: None,
+            env: Default::default(),
+            env_file: None,
             notify_on: None,
             notify_channel: None,
             notify_target: None,
             notify_chat_id: None,
             legacy_channel: None,
             interaction: InteractionMode::Interactive,
+            concurrency_group: None,
+            timezone: None,
+            jitter: None,
+            blackout: Vec::new(),
+            budget: Default::default(),
+            redact: Vec::new(),
+            notify_routes: Vec::new(),
+            notify_template: None,
+            on_complete_webhook: None,
+            dry_run: false,
             enabled: true,
         }
     }
@@ -2108,9 +3199,9 @@ mod tests {
 
         let context = build_interactive_caller_context(&schedule, Some(&check));
         assert_eq!(context.len(), 3);
-        assert_eq!(context[0]["name"], "schedule");
-        assert_eq!(context[1]["name"], "check_exit_code");
-        assert_eq!(context[2]["name"], "check_output");
+        assert_eq!(context[0].name, "schedule");
+        assert_eq!(context[1].name, "check_exit_code");
+        assert_eq!(context[2].name, "check_output");
     }
 
     #[test]
@@ -2134,6 +3225,7 @@ mod tests {
             watch: ScheduleSettings::default(),
             defaults: ScheduleDefaults::default(),
             notifications: None,
+            retention: Default::default(),
             schedules: vec![Schedule {
                 timeout: Some(StdDuration::from_secs(48 * 60 * 60)),
                 ..sample_schedule_for_context("long-run")
@@ -2150,6 +3242,7 @@ mod tests {
             watch: ScheduleSettings::default(),
             defaults: ScheduleDefaults::default(),
             notifications: None,
+            retention: Default::default(),
             schedules: Vec::new(),
         };
         let max_age = interactive_run_max_age(&config, "missing");
@@ -2171,10 +3264,36 @@ mod tests {
             channel: "discord".to_string(),
             target: "987654321".to_string(),
         });
+        let whatsapp = build_gateway_target(&crate::commands::watch::DeliveryConfig {
+            channel: "whatsapp".to_string(),
+            target: "4915112345678".to_string(),
+        });
 
         assert_eq!(slack, serde_json::json!({ "channel": "#ops" }));
         assert_eq!(telegram, serde_json::json!({ "chat_id": "123456789" }));
         assert_eq!(discord, serde_json::json!({ "channel_id": "987654321" }));
+        assert_eq!(whatsapp, serde_json::json!({ "to": "4915112345678" }));
+    }
+
+    #[test]
+    fn test_run_url_joins_configured_base() {
+        let mut notifications = crate::commands::watch::config::NotificationConfig {
+            gateway_url: "http://127.0.0.1:4096".to_string(),
+            gateway_token: None,
+            notify_on: None,
+            channel: None,
+            target: None,
+            chat_id: None,
+            template: None,
+            run_url_base: None,
+        };
+        assert_eq!(run_url(&notifications, 42), None);
+
+        notifications.run_url_base = Some("https://ops.example.com/runs/".to_string());
+        assert_eq!(
+            run_url(&notifications, 42).as_deref(),
+            Some("https://ops.example.com/runs/42")
+        );
     }
 
     #[test]
@@ -2606,6 +3725,33 @@ auto_approve = []
             .expect("failed to shutdown scheduler");
     }
 
+    #[test]
+    fn test_pause_summary_lists_pending_tools_and_resume_command() {
+        let reason = PauseReason::ToolApprovalRequired {
+            pending_tool_calls: vec![stakpak_shared::models::async_manifest::PendingToolCall {
+                id: "call-1".to_string(),
+                name: "stakpak__run_command".to_string(),
+                arguments: serde_json::json!({ "command": "systemctl restart nginx" }),
+            }],
+        };
+
+        assert_eq!(
+            format_pause_summary(Some(&reason), 7),
+            "Waiting for approval of 1 tool call(s):\n\
+             - run_command: {\"command\":\"systemctl restart nginx\"}\n\
+             \n\
+             Resume with `stakpak autopilot resume 7 --approve` or `--deny`."
+        );
+        assert!(format_pause_summary(None, 7).starts_with("Waiting for input."));
+    }
+
+    #[test]
+    fn test_join_agent_output_keeps_output_from_before_pause() {
+        assert_eq!(join_agent_output("", "after"), "after");
+        assert_eq!(join_agent_output("before", ""), "before");
+        assert_eq!(join_agent_output("before", "after"), "before\n\nafter");
+    }
+
     #[tokio::test]
     async fn test_config_reload_without_notifications_section_is_safe() {
         // Config has no [notifications] at all. Reload should not fabricate one.
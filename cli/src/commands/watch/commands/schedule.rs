@@ -1,8 +1,13 @@
 //! Autopilot schedule command - inspect or manually fire a schedule.
 
+use std::str::FromStr;
+
+use chrono::Utc;
+use croner::Cron;
+
 use crate::commands::watch::{
-    RunStatus, ScheduleConfig, ScheduleDb, assemble_prompt, build_schedule_caller_context,
-    is_process_running, run_check_script,
+    CheckSpec, RunStatus, Schedule, ScheduleConfig, ScheduleDb, is_process_running, render_dry_run,
+    run_check,
 };
 
 /// Show detailed information about a schedule.
@@ -42,7 +47,10 @@ pub async fn show_schedule(name: &str) -> Result<(), String> {
     );
 
     if let Some(check) = &schedule.check {
-        println!("Check script:  {}", check);
+        match check {
+            CheckSpec::Script(path) => println!("Check script:  {}", path),
+            CheckSpec::Probe(probe) => println!("Check probe:   {}", probe),
+        }
         println!(
             "Check timeout: {:?} {}",
             schedule.effective_check_timeout(&config.defaults),
@@ -67,15 +75,11 @@ pub async fn show_schedule(name: &str) -> Result<(), String> {
     println!("---");
 
     // Show recent runs
-    let db_path = config.db_path();
-    if let Ok(db_path_str) = db_path.to_str().ok_or("Invalid path")
-        && let Ok(db) = ScheduleDb::new(db_path_str).await
-    {
+    if let Ok(db) = ScheduleDb::open(&config.watch).await {
         let filter = crate::commands::watch::ListRunsFilter {
             schedule_name: Some(name.to_string()),
-            status: None,
             limit: Some(5),
-            offset: None,
+            ..Default::default()
         };
 
         if let Ok(runs) = db.list_runs(&filter).await
@@ -122,96 +126,13 @@ pub async fn fire_schedule(name: &str, dry_run: bool) -> Result<(), String> {
     // For dry run, show what would happen without queuing
     if dry_run {
         println!("Schedule: {}", schedule.name);
-
-        // Run check script if defined (for dry run preview)
-        let check_result = if let Some(check_path) = &schedule.check {
-            let expanded_path = crate::commands::watch::config::expand_tilde(check_path);
-            let timeout = schedule.effective_check_timeout(&config.defaults);
-
-            println!("Check script: {}", expanded_path.display());
-
-            match run_check_script(&expanded_path, timeout).await {
-                Ok(result) => {
-                    let exit_code = result.exit_code.unwrap_or(-1);
-                    println!("Check result: exit {}", exit_code);
-
-                    if !result.stdout.trim().is_empty() {
-                        println!("Check stdout:");
-                        for line in result.stdout.lines() {
-                            println!("  {}", line);
-                        }
-                    }
-
-                    if !result.stderr.trim().is_empty() {
-                        println!("Check stderr:");
-                        for line in result.stderr.lines() {
-                            println!("  {}", line);
-                        }
-                    }
-
-                    if result.timed_out {
-                        println!("\n\x1b[31mCheck script timed out\x1b[0m");
-                    } else {
-                        let trigger_on = schedule.effective_trigger_on(&config.defaults);
-                        let should_trigger = trigger_on.should_trigger(exit_code);
-                        println!("Check trigger_on: {}", trigger_on);
-                        if should_trigger {
-                            println!(
-                                "\n\x1b[32mCheck passed (exit {} matches trigger_on={}) - agent would be woken\x1b[0m",
-                                exit_code, trigger_on
-                            );
-                        } else {
-                            println!(
-                                "\n\x1b[33mCheck skipped (exit {} does not match trigger_on={}) - agent would not be woken\x1b[0m",
-                                exit_code, trigger_on
-                            );
-                        }
-                    }
-
-                    Some(result)
-                }
-                Err(e) => {
-                    println!("\n\x1b[31mFailed to run check script: {}\x1b[0m", e);
-                    None
-                }
-            }
-        } else {
-            println!("Check script: none");
-            None
-        };
-
-        // Assemble user prompt + structured caller context preview
-        let prompt = assemble_prompt(schedule, check_result.as_ref());
-        let caller_context = build_schedule_caller_context(schedule, check_result.as_ref());
-
-        println!("\nAssembled prompt:");
-        println!("---");
-        println!("{}", prompt);
-        println!("---");
-
-        println!("\nStructured caller context:");
-        for entry in caller_context {
-            println!("- name: {}", entry.name);
-            if let Some(priority) = entry.priority {
-                println!("  priority: {}", priority);
-            }
-            println!("  content:");
-            for line in entry.content.lines() {
-                println!("    {}", line);
-            }
-        }
-
+        preview_firing(schedule, &config).await;
         println!("\n\x1b[33m[Dry run - schedule not queued, nothing recorded]\x1b[0m");
         return Ok(());
     }
 
     // Connect to database
-    let db_path = config.db_path();
-    let db_path_str = db_path
-        .to_str()
-        .ok_or_else(|| "Invalid database path".to_string())?;
-
-    let db = ScheduleDb::new(db_path_str)
+    let db = ScheduleDb::open(&config.watch)
         .await
         .map_err(|e| format!("Failed to open database: {}", e))?;
 
@@ -255,3 +176,179 @@ pub async fn fire_schedule(name: &str, dry_run: bool) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Preview a schedule end to end: its next fire times, one run of its check
+/// with the trigger decision, and the prompt the agent would get. Nothing is
+/// queued or recorded, so autopilot does not need to be running.
+pub async fn test_schedule(name: &str, count: usize) -> Result<(), String> {
+    let config = ScheduleConfig::load_default()
+        .map_err(|e| format!("Failed to load watch config: {}", e))?;
+
+    let schedule = config
+        .schedules
+        .iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("Schedule '{}' not found", name))?;
+
+    let timezone = schedule.effective_timezone();
+    println!("Schedule: {}", schedule.name);
+    match Cron::from_str(&schedule.cron) {
+        Ok(cron) => {
+            println!("Cron: {} ({})", schedule.cron, timezone);
+            println!("      {}", cron.describe());
+        }
+        Err(e) => {
+            return Err(format!(
+                "Invalid cron expression '{}': {}",
+                schedule.cron, e
+            ));
+        }
+    }
+    if let Some(jitter) = schedule.jitter {
+        println!("Jitter: up to {:?} after each fire time", jitter);
+    }
+    if !schedule.enabled {
+        println!("\x1b[33mSchedule is disabled - it will not fire until enabled\x1b[0m");
+    }
+
+    println!("Next {} fire times:", count);
+    for fire in schedule.next_occurrences(Utc::now(), count) {
+        let local = fire.with_timezone(&timezone).format("%a %Y-%m-%d %H:%M %Z");
+        match schedule.active_blackout(&config.watch, fire) {
+            Some(window) => println!("  {}  \x1b[90m(skipped: blackout {})\x1b[0m", local, window),
+            None => println!("  {}", local),
+        }
+    }
+    println!();
+
+    preview_firing(schedule, &config).await;
+
+    println!("\n\x1b[33m[Test - schedule not queued, nothing recorded]\x1b[0m");
+    Ok(())
+}
+
+/// Run the schedule's check once and print the trigger decision, the
+/// assembled prompt and the spawn settings. Nothing is queued or recorded.
+async fn preview_firing(schedule: &Schedule, config: &ScheduleConfig) {
+    // Run check script if defined
+    let check_result = if let Some(check) = &schedule.check {
+        let timeout = schedule.effective_check_timeout(&config.defaults);
+
+        match check {
+            CheckSpec::Script(path) => println!(
+                "Check script: {}",
+                crate::commands::watch::config::expand_tilde(path).display()
+            ),
+            CheckSpec::Probe(probe) => println!("Check probe: {}", probe),
+        }
+
+        match run_check(check, timeout).await {
+            Ok(result) => {
+                let exit_code = result.exit_code.unwrap_or(-1);
+                println!("Check result: exit {}", exit_code);
+
+                if !result.stdout.trim().is_empty() {
+                    println!("Check stdout:");
+                    for line in result.stdout.lines() {
+                        println!("  {}", line);
+                    }
+                }
+
+                if !result.stderr.trim().is_empty() {
+                    println!("Check stderr:");
+                    for line in result.stderr.lines() {
+                        println!("  {}", line);
+                    }
+                }
+
+                if result.timed_out {
+                    println!("\n\x1b[31mCheck script timed out\x1b[0m");
+                } else {
+                    let trigger_on = schedule.effective_trigger_on(&config.defaults);
+                    let should_trigger = result.should_trigger(trigger_on);
+                    println!("Check trigger_on: {}", trigger_on);
+                    if should_trigger {
+                        println!(
+                            "\n\x1b[32mCheck passed (exit {} matches trigger_on={}) - agent would be woken\x1b[0m",
+                            exit_code, trigger_on
+                        );
+                    } else {
+                        println!(
+                            "\n\x1b[33mCheck skipped (exit {} does not match trigger_on={}) - agent would not be woken\x1b[0m",
+                            exit_code, trigger_on
+                        );
+                    }
+                }
+
+                Some(result)
+            }
+            Err(e) => {
+                println!("\n\x1b[31mFailed to run check script: {}\x1b[0m", e);
+                None
+            }
+        }
+    } else {
+        println!("Check script: none");
+        None
+    };
+
+    // Assembled prompt, structured caller context and spawn settings
+    print!(
+        "\n{}",
+        render_dry_run(schedule, &config.defaults, check_result.as_ref())
+    );
+}
+
+/// Approve or deny the pending tool calls of a paused run and let it continue.
+pub async fn resume_run(run_id: i64, approve: bool) -> Result<(), String> {
+    let config = ScheduleConfig::load_default()
+        .map_err(|e| format!("Failed to load watch config: {}", e))?;
+
+    let db = ScheduleDb::open(&config.watch)
+        .await
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+
+    let run = db
+        .get_run(run_id)
+        .await
+        .map_err(|e| format!("Run {} not found: {}", run_id, e))?;
+    if run.status != RunStatus::Paused {
+        return Err(format!(
+            "Run {} is not paused (status: {})",
+            run_id, run.status
+        ));
+    }
+    if run.agent_session_id.is_none() {
+        return Err(format!("Run {} has no agent session to resume", run_id));
+    }
+
+    // The paused session lives in the autopilot's agent server, so only a
+    // running autopilot can resume it.
+    let running = db
+        .get_autopilot_state()
+        .await
+        .map_err(|e| format!("Failed to check autopilot state: {}", e))?
+        .is_some_and(|state| is_process_running(state.pid as u32));
+    if !running {
+        return Err(
+            "Autopilot is not running. Start it with 'stakpak autopilot up' first.".to_string(),
+        );
+    }
+
+    db.insert_pending_resume(run_id, approve)
+        .await
+        .map_err(|e| format!("Failed to queue resume: {}", e))?;
+
+    println!(
+        "\x1b[32m✓\x1b[0m Run {} of '{}' queued to resume ({})",
+        run_id,
+        run.schedule_name,
+        if approve { "approved" } else { "denied" }
+    );
+    println!(
+        "  Use 'stakpak autopilot schedule show {}' to follow it.",
+        run_id
+    );
+
+    Ok(())
+}
@@ -0,0 +1,369 @@
+//! Concurrency limits for autopilot schedule runs.
+//!
+//! Every schedule is already guarded against overlapping with itself via the
+//! database singleton check. The limiter adds two coarser limits on top:
+//!
+//! - a global `max_parallel_runs` ceiling across all schedules
+//! - `concurrency_group`s, where at most one schedule of a group runs at a time
+//!
+//! Runs that hit a limit are either skipped or queued until a slot frees up,
+//! depending on `watch.on_concurrency_limit`.
+
+use super::config::{ConcurrencyLimitPolicy, Schedule, ScheduleSettings};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// Reason a run could not acquire a slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConcurrencyLimit {
+    /// The global `max_parallel_runs` ceiling is reached.
+    MaxParallelRuns(usize),
+    /// Another schedule in the same concurrency group is running.
+    Group(String),
+    /// A run of the same schedule is already waiting for a slot.
+    AlreadyQueued,
+}
+
+impl std::fmt::Display for ConcurrencyLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConcurrencyLimit::MaxParallelRuns(max) => {
+                write!(f, "max_parallel_runs={} reached", max)
+            }
+            ConcurrencyLimit::Group(group) => {
+                write!(f, "concurrency group '{}' is busy", group)
+            }
+            ConcurrencyLimit::AlreadyQueued => write!(f, "a run is already queued"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    active_total: usize,
+    active_groups: HashMap<String, usize>,
+    queued_schedules: HashSet<String>,
+}
+
+/// Tracks active runs and hands out slots according to the configured limits.
+///
+/// Limits are read from the settings passed to each call, so config hot-reloads
+/// apply to the next run without rebuilding the limiter.
+#[derive(Debug, Clone, Default)]
+pub struct RunLimiter {
+    state: Arc<Mutex<LimiterState>>,
+    released: Arc<Notify>,
+}
+
+/// A held run slot. Dropping it frees the slot and wakes queued runs.
+#[derive(Debug)]
+pub struct RunPermit {
+    state: Arc<Mutex<LimiterState>>,
+    released: Arc<Notify>,
+    group: Option<String>,
+}
+
+impl RunLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Try to take a slot without waiting.
+    pub fn try_acquire(
+        &self,
+        schedule: &Schedule,
+        settings: &ScheduleSettings,
+    ) -> Result<RunPermit, ConcurrencyLimit> {
+        let mut state = self.lock_state();
+        self.try_acquire_locked(&mut state, schedule, settings)
+    }
+
+    /// Take a slot, honoring `on_concurrency_limit`.
+    ///
+    /// With the `skip` policy this returns immediately. With `queue` it waits
+    /// until a slot is available; only one run per schedule may wait at a time.
+    pub async fn acquire(
+        &self,
+        schedule: &Schedule,
+        settings: &ScheduleSettings,
+    ) -> Result<RunPermit, ConcurrencyLimit> {
+        let limit = match self.try_acquire(schedule, settings) {
+            Ok(permit) => return Ok(permit),
+            Err(limit) => limit,
+        };
+
+        if settings.on_concurrency_limit == ConcurrencyLimitPolicy::Skip {
+            return Err(limit);
+        }
+
+        {
+            let mut state = self.lock_state();
+            if !state.queued_schedules.insert(schedule.name.clone()) {
+                return Err(ConcurrencyLimit::AlreadyQueued);
+            }
+        }
+
+        loop {
+            // Register for wake-ups before re-checking so a release between
+            // the check and the await is not missed.
+            let notified = self.released.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            {
+                let mut state = self.lock_state();
+                if let Ok(permit) = self.try_acquire_locked(&mut state, schedule, settings) {
+                    state.queued_schedules.remove(&schedule.name);
+                    return Ok(permit);
+                }
+            }
+
+            notified.await;
+        }
+    }
+
+    /// Number of runs currently holding a slot.
+    pub fn active_runs(&self) -> usize {
+        self.lock_state().active_total
+    }
+
+    fn try_acquire_locked(
+        &self,
+        state: &mut LimiterState,
+        schedule: &Schedule,
+        settings: &ScheduleSettings,
+    ) -> Result<RunPermit, ConcurrencyLimit> {
+        if let Some(max) = settings.max_parallel_runs
+            && state.active_total >= max
+        {
+            return Err(ConcurrencyLimit::MaxParallelRuns(max));
+        }
+
+        let group = schedule.concurrency_group().map(str::to_string);
+        if let Some(group) = &group
+            && state.active_groups.get(group).copied().unwrap_or(0) > 0
+        {
+            return Err(ConcurrencyLimit::Group(group.clone()));
+        }
+
+        state.active_total += 1;
+        if let Some(group) = &group {
+            *state.active_groups.entry(group.clone()).or_insert(0) += 1;
+        }
+
+        Ok(RunPermit {
+            state: Arc::clone(&self.state),
+            released: Arc::clone(&self.released),
+            group,
+        })
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, LimiterState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Drop for RunPermit {
+    fn drop(&mut self) {
+        {
+            let mut state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            state.active_total = state.active_total.saturating_sub(1);
+            if let Some(group) = &self.group
+                && let Some(count) = state.active_groups.get_mut(group)
+            {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    state.active_groups.remove(group);
+                }
+            }
+        }
+        self.released.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::watch::ScheduleConfig;
+    use std::time::Duration;
+
+    fn config(toml: &str) -> ScheduleConfig {
+        ScheduleConfig::parse(toml).expect("config should parse")
+    }
+
+    #[test]
+    fn test_max_parallel_runs_limits_active_runs() {
+        let config = config(
+            r#"
+[watch]
+max_parallel_runs = 2
+
+[[schedules]]
+name = "a"
+cron = "* * * * *"
+prompt = "a"
+
+[[schedules]]
+name = "b"
+cron = "* * * * *"
+prompt = "b"
+
+[[schedules]]
+name = "c"
+cron = "* * * * *"
+prompt = "c"
+"#,
+        );
+        let limiter = RunLimiter::new();
+
+        let first = limiter.try_acquire(&config.schedules[0], &config.watch);
+        let second = limiter.try_acquire(&config.schedules[1], &config.watch);
+        let third = limiter.try_acquire(&config.schedules[2], &config.watch);
+
+        assert!(first.is_ok());
+        assert!(second.is_ok());
+        assert_eq!(third.err(), Some(ConcurrencyLimit::MaxParallelRuns(2)));
+        assert_eq!(limiter.active_runs(), 2);
+
+        drop(first);
+        assert!(
+            limiter
+                .try_acquire(&config.schedules[2], &config.watch)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_concurrency_group_allows_one_run_per_group() {
+        let config = config(
+            r#"
+[[schedules]]
+name = "backup"
+cron = "* * * * *"
+prompt = "backup"
+concurrency_group = "db"
+
+[[schedules]]
+name = "vacuum"
+cron = "* * * * *"
+prompt = "vacuum"
+concurrency_group = "db"
+
+[[schedules]]
+name = "health"
+cron = "* * * * *"
+prompt = "health"
+"#,
+        );
+        let limiter = RunLimiter::new();
+
+        let backup = limiter.try_acquire(&config.schedules[0], &config.watch);
+        assert!(backup.is_ok());
+        assert_eq!(
+            limiter
+                .try_acquire(&config.schedules[1], &config.watch)
+                .err(),
+            Some(ConcurrencyLimit::Group("db".to_string()))
+        );
+        assert!(
+            limiter
+                .try_acquire(&config.schedules[2], &config.watch)
+                .is_ok()
+        );
+
+        drop(backup);
+        assert!(
+            limiter
+                .try_acquire(&config.schedules[1], &config.watch)
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_skip_policy_returns_immediately() {
+        let config = config(
+            r#"
+[watch]
+max_parallel_runs = 1
+
+[[schedules]]
+name = "a"
+cron = "* * * * *"
+prompt = "a"
+
+[[schedules]]
+name = "b"
+cron = "* * * * *"
+prompt = "b"
+"#,
+        );
+        let limiter = RunLimiter::new();
+
+        let _held = limiter
+            .acquire(&config.schedules[0], &config.watch)
+            .await
+            .expect("first run should acquire");
+        let result = limiter.acquire(&config.schedules[1], &config.watch).await;
+
+        assert_eq!(result.err(), Some(ConcurrencyLimit::MaxParallelRuns(1)));
+    }
+
+    #[tokio::test]
+    async fn test_queue_policy_waits_for_released_slot() {
+        let config = config(
+            r#"
+[watch]
+max_parallel_runs = 1
+on_concurrency_limit = "queue"
+
+[[schedules]]
+name = "a"
+cron = "* * * * *"
+prompt = "a"
+
+[[schedules]]
+name = "b"
+cron = "* * * * *"
+prompt = "b"
+"#,
+        );
+        let limiter = RunLimiter::new();
+
+        let held = limiter
+            .acquire(&config.schedules[0], &config.watch)
+            .await
+            .expect("first run should acquire");
+
+        let waiter = {
+            let limiter = limiter.clone();
+            let schedule = config.schedules[1].clone();
+            let settings = config.watch.clone();
+            tokio::spawn(async move { limiter.acquire(&schedule, &settings).await.is_ok() })
+        };
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished(), "queued run should wait for a slot");
+
+        // A second queued run of the same schedule is rejected.
+        assert_eq!(
+            limiter
+                .acquire(&config.schedules[1], &config.watch)
+                .await
+                .err(),
+            Some(ConcurrencyLimit::AlreadyQueued)
+        );
+
+        drop(held);
+        let acquired = tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("queued run should wake up")
+            .expect("waiter task should not panic");
+        assert!(acquired);
+    }
+}
@@ -0,0 +1,381 @@
+//! Storage for autopilot state and schedule run history.
+//!
+//! [`ScheduleStore`] is implemented by a local SQLite database (the default)
+//! and by Postgres. With Postgres, autopilot instances on several hosts share
+//! run history, and advisory locks keep a schedule from running on two hosts
+//! at once. [`ScheduleDb`] opens whichever backend `[watch]` selects.
+
+mod postgres;
+mod postgres_tls;
+mod sqlite;
+
+pub use postgres::describe_database_url;
+
+use postgres::PostgresScheduleStore;
+use sqlite::SqliteScheduleStore;
+
+use super::budget::BudgetUsage;
+use super::config::{ScheduleSettings, expand_tilde};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use stakpak_shared::error::StakpakError;
+use std::any::Any;
+
+/// Run status for schedule executions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    /// Currently executing
+    Running,
+    /// Completed successfully
+    Completed,
+    /// Failed with error
+    Failed,
+    /// Skipped (check script returned non-zero)
+    Skipped,
+    /// Timed out
+    TimedOut,
+    /// Paused (agent needs approval or input to continue)
+    Paused,
+}
+
+impl std::fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunStatus::Running => write!(f, "running"),
+            RunStatus::Completed => write!(f, "completed"),
+            RunStatus::Failed => write!(f, "failed"),
+            RunStatus::Skipped => write!(f, "skipped"),
+            RunStatus::TimedOut => write!(f, "timed_out"),
+            RunStatus::Paused => write!(f, "paused"),
+        }
+    }
+}
+
+impl std::str::FromStr for RunStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "running" => Ok(RunStatus::Running),
+            "completed" => Ok(RunStatus::Completed),
+            "failed" => Ok(RunStatus::Failed),
+            "skipped" => Ok(RunStatus::Skipped),
+            "timed_out" => Ok(RunStatus::TimedOut),
+            "paused" => Ok(RunStatus::Paused),
+            _ => Err(format!("Unknown run status: {}", s)),
+        }
+    }
+}
+
+/// Storage footprint of one run record, used by retention policies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunFootprint {
+    pub id: i64,
+    pub status: RunStatus,
+    pub created_at: DateTime<Utc>,
+    /// Size of the stored check and agent output, in bytes.
+    pub bytes: u64,
+    pub artifacts_dir: Option<String>,
+}
+
+/// A schedule run record.
+#[derive(Debug, Clone)]
+pub struct ScheduleRun {
+    pub id: i64,
+    pub schedule_name: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub check_exit_code: Option<i32>,
+    pub check_stdout: Option<String>,
+    pub check_stderr: Option<String>,
+    pub check_timed_out: bool,
+    pub agent_woken: bool,
+    pub interactive_delegated: bool,
+    pub agent_session_id: Option<String>,
+    pub agent_last_checkpoint_id: Option<String>,
+    pub agent_stdout: Option<String>,
+    pub agent_stderr: Option<String>,
+    /// Directory holding the run's full output and agent-written files.
+    pub artifacts_dir: Option<String>,
+    /// Tokens the agent used, once it has reported any.
+    pub total_tokens: Option<u64>,
+    /// Estimated cost of `total_tokens`, when the model's pricing is known.
+    pub estimated_cost: Option<f64>,
+    pub status: RunStatus,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ScheduleRun {
+    /// Why a failed run failed, in the error taxonomy. Older records without
+    /// a code are classified from their text.
+    pub fn error(&self) -> Option<StakpakError> {
+        if self.status != RunStatus::Failed {
+            return None;
+        }
+        self.error_message.as_deref().map(StakpakError::from_record)
+    }
+
+    /// The stored error or skip reason, without its error code.
+    pub fn error_text(&self) -> Option<String> {
+        self.error()
+            .map(|error| error.message)
+            .or_else(|| self.error_message.clone())
+    }
+}
+
+/// Watch state record.
+#[derive(Debug, Clone)]
+pub struct SchedulerState {
+    pub started_at: DateTime<Utc>,
+    pub pid: i64,
+    pub last_heartbeat: DateTime<Utc>,
+}
+
+/// Filter options for listing runs.
+#[derive(Debug, Default)]
+pub struct ListRunsFilter {
+    pub schedule_name: Option<String>,
+    pub status: Option<RunStatus>,
+    /// Only runs started at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only runs with an id greater than this (used to tail new runs).
+    pub after_id: Option<i64>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+/// Well-known sentinel name used to request in-process schedule config reload.
+pub const RELOAD_SENTINEL: &str = "__config_reload__";
+
+/// Informational note stored on runs delegated to gateway interactive sessions.
+pub const INTERACTIVE_DELEGATED_NOTE: &str = "Delegated to interactive gateway session";
+
+/// A pending schedule request (for manual schedule fires).
+#[derive(Debug, Clone)]
+pub struct PendingSchedule {
+    pub id: i64,
+    pub schedule_name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A request to resume a paused run (from `stakpak autopilot resume`).
+#[derive(Debug, Clone)]
+pub struct PendingResume {
+    pub id: i64,
+    pub run_id: i64,
+    /// Approve the pending tool calls, or deny them.
+    pub approve: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database errors.
+#[derive(Debug, thiserror::Error)]
+pub enum DbError {
+    #[error("Database connection error: {0}")]
+    Connection(String),
+
+    #[error("Query error: {0}")]
+    Query(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+}
+
+/// Operations on schedule runs, scheduler state and the signal queues.
+#[async_trait]
+pub trait ScheduleStore: Send + Sync {
+    /// Where the data lives, for logs and status output. Never includes credentials.
+    fn location(&self) -> String;
+
+    /// Check if a schedule already has a run in "running" status.
+    async fn has_running_run(&self, schedule_name: &str) -> Result<bool, DbError>;
+
+    /// Insert a new schedule run, returning the run ID.
+    async fn insert_run(&self, schedule_name: &str) -> Result<i64, DbError>;
+
+    /// Update run with check script results.
+    async fn update_run_check_result(
+        &self,
+        run_id: i64,
+        exit_code: i32,
+        stdout: &str,
+        stderr: &str,
+        timed_out: bool,
+    ) -> Result<(), DbError>;
+
+    /// Update run when agent is started.
+    async fn update_run_agent_started(&self, run_id: i64, session_id: &str) -> Result<(), DbError>;
+
+    /// Mark run as delegated to gateway interactive session while keeping status=running.
+    async fn update_run_interactive_started(
+        &self,
+        run_id: i64,
+        session_id: &str,
+        note: &str,
+    ) -> Result<(), DbError>;
+
+    /// Update run with latest checkpoint ID.
+    async fn update_run_checkpoint(&self, run_id: i64, checkpoint_id: &str) -> Result<(), DbError>;
+
+    /// Record where a run's artifacts are stored.
+    async fn update_run_artifacts_dir(
+        &self,
+        run_id: i64,
+        artifacts_dir: &str,
+    ) -> Result<(), DbError>;
+
+    /// Add tokens and estimated cost to a run's usage. Resumed runs add the
+    /// usage of each resumption to what was recorded before they paused.
+    async fn add_run_usage(
+        &self,
+        run_id: i64,
+        total_tokens: u64,
+        estimated_cost: Option<f64>,
+    ) -> Result<(), DbError>;
+
+    /// Total usage of runs started at or after `since`, for one schedule or all of them.
+    async fn usage_since(
+        &self,
+        schedule_name: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> Result<BudgetUsage, DbError>;
+
+    /// Whether a schedule has a skipped run since `since` whose error message
+    /// starts with `reason_prefix`.
+    async fn has_skipped_run_since(
+        &self,
+        schedule_name: &str,
+        reason_prefix: &str,
+        since: DateTime<Utc>,
+    ) -> Result<bool, DbError>;
+
+    /// Update run when finished.
+    async fn update_run_finished(
+        &self,
+        run_id: i64,
+        status: RunStatus,
+        error_message: Option<&str>,
+        agent_stdout: Option<&str>,
+        agent_stderr: Option<&str>,
+    ) -> Result<(), DbError>;
+
+    /// Move a paused run back to running while it is resumed. Returns `false`
+    /// when the run is not paused (e.g. it was already resumed).
+    async fn update_run_resumed(&self, run_id: i64) -> Result<bool, DbError>;
+
+    /// Get a run by ID.
+    async fn get_run(&self, run_id: i64) -> Result<ScheduleRun, DbError>;
+
+    /// List runs with optional filters.
+    async fn list_runs(&self, filter: &ListRunsFilter) -> Result<Vec<ScheduleRun>, DbError>;
+
+    /// Delete runs older than the specified number of days.
+    async fn prune_runs(&self, older_than_days: u32) -> Result<u64, DbError>;
+
+    /// Every run with its creation time and the size of its stored output.
+    async fn run_footprints(&self) -> Result<Vec<RunFootprint>, DbError>;
+
+    /// Delete the given runs. Returns how many were removed.
+    async fn delete_runs(&self, ids: &[i64]) -> Result<u64, DbError>;
+
+    /// Mark runs left in "running" by an autopilot that is no longer active as failed.
+    async fn clean_stale_runs(&self) -> Result<u64, DbError>;
+
+    /// Record this autopilot as started (upsert).
+    async fn set_autopilot_state(&self, pid: i64) -> Result<(), DbError>;
+
+    /// Update this autopilot's heartbeat.
+    async fn update_heartbeat(&self) -> Result<(), DbError>;
+
+    /// Get the state of the autopilot on this machine.
+    async fn get_autopilot_state(&self) -> Result<Option<SchedulerState>, DbError>;
+
+    /// Clear the state of the autopilot on this machine.
+    async fn clear_autopilot_state(&self) -> Result<(), DbError>;
+
+    /// Insert a pending schedule request (for manual schedule fires).
+    async fn insert_pending_schedule(&self, schedule_name: &str) -> Result<i64, DbError>;
+
+    /// Request a scheduler config reload using the pending_triggers signal queue.
+    async fn request_config_reload(&self) -> Result<(), DbError>;
+
+    /// Get and delete all pending schedules (atomic pop).
+    async fn pop_pending_schedules(&self) -> Result<Vec<PendingSchedule>, DbError>;
+
+    /// Queue a decision for a paused run, picked up by the running autopilot.
+    async fn insert_pending_resume(&self, run_id: i64, approve: bool) -> Result<(), DbError>;
+
+    /// Get and delete all pending resume requests, oldest first.
+    async fn pop_pending_resumes(&self) -> Result<Vec<PendingResume>, DbError>;
+
+    /// Claim a schedule for one run. Returns `None` when another autopilot
+    /// sharing this store holds the claim.
+    async fn try_lock_schedule(&self, schedule_name: &str)
+    -> Result<Option<ScheduleLock>, DbError>;
+}
+
+/// Exclusive claim on a schedule across every autopilot sharing a store,
+/// returned by [`ScheduleStore::try_lock_schedule`]. Dropping it releases the claim.
+#[must_use = "the schedule is released as soon as the lock is dropped"]
+pub struct ScheduleLock {
+    _held: Option<Box<dyn Any + Send + Sync>>,
+}
+
+impl ScheduleLock {
+    /// A claim that needs no coordination (the store serves a single autopilot).
+    fn local() -> Self {
+        Self { _held: None }
+    }
+
+    /// A claim that lasts as long as `held` is alive.
+    fn holding(held: impl Any + Send + Sync) -> Self {
+        Self {
+            _held: Some(Box::new(held)),
+        }
+    }
+}
+
+/// Watch database storage, backed by SQLite or Postgres.
+pub struct ScheduleDb {
+    store: Box<dyn ScheduleStore>,
+}
+
+impl ScheduleDb {
+    /// Open a local SQLite database, initializing schema if needed.
+    pub async fn new(db_path: &str) -> Result<Self, DbError> {
+        Ok(Self {
+            store: Box::new(SqliteScheduleStore::new(db_path).await?),
+        })
+    }
+
+    /// Connect to a Postgres database shared with other autopilots,
+    /// initializing schema if needed.
+    pub async fn connect(url: &str) -> Result<Self, DbError> {
+        Ok(Self {
+            store: Box::new(PostgresScheduleStore::connect(url).await?),
+        })
+    }
+
+    /// Open the backend selected by `[watch]`: Postgres when `database_url`
+    /// is set, otherwise SQLite at `db_path`.
+    pub async fn open(settings: &ScheduleSettings) -> Result<Self, DbError> {
+        if let Some(url) = settings.database_url.as_deref() {
+            return Self::connect(url).await;
+        }
+
+        let db_path = expand_tilde(&settings.db_path);
+        let db_path = db_path
+            .to_str()
+            .ok_or_else(|| DbError::Connection("Invalid database path".to_string()))?;
+        Self::new(db_path).await
+    }
+}
+
+impl std::ops::Deref for ScheduleDb {
+    type Target = dyn ScheduleStore;
+
+    fn deref(&self) -> &Self::Target {
+        self.store.as_ref()
+    }
+}
@@ -0,0 +1,929 @@
+//! Postgres backend for autopilot state and schedule run history.
+//!
+//! Several autopilot instances can share one database. Each records its host
+//! name on the runs it starts and keeps its own `autopilot_state` row, so a
+//! restarting autopilot only fails runs left behind by itself or by an
+//! instance whose heartbeat went stale. Manual fires and reload signals reach
+//! the autopilot on the host that queued them, resume decisions reach the
+//! host that owns the run, and retention only prunes a host's own runs
+//! (their artifacts live on that host).
+//!
+//! A schedule is claimed for a run with a session-level advisory lock held on
+//! a dedicated connection. Postgres releases it when the run ends or when the
+//! autopilot holding it dies and its connection drops.
+
+use super::postgres_tls::{MakeRustlsConnect, parse_database_url, strip_tls_options};
+use super::{
+    DbError, ListRunsFilter, PendingResume, PendingSchedule, RELOAD_SENTINEL, RunFootprint,
+    RunStatus, ScheduleLock, ScheduleRun, ScheduleStore, SchedulerState,
+};
+use crate::commands::watch::budget::BudgetUsage;
+use crate::commands::watch::commands::HEARTBEAT_STALE_SECONDS;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_postgres::config::Host;
+use tokio_postgres::{Client, Row};
+use tracing::warn;
+
+/// Namespace for schedule advisory locks, so they cannot collide with locks
+/// other applications take on the same database.
+const SCHEDULE_LOCK_NAMESPACE: &str = "stakpak-autopilot-schedule";
+
+/// Serializes schema creation when several autopilots start at once.
+const SCHEMA_LOCK_NAMESPACE: &str = "stakpak-autopilot-schema";
+
+const RUN_COLUMNS: &str =
+    "id, trigger_name, started_at, finished_at, check_exit_code, check_stdout,
+    check_stderr, check_timed_out, agent_woken, interactive_delegated, agent_session_id,
+    agent_last_checkpoint_id, agent_stdout, agent_stderr, status, error_message, created_at,
+    artifacts_dir, total_tokens, estimated_cost";
+
+/// Matches runs (aliased `r`) whose owning autopilot is still heartbeating.
+/// `{stale}` is replaced with the placeholder for the staleness threshold.
+const OWNER_HEARTBEATING: &str = "EXISTS (
+    SELECT 1 FROM autopilot_state s
+    WHERE s.host = r.host AND s.last_heartbeat > now() - make_interval(secs => {stale})
+)";
+
+/// Postgres-backed schedule store, shared by autopilots on several hosts.
+pub struct PostgresScheduleStore {
+    config: tokio_postgres::Config,
+    tls: MakeRustlsConnect,
+    client: Mutex<Arc<Client>>,
+    /// Identifies this autopilot's runs, state and signals.
+    host: String,
+}
+
+impl PostgresScheduleStore {
+    /// Connect to `url` and initialize the schema if needed.
+    pub async fn connect(url: &str) -> Result<Self, DbError> {
+        let (config, tls) = parse_database_url(url)?;
+        Self::connect_as(config, tls, host_name()).await
+    }
+
+    /// Connect as the autopilot on `host`.
+    async fn connect_as(
+        config: tokio_postgres::Config,
+        tls: MakeRustlsConnect,
+        host: String,
+    ) -> Result<Self, DbError> {
+        let client = open_client(&config, &tls).await?;
+        let store = Self {
+            config,
+            tls,
+            client: Mutex::new(Arc::new(client)),
+            host,
+        };
+        store.init_schema().await?;
+        Ok(store)
+    }
+
+    /// The shared connection, reopened if the previous one was closed.
+    async fn client(&self) -> Result<Arc<Client>, DbError> {
+        let mut client = self.client.lock().await;
+        if client.is_closed() {
+            *client = Arc::new(open_client(&self.config, &self.tls).await?);
+        }
+        Ok(Arc::clone(&client))
+    }
+
+    /// Initialize database schema.
+    async fn init_schema(&self) -> Result<(), DbError> {
+        let client = self.client().await?;
+        client
+            .batch_execute(&format!(
+                "BEGIN;
+                SELECT pg_advisory_xact_lock(hashtext('{SCHEMA_LOCK_NAMESPACE}'));
+                CREATE TABLE IF NOT EXISTS trigger_runs (
+                    id BIGSERIAL PRIMARY KEY,
+                    trigger_name TEXT NOT NULL,
+                    host TEXT NOT NULL,
+                    started_at TIMESTAMPTZ NOT NULL,
+                    finished_at TIMESTAMPTZ,
+                    check_exit_code INTEGER,
+                    check_stdout TEXT,
+                    check_stderr TEXT,
+                    check_timed_out BOOLEAN NOT NULL DEFAULT FALSE,
+                    agent_woken BOOLEAN NOT NULL DEFAULT FALSE,
+                    interactive_delegated BOOLEAN NOT NULL DEFAULT FALSE,
+                    agent_session_id TEXT,
+                    agent_last_checkpoint_id TEXT,
+                    agent_stdout TEXT,
+                    agent_stderr TEXT,
+                    artifacts_dir TEXT,
+                    total_tokens BIGINT,
+                    estimated_cost DOUBLE PRECISION,
+                    status TEXT NOT NULL,
+                    error_message TEXT,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+                );
+                CREATE INDEX IF NOT EXISTS idx_trigger_runs_trigger_name ON trigger_runs(trigger_name);
+                CREATE INDEX IF NOT EXISTS idx_trigger_runs_status ON trigger_runs(status);
+                CREATE INDEX IF NOT EXISTS idx_trigger_runs_created_at ON trigger_runs(created_at);
+                CREATE TABLE IF NOT EXISTS autopilot_state (
+                    host TEXT PRIMARY KEY,
+                    started_at TIMESTAMPTZ NOT NULL,
+                    pid BIGINT NOT NULL,
+                    last_heartbeat TIMESTAMPTZ NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS pending_triggers (
+                    id BIGSERIAL PRIMARY KEY,
+                    trigger_name TEXT NOT NULL,
+                    host TEXT NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+                );
+                CREATE TABLE IF NOT EXISTS pending_resumes (
+                    id BIGSERIAL PRIMARY KEY,
+                    run_id BIGINT NOT NULL,
+                    approve BOOLEAN NOT NULL,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+                );
+                COMMIT;"
+            ))
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ScheduleStore for PostgresScheduleStore {
+    fn location(&self) -> String {
+        describe_config(&self.config)
+    }
+
+    /// Runs owned by an autopilot whose heartbeat went stale do not count,
+    /// so a host that died mid-run does not block the schedule everywhere.
+    async fn has_running_run(&self, schedule_name: &str) -> Result<bool, DbError> {
+        let client = self.client().await?;
+        let status = RunStatus::Running.to_string();
+        let sql = format!(
+            "SELECT EXISTS (
+                SELECT 1 FROM trigger_runs r
+                WHERE r.trigger_name = $1 AND r.status = $2 AND (r.host = $3 OR {})
+            )",
+            owner_heartbeating("$4")
+        );
+        let row = client
+            .query_one(
+                &sql,
+                &[&schedule_name, &status, &self.host, &stale_seconds()],
+            )
+            .await
+            .map_err(query_error)?;
+        row.try_get(0).map_err(query_error)
+    }
+
+    async fn insert_run(&self, schedule_name: &str) -> Result<i64, DbError> {
+        let client = self.client().await?;
+        let status = RunStatus::Running.to_string();
+        let row = client
+            .query_one(
+                "INSERT INTO trigger_runs (trigger_name, host, started_at, status, created_at)
+                 VALUES ($1, $2, now(), $3, now())
+                 RETURNING id",
+                &[&schedule_name, &self.host, &status],
+            )
+            .await
+            .map_err(query_error)?;
+        row.try_get(0).map_err(query_error)
+    }
+
+    async fn update_run_check_result(
+        &self,
+        run_id: i64,
+        exit_code: i32,
+        stdout: &str,
+        stderr: &str,
+        timed_out: bool,
+    ) -> Result<(), DbError> {
+        let client = self.client().await?;
+        client
+            .execute(
+                "UPDATE trigger_runs SET check_exit_code = $1, check_stdout = $2, check_stderr = $3, check_timed_out = $4 WHERE id = $5",
+                &[&exit_code, &stdout, &stderr, &timed_out, &run_id],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn update_run_agent_started(&self, run_id: i64, session_id: &str) -> Result<(), DbError> {
+        let client = self.client().await?;
+        client
+            .execute(
+                "UPDATE trigger_runs SET agent_woken = TRUE, interactive_delegated = FALSE, agent_session_id = $1 WHERE id = $2",
+                &[&session_id, &run_id],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn update_run_interactive_started(
+        &self,
+        run_id: i64,
+        session_id: &str,
+        note: &str,
+    ) -> Result<(), DbError> {
+        let client = self.client().await?;
+        client
+            .execute(
+                "UPDATE trigger_runs SET agent_woken = TRUE, interactive_delegated = TRUE, agent_session_id = $1, error_message = $2 WHERE id = $3",
+                &[&session_id, &note, &run_id],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn update_run_checkpoint(&self, run_id: i64, checkpoint_id: &str) -> Result<(), DbError> {
+        let client = self.client().await?;
+        client
+            .execute(
+                "UPDATE trigger_runs SET agent_last_checkpoint_id = $1 WHERE id = $2",
+                &[&checkpoint_id, &run_id],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn update_run_artifacts_dir(
+        &self,
+        run_id: i64,
+        artifacts_dir: &str,
+    ) -> Result<(), DbError> {
+        let client = self.client().await?;
+        client
+            .execute(
+                "UPDATE trigger_runs SET artifacts_dir = $1 WHERE id = $2",
+                &[&artifacts_dir, &run_id],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn add_run_usage(
+        &self,
+        run_id: i64,
+        total_tokens: u64,
+        estimated_cost: Option<f64>,
+    ) -> Result<(), DbError> {
+        let client = self.client().await?;
+        let total_tokens = i64::try_from(total_tokens).unwrap_or(i64::MAX);
+        client
+            .execute(
+                "UPDATE trigger_runs SET
+                    total_tokens = COALESCE(total_tokens, 0) + $1,
+                    estimated_cost = CASE
+                        WHEN $2::DOUBLE PRECISION IS NULL THEN estimated_cost
+                        ELSE COALESCE(estimated_cost, 0) + $2
+                    END
+                 WHERE id = $3",
+                &[&total_tokens, &estimated_cost, &run_id],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn usage_since(
+        &self,
+        schedule_name: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> Result<BudgetUsage, DbError> {
+        let client = self.client().await?;
+        let row = client
+            .query_one(
+                "SELECT COALESCE(SUM(total_tokens), 0)::BIGINT,
+                        COALESCE(SUM(estimated_cost), 0)::DOUBLE PRECISION
+                 FROM trigger_runs
+                 WHERE started_at >= $1 AND ($2::TEXT IS NULL OR trigger_name = $2)",
+                &[&since, &schedule_name],
+            )
+            .await
+            .map_err(query_error)?;
+
+        let tokens: i64 = row.try_get(0).map_err(query_error)?;
+        Ok(BudgetUsage {
+            tokens: u64::try_from(tokens).unwrap_or_default(),
+            cost: row.try_get(1).map_err(query_error)?,
+        })
+    }
+
+    async fn has_skipped_run_since(
+        &self,
+        schedule_name: &str,
+        reason_prefix: &str,
+        since: DateTime<Utc>,
+    ) -> Result<bool, DbError> {
+        let client = self.client().await?;
+        let status = RunStatus::Skipped.to_string();
+        let row = client
+            .query_one(
+                "SELECT EXISTS (
+                    SELECT 1 FROM trigger_runs
+                    WHERE trigger_name = $1 AND status = $2 AND started_at >= $3
+                      AND left(error_message, char_length($4::TEXT)) = $4
+                )",
+                &[&schedule_name, &status, &since, &reason_prefix],
+            )
+            .await
+            .map_err(query_error)?;
+        row.try_get(0).map_err(query_error)
+    }
+
+    async fn update_run_finished(
+        &self,
+        run_id: i64,
+        status: RunStatus,
+        error_message: Option<&str>,
+        agent_stdout: Option<&str>,
+        agent_stderr: Option<&str>,
+    ) -> Result<(), DbError> {
+        let client = self.client().await?;
+        let status = status.to_string();
+        client
+            .execute(
+                "UPDATE trigger_runs SET finished_at = now(), status = $1, error_message = $2, agent_stdout = $3, agent_stderr = $4 WHERE id = $5",
+                &[&status, &error_message, &agent_stdout, &agent_stderr, &run_id],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn update_run_resumed(&self, run_id: i64) -> Result<bool, DbError> {
+        let client = self.client().await?;
+        let running = RunStatus::Running.to_string();
+        let paused = RunStatus::Paused.to_string();
+        let updated = client
+            .execute(
+                "UPDATE trigger_runs SET status = $1, finished_at = NULL, error_message = NULL
+                 WHERE id = $2 AND status = $3",
+                &[&running, &run_id, &paused],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(updated > 0)
+    }
+
+    async fn get_run(&self, run_id: i64) -> Result<ScheduleRun, DbError> {
+        let client = self.client().await?;
+        let row = client
+            .query_opt(
+                &format!("SELECT {} FROM trigger_runs WHERE id = $1", RUN_COLUMNS),
+                &[&run_id],
+            )
+            .await
+            .map_err(query_error)?
+            .ok_or_else(|| DbError::NotFound(format!("Run {} not found", run_id)))?;
+        parse_schedule_run(&row)
+    }
+
+    async fn list_runs(&self, filter: &ListRunsFilter) -> Result<Vec<ScheduleRun>, DbError> {
+        let client = self.client().await?;
+        let status = filter.status.map(|status| status.to_string());
+        let limit = filter.limit.map(i64::from);
+        let offset = filter.offset.map(i64::from);
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {} FROM trigger_runs
+                     WHERE ($1::TEXT IS NULL OR trigger_name = $1)
+                       AND ($2::TEXT IS NULL OR status = $2)
+                       AND ($3::TIMESTAMPTZ IS NULL OR started_at >= $3)
+                       AND ($4::BIGINT IS NULL OR id > $4)
+                     ORDER BY created_at DESC, id DESC
+                     LIMIT $5 OFFSET $6",
+                    RUN_COLUMNS
+                ),
+                &[
+                    &filter.schedule_name,
+                    &status,
+                    &filter.since,
+                    &filter.after_id,
+                    &limit,
+                    &offset,
+                ],
+            )
+            .await
+            .map_err(query_error)?;
+        rows.iter().map(parse_schedule_run).collect()
+    }
+
+    async fn prune_runs(&self, older_than_days: u32) -> Result<u64, DbError> {
+        let client = self.client().await?;
+        let days = i32::try_from(older_than_days).unwrap_or(i32::MAX);
+        client
+            .execute(
+                "DELETE FROM trigger_runs WHERE created_at < now() - make_interval(days => $1)",
+                &[&days],
+            )
+            .await
+            .map_err(query_error)
+    }
+
+    async fn run_footprints(&self) -> Result<Vec<RunFootprint>, DbError> {
+        let client = self.client().await?;
+        let rows = client
+            .query(
+                "SELECT id, status, created_at,
+                    (COALESCE(octet_length(check_stdout), 0) + COALESCE(octet_length(check_stderr), 0)
+                        + COALESCE(octet_length(agent_stdout), 0) + COALESCE(octet_length(agent_stderr), 0)
+                        + COALESCE(octet_length(error_message), 0))::BIGINT,
+                    artifacts_dir
+                 FROM trigger_runs WHERE host = $1",
+                &[&self.host],
+            )
+            .await
+            .map_err(query_error)?;
+
+        rows.iter()
+            .map(|row| {
+                let status: String = row.try_get(1).map_err(query_error)?;
+                let bytes: i64 = row.try_get(3).map_err(query_error)?;
+                Ok(RunFootprint {
+                    id: row.try_get(0).map_err(query_error)?,
+                    status: status.parse().map_err(DbError::Query)?,
+                    created_at: row.try_get(2).map_err(query_error)?,
+                    bytes: u64::try_from(bytes).unwrap_or_default(),
+                    artifacts_dir: row.try_get(4).map_err(query_error)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn delete_runs(&self, ids: &[i64]) -> Result<u64, DbError> {
+        let client = self.client().await?;
+        client
+            .execute("DELETE FROM trigger_runs WHERE id = ANY($1)", &[&ids])
+            .await
+            .map_err(query_error)
+    }
+
+    /// Fails this host's running runs and those of autopilots whose heartbeat went stale.
+    async fn clean_stale_runs(&self) -> Result<u64, DbError> {
+        let client = self.client().await?;
+        let sql = format!(
+            "UPDATE trigger_runs r SET status = 'failed', finished_at = now(),
+                error_message = 'Marked as failed: autopilot was stopped while run was in progress'
+             WHERE r.status = 'running' AND (r.host = $1 OR NOT {})",
+            owner_heartbeating("$2")
+        );
+        client
+            .execute(&sql, &[&self.host, &stale_seconds()])
+            .await
+            .map_err(query_error)
+    }
+
+    async fn set_autopilot_state(&self, pid: i64) -> Result<(), DbError> {
+        let client = self.client().await?;
+        client
+            .execute(
+                "INSERT INTO autopilot_state (host, started_at, pid, last_heartbeat)
+                 VALUES ($1, now(), $2, now())
+                 ON CONFLICT (host) DO UPDATE SET
+                    started_at = EXCLUDED.started_at,
+                    pid = EXCLUDED.pid,
+                    last_heartbeat = EXCLUDED.last_heartbeat",
+                &[&self.host, &pid],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn update_heartbeat(&self) -> Result<(), DbError> {
+        let client = self.client().await?;
+        client
+            .execute(
+                "UPDATE autopilot_state SET last_heartbeat = now() WHERE host = $1",
+                &[&self.host],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn get_autopilot_state(&self) -> Result<Option<SchedulerState>, DbError> {
+        let client = self.client().await?;
+        let row = client
+            .query_opt(
+                "SELECT started_at, pid, last_heartbeat FROM autopilot_state WHERE host = $1",
+                &[&self.host],
+            )
+            .await
+            .map_err(query_error)?;
+
+        row.map(|row| {
+            Ok(SchedulerState {
+                started_at: row.try_get(0).map_err(query_error)?,
+                pid: row.try_get(1).map_err(query_error)?,
+                last_heartbeat: row.try_get(2).map_err(query_error)?,
+            })
+        })
+        .transpose()
+    }
+
+    async fn clear_autopilot_state(&self) -> Result<(), DbError> {
+        let client = self.client().await?;
+        client
+            .execute("DELETE FROM autopilot_state WHERE host = $1", &[&self.host])
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn insert_pending_schedule(&self, schedule_name: &str) -> Result<i64, DbError> {
+        let client = self.client().await?;
+        let row = client
+            .query_one(
+                "INSERT INTO pending_triggers (trigger_name, host, created_at)
+                 VALUES ($1, $2, now())
+                 RETURNING id",
+                &[&schedule_name, &self.host],
+            )
+            .await
+            .map_err(query_error)?;
+        row.try_get(0).map_err(query_error)
+    }
+
+    async fn request_config_reload(&self) -> Result<(), DbError> {
+        let client = self.client().await?;
+        client
+            .execute(
+                "INSERT INTO pending_triggers (trigger_name, host, created_at)
+                 SELECT $1, $2, now()
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM pending_triggers WHERE trigger_name = $1 AND host = $2
+                 )",
+                &[&RELOAD_SENTINEL, &self.host],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    async fn pop_pending_schedules(&self) -> Result<Vec<PendingSchedule>, DbError> {
+        let client = self.client().await?;
+        let rows = client
+            .query(
+                "DELETE FROM pending_triggers WHERE host = $1
+                 RETURNING id, trigger_name, created_at",
+                &[&self.host],
+            )
+            .await
+            .map_err(query_error)?;
+
+        let mut schedules: Vec<PendingSchedule> = rows
+            .iter()
+            .filter_map(|row| {
+                Some(PendingSchedule {
+                    id: row.try_get(0).ok()?,
+                    schedule_name: row.try_get(1).ok()?,
+                    created_at: row.try_get(2).ok()?,
+                })
+            })
+            .collect();
+        schedules.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+        Ok(schedules)
+    }
+
+    async fn insert_pending_resume(&self, run_id: i64, approve: bool) -> Result<(), DbError> {
+        let client = self.client().await?;
+        client
+            .execute(
+                "INSERT INTO pending_resumes (run_id, approve, created_at) VALUES ($1, $2, now())",
+                &[&run_id, &approve],
+            )
+            .await
+            .map_err(query_error)?;
+        Ok(())
+    }
+
+    /// Only pops decisions for runs this host owns; the paused agent lives there.
+    async fn pop_pending_resumes(&self) -> Result<Vec<PendingResume>, DbError> {
+        let client = self.client().await?;
+        let rows = client
+            .query(
+                "DELETE FROM pending_resumes p
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM trigger_runs r WHERE r.id = p.run_id AND r.host <> $1
+                 )
+                 RETURNING id, run_id, approve, created_at",
+                &[&self.host],
+            )
+            .await
+            .map_err(query_error)?;
+
+        let mut resumes: Vec<PendingResume> = rows
+            .iter()
+            .filter_map(|row| {
+                Some(PendingResume {
+                    id: row.try_get(0).ok()?,
+                    run_id: row.try_get(1).ok()?,
+                    approve: row.try_get(2).ok()?,
+                    created_at: row.try_get(3).ok()?,
+                })
+            })
+            .collect();
+        resumes.sort_by_key(|resume| resume.id);
+
+        Ok(resumes)
+    }
+
+    async fn try_lock_schedule(
+        &self,
+        schedule_name: &str,
+    ) -> Result<Option<ScheduleLock>, DbError> {
+        // Session-level advisory locks belong to a connection, so each claim
+        // gets its own and the lock is released when that connection closes.
+        let client = open_client(&self.config, &self.tls).await?;
+        let row = client
+            .query_one(
+                "SELECT pg_try_advisory_lock(hashtext($1), hashtext($2))",
+                &[&SCHEDULE_LOCK_NAMESPACE, &schedule_name],
+            )
+            .await
+            .map_err(query_error)?;
+        let locked: bool = row.try_get(0).map_err(query_error)?;
+        Ok(locked.then(|| ScheduleLock::holding(client)))
+    }
+}
+
+/// Connect with TLS as the URL's `sslmode` asks.
+async fn open_client(
+    config: &tokio_postgres::Config,
+    tls: &MakeRustlsConnect,
+) -> Result<Client, DbError> {
+    let (client, connection) = config
+        .connect(tls.clone())
+        .await
+        .map_err(|e| DbError::Connection(format!("Failed to connect to database: {}", e)))?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            warn!(error = %e, "Autopilot database connection closed");
+        }
+    });
+    Ok(client)
+}
+
+/// `url` without credentials, for logs and status output.
+pub fn describe_database_url(url: &str) -> String {
+    match strip_tls_options(url).parse() {
+        Ok(config) => describe_config(&config),
+        Err(_) => "postgres://(invalid url)".to_string(),
+    }
+}
+
+fn describe_config(config: &tokio_postgres::Config) -> String {
+    let host = match config.get_hosts().first() {
+        Some(Host::Tcp(host)) => host.clone(),
+        _ => "localhost".to_string(),
+    };
+    let port = config.get_ports().first().copied().unwrap_or(5432);
+    format!(
+        "postgres://{}:{}/{}",
+        host,
+        port,
+        config.get_dbname().unwrap_or_default()
+    )
+}
+
+/// Name this autopilot is known by in the shared database.
+fn host_name() -> String {
+    gethostname::gethostname().to_string_lossy().into_owned()
+}
+
+fn owner_heartbeating(stale: &str) -> String {
+    OWNER_HEARTBEATING.replace("{stale}", stale)
+}
+
+fn stale_seconds() -> f64 {
+    HEARTBEAT_STALE_SECONDS as f64
+}
+
+fn query_error(e: tokio_postgres::Error) -> DbError {
+    DbError::Query(e.to_string())
+}
+
+/// Parse a row into a ScheduleRun.
+fn parse_schedule_run(row: &Row) -> Result<ScheduleRun, DbError> {
+    let status: String = row.try_get(14).map_err(query_error)?;
+    Ok(ScheduleRun {
+        id: row.try_get(0).map_err(query_error)?,
+        schedule_name: row.try_get(1).map_err(query_error)?,
+        started_at: row.try_get(2).map_err(query_error)?,
+        finished_at: row.try_get(3).map_err(query_error)?,
+        check_exit_code: row.try_get(4).map_err(query_error)?,
+        check_stdout: row.try_get(5).map_err(query_error)?,
+        check_stderr: row.try_get(6).map_err(query_error)?,
+        check_timed_out: row.try_get(7).map_err(query_error)?,
+        agent_woken: row.try_get(8).map_err(query_error)?,
+        interactive_delegated: row.try_get(9).map_err(query_error)?,
+        agent_session_id: row.try_get(10).map_err(query_error)?,
+        agent_last_checkpoint_id: row.try_get(11).map_err(query_error)?,
+        agent_stdout: row.try_get(12).map_err(query_error)?,
+        agent_stderr: row.try_get(13).map_err(query_error)?,
+        status: status.parse().map_err(DbError::Query)?,
+        error_message: row.try_get(15).map_err(query_error)?,
+        created_at: row.try_get(16).map_err(query_error)?,
+        artifacts_dir: row.try_get(17).map_err(query_error)?,
+        total_tokens: row
+            .try_get::<_, Option<i64>>(18)
+            .map_err(query_error)?
+            .and_then(|tokens| u64::try_from(tokens).ok()),
+        estimated_cost: row.try_get(19).map_err(query_error)?,
+    })
+}
+
+/// These tests need a server: set `STAKPAK_TEST_POSTGRES_URL`
+/// (e.g. `postgres://postgres@localhost/postgres`). They are skipped otherwise.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh schema on the test server, plus a connection per simulated host.
+    struct TestSchema {
+        admin: Client,
+        name: String,
+        config: tokio_postgres::Config,
+        tls: MakeRustlsConnect,
+    }
+
+    impl TestSchema {
+        async fn create() -> Option<Self> {
+            let url = std::env::var("STAKPAK_TEST_POSTGRES_URL").ok()?;
+            let (mut config, tls) = parse_database_url(&url).expect("valid test URL");
+            let admin = open_client(&config, &tls)
+                .await
+                .expect("connect to test server");
+            let name = format!("stakpak_test_{}", uuid::Uuid::new_v4().simple());
+            admin
+                .batch_execute(&format!("CREATE SCHEMA {}", name))
+                .await
+                .expect("create schema");
+            config.options(format!("-c search_path={}", name));
+            Some(Self {
+                admin,
+                name,
+                config,
+                tls,
+            })
+        }
+
+        async fn store(&self, host: &str) -> PostgresScheduleStore {
+            PostgresScheduleStore::connect_as(
+                self.config.clone(),
+                self.tls.clone(),
+                host.to_string(),
+            )
+            .await
+            .expect("connect store")
+        }
+
+        async fn drop_schema(self) {
+            let _ = self
+                .admin
+                .batch_execute(&format!("DROP SCHEMA {} CASCADE", self.name))
+                .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn hosts_share_history_but_keep_their_own_state_and_signals() {
+        let Some(schema) = TestSchema::create().await else {
+            return;
+        };
+        let a = schema.store("host-a").await;
+        let b = schema.store("host-b").await;
+
+        let run_id = a.insert_run("alpha").await.expect("insert run");
+        a.update_run_check_result(run_id, 0, "ok", "", false)
+            .await
+            .expect("update check");
+        a.add_run_usage(run_id, 120, Some(0.5))
+            .await
+            .expect("add usage");
+        a.update_run_finished(run_id, RunStatus::Completed, None, Some("done"), None)
+            .await
+            .expect("finish run");
+
+        let run = b.get_run(run_id).await.expect("get run from other host");
+        assert_eq!(run.schedule_name, "alpha");
+        assert_eq!(run.status, RunStatus::Completed);
+        assert_eq!(run.check_exit_code, Some(0));
+        assert_eq!(run.agent_stdout.as_deref(), Some("done"));
+        let usage = b
+            .usage_since(Some("alpha"), Utc::now() - chrono::Duration::minutes(1))
+            .await
+            .expect("usage");
+        assert_eq!(usage.tokens, 120);
+        let listed = b
+            .list_runs(&ListRunsFilter {
+                schedule_name: Some("alpha".to_string()),
+                ..Default::default()
+            })
+            .await
+            .expect("list runs");
+        assert_eq!(listed.len(), 1);
+
+        a.set_autopilot_state(10).await.expect("state a");
+        b.set_autopilot_state(20).await.expect("state b");
+        assert_eq!(
+            a.get_autopilot_state().await.expect("get").map(|s| s.pid),
+            Some(10)
+        );
+        assert_eq!(
+            b.get_autopilot_state().await.expect("get").map(|s| s.pid),
+            Some(20)
+        );
+
+        a.insert_pending_schedule("alpha")
+            .await
+            .expect("queue fire");
+        a.request_config_reload().await.expect("queue reload");
+        assert!(b.pop_pending_schedules().await.expect("pop b").is_empty());
+        let popped = a.pop_pending_schedules().await.expect("pop a");
+        assert_eq!(popped.len(), 2);
+
+        let paused = a.insert_run("beta").await.expect("insert paused run");
+        b.insert_pending_resume(paused, true)
+            .await
+            .expect("queue resume");
+        assert!(b.pop_pending_resumes().await.expect("pop b").is_empty());
+        let resumes = a.pop_pending_resumes().await.expect("pop a");
+        assert_eq!(resumes.len(), 1);
+        assert!(resumes[0].approve);
+
+        assert!(b.run_footprints().await.expect("footprints b").is_empty());
+        assert_eq!(a.run_footprints().await.expect("footprints a").len(), 2);
+
+        schema.drop_schema().await;
+    }
+
+    #[tokio::test]
+    async fn runs_of_stale_hosts_stop_blocking_and_are_cleaned() {
+        let Some(schema) = TestSchema::create().await else {
+            return;
+        };
+        let a = schema.store("host-a").await;
+        let b = schema.store("host-b").await;
+
+        a.set_autopilot_state(10).await.expect("state a");
+        a.insert_run("alpha").await.expect("insert run");
+
+        assert!(b.has_running_run("alpha").await.expect("running check"));
+        assert_eq!(b.clean_stale_runs().await.expect("clean"), 0);
+
+        schema
+            .admin
+            .batch_execute(&format!(
+                "UPDATE {}.autopilot_state SET last_heartbeat = now() - interval '1 hour'",
+                schema.name
+            ))
+            .await
+            .expect("age heartbeat");
+
+        assert!(!b.has_running_run("alpha").await.expect("running check"));
+        assert_eq!(b.clean_stale_runs().await.expect("clean"), 1);
+
+        schema.drop_schema().await;
+    }
+
+    #[tokio::test]
+    async fn schedule_lock_is_exclusive_across_hosts() {
+        let Some(schema) = TestSchema::create().await else {
+            return;
+        };
+        let a = schema.store("host-a").await;
+        let b = schema.store("host-b").await;
+
+        let lock = a
+            .try_lock_schedule("alpha")
+            .await
+            .expect("lock")
+            .expect("first claim succeeds");
+        assert!(b.try_lock_schedule("alpha").await.expect("lock").is_none());
+        assert!(b.try_lock_schedule("beta").await.expect("lock").is_some());
+
+        drop(lock);
+        let mut reclaimed = false;
+        for _ in 0..50 {
+            if b.try_lock_schedule("alpha").await.expect("lock").is_some() {
+                reclaimed = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(reclaimed, "dropping the lock should release the schedule");
+
+        schema.drop_schema().await;
+    }
+}
@@ -0,0 +1,430 @@
+//! TLS for the Postgres backend, following libpq's `sslmode`.
+//!
+//! tokio-postgres decides whether to negotiate TLS (`disable`, `prefer`,
+//! `require`) but only understands those three modes and has no connector of
+//! its own. This module accepts libpq's `verify-ca` and `verify-full` and its
+//! `sslrootcert` option too, and provides a rustls connector that checks the
+//! server certificate as strictly as the mode asks:
+//!
+//! - `prefer` (the default) and `require` encrypt without checking the
+//!   certificate, like libpq.
+//! - `verify-ca` checks that the certificate chains to a trusted root.
+//! - `verify-full` also checks that it names the host being connected to.
+//!
+//! Trusted roots come from `sslrootcert` when it is set, otherwise from the
+//! operating system's certificate store. `require` with `sslrootcert` behaves
+//! like `verify-ca`, as in libpq.
+
+use super::DbError;
+use rustls::client::WebPkiServerVerifier;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_postgres::config::SslMode;
+use tokio_postgres::tls::{ChannelBinding, MakeTlsConnect, TlsConnect, TlsStream};
+use tokio_rustls::TlsConnector;
+
+/// ALPN protocol Postgres servers expect from TLS clients.
+const POSTGRES_ALPN: &[u8] = b"postgresql";
+
+/// How much of the server certificate is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verification {
+    None,
+    Chain,
+    Full,
+}
+
+/// Parse a database URL, including the TLS options tokio-postgres does not
+/// understand, into a connection config and a matching TLS connector.
+pub(super) fn parse_database_url(
+    url: &str,
+) -> Result<(tokio_postgres::Config, MakeRustlsConnect), DbError> {
+    let (url, options) = split_tls_options(url);
+    let mut config: tokio_postgres::Config = url
+        .parse()
+        .map_err(|e| DbError::Connection(format!("Invalid database URL: {}", e)))?;
+    let verification = match options.verification {
+        Some(verification) => {
+            config.ssl_mode(SslMode::Require);
+            verification
+        }
+        None if options.root_cert.is_some() && config.get_ssl_mode() == SslMode::Require => {
+            Verification::Chain
+        }
+        None => Verification::None,
+    };
+    let tls = MakeRustlsConnect::new(verification, options.root_cert.as_deref())?;
+    Ok((config, tls))
+}
+
+/// `url` without the TLS options tokio-postgres would reject.
+pub(super) fn strip_tls_options(url: &str) -> String {
+    split_tls_options(url).0
+}
+
+#[derive(Debug, Default)]
+struct TlsOptions {
+    /// Set for `verify-ca` and `verify-full`; other modes stay in the URL.
+    verification: Option<Verification>,
+    root_cert: Option<PathBuf>,
+}
+
+/// Remove `sslmode=verify-*` and `sslrootcert` from the query of a
+/// `postgres://` URL. Key-value connection strings are returned unchanged.
+fn split_tls_options(url: &str) -> (String, TlsOptions) {
+    let mut options = TlsOptions::default();
+    let Some((base, query)) = url.split_once('?') else {
+        return (url.to_string(), options);
+    };
+
+    let mut kept = Vec::new();
+    for pair in query.split('&') {
+        match pair.split_once('=') {
+            Some(("sslmode", "verify-ca")) => options.verification = Some(Verification::Chain),
+            Some(("sslmode", "verify-full")) => options.verification = Some(Verification::Full),
+            Some(("sslrootcert", path)) if !path.is_empty() => {
+                options.root_cert = Some(PathBuf::from(path));
+            }
+            _ => kept.push(pair),
+        }
+    }
+
+    if kept.is_empty() {
+        (base.to_string(), options)
+    } else {
+        (format!("{}?{}", base, kept.join("&")), options)
+    }
+}
+
+/// Creates rustls connections for tokio-postgres.
+#[derive(Clone)]
+pub(super) struct MakeRustlsConnect {
+    config: Arc<ClientConfig>,
+}
+
+impl MakeRustlsConnect {
+    fn new(verification: Verification, root_cert: Option<&Path>) -> Result<Self, DbError> {
+        let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+        let verifier: Arc<dyn ServerCertVerifier> = match verification {
+            Verification::None => Arc::new(AcceptAnyCertificate {
+                provider: provider.clone(),
+            }),
+            Verification::Chain => {
+                Arc::new(IgnoreHostName(trusted_roots(root_cert, provider.clone())?))
+            }
+            Verification::Full => trusted_roots(root_cert, provider.clone())?,
+        };
+
+        let mut config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| DbError::Connection(format!("Failed to build TLS config: {}", e)))?
+            .dangerous()
+            .with_custom_certificate_verifier(verifier)
+            .with_no_client_auth();
+        config.alpn_protocols = vec![POSTGRES_ALPN.to_vec()];
+
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+/// Verifier against `root_cert`, or the platform's roots when it is unset.
+fn trusted_roots(
+    root_cert: Option<&Path>,
+    provider: Arc<CryptoProvider>,
+) -> Result<Arc<dyn ServerCertVerifier>, DbError> {
+    let Some(path) = root_cert else {
+        return Ok(Arc::new(
+            rustls_platform_verifier::Verifier::new().with_provider(provider),
+        ));
+    };
+
+    let read_error = |e: &dyn std::fmt::Display| {
+        DbError::Connection(format!(
+            "Failed to read sslrootcert {}: {}",
+            path.display(),
+            e
+        ))
+    };
+    let pem = std::fs::read(path).map_err(|e| read_error(&e))?;
+    let mut roots = RootCertStore::empty();
+    for cert in rustls_pemfile::certs(&mut pem.as_slice()) {
+        let cert = cert.map_err(|e| read_error(&e))?;
+        roots.add(cert).map_err(|e| read_error(&e))?;
+    }
+    if roots.is_empty() {
+        return Err(read_error(&"no certificates found"));
+    }
+
+    WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider)
+        .build()
+        .map(|verifier| verifier as Arc<dyn ServerCertVerifier>)
+        .map_err(|e| read_error(&e))
+}
+
+impl<S> MakeTlsConnect<S> for MakeRustlsConnect
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    type Stream = RustlsStream<S>;
+    type TlsConnect = RustlsConnect;
+    type Error = io::Error;
+
+    fn make_tls_connect(&mut self, domain: &str) -> io::Result<RustlsConnect> {
+        let server_name = ServerName::try_from(domain.to_string())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        Ok(RustlsConnect {
+            connector: TlsConnector::from(self.config.clone()),
+            server_name,
+        })
+    }
+}
+
+/// A single TLS handshake with the server named when it was made.
+pub(super) struct RustlsConnect {
+    connector: TlsConnector,
+    server_name: ServerName<'static>,
+}
+
+impl<S> TlsConnect<S> for RustlsConnect
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    type Stream = RustlsStream<S>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<RustlsStream<S>>> + Send>>;
+
+    fn connect(self, stream: S) -> Self::Future {
+        Box::pin(async move {
+            let stream = self.connector.connect(self.server_name, stream).await?;
+            Ok(RustlsStream(stream))
+        })
+    }
+}
+
+pub(super) struct RustlsStream<S>(tokio_rustls::client::TlsStream<S>);
+
+impl<S> TlsStream for RustlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    /// Without channel binding SCRAM authentication still works; the server
+    /// is just not asked to prove it holds the certificate's key a second time.
+    fn channel_binding(&self) -> ChannelBinding {
+        ChannelBinding::none()
+    }
+}
+
+impl<S> AsyncRead for RustlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl<S> AsyncWrite for RustlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+/// `prefer` and `require`: encrypt, but trust whatever certificate is offered.
+/// Handshake signatures are still checked so the session key is the server's.
+#[derive(Debug)]
+struct AcceptAnyCertificate {
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+/// `verify-ca`: the chain must be trusted, the host name is not checked.
+#[derive(Debug)]
+struct IgnoreHostName(Arc<dyn ServerCertVerifier>);
+
+impl ServerCertVerifier for IgnoreHostName {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        // The name is checked only once the chain has been validated, so a
+        // name mismatch means everything else passed.
+        match self
+            .0
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+        {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(ServerCertVerified::assertion()),
+            result => result,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_verify_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn libpq_only_options_are_taken_out_of_the_url() {
+        let (url, options) = split_tls_options(
+            "postgres://u:p@db:5432/app?sslmode=verify-full&sslrootcert=/etc/ca.pem&application_name=ap",
+        );
+        assert_eq!(url, "postgres://u:p@db:5432/app?application_name=ap");
+        assert_eq!(options.verification, Some(Verification::Full));
+        assert_eq!(options.root_cert, Some(PathBuf::from("/etc/ca.pem")));
+
+        let (url, options) = split_tls_options("postgres://db/app?sslmode=verify-ca");
+        assert_eq!(url, "postgres://db/app");
+        assert_eq!(options.verification, Some(Verification::Chain));
+    }
+
+    #[test]
+    fn modes_tokio_postgres_understands_stay_in_the_url() {
+        for mode in ["disable", "prefer", "require"] {
+            let url = format!("postgres://db/app?sslmode={}", mode);
+            let (kept, options) = split_tls_options(&url);
+            assert_eq!(kept, url);
+            assert_eq!(options.verification, None);
+        }
+    }
+
+    #[test]
+    fn verify_modes_require_tls() {
+        let (config, _) =
+            parse_database_url("postgres://db/app?sslmode=verify-full").expect("valid URL");
+        assert_eq!(config.get_ssl_mode(), SslMode::Require);
+
+        let (config, _) = parse_database_url("postgres://db/app").expect("valid URL");
+        assert_eq!(config.get_ssl_mode(), SslMode::Prefer);
+
+        let (config, _) =
+            parse_database_url("postgres://db/app?sslmode=disable").expect("valid URL");
+        assert_eq!(config.get_ssl_mode(), SslMode::Disable);
+    }
+
+    #[test]
+    fn unreadable_root_certificate_is_a_connection_error() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let missing = dir.path().join("missing.pem");
+        let url = format!(
+            "postgres://db/app?sslmode=verify-full&sslrootcert={}",
+            missing.display()
+        );
+        let Err(DbError::Connection(message)) = parse_database_url(&url) else {
+            panic!("expected a connection error");
+        };
+        assert!(message.contains("sslrootcert"), "{message}");
+
+        let empty = dir.path().join("empty.pem");
+        std::fs::write(&empty, "").expect("write");
+        let url = format!(
+            "postgres://db/app?sslmode=verify-ca&sslrootcert={}",
+            empty.display()
+        );
+        assert!(parse_database_url(&url).is_err());
+    }
+}
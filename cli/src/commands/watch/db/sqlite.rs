@@ -1,130 +1,26 @@
-//! SQLite database layer for autopilot state and schedule run history.
+//! SQLite backend for autopilot state and schedule run history.
 //!
-//! Uses libsql for async SQLite operations.
-
+//! Uses libsql for async SQLite operations. This is the default backend;
+//! it serves a single autopilot on the local machine.
+
+use super::{
+    DbError, ListRunsFilter, PendingResume, PendingSchedule, RELOAD_SENTINEL, RunFootprint,
+    RunStatus, ScheduleLock, ScheduleRun, ScheduleStore, SchedulerState,
+};
+use crate::commands::watch::budget::BudgetUsage;
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use libsql::{Connection, Database};
 use std::path::Path;
 
-/// Run status for schedule executions.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum RunStatus {
-    /// Currently executing
-    Running,
-    /// Completed successfully
-    Completed,
-    /// Failed with error
-    Failed,
-    /// Skipped (check script returned non-zero)
-    Skipped,
-    /// Timed out
-    TimedOut,
-    /// Paused (agent needs approval or input to continue)
-    Paused,
-}
-
-impl std::fmt::Display for RunStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            RunStatus::Running => write!(f, "running"),
-            RunStatus::Completed => write!(f, "completed"),
-            RunStatus::Failed => write!(f, "failed"),
-            RunStatus::Skipped => write!(f, "skipped"),
-            RunStatus::TimedOut => write!(f, "timed_out"),
-            RunStatus::Paused => write!(f, "paused"),
-        }
-    }
-}
-
-impl std::str::FromStr for RunStatus {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "running" => Ok(RunStatus::Running),
-            "completed" => Ok(RunStatus::Completed),
-            "failed" => Ok(RunStatus::Failed),
-            "skipped" => Ok(RunStatus::Skipped),
-            "timed_out" => Ok(RunStatus::TimedOut),
-            "paused" => Ok(RunStatus::Paused),
-            _ => Err(format!("Unknown run status: {}", s)),
-        }
-    }
-}
-
-/// A schedule run record.
-#[derive(Debug, Clone)]
-pub struct ScheduleRun {
-    pub id: i64,
-    pub schedule_name: String,
-    pub started_at: DateTime<Utc>,
-    pub finished_at: Option<DateTime<Utc>>,
-    pub check_exit_code: Option<i32>,
-    pub check_stdout: Option<String>,
-    pub check_stderr: Option<String>,
-    pub check_timed_out: bool,
-    pub agent_woken: bool,
-    pub interactive_delegated: bool,
-    pub agent_session_id: Option<String>,
-    pub agent_last_checkpoint_id: Option<String>,
-    pub agent_stdout: Option<String>,
-    pub agent_stderr: Option<String>,
-    pub status: RunStatus,
-    pub error_message: Option<String>,
-    pub created_at: DateTime<Utc>,
-}
-
-/// Watch state record.
-#[derive(Debug, Clone)]
-pub struct SchedulerState {
-    pub started_at: DateTime<Utc>,
-    pub pid: i64,
-    pub last_heartbeat: DateTime<Utc>,
-}
-
-/// Filter options for listing runs.
-#[derive(Debug, Default)]
-pub struct ListRunsFilter {
-    pub schedule_name: Option<String>,
-    pub status: Option<RunStatus>,
-    pub limit: Option<u32>,
-    pub offset: Option<u32>,
-}
-
-/// Well-known sentinel name used to request in-process schedule config reload.
-pub const RELOAD_SENTINEL: &str = "__config_reload__";
-
-/// Informational note stored on runs delegated to gateway interactive sessions.
-pub const INTERACTIVE_DELEGATED_NOTE: &str = "Delegated to interactive gateway session";
-
-/// A pending schedule request (for manual schedule fires).
-#[derive(Debug, Clone)]
-pub struct PendingSchedule {
-    pub id: i64,
-    pub schedule_name: String,
-    pub created_at: DateTime<Utc>,
-}
-
-/// Database errors.
-#[derive(Debug, thiserror::Error)]
-pub enum DbError {
-    #[error("Database connection error: {0}")]
-    Connection(String),
-
-    #[error("Query error: {0}")]
-    Query(String),
-
-    #[error("Not found: {0}")]
-    NotFound(String),
-}
-
-/// Watch database storage.
-pub struct ScheduleDb {
+/// SQLite-backed schedule store.
+pub struct SqliteScheduleStore {
     /// Keep the libsql Database handle alive for the lifetime of each operation connection.
     db: Database,
+    path: String,
 }
 
-impl ScheduleDb {
+impl SqliteScheduleStore {
     /// Create a new database instance, initializing schema if needed.
     pub async fn new(db_path: &str) -> Result<Self, DbError> {
         // Ensure parent directory exists
@@ -139,7 +35,10 @@ impl ScheduleDb {
             .await
             .map_err(|e| DbError::Connection(format!("Failed to open database: {}", e)))?;
 
-        let storage = Self { db };
+        let storage = Self {
+            db,
+            path: db_path.to_string(),
+        };
         storage.configure_database_pragmas().await?;
         storage.init_schema().await?;
 
@@ -192,6 +91,9 @@ impl ScheduleDb {
                 agent_last_checkpoint_id TEXT,
                 agent_stdout TEXT,
                 agent_stderr TEXT,
+                artifacts_dir TEXT,
+                total_tokens INTEGER,
+                estimated_cost REAL,
                 status TEXT NOT NULL,
                 error_message TEXT,
                 created_at TEXT DEFAULT CURRENT_TIMESTAMP
@@ -214,6 +116,21 @@ impl ScheduleDb {
                 (),
             )
             .await;
+        let _ = conn
+            .execute("ALTER TABLE trigger_runs ADD COLUMN artifacts_dir TEXT", ())
+            .await;
+        let _ = conn
+            .execute(
+                "ALTER TABLE trigger_runs ADD COLUMN total_tokens INTEGER",
+                (),
+            )
+            .await;
+        let _ = conn
+            .execute(
+                "ALTER TABLE trigger_runs ADD COLUMN estimated_cost REAL",
+                (),
+            )
+            .await;
 
         // Create autopilot_state table (singleton)
         conn.execute(
@@ -240,6 +157,19 @@ impl ScheduleDb {
         .await
         .map_err(|e| DbError::Query(e.to_string()))?;
 
+        // Create pending_resumes table for paused-run approval decisions
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS pending_resumes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                run_id INTEGER NOT NULL,
+                approve INTEGER NOT NULL,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+            (),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
         // Create index for faster queries
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_trigger_runs_trigger_name ON trigger_runs(trigger_name)",
@@ -264,9 +194,16 @@ impl ScheduleDb {
 
         Ok(())
     }
+}
+
+#[async_trait]
+impl ScheduleStore for SqliteScheduleStore {
+    fn location(&self) -> String {
+        self.path.clone()
+    }
 
     /// Check if a schedule already has a run in "running" status.
-    pub async fn has_running_run(&self, schedule_name: &str) -> Result<bool, DbError> {
+    async fn has_running_run(&self, schedule_name: &str) -> Result<bool, DbError> {
         let conn = self.connection().await?;
         let status = RunStatus::Running.to_string();
 
@@ -289,7 +226,7 @@ impl ScheduleDb {
     }
 
     /// Insert a new schedule run, returning the run ID.
-    pub async fn insert_run(&self, schedule_name: &str) -> Result<i64, DbError> {
+    async fn insert_run(&self, schedule_name: &str) -> Result<i64, DbError> {
         let conn = self.connection().await?;
         let now = Utc::now().to_rfc3339();
         let status = RunStatus::Running.to_string();
@@ -316,7 +253,7 @@ impl ScheduleDb {
     }
 
     /// Update run with check script results.
-    pub async fn update_run_check_result(
+    async fn update_run_check_result(
         &self,
         run_id: i64,
         exit_code: i32,
@@ -337,11 +274,7 @@ impl ScheduleDb {
     }
 
     /// Update run when agent is started.
-    pub async fn update_run_agent_started(
-        &self,
-        run_id: i64,
-        session_id: &str,
-    ) -> Result<(), DbError> {
+    async fn update_run_agent_started(&self, run_id: i64, session_id: &str) -> Result<(), DbError> {
         let conn = self.connection().await?;
 
         conn.execute(
@@ -355,7 +288,7 @@ impl ScheduleDb {
     }
 
     /// Mark run as delegated to gateway interactive session while keeping status=running.
-    pub async fn update_run_interactive_started(
+    async fn update_run_interactive_started(
         &self,
         run_id: i64,
         session_id: &str,
@@ -374,16 +307,30 @@ impl ScheduleDb {
     }
 
     /// Update run with latest checkpoint ID.
-    pub async fn update_run_checkpoint(
+    async fn update_run_checkpoint(&self, run_id: i64, checkpoint_id: &str) -> Result<(), DbError> {
+        let conn = self.connection().await?;
+
+        conn.execute(
+            "UPDATE trigger_runs SET agent_last_checkpoint_id = ? WHERE id = ?",
+            (checkpoint_id, run_id),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Record where a run's artifacts are stored.
+    async fn update_run_artifacts_dir(
         &self,
         run_id: i64,
-        checkpoint_id: &str,
+        artifacts_dir: &str,
     ) -> Result<(), DbError> {
         let conn = self.connection().await?;
 
         conn.execute(
-            "UPDATE trigger_runs SET agent_last_checkpoint_id = ? WHERE id = ?",
-            (checkpoint_id, run_id),
+            "UPDATE trigger_runs SET artifacts_dir = ? WHERE id = ?",
+            (artifacts_dir, run_id),
         )
         .await
         .map_err(|e| DbError::Query(e.to_string()))?;
@@ -391,8 +338,107 @@ impl ScheduleDb {
         Ok(())
     }
 
+    /// Add tokens and estimated cost to a run's usage. Resumed runs add the
+    /// usage of each resumption to what was recorded before they paused.
+    async fn add_run_usage(
+        &self,
+        run_id: i64,
+        total_tokens: u64,
+        estimated_cost: Option<f64>,
+    ) -> Result<(), DbError> {
+        let conn = self.connection().await?;
+        let total_tokens = i64::try_from(total_tokens).unwrap_or(i64::MAX);
+
+        conn.execute(
+            "UPDATE trigger_runs SET
+                total_tokens = COALESCE(total_tokens, 0) + ?,
+                estimated_cost = CASE
+                    WHEN ? IS NULL THEN estimated_cost
+                    ELSE COALESCE(estimated_cost, 0) + ?
+                END
+             WHERE id = ?",
+            libsql::params![total_tokens, estimated_cost, estimated_cost, run_id],
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Total usage of runs started at or after `since`, for one schedule or all of them.
+    async fn usage_since(
+        &self,
+        schedule_name: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> Result<BudgetUsage, DbError> {
+        let conn = self.connection().await?;
+
+        let mut sql = "SELECT COALESCE(SUM(total_tokens), 0), COALESCE(SUM(estimated_cost), 0.0)
+                       FROM trigger_runs WHERE started_at >= ?"
+            .to_string();
+        let mut params: Vec<libsql::Value> = vec![since.to_rfc3339().into()];
+        if let Some(name) = schedule_name {
+            sql.push_str(" AND trigger_name = ?");
+            params.push(name.to_string().into());
+        }
+
+        let mut rows = conn
+            .query(&sql, params)
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+        let row = rows
+            .next()
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?
+            .ok_or_else(|| DbError::NotFound("usage query returned no rows".to_string()))?;
+
+        let tokens: i64 = row.get(0).map_err(|e| DbError::Query(e.to_string()))?;
+        let cost: f64 = row.get(1).map_err(|e| DbError::Query(e.to_string()))?;
+        Ok(BudgetUsage {
+            tokens: u64::try_from(tokens).unwrap_or_default(),
+            cost,
+        })
+    }
+
+    /// Whether a schedule has a skipped run since `since` whose error message
+    /// starts with `reason_prefix`.
+    async fn has_skipped_run_since(
+        &self,
+        schedule_name: &str,
+        reason_prefix: &str,
+        since: DateTime<Utc>,
+    ) -> Result<bool, DbError> {
+        let conn = self.connection().await?;
+        let status = RunStatus::Skipped.to_string();
+        let since = since.to_rfc3339();
+
+        let mut rows = conn
+            .query(
+                "SELECT COUNT(*) FROM trigger_runs
+                 WHERE trigger_name = ? AND status = ? AND started_at >= ? AND substr(error_message, 1, ?) = ?",
+                libsql::params![
+                    schedule_name,
+                    status.as_str(),
+                    since.as_str(),
+                    reason_prefix.chars().count() as i64,
+                    reason_prefix
+                ],
+            )
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+
+        let row = rows
+            .next()
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?
+            .ok_or_else(|| DbError::NotFound("count query returned no rows".to_string()))?;
+
+        let count: i64 = row.get(0).map_err(|e| DbError::Query(e.to_string()))?;
+        Ok(count > 0)
+    }
+
     /// Update run when finished.
-    pub async fn update_run_finished(
+    async fn update_run_finished(
         &self,
         run_id: i64,
         status: RunStatus,
@@ -414,15 +460,35 @@ impl ScheduleDb {
         Ok(())
     }
 
+    /// Move a paused run back to running while it is resumed. Returns `false`
+    /// when the run is not paused (e.g. it was already resumed).
+    async fn update_run_resumed(&self, run_id: i64) -> Result<bool, DbError> {
+        let conn = self.connection().await?;
+        let running = RunStatus::Running.to_string();
+        let paused = RunStatus::Paused.to_string();
+
+        let updated = conn
+            .execute(
+                "UPDATE trigger_runs SET status = ?, finished_at = NULL, error_message = NULL
+                 WHERE id = ? AND status = ?",
+                (running.as_str(), run_id, paused.as_str()),
+            )
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(updated > 0)
+    }
+
     /// Get a run by ID.
-    pub async fn get_run(&self, run_id: i64) -> Result<ScheduleRun, DbError> {
+    async fn get_run(&self, run_id: i64) -> Result<ScheduleRun, DbError> {
         let conn = self.connection().await?;
 
         let mut rows = conn
             .query(
                 "SELECT id, trigger_name, started_at, finished_at, check_exit_code, check_stdout,
                         check_stderr, check_timed_out, agent_woken, interactive_delegated, agent_session_id,
-                        agent_last_checkpoint_id, agent_stdout, agent_stderr, status, error_message, created_at
+                        agent_last_checkpoint_id, agent_stdout, agent_stderr, status, error_message, created_at,
+                        artifacts_dir, total_tokens, estimated_cost
                  FROM trigger_runs WHERE id = ?",
                 [run_id],
             )
@@ -437,29 +503,41 @@ impl ScheduleDb {
     }
 
     /// List runs with optional filters.
-    pub async fn list_runs(&self, filter: &ListRunsFilter) -> Result<Vec<ScheduleRun>, DbError> {
+    async fn list_runs(&self, filter: &ListRunsFilter) -> Result<Vec<ScheduleRun>, DbError> {
         let conn = self.connection().await?;
 
         let mut sql =
             "SELECT id, trigger_name, started_at, finished_at, check_exit_code, check_stdout,
                               check_stderr, check_timed_out, agent_woken, interactive_delegated, agent_session_id,
-                              agent_last_checkpoint_id, agent_stdout, agent_stderr, status, error_message, created_at
+                              agent_last_checkpoint_id, agent_stdout, agent_stderr, status, error_message, created_at,
+                        artifacts_dir, total_tokens, estimated_cost
                        FROM trigger_runs WHERE 1=1"
                 .to_string();
 
-        let mut params: Vec<String> = Vec::new();
+        let mut params: Vec<libsql::Value> = Vec::new();
 
         if let Some(name) = &filter.schedule_name {
             sql.push_str(" AND trigger_name = ?");
-            params.push(name.clone());
+            params.push(name.clone().into());
         }
 
         if let Some(status) = &filter.status {
             sql.push_str(" AND status = ?");
-            params.push(status.to_string());
+            params.push(status.to_string().into());
+        }
+
+        if let Some(since) = &filter.since {
+            // Timestamps are stored as RFC 3339 in UTC, so string order is time order.
+            sql.push_str(" AND started_at >= ?");
+            params.push(since.to_rfc3339().into());
+        }
+
+        if let Some(after_id) = filter.after_id {
+            sql.push_str(" AND id > ?");
+            params.push(after_id.into());
         }
 
-        sql.push_str(" ORDER BY created_at DESC");
+        sql.push_str(" ORDER BY created_at DESC, id DESC");
 
         if let Some(limit) = filter.limit {
             sql.push_str(&format!(" LIMIT {}", limit));
@@ -469,17 +547,10 @@ impl ScheduleDb {
             sql.push_str(&format!(" OFFSET {}", offset));
         }
 
-        // Execute with appropriate number of params
-        let mut rows = match params.len() {
-            0 => conn.query(&sql, ()).await,
-            1 => conn.query(&sql, [params[0].as_str()]).await,
-            2 => {
-                conn.query(&sql, [params[0].as_str(), params[1].as_str()])
-                    .await
-            }
-            _ => return Err(DbError::Query("Too many filter parameters".to_string())),
-        }
-        .map_err(|e| DbError::Query(e.to_string()))?;
+        let mut rows = conn
+            .query(&sql, params)
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
 
         let mut runs = Vec::new();
         while let Ok(Some(row)) = rows.next().await {
@@ -490,7 +561,7 @@ impl ScheduleDb {
     }
 
     /// Delete runs older than the specified number of days.
-    pub async fn prune_runs(&self, older_than_days: u32) -> Result<u64, DbError> {
+    async fn prune_runs(&self, older_than_days: u32) -> Result<u64, DbError> {
         let conn = self.connection().await?;
 
         let result = conn
@@ -504,9 +575,59 @@ impl ScheduleDb {
         Ok(result)
     }
 
+    /// Every run with its creation time and the size of its stored output.
+    async fn run_footprints(&self) -> Result<Vec<RunFootprint>, DbError> {
+        let conn = self.connection().await?;
+        let mut rows = conn
+            .query(
+                "SELECT id, status, created_at,
+                    COALESCE(LENGTH(check_stdout), 0) + COALESCE(LENGTH(check_stderr), 0)
+                        + COALESCE(LENGTH(agent_stdout), 0) + COALESCE(LENGTH(agent_stderr), 0)
+                        + COALESCE(LENGTH(error_message), 0),
+                    artifacts_dir
+                 FROM trigger_runs",
+                (),
+            )
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+
+        let mut footprints = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?
+        {
+            let status: String = row.get(1).map_err(|e| DbError::Query(e.to_string()))?;
+            let created_at: String = row.get(2).map_err(|e| DbError::Query(e.to_string()))?;
+            let bytes: i64 = row.get(3).map_err(|e| DbError::Query(e.to_string()))?;
+            footprints.push(RunFootprint {
+                id: row.get(0).map_err(|e| DbError::Query(e.to_string()))?,
+                status: status.parse().map_err(DbError::Query)?,
+                created_at: parse_datetime(&created_at)?,
+                bytes: u64::try_from(bytes).unwrap_or_default(),
+                artifacts_dir: row.get(4).map_err(|e| DbError::Query(e.to_string()))?,
+            });
+        }
+
+        Ok(footprints)
+    }
+
+    /// Delete the given runs. Returns how many were removed.
+    async fn delete_runs(&self, ids: &[i64]) -> Result<u64, DbError> {
+        let conn = self.connection().await?;
+        let mut deleted = 0;
+        for id in ids {
+            deleted += conn
+                .execute("DELETE FROM trigger_runs WHERE id = ?", [*id])
+                .await
+                .map_err(|e| DbError::Query(e.to_string()))?;
+        }
+        Ok(deleted)
+    }
+
     /// Mark all stale "running" runs as failed.
     /// Runs are considered stale if they've been running and the autopilot service is no longer active.
-    pub async fn clean_stale_runs(&self) -> Result<u64, DbError> {
+    async fn clean_stale_runs(&self) -> Result<u64, DbError> {
         let conn = self.connection().await?;
         let now = Utc::now().to_rfc3339();
 
@@ -522,7 +643,7 @@ impl ScheduleDb {
     }
 
     /// Set autopilot state (upsert).
-    pub async fn set_autopilot_state(&self, pid: i64) -> Result<(), DbError> {
+    async fn set_autopilot_state(&self, pid: i64) -> Result<(), DbError> {
         let conn = self.connection().await?;
         let now = Utc::now().to_rfc3339();
 
@@ -537,7 +658,7 @@ impl ScheduleDb {
     }
 
     /// Update autopilot heartbeat.
-    pub async fn update_heartbeat(&self) -> Result<(), DbError> {
+    async fn update_heartbeat(&self) -> Result<(), DbError> {
         let conn = self.connection().await?;
         let now = Utc::now().to_rfc3339();
 
@@ -552,7 +673,7 @@ impl ScheduleDb {
     }
 
     /// Get autopilot state.
-    pub async fn get_autopilot_state(&self) -> Result<Option<SchedulerState>, DbError> {
+    async fn get_autopilot_state(&self) -> Result<Option<SchedulerState>, DbError> {
         let conn = self.connection().await?;
 
         let mut rows = conn
@@ -579,7 +700,7 @@ impl ScheduleDb {
     }
 
     /// Clear autopilot state.
-    pub async fn clear_autopilot_state(&self) -> Result<(), DbError> {
+    async fn clear_autopilot_state(&self) -> Result<(), DbError> {
         let conn = self.connection().await?;
 
         conn.execute("DELETE FROM autopilot_state WHERE id = 1", ())
@@ -590,7 +711,7 @@ impl ScheduleDb {
     }
 
     /// Insert a pending schedule request (for manual schedule fires).
-    pub async fn insert_pending_schedule(&self, schedule_name: &str) -> Result<i64, DbError> {
+    async fn insert_pending_schedule(&self, schedule_name: &str) -> Result<i64, DbError> {
         let conn = self.connection().await?;
         let now = Utc::now().to_rfc3339();
 
@@ -616,7 +737,7 @@ impl ScheduleDb {
     }
 
     /// Request a scheduler config reload using the pending_triggers signal queue.
-    pub async fn request_config_reload(&self) -> Result<(), DbError> {
+    async fn request_config_reload(&self) -> Result<(), DbError> {
         let conn = self.connection().await?;
         let now = Utc::now().to_rfc3339();
 
@@ -635,7 +756,7 @@ impl ScheduleDb {
     }
 
     /// Get and delete all pending schedules (atomic pop).
-    pub async fn pop_pending_schedules(&self) -> Result<Vec<PendingSchedule>, DbError> {
+    async fn pop_pending_schedules(&self) -> Result<Vec<PendingSchedule>, DbError> {
         let conn = self.connection().await?;
 
         let mut rows = conn
@@ -678,6 +799,66 @@ impl ScheduleDb {
 
         Ok(schedules)
     }
+
+    /// Queue a decision for a paused run, picked up by the running autopilot.
+    async fn insert_pending_resume(&self, run_id: i64, approve: bool) -> Result<(), DbError> {
+        let conn = self.connection().await?;
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO pending_resumes (run_id, approve, created_at) VALUES (?, ?, ?)",
+            (run_id, approve as i64, now.as_str()),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Get and delete all pending resume requests, oldest first.
+    async fn pop_pending_resumes(&self) -> Result<Vec<PendingResume>, DbError> {
+        let conn = self.connection().await?;
+
+        let mut rows = conn
+            .query(
+                "DELETE FROM pending_resumes RETURNING id, run_id, approve, created_at",
+                (),
+            )
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+
+        let mut resumes = Vec::new();
+        while let Ok(Some(row)) = rows.next().await {
+            let (Ok(id), Ok(run_id), Ok(approve), Ok(created_at_raw)) = (
+                row.get::<i64>(0),
+                row.get::<i64>(1),
+                row.get::<i64>(2),
+                row.get::<String>(3),
+            ) else {
+                continue;
+            };
+
+            resumes.push(PendingResume {
+                id,
+                run_id,
+                approve: approve != 0,
+                created_at: parse_datetime(&created_at_raw).unwrap_or_else(|_| Utc::now()),
+            });
+        }
+
+        resumes.sort_by_key(|resume| resume.id);
+
+        Ok(resumes)
+    }
+
+    /// Only one autopilot uses a SQLite database, so the `has_running_run`
+    /// guard alone keeps runs of a schedule from overlapping.
+    async fn try_lock_schedule(
+        &self,
+        _schedule_name: &str,
+    ) -> Result<Option<ScheduleLock>, DbError> {
+        Ok(Some(ScheduleLock::local()))
+    }
 }
 
 /// Parse a row into a ScheduleRun.
@@ -699,6 +880,9 @@ fn parse_schedule_run(row: &libsql::Row) -> Result<ScheduleRun, DbError> {
     let status: String = row.get(14).map_err(|e| DbError::Query(e.to_string()))?;
     let error_message: Option<String> = row.get(15).ok();
     let created_at: String = row.get(16).map_err(|e| DbError::Query(e.to_string()))?;
+    let artifacts_dir: Option<String> = row.get(17).ok();
+    let total_tokens: Option<i64> = row.get(18).ok();
+    let estimated_cost: Option<f64> = row.get(19).ok();
 
     Ok(ScheduleRun {
         id,
@@ -715,6 +899,9 @@ fn parse_schedule_run(row: &libsql::Row) -> Result<ScheduleRun, DbError> {
         agent_last_checkpoint_id,
         agent_stdout,
         agent_stderr,
+        artifacts_dir,
+        total_tokens: total_tokens.and_then(|tokens| u64::try_from(tokens).ok()),
+        estimated_cost,
         status: status.parse().map_err(DbError::Query)?,
         error_message,
         created_at: parse_datetime(&created_at)?,
@@ -737,7 +924,7 @@ mod tests {
     use super::*;
     use tempfile::{TempDir, tempdir};
 
-    async fn create_test_db() -> (ScheduleDb, TempDir) {
+    async fn create_test_db() -> (SqliteScheduleStore, TempDir) {
         let dir = match tempdir() {
             Ok(dir) => dir,
             Err(e) => panic!("Failed to create temp dir: {e}"),
@@ -747,7 +934,7 @@ mod tests {
             Some(path) => path,
             None => panic!("Invalid path: {}", db_path.display()),
         };
-        let db = match ScheduleDb::new(db_path_str).await {
+        let db = match SqliteScheduleStore::new(db_path_str).await {
             Ok(db) => db,
             Err(e) => panic!("Failed to create test db: {e}"),
         };
@@ -759,7 +946,7 @@ mod tests {
         let dir = tempdir().expect("Failed to create temp dir");
         let db_path = dir.path().join("test.db");
 
-        let db = ScheduleDb::new(db_path.to_str().expect("Invalid path"))
+        let db = SqliteScheduleStore::new(db_path.to_str().expect("Invalid path"))
             .await
             .expect("Failed to create db");
 
@@ -834,6 +1021,15 @@ mod tests {
             run.agent_last_checkpoint_id,
             Some("checkpoint-456".to_string())
         );
+        assert!(run.artifacts_dir.is_none());
+
+        // Record artifacts directory
+        db.update_run_artifacts_dir(run_id, "/tmp/artifacts/run-1")
+            .await
+            .expect("Update artifacts dir failed");
+
+        let run = db.get_run(run_id).await.expect("Get failed");
+        assert_eq!(run.artifacts_dir.as_deref(), Some("/tmp/artifacts/run-1"));
 
         // Update finished
         db.update_run_finished(run_id, RunStatus::Completed, None, None, None)
@@ -895,6 +1091,27 @@ mod tests {
             .await
             .expect("List failed");
         assert_eq!(runs.len(), 1);
+
+        // Filter by id cursor
+        let runs = db
+            .list_runs(&ListRunsFilter {
+                after_id: Some(id1),
+                ..Default::default()
+            })
+            .await
+            .expect("List failed");
+        assert_eq!(runs.len(), 2);
+        assert!(runs.iter().all(|run| run.id > id1));
+
+        // Filter by start time
+        let runs = db
+            .list_runs(&ListRunsFilter {
+                since: Some(Utc::now() + chrono::Duration::hours(1)),
+                ..Default::default()
+            })
+            .await
+            .expect("List failed");
+        assert!(runs.is_empty());
     }
 
     #[tokio::test]
@@ -912,6 +1129,32 @@ mod tests {
         assert_eq!(deleted, 0);
     }
 
+    #[tokio::test]
+    async fn test_run_footprints_and_delete_runs() {
+        let (db, _dir) = create_test_db().await;
+
+        let finished = db.insert_run("test-schedule").await.expect("Insert failed");
+        db.update_run_check_result(finished, 0, "disk at 91%", "", false)
+            .await
+            .expect("Update failed");
+        let running = db.insert_run("test-schedule").await.expect("Insert failed");
+
+        let footprints = db.run_footprints().await.expect("Footprints failed");
+        assert_eq!(footprints.len(), 2);
+        let finished_footprint = footprints
+            .iter()
+            .find(|footprint| footprint.id == finished)
+            .expect("finished run footprint");
+        assert_eq!(finished_footprint.bytes, "disk at 91%".len() as u64);
+
+        let deleted = db.delete_runs(&[finished]).await.expect("Delete failed");
+        assert_eq!(deleted, 1);
+        let remaining = db.run_footprints().await.expect("Footprints failed");
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, running);
+        assert_eq!(remaining[0].status, RunStatus::Running);
+    }
+
     #[tokio::test]
     async fn test_autopilot_state_lifecycle() {
         let (db, _dir) = create_test_db().await;
@@ -1023,6 +1266,43 @@ mod tests {
         assert!(pending.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_pending_resumes_and_resumed_run() {
+        let (db, _dir) = create_test_db().await;
+        let run_id = db.insert_run("deploy-check").await.expect("insert run");
+
+        // Only paused runs can be resumed.
+        assert!(!db.update_run_resumed(run_id).await.expect("resume"));
+        db.update_run_finished(run_id, RunStatus::Paused, None, Some("before pause"), None)
+            .await
+            .expect("pause run");
+
+        db.insert_pending_resume(run_id, true)
+            .await
+            .expect("queue approve");
+        db.insert_pending_resume(run_id, false)
+            .await
+            .expect("queue deny");
+        let pending = db.pop_pending_resumes().await.expect("pop resumes");
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].run_id, run_id);
+        assert!(pending[0].approve);
+        assert!(!pending[1].approve);
+        assert!(
+            db.pop_pending_resumes()
+                .await
+                .expect("pop again")
+                .is_empty()
+        );
+
+        assert!(db.update_run_resumed(run_id).await.expect("resume"));
+        assert!(!db.update_run_resumed(run_id).await.expect("resume twice"));
+        let run = db.get_run(run_id).await.expect("get run");
+        assert_eq!(run.status, RunStatus::Running);
+        assert!(run.finished_at.is_none());
+        assert_eq!(run.agent_stdout.as_deref(), Some("before pause"));
+    }
+
     #[tokio::test]
     async fn test_config_reload_signal_roundtrip() {
         let (db, _dir) = create_test_db().await;
@@ -1221,4 +1501,80 @@ mod tests {
             result.err()
         );
     }
+
+    #[tokio::test]
+    async fn test_usage_since_sums_tokens_and_cost() {
+        let (db, _dir) = create_test_db().await;
+        let since = Utc::now() - chrono::Duration::minutes(1);
+
+        let first = db.insert_run("billing").await.expect("insert run");
+        db.add_run_usage(first, 1_000, Some(0.25))
+            .await
+            .expect("record usage");
+        // A resumed run adds to its earlier usage.
+        db.add_run_usage(first, 500, None)
+            .await
+            .expect("record resumed usage");
+        let second = db.insert_run("billing").await.expect("insert run");
+        db.add_run_usage(second, 500, None)
+            .await
+            .expect("record usage");
+        let other = db.insert_run("health").await.expect("insert run");
+        db.add_run_usage(other, 1_000, Some(1.0))
+            .await
+            .expect("record usage");
+        db.insert_run("health")
+            .await
+            .expect("insert run without usage");
+
+        let billing = db
+            .usage_since(Some("billing"), since)
+            .await
+            .expect("schedule usage");
+        assert_eq!(billing.tokens, 2_000);
+        assert!((billing.cost - 0.25).abs() < f64::EPSILON);
+
+        let total = db.usage_since(None, since).await.expect("total usage");
+        assert_eq!(total.tokens, 3_000);
+        assert!((total.cost - 1.25).abs() < f64::EPSILON);
+
+        let later = db
+            .usage_since(None, Utc::now() + chrono::Duration::hours(1))
+            .await
+            .expect("future usage");
+        assert_eq!(later, BudgetUsage::default());
+    }
+
+    #[tokio::test]
+    async fn test_has_skipped_run_since_matches_reason_prefix() {
+        let (db, _dir) = create_test_db().await;
+        let since = Utc::now() - chrono::Duration::minutes(1);
+
+        let run_id = db.insert_run("billing").await.expect("insert run");
+        db.update_run_finished(
+            run_id,
+            RunStatus::Skipped,
+            Some("Budget exceeded: daily token budget reached"),
+            None,
+            None,
+        )
+        .await
+        .expect("finish run");
+
+        assert!(
+            db.has_skipped_run_since("billing", "Budget exceeded", since)
+                .await
+                .expect("query")
+        );
+        assert!(
+            !db.has_skipped_run_since("billing", "Blackout window", since)
+                .await
+                .expect("query")
+        );
+        assert!(
+            !db.has_skipped_run_since("health", "Budget exceeded", since)
+                .await
+                .expect("query")
+        );
+    }
 }
@@ -1,7 +1,19 @@
-//! Check script executor with timeout support.
+//! Check executor with timeout support.
 //!
-//! Executes check scripts as child processes, capturing output and enforcing timeouts.
-
+//! Executes check scripts as child processes, capturing output and enforcing
+//! timeouts, and runs the built-in probes ([`CheckProbe`]) that stand in for
+//! scripts in simple monitors.
+//!
+//! A script may print a JSON object following the [`CheckOutput`] contract
+//! instead of free-form text:
+//! ```json
+//! { "trigger": true, "severity": "high", "summary": "disk 95% full", "context": {"mount": "/var"} }
+//! ```
+//! Its fields are passed to the agent as structured values, and `trigger`
+//! overrides the exit code when deciding whether to wake the agent.
+
+use super::config::CheckTriggerOn;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::process::Stdio;
 use std::time::Duration;
@@ -9,6 +21,151 @@ use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 use tracing::{debug, warn};
 
+/// A schedule's check: a script path or a built-in probe.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CheckSpec {
+    Script(String),
+    Probe(CheckProbe),
+}
+
+impl CheckSpec {
+    /// The script path, `None` for probes.
+    pub fn script(&self) -> Option<&str> {
+        match self {
+            CheckSpec::Script(path) => Some(path),
+            CheckSpec::Probe(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for CheckSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckSpec::Script(path) => write!(f, "{}", path),
+            CheckSpec::Probe(probe) => write!(f, "{}", probe),
+        }
+    }
+}
+
+/// A built-in check that runs without a script.
+///
+/// Probes report like scripts: exit 0 when healthy and 2 when not, with what
+/// was observed on stdout. A probe that cannot complete (unreachable host,
+/// timeout, ...) counts as unhealthy rather than timed out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", deny_unknown_fields)]
+pub enum CheckProbe {
+    /// GET `url` and expect `expect_status`.
+    Http {
+        url: String,
+        #[serde(default = "default_expect_status")]
+        expect_status: u16,
+    },
+    /// Open a TCP connection to `host:port`.
+    Tcp { host: String, port: u16 },
+    /// Require `min_free_percent` free space on the filesystem holding `path`.
+    Disk { path: String, min_free_percent: f64 },
+    /// Require a running process named `name`.
+    Process { name: String },
+}
+
+fn default_expect_status() -> u16 {
+    200
+}
+
+impl CheckProbe {
+    /// Reject probes that can never succeed.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            CheckProbe::Http { url, expect_status } => {
+                let parsed = reqwest::Url::parse(url)
+                    .map_err(|e| format!("invalid url '{}': {}", url, e))?;
+                if !matches!(parsed.scheme(), "http" | "https") {
+                    return Err(format!("url '{}' must use http or https", url));
+                }
+                if !(100..=599).contains(expect_status) {
+                    return Err(format!("invalid expect_status {}", expect_status));
+                }
+            }
+            CheckProbe::Tcp { host, port } => {
+                if host.trim().is_empty() {
+                    return Err("tcp host cannot be empty".to_string());
+                }
+                if *port == 0 {
+                    return Err("tcp port must be between 1 and 65535".to_string());
+                }
+            }
+            CheckProbe::Disk {
+                path,
+                min_free_percent,
+            } => {
+                if path.trim().is_empty() {
+                    return Err("disk path cannot be empty".to_string());
+                }
+                if !(0.0..=100.0).contains(min_free_percent) {
+                    return Err(format!(
+                        "min_free_percent must be between 0 and 100, got {}",
+                        min_free_percent
+                    ));
+                }
+            }
+            CheckProbe::Process { name } => {
+                if name.trim().is_empty() {
+                    return Err("process name cannot be empty".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for CheckProbe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckProbe::Http { url, expect_status } => {
+                write!(f, "http {} (expect {})", url, expect_status)
+            }
+            CheckProbe::Tcp { host, port } => write!(f, "tcp {}:{}", host, port),
+            CheckProbe::Disk {
+                path,
+                min_free_percent,
+            } => write!(f, "disk {} (min {}% free)", path, min_free_percent),
+            CheckProbe::Process { name } => write!(f, "process {}", name),
+        }
+    }
+}
+
+/// Structured output a check script may print as a JSON object on stdout.
+///
+/// Every field is optional; stdout that is not a JSON object with at least one
+/// of these fields is treated as plain text.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CheckOutput {
+    /// Whether to wake the agent, overriding the schedule's `trigger_on`.
+    #[serde(default)]
+    pub trigger: Option<bool>,
+    #[serde(default)]
+    pub severity: Option<String>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Arbitrary data handed to the agent as-is.
+    #[serde(default)]
+    pub context: Option<serde_json::Value>,
+}
+
+impl CheckOutput {
+    /// Parse `stdout` as a check output contract, `None` for plain text output.
+    pub fn parse(stdout: &str) -> Option<Self> {
+        let stdout = stdout.trim();
+        if !stdout.starts_with('{') {
+            return None;
+        }
+        let output: Self = serde_json::from_str(stdout).ok()?;
+        (output != Self::default()).then_some(output)
+    }
+}
+
 /// Result of running a check script.
 #[derive(Debug, Clone)]
 pub struct CheckResult {
@@ -37,6 +194,19 @@ impl CheckResult {
     pub fn failed(&self) -> bool {
         self.timed_out || matches!(self.exit_code, Some(code) if code >= 2)
     }
+
+    /// Structured output, when stdout follows the [`CheckOutput`] contract.
+    pub fn output(&self) -> Option<CheckOutput> {
+        CheckOutput::parse(&self.stdout)
+    }
+
+    /// Whether this result should wake the agent: the output's `trigger` when
+    /// set, otherwise `trigger_on` applied to the exit code.
+    pub fn should_trigger(&self, trigger_on: CheckTriggerOn) -> bool {
+        self.output()
+            .and_then(|output| output.trigger)
+            .unwrap_or_else(|| trigger_on.should_trigger(self.exit_code.unwrap_or(-1)))
+    }
 }
 
 /// Errors that can occur during check script execution.
@@ -219,6 +389,193 @@ pub async fn run_check_script(
     }
 }
 
+/// Run a schedule's check, whether a script or a built-in probe.
+pub async fn run_check(check: &CheckSpec, timeout: Duration) -> Result<CheckResult, ExecutorError> {
+    match check {
+        CheckSpec::Script(path) => {
+            run_check_script(&super::config::expand_tilde(path), timeout).await
+        }
+        CheckSpec::Probe(probe) => Ok(run_check_probe(probe, timeout).await),
+    }
+}
+
+/// Run a built-in probe, giving up after `timeout`.
+pub async fn run_check_probe(probe: &CheckProbe, timeout: Duration) -> CheckResult {
+    debug!(probe = %probe, timeout_secs = timeout.as_secs(), "Running check probe");
+
+    let outcome = match tokio::time::timeout(timeout, probe_outcome(probe, timeout)).await {
+        Ok(outcome) => outcome,
+        Err(_) => Err(format!("{} timed out after {:?}", probe, timeout)),
+    };
+
+    let (exit_code, stdout, stderr) = match outcome {
+        Ok((true, observed)) => (0, observed, String::new()),
+        Ok((false, observed)) => (2, observed, String::new()),
+        Err(error) => (2, String::new(), error),
+    };
+    debug!(probe = %probe, exit_code, "Check probe completed");
+
+    CheckResult {
+        exit_code: Some(exit_code),
+        stdout: line(stdout),
+        stderr: line(stderr),
+        timed_out: false,
+    }
+}
+
+fn line(text: String) -> String {
+    if text.is_empty() {
+        text
+    } else {
+        format!("{}\n", text)
+    }
+}
+
+/// Whether the probe is healthy and what it observed, or why it could not tell.
+async fn probe_outcome(probe: &CheckProbe, timeout: Duration) -> Result<(bool, String), String> {
+    match probe {
+        CheckProbe::Http { url, expect_status } => probe_http(url, *expect_status, timeout).await,
+        CheckProbe::Tcp { host, port } => {
+            tokio::net::TcpStream::connect((host.as_str(), *port))
+                .await
+                .map_err(|e| format!("tcp {}:{} unreachable: {}", host, port, e))?;
+            Ok((true, format!("tcp {}:{} accepted a connection", host, port)))
+        }
+        CheckProbe::Disk {
+            path,
+            min_free_percent,
+        } => {
+            let path = super::config::expand_tilde(path);
+            let free_percent = disk_free_percent(&path).await?;
+            Ok((
+                free_percent >= *min_free_percent,
+                format!(
+                    "disk {}: {:.1}% free (minimum {}%)",
+                    path.display(),
+                    free_percent,
+                    min_free_percent
+                ),
+            ))
+        }
+        CheckProbe::Process { name } => {
+            let running = process_running(name).await?;
+            Ok((
+                running,
+                format!(
+                    "process {} is {}",
+                    name,
+                    if running { "running" } else { "not running" }
+                ),
+            ))
+        }
+    }
+}
+
+async fn probe_http(
+    url: &str,
+    expect_status: u16,
+    timeout: Duration,
+) -> Result<(bool, String), String> {
+    let client = stakpak_shared::tls_client::create_tls_client(
+        stakpak_shared::tls_client::TlsClientConfig::default().with_timeout(timeout),
+    )?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("GET {} failed: {}", url, e))?;
+    let status = response.status();
+    Ok((
+        status.as_u16() == expect_status,
+        format!(
+            "GET {} returned {} (expected {})",
+            url, status, expect_status
+        ),
+    ))
+}
+
+/// Free space on the filesystem holding `path`, as a percentage of its size.
+async fn disk_free_percent(path: &Path) -> Result<f64, String> {
+    #[cfg(unix)]
+    {
+        let output = Command::new("df")
+            .arg("-Pk")
+            .arg(path)
+            .output()
+            .await
+            .map_err(|e| format!("failed to run df: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "df {} failed: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        parse_df_free_percent(&String::from_utf8_lossy(&output.stdout))
+            .ok_or_else(|| format!("could not parse df output for {}", path.display()))
+    }
+
+    #[cfg(not(unix))]
+    {
+        Err(format!(
+            "disk probe is not supported on this platform ({})",
+            path.display()
+        ))
+    }
+}
+
+/// Free percentage from POSIX `df -P` output (used + available is the usable size).
+fn parse_df_free_percent(output: &str) -> Option<f64> {
+    let fields: Vec<&str> = output.lines().nth(1)?.split_whitespace().collect();
+    let used: f64 = fields.get(2)?.parse().ok()?;
+    let available: f64 = fields.get(3)?.parse().ok()?;
+    let usable = used + available;
+    if usable <= 0.0 {
+        return None;
+    }
+    Some(available / usable * 100.0)
+}
+
+async fn process_running(name: &str) -> Result<bool, String> {
+    #[cfg(unix)]
+    {
+        let output = Command::new("pgrep")
+            .arg("-x")
+            .arg(name)
+            .output()
+            .await
+            .map_err(|e| format!("failed to run pgrep: {}", e))?;
+        // pgrep exits 1 when nothing matched and 2+ on errors.
+        match output.status.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            _ => Err(format!(
+                "pgrep {} failed: {}",
+                name,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        let output = Command::new("tasklist")
+            .arg("/FI")
+            .arg(format!("IMAGENAME eq {}", name))
+            .arg("/FO")
+            .arg("CSV")
+            .arg("/NH")
+            .output()
+            .await
+            .map_err(|e| format!("failed to run tasklist: {}", e))?;
+        let listed = String::from_utf8_lossy(&output.stdout);
+        Ok(listed.lines().any(|line| {
+            line.to_lowercase()
+                .starts_with(&format!("\"{}", name.to_lowercase()))
+        }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -415,6 +772,120 @@ mod tests {
         assert!(result.stdout.lines().count() >= 1000);
     }
 
+    #[test]
+    fn test_probe_specs_parse_from_toml() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            check: Vec<CheckSpec>,
+        }
+        let parsed: Wrapper = toml::from_str(
+            r#"
+check = [
+    "~/.stakpak/checks/disk.sh",
+    { http = { url = "https://example.com/health" } },
+    { tcp = { host = "db.internal", port = 5432 } },
+    { disk = { path = "/var", min_free_percent = 15 } },
+    { process = { name = "nginx" } },
+]
+"#,
+        )
+        .expect("parse checks");
+
+        assert_eq!(
+            parsed.check,
+            vec![
+                CheckSpec::Script("~/.stakpak/checks/disk.sh".to_string()),
+                CheckSpec::Probe(CheckProbe::Http {
+                    url: "https://example.com/health".to_string(),
+                    expect_status: 200,
+                }),
+                CheckSpec::Probe(CheckProbe::Tcp {
+                    host: "db.internal".to_string(),
+                    port: 5432,
+                }),
+                CheckSpec::Probe(CheckProbe::Disk {
+                    path: "/var".to_string(),
+                    min_free_percent: 15.0,
+                }),
+                CheckSpec::Probe(CheckProbe::Process {
+                    name: "nginx".to_string(),
+                }),
+            ]
+        );
+        assert!(
+            toml::from_str::<Wrapper>(
+                r#"check = [{ tcp = { host = "db", port = 5432, tls = true } }]"#
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_probe_validation() {
+        let invalid = [
+            CheckProbe::Http {
+                url: "ftp://example.com".to_string(),
+                expect_status: 200,
+            },
+            CheckProbe::Http {
+                url: "https://example.com".to_string(),
+                expect_status: 42,
+            },
+            CheckProbe::Tcp {
+                host: "db".to_string(),
+                port: 0,
+            },
+            CheckProbe::Disk {
+                path: "/".to_string(),
+                min_free_percent: 120.0,
+            },
+            CheckProbe::Process {
+                name: " ".to_string(),
+            },
+        ];
+        for probe in invalid {
+            assert!(probe.validate().is_err(), "{probe}");
+        }
+        assert!(
+            CheckProbe::Tcp {
+                host: "db".to_string(),
+                port: 5432
+            }
+            .validate()
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_parse_df_free_percent() {
+        let output = "Filesystem     1024-blocks     Used Available Capacity Mounted on\n\
+                      /dev/sda1          1000000   750000    250000      75% /\n";
+        assert_eq!(parse_df_free_percent(output), Some(25.0));
+        assert_eq!(parse_df_free_percent("Filesystem\n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_probe_reports_listening_and_closed_ports() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind listener");
+        let port = listener.local_addr().expect("local addr").port();
+        let probe = CheckProbe::Tcp {
+            host: "127.0.0.1".to_string(),
+            port,
+        };
+
+        let open = run_check_probe(&probe, Duration::from_secs(5)).await;
+        assert!(open.passed(), "{open:?}");
+        assert!(open.stdout.contains("accepted a connection"));
+
+        drop(listener);
+        let closed = run_check_probe(&probe, Duration::from_secs(5)).await;
+        assert!(closed.failed());
+        assert!(!closed.timed_out);
+        assert!(closed.stderr.contains("unreachable"));
+    }
+
     #[tokio::test]
     async fn test_check_result_methods() {
         // Test passed
@@ -461,4 +932,45 @@ mod tests {
         assert!(!timed_out.skipped());
         assert!(timed_out.failed());
     }
+
+    #[test]
+    fn test_check_output_contract() {
+        let output = CheckOutput::parse(
+            r#"{"trigger": true, "severity": "high", "context": {"mount": "/var", "used": 95}}"#,
+        )
+        .expect("contract output");
+        assert_eq!(output.trigger, Some(true));
+        assert_eq!(output.severity.as_deref(), Some("high"));
+        assert_eq!(output.summary, None);
+        assert_eq!(
+            output.context,
+            Some(serde_json::json!({"mount": "/var", "used": 95}))
+        );
+
+        for stdout in [
+            "disk usage 92%",
+            "",
+            "{not json",
+            r#"{"status": "ok"}"#,
+            r#"{"trigger": "yes"}"#,
+            "[1, 2]",
+        ] {
+            assert_eq!(CheckOutput::parse(stdout), None, "{stdout}");
+        }
+    }
+
+    #[test]
+    fn test_output_trigger_overrides_exit_code() {
+        let result = |exit_code: i32, stdout: &str| CheckResult {
+            exit_code: Some(exit_code),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            timed_out: false,
+        };
+
+        assert!(!result(0, "all good").should_trigger(CheckTriggerOn::Failure));
+        assert!(result(0, r#"{"trigger": true}"#).should_trigger(CheckTriggerOn::Failure));
+        assert!(!result(2, r#"{"trigger": false}"#).should_trigger(CheckTriggerOn::Failure));
+        assert!(result(2, r#"{"severity": "low"}"#).should_trigger(CheckTriggerOn::Failure));
+    }
 }
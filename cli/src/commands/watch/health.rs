@@ -0,0 +1,269 @@
+//! Autopilot health HTTP endpoint.
+//!
+//! When `[watch].health_listen` is set, the run loop serves a small
+//! unauthenticated HTTP API for orchestrator probes:
+//! - `GET /healthz`: liveness. 200 while the heartbeat recorded in the
+//!   schedule database is fresh, 503 once it goes stale or cannot be read.
+//! - `GET /readyz`: readiness. 200 once the scheduler has started and the
+//!   database answers, 503 otherwise (including during shutdown).
+//! - `GET /schedules`: registered schedules with their next fire times.
+
+use crate::commands::watch::commands::HEARTBEAT_STALE_SECONDS;
+use crate::commands::watch::db::SchedulerState;
+use crate::commands::watch::reconciler::ScheduleSnapshot;
+use crate::commands::watch::{ScheduleDb, Scheduler};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Shared handles the health endpoints read from.
+#[derive(Clone)]
+pub struct HealthState {
+    pub db: Arc<ScheduleDb>,
+    pub scheduler: Arc<Mutex<Scheduler>>,
+    pub snapshot: Arc<Mutex<ScheduleSnapshot>>,
+    /// Cleared when the autopilot starts shutting down.
+    pub ready: Arc<AtomicBool>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct LivenessReport {
+    pub status: &'static str,
+    pub pid: Option<i64>,
+    pub started_at: Option<String>,
+    pub last_heartbeat: Option<String>,
+    pub heartbeat_age_secs: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ReadinessReport {
+    pub status: &'static str,
+    pub scheduler_running: bool,
+    pub database: bool,
+    pub schedules: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ScheduleStatus {
+    pub name: String,
+    pub cron: String,
+    pub next_fire: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SchedulesResponse {
+    schedules: Vec<ScheduleStatus>,
+}
+
+/// Bind `addr` and serve the health endpoints in the background.
+///
+/// Binding happens before returning so a taken port fails autopilot startup
+/// instead of leaving probes pointed at nothing.
+pub async fn spawn_health_server(
+    addr: SocketAddr,
+    state: HealthState,
+) -> Result<JoinHandle<()>, String> {
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("Failed to bind health endpoint on {}: {}", addr, e))?;
+    info!(addr = %addr, "Health endpoint listening");
+
+    Ok(tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router(state)).await {
+            warn!(error = %e, "Health endpoint stopped");
+        }
+    }))
+}
+
+fn router(state: HealthState) -> Router {
+    Router::new()
+        .route("/healthz", get(healthz_handler))
+        .route("/readyz", get(readyz_handler))
+        .route("/schedules", get(schedules_handler))
+        .with_state(state)
+}
+
+async fn healthz_handler(State(state): State<HealthState>) -> (StatusCode, Json<LivenessReport>) {
+    let autopilot_state = state
+        .db
+        .get_autopilot_state()
+        .await
+        .map_err(|e| e.to_string());
+    let (status, report) = liveness_report(autopilot_state, Utc::now());
+    (status, Json(report))
+}
+
+async fn readyz_handler(State(state): State<HealthState>) -> (StatusCode, Json<ReadinessReport>) {
+    let database = state.db.get_autopilot_state().await.is_ok();
+    let scheduler_running = state.ready.load(Ordering::Relaxed);
+    let schedules = state.snapshot.lock().await.registered.len();
+    let (status, report) = readiness_report(scheduler_running, database, schedules);
+    (status, Json(report))
+}
+
+async fn schedules_handler(State(state): State<HealthState>) -> Json<SchedulesResponse> {
+    let registered = state.snapshot.lock().await.registered.clone();
+    let mut schedules = Vec::with_capacity(registered.len());
+    {
+        let mut scheduler = state.scheduler.lock().await;
+        for (name, entry) in registered {
+            let next_fire = match scheduler.next_tick_for_job(entry.job_id).await {
+                Ok(next) => next.map(|next| next.to_rfc3339()),
+                Err(e) => {
+                    warn!(schedule = %name, error = %e, "Failed to read next fire time");
+                    None
+                }
+            };
+            schedules.push(ScheduleStatus {
+                name,
+                cron: entry.cron,
+                next_fire,
+            });
+        }
+    }
+    schedules.sort_by(|a, b| a.name.cmp(&b.name));
+    Json(SchedulesResponse { schedules })
+}
+
+/// Liveness verdict from the persisted autopilot state.
+pub fn liveness_report(
+    autopilot_state: Result<Option<SchedulerState>, String>,
+    now: DateTime<Utc>,
+) -> (StatusCode, LivenessReport) {
+    let state = match autopilot_state {
+        Ok(Some(state)) => state,
+        Ok(None) => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                LivenessReport {
+                    status: "unavailable",
+                    pid: None,
+                    started_at: None,
+                    last_heartbeat: None,
+                    heartbeat_age_secs: None,
+                    error: Some("autopilot state not recorded".to_string()),
+                },
+            );
+        }
+        Err(error) => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                LivenessReport {
+                    status: "unavailable",
+                    pid: None,
+                    started_at: None,
+                    last_heartbeat: None,
+                    heartbeat_age_secs: None,
+                    error: Some(error),
+                },
+            );
+        }
+    };
+
+    let age = now
+        .signed_duration_since(state.last_heartbeat)
+        .num_seconds()
+        .max(0);
+    let fresh = age <= HEARTBEAT_STALE_SECONDS;
+    (
+        if fresh {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        },
+        LivenessReport {
+            status: if fresh { "ok" } else { "stale" },
+            pid: Some(state.pid),
+            started_at: Some(state.started_at.to_rfc3339()),
+            last_heartbeat: Some(state.last_heartbeat.to_rfc3339()),
+            heartbeat_age_secs: Some(age),
+            error: None,
+        },
+    )
+}
+
+/// Readiness verdict: the scheduler is running and the database answers.
+pub fn readiness_report(
+    scheduler_running: bool,
+    database: bool,
+    schedules: usize,
+) -> (StatusCode, ReadinessReport) {
+    let ready = scheduler_running && database;
+    (
+        if ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        },
+        ReadinessReport {
+            status: if ready { "ready" } else { "not_ready" },
+            scheduler_running,
+            database,
+            schedules,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn state(last_heartbeat: DateTime<Utc>) -> SchedulerState {
+        SchedulerState {
+            started_at: last_heartbeat - Duration::seconds(600),
+            pid: 4242,
+            last_heartbeat,
+        }
+    }
+
+    #[test]
+    fn test_liveness_ok_while_heartbeat_fresh() {
+        let now = Utc::now();
+        let (status, report) = liveness_report(Ok(Some(state(now - Duration::seconds(10)))), now);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report.status, "ok");
+        assert_eq!(report.pid, Some(4242));
+        assert_eq!(report.heartbeat_age_secs, Some(10));
+    }
+
+    #[test]
+    fn test_liveness_fails_on_stale_or_missing_heartbeat() {
+        let now = Utc::now();
+        let stale = now - Duration::seconds(HEARTBEAT_STALE_SECONDS + 1);
+        let (status, report) = liveness_report(Ok(Some(state(stale))), now);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(report.status, "stale");
+
+        let (status, report) = liveness_report(Ok(None), now);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(report.status, "unavailable");
+
+        let (status, report) = liveness_report(Err("database is locked".to_string()), now);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(report.error.as_deref(), Some("database is locked"));
+    }
+
+    #[test]
+    fn test_readiness_requires_scheduler_and_database() {
+        assert_eq!(readiness_report(true, true, 3).0, StatusCode::OK);
+        assert_eq!(
+            readiness_report(false, true, 3).0,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        let (status, report) = readiness_report(true, false, 0);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(report.status, "not_ready");
+    }
+}
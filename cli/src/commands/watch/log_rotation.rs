@@ -0,0 +1,289 @@
+//! Size- and time-based rotation for autopilot component logs.
+//!
+//! [`RotatingFileWriter`] always appends to `<log_dir>/<name>` so `tail -F`
+//! and `stakpak autopilot logs` keep working on a stable path. When the file
+//! grows past the size limit or its rotation period ends, it is moved aside to
+//! `<name>.<timestamp>` and a fresh file is started; only the newest
+//! `max_files` rotated files are kept.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Time-based rotation schedule, aligned to UTC hour/day boundaries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    /// Only rotate by size.
+    Never,
+    Hourly,
+    /// Start a new file every day, the default behavior.
+    #[default]
+    Daily,
+}
+
+impl LogRotation {
+    fn period_seconds(&self) -> Option<u64> {
+        match self {
+            LogRotation::Never => None,
+            LogRotation::Hourly => Some(60 * 60),
+            LogRotation::Daily => Some(24 * 60 * 60),
+        }
+    }
+
+    /// Index of the rotation period containing `time`, `None` when time-based
+    /// rotation is disabled.
+    fn period_index(&self, time: SystemTime) -> Option<u64> {
+        let period = self.period_seconds()?;
+        let secs = time
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Some(secs / period)
+    }
+}
+
+impl std::fmt::Display for LogRotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogRotation::Never => write!(f, "never"),
+            LogRotation::Hourly => write!(f, "hourly"),
+            LogRotation::Daily => write!(f, "daily"),
+        }
+    }
+}
+
+/// When to rotate a log file and how many rotated files to keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogRotationPolicy {
+    /// Rotate once the file would grow past this size. `None` disables size-based rotation.
+    pub max_bytes: Option<u64>,
+    pub rotation: LogRotation,
+    /// Rotated files kept per log; older ones are deleted.
+    pub max_files: usize,
+}
+
+/// Append-only log file that rotates itself according to a [`LogRotationPolicy`].
+#[derive(Debug)]
+pub struct RotatingFileWriter {
+    dir: PathBuf,
+    file_name: String,
+    policy: LogRotationPolicy,
+    file: File,
+    size: u64,
+    period: Option<u64>,
+}
+
+impl RotatingFileWriter {
+    /// Open (or create) `dir/file_name` for appending.
+    pub fn open(dir: &Path, file_name: &str, policy: LogRotationPolicy) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(file_name);
+        let file = open_append(&path)?;
+        let metadata = file.metadata()?;
+        // An existing file belongs to the period it was last written in, so a
+        // log left over from yesterday is rotated on the first write today.
+        let last_written = if metadata.len() > 0 {
+            metadata.modified().unwrap_or_else(|_| SystemTime::now())
+        } else {
+            SystemTime::now()
+        };
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file_name: file_name.to_string(),
+            policy,
+            file,
+            size: metadata.len(),
+            period: policy.rotation.period_index(last_written),
+        })
+    }
+
+    fn active_path(&self) -> PathBuf {
+        self.dir.join(&self.file_name)
+    }
+
+    fn should_rotate(&self, incoming: usize, now: SystemTime) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+        let over_size = self
+            .policy
+            .max_bytes
+            .is_some_and(|max| self.size.saturating_add(incoming as u64) > max);
+        over_size || self.policy.rotation.period_index(now) != self.period
+    }
+
+    fn rotate(&mut self, now: SystemTime) -> io::Result<()> {
+        self.file.flush()?;
+
+        let timestamp = DateTime::<Utc>::from(now).format("%Y%m%dT%H%M%SZ");
+        let mut rotated = self.dir.join(format!("{}.{}", self.file_name, timestamp));
+        let mut suffix = 1;
+        while rotated.exists() {
+            rotated = self
+                .dir
+                .join(format!("{}.{}-{}", self.file_name, timestamp, suffix));
+            suffix += 1;
+        }
+        fs::rename(self.active_path(), &rotated)?;
+
+        self.file = open_append(&self.active_path())?;
+        self.size = 0;
+        self.period = self.policy.rotation.period_index(now);
+        self.prune()
+    }
+
+    /// Delete the oldest rotated files beyond `max_files`.
+    fn prune(&self) -> io::Result<()> {
+        let prefix = format!("{}.", self.file_name);
+        let mut rotated: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .map(|entry| entry.path())
+            .collect();
+        // Timestamps are fixed-width, so name order is age order.
+        rotated.sort();
+
+        let excess = rotated.len().saturating_sub(self.policy.max_files);
+        for path in rotated.iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn write_at(&mut self, buf: &[u8], now: SystemTime) -> io::Result<usize> {
+        // A failed rotation (e.g. a permissions problem in the log dir) must
+        // not drop log lines, so keep appending to the current file instead.
+        if self.should_rotate(buf.len(), now) && self.rotate(now).is_err() {
+            self.period = self.policy.rotation.period_index(now);
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_at(buf, SystemTime::now())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn rotated_files(dir: &Path, file_name: &str) -> Vec<String> {
+        let prefix = format!("{}.", file_name);
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .expect("read log dir")
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with(&prefix))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_rotates_by_size_and_prunes_old_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let policy = LogRotationPolicy {
+            max_bytes: Some(10),
+            rotation: LogRotation::Never,
+            max_files: 2,
+        };
+        let mut writer =
+            RotatingFileWriter::open(dir.path(), "scheduler.log", policy).expect("open");
+        let now = SystemTime::now();
+
+        for line in ["first-line\n", "second-line\n", "third-line\n", "last\n"] {
+            writer.write_at(line.as_bytes(), now).expect("write");
+        }
+        writer.flush().expect("flush");
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join("scheduler.log")).expect("read active"),
+            "last\n"
+        );
+        let rotated = rotated_files(dir.path(), "scheduler.log");
+        assert_eq!(rotated.len(), 2);
+        assert_eq!(
+            fs::read_to_string(dir.path().join(&rotated[0])).expect("read rotated"),
+            "second-line\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join(&rotated[1])).expect("read rotated"),
+            "third-line\n"
+        );
+    }
+
+    #[test]
+    fn test_rotates_when_period_changes() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let policy = LogRotationPolicy {
+            max_bytes: None,
+            rotation: LogRotation::Hourly,
+            max_files: 5,
+        };
+        let mut writer = RotatingFileWriter::open(dir.path(), "server.log", policy).expect("open");
+        let now = SystemTime::now();
+
+        writer.write_at(b"this hour\n", now).expect("write");
+        writer.write_at(b"still this hour\n", now).expect("write");
+        assert!(rotated_files(dir.path(), "server.log").is_empty());
+
+        writer
+            .write_at(b"next hour\n", now + Duration::from_secs(60 * 60))
+            .expect("write");
+        writer.flush().expect("flush");
+
+        let rotated = rotated_files(dir.path(), "server.log");
+        assert_eq!(rotated.len(), 1);
+        assert_eq!(
+            fs::read_to_string(dir.path().join(&rotated[0])).expect("read rotated"),
+            "this hour\nstill this hour\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("server.log")).expect("read active"),
+            "next hour\n"
+        );
+    }
+
+    #[test]
+    fn test_reopen_appends_to_existing_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let policy = LogRotationPolicy {
+            max_bytes: Some(1024),
+            rotation: LogRotation::Never,
+            max_files: 1,
+        };
+        {
+            let mut writer =
+                RotatingFileWriter::open(dir.path(), "gateway.log", policy).expect("open");
+            writer.write_all(b"before restart\n").expect("write");
+        }
+        let mut writer = RotatingFileWriter::open(dir.path(), "gateway.log", policy).expect("open");
+        writer.write_all(b"after restart\n").expect("write");
+        writer.flush().expect("flush");
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join("gateway.log")).expect("read active"),
+            "before restart\nafter restart\n"
+        );
+        assert!(rotated_files(dir.path(), "gateway.log").is_empty());
+    }
+}
@@ -4,19 +4,33 @@
 //! with scheduled tasks, check scripts, and automatic agent invocation.
 #![allow(dead_code)]
 mod agent;
+mod artifacts;
+mod blackout;
+mod budget;
 pub mod commands;
+mod concurrency;
 pub mod config;
 mod db;
 mod executor;
+mod health;
+mod log_rotation;
+mod notification_template;
 mod prompt;
 mod reconciler;
+mod redaction;
+pub mod retention;
 mod scheduler;
 mod utils;
+mod webhook;
 
 pub use agent::{AgentServerConnection, SpawnConfig, spawn_agent};
-pub use config::{DeliveryConfig, InteractionMode, Schedule, ScheduleConfig};
-pub use db::{INTERACTIVE_DELEGATED_NOTE, ListRunsFilter, RELOAD_SENTINEL, RunStatus, ScheduleDb};
-pub use executor::{CheckResult, run_check_script};
-pub use prompt::{assemble_prompt, build_schedule_caller_context};
+pub use config::{DeliveryConfig, InteractionMode, Schedule, ScheduleConfig, expand_tilde};
+pub use db::{
+    INTERACTIVE_DELEGATED_NOTE, ListRunsFilter, RELOAD_SENTINEL, RunStatus, ScheduleDb,
+    ScheduleRun, describe_database_url,
+};
+pub use executor::{CheckOutput, CheckResult, CheckSpec, run_check};
+pub use log_rotation::{LogRotationPolicy, RotatingFileWriter};
+pub use prompt::{assemble_prompt, build_schedule_caller_context, render_dry_run};
 pub use scheduler::Scheduler;
 pub use utils::is_process_running;
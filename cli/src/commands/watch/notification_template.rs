@@ -0,0 +1,188 @@
+//! User-configurable notification text.
+//!
+//! Templates are plain strings with `{{variable}}` placeholders, set globally
+//! as `[notifications] template` and per schedule as `notify_template`:
+//! ```toml
+//! [notifications]
+//! template = "{{schedule}} {{status}}: {{summary}}\n{{run_url}}"
+//! ```
+//! Available variables are listed in [`TEMPLATE_VARIABLES`]; variables with no
+//! value (e.g. `check_output` for a schedule without a check) render empty.
+//! Unknown variables are rejected when the config is loaded.
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Variables a template may reference.
+pub const TEMPLATE_VARIABLES: &[&str] =
+    &["schedule", "status", "summary", "check_output", "run_url"];
+
+/// A validated notification template, serialized back as its source text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct NotificationTemplate {
+    source: String,
+}
+
+/// Values substituted into a [`NotificationTemplate`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TemplateContext<'a> {
+    pub schedule: &'a str,
+    pub status: &'a str,
+    pub summary: &'a str,
+    pub check_output: &'a str,
+    pub run_url: &'a str,
+}
+
+impl TemplateContext<'_> {
+    fn get(&self, variable: &str) -> Option<&str> {
+        match variable {
+            "schedule" => Some(self.schedule),
+            "status" => Some(self.status),
+            "summary" => Some(self.summary),
+            "check_output" => Some(self.check_output),
+            "run_url" => Some(self.run_url),
+            _ => None,
+        }
+    }
+}
+
+impl NotificationTemplate {
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Render the template, trimming surrounding whitespace left by empty variables.
+    pub fn render(&self, context: &TemplateContext<'_>) -> String {
+        // Placeholders were validated on parse, so rendering cannot fail.
+        expand(&self.source, context)
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+}
+
+/// Substitute every `{{variable}}` in `source`.
+fn expand(source: &str, context: &TemplateContext<'_>) -> Result<String, String> {
+    let mut output = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some((before, after)) = rest.split_once("{{") {
+        output.push_str(before);
+        let (variable, tail) = after
+            .split_once("}}")
+            .ok_or_else(|| "unclosed '{{'".to_string())?;
+        let variable = variable.trim();
+        let value = context.get(variable).ok_or_else(|| {
+            format!(
+                "unknown variable '{}' (available: {})",
+                variable,
+                TEMPLATE_VARIABLES.join(", ")
+            )
+        })?;
+        output.push_str(value);
+        rest = tail;
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+impl std::fmt::Display for NotificationTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl FromStr for NotificationTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err("notification template cannot be empty".to_string());
+        }
+        expand(s, &TemplateContext::default())
+            .map_err(|reason| format!("invalid notification template: {}", reason))?;
+        Ok(Self {
+            source: s.to_string(),
+        })
+    }
+}
+
+impl TryFrom<String> for NotificationTemplate {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<NotificationTemplate> for String {
+    fn from(template: NotificationTemplate) -> Self {
+        template.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(source: &str) -> NotificationTemplate {
+        source.parse().expect("valid template")
+    }
+
+    #[test]
+    fn test_render_substitutes_all_variables() {
+        let context = TemplateContext {
+            schedule: "disk-check",
+            status: "failed",
+            summary: "Disk 95% full",
+            check_output: "/dev/sda1 95%",
+            run_url: "https://ops.example.com/runs/42",
+        };
+
+        assert_eq!(
+            template("{{schedule}} {{ status }}: {{summary}} ({{check_output}}) {{run_url}}")
+                .render(&context),
+            "disk-check failed: Disk 95% full (/dev/sda1 95%) https://ops.example.com/runs/42"
+        );
+        assert_eq!(
+            template("{{summary}}\n\n{{run_url}}").render(&TemplateContext {
+                summary: "done",
+                ..Default::default()
+            }),
+            "done"
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates_are_rejected() {
+        for source in [
+            "",
+            "  ",
+            "{{schedule",
+            "{{unknown}}",
+            "{{schedule}} {{ Status }}",
+        ] {
+            assert!(
+                source.parse::<NotificationTemplate>().is_err(),
+                "{source:?}"
+            );
+        }
+        assert!(
+            "no variables at all"
+                .parse::<NotificationTemplate>()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_serializes_as_source_text() {
+        let parsed: Vec<NotificationTemplate> =
+            serde_json::from_str(r#"["{{schedule}}: {{status}}"]"#).expect("deserialize");
+        assert_eq!(parsed[0].as_str(), "{{schedule}}: {{status}}");
+        assert_eq!(
+            serde_json::to_string(&parsed).expect("serialize"),
+            r#"["{{schedule}}: {{status}}"]"#
+        );
+        assert!(serde_json::from_str::<Vec<NotificationTemplate>>(r#"["{{nope}}"]"#).is_err());
+    }
+}
@@ -6,7 +6,8 @@
 //! compact metadata fallback in the user prompt so runs remain debuggable if
 //! structured context is unavailable.
 
-use crate::commands::watch::{CheckResult, Schedule};
+use crate::commands::watch::config::ScheduleDefaults;
+use crate::commands::watch::{CheckResult, CheckSpec, InteractionMode, Schedule};
 use stakpak_gateway::client::CallerContextInput;
 use stakpak_shared::utils::truncate_chars_with_ellipsis;
 
@@ -23,16 +24,38 @@ fn build_schedule_metadata_lines(
     lines.push(format!("Cron: {}", schedule.cron));
 
     if let Some(result) = check_result
-        && let Some(check_path) = &schedule.check
+        && let Some(check) = &schedule.check
     {
-        lines.push(format!("Check script: {}", check_path));
+        match check {
+            CheckSpec::Script(path) => lines.push(format!("Check script: {}", path)),
+            CheckSpec::Probe(probe) => lines.push(format!("Check probe: {}", probe)),
+        }
         lines.push(format!(
             "Check exit code: {}",
             result.exit_code.unwrap_or(-1)
         ));
 
         let stdout = result.stdout.trim();
-        if !stdout.is_empty() {
+        if let Some(output) = result.output() {
+            // Contract fields replace raw stdout so the agent gets them as-is.
+            if let Some(trigger) = output.trigger {
+                lines.push(format!("Check trigger: {}", trigger));
+            }
+            if let Some(severity) = &output.severity {
+                lines.push(format!("Check severity: {}", severity));
+            }
+            if let Some(summary) = &output.summary {
+                lines.push(format!("Check summary: {}", summary));
+            }
+            if let Some(context) = &output.context {
+                let context =
+                    serde_json::to_string_pretty(context).unwrap_or_else(|_| context.to_string());
+                lines.push(format!(
+                    "Check context (JSON):\n{}",
+                    truncate_chars_with_ellipsis(&context, stream_chars_limit)
+                ));
+            }
+        } else if !stdout.is_empty() {
             lines.push(format!(
                 "Check stdout:\n{}",
                 truncate_chars_with_ellipsis(stdout, stream_chars_limit)
@@ -95,17 +118,76 @@ pub fn build_schedule_caller_context(
     }]
 }
 
+/// Describe what a run would send to the agent without spawning it: the
+/// assembled prompt, the structured caller context and the spawn settings.
+pub fn render_dry_run(
+    schedule: &Schedule,
+    defaults: &ScheduleDefaults,
+    check_result: Option<&CheckResult>,
+) -> String {
+    let mut out = String::from("Assembled prompt:\n---\n");
+    out.push_str(&assemble_prompt(schedule, check_result));
+    out.push_str("\n---\n\nStructured caller context:\n");
+    for entry in build_schedule_caller_context(schedule, check_result) {
+        out.push_str(&format!("- name: {}\n", entry.name));
+        if let Some(priority) = entry.priority {
+            out.push_str(&format!("  priority: {}\n", priority));
+        }
+        out.push_str("  content:\n");
+        for line in entry.content.lines() {
+            out.push_str(&format!("    {}\n", line));
+        }
+    }
+
+    let interaction = match schedule.interaction {
+        InteractionMode::Interactive => "interactive",
+        InteractionMode::Silent => "silent",
+    };
+    out.push_str("\nSpawn configuration:\n");
+    for (key, value) in [
+        ("profile", schedule.effective_profile(defaults).to_string()),
+        (
+            "timeout",
+            format!("{}s", schedule.effective_timeout(defaults).as_secs()),
+        ),
+        (
+            "max_turns",
+            schedule
+                .max_turns
+                .map_or_else(|| "profile default".to_string(), |turns| turns.to_string()),
+        ),
+        ("interaction", interaction.to_string()),
+        ("sandbox", schedule.effective_sandbox(defaults).to_string()),
+        (
+            "pause_on_approval",
+            schedule.effective_pause_on_approval(defaults).to_string(),
+        ),
+        (
+            "enable_subagents",
+            schedule.effective_enable_subagents(defaults).to_string(),
+        ),
+        (
+            "enable_slack_tools",
+            schedule.effective_enable_slack_tools(defaults).to_string(),
+        ),
+    ] {
+        out.push_str(&format!("  {}: {}\n", key, value));
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::commands::watch::InteractionMode;
     use std::time::Duration;
 
     fn full_schedule() -> Schedule {
         Schedule {
             name: "disk-cleanup".to_string(),
             cron: "*/15 * * * *".to_string(),
-            check: Some("~/.stakpak/schedules/check-disk.sh".to_string()),
+            check: Some(CheckSpec::Script(
+                "~/.stakpak/schedules/check-disk.sh".to_string(),
+            )),
             check_timeout: Some(Duration::from_secs(30)),
             trigger_on: None,
             prompt: "Analyze disk usage and safely free up space.".to_string(),
@@ -117,12 +199,25 @@ mod tests {
             enable_subagents: None,
             pause_on_approval: None,
             sandbox: None,
+            workdir: None,
+            env: Default::default(),
+            env_file: None,
             notify_on: None,
             notify_channel: None,
             notify_target: None,
             notify_chat_id: None,
             legacy_channel: None,
             interaction: InteractionMode::Interactive,
+            concurrency_group: None,
+            timezone: None,
+            jitter: None,
+            blackout: Vec::new(),
+            budget: Default::default(),
+            redact: Vec::new(),
+            notify_routes: Vec::new(),
+            notify_template: None,
+            on_complete_webhook: None,
+            dry_run: false,
             enabled: true,
         }
     }
@@ -166,6 +261,43 @@ mod tests {
         assert!(context[0].content.contains("Board: board_abc123"));
     }
 
+    #[test]
+    fn structured_check_output_replaces_raw_stdout() {
+        let schedule = full_schedule();
+        let check_result = check_result_with_stdout(
+            r#"{"trigger": true, "severity": "high", "summary": "disk 95% full", "context": {"mount": "/var"}}"#,
+        );
+
+        let context = build_schedule_caller_context(&schedule, Some(&check_result));
+        let content = &context[0].content;
+        assert!(content.contains("Check trigger: true"));
+        assert!(content.contains("Check severity: high"));
+        assert!(content.contains("Check summary: disk 95% full"));
+        assert!(content.contains("Check context (JSON):\n{\n  \"mount\": \"/var\"\n}"));
+        assert!(!content.contains("Check stdout:"));
+
+        let prompt = assemble_prompt(&schedule, Some(&check_result));
+        assert!(prompt.contains("Check severity: high"));
+    }
+
+    #[test]
+    fn render_dry_run_includes_prompt_context_and_spawn_configuration() {
+        let mut schedule = full_schedule();
+        schedule.max_turns = Some(12);
+        schedule.sandbox = Some(true);
+        let check_result = check_result_with_stdout("disk usage 92%");
+
+        let report = render_dry_run(&schedule, &ScheduleDefaults::default(), Some(&check_result));
+        assert!(report.contains("Assembled prompt:\n---\nAnalyze disk usage"));
+        assert!(report.contains("- name: watch_schedule_context"));
+        assert!(report.contains("    Check stdout:"));
+        assert!(report.contains("Spawn configuration:\n  profile: infrastructure\n"));
+        assert!(report.contains("  timeout: 1800s\n"));
+        assert!(report.contains("  max_turns: 12\n"));
+        assert!(report.contains("  interaction: interactive\n"));
+        assert!(report.contains("  sandbox: true\n"));
+    }
+
     #[test]
     fn build_schedule_caller_context_omits_empty_streams() {
         let schedule = full_schedule();
@@ -214,12 +346,25 @@ mod tests {
             enable_subagents: None,
             pause_on_approval: None,
             sandbox: None,
+            workdir: None,
+            env: Default::default(),
+            env_file: None,
             notify_on: None,
             notify_channel: None,
             notify_target: None,
             notify_chat_id: None,
             legacy_channel: None,
             interaction: InteractionMode::Interactive,
+            concurrency_group: None,
+            timezone: None,
+            jitter: None,
+            blackout: Vec::new(),
+            budget: Default::default(),
+            redact: Vec::new(),
+            notify_routes: Vec::new(),
+            notify_template: None,
+            on_complete_webhook: None,
+            dry_run: false,
             enabled: true,
         };
 
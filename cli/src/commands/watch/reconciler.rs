@@ -189,12 +189,25 @@ mod tests {
             enable_subagents: None,
             pause_on_approval: None,
             sandbox: None,
+            workdir: None,
+            env: Default::default(),
+            env_file: None,
             notify_on: None,
             notify_channel: None,
             notify_target: None,
             notify_chat_id: None,
             legacy_channel: None,
             interaction: crate::commands::watch::InteractionMode::Interactive,
+            concurrency_group: None,
+            timezone: None,
+            jitter: None,
+            blackout: Vec::new(),
+            budget: Default::default(),
+            redact: Vec::new(),
+            notify_routes: Vec::new(),
+            notify_template: None,
+            on_complete_webhook: None,
+            dry_run: false,
             enabled: true,
         }
     }
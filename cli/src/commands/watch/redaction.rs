@@ -0,0 +1,39 @@
+//! Redaction of check and agent output.
+//!
+//! Schedules can list extra regexes (internal hostnames, customer IDs, ...)
+//! under `redact`. Matches are replaced with [`REDACTED`], and anything the
+//! shared redactor detects (`[settings.redaction]` plus built-in secret
+//! detectors) with its placeholder, wherever output leaves the run: the run
+//! database, artifacts, notifications and logs. The agent itself still sees
+//! the original check output.
+
+pub use stakpak_shared::secrets::redaction::RedactionPattern;
+
+/// Apply the schedule's `patterns`, then the shared redactor, to `text`.
+pub fn redact(text: &str, patterns: &[RedactionPattern]) -> String {
+    let redacted = stakpak_shared::secrets::redaction::redact(text, patterns);
+    stakpak_shared::secrets::redaction::redact_output(&redacted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stakpak_shared::secrets::redaction::REDACTED;
+
+    #[test]
+    fn test_applies_schedule_patterns_and_builtin_detectors() {
+        let patterns: Vec<RedactionPattern> = vec!["cust_[0-9]{6}".parse().expect("pattern")];
+        let redacted = redact(
+            "cust_123456 leaked postgres://app:hunter2@db/app",
+            &patterns,
+        );
+
+        assert!(redacted.starts_with(REDACTED), "{redacted}");
+        assert!(!redacted.contains("cust_123456"), "{redacted}");
+        assert!(!redacted.contains("hunter2"), "{redacted}");
+        assert!(
+            redacted.contains("[REDACTED:connection_string:"),
+            "{redacted}"
+        );
+    }
+}
@@ -0,0 +1,634 @@
+//! Retention policy for local data.
+//!
+//! `[retention]` in `autopilot.toml` bounds four categories of local data by
+//! age and size:
+//!
+//! - `sessions`: session transcripts in the local session store
+//!   (`~/.stakpak/data/local.db`). Sessions kept by the Stakpak API are not
+//!   touched.
+//! - `runs`: schedule run records and their artifact directories. Running and
+//!   paused runs are never removed.
+//! - `gateway`: channel routing entries in the gateway store.
+//! - `logs`: rotated autopilot component logs (scheduler, server, gateway),
+//!   which are the audit trail of what autopilot did. The active log files
+//!   are never removed.
+//!
+//! Entries older than a category's `max_age` are removed first, then the
+//! oldest remaining entries until the category fits `max_size_mb`. The most
+//! recent entry is never removed by the size limit. Autopilot applies the
+//! policy hourly; `stakpak retention apply` applies it on demand.
+
+use super::config::ScheduleConfig;
+use super::db::{RunStatus, ScheduleDb, describe_database_url};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use stakpak_api::local::storage::LocalStorage;
+use stakpak_gateway::{GatewayCliFlags, GatewayConfig, GatewayStore};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Age and size limits for one category. `None` (or zero) disables a limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryRetention {
+    /// Remove entries whose last activity is older than this (e.g. "90d").
+    #[serde(default, with = "super::config::option_humantime_serde")]
+    pub max_age: Option<Duration>,
+
+    /// Remove the oldest entries once the category exceeds this many megabytes.
+    #[serde(default)]
+    pub max_size_mb: Option<u64>,
+}
+
+impl CategoryRetention {
+    pub fn is_enabled(&self) -> bool {
+        self.max_age().is_some() || self.max_bytes().is_some()
+    }
+
+    fn max_age(&self) -> Option<Duration> {
+        self.max_age.filter(|age| !age.is_zero())
+    }
+
+    fn max_bytes(&self) -> Option<u64> {
+        self.max_size_mb
+            .filter(|mb| *mb > 0)
+            .map(|mb| mb.saturating_mul(1024 * 1024))
+    }
+}
+
+/// The `[retention]` section. Every category is unbounded by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetentionSettings {
+    #[serde(default)]
+    pub sessions: CategoryRetention,
+    #[serde(default)]
+    pub runs: CategoryRetention,
+    #[serde(default)]
+    pub gateway: CategoryRetention,
+    #[serde(default)]
+    pub logs: CategoryRetention,
+}
+
+impl RetentionSettings {
+    pub fn is_enabled(&self) -> bool {
+        RetentionCategory::ALL
+            .iter()
+            .any(|category| self.policy(*category).is_enabled())
+    }
+
+    pub fn policy(&self, category: RetentionCategory) -> CategoryRetention {
+        match category {
+            RetentionCategory::Sessions => self.sessions,
+            RetentionCategory::Runs => self.runs,
+            RetentionCategory::Gateway => self.gateway,
+            RetentionCategory::Logs => self.logs,
+        }
+    }
+}
+
+/// A kind of local data covered by the retention policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionCategory {
+    /// Session transcripts in the local session store
+    Sessions,
+    /// Schedule run records and their artifacts
+    Runs,
+    /// Gateway channel routing entries
+    Gateway,
+    /// Rotated autopilot component logs
+    Logs,
+}
+
+impl RetentionCategory {
+    pub const ALL: [RetentionCategory; 4] = [
+        RetentionCategory::Sessions,
+        RetentionCategory::Runs,
+        RetentionCategory::Gateway,
+        RetentionCategory::Logs,
+    ];
+}
+
+impl std::fmt::Display for RetentionCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            RetentionCategory::Sessions => "sessions",
+            RetentionCategory::Runs => "runs",
+            RetentionCategory::Gateway => "gateway",
+            RetentionCategory::Logs => "logs",
+        })
+    }
+}
+
+/// Where each category's data lives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionTargets {
+    pub sessions_db: PathBuf,
+    pub runs_db: PathBuf,
+    /// Postgres database holding run history instead of `runs_db`.
+    pub runs_database_url: Option<String>,
+    pub gateway_db: PathBuf,
+    pub log_dir: PathBuf,
+}
+
+impl RetentionTargets {
+    /// Resolve data locations from the autopilot config at `config_path`.
+    pub fn resolve(config: &ScheduleConfig, config_path: &Path) -> Self {
+        let gateway_db = GatewayConfig::load_unvalidated(config_path, &GatewayCliFlags::default())
+            .unwrap_or_default()
+            .gateway
+            .store_path;
+
+        Self {
+            sessions_db: PathBuf::from(stakpak_api::client::default_store_path()),
+            runs_db: config.db_path(),
+            runs_database_url: config.watch.database_url.clone(),
+            gateway_db,
+            log_dir: config.log_dir(),
+        }
+    }
+
+    /// Where a category's data lives, for status output.
+    pub fn describe(&self, category: RetentionCategory) -> String {
+        match (category, &self.runs_database_url) {
+            (RetentionCategory::Runs, Some(url)) => describe_database_url(url),
+            _ => self.location(category).display().to_string(),
+        }
+    }
+
+    pub fn location(&self, category: RetentionCategory) -> &Path {
+        match category {
+            RetentionCategory::Sessions => &self.sessions_db,
+            RetentionCategory::Runs => &self.runs_db,
+            RetentionCategory::Gateway => &self.gateway_db,
+            RetentionCategory::Logs => &self.log_dir,
+        }
+    }
+}
+
+/// How much data a category holds and what its policy would remove now.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CategoryStatus {
+    pub category: RetentionCategory,
+    pub location: String,
+    pub policy: CategoryRetention,
+    pub entries: usize,
+    pub bytes: u64,
+    pub oldest: Option<DateTime<Utc>>,
+    pub expired_entries: usize,
+    pub expired_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// What applying a category's policy removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CategoryCleanup {
+    pub category: RetentionCategory,
+    pub removed: usize,
+    pub freed_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Report usage for every category.
+pub async fn status(
+    settings: &RetentionSettings,
+    targets: &RetentionTargets,
+    now: DateTime<Utc>,
+) -> Vec<CategoryStatus> {
+    let mut statuses = Vec::with_capacity(RetentionCategory::ALL.len());
+    for category in RetentionCategory::ALL {
+        let policy = settings.policy(category);
+        let mut status = CategoryStatus {
+            category,
+            location: targets.describe(category),
+            policy,
+            entries: 0,
+            bytes: 0,
+            oldest: None,
+            expired_entries: 0,
+            expired_bytes: 0,
+            error: None,
+        };
+        match collect(category, targets).await {
+            Ok(entries) => {
+                let expired = select_expired(&entries, &policy, now);
+                status.entries = entries.len();
+                status.bytes = entries.iter().map(|entry| entry.bytes).sum();
+                status.oldest = entries.iter().map(|entry| entry.last_activity).min();
+                status.expired_entries = expired.len();
+                status.expired_bytes = expired.iter().map(|index| entries[*index].bytes).sum();
+            }
+            Err(error) => status.error = Some(error),
+        }
+        statuses.push(status);
+    }
+    statuses
+}
+
+/// Remove whatever exceeds the policy in each of `categories`.
+pub async fn apply(
+    settings: &RetentionSettings,
+    targets: &RetentionTargets,
+    categories: &[RetentionCategory],
+    now: DateTime<Utc>,
+) -> Vec<CategoryCleanup> {
+    let mut cleanups = Vec::with_capacity(categories.len());
+    for category in categories {
+        let mut cleanup = CategoryCleanup {
+            category: *category,
+            removed: 0,
+            freed_bytes: 0,
+            error: None,
+        };
+        let policy = settings.policy(*category);
+        if policy.is_enabled() {
+            let result = match collect(*category, targets).await {
+                Ok(entries) => {
+                    let expired: Vec<Entry> = select_expired(&entries, &policy, now)
+                        .into_iter()
+                        .map(|index| entries[index].clone())
+                        .collect();
+                    remove(*category, targets, &expired).await
+                }
+                Err(error) => Err(error),
+            };
+            match result {
+                Ok((removed, freed_bytes)) => {
+                    cleanup.removed = removed;
+                    cleanup.freed_bytes = freed_bytes;
+                }
+                Err(error) => cleanup.error = Some(error),
+            }
+        }
+        cleanups.push(cleanup);
+    }
+    cleanups
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum EntryKey {
+    Session(Uuid),
+    Run {
+        id: i64,
+        artifacts_dir: Option<PathBuf>,
+    },
+    GatewaySession(String),
+    LogFile(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    key: EntryKey,
+    last_activity: DateTime<Utc>,
+    bytes: u64,
+    /// Still in use; never removed.
+    protected: bool,
+}
+
+/// Indexes of the `entries` the policy removes, oldest first.
+fn select_expired(entries: &[Entry], policy: &CategoryRetention, now: DateTime<Utc>) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by_key(|index| entries[*index].last_activity);
+    let mut expired = vec![false; entries.len()];
+
+    let cutoff = policy
+        .max_age()
+        .and_then(|age| chrono::Duration::from_std(age).ok())
+        .and_then(|age| now.checked_sub_signed(age));
+    if let Some(cutoff) = cutoff {
+        for index in &order {
+            let entry = &entries[*index];
+            if !entry.protected && entry.last_activity < cutoff {
+                expired[*index] = true;
+            }
+        }
+    }
+
+    if let Some(max_bytes) = policy.max_bytes() {
+        let mut total: u64 = entries
+            .iter()
+            .zip(&expired)
+            .filter(|(_, expired)| !**expired)
+            .map(|(entry, _)| entry.bytes)
+            .sum();
+        let newest = order.last().copied();
+        for index in &order {
+            if total <= max_bytes {
+                break;
+            }
+            if expired[*index] || entries[*index].protected || Some(*index) == newest {
+                continue;
+            }
+            expired[*index] = true;
+            total = total.saturating_sub(entries[*index].bytes);
+        }
+    }
+
+    order.into_iter().filter(|index| expired[*index]).collect()
+}
+
+async fn collect(
+    category: RetentionCategory,
+    targets: &RetentionTargets,
+) -> Result<Vec<Entry>, String> {
+    let location = targets.location(category);
+    // Reporting on data that was never created must not create it.
+    let remote = category == RetentionCategory::Runs && targets.runs_database_url.is_some();
+    if !remote && !location.exists() {
+        return Ok(Vec::new());
+    }
+
+    match category {
+        RetentionCategory::Sessions => {
+            let footprints = open_sessions(location)
+                .await?
+                .session_footprints()
+                .await
+                .map_err(|e| format!("Failed to list sessions: {}", e))?;
+            Ok(footprints
+                .into_iter()
+                .map(|footprint| Entry {
+                    key: EntryKey::Session(footprint.session_id),
+                    last_activity: footprint.last_activity,
+                    bytes: footprint.bytes,
+                    protected: false,
+                })
+                .collect())
+        }
+        RetentionCategory::Runs => {
+            let footprints = open_runs(targets)
+                .await?
+                .run_footprints()
+                .await
+                .map_err(|e| format!("Failed to list runs: {}", e))?;
+            Ok(footprints
+                .into_iter()
+                .map(|footprint| {
+                    let artifacts_dir = footprint.artifacts_dir.map(PathBuf::from);
+                    let artifacts_bytes = artifacts_dir
+                        .as_deref()
+                        .filter(|dir| dir.is_dir())
+                        .and_then(|dir| super::artifacts::dir_usage(dir).ok())
+                        .map(|(size, _)| size)
+                        .unwrap_or_default();
+                    Entry {
+                        key: EntryKey::Run {
+                            id: footprint.id,
+                            artifacts_dir,
+                        },
+                        last_activity: footprint.created_at,
+                        bytes: footprint.bytes + artifacts_bytes,
+                        protected: matches!(
+                            footprint.status,
+                            RunStatus::Running | RunStatus::Paused
+                        ),
+                    }
+                })
+                .collect())
+        }
+        RetentionCategory::Gateway => {
+            let footprints = open_gateway(location)
+                .await?
+                .session_footprints()
+                .await
+                .map_err(|e| format!("Failed to list gateway sessions: {}", e))?;
+            Ok(footprints
+                .into_iter()
+                .map(|footprint| Entry {
+                    key: EntryKey::GatewaySession(footprint.routing_key),
+                    last_activity: DateTime::from_timestamp_millis(footprint.updated_at)
+                        .unwrap_or_default(),
+                    bytes: footprint.bytes,
+                    protected: false,
+                })
+                .collect())
+        }
+        RetentionCategory::Logs => list_log_files(location)
+            .map_err(|e| format!("Failed to list {}: {}", location.display(), e)),
+    }
+}
+
+/// Delete `entries`, returning how many were removed and the bytes they held.
+async fn remove(
+    category: RetentionCategory,
+    targets: &RetentionTargets,
+    entries: &[Entry],
+) -> Result<(usize, u64), String> {
+    if entries.is_empty() {
+        return Ok((0, 0));
+    }
+    let freed_bytes = entries.iter().map(|entry| entry.bytes).sum();
+    let location = targets.location(category);
+
+    match category {
+        RetentionCategory::Sessions => {
+            let ids: Vec<Uuid> = entries
+                .iter()
+                .filter_map(|entry| match entry.key {
+                    EntryKey::Session(id) => Some(id),
+                    _ => None,
+                })
+                .collect();
+            open_sessions(location)
+                .await?
+                .purge_sessions(&ids)
+                .await
+                .map_err(|e| format!("Failed to delete sessions: {}", e))?;
+        }
+        RetentionCategory::Runs => {
+            let ids: Vec<i64> = entries
+                .iter()
+                .filter_map(|entry| match entry.key {
+                    EntryKey::Run { id, .. } => Some(id),
+                    _ => None,
+                })
+                .collect();
+            open_runs(targets)
+                .await?
+                .delete_runs(&ids)
+                .await
+                .map_err(|e| format!("Failed to delete runs: {}", e))?;
+            for entry in entries {
+                if let EntryKey::Run {
+                    artifacts_dir: Some(dir),
+                    ..
+                } = &entry.key
+                    && dir.is_dir()
+                {
+                    fs::remove_dir_all(dir).map_err(|e| {
+                        format!("Failed to remove artifacts {}: {}", dir.display(), e)
+                    })?;
+                }
+            }
+        }
+        RetentionCategory::Gateway => {
+            let store = open_gateway(location).await?;
+            for entry in entries {
+                if let EntryKey::GatewaySession(routing_key) = &entry.key {
+                    store
+                        .delete(routing_key)
+                        .await
+                        .map_err(|e| format!("Failed to delete gateway session: {}", e))?;
+                }
+            }
+        }
+        RetentionCategory::Logs => {
+            for entry in entries {
+                if let EntryKey::LogFile(path) = &entry.key {
+                    fs::remove_file(path)
+                        .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+                }
+            }
+        }
+    }
+
+    Ok((entries.len(), freed_bytes))
+}
+
+async fn open_sessions(path: &Path) -> Result<LocalStorage, String> {
+    LocalStorage::new(&path.to_string_lossy())
+        .await
+        .map_err(|e| format!("Failed to open session store: {}", e))
+}
+
+async fn open_runs(targets: &RetentionTargets) -> Result<ScheduleDb, String> {
+    match &targets.runs_database_url {
+        Some(url) => ScheduleDb::connect(url).await,
+        None => ScheduleDb::new(&targets.runs_db.to_string_lossy()).await,
+    }
+    .map_err(|e| format!("Failed to open database: {}", e))
+}
+
+async fn open_gateway(path: &Path) -> Result<GatewayStore, String> {
+    GatewayStore::open(path)
+        .await
+        .map_err(|e| format!("Failed to open gateway store: {}", e))
+}
+
+/// Files in the log directory. Rotated files are named `<name>.log.<timestamp>`;
+/// the active `<name>.log` files are still being written and are protected.
+fn list_log_files(dir: &Path) -> io::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        entries.push(Entry {
+            key: EntryKey::LogFile(entry.path()),
+            last_activity: metadata.modified()?.into(),
+            bytes: metadata.len(),
+            protected: !name.contains(".log."),
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn entry(days_old: i64, bytes: u64, now: DateTime<Utc>) -> Entry {
+        Entry {
+            key: EntryKey::LogFile(PathBuf::from(format!("{days_old}.log.1"))),
+            last_activity: now - chrono::Duration::days(days_old),
+            bytes,
+            protected: false,
+        }
+    }
+
+    #[test]
+    fn select_expired_applies_age_then_size_oldest_first() {
+        let now = Utc::now();
+        let entries = vec![
+            entry(5, MB, now),
+            entry(40, MB, now),
+            entry(10, MB, now),
+            entry(1, MB, now),
+        ];
+        let policy = CategoryRetention {
+            max_age: Some(Duration::from_secs(30 * 24 * 60 * 60)),
+            max_size_mb: Some(2),
+        };
+
+        // The 40-day-old entry is past max_age; the 10-day-old one is then
+        // the oldest left over the 2 MB limit.
+        assert_eq!(select_expired(&entries, &policy, now), vec![1, 2]);
+        assert!(select_expired(&entries, &CategoryRetention::default(), now).is_empty());
+    }
+
+    #[test]
+    fn select_expired_keeps_protected_and_newest_entries() {
+        let now = Utc::now();
+        let mut entries = vec![entry(90, 3 * MB, now), entry(60, 3 * MB, now)];
+        entries[0].protected = true;
+        let policy = CategoryRetention {
+            max_age: Some(Duration::from_secs(24 * 60 * 60)),
+            max_size_mb: Some(1),
+        };
+
+        assert_eq!(select_expired(&entries, &policy, now), vec![1]);
+
+        let policy = CategoryRetention {
+            max_age: None,
+            max_size_mb: Some(1),
+        };
+        assert!(select_expired(&entries[1..], &policy, now).is_empty());
+    }
+
+    #[tokio::test]
+    async fn apply_removes_expired_rotated_logs_only() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let log_dir = root.path().join("logs");
+        fs::create_dir_all(&log_dir).expect("create log dir");
+        let old = SystemTime::now() - Duration::from_secs(10 * 24 * 60 * 60);
+        for name in ["scheduler.log", "scheduler.log.20260101T000000Z"] {
+            let path = log_dir.join(name);
+            fs::write(&path, "line\n").expect("write log");
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(old))
+                .expect("set mtime");
+        }
+
+        let targets = RetentionTargets {
+            sessions_db: root.path().join("local.db"),
+            runs_db: root.path().join("autopilot.db"),
+            runs_database_url: None,
+            gateway_db: root.path().join("gateway.db"),
+            log_dir: log_dir.clone(),
+        };
+        let settings = RetentionSettings {
+            logs: CategoryRetention {
+                max_age: Some(Duration::from_secs(7 * 24 * 60 * 60)),
+                max_size_mb: None,
+            },
+            ..Default::default()
+        };
+
+        let cleanups = apply(&settings, &targets, &RetentionCategory::ALL, Utc::now()).await;
+        let logs = cleanups
+            .iter()
+            .find(|cleanup| cleanup.category == RetentionCategory::Logs)
+            .expect("logs cleanup");
+        assert_eq!(logs.removed, 1);
+        assert_eq!(logs.freed_bytes, 5);
+        assert!(log_dir.join("scheduler.log").exists());
+        assert!(!log_dir.join("scheduler.log.20260101T000000Z").exists());
+        // Categories without data are left untouched, not created.
+        assert!(!targets.sessions_db.exists());
+        assert!(!targets.gateway_db.exists());
+    }
+}
@@ -3,7 +3,11 @@
 //! Uses tokio-cron-scheduler to schedule and execute schedules based on cron expressions.
 //! Users provide standard 5-part cron expressions (min hour day month weekday),
 //! which are converted internally to 6-part format (with seconds) for the scheduler.
+//! Expressions are evaluated in the schedule's `timezone` (UTC by default), and a
+//! schedule's `jitter` delays each fire by a random amount up to that duration.
 
+use rand::Rng;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio_cron_scheduler::{Job, JobScheduler, JobSchedulerError};
 use tracing::{debug, error, info};
@@ -25,6 +29,14 @@ fn to_six_part_cron(expr: &str) -> String {
     }
 }
 
+/// Random delay in `[0, jitter)` applied before a schedule's event is sent.
+fn jitter_delay(jitter: Option<Duration>) -> Duration {
+    match jitter {
+        Some(jitter) if !jitter.is_zero() => rand::rng().random_range(Duration::ZERO..jitter),
+        _ => Duration::ZERO,
+    }
+}
+
 /// Message sent when a schedule fires.
 #[derive(Debug, Clone)]
 pub struct SchedulerEvent {
@@ -96,23 +108,29 @@ impl Scheduler {
         let schedule_name = schedule.name.clone();
         let cron_expr = schedule.cron.clone();
         let schedule_6part = to_six_part_cron(&cron_expr);
+        let timezone = schedule.effective_timezone();
         let event_tx = self.event_tx.clone();
         let schedule_clone = schedule.clone();
 
         info!(
             schedule = %schedule_name,
             cron = %cron_expr,
+            timezone = %timezone,
             "Registering schedule with scheduler"
         );
 
         // Create the job with the 6-part cron schedule
-        let job = Job::new_async(schedule_6part.as_str(), move |_uuid, _lock| {
+        let job = Job::new_async_tz(schedule_6part.as_str(), timezone, move |_uuid, _lock| {
             let schedule_name = schedule_name.clone();
             let schedule = schedule_clone.clone();
             let tx = event_tx.clone();
 
             Box::pin(async move {
-                debug!(schedule = %schedule_name, "Schedule fired");
+                let delay = jitter_delay(schedule.jitter);
+                debug!(schedule = %schedule_name, delay_ms = delay.as_millis(), "Schedule fired");
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
 
                 let event = SchedulerEvent { schedule };
 
@@ -228,12 +246,25 @@ mod tests {
             enable_subagents: None,
             pause_on_approval: None,
             sandbox: None,
+            workdir: None,
+            env: Default::default(),
+            env_file: None,
             notify_on: None,
             notify_channel: None,
             notify_target: None,
             notify_chat_id: None,
             legacy_channel: None,
             interaction: crate::commands::watch::InteractionMode::Interactive,
+            concurrency_group: None,
+            timezone: None,
+            jitter: None,
+            blackout: Vec::new(),
+            budget: Default::default(),
+            redact: Vec::new(),
+            notify_routes: Vec::new(),
+            notify_template: None,
+            on_complete_webhook: None,
+            dry_run: false,
             enabled: true,
         }
     }
@@ -278,6 +309,36 @@ mod tests {
         assert_eq!(scheduler.job_count(), 3);
     }
 
+    #[tokio::test]
+    async fn test_register_schedule_in_timezone() {
+        let (mut scheduler, _rx) = Scheduler::new().await.expect("Failed to create scheduler");
+
+        // Asia/Tokyo has no DST, so 09:00 local is always 00:00 UTC.
+        let mut schedule = create_test_schedule("tokyo-morning", "0 9 * * *");
+        schedule.timezone = Some("Asia/Tokyo".to_string());
+        let job_id = scheduler
+            .register_schedule(schedule)
+            .await
+            .expect("Failed to register schedule");
+
+        let next = scheduler
+            .next_tick_for_job(job_id)
+            .await
+            .expect("next tick")
+            .expect("scheduled");
+        assert_eq!(next.format("%H:%M").to_string(), "00:00");
+    }
+
+    #[test]
+    fn test_jitter_delay_is_bounded() {
+        assert_eq!(jitter_delay(None), Duration::ZERO);
+        assert_eq!(jitter_delay(Some(Duration::ZERO)), Duration::ZERO);
+        let jitter = Duration::from_secs(120);
+        for _ in 0..100 {
+            assert!(jitter_delay(Some(jitter)) < jitter);
+        }
+    }
+
     #[tokio::test]
     async fn test_invalid_cron_expression() {
         let (mut scheduler, _rx) = Scheduler::new().await.expect("Failed to create scheduler");
@@ -0,0 +1,233 @@
+//! Run result webhooks.
+//!
+//! A schedule with `on_complete_webhook` gets a JSON summary of each finished
+//! agent run POSTed to that URL, so external systems (Grafana annotations,
+//! ticketing) can react to outcomes without polling the schedule database.
+//! Delivery is retried with exponential backoff on network errors, 429 and
+//! 5xx responses; other client errors are not retried.
+
+use crate::commands::watch::ScheduleRun;
+use serde::Serialize;
+use stakpak_shared::utils::truncate_chars_with_ellipsis;
+use std::time::Duration;
+
+/// Maximum characters of agent output included in the payload.
+const WEBHOOK_OUTPUT_CHARS: usize = 4_000;
+/// Delivery attempts before giving up.
+const WEBHOOK_MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; doubled for each further retry.
+const WEBHOOK_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// JSON body POSTed to `on_complete_webhook`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunWebhookPayload {
+    pub schedule: String,
+    pub run_id: i64,
+    pub status: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub duration_secs: Option<i64>,
+    pub session_id: Option<String>,
+    pub checkpoint_id: Option<String>,
+    pub output: Option<String>,
+    pub error: Option<String>,
+    /// Stable code of a failed run's error, e.g. `tool_execution`
+    pub error_code: Option<&'static str>,
+}
+
+impl RunWebhookPayload {
+    pub fn from_run(run: &ScheduleRun) -> Self {
+        Self {
+            schedule: run.schedule_name.clone(),
+            run_id: run.id,
+            status: run.status.to_string(),
+            started_at: run.started_at.to_rfc3339(),
+            finished_at: run.finished_at.map(|finished| finished.to_rfc3339()),
+            duration_secs: run
+                .finished_at
+                .map(|finished| (finished - run.started_at).num_seconds()),
+            session_id: run.agent_session_id.clone(),
+            checkpoint_id: run.agent_last_checkpoint_id.clone(),
+            output: run
+                .agent_stdout
+                .as_deref()
+                .map(str::trim)
+                .filter(|output| !output.is_empty())
+                .map(|output| truncate_chars_with_ellipsis(output, WEBHOOK_OUTPUT_CHARS)),
+            error: run.error_text(),
+            error_code: run.error().map(|error| error.code()),
+        }
+    }
+}
+
+/// POST `payload` to `url`, retrying transient failures.
+pub async fn send_run_webhook(
+    client: &reqwest::Client,
+    url: &str,
+    payload: &RunWebhookPayload,
+) -> Result<(), String> {
+    post_with_retries(
+        client,
+        url,
+        payload,
+        WEBHOOK_MAX_ATTEMPTS,
+        WEBHOOK_RETRY_DELAY,
+    )
+    .await
+}
+
+async fn post_with_retries(
+    client: &reqwest::Client,
+    url: &str,
+    payload: &RunWebhookPayload,
+    max_attempts: u32,
+    retry_delay: Duration,
+) -> Result<(), String> {
+    let mut delay = retry_delay;
+    let mut attempt = 1;
+    loop {
+        let error = match client.post(url).json(payload).send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                let error = format!("webhook returned {}", status);
+                if !(status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS) {
+                    return Err(error);
+                }
+                error
+            }
+            Err(error) => format!("webhook request failed: {}", error),
+        };
+
+        if attempt >= max_attempts {
+            return Err(format!("{} (after {} attempts)", error, attempt));
+        }
+        tokio::time::sleep(delay).await;
+        delay = delay.saturating_mul(2);
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::watch::RunStatus;
+    use chrono::{TimeZone, Utc};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn finished_run() -> ScheduleRun {
+        let started_at = Utc
+            .with_ymd_and_hms(2026, 10, 14, 9, 0, 0)
+            .single()
+            .expect("valid timestamp");
+        ScheduleRun {
+            id: 7,
+            schedule_name: "disk-check".to_string(),
+            started_at,
+            finished_at: Some(started_at + chrono::Duration::seconds(95)),
+            check_exit_code: Some(0),
+            check_stdout: None,
+            check_stderr: None,
+            check_timed_out: false,
+            agent_woken: true,
+            interactive_delegated: false,
+            agent_session_id: Some("session-1".to_string()),
+            agent_last_checkpoint_id: Some("checkpoint-1".to_string()),
+            agent_stdout: Some("x".repeat(WEBHOOK_OUTPUT_CHARS + 10)),
+            agent_stderr: None,
+            artifacts_dir: None,
+            total_tokens: None,
+            estimated_cost: None,
+            status: RunStatus::Completed,
+            error_message: None,
+            created_at: started_at,
+        }
+    }
+
+    #[test]
+    fn test_payload_from_run() {
+        let payload = RunWebhookPayload::from_run(&finished_run());
+        assert_eq!(payload.schedule, "disk-check");
+        assert_eq!(payload.status, "completed");
+        assert_eq!(payload.duration_secs, Some(95));
+        assert_eq!(payload.session_id.as_deref(), Some("session-1"));
+        assert_eq!(payload.checkpoint_id.as_deref(), Some("checkpoint-1"));
+        let output = payload.output.expect("output should be set");
+        assert_eq!(output.chars().count(), WEBHOOK_OUTPUT_CHARS + 3);
+        assert!(output.ends_with("..."));
+        assert_eq!(payload.error, None);
+        assert_eq!(payload.error_code, None);
+    }
+
+    #[test]
+    fn test_payload_splits_error_code_from_message() {
+        let mut run = finished_run();
+        run.status = RunStatus::Failed;
+        run.error_message = Some("[auth] Agent exited with code Some(4)".to_string());
+        let payload = RunWebhookPayload::from_run(&run);
+        assert_eq!(
+            payload.error.as_deref(),
+            Some("Agent exited with code Some(4)")
+        );
+        assert_eq!(payload.error_code, Some("auth"));
+
+        // Records from before error codes are classified from their text
+        run.error_message = Some("Check script error: exit 3".to_string());
+        let payload = RunWebhookPayload::from_run(&run);
+        assert_eq!(payload.error.as_deref(), Some("Check script error: exit 3"));
+        assert_eq!(payload.error_code, Some("tool_execution"));
+    }
+
+    /// Serve one canned HTTP response per accepted connection.
+    async fn serve(responses: Vec<&'static str>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind listener");
+        let addr = listener.local_addr().expect("local addr");
+        tokio::spawn(async move {
+            for status_line in responses {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    status_line
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{}/hook", addr)
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors_until_success() {
+        let url = serve(vec!["503 Service Unavailable", "200 OK"]).await;
+        let payload = RunWebhookPayload::from_run(&finished_run());
+        let result = post_with_retries(
+            &reqwest::Client::new(),
+            &url,
+            &payload,
+            3,
+            Duration::from_millis(10),
+        )
+        .await;
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_client_errors() {
+        let url = serve(vec!["404 Not Found", "200 OK"]).await;
+        let payload = RunWebhookPayload::from_run(&finished_run());
+        let result = post_with_retries(
+            &reqwest::Client::new(),
+            &url,
+            &payload,
+            3,
+            Duration::from_millis(10),
+        )
+        .await;
+        assert_eq!(result, Err("webhook returned 404 Not Found".to_string()));
+    }
+}
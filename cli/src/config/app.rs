@@ -16,7 +16,7 @@ use super::profile::{ProfileConfig, SubagentConfig};
 use super::rulebook::RulebookConfig;
 use super::types::{OldAppConfig, ProviderType, Settings};
 use super::warden::WardenConfig;
-use super::{STAKPAK_API_ENDPOINT, STAKPAK_CONFIG_PATH};
+use super::{LOCAL_PROVIDER, STAKPAK_API_ENDPOINT, STAKPAK_CONFIG_PATH};
 
 /// The main application configuration, built from config file and environment.
 #[derive(Clone, Debug)]
@@ -61,8 +61,20 @@ pub struct AppConfig {
     pub collect_telemetry: Option<bool>,
     /// Editor command
     pub editor: Option<String>,
+    /// Whether the TUI starts in accessibility mode
+    pub accessibility: Option<bool>,
+    /// TUI color scheme from `[settings.theme]`
+    pub theme: Option<stakpak_tui::services::theme::ThemeConfig>,
+    /// Attention notifications from `[settings.notifications]`
+    pub notifications: Option<stakpak_tui::services::notifications::NotificationConfig>,
+    /// Discovery probe settings from `[settings.discovery]`
+    pub discovery: Option<super::DiscoveryConfig>,
+    /// Output redaction settings from `[settings.redaction]`
+    pub redaction: Option<stakpak_shared::secrets::redaction::RedactionConfig>,
     /// Recently used model IDs (most recent first)
     pub recent_models: Vec<String>,
+    /// Local OpenAI-compatible endpoint for offline mode
+    pub local_endpoint: Option<String>,
 }
 
 impl AppConfig {
@@ -173,7 +185,15 @@ impl AppConfig {
             anonymous_id: settings.anonymous_id,
             collect_telemetry: settings.collect_telemetry,
             editor: settings.editor,
+            accessibility: settings.accessibility,
+            theme: settings.theme,
+            notifications: settings.notifications,
+            discovery: settings.discovery,
+            redaction: settings.redaction,
             recent_models: profile_config.recent_models,
+            local_endpoint: std::env::var("STAKPAK_LOCAL_ENDPOINT")
+                .ok()
+                .or(profile_config.local_endpoint),
         }
     }
 
@@ -803,6 +823,9 @@ impl AppConfig {
 
     /// Build LLMProviderConfig from the app configuration.
     pub fn get_llm_provider_config(&self) -> LLMProviderConfig {
+        if let Some(local) = self.local_provider_config() {
+            return local;
+        }
         let mut config = LLMProviderConfig::new();
 
         self.add_custom_providers(&mut config);
@@ -818,6 +841,9 @@ impl AppConfig {
 
     /// Build LLMProviderConfig from the app configuration (async version with OAuth refresh).
     pub async fn get_llm_provider_config_async(&self) -> LLMProviderConfig {
+        if let Some(local) = self.local_provider_config() {
+            return local;
+        }
         let mut config = LLMProviderConfig::new();
 
         self.add_custom_providers(&mut config);
@@ -831,9 +857,69 @@ impl AppConfig {
         config
     }
 
+    /// The endpoint all model traffic goes to in offline mode, if enabled.
+    pub fn offline_endpoint(&self) -> Option<&str> {
+        self.local_endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|endpoint| !endpoint.is_empty())
+    }
+
+    /// Offline mode: models are served by `local_endpoint` and the hosted
+    /// Stakpak API (auth, rulebooks, telemetry, update checks) is not used.
+    pub fn is_offline(&self) -> bool {
+        self.offline_endpoint().is_some()
+    }
+
+    /// Whether anonymous telemetry may be sent.
+    pub fn telemetry_enabled(&self) -> bool {
+        !self.is_offline() && self.collect_telemetry.unwrap_or(true)
+    }
+
+    /// In offline mode, the single custom provider pointing at the local
+    /// endpoint. Its API key comes from a `local` provider entry, if any.
+    fn local_provider_config(&self) -> Option<LLMProviderConfig> {
+        let endpoint = self.offline_endpoint()?;
+        let api_key = match self.resolve_provider_auth(LOCAL_PROVIDER) {
+            Some(ProviderAuth::Api { key }) => Some(key),
+            _ => None,
+        };
+        let mut config = LLMProviderConfig::new();
+        config.add_provider(
+            LOCAL_PROVIDER,
+            ProviderConfig::Custom {
+                api_key,
+                api_endpoint: endpoint.to_string(),
+                auth: None,
+            },
+        );
+        Some(config)
+    }
+
+    /// Model name to request from the local endpoint: the CLI override, the
+    /// most recent `local/` model, or the profile model, in that order.
+    pub fn offline_model(&self, cli_override: Option<&str>) -> Option<String> {
+        let local_prefix = format!("{}/", LOCAL_PROVIDER);
+        let recent_local = self
+            .recent_models
+            .iter()
+            .find(|model| model.starts_with(&local_prefix))
+            .map(String::as_str);
+        cli_override
+            .or(recent_local)
+            .or(self.model.as_deref())
+            .map(|model| model.strip_prefix(&local_prefix).unwrap_or(model))
+            .map(str::trim)
+            .filter(|model| !model.is_empty())
+            .map(str::to_string)
+    }
+
     /// Get Stakpak API key with resolved credentials from auth.toml fallback chain.
-    /// Returns None if the API key is empty or not set.
+    /// Returns None if the API key is empty or not set, or in offline mode.
     pub fn get_stakpak_api_key(&self) -> Option<String> {
+        if self.is_offline() {
+            return None;
+        }
         if let Some(ref key) = self.api_key
             && !key.is_empty()
         {
@@ -851,6 +937,13 @@ impl AppConfig {
 
     /// Get auth display info for the TUI.
     pub fn get_auth_display_info(&self) -> (Option<String>, Option<String>, Option<String>) {
+        if self.is_offline() {
+            return (
+                Some("Offline".to_string()),
+                Some(LOCAL_PROVIDER.to_string()),
+                None,
+            );
+        }
         if matches!(self.provider, ProviderType::Remote) {
             return (None, None, None);
         }
@@ -910,7 +1003,16 @@ impl AppConfig {
     /// Searches the model catalog by ID. If the model string has a provider
     /// prefix (e.g., "anthropic/claude-opus-4-5"), it searches within that
     /// provider first. Otherwise, it searches all providers.
+    ///
+    /// In offline mode the model always comes from [`Self::offline_model`]
+    /// and is served by the local endpoint.
     pub fn get_default_model(&self, cli_override: Option<&str>) -> stakpak_api::Model {
+        if self.is_offline() {
+            return stakpak_api::Model::custom(
+                self.offline_model(cli_override).unwrap_or_default(),
+                LOCAL_PROVIDER,
+            );
+        }
         let has_stakpak_key = self.get_stakpak_api_key().is_some();
 
         // Priority: cli_override > recent_models[0] > model > default
@@ -990,6 +1092,11 @@ impl From<AppConfig> for Settings {
             anonymous_id: config.anonymous_id,
             collect_telemetry: config.collect_telemetry,
             editor: config.editor,
+            accessibility: config.accessibility,
+            theme: config.theme,
+            notifications: config.notifications,
+            discovery: config.discovery,
+            redaction: config.redaction,
         }
     }
 }
@@ -1010,6 +1117,7 @@ impl From<AppConfig> for ProfileConfig {
             recent_models: config.recent_models,
             system_prompt: config.system_prompt,
             max_turns: config.max_turns,
+            local_endpoint: config.local_endpoint,
             // Legacy fields - not used in new format
             openai: None,
             anthropic: None,
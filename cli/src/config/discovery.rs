@@ -0,0 +1,87 @@
+//! Discovery probe configuration (`[settings.discovery]`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// How long a session reuses the last discovery results for the same
+/// working directory, unless `cache_ttl_secs` says otherwise.
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Which discovery probes run, in what order and for how long.
+///
+/// ```toml
+/// [settings.discovery]
+/// disable = ["crontabs", "systemd"]
+/// order = ["toolchain", "git-repos"]
+/// timeout_secs = 60
+/// cache_ttl_secs = 600
+///
+/// [settings.discovery.timeouts]
+/// kubernetes = 15
+///
+/// [[settings.discovery.scripts]]
+/// name = "Internal Services"
+/// command = "~/bin/list-services"
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct DiscoveryConfig {
+    /// Only run these probes; all of them when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable: Option<Vec<String>>,
+    /// Probes to skip
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disable: Vec<String>,
+    /// Probes to run (and report) first, in this order; the rest follow
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<String>,
+    /// Default time limit for each probe, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Per-probe time limits, in seconds
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub timeouts: BTreeMap<String, u64>,
+    /// How long a session start reuses cached results, in seconds; 0
+    /// rescans every time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl_secs: Option<u64>,
+    /// User-defined probes whose stdout becomes a discovery section
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scripts: Vec<ScriptProbeConfig>,
+}
+
+/// A user-defined discovery probe.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScriptProbeConfig {
+    /// Section title; its lowercase, dash-separated form is the probe id
+    pub name: String,
+    /// Executable to run; a leading `~/` is expanded
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Time limit in seconds, overriding `timeout_secs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl DiscoveryConfig {
+    /// Maximum age of cached discovery results a session start may reuse.
+    pub fn cache_max_age(&self) -> Option<Duration> {
+        match self.cache_ttl_secs {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(DEFAULT_CACHE_TTL),
+        }
+    }
+
+    /// Apply `--discovery-probes` and `--discovery-skip`. Listing probes on
+    /// the command line replaces the configured selection and order.
+    pub fn with_cli_overrides(mut self, probes: Option<Vec<String>>, skip: Vec<String>) -> Self {
+        if let Some(probes) = probes {
+            self.order = probes.clone();
+            self.enable = Some(probes);
+        }
+        self.disable.extend(skip);
+        self
+    }
+}
@@ -29,6 +29,11 @@ impl Default for ConfigFile {
                 anonymous_id: Some(uuid::Uuid::new_v4().to_string()),
                 collect_telemetry: Some(true),
                 editor: Some("nano".to_string()),
+                accessibility: None,
+                theme: None,
+                notifications: None,
+                discovery: None,
+                redaction: None,
             },
         }
     }
@@ -48,6 +53,11 @@ impl ConfigFile {
                 anonymous_id: Some(uuid::Uuid::new_v4().to_string()),
                 collect_telemetry: Some(true),
                 editor: Some("nano".to_string()),
+                accessibility: None,
+                theme: None,
+                notifications: None,
+                discovery: None,
+                redaction: None,
             },
         }
     }
@@ -93,6 +103,11 @@ impl ConfigFile {
         let existing_anonymous_id = self.settings.anonymous_id.clone();
         let existing_collect_telemetry = self.settings.collect_telemetry;
         let existing_editor = self.settings.editor.clone();
+        let existing_accessibility = self.settings.accessibility;
+        let existing_theme = self.settings.theme.clone();
+        let existing_notifications = self.settings.notifications.clone();
+        let existing_discovery = self.settings.discovery.clone();
+        let existing_redaction = self.settings.redaction.clone();
 
         self.settings = Settings {
             machine_name: config.machine_name,
@@ -100,6 +115,11 @@ impl ConfigFile {
             anonymous_id: config.anonymous_id.or(existing_anonymous_id),
             collect_telemetry: config.collect_telemetry.or(existing_collect_telemetry),
             editor: config.editor.or(existing_editor),
+            accessibility: config.accessibility.or(existing_accessibility),
+            theme: config.theme.or(existing_theme),
+            notifications: config.notifications.or(existing_notifications),
+            discovery: config.discovery.or(existing_discovery),
+            redaction: config.redaction.or(existing_redaction),
         };
     }
 
@@ -5,10 +5,12 @@
 //! - Provider configurations (OpenAI, Anthropic, Gemini)
 //! - Rulebook filtering
 //! - Warden (runtime security) settings
+//! - Discovery probe selection
 //! - Authentication and credential resolution
 //! - Models cache from models.dev
 
 mod app;
+pub(crate) mod discovery;
 mod file;
 pub mod models_cache;
 pub(crate) mod openai_resolver;
@@ -23,6 +25,7 @@ mod tests;
 
 // Re-export public types
 pub use app::AppConfig;
+pub use discovery::{DiscoveryConfig, ScriptProbeConfig};
 pub use file::ConfigFile;
 pub use models_cache::ModelsCache;
 pub use profile::{ProfileConfig, format_recent_model_id};
@@ -39,3 +42,5 @@ pub use warden::WardenConfig;
 // Constants
 pub const STAKPAK_API_ENDPOINT: &str = "https://apiv2.stakpak.dev";
 pub const STAKPAK_CONFIG_PATH: &str = ".stakpak/config.toml";
+/// Provider name model IDs use in offline mode (`local/<model>`)
+pub const LOCAL_PROVIDER: &str = "local";
@@ -99,6 +99,12 @@ pub struct ProfileConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_turns: Option<usize>,
 
+    /// OpenAI-compatible endpoint (Ollama, vLLM, ...) that receives all model
+    /// traffic. Turns on offline mode: the hosted Stakpak API is never
+    /// called and features that need it are switched off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_endpoint: Option<String>,
+
     // =========================================================================
     // Legacy model fields - kept for backward compatibility during migration
     // These are read but deprecated (will migrate to 'model' field)
@@ -140,6 +146,7 @@ impl ProfileConfig {
                 recent_models: default.recent_models.clone(),
                 system_prompt: default.system_prompt.clone(),
                 max_turns: default.max_turns,
+                local_endpoint: default.local_endpoint.clone(),
                 // Enable warden for readonly sandboxed execution
                 warden: Some(WardenConfig::readonly_profile()),
                 // Don't copy allowed_tools/auto_approve - readonly has its own restrictions
@@ -437,6 +444,10 @@ impl ProfileConfig {
             max_turns: self
                 .max_turns
                 .or_else(|| other.and_then(|config| config.max_turns)),
+            local_endpoint: self
+                .local_endpoint
+                .clone()
+                .or_else(|| other.and_then(|config| config.local_endpoint.clone())),
             // Legacy fields - kept for reading only, not merged
             eco_model: None,
             smart_model: None,
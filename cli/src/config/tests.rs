@@ -66,7 +66,13 @@ fn sample_app_config(profile_name: &str) -> AppConfig {
         anonymous_id: Some("test-user-id".into()),
         collect_telemetry: Some(true),
         editor: Some("nano".into()),
+        accessibility: None,
+        theme: None,
+        notifications: None,
+        discovery: None,
+        redaction: None,
         recent_models: Vec::new(),
+        local_endpoint: None,
     }
 }
 
@@ -202,6 +208,11 @@ fn resolved_profile_config_merges_all_profile_defaults() {
             anonymous_id: Some("test-user-id".into()),
             collect_telemetry: Some(true),
             editor: Some("nano".into()),
+            accessibility: None,
+            theme: None,
+            notifications: None,
+            discovery: None,
+            redaction: None,
         },
     };
 
@@ -325,6 +336,137 @@ editor = "nano"
     );
 }
 
+#[test]
+fn config_file_parses_accessibility_setting() {
+    let parsed: ConfigFile = toml::from_str(
+        r#"
+[profiles.default]
+
+[settings]
+editor = "nano"
+accessibility = true
+"#,
+    )
+    .expect("parse config with accessibility setting");
+    assert_eq!(parsed.settings.accessibility, Some(true));
+
+    let serialized = toml::to_string(&ConfigFile::default()).expect("serialize default config");
+    assert!(!serialized.contains("accessibility"));
+}
+
+#[test]
+fn config_file_parses_theme_settings() {
+    let parsed: ConfigFile = toml::from_str(
+        r##"
+[profiles.default]
+
+[settings]
+editor = "nano"
+
+[settings.theme]
+scheme = "solarized"
+
+[settings.theme.colors]
+accent = "#d33682"
+"##,
+    )
+    .expect("parse config with theme settings");
+    let theme = parsed.settings.theme.clone().expect("theme settings");
+    assert_eq!(theme.scheme.as_deref(), Some("solarized"));
+    assert_eq!(
+        theme.colors.get("accent").map(String::as_str),
+        Some("#d33682")
+    );
+    assert!(stakpak_tui::services::theme::ColorScheme::from_config(&theme).is_ok());
+
+    let config = AppConfig::build(
+        "default",
+        PathBuf::from("/tmp/config.toml"),
+        parsed.settings,
+        Default::default(),
+    );
+    assert_eq!(config.theme, Some(theme));
+
+    let serialized = toml::to_string(&ConfigFile::default()).expect("serialize default config");
+    assert!(!serialized.contains("theme"));
+}
+
+#[test]
+fn config_file_parses_discovery_settings_and_cli_overrides() {
+    let parsed: ConfigFile = toml::from_str(
+        r#"
+[profiles.default]
+
+[settings]
+editor = "nano"
+
+[settings.discovery]
+disable = ["crontabs"]
+order = ["toolchain"]
+
+[settings.discovery.timeouts]
+kubernetes = 10
+
+[[settings.discovery.scripts]]
+name = "Internal Services"
+command = "~/bin/list-services"
+"#,
+    )
+    .expect("parse config with discovery settings");
+    let discovery = parsed.settings.discovery.expect("discovery settings");
+    assert_eq!(discovery.disable, vec!["crontabs".to_string()]);
+    assert_eq!(discovery.timeouts.get("kubernetes"), Some(&10));
+    assert_eq!(discovery.scripts[0].name, "Internal Services");
+    assert!(discovery.scripts[0].args.is_empty());
+
+    let overridden = discovery.with_cli_overrides(
+        Some(vec!["git-repos".to_string(), "iac".to_string()]),
+        vec!["environment".to_string()],
+    );
+    assert_eq!(
+        overridden.enable,
+        Some(vec!["git-repos".to_string(), "iac".to_string()])
+    );
+    assert_eq!(
+        overridden.order,
+        overridden.enable.clone().unwrap_or_default()
+    );
+    assert_eq!(
+        overridden.disable,
+        vec!["crontabs".to_string(), "environment".to_string()]
+    );
+
+    let serialized = toml::to_string(&ConfigFile::default()).expect("serialize default config");
+    assert!(!serialized.contains("discovery"));
+}
+
+#[test]
+fn config_file_parses_redaction_settings() {
+    let parsed: ConfigFile = toml::from_str(
+        r#"
+[profiles.default]
+
+[settings.redaction]
+patterns = ['cust_[0-9]{6}']
+allowlist = ['EXAMPLE$']
+"#,
+    )
+    .expect("parse config with redaction settings");
+    let redaction = parsed.settings.redaction.expect("redaction settings");
+    assert_eq!(redaction.patterns[0].as_str(), "cust_[0-9]{6}");
+    assert_eq!(redaction.allowlist[0].as_str(), "EXAMPLE$");
+
+    let invalid = toml::from_str::<ConfigFile>(
+        r#"
+[profiles.default]
+
+[settings.redaction]
+patterns = ['.*']
+"#,
+    );
+    assert!(invalid.is_err());
+}
+
 #[test]
 fn profile_validate_rejects_invalid_max_turns_and_prompt_size() {
     let min_turns = ProfileConfig {
@@ -558,7 +700,13 @@ fn save_writes_profile_and_settings() {
         anonymous_id: Some("test-user-id".into()),
         collect_telemetry: Some(true),
         editor: Some("nano".into()),
+        accessibility: None,
+        theme: None,
+        notifications: None,
+        discovery: None,
+        redaction: None,
         recent_models: Vec::new(),
+        local_endpoint: None,
     };
 
     config.save().unwrap();
@@ -1785,3 +1933,49 @@ api_key = "sk-litellm"
     let litellm = profile.providers.get("litellm").unwrap();
     assert_eq!(litellm.api_endpoint(), Some("http://localhost:4000/v1"));
 }
+
+#[test]
+fn test_offline_mode_routes_models_to_local_endpoint() {
+    let mut config = sample_app_config("offline");
+    config.providers.insert(
+        "anthropic".into(),
+        ProviderConfig::Anthropic {
+            api_key: Some("sk-ant".into()),
+            api_endpoint: None,
+            access_token: None,
+            auth: None,
+        },
+    );
+    config.model = Some("llama3".into());
+    config.recent_models = vec!["anthropic/claude-opus-4-5".into(), "local/qwen3".into()];
+    assert!(config.get_stakpak_api_key().is_some());
+
+    config.local_endpoint = Some("http://localhost:11434/v1".into());
+    assert!(config.is_offline());
+    assert_eq!(config.get_stakpak_api_key(), None);
+    assert!(!config.telemetry_enabled());
+
+    let providers = config.get_llm_provider_config();
+    assert_eq!(providers.providers.len(), 1);
+    assert!(matches!(
+        providers.providers.get("local"),
+        Some(ProviderConfig::Custom { api_endpoint, .. }) if api_endpoint == "http://localhost:11434/v1"
+    ));
+
+    // Cloud models in the recent list are skipped
+    let model = config.get_default_model(None);
+    assert_eq!(
+        (model.id.as_str(), model.provider.as_str()),
+        ("qwen3", "local")
+    );
+    assert_eq!(
+        config
+            .offline_model(Some("local/meta-llama/Llama-3-8B"))
+            .as_deref(),
+        Some("meta-llama/Llama-3-8B")
+    );
+
+    config.model = None;
+    config.recent_models.clear();
+    assert_eq!(config.offline_model(None), None);
+}
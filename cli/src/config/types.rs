@@ -27,6 +27,22 @@ pub struct Settings {
     pub collect_telemetry: Option<bool>,
     /// Preferred external editor (e.g. vim, nano, code)
     pub editor: Option<String>,
+    /// Start the TUI in accessibility mode (text status markers, high
+    /// contrast, no animations, larger click targets)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessibility: Option<bool>,
+    /// TUI color scheme and per-role color overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<stakpak_tui::services::theme::ThemeConfig>,
+    /// Terminal bell and desktop notifications when the agent needs attention
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notifications: Option<stakpak_tui::services::notifications::NotificationConfig>,
+    /// Discovery probe selection, order, timeouts and script probes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discovery: Option<super::DiscoveryConfig>,
+    /// Extra secret patterns and allowlist for output redaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<stakpak_shared::secrets::redaction::RedactionConfig>,
 }
 
 /// Legacy configuration format for migration purposes.
@@ -46,6 +62,11 @@ impl From<OldAppConfig> for Settings {
             anonymous_id: Some(uuid::Uuid::new_v4().to_string()),
             collect_telemetry: Some(true),
             editor: Some("nano".to_string()),
+            accessibility: None,
+            theme: None,
+            notifications: None,
+            discovery: None,
+            redaction: None,
         }
     }
 }
@@ -26,6 +26,8 @@ use stakpak_api::local::skills::{default_skill_directories, discover_skills};
 use stakpak_api::models::Skill;
 use stakpak_api::{AgentClient, AgentClientConfig, AgentProvider};
 use stakpak_mcp_server::EnabledToolsConfig;
+use stakpak_shared::error::StakpakError;
+use stakpak_tui::services::session_templates::SessionTemplate;
 use std::{
     env,
     ffi::OsString,
@@ -48,8 +50,11 @@ use commands::{
         run::{
             AsyncOutcome, OutputFormat, ResumeInput, RunAsyncConfig, RunInteractiveConfig,
             pause::EXIT_CODE_PAUSED,
+            renderer::{OutputRenderer, RunEvent},
         },
     },
+    run::RunApprovalMode,
+    sessions::{ResumeTarget, SessionsCommands},
 };
 use config::{AppConfig, ModelsCache};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -57,6 +62,7 @@ use utils::agent_context::AgentContext;
 use utils::agents_md::discover_agents_md;
 use utils::apps_md::discover_apps_md;
 use utils::check_update::check_update;
+use utils::discovery::ProbeRegistry;
 use utils::gitignore;
 use utils::local_context::analyze_local_context;
 
@@ -133,6 +139,14 @@ struct Cli {
     #[arg(short = 's', long = "session", conflicts_with = "checkpoint_id")]
     session_id: Option<String>,
 
+    /// Resume the last autosaved TUI session with its draft input, scroll position and open overlays
+    #[arg(
+        long = "restore-last",
+        default_value_t = false,
+        conflicts_with_all = ["checkpoint_id", "session_id", "print", "async"]
+    )]
+    restore_last: bool,
+
     /// Run the agent in a specific directory
     #[arg(short = 'w', long = "workdir")]
     workdir: Option<String>,
@@ -141,7 +155,7 @@ struct Cli {
     #[arg(long = "verbose", default_value_t = false)]
     verbose: bool,
 
-    /// Output format: json or text
+    /// Output format: json, jsonl or text
     #[arg(short = 'o', long = "output", default_value_t = OutputFormat::Text)]
     output_format: OutputFormat,
 
@@ -221,11 +235,19 @@ struct Cli {
     #[arg(long = "ignore-apps-md", default_value_t = false)]
     ignore_apps_md: bool,
 
+    /// Run only these discovery probes, in this order (comma-separated ids)
+    #[arg(long = "discovery-probes", value_delimiter = ',')]
+    discovery_probes: Option<Vec<String>>,
+
+    /// Skip these discovery probes (comma-separated ids)
+    #[arg(long = "discovery-skip", value_delimiter = ',')]
+    discovery_skip: Vec<String>,
+
     /// Show session stats and URL after async mode completes
     #[arg(long = "show-session-stats", default_value_t = false)]
     show_session_stats: bool,
 
-    /// Color theme: auto, dark, or light (default: auto)
+    /// Color theme: auto, dark, light, solarized, high-contrast, or custom (default: auto)
     #[arg(long = "theme", default_value = "auto")]
     theme: String,
 
@@ -253,6 +275,14 @@ struct Cli {
     #[arg(long = "model")]
     model: Option<String>,
 
+    /// Send all model traffic to a local OpenAI-compatible endpoint (e.g. http://localhost:11434/v1) and never call the hosted Stakpak API
+    #[arg(long = "local-endpoint", value_name = "URL")]
+    local_endpoint: Option<String>,
+
+    /// Start from a session template in .stakpak/templates or ~/.stakpak/templates
+    #[arg(long = "template")]
+    template: Option<String>,
+
     /// Prompt to run the agent
     prompt: Option<String>,
 
@@ -272,7 +302,17 @@ async fn main() {
         modified_args.push("list".to_string());
     }
 
-    let cli = if modified_args != args {
+    // The completion scripts call the hidden helper on every tab press. It is
+    // kept out of clap so generated scripts don't offer it, and skips config
+    // loading, onboarding and the rest of startup
+    if args.get(1).map(String::as_str) == Some(commands::completions::HELPER) {
+        let words = args.get(2..).unwrap_or_default();
+        let words = words.strip_prefix(&["--".to_string()]).unwrap_or(words);
+        let found = commands::completions::run_helper(words).await;
+        std::process::exit(if found { 0 } else { 1 });
+    }
+
+    let mut cli = if modified_args != args {
         Cli::parse_from(&modified_args)
     } else {
         Cli::parse()
@@ -304,8 +344,11 @@ async fn main() {
         .unwrap_or_else(|| "default".to_string());
 
     let config_result = AppConfig::load(&profile_name, cli.config_path.as_deref());
+    let offline =
+        cli.local_endpoint.is_some() || config_result.as_ref().is_ok_and(AppConfig::is_offline);
 
     if config_result.is_ok()
+        && !offline
         && should_spawn_auto_update(&cli, std::env::var("STAKPAK_SKIP_WARDEN").is_ok())
     {
         let _ = spawn_background_auto_update(&cli);
@@ -345,6 +388,62 @@ async fn main() {
                 }
             }
 
+            // Applied after the save above so the flag only affects this run
+            if let Some(endpoint) = &cli.local_endpoint {
+                config.local_endpoint = Some(endpoint.clone());
+            }
+
+            // Every output path (TUI, gateway, autopilot runs) shares this redactor
+            if let Some(redaction) = &config.redaction {
+                stakpak_shared::secrets::redaction::init(redaction);
+            }
+
+            // `stakpak run` is async mode with its own prompt, output and approval flags
+            let mut reject_on_approval = false;
+            if let Some(Commands::Run(run)) = cli
+                .command
+                .take_if(|command| matches!(command, Commands::Run(_)))
+            {
+                match run.read_prompt() {
+                    Ok(prompt) => cli.prompt = Some(prompt),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+                cli.r#async = true;
+                cli.prompt_file = None;
+                cli.output_format = run.output_format;
+                cli.max_steps = run.max_steps.or(cli.max_steps);
+                match run.approval {
+                    RunApprovalMode::Auto => {}
+                    RunApprovalMode::Policy => reject_on_approval = true,
+                    RunApprovalMode::Pause => cli.pause_on_approval = true,
+                }
+            }
+
+            // `stakpak sessions resume` opens the TUI like `--session`/`--checkpoint`
+            if let Some(Commands::Sessions(SessionsCommands::Resume { id, checkpoint })) =
+                cli.command.take_if(|command| {
+                    matches!(command, Commands::Sessions(SessionsCommands::Resume { .. }))
+                })
+            {
+                match commands::sessions::resume_target(&config, &id, checkpoint.as_deref()).await {
+                    Ok(ResumeTarget::Session(id)) => {
+                        cli.session_id = Some(id.to_string());
+                        cli.checkpoint_id = None;
+                    }
+                    Ok(ResumeTarget::Checkpoint(id)) => {
+                        cli.checkpoint_id = Some(id.to_string());
+                        cli.session_id = None;
+                    }
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
             // Run interactive/async agent when no subcommand or Init; otherwise run the subcommand
             if matches!(cli.command, None | Some(Commands::Init)) {
                 // Initialize theme detection early, before any color code runs (e.g. onboarding).
@@ -373,7 +472,10 @@ async fn main() {
                 let send_init_prompt_on_start = cli.command == Some(Commands::Init);
 
                 // Initialize models cache in background (fetch if missing/stale)
-                let cache_task = tokio::spawn(async {
+                let cache_task = tokio::spawn(async move {
+                    if offline {
+                        return;
+                    }
                     if let Err(e) = ModelsCache::get().await {
                         tracing::warn!("Failed to load models cache: {}", e);
                     }
@@ -402,7 +504,9 @@ async fn main() {
                 let providers = config.get_llm_provider_config_async().await;
 
                 // Create unified AgentClient - automatically routes through Stakpak when API key is present
-                let mut client_config = AgentClientConfig::new().with_providers(providers);
+                let mut client_config = AgentClientConfig::new()
+                    .with_providers(providers)
+                    .with_offline(config.is_offline());
 
                 if let Some(api_key) = config.get_stakpak_api_key() {
                     client_config = client_config.with_stakpak(
@@ -424,7 +528,12 @@ async fn main() {
 
                 let (api_result, update_result, rulebooks_result) = tokio::join!(
                     client.get_my_account(),
-                    check_update(&current_version),
+                    async {
+                        if offline {
+                            return Ok(());
+                        }
+                        check_update(&current_version).await
+                    },
                     async {
                         client_for_rulebooks
                             .list_rulebooks()
@@ -510,10 +619,12 @@ async fn main() {
                 let system_prompt = if let Some(system_prompt_file_path) = &cli.system_prompt_file {
                     match std::fs::read_to_string(system_prompt_file_path) {
                         Ok(content) => {
-                            println!(
-                                "📖 Reading system prompt from file: {}",
-                                system_prompt_file_path
-                            );
+                            if cli.output_format == OutputFormat::Text {
+                                println!(
+                                    "📖 Reading system prompt from file: {}",
+                                    system_prompt_file_path
+                                );
+                            }
                             Some(content.trim().to_string())
                         }
                         Err(e) => {
@@ -528,10 +639,33 @@ async fn main() {
                     None
                 };
 
+                let template = match cli.template.as_deref().map(SessionTemplate::load) {
+                    Some(Ok(template)) => Some(template),
+                    Some(Err(e)) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                    None => None,
+                };
+                let system_prompt = match &template {
+                    Some(template) => match template.system_prompt_addendum() {
+                        Ok(addendum) if addendum.is_empty() => system_prompt,
+                        Ok(addendum) => Some(match system_prompt {
+                            Some(system_prompt) => format!("{}\n\n{}", system_prompt, addendum),
+                            None => addendum,
+                        }),
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            std::process::exit(1);
+                        }
+                    },
+                    None => system_prompt,
+                };
+
                 let prompt = if let Some(prompt_file_path) = &cli.prompt_file {
                     match std::fs::read_to_string(prompt_file_path) {
                         Ok(content) => {
-                            if cli.output_format != OutputFormat::Json {
+                            if cli.output_format == OutputFormat::Text {
                                 println!("📖 Reading prompt from file: {}", prompt_file_path);
                             }
                             content.trim().to_string()
@@ -558,11 +692,41 @@ async fn main() {
                 // Ensure .stakpak is in .gitignore before running agent
                 let _ = gitignore::ensure_stakpak_in_gitignore(&config);
 
-                let allowed_tools = cli.allowed_tools.or_else(|| config.allowed_tools.clone());
-                let auto_approve = config.auto_approve.clone();
-                let default_model = config.get_default_model(cli.model.as_deref());
+                let template_model = template.as_ref().and_then(|t| t.model.clone());
+                let allowed_tools = cli
+                    .allowed_tools
+                    .or_else(|| template.as_ref().and_then(|t| t.allowed_tools.clone()))
+                    .or_else(|| config.allowed_tools.clone());
+                let auto_approve = template
+                    .as_ref()
+                    .and_then(|t| t.auto_approve.clone())
+                    .or_else(|| config.auto_approve.clone());
+                let model_override = cli.model.as_deref().or(template_model.as_deref());
+                if config.is_offline() && config.offline_model(model_override).is_none() {
+                    eprintln!(
+                        "Offline mode needs a model served by {}: pass --model <name> or set `model` in the profile",
+                        config.offline_endpoint().unwrap_or_default()
+                    );
+                    std::process::exit(1);
+                }
+                let default_model = config.get_default_model(model_override);
                 let checkpoint_id = cli.checkpoint_id.clone();
-                let session_id = cli.session_id.clone();
+                let restore_snapshot = if cli.restore_last {
+                    match stakpak_tui::services::autosave::load_last() {
+                        Ok(snapshot) => Some(snapshot),
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                } else {
+                    None
+                };
+                let session_id = cli.session_id.clone().or_else(|| {
+                    restore_snapshot
+                        .as_ref()
+                        .map(|snapshot| snapshot.session_id.clone())
+                });
 
                 let result = match use_async_mode {
                     // Async mode: run continuously until no more tool calls (or max_steps=1 for single-step)
@@ -579,7 +743,7 @@ async fn main() {
                                 privacy_mode: cli.privacy_mode,
                                 enable_subagents,
                                 max_steps,
-                                output_format: cli.output_format,
+                                output_format: cli.output_format.clone(),
                                 enable_mtls: !cli.disable_mcp_mtls,
                                 allowed_tools,
                                 system_prompt,
@@ -592,6 +756,7 @@ async fn main() {
                                 plan_feedback: cli.plan_feedback.clone(),
                                 plan_new: cli.plan_new,
                                 pause_on_approval: cli.pause_on_approval,
+                                reject_on_approval,
                                 show_session_stats: cli.show_session_stats,
                                 resume_input: if cli.approve.is_some()
                                     || cli.reject.is_some()
@@ -631,6 +796,13 @@ async fn main() {
                             Ok(AsyncOutcome::Failed { error }) => Err(error),
                             Err(e) => Err(e),
                         }
+                        .inspect_err(|error| {
+                            print!(
+                                "{}",
+                                OutputRenderer::new(cli.output_format.clone(), false)
+                                    .render_event(&RunEvent::error(error))
+                            );
+                        })
                     }
 
                     // Interactive mode: run in TUI
@@ -642,6 +814,25 @@ async fn main() {
                             _ => None, // "auto" or anything else = auto-detect
                         };
 
+                        let discovery_config = config
+                            .discovery
+                            .clone()
+                            .unwrap_or_default()
+                            .with_cli_overrides(cli.discovery_probes, cli.discovery_skip);
+                        let discovery = match ProbeRegistry::from_config(&discovery_config) {
+                            Ok(registry) => registry,
+                            Err(e) => {
+                                eprintln!("{}", e);
+                                std::process::exit(1);
+                            }
+                        };
+                        // `stakpak init` always rescans; it is what users run to refresh.
+                        let discovery_cache_max_age = if send_init_prompt_on_start {
+                            None
+                        } else {
+                            discovery_config.cache_max_age()
+                        };
+
                         agent::run::run_interactive(
                             config,
                             RunInteractiveConfig {
@@ -664,9 +855,15 @@ async fn main() {
                                 model: default_model,
                                 send_init_prompt_on_start,
                                 theme,
+                                color_scheme: (!cli.theme.eq_ignore_ascii_case("auto"))
+                                    .then(|| cli.theme.clone()),
+                                restore_snapshot,
+                                discovery,
+                                discovery_cache_max_age,
                             },
                         )
                         .await
+                        .map_err(StakpakError::from)
                     }
                 };
 
@@ -674,8 +871,7 @@ async fn main() {
                 cache_task.abort();
 
                 if let Err(e) = result {
-                    eprintln!("Ops! something went wrong: {}", e);
-                    std::process::exit(1);
+                    exit_with_error(e);
                 }
             } else if let Some(command) = cli.command {
                 // Run a specific subcommand (Account, Config, etc.)
@@ -686,10 +882,7 @@ async fn main() {
                 let _ = gitignore::ensure_stakpak_in_gitignore(&config);
                 match command.run(config).await {
                     Ok(_) => {}
-                    Err(e) => {
-                        eprintln!("Ops! something went wrong: {e}");
-                        std::process::exit(1);
-                    }
+                    Err(e) => exit_with_error(StakpakError::from(e)),
                 }
             }
         }
@@ -697,6 +890,13 @@ async fn main() {
     }
 }
 
+/// Report a failed command with its error code and hint, and exit with the
+/// code for its kind.
+fn exit_with_error(error: StakpakError) -> ! {
+    eprintln!("Ops! something went wrong: {}", error.render());
+    std::process::exit(error.exit_code());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1067,6 +1267,49 @@ mod tests {
         assert!(parsed.is_err());
     }
 
+    #[test]
+    fn cli_parses_restore_last_flag() {
+        let parsed = Cli::try_parse_from(["stakpak", "--restore-last"]);
+        assert!(parsed.is_ok_and(|cli| cli.restore_last));
+
+        assert!(Cli::try_parse_from(["stakpak", "--restore-last", "-s", "session-id"]).is_err());
+        assert!(Cli::try_parse_from(["stakpak", "--restore-last", "--async"]).is_err());
+    }
+
+    #[test]
+    fn cli_parses_headless_run_command() {
+        let parsed = Cli::try_parse_from(["stakpak", "--model", "m", "run", "fix the build"]);
+        match parsed.map(|cli| cli.command) {
+            Ok(Some(Commands::Run(run))) => {
+                assert_eq!(run.prompt, Some("fix the build".to_string()));
+                assert_eq!(run.output_format, OutputFormat::Jsonl);
+                assert_eq!(run.approval, RunApprovalMode::Policy);
+            }
+            _ => panic!("Expected run command"),
+        }
+
+        let parsed = Cli::try_parse_from([
+            "stakpak",
+            "run",
+            "--prompt-file",
+            "task.md",
+            "--approval",
+            "pause",
+            "-o",
+            "json",
+        ]);
+        match parsed.map(|cli| cli.command) {
+            Ok(Some(Commands::Run(run))) => {
+                assert_eq!(run.prompt_file, Some("task.md".to_string()));
+                assert_eq!(run.approval, RunApprovalMode::Pause);
+                assert_eq!(run.output_format, OutputFormat::Json);
+            }
+            _ => panic!("Expected run command"),
+        }
+
+        assert!(Cli::try_parse_from(["stakpak", "run", "x", "--prompt-file", "task.md"]).is_err());
+    }
+
     #[test]
     fn cli_parses_up_alias_foreground_flag() {
         let parsed = Cli::try_parse_from(["stakpak", "up", "--foreground"]);
@@ -1155,6 +1398,177 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_parses_gateway_events_command() {
+        let parsed = Cli::try_parse_from([
+            "stakpak",
+            "gateway",
+            "events",
+            "session-1",
+            "--since",
+            "42",
+            "--json",
+        ]);
+        assert!(parsed.is_ok());
+
+        if let Ok(cli) = parsed {
+            match cli.command {
+                Some(Commands::Gateway(commands::gateway::GatewayCommands::Events {
+                    session_id,
+                    since,
+                    limit,
+                    json,
+                    ..
+                })) => {
+                    assert_eq!(session_id, "session-1");
+                    assert_eq!(since, Some(42));
+                    assert_eq!(limit, 200);
+                    assert!(json);
+                }
+                _ => panic!("Expected gateway events command"),
+            }
+        }
+    }
+
+    #[test]
+    fn cli_parses_gateway_policy_test_command() {
+        let parsed = Cli::try_parse_from([
+            "stakpak",
+            "gateway",
+            "policy",
+            "test",
+            "--tool",
+            "run_command",
+            "--args",
+            r#"{"command":"git status"}"#,
+            "--channel",
+            "slack",
+        ]);
+        assert!(parsed.is_ok());
+
+        if let Ok(cli) = parsed {
+            match cli.command {
+                Some(Commands::Gateway(commands::gateway::GatewayCommands::Policy(
+                    commands::gateway::GatewayPolicyCommands::Test {
+                        tool,
+                        args,
+                        file,
+                        channel,
+                        json,
+                    },
+                ))) => {
+                    assert_eq!(tool.as_deref(), Some("run_command"));
+                    assert_eq!(args.as_deref(), Some(r#"{"command":"git status"}"#));
+                    assert_eq!(file, None);
+                    assert_eq!(channel.as_deref(), Some("slack"));
+                    assert!(!json);
+                }
+                _ => panic!("Expected gateway policy test command"),
+            }
+        }
+
+        assert!(Cli::try_parse_from(["stakpak", "gateway", "policy", "test"]).is_err());
+        assert!(
+            Cli::try_parse_from([
+                "stakpak",
+                "gateway",
+                "policy",
+                "test",
+                "--tool",
+                "view",
+                "--file",
+                "calls.jsonl",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn cli_parses_gateway_bench_command() {
+        let parsed = Cli::try_parse_from([
+            "stakpak",
+            "gateway",
+            "bench",
+            "--messages",
+            "20",
+            "--channel",
+            "telegram",
+            "--target",
+            "telegram=123456",
+        ]);
+        assert!(parsed.is_ok());
+
+        if let Ok(cli) = parsed {
+            match cli.command {
+                Some(Commands::Gateway(commands::gateway::GatewayCommands::Bench {
+                    messages,
+                    channels,
+                    targets,
+                    interval_ms,
+                    json,
+                })) => {
+                    assert_eq!(messages, 20);
+                    assert_eq!(channels, vec!["telegram".to_string()]);
+                    assert_eq!(targets, vec!["telegram=123456".to_string()]);
+                    assert_eq!(interval_ms, 0);
+                    assert!(!json);
+                }
+                _ => panic!("Expected gateway bench command"),
+            }
+        }
+    }
+
+    #[test]
+    fn cli_parses_autopilot_resume_decision() {
+        let parsed = Cli::try_parse_from(["stakpak", "autopilot", "resume", "42", "--deny"]);
+        assert!(parsed.is_ok());
+
+        if let Ok(cli) = parsed {
+            match cli.command {
+                Some(Commands::Autopilot(commands::AutopilotCommands::Resume {
+                    run_id,
+                    approve,
+                    deny,
+                })) => {
+                    assert_eq!(run_id, 42);
+                    assert!(!approve);
+                    assert!(deny);
+                }
+                _ => panic!("Expected autopilot resume command"),
+            }
+        }
+
+        assert!(Cli::try_parse_from(["stakpak", "autopilot", "resume", "42"]).is_err());
+        assert!(
+            Cli::try_parse_from([
+                "stakpak",
+                "autopilot",
+                "resume",
+                "42",
+                "--approve",
+                "--deny"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn cli_parses_autopilot_run_dry_run() {
+        let parsed =
+            Cli::try_parse_from(["stakpak", "autopilot", "run", "disk-check", "--dry-run"]);
+        assert!(parsed.is_ok());
+
+        if let Ok(cli) = parsed {
+            match cli.command {
+                Some(Commands::Autopilot(commands::AutopilotCommands::Run { name, dry_run })) => {
+                    assert_eq!(name, "disk-check");
+                    assert!(dry_run);
+                }
+                _ => panic!("Expected autopilot run command"),
+            }
+        }
+    }
+
     #[test]
     fn cli_rejects_profile_flag_on_autopilot_status() {
         let parsed =
@@ -1206,6 +1620,7 @@ mod tests {
                     model: None,
                     auto_approve_all: false,
                     foreground: false,
+                    daemon: false,
                     non_interactive: false,
                     force: false,
                 },
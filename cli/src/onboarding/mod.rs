@@ -13,7 +13,7 @@ pub mod config_templates;
 pub mod menu;
 pub mod navigation;
 pub mod save_config;
-mod styled_output;
+pub mod styled_output;
 
 use crate::apikey_auth::prompt_for_api_key;
 use crate::config::AppConfig;
@@ -1,5 +1,5 @@
 use std::fmt::Write;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 /// Discover cloud account configurations by reading config files directly.
 /// No CLI calls — pure filesystem reads for speed. Cross-platform.
@@ -14,7 +14,6 @@ pub fn discover() -> String {
     discover_aws(&home, &mut out);
     discover_gcp(&home, &mut out);
     discover_azure(&home, &mut out);
-    discover_kubernetes(&home, &mut out);
     discover_docker_registries(&home, &mut out);
     discover_other_platforms(&home, &mut out);
 
@@ -344,59 +343,6 @@ fn discover_azure(home: &Path, out: &mut String) {
     out.push('\n');
 }
 
-/// Parse kubeconfig to enumerate contexts and clusters.
-fn discover_kubernetes(home: &Path, out: &mut String) {
-    // Check KUBECONFIG env var first, fall back to default
-    let kubeconfig_paths = match std::env::var("KUBECONFIG") {
-        Ok(val) => val.split(':').map(PathBuf::from).collect::<Vec<_>>(),
-        Err(_) => vec![home.join(".kube/config")],
-    };
-
-    let mut found_any = false;
-    for kc_path in &kubeconfig_paths {
-        if !kc_path.exists() {
-            continue;
-        }
-        if let Ok(content) = std::fs::read_to_string(kc_path)
-            && let Ok(yaml) = serde_yaml::from_str::<serde_yaml::Value>(&content)
-        {
-            if !found_any {
-                let _ = writeln!(out, "### Kubernetes\n");
-                found_any = true;
-            }
-
-            // Current context
-            if let Some(current) = yaml.get("current-context").and_then(|v| v.as_str()) {
-                let _ = writeln!(out, "- Current context: {}", current);
-            }
-
-            // List all contexts
-            if let Some(contexts) = yaml.get("contexts").and_then(|v| v.as_sequence()) {
-                for ctx in contexts {
-                    let name = ctx.get("name").and_then(|v| v.as_str()).unwrap_or("?");
-                    let cluster = ctx
-                        .get("context")
-                        .and_then(|c| c.get("cluster"))
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("?");
-                    let namespace = ctx
-                        .get("context")
-                        .and_then(|c| c.get("namespace"))
-                        .and_then(|v| v.as_str());
-                    let _ = write!(out, "- Context: {}  cluster:{}", name, cluster);
-                    if let Some(ns) = namespace {
-                        let _ = write!(out, "  namespace:{}", ns);
-                    }
-                    let _ = writeln!(out);
-                }
-            }
-        }
-    }
-    if found_any {
-        out.push('\n');
-    }
-}
-
 /// Parse ~/.docker/config.json for configured registries (names only, never creds).
 fn discover_docker_registries(home: &Path, out: &mut String) {
     let docker_config = home.join(".docker/config.json");
@@ -0,0 +1,374 @@
+use super::platform::command_stdout_timeout;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Container runtimes checked, in output order.
+const RUNTIMES: &[&str] = &["docker", "podman"];
+
+/// Upper bound for each runtime CLI call; a stuck daemon must not stall discovery.
+const RUNTIME_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Containers listed per compose project before summarizing the rest.
+const MAX_LISTED_CONTAINERS: usize = 10;
+
+/// Local images listed by name before summarizing the rest.
+const MAX_LISTED_IMAGES: usize = 10;
+
+const COMPOSE_PROJECT_LABELS: &[&str] =
+    &["com.docker.compose.project", "io.podman.compose.project"];
+const COMPOSE_SERVICE_LABELS: &[&str] =
+    &["com.docker.compose.service", "io.podman.compose.service"];
+
+/// Discover running containers, compose projects, published ports and local
+/// images from Docker and Podman, grouped by compose project. Only read-only
+/// `ps` and `images` calls are made, each timeboxed.
+pub fn discover() -> String {
+    let handles: Vec<_> = RUNTIMES
+        .iter()
+        .filter(|runtime| which::which(runtime).is_ok())
+        .map(|runtime| std::thread::spawn(move || probe_runtime(runtime)))
+        .collect();
+    let probes: Vec<RuntimeProbe> = handles
+        .into_iter()
+        .filter_map(|handle| handle.join().ok())
+        .collect();
+
+    let mut out = String::new();
+    for probe in &probes {
+        format_runtime(probe, &mut out);
+    }
+    out
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RuntimeProbe {
+    runtime: &'static str,
+    /// `None` when `ps` failed, usually because the daemon is not running.
+    containers: Option<Vec<Container>>,
+    images: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Container {
+    name: String,
+    image: String,
+    status: String,
+    ports: Vec<String>,
+    project: Option<String>,
+    service: Option<String>,
+}
+
+fn probe_runtime(runtime: &'static str) -> RuntimeProbe {
+    // Docker prints one JSON object per line for `{{json .}}`; Podman's
+    // `json` format is a single array with differently shaped fields.
+    let format = match runtime {
+        "podman" => "json",
+        _ => "{{json .}}",
+    };
+    let containers = command_stdout_timeout(runtime, &["ps", "--format", format], RUNTIME_TIMEOUT)
+        .map(|stdout| parse_containers(&stdout));
+    let images = if containers.is_some() {
+        command_stdout_timeout(
+            runtime,
+            &["images", "--format", "{{.Repository}}:{{.Tag}}"],
+            RUNTIME_TIMEOUT,
+        )
+        .map(|stdout| parse_images(&stdout))
+        .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    RuntimeProbe {
+        runtime,
+        containers,
+        images,
+    }
+}
+
+fn parse_containers(stdout: &str) -> Vec<Container> {
+    let trimmed = stdout.trim();
+    let values: Vec<serde_json::Value> = if trimmed.starts_with('[') {
+        serde_json::from_str(trimmed).unwrap_or_default()
+    } else {
+        trimmed
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    };
+    values.iter().map(parse_container).collect()
+}
+
+fn parse_container(value: &serde_json::Value) -> Container {
+    let text = |key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    // Docker: "a,b"; Podman: ["a", "b"].
+    let name = match value.get("Names") {
+        Some(serde_json::Value::Array(names)) => names
+            .first()
+            .and_then(|name| name.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        Some(serde_json::Value::String(names)) => {
+            names.split(',').next().unwrap_or_default().to_string()
+        }
+        _ => String::new(),
+    };
+
+    // Docker: "k=v,k2=v2"; Podman: {"k": "v"}.
+    let labels: BTreeMap<String, String> = match value.get("Labels") {
+        Some(serde_json::Value::Object(labels)) => labels
+            .iter()
+            .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+            .collect(),
+        Some(serde_json::Value::String(labels)) => labels
+            .split(',')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+        _ => BTreeMap::new(),
+    };
+    let label = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| labels.get(*key))
+            .filter(|value| !value.is_empty())
+            .cloned()
+    };
+
+    // Docker: "0.0.0.0:8080->80/tcp, :::8080->80/tcp"; Podman: port objects.
+    let ports = match value.get("Ports") {
+        Some(serde_json::Value::String(ports)) => dedup_ports(
+            ports
+                .split(", ")
+                .map(|port| port.trim().replace(":::", "[::]:"))
+                .filter(|port| !port.is_empty()),
+        ),
+        Some(serde_json::Value::Array(ports)) => {
+            dedup_ports(ports.iter().filter_map(format_podman_port))
+        }
+        _ => Vec::new(),
+    };
+
+    Container {
+        name,
+        image: text("Image"),
+        status: text("Status"),
+        ports,
+        project: label(COMPOSE_PROJECT_LABELS),
+        service: label(COMPOSE_SERVICE_LABELS),
+    }
+}
+
+/// `0.0.0.0:8080->80/tcp` from a Podman port mapping.
+fn format_podman_port(port: &serde_json::Value) -> Option<String> {
+    let container_port = port.get("container_port")?.as_u64()?;
+    let protocol = port
+        .get("protocol")
+        .and_then(|v| v.as_str())
+        .unwrap_or("tcp");
+    let host_ip = port
+        .get("host_ip")
+        .and_then(|v| v.as_str())
+        .filter(|ip| !ip.is_empty())
+        .unwrap_or("0.0.0.0");
+    match port.get("host_port").and_then(|v| v.as_u64()) {
+        Some(host_port) if host_port > 0 => Some(format!(
+            "{}:{}->{}/{}",
+            host_ip, host_port, container_port, protocol
+        )),
+        _ => Some(format!("{}/{}", container_port, protocol)),
+    }
+}
+
+/// Keep each mapping once; Docker lists IPv4 and IPv6 bindings separately.
+fn dedup_ports(ports: impl Iterator<Item = String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    ports
+        .filter(|port| {
+            let mapping = port
+                .rsplit_once(':')
+                .map_or(port.as_str(), |(_, rest)| rest);
+            seen.insert(mapping.to_string())
+        })
+        .collect()
+}
+
+fn parse_images(stdout: &str) -> Vec<String> {
+    let mut images: Vec<String> = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|image| !image.is_empty() && !image.contains("<none>"))
+        .map(str::to_string)
+        .collect();
+    images.sort();
+    images.dedup();
+    images
+}
+
+fn format_runtime(probe: &RuntimeProbe, out: &mut String) {
+    let Some(containers) = &probe.containers else {
+        let _ = writeln!(out, "- Runtime: {}  status:✗ not reachable", probe.runtime);
+        return;
+    };
+    let _ = writeln!(
+        out,
+        "- Runtime: {}  running:{}  images:{}",
+        probe.runtime,
+        containers.len(),
+        probe.images.len()
+    );
+
+    let mut projects: BTreeMap<&str, Vec<&Container>> = BTreeMap::new();
+    let mut standalone = Vec::new();
+    for container in containers {
+        match &container.project {
+            Some(project) => projects.entry(project).or_default().push(container),
+            None => standalone.push(container),
+        }
+    }
+
+    for (project, members) in &projects {
+        let _ = writeln!(
+            out,
+            "  - Compose project: {}  containers:{}",
+            project,
+            members.len()
+        );
+        format_containers(members, out);
+    }
+    if !standalone.is_empty() {
+        let _ = writeln!(out, "  - Standalone containers:{}", standalone.len());
+        format_containers(&standalone, out);
+    }
+
+    if !probe.images.is_empty() {
+        let listed = probe
+            .images
+            .iter()
+            .take(MAX_LISTED_IMAGES)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let rest = probe.images.len().saturating_sub(MAX_LISTED_IMAGES);
+        if rest > 0 {
+            let _ = writeln!(out, "  - Images: {}, +{} more", listed, rest);
+        } else {
+            let _ = writeln!(out, "  - Images: {}", listed);
+        }
+    }
+}
+
+fn format_containers(containers: &[&Container], out: &mut String) {
+    for container in containers.iter().take(MAX_LISTED_CONTAINERS) {
+        let _ = write!(out, "    - {}", container.name);
+        if let Some(service) = &container.service {
+            let _ = write!(out, "  service:{}", service);
+        }
+        let _ = write!(out, "  image:{}", container.image);
+        if !container.status.is_empty() {
+            let _ = write!(out, "  status:{}", container.status);
+        }
+        if !container.ports.is_empty() {
+            let _ = write!(out, "  ports:{}", container.ports.join(", "));
+        }
+        let _ = writeln!(out);
+    }
+    let rest = containers.len().saturating_sub(MAX_LISTED_CONTAINERS);
+    if rest > 0 {
+        let _ = writeln!(out, "    - +{} more", rest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_docker_ps_lines() {
+        let stdout = concat!(
+            r#"{"Names":"web-api-1","Image":"web-api:latest","Status":"Up 2 hours","Ports":"0.0.0.0:8080->80/tcp, :::8080->80/tcp","Labels":"com.docker.compose.project=web,com.docker.compose.service=api"}"#,
+            "\n",
+            r#"{"Names":"redis","Image":"redis:7","Status":"Up 3 days","Ports":"6379/tcp","Labels":""}"#,
+            "\n",
+        );
+        let containers = parse_containers(stdout);
+        assert_eq!(
+            containers[0],
+            Container {
+                name: "web-api-1".to_string(),
+                image: "web-api:latest".to_string(),
+                status: "Up 2 hours".to_string(),
+                ports: vec!["0.0.0.0:8080->80/tcp".to_string()],
+                project: Some("web".to_string()),
+                service: Some("api".to_string()),
+            }
+        );
+        assert_eq!(containers[1].project, None);
+        assert_eq!(containers[1].ports, vec!["6379/tcp".to_string()]);
+    }
+
+    #[test]
+    fn test_parses_podman_ps_array() {
+        let stdout = r#"[{"Names":["db"],"Image":"docker.io/library/postgres:16","Status":"Up 5 minutes","Ports":[{"host_ip":"","container_port":5432,"host_port":15432,"range":1,"protocol":"tcp"}],"Labels":{"io.podman.compose.project":"app"}}]"#;
+        let containers = parse_containers(stdout);
+        assert_eq!(containers.len(), 1);
+        assert_eq!(containers[0].name, "db");
+        assert_eq!(
+            containers[0].ports,
+            vec!["0.0.0.0:15432->5432/tcp".to_string()]
+        );
+        assert_eq!(containers[0].project.as_deref(), Some("app"));
+    }
+
+    #[test]
+    fn test_format_groups_by_compose_project() {
+        let containers = parse_containers(concat!(
+            r#"{"Names":"web-api-1","Image":"web-api","Status":"Up","Ports":"","Labels":"com.docker.compose.project=web,com.docker.compose.service=api"}"#,
+            "\n",
+            r#"{"Names":"redis","Image":"redis:7","Status":"Up","Ports":"","Labels":""}"#,
+        ));
+        let images: Vec<String> = (0..12).map(|i| format!("img{:02}:latest", i)).collect();
+        let mut out = String::new();
+        format_runtime(
+            &RuntimeProbe {
+                runtime: "docker",
+                containers: Some(containers),
+                images,
+            },
+            &mut out,
+        );
+        assert!(out.starts_with("- Runtime: docker  running:2  images:12\n"));
+        assert!(out.contains(
+            "  - Compose project: web  containers:1\n    - web-api-1  service:api  image:web-api  status:Up\n"
+        ));
+        assert!(
+            out.contains("  - Standalone containers:1\n    - redis  image:redis:7  status:Up\n")
+        );
+        assert!(out.contains("img09:latest, +2 more\n"));
+
+        let mut down = String::new();
+        format_runtime(
+            &RuntimeProbe {
+                runtime: "podman",
+                containers: None,
+                images: Vec::new(),
+            },
+            &mut down,
+        );
+        assert_eq!(down, "- Runtime: podman  status:✗ not reachable\n");
+    }
+
+    #[test]
+    fn test_parse_images_skips_dangling() {
+        assert_eq!(
+            parse_images("redis:7\n<none>:<none>\nnginx:latest\nredis:7\n"),
+            vec!["nginx:latest".to_string(), "redis:7".to_string()]
+        );
+    }
+}
@@ -1,15 +1,16 @@
+use super::platform::{Platform, command_stdout, powershell_stdout};
 use std::fmt::Write;
 use std::process::Command;
 
 /// Discover cron jobs / scheduled tasks for the current user.
-/// Linux/macOS: parse crontab. macOS also checks launchd. Windows: schtasks.
+/// Linux/macOS: parse crontab. macOS also checks launchd. Windows: Task Scheduler.
+/// Systemd timers are reported by the systemd probe.
 pub fn discover() -> String {
-    let os = std::env::consts::OS;
-    match os {
-        "linux" => discover_linux(),
-        "macos" => discover_macos(),
-        "windows" => discover_windows(),
-        _ => discover_linux(), // best effort
+    match Platform::current() {
+        Platform::Linux => discover_linux(),
+        Platform::MacOs => discover_macos(),
+        Platform::Windows => discover_windows(),
+        Platform::Other => discover_linux(), // best effort
     }
 }
 
@@ -65,23 +66,6 @@ fn discover_linux() -> String {
         out.push('\n');
     }
 
-    // Systemd timers
-    if let Ok(output) = Command::new("systemctl")
-        .args(["list-timers", "--no-pager", "--no-legend"])
-        .output()
-        && output.status.success()
-    {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let timers: Vec<&str> = stdout.lines().filter(|l| !l.trim().is_empty()).collect();
-        if !timers.is_empty() {
-            let _ = writeln!(out, "### Systemd Timers\n");
-            for timer in timers.iter().take(20) {
-                let _ = writeln!(out, "- {}", timer.trim());
-            }
-            out.push('\n');
-        }
-    }
-
     if out.is_empty() {
         "(no cron jobs or scheduled tasks found)\n".to_string()
     } else {
@@ -138,6 +122,18 @@ fn discover_macos() -> String {
         }
     }
 
+    // Jobs loaded into launchd, including ones installed outside LaunchAgents
+    if let Some(stdout) = command_stdout("launchctl", &["list"]) {
+        let jobs = parse_launchctl_list(&stdout);
+        if !jobs.is_empty() {
+            let _ = writeln!(out, "### Loaded launchd Jobs\n");
+            for job in jobs.iter().take(20) {
+                let _ = writeln!(out, "{}", job);
+            }
+            out.push('\n');
+        }
+    }
+
     if out.is_empty() {
         "(no cron jobs or scheduled tasks found)\n".to_string()
     } else {
@@ -145,7 +141,66 @@ fn discover_macos() -> String {
     }
 }
 
+/// Non-Apple jobs from `launchctl list` (`PID\tStatus\tLabel` rows).
+fn parse_launchctl_list(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let pid = fields.next()?.trim();
+            let status = fields.next()?.trim();
+            let label = fields.next()?.trim();
+            if label.is_empty() || label.starts_with("com.apple.") {
+                return None;
+            }
+            let state = if pid == "-" {
+                format!("not running, last exit {}", status)
+            } else {
+                format!("running, pid {}", pid)
+            };
+            Some(format!("- {} ({})", label, state))
+        })
+        .collect()
+}
+
+/// Windows: `Get-ScheduledTask`, whose fields are not localized like
+/// `schtasks` output. Falls back to `schtasks` when PowerShell is unavailable.
 fn discover_windows() -> String {
+    const SCRIPT: &str = "Get-ScheduledTask | Where-Object { $_.TaskPath -notlike '\\Microsoft\\*' } | \
+        ForEach-Object { \"$($_.TaskPath)$($_.TaskName)`t$($_.State)\" }";
+
+    let Some(stdout) = powershell_stdout(SCRIPT) else {
+        return discover_windows_schtasks();
+    };
+    format_windows_tasks(parse_scheduled_tasks(&stdout))
+}
+
+/// `<path><name>\t<state>` rows printed by the `Get-ScheduledTask` script.
+fn parse_scheduled_tasks(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (name, state) = line.trim().split_once('\t')?;
+            Some(format!("- {} ({})", name.trim(), state.trim()))
+        })
+        .collect()
+}
+
+fn format_windows_tasks(tasks: Vec<String>) -> String {
+    if tasks.is_empty() {
+        return "(no user scheduled tasks found)\n".to_string();
+    }
+
+    let mut out = String::with_capacity(tasks.len() * 60);
+    let _ = writeln!(out, "### Scheduled Tasks\n");
+    for task in tasks.iter().take(30) {
+        let _ = writeln!(out, "{}", task);
+    }
+    out
+}
+
+fn discover_windows_schtasks() -> String {
     let output = match Command::new("schtasks")
         .args(["/Query", "/FO", "LIST", "/V"])
         .output()
@@ -181,14 +236,34 @@ fn discover_windows() -> String {
         }
     }
 
-    if tasks.is_empty() {
-        return "(no user scheduled tasks found)\n".to_string();
+    format_windows_tasks(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_launchctl_list_skips_apple_jobs() {
+        let stdout = "PID\tStatus\tLabel\n412\t0\tcom.example.backup\n-\t78\thomebrew.mxcl.postgresql\n-\t0\tcom.apple.Finder\n";
+        assert_eq!(
+            parse_launchctl_list(stdout),
+            vec![
+                "- com.example.backup (running, pid 412)".to_string(),
+                "- homebrew.mxcl.postgresql (not running, last exit 78)".to_string(),
+            ]
+        );
     }
 
-    let mut out = String::with_capacity(tasks.len() * 60);
-    let _ = writeln!(out, "### Scheduled Tasks\n");
-    for task in tasks.iter().take(30) {
-        let _ = writeln!(out, "{}", task);
+    #[test]
+    fn test_parse_scheduled_tasks() {
+        let stdout = "\\BackupNightly\tReady\r\n\\Vendor\\Updater\tDisabled\r\n\r\n";
+        assert_eq!(
+            parse_scheduled_tasks(stdout),
+            vec![
+                "- \\BackupNightly (Ready)".to_string(),
+                "- \\Vendor\\Updater (Disabled)".to_string(),
+            ]
+        );
     }
-    out
 }
@@ -1,4 +1,4 @@
-use ignore::WalkBuilder;
+use stakpak_shared::file_index::{FileIndex, FileIndexOptions};
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -17,58 +17,26 @@ pub fn discover(home: Option<&Path>) -> String {
         if !root.exists() {
             continue;
         }
-        // Use ignore crate for fast traversal that respects .gitignore
-        let walker = WalkBuilder::new(root)
-            .hidden(false) // don't skip hidden dirs (we need .git)
-            .git_ignore(false) // don't use gitignore for the walk itself
-            .max_depth(Some(6))
-            .filter_entry(|entry| {
-                let name = entry.file_name().to_string_lossy();
-                // Skip known heavy dirs that never contain user repos
-                !matches!(
-                    name.as_ref(),
-                    "node_modules"
-                        | "vendor"
-                        | "target"
-                        | ".terraform"
-                        | "venv"
-                        | ".venv"
-                        | "__pycache__"
-                        | ".cache"
-                        | ".Trash"
-                        | "Library"
-                        | ".local"
-                        | ".cargo"
-                        | ".rustup"
-                        | ".npm"
-                        | ".nvm"
-                        | ".pyenv"
-                        | ".gradle"
-                        | ".m2"
-                        | ".docker"
-                        | ".kube"
-                        | ".aws"
-                )
-            })
-            .build();
-
-        for entry in walker.flatten() {
-            let path = entry.path();
-            if path.file_name().map(|n| n == ".git").unwrap_or(false) && path.is_dir() {
-                let repo_root = match path.parent() {
-                    Some(p) => p,
-                    None => continue,
-                };
-                let remote = get_remote(repo_root);
-                let lang = detect_language(repo_root);
-                let branch = get_branch(repo_root);
-                repos.push(RepoInfo {
-                    path: repo_root.to_path_buf(),
-                    remote,
-                    language: lang,
-                    branch,
-                });
+        // The shared home index lists `.git` directories without entering
+        // them and skips heavy dirs that never contain user repos
+        let index = FileIndex::open(root, &FileIndexOptions::home());
+        for git_dir in index.dirs(6) {
+            if git_dir.file_name().is_none_or(|name| name != ".git") {
+                continue;
             }
+            let repo_root = match git_dir.parent() {
+                Some(p) => index.absolute(p),
+                None => continue,
+            };
+            let remote = get_remote(&repo_root);
+            let lang = detect_language(&repo_root);
+            let branch = get_branch(&repo_root);
+            repos.push(RepoInfo {
+                path: repo_root,
+                remote,
+                language: lang,
+                branch,
+            });
         }
     }
 
@@ -0,0 +1,565 @@
+use stakpak_shared::file_index::{FileIndex, FileIndexOptions};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Projects listed before summarizing the rest.
+const MAX_LISTED_PROJECTS: usize = 25;
+
+/// Workspaces or stacks listed by name per project before summarizing the rest.
+const MAX_LISTED_WORKSPACES: usize = 8;
+
+/// Discover Terraform, Terragrunt and Pulumi projects under cwd and $HOME
+/// with their backend, workspaces or stacks, and when local state was last
+/// written. State files are never read, only their modification times.
+pub fn discover(home: Option<&Path>, cwd: Option<&Path>) -> String {
+    let mut roots = Vec::new();
+    if let Some(cwd) = cwd.filter(|dir| dir.is_dir()) {
+        roots.push((FileIndex::open(cwd, &FileIndexOptions::project()), 6));
+    }
+    if let Some(home) = home.filter(|dir| dir.is_dir()) {
+        roots.push((FileIndex::open(home, &FileIndexOptions::home()), 5));
+    }
+    let home_dir = home.map(Path::to_path_buf);
+    let (projects, modules) = find_projects(&roots, |dir, markers| {
+        inspect_project(dir, markers, home_dir.as_deref())
+    });
+    format_projects(&projects, modules)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IacTool {
+    Terraform,
+    Terragrunt,
+    Pulumi,
+}
+
+impl IacTool {
+    fn label(self) -> &'static str {
+        match self {
+            IacTool::Terraform => "Terraform",
+            IacTool::Terragrunt => "Terragrunt",
+            IacTool::Pulumi => "Pulumi",
+        }
+    }
+}
+
+/// Marker files seen in a directory during the walk.
+#[derive(Debug, Default, Clone, Copy)]
+struct Markers {
+    tf: bool,
+    terragrunt: bool,
+    pulumi: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct IacProject {
+    tool: IacTool,
+    path: PathBuf,
+    /// Pulumi project name.
+    name: Option<String>,
+    /// `local`, a backend type such as `s3`, or `inherited` for Terragrunt
+    /// configs that take `remote_state` from an included parent.
+    backend: Option<String>,
+    /// Terraform workspaces or Pulumi stacks known locally.
+    workspaces: Vec<String>,
+    current: Option<String>,
+    /// Newest local state write, with the workspace or stack it belongs to.
+    last_apply: Option<(SystemTime, String)>,
+}
+
+/// Look through the indexed files of `roots`, up to each one's depth, for IaC
+/// marker files and inspect each directory holding them. Hidden directories
+/// (module caches such as `.terraform/modules`) and dependency trees are
+/// skipped. Terraform directories that `inspect` rejects are counted as
+/// modules.
+fn find_projects(
+    roots: &[(FileIndex, usize)],
+    inspect: impl Fn(&Path, Markers) -> Option<IacProject>,
+) -> (Vec<IacProject>, usize) {
+    let mut dirs: BTreeMap<PathBuf, Markers> = BTreeMap::new();
+    for (index, depth) in roots {
+        for file in index.files(*depth) {
+            let (Some(name), Some(parent)) =
+                (file.file_name().and_then(|n| n.to_str()), file.parent())
+            else {
+                continue;
+            };
+            let is_tf = name.ends_with(".tf");
+            let is_terragrunt = name == "terragrunt.hcl";
+            let is_pulumi = name == "Pulumi.yaml" || name == "Pulumi.yml";
+            if !(is_tf || is_terragrunt || is_pulumi) || !visible(parent) {
+                continue;
+            }
+            let markers = dirs.entry(index.absolute(parent)).or_default();
+            markers.tf |= is_tf;
+            markers.terragrunt |= is_terragrunt;
+            markers.pulumi |= is_pulumi;
+        }
+    }
+
+    let mut projects = Vec::new();
+    let mut modules = 0;
+    for (dir, markers) in dirs {
+        match inspect(&dir, markers) {
+            Some(project) => projects.push(project),
+            None if markers.tf => modules += 1,
+            None => {}
+        }
+    }
+    (projects, modules)
+}
+
+fn visible(dir: &Path) -> bool {
+    dir.components().all(|component| {
+        let name = component.as_os_str().to_string_lossy();
+        !name.starts_with('.')
+            && !matches!(
+                name.as_ref(),
+                "node_modules" | "vendor" | "target" | "venv" | "Library"
+            )
+    })
+}
+
+fn inspect_project(dir: &Path, markers: Markers, home: Option<&Path>) -> Option<IacProject> {
+    if markers.pulumi {
+        inspect_pulumi(dir, home)
+    } else if markers.terragrunt {
+        Some(inspect_terragrunt(dir))
+    } else {
+        inspect_terraform(dir)
+    }
+}
+
+/// A Terraform root module, `None` for directories that only look like
+/// reusable modules (no backend, no state, never initialized).
+fn inspect_terraform(dir: &Path) -> Option<IacProject> {
+    let sources = read_files(dir, |name| name.ends_with(".tf"));
+    let configured_backend = sources.iter().find_map(|source| {
+        hcl_block_label(source, "backend")
+            .or_else(|| hcl_has_block(source, "cloud").then(|| "cloud".to_string()))
+    });
+
+    let dot_terraform = dir.join(".terraform");
+    let mut states = vec![("default".to_string(), dir.join("terraform.tfstate"))];
+    if let Ok(entries) = std::fs::read_dir(dir.join("terraform.tfstate.d")) {
+        let mut named: Vec<_> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                (name, entry.path().join("terraform.tfstate"))
+            })
+            .collect();
+        named.sort();
+        states.extend(named);
+    }
+
+    let initialized = dot_terraform.is_dir() || dir.join(".terraform.lock.hcl").is_file();
+    let has_state = states.iter().any(|(_, path)| path.is_file());
+    if configured_backend.is_none() && !initialized && !has_state {
+        return None;
+    }
+
+    let backend = configured_backend
+        .or_else(|| initialized_backend(&dot_terraform))
+        .unwrap_or_else(|| "local".to_string());
+    let current = std::fs::read_to_string(dot_terraform.join("environment"))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    let last_apply = if backend == "local" {
+        newest(
+            states
+                .iter()
+                .map(|(name, path)| (name.clone(), path.clone())),
+        )
+    } else {
+        None
+    };
+
+    Some(IacProject {
+        tool: IacTool::Terraform,
+        path: dir.to_path_buf(),
+        name: None,
+        backend: Some(backend),
+        workspaces: states.into_iter().map(|(name, _)| name).collect(),
+        current,
+        last_apply,
+    })
+}
+
+/// Backend type recorded by `terraform init`. Only the type is kept; the
+/// rest of that file can hold backend credentials.
+fn initialized_backend(dot_terraform: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dot_terraform.join("terraform.tfstate")).ok()?;
+    let json = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+    json.get("backend")
+        .and_then(|backend| backend.get("type"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+fn inspect_terragrunt(dir: &Path) -> IacProject {
+    let source = std::fs::read_to_string(dir.join("terragrunt.hcl")).unwrap_or_default();
+    let backend = hcl_string_attr(&source, "backend")
+        .or_else(|| hcl_has_block(&source, "include").then(|| "inherited".to_string()));
+    IacProject {
+        tool: IacTool::Terragrunt,
+        path: dir.to_path_buf(),
+        name: None,
+        backend,
+        workspaces: Vec::new(),
+        current: None,
+        last_apply: None,
+    }
+}
+
+fn inspect_pulumi(dir: &Path, home: Option<&Path>) -> Option<IacProject> {
+    let source = std::fs::read_to_string(dir.join("Pulumi.yaml"))
+        .or_else(|_| std::fs::read_to_string(dir.join("Pulumi.yml")))
+        .ok()?;
+    let yaml = serde_yaml::from_str::<serde_yaml::Value>(&source).ok()?;
+    let name = yaml
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let backend_url = yaml
+        .get("backend")
+        .and_then(|backend| backend.get("url"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+
+    let mut stacks: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let file = entry.file_name().to_string_lossy().to_string();
+                    let stack = file
+                        .strip_prefix("Pulumi.")?
+                        .strip_suffix(".yaml")
+                        .or_else(|| file.strip_prefix("Pulumi.")?.strip_suffix(".yml"))?;
+                    (!stack.is_empty()).then(|| stack.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    stacks.sort();
+
+    // Self-managed file backends keep one checkpoint per stack; their
+    // mtimes are the last updates. Other backends are not queried.
+    let last_apply = backend_url
+        .as_deref()
+        .and_then(|url| file_backend_root(url, dir, home))
+        .and_then(|root| {
+            let stacks_dir = root.join(".pulumi").join("stacks");
+            newest(stacks.iter().flat_map(|stack| {
+                let file = format!("{}.json", stack);
+                let project_scoped = name.as_deref().map(|project| stacks_dir.join(project));
+                project_scoped
+                    .into_iter()
+                    .chain(std::iter::once(stacks_dir.clone()))
+                    .map(move |base| (stack.clone(), base.join(&file)))
+                    .collect::<Vec<_>>()
+            }))
+        });
+
+    Some(IacProject {
+        tool: IacTool::Pulumi,
+        path: dir.to_path_buf(),
+        name,
+        backend: Some(match backend_url {
+            Some(url) => display_backend_url(&url),
+            None => "default".to_string(),
+        }),
+        workspaces: stacks,
+        current: None,
+        last_apply,
+    })
+}
+
+/// Directory behind a `file://` backend URL.
+fn file_backend_root(url: &str, project: &Path, home: Option<&Path>) -> Option<PathBuf> {
+    let path = url.strip_prefix("file://")?;
+    let path = path.split('?').next().unwrap_or_default();
+    if path == "~" {
+        home.map(Path::to_path_buf)
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        home.map(|home| home.join(rest))
+    } else if Path::new(path).is_absolute() {
+        Some(PathBuf::from(path))
+    } else {
+        Some(project.join(path))
+    }
+}
+
+/// Scheme and location of a backend URL, without query parameters that may
+/// carry credentials.
+fn display_backend_url(url: &str) -> String {
+    url.split('?').next().unwrap_or_default().to_string()
+}
+
+fn read_files(dir: &Path, predicate: impl Fn(&str) -> bool) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(&predicate)
+        })
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .collect()
+}
+
+/// `name` from the first `keyword "name" {` line, e.g. `backend "s3" {`.
+fn hcl_block_label(source: &str, keyword: &str) -> Option<String> {
+    source.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix(keyword)?;
+        if !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let label = rest.trim_start().strip_prefix('"')?;
+        let (label, after) = label.split_once('"')?;
+        after
+            .trim_start()
+            .starts_with('{')
+            .then(|| label.to_string())
+    })
+}
+
+/// Whether a line opens a `keyword {` or labelled `keyword "x" {` block.
+fn hcl_has_block(source: &str, keyword: &str) -> bool {
+    source.lines().any(|line| {
+        line.trim().strip_prefix(keyword).is_some_and(|rest| {
+            (rest.starts_with(char::is_whitespace) || rest.starts_with('{'))
+                && rest.trim_end().ends_with('{')
+        })
+    })
+}
+
+/// Value of the first `key = "value"` line.
+fn hcl_string_attr(source: &str, key: &str) -> Option<String> {
+    source.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix(key)?.trim_start();
+        let value = rest.strip_prefix('=')?.trim_start().strip_prefix('"')?;
+        value.split_once('"').map(|(value, _)| value.to_string())
+    })
+}
+
+/// Newest modification time among files that exist, with its label.
+fn newest(files: impl Iterator<Item = (String, PathBuf)>) -> Option<(SystemTime, String)> {
+    files
+        .filter_map(|(label, path)| {
+            let modified = std::fs::metadata(&path).ok()?.modified().ok()?;
+            Some((modified, label))
+        })
+        .max_by_key(|(modified, _)| *modified)
+}
+
+fn format_projects(projects: &[IacProject], modules: usize) -> String {
+    let mut out = String::new();
+    for project in projects.iter().take(MAX_LISTED_PROJECTS) {
+        let _ = write!(
+            out,
+            "- {}: {}",
+            project.tool.label(),
+            project.path.display()
+        );
+        if let Some(name) = &project.name {
+            let _ = write!(out, "  project:{}", name);
+        }
+        if let Some(backend) = &project.backend {
+            let _ = write!(out, "  backend:{}", backend);
+        }
+        if !project.workspaces.is_empty() {
+            let key = match project.tool {
+                IacTool::Pulumi => "stacks",
+                IacTool::Terraform | IacTool::Terragrunt => "workspaces",
+            };
+            let _ = write!(
+                out,
+                "  {}:{}",
+                key,
+                summarize_workspaces(&project.workspaces, project.current.as_deref())
+            );
+        }
+        if let Some((modified, label)) = &project.last_apply {
+            let timestamp = chrono::DateTime::<chrono::Utc>::from(*modified);
+            let _ = write!(
+                out,
+                "  last apply:{} ({})",
+                timestamp.format("%Y-%m-%d %H:%M UTC"),
+                label
+            );
+        }
+        let _ = writeln!(out);
+    }
+
+    let rest = projects.len().saturating_sub(MAX_LISTED_PROJECTS);
+    if rest > 0 {
+        let _ = writeln!(out, "- +{} more projects", rest);
+    }
+    if modules > 0 {
+        let _ = writeln!(
+            out,
+            "- Terraform modules without backend or state: {}",
+            modules
+        );
+    }
+    out
+}
+
+/// `default, staging*`, with the selected workspace starred.
+fn summarize_workspaces(workspaces: &[String], current: Option<&str>) -> String {
+    let listed = workspaces
+        .iter()
+        .take(MAX_LISTED_WORKSPACES)
+        .map(|name| {
+            if Some(name.as_str()) == current {
+                format!("{}*", name)
+            } else {
+                name.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rest = workspaces.len().saturating_sub(MAX_LISTED_WORKSPACES);
+    if rest > 0 {
+        format!("{}, +{} more", listed, rest)
+    } else {
+        listed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn write(path: &Path, content: &str) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create dir");
+        }
+        std::fs::write(path, content).expect("write file");
+    }
+
+    fn touch_at(path: &Path, unix_secs: u64) {
+        let file = std::fs::File::options()
+            .write(true)
+            .open(path)
+            .expect("open file");
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(unix_secs))
+            .expect("set mtime");
+    }
+
+    fn discover_in(root: &Path) -> String {
+        let index = FileIndex::build(root, &FileIndexOptions::project());
+        let (projects, modules) = find_projects(&[(index, 6)], |dir, markers| {
+            inspect_project(dir, markers, Some(root))
+        });
+        format_projects(&projects, modules)
+    }
+
+    #[test]
+    fn test_terraform_roots_report_backend_and_workspaces() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let prod = dir.path().join("infra/prod");
+        write(
+            &prod.join("main.tf"),
+            "terraform {\n  backend \"s3\" {\n    bucket = \"state\"\n  }\n}\n",
+        );
+        write(&prod.join(".terraform/environment"), "staging\n");
+        std::fs::create_dir_all(prod.join("terraform.tfstate.d/staging")).expect("workspace");
+        write(
+            &dir.path().join("modules/vpc/main.tf"),
+            "variable \"cidr\" {}\n",
+        );
+
+        let out = discover_in(dir.path());
+        assert!(out.contains(&format!(
+            "- Terraform: {}  backend:s3  workspaces:default, staging*\n",
+            prod.display()
+        )));
+        assert!(!out.contains("last apply"));
+        assert!(out.contains("- Terraform modules without backend or state: 1\n"));
+    }
+
+    #[test]
+    fn test_local_state_reports_last_apply_without_reading_it() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        write(
+            &dir.path().join("main.tf"),
+            "resource \"null_resource\" \"x\" {}\n",
+        );
+        let state = dir.path().join("terraform.tfstate");
+        write(&state, r#"{"outputs":{"db_password":"hunter2"}}"#);
+        touch_at(&state, 1_760_000_000);
+        let dev = dir.path().join("terraform.tfstate.d/dev/terraform.tfstate");
+        write(&dev, "{}");
+        touch_at(&dev, 1_700_000_000);
+
+        let out = discover_in(dir.path());
+        assert!(out.contains(
+            "backend:local  workspaces:default, dev  last apply:2025-10-09 08:53 UTC (default)\n"
+        ));
+        assert!(!out.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_terragrunt_backend_or_inherited() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        write(
+            &dir.path().join("terragrunt.hcl"),
+            "remote_state {\n  backend = \"gcs\"\n}\n",
+        );
+        write(
+            &dir.path().join("prod/vpc/terragrunt.hcl"),
+            "include \"root\" {\n  path = find_in_parent_folders()\n}\n",
+        );
+
+        let out = discover_in(dir.path());
+        assert!(out.contains(&format!(
+            "- Terragrunt: {}  backend:gcs\n",
+            dir.path().display()
+        )));
+        assert!(out.contains("vpc  backend:inherited\n"));
+    }
+
+    #[test]
+    fn test_pulumi_stacks_and_file_backend_updates() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let app = dir.path().join("app");
+        write(
+            &app.join("Pulumi.yaml"),
+            "name: shop\nruntime: nodejs\nbackend:\n  url: file://~\n",
+        );
+        write(&app.join("Pulumi.dev.yaml"), "config: {}\n");
+        write(&app.join("Pulumi.prod.yaml"), "config: {}\n");
+        let checkpoint = dir.path().join(".pulumi/stacks/shop/prod.json");
+        write(&checkpoint, "{}");
+        touch_at(&checkpoint, 1_760_000_000);
+
+        let out = discover_in(dir.path());
+        assert!(out.contains(&format!(
+            "- Pulumi: {}  project:shop  backend:file://~  stacks:dev, prod  last apply:2025-10-09 08:53 UTC (prod)\n",
+            app.display()
+        )));
+
+        assert_eq!(
+            display_backend_url("azblob://state?storage_account=acct&key=secret"),
+            "azblob://state"
+        );
+    }
+}
@@ -0,0 +1,372 @@
+use super::platform::command_stdout_timeout;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Contexts probed live; the rest are only listed. The current context is probed first.
+const MAX_PROBED_CONTEXTS: usize = 5;
+
+/// Upper bound for each `kubectl` call, including credential plugins.
+const KUBECTL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Namespaces listed by name per cluster before summarizing the rest.
+const MAX_LISTED_NAMESPACES: usize = 10;
+
+/// Discover Kubernetes contexts from kubeconfig and, when `kubectl` is
+/// installed, which clusters are reachable with their namespaces and node
+/// counts. Live checks are read-only `get` calls, run in parallel and timeboxed.
+pub fn discover() -> String {
+    let Some(kubeconfig) = load_kubeconfig(&kubeconfig_paths()) else {
+        return String::new();
+    };
+    if kubeconfig.contexts.is_empty() {
+        return String::new();
+    }
+
+    let kubectl_found = which::which("kubectl").is_ok();
+    let probes = if kubectl_found {
+        probe_contexts(&kubeconfig)
+    } else {
+        Vec::new()
+    };
+    format_kubernetes(&kubeconfig, &probes, kubectl_found)
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Kubeconfig {
+    current_context: Option<String>,
+    contexts: Vec<KubeContext>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct KubeContext {
+    name: String,
+    cluster: Option<String>,
+    namespace: Option<String>,
+}
+
+/// Live state of one context's cluster.
+#[derive(Debug, Clone, PartialEq)]
+struct ClusterProbe {
+    context: String,
+    /// Server version, `None` when the API server did not answer.
+    version: Option<String>,
+    /// Namespace names, `None` when listing them failed (often RBAC).
+    namespaces: Option<Vec<String>>,
+    nodes: Option<usize>,
+}
+
+/// Kubeconfig files in the order `kubectl` merges them.
+fn kubeconfig_paths() -> Vec<PathBuf> {
+    match std::env::var_os("KUBECONFIG") {
+        Some(value) if !value.is_empty() => std::env::split_paths(&value)
+            .filter(|path| !path.as_os_str().is_empty())
+            .collect(),
+        _ => dirs::home_dir()
+            .map(|home| vec![home.join(".kube/config")])
+            .unwrap_or_default(),
+    }
+}
+
+/// Merge kubeconfig files like `kubectl`: the first file to set
+/// `current-context` wins, and so does the first context with a given name.
+fn load_kubeconfig(paths: &[PathBuf]) -> Option<Kubeconfig> {
+    let mut merged = Kubeconfig::default();
+    let mut found = false;
+    let mut seen = HashSet::new();
+    for path in paths {
+        let Some(config) = read_kubeconfig(path) else {
+            continue;
+        };
+        found = true;
+        if merged.current_context.is_none() {
+            merged.current_context = config.current_context;
+        }
+        for context in config.contexts {
+            if seen.insert(context.name.clone()) {
+                merged.contexts.push(context);
+            }
+        }
+    }
+    found.then_some(merged)
+}
+
+fn read_kubeconfig(path: &Path) -> Option<Kubeconfig> {
+    let content = std::fs::read_to_string(path).ok()?;
+    parse_kubeconfig(&content)
+}
+
+fn parse_kubeconfig(content: &str) -> Option<Kubeconfig> {
+    let yaml = serde_yaml::from_str::<serde_yaml::Value>(content).ok()?;
+    let current_context = yaml
+        .get("current-context")
+        .and_then(|v| v.as_str())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string);
+    let contexts = yaml
+        .get("contexts")
+        .and_then(|v| v.as_sequence())
+        .map(|contexts| {
+            contexts
+                .iter()
+                .filter_map(|ctx| {
+                    let name = ctx.get("name").and_then(|v| v.as_str())?;
+                    let field = |key: &str| {
+                        ctx.get("context")
+                            .and_then(|c| c.get(key))
+                            .and_then(|v| v.as_str())
+                            .map(str::to_string)
+                    };
+                    Some(KubeContext {
+                        name: name.to_string(),
+                        cluster: field("cluster"),
+                        namespace: field("namespace"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(Kubeconfig {
+        current_context,
+        contexts,
+    })
+}
+
+/// Contexts to probe live: the current one first, then in kubeconfig order.
+fn contexts_to_probe(kubeconfig: &Kubeconfig) -> Vec<String> {
+    let mut names: Vec<String> = kubeconfig
+        .current_context
+        .iter()
+        .filter(|current| kubeconfig.contexts.iter().any(|c| &&c.name == current))
+        .cloned()
+        .collect();
+    for context in &kubeconfig.contexts {
+        if names.len() >= MAX_PROBED_CONTEXTS {
+            break;
+        }
+        if !names.contains(&context.name) {
+            names.push(context.name.clone());
+        }
+    }
+    names
+}
+
+fn probe_contexts(kubeconfig: &Kubeconfig) -> Vec<ClusterProbe> {
+    let handles: Vec<_> = contexts_to_probe(kubeconfig)
+        .into_iter()
+        .map(|context| std::thread::spawn(move || probe_context(context)))
+        .collect();
+    handles
+        .into_iter()
+        .filter_map(|handle| handle.join().ok())
+        .collect()
+}
+
+fn probe_context(context: String) -> ClusterProbe {
+    let kubectl = |args: &[&str]| {
+        let mut full = vec!["--context", context.as_str(), "--request-timeout=4s"];
+        full.extend_from_slice(args);
+        command_stdout_timeout("kubectl", &full, KUBECTL_TIMEOUT)
+    };
+
+    // `/version` is readable by any authenticated user, so it tells an
+    // unreachable cluster apart from one where listing is forbidden.
+    let version = kubectl(&["get", "--raw", "/version"]).and_then(|stdout| parse_version(&stdout));
+    let (namespaces, nodes) = if version.is_some() {
+        let namespaces = kubectl(&[
+            "get",
+            "namespaces",
+            "-o",
+            "jsonpath={.items[*].metadata.name}",
+        ])
+        .map(|stdout| stdout.split_whitespace().map(str::to_string).collect());
+        let nodes = kubectl(&["get", "nodes", "-o", "name"])
+            .map(|stdout| stdout.lines().filter(|l| !l.trim().is_empty()).count());
+        (namespaces, nodes)
+    } else {
+        (None, None)
+    };
+
+    ClusterProbe {
+        context,
+        version,
+        namespaces,
+        nodes,
+    }
+}
+
+fn parse_version(stdout: &str) -> Option<String> {
+    let json = serde_json::from_str::<serde_json::Value>(stdout).ok()?;
+    json.get("gitVersion")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+fn format_kubernetes(kubeconfig: &Kubeconfig, probes: &[ClusterProbe], live: bool) -> String {
+    let mut out = String::with_capacity(1024);
+    if let Some(current) = &kubeconfig.current_context {
+        let _ = writeln!(out, "- Current context: {}", current);
+    }
+
+    for context in &kubeconfig.contexts {
+        let _ = write!(
+            out,
+            "- Context: {}  cluster:{}",
+            context.name,
+            context.cluster.as_deref().unwrap_or("?")
+        );
+        if let Some(ns) = &context.namespace {
+            let _ = write!(out, "  namespace:{}", ns);
+        }
+
+        match probes.iter().find(|probe| probe.context == context.name) {
+            Some(probe) => match &probe.version {
+                Some(version) => {
+                    let _ = write!(out, "  status:✓ {}", version);
+                    match probe.nodes {
+                        Some(nodes) => {
+                            let _ = write!(out, "  nodes:{}", nodes);
+                        }
+                        None => {
+                            let _ = write!(out, "  nodes:forbidden");
+                        }
+                    }
+                    match &probe.namespaces {
+                        Some(namespaces) => {
+                            let _ =
+                                write!(out, "  namespaces:{}", summarize_namespaces(namespaces));
+                        }
+                        None => {
+                            let _ = write!(out, "  namespaces:forbidden");
+                        }
+                    }
+                }
+                None => {
+                    let _ = write!(out, "  status:✗ unreachable");
+                }
+            },
+            None if live => {
+                let _ = write!(out, "  (not probed)");
+            }
+            None => {}
+        }
+        let _ = writeln!(out);
+    }
+
+    if !live {
+        let _ = writeln!(out, "- kubectl not found; cluster reachability not checked");
+    }
+    out
+}
+
+/// `3 (default, kube-system, web)`, truncated after [`MAX_LISTED_NAMESPACES`].
+fn summarize_namespaces(namespaces: &[String]) -> String {
+    let listed = namespaces
+        .iter()
+        .take(MAX_LISTED_NAMESPACES)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rest = namespaces.len().saturating_sub(MAX_LISTED_NAMESPACES);
+    if namespaces.is_empty() {
+        "0".to_string()
+    } else if rest > 0 {
+        format!("{} ({}, +{} more)", namespaces.len(), listed, rest)
+    } else {
+        format!("{} ({})", namespaces.len(), listed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROD: &str = r#"
+current-context: prod
+contexts:
+- name: prod
+  context:
+    cluster: prod-eks
+    namespace: web
+- name: staging
+  context:
+    cluster: staging-eks
+"#;
+
+    const LOCAL: &str = r#"
+current-context: kind
+contexts:
+- name: kind
+  context:
+    cluster: kind-kind
+- name: staging
+  context:
+    cluster: shadowed
+"#;
+
+    #[test]
+    fn test_merges_kubeconfigs_like_kubectl() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let prod = dir.path().join("prod");
+        let local = dir.path().join("local");
+        std::fs::write(&prod, PROD).expect("write prod");
+        std::fs::write(&local, LOCAL).expect("write local");
+
+        let merged =
+            load_kubeconfig(&[dir.path().join("missing"), prod, local]).expect("merged kubeconfig");
+        assert_eq!(merged.current_context.as_deref(), Some("prod"));
+        let names: Vec<&str> = merged.contexts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["prod", "staging", "kind"]);
+        assert_eq!(merged.contexts[1].cluster.as_deref(), Some("staging-eks"));
+
+        assert_eq!(load_kubeconfig(&[dir.path().join("missing")]), None);
+    }
+
+    #[test]
+    fn test_probes_current_context_first() {
+        let mut kubeconfig = parse_kubeconfig(PROD).expect("parse");
+        kubeconfig.current_context = Some("staging".to_string());
+        assert_eq!(contexts_to_probe(&kubeconfig), vec!["staging", "prod"]);
+    }
+
+    #[test]
+    fn test_format_reports_reachability_and_inventory() {
+        let kubeconfig = parse_kubeconfig(PROD).expect("parse");
+        let namespaces: Vec<String> = (0..12).map(|i| format!("ns{}", i)).collect();
+        let probes = vec![
+            ClusterProbe {
+                context: "prod".to_string(),
+                version: Some("v1.29.3".to_string()),
+                namespaces: Some(namespaces),
+                nodes: None,
+            },
+            ClusterProbe {
+                context: "staging".to_string(),
+                version: None,
+                namespaces: None,
+                nodes: None,
+            },
+        ];
+
+        let out = format_kubernetes(&kubeconfig, &probes, true);
+        assert!(out.contains("- Current context: prod\n"));
+        assert!(out.contains(
+            "- Context: prod  cluster:prod-eks  namespace:web  status:✓ v1.29.3  nodes:forbidden  namespaces:12 (ns0, ns1, ns2, ns3, ns4, ns5, ns6, ns7, ns8, ns9, +2 more)\n"
+        ));
+        assert!(out.contains("- Context: staging  cluster:staging-eks  status:✗ unreachable\n"));
+
+        let offline = format_kubernetes(&kubeconfig, &[], false);
+        assert!(offline.contains("kubectl not found"));
+        assert!(!offline.contains("not probed"));
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            parse_version(r#"{"major":"1","minor":"29","gitVersion":"v1.29.3-eks-1"}"#),
+            Some("v1.29.3-eks-1".to_string())
+        );
+        assert_eq!(parse_version("not json"), None);
+    }
+}
@@ -1,15 +1,16 @@
+use super::platform::{Platform, command_output, command_stdout};
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::process::Command;
 
 /// Discover listening TCP ports on the local machine.
 /// Uses pure /proc parsing on Linux, lsof on macOS, netstat on Windows.
 pub fn discover() -> String {
-    let os = std::env::consts::OS;
-    match os {
-        "linux" => discover_linux(),
-        "macos" => discover_macos(),
-        "windows" => discover_windows(),
-        _ => discover_fallback(),
+    match Platform::current() {
+        Platform::Linux => discover_linux(),
+        Platform::MacOs => discover_macos(),
+        Platform::Windows => discover_windows(),
+        Platform::Other => discover_fallback(),
     }
 }
 
@@ -83,67 +84,130 @@ fn parse_proc_addr(hex_addr: &str, is_v6: bool) -> String {
     "?".to_string()
 }
 
-/// macOS: use lsof (always available).
-fn discover_macos() -> String {
-    let output = match Command::new("lsof")
-        .args(["-iTCP", "-sTCP:LISTEN", "-P", "-n"])
-        .output()
-    {
-        Ok(o) if o.status.success() => o,
-        Ok(_) | Err(_) => return "(failed to run lsof)\n".to_string(),
-    };
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut entries: Vec<String> = Vec::new();
-
-    for line in stdout.lines().skip(1) {
-        // COMMAND PID USER FD TYPE DEVICE SIZE/OFF NODE NAME
-        let fields: Vec<&str> = line.split_whitespace().collect();
-        if fields.len() < 9 {
-            continue;
-        }
-        let command = fields[0];
-        let pid = fields[1];
-        let name = fields[fields.len() - 1]; // last field is the address
-        entries.push(format!("- {} (pid:{} cmd:{})", name, pid, command));
-    }
+/// A listening socket and the process that owns it, when known.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Listener {
+    address: String,
+    pid: Option<u32>,
+    command: Option<String>,
+}
 
-    if entries.is_empty() {
+fn format_listeners(mut listeners: Vec<Listener>) -> String {
+    if listeners.is_empty() {
         return "(no listening ports detected)\n".to_string();
     }
 
-    entries.sort();
-    entries.dedup();
-    entries.join("\n") + "\n"
+    listeners.sort();
+    listeners.dedup();
+
+    let mut out = String::with_capacity(listeners.len() * 48);
+    for listener in &listeners {
+        let _ = match (&listener.pid, &listener.command) {
+            (Some(pid), Some(command)) => {
+                writeln!(out, "- {} (pid:{} cmd:{})", listener.address, pid, command)
+            }
+            (Some(pid), None) => writeln!(out, "- {} (pid:{})", listener.address, pid),
+            _ => writeln!(out, "- {}", listener.address),
+        };
+    }
+    out
 }
 
-/// Windows: use netstat.
-fn discover_windows() -> String {
-    let output = match Command::new("netstat").args(["-an", "-p", "TCP"]).output() {
-        Ok(o) if o.status.success() => o,
-        _ => return "(failed to run netstat)\n".to_string(),
-    };
+/// macOS: lsof's machine-readable output (`-F pcn`), which keeps addresses
+/// and process names intact where the columnar output splits them.
+fn discover_macos() -> String {
+    // lsof exits 1 when nothing is listening, so only a spawn failure is an error.
+    match command_output("lsof", &["-nP", "-iTCP", "-sTCP:LISTEN", "-F", "pcn"]) {
+        Some(stdout) => format_listeners(parse_lsof_fields(&stdout)),
+        None => "(failed to run lsof)\n".to_string(),
+    }
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut entries: Vec<String> = Vec::new();
+/// Parse `lsof -F pcn`: a `p<pid>` / `c<command>` process set followed by
+/// one `n<address>` line per socket.
+fn parse_lsof_fields(stdout: &str) -> Vec<Listener> {
+    let mut listeners = Vec::new();
+    let mut pid = None;
+    let mut command: Option<String> = None;
 
     for line in stdout.lines() {
-        let trimmed = line.trim();
-        if trimmed.contains("LISTENING") {
-            let fields: Vec<&str> = trimmed.split_whitespace().collect();
-            if fields.len() >= 2 {
-                entries.push(format!("- {}", fields[1]));
+        let mut chars = line.chars();
+        let Some(field) = chars.next() else {
+            continue;
+        };
+        let value = chars.as_str();
+        match field {
+            'p' => {
+                pid = value.parse().ok();
+                command = None;
             }
+            'c' => command = Some(value.to_string()),
+            'n' => listeners.push(Listener {
+                address: value.to_string(),
+                pid,
+                command: command.clone(),
+            }),
+            _ => {}
         }
     }
+    listeners
+}
 
-    if entries.is_empty() {
-        return "(no listening ports detected)\n".to_string();
+/// Windows: `netstat -ano` for IPv4 and IPv6 listeners, with owning process
+/// names looked up in `tasklist`.
+fn discover_windows() -> String {
+    let Some(stdout) = command_stdout("netstat", &["-ano", "-p", "TCP"]) else {
+        return "(failed to run netstat)\n".to_string();
+    };
+    let mut listeners = parse_netstat_listeners(&stdout);
+    if let Some(stdout_v6) = command_stdout("netstat", &["-ano", "-p", "TCPv6"]) {
+        listeners.extend(parse_netstat_listeners(&stdout_v6));
+    }
+
+    if let Some(tasks) = command_stdout("tasklist", &["/FO", "CSV", "/NH"]) {
+        let names = parse_tasklist_names(&tasks);
+        for listener in &mut listeners {
+            listener.command = listener.pid.and_then(|pid| names.get(&pid).cloned());
+        }
     }
 
-    entries.sort();
-    entries.dedup();
-    entries.join("\n") + "\n"
+    format_listeners(listeners)
+}
+
+/// Parse `netstat -ano` rows: `TCP <local> <remote> LISTENING <pid>`.
+fn parse_netstat_listeners(stdout: &str) -> Vec<Listener> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                [proto, local, _remote, state, pid]
+                    if proto.eq_ignore_ascii_case("tcp") && *state == "LISTENING" =>
+                {
+                    Some(Listener {
+                        address: local.to_string(),
+                        pid: pid.parse().ok(),
+                        command: None,
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Map PIDs to image names from `tasklist /FO CSV /NH` rows such as
+/// `"svchost.exe","1234","Services","0","12,345 K"`.
+fn parse_tasklist_names(stdout: &str) -> HashMap<u32, String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split("\",\"");
+            let name = columns.next()?.trim_start_matches('"');
+            let pid = columns.next()?.trim_end_matches('"').parse().ok()?;
+            Some((pid, name.to_string()))
+        })
+        .collect()
 }
 
 /// Fallback: try ss, then netstat, then give up.
@@ -174,3 +238,40 @@ fn discover_fallback() -> String {
     }
     "(no method available to detect listening ports)\n".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lsof_fields_keeps_process_per_socket() {
+        let stdout =
+            "p512\ncpostgres\nf7\nn127.0.0.1:5432\nf8\nn[::1]:5432\np900\ncnode\nf21\nn*:3000\n";
+        assert_eq!(
+            format_listeners(parse_lsof_fields(stdout)),
+            "- *:3000 (pid:900 cmd:node)\n\
+             - 127.0.0.1:5432 (pid:512 cmd:postgres)\n\
+             - [::1]:5432 (pid:512 cmd:postgres)\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_netstat_and_tasklist() {
+        let netstat = "\nActive Connections\n\n  Proto  Local Address          Foreign Address        State           PID\n  \
+                       TCP    0.0.0.0:135            0.0.0.0:0              LISTENING       1044\n  \
+                       TCP    10.0.0.5:50123         52.1.2.3:443           ESTABLISHED     2211\n  \
+                       TCP    [::]:445               [::]:0                 LISTENING       4\n";
+        let tasklist = "\"System\",\"4\",\"Services\",\"0\",\"144 K\"\n\"svchost.exe\",\"1044\",\"Services\",\"0\",\"12,345 K\"\n";
+
+        let names = parse_tasklist_names(tasklist);
+        let mut listeners = parse_netstat_listeners(netstat);
+        for listener in &mut listeners {
+            listener.command = listener.pid.and_then(|pid| names.get(&pid).cloned());
+        }
+
+        assert_eq!(
+            format_listeners(listeners),
+            "- 0.0.0.0:135 (pid:1044 cmd:svchost.exe)\n- [::]:445 (pid:4 cmd:System)\n"
+        );
+    }
+}
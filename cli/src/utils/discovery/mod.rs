@@ -1,69 +1,215 @@
 pub mod cloud_accounts;
+pub mod containers;
 pub mod crontabs;
+pub mod environment;
 pub mod git_repos;
+pub mod iac;
+pub mod kubernetes;
 pub mod listening_ports;
+pub mod network;
+mod platform;
 pub mod project_markers;
+pub mod registry;
+pub mod systemd;
+pub mod toolchain;
 
-use std::fmt::Write;
-use tokio::task::JoinSet;
+pub use registry::{DiscoveryProbe, ProbeContext, ProbeRegistry};
+pub use stakpak_shared::discovery::{DiscoveryCache, DiscoveryReport, ProbeResult};
 
-/// Result of a single discovery probe.
-pub struct ProbeResult {
-    pub name: &'static str,
-    pub output: String,
-}
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::{JoinHandle, JoinSet};
 
-/// Run all discovery probes in parallel and return combined output.
-pub async fn run_all() -> String {
-    let mut set: JoinSet<ProbeResult> = JoinSet::new();
+/// Probes run at once by [`BackgroundDiscovery`], so startup discovery does
+/// not compete with the TUI and the first agent call for CPU and disk.
+pub const BACKGROUND_MAX_CONCURRENT_PROBES: usize = 2;
 
-    let home = dirs::home_dir();
-    let cwd = std::env::current_dir().ok();
+/// Run the registry's probes, at most `max_concurrent` at a time, calling
+/// `on_progress(completed, total)` as each one finishes. Results follow
+/// registry order. A probe past its time limit is reported as timed out and
+/// frees its slot; its blocking thread is left to finish.
+pub async fn run_report(
+    registry: &ProbeRegistry,
+    max_concurrent: usize,
+    mut on_progress: impl FnMut(usize, usize),
+) -> DiscoveryReport {
+    let total = registry.probes().len();
+    let limiter = Arc::new(Semaphore::new(
+        max_concurrent.clamp(1, Semaphore::MAX_PERMITS),
+    ));
 
     // All probes run as blocking spawn since they do filesystem I/O
-    let home_c = home.clone();
-    set.spawn_blocking(move || ProbeResult {
-        name: "Git Repositories",
-        output: git_repos::discover(home_c.as_deref()),
-    });
-
-    let home_c = home.clone();
-    let cwd_c = cwd.clone();
-    set.spawn_blocking(move || ProbeResult {
-        name: "Project Markers",
-        output: project_markers::discover(home_c.as_deref(), cwd_c.as_deref()),
-    });
-
-    set.spawn_blocking(move || ProbeResult {
-        name: "Listening Ports",
-        output: listening_ports::discover(),
-    });
-
-    set.spawn_blocking(move || ProbeResult {
-        name: "Crontabs",
-        output: crontabs::discover(),
-    });
-
-    set.spawn_blocking(move || ProbeResult {
-        name: "Cloud Accounts",
-        output: cloud_accounts::discover(),
-    });
-
-    // Collect results, preserving a stable order by name
-    let mut results: Vec<ProbeResult> = Vec::new();
-    while let Some(Ok(result)) = set.join_next().await {
-        results.push(result);
+    let mut set: JoinSet<(usize, ProbeResult)> = JoinSet::new();
+    for (index, entry) in registry.probes().iter().cloned().enumerate() {
+        let limiter = limiter.clone();
+        set.spawn(async move {
+            let _permit = limiter.acquire_owned().await.ok();
+            let started = Instant::now();
+            let ctx = ProbeContext::current(entry.timeout);
+            let probe = entry.probe.clone();
+            let run = tokio::task::spawn_blocking(move || probe.run(&ctx));
+            let (output, timed_out) = match tokio::time::timeout(entry.timeout, run).await {
+                Ok(joined) => (joined.unwrap_or_default(), false),
+                Err(_) => (
+                    format!("(timed out after {}s)\n", entry.timeout.as_secs()),
+                    true,
+                ),
+            };
+            let result = ProbeResult {
+                id: entry.probe.id().to_string(),
+                name: entry.probe.title().to_string(),
+                output,
+                timed_out,
+                duration_ms: started.elapsed().as_millis() as u64,
+            };
+            (index, result)
+        });
     }
-    results.sort_by_key(|r| r.name);
 
-    let mut out = String::with_capacity(4096);
-    for r in &results {
-        if r.output.is_empty() {
-            continue;
+    on_progress(0, total);
+    let mut completed = 0;
+    let mut results: Vec<(usize, ProbeResult)> = Vec::new();
+    while let Some(joined) = set.join_next().await {
+        completed += 1;
+        if let Ok(result) = joined {
+            results.push(result);
         }
-        let _ = writeln!(out, "## {}\n", r.name);
-        let _ = writeln!(out, "{}", r.output.trim());
-        out.push('\n');
+        on_progress(completed, total);
+    }
+
+    results.sort_by_key(|(index, _)| *index);
+    DiscoveryReport::new(
+        std::env::current_dir().ok(),
+        registry.fingerprint(),
+        results.into_iter().map(|(_, result)| result).collect(),
+    )
+}
+
+/// Progress reported by [`BackgroundDiscovery`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscoveryStatus {
+    Running {
+        completed: usize,
+        total: usize,
+    },
+    /// Probes finished; [`BackgroundDiscovery::output`] is now available.
+    Finished,
+}
+
+/// Discovery running in the background while the session starts.
+pub struct BackgroundDiscovery {
+    output: Arc<OnceLock<String>>,
+    handle: JoinHandle<()>,
+}
+
+impl BackgroundDiscovery {
+    /// Start discovery with [`BACKGROUND_MAX_CONCURRENT_PROBES`] probes at a time.
+    ///
+    /// With `cache_max_age`, a cached report for the working directory and
+    /// the same probe selection that is younger than it is used instead of
+    /// running the probes. Fresh results are cached unless a probe timed out.
+    pub fn spawn(
+        registry: ProbeRegistry,
+        cache_max_age: Option<Duration>,
+        mut on_status: impl FnMut(DiscoveryStatus) + Send + 'static,
+    ) -> Self {
+        let output = Arc::new(OnceLock::new());
+        let output_c = output.clone();
+        let handle = tokio::spawn(async move {
+            let cache = std::env::current_dir()
+                .ok()
+                .map(|cwd| DiscoveryCache::for_dir(&cwd));
+            let cached = cache
+                .as_ref()
+                .zip(cache_max_age)
+                .and_then(|(cache, max_age)| cache.load(max_age))
+                .filter(|report| report.selection == registry.fingerprint());
+            let report = match cached {
+                Some(report) => report,
+                None => {
+                    let report = run_report(
+                        &registry,
+                        BACKGROUND_MAX_CONCURRENT_PROBES,
+                        |completed, total| on_status(DiscoveryStatus::Running { completed, total }),
+                    )
+                    .await;
+                    if let Some(cache) = &cache
+                        && !report.results.iter().any(|result| result.timed_out)
+                    {
+                        cache.store(&report);
+                    }
+                    report
+                }
+            };
+            // Store before reporting, so anyone reacting to `Finished` sees the output.
+            let _ = output_c.set(report.to_markdown());
+            on_status(DiscoveryStatus::Finished);
+        });
+        Self { output, handle }
+    }
+
+    /// Combined probe output once discovery has finished with any results.
+    pub fn output(&self) -> Option<&str> {
+        self.output
+            .get()
+            .map(String::as_str)
+            .filter(|output| !output.trim().is_empty())
+    }
+
+    /// Stop discovery; probes already running finish but their output is dropped.
+    pub fn cancel(&self) {
+        self.handle.abort();
+    }
+}
+
+/// Machine profile for server sessions, rescanned in the background every
+/// `refresh` so a long-running autopilot keeps reporting current results.
+pub struct BackgroundMachineProfile {
+    latest: Arc<RwLock<Option<DiscoveryReport>>>,
+    refresh: Duration,
+    handle: JoinHandle<()>,
+}
+
+impl BackgroundMachineProfile {
+    pub fn spawn(registry: ProbeRegistry, refresh: Duration) -> Self {
+        let latest = Arc::new(RwLock::new(None));
+        let latest_c = latest.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                let report =
+                    run_report(&registry, BACKGROUND_MAX_CONCURRENT_PROBES, |_, _| {}).await;
+                if let Some(cwd) = &report.cwd
+                    && !report.results.iter().any(|result| result.timed_out)
+                {
+                    DiscoveryCache::for_dir(cwd).store(&report);
+                }
+                if let Ok(mut latest) = latest_c.write() {
+                    *latest = Some(report);
+                }
+                tokio::time::sleep(refresh).await;
+            }
+        });
+        Self {
+            latest,
+            refresh,
+            handle,
+        }
+    }
+}
+
+impl stakpak_server::MachineProfileSource for BackgroundMachineProfile {
+    /// The latest scan, or until the first one finishes, a cached report
+    /// for `cwd` no older than one refresh interval.
+    fn machine_profile(&self, cwd: &Path) -> Option<DiscoveryReport> {
+        let latest = self.latest.read().ok().and_then(|latest| latest.clone());
+        latest.or_else(|| DiscoveryCache::for_dir(cwd).load(self.refresh))
+    }
+}
+
+impl Drop for BackgroundMachineProfile {
+    fn drop(&mut self) {
+        self.handle.abort();
     }
-    out
 }
@@ -0,0 +1,101 @@
+//! Platform detection and command helpers shared by discovery probes.
+//!
+//! Probes with OS-specific implementations dispatch on [`Platform::current`]
+//! and shell out through these helpers, so a missing or failing tool only
+//! empties that probe's section instead of aborting discovery.
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Operating systems with native probe implementations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Linux,
+    MacOs,
+    Windows,
+    /// Anything else; probes fall back to best-effort Unix tools.
+    Other,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        match std::env::consts::OS {
+            "linux" => Platform::Linux,
+            "macos" => Platform::MacOs,
+            "windows" => Platform::Windows,
+            _ => Platform::Other,
+        }
+    }
+}
+
+/// Stdout of `program`, `None` if it could not be started.
+///
+/// The exit status is ignored: tools like `lsof` exit non-zero when they
+/// simply found nothing.
+pub fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Stdout of `program` when it ran and exited successfully.
+pub fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Stdout of `program` when it exits successfully within `timeout`. Slower
+/// runs are killed, so a hanging network call cannot stall discovery.
+pub fn command_stdout_timeout(program: &str, args: &[&str], timeout: Duration) -> Option<String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Drain stdout while waiting, so a full pipe cannot block the child.
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf);
+        buf
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(25));
+            }
+            _ => {
+                // Not joining the reader: a grandchild (e.g. a credential
+                // plugin) may still hold the pipe open.
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+
+    let buf = reader.join().ok()?;
+    status
+        .success()
+        .then(|| String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// Stdout of a Windows PowerShell script, run without loading a profile.
+pub fn powershell_stdout(script: &str) -> Option<String> {
+    command_stdout(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", script],
+    )
+}
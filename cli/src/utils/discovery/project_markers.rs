@@ -1,4 +1,4 @@
-use ignore::WalkBuilder;
+use stakpak_shared::file_index::{FileIndex, FileIndexOptions};
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
 
@@ -58,6 +58,7 @@ pub fn discover(_home: Option<&Path>, cwd: Option<&Path>) -> String {
     // Deep scan of cwd (if it looks like a project)
     if let Some(dir) = cwd {
         let _ = writeln!(out, "### Working Directory: {}\n", dir.display());
+        let index = FileIndex::open(dir, &FileIndexOptions::project());
 
         // Project language markers
         let mut found_lang = false;
@@ -72,7 +73,7 @@ pub fn discover(_home: Option<&Path>, cwd: Option<&Path>) -> String {
         }
 
         // IaC markers — scan recursively
-        let iac_hits = scan_for_markers(dir, IAC_MARKERS, 5);
+        let iac_hits = scan_for_markers(&index, IAC_MARKERS, 5);
         if !iac_hits.is_empty() {
             let _ = writeln!(out, "IaC:");
             for (tool, path) in &iac_hits {
@@ -109,7 +110,7 @@ pub fn discover(_home: Option<&Path>, cwd: Option<&Path>) -> String {
         }
 
         // Dockerfiles in cwd
-        let dockerfiles = find_files_by_name(dir, 5, |name| {
+        let dockerfiles = find_files_by_name(&index, 5, |name| {
             name == "Dockerfile" || name.starts_with("Dockerfile.") || name.ends_with(".dockerfile")
         });
         if !dockerfiles.is_empty() {
@@ -141,7 +142,7 @@ pub fn discover(_home: Option<&Path>, cwd: Option<&Path>) -> String {
         }
 
         // Env files (existence only)
-        let env_files = find_files_by_name(dir, 4, |name| {
+        let env_files = find_files_by_name(&index, 4, |name| {
             name == ".env"
                 || name.starts_with(".env.")
                 || name == ".env.example"
@@ -161,7 +162,7 @@ pub fn discover(_home: Option<&Path>, cwd: Option<&Path>) -> String {
 
 /// Scan a directory for IaC markers, returning (tool_name, path) pairs.
 fn scan_for_markers(
-    dir: &Path,
+    index: &FileIndex,
     markers: &[(&str, &str)],
     max_depth: usize,
 ) -> Vec<(String, PathBuf)> {
@@ -173,13 +174,13 @@ fn scan_for_markers(
             // Glob-style — handled below via walker
             continue;
         }
-        let target = dir.join(marker);
+        let target = index.root().join(marker);
         if target.exists() {
             hits.push((tool.to_string(), target));
         }
     }
 
-    // For glob markers (e.g. "*.tf"), walk the tree
+    // For glob markers (e.g. "*.tf"), look through the indexed tree
     let glob_markers: Vec<(&str, &str)> = markers
         .iter()
         .filter(|(m, _)| m.starts_with('*'))
@@ -187,16 +188,13 @@ fn scan_for_markers(
         .collect();
 
     if !glob_markers.is_empty() {
-        let walker = WalkBuilder::new(dir)
-            .hidden(true)
-            .git_ignore(true)
-            .max_depth(Some(max_depth))
-            .build();
-
         let mut seen_tools: std::collections::HashSet<String> = std::collections::HashSet::new();
-        for entry in walker.flatten() {
-            let path = entry.path();
-            if !path.is_file() {
+        for path in index.files(max_depth) {
+            // Hidden files and directories are skipped
+            let hidden = path
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+            if hidden {
                 continue;
             }
             let name = path
@@ -206,7 +204,7 @@ fn scan_for_markers(
             for (marker, tool) in &glob_markers {
                 let ext = &marker[1..]; // e.g. ".tf"
                 if name.ends_with(ext) && seen_tools.insert(tool.to_string()) {
-                    hits.push((tool.to_string(), path.to_path_buf()));
+                    hits.push((tool.to_string(), index.absolute(&path)));
                     break;
                 }
             }
@@ -216,28 +214,19 @@ fn scan_for_markers(
     hits
 }
 
-/// Find files matching a name predicate under a directory.
-fn find_files_by_name<F>(dir: &Path, max_depth: usize, predicate: F) -> Vec<PathBuf>
+/// Find indexed files matching a name predicate.
+fn find_files_by_name<F>(index: &FileIndex, max_depth: usize, predicate: F) -> Vec<PathBuf>
 where
     F: Fn(&str) -> bool,
 {
-    let walker = WalkBuilder::new(dir)
-        .hidden(false)
-        .git_ignore(true)
-        .max_depth(Some(max_depth))
-        .build();
-
-    let mut results = Vec::new();
-    for entry in walker.flatten() {
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
-        }
-        if let Some(name) = path.file_name().and_then(|n| n.to_str())
-            && predicate(name)
-        {
-            results.push(path.to_path_buf());
-        }
-    }
-    results
+    index
+        .files(max_depth)
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(&predicate)
+        })
+        .map(|path| index.absolute(&path))
+        .collect()
 }
@@ -0,0 +1,452 @@
+//! Probe registry: which discovery probes run, in what order, and for how long.
+//!
+//! Built-in probes and user-defined script probes share the
+//! [`DiscoveryProbe`] trait. [`ProbeRegistry::from_config`] applies
+//! `[settings.discovery]` and the `--discovery-*` flags on top of them.
+
+use super::platform::command_stdout_timeout;
+use super::{
+    cloud_accounts, containers, crontabs, environment, git_repos, iac, kubernetes, listening_ports,
+    network, project_markers, systemd, toolchain,
+};
+use crate::config::{DiscoveryConfig, ScriptProbeConfig};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Time limit for a probe without a configured one. Probes bound their own
+/// walks and commands well below this; it only catches a probe stuck on I/O.
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// What a probe run gets to work with.
+#[derive(Debug, Clone)]
+pub struct ProbeContext {
+    pub home: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    /// Time limit for this run. Probes that shell out should kill their
+    /// commands past it; others are abandoned when it expires.
+    pub timeout: Duration,
+}
+
+impl ProbeContext {
+    pub fn current(timeout: Duration) -> Self {
+        Self {
+            home: dirs::home_dir(),
+            cwd: std::env::current_dir().ok(),
+            timeout,
+        }
+    }
+}
+
+/// A discovery probe, reported as one `## title` section.
+pub trait DiscoveryProbe: Send + Sync {
+    /// Stable id used in config and on the command line, e.g. `git-repos`.
+    fn id(&self) -> &str;
+
+    /// Section title in the discovery output.
+    fn title(&self) -> &str;
+
+    /// Time limit this probe asks for, below a per-probe `timeouts` entry
+    /// and above the configured default.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Command line of a script probe, part of the cache fingerprint.
+    fn command(&self) -> Option<String> {
+        None
+    }
+
+    /// Run the probe; an empty result drops its section.
+    fn run(&self, ctx: &ProbeContext) -> String;
+}
+
+struct BuiltinProbe {
+    id: &'static str,
+    title: &'static str,
+    run: fn(&ProbeContext) -> String,
+}
+
+impl DiscoveryProbe for BuiltinProbe {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn title(&self) -> &str {
+        self.title
+    }
+
+    fn run(&self, ctx: &ProbeContext) -> String {
+        (self.run)(ctx)
+    }
+}
+
+/// Built-in probes, in default order.
+fn builtins() -> Vec<Arc<dyn DiscoveryProbe>> {
+    let probes: [BuiltinProbe; 12] = [
+        BuiltinProbe {
+            id: "cloud-accounts",
+            title: "Cloud Accounts",
+            run: |_| cloud_accounts::discover(),
+        },
+        BuiltinProbe {
+            id: "containers",
+            title: "Containers",
+            run: |_| containers::discover(),
+        },
+        BuiltinProbe {
+            id: "crontabs",
+            title: "Crontabs",
+            run: |_| crontabs::discover(),
+        },
+        BuiltinProbe {
+            id: "environment",
+            title: "Environment",
+            run: |ctx| environment::discover(ctx.home.as_deref(), ctx.cwd.as_deref()),
+        },
+        BuiltinProbe {
+            id: "git-repos",
+            title: "Git Repositories",
+            run: |ctx| git_repos::discover(ctx.home.as_deref()),
+        },
+        BuiltinProbe {
+            id: "iac",
+            title: "Infrastructure as Code",
+            run: |ctx| iac::discover(ctx.home.as_deref(), ctx.cwd.as_deref()),
+        },
+        BuiltinProbe {
+            id: "kubernetes",
+            title: "Kubernetes",
+            run: |_| kubernetes::discover(),
+        },
+        BuiltinProbe {
+            id: "listening-ports",
+            title: "Listening Ports",
+            run: |_| listening_ports::discover(),
+        },
+        BuiltinProbe {
+            id: "network",
+            title: "Network",
+            run: |ctx| network::discover(ctx.home.as_deref()),
+        },
+        BuiltinProbe {
+            id: "project-markers",
+            title: "Project Markers",
+            run: |ctx| project_markers::discover(ctx.home.as_deref(), ctx.cwd.as_deref()),
+        },
+        BuiltinProbe {
+            id: "systemd",
+            title: "Systemd",
+            run: |_| systemd::discover(),
+        },
+        BuiltinProbe {
+            id: "toolchain",
+            title: "Toolchain",
+            run: |ctx| toolchain::discover(ctx.cwd.as_deref()),
+        },
+    ];
+    probes
+        .into_iter()
+        .map(|probe| Arc::new(probe) as Arc<dyn DiscoveryProbe>)
+        .collect()
+}
+
+/// A user-defined probe: the command's stdout becomes its section.
+struct ScriptProbe {
+    id: String,
+    config: ScriptProbeConfig,
+}
+
+impl ScriptProbe {
+    fn new(config: ScriptProbeConfig) -> Result<Self, String> {
+        let id = probe_id(&config.name);
+        if id.is_empty() {
+            return Err(format!(
+                "Discovery script probe name '{}' has no letters or digits",
+                config.name
+            ));
+        }
+        if config.command.trim().is_empty() {
+            return Err(format!(
+                "Discovery script probe '{}' has no command",
+                config.name
+            ));
+        }
+        Ok(Self { id, config })
+    }
+}
+
+impl DiscoveryProbe for ScriptProbe {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn title(&self) -> &str {
+        &self.config.name
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.config.timeout_secs.map(Duration::from_secs)
+    }
+
+    fn command(&self) -> Option<String> {
+        let mut command = vec![self.config.command.as_str()];
+        command.extend(self.config.args.iter().map(String::as_str));
+        Some(command.join(" "))
+    }
+
+    fn run(&self, ctx: &ProbeContext) -> String {
+        let command = match (self.config.command.strip_prefix("~/"), &ctx.home) {
+            (Some(rest), Some(home)) => home.join(rest).to_string_lossy().into_owned(),
+            _ => self.config.command.clone(),
+        };
+        let args: Vec<&str> = self.config.args.iter().map(String::as_str).collect();
+        match command_stdout_timeout(&command, &args, ctx.timeout) {
+            Some(stdout) => stdout,
+            None => format!("(`{}` failed or timed out)\n", self.config.command),
+        }
+    }
+}
+
+/// Lowercase, dash-separated form of a probe name: `Internal Services` is
+/// `internal-services`.
+fn probe_id(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// A probe selected to run, with its resolved time limit.
+#[derive(Clone)]
+pub struct RegisteredProbe {
+    pub probe: Arc<dyn DiscoveryProbe>,
+    pub timeout: Duration,
+}
+
+/// The probes discovery runs, in run and report order.
+#[derive(Clone)]
+pub struct ProbeRegistry {
+    probes: Vec<RegisteredProbe>,
+}
+
+impl Default for ProbeRegistry {
+    /// Every built-in probe with its default time limit.
+    fn default() -> Self {
+        Self {
+            probes: builtins()
+                .into_iter()
+                .map(|probe| RegisteredProbe {
+                    timeout: probe.timeout().unwrap_or(DEFAULT_PROBE_TIMEOUT),
+                    probe,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ProbeRegistry {
+    /// Built-in and script probes, filtered, ordered and timed by `config`.
+    /// Probe ids that match nothing are an error, so a typo does not
+    /// silently run everything.
+    pub fn from_config(config: &DiscoveryConfig) -> Result<Self, String> {
+        let mut available = builtins();
+        for script in &config.scripts {
+            let probe = ScriptProbe::new(script.clone())?;
+            if available.iter().any(|existing| existing.id() == probe.id) {
+                return Err(format!(
+                    "Discovery script probe '{}' clashes with the '{}' probe",
+                    script.name, probe.id
+                ));
+            }
+            available.push(Arc::new(probe));
+        }
+
+        let referenced = config
+            .enable
+            .iter()
+            .flatten()
+            .chain(&config.disable)
+            .chain(&config.order)
+            .chain(config.timeouts.keys());
+        for id in referenced {
+            if !available.iter().any(|probe| probe.id() == id) {
+                let known: Vec<&str> = available.iter().map(|probe| probe.id()).collect();
+                return Err(format!(
+                    "Unknown discovery probe '{}'. Available probes: {}",
+                    id,
+                    known.join(", ")
+                ));
+            }
+        }
+
+        available.retain(|probe| {
+            let id = probe.id().to_string();
+            config.enable.as_ref().is_none_or(|ids| ids.contains(&id))
+                && !config.disable.contains(&id)
+        });
+        // Stable sort: listed probes first in listed order, the rest as before.
+        available.sort_by_key(|probe| {
+            config
+                .order
+                .iter()
+                .position(|id| id == probe.id())
+                .unwrap_or(config.order.len())
+        });
+
+        let probes = available
+            .into_iter()
+            .map(|probe| {
+                let timeout = config
+                    .timeouts
+                    .get(probe.id())
+                    .map(|secs| Duration::from_secs(*secs))
+                    .or_else(|| probe.timeout())
+                    .or(config.timeout_secs.map(Duration::from_secs))
+                    .unwrap_or(DEFAULT_PROBE_TIMEOUT);
+                RegisteredProbe { probe, timeout }
+            })
+            .collect();
+        Ok(Self { probes })
+    }
+
+    pub fn probes(&self) -> &[RegisteredProbe] {
+        &self.probes
+    }
+
+    /// Identifies the selected probes and what they run, so a cached report
+    /// is not reused after the selection or a script probe changes.
+    pub fn fingerprint(&self) -> String {
+        self.probes
+            .iter()
+            .map(|entry| match entry.probe.command() {
+                Some(command) => format!("{}={}", entry.probe.id(), command),
+                None => entry.probe.id().to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn ids(registry: &ProbeRegistry) -> Vec<&str> {
+        registry
+            .probes()
+            .iter()
+            .map(|entry| entry.probe.id())
+            .collect()
+    }
+
+    fn script(name: &str, command: &str) -> ScriptProbeConfig {
+        ScriptProbeConfig {
+            name: name.to_string(),
+            command: command.to_string(),
+            args: Vec::new(),
+            timeout_secs: None,
+        }
+    }
+
+    #[test]
+    fn test_enable_disable_and_order() {
+        let config = DiscoveryConfig {
+            enable: Some(vec![
+                "systemd".to_string(),
+                "toolchain".to_string(),
+                "git-repos".to_string(),
+                "crontabs".to_string(),
+            ]),
+            disable: vec!["crontabs".to_string()],
+            order: vec!["toolchain".to_string()],
+            ..Default::default()
+        };
+        let registry = ProbeRegistry::from_config(&config).expect("valid config");
+        assert_eq!(ids(&registry), vec!["toolchain", "git-repos", "systemd"]);
+        assert_eq!(registry.fingerprint(), "toolchain,git-repos,systemd");
+
+        assert_eq!(
+            ProbeRegistry::from_config(&DiscoveryConfig::default())
+                .expect("default config")
+                .probes()
+                .len(),
+            ProbeRegistry::default().probes().len()
+        );
+    }
+
+    #[test]
+    fn test_unknown_probe_ids_are_rejected() {
+        let config = DiscoveryConfig {
+            disable: vec!["systemctl".to_string()],
+            ..Default::default()
+        };
+        let err = ProbeRegistry::from_config(&config)
+            .err()
+            .expect("unknown id is an error");
+        assert!(err.contains("'systemctl'"), "{err}");
+        assert!(err.contains("systemd"), "{err}");
+
+        let clash = DiscoveryConfig {
+            scripts: vec![script("Kubernetes", "kubectl")],
+            ..Default::default()
+        };
+        assert!(ProbeRegistry::from_config(&clash).is_err());
+    }
+
+    #[test]
+    fn test_timeouts_resolve_most_specific_first() {
+        let mut slow = script("Internal Services", "list-services");
+        slow.args = vec!["--short".to_string()];
+        slow.timeout_secs = Some(90);
+        let config = DiscoveryConfig {
+            enable: Some(vec![
+                "kubernetes".to_string(),
+                "toolchain".to_string(),
+                "internal-services".to_string(),
+            ]),
+            timeout_secs: Some(20),
+            timeouts: BTreeMap::from([("kubernetes".to_string(), 5)]),
+            scripts: vec![slow],
+            ..Default::default()
+        };
+        let registry = ProbeRegistry::from_config(&config).expect("valid config");
+        let timeouts: Vec<(&str, u64)> = registry
+            .probes()
+            .iter()
+            .map(|entry| (entry.probe.id(), entry.timeout.as_secs()))
+            .collect();
+        assert_eq!(
+            timeouts,
+            vec![
+                ("kubernetes", 5),
+                ("toolchain", 20),
+                ("internal-services", 90)
+            ]
+        );
+        assert_eq!(
+            registry.fingerprint(),
+            "kubernetes,toolchain,internal-services=list-services --short"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_script_probe_reports_stdout() {
+        let mut echo = script("Team Notes", "echo");
+        echo.args = vec!["- On call: platform".to_string()];
+        let probe = ScriptProbe::new(echo).expect("valid script");
+        assert_eq!(probe.id(), "team-notes");
+
+        let ctx = ProbeContext::current(Duration::from_secs(5));
+        assert_eq!(probe.run(&ctx), "- On call: platform\n");
+
+        let missing = ScriptProbe::new(script("Missing", "/nonexistent/probe")).expect("valid");
+        assert_eq!(
+            missing.run(&ctx),
+            "(`/nonexistent/probe` failed or timed out)\n"
+        );
+    }
+}
@@ -0,0 +1,492 @@
+use super::platform::{Platform, command_stdout_timeout};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Upper bound for each `systemctl` call; a wedged bus must not stall discovery.
+const SYSTEMCTL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Services listed per scope before summarizing the rest.
+const MAX_LISTED_SERVICES: usize = 30;
+
+/// Failed units and timers listed per scope.
+const MAX_LISTED_UNITS: usize = 20;
+
+/// Enabled services every systemd host has; hidden so the host's own
+/// services stand out.
+const PLUMBING_PREFIXES: &[&str] = &[
+    "systemd-",
+    "getty@",
+    "serial-getty@",
+    "autovt@",
+    "console-",
+    "user@",
+    "user-runtime-dir@",
+    "dbus",
+    "modprobe@",
+    "emergency.",
+    "rescue.",
+];
+
+/// Discover enabled services, failed units and timers from systemd, for the
+/// system manager and the user manager. Linux only; only read-only
+/// `list-*` and `show` calls are made, each timeboxed.
+pub fn discover() -> String {
+    if !matches!(Platform::current(), Platform::Linux | Platform::Other)
+        || which::which("systemctl").is_err()
+    {
+        return String::new();
+    }
+
+    let handles: Vec<_> = [Scope::System, Scope::User]
+        .into_iter()
+        .map(|scope| std::thread::spawn(move || probe_scope(scope)))
+        .collect();
+    let mut out = String::new();
+    for probe in handles
+        .into_iter()
+        .filter_map(|handle| handle.join().ok().flatten())
+    {
+        format_scope(&probe, &mut out);
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    System,
+    User,
+}
+
+impl Scope {
+    fn label(self) -> &'static str {
+        match self {
+            Scope::System => "System",
+            Scope::User => "User",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ScopeProbe {
+    scope: Scope,
+    services: Vec<Service>,
+    /// Plumbing services left out of `services`.
+    hidden_services: usize,
+    failed: Vec<FailedUnit>,
+    timers: Vec<Timer>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Service {
+    name: String,
+    /// `running`, `exited`, `dead`...; `None` when the unit is not loaded.
+    state: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FailedUnit {
+    name: String,
+    description: String,
+    since: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Timer {
+    name: String,
+    activates: String,
+    next: Option<String>,
+}
+
+/// A row of `systemctl list-units --plain`.
+#[derive(Debug, Clone, PartialEq)]
+struct LoadedUnit {
+    name: String,
+    active: String,
+    sub: String,
+    description: String,
+}
+
+/// `None` when this manager is unreachable, e.g. no user session bus.
+fn probe_scope(scope: Scope) -> Option<ScopeProbe> {
+    let systemctl = |args: &[&str]| {
+        let mut full = Vec::with_capacity(args.len() + 3);
+        if scope == Scope::User {
+            full.push("--user");
+        }
+        full.extend_from_slice(args);
+        full.extend_from_slice(&["--no-legend", "--no-pager"]);
+        command_stdout_timeout("systemctl", &full, SYSTEMCTL_TIMEOUT)
+    };
+
+    let units = parse_list_units(&systemctl(&["list-units", "--all", "--plain"])?);
+    let enabled = systemctl(&["list-unit-files", "--type=service", "--state=enabled"])
+        .map(|stdout| parse_unit_files(&stdout))
+        .unwrap_or_default();
+    let timers = systemctl(&["list-timers", "--all"])
+        .map(|stdout| parse_timers(&stdout))
+        .unwrap_or_default();
+
+    let mut failed: Vec<FailedUnit> = units
+        .iter()
+        .filter(|unit| unit.active == "failed")
+        .map(|unit| FailedUnit {
+            name: unit.name.clone(),
+            description: unit.description.clone(),
+            since: None,
+        })
+        .collect();
+    if !failed.is_empty() {
+        let mut args = vec!["show", "--property=Id,StateChangeTimestamp"];
+        args.extend(
+            failed
+                .iter()
+                .take(MAX_LISTED_UNITS)
+                .map(|u| u.name.as_str()),
+        );
+        if let Some(stdout) = systemctl(&args) {
+            let since = parse_state_change_timestamps(&stdout);
+            for unit in &mut failed {
+                unit.since = since.get(&unit.name).cloned();
+            }
+        }
+    }
+
+    let (services, hidden_services) = enabled_services(&enabled, &units);
+    Some(ScopeProbe {
+        scope,
+        services,
+        hidden_services,
+        failed,
+        timers,
+    })
+}
+
+fn parse_list_units(stdout: &str) -> Vec<LoadedUnit> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mut name = fields.next()?;
+            // Older systemd marks failed units with a bullet even in plain mode.
+            if name == "●" || name == "*" {
+                name = fields.next()?;
+            }
+            let _load = fields.next()?;
+            let active = fields.next()?;
+            let sub = fields.next()?;
+            Some(LoadedUnit {
+                name: name.to_string(),
+                active: active.to_string(),
+                sub: sub.to_string(),
+                description: fields.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+/// Unit file names from `list-unit-files` rows (`name state [preset]`).
+fn parse_unit_files(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
+/// `list-timers` rows end in `UNIT ACTIVATES`; NEXT leads as
+/// `Tue 2026-10-13 00:00:00 UTC` or `n/a` for timers that will not fire.
+fn parse_timers(stdout: &str) -> Vec<Timer> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (activates, rest) = fields.split_last()?;
+            let (name, _) = rest.split_last()?;
+            if !name.ends_with(".timer") {
+                return None;
+            }
+            let next = match fields.first() {
+                Some(&"n/a") | Some(&"-") | None => None,
+                Some(_) => fields.get(..4).map(|next| next.join(" ")),
+            };
+            Some(Timer {
+                name: name.to_string(),
+                activates: activates.to_string(),
+                next,
+            })
+        })
+        .collect()
+}
+
+/// `Id=` / `StateChangeTimestamp=` blocks from `systemctl show`.
+fn parse_state_change_timestamps(stdout: &str) -> HashMap<String, String> {
+    let mut timestamps = HashMap::new();
+    let mut id = None;
+    let mut timestamp = None;
+    for line in stdout.lines().chain(std::iter::once("")) {
+        if let Some(value) = line.strip_prefix("Id=") {
+            id = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("StateChangeTimestamp=") {
+            timestamp = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+        } else if line.trim().is_empty() {
+            if let (Some(id), Some(timestamp)) = (id.take(), timestamp.take()) {
+                timestamps.insert(id, timestamp);
+            }
+            id = None;
+            timestamp = None;
+        }
+    }
+    timestamps
+}
+
+/// Enabled services with their state, expanding `name@.service` templates
+/// to their loaded instances. Returns the services and how many plumbing
+/// services were left out.
+fn enabled_services(enabled: &[String], units: &[LoadedUnit]) -> (Vec<Service>, usize) {
+    let loaded: HashMap<&str, &LoadedUnit> = units
+        .iter()
+        .map(|unit| (unit.name.as_str(), unit))
+        .collect();
+    let mut seen = HashSet::new();
+    let mut services = Vec::new();
+    let mut hidden = 0;
+    for name in enabled {
+        if PLUMBING_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+        {
+            hidden += 1;
+            continue;
+        }
+        let instances: Vec<&LoadedUnit> = match name.strip_suffix("@.service") {
+            Some(template) => units
+                .iter()
+                .filter(|unit| {
+                    unit.name.starts_with(&format!("{}@", template))
+                        && unit.name.ends_with(".service")
+                })
+                .collect(),
+            None => loaded.get(name.as_str()).copied().into_iter().collect(),
+        };
+        if instances.is_empty() {
+            if seen.insert(name.clone()) {
+                services.push(Service {
+                    name: name.clone(),
+                    state: None,
+                    description: None,
+                });
+            }
+            continue;
+        }
+        for unit in instances {
+            if seen.insert(unit.name.clone()) {
+                services.push(Service {
+                    name: unit.name.clone(),
+                    state: Some(unit.sub.clone()),
+                    description: Some(unit.description.clone()).filter(|d| !d.is_empty()),
+                });
+            }
+        }
+    }
+    (services, hidden)
+}
+
+fn format_scope(probe: &ScopeProbe, out: &mut String) {
+    let label = probe.scope.label();
+    let running = probe
+        .services
+        .iter()
+        .filter(|service| service.state.as_deref() == Some("running"))
+        .count();
+    let _ = write!(
+        out,
+        "- {} services enabled:{}  running:{}",
+        label,
+        probe.services.len(),
+        running
+    );
+    if probe.hidden_services > 0 {
+        let _ = write!(
+            out,
+            "  (+{} systemd plumbing hidden)",
+            probe.hidden_services
+        );
+    }
+    let _ = writeln!(out);
+    for service in probe.services.iter().take(MAX_LISTED_SERVICES) {
+        let _ = write!(
+            out,
+            "  - {}  {}",
+            service.name,
+            service.state.as_deref().unwrap_or("not loaded")
+        );
+        if let Some(description) = &service.description {
+            let _ = write!(out, "  {}", description);
+        }
+        let _ = writeln!(out);
+    }
+    let rest = probe.services.len().saturating_sub(MAX_LISTED_SERVICES);
+    if rest > 0 {
+        let _ = writeln!(out, "  - +{} more", rest);
+    }
+
+    if !probe.failed.is_empty() {
+        let _ = writeln!(out, "- {} failed units:{}", label, probe.failed.len());
+        for unit in probe.failed.iter().take(MAX_LISTED_UNITS) {
+            let _ = write!(out, "  - {}", unit.name);
+            if let Some(since) = &unit.since {
+                let _ = write!(out, "  since:{}", since);
+            }
+            if !unit.description.is_empty() {
+                let _ = write!(out, "  {}", unit.description);
+            }
+            let _ = writeln!(out);
+        }
+    }
+
+    if !probe.timers.is_empty() {
+        let _ = writeln!(out, "- {} timers:{}", label, probe.timers.len());
+        for timer in probe.timers.iter().take(MAX_LISTED_UNITS) {
+            let _ = writeln!(
+                out,
+                "  - {}  activates:{}  next:{}",
+                timer.name,
+                timer.activates,
+                timer.next.as_deref().unwrap_or("n/a")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST_UNITS: &str = "\
+nginx.service          loaded active   running A high performance web server
+postgresql@16-main.service loaded active running PostgreSQL Cluster 16-main
+backup.service         loaded failed   failed  Nightly backup
+● mnt-data.mount       loaded failed   failed  /mnt/data
+systemd-journald.service loaded active running Journal Service
+logrotate.timer        loaded active   waiting Daily rotation of log files
+";
+
+    #[test]
+    fn test_parse_list_units_handles_failed_bullet() {
+        let units = parse_list_units(LIST_UNITS);
+        assert_eq!(units.len(), 6);
+        assert_eq!(
+            units[3],
+            LoadedUnit {
+                name: "mnt-data.mount".to_string(),
+                active: "failed".to_string(),
+                sub: "failed".to_string(),
+                description: "/mnt/data".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_enabled_services_expand_templates_and_hide_plumbing() {
+        let units = parse_list_units(LIST_UNITS);
+        let enabled = parse_unit_files(
+            "nginx.service enabled enabled\n\
+             postgresql@.service enabled enabled\n\
+             systemd-journald.service enabled enabled\n\
+             getty@.service enabled enabled\n\
+             myapp.service enabled disabled\n",
+        );
+
+        let (services, hidden) = enabled_services(&enabled, &units);
+        let names: Vec<&str> = services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "nginx.service",
+                "postgresql@16-main.service",
+                "myapp.service"
+            ]
+        );
+        assert_eq!(hidden, 2);
+        assert_eq!(services[1].state.as_deref(), Some("running"));
+        assert_eq!(services[2].state, None);
+    }
+
+    #[test]
+    fn test_parse_timers_and_failure_timestamps() {
+        let timers = parse_timers(
+            "Tue 2026-10-13 00:00:00 UTC 5h left Mon 2026-10-12 00:00:02 UTC 18h ago logrotate.timer logrotate.service\n\
+             n/a n/a n/a n/a certbot.timer certbot.service\n",
+        );
+        assert_eq!(
+            timers,
+            vec![
+                Timer {
+                    name: "logrotate.timer".to_string(),
+                    activates: "logrotate.service".to_string(),
+                    next: Some("Tue 2026-10-13 00:00:00 UTC".to_string()),
+                },
+                Timer {
+                    name: "certbot.timer".to_string(),
+                    activates: "certbot.service".to_string(),
+                    next: None,
+                },
+            ]
+        );
+
+        let since = parse_state_change_timestamps(
+            "Id=backup.service\nStateChangeTimestamp=Mon 2026-10-12 03:00:14 UTC\n\n\
+             Id=mnt-data.mount\nStateChangeTimestamp=\n",
+        );
+        assert_eq!(
+            since.get("backup.service").map(String::as_str),
+            Some("Mon 2026-10-12 03:00:14 UTC")
+        );
+        assert!(!since.contains_key("mnt-data.mount"));
+    }
+
+    #[test]
+    fn test_format_scope() {
+        let probe = ScopeProbe {
+            scope: Scope::System,
+            services: vec![
+                Service {
+                    name: "nginx.service".to_string(),
+                    state: Some("running".to_string()),
+                    description: Some("A high performance web server".to_string()),
+                },
+                Service {
+                    name: "myapp.service".to_string(),
+                    state: None,
+                    description: None,
+                },
+            ],
+            hidden_services: 2,
+            failed: vec![FailedUnit {
+                name: "backup.service".to_string(),
+                description: "Nightly backup".to_string(),
+                since: Some("Mon 2026-10-12 03:00:14 UTC".to_string()),
+            }],
+            timers: vec![Timer {
+                name: "certbot.timer".to_string(),
+                activates: "certbot.service".to_string(),
+                next: None,
+            }],
+        };
+
+        let mut out = String::new();
+        format_scope(&probe, &mut out);
+        assert_eq!(
+            out,
+            "- System services enabled:2  running:1  (+2 systemd plumbing hidden)\n\
+             \x20 - nginx.service  running  A high performance web server\n\
+             \x20 - myapp.service  not loaded\n\
+             - System failed units:1\n\
+             \x20 - backup.service  since:Mon 2026-10-12 03:00:14 UTC  Nightly backup\n\
+             - System timers:1\n\
+             \x20 - certbot.timer  activates:certbot.service  next:n/a\n"
+        );
+    }
+}
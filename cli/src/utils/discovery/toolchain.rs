@@ -0,0 +1,327 @@
+use super::platform::command_stdout_timeout;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// Upper bound for each `--version` call; some CLIs (gcloud, az) start slowly.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Tool {
+    label: &'static str,
+    /// Executables tried in order; the first one on `PATH` is used.
+    programs: &'static [&'static str],
+    args: &'static [&'static str],
+}
+
+const fn tool(
+    label: &'static str,
+    programs: &'static [&'static str],
+    args: &'static [&'static str],
+) -> Tool {
+    Tool {
+        label,
+        programs,
+        args,
+    }
+}
+
+/// Tools checked, grouped as they are reported.
+const GROUPS: &[(&str, &[Tool])] = &[
+    (
+        "Infrastructure",
+        &[
+            tool("kubectl", &["kubectl"], &["version", "--client"]),
+            tool("helm", &["helm"], &["version", "--short"]),
+            tool("terraform", &["terraform"], &["version"]),
+            tool("tofu", &["tofu"], &["version"]),
+            tool("terragrunt", &["terragrunt"], &["--version"]),
+            tool("pulumi", &["pulumi"], &["version"]),
+            tool("ansible", &["ansible"], &["--version"]),
+        ],
+    ),
+    (
+        "Cloud CLIs",
+        &[
+            tool("aws", &["aws"], &["--version"]),
+            tool("gcloud", &["gcloud"], &["--version"]),
+            tool("az", &["az"], &["version", "--output", "tsv"]),
+        ],
+    ),
+    (
+        "Containers",
+        &[
+            tool("docker", &["docker"], &["--version"]),
+            tool("podman", &["podman"], &["--version"]),
+        ],
+    ),
+    (
+        "Languages",
+        &[
+            tool("node", &["node"], &["--version"]),
+            tool("python", &["python3", "python"], &["--version"]),
+            tool("go", &["go"], &["version"]),
+            tool("rust", &["rustc"], &["--version"]),
+            tool("java", &["java"], &["--version"]),
+        ],
+    ),
+    (
+        "Package managers",
+        &[
+            tool("npm", &["npm"], &["--version"]),
+            tool("pnpm", &["pnpm"], &["--version"]),
+            tool("yarn", &["yarn"], &["--version"]),
+            tool("bun", &["bun"], &["--version"]),
+            tool("pip", &["pip3", "pip"], &["--version"]),
+            tool("uv", &["uv"], &["--version"]),
+            tool("poetry", &["poetry"], &["--version"]),
+            tool("pipenv", &["pipenv"], &["--version"]),
+            tool("cargo", &["cargo"], &["--version"]),
+            tool("bundler", &["bundle"], &["--version"]),
+            tool("composer", &["composer"], &["--version"]),
+            tool("brew", &["brew"], &["--version"]),
+            tool("apt", &["apt-get"], &["--version"]),
+            tool("dnf", &["dnf"], &["--version"]),
+            tool("yum", &["yum"], &["--version"]),
+            tool("apk", &["apk"], &["--version"]),
+            tool("pacman", &["pacman"], &["--version"]),
+            tool("winget", &["winget"], &["--version"]),
+            tool("choco", &["choco"], &["--version"]),
+            tool("scoop", &["scoop"], &["--version"]),
+        ],
+    ),
+];
+
+/// Lockfiles and manifests that show which package manager a project uses.
+const PROJECT_MANAGERS: &[(&str, &str)] = &[
+    ("pnpm-lock.yaml", "pnpm"),
+    ("yarn.lock", "yarn"),
+    ("bun.lock", "bun"),
+    ("bun.lockb", "bun"),
+    ("package-lock.json", "npm"),
+    ("uv.lock", "uv"),
+    ("poetry.lock", "poetry"),
+    ("Pipfile.lock", "pipenv"),
+    ("requirements.txt", "pip"),
+    ("Cargo.lock", "cargo"),
+    ("go.sum", "go"),
+    ("Gemfile.lock", "bundler"),
+    ("composer.lock", "composer"),
+];
+
+/// Discover installed tooling with versions, grouped by kind, and the
+/// package managers the working directory's project uses. Only local
+/// `--version` style calls are made, each timeboxed and run in parallel.
+pub fn discover(cwd: Option<&Path>) -> String {
+    let handles: Vec<_> = GROUPS
+        .iter()
+        .flat_map(|(group, tools)| tools.iter().map(move |tool| (*group, tool)))
+        .filter_map(|(group, tool)| {
+            let program = tool
+                .programs
+                .iter()
+                .find(|program| which::which(program).is_ok())?;
+            Some(std::thread::spawn(move || {
+                let version = command_stdout_timeout(program, tool.args, VERSION_TIMEOUT)
+                    .and_then(|stdout| parse_version(&stdout));
+                (group, tool.label, version)
+            }))
+        })
+        .collect();
+    let installed: Vec<(&str, &str, Option<String>)> = handles
+        .into_iter()
+        .filter_map(|handle| handle.join().ok())
+        .collect();
+
+    let active = cwd.map(project_managers).unwrap_or_default();
+    format_toolchain(&installed, &active)
+}
+
+/// First version-looking token of a `--version` output, without a leading
+/// `v`/`go` or trailing punctuation: `go version go1.22.0 linux/amd64` is
+/// `1.22.0`, `Docker version 25.0.3, build 4debf41` is `25.0.3`.
+fn parse_version(stdout: &str) -> Option<String> {
+    stdout.split_whitespace().find_map(|token| {
+        let token = token
+            .trim_start_matches(|c: char| !c.is_ascii_digit())
+            .trim_end_matches(|c: char| !c.is_ascii_alphanumeric());
+        let token = token.split('+').next().unwrap_or(token);
+        let mut parts = token.split('.');
+        let numeric = |part: Option<&str>| {
+            part.and_then(|part| part.chars().next())
+                .is_some_and(|c| c.is_ascii_digit())
+        };
+        (token.starts_with(|c: char| c.is_ascii_digit())
+            && numeric(parts.next())
+            && numeric(parts.next()))
+        .then(|| token.to_string())
+    })
+}
+
+/// Package managers `dir`'s project uses, with the file that shows it. A
+/// `packageManager` field in `package.json` (Corepack) wins over lockfiles.
+fn project_managers(dir: &Path) -> Vec<(String, String)> {
+    let mut managers: Vec<(String, String)> = Vec::new();
+    if let Some(manager) = std::fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|package| {
+            package
+                .get("packageManager")
+                .and_then(|v| v.as_str())
+                .and_then(|spec| spec.split('@').next())
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+        })
+    {
+        managers.push((manager, "package.json packageManager".to_string()));
+    }
+    for (file, manager) in PROJECT_MANAGERS {
+        let is_node = matches!(*manager, "pnpm" | "yarn" | "bun" | "npm");
+        let has_node_manager = managers
+            .iter()
+            .any(|(name, _)| matches!(name.as_str(), "pnpm" | "yarn" | "bun" | "npm"));
+        if (is_node && has_node_manager) || !dir.join(file).is_file() {
+            continue;
+        }
+        managers.push((manager.to_string(), file.to_string()));
+    }
+    managers
+}
+
+fn format_toolchain(
+    installed: &[(&str, &str, Option<String>)],
+    active: &[(String, String)],
+) -> String {
+    let mut out = String::new();
+    for (group, _) in GROUPS {
+        let tools: Vec<String> = installed
+            .iter()
+            .filter(|(tool_group, _, _)| tool_group == group)
+            .map(|(_, label, version)| match version {
+                Some(version) => format!("{} {}", label, version),
+                None => label.to_string(),
+            })
+            .collect();
+        if !tools.is_empty() {
+            let _ = writeln!(out, "- {}: {}", group, tools.join(", "));
+        }
+    }
+    if !active.is_empty() {
+        let managers: Vec<String> = active
+            .iter()
+            .map(|(manager, source)| {
+                if installed.iter().any(|(_, label, _)| label == manager) {
+                    format!("{} ({})", manager, source)
+                } else {
+                    format!("{} ({}, not installed)", manager, source)
+                }
+            })
+            .collect();
+        let _ = writeln!(out, "- Project uses: {}", managers.join(", "));
+    }
+    if out.is_empty() {
+        out.push_str("(no known tooling found)\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version_outputs() {
+        let cases = [
+            (
+                "Client Version: v1.30.2\nKustomize Version: v5.0.4",
+                "1.30.2",
+            ),
+            ("v3.14.0+g3fc9f4b", "3.14.0"),
+            ("Terraform v1.7.5\non linux_amd64\n", "1.7.5"),
+            (
+                "aws-cli/2.15.0 Python/3.11.6 Linux/6.5 exe/x86_64",
+                "2.15.0",
+            ),
+            ("Docker version 25.0.3, build 4debf41", "25.0.3"),
+            ("go version go1.22.0 linux/amd64", "1.22.0"),
+            ("rustc 1.79.0 (129f3b996 2024-06-10)", "1.79.0"),
+            ("Poetry (version 1.7.1)", "1.7.1"),
+            (
+                "pip 23.3.1 from /usr/lib/python3/dist-packages/pip (python 3.12)",
+                "23.3.1",
+            ),
+            ("azure-cli\t2.57.0", "2.57.0"),
+        ];
+        for (stdout, expected) in cases {
+            assert_eq!(parse_version(stdout).as_deref(), Some(expected), "{stdout}");
+        }
+        assert_eq!(parse_version("Homebrew 4 beta"), None);
+    }
+
+    #[test]
+    fn test_package_manager_field_wins_over_node_lockfiles() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"name": "web", "packageManager": "pnpm@9.1.0"}"#,
+        )
+        .expect("write package.json");
+        for file in ["package-lock.json", "yarn.lock", "uv.lock"] {
+            std::fs::write(dir.path().join(file), "").expect("write lockfile");
+        }
+
+        assert_eq!(
+            project_managers(dir.path()),
+            vec![
+                (
+                    "pnpm".to_string(),
+                    "package.json packageManager".to_string()
+                ),
+                ("uv".to_string(), "uv.lock".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lockfiles_without_package_manager_field() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("package.json"), r#"{"name": "web"}"#)
+            .expect("write package.json");
+        for file in ["yarn.lock", "Cargo.lock"] {
+            std::fs::write(dir.path().join(file), "").expect("write lockfile");
+        }
+
+        assert_eq!(
+            project_managers(dir.path()),
+            vec![
+                ("yarn".to_string(), "yarn.lock".to_string()),
+                ("cargo".to_string(), "Cargo.lock".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_groups_tools_and_flags_missing_managers() {
+        let installed = vec![
+            ("Languages", "node", Some("20.11.0".to_string())),
+            ("Infrastructure", "kubectl", Some("1.30.2".to_string())),
+            ("Cloud CLIs", "aws", None),
+            ("Package managers", "npm", Some("10.2.4".to_string())),
+            ("Infrastructure", "helm", Some("3.14.0".to_string())),
+        ];
+        let active = vec![
+            ("npm".to_string(), "package-lock.json".to_string()),
+            ("uv".to_string(), "uv.lock".to_string()),
+        ];
+
+        assert_eq!(
+            format_toolchain(&installed, &active),
+            "- Infrastructure: kubectl 1.30.2, helm 3.14.0\n\
+             - Cloud CLIs: aws\n\
+             - Languages: node 20.11.0\n\
+             - Package managers: npm 10.2.4\n\
+             - Project uses: npm (package-lock.json), uv (uv.lock, not installed)\n"
+        );
+        assert_eq!(format_toolchain(&[], &[]), "(no known tooling found)\n");
+    }
+}
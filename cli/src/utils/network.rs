@@ -48,3 +48,12 @@ pub async fn find_available_bind_address_with_listener() -> Result<(String, TcpL
 
     Ok((bind_address, listener))
 }
+
+/// Like [`find_available_bind_address_with_listener`], but always on
+/// 127.0.0.1, for servers that must not be reachable from other hosts.
+pub async fn find_available_loopback_address_with_listener() -> Result<(String, TcpListener), String>
+{
+    let host = "127.0.0.1";
+    let (listener, port) = find_available_port_with_listener(host).await?;
+    Ok((format!("{}:{}", host, port), listener))
+}
@@ -1,5 +1,6 @@
 use crate::{
     approval::ApprovalStateMachine,
+    budget_context::BudgetAwareContextReducer,
     compaction::CompactionEngine,
     context::ContextReducer,
     error::AgentError,
@@ -13,6 +14,7 @@ use crate::{
 };
 use serde_json::json;
 use stakai::{ContentPart, FinishReasonKind, Message, MessageContent, Role};
+use stakpak_shared::error::ErrorKind;
 use std::collections::{HashMap, HashSet, VecDeque};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
@@ -132,6 +134,19 @@ pub async fn run_agent(
         )
         .await;
 
+        if config.compaction.enabled && compactor.should_compact(&messages, &current_model) {
+            compact_history(
+                &run,
+                &event_tx,
+                compactor,
+                &mut messages,
+                &current_model,
+                context_metadata,
+                "Conversation history exceeds the context budget".to_string(),
+            )
+            .await?;
+        }
+
         let mut attempt = 0usize;
         let response = loop {
             if cancel.is_cancelled() {
@@ -190,30 +205,21 @@ pub async fn run_agent(
                     let reason = error.to_string();
                     attempt += 1;
 
-                    if config.compaction.enabled && is_context_overflow_error(&reason) {
-                        emit(
+                    // Compaction that frees nothing would overflow again, so
+                    // fall through to the retry budget instead of looping.
+                    if config.compaction.enabled
+                        && is_context_overflow_error(&reason)
+                        && compact_history(
+                            &run,
                             &event_tx,
-                            AgentEvent::CompactionStarted {
-                                run_id: run.run_id,
-                                reason: reason.clone(),
-                            },
+                            compactor,
+                            &mut messages,
+                            &current_model,
+                            context_metadata,
+                            reason.clone(),
                         )
-                        .await;
-
-                        let compacted = compactor.compact(messages.clone(), &current_model).await?;
-                        messages = compacted.messages;
-
-                        emit(
-                            &event_tx,
-                            AgentEvent::CompactionCompleted {
-                                run_id: run.run_id,
-                                tokens_before: compacted.tokens_before,
-                                tokens_after: compacted.tokens_after,
-                                truncated: compacted.truncated,
-                            },
-                        )
-                        .await;
-
+                        .await?
+                    {
                         total_turns = total_turns.saturating_sub(1);
                         continue 'run_loop;
                     }
@@ -246,6 +252,7 @@ pub async fn run_agent(
                         AgentEvent::RunError {
                             run_id: run.run_id,
                             error: reason,
+                            kind: ErrorKind::from(&error),
                             retryable: false,
                         },
                     )
@@ -845,6 +852,49 @@ fn has_system_message(messages: &[Message]) -> bool {
     messages.iter().any(|message| message.role == Role::System)
 }
 
+/// Replace `messages` with their compacted form; `true` when that freed
+/// tokens.
+#[allow(clippy::too_many_arguments)]
+async fn compact_history(
+    run: &AgentRunContext,
+    event_tx: &mpsc::Sender<AgentEvent>,
+    compactor: &dyn CompactionEngine,
+    messages: &mut Vec<Message>,
+    model: &stakai::Model,
+    context_metadata: &mut serde_json::Value,
+    reason: String,
+) -> Result<bool, AgentError> {
+    emit(
+        event_tx,
+        AgentEvent::CompactionStarted {
+            run_id: run.run_id,
+            reason,
+        },
+    )
+    .await;
+
+    let compacted = compactor.compact(messages.clone(), model).await?;
+    let freed = compacted.tokens_after < compacted.tokens_before;
+    if freed {
+        *messages = compacted.messages;
+        // The reducer's trim boundary indexes the replaced messages.
+        BudgetAwareContextReducer::reset(context_metadata);
+    }
+
+    emit(
+        event_tx,
+        AgentEvent::CompactionCompleted {
+            run_id: run.run_id,
+            tokens_before: compacted.tokens_before,
+            tokens_after: compacted.tokens_after,
+            truncated: compacted.truncated,
+        },
+    )
+    .await;
+
+    Ok(freed)
+}
+
 fn is_context_overflow_error(error: &str) -> bool {
     let error = error.to_ascii_lowercase();
     (error.contains("context") || error.contains("token"))
@@ -11,6 +11,7 @@ const SAFETY_BUFFER_FACTOR: f64 = 1.05;
 /// across multiple turns, preserving Anthropic prompt cache stability. Without this,
 /// trim_end advances every turn, invalidating cache on every request.
 const TRIM_HEADROOM_FACTOR: f64 = 0.75;
+const TRIMMED_UP_TO_METADATA_KEY: &str = "trimmed_up_to_message_index";
 
 #[derive(Debug, Clone)]
 pub struct BudgetAwareContextReducer {
@@ -116,9 +117,17 @@ impl BudgetAwareContextReducer {
         after as i64 - before as i64
     }
 
+    /// Forget the trim boundary stored in `metadata`, for when the messages
+    /// it indexes have been replaced (e.g. by compaction).
+    pub fn reset(metadata: &mut serde_json::Value) {
+        if let Some(obj) = metadata.as_object_mut() {
+            obj.remove(TRIMMED_UP_TO_METADATA_KEY);
+        }
+    }
+
     fn metadata_trimmed_up_to(metadata: &serde_json::Value) -> usize {
         metadata
-            .get("trimmed_up_to_message_index")
+            .get(TRIMMED_UP_TO_METADATA_KEY)
             .and_then(serde_json::Value::as_u64)
             .unwrap_or(0) as usize
     }
@@ -244,7 +253,7 @@ impl ContextReducer for BudgetAwareContextReducer {
         Self::ensure_metadata_object(metadata);
         if let Some(obj) = metadata.as_object_mut() {
             obj.insert(
-                "trimmed_up_to_message_index".to_string(),
+                TRIMMED_UP_TO_METADATA_KEY.to_string(),
                 serde_json::json!(effective_trim_end),
             );
         }
@@ -12,6 +12,12 @@ pub struct CompactionResult {
 
 #[async_trait]
 pub trait CompactionEngine: Send + Sync {
+    /// Whether `messages` should be compacted before the next inference,
+    /// without waiting for the provider to reject them as too long.
+    fn should_compact(&self, _messages: &[Message], _model: &Model) -> bool {
+        false
+    }
+
     async fn compact(
         &self,
         messages: Vec<Message>,
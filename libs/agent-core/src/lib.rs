@@ -37,5 +37,6 @@ pub use tools::{ToolExecutionResult, ToolExecutor};
 pub use types::{
     AgentCommand, AgentConfig, AgentEvent, AgentLoopResult, AgentRunContext, CompactionConfig,
     ContextConfig, ProposedToolCall, RetryConfig, SAFE_AUTOPILOT_TOOLS, StopReason, TokenUsage,
-    ToolApprovalAction, ToolApprovalPolicy, ToolDecision, TurnFinishReason, strip_tool_prefix,
+    ToolApprovalAction, ToolApprovalMatch, ToolApprovalPolicy, ToolDecision, TurnFinishReason,
+    strip_tool_prefix,
 };
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use stakpak_shared::error::ErrorKind;
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -92,6 +93,7 @@ const DEFAULT_AUTO_APPROVE_TOOLS: &[&str] = &[
     "generate_password",
     "search_docs",
     "search_memory",
+    "recall_memory",
     "load_skill",
     "local_code_search",
     "get_all_tasks",
@@ -183,12 +185,21 @@ impl ToolApprovalPolicy {
         tool_name: &str,
         tool_arguments: Option<&Value>,
     ) -> ToolApprovalAction {
+        self.explain(tool_name, tool_arguments).action
+    }
+
+    /// Like [`Self::action_for`], but also reports which rule decided.
+    pub fn explain(&self, tool_name: &str, tool_arguments: Option<&Value>) -> ToolApprovalMatch {
         let stripped = strip_tool_prefix(tool_name);
 
         match self {
-            Self::None => ToolApprovalAction::Ask,
-            Self::All => ToolApprovalAction::Approve,
+            Self::None => ToolApprovalMatch::default_action(ToolApprovalAction::Ask),
+            Self::All => ToolApprovalMatch::default_action(ToolApprovalAction::Approve),
             Self::Custom { rules, default } => {
+                if let Some(server_match) = server_scoped_rule(rules, tool_name) {
+                    return server_match;
+                }
+
                 if SHELL_TOOLS.contains(&stripped)
                     && let Some(args) = tool_arguments
                     && let Some(command_str) = args.get("command").and_then(|v| v.as_str())
@@ -202,44 +213,91 @@ impl ToolApprovalPolicy {
                         Vec::new()
                     };
 
-                    match stakpak_shell_tool_approvals::resolve_hierarchical_policy(
+                    match stakpak_shell_tool_approvals::explain_hierarchical_policy(
                         command_str,
                         stripped,
                         &fallback_scopes,
                         rules,
                         *default,
                     ) {
-                        Ok(Some(action)) => return action,
+                        Ok(Some((rule, action))) => return ToolApprovalMatch { action, rule },
                         Ok(None) => {}
                         Err(_) => {
-                            return conservative_shell_parse_fallback(stripped, rules, *default)
-                                .max(ToolApprovalAction::Ask);
+                            let fallback =
+                                conservative_shell_parse_fallback(stripped, rules, *default);
+                            return ToolApprovalMatch {
+                                action: fallback.action.max(ToolApprovalAction::Ask),
+                                rule: fallback.rule,
+                            };
                         }
                     }
                 }
 
-                rules.get(stripped).copied().unwrap_or(*default)
+                match rules.get(stripped) {
+                    Some(action) => ToolApprovalMatch {
+                        action: *action,
+                        rule: Some(stripped.to_string()),
+                    },
+                    None => ToolApprovalMatch::default_action(*default),
+                }
             }
         }
     }
 }
 
+/// The approval action for a tool call and the rule key that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolApprovalMatch {
+    pub action: ToolApprovalAction,
+    /// Rule key such as `view` or `run_command::git::status`; `None` when the
+    /// policy's default applied.
+    pub rule: Option<String>,
+}
+
+impl ToolApprovalMatch {
+    fn default_action(action: ToolApprovalAction) -> Self {
+        Self { action, rule: None }
+    }
+}
+
+/// A rule for one MCP server's tool (`postgres__query`) or for all of the
+/// server's tools (`postgres__*`). These come from per-server approval
+/// policies and win over rules for the bare tool name.
+fn server_scoped_rule(
+    rules: &HashMap<String, ToolApprovalAction>,
+    tool_name: &str,
+) -> Option<ToolApprovalMatch> {
+    let (server, tool) = tool_name.split_once("__")?;
+    let tool = tool.strip_suffix("()").unwrap_or(tool);
+    [format!("{server}__{tool}"), format!("{server}__*")]
+        .into_iter()
+        .find_map(|key| {
+            rules.get(&key).map(|action| ToolApprovalMatch {
+                action: *action,
+                rule: Some(key),
+            })
+        })
+}
+
 fn conservative_shell_parse_fallback(
     tool_scope: &str,
     rules: &HashMap<String, ToolApprovalAction>,
     default: ToolApprovalAction,
-) -> ToolApprovalAction {
-    rules
-        .get(tool_scope)
-        .copied()
-        .or_else(|| {
-            if SHELL_TOOLS.contains(&tool_scope) && tool_scope != BASE_SHELL_TOOL {
-                rules.get(BASE_SHELL_TOOL).copied()
-            } else {
-                None
-            }
+) -> ToolApprovalMatch {
+    let fallback_scope = if SHELL_TOOLS.contains(&tool_scope) && tool_scope != BASE_SHELL_TOOL {
+        Some(BASE_SHELL_TOOL)
+    } else {
+        None
+    };
+    std::iter::once(tool_scope)
+        .chain(fallback_scope)
+        .find_map(|scope| {
+            rules.get(scope).map(|action| ToolApprovalMatch {
+                action: *action,
+                rule: Some(scope.to_string()),
+            })
         })
-        .unwrap_or(default)
+        .unwrap_or(ToolApprovalMatch::default_action(default))
 }
 
 /// Strip MCP server prefix from tool name (e.g. "stakpak__run_command" -> "run_command").
@@ -365,6 +423,8 @@ pub enum AgentEvent {
     RunError {
         run_id: Uuid,
         error: String,
+        #[serde(default)]
+        kind: ErrorKind,
         retryable: bool,
     },
 
@@ -484,6 +544,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn explain_reports_matched_rule() {
+        let policy = ToolApprovalPolicy::with_defaults().with_overrides([(
+            "run_command::git::status".to_string(),
+            ToolApprovalAction::Approve,
+        )]);
+
+        assert_eq!(
+            policy.explain("stakpak__view", None),
+            ToolApprovalMatch {
+                action: ToolApprovalAction::Approve,
+                rule: Some("view".to_string()),
+            }
+        );
+        assert_eq!(
+            policy.explain(
+                "run_command",
+                Some(&serde_json::json!({"command": "git status"}))
+            ),
+            ToolApprovalMatch {
+                action: ToolApprovalAction::Approve,
+                rule: Some("run_command::git::status".to_string()),
+            }
+        );
+        assert_eq!(
+            policy.explain("some_unknown_tool", None),
+            ToolApprovalMatch {
+                action: ToolApprovalAction::Ask,
+                rule: None,
+            }
+        );
+    }
+
+    #[test]
+    fn server_scoped_rules_win_over_bare_tool_rules() {
+        let policy = ToolApprovalPolicy::with_defaults().with_overrides([
+            ("postgres__*".to_string(), ToolApprovalAction::Ask),
+            ("postgres__query".to_string(), ToolApprovalAction::Approve),
+            ("postgres__view".to_string(), ToolApprovalAction::Deny),
+        ]);
+
+        assert_eq!(
+            policy.explain("postgres__query", None),
+            ToolApprovalMatch {
+                action: ToolApprovalAction::Approve,
+                rule: Some("postgres__query".to_string()),
+            }
+        );
+        assert_eq!(
+            policy.action_for("postgres__view", None),
+            ToolApprovalAction::Deny
+        );
+        assert_eq!(
+            policy.explain("postgres__search", None).rule,
+            Some("postgres__*".to_string())
+        );
+        assert_eq!(
+            policy.action_for("stakpak__view", None),
+            ToolApprovalAction::Approve
+        );
+    }
+
     #[test]
     fn from_allowlist_approves_listed() {
         let tools = vec!["view".to_string()];
@@ -61,6 +61,8 @@ pub struct AgentClientConfig {
     pub store_path: Option<String>,
     /// Hook registry for lifecycle events
     pub hook_registry: Option<HookRegistry<AgentState>>,
+    /// Never fall back to the public Stakpak API endpoints (airgapped setups)
+    pub offline: bool,
 }
 
 impl AgentClientConfig {
@@ -94,6 +96,12 @@ impl AgentClientConfig {
         self.hook_registry = Some(registry);
         self
     }
+
+    /// Set offline mode
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
 }
 
 // =============================================================================
@@ -102,6 +110,13 @@ impl AgentClientConfig {
 
 const DEFAULT_STORE_PATH: &str = ".stakpak/data/local.db";
 
+/// Path of the local SQLite session store used when no Stakpak API key is set.
+pub fn default_store_path() -> String {
+    std::env::var("HOME")
+        .map(|h| format!("{}/{}", h, DEFAULT_STORE_PATH))
+        .unwrap_or_else(|_| DEFAULT_STORE_PATH.to_string())
+}
+
 /// Unified agent client
 ///
 /// Provides a single interface for:
@@ -120,6 +135,8 @@ pub struct AgentClient {
     pub(crate) hook_registry: Arc<HookRegistry<AgentState>>,
     /// Stakpak configuration (for reference)
     pub(crate) stakpak: Option<StakpakConfig>,
+    /// Skip unauthenticated requests to the public Stakpak API
+    pub(crate) offline: bool,
 }
 
 impl AgentClient {
@@ -145,11 +162,7 @@ impl AgentClient {
             .map_err(|e| format!("Failed to create Stakpak storage: {}", e))?;
             Ok(Arc::new(storage))
         } else {
-            let store_path = store_path.unwrap_or_else(|| {
-                std::env::var("HOME")
-                    .map(|h| format!("{}/{}", h, DEFAULT_STORE_PATH))
-                    .unwrap_or_else(|_| DEFAULT_STORE_PATH.to_string())
-            });
+            let store_path = store_path.unwrap_or_else(default_store_path);
             let storage = LocalStorage::new(&store_path)
                 .await
                 .map_err(|e| format!("Failed to create local storage: {}", e))?;
@@ -204,11 +217,7 @@ impl AgentClient {
                     .map_err(|e| format!("Failed to create Stakpak storage: {}", e))?,
             )
         } else {
-            let store_path = config.store_path.clone().unwrap_or_else(|| {
-                std::env::var("HOME")
-                    .map(|h| format!("{}/{}", h, DEFAULT_STORE_PATH))
-                    .unwrap_or_else(|_| DEFAULT_STORE_PATH.to_string())
-            });
+            let store_path = config.store_path.clone().unwrap_or_else(default_store_path);
             Arc::new(
                 LocalStorage::new(&store_path)
                     .await
@@ -233,6 +242,7 @@ impl AgentClient {
             session_storage,
             hook_registry,
             stakpak: config.stakpak,
+            offline: config.offline,
         })
     }
 
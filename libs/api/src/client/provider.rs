@@ -93,6 +93,8 @@ impl AgentProvider for AgentClient {
     async fn list_rulebooks(&self) -> Result<Vec<ListRuleBook>, String> {
         if let Some(api) = &self.stakpak_api {
             api.list_rulebooks().await
+        } else if self.offline {
+            Ok(vec![])
         } else {
             // Try to fetch public rulebooks via unauthenticated request
             let client = stakpak_shared::tls_client::create_tls_client(
@@ -118,6 +120,8 @@ impl AgentProvider for AgentClient {
     async fn get_rulebook_by_uri(&self, uri: &str) -> Result<RuleBook, String> {
         if let Some(api) = &self.stakpak_api {
             api.get_rulebook_by_uri(uri).await
+        } else if self.offline {
+            Err("Rulebooks are not available in offline mode".to_string())
         } else {
             // Try to fetch public rulebook via unauthenticated request
             let client = stakpak_shared::tls_client::create_tls_client(
@@ -16,6 +16,16 @@ use std::str::FromStr;
 use tempfile::TempDir;
 use uuid::Uuid;
 
+/// Storage footprint of one local session, used by retention policies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionFootprint {
+    pub session_id: Uuid,
+    /// Latest update to the session or any of its checkpoints.
+    pub last_activity: DateTime<Utc>,
+    /// Title plus serialized checkpoint state, in bytes.
+    pub bytes: u64,
+}
+
 /// Local SQLite storage implementation
 ///
 /// Uses a shared `Database` handle and opens a fresh `Connection` per
@@ -131,6 +141,90 @@ impl LocalStorage {
         Ok(conn)
     }
 
+    /// Every session with its last activity time and stored transcript size,
+    /// for retention policies. Soft-deleted sessions are included.
+    pub async fn session_footprints(&self) -> Result<Vec<SessionFootprint>, StorageError> {
+        let conn = self.connection().await?;
+        let mut rows = conn
+            .query(
+                "SELECT s.id, s.updated_at,
+                    (SELECT MAX(c.updated_at) FROM checkpoints c WHERE c.session_id = s.id),
+                    LENGTH(s.title) + COALESCE(
+                        (SELECT SUM(LENGTH(c.state)) FROM checkpoints c WHERE c.session_id = s.id),
+                        0
+                    )
+                 FROM sessions s",
+                (),
+            )
+            .await
+            .map_err(|e| StorageError::Internal(e.to_string()))?;
+
+        let mut footprints = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| StorageError::Internal(e.to_string()))?
+        {
+            let id: String = row
+                .get(0)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            let updated_at: String = row
+                .get(1)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            let checkpoint_updated_at: Option<String> = row
+                .get(2)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            let bytes: i64 = row
+                .get(3)
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+
+            let mut last_activity = parse_datetime(&updated_at)?;
+            if let Some(checkpoint_updated_at) = checkpoint_updated_at {
+                last_activity = last_activity.max(parse_datetime(&checkpoint_updated_at)?);
+            }
+            footprints.push(SessionFootprint {
+                session_id: Uuid::from_str(&id)
+                    .map_err(|e| StorageError::Internal(e.to_string()))?,
+                last_activity,
+                bytes: u64::try_from(bytes).unwrap_or_default(),
+            });
+        }
+
+        Ok(footprints)
+    }
+
+    /// Permanently delete sessions and their checkpoints. Unlike
+    /// [`SessionStorage::delete_session`], which only marks a session as
+    /// deleted, this removes the transcript from disk. Returns how many
+    /// sessions were removed.
+    pub async fn purge_sessions(&self, session_ids: &[Uuid]) -> Result<u64, StorageError> {
+        let conn = self.connection().await?;
+        let mut purged = 0;
+        for session_id in session_ids {
+            let tx = conn
+                .transaction()
+                .await
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            tx.execute(
+                "DELETE FROM checkpoints WHERE session_id = ?",
+                [session_id.to_string()],
+            )
+            .await
+            .map_err(|e| StorageError::Internal(e.to_string()))?;
+            purged += tx
+                .execute(
+                    "DELETE FROM sessions WHERE id = ?",
+                    [session_id.to_string()],
+                )
+                .await
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+            tx.commit()
+                .await
+                .map_err(|e| StorageError::Internal(e.to_string()))?;
+        }
+        Ok(purged)
+    }
+
     /// Get the latest checkpoint for a session using a caller-provided connection.
     async fn get_latest_checkpoint_for_session_inner(
         conn: &Connection,
@@ -1904,6 +1904,42 @@ mod local_storage_tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_purge_sessions_removes_transcripts() {
+        let storage = create_test_storage().await;
+        let kept = storage
+            .create_session(&session_request("Kept", vec![user_msg("hello")]))
+            .await
+            .unwrap();
+        let purged = storage
+            .create_session(&session_request(
+                "Purged",
+                vec![user_msg("a long transcript")],
+            ))
+            .await
+            .unwrap();
+
+        let footprints = storage.session_footprints().await.unwrap();
+        assert_eq!(footprints.len(), 2);
+        assert!(footprints.iter().all(|footprint| footprint.bytes > 0));
+
+        let removed = storage.purge_sessions(&[purged.session_id]).await.unwrap();
+        assert_eq!(removed, 1);
+
+        let footprints = storage.session_footprints().await.unwrap();
+        assert_eq!(footprints.len(), 1);
+        assert_eq!(footprints[0].session_id, kept.session_id);
+        assert!(matches!(
+            storage.get_session(purged.session_id).await,
+            Err(StorageError::NotFound(_))
+        ));
+        let checkpoints = storage
+            .list_checkpoints(purged.session_id, &ListCheckpointsQuery::default())
+            .await
+            .unwrap();
+        assert!(checkpoints.checkpoints.is_empty());
+    }
+
     /// Constructor regression test: startup uses a one-off raw connection for
     /// database-level PRAGMAs before migrations run. That path must also wait
     /// on transient locks instead of failing immediately with SQLITE_BUSY.
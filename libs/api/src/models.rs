@@ -3,6 +3,7 @@ use rmcp::model::Content;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use stakai::Model;
+use stakpak_shared::error::ErrorKind;
 use stakpak_shared::models::{
     integrations::openai::{ChatMessage, FunctionCall, MessageContent, Role, Tool, ToolCall},
     llm::{LLMInput, LLMMessage, LLMMessageContent, LLMMessageTypedContent, LLMTokenUsage},
@@ -54,6 +55,23 @@ impl From<&str> for ApiStreamError {
     }
 }
 
+impl ApiStreamError {
+    /// Where this falls in the user-facing error taxonomy.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ApiStreamError::AgentExecutionLimitExceeded | ApiStreamError::CopilotError => {
+                ErrorKind::RateLimit
+            }
+            ApiStreamError::AgentNotSupported => ErrorKind::Config,
+            ApiStreamError::AgentInputInvalid(_)
+            | ApiStreamError::AgentStateInvalid
+            | ApiStreamError::AgentInvalidResponseStream
+            | ApiStreamError::InvalidGeneratedCode => ErrorKind::Model,
+            ApiStreamError::SaveError | ApiStreamError::Unknown(_) => ErrorKind::Internal,
+        }
+    }
+}
+
 impl From<String> for ApiStreamError {
     fn from(error_str: String) -> Self {
         ApiStreamError::from(error_str.as_str())
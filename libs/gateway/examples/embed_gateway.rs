@@ -0,0 +1,111 @@
+//! Embed the gateway in another Rust service
+//!
+//! Registers a custom channel that reads messages from stdin and prints
+//! replies, keeps sessions in a throwaway store, and drives the run loop
+//! until Ctrl-C:
+//!
+//! ```sh
+//! STAKPAK_SERVER_URL=http://127.0.0.1:4096 STAKPAK_SERVER_TOKEN=... \
+//!     cargo run -p stakpak-gateway --example embed_gateway
+//! ```
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use stakpak_gateway::{
+    Channel, ChannelId, ChannelTestResult, ChatType, Gateway, GatewayConfig, GatewayStore,
+    InboundMessage, OutboundReply, PeerId,
+};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// One direct conversation over stdin and stdout.
+struct ConsoleChannel {
+    id: ChannelId,
+}
+
+#[async_trait]
+impl Channel for ConsoleChannel {
+    fn id(&self) -> &ChannelId {
+        &self.id
+    }
+
+    fn display_name(&self) -> &str {
+        "Console"
+    }
+
+    async fn start(
+        &self,
+        inbound_tx: mpsc::Sender<InboundMessage>,
+        cancel: CancellationToken,
+    ) -> Result<()> {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        loop {
+            let line = tokio::select! {
+                _ = cancel.cancelled() => return Ok(()),
+                line = lines.next_line() => line?,
+            };
+            let Some(text) = line else {
+                return Ok(());
+            };
+            if text.trim().is_empty() {
+                continue;
+            }
+            let message = InboundMessage {
+                channel: self.id.clone(),
+                peer_id: PeerId::from("console"),
+                chat_type: ChatType::Direct,
+                text,
+                media: Vec::new(),
+                metadata: serde_json::json!({}),
+                timestamp: chrono::Utc::now(),
+                control: None,
+            };
+            if inbound_tx.send(message).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn send(&self, reply: OutboundReply) -> Result<()> {
+        println!("{}", reply.text);
+        Ok(())
+    }
+
+    async fn test(&self) -> Result<ChannelTestResult> {
+        Ok(ChannelTestResult {
+            channel: self.id.0.clone(),
+            identity: "console".to_string(),
+            details: "reads stdin, prints replies".to_string(),
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut config = GatewayConfig::default();
+    if let Ok(url) = std::env::var("STAKPAK_SERVER_URL") {
+        config.server.url = url;
+    }
+    if let Ok(token) = std::env::var("STAKPAK_SERVER_TOKEN") {
+        config.server.token = token;
+    }
+
+    let gateway = Gateway::builder(config)
+        .channel(Arc::new(ConsoleChannel {
+            id: ChannelId::from("console"),
+        }))
+        .store(Arc::new(GatewayStore::open_in_memory().await?))
+        .build()
+        .await?;
+
+    let cancel = CancellationToken::new();
+    let on_ctrl_c = cancel.clone();
+    tokio::spawn(async move {
+        let _ = tokio::signal::ctrl_c().await;
+        on_ctrl_c.cancel();
+    });
+    gateway.run(cancel).await
+}
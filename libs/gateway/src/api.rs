@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Arc, time::Instant};
 
 use axum::{
     Json, Router,
-    extract::Path,
+    extract::{Path, Query},
     http::{HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
@@ -12,20 +12,25 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::{RwLock, mpsc};
 use tracing::warn;
 
+pub use stakpak_shared::models::gateway_api::{
+    ApprovalOptions, CallerContextInput, GATEWAY_API_VERSION, GatewayApiError, GatewayErrorCode,
+    GatewaySendRequest, GatewaySendResponse, InteractiveOptions, NotificationContext,
+};
+
 use crate::{
     channels::Channel,
     client::StakpakClient,
     dispatcher::Dispatcher,
     router::{RouterConfig, resolve_routing_key},
-    store::{GatewayStore, SessionMapping},
+    store::{SessionMapping, SessionMappingFilter, StoreBackend},
     targeting::{ChannelTarget, render_title_template},
-    types::{DeliveryContext, InboundMessage, OutboundReply},
+    types::{ChatType, DeliveryContext, InboundMessage, OutboundReply},
 };
 
 #[derive(Clone)]
 pub struct GatewayApiState {
     pub channels: HashMap<String, Arc<dyn Channel>>,
-    pub store: Arc<GatewayStore>,
+    pub store: Arc<dyn StoreBackend>,
     pub started_at: Instant,
     pub delivery_context_ttl_hours: u64,
     pub auth_token: Option<String>,
@@ -36,49 +41,6 @@ pub struct GatewayApiState {
     pub inbound_tx: Arc<RwLock<Option<mpsc::Sender<InboundMessage>>>>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct GatewaySendRequest {
-    pub channel: String,
-    pub target: serde_json::Value,
-    pub text: String,
-    #[serde(default)]
-    pub context: Option<serde_json::Value>,
-    #[serde(default)]
-    pub interactive: Option<InteractiveOptions>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct CallerContextInput {
-    pub name: String,
-    pub content: String,
-    #[serde(default)]
-    pub priority: Option<String>,
-}
-
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct InteractiveOptions {
-    pub prompt: String,
-    #[serde(default)]
-    pub caller_context: Vec<CallerContextInput>,
-    #[serde(default)]
-    pub model: Option<String>,
-    #[serde(default)]
-    pub sandbox: Option<bool>,
-    #[serde(default)]
-    pub timeout: Option<u64>,
-    #[serde(default)]
-    pub title: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct GatewaySendResponse {
-    pub delivered: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub session_id: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub thread_id: Option<String>,
-}
-
 #[derive(Debug, Serialize)]
 pub struct GatewayChannelStatus {
     pub id: String,
@@ -99,21 +61,45 @@ pub struct GatewayStatusResponse {
     pub uptime_seconds: u64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GatewaySessionItem {
     pub routing_key: String,
     pub session_id: String,
     pub channel: String,
     pub target_key: String,
     pub title: String,
+    #[serde(default)]
+    pub peer_id: String,
+    pub chat_type: Option<ChatType>,
+    #[serde(default)]
+    pub created_at: i64,
     pub updated_at: i64,
+    /// A run is in progress for the session.
+    #[serde(default)]
+    pub active: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GatewaySessionsResponse {
     pub sessions: Vec<GatewaySessionItem>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct GatewayMappingsQuery {
+    #[serde(default)]
+    pub channel: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// `PUT /mappings/{routing_key}` body.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GatewayRelinkRequest {
+    pub session_id: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct GatewaySessionStatusResponse {
     pub session_id: String,
@@ -122,18 +108,52 @@ pub struct GatewaySessionStatusResponse {
     pub updated_at: i64,
 }
 
-#[derive(Debug, Serialize)]
-struct ApiError {
-    error: String,
-    message: String,
+#[derive(Debug, Deserialize)]
+pub struct GatewaySessionEventsQuery {
+    /// Replay events after this id; defaults to the stored session cursor.
+    #[serde(default)]
+    pub since: Option<u64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GatewaySessionEventsResponse {
+    pub session_id: String,
+    /// Cursor the replay started after.
+    pub since: u64,
+    /// Id of the last event returned; pass as `since` to continue.
+    pub next_cursor: Option<u64>,
+    pub cursors: GatewaySessionCursors,
+    pub events: Vec<GatewayReplayedEvent>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GatewaySessionCursors {
+    pub session: Option<u64>,
+    pub runs: Vec<GatewayRunCursor>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GatewayRunCursor {
+    pub run_id: String,
+    pub run_session_id: String,
+    pub cursor: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GatewayReplayedEvent {
+    pub id: Option<u64>,
+    pub event_type: String,
+    pub run_id: Option<String>,
+    /// The server's event envelope, or the raw text when it is not JSON.
+    pub data: serde_json::Value,
 }
 
-const MAX_INTERACTIVE_PROMPT_BYTES: usize = 32 * 1024;
-const MAX_INTERACTIVE_CALLER_CONTEXT_ITEMS: usize = 50;
-const MAX_INTERACTIVE_CALLER_CONTEXT_NAME_BYTES: usize = 256;
-const MAX_INTERACTIVE_CALLER_CONTEXT_PRIORITY_BYTES: usize = 32;
-const MAX_INTERACTIVE_CALLER_CONTEXT_ITEM_BYTES: usize = 10 * 1024;
-const MAX_INTERACTIVE_CALLER_CONTEXT_TOTAL_BYTES: usize = 100 * 1024;
+const DEFAULT_EVENT_REPLAY_LIMIT: usize = 500;
+const MAX_EVENT_REPLAY_LIMIT: usize = 5_000;
+const DEFAULT_MAPPINGS_LIMIT: usize = 1_000;
+const MAX_MAPPINGS_LIMIT: usize = 10_000;
 
 pub fn router(state: Arc<GatewayApiState>) -> Router {
     Router::new()
@@ -179,9 +199,57 @@ pub fn router(state: Arc<GatewayApiState>) -> Router {
         )
         .route(
             "/sessions/{session_id}",
-            get(move |headers: HeaderMap, Path(session_id): Path<String>| {
+            get({
+                let state = state.clone();
+                move |headers: HeaderMap, Path(session_id): Path<String>| {
+                    let state = state.clone();
+                    async move { session_status_handler(state, headers, session_id).await }
+                }
+            }),
+        )
+        .route(
+            "/sessions/{session_id}/events",
+            get({
+                let state = state.clone();
+                move |headers: HeaderMap,
+                      Path(session_id): Path<String>,
+                      Query(query): Query<GatewaySessionEventsQuery>| {
+                    let state = state.clone();
+                    async move { session_events_handler(state, headers, session_id, query).await }
+                }
+            }),
+        )
+        .route(
+            "/mappings",
+            get({
+                let state = state.clone();
+                move |headers: HeaderMap, Query(query): Query<GatewayMappingsQuery>| {
+                    let state = state.clone();
+                    async move { mappings_handler(state, headers, query).await }
+                }
+            }),
+        )
+        .route(
+            "/mappings/{routing_key}",
+            get({
+                let state = state.clone();
+                move |headers: HeaderMap, Path(routing_key): Path<String>| {
+                    let state = state.clone();
+                    async move { mapping_handler(state, headers, routing_key).await }
+                }
+            })
+            .put({
+                let state = state.clone();
+                move |headers: HeaderMap,
+                      Path(routing_key): Path<String>,
+                      Json(request): Json<GatewayRelinkRequest>| {
+                    let state = state.clone();
+                    async move { relink_mapping_handler(state, headers, routing_key, request).await }
+                }
+            })
+            .delete(move |headers: HeaderMap, Path(routing_key): Path<String>| {
                 let state = state.clone();
-                async move { session_status_handler(state, headers, session_id).await }
+                async move { unlink_mapping_handler(state, headers, routing_key).await }
             }),
         )
 }
@@ -201,8 +269,8 @@ fn require_auth(state: &GatewayApiState, headers: &HeaderMap) -> Option<axum::re
     Some(
         (
             StatusCode::UNAUTHORIZED,
-            Json(ApiError {
-                error: "unauthorized".to_string(),
+            Json(GatewayApiError {
+                error: GatewayErrorCode::Unauthorized,
                 message: "Missing or invalid bearer token".to_string(),
             }),
         )
@@ -219,12 +287,18 @@ async fn send_handler(
         return response;
     }
 
+    if let Err(error) = request.validate() {
+        return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+    }
+
     let GatewaySendRequest {
+        api_version: _,
         channel: request_channel,
         target: request_target,
         text: request_text,
         context: request_context,
         interactive,
+        approval,
     } = request;
 
     let target = match ChannelTarget::parse(&request_channel, &request_target) {
@@ -232,8 +306,8 @@ async fn send_handler(
         Err(error) => {
             return (
                 StatusCode::BAD_REQUEST,
-                Json(ApiError {
-                    error: "invalid_target".to_string(),
+                Json(GatewayApiError {
+                    error: GatewayErrorCode::InvalidTarget,
                     message: error.to_string(),
                 }),
             )
@@ -244,30 +318,34 @@ async fn send_handler(
     let Some(channel) = state.channels.get(&request_channel) else {
         return (
             StatusCode::NOT_FOUND,
-            Json(ApiError {
-                error: "channel_not_found".to_string(),
+            Json(GatewayApiError {
+                error: GatewayErrorCode::ChannelNotFound,
                 message: format!("Channel '{}' is not connected", request_channel),
             }),
         )
             .into_response();
     };
 
-    if let Some(interactive_request) = interactive.as_ref() {
-        if state.auth_token.is_none() {
-            return (
-                StatusCode::FORBIDDEN,
-                Json(ApiError {
-                    error: "interactive_auth_required".to_string(),
-                    message: "interactive sends require gateway auth token configuration"
-                        .to_string(),
-                }),
-            )
-                .into_response();
-        }
+    if interactive.is_some() && state.auth_token.is_none() {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(GatewayApiError {
+                error: GatewayErrorCode::InteractiveAuthRequired,
+                message: "interactive sends require gateway auth token configuration".to_string(),
+            }),
+        )
+            .into_response();
+    }
 
-        if let Err(error) = validate_interactive_options(interactive_request) {
-            return (StatusCode::BAD_REQUEST, Json(error)).into_response();
-        }
+    if approval.is_some() && state.auth_token.is_none() {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(GatewayApiError {
+                error: GatewayErrorCode::ApprovalAuthRequired,
+                message: "approval sends require gateway auth token configuration".to_string(),
+            }),
+        )
+            .into_response();
     }
 
     let mut effective_target = target.clone();
@@ -290,8 +368,8 @@ async fn send_handler(
             );
             return (
                 StatusCode::BAD_GATEWAY,
-                Json(ApiError {
-                    error: "delivery_failed".to_string(),
+                Json(GatewayApiError {
+                    error: GatewayErrorCode::DeliveryFailed,
                     message: "Failed to deliver message to channel".to_string(),
                 }),
             )
@@ -382,8 +460,8 @@ async fn send_handler(
                         .await;
                         return (
                             StatusCode::BAD_GATEWAY,
-                            Json(ApiError {
-                                error: "session_create_failed".to_string(),
+                            Json(GatewayApiError {
+                                error: GatewayErrorCode::SessionCreateFailed,
                                 message: "Failed to create interactive session".to_string(),
                             }),
                         )
@@ -416,8 +494,8 @@ async fn send_handler(
                     .await;
                     return (
                         StatusCode::BAD_GATEWAY,
-                        Json(ApiError {
-                            error: "session_persist_failed".to_string(),
+                        Json(GatewayApiError {
+                            error: GatewayErrorCode::SessionPersistFailed,
                             message: "Failed to persist interactive session mapping".to_string(),
                         }),
                     )
@@ -435,8 +513,8 @@ async fn send_handler(
                 );
                 return (
                     StatusCode::BAD_GATEWAY,
-                    Json(ApiError {
-                        error: "session_lookup_failed".to_string(),
+                    Json(GatewayApiError {
+                        error: GatewayErrorCode::SessionLookupFailed,
                         message: "Failed to resolve interactive session routing".to_string(),
                     }),
                 )
@@ -466,6 +544,7 @@ async fn send_handler(
             media: Vec::new(),
             metadata: inbound_metadata,
             timestamp: Utc::now(),
+            control: None,
         };
 
         let inbound_tx = state.inbound_tx.read().await.clone();
@@ -483,8 +562,8 @@ async fn send_handler(
             .await;
             return (
                 StatusCode::SERVICE_UNAVAILABLE,
-                Json(ApiError {
-                    error: "dispatcher_unavailable".to_string(),
+                Json(GatewayApiError {
+                    error: GatewayErrorCode::DispatcherUnavailable,
                     message: "gateway runtime is not ready to start interactive sessions"
                         .to_string(),
                 }),
@@ -512,8 +591,8 @@ async fn send_handler(
             .await;
             return (
                 StatusCode::BAD_GATEWAY,
-                Json(ApiError {
-                    error: "interactive_start_failed".to_string(),
+                Json(GatewayApiError {
+                    error: GatewayErrorCode::InteractiveStartFailed,
                     message: "Failed to start interactive agent run".to_string(),
                 }),
             )
@@ -527,7 +606,7 @@ async fn send_handler(
                 .set_delivery_context(
                     &request_channel,
                     &target_key,
-                    &context,
+                    &serde_json::to_value(&context).unwrap_or_default(),
                     state.delivery_context_ttl_hours,
                 )
                 .await
@@ -544,6 +623,7 @@ async fn send_handler(
         return (
             StatusCode::OK,
             Json(GatewaySendResponse {
+                api_version: GATEWAY_API_VERSION,
                 delivered: true,
                 session_id: Some(session_id),
                 thread_id: effective_target.thread_id(),
@@ -552,6 +632,45 @@ async fn send_handler(
             .into_response();
     }
 
+    if let Some(approval) = approval {
+        let delivery = DeliveryContext {
+            channel: request_channel.clone().into(),
+            peer_id: effective_target.peer_id(),
+            chat_type: effective_target.chat_type(),
+            channel_meta: effective_target.metadata(),
+            updated_at: Utc::now().timestamp_millis(),
+        };
+        match state
+            .dispatcher
+            .request_detached_approval(&approval.session_id, delivery)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                warn!(
+                    session_id = %approval.session_id,
+                    "approval requested but session has no pending tool calls"
+                );
+            }
+            Err(error) => {
+                warn!(
+                    channel = %request_channel,
+                    session_id = %approval.session_id,
+                    error = %error,
+                    "failed to post approval prompt"
+                );
+                return (
+                    StatusCode::BAD_GATEWAY,
+                    Json(GatewayApiError {
+                        error: GatewayErrorCode::ApprovalFailed,
+                        message: "Failed to post approval prompt".to_string(),
+                    }),
+                )
+                    .into_response();
+            }
+        }
+    }
+
     if let Some(context) = request_context {
         let target_key = effective_target.target_key();
         if let Err(error) = state
@@ -559,7 +678,7 @@ async fn send_handler(
             .set_delivery_context(
                 &request_channel,
                 &target_key,
-                &context,
+                &serde_json::to_value(&context).unwrap_or_default(),
                 state.delivery_context_ttl_hours,
             )
             .await
@@ -576,6 +695,7 @@ async fn send_handler(
     (
         StatusCode::OK,
         Json(GatewaySendResponse {
+            api_version: GATEWAY_API_VERSION,
             delivered: true,
             session_id: None,
             thread_id: effective_target.thread_id(),
@@ -607,113 +727,13 @@ async fn send_interactive_failure_notice(
     }
 }
 
-fn validate_interactive_options(interactive: &InteractiveOptions) -> Result<(), ApiError> {
-    if interactive.prompt.trim().is_empty() {
-        return Err(ApiError {
-            error: "invalid_interactive_prompt".to_string(),
-            message: "interactive.prompt must not be empty".to_string(),
-        });
-    }
-
-    if interactive.prompt.len() > MAX_INTERACTIVE_PROMPT_BYTES {
-        return Err(ApiError {
-            error: "invalid_interactive_prompt".to_string(),
-            message: format!(
-                "interactive.prompt exceeds {} bytes",
-                MAX_INTERACTIVE_PROMPT_BYTES
-            ),
-        });
-    }
-
-    if interactive.timeout == Some(0) {
-        return Err(ApiError {
-            error: "invalid_interactive_timeout".to_string(),
-            message: "interactive.timeout must be greater than 0".to_string(),
-        });
-    }
-
-    if interactive.caller_context.len() > MAX_INTERACTIVE_CALLER_CONTEXT_ITEMS {
-        return Err(ApiError {
-            error: "invalid_interactive_caller_context".to_string(),
-            message: format!(
-                "interactive.caller_context supports up to {} items",
-                MAX_INTERACTIVE_CALLER_CONTEXT_ITEMS
-            ),
-        });
-    }
-
-    let mut total_payload_bytes = interactive.prompt.len();
-
-    for item in &interactive.caller_context {
-        let name = item.name.trim();
-        if name.is_empty() {
-            return Err(ApiError {
-                error: "invalid_interactive_caller_context".to_string(),
-                message: "interactive.caller_context item name must not be empty".to_string(),
-            });
-        }
-
-        let name_bytes = item.name.len();
-        if name_bytes > MAX_INTERACTIVE_CALLER_CONTEXT_NAME_BYTES {
-            return Err(ApiError {
-                error: "invalid_interactive_caller_context".to_string(),
-                message: format!(
-                    "interactive.caller_context item name '{}' exceeds {} bytes",
-                    item.name, MAX_INTERACTIVE_CALLER_CONTEXT_NAME_BYTES
-                ),
-            });
-        }
-
-        let priority_bytes = item.priority.as_ref().map_or(0, String::len);
-        if priority_bytes > MAX_INTERACTIVE_CALLER_CONTEXT_PRIORITY_BYTES {
-            return Err(ApiError {
-                error: "invalid_interactive_caller_context".to_string(),
-                message: format!(
-                    "interactive.caller_context item '{}' priority exceeds {} bytes",
-                    item.name, MAX_INTERACTIVE_CALLER_CONTEXT_PRIORITY_BYTES
-                ),
-            });
-        }
-
-        let content_bytes = item.content.len();
-        if content_bytes > MAX_INTERACTIVE_CALLER_CONTEXT_ITEM_BYTES {
-            return Err(ApiError {
-                error: "invalid_interactive_caller_context".to_string(),
-                message: format!(
-                    "interactive.caller_context item '{}' exceeds {} bytes",
-                    item.name, MAX_INTERACTIVE_CALLER_CONTEXT_ITEM_BYTES
-                ),
-            });
-        }
-
-        total_payload_bytes = total_payload_bytes
-            .saturating_add(name_bytes)
-            .saturating_add(priority_bytes)
-            .saturating_add(content_bytes);
-        if total_payload_bytes > MAX_INTERACTIVE_CALLER_CONTEXT_TOTAL_BYTES {
-            return Err(ApiError {
-                error: "invalid_interactive_caller_context".to_string(),
-                message: format!(
-                    "interactive payload exceeds {} bytes",
-                    MAX_INTERACTIVE_CALLER_CONTEXT_TOTAL_BYTES
-                ),
-            });
-        }
-    }
-
-    Ok(())
-}
-
-fn extract_check_output(context: &serde_json::Value) -> Option<String> {
-    context
-        .get("check_output")
-        .and_then(|value| value.as_str())
-        .map(ToOwned::to_owned)
+fn extract_check_output(context: &NotificationContext) -> Option<String> {
+    context.check_output.clone()
 }
 
 fn build_interactive_prompt(
     interactive: &InteractiveOptions,
-    context: Option<&serde_json::Value>,
+    context: Option<&NotificationContext>,
 ) -> String {
     let mut context_lines = Vec::new();
 
@@ -802,27 +822,231 @@ async fn sessions_handler(state: Arc<GatewayApiState>, headers: HeaderMap) -> im
     }
     let sessions = state
         .store
-        .list(1000)
+        .list(DEFAULT_MAPPINGS_LIMIT)
         .await
         .unwrap_or_default()
         .into_iter()
-        .map(|(routing_key, mapping)| GatewaySessionItem {
-            routing_key,
-            session_id: mapping.session_id,
-            channel: mapping.delivery.channel.0.clone(),
-            target_key: crate::targeting::target_key_from_channel_chat(
-                &mapping.delivery.channel,
-                &mapping.delivery.chat_type,
-                &mapping.delivery.peer_id,
+        .map(|(routing_key, mapping)| session_item(&state, routing_key, mapping))
+        .collect();
+
+    (StatusCode::OK, Json(GatewaySessionsResponse { sessions })).into_response()
+}
+
+fn session_item(
+    state: &GatewayApiState,
+    routing_key: String,
+    mapping: SessionMapping,
+) -> GatewaySessionItem {
+    GatewaySessionItem {
+        routing_key,
+        channel: mapping.delivery.channel.0.clone(),
+        target_key: crate::targeting::target_key_from_channel_chat(
+            &mapping.delivery.channel,
+            &mapping.delivery.chat_type,
+            &mapping.delivery.peer_id,
+        ),
+        title: mapping.title,
+        peer_id: mapping.delivery.peer_id.0,
+        chat_type: Some(mapping.delivery.chat_type),
+        created_at: mapping.created_at,
+        updated_at: mapping.delivery.updated_at,
+        active: state.dispatcher.is_run_active(&mapping.session_id),
+        session_id: mapping.session_id,
+    }
+}
+
+fn api_error(
+    status: StatusCode,
+    error: GatewayErrorCode,
+    message: String,
+) -> axum::response::Response {
+    (status, Json(GatewayApiError { error, message })).into_response()
+}
+
+fn mapping_not_found(routing_key: &str) -> axum::response::Response {
+    api_error(
+        StatusCode::NOT_FOUND,
+        GatewayErrorCode::MappingNotFound,
+        format!("No session is mapped to routing key '{}'", routing_key),
+    )
+}
+
+/// Look up a mapping for `unlink`/`relink`, refusing while its session runs:
+/// the run would keep replying to the chat after the mapping changed.
+async fn idle_mapping(
+    state: &GatewayApiState,
+    routing_key: &str,
+) -> Result<SessionMapping, axum::response::Response> {
+    let mapping = match state.store.get(routing_key).await {
+        Ok(Some(mapping)) => mapping,
+        Ok(None) => return Err(mapping_not_found(routing_key)),
+        Err(error) => {
+            return Err(api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GatewayErrorCode::SessionLookupFailed,
+                error.to_string(),
+            ));
+        }
+    };
+    if state.dispatcher.is_run_active(&mapping.session_id) {
+        return Err(api_error(
+            StatusCode::CONFLICT,
+            GatewayErrorCode::RunActive,
+            format!(
+                "Session '{}' has a run in progress; retry when it finishes",
+                mapping.session_id
             ),
-            title: mapping.title,
-            updated_at: mapping.delivery.updated_at,
-        })
+        ));
+    }
+    Ok(mapping)
+}
+
+async fn mappings_handler(
+    state: Arc<GatewayApiState>,
+    headers: HeaderMap,
+    query: GatewayMappingsQuery,
+) -> impl IntoResponse {
+    if let Some(response) = require_auth(&state, &headers) {
+        return response;
+    }
+
+    let filter = SessionMappingFilter {
+        channel: query.channel,
+        session_id: query.session_id,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_MAPPINGS_LIMIT)
+        .clamp(1, MAX_MAPPINGS_LIMIT);
+    let rows = match state.store.list_filtered(&filter, limit).await {
+        Ok(rows) => rows,
+        Err(error) => {
+            return api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                GatewayErrorCode::SessionLookupFailed,
+                error.to_string(),
+            );
+        }
+    };
+    let sessions = rows
+        .into_iter()
+        .map(|(routing_key, mapping)| session_item(&state, routing_key, mapping))
         .collect();
 
     (StatusCode::OK, Json(GatewaySessionsResponse { sessions })).into_response()
 }
 
+async fn mapping_handler(
+    state: Arc<GatewayApiState>,
+    headers: HeaderMap,
+    routing_key: String,
+) -> impl IntoResponse {
+    if let Some(response) = require_auth(&state, &headers) {
+        return response;
+    }
+
+    match state.store.get(&routing_key).await {
+        Ok(Some(mapping)) => (
+            StatusCode::OK,
+            Json(session_item(&state, routing_key, mapping)),
+        )
+            .into_response(),
+        Ok(None) => mapping_not_found(&routing_key),
+        Err(error) => api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GatewayErrorCode::SessionLookupFailed,
+            error.to_string(),
+        ),
+    }
+}
+
+async fn relink_mapping_handler(
+    state: Arc<GatewayApiState>,
+    headers: HeaderMap,
+    routing_key: String,
+    request: GatewayRelinkRequest,
+) -> impl IntoResponse {
+    if let Some(response) = require_auth(&state, &headers) {
+        return response;
+    }
+
+    let session_id = request.session_id.trim().to_string();
+    if session_id.is_empty() {
+        return api_error(
+            StatusCode::BAD_REQUEST,
+            GatewayErrorCode::InvalidRequest,
+            "session_id must not be empty".to_string(),
+        );
+    }
+    if let Err(response) = idle_mapping(&state, &routing_key).await {
+        return response;
+    }
+    match state.client.get_session(&session_id).await {
+        Ok(_) => {}
+        Err(crate::client::ClientError::NotFound(_)) => {
+            return api_error(
+                StatusCode::NOT_FOUND,
+                GatewayErrorCode::SessionNotFound,
+                format!("Session '{}' was not found", session_id),
+            );
+        }
+        Err(error) => {
+            return api_error(
+                StatusCode::BAD_GATEWAY,
+                GatewayErrorCode::SessionLookupFailed,
+                error.to_string(),
+            );
+        }
+    }
+
+    let relinked = match state.store.relink(&routing_key, &session_id).await {
+        Ok(true) => state.store.get(&routing_key).await,
+        Ok(false) => return mapping_not_found(&routing_key),
+        Err(error) => Err(error),
+    };
+    match relinked {
+        Ok(Some(mapping)) => (
+            StatusCode::OK,
+            Json(session_item(&state, routing_key, mapping)),
+        )
+            .into_response(),
+        Ok(None) => mapping_not_found(&routing_key),
+        Err(error) => api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GatewayErrorCode::MappingUpdateFailed,
+            error.to_string(),
+        ),
+    }
+}
+
+async fn unlink_mapping_handler(
+    state: Arc<GatewayApiState>,
+    headers: HeaderMap,
+    routing_key: String,
+) -> impl IntoResponse {
+    if let Some(response) = require_auth(&state, &headers) {
+        return response;
+    }
+
+    let mapping = match idle_mapping(&state, &routing_key).await {
+        Ok(mapping) => mapping,
+        Err(response) => return response,
+    };
+    match state.store.delete(&routing_key).await {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(session_item(&state, routing_key, mapping)),
+        )
+            .into_response(),
+        Ok(false) => mapping_not_found(&routing_key),
+        Err(error) => api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            GatewayErrorCode::MappingUpdateFailed,
+            error.to_string(),
+        ),
+    }
+}
+
 async fn session_status_handler(
     state: Arc<GatewayApiState>,
     headers: HeaderMap,
@@ -837,8 +1061,8 @@ async fn session_status_handler(
         Ok(None) => {
             return (
                 StatusCode::NOT_FOUND,
-                Json(ApiError {
-                    error: "session_not_found".to_string(),
+                Json(GatewayApiError {
+                    error: GatewayErrorCode::SessionNotFound,
                     message: format!("Session '{}' was not found", session_id),
                 }),
             )
@@ -847,8 +1071,8 @@ async fn session_status_handler(
         Err(error) => {
             return (
                 StatusCode::BAD_GATEWAY,
-                Json(ApiError {
-                    error: "session_lookup_failed".to_string(),
+                Json(GatewayApiError {
+                    error: GatewayErrorCode::SessionLookupFailed,
                     message: error.to_string(),
                 }),
             )
@@ -870,21 +1094,105 @@ async fn session_status_handler(
         .into_response()
 }
 
+async fn session_events_handler(
+    state: Arc<GatewayApiState>,
+    headers: HeaderMap,
+    session_id: String,
+    query: GatewaySessionEventsQuery,
+) -> impl IntoResponse {
+    if let Some(response) = require_auth(&state, &headers) {
+        return response;
+    }
+
+    let cursors = match state.dispatcher.session_cursors(&session_id) {
+        Ok(cursors) => cursors,
+        Err(error) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(GatewayApiError {
+                    error: GatewayErrorCode::CursorLookupFailed,
+                    message: error.message,
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_EVENT_REPLAY_LIMIT)
+        .clamp(1, MAX_EVENT_REPLAY_LIMIT);
+    let (since, events) = match state
+        .dispatcher
+        .replay_session_events(&session_id, query.since, limit)
+        .await
+    {
+        Ok(replay) => replay,
+        Err(error) => {
+            return (
+                StatusCode::BAD_GATEWAY,
+                Json(GatewayApiError {
+                    error: GatewayErrorCode::ReplayFailed,
+                    message: error.message,
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let events: Vec<GatewayReplayedEvent> = events
+        .into_iter()
+        .map(|event| GatewayReplayedEvent {
+            id: event.event_id_u64,
+            run_id: event.run_id(),
+            data: serde_json::from_str(&event.data)
+                .unwrap_or(serde_json::Value::String(event.data)),
+            event_type: event.event_type,
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(GatewaySessionEventsResponse {
+            session_id,
+            since,
+            next_cursor: events.iter().filter_map(|event| event.id).max(),
+            cursors: GatewaySessionCursors {
+                session: cursors.session,
+                runs: cursors
+                    .runs
+                    .into_iter()
+                    .map(|run| GatewayRunCursor {
+                        run_id: run.run_id,
+                        run_session_id: run.run_session_id,
+                        cursor: run.cursor,
+                    })
+                    .collect(),
+            },
+            events,
+        }),
+    )
+        .into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        CallerContextInput, GatewayApiState, GatewaySendRequest, InteractiveOptions,
-        build_interactive_prompt, extract_check_output, render_title, send_handler,
-        validate_interactive_options,
+        CallerContextInput, GatewayApiState, GatewayRelinkRequest, GatewaySendRequest,
+        GatewaySessionEventsQuery, InteractiveOptions, NotificationContext,
+        build_interactive_prompt, extract_check_output, mapping_handler, relink_mapping_handler,
+        render_title, send_handler, session_events_handler, unlink_mapping_handler,
     };
     use crate::channels::{Channel, ChannelTestResult};
     use crate::client::StakpakClient;
     use crate::config::ApprovalMode;
     use crate::dispatcher::Dispatcher;
     use crate::router::RouterConfig;
-    use crate::store::GatewayStore;
+    use crate::store::{GatewayStore, SessionMapping};
     use crate::targeting::ChannelTarget;
-    use crate::types::{ChannelId, InboundMessage, OutboundReply};
+    use crate::types::{
+        ChannelId, ChatType, DeliveryContext, InboundMessage, OutboundReply, PeerId,
+    };
     use anyhow::Result;
     use async_trait::async_trait;
     use axum::http::{HeaderMap, StatusCode};
@@ -942,10 +1250,11 @@ mod tests {
         }
     }
 
-    #[tokio::test]
-    async fn interactive_request_without_auth_is_rejected_before_delivery() {
-        let send_count = Arc::new(AtomicUsize::new(0));
-        let channel_impl = Arc::new(MockChannel::new("slack", send_count.clone()));
+    async fn test_state(
+        send_count: Arc<AtomicUsize>,
+        auth_token: Option<&str>,
+    ) -> Arc<GatewayApiState> {
+        let channel_impl = Arc::new(MockChannel::new("slack", send_count));
 
         let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
         channels.insert("slack".to_string(), channel_impl);
@@ -969,20 +1278,27 @@ mod tests {
             "{channel}:{chat_type}:{chat_id}".to_string(),
         ));
 
-        let state = Arc::new(GatewayApiState {
+        Arc::new(GatewayApiState {
             channels,
             store,
             started_at: Instant::now(),
             delivery_context_ttl_hours: 4,
-            auth_token: None,
+            auth_token: auth_token.map(String::from),
             client,
             dispatcher,
             router_config: RouterConfig::default(),
             title_template: "{channel}:{chat_type}:{chat_id}".to_string(),
             inbound_tx: Arc::new(RwLock::new(None)),
-        });
+        })
+    }
+
+    #[tokio::test]
+    async fn interactive_request_without_auth_is_rejected_before_delivery() {
+        let send_count = Arc::new(AtomicUsize::new(0));
+        let state = test_state(send_count.clone(), None).await;
 
         let request = GatewaySendRequest {
+            api_version: 1,
             channel: "slack".to_string(),
             target: serde_json::json!({"channel": "C123"}),
             text: "hello".to_string(),
@@ -995,6 +1311,7 @@ mod tests {
                 timeout: None,
                 title: None,
             }),
+            approval: None,
         };
 
         let response = send_handler(state, HeaderMap::new(), request)
@@ -1005,9 +1322,109 @@ mod tests {
         assert_eq!(send_count.load(Ordering::SeqCst), 0);
     }
 
+    #[tokio::test]
+    async fn session_events_require_bearer_token() {
+        let state = test_state(Arc::new(AtomicUsize::new(0)), Some("secret")).await;
+        let query = || GatewaySessionEventsQuery {
+            since: Some(0),
+            limit: None,
+        };
+
+        let response = session_events_handler(
+            state.clone(),
+            HeaderMap::new(),
+            "session-1".to_string(),
+            query(),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Authorized requests reach the server; none is listening here.
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            "Bearer secret".parse().expect("valid header"),
+        );
+        let response = session_events_handler(state, headers, "session-1".to_string(), query())
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn unlink_and_relink_edit_the_stored_mapping() {
+        let state = test_state(Arc::new(AtomicUsize::new(0)), None).await;
+        let mapping = SessionMapping {
+            session_id: "session-1".to_string(),
+            title: "slack:group:C123".to_string(),
+            delivery: DeliveryContext {
+                channel: ChannelId::from("slack"),
+                peer_id: PeerId::from("U1"),
+                chat_type: ChatType::Group {
+                    id: "C123".to_string(),
+                },
+                channel_meta: serde_json::json!({}),
+                updated_at: 1,
+            },
+            created_at: 1,
+        };
+        state
+            .store
+            .set("slack:group:C123", &mapping)
+            .await
+            .expect("set mapping");
+
+        let response = mapping_handler(state.clone(), HeaderMap::new(), "missing".to_string())
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // The target session is checked on the server first; none is listening here.
+        let response = relink_mapping_handler(
+            state.clone(),
+            HeaderMap::new(),
+            "slack:group:C123".to_string(),
+            GatewayRelinkRequest {
+                session_id: "session-2".to_string(),
+            },
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        let stored = state.store.get("slack:group:C123").await.expect("get");
+        assert_eq!(stored.map(|m| m.session_id).as_deref(), Some("session-1"));
+
+        let response = unlink_mapping_handler(
+            state.clone(),
+            HeaderMap::new(),
+            "slack:group:C123".to_string(),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(
+            state
+                .store
+                .get("slack:group:C123")
+                .await
+                .expect("get")
+                .is_none()
+        );
+
+        let response =
+            unlink_mapping_handler(state, HeaderMap::new(), "slack:group:C123".to_string())
+                .await
+                .into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
     #[test]
     fn extract_check_output_reads_context_field() {
-        let context = serde_json::json!({"check_output": "disk at 91%"});
+        let context = NotificationContext {
+            check_output: Some("disk at 91%".to_string()),
+            ..Default::default()
+        };
         assert_eq!(
             extract_check_output(&context).as_deref(),
             Some("disk at 91%")
@@ -1031,7 +1448,10 @@ mod tests {
 
         let prompt = build_interactive_prompt(
             &options,
-            Some(&serde_json::json!({"check_output": "disk=91%"})),
+            Some(&NotificationContext {
+                check_output: Some("disk=91%".to_string()),
+                ..Default::default()
+            }),
         );
 
         assert!(prompt.contains("runtime_model"));
@@ -1064,7 +1484,10 @@ mod tests {
 
         let prompt = build_interactive_prompt(
             &options,
-            Some(&serde_json::json!({"check_output": "disk=91%"})),
+            Some(&NotificationContext {
+                check_output: Some("disk=91%".to_string()),
+                ..Default::default()
+            }),
         );
 
         assert!(prompt.contains("check_output"));
@@ -1081,7 +1504,7 @@ mod tests {
             title: None,
         };
 
-        let result = validate_interactive_options(&options);
+        let result = options.validate();
         assert!(result.is_err());
     }
 
@@ -1100,7 +1523,7 @@ mod tests {
             title: None,
         };
 
-        let result = validate_interactive_options(&options);
+        let result = options.validate();
         assert!(result.is_err());
     }
 
@@ -1115,7 +1538,7 @@ mod tests {
             title: None,
         };
 
-        let result = validate_interactive_options(&options);
+        let result = options.validate();
         assert!(result.is_err());
     }
 
@@ -1134,7 +1557,7 @@ mod tests {
             title: None,
         };
 
-        let result = validate_interactive_options(&options);
+        let result = options.validate();
         assert!(result.is_err());
     }
 
@@ -1153,7 +1576,7 @@ mod tests {
             title: None,
         };
 
-        let result = validate_interactive_options(&options);
+        let result = options.validate();
         assert!(result.is_err());
     }
 }
@@ -0,0 +1,388 @@
+//! Which approval mode and allowlist govern a channel's runs.
+//!
+//! The server decides first: tools its policy approves or denies never reach
+//! the gateway. Calls it asks about are checked against `approval_rules`
+//! first, then settled by the run's `auto_approve` override (usually from
+//! the channel's profile), the channel's inline overrides, or the gateway
+//! defaults, in that order.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use serde::Serialize;
+use stakpak_shell_tool_approvals::{ApprovalRules, RuleAction};
+use tracing::debug;
+
+use crate::{
+    client::{AutoApproveOverride, RunOverrides},
+    config::{ApprovalMode, ChannelOverrides, GatewayConfig},
+    dispatcher::{RunOverrideResolver, noop_run_override_resolver, strip_mcp_prefix},
+};
+
+#[derive(Clone)]
+pub struct ApprovalPolicy {
+    approval_mode: ApprovalMode,
+    approval_allowlist: HashSet<String>,
+    approval_rules: Arc<ApprovalRules>,
+    channel_overrides: HashMap<String, ChannelOverrides>,
+    channel_profiles: HashMap<String, String>,
+    override_resolver: Arc<dyn RunOverrideResolver>,
+}
+
+/// Where a run's approval mode came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalSource {
+    /// The run's `auto_approve` override.
+    RunOverride,
+    /// `approval_mode` / `approval_allowlist` on the channel.
+    Channel,
+    /// `[gateway]` defaults.
+    Gateway,
+}
+
+/// Approval mode and allowlist in effect for one run.
+#[derive(Debug, Clone)]
+pub struct RunApproval {
+    pub mode: ApprovalMode,
+    pub allowlist: HashSet<String>,
+    pub source: ApprovalSource,
+    /// `[gateway] approval_rules`, checked before `mode`.
+    pub rules: Arc<ApprovalRules>,
+}
+
+/// What the gateway does with one tool call, and the rule that decided it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCallApproval {
+    pub action: GatewayApprovalAction,
+    /// The `approval_rules` pattern that matched; `None` when the mode decided.
+    pub rule: Option<String>,
+}
+
+/// Tools whose `command` argument `approval_rules` are matched against.
+const SHELL_TOOLS: &[&str] = &[
+    "run_command",
+    "run_command_task",
+    "run_remote_command",
+    "run_remote_command_task",
+];
+
+/// What the gateway does with a tool call the server asked about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatewayApprovalAction {
+    Accept,
+    Reject,
+    /// Prompt in the channel.
+    Ask,
+}
+
+impl ApprovalPolicy {
+    pub fn new(
+        approval_mode: ApprovalMode,
+        approval_allowlist: Vec<String>,
+        channel_overrides: HashMap<String, ChannelOverrides>,
+    ) -> Self {
+        Self {
+            approval_mode,
+            approval_allowlist: approval_allowlist.into_iter().collect(),
+            approval_rules: Arc::new(ApprovalRules::default()),
+            channel_overrides,
+            channel_profiles: HashMap::new(),
+            override_resolver: noop_run_override_resolver(),
+        }
+    }
+
+    pub fn from_config(config: &GatewayConfig) -> Self {
+        Self::new(
+            config.gateway.approval_mode.clone(),
+            config.gateway.approval_allowlist.clone(),
+            config.channels.overrides_map(),
+        )
+        .with_rules(config.gateway.approval_rules.clone())
+    }
+
+    pub fn with_rules(mut self, rules: ApprovalRules) -> Self {
+        self.approval_rules = Arc::new(rules);
+        self
+    }
+
+    pub fn with_profile_resolution(
+        mut self,
+        channel_profiles: HashMap<String, String>,
+        override_resolver: Arc<dyn RunOverrideResolver>,
+    ) -> Self {
+        self.channel_profiles = channel_profiles;
+        self.override_resolver = override_resolver;
+        self
+    }
+
+    /// Overrides sent with every run started from `channel_name`: the
+    /// channel profile's when it has any, else the channel's inline ones.
+    pub fn run_overrides(&self, channel_name: &str) -> Option<RunOverrides> {
+        if let Some(profile_name) = self.channel_profiles.get(channel_name)
+            && let Some(overrides) = self.override_resolver.resolve_run_overrides(profile_name)
+            && !overrides.is_empty()
+        {
+            return Some(overrides);
+        }
+
+        let channel_overrides = self.channel_overrides.get(channel_name)?;
+
+        let auto_approve = channel_overrides
+            .approval_allowlist
+            .as_ref()
+            .map(|allowlist| AutoApproveOverride::AllowList(allowlist.clone()));
+
+        let overrides = RunOverrides {
+            model: channel_overrides.model.clone(),
+            auto_approve,
+            ..RunOverrides::default()
+        };
+
+        if overrides.is_empty() {
+            None
+        } else {
+            Some(overrides)
+        }
+    }
+
+    pub fn run_approval(
+        &self,
+        channel_name: &str,
+        run_overrides: Option<&RunOverrides>,
+    ) -> RunApproval {
+        if let Some(run_overrides) = run_overrides
+            && let Some(override_auto_approve) = run_overrides.auto_approve.as_ref()
+            && let Some((mode, allowlist)) = resolve_approval_from_override(override_auto_approve)
+        {
+            return RunApproval {
+                mode,
+                allowlist,
+                source: ApprovalSource::RunOverride,
+                rules: self.approval_rules.clone(),
+            };
+        }
+
+        self.channel_approval(channel_name)
+    }
+
+    pub fn channel_approval(&self, channel_name: &str) -> RunApproval {
+        let Some(overrides) = self.channel_overrides.get(channel_name) else {
+            return RunApproval {
+                mode: self.approval_mode.clone(),
+                allowlist: self.approval_allowlist.clone(),
+                source: ApprovalSource::Gateway,
+                rules: self.approval_rules.clone(),
+            };
+        };
+
+        let approval_mode = overrides
+            .approval_mode
+            .clone()
+            .unwrap_or_else(|| self.approval_mode.clone());
+
+        let approval_allowlist = overrides
+            .approval_allowlist
+            .as_ref()
+            .map(|list| list.iter().cloned().collect())
+            .unwrap_or_else(|| self.approval_allowlist.clone());
+
+        let source = if overrides.approval_mode.is_some() || overrides.approval_allowlist.is_some()
+        {
+            ApprovalSource::Channel
+        } else {
+            ApprovalSource::Gateway
+        };
+
+        RunApproval {
+            mode: approval_mode,
+            allowlist: approval_allowlist,
+            source,
+            rules: self.approval_rules.clone(),
+        }
+    }
+}
+
+impl RunApproval {
+    /// Settle a call by the first matching `approval_rules` pattern, else by
+    /// the approval mode.
+    pub fn decide(&self, tool_name: &str, arguments: &serde_json::Value) -> ToolCallApproval {
+        let normalized = strip_mcp_prefix(tool_name);
+        let command = SHELL_TOOLS
+            .contains(&normalized)
+            .then(|| arguments.get("command").and_then(|value| value.as_str()))
+            .flatten();
+        if let Some(rule) = self.rules.evaluate(normalized, command) {
+            let action = match rule.action {
+                RuleAction::Allow => GatewayApprovalAction::Accept,
+                RuleAction::RequireApproval => GatewayApprovalAction::Ask,
+                RuleAction::Deny => GatewayApprovalAction::Reject,
+            };
+            return ToolCallApproval {
+                action,
+                rule: Some(rule.pattern),
+            };
+        }
+        ToolCallApproval {
+            action: self.action_for(tool_name),
+            rule: None,
+        }
+    }
+
+    pub fn action_for(&self, tool_name: &str) -> GatewayApprovalAction {
+        match self.mode {
+            ApprovalMode::AllowAll => GatewayApprovalAction::Accept,
+            ApprovalMode::DenyAll => GatewayApprovalAction::Reject,
+            ApprovalMode::Allowlist if is_allowlisted(tool_name, &self.allowlist) => {
+                GatewayApprovalAction::Accept
+            }
+            ApprovalMode::Allowlist => GatewayApprovalAction::Ask,
+        }
+    }
+
+    /// The allowlist entry that accepts `tool_name`, if any.
+    pub fn allowlist_match(&self, tool_name: &str) -> Option<&str> {
+        let normalized = strip_mcp_prefix(tool_name);
+        self.allowlist
+            .get(tool_name)
+            .or_else(|| self.allowlist.get(normalized))
+            .map(String::as_str)
+    }
+}
+
+fn resolve_approval_from_override(
+    override_value: &AutoApproveOverride,
+) -> Option<(ApprovalMode, HashSet<String>)> {
+    match override_value {
+        AutoApproveOverride::Mode(mode) => match mode.trim().to_ascii_lowercase().as_str() {
+            "all" => Some((ApprovalMode::AllowAll, HashSet::new())),
+            "none" => Some((ApprovalMode::DenyAll, HashSet::new())),
+            _ => {
+                debug!(mode = %mode, "unknown auto_approve override mode; falling back to channel/default approval policy");
+                None
+            }
+        },
+        AutoApproveOverride::AllowList(tools) => {
+            let allowlist = tools
+                .iter()
+                .filter_map(|tool| {
+                    let trimmed = tool.trim();
+                    if trimmed.is_empty() {
+                        None
+                    } else {
+                        Some(trimmed.to_string())
+                    }
+                })
+                .collect::<HashSet<_>>();
+            Some((ApprovalMode::Allowlist, allowlist))
+        }
+    }
+}
+
+pub(crate) fn is_allowlisted(tool_name: &str, approval_allowlist: &HashSet<String>) -> bool {
+    let normalized = strip_mcp_prefix(tool_name);
+    approval_allowlist.contains(tool_name) || approval_allowlist.contains(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_approval_reports_where_the_mode_came_from() {
+        let policy = ApprovalPolicy::new(
+            ApprovalMode::DenyAll,
+            Vec::new(),
+            HashMap::from([
+                (
+                    "slack".to_string(),
+                    ChannelOverrides {
+                        approval_mode: Some(ApprovalMode::Allowlist),
+                        approval_allowlist: Some(vec!["view".to_string()]),
+                        ..ChannelOverrides::default()
+                    },
+                ),
+                (
+                    "discord".to_string(),
+                    ChannelOverrides {
+                        model: Some("openai/gpt-4o-mini".to_string()),
+                        ..ChannelOverrides::default()
+                    },
+                ),
+            ]),
+        );
+
+        let slack = policy.channel_approval("slack");
+        assert_eq!(slack.source, ApprovalSource::Channel);
+        assert_eq!(
+            slack.action_for("stakpak__view"),
+            GatewayApprovalAction::Accept
+        );
+        assert_eq!(slack.allowlist_match("stakpak__view"), Some("view"));
+        assert_eq!(slack.action_for("run_command"), GatewayApprovalAction::Ask);
+
+        let discord = policy.channel_approval("discord");
+        assert_eq!(discord.source, ApprovalSource::Gateway);
+        assert_eq!(discord.action_for("view"), GatewayApprovalAction::Reject);
+    }
+
+    #[test]
+    fn run_override_takes_precedence_over_channel() {
+        let policy = ApprovalPolicy::new(ApprovalMode::AllowAll, Vec::new(), HashMap::new());
+        let overrides = RunOverrides {
+            auto_approve: Some(AutoApproveOverride::Mode("none".to_string())),
+            ..RunOverrides::default()
+        };
+
+        let approval = policy.run_approval("telegram", Some(&overrides));
+        assert_eq!(approval.source, ApprovalSource::RunOverride);
+        assert_eq!(approval.action_for("view"), GatewayApprovalAction::Reject);
+
+        let unknown = RunOverrides {
+            auto_approve: Some(AutoApproveOverride::Mode("sometimes".to_string())),
+            ..RunOverrides::default()
+        };
+        let approval = policy.run_approval("telegram", Some(&unknown));
+        assert_eq!(approval.source, ApprovalSource::Gateway);
+        assert_eq!(approval.action_for("view"), GatewayApprovalAction::Accept);
+    }
+
+    #[test]
+    fn rules_settle_calls_before_the_mode() {
+        let policy = ApprovalPolicy::new(ApprovalMode::AllowAll, Vec::new(), HashMap::new())
+            .with_rules(ApprovalRules {
+                allow: Vec::new(),
+                require_approval: vec!["terraform apply".to_string()],
+                deny: vec!["rm -rf *".to_string()],
+            });
+        let approval = policy.channel_approval("slack");
+        let decide = |name: &str, command: &str| {
+            approval.decide(name, &serde_json::json!({ "command": command }))
+        };
+
+        assert_eq!(
+            decide("stakpak__run_command", "rm -rf /"),
+            ToolCallApproval {
+                action: GatewayApprovalAction::Reject,
+                rule: Some("rm -rf".to_string()),
+            }
+        );
+        assert_eq!(
+            decide("run_command_task", "cd infra && terraform apply").action,
+            GatewayApprovalAction::Ask
+        );
+        assert_eq!(
+            decide("run_command", "terraform plan"),
+            ToolCallApproval {
+                action: GatewayApprovalAction::Accept,
+                rule: None,
+            }
+        );
+        // Only shell tools have their `command` matched
+        assert_eq!(
+            decide("create", "rm -rf /").action,
+            GatewayApprovalAction::Accept
+        );
+    }
+}
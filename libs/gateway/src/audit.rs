@@ -0,0 +1,221 @@
+//! Approval audit log: one record per tool call the gateway approved or
+//! denied, whether a chat user pressed a button, the approval mode settled
+//! it, or the gateway rejected it on its own (new message, shutdown,
+//! channel unavailable). Records are kept in the [`StoreBackend`] and never
+//! pruned; `stakpak gateway audit` exports them.
+//!
+//! Arguments are stored as a SHA-256 hash, so the log proves what was
+//! approved without keeping secrets that tool calls carry.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use stakpak_agent_core::ProposedToolCall;
+use tracing::warn;
+
+use crate::store::StoreBackend;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditDecision {
+    Approved,
+    Denied,
+}
+
+/// Who settled a tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSource {
+    /// A chat user, named by `decided_by`.
+    User,
+    /// The approval mode named by `decided_by` (`allow_all`, `deny_all`,
+    /// `allowlist`), or `approval_rules` with the matching pattern as `reason`.
+    Policy,
+    /// The gateway itself; `reason` says why.
+    System,
+}
+
+impl AuditDecision {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditDecision::Approved => "approved",
+            AuditDecision::Denied => "denied",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "approved" => Some(AuditDecision::Approved),
+            "denied" => Some(AuditDecision::Denied),
+            _ => None,
+        }
+    }
+}
+
+impl AuditSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditSource::User => "user",
+            AuditSource::Policy => "policy",
+            AuditSource::System => "system",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "user" => Some(AuditSource::User),
+            "policy" => Some(AuditSource::Policy),
+            "system" => Some(AuditSource::System),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalAuditRecord {
+    /// Assigned by the store; 0 until recorded.
+    pub id: i64,
+    /// Milliseconds since the Unix epoch.
+    pub decided_at: i64,
+    pub channel: String,
+    pub session_id: String,
+    pub run_id: String,
+    pub tool_call_id: String,
+    pub tool_name: String,
+    /// Hex SHA-256 of the arguments' JSON.
+    pub arguments_hash: String,
+    pub decision: AuditDecision,
+    pub source: AuditSource,
+    /// Peer id of the user, the approval mode, `approval_rules` or `gateway`.
+    pub decided_by: String,
+    pub reason: Option<String>,
+}
+
+/// Narrows [`StoreBackend::list_approval_audit`]; unset fields match every record.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApprovalAuditFilter {
+    /// Inclusive lower bound on `decided_at`, in milliseconds.
+    pub since: Option<i64>,
+    /// Exclusive upper bound on `decided_at`, in milliseconds.
+    pub until: Option<i64>,
+    pub channel: Option<String>,
+    pub session_id: Option<String>,
+    pub tool_name: Option<String>,
+}
+
+/// One decision applied to a batch of tool calls in the same run.
+pub struct AuditEntry<'a> {
+    pub channel: &'a str,
+    pub session_id: &'a str,
+    pub run_id: &'a str,
+    pub decision: AuditDecision,
+    pub source: AuditSource,
+    pub decided_by: &'a str,
+    pub reason: Option<&'a str>,
+}
+
+impl AuditEntry<'_> {
+    pub fn records<'c>(
+        &self,
+        tool_calls: impl IntoIterator<Item = &'c ProposedToolCall>,
+    ) -> Vec<ApprovalAuditRecord> {
+        let decided_at = chrono::Utc::now().timestamp_millis();
+        tool_calls
+            .into_iter()
+            .map(|tool_call| ApprovalAuditRecord {
+                id: 0,
+                decided_at,
+                channel: self.channel.to_string(),
+                session_id: self.session_id.to_string(),
+                run_id: self.run_id.to_string(),
+                tool_call_id: tool_call.id.clone(),
+                tool_name: tool_call.name.clone(),
+                arguments_hash: arguments_hash(&tool_call.arguments),
+                decision: self.decision,
+                source: self.source,
+                decided_by: self.decided_by.to_string(),
+                reason: self.reason.map(str::to_string),
+            })
+            .collect()
+    }
+}
+
+/// Hex SHA-256 of `arguments` serialized as JSON. Object keys serialize in
+/// sorted order, so equal arguments hash the same however they arrived.
+pub fn arguments_hash(arguments: &serde_json::Value) -> String {
+    let digest = Sha256::digest(arguments.to_string().as_bytes());
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Append the decision to the audit log. A failed write is logged, never
+/// returned: the decision itself has already been sent to the server.
+pub async fn record<'c>(
+    store: &dyn StoreBackend,
+    entry: AuditEntry<'_>,
+    tool_calls: impl IntoIterator<Item = &'c ProposedToolCall>,
+) {
+    let records = entry.records(tool_calls);
+    if records.is_empty() {
+        return;
+    }
+    if let Err(error) = store.record_approval_audit(&records).await {
+        warn!(
+            session_id = %entry.session_id,
+            run_id = %entry.run_id,
+            error = %error,
+            "failed to record approval audit entries"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments_hash_ignores_key_order() {
+        let a: serde_json::Value =
+            serde_json::from_str(r#"{"command":"ls","cwd":"/tmp"}"#).expect("json");
+        let b: serde_json::Value =
+            serde_json::from_str(r#"{"cwd":"/tmp","command":"ls"}"#).expect("json");
+
+        assert_eq!(arguments_hash(&a), arguments_hash(&b));
+        assert_eq!(arguments_hash(&a).len(), 64);
+        assert_ne!(
+            arguments_hash(&a),
+            arguments_hash(&serde_json::json!({"command": "rm"}))
+        );
+    }
+
+    #[test]
+    fn entry_records_one_row_per_tool_call() {
+        let calls = vec![
+            ProposedToolCall {
+                id: "tc1".to_string(),
+                name: "run_command".to_string(),
+                arguments: serde_json::json!({"command": "ls"}),
+                metadata: None,
+            },
+            ProposedToolCall {
+                id: "tc2".to_string(),
+                name: "view".to_string(),
+                arguments: serde_json::json!({}),
+                metadata: None,
+            },
+        ];
+        let records = AuditEntry {
+            channel: "slack",
+            session_id: "s1",
+            run_id: "r1",
+            decision: AuditDecision::Denied,
+            source: AuditSource::User,
+            decided_by: "U123",
+            reason: None,
+        }
+        .records(&calls);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].tool_call_id, "tc1");
+        assert_eq!(records[1].tool_name, "view");
+        assert!(records.iter().all(|r| r.decided_by == "U123" && r.id == 0));
+    }
+}
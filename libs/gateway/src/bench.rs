@@ -0,0 +1,340 @@
+//! Round-trip latency and rate-limit behaviour of channel sends.
+//!
+//! A bench calls [`Channel::test`] once, then posts a burst of messages to a
+//! target and times each send. Channels retry rate-limited requests after
+//! the provider's `retry_after`, so throttling shows up as sends far slower
+//! than the median rather than as errors; both are counted.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::{channels::Channel, targeting::ChannelTarget, types::OutboundReply};
+
+/// Sends slower than this, and well above the median, count as throttled.
+const THROTTLED_AFTER: Duration = Duration::from_secs(1);
+
+/// How many times the median a send must take to count as throttled.
+const THROTTLED_MEDIAN_FACTOR: u64 = 3;
+
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub messages: usize,
+    /// Pause between sends; zero sends back to back to find the limit.
+    pub interval: Duration,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SendSample {
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LatencySummary {
+    pub min_ms: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelBenchReport {
+    pub channel: String,
+    /// Target key the burst was sent to; `None` when only `test` ran.
+    pub target: Option<String>,
+    pub test_ms: Option<u64>,
+    /// Why the channel test failed; no messages are sent then.
+    pub test_error: Option<String>,
+    pub samples: Vec<SendSample>,
+    /// Latency of successful sends.
+    pub latency: Option<LatencySummary>,
+    pub failed: usize,
+    /// Sends failed with a rate-limit error or slow enough to have waited one out.
+    pub throttled: usize,
+    /// Successful sends per second over the whole burst.
+    pub sends_per_second: Option<f64>,
+}
+
+pub async fn bench_channel(
+    channel: Arc<dyn Channel>,
+    channel_name: &str,
+    target: Option<&ChannelTarget>,
+    options: &BenchOptions,
+) -> ChannelBenchReport {
+    let started = Instant::now();
+    let test = channel.test().await;
+    let test_ms = duration_ms(started.elapsed());
+
+    let mut report = ChannelBenchReport {
+        channel: channel_name.to_string(),
+        target: target.map(ChannelTarget::target_key),
+        test_ms: Some(test_ms),
+        test_error: test.err().map(|error| error.to_string()),
+        samples: Vec::new(),
+        latency: None,
+        failed: 0,
+        throttled: 0,
+        sends_per_second: None,
+    };
+    let Some(target) = target.filter(|_| report.test_error.is_none()) else {
+        return report;
+    };
+
+    let burst_started = Instant::now();
+    for index in 0..options.messages {
+        if index > 0 && !options.interval.is_zero() {
+            tokio::time::sleep(options.interval).await;
+        }
+        let reply = OutboundReply {
+            channel: channel_name.into(),
+            peer_id: target.peer_id(),
+            chat_type: target.chat_type(),
+            text: format!("stakpak gateway bench {}/{}", index + 1, options.messages),
+            metadata: target.metadata(),
+        };
+        let sent = Instant::now();
+        let result = channel.send_with_receipt(reply).await;
+        report.samples.push(SendSample {
+            latency_ms: duration_ms(sent.elapsed()),
+            error: result.err().map(|error| error.to_string()),
+        });
+    }
+
+    summarize(&mut report, burst_started.elapsed());
+    report
+}
+
+fn summarize(report: &mut ChannelBenchReport, burst: Duration) {
+    let mut latencies: Vec<u64> = report
+        .samples
+        .iter()
+        .filter(|sample| sample.error.is_none())
+        .map(|sample| sample.latency_ms)
+        .collect();
+    latencies.sort_unstable();
+    report.failed = report.samples.len() - latencies.len();
+    report.latency = latency_summary(&latencies);
+
+    let slow_after = report.latency.map_or(u64::MAX, |latency| {
+        duration_ms(THROTTLED_AFTER).max(latency.p50_ms * THROTTLED_MEDIAN_FACTOR)
+    });
+    report.throttled = report
+        .samples
+        .iter()
+        .filter(|sample| match &sample.error {
+            Some(error) => is_rate_limit_error(error),
+            None => sample.latency_ms >= slow_after,
+        })
+        .count();
+
+    if !latencies.is_empty() && !burst.is_zero() {
+        report.sends_per_second = Some(latencies.len() as f64 / burst.as_secs_f64());
+    }
+}
+
+fn latency_summary(sorted: &[u64]) -> Option<LatencySummary> {
+    let (first, last) = (sorted.first()?, sorted.last()?);
+    Some(LatencySummary {
+        min_ms: *first,
+        p50_ms: percentile(sorted, 50),
+        p95_ms: percentile(sorted, 95),
+        max_ms: *last,
+    })
+}
+
+/// Nearest-rank percentile of an ascending, non-empty slice.
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied().unwrap_or_default()
+}
+
+fn is_rate_limit_error(error: &str) -> bool {
+    let error = error.to_ascii_lowercase();
+    error.contains("429") || error.contains("too many requests") || error.contains("ratelimited")
+}
+
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::ChannelTestResult;
+    use crate::types::{ChannelId, InboundMessage};
+    use anyhow::{Result, anyhow};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use tokio::sync::mpsc;
+    use tokio_util::sync::CancellationToken;
+
+    struct ScriptedChannel {
+        id: ChannelId,
+        /// Per-send delay in ms; `None` fails the send as rate limited.
+        script: Mutex<Vec<Option<u64>>>,
+        sent: Mutex<Vec<OutboundReply>>,
+    }
+
+    #[async_trait]
+    impl Channel for ScriptedChannel {
+        fn id(&self) -> &ChannelId {
+            &self.id
+        }
+
+        fn display_name(&self) -> &str {
+            "Scripted"
+        }
+
+        async fn start(
+            &self,
+            _inbound_tx: mpsc::Sender<InboundMessage>,
+            _cancel: CancellationToken,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send(&self, reply: OutboundReply) -> Result<()> {
+            let step = {
+                let mut script = self.script.lock().map_err(|_| anyhow!("poisoned"))?;
+                if script.is_empty() {
+                    Some(0)
+                } else {
+                    script.remove(0)
+                }
+            };
+            let Some(delay) = step else {
+                return Err(anyhow!(
+                    "telegram sendMessage failed: 429 Too Many Requests"
+                ));
+            };
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            self.sent
+                .lock()
+                .map_err(|_| anyhow!("poisoned"))?
+                .push(reply);
+            Ok(())
+        }
+
+        async fn test(&self) -> Result<ChannelTestResult> {
+            Ok(ChannelTestResult {
+                channel: self.id.0.clone(),
+                identity: "scripted".to_string(),
+                details: "ok".to_string(),
+            })
+        }
+    }
+
+    fn sample(latency_ms: u64, error: Option<&str>) -> SendSample {
+        SendSample {
+            latency_ms,
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn summarize_counts_slow_and_rate_limited_sends() {
+        let mut report = ChannelBenchReport {
+            channel: "telegram".to_string(),
+            target: None,
+            test_ms: None,
+            test_error: None,
+            samples: vec![
+                sample(100, None),
+                sample(120, None),
+                sample(110, None),
+                sample(2_000, None),
+                sample(5, Some("429 Too Many Requests")),
+                sample(8, Some("connection reset")),
+                sample(90, None),
+            ],
+            latency: None,
+            failed: 0,
+            throttled: 0,
+            sends_per_second: None,
+        };
+        summarize(&mut report, Duration::from_millis(2_500));
+
+        assert_eq!(report.failed, 2);
+        assert_eq!(report.throttled, 2);
+        assert_eq!(
+            report.latency,
+            Some(LatencySummary {
+                min_ms: 90,
+                p50_ms: 110,
+                p95_ms: 2_000,
+                max_ms: 2_000,
+            })
+        );
+        assert_eq!(report.sends_per_second, Some(2.0));
+    }
+
+    #[tokio::test]
+    async fn bench_sends_numbered_messages_to_the_target() {
+        let channel = Arc::new(ScriptedChannel {
+            id: ChannelId("telegram".to_string()),
+            script: Mutex::new(vec![Some(1), None, Some(1)]),
+            sent: Mutex::new(Vec::new()),
+        });
+        let target = ChannelTarget::Telegram {
+            chat_id: "42".to_string(),
+            thread_id: None,
+        };
+
+        let report = bench_channel(
+            channel.clone(),
+            "telegram",
+            Some(&target),
+            &BenchOptions {
+                messages: 3,
+                interval: Duration::ZERO,
+            },
+        )
+        .await;
+
+        assert_eq!(report.target.as_deref(), Some("telegram:chat:42"));
+        assert_eq!(report.samples.len(), 3);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.throttled, 1);
+        assert!(report.sends_per_second.is_some());
+
+        let sent = channel.sent.lock().expect("sent");
+        let texts: Vec<&str> = sent.iter().map(|reply| reply.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["stakpak gateway bench 1/3", "stakpak gateway bench 3/3"]
+        );
+        assert_eq!(sent[0].peer_id.0, "42");
+    }
+
+    #[tokio::test]
+    async fn bench_without_target_only_tests_the_channel() {
+        let channel = Arc::new(ScriptedChannel {
+            id: ChannelId("slack".to_string()),
+            script: Mutex::new(Vec::new()),
+            sent: Mutex::new(Vec::new()),
+        });
+        let report = bench_channel(
+            channel,
+            "slack",
+            None,
+            &BenchOptions {
+                messages: 5,
+                interval: Duration::ZERO,
+            },
+        )
+        .await;
+        assert!(report.test_ms.is_some());
+        assert!(report.samples.is_empty());
+        assert_eq!(report.latency, None);
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let sorted: Vec<u64> = (1..=20).collect();
+        assert_eq!(percentile(&sorted, 50), 10);
+        assert_eq!(percentile(&sorted, 95), 19);
+        assert_eq!(percentile(&[7], 95), 7);
+    }
+}
@@ -14,11 +14,14 @@ use tracing::{error, warn};
 use crate::{
     channels::{ApprovalButton, ButtonStyle, Channel, ChannelTestResult, parse_approval_callback},
     chunking::chunk_text,
-    types::{ChannelId, ChatType, InboundMessage, OutboundReply, PeerId},
+    types::{ChannelId, ChatType, InboundControl, InboundMessage, OutboundReply, PeerId},
 };
 
 const DISCORD_TEXT_LIMIT: usize = 2000;
-const DISCORD_INTENTS: u64 = (1 << 0) | (1 << 9) | (1 << 12) | (1 << 15); // 37377
+// Guilds, guild messages, guild message reactions, direct messages, direct
+// message reactions and message content.
+const DISCORD_INTENTS: u64 = (1 << 0) | (1 << 9) | (1 << 10) | (1 << 12) | (1 << 13) | (1 << 15); // 46593
+const DEFAULT_CANCEL_REACTION: &str = "🛑";
 
 const DISCORD_OP_DISPATCH: u8 = 0;
 const DISCORD_OP_HEARTBEAT: u8 = 1;
@@ -46,6 +49,7 @@ pub struct DiscordChannel {
     http: reqwest::Client,
     bot_user_id: Mutex<Option<String>>,
     channel_cache: Mutex<HashMap<String, DiscordChannelMeta>>,
+    cancel_reaction: String,
 }
 
 #[derive(Debug, Clone)]
@@ -62,6 +66,54 @@ impl DiscordChannel {
             http: reqwest::Client::new(),
             bot_user_id: Mutex::new(None),
             channel_cache: Mutex::new(HashMap::new()),
+            cancel_reaction: DEFAULT_CANCEL_REACTION.to_string(),
+        }
+    }
+
+    /// Emoji (or custom emoji name) that cancels the active run when added to
+    /// one of the bot's messages; empty turns it off.
+    pub fn with_cancel_reaction(mut self, reaction: String) -> Self {
+        self.cancel_reaction = reaction.trim().to_string();
+        self
+    }
+
+    /// Whether `event` is the cancel reaction added by a user to one of the
+    /// bot's messages.
+    fn is_cancel_reaction(&self, event: &ReactionAddEvent) -> bool {
+        let own_bot_id = self
+            .bot_user_id
+            .lock()
+            .ok()
+            .and_then(|guard| guard.clone())
+            .unwrap_or_default();
+        let emoji = event.emoji.name.as_deref().unwrap_or_default();
+
+        !self.cancel_reaction.is_empty()
+            && emoji.trim_end_matches('\u{fe0f}')
+                == self.cancel_reaction.trim_end_matches('\u{fe0f}')
+            && !own_bot_id.is_empty()
+            && event.user_id != own_bot_id
+            && event.message_author_id.as_deref() == Some(own_bot_id.as_str())
+    }
+
+    async fn chat_type_for(&self, channel_id: &str, guild_id: Option<&str>) -> ChatType {
+        if guild_id.is_none() {
+            return ChatType::Direct;
+        }
+
+        match self.fetch_channel_meta(channel_id).await.ok() {
+            Some(meta)
+                if meta.kind == DISCORD_CHANNEL_TYPE_PUBLIC_THREAD
+                    || meta.kind == DISCORD_CHANNEL_TYPE_PRIVATE_THREAD =>
+            {
+                ChatType::Thread {
+                    group_id: meta.parent_id.unwrap_or_else(|| channel_id.to_string()),
+                    thread_id: channel_id.to_string(),
+                }
+            }
+            _ => ChatType::Group {
+                id: channel_id.to_string(),
+            },
         }
     }
 
@@ -539,6 +591,44 @@ impl Channel for DiscordChannel {
                                                     "message_id": interaction.message.as_ref().map(|msg| msg.id.clone()),
                                                 }),
                                                 timestamp: Utc::now(),
+                                                control: None,
+                                            };
+
+                                            if inbound_tx.send(inbound).await.is_err() {
+                                                return Ok(());
+                                            }
+
+                                            continue;
+                                        }
+
+                                        if event == "MESSAGE_REACTION_ADD" {
+                                            let reaction: ReactionAddEvent = match serde_json::from_value(payload.d.unwrap_or_default()) {
+                                                Ok(value) => value,
+                                                Err(error) => {
+                                                    warn!(error = %error, "discord MESSAGE_REACTION_ADD decode failed");
+                                                    continue;
+                                                }
+                                            };
+
+                                            if !self.is_cancel_reaction(&reaction) {
+                                                continue;
+                                            }
+
+                                            let inbound = InboundMessage {
+                                                channel: self.id.clone(),
+                                                peer_id: PeerId(reaction.user_id),
+                                                chat_type: self
+                                                    .chat_type_for(&reaction.channel_id, reaction.guild_id.as_deref())
+                                                    .await,
+                                                text: String::new(),
+                                                media: Vec::new(),
+                                                metadata: serde_json::json!({
+                                                    "channel_id": reaction.channel_id,
+                                                    "guild_id": reaction.guild_id,
+                                                    "message_id": reaction.message_id,
+                                                }),
+                                                timestamp: Utc::now(),
+                                                control: Some(InboundControl::Cancel),
                                             };
 
                                             if inbound_tx.send(inbound).await.is_err() {
@@ -580,25 +670,9 @@ impl Channel for DiscordChannel {
                                             continue;
                                         }
 
-                                        let channel_meta = self.fetch_channel_meta(&message_event.channel_id).await.ok();
-
-                                        let chat_type = match (&message_event.guild_id, channel_meta) {
-                                            (None, _) => ChatType::Direct,
-                                            (Some(_guild_id), Some(meta))
-                                                if meta.kind == DISCORD_CHANNEL_TYPE_PUBLIC_THREAD
-                                                    || meta.kind == DISCORD_CHANNEL_TYPE_PRIVATE_THREAD =>
-                                            {
-                                                ChatType::Thread {
-                                                    group_id: meta
-                                                        .parent_id
-                                                        .unwrap_or_else(|| message_event.channel_id.clone()),
-                                                    thread_id: message_event.channel_id.clone(),
-                                                }
-                                            }
-                                            (Some(_), _) => ChatType::Group {
-                                                id: message_event.channel_id.clone(),
-                                            },
-                                        };
+                                        let chat_type = self
+                                            .chat_type_for(&message_event.channel_id, message_event.guild_id.as_deref())
+                                            .await;
 
                                         let timestamp = DateTime::parse_from_rfc3339(&message_event.timestamp)
                                             .map(|value| value.with_timezone(&Utc))
@@ -616,6 +690,7 @@ impl Channel for DiscordChannel {
                                                 "message_id": message_event.id,
                                             }),
                                             timestamp,
+                                            control: None,
                                         };
 
                                         if inbound_tx.send(inbound).await.is_err() {
@@ -762,6 +837,24 @@ struct MessageCreateEvent {
     kind: u8,
 }
 
+#[derive(Debug, Deserialize)]
+struct ReactionAddEvent {
+    user_id: String,
+    channel_id: String,
+    message_id: String,
+    #[serde(default)]
+    guild_id: Option<String>,
+    #[serde(default)]
+    message_author_id: Option<String>,
+    emoji: DiscordEmoji,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscordEmoji {
+    #[serde(default)]
+    name: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct InteractionCreateEvent {
     id: String,
@@ -902,7 +995,8 @@ fn parse_discord_message_id(message_id: &str) -> Option<(&str, &str)> {
 #[cfg(test)]
 mod tests {
     use super::{
-        DISCORD_OP_DISPATCH, GatewayPayload, MessageCreateEvent, parse_discord_message_id,
+        DISCORD_OP_DISPATCH, DiscordChannel, GatewayPayload, MessageCreateEvent, ReactionAddEvent,
+        parse_discord_message_id,
     };
 
     #[test]
@@ -950,4 +1044,37 @@ mod tests {
             Some(("team:123", "456"))
         );
     }
+
+    #[test]
+    fn cancel_reaction_matches_user_reactions_on_bot_messages() {
+        let discord = DiscordChannel::new(String::new());
+        if let Ok(mut guard) = discord.bot_user_id.lock() {
+            *guard = Some("bot".to_string());
+        }
+        let reaction = |emoji: &str, user: &str, author: &str| -> ReactionAddEvent {
+            match serde_json::from_value(serde_json::json!({
+                "user_id": user,
+                "channel_id": "c1",
+                "message_id": "m1",
+                "guild_id": "g1",
+                "message_author_id": author,
+                "emoji": {"id": null, "name": emoji}
+            })) {
+                Ok(value) => value,
+                Err(error) => panic!("failed to parse reaction: {error}"),
+            }
+        };
+
+        assert!(discord.is_cancel_reaction(&reaction("🛑", "u1", "bot")));
+        assert!(!discord.is_cancel_reaction(&reaction("👍", "u1", "bot")));
+        assert!(!discord.is_cancel_reaction(&reaction("🛑", "u1", "u2")));
+        assert!(!discord.is_cancel_reaction(&reaction("🛑", "bot", "bot")));
+
+        let custom = DiscordChannel::new(String::new()).with_cancel_reaction("❌".to_string());
+        if let Ok(mut guard) = custom.bot_user_id.lock() {
+            *guard = Some("bot".to_string());
+        }
+        assert!(custom.is_cancel_reaction(&reaction("❌", "u1", "bot")));
+        assert!(!custom.is_cancel_reaction(&reaction("🛑", "u1", "bot")));
+    }
 }
@@ -0,0 +1,800 @@
+//! Matrix channel over the client-server API.
+//!
+//! Listens with `/sync` long polling on rooms the bot user has joined and
+//! replies with HTML rendered from the markdown. Encrypted rooms are not
+//! supported yet: their events are skipped with a warning. Matrix has no
+//! buttons, so approval prompts get ✅/❌ reactions the user can click.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, warn};
+
+use crate::{
+    channels::{
+        ApprovalButton, ButtonStyle, Channel, ChannelTestResult, DeliveryReceipt,
+        parse_approval_callback,
+    },
+    chunking::chunk_text,
+    matrix_html::markdown_to_matrix_html,
+    types::{ChannelId, ChatType, InboundMessage, OutboundReply, PeerId},
+};
+
+/// Events are capped at 64 KiB; the HTML copy roughly doubles the text.
+const MATRIX_TEXT_LIMIT: usize = 16_000;
+const SYNC_TIMEOUT_MS: u64 = 30_000;
+/// Keeps presence and account data out of the sync responses.
+const SYNC_FILTER: &str = r#"{"presence":{"not_types":["*"]},"account_data":{"not_types":["*"]},"room":{"timeline":{"limit":50}}}"#;
+const APPROVE_REACTION: &str = "✅";
+const DENY_REACTION: &str = "❌";
+
+pub struct MatrixChannel {
+    id: ChannelId,
+    homeserver: String,
+    access_token: String,
+    rooms: HashSet<String>,
+    auto_join: bool,
+    client: reqwest::Client,
+    user_id: Mutex<Option<String>>,
+    /// Approval prompts by event id: reaction key → callback data.
+    approval_prompts: Mutex<HashMap<String, Vec<(String, String)>>>,
+    warned_encrypted: Mutex<HashSet<String>>,
+}
+
+impl MatrixChannel {
+    pub fn new(homeserver: String, access_token: String) -> Self {
+        Self {
+            id: "matrix".into(),
+            homeserver: homeserver.trim_end_matches('/').to_string(),
+            access_token,
+            rooms: HashSet::new(),
+            auto_join: false,
+            client: reqwest::Client::new(),
+            user_id: Mutex::new(None),
+            approval_prompts: Mutex::new(HashMap::new()),
+            warned_encrypted: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Only listen in these room ids; empty means every joined room.
+    pub fn with_rooms(mut self, rooms: Vec<String>) -> Self {
+        self.rooms = rooms.into_iter().collect();
+        self
+    }
+
+    /// Join rooms the bot is invited to (limited to `rooms` when set).
+    pub fn with_auto_join(mut self, auto_join: bool) -> Self {
+        self.auto_join = auto_join;
+        self
+    }
+
+    fn api_url(&self, segments: &[&str]) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(&self.homeserver)
+            .with_context(|| format!("invalid matrix homeserver url '{}'", self.homeserver))?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow!("matrix homeserver url cannot be a base"))?
+            .pop_if_empty()
+            .extend(["_matrix", "client", "v3"])
+            .extend(segments);
+        Ok(url)
+    }
+
+    async fn whoami(&self) -> Result<MxWhoami> {
+        let response = self
+            .client
+            .get(self.api_url(&["account", "whoami"])?)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .context("matrix whoami request failed")?;
+        decode_response(response, "whoami").await
+    }
+
+    async fn sync(&self, since: Option<&str>, timeout_ms: u64) -> Result<MxSync> {
+        let mut query = vec![
+            ("timeout", timeout_ms.to_string()),
+            ("filter", SYNC_FILTER.to_string()),
+        ];
+        if let Some(since) = since {
+            query.push(("since", since.to_string()));
+        }
+
+        let response = self
+            .client
+            .get(self.api_url(&["sync"])?)
+            .bearer_auth(&self.access_token)
+            .query(&query)
+            .send()
+            .await
+            .context("matrix sync request failed")?;
+        decode_response(response, "sync").await
+    }
+
+    async fn join_room(&self, room_id: &str) -> Result<()> {
+        let response = self
+            .client
+            .post(self.api_url(&["rooms", room_id, "join"])?)
+            .bearer_auth(&self.access_token)
+            .json(&serde_json::json!({}))
+            .send()
+            .await
+            .context("matrix join request failed")?;
+        decode_response::<serde_json::Value>(response, "join")
+            .await
+            .map(|_| ())
+    }
+
+    async fn send_event(
+        &self,
+        room_id: &str,
+        event_type: &str,
+        content: &serde_json::Value,
+    ) -> Result<String> {
+        let txn_id = uuid::Uuid::new_v4().to_string();
+        let response = self
+            .client
+            .put(self.api_url(&["rooms", room_id, "send", event_type, &txn_id])?)
+            .bearer_auth(&self.access_token)
+            .json(content)
+            .send()
+            .await
+            .context("matrix send request failed")?;
+        decode_response::<MxEventId>(response, "send")
+            .await
+            .map(|sent| sent.event_id)
+    }
+
+    async fn send_markdown(
+        &self,
+        room_id: &str,
+        thread_id: Option<&str>,
+        text: &str,
+    ) -> Result<String> {
+        let mut content = message_content(text);
+        if let Some(thread_id) = thread_id {
+            content["m.relates_to"] = serde_json::json!({
+                "rel_type": "m.thread",
+                "event_id": thread_id,
+            });
+        }
+        self.send_event(room_id, "m.room.message", &content).await
+    }
+
+    fn own_user_id(&self) -> Option<String> {
+        self.user_id.lock().ok().and_then(|guard| guard.clone())
+    }
+
+    fn room_allowed(&self, room_id: &str) -> bool {
+        self.rooms.is_empty() || self.rooms.contains(room_id)
+    }
+
+    fn extract_target(reply: &OutboundReply) -> Result<(String, Option<String>)> {
+        let room_id = reply
+            .metadata
+            .get("room_id")
+            .and_then(|value| value.as_str())
+            .map(ToOwned::to_owned)
+            .or_else(|| match &reply.chat_type {
+                ChatType::Group { id } => Some(id.clone()),
+                ChatType::Thread { group_id, .. } => Some(group_id.clone()),
+                ChatType::Direct => None,
+            })
+            .ok_or_else(|| anyhow!("matrix reply missing room_id in metadata/chat_type"))?;
+
+        let thread_id = reply
+            .metadata
+            .get("thread_id")
+            .and_then(|value| value.as_str())
+            .map(ToOwned::to_owned);
+
+        Ok((room_id, thread_id))
+    }
+
+    /// Inbound messages from one sync response, in timeline order.
+    fn map_sync(&self, sync: &MxSync) -> Vec<InboundMessage> {
+        let own_user_id = self.own_user_id().unwrap_or_default();
+        let mut inbound = Vec::new();
+
+        for (room_id, room) in &sync.rooms.join {
+            if !self.room_allowed(room_id) {
+                continue;
+            }
+            for event in &room.timeline.events {
+                if event.sender == own_user_id {
+                    continue;
+                }
+                let message = match event.r#type.as_str() {
+                    "m.room.message" => self.map_message_inbound(room_id, event),
+                    "m.reaction" => self.map_reaction_inbound(room_id, event),
+                    "m.room.encrypted" => {
+                        self.warn_encrypted(room_id);
+                        None
+                    }
+                    _ => None,
+                };
+                inbound.extend(message);
+            }
+        }
+
+        inbound
+    }
+
+    fn map_message_inbound(&self, room_id: &str, event: &MxEvent) -> Option<InboundMessage> {
+        let content: MxMessageContent = serde_json::from_value(event.content.clone()).ok()?;
+        if content.msgtype.as_deref() != Some("m.text") {
+            return None;
+        }
+        let relation = content.relates_to.as_ref();
+        // Edits re-send the whole message; the original was already handled.
+        if relation.and_then(|relation| relation.rel_type.as_deref()) == Some("m.replace") {
+            return None;
+        }
+
+        let body = content.body.as_deref()?;
+        let text = if relation.is_some_and(|relation| relation.in_reply_to.is_some()) {
+            strip_reply_fallback(body)
+        } else {
+            body.to_string()
+        };
+        if text.trim().is_empty() {
+            return None;
+        }
+
+        let thread_id = relation
+            .filter(|relation| relation.rel_type.as_deref() == Some("m.thread"))
+            .and_then(|relation| relation.event_id.clone());
+        let chat_type = match &thread_id {
+            Some(thread_id) => ChatType::Thread {
+                group_id: room_id.to_string(),
+                thread_id: thread_id.clone(),
+            },
+            None => ChatType::Group {
+                id: room_id.to_string(),
+            },
+        };
+
+        Some(InboundMessage {
+            channel: self.id.clone(),
+            peer_id: PeerId(event.sender.clone()),
+            chat_type,
+            text,
+            media: Vec::new(),
+            metadata: serde_json::json!({
+                "room_id": room_id,
+                "thread_id": thread_id,
+                "event_id": event.event_id,
+            }),
+            timestamp: DateTime::from_timestamp_millis(event.origin_server_ts)
+                .unwrap_or_else(Utc::now),
+            control: None,
+        })
+    }
+
+    fn map_reaction_inbound(&self, room_id: &str, event: &MxEvent) -> Option<InboundMessage> {
+        let content: MxMessageContent = serde_json::from_value(event.content.clone()).ok()?;
+        let relation = content.relates_to?;
+        let prompt_id = relation.event_id?;
+        let key = relation.key?;
+
+        let callback_data = self.approval_prompts.lock().ok().and_then(|prompts| {
+            prompts.get(&prompt_id).and_then(|reactions| {
+                reactions
+                    .iter()
+                    .find(|(reaction, _)| same_reaction(reaction, &key))
+                    .map(|(_, callback_data)| callback_data.clone())
+            })
+        })?;
+        let (approval_id, decision) = parse_approval_callback(&callback_data)?;
+
+        Some(InboundMessage {
+            channel: self.id.clone(),
+            peer_id: PeerId(event.sender.clone()),
+            chat_type: ChatType::Group {
+                id: room_id.to_string(),
+            },
+            text: String::new(),
+            media: Vec::new(),
+            metadata: serde_json::json!({
+                "type": "approval_response",
+                "approval_id": approval_id,
+                "decision": decision,
+                "room_id": room_id,
+                "event_id": prompt_id,
+            }),
+            timestamp: Utc::now(),
+            control: None,
+        })
+    }
+
+    fn warn_encrypted(&self, room_id: &str) {
+        let first = self
+            .warned_encrypted
+            .lock()
+            .map(|mut warned| warned.insert(room_id.to_string()))
+            .unwrap_or(false);
+        if first {
+            warn!(room_id = %room_id, "matrix room is end-to-end encrypted; its messages are ignored");
+        }
+    }
+}
+
+#[async_trait]
+impl Channel for MatrixChannel {
+    fn id(&self) -> &ChannelId {
+        &self.id
+    }
+
+    fn display_name(&self) -> &str {
+        "Matrix"
+    }
+
+    async fn start(
+        &self,
+        inbound_tx: mpsc::Sender<InboundMessage>,
+        cancel: CancellationToken,
+    ) -> Result<()> {
+        let me = self.whoami().await?;
+        if let Ok(mut guard) = self.user_id.lock() {
+            *guard = Some(me.user_id);
+        }
+
+        // Start from now: the backlog was either answered or is stale.
+        let mut since = self.sync(None, 0).await?.next_batch;
+
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    break;
+                }
+                sync = self.sync(Some(&since), SYNC_TIMEOUT_MS) => {
+                    let sync = match sync {
+                        Ok(sync) => sync,
+                        Err(error) => {
+                            error!(error = %error, "matrix sync failed");
+                            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                            continue;
+                        }
+                    };
+                    since.clone_from(&sync.next_batch);
+
+                    if self.auto_join {
+                        for room_id in sync.rooms.invite.keys() {
+                            if !self.room_allowed(room_id) {
+                                continue;
+                            }
+                            if let Err(error) = self.join_room(room_id).await {
+                                warn!(room_id = %room_id, error = %error, "failed to join matrix room");
+                            }
+                        }
+                    }
+
+                    for inbound in self.map_sync(&sync) {
+                        if inbound_tx.send(inbound).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn send(&self, reply: OutboundReply) -> Result<()> {
+        self.send_with_receipt(reply).await.map(|_| ())
+    }
+
+    async fn send_with_receipt(&self, reply: OutboundReply) -> Result<DeliveryReceipt> {
+        let (room_id, thread_id) = Self::extract_target(&reply)?;
+
+        let mut first_event_id: Option<String> = None;
+        for chunk in chunk_text(&reply.text, MATRIX_TEXT_LIMIT) {
+            let event_id = self
+                .send_markdown(&room_id, thread_id.as_deref(), &chunk)
+                .await?;
+            first_event_id.get_or_insert(event_id);
+        }
+
+        Ok(DeliveryReceipt {
+            message_id: first_event_id.map(|event_id| format!("{room_id}|{event_id}")),
+            thread_id,
+        })
+    }
+
+    async fn send_with_buttons(
+        &self,
+        reply: OutboundReply,
+        buttons: Vec<ApprovalButton>,
+    ) -> Result<String> {
+        let (room_id, thread_id) = Self::extract_target(&reply)?;
+
+        let reactions = buttons
+            .iter()
+            .map(|button| {
+                let key = match button.style {
+                    ButtonStyle::Success => APPROVE_REACTION,
+                    ButtonStyle::Danger => DENY_REACTION,
+                };
+                (
+                    key.to_string(),
+                    button.label.clone(),
+                    button.callback_data.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let legend = reactions
+            .iter()
+            .map(|(key, label, _)| format!("{key} {label}"))
+            .collect::<Vec<_>>()
+            .join(" · ");
+        let text = format!("{}\n\nReact with {legend}", reply.text);
+
+        let event_id = self
+            .send_markdown(&room_id, thread_id.as_deref(), &text)
+            .await?;
+
+        if let Ok(mut prompts) = self.approval_prompts.lock() {
+            prompts.insert(
+                event_id.clone(),
+                reactions
+                    .iter()
+                    .map(|(key, _, callback_data)| (key.clone(), callback_data.clone()))
+                    .collect(),
+            );
+        }
+
+        for (key, _, _) in &reactions {
+            let content = serde_json::json!({
+                "m.relates_to": {
+                    "rel_type": "m.annotation",
+                    "event_id": event_id,
+                    "key": key,
+                }
+            });
+            if let Err(error) = self.send_event(&room_id, "m.reaction", &content).await {
+                warn!(error = %error, "failed to add matrix approval reaction");
+            }
+        }
+
+        Ok(format!("{room_id}|{event_id}"))
+    }
+
+    async fn edit_message(&self, message_id: &str, new_text: &str) -> Result<()> {
+        let Some((room_id, event_id)) = message_id.split_once('|') else {
+            return Ok(());
+        };
+
+        if let Ok(mut prompts) = self.approval_prompts.lock() {
+            prompts.remove(event_id);
+        }
+
+        let new_content = message_content(new_text);
+        let mut content = message_content(&format!("* {new_text}"));
+        content["m.new_content"] = new_content;
+        content["m.relates_to"] = serde_json::json!({
+            "rel_type": "m.replace",
+            "event_id": event_id,
+        });
+        self.send_event(room_id, "m.room.message", &content)
+            .await
+            .map(|_| ())
+    }
+
+    async fn test(&self) -> Result<ChannelTestResult> {
+        let me = self.whoami().await?;
+
+        Ok(ChannelTestResult {
+            channel: self.id.0.clone(),
+            identity: me.user_id,
+            details: format!(
+                "homeserver={} device_id={}",
+                self.homeserver,
+                me.device_id.unwrap_or_else(|| "-".to_string())
+            ),
+        })
+    }
+}
+
+async fn decode_response<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+    endpoint: &str,
+) -> Result<T> {
+    let status = response.status();
+    if status.is_success() {
+        return response
+            .json()
+            .await
+            .with_context(|| format!("matrix {endpoint} decode failed"));
+    }
+
+    let error: MxError = response.json().await.unwrap_or_default();
+    Err(anyhow!(
+        "matrix {endpoint} error {} {}: {}",
+        status.as_u16(),
+        error.errcode.unwrap_or_default(),
+        error.error.unwrap_or_else(|| "unknown error".to_string())
+    ))
+}
+
+fn message_content(text: &str) -> serde_json::Value {
+    serde_json::json!({
+        "msgtype": "m.text",
+        "body": text,
+        "format": "org.matrix.custom.html",
+        "formatted_body": markdown_to_matrix_html(text),
+    })
+}
+
+/// Drop the `> <@user:server> quoted` lines clients prepend to replies.
+fn strip_reply_fallback(body: &str) -> String {
+    if !body.starts_with("> ") {
+        return body.to_string();
+    }
+    body.lines()
+        .skip_while(|line| line.starts_with('>'))
+        .skip_while(|line| line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compare reaction keys ignoring the emoji variation selector clients add.
+fn same_reaction(a: &str, b: &str) -> bool {
+    a.trim_end_matches('\u{fe0f}') == b.trim_end_matches('\u{fe0f}')
+}
+
+#[derive(Debug, Deserialize)]
+struct MxWhoami {
+    user_id: String,
+    #[serde(default)]
+    device_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MxEventId {
+    event_id: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MxError {
+    #[serde(default)]
+    errcode: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MxSync {
+    next_batch: String,
+    #[serde(default)]
+    rooms: MxSyncRooms,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MxSyncRooms {
+    #[serde(default)]
+    join: HashMap<String, MxJoinedRoom>,
+    #[serde(default)]
+    invite: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MxJoinedRoom {
+    #[serde(default)]
+    timeline: MxTimeline,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MxTimeline {
+    #[serde(default)]
+    events: Vec<MxEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MxEvent {
+    r#type: String,
+    #[serde(default)]
+    event_id: String,
+    #[serde(default)]
+    sender: String,
+    #[serde(default)]
+    origin_server_ts: i64,
+    #[serde(default)]
+    content: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct MxMessageContent {
+    #[serde(default)]
+    msgtype: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default, rename = "m.relates_to")]
+    relates_to: Option<MxRelation>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct MxRelation {
+    #[serde(default)]
+    rel_type: Option<String>,
+    #[serde(default)]
+    event_id: Option<String>,
+    #[serde(default)]
+    key: Option<String>,
+    #[serde(default, rename = "m.in_reply_to")]
+    in_reply_to: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel() -> MatrixChannel {
+        let channel = MatrixChannel::new(
+            "https://matrix.example.org/".to_string(),
+            "syt_token".to_string(),
+        );
+        if let Ok(mut guard) = channel.user_id.lock() {
+            *guard = Some("@stakpak:example.org".to_string());
+        }
+        channel
+    }
+
+    fn sync(events: serde_json::Value) -> MxSync {
+        let raw = serde_json::json!({
+            "next_batch": "s72595_4483_1934",
+            "rooms": {
+                "join": {
+                    "!ops:example.org": {"timeline": {"events": events}}
+                },
+                "invite": {"!new:example.org": {}}
+            }
+        });
+        serde_json::from_value(raw).expect("sync payload")
+    }
+
+    #[test]
+    fn api_urls_escape_room_ids() {
+        let url = channel()
+            .api_url(&["rooms", "!ops:example.org", "send", "m.room.message", "t1"])
+            .expect("url");
+        assert_eq!(
+            url.as_str(),
+            "https://matrix.example.org/_matrix/client/v3/rooms/!ops:example.org/send/m.room.message/t1"
+        );
+        let url = MatrixChannel::new("https://example.org/matrix".to_string(), String::new())
+            .api_url(&["rooms", "#alias/x", "join"])
+            .expect("url");
+        assert_eq!(
+            url.as_str(),
+            "https://example.org/matrix/_matrix/client/v3/rooms/%23alias%2Fx/join"
+        );
+    }
+
+    #[test]
+    fn sync_maps_room_messages_and_threads() {
+        let sync = sync(serde_json::json!([
+            {
+                "type": "m.room.message",
+                "event_id": "$1",
+                "sender": "@dana:example.org",
+                "origin_server_ts": 1_710_000_000_000_i64,
+                "content": {"msgtype": "m.text", "body": "is the api up?"}
+            },
+            {
+                "type": "m.room.message",
+                "event_id": "$2",
+                "sender": "@dana:example.org",
+                "origin_server_ts": 1_710_000_001_000_i64,
+                "content": {
+                    "msgtype": "m.text",
+                    "body": "> <@stakpak:example.org> yes\n\nand the workers?",
+                    "m.relates_to": {
+                        "rel_type": "m.thread",
+                        "event_id": "$1",
+                        "m.in_reply_to": {"event_id": "$1"}
+                    }
+                }
+            },
+            {
+                "type": "m.room.message",
+                "event_id": "$3",
+                "sender": "@stakpak:example.org",
+                "content": {"msgtype": "m.text", "body": "own echo"}
+            },
+            {
+                "type": "m.room.message",
+                "event_id": "$4",
+                "sender": "@dana:example.org",
+                "content": {
+                    "msgtype": "m.text",
+                    "body": "* is the api up??",
+                    "m.relates_to": {"rel_type": "m.replace", "event_id": "$1"}
+                }
+            },
+            {
+                "type": "m.room.encrypted",
+                "event_id": "$5",
+                "sender": "@dana:example.org",
+                "content": {"algorithm": "m.megolm.v1.aes-sha2"}
+            }
+        ]));
+
+        let channel = channel();
+        let messages = channel.map_sync(&sync);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].text, "is the api up?");
+        assert_eq!(messages[0].peer_id.0, "@dana:example.org");
+        assert_eq!(
+            messages[0].chat_type,
+            ChatType::Group {
+                id: "!ops:example.org".to_string()
+            }
+        );
+        assert_eq!(messages[0].timestamp.timestamp(), 1_710_000_000);
+        assert_eq!(messages[1].text, "and the workers?");
+        assert_eq!(
+            messages[1].chat_type,
+            ChatType::Thread {
+                group_id: "!ops:example.org".to_string(),
+                thread_id: "$1".to_string()
+            }
+        );
+        assert_eq!(messages[1].metadata["thread_id"], "$1");
+
+        let other_rooms = channel.with_rooms(vec!["!other:example.org".to_string()]);
+        assert!(other_rooms.map_sync(&sync).is_empty());
+    }
+
+    #[test]
+    fn approval_reactions_map_to_approval_responses() {
+        let channel = channel();
+        if let Ok(mut prompts) = channel.approval_prompts.lock() {
+            prompts.insert(
+                "$prompt".to_string(),
+                vec![
+                    (APPROVE_REACTION.to_string(), "a:a3f0c92d:allow".to_string()),
+                    (DENY_REACTION.to_string(), "a:a3f0c92d:deny".to_string()),
+                ],
+            );
+        }
+
+        let sync = sync(serde_json::json!([
+            {
+                "type": "m.reaction",
+                "event_id": "$r1",
+                "sender": "@dana:example.org",
+                "content": {"m.relates_to": {"rel_type": "m.annotation", "event_id": "$prompt", "key": "❌"}}
+            },
+            {
+                "type": "m.reaction",
+                "event_id": "$r2",
+                "sender": "@dana:example.org",
+                "content": {"m.relates_to": {"rel_type": "m.annotation", "event_id": "$other", "key": "✅"}}
+            }
+        ]));
+
+        let messages = channel.map_sync(&sync);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].metadata["type"], "approval_response");
+        assert_eq!(messages[0].metadata["approval_id"], "a3f0c92d");
+        assert_eq!(messages[0].metadata["decision"], "deny");
+    }
+
+    #[test]
+    fn message_content_carries_html() {
+        let content = message_content("**done**");
+        assert_eq!(content["msgtype"], "m.text");
+        assert_eq!(content["body"], "**done**");
+        assert_eq!(content["format"], "org.matrix.custom.html");
+        assert_eq!(content["formatted_body"], "<p><strong>done</strong></p>");
+
+        assert_eq!(strip_reply_fallback("plain"), "plain");
+        assert!(same_reaction("✅\u{fe0f}", "✅"));
+    }
+}
@@ -1,6 +1,10 @@
 pub mod discord;
+pub mod matrix;
+pub mod redacting;
+pub mod rocketchat;
 pub mod slack;
 pub mod telegram;
+pub mod whatsapp;
 
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
@@ -35,12 +39,16 @@ pub struct ChannelTestResult {
     pub details: String,
 }
 
+/// A chat platform the gateway listens on and replies through.
 #[async_trait]
 pub trait Channel: Send + Sync + 'static {
+    /// Name the channel is registered and routed under, e.g. `slack`.
     fn id(&self) -> &ChannelId;
 
     fn display_name(&self) -> &str;
 
+    /// Listen for messages and forward them to `inbound_tx` until `cancel`
+    /// fires. Returning early stops only this channel.
     async fn start(
         &self,
         inbound_tx: mpsc::Sender<InboundMessage>,
@@ -72,6 +80,7 @@ pub trait Channel: Send + Sync + 'static {
         ))
     }
 
+    /// Check credentials and connectivity without sending anything.
     async fn test(&self) -> Result<ChannelTestResult>;
 }
 
@@ -0,0 +1,977 @@
+//! Rocket.Chat channel over the REST and realtime (DDP) APIs.
+//!
+//! Listens on the realtime websocket for messages in every room the bot user
+//! is in and replies through REST. Replies to room messages go to the
+//! message's thread, like Slack. Uploaded files are passed to the agent as
+//! links, and markdown images in replies are sent as message attachments.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, warn};
+
+use crate::{
+    channels::{
+        ApprovalButton, ButtonStyle, Channel, ChannelTestResult, DeliveryReceipt,
+        parse_approval_callback,
+    },
+    chunking::chunk_text,
+    types::{ChannelId, ChatType, InboundMessage, OutboundReply, PeerId},
+};
+
+/// Rocket.Chat's default `Message_MaxAllowedSize`.
+const ROCKETCHAT_TEXT_LIMIT: usize = 5_000;
+/// Message updates (reactions, thread counts) re-send the message; ids seen
+/// recently are skipped.
+const SEEN_MESSAGES: usize = 512;
+const LOGIN_ID: &str = "login";
+const SUBSCRIPTION_ID: &str = "my-messages";
+
+pub struct RocketChatChannel {
+    id: ChannelId,
+    server_url: String,
+    user_id: String,
+    auth_token: String,
+    client: reqwest::Client,
+    seen: Mutex<VecDeque<String>>,
+}
+
+impl RocketChatChannel {
+    pub fn new(server_url: String, user_id: String, auth_token: String) -> Self {
+        Self {
+            id: "rocketchat".into(),
+            server_url: server_url.trim_end_matches('/').to_string(),
+            user_id,
+            auth_token,
+            client: reqwest::Client::new(),
+            seen: Mutex::new(VecDeque::with_capacity(SEEN_MESSAGES)),
+        }
+    }
+
+    fn api_url(&self, method: &str) -> String {
+        format!("{}/api/v1/{method}", self.server_url)
+    }
+
+    fn websocket_url(&self) -> String {
+        let url = if let Some(rest) = self.server_url.strip_prefix("https://") {
+            format!("wss://{rest}")
+        } else if let Some(rest) = self.server_url.strip_prefix("http://") {
+            format!("ws://{rest}")
+        } else {
+            self.server_url.clone()
+        };
+        format!("{url}/websocket")
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, method: &str) -> Result<T> {
+        let response = self
+            .client
+            .get(self.api_url(method))
+            .header("X-User-Id", &self.user_id)
+            .header("X-Auth-Token", &self.auth_token)
+            .send()
+            .await
+            .with_context(|| format!("rocket.chat {method} request failed"))?;
+        decode_response(response, method).await
+    }
+
+    async fn post<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        body: &serde_json::Value,
+    ) -> Result<T> {
+        let response = self
+            .client
+            .post(self.api_url(method))
+            .header("X-User-Id", &self.user_id)
+            .header("X-Auth-Token", &self.auth_token)
+            .json(body)
+            .send()
+            .await
+            .with_context(|| format!("rocket.chat {method} request failed"))?;
+        decode_response(response, method).await
+    }
+
+    async fn send_message(&self, message: serde_json::Value) -> Result<String> {
+        let sent: RcSendResponse = self
+            .post(
+                "chat.sendMessage",
+                &serde_json::json!({ "message": message }),
+            )
+            .await?;
+        Ok(sent.message.id)
+    }
+
+    fn extract_target(reply: &OutboundReply) -> Result<(String, Option<String>)> {
+        let room_id = reply
+            .metadata
+            .get("room_id")
+            .and_then(|value| value.as_str())
+            .map(ToOwned::to_owned)
+            .or_else(|| match &reply.chat_type {
+                ChatType::Group { id } => Some(id.clone()),
+                ChatType::Thread { group_id, .. } => Some(group_id.clone()),
+                ChatType::Direct => None,
+            })
+            .ok_or_else(|| anyhow!("rocket.chat reply missing room_id in metadata/chat_type"))?;
+
+        // Room messages are answered in their thread; direct messages inline.
+        let thread_id = reply
+            .metadata
+            .get("thread_id")
+            .and_then(|value| value.as_str())
+            .map(ToOwned::to_owned)
+            .or_else(|| match reply.chat_type {
+                ChatType::Direct => None,
+                _ => reply
+                    .metadata
+                    .get("message_id")
+                    .and_then(|value| value.as_str())
+                    .map(ToOwned::to_owned),
+            });
+
+        Ok((room_id, thread_id))
+    }
+
+    /// Record a message id, returning false when it was already seen.
+    fn first_sighting(&self, message_id: &str) -> bool {
+        let Ok(mut seen) = self.seen.lock() else {
+            return true;
+        };
+        if seen.iter().any(|seen| seen == message_id) {
+            return false;
+        }
+        if seen.len() == SEEN_MESSAGES {
+            seen.pop_front();
+        }
+        seen.push_back(message_id.to_string());
+        true
+    }
+
+    fn map_message(&self, message: &RcMessage, room: &RcRoomInfo) -> Option<InboundMessage> {
+        // System messages (joins, topic changes) carry a type; edits carry
+        // `editedAt` and were handled when first sent.
+        if message.t.is_some() || message.edited_at.is_some() || message.user.id == self.user_id {
+            return None;
+        }
+        if !self.first_sighting(&message.id) {
+            return None;
+        }
+
+        let timestamp = message
+            .ts
+            .as_ref()
+            .and_then(parse_timestamp)
+            .unwrap_or_else(Utc::now);
+
+        if let Some((approval_id, decision)) = parse_approval_callback(message.msg.trim()) {
+            return Some(InboundMessage {
+                channel: self.id.clone(),
+                peer_id: PeerId(message.user.id.clone()),
+                chat_type: self.chat_type(message, room),
+                text: String::new(),
+                media: Vec::new(),
+                metadata: serde_json::json!({
+                    "type": "approval_response",
+                    "approval_id": approval_id,
+                    "decision": decision,
+                    "room_id": message.rid,
+                    "thread_id": message.tmid,
+                }),
+                timestamp,
+                control: None,
+            });
+        }
+
+        let files = message
+            .attachments
+            .iter()
+            .filter_map(|attachment| {
+                let link = attachment.title_link.as_deref()?;
+                Some(serde_json::json!({
+                    "name": attachment.title.clone().unwrap_or_default(),
+                    "type": attachment.image_type.clone().or_else(|| attachment.r#type.clone()),
+                    "url": self.absolute_url(link),
+                }))
+            })
+            .collect::<Vec<_>>();
+
+        let mut text = message.msg.clone();
+        if text.trim().is_empty()
+            && let Some(description) = message
+                .attachments
+                .iter()
+                .find_map(|attachment| attachment.description.clone())
+        {
+            text = description;
+        }
+        for file in &files {
+            text.push_str(&format!(
+                "\n[{}]({})",
+                file["name"].as_str().unwrap_or("attachment"),
+                file["url"].as_str().unwrap_or_default()
+            ));
+        }
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            return None;
+        }
+
+        Some(InboundMessage {
+            channel: self.id.clone(),
+            peer_id: PeerId(message.user.id.clone()),
+            chat_type: self.chat_type(message, room),
+            text,
+            media: Vec::new(),
+            metadata: serde_json::json!({
+                "room_id": message.rid,
+                "message_id": message.id,
+                "thread_id": message.tmid,
+                "room_type": room.room_type,
+                "username": message.user.username,
+                "files": files,
+            }),
+            timestamp,
+            control: None,
+        })
+    }
+
+    fn chat_type(&self, message: &RcMessage, room: &RcRoomInfo) -> ChatType {
+        match (&message.tmid, room.room_type.as_deref()) {
+            (Some(thread_id), _) => ChatType::Thread {
+                group_id: message.rid.clone(),
+                thread_id: thread_id.clone(),
+            },
+            (None, Some("d")) => ChatType::Direct,
+            (None, _) => ChatType::Group {
+                id: message.rid.clone(),
+            },
+        }
+    }
+
+    fn absolute_url(&self, link: &str) -> String {
+        if link.starts_with("http://") || link.starts_with("https://") {
+            link.to_string()
+        } else {
+            format!("{}/{}", self.server_url, link.trim_start_matches('/'))
+        }
+    }
+}
+
+#[async_trait]
+impl Channel for RocketChatChannel {
+    fn id(&self) -> &ChannelId {
+        &self.id
+    }
+
+    fn display_name(&self) -> &str {
+        "Rocket.Chat"
+    }
+
+    async fn start(
+        &self,
+        inbound_tx: mpsc::Sender<InboundMessage>,
+        cancel: CancellationToken,
+    ) -> Result<()> {
+        let mut reconnect_backoff_secs = 1_u64;
+
+        loop {
+            if cancel.is_cancelled() {
+                break;
+            }
+
+            let ws = match tokio_tungstenite::connect_async(self.websocket_url()).await {
+                Ok((stream, _response)) => stream,
+                Err(error) => {
+                    error!(error = %error, "rocket.chat websocket connect failed");
+                    if !wait_to_reconnect(&cancel, reconnect_backoff_secs).await {
+                        break;
+                    }
+                    reconnect_backoff_secs = (reconnect_backoff_secs * 2).min(30);
+                    continue;
+                }
+            };
+
+            reconnect_backoff_secs = 1;
+            let (mut writer, mut reader) = ws.split();
+
+            let handshake = [
+                serde_json::json!({"msg": "connect", "version": "1", "support": ["1"]}),
+                serde_json::json!({
+                    "msg": "method",
+                    "method": "login",
+                    "id": LOGIN_ID,
+                    "params": [{"resume": self.auth_token}],
+                }),
+            ];
+            let mut handshake_sent = true;
+            for frame in handshake {
+                if let Err(error) = writer.send(WsMessage::Text(frame.to_string())).await {
+                    warn!(error = %error, "rocket.chat handshake write failed");
+                    handshake_sent = false;
+                    break;
+                }
+            }
+            if !handshake_sent {
+                if !wait_to_reconnect(&cancel, reconnect_backoff_secs).await {
+                    break;
+                }
+                reconnect_backoff_secs = (reconnect_backoff_secs * 2).min(30);
+                continue;
+            }
+
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => {
+                        let _ = writer.send(WsMessage::Close(None)).await;
+                        return Ok(());
+                    }
+                    next = reader.next() => {
+                        let Some(next) = next else {
+                            break;
+                        };
+
+                        let text = match next {
+                            Ok(WsMessage::Text(text)) => text,
+                            Ok(WsMessage::Ping(payload)) => {
+                                if writer.send(WsMessage::Pong(payload)).await.is_err() {
+                                    break;
+                                }
+                                continue;
+                            }
+                            Ok(WsMessage::Close(_)) => break,
+                            Ok(_) => continue,
+                            Err(error) => {
+                                warn!(error = %error, "rocket.chat websocket read failed");
+                                break;
+                            }
+                        };
+
+                        let reply = match parse_frame(&text) {
+                            DdpFrame::Ping => Some(serde_json::json!({"msg": "pong"})),
+                            DdpFrame::LoggedIn => Some(serde_json::json!({
+                                "msg": "sub",
+                                "id": SUBSCRIPTION_ID,
+                                "name": "stream-room-messages",
+                                "params": ["__my_messages__", {"useCollection": false, "args": []}],
+                            })),
+                            DdpFrame::LoginFailed(reason) => {
+                                return Err(anyhow!("rocket.chat login failed: {reason}"));
+                            }
+                            DdpFrame::SubscriptionFailed(reason) => {
+                                warn!(reason = %reason, "rocket.chat message subscription failed");
+                                break;
+                            }
+                            DdpFrame::Message(message, room) => {
+                                if let Some(inbound) = self.map_message(&message, &room)
+                                    && inbound_tx.send(inbound).await.is_err()
+                                {
+                                    return Ok(());
+                                }
+                                None
+                            }
+                            DdpFrame::Other => None,
+                        };
+
+                        if let Some(reply) = reply
+                            && writer.send(WsMessage::Text(reply.to_string())).await.is_err()
+                        {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            if !wait_to_reconnect(&cancel, reconnect_backoff_secs).await {
+                break;
+            }
+            reconnect_backoff_secs = (reconnect_backoff_secs * 2).min(30);
+        }
+
+        Ok(())
+    }
+
+    async fn send(&self, reply: OutboundReply) -> Result<()> {
+        self.send_with_receipt(reply).await.map(|_| ())
+    }
+
+    async fn send_with_receipt(&self, reply: OutboundReply) -> Result<DeliveryReceipt> {
+        let (room_id, thread_id) = Self::extract_target(&reply)?;
+        let (text, attachments) = extract_attachments(&reply.text);
+
+        let mut chunks = chunk_text(&text, ROCKETCHAT_TEXT_LIMIT);
+        if chunks.is_empty() {
+            chunks.push(String::new());
+        }
+        let last = chunks.len() - 1;
+
+        let mut first_message_id: Option<String> = None;
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            let mut message = serde_json::json!({ "rid": room_id, "msg": chunk });
+            if let Some(thread_id) = &thread_id {
+                message["tmid"] = serde_json::json!(thread_id);
+            }
+            if index == last && !attachments.is_empty() {
+                message["attachments"] = serde_json::json!(attachments);
+            }
+            let message_id = self.send_message(message).await?;
+            first_message_id.get_or_insert(message_id);
+        }
+
+        Ok(DeliveryReceipt {
+            message_id: first_message_id.map(|message_id| format!("{room_id}|{message_id}")),
+            thread_id,
+        })
+    }
+
+    async fn send_with_buttons(
+        &self,
+        reply: OutboundReply,
+        buttons: Vec<ApprovalButton>,
+    ) -> Result<String> {
+        let (room_id, thread_id) = Self::extract_target(&reply)?;
+
+        // Clicking a button posts its `msg` as the user, which comes back
+        // through the realtime stream as the approval response.
+        let actions = buttons
+            .iter()
+            .map(|button| {
+                let label = match button.style {
+                    ButtonStyle::Success => format!("✅ {}", button.label),
+                    ButtonStyle::Danger => format!("❌ {}", button.label),
+                };
+                serde_json::json!({
+                    "type": "button",
+                    "text": label,
+                    "msg": button.callback_data,
+                    "msg_in_chat_window": true,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut message = serde_json::json!({
+            "rid": room_id,
+            "msg": reply.text,
+            "attachments": [{"button_alignment": "horizontal", "actions": actions}],
+        });
+        if let Some(thread_id) = &thread_id {
+            message["tmid"] = serde_json::json!(thread_id);
+        }
+
+        let message_id = self.send_message(message).await?;
+        Ok(format!("{room_id}|{message_id}"))
+    }
+
+    async fn edit_message(&self, message_id: &str, new_text: &str) -> Result<()> {
+        let Some((room_id, message_id)) = message_id.split_once('|') else {
+            return Ok(());
+        };
+
+        self.post::<serde_json::Value>(
+            "chat.update",
+            &serde_json::json!({
+                "roomId": room_id,
+                "msgId": message_id,
+                "text": new_text,
+            }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn test(&self) -> Result<ChannelTestResult> {
+        let me: RcMe = self.get("me").await?;
+
+        Ok(ChannelTestResult {
+            channel: self.id.0.clone(),
+            identity: format!("@{}", me.username),
+            details: format!("server={} user_id={}", self.server_url, me.id),
+        })
+    }
+}
+
+/// Wait `secs` before the next connection attempt. Returns false if the
+/// channel was stopped in the meantime.
+async fn wait_to_reconnect(cancel: &CancellationToken, secs: u64) -> bool {
+    tokio::select! {
+        _ = cancel.cancelled() => false,
+        _ = tokio::time::sleep(std::time::Duration::from_secs(secs)) => true,
+    }
+}
+
+async fn decode_response<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+    method: &str,
+) -> Result<T> {
+    let status = response.status();
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .with_context(|| format!("rocket.chat {method} decode failed"))?;
+
+    if !status.is_success() || body.get("success") == Some(&serde_json::Value::Bool(false)) {
+        let reason = body
+            .get("error")
+            .or_else(|| body.get("message"))
+            .and_then(|value| value.as_str())
+            .unwrap_or("unknown error");
+        return Err(anyhow!(
+            "rocket.chat {method} error {}: {reason}",
+            status.as_u16()
+        ));
+    }
+
+    serde_json::from_value(body).with_context(|| format!("rocket.chat {method} decode failed"))
+}
+
+#[derive(Debug)]
+enum DdpFrame {
+    Ping,
+    LoggedIn,
+    LoginFailed(String),
+    SubscriptionFailed(String),
+    Message(Box<RcMessage>, RcRoomInfo),
+    Other,
+}
+
+fn parse_frame(text: &str) -> DdpFrame {
+    let Ok(frame) = serde_json::from_str::<serde_json::Value>(text) else {
+        return DdpFrame::Other;
+    };
+    let error_reason = |frame: &serde_json::Value| {
+        frame
+            .get("error")
+            .and_then(|error| error.get("reason").or_else(|| error.get("message")))
+            .and_then(|reason| reason.as_str())
+            .unwrap_or("unknown error")
+            .to_string()
+    };
+
+    match frame.get("msg").and_then(|msg| msg.as_str()) {
+        Some("ping") => DdpFrame::Ping,
+        Some("result") if frame.get("id").and_then(|id| id.as_str()) == Some(LOGIN_ID) => {
+            if frame.get("error").is_some() {
+                DdpFrame::LoginFailed(error_reason(&frame))
+            } else {
+                DdpFrame::LoggedIn
+            }
+        }
+        Some("nosub") if frame.get("id").and_then(|id| id.as_str()) == Some(SUBSCRIPTION_ID) => {
+            DdpFrame::SubscriptionFailed(error_reason(&frame))
+        }
+        Some("changed")
+            if frame.get("collection").and_then(|c| c.as_str()) == Some("stream-room-messages") =>
+        {
+            let args = frame
+                .pointer("/fields/args")
+                .and_then(|args| args.as_array())
+                .cloned()
+                .unwrap_or_default();
+            let mut args = args.into_iter();
+            let Some(message) = args
+                .next()
+                .and_then(|message| serde_json::from_value::<RcMessage>(message).ok())
+            else {
+                return DdpFrame::Other;
+            };
+            let room = args
+                .next()
+                .and_then(|room| serde_json::from_value::<RcRoomInfo>(room).ok())
+                .unwrap_or_default();
+            DdpFrame::Message(Box::new(message), room)
+        }
+        _ => DdpFrame::Other,
+    }
+}
+
+/// Realtime messages carry `{"$date": millis}`, REST ones ISO 8601 strings.
+fn parse_timestamp(ts: &serde_json::Value) -> Option<DateTime<Utc>> {
+    match ts {
+        serde_json::Value::Object(date) => date
+            .get("$date")
+            .and_then(|millis| millis.as_i64())
+            .and_then(DateTime::from_timestamp_millis),
+        serde_json::Value::String(text) => DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|ts| ts.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
+/// Split markdown images with http(s) links out of a reply as message
+/// attachments; the rest of the text is sent as is.
+fn extract_attachments(text: &str) -> (String, Vec<serde_json::Value>) {
+    let mut attachments = Vec::new();
+    let mut removed = Vec::new();
+    let mut current: Option<(std::ops::Range<usize>, String, String)> = None;
+
+    for (event, range) in Parser::new(text).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Image { dest_url, .. })
+                if dest_url.starts_with("https://") || dest_url.starts_with("http://") =>
+            {
+                current = Some((range, dest_url.to_string(), String::new()));
+            }
+            Event::Text(alt) | Event::Code(alt) => {
+                if let Some((_, _, title)) = current.as_mut() {
+                    title.push_str(&alt);
+                }
+            }
+            Event::End(TagEnd::Image) => {
+                if let Some((range, link, title)) = current.take() {
+                    let title = title.trim();
+                    attachments.push(if is_image_link(&link) {
+                        serde_json::json!({ "title": title, "image_url": link })
+                    } else {
+                        serde_json::json!({
+                            "title": if title.is_empty() { link.as_str() } else { title },
+                            "title_link": link,
+                            "title_link_download": true,
+                        })
+                    });
+                    removed.push(range);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if removed.is_empty() {
+        return (text.to_string(), attachments);
+    }
+
+    let mut remaining = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for range in removed {
+        remaining.push_str(text.get(cursor..range.start).unwrap_or_default());
+        cursor = range.end;
+    }
+    remaining.push_str(text.get(cursor..).unwrap_or_default());
+    (remaining.trim().to_string(), attachments)
+}
+
+fn is_image_link(link: &str) -> bool {
+    let path = link.split(['?', '#']).next().unwrap_or(link);
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+    matches!(
+        extension.as_str(),
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg"
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct RcMe {
+    #[serde(rename = "_id")]
+    id: String,
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RcSendResponse {
+    message: RcSentMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct RcSentMessage {
+    #[serde(rename = "_id")]
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RcMessage {
+    #[serde(rename = "_id")]
+    id: String,
+    rid: String,
+    #[serde(default)]
+    msg: String,
+    #[serde(rename = "u")]
+    user: RcUser,
+    #[serde(default)]
+    tmid: Option<String>,
+    #[serde(default)]
+    t: Option<String>,
+    #[serde(default, rename = "editedAt")]
+    edited_at: Option<serde_json::Value>,
+    #[serde(default)]
+    ts: Option<serde_json::Value>,
+    #[serde(default)]
+    attachments: Vec<RcAttachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RcUser {
+    #[serde(rename = "_id")]
+    id: String,
+    #[serde(default)]
+    username: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RcAttachment {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    title_link: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    r#type: Option<String>,
+    #[serde(default)]
+    image_type: Option<String>,
+}
+
+/// Second argument of `__my_messages__` events.
+#[derive(Debug, Default, Deserialize)]
+struct RcRoomInfo {
+    #[serde(default, rename = "roomType")]
+    room_type: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel() -> RocketChatChannel {
+        RocketChatChannel::new(
+            "https://chat.example.org/".to_string(),
+            "botuserid".to_string(),
+            "pat-token".to_string(),
+        )
+    }
+
+    fn changed(message: serde_json::Value, room_type: &str) -> String {
+        serde_json::json!({
+            "msg": "changed",
+            "collection": "stream-room-messages",
+            "id": "id",
+            "fields": {
+                "eventName": "__my_messages__",
+                "args": [message, {"roomParticipant": true, "roomType": room_type}],
+            }
+        })
+        .to_string()
+    }
+
+    fn inbound(channel: &RocketChatChannel, frame: &str) -> Option<InboundMessage> {
+        match parse_frame(frame) {
+            DdpFrame::Message(message, room) => channel.map_message(&message, &room),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn stopping_interrupts_the_reconnect_backoff() {
+        // Nothing listens on port 1, so every connect fails and backs off.
+        let channel = RocketChatChannel::new(
+            "http://127.0.0.1:1".to_string(),
+            "botuserid".to_string(),
+            "pat-token".to_string(),
+        );
+        let (inbound_tx, _inbound_rx) = mpsc::channel(1);
+        let cancel = CancellationToken::new();
+        let stop = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            stop.cancel();
+        });
+
+        let stopped = tokio::time::timeout(
+            std::time::Duration::from_millis(500),
+            channel.start(inbound_tx, cancel),
+        )
+        .await;
+        assert!(matches!(stopped, Ok(Ok(()))));
+    }
+
+    #[test]
+    fn parses_ddp_control_frames() {
+        assert!(matches!(parse_frame(r#"{"msg":"ping"}"#), DdpFrame::Ping));
+        assert!(matches!(
+            parse_frame(r#"{"msg":"result","id":"login","result":{"id":"botuserid"}}"#),
+            DdpFrame::LoggedIn
+        ));
+        assert!(matches!(
+            parse_frame(r#"{"msg":"result","id":"login","error":{"reason":"You've been logged out by the server"}}"#),
+            DdpFrame::LoginFailed(reason) if reason.contains("logged out")
+        ));
+        assert!(matches!(
+            parse_frame(r#"{"msg":"nosub","id":"my-messages","error":{"reason":"not-allowed"}}"#),
+            DdpFrame::SubscriptionFailed(_)
+        ));
+        assert!(matches!(
+            parse_frame(r#"{"msg":"connected","session":"s1"}"#),
+            DdpFrame::Other
+        ));
+        assert_eq!(
+            channel().websocket_url(),
+            "wss://chat.example.org/websocket"
+        );
+    }
+
+    #[test]
+    fn maps_room_thread_and_direct_messages() {
+        let channel = channel();
+
+        let room = inbound(
+            &channel,
+            &changed(
+                serde_json::json!({
+                    "_id": "m1", "rid": "GENERAL", "msg": "is the api up?",
+                    "u": {"_id": "u1", "username": "dana"},
+                    "ts": {"$date": 1_710_000_000_000_i64}
+                }),
+                "c",
+            ),
+        )
+        .expect("room message");
+        assert_eq!(
+            room.chat_type,
+            ChatType::Group {
+                id: "GENERAL".to_string()
+            }
+        );
+        assert_eq!(room.timestamp.timestamp(), 1_710_000_000);
+        assert_eq!(room.metadata["username"], "dana");
+
+        let thread = inbound(
+            &channel,
+            &changed(
+                serde_json::json!({
+                    "_id": "m2", "rid": "GENERAL", "msg": "and the workers?", "tmid": "m1",
+                    "u": {"_id": "u1", "username": "dana"}
+                }),
+                "c",
+            ),
+        )
+        .expect("thread message");
+        assert_eq!(
+            thread.chat_type,
+            ChatType::Thread {
+                group_id: "GENERAL".to_string(),
+                thread_id: "m1".to_string()
+            }
+        );
+
+        let direct = inbound(
+            &channel,
+            &changed(
+                serde_json::json!({
+                    "_id": "m3", "rid": "botuseridu1", "msg": "hi",
+                    "u": {"_id": "u1", "username": "dana"}
+                }),
+                "d",
+            ),
+        )
+        .expect("direct message");
+        assert_eq!(direct.chat_type, ChatType::Direct);
+
+        // Re-sent updates, edits, system messages and the bot's own messages
+        // are ignored.
+        let ignored = [
+            serde_json::json!({"_id": "m1", "rid": "GENERAL", "msg": "is the api up?", "u": {"_id": "u1"}}),
+            serde_json::json!({"_id": "m4", "rid": "GENERAL", "msg": "x", "editedAt": {"$date": 1}, "u": {"_id": "u1"}}),
+            serde_json::json!({"_id": "m5", "rid": "GENERAL", "msg": "dana", "t": "uj", "u": {"_id": "u1"}}),
+            serde_json::json!({"_id": "m6", "rid": "GENERAL", "msg": "done", "u": {"_id": "botuserid"}}),
+        ];
+        for message in ignored {
+            assert!(inbound(&channel, &changed(message, "c")).is_none());
+        }
+    }
+
+    #[test]
+    fn maps_uploads_and_approval_clicks() {
+        let channel = channel();
+
+        let upload = inbound(
+            &channel,
+            &changed(
+                serde_json::json!({
+                    "_id": "m1", "rid": "GENERAL", "msg": "",
+                    "u": {"_id": "u1", "username": "dana"},
+                    "attachments": [{
+                        "title": "crash.log",
+                        "title_link": "/file-upload/f1/crash.log",
+                        "description": "why did this crash?",
+                        "type": "file"
+                    }]
+                }),
+                "c",
+            ),
+        )
+        .expect("upload");
+        assert_eq!(
+            upload.text,
+            "why did this crash?\n[crash.log](https://chat.example.org/file-upload/f1/crash.log)"
+        );
+        assert_eq!(upload.metadata["files"][0]["name"], "crash.log");
+
+        let approval = inbound(
+            &channel,
+            &changed(
+                serde_json::json!({
+                    "_id": "m2", "rid": "GENERAL", "msg": "a:a3f0c92d:allow", "tmid": "m1",
+                    "u": {"_id": "u1", "username": "dana"}
+                }),
+                "c",
+            ),
+        )
+        .expect("approval");
+        assert_eq!(approval.metadata["type"], "approval_response");
+        assert_eq!(approval.metadata["approval_id"], "a3f0c92d");
+        assert_eq!(approval.metadata["decision"], "allow");
+    }
+
+    #[test]
+    fn replies_thread_room_messages_and_attach_images() {
+        let reply = |chat_type: ChatType, metadata: serde_json::Value| OutboundReply {
+            channel: "rocketchat".into(),
+            peer_id: "u1".into(),
+            chat_type,
+            text: String::new(),
+            metadata,
+        };
+
+        let room = reply(
+            ChatType::Group {
+                id: "GENERAL".to_string(),
+            },
+            serde_json::json!({"room_id": "GENERAL", "message_id": "m1"}),
+        );
+        assert_eq!(
+            RocketChatChannel::extract_target(&room).expect("target"),
+            ("GENERAL".to_string(), Some("m1".to_string()))
+        );
+        let direct = reply(
+            ChatType::Direct,
+            serde_json::json!({"room_id": "botuseridu1", "message_id": "m3"}),
+        );
+        assert_eq!(
+            RocketChatChannel::extract_target(&direct).expect("target"),
+            ("botuseridu1".to_string(), None)
+        );
+
+        let (text, attachments) = extract_attachments(
+            "CPU is back to normal.\n\n![CPU last 24h](https://grafana.example.org/cpu.png)\n\n![report](https://x.io/r.pdf)",
+        );
+        assert_eq!(text, "CPU is back to normal.");
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(
+            attachments[0]["image_url"],
+            "https://grafana.example.org/cpu.png"
+        );
+        assert_eq!(attachments[0]["title"], "CPU last 24h");
+        assert_eq!(attachments[1]["title_link"], "https://x.io/r.pdf");
+    }
+}
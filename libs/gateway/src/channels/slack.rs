@@ -1,4 +1,4 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Mutex;
 
 use anyhow::{Context, Result, anyhow};
@@ -16,11 +16,15 @@ use crate::{
         ApprovalButton, ButtonStyle, Channel, ChannelTestResult, DeliveryReceipt,
         parse_approval_callback,
     },
-    slack_blocks::markdown_to_slack_messages,
-    types::{ChannelId, ChatType, InboundMessage, OutboundReply, PeerId},
+    slack_blocks::{
+        SlackBlockFormat, SlackMentions, SlackRenderOptions, SlackTableRendering,
+        markdown_to_slack_messages_with_options,
+    },
+    types::{ChannelId, ChatType, InboundControl, InboundMessage, OutboundReply, PeerId},
 };
 
 const RECEIVED_REACTION: &str = "eyes";
+const DEFAULT_CANCEL_REACTION: &str = "octagonal_sign";
 
 pub struct SlackChannel {
     id: ChannelId,
@@ -30,6 +34,9 @@ pub struct SlackChannel {
     bot_user_id: Mutex<Option<String>>,
     dedup: Mutex<DedupBuffer>,
     active_threads: Mutex<HashSet<(String, String)>>,
+    sent_messages: Mutex<SentMessages>,
+    render_options: SlackRenderOptions,
+    cancel_reaction: String,
 }
 
 impl SlackChannel {
@@ -42,9 +49,34 @@ impl SlackChannel {
             bot_user_id: Mutex::new(None),
             dedup: Mutex::new(DedupBuffer::new(2048)),
             active_threads: Mutex::new(HashSet::new()),
+            sent_messages: Mutex::new(SentMessages::new(2048)),
+            render_options: SlackRenderOptions::default(),
+            cancel_reaction: DEFAULT_CANCEL_REACTION.to_string(),
         }
     }
 
+    pub fn with_table_rendering(mut self, table_rendering: SlackTableRendering) -> Self {
+        self.render_options.table_rendering = table_rendering;
+        self
+    }
+
+    pub fn with_block_format(mut self, block_format: SlackBlockFormat) -> Self {
+        self.render_options.block_format = block_format;
+        self
+    }
+
+    pub fn with_mentions(mut self, mentions: SlackMentions) -> Self {
+        self.render_options.mentions = mentions;
+        self
+    }
+
+    /// Reaction name (e.g. `octagonal_sign`) that cancels the active run when
+    /// added to one of the bot's messages; empty turns it off.
+    pub fn with_cancel_reaction(mut self, reaction: String) -> Self {
+        self.cancel_reaction = reaction.trim().trim_matches(':').to_string();
+        self
+    }
+
     async fn auth_test(&self) -> Result<AuthTestResponse> {
         let response = self
             .http
@@ -345,6 +377,14 @@ impl SlackChannel {
                 }
 
                 let event = event_payload.event;
+                if event.event_type == "reaction_added" {
+                    if let Some(inbound) = self.cancel_reaction_inbound(&event)
+                        && inbound_tx.send(inbound).await.is_err()
+                    {
+                        return Ok(HandleAction::Stop);
+                    }
+                    return Ok(HandleAction::Continue);
+                }
                 if event.event_type != "message" {
                     return Ok(HandleAction::Continue);
                 }
@@ -442,6 +482,7 @@ impl SlackChannel {
                         "user_id": user,
                     }),
                     timestamp: parse_slack_ts_to_datetime(event.ts.as_deref()),
+                    control: None,
                 };
 
                 if inbound_tx.send(inbound).await.is_err() {
@@ -513,6 +554,7 @@ impl SlackChannel {
                             "thread_ts": thread_ts.clone(),
                         }),
                         timestamp: Utc::now(),
+                        control: None,
                     };
 
                     if inbound_tx.send(inbound).await.is_err() {
@@ -526,6 +568,75 @@ impl SlackChannel {
         }
     }
 
+    /// A cancel request for a cancel reaction on one of the bot's messages.
+    /// Reactions only carry the message's channel and ts, so the thread comes
+    /// from the messages this channel sent; DMs need no thread.
+    fn cancel_reaction_inbound(&self, event: &SlackEvent) -> Option<InboundMessage> {
+        let reaction = event.reaction.as_deref()?;
+        // Skin tone variants arrive as `name::skin-tone-2`.
+        let reaction = reaction.split("::").next().unwrap_or(reaction);
+        if self.cancel_reaction.is_empty() || reaction != self.cancel_reaction {
+            return None;
+        }
+
+        let own_bot_id = self
+            .bot_user_id
+            .lock()
+            .ok()
+            .and_then(|guard| guard.clone())?;
+        let user = event.user.clone()?;
+        if user == own_bot_id || event.item_user.as_deref() != Some(own_bot_id.as_str()) {
+            return None;
+        }
+
+        let item = event.item.as_ref()?;
+        if item.item_type != "message" {
+            return None;
+        }
+        let channel = item.channel.clone()?;
+        let ts = item.ts.clone()?;
+
+        let sent = self
+            .sent_messages
+            .lock()
+            .ok()
+            .and_then(|guard| guard.get(&channel, &ts).cloned());
+        let (channel_type, thread_ts) = match sent {
+            Some(sent) => (sent.channel_type, sent.thread_ts),
+            None if channel.starts_with('D') => ("im".to_string(), None),
+            None => return None,
+        };
+
+        Some(InboundMessage {
+            channel: self.id.clone(),
+            peer_id: PeerId(user.clone()),
+            chat_type: map_chat_type(&channel, &channel_type, thread_ts.as_deref()),
+            text: String::new(),
+            media: Vec::new(),
+            metadata: serde_json::json!({
+                "channel": channel,
+                "ts": ts,
+                "thread_ts": thread_ts,
+                "channel_type": channel_type,
+                "user_id": user,
+            }),
+            timestamp: parse_slack_ts_to_datetime(event.event_ts.as_deref()),
+            control: Some(InboundControl::Cancel),
+        })
+    }
+
+    fn record_sent(&self, channel: &str, channel_type: &str, ts: &str, thread_ts: Option<&str>) {
+        if let Ok(mut guard) = self.sent_messages.lock() {
+            guard.insert(
+                (channel.to_string(), ts.to_string()),
+                SentMessage {
+                    channel_type: channel_type.to_string(),
+                    thread_ts: thread_ts.map(ToOwned::to_owned),
+                },
+            );
+        }
+    }
+
     fn is_duplicate(&self, channel: &str, ts: &str) -> bool {
         match self.dedup.lock() {
             Ok(mut guard) => guard.is_duplicate(channel.to_string(), ts.to_string()),
@@ -662,8 +773,10 @@ impl Channel for SlackChannel {
     async fn send_with_receipt(&self, reply: OutboundReply) -> Result<DeliveryReceipt> {
         let (channel, channel_type, thread_ts) = Self::extract_target(&reply)?;
 
-        let slack_messages = markdown_to_slack_messages(&reply.text);
+        let slack_messages =
+            markdown_to_slack_messages_with_options(&reply.text, &self.render_options);
         let mut first_message_ts: Option<String> = None;
+        let mut sent_ts = Vec::new();
         let multi_message = slack_messages.len() > 1;
 
         if slack_messages.is_empty() {
@@ -671,6 +784,7 @@ impl Channel for SlackChannel {
             let ts = self
                 .post_message(&channel, &reply.text, None, None, thread_ts.as_deref())
                 .await?;
+            sent_ts.push(ts.clone());
             first_message_ts = Some(ts);
         } else {
             for (i, msg) in slack_messages.into_iter().enumerate() {
@@ -705,13 +819,22 @@ impl Channel for SlackChannel {
                         thread_ts.as_deref(),
                     )
                     .await?;
+                sent_ts.push(ts.clone());
                 if first_message_ts.is_none() {
                     first_message_ts = Some(ts);
                 }
             }
         }
 
-        let effective_thread_id = thread_ts.or_else(|| first_message_ts.clone());
+        let effective_thread_id = thread_ts.clone().or_else(|| first_message_ts.clone());
+        let sent_thread = if channel_type == "im" {
+            thread_ts.as_deref()
+        } else {
+            effective_thread_id.as_deref()
+        };
+        for ts in &sent_ts {
+            self.record_sent(&channel, &channel_type, ts, sent_thread);
+        }
 
         if channel_type != "im"
             && let Some(thread_id) = effective_thread_id.as_deref()
@@ -730,7 +853,7 @@ impl Channel for SlackChannel {
         reply: OutboundReply,
         buttons: Vec<ApprovalButton>,
     ) -> Result<String> {
-        let (channel, _channel_type, thread_ts) = Self::extract_target(&reply)?;
+        let (channel, channel_type, thread_ts) = Self::extract_target(&reply)?;
         let blocks = build_approval_blocks(&reply.text, &buttons);
         let ts = self
             .post_message(
@@ -741,6 +864,7 @@ impl Channel for SlackChannel {
                 thread_ts.as_deref(),
             )
             .await?;
+        self.record_sent(&channel, &channel_type, &ts, thread_ts.as_deref());
         Ok(format!("{channel}:{ts}"))
     }
 
@@ -818,6 +942,24 @@ struct SlackEvent {
     subtype: Option<String>,
     #[serde(default)]
     bot_id: Option<String>,
+    #[serde(default)]
+    reaction: Option<String>,
+    #[serde(default)]
+    item_user: Option<String>,
+    #[serde(default)]
+    item: Option<SlackReactionItem>,
+    #[serde(default)]
+    event_ts: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackReactionItem {
+    #[serde(rename = "type")]
+    item_type: String,
+    #[serde(default)]
+    channel: Option<String>,
+    #[serde(default)]
+    ts: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -960,6 +1102,46 @@ impl DedupBuffer {
     }
 }
 
+/// Recently sent bot messages by `(channel, ts)`, oldest evicted first.
+#[derive(Debug)]
+struct SentMessages {
+    messages: HashMap<(String, String), SentMessage>,
+    order: VecDeque<(String, String)>,
+    capacity: usize,
+}
+
+#[derive(Debug, Clone)]
+struct SentMessage {
+    channel_type: String,
+    thread_ts: Option<String>,
+}
+
+impl SentMessages {
+    fn new(capacity: usize) -> Self {
+        Self {
+            messages: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn insert(&mut self, key: (String, String), message: SentMessage) {
+        if self.messages.insert(key.clone(), message).is_none() {
+            self.order.push_back(key);
+        }
+
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.messages.remove(&oldest);
+            }
+        }
+    }
+
+    fn get(&self, channel: &str, ts: &str) -> Option<&SentMessage> {
+        self.messages.get(&(channel.to_string(), ts.to_string()))
+    }
+}
+
 fn map_chat_type(channel: &str, channel_type: &str, thread_ts: Option<&str>) -> ChatType {
     match channel_type {
         "im" => ChatType::Direct,
@@ -1088,9 +1270,10 @@ fn collapse_whitespace(text: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::{
-        DedupBuffer, is_bot_mentioned, map_chat_type, parse_slack_message_id, strip_bot_mention,
+        DedupBuffer, SlackChannel, SlackEvent, is_bot_mentioned, map_chat_type,
+        parse_slack_message_id, strip_bot_mention,
     };
-    use crate::types::ChatType;
+    use crate::types::{ChatType, InboundControl};
 
     #[test]
     fn map_chat_type_im_to_direct() {
@@ -1150,4 +1333,81 @@ mod tests {
             Some(("team:C123", "1700000000.123456"))
         );
     }
+
+    fn reaction_event(reaction: &str, user: &str, channel: &str, ts: &str) -> SlackEvent {
+        serde_json::from_value(serde_json::json!({
+            "type": "reaction_added",
+            "user": user,
+            "reaction": reaction,
+            "item_user": "UBOT",
+            "item": {"type": "message", "channel": channel, "ts": ts},
+            "event_ts": "1700000100.000100"
+        }))
+        .expect("reaction event")
+    }
+
+    #[test]
+    fn cancel_reaction_on_bot_message_becomes_cancel_request() {
+        let slack = SlackChannel::new(String::new(), String::new());
+        if let Ok(mut guard) = slack.bot_user_id.lock() {
+            *guard = Some("UBOT".to_string());
+        }
+        slack.record_sent(
+            "C123",
+            "channel",
+            "1700000050.000200",
+            Some("1700000000.000100"),
+        );
+
+        let inbound = slack
+            .cancel_reaction_inbound(&reaction_event(
+                "octagonal_sign",
+                "U1",
+                "C123",
+                "1700000050.000200",
+            ))
+            .expect("cancel request");
+        assert_eq!(inbound.control, Some(InboundControl::Cancel));
+        assert_eq!(inbound.peer_id.0, "U1");
+        assert_eq!(
+            inbound.chat_type,
+            ChatType::Thread {
+                group_id: "C123".to_string(),
+                thread_id: "1700000000.000100".to_string(),
+            }
+        );
+        assert_eq!(inbound.metadata["thread_ts"], "1700000000.000100");
+
+        // DMs resolve without a record of the message.
+        let dm = slack
+            .cancel_reaction_inbound(&reaction_event("octagonal_sign", "U1", "D42", "1.0"))
+            .expect("dm cancel request");
+        assert_eq!(dm.chat_type, ChatType::Direct);
+
+        // Other reactions, the bot's own reactions and unknown channel
+        // messages are ignored.
+        for event in [
+            reaction_event("thumbsup", "U1", "C123", "1700000050.000200"),
+            reaction_event("octagonal_sign", "UBOT", "C123", "1700000050.000200"),
+            reaction_event("octagonal_sign", "U1", "C123", "1600000000.000000"),
+        ] {
+            assert!(slack.cancel_reaction_inbound(&event).is_none());
+        }
+
+        let custom =
+            SlackChannel::new(String::new(), String::new()).with_cancel_reaction(":x:".to_string());
+        if let Ok(mut guard) = custom.bot_user_id.lock() {
+            *guard = Some("UBOT".to_string());
+        }
+        assert!(
+            custom
+                .cancel_reaction_inbound(&reaction_event("x", "U1", "D42", "1.0"))
+                .is_some()
+        );
+        assert!(
+            custom
+                .cancel_reaction_inbound(&reaction_event("octagonal_sign", "U1", "D42", "1.0"))
+                .is_none()
+        );
+    }
 }
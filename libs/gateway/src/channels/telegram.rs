@@ -298,6 +298,7 @@ impl TelegramChannel {
                 "display_name": from.first_name,
             }),
             timestamp,
+            control: None,
         })
     }
 
@@ -337,6 +338,7 @@ impl TelegramChannel {
                 "thread_id": message.as_ref().and_then(|value| value.message_thread_id),
             }),
             timestamp: Utc::now(),
+            control: None,
         })
     }
 }
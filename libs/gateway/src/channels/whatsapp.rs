@@ -0,0 +1,1168 @@
+//! WhatsApp Business channel over the Cloud API.
+//!
+//! Inbound messages arrive on a webhook the channel serves itself (Meta needs
+//! a public HTTPS URL, so put a reverse proxy or tunnel in front of
+//! `listen`). Replies go out through the Graph API. Free-form messages are
+//! only allowed within 24 hours of the user's last message; outside that
+//! window the configured template is sent instead. Markdown images in a
+//! reply (`![caption](https://...)`) are sent as media messages.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use axum::{
+    Router,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::get,
+};
+use bytes::Bytes;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::{
+    channels::{
+        ApprovalButton, Channel, ChannelTestResult, DeliveryReceipt, parse_approval_callback,
+    },
+    chunking::chunk_text,
+    types::{ChannelId, ChatType, InboundMessage, OutboundReply, PeerId},
+};
+
+const WHATSAPP_TEXT_LIMIT: usize = 4096;
+/// Body limit of interactive (button) messages.
+const WHATSAPP_INTERACTIVE_BODY_LIMIT: usize = 1024;
+const WHATSAPP_MAX_BUTTONS: usize = 3;
+const WHATSAPP_BUTTON_TITLE_LIMIT: usize = 20;
+const WHATSAPP_TEMPLATE_PARAMETER_LIMIT: usize = 1024;
+/// Graph API error for free-form messages outside the service window.
+const WHATSAPP_REENGAGEMENT_ERROR: i64 = 131047;
+const GRAPH_API_BASE: &str = "https://graph.facebook.com/v21.0";
+pub const WHATSAPP_WEBHOOK_PATH: &str = "/whatsapp/webhook";
+pub const DEFAULT_WHATSAPP_LISTEN: &str = "127.0.0.1:4099";
+
+/// Approved message template sent when the 24-hour service window is closed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhatsAppTemplate {
+    pub name: String,
+    #[serde(default = "default_template_language")]
+    pub language: String,
+    /// Pass the reply, flattened to one line, as the template's `{{1}}`.
+    #[serde(default)]
+    pub body_text: bool,
+}
+
+fn default_template_language() -> String {
+    "en_US".to_string()
+}
+
+pub struct WhatsAppChannel {
+    id: ChannelId,
+    access_token: String,
+    phone_number_id: String,
+    verify_token: String,
+    app_secret: String,
+    listen: String,
+    template: Option<WhatsAppTemplate>,
+    client: reqwest::Client,
+    /// When each user last wrote to us, which opens the service window.
+    last_inbound: Arc<Mutex<HashMap<String, DateTime<Utc>>>>,
+}
+
+impl WhatsAppChannel {
+    /// Webhook deliveries whose `X-Hub-Signature-256` doesn't match
+    /// `app_secret` are rejected.
+    pub fn new(
+        access_token: String,
+        phone_number_id: String,
+        verify_token: String,
+        app_secret: String,
+    ) -> Self {
+        Self {
+            id: "whatsapp".into(),
+            access_token,
+            phone_number_id,
+            verify_token,
+            app_secret,
+            listen: DEFAULT_WHATSAPP_LISTEN.to_string(),
+            template: None,
+            client: reqwest::Client::new(),
+            last_inbound: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn with_listen(mut self, listen: String) -> Self {
+        self.listen = listen;
+        self
+    }
+
+    pub fn with_template(mut self, template: Option<WhatsAppTemplate>) -> Self {
+        self.template = template;
+        self
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{GRAPH_API_BASE}/{}{path}", self.phone_number_id)
+    }
+
+    async fn post_message(&self, message: &WaOutbound<'_>) -> Result<String> {
+        let response = self
+            .client
+            .post(self.api_url("/messages"))
+            .bearer_auth(&self.access_token)
+            .json(message)
+            .send()
+            .await
+            .context("whatsapp messages request failed")?;
+
+        let payload: WaSendResponse = response
+            .json()
+            .await
+            .context("whatsapp messages decode failed")?;
+
+        if let Some(error) = payload.error {
+            return Err(error.into());
+        }
+
+        payload
+            .messages
+            .into_iter()
+            .next()
+            .map(|message| message.id)
+            .ok_or_else(|| anyhow!("whatsapp messages response missing message id"))
+    }
+
+    async fn send_text(&self, to: &str, text: &str, context: Option<&str>) -> Result<String> {
+        self.post_message(
+            &WaOutbound::new(
+                to,
+                WaOutboundBody::Text {
+                    text: WaText {
+                        body: text.to_string(),
+                        preview_url: true,
+                    },
+                },
+            )
+            .with_context(context),
+        )
+        .await
+    }
+
+    async fn send_template(
+        &self,
+        to: &str,
+        template: &WhatsAppTemplate,
+        text: &str,
+    ) -> Result<String> {
+        self.post_message(&WaOutbound::new(
+            to,
+            WaOutboundBody::Template {
+                template: template_payload(template, text),
+            },
+        ))
+        .await
+    }
+
+    fn service_window_closed(&self, to: &str) -> bool {
+        let last_inbound = self
+            .last_inbound
+            .lock()
+            .ok()
+            .and_then(|guard| guard.get(to).copied());
+        window_closed(last_inbound, Utc::now())
+    }
+
+    fn extract_target(reply: &OutboundReply) -> Result<String> {
+        reply
+            .metadata
+            .get("to")
+            .and_then(|value| value.as_str())
+            .map(ToOwned::to_owned)
+            .or_else(|| (!reply.peer_id.0.is_empty()).then(|| reply.peer_id.0.clone()))
+            .ok_or_else(|| anyhow!("whatsapp reply missing recipient in metadata/peer_id"))
+    }
+}
+
+#[async_trait]
+impl Channel for WhatsAppChannel {
+    fn id(&self) -> &ChannelId {
+        &self.id
+    }
+
+    fn display_name(&self) -> &str {
+        "WhatsApp"
+    }
+
+    async fn start(
+        &self,
+        inbound_tx: mpsc::Sender<InboundMessage>,
+        cancel: CancellationToken,
+    ) -> Result<()> {
+        let addr: SocketAddr = self
+            .listen
+            .parse()
+            .with_context(|| format!("invalid whatsapp listen address '{}'", self.listen))?;
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("failed to bind whatsapp webhook listener on {addr}"))?;
+
+        let state = Arc::new(WebhookState {
+            channel: self.id.clone(),
+            phone_number_id: self.phone_number_id.clone(),
+            verify_token: self.verify_token.clone(),
+            app_secret: self.app_secret.clone(),
+            last_inbound: self.last_inbound.clone(),
+            inbound_tx,
+        });
+        let app = Router::new()
+            .route(
+                WHATSAPP_WEBHOOK_PATH,
+                get(verify_webhook).post(receive_webhook),
+            )
+            .with_state(state);
+
+        info!(listen = %addr, path = WHATSAPP_WEBHOOK_PATH, "whatsapp webhook listening");
+
+        axum::serve(listener, app)
+            .with_graceful_shutdown(cancel.cancelled_owned())
+            .await
+            .context("whatsapp webhook server failed")
+    }
+
+    async fn send(&self, reply: OutboundReply) -> Result<()> {
+        self.send_with_receipt(reply).await.map(|_| ())
+    }
+
+    async fn send_with_receipt(&self, reply: OutboundReply) -> Result<DeliveryReceipt> {
+        let to = Self::extract_target(&reply)?;
+
+        if let Some(template) = &self.template
+            && self.service_window_closed(&to)
+        {
+            let message_id = self.send_template(&to, template, &reply.text).await?;
+            return Ok(DeliveryReceipt {
+                message_id: Some(format!("{to}:{message_id}")),
+                thread_id: None,
+            });
+        }
+
+        let (text, media) = extract_media(&reply.text);
+        let mut first_message_id: Option<String> = None;
+        for chunk in chunk_text(&text, WHATSAPP_TEXT_LIMIT) {
+            let message_id = match self.send_text(&to, &chunk, None).await {
+                Ok(message_id) => message_id,
+                Err(error) => match &self.template {
+                    // The window closed without us seeing it, e.g. after a restart.
+                    Some(template) if is_reengagement_error(&error) => {
+                        let message_id = self.send_template(&to, template, &reply.text).await?;
+                        return Ok(DeliveryReceipt {
+                            message_id: Some(format!("{to}:{message_id}")),
+                            thread_id: None,
+                        });
+                    }
+                    _ => return Err(error),
+                },
+            };
+            first_message_id.get_or_insert(message_id);
+        }
+
+        for item in media {
+            let message_id = self
+                .post_message(&WaOutbound::new(&to, item.into_body()))
+                .await?;
+            first_message_id.get_or_insert(message_id);
+        }
+
+        Ok(DeliveryReceipt {
+            message_id: first_message_id.map(|message_id| format!("{to}:{message_id}")),
+            thread_id: None,
+        })
+    }
+
+    async fn send_with_buttons(
+        &self,
+        reply: OutboundReply,
+        buttons: Vec<ApprovalButton>,
+    ) -> Result<String> {
+        let to = Self::extract_target(&reply)?;
+
+        let mut chunks = chunk_text(&reply.text, WHATSAPP_INTERACTIVE_BODY_LIMIT);
+        let body = chunks.pop().unwrap_or_default();
+        for chunk in chunks {
+            self.send_text(&to, &chunk, None).await?;
+        }
+
+        let interactive = WaInteractive {
+            r#type: "button",
+            body: WaInteractiveBody { text: body },
+            action: WaInteractiveAction {
+                buttons: buttons
+                    .iter()
+                    .take(WHATSAPP_MAX_BUTTONS)
+                    .map(|button| WaReplyButton {
+                        r#type: "reply",
+                        reply: WaReplyButtonBody {
+                            id: button.callback_data.clone(),
+                            title: button
+                                .label
+                                .chars()
+                                .take(WHATSAPP_BUTTON_TITLE_LIMIT)
+                                .collect(),
+                        },
+                    })
+                    .collect(),
+            },
+        };
+
+        let message_id = self
+            .post_message(&WaOutbound::new(
+                &to,
+                WaOutboundBody::Interactive { interactive },
+            ))
+            .await?;
+
+        Ok(format!("{to}:{message_id}"))
+    }
+
+    /// WhatsApp can't edit sent messages, so the new text goes out as a
+    /// reply quoting the original.
+    async fn edit_message(&self, message_id: &str, new_text: &str) -> Result<()> {
+        let Some((to, message_id)) = message_id.split_once(':') else {
+            return Ok(());
+        };
+
+        self.send_text(to, new_text, Some(message_id))
+            .await
+            .map(|_| ())
+    }
+
+    async fn test(&self) -> Result<ChannelTestResult> {
+        let response = self
+            .client
+            .get(self.api_url(""))
+            .query(&[("fields", "display_phone_number,verified_name")])
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .context("whatsapp phone number request failed")?;
+
+        let payload: WaPhoneNumber = response
+            .json()
+            .await
+            .context("whatsapp phone number decode failed")?;
+
+        if let Some(error) = payload.error {
+            return Err(error.into());
+        }
+
+        let number = payload.display_phone_number.unwrap_or_default();
+        Ok(ChannelTestResult {
+            channel: self.id.0.clone(),
+            identity: payload.verified_name.unwrap_or_else(|| number.clone()),
+            details: format!("phone_number_id={} number={number}", self.phone_number_id),
+        })
+    }
+}
+
+struct WebhookState {
+    channel: ChannelId,
+    phone_number_id: String,
+    verify_token: String,
+    app_secret: String,
+    last_inbound: Arc<Mutex<HashMap<String, DateTime<Utc>>>>,
+    inbound_tx: mpsc::Sender<InboundMessage>,
+}
+
+/// Meta's subscription handshake: echo `hub.challenge` when the token matches.
+async fn verify_webhook(
+    State(state): State<Arc<WebhookState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let mode = params.get("hub.mode").map(String::as_str);
+    let token = params.get("hub.verify_token").map(String::as_str);
+
+    match params.get("hub.challenge") {
+        Some(challenge) if mode == Some("subscribe") && token == Some(&state.verify_token) => {
+            (StatusCode::OK, challenge.clone())
+        }
+        _ => (StatusCode::FORBIDDEN, String::new()),
+    }
+}
+
+async fn receive_webhook(
+    State(state): State<Arc<WebhookState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let signature = headers
+        .get("x-hub-signature-256")
+        .and_then(|value| value.to_str().ok());
+    if !verify_signature(&state.app_secret, &body, signature) {
+        warn!("rejected whatsapp webhook with a bad signature");
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let payload: WaWebhook = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(error) => {
+            warn!(error = %error, "failed to decode whatsapp webhook");
+            return StatusCode::BAD_REQUEST;
+        }
+    };
+
+    let messages = map_webhook(payload, &state.channel, &state.phone_number_id);
+    if let Ok(mut last_inbound) = state.last_inbound.lock() {
+        for message in &messages {
+            last_inbound.insert(message.peer_id.0.clone(), message.timestamp);
+        }
+    }
+
+    for message in messages {
+        if state.inbound_tx.send(message).await.is_err() {
+            break;
+        }
+    }
+
+    // Anything but 200 makes Meta retry the delivery.
+    StatusCode::OK
+}
+
+fn verify_signature(app_secret: &str, body: &[u8], signature: Option<&str>) -> bool {
+    let Some(expected) = signature
+        .and_then(|value| value.strip_prefix("sha256="))
+        .and_then(decode_hex)
+    else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(app_secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| {
+            text.get(index..index + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+        })
+        .collect()
+}
+
+fn window_closed(last_inbound: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    last_inbound.is_some_and(|at| now - at >= Duration::hours(24))
+}
+
+fn is_reengagement_error(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<WaApiError>()
+        .is_some_and(|error| error.code == WHATSAPP_REENGAGEMENT_ERROR)
+}
+
+/// Inbound messages for our number; statuses and other numbers' traffic
+/// on the same app are dropped.
+fn map_webhook(
+    payload: WaWebhook,
+    channel: &ChannelId,
+    phone_number_id: &str,
+) -> Vec<InboundMessage> {
+    let mut inbound = Vec::new();
+    for change in payload.entry.into_iter().flat_map(|entry| entry.changes) {
+        if change.field != "messages" {
+            continue;
+        }
+        let value = change.value;
+        if value
+            .metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.phone_number_id != phone_number_id)
+        {
+            continue;
+        }
+
+        for message in value.messages {
+            let display_name = value
+                .contacts
+                .iter()
+                .find(|contact| contact.wa_id == message.from)
+                .and_then(|contact| contact.profile.as_ref())
+                .map(|profile| profile.name.clone());
+            if let Some(message) = map_message_inbound(channel, message, display_name) {
+                inbound.push(message);
+            }
+        }
+    }
+    inbound
+}
+
+fn map_message_inbound(
+    channel: &ChannelId,
+    message: WaMessage,
+    display_name: Option<String>,
+) -> Option<InboundMessage> {
+    let timestamp = message
+        .timestamp
+        .parse::<i64>()
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .unwrap_or_else(Utc::now);
+
+    if let Some((approval_id, decision)) = message
+        .interactive
+        .as_ref()
+        .and_then(|interactive| interactive.button_reply.as_ref())
+        .and_then(|reply| parse_approval_callback(&reply.id))
+    {
+        return Some(InboundMessage {
+            channel: channel.clone(),
+            peer_id: PeerId(message.from.clone()),
+            chat_type: ChatType::Direct,
+            text: String::new(),
+            media: Vec::new(),
+            metadata: serde_json::json!({
+                "type": "approval_response",
+                "approval_id": approval_id,
+                "decision": decision,
+                "to": message.from,
+                "message_id": message.id,
+            }),
+            timestamp,
+            control: None,
+        });
+    }
+
+    let media = [
+        ("image", &message.image),
+        ("document", &message.document),
+        ("video", &message.video),
+        ("audio", &message.audio),
+    ]
+    .into_iter()
+    .find_map(|(kind, media)| media.as_ref().map(|media| (kind, media)));
+
+    let text = match message.r#type.as_str() {
+        "text" => message.text.map(|text| text.body),
+        "button" => message.button.map(|button| button.text),
+        "interactive" => message
+            .interactive
+            .and_then(|interactive| interactive.button_reply)
+            .map(|reply| reply.title),
+        _ => media.and_then(|(_, media)| media.caption.clone()),
+    }
+    .filter(|text| !text.trim().is_empty())?;
+
+    Some(InboundMessage {
+        channel: channel.clone(),
+        peer_id: PeerId(message.from.clone()),
+        chat_type: ChatType::Direct,
+        text,
+        media: Vec::new(),
+        metadata: serde_json::json!({
+            "to": message.from,
+            "message_id": message.id,
+            "display_name": display_name,
+            "reply_to": message.context.map(|context| context.id),
+            "media": media.map(|(kind, media)| serde_json::json!({
+                "type": kind,
+                "id": media.id,
+                "mime_type": media.mime_type,
+                "filename": media.filename,
+            })),
+        }),
+        timestamp,
+        control: None,
+    })
+}
+
+fn template_payload(template: &WhatsAppTemplate, text: &str) -> WaTemplatePayload {
+    let components = if template.body_text {
+        vec![WaTemplateComponent {
+            r#type: "body",
+            parameters: vec![WaTemplateParameter {
+                r#type: "text",
+                text: template_parameter(text),
+            }],
+        }]
+    } else {
+        Vec::new()
+    };
+
+    WaTemplatePayload {
+        name: template.name.clone(),
+        language: WaTemplateLanguage {
+            code: template.language.clone(),
+        },
+        components,
+    }
+}
+
+/// Template parameters can't contain newlines, tabs or runs of spaces.
+fn template_parameter(text: &str) -> String {
+    let flattened = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flattened.chars().count() <= WHATSAPP_TEMPLATE_PARAMETER_LIMIT {
+        return flattened;
+    }
+    let mut truncated: String = flattened
+        .chars()
+        .take(WHATSAPP_TEMPLATE_PARAMETER_LIMIT - 1)
+        .collect();
+    truncated.push('…');
+    truncated
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MediaReply {
+    kind: MediaKind,
+    link: String,
+    caption: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MediaKind {
+    Image,
+    Video,
+    Audio,
+    Document,
+}
+
+impl MediaKind {
+    fn from_link(link: &str) -> Self {
+        let path = link.split(['?', '#']).next().unwrap_or(link);
+        let extension = path
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_ascii_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "jpg" | "jpeg" | "png" => Self::Image,
+            "mp4" | "3gp" => Self::Video,
+            "mp3" | "ogg" | "aac" | "amr" | "m4a" | "opus" => Self::Audio,
+            _ => Self::Document,
+        }
+    }
+}
+
+impl MediaReply {
+    fn into_body(self) -> WaOutboundBody {
+        let filename = (self.kind == MediaKind::Document).then(|| {
+            let path = self.link.split(['?', '#']).next().unwrap_or(&self.link);
+            path.rsplit('/').next().unwrap_or(path).to_string()
+        });
+        let media = WaMediaLink {
+            link: self.link,
+            caption: if self.kind == MediaKind::Audio {
+                None
+            } else {
+                self.caption
+            },
+            filename,
+        };
+        match self.kind {
+            MediaKind::Image => WaOutboundBody::Image { image: media },
+            MediaKind::Video => WaOutboundBody::Video { video: media },
+            MediaKind::Audio => WaOutboundBody::Audio { audio: media },
+            MediaKind::Document => WaOutboundBody::Document { document: media },
+        }
+    }
+}
+
+/// Split markdown images with http(s) links out of a reply; the rest of the
+/// text is sent as is.
+fn extract_media(text: &str) -> (String, Vec<MediaReply>) {
+    let mut media = Vec::new();
+    let mut removed: Vec<Range<usize>> = Vec::new();
+    let mut current: Option<(Range<usize>, String, String)> = None;
+
+    for (event, range) in Parser::new(text).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Image { dest_url, .. })
+                if dest_url.starts_with("https://") || dest_url.starts_with("http://") =>
+            {
+                current = Some((range, dest_url.to_string(), String::new()));
+            }
+            Event::Text(alt) | Event::Code(alt) => {
+                if let Some((_, _, caption)) = current.as_mut() {
+                    caption.push_str(&alt);
+                }
+            }
+            Event::End(TagEnd::Image) => {
+                if let Some((range, link, caption)) = current.take() {
+                    let caption = caption.trim().to_string();
+                    media.push(MediaReply {
+                        kind: MediaKind::from_link(&link),
+                        link,
+                        caption: (!caption.is_empty()).then_some(caption),
+                    });
+                    removed.push(range);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if removed.is_empty() {
+        return (text.to_string(), media);
+    }
+
+    let mut remaining = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for range in removed {
+        remaining.push_str(text.get(cursor..range.start).unwrap_or_default());
+        cursor = range.end;
+    }
+    remaining.push_str(text.get(cursor..).unwrap_or_default());
+    (remaining.trim().to_string(), media)
+}
+
+#[derive(Debug, Serialize)]
+struct WaOutbound<'a> {
+    messaging_product: &'static str,
+    recipient_type: &'static str,
+    to: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<WaContextRef>,
+    #[serde(flatten)]
+    body: WaOutboundBody,
+}
+
+impl<'a> WaOutbound<'a> {
+    fn new(to: &'a str, body: WaOutboundBody) -> Self {
+        Self {
+            messaging_product: "whatsapp",
+            recipient_type: "individual",
+            to,
+            context: None,
+            body,
+        }
+    }
+
+    fn with_context(mut self, message_id: Option<&str>) -> Self {
+        self.context = message_id.map(|message_id| WaContextRef {
+            message_id: message_id.to_string(),
+        });
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct WaContextRef {
+    message_id: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WaOutboundBody {
+    Text { text: WaText },
+    Image { image: WaMediaLink },
+    Video { video: WaMediaLink },
+    Audio { audio: WaMediaLink },
+    Document { document: WaMediaLink },
+    Template { template: WaTemplatePayload },
+    Interactive { interactive: WaInteractive },
+}
+
+#[derive(Debug, Serialize)]
+struct WaText {
+    body: String,
+    preview_url: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct WaMediaLink {
+    link: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    caption: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filename: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct WaTemplatePayload {
+    name: String,
+    language: WaTemplateLanguage,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    components: Vec<WaTemplateComponent>,
+}
+
+#[derive(Debug, Serialize)]
+struct WaTemplateLanguage {
+    code: String,
+}
+
+#[derive(Debug, Serialize)]
+struct WaTemplateComponent {
+    r#type: &'static str,
+    parameters: Vec<WaTemplateParameter>,
+}
+
+#[derive(Debug, Serialize)]
+struct WaTemplateParameter {
+    r#type: &'static str,
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct WaInteractive {
+    r#type: &'static str,
+    body: WaInteractiveBody,
+    action: WaInteractiveAction,
+}
+
+#[derive(Debug, Serialize)]
+struct WaInteractiveBody {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct WaInteractiveAction {
+    buttons: Vec<WaReplyButton>,
+}
+
+#[derive(Debug, Serialize)]
+struct WaReplyButton {
+    r#type: &'static str,
+    reply: WaReplyButtonBody,
+}
+
+#[derive(Debug, Serialize)]
+struct WaReplyButtonBody {
+    id: String,
+    title: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaSendResponse {
+    #[serde(default)]
+    messages: Vec<WaSentMessage>,
+    #[serde(default)]
+    error: Option<WaApiError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaSentMessage {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaPhoneNumber {
+    #[serde(default)]
+    display_phone_number: Option<String>,
+    #[serde(default)]
+    verified_name: Option<String>,
+    #[serde(default)]
+    error: Option<WaApiError>,
+}
+
+#[derive(Debug, Deserialize, thiserror::Error)]
+#[error("whatsapp api error {code}: {message}")]
+struct WaApiError {
+    #[serde(default)]
+    code: i64,
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaWebhook {
+    #[serde(default)]
+    entry: Vec<WaEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaEntry {
+    #[serde(default)]
+    changes: Vec<WaChange>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaChange {
+    field: String,
+    value: WaChangeValue,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaChangeValue {
+    #[serde(default)]
+    metadata: Option<WaPhoneMetadata>,
+    #[serde(default)]
+    contacts: Vec<WaContact>,
+    #[serde(default)]
+    messages: Vec<WaMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaPhoneMetadata {
+    phone_number_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaContact {
+    wa_id: String,
+    #[serde(default)]
+    profile: Option<WaProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaProfile {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaMessage {
+    from: String,
+    id: String,
+    timestamp: String,
+    r#type: String,
+    #[serde(default)]
+    context: Option<WaMessageContext>,
+    #[serde(default)]
+    text: Option<WaInboundText>,
+    #[serde(default)]
+    button: Option<WaInboundButton>,
+    #[serde(default)]
+    interactive: Option<WaInboundInteractive>,
+    #[serde(default)]
+    image: Option<WaInboundMedia>,
+    #[serde(default)]
+    document: Option<WaInboundMedia>,
+    #[serde(default)]
+    video: Option<WaInboundMedia>,
+    #[serde(default)]
+    audio: Option<WaInboundMedia>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaMessageContext {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaInboundText {
+    body: String,
+}
+
+/// Quick reply on a template message.
+#[derive(Debug, Deserialize)]
+struct WaInboundButton {
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaInboundInteractive {
+    #[serde(default)]
+    button_reply: Option<WaButtonReply>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaButtonReply {
+    id: String,
+    title: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaInboundMedia {
+    id: String,
+    #[serde(default)]
+    mime_type: Option<String>,
+    #[serde(default)]
+    caption: Option<String>,
+    #[serde(default)]
+    filename: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(messages: serde_json::Value) -> WaWebhook {
+        let raw = serde_json::json!({
+            "object": "whatsapp_business_account",
+            "entry": [{
+                "id": "WABA_ID",
+                "changes": [{
+                    "field": "messages",
+                    "value": {
+                        "messaging_product": "whatsapp",
+                        "metadata": {"display_phone_number": "15550001111", "phone_number_id": "PN1"},
+                        "contacts": [{"profile": {"name": "Dana"}, "wa_id": "4915112345678"}],
+                        "messages": messages,
+                    }
+                }]
+            }]
+        });
+        serde_json::from_value(raw).expect("webhook payload")
+    }
+
+    #[test]
+    fn webhook_text_message_maps_to_inbound() {
+        let payload = webhook(serde_json::json!([{
+            "from": "4915112345678",
+            "id": "wamid.1",
+            "timestamp": "1710000000",
+            "type": "text",
+            "text": {"body": "pump 3 is down"}
+        }]));
+
+        let messages = map_webhook(payload, &"whatsapp".into(), "PN1");
+        assert_eq!(messages.len(), 1);
+        let message = &messages[0];
+        assert_eq!(message.peer_id.0, "4915112345678");
+        assert_eq!(message.chat_type, ChatType::Direct);
+        assert_eq!(message.text, "pump 3 is down");
+        assert_eq!(message.metadata["to"], "4915112345678");
+        assert_eq!(message.metadata["display_name"], "Dana");
+        assert_eq!(message.timestamp.timestamp(), 1_710_000_000);
+
+        let other_number = webhook(serde_json::json!([{
+            "from": "4915112345678", "id": "wamid.2", "timestamp": "1710000000",
+            "type": "text", "text": {"body": "hi"}
+        }]));
+        assert!(map_webhook(other_number, &"whatsapp".into(), "PN2").is_empty());
+    }
+
+    #[test]
+    fn webhook_button_reply_maps_to_approval_response() {
+        let payload = webhook(serde_json::json!([{
+            "from": "4915112345678",
+            "id": "wamid.3",
+            "timestamp": "1710000000",
+            "type": "interactive",
+            "interactive": {
+                "type": "button_reply",
+                "button_reply": {"id": "a:a3f0c92d:allow", "title": "Allow"}
+            }
+        }, {
+            "from": "4915112345678",
+            "id": "wamid.4",
+            "timestamp": "1710000000",
+            "type": "image",
+            "image": {"id": "MEDIA1", "mime_type": "image/jpeg", "caption": "gauge reading"}
+        }, {
+            "from": "4915112345678",
+            "id": "wamid.5",
+            "timestamp": "1710000000",
+            "type": "sticker",
+            "sticker": {"id": "MEDIA2"}
+        }]));
+
+        let messages = map_webhook(payload, &"whatsapp".into(), "PN1");
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].metadata["type"], "approval_response");
+        assert_eq!(messages[0].metadata["approval_id"], "a3f0c92d");
+        assert_eq!(messages[0].metadata["decision"], "allow");
+        assert_eq!(messages[1].text, "gauge reading");
+        assert_eq!(messages[1].metadata["media"]["type"], "image");
+        assert_eq!(messages[1].metadata["media"]["id"], "MEDIA1");
+    }
+
+    #[test]
+    fn signature_must_match_app_secret() {
+        let body = br#"{"entry":[]}"#;
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"app-secret").expect("hmac key");
+        mac.update(body);
+        let digest = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+
+        let header = format!("sha256={digest}");
+        assert!(verify_signature("app-secret", body, Some(&header)));
+        assert!(!verify_signature("other-secret", body, Some(&header)));
+        assert!(!verify_signature("app-secret", b"{}", Some(&header)));
+        assert!(!verify_signature("app-secret", body, Some(&digest)));
+        assert!(!verify_signature("app-secret", body, None));
+    }
+
+    #[test]
+    fn service_window_closes_after_24_hours() {
+        let now = Utc::now();
+        assert!(!window_closed(None, now));
+        assert!(!window_closed(Some(now - Duration::hours(23)), now));
+        assert!(window_closed(Some(now - Duration::hours(24)), now));
+
+        let error = anyhow::Error::from(WaApiError {
+            code: WHATSAPP_REENGAGEMENT_ERROR,
+            message: "Re-engagement message".to_string(),
+        });
+        assert!(is_reengagement_error(&error));
+        assert!(!is_reengagement_error(&anyhow!("timeout")));
+    }
+
+    #[test]
+    fn template_payload_flattens_reply_into_body_parameter() {
+        let template = WhatsAppTemplate {
+            name: "ops_update".to_string(),
+            language: "en_US".to_string(),
+            body_text: true,
+        };
+        let message = WaOutbound::new(
+            "4915112345678",
+            WaOutboundBody::Template {
+                template: template_payload(&template, "Deploy done.\n\n\tAll  checks green"),
+            },
+        );
+
+        let json = serde_json::to_value(&message).expect("serialize template message");
+        assert_eq!(json["type"], "template");
+        assert_eq!(json["messaging_product"], "whatsapp");
+        assert_eq!(json["template"]["name"], "ops_update");
+        assert_eq!(json["template"]["language"]["code"], "en_US");
+        assert_eq!(
+            json["template"]["components"][0]["parameters"][0]["text"],
+            "Deploy done. All checks green"
+        );
+        assert!(json.get("context").is_none());
+
+        let without_body = template_payload(
+            &WhatsAppTemplate {
+                body_text: false,
+                ..template
+            },
+            "ignored",
+        );
+        let json = serde_json::to_value(&without_body).expect("serialize template");
+        assert!(json.get("components").is_none());
+    }
+
+    #[test]
+    fn markdown_images_become_media_replies() {
+        let (text, media) = extract_media(
+            "Here is the trend:\n\n![CPU last 24h](https://cdn.example.com/cpu.png?sig=1)\n\nAnd the report ![report](https://cdn.example.com/r/incident.pdf). Local ![x](./x.png)",
+        );
+
+        assert_eq!(
+            text,
+            "Here is the trend:\n\n\n\nAnd the report . Local ![x](./x.png)"
+        );
+        assert_eq!(media.len(), 2);
+        assert_eq!(media[0].kind, MediaKind::Image);
+        assert_eq!(media[0].caption.as_deref(), Some("CPU last 24h"));
+        assert_eq!(media[1].kind, MediaKind::Document);
+
+        let json = serde_json::to_value(WaOutbound::new("1", media[1].clone().into_body()))
+            .expect("serialize document message");
+        assert_eq!(json["type"], "document");
+        assert_eq!(
+            json["document"]["link"],
+            "https://cdn.example.com/r/incident.pdf"
+        );
+        assert_eq!(json["document"]["filename"], "incident.pdf");
+        assert_eq!(json["document"]["caption"], "report");
+    }
+}
@@ -6,6 +6,7 @@ use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use stakpak_agent_core::ProposedToolCall;
+use stakpak_shared::error::ErrorKind;
 pub use stakpak_shared::models::context::{
     CallerContextInput, MAX_CALLER_CONTEXT_CONTENT_CHARS, MAX_CALLER_CONTEXT_ITEMS,
     MAX_CALLER_CONTEXT_NAME_CHARS, validate_caller_context,
@@ -37,6 +38,50 @@ pub struct CreateSessionResponse {
 pub struct SessionResponse {
     pub id: Uuid,
     pub title: String,
+    #[serde(default)]
+    pub run_status: Option<SessionRunStatus>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRunStatus {
+    pub state: SessionRunState,
+    #[serde(default)]
+    pub run_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionRunState {
+    Idle,
+    Starting,
+    Running,
+    Failed,
+}
+
+impl SessionResponse {
+    /// The run in flight in this session, if one is starting or running.
+    pub fn active_run_id(&self) -> Option<Uuid> {
+        self.run_status
+            .as_ref()
+            .filter(|status| {
+                matches!(
+                    status.state,
+                    SessionRunState::Starting | SessionRunState::Running
+                )
+            })
+            .and_then(|status| status.run_id)
+    }
+}
+
+/// Optional settings for a new session.
+#[derive(Debug, Clone, Default)]
+pub struct CreateSessionOptions {
+    /// Directory the session's tools run in instead of the server's project directory.
+    pub cwd: Option<String>,
+    /// Tags to find the session by later with [`StakpakClient::list_sessions_tagged`].
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -134,12 +179,87 @@ pub struct RunCompletedPayload {
     pub total_turns: Option<u32>,
 }
 
+/// Token counts reported for a single agent turn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// Usage totals as summed by the server for a run or session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageTotals {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunUsage {
+    pub run_id: Uuid,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub turns: usize,
+    #[serde(default)]
+    pub usage: UsageTotals,
+    #[serde(default)]
+    pub estimated_cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionUsage {
+    pub session_id: Uuid,
+    #[serde(default)]
+    pub usage: UsageTotals,
+    #[serde(default)]
+    pub estimated_cost: Option<f64>,
+    #[serde(default)]
+    pub runs: Vec<RunUsage>,
+}
+
+impl SessionUsage {
+    pub fn run(&self, run_id: Uuid) -> Option<&RunUsage> {
+        self.runs.iter().rev().find(|run| run.run_id == run_id)
+    }
+}
+
+/// Render usage compactly, e.g. `12.3k tokens, ~$0.04`. The cost is left
+/// out when unknown.
+pub fn format_usage(total_tokens: u64, estimated_cost: Option<f64>) -> String {
+    let tokens = match total_tokens {
+        0..1_000 => format!("{total_tokens} tokens"),
+        1_000..1_000_000 => format!("{:.1}k tokens", total_tokens as f64 / 1_000.0),
+        _ => format!("{:.1}M tokens", total_tokens as f64 / 1_000_000.0),
+    };
+    match estimated_cost {
+        Some(cost) if cost > 0.0 && cost < 0.01 => format!("{tokens}, <$0.01"),
+        Some(cost) => format!("{tokens}, ~${cost:.2}"),
+        None => tokens,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageReportPayload {
+    #[serde(default)]
+    pub run_id: Option<Uuid>,
+    #[serde(default)]
+    pub usage: TokenUsage,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunErrorPayload {
     #[serde(default)]
     pub run_id: Option<Uuid>,
     #[serde(default)]
     pub error: Option<String>,
+    #[serde(default)]
+    pub kind: ErrorKind,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +270,18 @@ pub struct ToolCallsProposedPayload {
     pub tool_calls: Vec<ProposedToolCall>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolExecutionCompletedPayload {
+    #[serde(default)]
+    pub run_id: Option<Uuid>,
+    pub tool_call_id: String,
+    pub tool_name: String,
+    #[serde(default)]
+    pub result: String,
+    #[serde(default)]
+    pub is_error: bool,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ClientError {
     #[error("http request failed: {0}")]
@@ -178,6 +310,40 @@ pub enum ClientError {
     InvalidRequest(String),
 }
 
+impl ClientError {
+    /// The server could not be reached at all, as opposed to rejecting the request.
+    pub fn is_connectivity(&self) -> bool {
+        match self {
+            ClientError::Http(error) => error.is_connect() || error.is_timeout(),
+            ClientError::Connection(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Where this falls in the user-facing error taxonomy.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            _ if self.is_connectivity() => ErrorKind::Network,
+            ClientError::ApiError {
+                status: 401 | 403, ..
+            } => ErrorKind::Auth,
+            ClientError::ApiError { status: 429, .. } => ErrorKind::RateLimit,
+            ClientError::ApiError {
+                status: 408 | 502 | 503 | 504,
+                ..
+            } => ErrorKind::Network,
+            ClientError::ApiError { code, .. } if code == "invalid_model" => ErrorKind::Config,
+            ClientError::ApiError { .. } => ErrorKind::Internal,
+            ClientError::InvalidRequest(_) => ErrorKind::Config,
+            ClientError::Http(_)
+            | ClientError::NotFound(_)
+            | ClientError::Conflict
+            | ClientError::SseParse(_)
+            | ClientError::Connection(_) => ErrorKind::Internal,
+        }
+    }
+}
+
 impl StakpakClient {
     pub fn new(base_url: String, auth_token: String) -> Self {
         Self {
@@ -207,7 +373,22 @@ impl StakpakClient {
     }
 
     pub async fn create_session(&self, title: &str) -> Result<CreateSessionResponse, ClientError> {
-        let payload = serde_json::json!({ "title": title });
+        self.create_session_with(title, CreateSessionOptions::default())
+            .await
+    }
+
+    pub async fn create_session_with(
+        &self,
+        title: &str,
+        options: CreateSessionOptions,
+    ) -> Result<CreateSessionResponse, ClientError> {
+        let mut payload = serde_json::json!({ "title": title });
+        if let Some(cwd) = options.cwd {
+            payload["cwd"] = Value::String(cwd);
+        }
+        if !options.tags.is_empty() {
+            payload["tags"] = serde_json::json!(options.tags);
+        }
         self.request_json(reqwest::Method::POST, "/v1/sessions", Some(payload))
             .await
     }
@@ -229,6 +410,19 @@ impl StakpakClient {
             .await
     }
 
+    /// Sessions created with `tag` since the server started, newest first.
+    pub async fn list_sessions_tagged(
+        &self,
+        tag: &str,
+    ) -> Result<ListSessionsResponse, ClientError> {
+        self.request_json(
+            reqwest::Method::GET,
+            &format!("/v1/sessions?tag={}", encode_query_value(tag)),
+            None,
+        )
+        .await
+    }
+
     pub async fn delete_session(&self, session_id: &str) -> Result<(), ClientError> {
         let response = self
             .request(
@@ -337,6 +531,35 @@ impl StakpakClient {
         })
     }
 
+    /// Replay a session's buffered events after `after_id`.
+    ///
+    /// The server streams the replay and then stays open for live events, so
+    /// reading stops once no event arrives for `idle`, after `limit` events,
+    /// or when `deadline` passes.
+    pub async fn replay_events(
+        &self,
+        session_id: &str,
+        after_id: u64,
+        limit: usize,
+        idle: std::time::Duration,
+        deadline: std::time::Duration,
+    ) -> Result<Vec<SseEvent>, ClientError> {
+        let mut stream = self.subscribe_events(session_id, Some(after_id)).await?;
+        let deadline = tokio::time::Instant::now() + deadline;
+        let mut events = Vec::new();
+
+        while events.len() < limit {
+            let wait = idle.min(deadline.saturating_duration_since(tokio::time::Instant::now()));
+            match tokio::time::timeout(wait, stream.next_event()).await {
+                Ok(Ok(Some(event))) => events.push(event),
+                Ok(Ok(None)) | Err(_) => break,
+                Ok(Err(error)) => return Err(error),
+            }
+        }
+
+        Ok(events)
+    }
+
     pub async fn pending_tools(
         &self,
         session_id: &str,
@@ -349,6 +572,15 @@ impl StakpakClient {
         .await
     }
 
+    pub async fn session_usage(&self, session_id: &str) -> Result<SessionUsage, ClientError> {
+        self.request_json(
+            reqwest::Method::GET,
+            &format!("/v1/sessions/{session_id}/usage"),
+            None,
+        )
+        .await
+    }
+
     pub async fn resolve_tools(
         &self,
         session_id: &str,
@@ -500,6 +732,12 @@ impl SseEvent {
         serde_json::from_value(payload.clone()).ok()
     }
 
+    pub fn as_usage_report(&self) -> Option<UsageReportPayload> {
+        let envelope = parse_event_envelope(&self.data).ok()?;
+        let payload = extract_variant_payload(&envelope.event, "UsageReport")?;
+        serde_json::from_value(payload.clone()).ok()
+    }
+
     pub fn as_run_error(&self) -> Option<RunErrorPayload> {
         let envelope = parse_event_envelope(&self.data).ok()?;
         let payload = extract_variant_payload(&envelope.event, "RunError")?;
@@ -512,6 +750,12 @@ impl SseEvent {
         serde_json::from_value(payload.clone()).ok()
     }
 
+    pub fn as_tool_execution_completed(&self) -> Option<ToolExecutionCompletedPayload> {
+        let envelope = parse_event_envelope(&self.data).ok()?;
+        let payload = extract_variant_payload(&envelope.event, "ToolExecutionCompleted")?;
+        serde_json::from_value(payload.clone()).ok()
+    }
+
     pub fn is_run_terminal(&self) -> bool {
         self.event_type == "run_completed" || self.event_type == "run_error"
     }
@@ -606,20 +850,100 @@ fn map_error_status(status: StatusCode, body: String) -> Result<(), ClientError>
         StatusCode::CONFLICT => Err(ClientError::Conflict),
         _ => Err(ClientError::ApiError {
             status: status.as_u16(),
-            code: "api_error".to_string(),
+            code: api_error_code(&body).unwrap_or_else(|| "api_error".to_string()),
             body,
         }),
     }
 }
 
+/// The `code` of a server error body (`{"error": ..., "code": ...}`).
+fn api_error_code(body: &str) -> Option<String> {
+    serde_json::from_str::<Value>(body)
+        .ok()?
+        .get("code")?
+        .as_str()
+        .map(str::to_string)
+}
+
 fn validate_context_inputs(inputs: &[CallerContextInput]) -> Result<(), ClientError> {
     validate_caller_context(inputs).map_err(ClientError::InvalidRequest)
 }
 
+/// Percent-encode `value` for use in a URL query string.
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn client_errors_map_to_error_kinds() {
+        let api_error = |status: u16, body: &str| {
+            map_error_status(
+                StatusCode::from_u16(status).expect("status"),
+                body.to_string(),
+            )
+            .expect_err("error status")
+        };
+        assert_eq!(api_error(401, "nope").kind(), ErrorKind::Auth);
+        assert_eq!(api_error(429, "slow down").kind(), ErrorKind::RateLimit);
+        assert_eq!(api_error(502, "").kind(), ErrorKind::Network);
+        assert_eq!(
+            api_error(400, r#"{"error":"Unknown model","code":"invalid_model"}"#).kind(),
+            ErrorKind::Config
+        );
+        // The body text is never used to guess a kind
+        assert_eq!(
+            api_error(500, "model is overloaded").kind(),
+            ErrorKind::Internal
+        );
+        assert_eq!(
+            ClientError::Connection("refused".to_string()).kind(),
+            ErrorKind::Network
+        );
+        assert_eq!(ClientError::Conflict.kind(), ErrorKind::Internal);
+    }
+
+    #[test]
+    fn session_response_reports_active_run_only_while_in_flight() {
+        let run_id = Uuid::new_v4();
+        let running: SessionResponse = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "title": "autopilot",
+            "run_status": { "state": "running", "run_id": run_id },
+            "tags": ["schedule:backup"],
+        }))
+        .expect("running session");
+        assert_eq!(running.active_run_id(), Some(run_id));
+
+        let idle: SessionResponse = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "title": "autopilot",
+            "run_status": { "state": "idle", "run_id": null },
+        }))
+        .expect("idle session");
+        assert_eq!(idle.active_run_id(), None);
+    }
+
+    #[test]
+    fn encode_query_value_escapes_reserved_characters() {
+        assert_eq!(
+            encode_query_value("autopilot-schedule:disk check&x"),
+            "autopilot-schedule%3Adisk%20check%26x"
+        );
+    }
+
     #[test]
     fn parse_sse_event_block() {
         let block = "id:1\nevent:text_delta\ndata:{\"id\":1}\n";
@@ -634,6 +958,78 @@ mod tests {
         assert_eq!(event.event_id_u64, Some(1));
     }
 
+    #[test]
+    fn usage_report_payload_parses_token_counts() {
+        let event = SseEvent {
+            id: Some("7".to_string()),
+            event_id_u64: Some(7),
+            event_type: "usage_report".to_string(),
+            data: r#"{"id":7,"session_id":"s","timestamp":"t","event":{"UsageReport":{"run_id":null,"turn":1,"usage":{"prompt_tokens":1200,"completion_tokens":300,"total_tokens":1500,"input_token_details":{"no_cache":1200}}}}}"#.to_string(),
+        };
+
+        let report = event.as_usage_report().expect("usage report");
+        assert_eq!(
+            report.usage,
+            TokenUsage {
+                prompt_tokens: 1200,
+                completion_tokens: 300,
+                total_tokens: 1500,
+            }
+        );
+        assert!(event.as_run_completed().is_none());
+    }
+
+    #[test]
+    fn run_error_payload_carries_its_kind() {
+        let event = |payload: &str| SseEvent {
+            id: Some("4".to_string()),
+            event_id_u64: Some(4),
+            event_type: "run_error".to_string(),
+            data: format!(
+                r#"{{"id":4,"session_id":"s","timestamp":"t","event":{{"RunError":{payload}}}}}"#
+            ),
+        };
+
+        let typed = event(r#"{"run_id":null,"error":"Rate limit exceeded: slow down","kind":"rate_limit","retryable":false}"#)
+            .as_run_error()
+            .expect("run error");
+        assert_eq!(typed.kind, ErrorKind::RateLimit);
+
+        // Events recorded before runs carried a kind
+        let legacy = event(r#"{"run_id":null,"error":"server returned 429","retryable":false}"#)
+            .as_run_error()
+            .expect("run error");
+        assert_eq!(legacy.kind, ErrorKind::Internal);
+    }
+
+    #[test]
+    fn format_usage_abbreviates_tokens_and_rounds_cost() {
+        assert_eq!(format_usage(850, None), "850 tokens");
+        assert_eq!(format_usage(12_345, Some(0.0412)), "12.3k tokens, ~$0.04");
+        assert_eq!(format_usage(2_400_000, Some(3.5)), "2.4M tokens, ~$3.50");
+        assert_eq!(format_usage(900, Some(0.002)), "900 tokens, <$0.01");
+        assert_eq!(format_usage(900, Some(0.0)), "900 tokens, ~$0.00");
+    }
+
+    #[test]
+    fn tool_execution_completed_payload_parses_result() {
+        let event = SseEvent {
+            id: Some("9".to_string()),
+            event_id_u64: Some(9),
+            event_type: "tool_execution_completed".to_string(),
+            data: r#"{"id":9,"session_id":"s","timestamp":"t","event":{"ToolExecutionCompleted":{"run_id":"8c3bbf3e-57a5-4a8e-9a43-6c1d8e0f51f4","tool_call_id":"call_1","tool_name":"stakpak__run_command","result":"NAME  READY","is_error":false}}}"#.to_string(),
+        };
+
+        let completed = event
+            .as_tool_execution_completed()
+            .expect("tool execution completed");
+        assert_eq!(completed.tool_call_id, "call_1");
+        assert_eq!(completed.tool_name, "stakpak__run_command");
+        assert_eq!(completed.result, "NAME  READY");
+        assert!(!completed.is_error);
+        assert!(event.as_tool_calls_proposed().is_none());
+    }
+
     #[test]
     fn validate_context_inputs_accepts_exact_limits() {
         let input = CallerContextInput {
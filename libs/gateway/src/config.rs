@@ -1,11 +1,20 @@
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
+use stakpak_shared::pending_interactions::{
+    DEFAULT_ESCALATE_AFTER, DEFAULT_REPING_AFTER, ReminderPolicy,
+};
 use stakpak_shared::utils::normalize_optional_string;
+pub use stakpak_shell_tool_approvals::ApprovalRules;
 use thiserror::Error;
 
+use crate::channels::whatsapp::{DEFAULT_WHATSAPP_LISTEN, WhatsAppTemplate};
 use crate::router::{Binding, BindingMatch, DmScope, PeerMatch, PeerMatchKind, RouterConfig};
+use crate::slack_blocks::{SlackBlockFormat, SlackMentions, SlackTableRendering};
+use crate::targeting::ChannelTarget;
+use crate::templates::MessageTemplates;
 
 #[derive(Debug, Clone, Default)]
 pub struct GatewayCliFlags {
@@ -41,6 +50,80 @@ pub struct GatewaySettings {
     pub delivery_context_ttl_hours: u64,
     pub approval_mode: ApprovalMode,
     pub approval_allowlist: Vec<String>,
+    /// Tool-call patterns that settle a call before `approval_mode` is consulted.
+    pub approval_rules: ApprovalRules,
+    pub approval_reminders: ApprovalReminderConfig,
+    /// Parallel runs allowed per session. `1` (the default) serializes messages.
+    pub max_concurrent_runs_per_session: usize,
+    /// Which finished tool calls are posted to the chat.
+    pub tool_results: ToolResultDisplay,
+    /// Append the run's token usage and estimated cost to its final reply.
+    pub usage_footer: bool,
+    /// Outbound message templates; channels can override them one by one.
+    pub templates: MessageTemplates,
+    /// Users allowed to control schedules with `/autopilot` commands, as
+    /// `<channel>:<user id>`, a bare user id or `*`. Empty disables them.
+    pub schedule_control_allowlist: Vec<String>,
+}
+
+/// Reminders for approval prompts that sit unanswered in a channel.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApprovalReminderConfig {
+    /// Minutes before the prompt is re-pinged on its own channel. `0` disables.
+    #[serde(default = "default_reping_after_minutes")]
+    pub reping_after_minutes: u64,
+    /// Minutes before the prompt is escalated to `escalate_channel`.
+    #[serde(default = "default_escalate_after_minutes")]
+    pub escalate_after_minutes: u64,
+    /// Secondary channel (`telegram`, `discord`, `slack`) to escalate to.
+    #[serde(default)]
+    pub escalate_channel: Option<String>,
+    /// Channel target for escalations, same shape as `/v1/gateway/send`.
+    #[serde(default)]
+    pub escalate_target: Option<serde_json::Value>,
+}
+
+fn default_reping_after_minutes() -> u64 {
+    DEFAULT_REPING_AFTER.as_secs() / 60
+}
+
+fn default_escalate_after_minutes() -> u64 {
+    DEFAULT_ESCALATE_AFTER.as_secs() / 60
+}
+
+impl Default for ApprovalReminderConfig {
+    fn default() -> Self {
+        Self {
+            reping_after_minutes: default_reping_after_minutes(),
+            escalate_after_minutes: default_escalate_after_minutes(),
+            escalate_channel: None,
+            escalate_target: None,
+        }
+    }
+}
+
+impl ApprovalReminderConfig {
+    /// Escalation is only active once both a channel and a target are set.
+    pub fn escalation(&self) -> Option<(&str, &serde_json::Value)> {
+        let channel = self
+            .escalate_channel
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())?;
+        let target = self.escalate_target.as_ref()?;
+        Some((channel, target))
+    }
+
+    pub fn reminder_policy(&self) -> ReminderPolicy {
+        let minutes = |value: u64| (value > 0).then(|| Duration::from_secs(value * 60));
+        ReminderPolicy {
+            bell_after: None,
+            reping_after: minutes(self.reping_after_minutes),
+            escalate_after: self
+                .escalation()
+                .and_then(|_| minutes(self.escalate_after_minutes)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
@@ -52,6 +135,18 @@ pub enum ApprovalMode {
     Allowlist,
 }
 
+/// Which tool results the dispatcher posts while a run streams.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolResultDisplay {
+    Off,
+    /// Only results a tool renderer understands (tables, plans, diffs).
+    #[default]
+    Rendered,
+    /// Every result, using the generic renderer when no tool renderer applies.
+    All,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum GatewayConfigValidationError {
     #[error("at least one channel must be configured")]
@@ -64,8 +159,32 @@ pub enum GatewayConfigValidationError {
     EmptySlackBotToken,
     #[error("slack app_token cannot be empty")]
     EmptySlackAppToken,
+    #[error("whatsapp access_token cannot be empty")]
+    EmptyWhatsAppAccessToken,
+    #[error("whatsapp phone_number_id cannot be empty")]
+    EmptyWhatsAppPhoneNumberId,
+    #[error("whatsapp verify_token cannot be empty")]
+    EmptyWhatsAppVerifyToken,
+    #[error("whatsapp app_secret cannot be empty")]
+    EmptyWhatsAppAppSecret,
+    #[error("whatsapp listen '{0}' is not a socket address")]
+    InvalidWhatsAppListen(String),
+    #[error("matrix homeserver cannot be empty")]
+    EmptyMatrixHomeserver,
+    #[error("matrix access_token cannot be empty")]
+    EmptyMatrixAccessToken,
+    #[error("rocketchat server_url cannot be empty")]
+    EmptyRocketChatServerUrl,
+    #[error("rocketchat user_id cannot be empty")]
+    EmptyRocketChatUserId,
+    #[error("rocketchat auth_token cannot be empty")]
+    EmptyRocketChatAuthToken,
     #[error("approval_mode=allowlist requires non-empty approval_allowlist")]
     EmptyApprovalAllowlist,
+    #[error("approval_reminders.escalate_channel '{0}' is not configured")]
+    UnknownEscalationChannel(String),
+    #[error("approval_reminders.escalate_target is invalid: {0}")]
+    InvalidEscalationTarget(String),
 }
 
 impl Default for GatewaySettings {
@@ -78,6 +197,13 @@ impl Default for GatewaySettings {
             delivery_context_ttl_hours: 4,
             approval_mode: ApprovalMode::AllowAll,
             approval_allowlist: Vec::new(),
+            approval_rules: ApprovalRules::default(),
+            approval_reminders: ApprovalReminderConfig::default(),
+            max_concurrent_runs_per_session: 1,
+            tool_results: ToolResultDisplay::default(),
+            usage_footer: true,
+            templates: MessageTemplates::default(),
+            schedule_control_allowlist: Vec::new(),
         }
     }
 }
@@ -131,6 +257,9 @@ pub struct ChannelConfigs {
     pub telegram: Option<TelegramConfig>,
     pub discord: Option<DiscordConfig>,
     pub slack: Option<SlackConfig>,
+    pub whatsapp: Option<WhatsAppConfig>,
+    pub matrix: Option<MatrixConfig>,
+    pub rocketchat: Option<RocketChatConfig>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -151,6 +280,9 @@ pub struct TelegramConfig {
     pub auto_approve: Option<Vec<String>>,
     #[serde(default)]
     pub profile: Option<String>,
+    /// Outbound message templates for this channel, over `[gateway.templates]`.
+    #[serde(default, skip_serializing_if = "MessageTemplates::is_empty")]
+    pub templates: MessageTemplates,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -164,6 +296,13 @@ pub struct DiscordConfig {
     pub auto_approve: Option<Vec<String>>,
     #[serde(default)]
     pub profile: Option<String>,
+    /// Outbound message templates for this channel, over `[gateway.templates]`.
+    #[serde(default, skip_serializing_if = "MessageTemplates::is_empty")]
+    pub templates: MessageTemplates,
+    /// Reaction on one of the bot's messages that cancels the active run;
+    /// defaults to `🛑`. An empty value turns reaction cancelling off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancel_reaction: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -176,6 +315,101 @@ pub struct SlackConfig {
     pub auto_approve: Option<Vec<String>>,
     #[serde(default)]
     pub profile: Option<String>,
+    /// How markdown tables are rendered: "block" (default), "both" or
+    /// "preformatted" for clients that don't show table blocks.
+    #[serde(default)]
+    pub table_rendering: SlackTableRendering,
+    /// Block types carrying message text: "rich_text" (default) or
+    /// "mrkdwn" for apps that only read classic `section` blocks.
+    #[serde(default)]
+    pub block_format: SlackBlockFormat,
+    /// Names that `@name` / `#name` in messages resolve to, so
+    /// notifications mention the user or channel.
+    #[serde(default, skip_serializing_if = "SlackMentions::is_empty")]
+    pub mentions: SlackMentions,
+    /// Outbound message templates for this channel, over `[gateway.templates]`.
+    #[serde(default, skip_serializing_if = "MessageTemplates::is_empty")]
+    pub templates: MessageTemplates,
+    /// Reaction on one of the bot's messages that cancels the active run;
+    /// defaults to `octagonal_sign`. An empty value turns reaction cancelling off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancel_reaction: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhatsAppConfig {
+    /// Cloud API access token, e.g. a system user token.
+    pub access_token: String,
+    /// Business phone number messages are sent from.
+    pub phone_number_id: String,
+    /// Token Meta sends back when verifying the webhook subscription.
+    pub verify_token: String,
+    /// App secret used to check the signature of webhook deliveries. Without
+    /// it anyone who reaches the listener could post messages as any number.
+    pub app_secret: String,
+    /// Address the webhook listener binds to; Meta must reach it over HTTPS,
+    /// e.g. through a reverse proxy.
+    #[serde(default = "default_whatsapp_listen")]
+    pub listen: String,
+    /// Approved template sent instead of a reply when the user hasn't
+    /// written in the last 24 hours.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<WhatsAppTemplate>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub auto_approve: Option<Vec<String>>,
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Outbound message templates for this channel, over `[gateway.templates]`.
+    #[serde(default, skip_serializing_if = "MessageTemplates::is_empty")]
+    pub templates: MessageTemplates,
+}
+
+fn default_whatsapp_listen() -> String {
+    DEFAULT_WHATSAPP_LISTEN.to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixConfig {
+    /// Homeserver base URL, e.g. `https://matrix.example.org`.
+    pub homeserver: String,
+    /// Access token of the bot user.
+    pub access_token: String,
+    /// Room ids to listen in; empty means every room the bot has joined.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rooms: Vec<String>,
+    /// Accept room invites automatically (only for `rooms` when set).
+    #[serde(default)]
+    pub auto_join: bool,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub auto_approve: Option<Vec<String>>,
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Outbound message templates for this channel, over `[gateway.templates]`.
+    #[serde(default, skip_serializing_if = "MessageTemplates::is_empty")]
+    pub templates: MessageTemplates,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RocketChatConfig {
+    /// Server base URL, e.g. `https://chat.example.org`.
+    pub server_url: String,
+    /// User id of the bot user, shown with its personal access token.
+    pub user_id: String,
+    /// Personal access token of the bot user.
+    pub auth_token: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub auto_approve: Option<Vec<String>>,
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Outbound message templates for this channel, over `[gateway.templates]`.
+    #[serde(default, skip_serializing_if = "MessageTemplates::is_empty")]
+    pub templates: MessageTemplates,
 }
 
 impl Default for GatewayConfig {
@@ -294,6 +528,61 @@ impl GatewayConfig {
                         .collect(),
                 ),
             );
+            if self.gateway.approval_rules.is_empty() {
+                gateway.remove("approval_rules");
+            } else {
+                gateway.insert(
+                    "approval_rules".to_string(),
+                    toml::Value::try_from(&self.gateway.approval_rules)
+                        .map_err(|error| anyhow!("failed to serialize approval_rules: {error}"))?,
+                );
+            }
+            gateway.insert(
+                "approval_reminders".to_string(),
+                toml::Value::try_from(&self.gateway.approval_reminders)
+                    .map_err(|error| anyhow!("failed to serialize approval_reminders: {error}"))?,
+            );
+            gateway.insert(
+                "max_concurrent_runs_per_session".to_string(),
+                toml::Value::Integer(
+                    i64::try_from(self.gateway.max_concurrent_runs_per_session).map_err(|_| {
+                        anyhow!("max_concurrent_runs_per_session exceeds i64 range")
+                    })?,
+                ),
+            );
+            gateway.insert(
+                "tool_results".to_string(),
+                toml::Value::try_from(self.gateway.tool_results)
+                    .map_err(|error| anyhow!("failed to serialize tool_results: {error}"))?,
+            );
+            gateway.insert(
+                "usage_footer".to_string(),
+                toml::Value::Boolean(self.gateway.usage_footer),
+            );
+            if self.gateway.templates.is_empty() {
+                gateway.remove("templates");
+            } else {
+                gateway.insert(
+                    "templates".to_string(),
+                    toml::Value::try_from(&self.gateway.templates)
+                        .map_err(|error| anyhow!("failed to serialize templates: {error}"))?,
+                );
+            }
+            if self.gateway.schedule_control_allowlist.is_empty() {
+                gateway.remove("schedule_control_allowlist");
+            } else {
+                gateway.insert(
+                    "schedule_control_allowlist".to_string(),
+                    toml::Value::Array(
+                        self.gateway
+                            .schedule_control_allowlist
+                            .iter()
+                            .cloned()
+                            .map(toml::Value::String)
+                            .collect(),
+                    ),
+                );
+            }
         }
 
         {
@@ -315,6 +604,9 @@ impl GatewayConfig {
             upsert_optional_subtable(channels, "telegram", &self.channels.telegram)?;
             upsert_optional_subtable(channels, "discord", &self.channels.discord)?;
             upsert_optional_subtable(channels, "slack", &self.channels.slack)?;
+            upsert_optional_subtable(channels, "whatsapp", &self.channels.whatsapp)?;
+            upsert_optional_subtable(channels, "matrix", &self.channels.matrix)?;
+            upsert_optional_subtable(channels, "rocketchat", &self.channels.rocketchat)?;
         }
 
         let text = toml::to_string_pretty(&toml::Value::Table(root))
@@ -331,7 +623,18 @@ impl GatewayConfig {
     }
 
     pub fn validate_with_error(&self) -> std::result::Result<(), GatewayConfigValidationError> {
-        if self.enabled_channels().is_empty() {
+        self.validate_with_custom_channels(&[])
+    }
+
+    /// Validate for a gateway that also runs `custom_channels`, registered in
+    /// code rather than configured; they count as enabled channels.
+    pub fn validate_with_custom_channels(
+        &self,
+        custom_channels: &[&str],
+    ) -> std::result::Result<(), GatewayConfigValidationError> {
+        let mut enabled_channels = self.enabled_channels();
+        enabled_channels.extend_from_slice(custom_channels);
+        if enabled_channels.is_empty() {
             return Err(GatewayConfigValidationError::MissingChannels);
         }
 
@@ -356,12 +659,64 @@ impl GatewayConfig {
             }
         }
 
+        if let Some(whatsapp) = &self.channels.whatsapp {
+            if whatsapp.access_token.trim().is_empty() {
+                return Err(GatewayConfigValidationError::EmptyWhatsAppAccessToken);
+            }
+            if whatsapp.phone_number_id.trim().is_empty() {
+                return Err(GatewayConfigValidationError::EmptyWhatsAppPhoneNumberId);
+            }
+            if whatsapp.verify_token.trim().is_empty() {
+                return Err(GatewayConfigValidationError::EmptyWhatsAppVerifyToken);
+            }
+            if whatsapp.app_secret.trim().is_empty() {
+                return Err(GatewayConfigValidationError::EmptyWhatsAppAppSecret);
+            }
+            if whatsapp.listen.parse::<std::net::SocketAddr>().is_err() {
+                return Err(GatewayConfigValidationError::InvalidWhatsAppListen(
+                    whatsapp.listen.clone(),
+                ));
+            }
+        }
+
+        if let Some(matrix) = &self.channels.matrix {
+            if matrix.homeserver.trim().is_empty() {
+                return Err(GatewayConfigValidationError::EmptyMatrixHomeserver);
+            }
+            if matrix.access_token.trim().is_empty() {
+                return Err(GatewayConfigValidationError::EmptyMatrixAccessToken);
+            }
+        }
+
+        if let Some(rocketchat) = &self.channels.rocketchat {
+            if rocketchat.server_url.trim().is_empty() {
+                return Err(GatewayConfigValidationError::EmptyRocketChatServerUrl);
+            }
+            if rocketchat.user_id.trim().is_empty() {
+                return Err(GatewayConfigValidationError::EmptyRocketChatUserId);
+            }
+            if rocketchat.auth_token.trim().is_empty() {
+                return Err(GatewayConfigValidationError::EmptyRocketChatAuthToken);
+            }
+        }
+
         if matches!(self.gateway.approval_mode, ApprovalMode::Allowlist)
             && self.gateway.approval_allowlist.is_empty()
         {
             return Err(GatewayConfigValidationError::EmptyApprovalAllowlist);
         }
 
+        if let Some((channel, target)) = self.gateway.approval_reminders.escalation() {
+            if !enabled_channels.contains(&channel) {
+                return Err(GatewayConfigValidationError::UnknownEscalationChannel(
+                    channel.to_string(),
+                ));
+            }
+            ChannelTarget::parse(channel, target).map_err(|error| {
+                GatewayConfigValidationError::InvalidEscalationTarget(error.to_string())
+            })?;
+        }
+
         Ok(())
     }
 
@@ -380,6 +735,15 @@ impl GatewayConfig {
         if self.channels.slack.is_some() {
             channels.push("slack");
         }
+        if self.channels.whatsapp.is_some() {
+            channels.push("whatsapp");
+        }
+        if self.channels.matrix.is_some() {
+            channels.push("matrix");
+        }
+        if self.channels.rocketchat.is_some() {
+            channels.push("rocketchat");
+        }
         channels
     }
 
@@ -402,6 +766,18 @@ impl GatewayConfig {
             append_channel_deprecation_warnings(&mut warnings, "slack", channel);
         }
 
+        if let Some(channel) = self.channels.whatsapp.as_ref() {
+            append_channel_deprecation_warnings(&mut warnings, "whatsapp", channel);
+        }
+
+        if let Some(channel) = self.channels.matrix.as_ref() {
+            append_channel_deprecation_warnings(&mut warnings, "matrix", channel);
+        }
+
+        if let Some(channel) = self.channels.rocketchat.as_ref() {
+            append_channel_deprecation_warnings(&mut warnings, "rocketchat", channel);
+        }
+
         warnings
     }
 
@@ -462,6 +838,7 @@ impl GatewayConfig {
                 model: None,
                 auto_approve: None,
                 profile: None,
+                templates: MessageTemplates::default(),
             });
         }
 
@@ -474,6 +851,8 @@ impl GatewayConfig {
                 model: None,
                 auto_approve: None,
                 profile: None,
+                templates: MessageTemplates::default(),
+                cancel_reaction: None,
             });
         }
 
@@ -487,9 +866,77 @@ impl GatewayConfig {
                     model: None,
                     auto_approve: None,
                     profile: None,
+                    table_rendering: SlackTableRendering::default(),
+                    block_format: SlackBlockFormat::default(),
+                    mentions: SlackMentions::default(),
+                    templates: MessageTemplates::default(),
+                    cancel_reaction: None,
                 });
             }
         }
+
+        if self.channels.whatsapp.is_none() {
+            let access_token = std::env::var("WHATSAPP_ACCESS_TOKEN").ok();
+            let phone_number_id = std::env::var("WHATSAPP_PHONE_NUMBER_ID").ok();
+            let verify_token = std::env::var("WHATSAPP_VERIFY_TOKEN").ok();
+            let app_secret = std::env::var("WHATSAPP_APP_SECRET").ok();
+            if let (
+                Some(access_token),
+                Some(phone_number_id),
+                Some(verify_token),
+                Some(app_secret),
+            ) = (access_token, phone_number_id, verify_token, app_secret)
+            {
+                self.channels.whatsapp = Some(WhatsAppConfig {
+                    access_token,
+                    phone_number_id,
+                    verify_token,
+                    app_secret,
+                    listen: default_whatsapp_listen(),
+                    template: None,
+                    model: None,
+                    auto_approve: None,
+                    profile: None,
+                    templates: MessageTemplates::default(),
+                });
+            }
+        }
+
+        if self.channels.matrix.is_none()
+            && let (Ok(homeserver), Ok(access_token)) = (
+                std::env::var("MATRIX_HOMESERVER"),
+                std::env::var("MATRIX_ACCESS_TOKEN"),
+            )
+        {
+            self.channels.matrix = Some(MatrixConfig {
+                homeserver,
+                access_token,
+                rooms: Vec::new(),
+                auto_join: false,
+                model: None,
+                auto_approve: None,
+                profile: None,
+                templates: MessageTemplates::default(),
+            });
+        }
+
+        if self.channels.rocketchat.is_none()
+            && let (Ok(server_url), Ok(user_id), Ok(auth_token)) = (
+                std::env::var("ROCKETCHAT_URL"),
+                std::env::var("ROCKETCHAT_USER_ID"),
+                std::env::var("ROCKETCHAT_AUTH_TOKEN"),
+            )
+        {
+            self.channels.rocketchat = Some(RocketChatConfig {
+                server_url,
+                user_id,
+                auth_token,
+                model: None,
+                auto_approve: None,
+                profile: None,
+                templates: MessageTemplates::default(),
+            });
+        }
     }
 
     fn apply_cli_overrides(&mut self, cli: &GatewayCliFlags) {
@@ -527,6 +974,12 @@ impl GatewayConfig {
                     .telegram
                     .as_ref()
                     .and_then(|value| value.profile.clone()),
+                templates: self
+                    .channels
+                    .telegram
+                    .as_ref()
+                    .map(|value| value.templates.clone())
+                    .unwrap_or_default(),
             });
         }
 
@@ -555,6 +1008,17 @@ impl GatewayConfig {
                     .discord
                     .as_ref()
                     .and_then(|value| value.profile.clone()),
+                templates: self
+                    .channels
+                    .discord
+                    .as_ref()
+                    .map(|value| value.templates.clone())
+                    .unwrap_or_default(),
+                cancel_reaction: self
+                    .channels
+                    .discord
+                    .as_ref()
+                    .and_then(|value| value.cancel_reaction.clone()),
             });
         }
 
@@ -577,6 +1041,35 @@ impl GatewayConfig {
                     .slack
                     .as_ref()
                     .and_then(|value| value.profile.clone()),
+                table_rendering: self
+                    .channels
+                    .slack
+                    .as_ref()
+                    .map(|value| value.table_rendering)
+                    .unwrap_or_default(),
+                block_format: self
+                    .channels
+                    .slack
+                    .as_ref()
+                    .map(|value| value.block_format)
+                    .unwrap_or_default(),
+                mentions: self
+                    .channels
+                    .slack
+                    .as_ref()
+                    .map(|value| value.mentions.clone())
+                    .unwrap_or_default(),
+                templates: self
+                    .channels
+                    .slack
+                    .as_ref()
+                    .map(|value| value.templates.clone())
+                    .unwrap_or_default(),
+                cancel_reaction: self
+                    .channels
+                    .slack
+                    .as_ref()
+                    .and_then(|value| value.cancel_reaction.clone()),
             });
         }
     }
@@ -604,6 +1097,21 @@ impl ChannelConfigs {
                 .as_ref()
                 .map(channel_overrides_from_parts)
                 .unwrap_or_default(),
+            "whatsapp" => self
+                .whatsapp
+                .as_ref()
+                .map(channel_overrides_from_parts)
+                .unwrap_or_default(),
+            "matrix" => self
+                .matrix
+                .as_ref()
+                .map(channel_overrides_from_parts)
+                .unwrap_or_default(),
+            "rocketchat" => self
+                .rocketchat
+                .as_ref()
+                .map(channel_overrides_from_parts)
+                .unwrap_or_default(),
             _ => ChannelOverrides::default(),
         }
     }
@@ -626,6 +1134,21 @@ impl ChannelConfigs {
             overrides.insert("slack".to_string(), slack);
         }
 
+        let whatsapp = self.overrides_for("whatsapp");
+        if whatsapp != ChannelOverrides::default() {
+            overrides.insert("whatsapp".to_string(), whatsapp);
+        }
+
+        let matrix = self.overrides_for("matrix");
+        if matrix != ChannelOverrides::default() {
+            overrides.insert("matrix".to_string(), matrix);
+        }
+
+        let rocketchat = self.overrides_for("rocketchat");
+        if rocketchat != ChannelOverrides::default() {
+            overrides.insert("rocketchat".to_string(), rocketchat);
+        }
+
         overrides
     }
 
@@ -656,8 +1179,51 @@ impl ChannelConfigs {
             profiles.insert("slack".to_string(), profile);
         }
 
+        if let Some(profile) = self
+            .whatsapp
+            .as_ref()
+            .and_then(|channel| normalize_optional_string(channel.profile.clone()))
+        {
+            profiles.insert("whatsapp".to_string(), profile);
+        }
+
+        if let Some(profile) = self
+            .matrix
+            .as_ref()
+            .and_then(|channel| normalize_optional_string(channel.profile.clone()))
+        {
+            profiles.insert("matrix".to_string(), profile);
+        }
+
+        if let Some(profile) = self
+            .rocketchat
+            .as_ref()
+            .and_then(|channel| normalize_optional_string(channel.profile.clone()))
+        {
+            profiles.insert("rocketchat".to_string(), profile);
+        }
+
         profiles
     }
+
+    /// Message templates set on each channel, keyed by channel name.
+    pub fn templates_map(&self) -> std::collections::HashMap<String, MessageTemplates> {
+        [
+            ("telegram", self.telegram.as_ref().map(|c| &c.templates)),
+            ("discord", self.discord.as_ref().map(|c| &c.templates)),
+            ("slack", self.slack.as_ref().map(|c| &c.templates)),
+            ("whatsapp", self.whatsapp.as_ref().map(|c| &c.templates)),
+            ("matrix", self.matrix.as_ref().map(|c| &c.templates)),
+            ("rocketchat", self.rocketchat.as_ref().map(|c| &c.templates)),
+        ]
+        .into_iter()
+        .filter_map(|(name, templates)| {
+            templates
+                .filter(|templates| !templates.is_empty())
+                .map(|templates| (name.to_string(), templates.clone()))
+        })
+        .collect()
+    }
 }
 
 trait ChannelOverrideParts {
@@ -708,6 +1274,48 @@ impl ChannelOverrideParts for SlackConfig {
     }
 }
 
+impl ChannelOverrideParts for WhatsAppConfig {
+    fn model(&self) -> Option<String> {
+        self.model.clone()
+    }
+
+    fn auto_approve(&self) -> Option<Vec<String>> {
+        self.auto_approve.clone()
+    }
+
+    fn profile(&self) -> Option<String> {
+        self.profile.clone()
+    }
+}
+
+impl ChannelOverrideParts for MatrixConfig {
+    fn model(&self) -> Option<String> {
+        self.model.clone()
+    }
+
+    fn auto_approve(&self) -> Option<Vec<String>> {
+        self.auto_approve.clone()
+    }
+
+    fn profile(&self) -> Option<String> {
+        self.profile.clone()
+    }
+}
+
+impl ChannelOverrideParts for RocketChatConfig {
+    fn model(&self) -> Option<String> {
+        self.model.clone()
+    }
+
+    fn auto_approve(&self) -> Option<Vec<String>> {
+        self.auto_approve.clone()
+    }
+
+    fn profile(&self) -> Option<String> {
+        self.profile.clone()
+    }
+}
+
 fn channel_overrides_from_parts(parts: &impl ChannelOverrideParts) -> ChannelOverrides {
     let allowlist = normalize_allowlist(parts.auto_approve());
 
@@ -858,6 +1466,20 @@ impl PersistedGatewayConfig {
                 delivery_context_ttl_hours: self.gateway.delivery_context_ttl_hours.unwrap_or(4),
                 approval_mode: self.gateway.approval_mode.unwrap_or_default(),
                 approval_allowlist: self.gateway.approval_allowlist.unwrap_or_default(),
+                approval_rules: self.gateway.approval_rules.unwrap_or_default(),
+                approval_reminders: self.gateway.approval_reminders.unwrap_or_default(),
+                max_concurrent_runs_per_session: self
+                    .gateway
+                    .max_concurrent_runs_per_session
+                    .unwrap_or(1)
+                    .max(1),
+                tool_results: self.gateway.tool_results.unwrap_or_default(),
+                usage_footer: self.gateway.usage_footer.unwrap_or(true),
+                templates: self.gateway.templates.unwrap_or_default(),
+                schedule_control_allowlist: self
+                    .gateway
+                    .schedule_control_allowlist
+                    .unwrap_or_default(),
             },
             routing: RoutingConfig {
                 dm_scope: self.routing.dm_scope.unwrap_or_default(),
@@ -867,6 +1489,9 @@ impl PersistedGatewayConfig {
                 telegram: self.channels.telegram,
                 discord: self.channels.discord,
                 slack: self.channels.slack,
+                whatsapp: self.channels.whatsapp,
+                matrix: self.channels.matrix,
+                rocketchat: self.channels.rocketchat,
             },
         }
     }
@@ -896,6 +1521,20 @@ struct PersistedGatewaySettings {
     approval_mode: Option<ApprovalMode>,
     #[serde(default)]
     approval_allowlist: Option<Vec<String>>,
+    #[serde(default)]
+    approval_rules: Option<ApprovalRules>,
+    #[serde(default)]
+    approval_reminders: Option<ApprovalReminderConfig>,
+    #[serde(default)]
+    max_concurrent_runs_per_session: Option<usize>,
+    #[serde(default)]
+    tool_results: Option<ToolResultDisplay>,
+    #[serde(default)]
+    usage_footer: Option<bool>,
+    #[serde(default)]
+    templates: Option<MessageTemplates>,
+    #[serde(default)]
+    schedule_control_allowlist: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
@@ -914,6 +1553,12 @@ struct PersistedChannelConfigs {
     discord: Option<DiscordConfig>,
     #[serde(default)]
     slack: Option<SlackConfig>,
+    #[serde(default)]
+    whatsapp: Option<WhatsAppConfig>,
+    #[serde(default)]
+    matrix: Option<MatrixConfig>,
+    #[serde(default)]
+    rocketchat: Option<RocketChatConfig>,
 }
 
 fn binding_to_runtime(binding: &BindingConfig) -> Binding {
@@ -968,9 +1613,11 @@ fn expand_tilde_path(path: &Path) -> PathBuf {
 mod tests {
     use std::fs;
 
+    use std::time::Duration;
+
     use super::{
-        ApprovalMode, ChannelConfigs, GatewayCliFlags, GatewayConfig, GatewaySettings,
-        TelegramConfig,
+        ApprovalMode, ApprovalReminderConfig, ChannelConfigs, GatewayCliFlags, GatewayConfig,
+        GatewayConfigValidationError, GatewaySettings, TelegramConfig, ToolResultDisplay,
     };
 
     #[test]
@@ -998,6 +1645,7 @@ mod tests {
             model: None,
             auto_approve: None,
             profile: None,
+            templates: super::MessageTemplates::default(),
         });
         config.gateway.approval_mode = ApprovalMode::Allowlist;
         config.gateway.approval_allowlist.clear();
@@ -1035,9 +1683,13 @@ mod tests {
                     model: None,
                     auto_approve: None,
                     profile: None,
+                    templates: super::MessageTemplates::default(),
                 }),
                 discord: None,
                 slack: None,
+                whatsapp: None,
+                matrix: None,
+                rocketchat: None,
             },
             gateway: GatewaySettings {
                 title_template: "{channel}:{chat_type}:{peer}".to_string(),
@@ -1059,9 +1711,13 @@ mod tests {
                 model: Some("anthropic/claude-sonnet-4-5".to_string()),
                 auto_approve: Some(vec!["view".to_string(), "  ".to_string()]),
                 profile: Some("prod".to_string()),
+                templates: super::MessageTemplates::default(),
             }),
             discord: None,
             slack: None,
+            whatsapp: None,
+            matrix: None,
+            rocketchat: None,
         };
 
         let overrides = channels.overrides_map();
@@ -1090,9 +1746,13 @@ mod tests {
                 model: None,
                 auto_approve: Some(vec![]),
                 profile: None,
+                templates: super::MessageTemplates::default(),
             }),
             discord: None,
             slack: None,
+            whatsapp: None,
+            matrix: None,
+            rocketchat: None,
         };
 
         let overrides = channels.overrides_for("telegram");
@@ -1112,9 +1772,13 @@ mod tests {
                 model: None,
                 auto_approve: Some(vec!["  ".to_string(), "".to_string()]),
                 profile: None,
+                templates: super::MessageTemplates::default(),
             }),
             discord: None,
             slack: None,
+            whatsapp: None,
+            matrix: None,
+            rocketchat: None,
         };
 
         let overrides = channels.overrides_for("telegram");
@@ -1134,6 +1798,7 @@ mod tests {
             model: Some("anthropic/claude-sonnet-4-5".to_string()),
             auto_approve: None,
             profile: None,
+            templates: super::MessageTemplates::default(),
         });
 
         let warnings = config.check_deprecations();
@@ -1150,6 +1815,11 @@ mod tests {
             model: Some("anthropic/claude-sonnet-4-5".to_string()),
             auto_approve: None,
             profile: Some("ops".to_string()),
+            table_rendering: super::SlackTableRendering::default(),
+            block_format: super::SlackBlockFormat::default(),
+            mentions: super::SlackMentions::default(),
+            templates: super::MessageTemplates::default(),
+            cancel_reaction: None,
         });
 
         let warnings = config.check_deprecations();
@@ -1166,6 +1836,8 @@ mod tests {
             model: None,
             auto_approve: None,
             profile: Some("monitoring".to_string()),
+            templates: super::MessageTemplates::default(),
+            cancel_reaction: None,
         });
 
         let warnings = config.check_deprecations();
@@ -1181,9 +1853,13 @@ mod tests {
                 model: None,
                 auto_approve: None,
                 profile: Some("monitoring".to_string()),
+                templates: super::MessageTemplates::default(),
             }),
             discord: None,
             slack: None,
+            whatsapp: None,
+            matrix: None,
+            rocketchat: None,
         };
 
         let profiles = channels.profiles_map();
@@ -1270,6 +1946,7 @@ chat_id = "#ops"
             model: None,
             auto_approve: None,
             profile: None,
+            templates: super::MessageTemplates::default(),
         });
 
         let save_result = config.save(&path);
@@ -1289,4 +1966,388 @@ chat_id = "#ops"
         assert!(reloaded.contains("url = \"http://127.0.0.1:5001\""));
         assert!(reloaded.contains("token = \"secret-token\""));
     }
+
+    #[test]
+    fn approval_reminders_default_to_reping_without_escalation() {
+        let reminders = ApprovalReminderConfig::default();
+        let policy = reminders.reminder_policy();
+
+        assert_eq!(policy.bell_after, None);
+        assert_eq!(policy.reping_after, Some(Duration::from_secs(600)));
+        assert_eq!(policy.escalate_after, None);
+    }
+
+    #[test]
+    fn max_concurrent_runs_defaults_to_serial_and_clamps_zero() {
+        let dir = match tempfile::tempdir() {
+            Ok(value) => value,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = dir.path().join("autopilot.toml");
+        assert_eq!(
+            GatewaySettings::default().max_concurrent_runs_per_session,
+            1
+        );
+
+        for (value, expected) in [(3, 3), (0, 1)] {
+            let write_result = fs::write(
+                &path,
+                format!(
+                    "[gateway]\nmax_concurrent_runs_per_session = {value}\n\n[channels.telegram]\ntoken = \"123:ABC\"\n"
+                ),
+            );
+            assert!(write_result.is_ok());
+
+            let config = match GatewayConfig::load(&path, &GatewayCliFlags::default()) {
+                Ok(value) => value,
+                Err(error) => panic!("failed to load config: {error}"),
+            };
+            assert_eq!(config.gateway.max_concurrent_runs_per_session, expected);
+        }
+    }
+
+    #[test]
+    fn tool_results_default_to_rendered() {
+        let dir = match tempfile::tempdir() {
+            Ok(value) => value,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = dir.path().join("autopilot.toml");
+        assert_eq!(
+            GatewaySettings::default().tool_results,
+            ToolResultDisplay::Rendered
+        );
+
+        for (value, expected) in [
+            ("off", ToolResultDisplay::Off),
+            ("all", ToolResultDisplay::All),
+        ] {
+            let write_result = fs::write(
+                &path,
+                format!(
+                    "[gateway]\ntool_results = \"{value}\"\n\n[channels.telegram]\ntoken = \"123:ABC\"\n"
+                ),
+            );
+            assert!(write_result.is_ok());
+
+            let config = match GatewayConfig::load(&path, &GatewayCliFlags::default()) {
+                Ok(value) => value,
+                Err(error) => panic!("failed to load config: {error}"),
+            };
+            assert_eq!(config.gateway.tool_results, expected);
+        }
+    }
+
+    #[test]
+    fn usage_footer_defaults_on_and_can_be_disabled() {
+        let dir = match tempfile::tempdir() {
+            Ok(value) => value,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = dir.path().join("autopilot.toml");
+        assert!(GatewaySettings::default().usage_footer);
+
+        let write_result = fs::write(
+            &path,
+            "[gateway]\nusage_footer = false\n\n[channels.telegram]\ntoken = \"123:ABC\"\n",
+        );
+        assert!(write_result.is_ok());
+
+        let config = match GatewayConfig::load(&path, &GatewayCliFlags::default()) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        assert!(!config.gateway.usage_footer);
+    }
+
+    #[test]
+    fn approval_rules_load_and_save() {
+        let dir = match tempfile::tempdir() {
+            Ok(value) => value,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = dir.path().join("autopilot.toml");
+
+        let write_result = fs::write(
+            &path,
+            "[gateway.approval_rules]\nallow = [\"kubectl get *\"]\ndeny = [\"rm -rf *\"]\n\n[channels.telegram]\ntoken = \"123:ABC\"\n",
+        );
+        assert!(write_result.is_ok());
+
+        let mut config = match GatewayConfig::load(&path, &GatewayCliFlags::default()) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        assert_eq!(config.gateway.approval_rules.allow, vec!["kubectl get *"]);
+        assert_eq!(config.gateway.approval_rules.deny, vec!["rm -rf *"]);
+        assert!(config.gateway.approval_rules.require_approval.is_empty());
+
+        config
+            .gateway
+            .approval_rules
+            .require_approval
+            .push("terraform apply".to_string());
+        assert!(config.save(&path).is_ok());
+        let reloaded = match GatewayConfig::load(&path, &GatewayCliFlags::default()) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        assert_eq!(
+            reloaded.gateway.approval_rules,
+            config.gateway.approval_rules
+        );
+    }
+
+    #[test]
+    fn message_templates_load_and_save_per_channel() {
+        let dir = match tempfile::tempdir() {
+            Ok(value) => value,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = dir.path().join("autopilot.toml");
+
+        let write_result = fs::write(
+            &path,
+            "[gateway.templates]\nrun_failed = \"Run failed: {summary}\"\n\n[channels.telegram]\ntoken = \"123:ABC\"\n\n[channels.telegram.templates]\nrun_completed = \"Done in {duration}\"\n",
+        );
+        assert!(write_result.is_ok());
+
+        let config = match GatewayConfig::load(&path, &GatewayCliFlags::default()) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        assert_eq!(
+            config.gateway.templates.run_failed.as_deref(),
+            Some("Run failed: {summary}")
+        );
+        let channel_templates = config.channels.templates_map();
+        assert_eq!(channel_templates.len(), 1);
+        assert_eq!(
+            channel_templates["telegram"].run_completed.as_deref(),
+            Some("Done in {duration}")
+        );
+
+        assert!(config.save(&path).is_ok());
+        let reloaded = match GatewayConfig::load(&path, &GatewayCliFlags::default()) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        assert_eq!(reloaded.gateway.templates, config.gateway.templates);
+        assert_eq!(reloaded.channels.templates_map(), channel_templates);
+    }
+
+    #[test]
+    fn whatsapp_channel_loads_template_and_validates_listen() {
+        let dir = match tempfile::tempdir() {
+            Ok(value) => value,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = dir.path().join("autopilot.toml");
+
+        let write_result = fs::write(
+            &path,
+            r##"
+[channels.whatsapp]
+access_token = "EAAG-token"
+phone_number_id = "1234567890"
+verify_token = "verify-me"
+app_secret = "app-secret"
+profile = "field-ops"
+
+[channels.whatsapp.template]
+name = "ops_update"
+body_text = true
+"##,
+        );
+        assert!(write_result.is_ok());
+
+        let config = match GatewayConfig::load(&path, &GatewayCliFlags::default()) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        let whatsapp = config.channels.whatsapp.as_ref().expect("whatsapp channel");
+        assert_eq!(whatsapp.listen, "127.0.0.1:4099");
+        assert_eq!(whatsapp.app_secret, "app-secret");
+        let template = whatsapp.template.as_ref().expect("template");
+        assert_eq!(template.name, "ops_update");
+        assert_eq!(template.language, "en_US");
+        assert!(template.body_text);
+        assert!(config.enabled_channels().contains(&"whatsapp"));
+        assert_eq!(
+            config
+                .channels
+                .profiles_map()
+                .get("whatsapp")
+                .map(String::as_str),
+            Some("field-ops")
+        );
+
+        let mut invalid = config.clone();
+        if let Some(whatsapp) = invalid.channels.whatsapp.as_mut() {
+            whatsapp.listen = "localhost".to_string();
+        }
+        assert_eq!(
+            invalid.validate_with_error(),
+            Err(GatewayConfigValidationError::InvalidWhatsAppListen(
+                "localhost".to_string()
+            ))
+        );
+
+        let mut unsigned = config.clone();
+        if let Some(whatsapp) = unsigned.channels.whatsapp.as_mut() {
+            whatsapp.app_secret = "  ".to_string();
+        }
+        assert_eq!(
+            unsigned.validate_with_error(),
+            Err(GatewayConfigValidationError::EmptyWhatsAppAppSecret)
+        );
+    }
+
+    #[test]
+    fn matrix_channel_loads_rooms_and_requires_homeserver() {
+        let dir = match tempfile::tempdir() {
+            Ok(value) => value,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = dir.path().join("autopilot.toml");
+
+        let write_result = fs::write(
+            &path,
+            r##"
+[channels.matrix]
+homeserver = "https://matrix.example.org"
+access_token = "syt_token"
+rooms = ["!ops:example.org"]
+auto_approve = ["kubectl get *"]
+"##,
+        );
+        assert!(write_result.is_ok());
+
+        let config = match GatewayConfig::load(&path, &GatewayCliFlags::default()) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        let matrix = config.channels.matrix.as_ref().expect("matrix channel");
+        assert_eq!(matrix.rooms, vec!["!ops:example.org"]);
+        assert!(!matrix.auto_join);
+        assert!(config.enabled_channels().contains(&"matrix"));
+        assert_eq!(
+            config.channels.overrides_for("matrix").approval_mode,
+            Some(ApprovalMode::Allowlist)
+        );
+
+        let mut invalid = config.clone();
+        if let Some(matrix) = invalid.channels.matrix.as_mut() {
+            matrix.homeserver = " ".to_string();
+        }
+        assert_eq!(
+            invalid.validate_with_error(),
+            Err(GatewayConfigValidationError::EmptyMatrixHomeserver)
+        );
+    }
+
+    #[test]
+    fn rocketchat_channel_loads_and_requires_credentials() {
+        let dir = match tempfile::tempdir() {
+            Ok(value) => value,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = dir.path().join("autopilot.toml");
+
+        let write_result = fs::write(
+            &path,
+            r##"
+[channels.rocketchat]
+server_url = "https://chat.example.org"
+user_id = "botuserid"
+auth_token = "pat-token"
+profile = "ops"
+"##,
+        );
+        assert!(write_result.is_ok());
+
+        let config = match GatewayConfig::load(&path, &GatewayCliFlags::default()) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        let rocketchat = config
+            .channels
+            .rocketchat
+            .as_ref()
+            .expect("rocketchat channel");
+        assert_eq!(rocketchat.server_url, "https://chat.example.org");
+        assert!(config.enabled_channels().contains(&"rocketchat"));
+        assert_eq!(
+            config
+                .channels
+                .profiles_map()
+                .get("rocketchat")
+                .map(String::as_str),
+            Some("ops")
+        );
+
+        let mut invalid = config.clone();
+        if let Some(rocketchat) = invalid.channels.rocketchat.as_mut() {
+            rocketchat.user_id = String::new();
+        }
+        assert_eq!(
+            invalid.validate_with_error(),
+            Err(GatewayConfigValidationError::EmptyRocketChatUserId)
+        );
+    }
+
+    #[test]
+    fn approval_reminders_load_escalation_target() {
+        let dir = match tempfile::tempdir() {
+            Ok(value) => value,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let path = dir.path().join("autopilot.toml");
+
+        let write_result = fs::write(
+            &path,
+            r##"
+[gateway.approval_reminders]
+reping_after_minutes = 5
+escalate_after_minutes = 20
+escalate_channel = "telegram"
+escalate_target = { chat_id = 12345 }
+
+[channels.telegram]
+token = "123:ABC"
+"##,
+        );
+        assert!(write_result.is_ok());
+
+        let config = match GatewayConfig::load(&path, &GatewayCliFlags::default()) {
+            Ok(value) => value,
+            Err(error) => panic!("failed to load config: {error}"),
+        };
+        let policy = config.gateway.approval_reminders.reminder_policy();
+        assert_eq!(policy.reping_after, Some(Duration::from_secs(300)));
+        assert_eq!(policy.escalate_after, Some(Duration::from_secs(1200)));
+    }
+
+    #[test]
+    fn validate_rejects_unknown_escalation_channel() {
+        let mut config = GatewayConfig::default();
+        config.channels.telegram = Some(TelegramConfig {
+            token: "abc".to_string(),
+            require_mention: false,
+            model: None,
+            auto_approve: None,
+            profile: None,
+            templates: super::MessageTemplates::default(),
+        });
+        config.gateway.approval_reminders.escalate_channel = Some("slack".to_string());
+        config.gateway.approval_reminders.escalate_target =
+            Some(serde_json::json!({"channel": "#oncall"}));
+
+        assert_eq!(
+            config.validate_with_error(),
+            Err(GatewayConfigValidationError::UnknownEscalationChannel(
+                "slack".to_string()
+            ))
+        );
+    }
 }
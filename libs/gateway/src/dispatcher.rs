@@ -1,6 +1,9 @@
 use std::{
     collections::{HashMap, HashSet},
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
     time::{Duration, Instant},
 };
 
@@ -12,19 +15,28 @@ use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use stakpak_shared::utils::truncate_chars_with_ellipsis;
+use stakpak_shared::error::{ErrorKind, StakpakError};
+use stakpak_shared::pending_interactions::{
+    InteractionKind, PendingInteractionTracker, ReminderPolicy, ReminderStage, format_waiting,
+};
+use stakpak_shared::utils::{strip_tool_name, truncate_chars_with_ellipsis};
+use stakpak_shell_tool_approvals::ApprovalRules;
 
 use crate::{
+    approval::{ApprovalPolicy, GatewayApprovalAction, RunApproval, ToolCallApproval},
+    audit::{self, AuditDecision, AuditEntry, AuditSource},
     channels::{ApprovalButton, ButtonStyle, Channel},
     client::{
-        AutoApproveOverride, CallerContextInput, MessageType, RunErrorPayload, RunOverrides,
-        SendMessageOptions, StakpakClient, ToolCallsProposedPayload, ToolDecisionAction,
-        ToolDecisionInput,
+        CallerContextInput, MessageType, RunErrorPayload, RunOverrides, SendMessageOptions,
+        SseEvent, StakpakClient, ToolDecisionAction, ToolDecisionInput, format_usage,
     },
-    config::{ApprovalMode, ChannelOverrides},
+    config::{ApprovalMode, ApprovalReminderConfig, ChannelOverrides, ToolResultDisplay},
     router::{RouterConfig, resolve_routing_key},
-    store::{GatewayStore, SessionMapping},
-    targeting::{render_title_template, target_key_from_inbound},
+    schedules::{ScheduleCommand, ScheduleControl, is_schedule_controller},
+    store::{SessionMapping, StoreBackend},
+    targeting::{ChannelTarget, render_title_template, target_key_from_inbound},
+    templates::{MessageEvent, MessageTemplates, TemplateVars},
+    tool_renderers::{ToolResultRenderers, ToolResultView},
     types::{ChatType, DeliveryContext, InboundMessage, OutboundReply, PeerId},
 };
 
@@ -48,32 +60,154 @@ pub fn noop_run_override_resolver() -> Arc<dyn RunOverrideResolver> {
 pub struct Dispatcher {
     client: StakpakClient,
     channels: HashMap<String, Arc<dyn Channel>>,
-    store: Arc<GatewayStore>,
+    store: Arc<dyn StoreBackend>,
     router_config: RouterConfig,
     // TODO: persist dispatcher state (active_runs, pending_queues, event_cursors) to store
     // for crash recovery. Current behavior relies on watch-side reconciler for eventual
     // consistency after gateway restart.
+    // Keyed by run_id; a session has more than one entry only in fan-in mode.
     active_runs: Mutex<HashMap<String, ActiveRun>>,
     pending_queues: Mutex<HashMap<String, Vec<QueuedMessage>>>,
     // Invariant: at most one pending approval batch per session.
     // The run stream pauses on `tool_calls_proposed` until decisions are submitted.
     pending_approvals: Mutex<HashMap<String, PendingApproval>>,
-    event_cursors: Mutex<HashMap<String, u64>>,
+    // Keyed by approval_id; synced from `pending_approvals` on every reminder tick.
+    approval_reminders: Mutex<PendingInteractionTracker<String>>,
+    approval_reminder_config: ApprovalReminderConfig,
+    event_cursors: Mutex<EventCursors>,
+    // Runs allowed in flight per session; above 1, extra runs use side sessions.
+    max_concurrent_runs: usize,
+    // Set while the agent server is unreachable; inbound messages are held in
+    // the store and replayed in order once it responds again.
+    offline: AtomicBool,
+    // Targets already told about the outage, cleared on reconnect.
+    offline_notified: Mutex<HashSet<String>>,
+    // Keyed by code; issued by `/link` and redeemed once from another chat.
+    link_codes: Mutex<HashMap<String, LinkCode>>,
+    // Runs `/autopilot` commands; without one they are refused.
+    schedule_control: Option<Arc<dyn ScheduleControl>>,
+    // Users allowed to run `/autopilot` commands.
+    schedule_control_allowlist: Vec<String>,
     default_model: Option<String>,
-    approval_mode: ApprovalMode,
-    approval_allowlist: HashSet<String>,
+    approval: ApprovalPolicy,
     channel_overrides: HashMap<String, ChannelOverrides>,
-    channel_profiles: HashMap<String, String>,
-    override_resolver: Arc<dyn RunOverrideResolver>,
     title_template: String,
+    tool_results: ToolResultDisplay,
+    tool_renderers: Arc<ToolResultRenderers>,
+    usage_footer: bool,
+    message_templates: MessageTemplates,
+    channel_templates: HashMap<String, MessageTemplates>,
+}
+
+/// A `/link` code that lets another chat continue a session.
+#[derive(Debug, Clone)]
+struct LinkCode {
+    session_id: String,
+    title: String,
+    /// Routing key of the chat that issued the code.
+    routing_key: String,
+    /// Where to tell the issuing chat that the session was linked.
+    delivery: DeliveryContext,
+    expires_at: Instant,
 }
 
 #[derive(Debug, Clone)]
 struct ActiveRun {
-    run_id: String,
+    /// Session whose queue the run drains when it finishes.
+    session_id: String,
+    /// Server session the run executes in; differs from `session_id` for side runs.
+    run_session_id: String,
     cancel: CancellationToken,
-    approval_mode: ApprovalMode,
-    approval_allowlist: HashSet<String>,
+    approval: RunApproval,
+    attribution: Option<RunAttribution>,
+    templates: RunTemplates,
+}
+
+/// A run's message templates with the values fixed when it started.
+#[derive(Debug, Clone)]
+struct RunTemplates {
+    templates: MessageTemplates,
+    session_title: String,
+    started_at: Instant,
+}
+
+impl Default for RunTemplates {
+    fn default() -> Self {
+        Self {
+            templates: MessageTemplates::default(),
+            session_title: String::new(),
+            started_at: Instant::now(),
+        }
+    }
+}
+
+/// Quote of the message that started a run, prefixed to its replies while
+/// other runs of the same session are in flight so answers can be told apart.
+#[derive(Debug, Clone)]
+struct RunAttribution {
+    quote: String,
+    // Shared by every run of the session.
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl RunAttribution {
+    fn apply(&self, text: String) -> String {
+        if self.in_flight.load(Ordering::Relaxed) > 1 {
+            format!("{}\n{text}", self.quote)
+        } else {
+            text
+        }
+    }
+}
+
+/// Event stream positions. Each run advances its own cursor so concurrent runs
+/// never move each other's replay point; a finished run folds its cursor into
+/// the session's high-water mark, where the next run in that session starts.
+#[derive(Debug, Default)]
+struct EventCursors {
+    runs: HashMap<String, u64>,
+    sessions: HashMap<String, u64>,
+}
+
+impl EventCursors {
+    fn advance_run(&mut self, run_id: &str, cursor: u64) {
+        let entry = self.runs.entry(run_id.to_string()).or_insert(cursor);
+        *entry = (*entry).max(cursor);
+    }
+
+    fn finish_run(&mut self, session_id: &str, run_id: &str, cursor: Option<u64>) {
+        let run_cursor = self.runs.remove(run_id);
+        let Some(cursor) = cursor.max(run_cursor) else {
+            return;
+        };
+        let entry = self
+            .sessions
+            .entry(session_id.to_string())
+            .or_insert(cursor);
+        *entry = (*entry).max(cursor);
+    }
+
+    fn session(&self, session_id: &str) -> Option<u64> {
+        self.sessions.get(session_id).copied()
+    }
+}
+
+/// Event stream positions the dispatcher holds for one session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionCursors {
+    /// High-water mark of the session's finished runs; its next run starts here.
+    pub session: Option<u64>,
+    /// Runs still in flight, ordered by run id.
+    pub runs: Vec<RunCursor>,
+}
+
+/// Position of an in-flight run in the event stream of the server session it runs in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunCursor {
+    pub run_id: String,
+    pub run_session_id: String,
+    /// Last event the run has seen, `None` before its first event.
+    pub cursor: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -88,6 +222,9 @@ struct PendingApproval {
     cursor: Option<u64>,
     timeout_seconds: Option<u64>,
     requested_at: Instant,
+    /// Run owned by another consumer (e.g. a paused autopilot run): deciding
+    /// only resolves the tool calls and does not resume streaming here.
+    detached: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -141,6 +278,31 @@ struct RunContext {
     session_id: String,
     run_id: String,
     timeout_seconds: Option<u64>,
+    attribution: Option<RunAttribution>,
+    tool_results: ToolResultDisplay,
+    tool_renderers: Arc<ToolResultRenderers>,
+    usage_footer: bool,
+    templates: RunTemplates,
+    /// Arguments of the run's proposed tool calls, keyed by tool call id, so
+    /// results can be rendered with the call that produced them.
+    tool_call_args: HashMap<String, serde_json::Value>,
+}
+
+impl RunContext {
+    /// The templated message for `event`, or `None` when none is configured.
+    fn render_message(&self, event: MessageEvent, vars: TemplateVars<'_>) -> Option<String> {
+        self.templates.templates.render(
+            event,
+            &TemplateVars {
+                session_title: &self.templates.session_title,
+                session_id: &self.session_id,
+                run_id: &self.run_id,
+                channel: &self.delivery.channel.0,
+                duration: self.templates.started_at.elapsed(),
+                ..vars
+            },
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -171,7 +333,7 @@ enum RunOutcome {
 
 #[derive(Debug)]
 struct ResolveApprovalError {
-    message: String,
+    error: StakpakError,
     decision_sent: bool,
 }
 
@@ -180,7 +342,7 @@ impl Dispatcher {
     pub fn new(
         client: StakpakClient,
         channels: HashMap<String, Arc<dyn Channel>>,
-        store: Arc<GatewayStore>,
+        store: Arc<dyn StoreBackend>,
         router_config: RouterConfig,
         default_model: Option<String>,
         approval_mode: ApprovalMode,
@@ -196,14 +358,30 @@ impl Dispatcher {
             active_runs: Mutex::new(HashMap::new()),
             pending_queues: Mutex::new(HashMap::new()),
             pending_approvals: Mutex::new(HashMap::new()),
-            event_cursors: Mutex::new(HashMap::new()),
+            approval_reminders: Mutex::new(PendingInteractionTracker::new(
+                ReminderPolicy::disabled(),
+            )),
+            approval_reminder_config: ApprovalReminderConfig::default(),
+            event_cursors: Mutex::new(EventCursors::default()),
+            max_concurrent_runs: 1,
+            offline: AtomicBool::new(false),
+            offline_notified: Mutex::new(HashSet::new()),
+            link_codes: Mutex::new(HashMap::new()),
+            schedule_control: None,
+            schedule_control_allowlist: Vec::new(),
             default_model,
-            approval_mode,
-            approval_allowlist: approval_allowlist.into_iter().collect(),
+            approval: ApprovalPolicy::new(
+                approval_mode,
+                approval_allowlist,
+                channel_overrides.clone(),
+            ),
             channel_overrides,
-            channel_profiles: HashMap::new(),
-            override_resolver: Arc::new(NoopRunOverrideResolver),
             title_template,
+            tool_results: ToolResultDisplay::default(),
+            tool_renderers: Arc::new(ToolResultRenderers::default()),
+            usage_footer: false,
+            message_templates: MessageTemplates::default(),
+            channel_templates: HashMap::new(),
         }
     }
 
@@ -212,8 +390,72 @@ impl Dispatcher {
         channel_profiles: HashMap<String, String>,
         override_resolver: Arc<dyn RunOverrideResolver>,
     ) -> Self {
-        self.channel_profiles = channel_profiles;
-        self.override_resolver = override_resolver;
+        self.approval = self
+            .approval
+            .with_profile_resolution(channel_profiles, override_resolver);
+        self
+    }
+
+    /// Settle proposed tool calls by `rules` before the approval mode.
+    pub fn with_approval_rules(mut self, rules: ApprovalRules) -> Self {
+        self.approval = self.approval.with_rules(rules);
+        self
+    }
+
+    pub fn with_approval_reminders(mut self, config: ApprovalReminderConfig) -> Self {
+        self.approval_reminders =
+            Mutex::new(PendingInteractionTracker::new(config.reminder_policy()));
+        self.approval_reminder_config = config;
+        self
+    }
+
+    /// Allow up to `max` runs per session at once (fan-in). Messages arriving
+    /// while the session is busy start a parallel run in a side session instead
+    /// of queueing, until `max` runs are in flight.
+    pub fn with_max_concurrent_runs(mut self, max: usize) -> Self {
+        self.max_concurrent_runs = max.max(1);
+        self
+    }
+
+    /// Post finished tool calls to the chat per `display`, using `renderers`
+    /// before the generic renderer.
+    pub fn with_tool_results(
+        mut self,
+        display: ToolResultDisplay,
+        renderers: ToolResultRenderers,
+    ) -> Self {
+        self.tool_results = display;
+        self.tool_renderers = Arc::new(renderers);
+        self
+    }
+
+    /// End each run's reply with its token usage and estimated cost.
+    pub fn with_usage_footer(mut self, enabled: bool) -> Self {
+        self.usage_footer = enabled;
+        self
+    }
+
+    /// Replace built-in run messages with `templates`, which
+    /// `channel_templates` override per channel name.
+    pub fn with_message_templates(
+        mut self,
+        templates: MessageTemplates,
+        channel_templates: HashMap<String, MessageTemplates>,
+    ) -> Self {
+        self.message_templates = templates;
+        self.channel_templates = channel_templates;
+        self
+    }
+
+    /// Carry out `/autopilot` commands from users on `allowlist` with
+    /// `control`.
+    pub fn with_schedule_control(
+        mut self,
+        control: Arc<dyn ScheduleControl>,
+        allowlist: Vec<String>,
+    ) -> Self {
+        self.schedule_control = Some(control);
+        self.schedule_control_allowlist = allowlist;
         self
     }
 
@@ -223,6 +465,20 @@ impl Dispatcher {
         cancel: CancellationToken,
     ) {
         let (run_tx, mut run_rx) = mpsc::channel::<RunTaskResult>(128);
+        let mut reminder_interval = tokio::time::interval(APPROVAL_REMINDER_TICK);
+        reminder_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut offline_interval = tokio::time::interval(OFFLINE_PROBE_TICK);
+        offline_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        // Messages held during a previous outage are replayed on the first probe.
+        match self.store.count_offline_inbound().await {
+            Ok(0) => {}
+            Ok(count) => {
+                info!(count, "found inbound messages held while offline");
+                self.offline.store(true, Ordering::Relaxed);
+            }
+            Err(error) => warn!(error = %error, "failed to count offline inbound messages"),
+        }
 
         loop {
             tokio::select! {
@@ -230,12 +486,21 @@ impl Dispatcher {
                     self.cancel_all_runs().await;
                     break;
                 }
+                _ = reminder_interval.tick() => {
+                    self.send_approval_reminders().await;
+                }
+                _ = offline_interval.tick() => {
+                    self.replay_offline_inbound(run_tx.clone()).await;
+                }
                 maybe_inbound = inbound_rx.recv() => {
                     let Some(inbound) = maybe_inbound else {
                         break;
                     };
+                    let delivery = self.delivery_context_from_inbound(&inbound);
                     if let Err(error) = self.handle_inbound(inbound, run_tx.clone()).await {
-                        error!(error = %error, "failed to handle inbound message");
+                        error!(error = %error, code = error.code(), "failed to handle inbound message");
+                        deliver_channel_text(&self.channels, &delivery, &render_error_reply(&error))
+                            .await;
                     }
                 }
                 maybe_result = run_rx.recv() => {
@@ -243,18 +508,136 @@ impl Dispatcher {
                         continue;
                     };
                     if let Err(error) = self.handle_run_result(result, run_tx.clone()).await {
-                        error!(error = %error, "failed to handle run result");
+                        error!(error = %error, code = error.code(), "failed to handle run result");
                     }
                 }
             }
         }
     }
 
+    async fn send_approval_reminders(&self) {
+        let due = {
+            let Ok(mut tracker) = self.approval_reminders.lock() else {
+                return;
+            };
+            if !tracker.policy().is_enabled() {
+                return;
+            }
+            let Ok(pending) = self.pending_approvals.lock() else {
+                return;
+            };
+
+            let open: HashMap<&str, &PendingApproval> = pending
+                .values()
+                .map(|approval| (approval.approval_id.as_str(), approval))
+                .collect();
+            tracker.retain(|approval_id| open.contains_key(approval_id.as_str()));
+            for approval in open.values() {
+                tracker.track(
+                    approval.approval_id.clone(),
+                    InteractionKind::Approval,
+                    approval.requested_at,
+                );
+            }
+
+            tracker
+                .due(Instant::now())
+                .into_iter()
+                .filter_map(|reminder| {
+                    open.get(reminder.key.as_str())
+                        .map(|approval| (reminder.stage, reminder.waiting, (*approval).clone()))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for (stage, waiting, approval) in due {
+            let result = match stage {
+                ReminderStage::Reping => self.reping_approval(&approval, waiting).await,
+                ReminderStage::Escalate => self.escalate_approval(&approval, waiting).await,
+                ReminderStage::Bell => Ok(()),
+            };
+            if let Err(error) = result {
+                warn!(
+                    approval_id = %approval.approval_id,
+                    stage = ?stage,
+                    error = %error,
+                    "failed to send approval reminder"
+                );
+            }
+        }
+    }
+
+    async fn reping_approval(
+        &self,
+        approval: &PendingApproval,
+        waiting: Duration,
+    ) -> Result<(), StakpakError> {
+        let channel = self.channels.get(&approval.channel_name).ok_or_else(|| {
+            StakpakError::network(format!("channel '{}' not connected", approval.channel_name))
+        })?;
+
+        let reply = OutboundReply {
+            channel: approval.delivery.channel.clone(),
+            peer_id: approval.delivery.peer_id.clone(),
+            chat_type: approval.delivery.chat_type.clone(),
+            text: format!(
+                "⏰ Approval still waiting ({}) — use the buttons above to allow or deny.",
+                format_waiting(waiting)
+            ),
+            metadata: approval.delivery.channel_meta.clone(),
+        };
+
+        channel
+            .send(reply)
+            .await
+            .map_err(|error| StakpakError::network(error.to_string()))
+    }
+
+    async fn escalate_approval(
+        &self,
+        approval: &PendingApproval,
+        waiting: Duration,
+    ) -> Result<(), StakpakError> {
+        let Some((channel_name, target)) = self.approval_reminder_config.escalation() else {
+            return Ok(());
+        };
+        let channel = self.channels.get(channel_name).ok_or_else(|| {
+            StakpakError::config(format!("escalation channel '{channel_name}' not connected"))
+        })?;
+        let target = ChannelTarget::parse(channel_name, target)
+            .map_err(|error| StakpakError::config(error.to_string()))?;
+
+        let tools = approval
+            .tool_calls
+            .iter()
+            .map(|call| strip_tool_name(&call.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let reply = OutboundReply {
+            channel: channel_name.into(),
+            peer_id: target.peer_id(),
+            chat_type: target.chat_type(),
+            text: format!(
+                "🚨 Approval unanswered for {} on {} ({}): {}",
+                format_waiting(waiting),
+                approval.channel_name,
+                approval.delivery.peer_id,
+                tools
+            ),
+            metadata: target.metadata(),
+        };
+
+        channel
+            .send(reply)
+            .await
+            .map_err(|error| StakpakError::network(error.to_string()))
+    }
+
     async fn handle_inbound(
         self: &Arc<Self>,
         inbound: InboundMessage,
         run_tx: mpsc::Sender<RunTaskResult>,
-    ) -> Result<(), String> {
+    ) -> Result<(), StakpakError> {
         if inbound
             .metadata
             .get("type")
@@ -265,6 +648,26 @@ impl Dispatcher {
             return Ok(());
         }
 
+        if inbound.is_cancel() {
+            self.handle_cancel_request(inbound, run_tx).await?;
+            return Ok(());
+        }
+
+        if let Some(("link", code)) = inbound.command() {
+            self.handle_link_command(&inbound, code).await?;
+            return Ok(());
+        }
+
+        if let Some(("autopilot", args)) = inbound.command() {
+            self.handle_schedule_command(&inbound, args).await;
+            return Ok(());
+        }
+
+        if self.offline.load(Ordering::Relaxed) {
+            self.hold_offline(inbound).await?;
+            return Ok(());
+        }
+
         let routing_key = resolve_routing_key(
             &self.router_config,
             &inbound.channel,
@@ -286,11 +689,10 @@ impl Dispatcher {
             }
         };
 
-        let maybe_mapping = self
-            .store
-            .get(&routing_key)
-            .await
-            .map_err(|error| format!("failed to get mapping: {error}"))?;
+        let maybe_mapping =
+            self.store.get(&routing_key).await.map_err(|error| {
+                StakpakError::internal(format!("failed to get mapping: {error}"))
+            })?;
 
         let mapping = if let Some(mapping) = maybe_mapping {
             let delivery = self.delivery_context_from_inbound(&inbound);
@@ -300,11 +702,20 @@ impl Dispatcher {
             mapping
         } else {
             let title = self.render_title(&inbound);
-            let created = self
-                .client
-                .create_session(&title)
-                .await
-                .map_err(|error| format!("create session failed: {error}"))?;
+            let created = match self.client.create_session(&title).await {
+                Ok(created) => created,
+                Err(error) if error.is_connectivity() => {
+                    warn!(error = %error, "agent server unreachable while creating session");
+                    self.hold_offline(inbound).await?;
+                    return Ok(());
+                }
+                Err(error) => {
+                    return Err(StakpakError::new(
+                        error.kind(),
+                        format!("create session failed: {error}"),
+                    ));
+                }
+            };
 
             let now = Utc::now().timestamp_millis();
             let mapping = SessionMapping {
@@ -317,7 +728,9 @@ impl Dispatcher {
             self.store
                 .set(&routing_key, &mapping)
                 .await
-                .map_err(|error| format!("failed to persist mapping: {error}"))?;
+                .map_err(|error| {
+                    StakpakError::internal(format!("failed to persist mapping: {error}"))
+                })?;
 
             mapping
         };
@@ -330,123 +743,482 @@ impl Dispatcher {
             inbound,
         };
 
-        if self.is_run_active(&mapping.session_id) {
+        if self.active_run_count(&mapping.session_id) >= self.max_concurrent_runs {
             self.enqueue_message(mapping.session_id.clone(), queued)?;
-            self.reject_pending_approval_for_session(&mapping.session_id, run_tx)
-                .await?;
+            // In fan-in mode messages are independent questions, so a new one
+            // does not supersede a run waiting on approval.
+            if self.max_concurrent_runs == 1 {
+                self.reject_pending_approval_for_session(&mapping.session_id, run_tx)
+                    .await?;
+            }
             return Ok(());
         }
 
-        self.start_run(mapping.session_id, queued, run_tx).await
+        self.start_run(mapping.session_id, queued, run_tx).await?;
+        Ok(())
     }
 
-    async fn handle_approval_response(
+    /// Hold `inbound` in the store until the agent server is reachable again,
+    /// telling each target once per outage.
+    /// Cancel the active runs of the conversation `inbound` belongs to, for a
+    /// cancel reaction or a `/cancel` message.
+    async fn handle_cancel_request(
         self: &Arc<Self>,
         inbound: InboundMessage,
         run_tx: mpsc::Sender<RunTaskResult>,
-    ) -> Result<(), String> {
-        let approval_id = inbound
-            .metadata
-            .get("approval_id")
-            .and_then(|value| value.as_str())
-            .unwrap_or_default()
-            .to_string();
-        let decision = inbound
-            .metadata
-            .get("decision")
-            .and_then(|value| value.as_str())
-            .unwrap_or_default()
-            .to_string();
-
-        if approval_id.is_empty() || !matches!(decision.as_str(), "allow" | "deny") {
-            warn!(
-                approval_id = %approval_id,
-                decision = %decision,
-                "ignoring invalid approval callback"
-            );
-            return Ok(());
-        }
+    ) -> Result<(), StakpakError> {
+        let routing_key = resolve_routing_key(
+            &self.router_config,
+            &inbound.channel,
+            &inbound.peer_id,
+            &inbound.chat_type,
+        );
+        let mapping =
+            self.store.get(&routing_key).await.map_err(|error| {
+                StakpakError::internal(format!("failed to get mapping: {error}"))
+            })?;
 
-        let pending = {
-            let mut guard = self
-                .pending_approvals
+        let runs: Vec<(String, String)> = match &mapping {
+            Some(mapping) => self
+                .active_runs
                 .lock()
-                .map_err(|_| "failed to lock pending_approvals".to_string())?;
-
-            let session_id = guard.iter().find_map(|(session_id, pending)| {
-                if pending.approval_id == approval_id {
-                    Some(session_id.clone())
-                } else {
-                    None
-                }
-            });
-
-            session_id.and_then(|session_id| guard.remove(&session_id))
+                .map_err(|_| StakpakError::internal("failed to lock active_runs"))?
+                .iter()
+                .filter(|(_, active)| active.session_id == mapping.session_id)
+                .map(|(run_id, active)| {
+                    active.cancel.cancel();
+                    (run_id.clone(), active.run_session_id.clone())
+                })
+                .collect(),
+            None => Vec::new(),
         };
 
-        let Some(pending) = pending else {
-            warn!(
-                approval_id = %approval_id,
-                "approval already resolved or expired"
-            );
+        let delivery = self.delivery_context_from_inbound(&inbound);
+        if runs.is_empty() {
+            deliver_channel_text(&self.channels, &delivery, NO_ACTIVE_RUN_NOTICE).await;
             return Ok(());
-        };
+        }
+        deliver_channel_text(&self.channels, &delivery, RUN_CANCELLED_NOTICE).await;
+
+        for (run_id, run_session_id) in runs {
+            info!(
+                session_id = %run_session_id,
+                run_id = %run_id,
+                cancelled_by = %inbound.peer_id,
+                "cancelling run"
+            );
+            if let Err(error) = self.client.cancel_run(&run_session_id, &run_id).await {
+                warn!(run_id = %run_id, error = %error, "failed to cancel run on agent server");
+            }
 
-        debug!(
-            approval_id = %approval_id,
-            decision = %decision,
-            resolved_by = %inbound.peer_id.0,
-            "received approval response"
-        );
+            // A run waiting for approval has no consumer watching its token,
+            // so finish it here.
+            let pending = {
+                let mut guard = self
+                    .pending_approvals
+                    .lock()
+                    .map_err(|_| StakpakError::internal("failed to lock pending_approvals"))?;
+                match guard.get(&run_session_id) {
+                    Some(pending) if pending.run_id == run_id => guard.remove(&run_session_id),
+                    _ => None,
+                }
+            };
+            let Some(pending) = pending else {
+                continue;
+            };
 
-        let approve = decision == "allow";
-        if let Err(error) = self
-            .resolve_approval(&pending, approve, &inbound.peer_id, run_tx)
-            .await
-        {
-            if !error.decision_sent
-                && let Ok(mut guard) = self.pending_approvals.lock()
+            audit::record(
+                self.store.as_ref(),
+                AuditEntry {
+                    channel: &pending.channel_name,
+                    session_id: &pending.session_id,
+                    run_id: &pending.run_id,
+                    decision: AuditDecision::Denied,
+                    source: AuditSource::User,
+                    decided_by: &inbound.peer_id.0,
+                    reason: Some("Run cancelled"),
+                },
+                &pending.tool_calls,
+            )
+            .await;
+
+            if let Some(channel) = self.channels.get(&pending.channel_name)
+                && let Err(error) = channel
+                    .edit_message(
+                        &pending.prompt_message_id,
+                        "🛑 Tools skipped — run cancelled",
+                    )
+                    .await
             {
-                guard.insert(pending.session_id.clone(), pending);
+                warn!(error = %error, "failed to edit approval prompt after cancel");
             }
-            return Err(error.message);
+
+            self.handle_run_result(
+                RunTaskResult {
+                    session_id: run_session_id,
+                    run_id,
+                    outcome: RunOutcome::Cancelled {
+                        cursor: pending.cursor,
+                    },
+                },
+                run_tx.clone(),
+            )
+            .await?;
         }
 
         Ok(())
     }
 
-    async fn handle_run_result(
-        self: &Arc<Self>,
-        result: RunTaskResult,
-        run_tx: mpsc::Sender<RunTaskResult>,
-    ) -> Result<(), String> {
-        match result.outcome {
-            RunOutcome::ApprovalNeeded {
-                cursor,
-                session_id,
-                run_id,
-                tool_calls,
-                auto_resolved_count,
-                delivery,
-                timeout_seconds,
-            } => {
-                if let Some(cursor) = cursor {
-                    self.set_cursor(&session_id, cursor)?;
+    /// `/autopilot <command>` controls schedules for users on the schedule
+    /// control allowlist; everyone else gets a refusal.
+    async fn handle_schedule_command(&self, inbound: &InboundMessage, args: &str) {
+        let reply = match &self.schedule_control {
+            None => SCHEDULE_CONTROL_UNAVAILABLE_NOTICE.to_string(),
+            Some(_)
+                if !is_schedule_controller(
+                    &self.schedule_control_allowlist,
+                    &inbound.channel,
+                    &inbound.peer_id,
+                ) =>
+            {
+                warn!(
+                    channel = %inbound.channel,
+                    peer = %inbound.peer_id,
+                    "schedule command refused: sender not on the allowlist"
+                );
+                SCHEDULE_CONTROL_DENIED_NOTICE.to_string()
+            }
+            Some(control) => match ScheduleCommand::parse(args) {
+                Ok(command) => {
+                    info!(
+                        channel = %inbound.channel,
+                        peer = %inbound.peer_id,
+                        command = ?command,
+                        "schedule command"
+                    );
+                    command.execute(control.as_ref()).await
                 }
+                Err(usage) => usage,
+            },
+        };
 
-                self.handle_approval_needed(
-                    ApprovalNeededContext {
-                        session_id,
-                        run_id,
-                        tool_calls,
-                        auto_resolved_count,
-                        delivery,
-                        cursor,
-                        timeout_seconds,
-                    },
-                    run_tx,
-                )
-                .await
+        let delivery = self.delivery_context_from_inbound(inbound);
+        deliver_channel_text(&self.channels, &delivery, reply).await;
+    }
+
+    /// `/link` issues a code for the session of this chat; `/link <code>` in
+    /// another chat, on any channel, binds that chat to the same session.
+    /// Both chats keep their own delivery context, so each run replies where
+    /// its message came from.
+    async fn handle_link_command(
+        &self,
+        inbound: &InboundMessage,
+        code: &str,
+    ) -> Result<(), StakpakError> {
+        let routing_key = resolve_routing_key(
+            &self.router_config,
+            &inbound.channel,
+            &inbound.peer_id,
+            &inbound.chat_type,
+        );
+        let delivery = self.delivery_context_from_inbound(inbound);
+
+        if code.is_empty() {
+            let Some(mapping) = self.store.get(&routing_key).await.map_err(|error| {
+                StakpakError::internal(format!("failed to get mapping: {error}"))
+            })?
+            else {
+                deliver_channel_text(&self.channels, &delivery, LINK_NO_SESSION_NOTICE).await;
+                return Ok(());
+            };
+
+            let code = generate_link_code();
+            {
+                let mut guard = self
+                    .link_codes
+                    .lock()
+                    .map_err(|_| StakpakError::internal("failed to lock link_codes"))?;
+                let now = Instant::now();
+                guard.retain(|_, link| link.expires_at > now);
+                guard.insert(
+                    code.clone(),
+                    LinkCode {
+                        session_id: mapping.session_id,
+                        title: mapping.title,
+                        routing_key,
+                        delivery: delivery.clone(),
+                        expires_at: now + LINK_CODE_TTL,
+                    },
+                );
+            }
+
+            let text = format!(
+                "🔗 Send `/link {code}` from another chat within {} minutes to continue this session there.",
+                LINK_CODE_TTL.as_secs() / 60
+            );
+            deliver_channel_text(&self.channels, &delivery, &text).await;
+            return Ok(());
+        }
+
+        let link = self
+            .link_codes
+            .lock()
+            .map_err(|_| StakpakError::internal("failed to lock link_codes"))?
+            .remove(&code.to_ascii_uppercase())
+            .filter(|link| link.expires_at > Instant::now());
+        let Some(link) = link else {
+            deliver_channel_text(&self.channels, &delivery, LINK_UNKNOWN_CODE_NOTICE).await;
+            return Ok(());
+        };
+        if link.routing_key == routing_key {
+            deliver_channel_text(&self.channels, &delivery, LINK_SAME_CHAT_NOTICE).await;
+            return Ok(());
+        }
+
+        let relinked = self
+            .store
+            .relink(&routing_key, &link.session_id)
+            .await
+            .map_err(|error| {
+                StakpakError::internal(format!("failed to relink routing key: {error}"))
+            })?;
+        if relinked {
+            self.store
+                .update_delivery(&routing_key, &delivery)
+                .await
+                .map_err(|error| {
+                    StakpakError::internal(format!("failed to update delivery context: {error}"))
+                })?;
+        } else {
+            self.store
+                .set(
+                    &routing_key,
+                    &SessionMapping {
+                        session_id: link.session_id.clone(),
+                        title: link.title.clone(),
+                        delivery: delivery.clone(),
+                        created_at: Utc::now().timestamp_millis(),
+                    },
+                )
+                .await
+                .map_err(|error| {
+                    StakpakError::internal(format!("failed to persist mapping: {error}"))
+                })?;
+        }
+
+        info!(
+            session_id = %link.session_id,
+            from = %link.routing_key,
+            to = %routing_key,
+            "linked chat to session"
+        );
+        deliver_channel_text(
+            &self.channels,
+            &delivery,
+            &format!("🔗 Linked. This chat now continues \"{}\".", link.title),
+        )
+        .await;
+        deliver_channel_text(
+            &self.channels,
+            &link.delivery,
+            &format!(
+                "🔗 This session is now also continued from {}.",
+                inbound.channel
+            ),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    async fn hold_offline(&self, inbound: InboundMessage) -> Result<(), StakpakError> {
+        if !self.offline.swap(true, Ordering::Relaxed) {
+            warn!("agent server unreachable; holding inbound messages until it reconnects");
+        }
+
+        self.store
+            .enqueue_offline_inbound(&inbound)
+            .await
+            .map_err(|error| {
+                StakpakError::internal(format!("failed to hold offline message: {error}"))
+            })?;
+
+        let target = format!(
+            "{}:{}",
+            inbound.channel.0,
+            target_key_from_inbound(&inbound)
+        );
+        let first_notice = self
+            .offline_notified
+            .lock()
+            .map_err(|_| StakpakError::internal("failed to lock offline_notified"))?
+            .insert(target);
+        if first_notice {
+            deliver_channel_text(
+                &self.channels,
+                &self.delivery_context_from_inbound(&inbound),
+                OFFLINE_NOTICE,
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+
+    /// Probe the agent server while offline and, once it answers, replay held
+    /// messages in the order they were received.
+    async fn replay_offline_inbound(self: &Arc<Self>, run_tx: mpsc::Sender<RunTaskResult>) {
+        if !self.offline.load(Ordering::Relaxed) {
+            return;
+        }
+        if !matches!(
+            tokio::time::timeout(OFFLINE_PROBE_TIMEOUT, self.client.health()).await,
+            Ok(Ok(_))
+        ) {
+            return;
+        }
+
+        let held = match self.store.list_offline_inbound().await {
+            Ok(held) => held,
+            Err(error) => {
+                warn!(error = %error, "failed to load offline inbound messages");
+                return;
+            }
+        };
+
+        self.offline.store(false, Ordering::Relaxed);
+        if let Ok(mut notified) = self.offline_notified.lock() {
+            notified.clear();
+        }
+        info!(
+            count = held.len(),
+            "agent server reachable again; replaying held messages"
+        );
+
+        for (id, inbound) in held {
+            if let Err(error) = self.store.delete_offline_inbound(id).await {
+                // Retry on the next probe rather than replaying it twice.
+                warn!(error = %error, "failed to release offline inbound message");
+                self.offline.store(true, Ordering::Relaxed);
+                return;
+            }
+            if let Err(error) = self.handle_inbound(inbound, run_tx.clone()).await {
+                error!(error = %error, "failed to replay offline inbound message");
+            }
+            // Lost the server again: the rest stays held, in order, for the next probe.
+            if self.offline.load(Ordering::Relaxed) {
+                return;
+            }
+        }
+    }
+
+    async fn handle_approval_response(
+        self: &Arc<Self>,
+        inbound: InboundMessage,
+        run_tx: mpsc::Sender<RunTaskResult>,
+    ) -> Result<(), StakpakError> {
+        let approval_id = inbound
+            .metadata
+            .get("approval_id")
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let decision = inbound
+            .metadata
+            .get("decision")
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        if approval_id.is_empty() || !matches!(decision.as_str(), "allow" | "deny") {
+            warn!(
+                approval_id = %approval_id,
+                decision = %decision,
+                "ignoring invalid approval callback"
+            );
+            return Ok(());
+        }
+
+        let pending = {
+            let mut guard = self
+                .pending_approvals
+                .lock()
+                .map_err(|_| StakpakError::internal("failed to lock pending_approvals"))?;
+
+            let session_id = guard.iter().find_map(|(session_id, pending)| {
+                if pending.approval_id == approval_id {
+                    Some(session_id.clone())
+                } else {
+                    None
+                }
+            });
+
+            session_id.and_then(|session_id| guard.remove(&session_id))
+        };
+
+        let Some(pending) = pending else {
+            warn!(
+                approval_id = %approval_id,
+                "approval already resolved or expired"
+            );
+            return Ok(());
+        };
+
+        debug!(
+            approval_id = %approval_id,
+            decision = %decision,
+            resolved_by = %inbound.peer_id.0,
+            "received approval response"
+        );
+
+        let approve = decision == "allow";
+        if let Err(error) = self
+            .resolve_approval(&pending, approve, &inbound.peer_id, run_tx)
+            .await
+        {
+            if !error.decision_sent
+                && let Ok(mut guard) = self.pending_approvals.lock()
+            {
+                guard.insert(pending.session_id.clone(), pending);
+            }
+            return Err(error.error);
+        }
+
+        Ok(())
+    }
+
+    async fn handle_run_result(
+        self: &Arc<Self>,
+        result: RunTaskResult,
+        run_tx: mpsc::Sender<RunTaskResult>,
+    ) -> Result<(), StakpakError> {
+        match result.outcome {
+            RunOutcome::ApprovalNeeded {
+                cursor,
+                session_id,
+                run_id,
+                tool_calls,
+                auto_resolved_count,
+                delivery,
+                timeout_seconds,
+            } => {
+                if let Some(cursor) = cursor {
+                    self.advance_run_cursor(&run_id, cursor)?;
+                }
+
+                self.handle_approval_needed(
+                    ApprovalNeededContext {
+                        session_id,
+                        run_id,
+                        tool_calls,
+                        auto_resolved_count,
+                        delivery,
+                        cursor,
+                        timeout_seconds,
+                    },
+                    run_tx,
+                )
+                .await
             }
             RunOutcome::Error { error, cursor } => {
                 if let Some(error) = error
@@ -455,24 +1227,22 @@ impl Dispatcher {
                     warn!(session_id = %result.session_id, run_id = %result.run_id, error = %message, "run failed");
                 }
 
-                self.remove_active_run(&result.session_id, &result.run_id);
-
-                if let Some(cursor) = cursor {
-                    self.set_cursor(&result.session_id, cursor)?;
-                }
+                let session_id = self
+                    .remove_active_run(&result.run_id)
+                    .unwrap_or_else(|| result.session_id.clone());
+                self.finish_run_cursor(&result.session_id, &result.run_id, cursor)?;
 
-                self.drain_queue(&result.session_id, run_tx).await
+                self.drain_queue(&session_id, run_tx).await
             }
             RunOutcome::Completed { cursor }
             | RunOutcome::Cancelled { cursor }
             | RunOutcome::StreamEnded { cursor } => {
-                self.remove_active_run(&result.session_id, &result.run_id);
+                let session_id = self
+                    .remove_active_run(&result.run_id)
+                    .unwrap_or_else(|| result.session_id.clone());
+                self.finish_run_cursor(&result.session_id, &result.run_id, cursor)?;
 
-                if let Some(cursor) = cursor {
-                    self.set_cursor(&result.session_id, cursor)?;
-                }
-
-                self.drain_queue(&result.session_id, run_tx).await
+                self.drain_queue(&session_id, run_tx).await
             }
         }
     }
@@ -481,7 +1251,7 @@ impl Dispatcher {
         self: &Arc<Self>,
         approval: ApprovalNeededContext,
         run_tx: mpsc::Sender<RunTaskResult>,
-    ) -> Result<(), String> {
+    ) -> Result<(), StakpakError> {
         let ApprovalNeededContext {
             session_id,
             run_id,
@@ -500,7 +1270,7 @@ impl Dispatcher {
             let guard = self
                 .pending_approvals
                 .lock()
-                .map_err(|_| "failed to lock pending_approvals".to_string())?;
+                .map_err(|_| StakpakError::internal("failed to lock pending_approvals"))?;
             if let Some(existing) = guard.get(&session_id) {
                 warn!(
                     session_id = %session_id,
@@ -532,19 +1302,7 @@ impl Dispatcher {
 
         let approval_id = generate_approval_id();
         let text = render_approval_prompt(&tool_calls, auto_resolved_count);
-        let button_label_suffix = if tool_calls.len() == 1 { "" } else { " All" };
-        let buttons = vec![
-            ApprovalButton {
-                label: format!("Allow{button_label_suffix}"),
-                callback_data: format!("a:{approval_id}:allow"),
-                style: ButtonStyle::Success,
-            },
-            ApprovalButton {
-                label: format!("Deny{button_label_suffix}"),
-                callback_data: format!("a:{approval_id}:deny"),
-                style: ButtonStyle::Danger,
-            },
-        ];
+        let buttons = approval_buttons(&approval_id, tool_calls.len());
 
         let reply = OutboundReply {
             channel: delivery.channel.clone(),
@@ -587,13 +1345,14 @@ impl Dispatcher {
             cursor,
             timeout_seconds,
             requested_at: Instant::now(),
+            detached: false,
         };
 
         let has_pending = {
             let mut guard = self
                 .pending_approvals
                 .lock()
-                .map_err(|_| "failed to lock pending_approvals".to_string())?;
+                .map_err(|_| StakpakError::internal("failed to lock pending_approvals"))?;
 
             if let Some(existing) = guard.get(&session_id) {
                 warn!(
@@ -624,16 +1383,95 @@ impl Dispatcher {
         Ok(())
     }
 
+    /// Post allow/deny buttons for the tool calls a run outside this dispatcher
+    /// is waiting on, such as a paused autopilot run. Returns `false` when the
+    /// session has nothing pending. A decision only resolves the tool calls;
+    /// whoever started the run keeps consuming its events.
+    pub async fn request_detached_approval(
+        &self,
+        session_id: &str,
+        delivery: DeliveryContext,
+    ) -> Result<bool, StakpakError> {
+        let pending_tools = self
+            .client
+            .pending_tools(session_id)
+            .await
+            .map_err(|error| {
+                StakpakError::new(error.kind(), format!("pending_tools failed: {error}"))
+            })?;
+        let Some(run_id) = pending_tools.run_id else {
+            return Ok(false);
+        };
+        if pending_tools.tool_calls.is_empty() {
+            return Ok(false);
+        }
+
+        {
+            let guard = self
+                .pending_approvals
+                .lock()
+                .map_err(|_| StakpakError::internal("failed to lock pending_approvals"))?;
+            if guard.contains_key(session_id) {
+                return Ok(true);
+            }
+        }
+
+        let channel_name = delivery.channel.0.clone();
+        let channel = self.channels.get(&channel_name).ok_or_else(|| {
+            StakpakError::network(format!("channel '{channel_name}' not connected"))
+        })?;
+
+        let approval_id = generate_approval_id();
+        let reply = OutboundReply {
+            channel: delivery.channel.clone(),
+            peer_id: delivery.peer_id.clone(),
+            chat_type: delivery.chat_type.clone(),
+            text: render_approval_prompt(&pending_tools.tool_calls, 0),
+            metadata: delivery.channel_meta.clone(),
+        };
+        let prompt_message_id = channel
+            .send_with_buttons(
+                reply,
+                approval_buttons(&approval_id, pending_tools.tool_calls.len()),
+            )
+            .await
+            .map_err(|error| {
+                StakpakError::network(format!("failed to send approval prompt: {error}"))
+            })?;
+
+        let pending = PendingApproval {
+            session_id: session_id.to_string(),
+            run_id: run_id.to_string(),
+            tool_calls: pending_tools.tool_calls,
+            approval_id,
+            prompt_message_id,
+            channel_name,
+            delivery,
+            cursor: None,
+            timeout_seconds: None,
+            requested_at: Instant::now(),
+            detached: true,
+        };
+
+        self.pending_approvals
+            .lock()
+            .map_err(|_| StakpakError::internal("failed to lock pending_approvals"))?
+            .entry(session_id.to_string())
+            .or_insert(pending);
+
+        Ok(true)
+    }
+
     async fn reject_pending_approval_for_session(
         self: &Arc<Self>,
         session_id: &str,
         run_tx: mpsc::Sender<RunTaskResult>,
-    ) -> Result<(), String> {
+    ) -> Result<(), StakpakError> {
         let pending = {
             let mut guard = self
                 .pending_approvals
                 .lock()
-                .map_err(|_| "failed to lock pending_approvals".to_string())?;
+                .map_err(|_| StakpakError::internal("failed to lock pending_approvals"))?;
             guard.remove(session_id)
         };
 
@@ -662,8 +1500,25 @@ impl Dispatcher {
             if let Ok(mut guard) = self.pending_approvals.lock() {
                 guard.insert(pending.session_id.clone(), pending);
             }
-            return Err(format!("resolve_tools failed: {error}"));
+            return Err(StakpakError::new(
+                error.kind(),
+                format!("resolve_tools failed: {error}"),
+            ));
         }
+        audit::record(
+            self.store.as_ref(),
+            AuditEntry {
+                channel: &pending.channel_name,
+                session_id: &pending.session_id,
+                run_id: &pending.run_id,
+                decision: AuditDecision::Denied,
+                source: AuditSource::System,
+                decided_by: GATEWAY_DECIDER,
+                reason: Some("Cancelled — new message received"),
+            },
+            &pending.tool_calls,
+        )
+        .await;
 
         if let Some(channel) = self.channels.get(&pending.channel_name)
             && let Err(error) = channel
@@ -679,6 +1534,7 @@ impl Dispatcher {
         self.resume_run_after_approval(
             &pending.session_id,
             &pending.run_id,
+            &pending.tool_calls,
             &pending.delivery,
             pending.cursor,
             remaining_timeout_after_approval(
@@ -700,7 +1556,9 @@ impl Dispatcher {
             && pending.delivery.peer_id != *resolved_by
         {
             return Err(ResolveApprovalError {
-                message: "approval responder does not match the direct-chat requester".to_string(),
+                error: StakpakError::auth(
+                    "approval responder does not match the direct-chat requester",
+                ),
                 decision_sent: false,
             });
         }
@@ -745,11 +1603,29 @@ impl Dispatcher {
             }
 
             return Err(ResolveApprovalError {
-                message: format!("resolve_tools failed: {error}"),
+                error: StakpakError::new(error.kind(), format!("resolve_tools failed: {error}")),
                 // Avoid re-inserting stale approvals when server rejects with 409.
                 decision_sent: is_conflict,
             });
         }
+        audit::record(
+            self.store.as_ref(),
+            AuditEntry {
+                channel: &pending.channel_name,
+                session_id: &pending.session_id,
+                run_id: &pending.run_id,
+                decision: if approve {
+                    AuditDecision::Approved
+                } else {
+                    AuditDecision::Denied
+                },
+                source: AuditSource::User,
+                decided_by: &resolved_by.0,
+                reason: None,
+            },
+            &pending.tool_calls,
+        )
+        .await;
 
         if let Some(channel) = self.channels.get(&pending.channel_name) {
             let resolved_by_display = render_approver_display(&pending.channel_name, resolved_by);
@@ -775,9 +1651,14 @@ impl Dispatcher {
             }
         }
 
+        if pending.detached {
+            return Ok(());
+        }
+
         self.resume_run_after_approval(
             &pending.session_id,
             &pending.run_id,
+            &pending.tool_calls,
             &pending.delivery,
             pending.cursor,
             remaining_timeout_after_approval(
@@ -787,7 +1668,7 @@ impl Dispatcher {
             run_tx,
         )
         .map_err(|error| ResolveApprovalError {
-            message: error,
+            error,
             decision_sent: true,
         })
     }
@@ -796,7 +1677,7 @@ impl Dispatcher {
         self: &Arc<Self>,
         request: RejectAndResumeContext,
         run_tx: mpsc::Sender<RunTaskResult>,
-    ) -> Result<(), String> {
+    ) -> Result<(), StakpakError> {
         let RejectAndResumeContext {
             session_id,
             run_id,
@@ -824,11 +1705,28 @@ impl Dispatcher {
         self.client
             .resolve_tools(&session_id, &run_id, decisions)
             .await
-            .map_err(|error| format!("resolve_tools failed: {error}"))?;
+            .map_err(|error| {
+                StakpakError::new(error.kind(), format!("resolve_tools failed: {error}"))
+            })?;
+        audit::record(
+            self.store.as_ref(),
+            AuditEntry {
+                channel: &delivery.channel.0,
+                session_id: &session_id,
+                run_id: &run_id,
+                decision: AuditDecision::Denied,
+                source: AuditSource::System,
+                decided_by: GATEWAY_DECIDER,
+                reason: Some(&reason),
+            },
+            &tool_calls,
+        )
+        .await;
 
         self.resume_run_after_approval(
             &session_id,
             &run_id,
+            &tool_calls,
             &delivery,
             cursor,
             timeout_seconds,
@@ -840,25 +1738,19 @@ impl Dispatcher {
         self: &Arc<Self>,
         run_context: RunContext,
         last_event_id: Option<u64>,
-        approval_mode: ApprovalMode,
-        approval_allowlist: HashSet<String>,
+        approval: RunApproval,
         cancel: CancellationToken,
         run_tx: mpsc::Sender<RunTaskResult>,
     ) {
         let client = self.client.clone();
+        let store = self.store.clone();
         let session_id_for_task = run_context.session_id.clone();
         let run_id_for_task = run_context.run_id.clone();
 
         tokio::spawn(async move {
-            let outcome = consume_run_events(
-                client,
-                run_context,
-                last_event_id,
-                approval_mode,
-                approval_allowlist,
-                cancel,
-            )
-            .await;
+            let outcome =
+                consume_run_events(client, store, run_context, last_event_id, approval, cancel)
+                    .await;
 
             if let Err(error) = run_tx
                 .send(RunTaskResult {
@@ -878,11 +1770,17 @@ impl Dispatcher {
         session_id: String,
         queued: QueuedMessage,
         run_tx: mpsc::Sender<RunTaskResult>,
-    ) -> Result<(), String> {
+    ) -> Result<(), StakpakError> {
+        let Some(run_session_id) = self.select_run_session(&session_id, &queued).await? else {
+            self.enqueue_message(session_id, queued)?;
+            return Ok(());
+        };
+
         let channel_name = queued.inbound.channel.0.clone();
         let run_overrides = self.build_run_overrides(&channel_name);
-        let (run_approval_mode, run_approval_allowlist) =
-            self.resolve_run_approval(&channel_name, run_overrides.as_ref());
+        let run_approval = self
+            .approval
+            .run_approval(&channel_name, run_overrides.as_ref());
 
         let top_level_model = if run_overrides
             .as_ref()
@@ -898,7 +1796,7 @@ impl Dispatcher {
         let response = self
             .client
             .send_messages(
-                &session_id,
+                &run_session_id,
                 vec![message],
                 SendMessageOptions {
                     model: top_level_model,
@@ -917,78 +1815,178 @@ impl Dispatcher {
                 self.enqueue_message(session_id, queued)?;
                 return Ok(());
             }
-            Err(error) => return Err(format!("send message failed: {error}")),
+            Err(error) if error.is_connectivity() => {
+                warn!(error = %error, "agent server unreachable while sending message");
+                // Keep the (possibly batched) text so nothing drained from the
+                // session queue is lost.
+                let mut inbound = queued.inbound;
+                inbound.text = queued.text;
+                self.hold_offline(inbound).await?;
+                return Ok(());
+            }
+            Err(error) => {
+                return Err(StakpakError::new(
+                    error.kind(),
+                    format!("send message failed: {error}"),
+                ));
+            }
         };
 
         let run_id = response.run_id.to_string();
         let cancel = CancellationToken::new();
+        let templates = RunTemplates {
+            templates: self
+                .channel_templates
+                .get(&channel_name)
+                .map(|templates| templates.or(&self.message_templates))
+                .unwrap_or_else(|| self.message_templates.clone()),
+            session_title: self.render_title(&queued.inbound),
+            started_at: Instant::now(),
+        };
 
-        {
+        let attribution = {
             let mut guard = self
                 .active_runs
                 .lock()
-                .map_err(|_| "failed to lock active_runs".to_string())?;
+                .map_err(|_| StakpakError::internal("failed to lock active_runs"))?;
+            let attribution = (self.max_concurrent_runs > 1).then(|| {
+                let in_flight = guard
+                    .values()
+                    .filter(|active| active.session_id == session_id)
+                    .find_map(|active| active.attribution.as_ref())
+                    .map(|attribution| attribution.in_flight.clone())
+                    .unwrap_or_default();
+                in_flight.fetch_add(1, Ordering::Relaxed);
+                RunAttribution {
+                    quote: render_reply_quote(&queued.text),
+                    in_flight,
+                }
+            });
             guard.insert(
-                session_id.clone(),
+                run_id.clone(),
                 ActiveRun {
-                    run_id: run_id.clone(),
+                    session_id: session_id.clone(),
+                    run_session_id: run_session_id.clone(),
                     cancel: cancel.clone(),
-                    approval_mode: run_approval_mode.clone(),
-                    approval_allowlist: run_approval_allowlist.clone(),
+                    approval: run_approval.clone(),
+                    attribution: attribution.clone(),
+                    templates: templates.clone(),
                 },
             );
-        }
+            attribution
+        };
 
+        let last_event_id = self.session_cursor(&run_session_id)?;
         let run_context = RunContext {
             channels: self.channels.clone(),
             delivery: self.delivery_context_from_inbound(&queued.inbound),
-            session_id: session_id.clone(),
+            session_id: run_session_id,
             run_id,
             timeout_seconds: queued.run_options.timeout_seconds,
+            attribution,
+            tool_results: self.tool_results,
+            tool_renderers: self.tool_renderers.clone(),
+            usage_footer: self.usage_footer,
+            templates,
+            tool_call_args: HashMap::new(),
         };
 
-        let last_event_id = self.get_cursor(&session_id)?;
-        self.spawn_run_consumer(
-            run_context,
-            last_event_id,
-            run_approval_mode,
-            run_approval_allowlist,
-            cancel,
-            run_tx,
-        );
+        let request = queued
+            .text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default();
+        if let Some(text) = run_context.render_message(
+            MessageEvent::RunStarted,
+            TemplateVars {
+                summary: &truncate_chars_with_ellipsis(request, MAX_TEMPLATE_SUMMARY_CHARS),
+                ..TemplateVars::default()
+            },
+        ) {
+            deliver_run_text(&run_context, text).await;
+        }
+
+        self.spawn_run_consumer(run_context, last_event_id, run_approval, cancel, run_tx);
 
         Ok(())
     }
 
+    /// Pick the server session a new run in `session_id` executes in: the
+    /// session itself when it is idle, a fresh side session while fan-in has
+    /// spare capacity, or `None` when the message has to wait in the queue.
+    async fn select_run_session(
+        &self,
+        session_id: &str,
+        queued: &QueuedMessage,
+    ) -> Result<Option<String>, StakpakError> {
+        let (active, session_busy) = {
+            let guard = self
+                .active_runs
+                .lock()
+                .map_err(|_| StakpakError::internal("failed to lock active_runs"))?;
+            let runs = guard
+                .values()
+                .filter(|active| active.session_id == session_id)
+                .collect::<Vec<_>>();
+            (
+                runs.len(),
+                runs.iter()
+                    .any(|active| active.run_session_id == session_id),
+            )
+        };
+
+        if active >= self.max_concurrent_runs {
+            return Ok(None);
+        }
+        if !session_busy {
+            return Ok(Some(session_id.to_string()));
+        }
+
+        let title = format!("{} (parallel)", self.render_title(&queued.inbound));
+        let created = self.client.create_session(&title).await.map_err(|error| {
+            StakpakError::new(error.kind(), format!("create side session failed: {error}"))
+        })?;
+        debug!(
+            session_id = %session_id,
+            side_session_id = %created.id,
+            active_runs = active,
+            "starting parallel run in side session"
+        );
+        Ok(Some(created.id.to_string()))
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn resume_run_after_approval(
         self: &Arc<Self>,
         session_id: &str,
         run_id: &str,
+        tool_calls: &[ProposedToolCall],
         delivery: &DeliveryContext,
         cursor: Option<u64>,
         timeout_seconds: Option<u64>,
         run_tx: mpsc::Sender<RunTaskResult>,
-    ) -> Result<(), String> {
-        let (cancel, run_approval_mode, run_approval_allowlist) = {
+    ) -> Result<(), StakpakError> {
+        let (cancel, run_approval, attribution, templates) = {
             let guard = self
                 .active_runs
                 .lock()
-                .map_err(|_| "failed to lock active_runs".to_string())?;
+                .map_err(|_| StakpakError::internal("failed to lock active_runs"))?;
             guard
-                .get(session_id)
+                .get(run_id)
                 .and_then(|active| {
-                    if active.run_id == run_id {
+                    if active.run_session_id == session_id {
                         Some((
                             active.cancel.clone(),
-                            active.approval_mode.clone(),
-                            active.approval_allowlist.clone(),
+                            active.approval.clone(),
+                            active.attribution.clone(),
+                            active.templates.clone(),
                         ))
                     } else {
                         None
                     }
                 })
-                .ok_or_else(|| "run is no longer active".to_string())?
+                .ok_or_else(|| StakpakError::internal("run is no longer active"))?
         };
 
         let run_context = RunContext {
@@ -997,16 +1995,15 @@ impl Dispatcher {
             session_id: session_id.to_string(),
             run_id: run_id.to_string(),
             timeout_seconds,
+            attribution,
+            tool_results: self.tool_results,
+            tool_renderers: self.tool_renderers.clone(),
+            usage_footer: self.usage_footer,
+            templates,
+            tool_call_args: tool_call_args(tool_calls),
         };
 
-        self.spawn_run_consumer(
-            run_context,
-            cursor,
-            run_approval_mode,
-            run_approval_allowlist,
-            cancel,
-            run_tx,
-        );
+        self.spawn_run_consumer(run_context, cursor, run_approval, cancel, run_tx);
 
         Ok(())
     }
@@ -1015,12 +2012,12 @@ impl Dispatcher {
         self: &Arc<Self>,
         session_id: &str,
         run_tx: mpsc::Sender<RunTaskResult>,
-    ) -> Result<(), String> {
+    ) -> Result<(), StakpakError> {
         let queue = {
             let mut guard = self
                 .pending_queues
                 .lock()
-                .map_err(|_| "failed to lock pending_queues".to_string())?;
+                .map_err(|_| StakpakError::internal("failed to lock pending_queues"))?;
             guard.remove(session_id).unwrap_or_default()
         };
 
@@ -1080,29 +2077,101 @@ impl Dispatcher {
         }
     }
 
+    /// Stored event cursors of `session_id` and its in-flight runs.
+    pub fn session_cursors(&self, session_id: &str) -> Result<SessionCursors, StakpakError> {
+        let mut runs: Vec<(String, String)> = self
+            .active_runs
+            .lock()
+            .map_err(|_| StakpakError::internal("failed to lock active_runs"))?
+            .iter()
+            .filter(|(_, active)| active.session_id == session_id)
+            .map(|(run_id, active)| (run_id.clone(), active.run_session_id.clone()))
+            .collect();
+        runs.sort();
+
+        let cursors = self
+            .event_cursors
+            .lock()
+            .map_err(|_| StakpakError::internal("failed to lock event_cursors"))?;
+        Ok(SessionCursors {
+            session: cursors.session(session_id),
+            runs: runs
+                .into_iter()
+                .map(|(run_id, run_session_id)| RunCursor {
+                    cursor: cursors.runs.get(&run_id).copied(),
+                    run_id,
+                    run_session_id,
+                })
+                .collect(),
+        })
+    }
+
+    /// Re-fetch up to `limit` events of `session_id` from the agent server,
+    /// after `since` or by default after the stored session cursor. Returns
+    /// the cursor used and the events.
+    pub async fn replay_session_events(
+        &self,
+        session_id: &str,
+        since: Option<u64>,
+        limit: usize,
+    ) -> Result<(u64, Vec<SseEvent>), StakpakError> {
+        let since = match since {
+            Some(since) => since,
+            None => self.session_cursor(session_id)?.unwrap_or(0),
+        };
+        let events = self
+            .client
+            .replay_events(
+                session_id,
+                since,
+                limit,
+                EVENT_REPLAY_IDLE_TIMEOUT,
+                EVENT_REPLAY_DEADLINE,
+            )
+            .await
+            .map_err(|error| StakpakError::new(error.kind(), error.to_string()))?;
+        Ok((since, events))
+    }
+
     pub fn is_run_active(&self, session_id: &str) -> bool {
+        self.active_run_count(session_id) > 0
+    }
+
+    fn active_run_count(&self, session_id: &str) -> usize {
         self.active_runs
             .lock()
-            .ok()
-            .and_then(|guard| guard.get(session_id).cloned())
-            .is_some()
+            .map(|guard| {
+                guard
+                    .values()
+                    .filter(|active| active.session_id == session_id)
+                    .count()
+            })
+            .unwrap_or_default()
     }
 
-    fn enqueue_message(&self, session_id: String, message: QueuedMessage) -> Result<(), String> {
+    fn enqueue_message(
+        &self,
+        session_id: String,
+        message: QueuedMessage,
+    ) -> Result<(), StakpakError> {
         let mut guard = self
             .pending_queues
             .lock()
-            .map_err(|_| "failed to lock pending_queues".to_string())?;
+            .map_err(|_| StakpakError::internal("failed to lock pending_queues"))?;
 
         guard.entry(session_id).or_default().push(message);
         Ok(())
     }
 
-    fn restore_queue(&self, session_id: String, drained: Vec<QueuedMessage>) -> Result<(), String> {
+    fn restore_queue(
+        &self,
+        session_id: String,
+        drained: Vec<QueuedMessage>,
+    ) -> Result<(), StakpakError> {
         let mut guard = self
             .pending_queues
             .lock()
-            .map_err(|_| "failed to lock pending_queues".to_string())?;
+            .map_err(|_| StakpakError::internal("failed to lock pending_queues"))?;
 
         let entry = guard.entry(session_id).or_default();
         let existing = std::mem::take(entry);
@@ -1111,13 +2180,13 @@ impl Dispatcher {
         Ok(())
     }
 
-    fn remove_active_run(&self, session_id: &str, run_id: &str) {
-        if let Ok(mut guard) = self.active_runs.lock()
-            && let Some(active) = guard.get(session_id)
-            && active.run_id == run_id
-        {
-            guard.remove(session_id);
+    /// Remove a finished run, returning the session whose queue it drains.
+    fn remove_active_run(&self, run_id: &str) -> Option<String> {
+        let active = self.active_runs.lock().ok()?.remove(run_id)?;
+        if let Some(attribution) = &active.attribution {
+            attribution.in_flight.fetch_sub(1, Ordering::Relaxed);
         }
+        Some(active.session_id)
     }
 
     async fn cancel_all_runs(&self) {
@@ -1141,6 +2210,7 @@ impl Dispatcher {
         const SHUTDOWN_REJECT_CONCURRENCY: usize = 8;
 
         let client = self.client.clone();
+        let store = self.store.clone();
         let mut set = tokio::task::JoinSet::new();
 
         for pending in pending_approvals.into_values() {
@@ -1153,11 +2223,13 @@ impl Dispatcher {
             }
 
             let client = client.clone();
+            let store = store.clone();
             set.spawn(async move {
+                let reason = "Cancelled — gateway shutting down";
                 let decisions = build_decisions_for_tool_calls(
                     &pending.tool_calls,
                     ToolDecisionAction::Reject,
-                    Some("Cancelled — gateway shutting down"),
+                    Some(reason),
                 );
 
                 match tokio::time::timeout(
@@ -1166,7 +2238,22 @@ impl Dispatcher {
                 )
                 .await
                 {
-                    Ok(Ok(())) => {}
+                    Ok(Ok(())) => {
+                        audit::record(
+                            store.as_ref(),
+                            AuditEntry {
+                                channel: &pending.channel_name,
+                                session_id: &pending.session_id,
+                                run_id: &pending.run_id,
+                                decision: AuditDecision::Denied,
+                                source: AuditSource::System,
+                                decided_by: GATEWAY_DECIDER,
+                                reason: Some(reason),
+                            },
+                            &pending.tool_calls,
+                        )
+                        .await;
+                    }
                     Ok(Err(error)) => {
                         warn!(
                             session_id = %pending.session_id,
@@ -1194,22 +2281,32 @@ impl Dispatcher {
         }
     }
 
-    fn get_cursor(&self, session_id: &str) -> Result<Option<u64>, String> {
+    fn session_cursor(&self, session_id: &str) -> Result<Option<u64>, StakpakError> {
         let guard = self
             .event_cursors
             .lock()
-            .map_err(|_| "failed to lock event_cursors".to_string())?;
-        Ok(guard.get(session_id).copied())
+            .map_err(|_| StakpakError::internal("failed to lock event_cursors"))?;
+        Ok(guard.session(session_id))
     }
 
-    fn set_cursor(&self, session_id: &str, cursor: u64) -> Result<(), String> {
-        let mut guard = self
-            .event_cursors
+    fn advance_run_cursor(&self, run_id: &str, cursor: u64) -> Result<(), StakpakError> {
+        self.event_cursors
+            .lock()
+            .map_err(|_| StakpakError::internal("failed to lock event_cursors"))?
+            .advance_run(run_id, cursor);
+        Ok(())
+    }
+
+    fn finish_run_cursor(
+        &self,
+        session_id: &str,
+        run_id: &str,
+        cursor: Option<u64>,
+    ) -> Result<(), StakpakError> {
+        self.event_cursors
             .lock()
-            .map_err(|_| "failed to lock event_cursors".to_string())?;
-        let current = guard.get(session_id).copied();
-        let next = current.map_or(cursor, |value| value.max(cursor));
-        guard.insert(session_id.to_string(), next);
+            .map_err(|_| StakpakError::internal("failed to lock event_cursors"))?
+            .finish_run(session_id, run_id, cursor);
         Ok(())
     }
 
@@ -1225,96 +2322,20 @@ impl Dispatcher {
             .or_else(|| self.default_model.clone())
     }
 
-    fn resolve_run_approval(
-        &self,
-        channel_name: &str,
-        run_overrides: Option<&RunOverrides>,
-    ) -> (ApprovalMode, HashSet<String>) {
-        if let Some(run_overrides) = run_overrides
-            && let Some(override_auto_approve) = run_overrides.auto_approve.as_ref()
-            && let Some(resolved) = resolve_approval_from_override(override_auto_approve)
-        {
-            return resolved;
-        }
-
-        self.resolve_channel_approval(channel_name)
-    }
-
+    #[cfg(test)]
     fn resolve_channel_approval(&self, channel_name: &str) -> (ApprovalMode, HashSet<String>) {
-        let Some(overrides) = self.channel_overrides.get(channel_name) else {
-            return (self.approval_mode.clone(), self.approval_allowlist.clone());
-        };
-
-        let approval_mode = overrides
-            .approval_mode
-            .clone()
-            .unwrap_or_else(|| self.approval_mode.clone());
-
-        let approval_allowlist = overrides
-            .approval_allowlist
-            .as_ref()
-            .map(|list| list.iter().cloned().collect())
-            .unwrap_or_else(|| self.approval_allowlist.clone());
-
-        (approval_mode, approval_allowlist)
+        let approval = self.approval.channel_approval(channel_name);
+        (approval.mode, approval.allowlist)
     }
 
     fn build_run_overrides(&self, channel_name: &str) -> Option<RunOverrides> {
-        if let Some(profile_name) = self.channel_profiles.get(channel_name)
-            && let Some(overrides) = self.override_resolver.resolve_run_overrides(profile_name)
-            && !overrides.is_empty()
-        {
-            return Some(overrides);
-        }
-
-        let channel_overrides = self.channel_overrides.get(channel_name)?;
-
-        let auto_approve = channel_overrides
-            .approval_allowlist
-            .as_ref()
-            .map(|allowlist| AutoApproveOverride::AllowList(allowlist.clone()));
-
-        let overrides = RunOverrides {
-            model: channel_overrides.model.clone(),
-            auto_approve,
-            ..RunOverrides::default()
-        };
-
-        if overrides.is_empty() {
-            None
-        } else {
-            Some(overrides)
-        }
+        self.approval.run_overrides(channel_name)
     }
 }
 
-fn resolve_approval_from_override(
-    override_value: &AutoApproveOverride,
-) -> Option<(ApprovalMode, HashSet<String>)> {
-    match override_value {
-        AutoApproveOverride::Mode(mode) => match mode.trim().to_ascii_lowercase().as_str() {
-            "all" => Some((ApprovalMode::AllowAll, HashSet::new())),
-            "none" => Some((ApprovalMode::DenyAll, HashSet::new())),
-            _ => {
-                debug!(mode = %mode, "unknown auto_approve override mode; falling back to channel/default approval policy");
-                None
-            }
-        },
-        AutoApproveOverride::AllowList(tools) => {
-            let allowlist = tools
-                .iter()
-                .filter_map(|tool| {
-                    let trimmed = tool.trim();
-                    if trimmed.is_empty() {
-                        None
-                    } else {
-                        Some(trimmed.to_string())
-                    }
-                })
-                .collect::<HashSet<_>>();
-            Some((ApprovalMode::Allowlist, allowlist))
-        }
-    }
+/// An error as shown in chat: the message and its code, then the hint.
+fn render_error_reply(error: &StakpakError) -> String {
+    format!("{} [{}]\n💡 {}", error.message, error.code(), error.hint())
 }
 
 fn merge_drained_queue(
@@ -1327,10 +2348,10 @@ fn merge_drained_queue(
 
 async fn consume_run_events(
     client: StakpakClient,
-    run_context: RunContext,
+    store: Arc<dyn StoreBackend>,
+    mut run_context: RunContext,
     last_event_id: Option<u64>,
-    approval_mode: ApprovalMode,
-    approval_allowlist: HashSet<String>,
+    approval: RunApproval,
     cancel: CancellationToken,
 ) -> RunOutcome {
     let mut stream = match client
@@ -1348,6 +2369,8 @@ async fn consume_run_events(
     };
 
     let mut streamed_buffer = String::new();
+    // Text since the last tool call: the final reply once the run completes.
+    let mut reply_text = String::new();
     let mut last_stream_at = Instant::now();
     let mut cursor = last_event_id;
     let timeout_deadline = run_context
@@ -1368,12 +2391,22 @@ async fn consume_run_events(
                 return RunOutcome::Cancelled { cursor };
             }
             _ = &mut timeout_future => {
-                flush_stream_buffer(&run_context.channels, &run_context.delivery, &mut streamed_buffer, true).await;
-                deliver_channel_text(&run_context.channels, &run_context.delivery, "⏱️ Interactive run timed out.").await;
+                flush_stream_buffer(&run_context, &mut streamed_buffer, true).await;
+                let text = run_context
+                    .render_message(
+                        MessageEvent::RunFailed,
+                        TemplateVars {
+                            summary: "Interactive run timed out",
+                            ..TemplateVars::default()
+                        },
+                    )
+                    .unwrap_or_else(|| "⏱️ Interactive run timed out.".to_string());
+                deliver_run_text(&run_context, text).await;
                 return RunOutcome::Error {
                     error: Some(RunErrorPayload {
                         run_id: None,
                         error: Some("Interactive run timed out".to_string()),
+                        kind: ErrorKind::Internal,
                     }),
                     cursor,
                 };
@@ -1382,11 +2415,11 @@ async fn consume_run_events(
                 let event = match next {
                     Ok(Some(event)) => event,
                     Ok(None) => {
-                        flush_stream_buffer(&run_context.channels, &run_context.delivery, &mut streamed_buffer, true).await;
+                        flush_stream_buffer(&run_context, &mut streamed_buffer, true).await;
                         return RunOutcome::StreamEnded { cursor };
                     }
                     Err(error) => {
-                        flush_stream_buffer(&run_context.channels, &run_context.delivery, &mut streamed_buffer, true).await;
+                        flush_stream_buffer(&run_context, &mut streamed_buffer, true).await;
                         warn!(error = %error, "run event stream read failed");
                         return RunOutcome::Error {
                             error: None,
@@ -1407,106 +2440,147 @@ async fn consume_run_events(
                     "text_delta" => {
                         if let Some(delta) = event.as_text_delta() {
                             streamed_buffer.push_str(&delta);
+                            reply_text.push_str(&delta);
 
                             if should_flush_stream_buffer(&streamed_buffer, last_stream_at.elapsed()) {
-                                flush_stream_buffer(&run_context.channels, &run_context.delivery, &mut streamed_buffer, false).await;
+                                flush_stream_buffer(&run_context, &mut streamed_buffer, false).await;
                                 last_stream_at = Instant::now();
                             }
                         }
                     }
                     "tool_calls_proposed" => {
                         if let Some(proposed) = event.as_tool_calls_proposed() {
-                            flush_stream_buffer(&run_context.channels, &run_context.delivery, &mut streamed_buffer, true).await;
-
-                            match approval_mode {
-                                ApprovalMode::Allowlist => {
-                                    let mut auto = HashMap::new();
-                                    let mut need_ask = Vec::new();
-
-                                    for tool_call in proposed.tool_calls {
-                                        if is_allowlisted(&tool_call.name, &approval_allowlist) {
-                                            auto.insert(
-                                                tool_call.id.clone(),
-                                                ToolDecisionInput {
-                                                    action: ToolDecisionAction::Accept,
-                                                    content: None,
-                                                },
-                                            );
-                                        } else {
-                                            need_ask.push(tool_call);
-                                        }
-                                    }
-
-                                    let auto_resolved_count = auto.len();
-                                    if !auto.is_empty()
-                                        && let Err(error) = client
-                                            .resolve_tools(&run_context.session_id, &run_context.run_id, auto)
-                                            .await
-                                    {
-                                        warn!(error = %error, "resolve_tools failed");
-                                        return RunOutcome::Error {
-                                            error: Some(RunErrorPayload {
-                                                run_id: None,
-                                                error: Some(format!("resolve_tools failed: {error}")),
-                                            }),
-                                            cursor,
-                                        };
+                            flush_stream_buffer(&run_context, &mut streamed_buffer, true).await;
+                            reply_text.clear();
+                            run_context
+                                .tool_call_args
+                                .extend(tool_call_args(&proposed.tool_calls));
+
+                            let mut decisions = HashMap::new();
+                            let mut settled = Vec::new();
+                            let mut need_ask = Vec::new();
+                            for tool_call in proposed.tool_calls {
+                                let decision = approval.decide(&tool_call.name, &tool_call.arguments);
+                                let action = match decision.action {
+                                    GatewayApprovalAction::Accept => ToolDecisionAction::Accept,
+                                    GatewayApprovalAction::Reject => ToolDecisionAction::Reject,
+                                    GatewayApprovalAction::Ask => {
+                                        need_ask.push(tool_call);
+                                        continue;
                                     }
+                                };
+                                decisions.insert(
+                                    tool_call.id.clone(),
+                                    ToolDecisionInput {
+                                        action,
+                                        content: None,
+                                    },
+                                );
+                                settled.push((tool_call, decision));
+                            }
 
-                                    if !need_ask.is_empty() {
-                                        return RunOutcome::ApprovalNeeded {
-                                            cursor,
-                                            session_id: run_context.session_id.clone(),
-                                            run_id: run_context.run_id.clone(),
-                                            tool_calls: need_ask,
-                                            auto_resolved_count,
-                                            delivery: run_context.delivery.clone(),
-                                            timeout_seconds: run_context.timeout_seconds,
-                                        };
-                                    }
-                                }
-                                ApprovalMode::AllowAll | ApprovalMode::DenyAll => {
-                                    if !proposed.tool_calls.is_empty() {
-                                        let text = render_running_tools_summary(&proposed.tool_calls);
-                                        deliver_channel_text(&run_context.channels, &run_context.delivery, text).await;
-                                    }
+                            if !matches!(approval.mode, ApprovalMode::Allowlist) && !settled.is_empty() {
+                                let tool_calls: Vec<ProposedToolCall> =
+                                    settled.iter().map(|(tool_call, _)| tool_call.clone()).collect();
+                                let names: Vec<&str> = tool_calls
+                                    .iter()
+                                    .map(|tool_call| strip_mcp_prefix(&tool_call.name))
+                                    .collect();
+                                let text = run_context
+                                    .render_message(
+                                        MessageEvent::ToolProposed,
+                                        TemplateVars {
+                                            summary: &names.join(", "),
+                                            tool_count: tool_calls.len(),
+                                            tools: &render_tool_list(&tool_calls),
+                                            ..TemplateVars::default()
+                                        },
+                                    )
+                                    .unwrap_or_else(|| render_running_tools_summary(&tool_calls));
+                                deliver_run_text(&run_context, text).await;
+                            }
 
-                                    let decisions = build_tool_decisions(
-                                        proposed,
-                                        &approval_mode,
-                                        &approval_allowlist,
-                                    );
-                                    if let Err(error) = client
-                                        .resolve_tools(&run_context.session_id, &run_context.run_id, decisions)
-                                        .await
-                                    {
-                                        warn!(error = %error, "resolve_tools failed");
-                                        return RunOutcome::Error {
-                                            error: Some(RunErrorPayload {
-                                                run_id: None,
-                                                error: Some(format!("resolve_tools failed: {error}")),
-                                            }),
-                                            cursor,
-                                        };
-                                    }
-                                }
+                            let auto_resolved_count = decisions.len();
+                            if !decisions.is_empty()
+                                && let Err(error) = client
+                                    .resolve_tools(&run_context.session_id, &run_context.run_id, decisions)
+                                    .await
+                            {
+                                warn!(error = %error, "resolve_tools failed");
+                                return RunOutcome::Error {
+                                    error: Some(RunErrorPayload {
+                                        run_id: None,
+                                        error: Some(format!("resolve_tools failed: {error}")),
+                                        kind: error.kind(),
+                                    }),
+                                    cursor,
+                                };
+                            }
+                            record_policy_decisions(&store, &run_context, &approval.mode, &settled).await;
+
+                            if !need_ask.is_empty() {
+                                return RunOutcome::ApprovalNeeded {
+                                    cursor,
+                                    session_id: run_context.session_id.clone(),
+                                    run_id: run_context.run_id.clone(),
+                                    tool_calls: need_ask,
+                                    auto_resolved_count,
+                                    delivery: run_context.delivery.clone(),
+                                    timeout_seconds: run_context.timeout_seconds,
+                                };
                             }
 
                             last_stream_at = Instant::now();
                         }
                     }
+                    "tool_execution_completed" => {
+                        if let Some(completed) = event.as_tool_execution_completed() {
+                            let args = run_context
+                                .tool_call_args
+                                .remove(&completed.tool_call_id)
+                                .unwrap_or_default();
+                            let rendered = run_context.tool_renderers.render(
+                                run_context.tool_results,
+                                &ToolResultView {
+                                    tool_name: &completed.tool_name,
+                                    args: &args,
+                                    result: &completed.result,
+                                    is_error: completed.is_error,
+                                },
+                            );
+                            if let Some(text) = rendered {
+                                flush_stream_buffer(&run_context, &mut streamed_buffer, true).await;
+                                deliver_run_text(&run_context, text).await;
+                                last_stream_at = Instant::now();
+                            }
+                        }
+                    }
                     "run_completed" => {
-                        flush_stream_buffer(&run_context.channels, &run_context.delivery, &mut streamed_buffer, true).await;
+                        if run_context.usage_footer
+                            && let Some(footer) = run_usage_footer(&client, &run_context).await
+                        {
+                            if !streamed_buffer.is_empty() {
+                                streamed_buffer.push_str("\n\n");
+                            }
+                            streamed_buffer.push_str(&footer);
+                        }
+                        flush_stream_buffer(&run_context, &mut streamed_buffer, true).await;
+                        if let Some(text) = run_context.render_message(
+                            MessageEvent::RunCompleted,
+                            TemplateVars {
+                                summary: &truncate_chars_with_ellipsis(
+                                    reply_text.trim(),
+                                    MAX_TEMPLATE_SUMMARY_CHARS,
+                                ),
+                                ..TemplateVars::default()
+                            },
+                        ) {
+                            deliver_run_text(&run_context, text).await;
+                        }
                         return RunOutcome::Completed { cursor };
                     }
                     "run_error" => {
-                        flush_stream_buffer(
-                            &run_context.channels,
-                            &run_context.delivery,
-                            &mut streamed_buffer,
-                            true,
-                        )
-                        .await;
+                        flush_stream_buffer(&run_context, &mut streamed_buffer, true).await;
                         let payload = event.as_run_error();
                         let error_text = payload
                             .as_ref()
@@ -1518,12 +2592,27 @@ async fn consume_run_events(
                             error = %error_text,
                             "interactive run failed"
                         );
-                        deliver_channel_text(
-                            &run_context.channels,
-                            &run_context.delivery,
-                            format!("⚠️ Agent run failed (session: {})", run_context.session_id),
-                        )
-                        .await;
+                        let text = run_context
+                            .render_message(
+                                MessageEvent::RunFailed,
+                                TemplateVars {
+                                    summary: &error_text,
+                                    ..TemplateVars::default()
+                                },
+                            )
+                            .unwrap_or_else(|| {
+                                let kind = payload
+                                    .as_ref()
+                                    .map(|payload| payload.kind)
+                                    .unwrap_or_default();
+                                let error = StakpakError::new(kind, error_text.as_str());
+                                format!(
+                                    "⚠️ Agent run failed (session: {}): {}",
+                                    run_context.session_id,
+                                    render_error_reply(&error)
+                                )
+                            });
+                        deliver_run_text(&run_context, text).await;
 
                         return RunOutcome::Error {
                             error: payload,
@@ -1537,6 +2626,13 @@ async fn consume_run_events(
     }
 }
 
+fn tool_call_args(tool_calls: &[ProposedToolCall]) -> HashMap<String, serde_json::Value> {
+    tool_calls
+        .iter()
+        .map(|call| (call.id.clone(), call.arguments.clone()))
+        .collect()
+}
+
 fn should_flush_stream_buffer(buffer: &str, elapsed_since_last_stream: Duration) -> bool {
     const STREAM_MIN_INTERVAL: Duration = Duration::from_secs(3);
     const STREAM_MAX_BUFFER_LEN: usize = 500;
@@ -1585,12 +2681,7 @@ fn last_safe_markdown_split(buffer: &str) -> Option<usize> {
     last_safe_split
 }
 
-async fn flush_stream_buffer(
-    channels: &HashMap<String, Arc<dyn Channel>>,
-    delivery: &DeliveryContext,
-    buffer: &mut String,
-    force: bool,
-) {
+async fn flush_stream_buffer(run_context: &RunContext, buffer: &mut String, force: bool) {
     if buffer.trim().is_empty() {
         buffer.clear();
         return;
@@ -1609,7 +2700,30 @@ async fn flush_stream_buffer(
         return;
     }
 
-    deliver_channel_text(channels, delivery, text.trim()).await;
+    deliver_run_text(run_context, text.trim()).await;
+}
+
+async fn deliver_run_text(run_context: &RunContext, text: impl Into<String>) {
+    let text = match &run_context.attribution {
+        Some(attribution) => attribution.apply(text.into()),
+        None => text.into(),
+    };
+    deliver_channel_text(&run_context.channels, &run_context.delivery, text).await;
+}
+
+/// Quote the first line of the originating message for reply attribution.
+fn render_reply_quote(text: &str) -> String {
+    const REPLY_QUOTE_MAX_CHARS: usize = 80;
+
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    format!(
+        "> {}",
+        truncate_chars_with_ellipsis(line, REPLY_QUOTE_MAX_CHARS)
+    )
 }
 
 async fn deliver_channel_text(
@@ -1635,6 +2749,24 @@ async fn deliver_channel_text(
     }
 }
 
+/// The run's usage line, or `None` when the server has no usage for it.
+async fn run_usage_footer(client: &StakpakClient, run_context: &RunContext) -> Option<String> {
+    let run_id = Uuid::parse_str(&run_context.run_id).ok()?;
+    let usage = match client.session_usage(&run_context.session_id).await {
+        Ok(usage) => usage,
+        Err(error) => {
+            debug!(
+                session_id = %run_context.session_id,
+                error = %error,
+                "failed to fetch run usage"
+            );
+            return None;
+        }
+    };
+    let run = usage.run(run_id)?;
+    (run.usage.total_tokens > 0).then(|| format_usage(run.usage.total_tokens, run.estimated_cost))
+}
+
 fn format_batched_queue_messages(queue: &[QueuedMessage]) -> String {
     if queue.len() <= 1 {
         return queue
@@ -1690,41 +2822,43 @@ fn extract_run_options(metadata: &serde_json::Value) -> RunStartOptions {
     }
 }
 
-fn build_tool_decisions(
-    proposed: ToolCallsProposedPayload,
+/// Audit the tool calls `approval_rules` or the approval mode settled
+/// without asking.
+async fn record_policy_decisions(
+    store: &Arc<dyn StoreBackend>,
+    run_context: &RunContext,
     approval_mode: &ApprovalMode,
-    approval_allowlist: &HashSet<String>,
-) -> HashMap<String, ToolDecisionInput> {
-    proposed
-        .tool_calls
-        .into_iter()
-        .map(|tool_call| {
-            let action = match approval_mode {
-                ApprovalMode::AllowAll => ToolDecisionAction::Accept,
-                ApprovalMode::DenyAll => ToolDecisionAction::Reject,
-                ApprovalMode::Allowlist => {
-                    if is_allowlisted(&tool_call.name, approval_allowlist) {
-                        ToolDecisionAction::Accept
-                    } else {
-                        ToolDecisionAction::Reject
-                    }
-                }
-            };
-
-            (
-                tool_call.id,
-                ToolDecisionInput {
-                    action,
-                    content: None,
+    settled: &[(ProposedToolCall, ToolCallApproval)],
+) {
+    let mode = match approval_mode {
+        ApprovalMode::AllowAll => "allow_all",
+        ApprovalMode::DenyAll => "deny_all",
+        ApprovalMode::Allowlist => "allowlist",
+    };
+    for (tool_call, approval) in settled {
+        let decision = match approval.action {
+            GatewayApprovalAction::Reject => AuditDecision::Denied,
+            GatewayApprovalAction::Accept | GatewayApprovalAction::Ask => AuditDecision::Approved,
+        };
+        audit::record(
+            store.as_ref(),
+            AuditEntry {
+                channel: &run_context.delivery.channel.0,
+                session_id: &run_context.session_id,
+                run_id: &run_context.run_id,
+                decision,
+                source: AuditSource::Policy,
+                decided_by: if approval.rule.is_some() {
+                    "approval_rules"
+                } else {
+                    mode
                 },
-            )
-        })
-        .collect()
-}
-
-fn is_allowlisted(tool_name: &str, approval_allowlist: &HashSet<String>) -> bool {
-    let normalized = strip_mcp_prefix(tool_name);
-    approval_allowlist.contains(tool_name) || approval_allowlist.contains(normalized)
+                reason: approval.rule.as_deref(),
+            },
+            std::iter::once(tool_call),
+        )
+        .await;
+    }
 }
 
 fn build_decisions_for_tool_calls(
@@ -1754,14 +2888,55 @@ fn render_approver_display(channel_name: &str, resolved_by: &PeerId) -> String {
     }
 }
 
+/// `decided_by` of decisions the gateway made on its own.
+const GATEWAY_DECIDER: &str = "gateway";
+
 /// Maximum characters for the entire approval prompt text.
 /// Slack mrkdwn section blocks allow 3000 chars; Discord messages 2000.
 /// We target the lower bound with some headroom for the header/footer.
 const MAX_APPROVAL_PROMPT_CHARS: usize = 1800;
+const APPROVAL_REMINDER_TICK: Duration = Duration::from_secs(30);
+const OFFLINE_PROBE_TICK: Duration = Duration::from_secs(15);
+const OFFLINE_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+// Replayed events arrive back to back; a pause this long means the replay is over.
+const EVENT_REPLAY_IDLE_TIMEOUT: Duration = Duration::from_millis(500);
+const EVENT_REPLAY_DEADLINE: Duration = Duration::from_secs(10);
+const LINK_CODE_TTL: Duration = Duration::from_secs(10 * 60);
+const LINK_NO_SESSION_NOTICE: &str =
+    "Nothing to link yet. Send a message here first to start a session.";
+const LINK_UNKNOWN_CODE_NOTICE: &str =
+    "Unknown or expired link code. Send `/link` in the chat you want to continue to get a new one.";
+const LINK_SAME_CHAT_NOTICE: &str = "This chat is already in that session.";
+const SCHEDULE_CONTROL_UNAVAILABLE_NOTICE: &str =
+    "Schedule commands are only available when the gateway runs inside autopilot.";
+const SCHEDULE_CONTROL_DENIED_NOTICE: &str = "🔒 You are not allowed to control schedules. Ask an admin to add you to `schedule_control_allowlist`.";
+const RUN_CANCELLED_NOTICE: &str = "🛑 Run cancelled.";
+const NO_ACTIVE_RUN_NOTICE: &str = "Nothing to cancel — no run is active here.";
+const OFFLINE_NOTICE: &str =
+    "⚠️ I can't reach the agent right now. I'll get back to you when I'm reconnected.";
 
 /// Maximum characters for a single tool preview body (code block content, etc.).
 const MAX_TOOL_PREVIEW_CHARS: usize = 500;
 
+/// Maximum characters of a reply or request quoted as a template's `{summary}`.
+const MAX_TEMPLATE_SUMMARY_CHARS: usize = 500;
+
+fn approval_buttons(approval_id: &str, tool_count: usize) -> Vec<ApprovalButton> {
+    let label_suffix = if tool_count == 1 { "" } else { " All" };
+    vec![
+        ApprovalButton {
+            label: format!("Allow{label_suffix}"),
+            callback_data: format!("a:{approval_id}:allow"),
+            style: ButtonStyle::Success,
+        },
+        ApprovalButton {
+            label: format!("Deny{label_suffix}"),
+            callback_data: format!("a:{approval_id}:deny"),
+            style: ButtonStyle::Danger,
+        },
+    ]
+}
+
 fn render_approval_prompt(tool_calls: &[ProposedToolCall], auto_count: usize) -> String {
     let mut text = if tool_calls.len() == 1 {
         "🔧 Tool approval required\n\n".to_string()
@@ -1791,12 +2966,18 @@ fn render_approval_prompt(tool_calls: &[ProposedToolCall], auto_count: usize) ->
 }
 
 fn render_running_tools_summary(tool_calls: &[ProposedToolCall]) -> String {
-    let mut text = if tool_calls.len() == 1 {
+    let heading = if tool_calls.len() == 1 {
         "🔧 Running tool\n\n".to_string()
     } else {
         format!("🔧 Running {} tools\n\n", tool_calls.len())
     };
+    heading + &render_tool_list(tool_calls)
+}
 
+/// Each tool call with its argument preview, cut off after
+/// `MAX_APPROVAL_PROMPT_CHARS`.
+fn render_tool_list(tool_calls: &[ProposedToolCall]) -> String {
+    let mut text = String::new();
     for (index, tool_call) in tool_calls.iter().enumerate() {
         let name = strip_mcp_prefix(&tool_call.name);
         let preview = render_tool_preview(name, &tool_call.arguments);
@@ -2077,7 +3258,18 @@ fn generate_approval_id() -> String {
         .collect()
 }
 
-fn strip_mcp_prefix(name: &str) -> &str {
+/// Six hex digits, short enough to type on a phone.
+fn generate_link_code() -> String {
+    Uuid::new_v4()
+        .simple()
+        .to_string()
+        .chars()
+        .take(6)
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+pub(crate) fn strip_mcp_prefix(name: &str) -> &str {
     // Tool names can be namespaced (e.g. `mcp__run_command`,
     // `mcp__server__run_command`, `stakpak__view`).
     // Normalize display/allowlist matching to the bare tool segment.
@@ -2179,12 +3371,16 @@ fn delivery_context_to_caller_context(context: &serde_json::Value) -> Vec<Caller
 mod tests {
     use super::*;
     use crate::{
+        approval::is_allowlisted,
         channels::{Channel, ChannelTestResult},
-        client::{CallerContextInput, StakpakClient},
+        client::{AutoApproveOverride, CallerContextInput, StakpakClient},
         config::{ApprovalMode, ChannelOverrides},
         router::RouterConfig,
         store::GatewayStore,
-        types::{ChannelId, ChatType, DeliveryContext, InboundMessage, OutboundReply, PeerId},
+        types::{
+            ChannelId, ChatType, DeliveryContext, InboundControl, InboundMessage, OutboundReply,
+            PeerId,
+        },
     };
     use anyhow::Result;
     use async_trait::async_trait;
@@ -2207,6 +3403,10 @@ mod tests {
     use tokio::sync::{Mutex as AsyncMutex, mpsc};
     use tokio_util::sync::CancellationToken;
 
+    fn test_run_approval(mode: ApprovalMode) -> RunApproval {
+        ApprovalPolicy::new(mode, Vec::new(), HashMap::new()).channel_approval("test")
+    }
+
     fn queued(text: &str, display_name: Option<&str>, peer: &str) -> QueuedMessage {
         let metadata = match display_name {
             Some(name) => serde_json::json!({"display_name": name}),
@@ -2222,6 +3422,7 @@ mod tests {
                 media: Vec::new(),
                 metadata,
                 timestamp: Utc::now(),
+                control: None,
             },
             text: text.to_string(),
             run_options: RunStartOptions::default(),
@@ -2240,6 +3441,7 @@ mod tests {
     struct TestChannel {
         id: ChannelId,
         edits: Arc<AsyncMutex<Vec<(String, String)>>>,
+        sent: Arc<AsyncMutex<Vec<OutboundReply>>>,
     }
 
     impl TestChannel {
@@ -2247,6 +3449,7 @@ mod tests {
             Self {
                 id: ChannelId(id.to_string()),
                 edits: Arc::new(AsyncMutex::new(Vec::new())),
+                sent: Arc::new(AsyncMutex::new(Vec::new())),
             }
         }
     }
@@ -2269,10 +3472,20 @@ mod tests {
             Ok(())
         }
 
-        async fn send(&self, _reply: OutboundReply) -> Result<()> {
+        async fn send(&self, reply: OutboundReply) -> Result<()> {
+            self.sent.lock().await.push(reply);
             Ok(())
         }
 
+        async fn send_with_buttons(
+            &self,
+            reply: OutboundReply,
+            _buttons: Vec<ApprovalButton>,
+        ) -> Result<String> {
+            self.sent.lock().await.push(reply);
+            Ok("prompt-1".to_string())
+        }
+
         async fn edit_message(&self, message_id: &str, new_text: &str) -> Result<()> {
             self.edits
                 .lock()
@@ -2318,6 +3531,85 @@ mod tests {
         Sse::new(stream::iter(vec![Ok::<Event, Infallible>(event)]))
     }
 
+    #[tokio::test]
+    async fn stale_approval_is_repinged_and_escalated_once() {
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+
+        let slack = Arc::new(TestChannel::new("slack"));
+        let telegram = Arc::new(TestChannel::new("telegram"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), slack.clone());
+        channels.insert("telegram".to_string(), telegram.clone());
+
+        let dispatcher = Dispatcher::new(
+            StakpakClient::new("http://127.0.0.1:1".to_string(), String::new()),
+            channels,
+            store,
+            RouterConfig::default(),
+            None,
+            ApprovalMode::AllowAll,
+            Vec::new(),
+            HashMap::new(),
+            "{channel}-{peer}".to_string(),
+        )
+        .with_approval_reminders(crate::config::ApprovalReminderConfig {
+            escalate_channel: Some("telegram".to_string()),
+            escalate_target: Some(serde_json::json!({"chat_id": "-100"})),
+            ..Default::default()
+        });
+
+        let requested_at = Instant::now()
+            .checked_sub(Duration::from_secs(31 * 60))
+            .expect("instant in the past");
+        dispatcher
+            .pending_approvals
+            .lock()
+            .expect("lock pending_approvals")
+            .insert(
+                "session-1".to_string(),
+                PendingApproval {
+                    session_id: "session-1".to_string(),
+                    run_id: "run-1".to_string(),
+                    tool_calls: vec![ProposedToolCall {
+                        id: "tc-1".to_string(),
+                        name: "stakpak__run_command".to_string(),
+                        arguments: serde_json::json!({"command": "kubectl get pods"}),
+                        metadata: None,
+                    }],
+                    approval_id: "a3f0c92d".to_string(),
+                    prompt_message_id: "C123:123.456".to_string(),
+                    channel_name: "slack".to_string(),
+                    delivery: DeliveryContext {
+                        channel: ChannelId("slack".to_string()),
+                        peer_id: PeerId("u1".to_string()),
+                        chat_type: ChatType::Direct,
+                        channel_meta: serde_json::json!({"channel": "C123"}),
+                        updated_at: Utc::now().timestamp_millis(),
+                    },
+                    cursor: None,
+                    timeout_seconds: None,
+                    requested_at,
+                    detached: false,
+                },
+            );
+
+        dispatcher.send_approval_reminders().await;
+        dispatcher.send_approval_reminders().await;
+
+        let repings = slack.sent.lock().await.clone();
+        assert_eq!(repings.len(), 1);
+        assert!(repings[0].text.contains("Approval still waiting (31m)"));
+
+        let escalations = telegram.sent.lock().await.clone();
+        assert_eq!(escalations.len(), 1);
+        assert_eq!(escalations[0].peer_id, PeerId("-100".to_string()));
+        assert!(escalations[0].text.contains("run_command"));
+    }
+
     #[tokio::test]
     async fn approval_not_reinserted_when_resume_fails_after_decision_sent() {
         let server_state = TestServerState {
@@ -2394,6 +3686,7 @@ mod tests {
                     cursor: Some(5),
                     timeout_seconds: None,
                     requested_at: Instant::now(),
+                    detached: false,
                 },
             );
 
@@ -2409,6 +3702,7 @@ mod tests {
                 "decision": "allow"
             }),
             timestamp: Utc::now(),
+            control: None,
         };
 
         let (run_tx, _run_rx) = mpsc::channel(4);
@@ -2483,12 +3777,14 @@ mod tests {
             .lock()
             .expect("lock active_runs")
             .insert(
-                session_id.clone(),
+                run_id.clone(),
                 ActiveRun {
-                    run_id: run_id.clone(),
+                    session_id: session_id.clone(),
+                    run_session_id: session_id.clone(),
                     cancel: CancellationToken::new(),
-                    approval_mode: ApprovalMode::Allowlist,
-                    approval_allowlist: HashSet::new(),
+                    approval: test_run_approval(ApprovalMode::Allowlist),
+                    attribution: None,
+                    templates: RunTemplates::default(),
                 },
             );
 
@@ -2520,6 +3816,7 @@ mod tests {
                     cursor: Some(5),
                     timeout_seconds: None,
                     requested_at: Instant::now(),
+                    detached: false,
                 },
             );
 
@@ -2582,6 +3879,1097 @@ mod tests {
         server_handle.abort();
     }
 
+    #[tokio::test]
+    async fn detached_approval_resolves_tools_without_resuming_run() {
+        let run_id = uuid::Uuid::new_v4();
+        let server_state = TestServerState {
+            run_id: run_id.to_string(),
+            resolve_payloads: Arc::new(AsyncMutex::new(Vec::new())),
+            last_event_ids: Arc::new(AsyncMutex::new(Vec::new())),
+        };
+
+        let app = Router::new()
+            .route(
+                "/v1/sessions/{session_id}/tools/pending",
+                get(move || async move {
+                    Json(serde_json::json!({
+                        "run_id": run_id,
+                        "tool_calls": [{
+                            "id": "tc-1",
+                            "name": "stakpak__run_command",
+                            "arguments": {"command": "systemctl restart nginx"}
+                        }]
+                    }))
+                }),
+            )
+            .route(
+                "/v1/sessions/{session_id}/tools/decisions",
+                post(test_resolve_tools_handler),
+            )
+            .route("/v1/sessions/{session_id}/events", get(test_events_handler))
+            .with_state(server_state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("read listener addr");
+        let server_handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+        let test_channel = Arc::new(TestChannel::new("slack"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), test_channel.clone());
+
+        let dispatcher = Arc::new(Dispatcher::new(
+            StakpakClient::new(format!("http://{addr}"), String::new()),
+            channels,
+            store.clone(),
+            RouterConfig::default(),
+            None,
+            ApprovalMode::Allowlist,
+            Vec::new(),
+            HashMap::new(),
+            "{channel}-{peer}".to_string(),
+        ));
+
+        let delivery = DeliveryContext {
+            channel: ChannelId("slack".to_string()),
+            peer_id: PeerId("u1".to_string()),
+            chat_type: ChatType::Direct,
+            channel_meta: serde_json::json!({"channel": "C123"}),
+            updated_at: Utc::now().timestamp_millis(),
+        };
+        assert!(
+            dispatcher
+                .request_detached_approval("session-1", delivery)
+                .await
+                .expect("post detached approval")
+        );
+
+        let sent = test_channel.sent.lock().await.clone();
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].text.contains("systemctl restart nginx"));
+
+        let approval_id = dispatcher
+            .pending_approvals
+            .lock()
+            .expect("lock pending_approvals")
+            .get("session-1")
+            .map(|pending| pending.approval_id.clone())
+            .expect("pending approval recorded");
+
+        let (run_tx, mut run_rx) = mpsc::channel(8);
+        dispatcher
+            .handle_approval_response(
+                InboundMessage {
+                    channel: ChannelId("slack".to_string()),
+                    peer_id: PeerId("u1".to_string()),
+                    chat_type: ChatType::Direct,
+                    text: String::new(),
+                    media: Vec::new(),
+                    metadata: serde_json::json!({
+                        "type": "approval_response",
+                        "approval_id": approval_id,
+                        "decision": "allow"
+                    }),
+                    timestamp: Utc::now(),
+                    control: None,
+                },
+                run_tx,
+            )
+            .await
+            .expect("resolve detached approval");
+
+        let payloads = server_state.resolve_payloads.lock().await.clone();
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(
+            payloads[0]["decisions"]["tc-1"]["action"].as_str(),
+            Some("accept")
+        );
+
+        let edits = test_channel.edits.lock().await.clone();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].0, "prompt-1");
+        assert!(edits[0].1.contains("approved"));
+
+        let audit = store
+            .list_approval_audit(&crate::audit::ApprovalAuditFilter::default(), 10)
+            .await
+            .expect("list audit");
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].tool_call_id, "tc-1");
+        assert_eq!(audit[0].decision, AuditDecision::Approved);
+        assert_eq!(audit[0].source, AuditSource::User);
+        assert_eq!(audit[0].decided_by, "u1");
+        assert_eq!(
+            audit[0].arguments_hash,
+            crate::audit::arguments_hash(
+                &serde_json::json!({"command": "systemctl restart nginx"})
+            )
+        );
+
+        // The run's owner keeps consuming events: no run consumer was spawned,
+        // so the result channel closes without an outcome.
+        assert!(run_rx.recv().await.is_none());
+        assert!(server_state.last_event_ids.lock().await.is_empty());
+
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn completed_run_reply_ends_with_usage_footer() {
+        let server_state = FanInServerState {
+            side_session_id: uuid::Uuid::new_v4(),
+            run_id: uuid::Uuid::new_v4(),
+            message_sessions: Arc::new(AsyncMutex::new(Vec::new())),
+        };
+        let run_id = server_state.run_id;
+        let session_id = server_state.side_session_id;
+
+        let app = Router::new()
+            .route(
+                "/v1/sessions/{session_id}/events",
+                get(fan_in_events_handler),
+            )
+            .route(
+                "/v1/sessions/{session_id}/usage",
+                get(move || async move {
+                    Json(serde_json::json!({
+                        "session_id": session_id,
+                        "usage": {"total_tokens": 20_000},
+                        "estimated_cost": 0.09,
+                        "runs": [{
+                            "run_id": run_id,
+                            "usage": {"total_tokens": 12_345},
+                            "estimated_cost": 0.0412,
+                        }],
+                    }))
+                }),
+            )
+            .with_state(server_state);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("read listener addr");
+        let server_handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let test_channel = Arc::new(TestChannel::new("slack"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), test_channel.clone());
+        let run_context = RunContext {
+            channels,
+            delivery: DeliveryContext {
+                channel: ChannelId("slack".to_string()),
+                peer_id: PeerId("u1".to_string()),
+                chat_type: ChatType::Direct,
+                channel_meta: serde_json::json!({"channel": "C123"}),
+                updated_at: Utc::now().timestamp_millis(),
+            },
+            session_id: session_id.to_string(),
+            run_id: run_id.to_string(),
+            timeout_seconds: None,
+            attribution: None,
+            tool_results: ToolResultDisplay::default(),
+            tool_renderers: Arc::new(ToolResultRenderers::default()),
+            usage_footer: true,
+            templates: RunTemplates::default(),
+            tool_call_args: HashMap::new(),
+        };
+
+        let outcome = consume_run_events(
+            StakpakClient::new(format!("http://{addr}"), String::new()),
+            Arc::new(GatewayStore::open_in_memory().await.expect("store")),
+            run_context,
+            None,
+            test_run_approval(ApprovalMode::AllowAll),
+            CancellationToken::new(),
+        )
+        .await;
+        assert!(matches!(outcome, RunOutcome::Completed { cursor: Some(9) }));
+
+        let sent = test_channel.sent.lock().await.clone();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].text, "There are 3 pods.\n\n12.3k tokens, ~$0.04");
+
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn completed_run_posts_templated_message() {
+        let server_state = FanInServerState {
+            side_session_id: uuid::Uuid::new_v4(),
+            run_id: uuid::Uuid::new_v4(),
+            message_sessions: Arc::new(AsyncMutex::new(Vec::new())),
+        };
+        let run_id = server_state.run_id;
+        let session_id = server_state.side_session_id;
+
+        let app = Router::new()
+            .route(
+                "/v1/sessions/{session_id}/events",
+                get(fan_in_events_handler),
+            )
+            .with_state(server_state);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("read listener addr");
+        let server_handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let test_channel = Arc::new(TestChannel::new("slack"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), test_channel.clone());
+        let run_context = RunContext {
+            channels,
+            delivery: DeliveryContext {
+                channel: ChannelId("slack".to_string()),
+                peer_id: PeerId("u1".to_string()),
+                chat_type: ChatType::Direct,
+                channel_meta: serde_json::json!({"channel": "C123"}),
+                updated_at: Utc::now().timestamp_millis(),
+            },
+            session_id: session_id.to_string(),
+            run_id: run_id.to_string(),
+            timeout_seconds: None,
+            attribution: None,
+            tool_results: ToolResultDisplay::default(),
+            tool_renderers: Arc::new(ToolResultRenderers::default()),
+            usage_footer: false,
+            templates: RunTemplates {
+                templates: MessageTemplates {
+                    run_completed: Some("✅ {session_title} ({channel}): {summary}".to_string()),
+                    ..MessageTemplates::default()
+                },
+                session_title: "slack / u1".to_string(),
+                started_at: Instant::now(),
+            },
+            tool_call_args: HashMap::new(),
+        };
+
+        let outcome = consume_run_events(
+            StakpakClient::new(format!("http://{addr}"), String::new()),
+            Arc::new(GatewayStore::open_in_memory().await.expect("store")),
+            run_context,
+            None,
+            test_run_approval(ApprovalMode::AllowAll),
+            CancellationToken::new(),
+        )
+        .await;
+        assert!(matches!(outcome, RunOutcome::Completed { .. }));
+
+        let sent = test_channel.sent.lock().await.clone();
+        let texts: Vec<&str> = sent.iter().map(|reply| reply.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "There are 3 pods.",
+                "✅ slack / u1 (slack): There are 3 pods."
+            ]
+        );
+
+        server_handle.abort();
+    }
+
+    type CancelledRuns = Arc<AsyncMutex<Vec<(String, serde_json::Value)>>>;
+
+    async fn test_cancel_handler(
+        State(cancelled): State<CancelledRuns>,
+        Path(session_id): Path<String>,
+        Json(payload): Json<serde_json::Value>,
+    ) -> StatusCode {
+        cancelled.lock().await.push((session_id, payload));
+        StatusCode::OK
+    }
+
+    #[tokio::test]
+    async fn cancel_request_stops_active_runs_of_the_conversation() {
+        let cancelled = CancelledRuns::default();
+        let app = Router::new()
+            .route(
+                "/v1/sessions/{session_id}/cancel",
+                post(test_cancel_handler),
+            )
+            .with_state(cancelled.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("read listener addr");
+        let server_handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+        let test_channel = Arc::new(TestChannel::new("slack"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), test_channel.clone());
+
+        let dispatcher = Arc::new(Dispatcher::new(
+            StakpakClient::new(format!("http://{addr}"), String::new()),
+            channels,
+            store.clone(),
+            RouterConfig::default(),
+            None,
+            ApprovalMode::Allowlist,
+            Vec::new(),
+            HashMap::new(),
+            "{channel}-{peer}".to_string(),
+        ));
+
+        let delivery = DeliveryContext {
+            channel: ChannelId("slack".to_string()),
+            peer_id: PeerId("u1".to_string()),
+            chat_type: ChatType::Direct,
+            channel_meta: serde_json::json!({"channel": "D123"}),
+            updated_at: Utc::now().timestamp_millis(),
+        };
+        let routing_key = resolve_routing_key(
+            &RouterConfig::default(),
+            &delivery.channel,
+            &delivery.peer_id,
+            &delivery.chat_type,
+        );
+        store
+            .set(
+                &routing_key,
+                &SessionMapping {
+                    session_id: "session-1".to_string(),
+                    title: "slack-u1".to_string(),
+                    delivery: delivery.clone(),
+                    created_at: Utc::now().timestamp_millis(),
+                },
+            )
+            .await
+            .expect("persist mapping");
+
+        // One run waits for approval, a parallel one is streaming.
+        let streaming_cancel = CancellationToken::new();
+        {
+            let mut active_runs = dispatcher.active_runs.lock().expect("lock active_runs");
+            for (run_id, run_session_id, cancel) in [
+                ("run-1", "session-1", CancellationToken::new()),
+                ("run-2", "side-1", streaming_cancel.clone()),
+            ] {
+                active_runs.insert(
+                    run_id.to_string(),
+                    ActiveRun {
+                        session_id: "session-1".to_string(),
+                        run_session_id: run_session_id.to_string(),
+                        cancel,
+                        approval: test_run_approval(ApprovalMode::Allowlist),
+                        attribution: None,
+                        templates: RunTemplates::default(),
+                    },
+                );
+            }
+        }
+        dispatcher
+            .pending_approvals
+            .lock()
+            .expect("lock pending_approvals")
+            .insert(
+                "session-1".to_string(),
+                PendingApproval {
+                    session_id: "session-1".to_string(),
+                    run_id: "run-1".to_string(),
+                    tool_calls: vec![ProposedToolCall {
+                        id: "tc-1".to_string(),
+                        name: "mcp__run_command".to_string(),
+                        arguments: serde_json::json!({"command": "kubectl delete pod web-1"}),
+                        metadata: None,
+                    }],
+                    approval_id: "a3f0c92d".to_string(),
+                    prompt_message_id: "D123:123.456".to_string(),
+                    channel_name: "slack".to_string(),
+                    delivery: delivery.clone(),
+                    cursor: Some(5),
+                    timeout_seconds: None,
+                    requested_at: Instant::now(),
+                    detached: false,
+                },
+            );
+
+        let inbound = |peer: &str| InboundMessage {
+            channel: ChannelId("slack".to_string()),
+            peer_id: PeerId(peer.to_string()),
+            chat_type: ChatType::Direct,
+            text: String::new(),
+            media: Vec::new(),
+            metadata: serde_json::json!({"channel": "D123"}),
+            timestamp: Utc::now(),
+            control: Some(InboundControl::Cancel),
+        };
+
+        let (run_tx, _run_rx) = mpsc::channel(8);
+        dispatcher
+            .handle_inbound(inbound("u1"), run_tx.clone())
+            .await
+            .expect("cancel runs");
+
+        assert!(streaming_cancel.is_cancelled());
+        let mut cancelled = cancelled.lock().await.clone();
+        cancelled.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            cancelled,
+            vec![
+                (
+                    "session-1".to_string(),
+                    serde_json::json!({"run_id": "run-1"})
+                ),
+                ("side-1".to_string(), serde_json::json!({"run_id": "run-2"})),
+            ]
+        );
+
+        // The run waiting for approval is finished here; the streaming one
+        // finishes when its consumer sees the token.
+        assert!(
+            dispatcher
+                .pending_approvals
+                .lock()
+                .expect("lock pending_approvals")
+                .is_empty()
+        );
+        let remaining: Vec<String> = dispatcher
+            .active_runs
+            .lock()
+            .expect("lock active_runs")
+            .keys()
+            .cloned()
+            .collect();
+        assert_eq!(remaining, vec!["run-2".to_string()]);
+
+        let edits = test_channel.edits.lock().await.clone();
+        assert_eq!(
+            edits,
+            vec![(
+                "D123:123.456".to_string(),
+                "🛑 Tools skipped — run cancelled".to_string()
+            )]
+        );
+        let audit = store
+            .list_approval_audit(&crate::audit::ApprovalAuditFilter::default(), 10)
+            .await
+            .expect("list audit");
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].decision, AuditDecision::Denied);
+        assert_eq!(audit[0].decided_by, "u1");
+
+        dispatcher
+            .handle_inbound(inbound("u2"), run_tx)
+            .await
+            .expect("nothing to cancel");
+        let sent = test_channel.sent.lock().await.clone();
+        let texts: Vec<&str> = sent.iter().map(|reply| reply.text.as_str()).collect();
+        assert_eq!(texts, vec![RUN_CANCELLED_NOTICE, NO_ACTIVE_RUN_NOTICE]);
+
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn link_command_binds_another_chat_to_the_session() {
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+        let slack = Arc::new(TestChannel::new("slack"));
+        let telegram = Arc::new(TestChannel::new("telegram"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), slack.clone());
+        channels.insert("telegram".to_string(), telegram.clone());
+
+        let dispatcher = Arc::new(Dispatcher::new(
+            StakpakClient::new("http://127.0.0.1:9".to_string(), String::new()),
+            channels,
+            store.clone(),
+            RouterConfig::default(),
+            None,
+            ApprovalMode::Allowlist,
+            Vec::new(),
+            HashMap::new(),
+            "{channel}-{peer}".to_string(),
+        ));
+
+        let message = |channel: &str, peer: &str, text: &str| InboundMessage {
+            channel: ChannelId(channel.to_string()),
+            peer_id: PeerId(peer.to_string()),
+            chat_type: ChatType::Direct,
+            text: text.to_string(),
+            media: Vec::new(),
+            metadata: serde_json::json!({"chat_id": 42}),
+            timestamp: Utc::now(),
+            control: None,
+        };
+        let routing_key = |inbound: &InboundMessage| {
+            resolve_routing_key(
+                &RouterConfig::default(),
+                &inbound.channel,
+                &inbound.peer_id,
+                &inbound.chat_type,
+            )
+        };
+
+        let slack_link = message("slack", "U1", "/link");
+        store
+            .set(
+                &routing_key(&slack_link),
+                &SessionMapping {
+                    session_id: "session-1".to_string(),
+                    title: "slack-U1".to_string(),
+                    delivery: dispatcher.delivery_context_from_inbound(&slack_link),
+                    created_at: Utc::now().timestamp_millis(),
+                },
+            )
+            .await
+            .expect("persist mapping");
+
+        let (run_tx, _run_rx) = mpsc::channel(4);
+        dispatcher
+            .handle_inbound(slack_link, run_tx.clone())
+            .await
+            .expect("issue link code");
+        let issued = slack.sent.lock().await.clone();
+        assert_eq!(issued.len(), 1);
+        let code = dispatcher
+            .link_codes
+            .lock()
+            .expect("lock link_codes")
+            .keys()
+            .next()
+            .cloned()
+            .expect("link code");
+        assert!(issued[0].text.contains(&format!("`/link {code}`")));
+
+        let redeem = message("telegram", "77", &format!("/link {}", code.to_lowercase()));
+        let telegram_key = routing_key(&redeem);
+        dispatcher
+            .handle_inbound(redeem, run_tx.clone())
+            .await
+            .expect("redeem link code");
+
+        let linked = store
+            .get(&telegram_key)
+            .await
+            .expect("get mapping")
+            .expect("telegram mapping");
+        assert_eq!(linked.session_id, "session-1");
+        assert_eq!(linked.delivery.channel.0, "telegram");
+        assert_eq!(linked.delivery.channel_meta["chat_id"], 42);
+        assert_eq!(
+            telegram.sent.lock().await[0].text,
+            "🔗 Linked. This chat now continues \"slack-U1\"."
+        );
+        assert_eq!(
+            slack.sent.lock().await[1].text,
+            "🔗 This session is now also continued from telegram."
+        );
+
+        // Codes are single use.
+        dispatcher
+            .handle_inbound(message("telegram", "78", &format!("/link {code}")), run_tx)
+            .await
+            .expect("reuse link code");
+        assert_eq!(telegram.sent.lock().await[1].text, LINK_UNKNOWN_CODE_NOTICE);
+    }
+
+    #[derive(Default)]
+    struct RecordingScheduleControl {
+        calls: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ScheduleControl for RecordingScheduleControl {
+        async fn list(&self) -> Result<Vec<crate::schedules::ScheduleSummary>, String> {
+            Ok(Vec::new())
+        }
+
+        async fn run(&self, name: &str) -> Result<(), String> {
+            self.calls
+                .lock()
+                .expect("lock calls")
+                .push(format!("run {name}"));
+            Ok(())
+        }
+
+        async fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), String> {
+            self.calls
+                .lock()
+                .expect("lock calls")
+                .push(format!("enabled {name} {enabled}"));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn schedule_commands_require_the_allowlist() {
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+        let slack = Arc::new(TestChannel::new("slack"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), slack.clone());
+        let control = Arc::new(RecordingScheduleControl::default());
+
+        let dispatcher = |control: Option<Arc<RecordingScheduleControl>>| {
+            let dispatcher = Dispatcher::new(
+                StakpakClient::new("http://127.0.0.1:9".to_string(), String::new()),
+                channels.clone(),
+                store.clone(),
+                RouterConfig::default(),
+                None,
+                ApprovalMode::Allowlist,
+                Vec::new(),
+                HashMap::new(),
+                "{channel}-{peer}".to_string(),
+            );
+            Arc::new(match control {
+                Some(control) => {
+                    dispatcher.with_schedule_control(control, vec!["slack:U1".to_string()])
+                }
+                None => dispatcher,
+            })
+        };
+        let message = |peer: &str, text: &str| InboundMessage {
+            channel: ChannelId("slack".to_string()),
+            peer_id: PeerId(peer.to_string()),
+            chat_type: ChatType::Direct,
+            text: text.to_string(),
+            media: Vec::new(),
+            metadata: serde_json::json!({"channel": "D1"}),
+            timestamp: Utc::now(),
+            control: None,
+        };
+
+        let (run_tx, _run_rx) = mpsc::channel(4);
+        let with_control = dispatcher(Some(control.clone()));
+        with_control
+            .handle_inbound(message("U1", "/autopilot pause nightly"), run_tx.clone())
+            .await
+            .expect("pause schedule");
+        with_control
+            .handle_inbound(message("U2", "/autopilot run nightly"), run_tx.clone())
+            .await
+            .expect("refuse schedule command");
+        with_control
+            .handle_inbound(message("U1", "/autopilot delete nightly"), run_tx.clone())
+            .await
+            .expect("reply with usage");
+        dispatcher(None)
+            .handle_inbound(message("U1", "/autopilot list"), run_tx)
+            .await
+            .expect("reply without control");
+
+        assert_eq!(
+            *control.calls.lock().expect("lock calls"),
+            vec!["enabled nightly false".to_string()]
+        );
+        let replies: Vec<String> = slack
+            .sent
+            .lock()
+            .await
+            .iter()
+            .map(|reply| reply.text.clone())
+            .collect();
+        assert_eq!(
+            replies,
+            vec![
+                "⏸️ Schedule `nightly` paused.".to_string(),
+                SCHEDULE_CONTROL_DENIED_NOTICE.to_string(),
+                crate::schedules::SCHEDULE_COMMAND_USAGE.to_string(),
+                SCHEDULE_CONTROL_UNAVAILABLE_NOTICE.to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn running_tools_summary_keeps_default_heading() {
+        let tool_calls = vec![ProposedToolCall {
+            id: "tc-1".to_string(),
+            name: "mcp__run_command".to_string(),
+            arguments: serde_json::json!({"command": "kubectl get pods"}),
+            metadata: None,
+        }];
+        let summary = render_running_tools_summary(&tool_calls);
+        assert!(summary.starts_with("🔧 Running tool\n\n**1 · run_command**"));
+        assert_eq!(
+            summary.trim_start_matches("🔧 Running tool\n\n"),
+            render_tool_list(&tool_calls)
+        );
+    }
+
+    #[derive(Clone)]
+    struct FanInServerState {
+        side_session_id: uuid::Uuid,
+        run_id: uuid::Uuid,
+        message_sessions: Arc<AsyncMutex<Vec<String>>>,
+    }
+
+    async fn fan_in_create_session_handler(
+        State(state): State<FanInServerState>,
+    ) -> Json<serde_json::Value> {
+        Json(serde_json::json!({"id": state.side_session_id, "title": "side"}))
+    }
+
+    async fn fan_in_messages_handler(
+        State(state): State<FanInServerState>,
+        Path(session_id): Path<String>,
+    ) -> Json<serde_json::Value> {
+        state.message_sessions.lock().await.push(session_id);
+        Json(serde_json::json!({"run_id": state.run_id}))
+    }
+
+    async fn fan_in_events_handler(
+        State(state): State<FanInServerState>,
+        Path(_session_id): Path<String>,
+    ) -> Sse<impl futures_util::Stream<Item = std::result::Result<Event, Infallible>>> {
+        let delta = Event::default().id("8").event("text_delta").data(
+            serde_json::json!({
+                "run_id": state.run_id,
+                "event": {"TextDelta": {"run_id": state.run_id, "delta": "There are 3 pods."}},
+            })
+            .to_string(),
+        );
+        let completed = Event::default()
+            .id("9")
+            .event("run_completed")
+            .data(serde_json::json!({"run_id": state.run_id}).to_string());
+
+        Sse::new(stream::iter(vec![
+            Ok::<Event, Infallible>(delta),
+            Ok::<Event, Infallible>(completed),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn fan_in_starts_parallel_run_in_side_session_with_attribution() {
+        let server_state = FanInServerState {
+            side_session_id: uuid::Uuid::new_v4(),
+            run_id: uuid::Uuid::new_v4(),
+            message_sessions: Arc::new(AsyncMutex::new(Vec::new())),
+        };
+        let side_session_id = server_state.side_session_id.to_string();
+        let side_run_id = server_state.run_id.to_string();
+
+        let app = Router::new()
+            .route("/v1/sessions", post(fan_in_create_session_handler))
+            .route(
+                "/v1/sessions/{session_id}/messages",
+                post(fan_in_messages_handler),
+            )
+            .route(
+                "/v1/sessions/{session_id}/events",
+                get(fan_in_events_handler),
+            )
+            .with_state(server_state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("read listener addr");
+        let server_handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+        let test_channel = Arc::new(TestChannel::new("slack"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), test_channel.clone());
+
+        let dispatcher = Arc::new(
+            Dispatcher::new(
+                StakpakClient::new(format!("http://{addr}"), String::new()),
+                channels,
+                store,
+                RouterConfig::default(),
+                None,
+                ApprovalMode::AllowAll,
+                Vec::new(),
+                HashMap::new(),
+                "{channel}-{peer}".to_string(),
+            )
+            .with_max_concurrent_runs(2),
+        );
+
+        let session_id = "session-1".to_string();
+        dispatcher
+            .active_runs
+            .lock()
+            .expect("lock active_runs")
+            .insert(
+                "run-primary".to_string(),
+                ActiveRun {
+                    session_id: session_id.clone(),
+                    run_session_id: session_id.clone(),
+                    cancel: CancellationToken::new(),
+                    approval: test_run_approval(ApprovalMode::AllowAll),
+                    attribution: Some(RunAttribution {
+                        quote: "> Deploy the API".to_string(),
+                        in_flight: Arc::new(AtomicUsize::new(1)),
+                    }),
+                    templates: RunTemplates::default(),
+                },
+            );
+
+        let (run_tx, mut run_rx) = mpsc::channel(8);
+        dispatcher
+            .start_run(
+                session_id.clone(),
+                queued("How many pods?\nIn prod.", None, "u1"),
+                run_tx.clone(),
+            )
+            .await
+            .expect("start parallel run");
+
+        assert_eq!(
+            server_state.message_sessions.lock().await.clone(),
+            vec![side_session_id.clone()]
+        );
+        assert_eq!(dispatcher.active_run_count(&session_id), 2);
+
+        // At capacity: the next message waits in the session queue.
+        dispatcher
+            .start_run(
+                session_id.clone(),
+                queued("And nodes?", None, "u1"),
+                run_tx.clone(),
+            )
+            .await
+            .expect("queue message at capacity");
+        assert_eq!(server_state.message_sessions.lock().await.len(), 1);
+        assert_eq!(
+            dispatcher
+                .pending_queues
+                .lock()
+                .expect("lock pending_queues")
+                .get(&session_id)
+                .map(Vec::len),
+            Some(1)
+        );
+
+        let run_result = tokio::time::timeout(Duration::from_secs(3), run_rx.recv())
+            .await
+            .expect("timed out waiting for parallel run result")
+            .expect("expected parallel run result");
+        assert_eq!(run_result.session_id, side_session_id);
+        assert_eq!(run_result.run_id, side_run_id);
+        assert!(matches!(
+            run_result.outcome,
+            RunOutcome::Completed { cursor: Some(9) }
+        ));
+
+        let sent = test_channel.sent.lock().await.clone();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].text, "> How many pods?\nThere are 3 pods.");
+
+        dispatcher
+            .handle_run_result(run_result, run_tx)
+            .await
+            .expect("handle parallel run result");
+
+        assert_eq!(
+            dispatcher
+                .session_cursor(&side_session_id)
+                .expect("read session cursor"),
+            Some(9)
+        );
+        // The finished side run frees a slot, so the queued message starts.
+        assert_eq!(server_state.message_sessions.lock().await.len(), 2);
+        assert!(
+            dispatcher
+                .pending_queues
+                .lock()
+                .expect("lock pending_queues")
+                .get(&session_id)
+                .is_none()
+        );
+
+        server_handle.abort();
+    }
+
+    async fn health_handler() -> Json<serde_json::Value> {
+        Json(serde_json::json!({"status": "ok", "version": "test", "uptime_seconds": 1}))
+    }
+
+    fn offline_inbound(text: &str, age_secs: i64) -> InboundMessage {
+        InboundMessage {
+            channel: ChannelId("slack".to_string()),
+            peer_id: PeerId("u1".to_string()),
+            chat_type: ChatType::Direct,
+            text: text.to_string(),
+            media: Vec::new(),
+            metadata: serde_json::json!({"channel": "C123"}),
+            timestamp: Utc::now() - chrono::Duration::seconds(age_secs),
+            control: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn unreachable_server_holds_messages_and_replays_them_in_order() {
+        let store = Arc::new(
+            GatewayStore::open_in_memory()
+                .await
+                .expect("open in-memory gateway store"),
+        );
+        let test_channel = Arc::new(TestChannel::new("slack"));
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("slack".to_string(), test_channel.clone());
+
+        let dead_addr = {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+                .await
+                .expect("bind placeholder listener");
+            listener.local_addr().expect("read listener addr")
+        };
+        let offline_dispatcher = Arc::new(Dispatcher::new(
+            StakpakClient::new(format!("http://{dead_addr}"), String::new()),
+            channels.clone(),
+            store.clone(),
+            RouterConfig::default(),
+            None,
+            ApprovalMode::AllowAll,
+            Vec::new(),
+            HashMap::new(),
+            "{channel}-{peer}".to_string(),
+        ));
+
+        let (run_tx, _run_rx) = mpsc::channel(8);
+        for (text, age_secs) in [("first", 20), ("second", 10)] {
+            offline_dispatcher
+                .handle_inbound(offline_inbound(text, age_secs), run_tx.clone())
+                .await
+                .expect("hold message while offline");
+        }
+
+        assert!(offline_dispatcher.offline.load(Ordering::Relaxed));
+        assert_eq!(store.count_offline_inbound().await.expect("count"), 2);
+        let sent = test_channel.sent.lock().await.clone();
+        assert_eq!(sent.len(), 1, "the sender is told about the outage once");
+        assert_eq!(sent[0].text, OFFLINE_NOTICE);
+
+        let server_state = FanInServerState {
+            side_session_id: uuid::Uuid::new_v4(),
+            run_id: uuid::Uuid::new_v4(),
+            message_sessions: Arc::new(AsyncMutex::new(Vec::new())),
+        };
+        let app = Router::new()
+            .route("/v1/health", get(health_handler))
+            .route("/v1/sessions", post(fan_in_create_session_handler))
+            .route(
+                "/v1/sessions/{session_id}/messages",
+                post(fan_in_messages_handler),
+            )
+            .route(
+                "/v1/sessions/{session_id}/events",
+                get(fan_in_events_handler),
+            )
+            .with_state(server_state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("read listener addr");
+        let server_handle = tokio::spawn(async move {
+            let _ = axum::serve(listener, app.into_make_service()).await;
+        });
+
+        let dispatcher = Arc::new(Dispatcher::new(
+            StakpakClient::new(format!("http://{addr}"), String::new()),
+            channels,
+            store.clone(),
+            RouterConfig::default(),
+            None,
+            ApprovalMode::AllowAll,
+            Vec::new(),
+            HashMap::new(),
+            "{channel}-{peer}".to_string(),
+        ));
+        dispatcher.offline.store(true, Ordering::Relaxed);
+        dispatcher.replay_offline_inbound(run_tx).await;
+
+        assert!(!dispatcher.offline.load(Ordering::Relaxed));
+        assert_eq!(store.count_offline_inbound().await.expect("count"), 0);
+        // "first" started the run; "second" waits behind it in the session queue.
+        let session_id = server_state.side_session_id.to_string();
+        assert_eq!(server_state.message_sessions.lock().await.len(), 1);
+        let queued_texts = dispatcher
+            .pending_queues
+            .lock()
+            .expect("lock pending_queues")
+            .get(&session_id)
+            .map(|queue| {
+                queue
+                    .iter()
+                    .map(|item| item.text.clone())
+                    .collect::<Vec<_>>()
+            });
+        assert_eq!(queued_texts, Some(vec!["second".to_string()]));
+
+        server_handle.abort();
+    }
+
+    #[test]
+    fn event_cursors_are_per_run_and_fold_into_session_on_finish() {
+        let mut cursors = EventCursors::default();
+        cursors.advance_run("run-a", 12);
+        cursors.advance_run("run-a", 7);
+        cursors.advance_run("run-b", 30);
+
+        assert_eq!(cursors.session("s1"), None);
+
+        cursors.finish_run("s1", "run-a", Some(10));
+        assert_eq!(cursors.session("s1"), Some(12));
+        assert_eq!(cursors.runs.get("run-b"), Some(&30));
+
+        cursors.finish_run("s1", "run-c", Some(5));
+        assert_eq!(cursors.session("s1"), Some(12));
+
+        cursors.finish_run("s2", "run-b", None);
+        assert_eq!(cursors.session("s2"), Some(30));
+        assert!(cursors.runs.is_empty());
+    }
+
+    #[test]
+    fn reply_attribution_quotes_origin_only_while_runs_overlap() {
+        assert_eq!(
+            render_reply_quote("\n  Why is checkout slow?  \nSee logs"),
+            "> Why is checkout slow?"
+        );
+        assert_eq!(render_reply_quote(&"x".repeat(100)).chars().count(), 85);
+
+        let attribution = RunAttribution {
+            quote: render_reply_quote("Why is checkout slow?"),
+            in_flight: Arc::new(AtomicUsize::new(1)),
+        };
+        assert_eq!(attribution.apply("Looking.".to_string()), "Looking.");
+
+        attribution.in_flight.store(2, Ordering::Relaxed);
+        assert_eq!(
+            attribution.apply("Looking.".to_string()),
+            "> Why is checkout slow?\nLooking."
+        );
+    }
+
     fn inbound() -> InboundMessage {
         InboundMessage {
             channel: ChannelId("slack".to_string()),
@@ -2591,6 +4979,7 @@ mod tests {
             media: Vec::new(),
             metadata: serde_json::Value::Null,
             timestamp: Utc::now(),
+            control: None,
         }
     }
 
@@ -3198,6 +5587,7 @@ mod tests {
                     auto_approve: Some(AutoApproveOverride::AllowList(vec!["view".to_string()])),
                     system_prompt: Some("ops prompt".to_string()),
                     max_turns: Some(16),
+                    env: None,
                 },
             )]),
         };
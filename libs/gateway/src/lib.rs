@@ -1,22 +1,52 @@
+//! Chat gateway for Stakpak: routes Telegram, Discord, Slack, WhatsApp, Matrix
+//! and Rocket.Chat messages (or any [`Channel`] you implement) to Stakpak
+//! sessions and posts the agent's replies, tool calls and approval prompts
+//! back.
+//!
+//! The CLI runs it from `stakpak gateway` and autopilot. To embed it in
+//! your own service, assemble a [`Gateway`] with [`Gateway::builder`]:
+//!
+//! - [`GatewayBuilder::channel`] adds a channel implemented in code,
+//! - [`GatewayBuilder::store`] replaces the SQLite session store with any
+//!   [`StoreBackend`],
+//! - [`Gateway::run`] drives channel listeners and the dispatcher until
+//!   cancelled, and [`Gateway::api_router`] serves the HTTP API.
+//!
+//! See `examples/embed_gateway.rs` for a complete embedding binary. The
+//! items re-exported here are the supported surface; the modules expose
+//! internals used by the CLI.
+
 pub mod api;
+pub mod approval;
+pub mod audit;
+pub mod bench;
 pub mod channels;
 pub mod chunking;
 pub mod client;
 pub mod config;
 pub mod dispatcher;
+pub mod matrix_html;
 pub mod router;
 pub mod runtime;
+pub mod schedules;
 pub mod slack_blocks;
 pub mod store;
 pub mod targeting;
+pub mod templates;
+pub mod tool_renderers;
 pub mod types;
 
+pub use audit::{ApprovalAuditFilter, ApprovalAuditRecord, AuditDecision, AuditSource};
 pub use channels::{Channel, ChannelTestResult};
 pub use client::StakpakClient;
-pub use config::{ApprovalMode, GatewayCliFlags, GatewayConfig};
+pub use config::{ApprovalMode, ApprovalRules, GatewayCliFlags, GatewayConfig};
 pub use router::{Binding, BindingMatch, DmScope, PeerMatch, PeerMatchKind, RouterConfig};
-pub use runtime::{Gateway, build_channels};
-pub use store::{GatewayStore, SessionMapping};
+pub use runtime::{DispatcherProfileOverrides, Gateway, GatewayBuilder, build_channels};
+pub use schedules::{ScheduleControl, ScheduleSummary};
+pub use store::{
+    GatewayStore, SessionFootprint, SessionMapping, SessionMappingFilter, StoreBackend,
+};
+pub use tool_renderers::{ToolResultRenderer, ToolResultRenderers, ToolResultView};
 pub use types::{
     ChannelId, ChatType, DeliveryContext, InboundMessage, MediaAttachment, OutboundReply, PeerId,
 };
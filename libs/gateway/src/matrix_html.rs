@@ -0,0 +1,154 @@
+//! Markdown → Matrix `org.matrix.custom.html`.
+//!
+//! Emits only the tags the Matrix spec recommends clients render. Raw HTML
+//! in the markdown is escaped rather than passed through, and images become
+//! links since Matrix `<img>` only accepts `mxc://` URLs.
+
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+
+/// Render markdown as the `formatted_body` of a Matrix message.
+pub fn markdown_to_matrix_html(text: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut html = String::with_capacity(text.len() + text.len() / 4);
+    let mut in_table_head = false;
+
+    for event in Parser::new_ext(text, options) {
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::Paragraph => html.push_str("<p>"),
+                Tag::Heading { level, .. } => {
+                    html.push_str(&format!("<h{}>", heading_number(level)));
+                }
+                Tag::BlockQuote(_) => html.push_str("<blockquote>"),
+                Tag::CodeBlock(CodeBlockKind::Fenced(language)) if !language.trim().is_empty() => {
+                    let language = language.split_whitespace().next().unwrap_or_default();
+                    html.push_str("<pre><code class=\"language-");
+                    push_escaped(&mut html, language);
+                    html.push_str("\">");
+                }
+                Tag::CodeBlock(_) => html.push_str("<pre><code>"),
+                Tag::List(Some(1)) => html.push_str("<ol>"),
+                Tag::List(Some(start)) => html.push_str(&format!("<ol start=\"{start}\">")),
+                Tag::List(None) => html.push_str("<ul>"),
+                Tag::Item => html.push_str("<li>"),
+                Tag::Table(_) => html.push_str("<table>"),
+                Tag::TableHead => {
+                    in_table_head = true;
+                    html.push_str("<thead><tr>");
+                }
+                Tag::TableRow => html.push_str("<tr>"),
+                Tag::TableCell => html.push_str(if in_table_head { "<th>" } else { "<td>" }),
+                Tag::Emphasis => html.push_str("<em>"),
+                Tag::Strong => html.push_str("<strong>"),
+                Tag::Strikethrough => html.push_str("<del>"),
+                Tag::Superscript => html.push_str("<sup>"),
+                Tag::Subscript => html.push_str("<sub>"),
+                Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. } => {
+                    html.push_str("<a href=\"");
+                    push_escaped(&mut html, &dest_url);
+                    html.push_str("\">");
+                }
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                TagEnd::Paragraph => html.push_str("</p>"),
+                TagEnd::Heading(level) => {
+                    html.push_str(&format!("</h{}>", heading_number(level)));
+                }
+                TagEnd::BlockQuote(_) => html.push_str("</blockquote>"),
+                TagEnd::CodeBlock => html.push_str("</code></pre>"),
+                TagEnd::List(true) => html.push_str("</ol>"),
+                TagEnd::List(false) => html.push_str("</ul>"),
+                TagEnd::Item => html.push_str("</li>"),
+                TagEnd::Table => html.push_str("</tbody></table>"),
+                TagEnd::TableHead => {
+                    in_table_head = false;
+                    html.push_str("</tr></thead><tbody>");
+                }
+                TagEnd::TableRow => html.push_str("</tr>"),
+                TagEnd::TableCell => html.push_str(if in_table_head { "</th>" } else { "</td>" }),
+                TagEnd::Emphasis => html.push_str("</em>"),
+                TagEnd::Strong => html.push_str("</strong>"),
+                TagEnd::Strikethrough => html.push_str("</del>"),
+                TagEnd::Superscript => html.push_str("</sup>"),
+                TagEnd::Subscript => html.push_str("</sub>"),
+                TagEnd::Link | TagEnd::Image => html.push_str("</a>"),
+                _ => {}
+            },
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                push_escaped(&mut html, &text);
+            }
+            Event::Code(code) => {
+                html.push_str("<code>");
+                push_escaped(&mut html, &code);
+                html.push_str("</code>");
+            }
+            Event::SoftBreak => html.push('\n'),
+            Event::HardBreak => html.push_str("<br>"),
+            Event::Rule => html.push_str("<hr>"),
+            Event::TaskListMarker(checked) => html.push_str(if checked { "☑ " } else { "☐ " }),
+            _ => {}
+        }
+    }
+
+    html
+}
+
+fn heading_number(level: HeadingLevel) -> usize {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+fn push_escaped(html: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            _ => html.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::markdown_to_matrix_html;
+
+    #[test]
+    fn renders_inline_formatting_and_links() {
+        assert_eq!(
+            markdown_to_matrix_html(
+                "**Deploy** _done_ ~~late~~, see `kubectl` [logs](https://x.io/a?b=1&c=2)"
+            ),
+            "<p><strong>Deploy</strong> <em>done</em> <del>late</del>, see <code>kubectl</code> <a href=\"https://x.io/a?b=1&amp;c=2\">logs</a></p>"
+        );
+    }
+
+    #[test]
+    fn renders_blocks_lists_and_tables() {
+        let html = markdown_to_matrix_html(
+            "## Status\n\n1. one\n2. two\n\n- [x] done\n\n```rust\nlet a = 1 < 2;\n```\n\n| pod | ready |\n|---|---|\n| api | 1/1 |\n",
+        );
+        assert_eq!(
+            html,
+            "<h2>Status</h2><ol><li>one</li><li>two</li></ol><ul><li>☑ done</li></ul><pre><code class=\"language-rust\">let a = 1 &lt; 2;\n</code></pre><table><thead><tr><th>pod</th><th>ready</th></tr></thead><tbody><tr><td>api</td><td>1/1</td></tr></tbody></table>"
+        );
+    }
+
+    #[test]
+    fn escapes_raw_html_and_links_images() {
+        assert_eq!(
+            markdown_to_matrix_html("<script>alert(1)</script>\n\n![graph](https://x.io/g.png)"),
+            "&lt;script&gt;alert(1)&lt;/script&gt;\n<p><a href=\"https://x.io/g.png\">graph</a></p>"
+        );
+    }
+}
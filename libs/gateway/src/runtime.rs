@@ -7,16 +7,28 @@ use tracing::{error, info, warn};
 
 use crate::{
     api::{GatewayApiState, router as api_router},
-    channels::{Channel, discord::DiscordChannel, slack::SlackChannel, telegram::TelegramChannel},
+    channels::{
+        Channel, discord::DiscordChannel, matrix::MatrixChannel, redacting::RedactingChannel,
+        rocketchat::RocketChatChannel, slack::SlackChannel, telegram::TelegramChannel,
+        whatsapp::WhatsAppChannel,
+    },
     client::StakpakClient,
     config::GatewayConfig,
     dispatcher::{Dispatcher, RunOverrideResolver, noop_run_override_resolver},
-    store::GatewayStore,
+    schedules::ScheduleControl,
+    store::{GatewayStore, StoreBackend},
+    tool_renderers::ToolResultRenderers,
 };
 
+/// A running gateway: channel listeners feeding the dispatcher, which routes
+/// messages to Stakpak sessions and posts replies back.
+///
+/// Build one with [`Gateway::builder`] to register channels or a store in
+/// code, then drive it with [`Gateway::run`] until the token is cancelled.
+/// Serve [`Gateway::api_router`] to accept `/v1/gateway` requests.
 pub struct Gateway {
     config: GatewayConfig,
-    store: Arc<GatewayStore>,
+    store: Arc<dyn StoreBackend>,
     channels: HashMap<String, Arc<dyn Channel>>,
     dispatcher: Arc<Dispatcher>,
     api_state: Arc<GatewayApiState>,
@@ -47,19 +59,91 @@ impl DispatcherProfileOverrides {
     }
 }
 
-impl Gateway {
-    pub async fn new(config: GatewayConfig) -> Result<Self> {
-        Self::new_with_profile_overrides(config, DispatcherProfileOverrides::none()).await
+/// Assembles a [`Gateway`] from its config plus channels, a store and
+/// renderers supplied in code.
+pub struct GatewayBuilder {
+    config: GatewayConfig,
+    channels: Vec<Arc<dyn Channel>>,
+    store: Option<Arc<dyn StoreBackend>>,
+    profile_overrides: DispatcherProfileOverrides,
+    tool_renderers: ToolResultRenderers,
+    schedule_control: Option<Arc<dyn ScheduleControl>>,
+}
+
+impl GatewayBuilder {
+    pub fn new(config: GatewayConfig) -> Self {
+        Self {
+            config,
+            channels: Vec::new(),
+            store: None,
+            profile_overrides: DispatcherProfileOverrides::none(),
+            tool_renderers: ToolResultRenderers::default(),
+            schedule_control: None,
+        }
     }
 
-    pub async fn new_with_profile_overrides(
-        config: GatewayConfig,
-        profile_overrides: DispatcherProfileOverrides,
-    ) -> Result<Self> {
-        config.validate()?;
+    /// Run `channel` alongside the configured ones, under its
+    /// [`Channel::id`]. It replaces a configured channel with the same id.
+    pub fn channel(mut self, channel: Arc<dyn Channel>) -> Self {
+        self.channels.push(channel);
+        self
+    }
+
+    /// Keep sessions in `store` instead of the SQLite file at
+    /// `gateway.store_path`.
+    pub fn store(mut self, store: Arc<dyn StoreBackend>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    pub fn profile_overrides(mut self, profile_overrides: DispatcherProfileOverrides) -> Self {
+        self.profile_overrides = profile_overrides;
+        self
+    }
+
+    /// Renderers for tool results posted to channels; the built-in ones by
+    /// default.
+    pub fn tool_result_renderers(mut self, renderers: ToolResultRenderers) -> Self {
+        self.tool_renderers = renderers;
+        self
+    }
 
-        let store = Arc::new(GatewayStore::open(&config.gateway.store_path).await?);
-        let channels = build_channels(&config)?;
+    /// Answer `/autopilot` schedule commands from users on
+    /// `gateway.schedule_control_allowlist` with `control`.
+    pub fn schedule_control(mut self, control: Arc<dyn ScheduleControl>) -> Self {
+        self.schedule_control = Some(control);
+        self
+    }
+
+    pub async fn build(self) -> Result<Gateway> {
+        let Self {
+            config,
+            channels: custom_channels,
+            store,
+            profile_overrides,
+            tool_renderers,
+            schedule_control,
+        } = self;
+
+        let custom_names: Vec<String> = custom_channels
+            .iter()
+            .map(|channel| channel.id().0.clone())
+            .collect();
+        let custom_names: Vec<&str> = custom_names.iter().map(String::as_str).collect();
+        config.validate_with_custom_channels(&custom_names)?;
+
+        let store = match store {
+            Some(store) => store,
+            None => Arc::new(GatewayStore::open(&config.gateway.store_path).await?),
+        };
+        let mut channels = build_channels(&config)?;
+        for channel in custom_channels {
+            channels.insert(channel.id().0.clone(), channel);
+        }
+        let channels: HashMap<String, Arc<dyn Channel>> = channels
+            .into_iter()
+            .map(|(id, channel)| (id, RedactingChannel::wrap(channel)))
+            .collect();
 
         if channels.is_empty() {
             return Err(anyhow!("gateway has no enabled channels"));
@@ -67,23 +151,35 @@ impl Gateway {
 
         let client = StakpakClient::new(config.server.url.clone(), config.server.token.clone());
 
-        let dispatcher = Arc::new(
-            Dispatcher::new(
-                client.clone(),
-                channels.clone(),
-                store.clone(),
-                config.router_config(),
-                config.gateway.model.clone(),
-                config.gateway.approval_mode.clone(),
-                config.gateway.approval_allowlist.clone(),
-                config.channels.overrides_map(),
-                config.gateway.title_template.clone(),
-            )
-            .with_profile_resolution(
-                profile_overrides.channel_profiles,
-                profile_overrides.override_resolver,
-            ),
+        let mut dispatcher = Dispatcher::new(
+            client.clone(),
+            channels.clone(),
+            store.clone(),
+            config.router_config(),
+            config.gateway.model.clone(),
+            config.gateway.approval_mode.clone(),
+            config.gateway.approval_allowlist.clone(),
+            config.channels.overrides_map(),
+            config.gateway.title_template.clone(),
+        )
+        .with_profile_resolution(
+            profile_overrides.channel_profiles,
+            profile_overrides.override_resolver,
+        )
+        .with_approval_rules(config.gateway.approval_rules.clone())
+        .with_approval_reminders(config.gateway.approval_reminders.clone())
+        .with_max_concurrent_runs(config.gateway.max_concurrent_runs_per_session)
+        .with_tool_results(config.gateway.tool_results, tool_renderers)
+        .with_usage_footer(config.gateway.usage_footer)
+        .with_message_templates(
+            config.gateway.templates.clone(),
+            config.channels.templates_map(),
         );
+        if let Some(control) = schedule_control {
+            dispatcher = dispatcher
+                .with_schedule_control(control, config.gateway.schedule_control_allowlist.clone());
+        }
+        let dispatcher = Arc::new(dispatcher);
 
         let api_state = Arc::new(GatewayApiState {
             channels: channels.clone(),
@@ -108,7 +204,7 @@ impl Gateway {
             );
         }
 
-        Ok(Self {
+        Ok(Gateway {
             config,
             store,
             channels,
@@ -116,6 +212,26 @@ impl Gateway {
             api_state,
         })
     }
+}
+
+impl Gateway {
+    pub fn builder(config: GatewayConfig) -> GatewayBuilder {
+        GatewayBuilder::new(config)
+    }
+
+    pub async fn new(config: GatewayConfig) -> Result<Self> {
+        GatewayBuilder::new(config).build().await
+    }
+
+    pub async fn new_with_profile_overrides(
+        config: GatewayConfig,
+        profile_overrides: DispatcherProfileOverrides,
+    ) -> Result<Self> {
+        GatewayBuilder::new(config)
+            .profile_overrides(profile_overrides)
+            .build()
+            .await
+    }
 
     pub fn api_router(&self) -> axum::Router {
         api_router(self.api_state.clone())
@@ -125,6 +241,8 @@ impl Gateway {
         &self.channels
     }
 
+    /// Start every channel listener and the dispatcher, and run until
+    /// `cancel` fires; then stop them and wait for them to finish.
     pub async fn run(&self, cancel: CancellationToken) -> Result<()> {
         let (inbound_tx, inbound_rx) = mpsc::channel(512);
 
@@ -220,21 +338,150 @@ pub fn build_channels(config: &GatewayConfig) -> Result<HashMap<String, Arc<dyn
     }
 
     if let Some(discord) = &config.channels.discord {
-        channels.insert(
-            "discord".to_string(),
-            Arc::new(DiscordChannel::new(discord.token.clone())),
-        );
+        let mut channel = DiscordChannel::new(discord.token.clone());
+        if let Some(reaction) = &discord.cancel_reaction {
+            channel = channel.with_cancel_reaction(reaction.clone());
+        }
+        channels.insert("discord".to_string(), Arc::new(channel));
     }
 
     if let Some(slack) = &config.channels.slack {
-        channels.insert(
-            "slack".to_string(),
-            Arc::new(SlackChannel::new(
-                slack.bot_token.clone(),
-                slack.app_token.clone(),
-            )),
+        let mut channel = SlackChannel::new(slack.bot_token.clone(), slack.app_token.clone())
+            .with_table_rendering(slack.table_rendering)
+            .with_block_format(slack.block_format)
+            .with_mentions(slack.mentions.clone());
+        if let Some(reaction) = &slack.cancel_reaction {
+            channel = channel.with_cancel_reaction(reaction.clone());
+        }
+        channels.insert("slack".to_string(), Arc::new(channel));
+    }
+
+    if let Some(whatsapp) = &config.channels.whatsapp {
+        let channel = WhatsAppChannel::new(
+            whatsapp.access_token.clone(),
+            whatsapp.phone_number_id.clone(),
+            whatsapp.verify_token.clone(),
+            whatsapp.app_secret.clone(),
+        )
+        .with_listen(whatsapp.listen.clone())
+        .with_template(whatsapp.template.clone());
+        channels.insert("whatsapp".to_string(), Arc::new(channel));
+    }
+
+    if let Some(matrix) = &config.channels.matrix {
+        let channel = MatrixChannel::new(matrix.homeserver.clone(), matrix.access_token.clone())
+            .with_rooms(matrix.rooms.clone())
+            .with_auto_join(matrix.auto_join);
+        channels.insert("matrix".to_string(), Arc::new(channel));
+    }
+
+    if let Some(rocketchat) = &config.channels.rocketchat {
+        let channel = RocketChatChannel::new(
+            rocketchat.server_url.clone(),
+            rocketchat.user_id.clone(),
+            rocketchat.auth_token.clone(),
         );
+        channels.insert("rocketchat".to_string(), Arc::new(channel));
     }
 
     Ok(channels)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        channels::ChannelTestResult,
+        types::{ChannelId, InboundMessage, OutboundReply},
+    };
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    struct IdleChannel {
+        id: ChannelId,
+        started: AtomicBool,
+    }
+
+    #[async_trait]
+    impl Channel for IdleChannel {
+        fn id(&self) -> &ChannelId {
+            &self.id
+        }
+
+        fn display_name(&self) -> &str {
+            "Idle"
+        }
+
+        async fn start(
+            &self,
+            _inbound_tx: mpsc::Sender<InboundMessage>,
+            cancel: CancellationToken,
+        ) -> Result<()> {
+            self.started.store(true, Ordering::SeqCst);
+            cancel.cancelled().await;
+            Ok(())
+        }
+
+        async fn send(&self, _reply: OutboundReply) -> Result<()> {
+            Ok(())
+        }
+
+        async fn test(&self) -> Result<ChannelTestResult> {
+            Ok(ChannelTestResult {
+                channel: self.id.0.clone(),
+                identity: "idle".to_string(),
+                details: "ok".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn builder_runs_custom_channel_with_injected_store() {
+        let channel = Arc::new(IdleChannel {
+            id: ChannelId::from("matrix"),
+            started: AtomicBool::new(false),
+        });
+        let store = GatewayStore::open_in_memory().await.expect("store");
+        let gateway = Gateway::builder(GatewayConfig::default())
+            .channel(channel.clone())
+            .store(Arc::new(store))
+            .build()
+            .await
+            .expect("build gateway");
+        assert_eq!(
+            gateway.channels().keys().collect::<Vec<_>>(),
+            vec!["matrix"]
+        );
+
+        let cancel = CancellationToken::new();
+        let run = {
+            let cancel = cancel.clone();
+            async move { gateway.run(cancel).await }
+        };
+        let stop = async {
+            while !channel.started.load(Ordering::SeqCst) {
+                tokio::task::yield_now().await;
+            }
+            cancel.cancel();
+        };
+        let (result, ()) = tokio::join!(run, stop);
+        result.expect("run gateway");
+    }
+
+    #[tokio::test]
+    async fn builder_without_any_channel_is_rejected() {
+        let store = GatewayStore::open_in_memory().await.expect("store");
+        let error = match Gateway::builder(GatewayConfig::default())
+            .store(Arc::new(store))
+            .build()
+            .await
+        {
+            Ok(_) => panic!("gateway without channels should not build"),
+            Err(error) => error,
+        };
+        assert!(
+            error.to_string().contains("channel"),
+            "unexpected error: {error}"
+        );
+    }
+}
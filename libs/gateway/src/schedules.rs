@@ -0,0 +1,206 @@
+//! Schedule control from chat.
+//!
+//! `/autopilot list`, `/autopilot run <name>`, `/autopilot pause <name>` and
+//! `/autopilot enable <name>` let allowed users drive autopilot schedules
+//! from any channel. The gateway parses the command and checks the sender
+//! against `gateway.schedule_control_allowlist`; the host that owns the
+//! schedules carries it out through a [`ScheduleControl`] registered with
+//! [`GatewayBuilder::schedule_control`](crate::GatewayBuilder::schedule_control).
+
+use async_trait::async_trait;
+
+use crate::types::{ChannelId, PeerId};
+
+/// Carries out `/autopilot` commands against the host's schedules.
+#[async_trait]
+pub trait ScheduleControl: Send + Sync {
+    /// Every configured schedule, in config order.
+    async fn list(&self) -> Result<Vec<ScheduleSummary>, String>;
+
+    /// Queue `name` to run now.
+    async fn run(&self, name: &str) -> Result<(), String>;
+
+    /// Enable or pause `name`.
+    async fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleSummary {
+    pub name: String,
+    pub cron: String,
+    pub enabled: bool,
+    /// Status of the most recent run, if the schedule has run.
+    pub last_status: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleCommand {
+    List,
+    Run(String),
+    Pause(String),
+    Enable(String),
+}
+
+pub const SCHEDULE_COMMAND_USAGE: &str = "Usage: `/autopilot list`, `/autopilot run <name>`, `/autopilot pause <name>`, `/autopilot enable <name>`";
+
+impl ScheduleCommand {
+    /// Parse the arguments of `/autopilot`.
+    pub fn parse(args: &str) -> Result<Self, String> {
+        let mut parts = args.split_whitespace();
+        let action = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let name = parts.next().map(str::to_string);
+        if parts.next().is_some() {
+            return Err(SCHEDULE_COMMAND_USAGE.to_string());
+        }
+
+        match (action.as_str(), name) {
+            ("list", None) => Ok(Self::List),
+            ("run", Some(name)) => Ok(Self::Run(name)),
+            ("pause" | "disable", Some(name)) => Ok(Self::Pause(name)),
+            ("enable" | "resume", Some(name)) => Ok(Self::Enable(name)),
+            _ => Err(SCHEDULE_COMMAND_USAGE.to_string()),
+        }
+    }
+
+    /// Carry out the command and return the reply for the chat.
+    pub async fn execute(&self, control: &dyn ScheduleControl) -> String {
+        match self {
+            Self::List => match control.list().await {
+                Ok(schedules) => render_schedule_list(&schedules),
+                Err(error) => format!("⚠️ Could not list schedules: {}", error),
+            },
+            Self::Run(name) => match control.run(name).await {
+                Ok(()) => format!("▶️ Schedule `{}` queued to run.", name),
+                Err(error) => format!("⚠️ Could not run `{}`: {}", name, error),
+            },
+            Self::Pause(name) => match control.set_enabled(name, false).await {
+                Ok(()) => format!("⏸️ Schedule `{}` paused.", name),
+                Err(error) => format!("⚠️ Could not pause `{}`: {}", name, error),
+            },
+            Self::Enable(name) => match control.set_enabled(name, true).await {
+                Ok(()) => format!("✅ Schedule `{}` enabled.", name),
+                Err(error) => format!("⚠️ Could not enable `{}`: {}", name, error),
+            },
+        }
+    }
+}
+
+/// Whether `peer` on `channel` may control schedules. Allowlist entries are
+/// `<channel>:<user id>`, a bare user id for any channel, or `*` for
+/// everyone.
+pub fn is_schedule_controller(allowlist: &[String], channel: &ChannelId, peer: &PeerId) -> bool {
+    allowlist.iter().map(|entry| entry.trim()).any(|entry| {
+        entry == "*"
+            || entry == peer.0
+            || entry
+                .split_once(':')
+                .is_some_and(|(entry_channel, user)| entry_channel == channel.0 && user == peer.0)
+    })
+}
+
+/// One line per schedule: name, state, cron and last run.
+pub fn render_schedule_list(schedules: &[ScheduleSummary]) -> String {
+    if schedules.is_empty() {
+        return "No schedules configured.".to_string();
+    }
+
+    schedules
+        .iter()
+        .map(|schedule| {
+            let state = if schedule.enabled {
+                "enabled"
+            } else {
+                "paused"
+            };
+            let mut line = format!("• `{}` — {}, `{}`", schedule.name, state, schedule.cron);
+            if let Some(status) = &schedule.last_status {
+                line.push_str(&format!(", last run {}", status));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_schedule_commands() {
+        assert_eq!(ScheduleCommand::parse("list"), Ok(ScheduleCommand::List));
+        assert_eq!(
+            ScheduleCommand::parse("RUN nightly"),
+            Ok(ScheduleCommand::Run("nightly".to_string()))
+        );
+        assert_eq!(
+            ScheduleCommand::parse("pause nightly"),
+            Ok(ScheduleCommand::Pause("nightly".to_string()))
+        );
+        assert_eq!(
+            ScheduleCommand::parse("enable  nightly "),
+            Ok(ScheduleCommand::Enable("nightly".to_string()))
+        );
+
+        for invalid in ["", "run", "list nightly", "pause a b", "delete nightly"] {
+            assert!(ScheduleCommand::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn allowlist_matches_users_per_channel_or_everywhere() {
+        let allowlist = vec!["slack:U1".to_string(), "42".to_string()];
+        let slack = ChannelId::from("slack");
+        let telegram = ChannelId::from("telegram");
+
+        assert!(is_schedule_controller(
+            &allowlist,
+            &slack,
+            &PeerId::from("U1")
+        ));
+        assert!(!is_schedule_controller(
+            &allowlist,
+            &telegram,
+            &PeerId::from("U1")
+        ));
+        assert!(is_schedule_controller(
+            &allowlist,
+            &telegram,
+            &PeerId::from("42")
+        ));
+        assert!(!is_schedule_controller(
+            &allowlist,
+            &slack,
+            &PeerId::from("U2")
+        ));
+        assert!(!is_schedule_controller(&[], &slack, &PeerId::from("U1")));
+        assert!(is_schedule_controller(
+            &["*".to_string()],
+            &slack,
+            &PeerId::from("U2")
+        ));
+    }
+
+    #[test]
+    fn lists_schedules_with_state_and_last_run() {
+        let rendered = render_schedule_list(&[
+            ScheduleSummary {
+                name: "nightly".to_string(),
+                cron: "0 2 * * *".to_string(),
+                enabled: true,
+                last_status: Some("completed".to_string()),
+            },
+            ScheduleSummary {
+                name: "disk".to_string(),
+                cron: "*/5 * * * *".to_string(),
+                enabled: false,
+                last_status: None,
+            },
+        ]);
+        assert_eq!(
+            rendered,
+            "• `nightly` — enabled, `0 2 * * *`, last run completed\n• `disk` — paused, `*/5 * * * *`"
+        );
+        assert_eq!(render_schedule_list(&[]), "No schedules configured.");
+    }
+}
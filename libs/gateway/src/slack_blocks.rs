@@ -1,5 +1,7 @@
 use pulldown_cmark::{Alignment, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
 
 /// Maximum blocks per Slack message.
 const MAX_BLOCKS_PER_MESSAGE: usize = 50;
@@ -13,6 +15,11 @@ const MAX_TABLE_ROWS: usize = 100;
 /// Maximum columns in a Slack table block.
 const MAX_TABLE_COLUMNS: usize = 20;
 
+/// Maximum characters per column in the preformatted table fallback.
+/// Longer cells are truncated with "…" so one wide cell cannot push every
+/// other column off-screen.
+const MAX_PREFORMATTED_COLUMN_WIDTH: usize = 40;
+
 /// Maximum indent level for nested lists (Slack renders ~8 levels).
 const MAX_LIST_INDENT: u32 = 8;
 
@@ -31,6 +38,9 @@ const MAX_PREFORMATTED_CHARS: usize = 30_000;
 /// to a new block. Prevents any single block from becoming excessively large.
 const MAX_RT_ELEMENTS_PER_BLOCK: usize = 256;
 
+/// Maximum characters in a `section` block's mrkdwn text.
+const SECTION_TEXT_LIMIT: usize = 3_000;
+
 /// A rendered Slack message ready for `chat.postMessage`.
 #[derive(Debug, Clone)]
 pub struct SlackMessage {
@@ -42,6 +52,64 @@ pub struct SlackMessage {
     pub fallback_text: String,
 }
 
+/// How markdown tables are rendered.
+///
+/// Some Slack clients and plan tiers don't render `table` blocks in
+/// attachments; the preformatted fallback is an aligned monospace rendering
+/// that works everywhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlackTableRendering {
+    /// Native table block in an attachment (default).
+    #[default]
+    Block,
+    /// Native table block plus a preformatted copy in the message body.
+    Both,
+    /// Preformatted text only, no table block.
+    Preformatted,
+}
+
+/// Which block types carry the message text.
+///
+/// Some enterprise Slack apps and workflows read payloads from shared
+/// webhooks and only understand classic mrkdwn `section` blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlackBlockFormat {
+    /// `rich_text` blocks, with `header` blocks for H1/H2 (default).
+    #[default]
+    RichText,
+    /// `section` blocks with mrkdwn text. Headings become bold lines and
+    /// tables are always sent preformatted.
+    Mrkdwn,
+}
+
+/// Names that `@name` and `#name` tokens resolve to, so notifications
+/// mention real users and channels instead of printing plain text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlackMentions {
+    /// `@name` → Slack user ID (e.g. `U012AB3CD`).
+    #[serde(default)]
+    pub users: BTreeMap<String, String>,
+    /// `#name` → Slack channel ID (e.g. `C012AB3CD`).
+    #[serde(default)]
+    pub channels: BTreeMap<String, String>,
+}
+
+impl SlackMentions {
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty() && self.channels.is_empty()
+    }
+}
+
+/// Per-workspace rendering settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlackRenderOptions {
+    pub table_rendering: SlackTableRendering,
+    pub block_format: SlackBlockFormat,
+    pub mentions: SlackMentions,
+}
+
 /// Convert markdown text to a sequence of Slack messages.
 ///
 /// Returns one or more `SlackMessage` values, split as needed for:
@@ -50,11 +118,42 @@ pub struct SlackMessage {
 ///
 /// On any internal error, returns a single plain-text fallback message.
 pub fn markdown_to_slack_messages(text: &str) -> Vec<SlackMessage> {
+    markdown_to_slack_messages_with_tables(text, SlackTableRendering::default())
+}
+
+/// Like [`markdown_to_slack_messages`], rendering tables with `table_rendering`.
+pub fn markdown_to_slack_messages_with_tables(
+    text: &str,
+    table_rendering: SlackTableRendering,
+) -> Vec<SlackMessage> {
+    markdown_to_slack_messages_with_options(
+        text,
+        &SlackRenderOptions {
+            table_rendering,
+            ..SlackRenderOptions::default()
+        },
+    )
+}
+
+/// Like [`markdown_to_slack_messages`], rendering with `options`.
+pub fn markdown_to_slack_messages_with_options(
+    text: &str,
+    options: &SlackRenderOptions,
+) -> Vec<SlackMessage> {
     if text.trim().is_empty() {
         return Vec::new();
     }
 
-    match render_blocks(text) {
+    let rendered = match options.block_format {
+        SlackBlockFormat::RichText => {
+            render_blocks(text, options.table_rendering).map(|mut rendered| {
+                convert_inline_tokens(&mut rendered, &options.mentions);
+                rendered
+            })
+        }
+        SlackBlockFormat::Mrkdwn => render_mrkdwn_blocks(text, &options.mentions),
+    };
+    match rendered {
         Ok(rendered) => {
             let fallback = generate_fallback_text(text);
             split_into_messages(rendered.blocks, rendered.tables, &fallback)
@@ -156,6 +255,9 @@ struct TableState {
     column_index: usize,
     /// Total rows seen (including those dropped due to MAX_TABLE_ROWS).
     total_rows_seen: usize,
+    /// Plain-text copy of `rows` for the preformatted fallback.
+    plain_rows: Vec<Vec<String>>,
+    current_plain_row: Vec<String>,
 }
 
 impl TableState {
@@ -169,17 +271,54 @@ impl TableState {
             in_header: false,
             column_index: 0,
             total_rows_seen: 0,
+            plain_rows: Vec::new(),
+            current_plain_row: Vec::new(),
         }
     }
 }
 
+/// Markdown extensions every renderer parses.
+fn markdown_options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_FOOTNOTES
+}
+
+/// Footnote numbers in order of first appearance, reference or definition.
+#[derive(Debug, Default)]
+struct FootnoteNumbers(HashMap<String, usize>);
+
+impl FootnoteNumbers {
+    fn number(&mut self, label: &str) -> usize {
+        let next = self.0.len() + 1;
+        *self.0.entry(label.to_string()).or_insert(next)
+    }
+}
+
+/// A footnote number as superscript digits (`12` → `¹²`).
+fn superscript(number: usize) -> String {
+    number
+        .to_string()
+        .chars()
+        .map(|digit| match digit {
+            '0' => '⁰',
+            '1' => '¹',
+            '2' => '²',
+            '3' => '³',
+            '4' => '⁴',
+            '5' => '⁵',
+            '6' => '⁶',
+            '7' => '⁷',
+            '8' => '⁸',
+            _ => '⁹',
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // Core renderer
 // ---------------------------------------------------------------------------
 
-fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
-    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
-    let parser = Parser::new_ext(text, options);
+fn render_blocks(text: &str, table_rendering: SlackTableRendering) -> Result<RenderedBlocks, ()> {
+    let parser = Parser::new_ext(text, markdown_options());
 
     let mut blocks: Vec<BlockOrTable> = Vec::new();
     let mut tables: Vec<Value> = Vec::new();
@@ -203,6 +342,12 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
     // Header text accumulator (for H1/H2 which use plain_text header blocks).
     let mut header_text_buffer: Option<String> = None;
 
+    let mut footnote_numbers = FootnoteNumbers::default();
+    // Number of the footnote definition being rendered; its content is
+    // collected for the notes section instead of flushed in place.
+    let mut current_footnote: Option<usize> = None;
+    let mut footnotes: Vec<(usize, Vec<Value>)> = Vec::new();
+
     /// Flush inline_elements into a rich_text_section and push to rt_elements.
     /// If rt_elements exceeds the per-block element limit, auto-flush to a new block.
     fn flush_section(
@@ -233,6 +378,37 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
         }
     }
 
+    /// Flush inline_elements ahead of a nested block: directly inside a
+    /// blockquote they stay quoted, elsewhere they become a section.
+    fn flush_before_nested_block(
+        context_stack: &[RenderContext],
+        inline_elements: &mut Vec<Value>,
+        rt_elements: &mut Vec<Value>,
+        blocks: &mut Vec<BlockOrTable>,
+    ) {
+        if context_stack.last() == Some(&RenderContext::BlockQuote) {
+            if !inline_elements.is_empty() {
+                rt_elements.push(json!({
+                    "type": "rich_text_quote",
+                    "elements": std::mem::take(inline_elements)
+                }));
+            }
+        } else {
+            flush_section(inline_elements, rt_elements, blocks);
+        }
+    }
+
+    /// Lists and code blocks inside a blockquote can't nest in a
+    /// rich_text_quote, so they carry a quote border instead.
+    fn with_quote_border(mut element: Value, context_stack: &[RenderContext]) -> Value {
+        if context_stack.contains(&RenderContext::BlockQuote) {
+            element
+                .as_object_mut()
+                .map(|o| o.insert("border".to_string(), json!(1)));
+        }
+        element
+    }
+
     fn current_context(stack: &[RenderContext]) -> Option<&RenderContext> {
         stack.last()
     }
@@ -256,23 +432,49 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
                     || current_context(&context_stack) == Some(&RenderContext::ListItem)
                 {
                     // Inside blockquote or list item — don't create new context.
+                    // Separate a quote's paragraphs with a line break.
+                    if current_context(&context_stack) == Some(&RenderContext::BlockQuote)
+                        && !inline_elements.is_empty()
+                    {
+                        inline_elements.push(json!({ "type": "text", "text": "\n" }));
+                    }
                     continue;
                 }
                 context_stack.push(RenderContext::Paragraph);
             }
 
             Event::Start(Tag::CodeBlock(_kind)) => {
-                flush_section(&mut inline_elements, &mut rt_elements, &mut blocks);
+                flush_before_nested_block(
+                    &context_stack,
+                    &mut inline_elements,
+                    &mut rt_elements,
+                    &mut blocks,
+                );
                 context_stack.push(RenderContext::CodeBlock);
             }
 
             Event::Start(Tag::BlockQuote(_)) => {
-                flush_section(&mut inline_elements, &mut rt_elements, &mut blocks);
+                flush_before_nested_block(
+                    &context_stack,
+                    &mut inline_elements,
+                    &mut rt_elements,
+                    &mut blocks,
+                );
                 context_stack.push(RenderContext::BlockQuote);
             }
 
-            Event::Start(Tag::List(start_num)) => {
+            Event::Start(Tag::FootnoteDefinition(label)) => {
                 flush_section(&mut inline_elements, &mut rt_elements, &mut blocks);
+                current_footnote = Some(footnote_numbers.number(&label));
+            }
+
+            Event::Start(Tag::List(start_num)) => {
+                flush_before_nested_block(
+                    &context_stack,
+                    &mut inline_elements,
+                    &mut rt_elements,
+                    &mut blocks,
+                );
                 let indent = if list_stack.is_empty() {
                     // Starting a new top-level list: flush any accumulated rich_text.
                     0
@@ -307,6 +509,7 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
             Event::Start(Tag::TableRow) => {
                 if let Some(ref mut ts) = table_state {
                     ts.current_row = Vec::new();
+                    ts.current_plain_row = Vec::new();
                     ts.column_index = 0;
                 }
             }
@@ -441,6 +644,20 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
                 }));
             }
 
+            Event::FootnoteReference(label) => {
+                let marker = superscript(footnote_numbers.number(&label));
+                if let Some(ref mut buf) = header_text_buffer {
+                    buf.push_str(&marker);
+                } else if let Some(ref mut buf) = link_text_buffer {
+                    buf.push_str(&marker);
+                } else if let Some(ref mut ts) = table_state {
+                    ts.current_cell_elements
+                        .push(json!({ "type": "text", "text": marker }));
+                } else {
+                    inline_elements.push(json!({ "type": "text", "text": marker }));
+                }
+            }
+
             Event::SoftBreak => {
                 if let Some(ref mut buf) = header_text_buffer {
                     buf.push(' ');
@@ -520,9 +737,24 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
                     continue;
                 }
                 context_stack.pop();
+                if current_footnote.is_some() {
+                    // Keep a footnote's paragraphs together for the notes section.
+                    inline_elements.push(json!({ "type": "text", "text": "\n" }));
+                    continue;
+                }
                 flush_section(&mut inline_elements, &mut rt_elements, &mut blocks);
             }
 
+            Event::End(TagEnd::FootnoteDefinition) => {
+                let mut elements = std::mem::take(&mut inline_elements);
+                while elements.last().and_then(|e| e["text"].as_str()) == Some("\n") {
+                    elements.pop();
+                }
+                if let Some(number) = current_footnote.take() {
+                    footnotes.push((number, elements));
+                }
+            }
+
             Event::End(TagEnd::CodeBlock) => {
                 context_stack.pop();
                 // Collect all text from inline_elements into a single string.
@@ -534,16 +766,22 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
 
                 if full_text.is_empty() {
                     // Empty code block — emit with a single space to avoid Slack errors.
-                    rt_elements.push(json!({
-                        "type": "rich_text_preformatted",
-                        "elements": [{ "type": "text", "text": " " }]
-                    }));
+                    rt_elements.push(with_quote_border(
+                        json!({
+                            "type": "rich_text_preformatted",
+                            "elements": [{ "type": "text", "text": " " }]
+                        }),
+                        &context_stack,
+                    ));
                 } else if full_text.chars().count() <= MAX_PREFORMATTED_CHARS {
                     // Fits in a single preformatted block.
-                    rt_elements.push(json!({
-                        "type": "rich_text_preformatted",
-                        "elements": [{ "type": "text", "text": full_text }]
-                    }));
+                    rt_elements.push(with_quote_border(
+                        json!({
+                            "type": "rich_text_preformatted",
+                            "elements": [{ "type": "text", "text": full_text }]
+                        }),
+                        &context_stack,
+                    ));
                 } else {
                     // Split large code blocks into multiple preformatted elements.
                     // Each chunk becomes its own rich_text block to stay within limits.
@@ -551,10 +789,13 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
                         // Flush current rt_elements first, then emit each chunk
                         // as its own rich_text block.
                         flush_rich_text(&mut rt_elements, &mut blocks);
-                        rt_elements.push(json!({
-                            "type": "rich_text_preformatted",
-                            "elements": [{ "type": "text", "text": chunk }]
-                        }));
+                        rt_elements.push(with_quote_border(
+                            json!({
+                                "type": "rich_text_preformatted",
+                                "elements": [{ "type": "text", "text": chunk }]
+                            }),
+                            &context_stack,
+                        ));
                         flush_rich_text(&mut rt_elements, &mut blocks);
                     }
                 }
@@ -605,11 +846,12 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
                             .as_object_mut()
                             .map(|o| o.insert("indent".to_string(), json!(info.indent)));
                     }
-                    rt_elements.push(list_block);
+                    rt_elements.push(with_quote_border(list_block, &context_stack));
                 }
 
-                // If we're back to top-level (no more lists), flush the rich_text block.
-                if list_stack.is_empty() {
+                // If we're back to top-level (no more lists), flush the rich_text
+                // block — unless the list sits in a quote that continues after it.
+                if list_stack.is_empty() && !context_stack.contains(&RenderContext::BlockQuote) {
                     flush_rich_text(&mut rt_elements, &mut blocks);
                 }
             }
@@ -617,6 +859,7 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
             Event::End(TagEnd::TableCell) => {
                 context_stack.pop();
                 if let Some(ref mut ts) = table_state {
+                    let plain_text = cell_plain_text(&ts.current_cell_elements);
                     let cell = if ts.current_cell_has_formatting {
                         // Use rich_text cell type.
                         json!({
@@ -642,6 +885,7 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
                     };
                     if ts.current_row.len() < MAX_TABLE_COLUMNS {
                         ts.current_row.push(cell);
+                        ts.current_plain_row.push(plain_text);
                     }
                     ts.column_index += 1;
                 }
@@ -652,6 +896,8 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
                     ts.total_rows_seen += 1;
                     if ts.rows.len() < MAX_TABLE_ROWS {
                         ts.rows.push(std::mem::take(&mut ts.current_row));
+                        ts.plain_rows
+                            .push(std::mem::take(&mut ts.current_plain_row));
                     }
                 }
             }
@@ -663,6 +909,8 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
                     ts.total_rows_seen += 1;
                     if !ts.current_row.is_empty() && ts.rows.len() < MAX_TABLE_ROWS {
                         ts.rows.push(std::mem::take(&mut ts.current_row));
+                        ts.plain_rows
+                            .push(std::mem::take(&mut ts.current_plain_row));
                     }
                     ts.in_header = false;
                 }
@@ -690,9 +938,19 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
                         "rows": ts.rows
                     });
 
-                    let table_idx = tables.len();
-                    tables.push(table_block);
-                    blocks.push(BlockOrTable::Table(table_idx));
+                    // The preformatted copy goes before the table marker so it
+                    // lands in the same message as the table attachment.
+                    if table_rendering != SlackTableRendering::Block {
+                        blocks.push(BlockOrTable::Block(preformatted_table_block(
+                            &ts.plain_rows,
+                            &ts.alignments,
+                        )));
+                    }
+                    if table_rendering != SlackTableRendering::Preformatted {
+                        let table_idx = tables.len();
+                        tables.push(table_block);
+                        blocks.push(BlockOrTable::Table(table_idx));
+                    }
 
                     // If rows were truncated, add a note after the table.
                     let dropped = ts.total_rows_seen.saturating_sub(ts.rows.len());
@@ -791,9 +1049,6 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
             | Event::End(TagEnd::HtmlBlock)
             | Event::Html(_)
             | Event::InlineHtml(_)
-            | Event::Start(Tag::FootnoteDefinition(_))
-            | Event::End(TagEnd::FootnoteDefinition)
-            | Event::FootnoteReference(_)
             | Event::Start(Tag::MetadataBlock(_))
             | Event::End(TagEnd::MetadataBlock(_))
             | Event::Start(Tag::DefinitionList)
@@ -816,9 +1071,652 @@ fn render_blocks(text: &str) -> Result<RenderedBlocks, ()> {
     flush_section(&mut inline_elements, &mut rt_elements, &mut blocks);
     flush_rich_text(&mut rt_elements, &mut blocks);
 
+    if !footnotes.is_empty() {
+        footnotes.sort_by_key(|(number, _)| *number);
+        let mut notes = vec![json!({
+            "type": "rich_text_section",
+            "elements": [{ "type": "text", "text": "Notes", "style": { "bold": true } }]
+        })];
+        for (number, elements) in footnotes {
+            let mut section = vec![json!({
+                "type": "text",
+                "text": format!("{} ", superscript(number))
+            })];
+            section.extend(elements);
+            notes.push(json!({ "type": "rich_text_section", "elements": section }));
+        }
+        blocks.push(BlockOrTable::Block(json!({
+            "type": "rich_text",
+            "elements": notes
+        })));
+    }
+
     Ok(RenderedBlocks { blocks, tables })
 }
 
+/// Plain text of a table cell: text elements verbatim, links by their
+/// display text or URL.
+fn cell_plain_text(elements: &[Value]) -> String {
+    let text: String = elements
+        .iter()
+        .filter_map(|e| {
+            e.get("text")
+                .or_else(|| e.get("url"))
+                .and_then(|t| t.as_str())
+        })
+        .collect();
+    text.trim().to_string()
+}
+
+/// Render table rows as an aligned monospace `rich_text_preformatted` block.
+fn preformatted_table_block(rows: &[Vec<String>], alignments: &[Alignment]) -> Value {
+    json!({
+        "type": "rich_text",
+        "elements": [{
+            "type": "rich_text_preformatted",
+            "elements": [{
+                "type": "text",
+                "text": truncate_to_char_limit(
+                    &preformatted_table_text(rows, alignments),
+                    MAX_PREFORMATTED_CHARS
+                )
+            }]
+        }]
+    })
+}
+
+/// Table rows as aligned monospace lines.
+///
+/// The first row is the header and is followed by a separator line. Column
+/// widths fit the widest cell, capped at `MAX_PREFORMATTED_COLUMN_WIDTH`.
+fn preformatted_table_text(rows: &[Vec<String>], alignments: &[Alignment]) -> String {
+    let column_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..column_count)
+        .map(|col| {
+            rows.iter()
+                .filter_map(|row| row.get(col))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+                .min(MAX_PREFORMATTED_COLUMN_WIDTH)
+        })
+        .collect();
+
+    let mut lines = Vec::with_capacity(rows.len() + 1);
+    for (row_idx, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(col, &width)| {
+                let cell = row.get(col).map(String::as_str).unwrap_or("");
+                let cell = truncate_to_char_limit(cell, width);
+                match alignments.get(col) {
+                    Some(Alignment::Center) => format!("{cell:^width$}"),
+                    Some(Alignment::Right) => format!("{cell:>width$}"),
+                    Some(Alignment::Left) | Some(Alignment::None) | None => {
+                        format!("{cell:<width$}")
+                    }
+                }
+            })
+            .collect();
+        lines.push(cells.join(" | ").trim_end().to_string());
+
+        if row_idx == 0 {
+            let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+            lines.push(separator.join("-+-"));
+        }
+    }
+
+    lines.join("\n")
+}
+
+// ---------------------------------------------------------------------------
+// Mrkdwn renderer
+// ---------------------------------------------------------------------------
+
+/// Where the mrkdwn renderer writes: the current top-level chunk, the
+/// innermost open blockquote, whose lines are prefixed when it closes, or the
+/// open footnote definition, which is kept for the trailing notes.
+#[derive(Default)]
+struct MrkdwnOutput {
+    chunk: String,
+    quotes: Vec<String>,
+    note: Option<(usize, String)>,
+    notes: Vec<(usize, String)>,
+}
+
+impl MrkdwnOutput {
+    fn current(&mut self) -> &mut String {
+        if let Some(quote) = self.quotes.last_mut() {
+            return quote;
+        }
+        match self.note.as_mut() {
+            Some((_, note)) => note,
+            None => &mut self.chunk,
+        }
+    }
+}
+
+/// Table accumulated as plain text for the mrkdwn renderer.
+struct MrkdwnTable {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+    current_row: Vec<String>,
+    current_cell: String,
+    total_rows_seen: usize,
+}
+
+/// Render markdown as `section` blocks with classic mrkdwn text.
+///
+/// Each top-level markdown block becomes a chunk of mrkdwn; consecutive
+/// chunks share a section until it would exceed `SECTION_TEXT_LIMIT`.
+/// Rules become `divider` blocks.
+fn render_mrkdwn_blocks(text: &str, mentions: &SlackMentions) -> Result<RenderedBlocks, ()> {
+    let parser = Parser::new_ext(text, markdown_options());
+
+    let mut blocks: Vec<BlockOrTable> = Vec::new();
+    let mut section = String::new();
+    let mut output = MrkdwnOutput::default();
+    // Next number for ordered lists, `None` for bullet lists.
+    let mut list_stack: Vec<Option<u64>> = Vec::new();
+    let mut in_heading = false;
+    // Right after a list marker, where a paragraph must not start a new line.
+    let mut at_item_start = false;
+    let mut pending_link_url: Option<String> = None;
+    let mut link_text_buffer: Option<String> = None;
+    let mut table: Option<MrkdwnTable> = None;
+    let mut footnote_numbers = FootnoteNumbers::default();
+
+    fn ensure_line_start(buffer: &mut String) {
+        if !buffer.is_empty() && !buffer.ends_with('\n') {
+            buffer.push('\n');
+        }
+    }
+
+    /// Move a finished chunk into the current section, starting a new
+    /// section when it would not fit.
+    fn push_chunk(chunk: &str, section: &mut String, blocks: &mut Vec<BlockOrTable>) {
+        let chunk = chunk.trim_end();
+        if chunk.trim().is_empty() {
+            return;
+        }
+        let separator = if section.is_empty() { 0 } else { 2 };
+        if section.chars().count() + separator + chunk.chars().count() > SECTION_TEXT_LIMIT {
+            flush_mrkdwn_section(section, blocks);
+        }
+        if chunk.chars().count() > SECTION_TEXT_LIMIT {
+            for part in split_text_by_chars(chunk, SECTION_TEXT_LIMIT) {
+                section.push_str(&part);
+                flush_mrkdwn_section(section, blocks);
+            }
+            return;
+        }
+        if !section.is_empty() {
+            section.push_str("\n\n");
+        }
+        section.push_str(chunk);
+    }
+
+    fn flush_mrkdwn_section(section: &mut String, blocks: &mut Vec<BlockOrTable>) {
+        if !section.trim().is_empty() {
+            blocks.push(BlockOrTable::Block(json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": std::mem::take(section) }
+            })));
+        }
+        section.clear();
+    }
+
+    /// End a block: at the top level its chunk goes into the section.
+    fn end_block(
+        output: &mut MrkdwnOutput,
+        list_depth: usize,
+        section: &mut String,
+        blocks: &mut Vec<BlockOrTable>,
+    ) {
+        if output.quotes.is_empty() && output.note.is_none() && list_depth == 0 {
+            let chunk = std::mem::take(&mut output.chunk);
+            push_chunk(&chunk, section, blocks);
+        } else {
+            ensure_line_start(output.current());
+        }
+    }
+
+    for event in parser {
+        match event {
+            Event::Start(Tag::Heading { .. }) => {
+                in_heading = true;
+                ensure_line_start(output.current());
+                output.current().push('*');
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                in_heading = false;
+                output.current().push('*');
+                end_block(&mut output, list_stack.len(), &mut section, &mut blocks);
+            }
+
+            Event::Start(Tag::Paragraph) => {
+                if table.is_none() && !at_item_start {
+                    ensure_line_start(output.current());
+                }
+            }
+            Event::End(TagEnd::Paragraph) => {
+                if table.is_none() {
+                    end_block(&mut output, list_stack.len(), &mut section, &mut blocks);
+                }
+            }
+
+            Event::Start(Tag::CodeBlock(_)) => {
+                ensure_line_start(output.current());
+                output.current().push_str("```\n");
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                ensure_line_start(output.current());
+                output.current().push_str("```");
+                end_block(&mut output, list_stack.len(), &mut section, &mut blocks);
+            }
+
+            Event::Start(Tag::BlockQuote(_)) => {
+                ensure_line_start(output.current());
+                output.quotes.push(String::new());
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                let quoted = output.quotes.pop().unwrap_or_default();
+                let quoted: Vec<String> = quoted
+                    .trim_end()
+                    .lines()
+                    .map(|line| format!("> {line}").trim_end().to_string())
+                    .collect();
+                output.current().push_str(&quoted.join("\n"));
+                end_block(&mut output, list_stack.len(), &mut section, &mut blocks);
+            }
+
+            Event::Start(Tag::FootnoteDefinition(label)) => {
+                let chunk = std::mem::take(&mut output.chunk);
+                push_chunk(&chunk, &mut section, &mut blocks);
+                output.note = Some((footnote_numbers.number(&label), String::new()));
+            }
+            Event::End(TagEnd::FootnoteDefinition) => {
+                if let Some(note) = output.note.take() {
+                    output.notes.push(note);
+                }
+            }
+            Event::FootnoteReference(label) => {
+                let marker = superscript(footnote_numbers.number(&label));
+                if let Some(ref mut buf) = link_text_buffer {
+                    buf.push_str(&marker);
+                } else if let Some(ref mut t) = table {
+                    t.current_cell.push_str(&marker);
+                } else {
+                    output.current().push_str(&marker);
+                }
+            }
+
+            Event::Start(Tag::List(start)) => {
+                ensure_line_start(output.current());
+                list_stack.push(start);
+            }
+            Event::End(TagEnd::List(_)) => {
+                list_stack.pop();
+                end_block(&mut output, list_stack.len(), &mut section, &mut blocks);
+            }
+
+            Event::Start(Tag::Item) => {
+                let indent = "    ".repeat(list_stack.len().saturating_sub(1));
+                let marker = match list_stack.last_mut() {
+                    Some(Some(number)) => {
+                        let marker = format!("{number}. ");
+                        *number += 1;
+                        marker
+                    }
+                    _ => "• ".to_string(),
+                };
+                let buffer = output.current();
+                ensure_line_start(buffer);
+                buffer.push_str(&indent);
+                buffer.push_str(&marker);
+                at_item_start = true;
+                continue;
+            }
+            Event::End(TagEnd::Item) => {
+                ensure_line_start(output.current());
+            }
+
+            Event::Start(Tag::Table(alignments)) => {
+                table = Some(MrkdwnTable {
+                    alignments,
+                    rows: Vec::new(),
+                    current_row: Vec::new(),
+                    current_cell: String::new(),
+                    total_rows_seen: 0,
+                });
+            }
+            Event::Start(Tag::TableCell) => {
+                if let Some(ref mut t) = table {
+                    t.current_cell.clear();
+                }
+            }
+            Event::End(TagEnd::TableCell) => {
+                if let Some(ref mut t) = table
+                    && t.current_row.len() < MAX_TABLE_COLUMNS
+                {
+                    let cell = std::mem::take(&mut t.current_cell);
+                    t.current_row.push(cell.trim().to_string());
+                }
+            }
+            Event::End(TagEnd::TableHead) | Event::End(TagEnd::TableRow) => {
+                if let Some(ref mut t) = table {
+                    t.total_rows_seen += 1;
+                    let row = std::mem::take(&mut t.current_row);
+                    if !row.is_empty() && t.rows.len() < MAX_TABLE_ROWS {
+                        t.rows.push(row);
+                    }
+                }
+            }
+            Event::End(TagEnd::Table) => {
+                if let Some(t) = table.take() {
+                    let buffer = output.current();
+                    ensure_line_start(buffer);
+                    buffer.push_str("```\n");
+                    buffer.push_str(&escape_mrkdwn(&preformatted_table_text(
+                        &t.rows,
+                        &t.alignments,
+                    )));
+                    buffer.push_str("\n```");
+                    let dropped = t.total_rows_seen.saturating_sub(t.rows.len());
+                    if dropped > 0 {
+                        buffer.push_str(&format!(
+                            "\n_… {dropped} more row{} not shown_",
+                            if dropped == 1 { "" } else { "s" }
+                        ));
+                    }
+                    end_block(&mut output, list_stack.len(), &mut section, &mut blocks);
+                }
+            }
+
+            Event::Start(Tag::Strong) if !in_heading && table.is_none() => {
+                output.current().push('*');
+            }
+            Event::End(TagEnd::Strong) if !in_heading && table.is_none() => {
+                output.current().push('*');
+            }
+            Event::Start(Tag::Emphasis) if !in_heading && table.is_none() => {
+                output.current().push('_');
+            }
+            Event::End(TagEnd::Emphasis) if !in_heading && table.is_none() => {
+                output.current().push('_');
+            }
+            Event::Start(Tag::Strikethrough) if table.is_none() => {
+                output.current().push('~');
+            }
+            Event::End(TagEnd::Strikethrough) if table.is_none() => {
+                output.current().push('~');
+            }
+
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                pending_link_url = Some(dest_url.to_string());
+                link_text_buffer = Some(String::new());
+            }
+            Event::End(TagEnd::Link) => {
+                let url = pending_link_url.take().unwrap_or_default();
+                let display_text = link_text_buffer.take().unwrap_or_default();
+                if let Some(ref mut t) = table {
+                    t.current_cell.push_str(if display_text.is_empty() {
+                        &url
+                    } else {
+                        &display_text
+                    });
+                } else if display_text.is_empty() || display_text == url {
+                    output.current().push_str(&format!("<{url}>"));
+                } else {
+                    output.current().push_str(&format!(
+                        "<{url}|{}>",
+                        escape_mrkdwn(&display_text).replace('|', "¦")
+                    ));
+                }
+            }
+
+            Event::Text(content) => {
+                if let Some(ref mut buf) = link_text_buffer {
+                    buf.push_str(&content);
+                } else if let Some(ref mut t) = table {
+                    t.current_cell.push_str(&content);
+                } else {
+                    output
+                        .current()
+                        .push_str(&mrkdwn_mentions(&escape_mrkdwn(&content), mentions));
+                }
+            }
+            Event::Code(content) => {
+                if let Some(ref mut buf) = link_text_buffer {
+                    buf.push_str(&content);
+                } else if let Some(ref mut t) = table {
+                    t.current_cell.push_str(&content);
+                } else if in_heading {
+                    output.current().push_str(&escape_mrkdwn(&content));
+                } else {
+                    output
+                        .current()
+                        .push_str(&format!("`{}`", escape_mrkdwn(&content)));
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some(ref mut buf) = link_text_buffer {
+                    buf.push(' ');
+                } else if let Some(ref mut t) = table {
+                    t.current_cell.push(' ');
+                } else if in_heading {
+                    output.current().push(' ');
+                } else {
+                    output.current().push('\n');
+                }
+            }
+
+            Event::Rule => {
+                let chunk = std::mem::take(&mut output.chunk);
+                push_chunk(&chunk, &mut section, &mut blocks);
+                flush_mrkdwn_section(&mut section, &mut blocks);
+                blocks.push(BlockOrTable::Block(json!({ "type": "divider" })));
+            }
+
+            _ => {}
+        }
+        at_item_start = false;
+    }
+
+    let chunk = std::mem::take(&mut output.chunk);
+    push_chunk(&chunk, &mut section, &mut blocks);
+
+    if !output.notes.is_empty() {
+        output.notes.sort_by_key(|(number, _)| *number);
+        let mut notes = String::from("*Notes*");
+        for (number, note) in &output.notes {
+            notes.push_str(&format!("\n{} {}", superscript(*number), note.trim()));
+        }
+        push_chunk(&notes, &mut section, &mut blocks);
+    }
+    flush_mrkdwn_section(&mut section, &mut blocks);
+
+    Ok(RenderedBlocks {
+        blocks,
+        tables: Vec::new(),
+    })
+}
+
+/// Escape the characters Slack reserves for links, mentions and entities.
+fn escape_mrkdwn(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// ---------------------------------------------------------------------------
+// Emoji and mentions
+// ---------------------------------------------------------------------------
+
+/// A piece of text split on emoji shortcodes and mapped mentions.
+#[derive(Debug, PartialEq, Eq)]
+enum InlineToken<'a> {
+    Text(&'a str),
+    Emoji(&'a str),
+    User(&'a str),
+    Channel(&'a str),
+}
+
+/// Longest emoji name Slack accepts.
+const MAX_EMOJI_NAME_CHARS: usize = 100;
+
+/// Split `text` into plain runs, `:shortcode:` emoji and `@name` / `#name`
+/// tokens found in `mentions`. Unmapped names stay plain text, and tokens
+/// glued to a preceding word (`a@b.io`, `10:30:00`) are left alone.
+fn split_inline_tokens<'a>(text: &'a str, mentions: &'a SlackMentions) -> Vec<InlineToken<'a>> {
+    let mut tokens = Vec::new();
+    let mut plain_start = 0;
+    let mut pos = 0;
+
+    while let Some(ch) = text.get(pos..).and_then(|rest| rest.chars().next()) {
+        let at_boundary = text
+            .get(..pos)
+            .and_then(|before| before.chars().next_back())
+            .is_none_or(|prev| !prev.is_alphanumeric() && prev != '_');
+        let rest = text.get(pos + ch.len_utf8()..).unwrap_or_default();
+
+        let found = match ch {
+            ':' if at_boundary => rest
+                .find(':')
+                .and_then(|end| rest.get(..end))
+                .filter(|name| {
+                    name.chars().count() <= MAX_EMOJI_NAME_CHARS
+                        && name.chars().any(|c| c.is_ascii_lowercase())
+                        && name.chars().all(|c| {
+                            c.is_ascii_lowercase() || c.is_ascii_digit() || "_+-'".contains(c)
+                        })
+                })
+                .map(|name| (InlineToken::Emoji(name), name.len() + 2)),
+            '@' | '#' if at_boundary => {
+                let name_len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || "._-".contains(c)))
+                    .unwrap_or(rest.len());
+                let name = rest
+                    .get(..name_len)
+                    .unwrap_or_default()
+                    .trim_end_matches(['.', '-']);
+                let mapped = if ch == '@' {
+                    mentions.users.get(name).map(|id| InlineToken::User(id))
+                } else {
+                    mentions
+                        .channels
+                        .get(name)
+                        .map(|id| InlineToken::Channel(id))
+                };
+                mapped.map(|token| (token, name.len() + 1))
+            }
+            _ => None,
+        };
+
+        match found {
+            Some((token, len)) => {
+                if let Some(plain) = text.get(plain_start..pos).filter(|p| !p.is_empty()) {
+                    tokens.push(InlineToken::Text(plain));
+                }
+                tokens.push(token);
+                pos += len;
+                plain_start = pos;
+            }
+            None => pos += ch.len_utf8(),
+        }
+    }
+    if let Some(plain) = text.get(plain_start..).filter(|p| !p.is_empty()) {
+        tokens.push(InlineToken::Text(plain));
+    }
+    tokens
+}
+
+/// Replace `:shortcode:` and mapped `@name` / `#name` tokens in every
+/// rich_text `text` element with `emoji`, `user` and `channel` elements.
+/// Code spans and preformatted blocks are left as written.
+fn convert_inline_tokens(rendered: &mut RenderedBlocks, mentions: &SlackMentions) {
+    for block in &mut rendered.blocks {
+        if let BlockOrTable::Block(block) = block {
+            convert_value_tokens(block, mentions);
+        }
+    }
+    for table in &mut rendered.tables {
+        convert_value_tokens(table, mentions);
+    }
+}
+
+fn convert_value_tokens(value: &mut Value, mentions: &SlackMentions) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                convert_value_tokens(item, mentions);
+            }
+        }
+        Value::Object(map) => {
+            if map.get("type").and_then(Value::as_str) == Some("rich_text_preformatted") {
+                return;
+            }
+            if let Some(Value::Array(elements)) = map.get_mut("elements") {
+                *elements = std::mem::take(elements)
+                    .into_iter()
+                    .flat_map(|element| split_text_element(element, mentions))
+                    .collect();
+            }
+            for child in map.values_mut() {
+                convert_value_tokens(child, mentions);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Split one rich_text `text` element; anything else is returned as is.
+fn split_text_element(element: Value, mentions: &SlackMentions) -> Vec<Value> {
+    let is_plain_text = element["type"] == "text" && element["style"]["code"] != true;
+    let Some(text) = element["text"].as_str().filter(|_| is_plain_text) else {
+        return vec![element];
+    };
+    let tokens = split_inline_tokens(text, mentions);
+    if let [InlineToken::Text(_)] = tokens.as_slice() {
+        return vec![element];
+    }
+
+    let style = element.get("style");
+    tokens
+        .into_iter()
+        .map(|token| {
+            let mut converted = match token {
+                InlineToken::Text(text) => json!({ "type": "text", "text": text }),
+                InlineToken::Emoji(name) => return json!({ "type": "emoji", "name": name }),
+                InlineToken::User(id) => json!({ "type": "user", "user_id": id }),
+                InlineToken::Channel(id) => json!({ "type": "channel", "channel_id": id }),
+            };
+            if let (Some(style), Some(object)) = (style, converted.as_object_mut()) {
+                object.insert("style".to_string(), style.clone());
+            }
+            converted
+        })
+        .collect()
+}
+
+/// Mrkdwn already renders `:shortcode:` emoji, so only mapped mentions are
+/// rewritten, to `<@U…>` / `<#C…>`.
+fn mrkdwn_mentions(text: &str, mentions: &SlackMentions) -> String {
+    if mentions.is_empty() {
+        return text.to_string();
+    }
+    split_inline_tokens(text, mentions)
+        .into_iter()
+        .map(|token| match token {
+            InlineToken::Text(text) => text.to_string(),
+            InlineToken::Emoji(name) => format!(":{name}:"),
+            InlineToken::User(id) => format!("<@{id}>"),
+            InlineToken::Channel(id) => format!("<#{id}>"),
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // Message splitting
 // ---------------------------------------------------------------------------
@@ -915,12 +1813,12 @@ fn fallback_chunk(fallback: &str, _offset: usize, _block_count: usize) -> String
 /// wall of concatenated values (e.g. "IDNameAge1Alice30") in notifications.
 /// A short "[table]" placeholder is emitted instead.
 fn generate_fallback_text(text: &str) -> String {
-    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
-    let parser = Parser::new_ext(text, options);
+    let parser = Parser::new_ext(text, markdown_options());
 
     let mut output = String::new();
     let mut last_was_block = false;
     let mut in_table = false;
+    let mut footnote_numbers = FootnoteNumbers::default();
 
     for event in parser {
         match event {
@@ -992,6 +1890,19 @@ fn generate_fallback_text(text: &str) -> String {
                 output.push_str("---\n");
                 last_was_block = true;
             }
+            Event::FootnoteReference(label) => {
+                output.push_str(&superscript(footnote_numbers.number(&label)));
+                last_was_block = false;
+            }
+            Event::Start(Tag::FootnoteDefinition(label)) => {
+                if !output.is_empty() && !last_was_block {
+                    output.push('\n');
+                }
+                output.push_str(&superscript(footnote_numbers.number(&label)));
+                output.push(' ');
+                // The definition's paragraph continues on the marker's line.
+                last_was_block = true;
+            }
             _ => {}
         }
     }
@@ -1420,6 +2331,46 @@ mod tests {
         assert_eq!(text["text"], "This is quoted text");
     }
 
+    #[test]
+    fn blockquote_with_list_and_code_block() {
+        let md = "> Steps:\n>\n> - drain\n> - restart\n>\n> ```\n> kubectl rollout restart\n> ```\n>\n> Done.";
+        let elements = first_rich_text_elements(md);
+        let types: Vec<&str> = elements
+            .iter()
+            .map(|e| e["type"].as_str().unwrap_or_default())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                "rich_text_quote",
+                "rich_text_list",
+                "rich_text_preformatted",
+                "rich_text_quote"
+            ]
+        );
+        assert_eq!(elements[0]["elements"][0]["text"], "Steps:");
+        assert_eq!(elements[1]["border"], 1);
+        assert_eq!(elements[2]["border"], 1);
+        assert_eq!(elements[3]["elements"][0]["text"], "Done.");
+
+        // Outside a quote, lists carry no border.
+        let list = first_rich_text_elements("- a\n- b");
+        assert!(list[0].get("border").is_none());
+    }
+
+    #[test]
+    fn blockquote_paragraphs_stay_in_one_quote() {
+        let elements = first_rich_text_elements("> first\n>\n> second");
+        assert_eq!(elements.len(), 1);
+        let texts: Vec<&str> = elements[0]["elements"]
+            .as_array()
+            .expect("quote elements")
+            .iter()
+            .filter_map(|e| e["text"].as_str())
+            .collect();
+        assert_eq!(texts, vec!["first", "\n", "second"]);
+    }
+
     // ---- 7. Horizontal Rules ----
 
     #[test]
@@ -1475,6 +2426,55 @@ mod tests {
         assert_eq!(table_msgs.len(), 2, "expected 2 messages with tables");
     }
 
+    fn preformatted_text(msg: &SlackMessage) -> Option<String> {
+        msg.blocks.iter().find_map(|block| {
+            let element = &block["elements"][0];
+            if element["type"] == "rich_text_preformatted" {
+                element["elements"][0]["text"].as_str().map(String::from)
+            } else {
+                None
+            }
+        })
+    }
+
+    #[test]
+    fn preformatted_table_replaces_table_block() {
+        let md = "| Item | Count |\n|:-----|------:|\n| apples | 3 |\n| kiwi | 12 |";
+        let msgs = markdown_to_slack_messages_with_tables(md, SlackTableRendering::Preformatted);
+        assert_eq!(msgs.len(), 1);
+        assert!(msgs[0].attachments.is_none(), "no table block expected");
+        assert_eq!(
+            preformatted_text(&msgs[0]).expect("preformatted table"),
+            "Item   | Count\n-------+------\napples |     3\nkiwi   |    12"
+        );
+    }
+
+    #[test]
+    fn both_table_modes_share_a_message() {
+        let md = "Status:\n\n| Name | Status |\n|------|--------|\n| App  | Running |";
+        let msgs = markdown_to_slack_messages_with_tables(md, SlackTableRendering::Both);
+        assert_eq!(msgs.len(), 1);
+        let attachments = msgs[0].attachments.as_ref().expect("table attachment");
+        assert_eq!(attachments[0]["blocks"][0]["type"], "table");
+        assert_eq!(
+            preformatted_text(&msgs[0]).expect("preformatted table"),
+            "Name | Status\n-----+--------\nApp  | Running"
+        );
+    }
+
+    #[test]
+    fn preformatted_table_truncates_wide_columns() {
+        let long_cell = "x".repeat(60);
+        let md = format!("| Key | Value |\n|-----|-------|\n| a | {long_cell} |");
+        let msgs = markdown_to_slack_messages_with_tables(&md, SlackTableRendering::Preformatted);
+        let text = preformatted_text(&msgs[0]).expect("preformatted table");
+        let data_row = text.lines().last().expect("data row");
+        assert_eq!(
+            data_row,
+            format!("a   | {}…", "x".repeat(MAX_PREFORMATTED_COLUMN_WIDTH - 1))
+        );
+    }
+
     // ---- 9. Fallback Text ----
 
     #[test]
@@ -1770,4 +2770,239 @@ mod tests {
             assert!(chunk.chars().count() <= 12);
         }
     }
+
+    // ---- 12. Mrkdwn Sections ----
+
+    fn mrkdwn(md: &str) -> Vec<SlackMessage> {
+        markdown_to_slack_messages_with_options(
+            md,
+            &SlackRenderOptions {
+                block_format: SlackBlockFormat::Mrkdwn,
+                ..SlackRenderOptions::default()
+            },
+        )
+    }
+
+    fn mrkdwn_texts(md: &str) -> Vec<String> {
+        mrkdwn(md)
+            .iter()
+            .flat_map(|msg| msg.blocks.iter())
+            .filter(|block| block["type"] == "section")
+            .map(|block| {
+                assert_eq!(block["text"]["type"], "mrkdwn");
+                block["text"]["text"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mrkdwn_inline_formatting() {
+        assert_eq!(
+            mrkdwn_texts("**bold** *it* ~~gone~~ `kubectl` [docs](https://x.io) <https://y.io>"),
+            vec!["*bold* _it_ ~gone~ `kubectl` <https://x.io|docs> <https://y.io>"]
+        );
+    }
+
+    #[test]
+    fn mrkdwn_block_structure() {
+        let md = "# Incident\n\nSummary & impact\n\n- api\n  - pods\n- db\n\n1. restart\n2. verify\n\n> quoted\n> text\n\n```\nif a < b {}\n```";
+        let texts = mrkdwn_texts(md);
+        assert_eq!(texts.len(), 1);
+        assert_eq!(
+            texts[0],
+            "*Incident*\n\nSummary &amp; impact\n\n• api\n    • pods\n• db\n\n1. restart\n2. verify\n\n> quoted\n> text\n\n```\nif a &lt; b {}\n```"
+        );
+        let blocks = &mrkdwn(md)[0].blocks;
+        assert!(blocks.iter().all(|block| block["type"] != "rich_text"));
+        assert!(blocks.iter().all(|block| block["type"] != "header"));
+    }
+
+    #[test]
+    fn mrkdwn_loose_list_keeps_marker_on_item_line() {
+        assert_eq!(
+            mrkdwn_texts("- first\n\n- second"),
+            vec!["• first\n• second"]
+        );
+    }
+
+    #[test]
+    fn mrkdwn_rule_becomes_divider() {
+        let msgs = mrkdwn("above\n\n---\n\nbelow");
+        let types: Vec<&str> = msgs[0]
+            .blocks
+            .iter()
+            .filter_map(|block| block["type"].as_str())
+            .collect();
+        assert_eq!(types, vec!["section", "divider", "section"]);
+    }
+
+    #[test]
+    fn mrkdwn_tables_are_preformatted() {
+        let msgs = mrkdwn("| Name | Age |\n|------|-----|\n| **Al** | 30 |");
+        assert_eq!(msgs.len(), 1);
+        assert!(msgs[0].attachments.is_none());
+        assert_eq!(
+            mrkdwn_texts("| Name | Age |\n|------|-----|\n| **Al** | 30 |"),
+            vec!["```\nName | Age\n-----+----\nAl   | 30\n```"]
+        );
+    }
+
+    #[test]
+    fn mrkdwn_sections_stay_within_limit() {
+        let paragraph = "word ".repeat(400);
+        let md = [paragraph.trim(); 4].join("\n\n");
+        let texts = mrkdwn_texts(&md);
+        assert!(texts.len() >= 2);
+        for text in &texts {
+            assert!(text.chars().count() <= SECTION_TEXT_LIMIT);
+        }
+
+        let huge = "x".repeat(SECTION_TEXT_LIMIT * 2 + 10);
+        let texts = mrkdwn_texts(&huge);
+        assert_eq!(texts.len(), 3);
+        assert_eq!(texts.concat(), huge);
+    }
+
+    // ---- 13. Footnotes ----
+
+    const FOOTNOTE_MD: &str = "Disk is full[^disk] and CPU is high[^cpu].\n\n[^cpu]: Load average 12.\n[^disk]: See `df -h`.\n\nAfter notes.";
+
+    #[test]
+    fn footnotes_render_markers_and_notes_section() {
+        let blocks = first_blocks(FOOTNOTE_MD);
+        let body = &blocks[0]["elements"][0]["elements"];
+        let texts: Vec<&str> = body
+            .as_array()
+            .expect("section elements")
+            .iter()
+            .filter_map(|e| e["text"].as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["Disk is full", "¹", " and CPU is high", "²", "."]
+        );
+
+        let notes = blocks.last().expect("notes block");
+        assert_eq!(notes["type"], "rich_text");
+        assert_eq!(notes["elements"][0]["elements"][0]["text"], "Notes");
+        assert_eq!(notes["elements"][1]["elements"][0]["text"], "¹ ");
+        assert_eq!(notes["elements"][1]["elements"][1]["text"], "See ");
+        assert_eq!(notes["elements"][1]["elements"][2]["text"], "df -h");
+        assert_eq!(notes["elements"][2]["elements"][0]["text"], "² ");
+        assert_eq!(
+            notes["elements"][2]["elements"][1]["text"],
+            "Load average 12."
+        );
+
+        // Text after the definitions stays in place, ahead of the notes.
+        let all = serde_json::to_string(&blocks).unwrap_or_default();
+        assert!(all.find("After notes.") < all.find("Notes"));
+    }
+
+    #[test]
+    fn superscript_numbers() {
+        assert_eq!(superscript(1), "¹");
+        assert_eq!(superscript(10), "¹⁰");
+        assert_eq!(superscript(42), "⁴²");
+    }
+
+    #[test]
+    fn footnotes_in_fallback_and_mrkdwn() {
+        let fallback = generate_fallback_text(FOOTNOTE_MD);
+        assert!(fallback.starts_with("Disk is full¹ and CPU is high²."));
+        assert!(fallback.contains("² Load average 12."));
+        assert!(fallback.contains("¹ See df -h."));
+
+        assert_eq!(
+            mrkdwn_texts(FOOTNOTE_MD),
+            vec![
+                "Disk is full¹ and CPU is high².\n\nAfter notes.\n\n*Notes*\n¹ See `df -h`.\n² Load average 12."
+            ]
+        );
+    }
+
+    // ---- 14. Emoji and Mentions ----
+
+    fn mentions() -> SlackMentions {
+        SlackMentions {
+            users: BTreeMap::from([("alice".to_string(), "U111".to_string())]),
+            channels: BTreeMap::from([("ops-alerts".to_string(), "C222".to_string())]),
+        }
+    }
+
+    fn render_with_mentions(md: &str, block_format: SlackBlockFormat) -> Vec<SlackMessage> {
+        markdown_to_slack_messages_with_options(
+            md,
+            &SlackRenderOptions {
+                block_format,
+                mentions: mentions(),
+                ..SlackRenderOptions::default()
+            },
+        )
+    }
+
+    #[test]
+    fn emoji_and_mentions_become_rich_text_elements() {
+        let msgs = render_with_mentions(
+            "Deployed :rocket: cc @alice, see #ops-alerts. **@alice**",
+            SlackBlockFormat::RichText,
+        );
+        let elements = &msgs[0].blocks[0]["elements"][0]["elements"];
+        assert_eq!(
+            elements,
+            &json!([
+                { "type": "text", "text": "Deployed " },
+                { "type": "emoji", "name": "rocket" },
+                { "type": "text", "text": " cc " },
+                { "type": "user", "user_id": "U111" },
+                { "type": "text", "text": ", see " },
+                { "type": "channel", "channel_id": "C222" },
+                { "type": "text", "text": ". " },
+                { "type": "user", "user_id": "U111", "style": { "bold": true } }
+            ])
+        );
+    }
+
+    #[test]
+    fn unmapped_and_embedded_tokens_stay_text() {
+        let mentions = mentions();
+        assert_eq!(
+            split_inline_tokens("@bob mail a@alice.io at 10:30:00 :: #42", &mentions),
+            vec![InlineToken::Text("@bob mail a@alice.io at 10:30:00 :: #42")]
+        );
+        assert_eq!(
+            split_inline_tokens("@alice. :+1: :thumbsup:", &mentions),
+            vec![
+                InlineToken::User("U111"),
+                InlineToken::Text(". :+1: "),
+                InlineToken::Emoji("thumbsup"),
+            ]
+        );
+    }
+
+    #[test]
+    fn code_is_not_converted() {
+        let msgs = render_with_mentions(
+            "`@alice :rocket:`\n\n```\n@alice :rocket:\n```",
+            SlackBlockFormat::RichText,
+        );
+        let all = serde_json::to_string(&msgs[0].blocks).unwrap_or_default();
+        assert!(!all.contains("\"emoji\""));
+        assert!(!all.contains("U111"));
+    }
+
+    #[test]
+    fn mrkdwn_mentions_use_slack_escapes() {
+        let msgs = render_with_mentions(
+            "Ping @alice in #ops-alerts :rocket:",
+            SlackBlockFormat::Mrkdwn,
+        );
+        assert_eq!(
+            msgs[0].blocks[0]["text"]["text"],
+            "Ping <@U111> in <#C222> :rocket:"
+        );
+    }
 }
@@ -1,10 +1,12 @@
 use std::path::Path;
 
 use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
 use libsql::{Connection, Database};
 use tempfile::TempDir;
 
-use crate::types::DeliveryContext;
+use crate::audit::{ApprovalAuditFilter, ApprovalAuditRecord, AuditDecision, AuditSource};
+use crate::types::{DeliveryContext, InboundMessage};
 
 #[derive(Debug, Clone)]
 pub struct SessionMapping {
@@ -14,6 +16,83 @@ pub struct SessionMapping {
     pub created_at: i64,
 }
 
+/// Narrows [`StoreBackend::list_filtered`]; unset fields match every mapping.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionMappingFilter {
+    pub channel: Option<String>,
+    pub session_id: Option<String>,
+}
+
+/// Storage footprint of one routing entry, used by retention policies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionFootprint {
+    pub routing_key: String,
+    pub updated_at: i64,
+    /// Size of the stored mapping, in bytes.
+    pub bytes: u64,
+}
+
+/// Persistence the gateway runtime needs: session routing, delivery contexts
+/// for scheduled notifications, inbound messages queued while the server
+/// was down, and the approval audit log.
+///
+/// [`GatewayStore`] keeps everything in a local SQLite file. Embedders that
+/// already run a database implement this trait and pass their store to
+/// [`GatewayBuilder::store`](crate::GatewayBuilder::store).
+#[async_trait]
+pub trait StoreBackend: Send + Sync {
+    async fn get(&self, routing_key: &str) -> Result<Option<SessionMapping>>;
+    async fn set(&self, routing_key: &str, mapping: &SessionMapping) -> Result<()>;
+    async fn find_by_session_id(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<(String, SessionMapping)>>;
+    async fn update_delivery(&self, routing_key: &str, delivery: &DeliveryContext) -> Result<()>;
+    /// Most recently updated mappings first.
+    async fn list(&self, limit: usize) -> Result<Vec<(String, SessionMapping)>>;
+    /// Like [`list`](Self::list), restricted to mappings matching `filter`.
+    async fn list_filtered(
+        &self,
+        filter: &SessionMappingFilter,
+        limit: usize,
+    ) -> Result<Vec<(String, SessionMapping)>>;
+    async fn session_footprints(&self) -> Result<Vec<SessionFootprint>>;
+    /// Point a routing key at another session, keeping its delivery
+    /// context. Returns false when the key has no mapping.
+    async fn relink(&self, routing_key: &str, session_id: &str) -> Result<bool>;
+    /// Returns whether a mapping was removed.
+    async fn delete(&self, routing_key: &str) -> Result<bool>;
+    /// Delete mappings not updated within `max_age_ms`, returning how many.
+    async fn prune(&self, max_age_ms: i64) -> Result<usize>;
+    async fn set_delivery_context(
+        &self,
+        channel: &str,
+        target_key: &str,
+        context: &serde_json::Value,
+        ttl_hours: u64,
+    ) -> Result<()>;
+    /// Take the unexpired context for a target, removing it.
+    async fn pop_delivery_context(
+        &self,
+        channel: &str,
+        target_key: &str,
+    ) -> Result<Option<serde_json::Value>>;
+    async fn prune_delivery_contexts(&self) -> Result<usize>;
+    async fn enqueue_offline_inbound(&self, message: &InboundMessage) -> Result<i64>;
+    /// Queued messages, oldest first.
+    async fn list_offline_inbound(&self) -> Result<Vec<(i64, InboundMessage)>>;
+    async fn delete_offline_inbound(&self, id: i64) -> Result<()>;
+    async fn count_offline_inbound(&self) -> Result<usize>;
+    /// Append decisions to the approval audit log.
+    async fn record_approval_audit(&self, records: &[ApprovalAuditRecord]) -> Result<()>;
+    /// Audit records matching `filter`, most recent first.
+    async fn list_approval_audit(
+        &self,
+        filter: &ApprovalAuditFilter,
+        limit: usize,
+    ) -> Result<Vec<ApprovalAuditRecord>>;
+}
+
 pub struct GatewayStore {
     /// Keep the libsql Database handle alive for the lifetime of each operation connection.
     db: Database,
@@ -223,13 +302,95 @@ impl GatewayStore {
         Ok(out)
     }
 
-    pub async fn delete(&self, routing_key: &str) -> Result<()> {
+    pub async fn list_filtered(
+        &self,
+        filter: &SessionMappingFilter,
+        limit: usize,
+    ) -> Result<Vec<(String, SessionMapping)>> {
+        let conn = self.connection().await?;
+        let mut rows = conn
+            .query(
+                "SELECT routing_key, session_id, title, channel, peer_id, chat_type, channel_meta, created_at, updated_at
+                 FROM sessions
+                 WHERE (?1 IS NULL OR channel = ?1)
+                   AND (?2 IS NULL OR session_id = ?2)
+                 ORDER BY updated_at DESC
+                 LIMIT ?3",
+                (
+                    filter.channel.as_deref(),
+                    filter.session_id.as_deref(),
+                    limit as i64,
+                ),
+            )
+            .await
+            .context("failed to list session mappings")?;
+
+        let mut out = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .await
+            .context("failed to read session mappings row")?
+        {
+            let routing_key: String = row.get(0).context("failed to parse routing_key")?;
+            let mapping = parse_session_mapping_row(&row, 1)?;
+            out.push((routing_key, mapping));
+        }
+
+        Ok(out)
+    }
+
+    /// Every routing entry with its last update time and stored size.
+    pub async fn session_footprints(&self) -> Result<Vec<SessionFootprint>> {
+        let conn = self.connection().await?;
+        let mut rows = conn
+            .query(
+                "SELECT routing_key, updated_at,
+                    LENGTH(routing_key) + LENGTH(session_id) + LENGTH(title) + LENGTH(channel)
+                        + LENGTH(peer_id) + LENGTH(chat_type) + LENGTH(channel_meta)
+                 FROM sessions",
+                (),
+            )
+            .await
+            .context("failed to list session footprints")?;
+
+        let mut out = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .await
+            .context("failed to read session footprint row")?
+        {
+            let bytes: i64 = row.get(2).context("failed to parse footprint size")?;
+            out.push(SessionFootprint {
+                routing_key: row.get(0).context("failed to parse routing_key")?,
+                updated_at: row.get(1).context("failed to parse updated_at")?,
+                bytes: u64::try_from(bytes).unwrap_or_default(),
+            });
+        }
+
+        Ok(out)
+    }
+
+    pub async fn relink(&self, routing_key: &str, session_id: &str) -> Result<bool> {
+        let conn = self.connection().await?;
+        let updated = conn
+            .execute(
+                "UPDATE sessions SET session_id = ?, updated_at = ? WHERE routing_key = ?",
+                (session_id, now_millis(), routing_key),
+            )
+            .await
+            .context("failed to relink routing key")?;
+
+        Ok(updated > 0)
+    }
+
+    pub async fn delete(&self, routing_key: &str) -> Result<bool> {
         let conn = self.connection().await?;
-        conn.execute("DELETE FROM sessions WHERE routing_key = ?", [routing_key])
+        let deleted = conn
+            .execute("DELETE FROM sessions WHERE routing_key = ?", [routing_key])
             .await
             .context("failed to delete routing key")?;
 
-        Ok(())
+        Ok(deleted > 0)
     }
 
     pub async fn prune(&self, max_age_ms: i64) -> Result<usize> {
@@ -325,6 +486,178 @@ impl GatewayStore {
         Ok(deleted as usize)
     }
 
+    /// Hold an inbound message while the agent server is unreachable.
+    pub async fn enqueue_offline_inbound(&self, message: &InboundMessage) -> Result<i64> {
+        let message_json =
+            serde_json::to_string(message).context("failed to serialize inbound message")?;
+
+        let conn = self.connection().await?;
+        conn.execute(
+            "INSERT INTO offline_inbound (message, received_at, queued_at) VALUES (?, ?, ?)",
+            (
+                message_json.as_str(),
+                message.timestamp.timestamp_millis(),
+                now_millis(),
+            ),
+        )
+        .await
+        .context("failed to enqueue offline inbound message")?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Held inbound messages in the order they were received.
+    pub async fn list_offline_inbound(&self) -> Result<Vec<(i64, InboundMessage)>> {
+        let conn = self.connection().await?;
+        let mut rows = conn
+            .query(
+                "SELECT id, message FROM offline_inbound ORDER BY received_at ASC, id ASC",
+                (),
+            )
+            .await
+            .context("failed to list offline inbound messages")?;
+
+        let mut messages = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .await
+            .context("failed to read offline inbound row")?
+        {
+            let id: i64 = row.get(0).context("failed to parse offline inbound id")?;
+            let message_json: String = row
+                .get(1)
+                .context("failed to parse offline inbound message")?;
+            messages.push((
+                id,
+                parse_json_value(&message_json, "offline inbound message")?,
+            ));
+        }
+
+        Ok(messages)
+    }
+
+    pub async fn delete_offline_inbound(&self, id: i64) -> Result<()> {
+        let conn = self.connection().await?;
+        conn.execute("DELETE FROM offline_inbound WHERE id = ?", [id])
+            .await
+            .context("failed to delete offline inbound message")?;
+        Ok(())
+    }
+
+    pub async fn count_offline_inbound(&self) -> Result<usize> {
+        let conn = self.connection().await?;
+        let mut rows = conn
+            .query("SELECT COUNT(*) FROM offline_inbound", ())
+            .await
+            .context("failed to count offline inbound messages")?;
+        let count: i64 = match rows
+            .next()
+            .await
+            .context("failed to read offline inbound count")?
+        {
+            Some(row) => row
+                .get(0)
+                .context("failed to parse offline inbound count")?,
+            None => 0,
+        };
+        Ok(count as usize)
+    }
+
+    pub async fn record_approval_audit(&self, records: &[ApprovalAuditRecord]) -> Result<()> {
+        let conn = self.connection().await?;
+        let tx = conn
+            .transaction()
+            .await
+            .context("failed to begin approval audit transaction")?;
+        for record in records {
+            tx.execute(
+                "INSERT INTO approval_audit
+                 (decided_at, channel, session_id, run_id, tool_call_id, tool_name, arguments_hash,
+                  decision, source, decided_by, reason)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                (
+                    record.decided_at,
+                    record.channel.as_str(),
+                    record.session_id.as_str(),
+                    record.run_id.as_str(),
+                    record.tool_call_id.as_str(),
+                    record.tool_name.as_str(),
+                    record.arguments_hash.as_str(),
+                    record.decision.as_str(),
+                    record.source.as_str(),
+                    record.decided_by.as_str(),
+                    record.reason.as_deref(),
+                ),
+            )
+            .await
+            .context("failed to insert approval audit record")?;
+        }
+        tx.commit()
+            .await
+            .context("failed to commit approval audit records")?;
+
+        Ok(())
+    }
+
+    pub async fn list_approval_audit(
+        &self,
+        filter: &ApprovalAuditFilter,
+        limit: usize,
+    ) -> Result<Vec<ApprovalAuditRecord>> {
+        let conn = self.connection().await?;
+        let mut rows = conn
+            .query(
+                "SELECT id, decided_at, channel, session_id, run_id, tool_call_id, tool_name,
+                        arguments_hash, decision, source, decided_by, reason
+                 FROM approval_audit
+                 WHERE (?1 IS NULL OR decided_at >= ?1)
+                   AND (?2 IS NULL OR decided_at < ?2)
+                   AND (?3 IS NULL OR channel = ?3)
+                   AND (?4 IS NULL OR session_id = ?4)
+                   AND (?5 IS NULL OR tool_name = ?5)
+                 ORDER BY decided_at DESC, id DESC
+                 LIMIT ?6",
+                (
+                    filter.since,
+                    filter.until,
+                    filter.channel.as_deref(),
+                    filter.session_id.as_deref(),
+                    filter.tool_name.as_deref(),
+                    limit as i64,
+                ),
+            )
+            .await
+            .context("failed to list approval audit records")?;
+
+        let mut out = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .await
+            .context("failed to read approval audit row")?
+        {
+            let decision: String = row.get(8).context("failed to parse decision")?;
+            let source: String = row.get(9).context("failed to parse source")?;
+            out.push(ApprovalAuditRecord {
+                id: row.get(0).context("failed to parse id")?,
+                decided_at: row.get(1).context("failed to parse decided_at")?,
+                channel: row.get(2).context("failed to parse channel")?,
+                session_id: row.get(3).context("failed to parse session_id")?,
+                run_id: row.get(4).context("failed to parse run_id")?,
+                tool_call_id: row.get(5).context("failed to parse tool_call_id")?,
+                tool_name: row.get(6).context("failed to parse tool_name")?,
+                arguments_hash: row.get(7).context("failed to parse arguments_hash")?,
+                decision: AuditDecision::parse(&decision)
+                    .ok_or_else(|| anyhow!("unknown audit decision '{decision}'"))?,
+                source: AuditSource::parse(&source)
+                    .ok_or_else(|| anyhow!("unknown audit source '{source}'"))?,
+                decided_by: row.get(10).context("failed to parse decided_by")?,
+                reason: row.get(11).context("failed to parse reason")?,
+            });
+        }
+
+        Ok(out)
+    }
+
     async fn run_migrations(&self) -> Result<()> {
         let conn = self.connection().await?;
         conn.execute_batch(
@@ -352,6 +685,30 @@ impl GatewayStore {
                 expires_at   INTEGER NOT NULL,
                 PRIMARY KEY (channel, target_key)
             );
+
+            CREATE TABLE IF NOT EXISTS offline_inbound (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                message     TEXT NOT NULL,
+                received_at INTEGER NOT NULL,
+                queued_at   INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS approval_audit (
+                id             INTEGER PRIMARY KEY AUTOINCREMENT,
+                decided_at     INTEGER NOT NULL,
+                channel        TEXT NOT NULL,
+                session_id     TEXT NOT NULL,
+                run_id         TEXT NOT NULL,
+                tool_call_id   TEXT NOT NULL,
+                tool_name      TEXT NOT NULL,
+                arguments_hash TEXT NOT NULL,
+                decision       TEXT NOT NULL,
+                source         TEXT NOT NULL,
+                decided_by     TEXT NOT NULL,
+                reason         TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_approval_audit_decided_at ON approval_audit(decided_at);
             ",
         )
         .await
@@ -361,6 +718,106 @@ impl GatewayStore {
     }
 }
 
+#[async_trait]
+impl StoreBackend for GatewayStore {
+    async fn get(&self, routing_key: &str) -> Result<Option<SessionMapping>> {
+        GatewayStore::get(self, routing_key).await
+    }
+
+    async fn set(&self, routing_key: &str, mapping: &SessionMapping) -> Result<()> {
+        GatewayStore::set(self, routing_key, mapping).await
+    }
+
+    async fn find_by_session_id(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<(String, SessionMapping)>> {
+        GatewayStore::find_by_session_id(self, session_id).await
+    }
+
+    async fn update_delivery(&self, routing_key: &str, delivery: &DeliveryContext) -> Result<()> {
+        GatewayStore::update_delivery(self, routing_key, delivery).await
+    }
+
+    async fn list(&self, limit: usize) -> Result<Vec<(String, SessionMapping)>> {
+        GatewayStore::list(self, limit).await
+    }
+
+    async fn list_filtered(
+        &self,
+        filter: &SessionMappingFilter,
+        limit: usize,
+    ) -> Result<Vec<(String, SessionMapping)>> {
+        GatewayStore::list_filtered(self, filter, limit).await
+    }
+
+    async fn session_footprints(&self) -> Result<Vec<SessionFootprint>> {
+        GatewayStore::session_footprints(self).await
+    }
+
+    async fn relink(&self, routing_key: &str, session_id: &str) -> Result<bool> {
+        GatewayStore::relink(self, routing_key, session_id).await
+    }
+
+    async fn delete(&self, routing_key: &str) -> Result<bool> {
+        GatewayStore::delete(self, routing_key).await
+    }
+
+    async fn prune(&self, max_age_ms: i64) -> Result<usize> {
+        GatewayStore::prune(self, max_age_ms).await
+    }
+
+    async fn set_delivery_context(
+        &self,
+        channel: &str,
+        target_key: &str,
+        context: &serde_json::Value,
+        ttl_hours: u64,
+    ) -> Result<()> {
+        GatewayStore::set_delivery_context(self, channel, target_key, context, ttl_hours).await
+    }
+
+    async fn pop_delivery_context(
+        &self,
+        channel: &str,
+        target_key: &str,
+    ) -> Result<Option<serde_json::Value>> {
+        GatewayStore::pop_delivery_context(self, channel, target_key).await
+    }
+
+    async fn prune_delivery_contexts(&self) -> Result<usize> {
+        GatewayStore::prune_delivery_contexts(self).await
+    }
+
+    async fn enqueue_offline_inbound(&self, message: &InboundMessage) -> Result<i64> {
+        GatewayStore::enqueue_offline_inbound(self, message).await
+    }
+
+    async fn list_offline_inbound(&self) -> Result<Vec<(i64, InboundMessage)>> {
+        GatewayStore::list_offline_inbound(self).await
+    }
+
+    async fn delete_offline_inbound(&self, id: i64) -> Result<()> {
+        GatewayStore::delete_offline_inbound(self, id).await
+    }
+
+    async fn count_offline_inbound(&self) -> Result<usize> {
+        GatewayStore::count_offline_inbound(self).await
+    }
+
+    async fn record_approval_audit(&self, records: &[ApprovalAuditRecord]) -> Result<()> {
+        GatewayStore::record_approval_audit(self, records).await
+    }
+
+    async fn list_approval_audit(
+        &self,
+        filter: &ApprovalAuditFilter,
+        limit: usize,
+    ) -> Result<Vec<ApprovalAuditRecord>> {
+        GatewayStore::list_approval_audit(self, filter, limit).await
+    }
+}
+
 fn parse_session_mapping_row(row: &libsql::Row, start_idx: usize) -> Result<SessionMapping> {
     let col = |offset: usize| -> Result<i32> {
         i32::try_from(start_idx + offset)
@@ -421,8 +878,9 @@ fn now_millis() -> i64 {
 mod tests {
     use serde_json::json;
 
-    use super::{GatewayStore, SessionMapping, now_millis};
-    use crate::types::{ChannelId, ChatType, DeliveryContext, PeerId};
+    use super::{GatewayStore, SessionMapping, SessionMappingFilter, now_millis};
+    use crate::audit::{ApprovalAuditFilter, ApprovalAuditRecord, AuditDecision, AuditSource};
+    use crate::types::{ChannelId, ChatType, DeliveryContext, InboundMessage, PeerId};
 
     fn sample_mapping(session_id: &str, updated_at: i64) -> SessionMapping {
         SessionMapping {
@@ -528,6 +986,27 @@ mod tests {
         assert!(store.get("new").await.expect("get new").is_some());
     }
 
+    #[tokio::test]
+    async fn session_footprints_report_every_entry() {
+        let store = GatewayStore::open_in_memory().await.expect("store");
+        let now = now_millis();
+
+        store
+            .set("rk-1", &sample_mapping("s1", now - 1_000))
+            .await
+            .expect("set rk-1");
+        store
+            .set("rk-2", &sample_mapping("s2", now))
+            .await
+            .expect("set rk-2");
+
+        let mut footprints = store.session_footprints().await.expect("footprints");
+        footprints.sort_by_key(|footprint| footprint.updated_at);
+        assert_eq!(footprints.len(), 2);
+        assert_eq!(footprints[0].routing_key, "rk-1");
+        assert!(footprints.iter().all(|footprint| footprint.bytes > 0));
+    }
+
     #[tokio::test]
     async fn set_overwrites_existing_key() {
         let store = GatewayStore::open_in_memory().await.expect("store");
@@ -574,9 +1053,81 @@ mod tests {
             .set("rk", &sample_mapping("s1", now_millis()))
             .await
             .expect("set");
-        store.delete("rk").await.expect("delete");
+        assert!(store.delete("rk").await.expect("delete"));
 
         assert!(store.get("rk").await.expect("get").is_none());
+        assert!(!store.delete("rk").await.expect("delete again"));
+    }
+
+    #[tokio::test]
+    async fn list_filtered_matches_channel_and_session() {
+        let store = GatewayStore::open_in_memory().await.expect("store");
+        let now = now_millis();
+        let mut slack = sample_mapping("s1", now);
+        slack.delivery.channel = ChannelId::from("slack");
+
+        store.set("rk1", &slack).await.expect("set rk1");
+        store
+            .set("rk2", &sample_mapping("s1", now - 100))
+            .await
+            .expect("set rk2");
+        store
+            .set("rk3", &sample_mapping("s2", now - 200))
+            .await
+            .expect("set rk3");
+
+        let keys = |rows: Vec<(String, SessionMapping)>| {
+            rows.into_iter().map(|(key, _)| key).collect::<Vec<_>>()
+        };
+        let by_session = SessionMappingFilter {
+            session_id: Some("s1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            keys(store.list_filtered(&by_session, 10).await.expect("list")),
+            vec!["rk1", "rk2"]
+        );
+        let by_channel = SessionMappingFilter {
+            channel: Some("telegram".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            keys(store.list_filtered(&by_channel, 10).await.expect("list")),
+            vec!["rk2", "rk3"]
+        );
+        let both = SessionMappingFilter {
+            channel: Some("telegram".to_string()),
+            session_id: Some("s1".to_string()),
+        };
+        assert_eq!(
+            keys(store.list_filtered(&both, 10).await.expect("list")),
+            vec!["rk2"]
+        );
+        assert_eq!(
+            store
+                .list_filtered(&SessionMappingFilter::default(), 2)
+                .await
+                .expect("list")
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn relink_points_key_at_new_session() {
+        let store = GatewayStore::open_in_memory().await.expect("store");
+        let mapping = sample_mapping("s1", now_millis() - 1_000);
+        store.set("rk", &mapping).await.expect("set");
+
+        assert!(store.relink("rk", "s2").await.expect("relink"));
+        let fetched = store.get("rk").await.expect("get").expect("mapping");
+        assert_eq!(fetched.session_id, "s2");
+        assert_eq!(fetched.title, mapping.title);
+        assert_eq!(fetched.delivery.channel_meta, mapping.delivery.channel_meta);
+        assert!(fetched.delivery.updated_at > mapping.delivery.updated_at);
+
+        assert!(!store.relink("missing", "s2").await.expect("relink missing"));
+        assert!(store.get("missing").await.expect("get").is_none());
     }
 
     #[tokio::test]
@@ -836,4 +1387,109 @@ mod tests {
             result.err()
         );
     }
+
+    #[tokio::test]
+    async fn offline_inbound_lists_in_received_order_and_deletes() {
+        let store = GatewayStore::open_in_memory().await.expect("store");
+        let now = chrono::Utc::now();
+        let message = |text: &str, age_secs: i64| InboundMessage {
+            channel: ChannelId::from("telegram"),
+            peer_id: PeerId::from("123"),
+            chat_type: ChatType::Direct,
+            text: text.to_string(),
+            media: Vec::new(),
+            metadata: json!({"chat_id": 1}),
+            timestamp: now - chrono::Duration::seconds(age_secs),
+            control: None,
+        };
+
+        store
+            .enqueue_offline_inbound(&message("second", 10))
+            .await
+            .expect("enqueue");
+        // Re-held after a failed replay: older message queued later.
+        store
+            .enqueue_offline_inbound(&message("first", 20))
+            .await
+            .expect("enqueue");
+
+        let held = store.list_offline_inbound().await.expect("list");
+        let texts = held
+            .iter()
+            .map(|(_, message)| message.text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, vec!["first", "second"]);
+        assert_eq!(store.count_offline_inbound().await.expect("count"), 2);
+
+        store
+            .delete_offline_inbound(held[0].0)
+            .await
+            .expect("delete");
+        assert_eq!(store.count_offline_inbound().await.expect("count"), 1);
+    }
+
+    #[tokio::test]
+    async fn approval_audit_round_trip_and_filters() {
+        let store = GatewayStore::open_in_memory().await.expect("store");
+        let record = |tool_call_id: &str, channel: &str, decided_at: i64| ApprovalAuditRecord {
+            id: 0,
+            decided_at,
+            channel: channel.to_string(),
+            session_id: "s1".to_string(),
+            run_id: "r1".to_string(),
+            tool_call_id: tool_call_id.to_string(),
+            tool_name: "run_command".to_string(),
+            arguments_hash: "ab".repeat(32),
+            decision: AuditDecision::Approved,
+            source: AuditSource::User,
+            decided_by: "U1".to_string(),
+            reason: None,
+        };
+        let mut denied = record("tc3", "telegram", 300);
+        denied.decision = AuditDecision::Denied;
+        denied.source = AuditSource::System;
+        denied.reason = Some("Cancelled — gateway shutting down".to_string());
+
+        store
+            .record_approval_audit(&[record("tc1", "slack", 100), record("tc2", "slack", 200)])
+            .await
+            .expect("record");
+        store
+            .record_approval_audit(std::slice::from_ref(&denied))
+            .await
+            .expect("record denied");
+
+        let all = store
+            .list_approval_audit(&ApprovalAuditFilter::default(), 10)
+            .await
+            .expect("list");
+        let ids: Vec<&str> = all.iter().map(|r| r.tool_call_id.as_str()).collect();
+        assert_eq!(ids, vec!["tc3", "tc2", "tc1"]);
+        assert!(all.iter().all(|r| r.id > 0));
+        assert_eq!(all[0].reason, denied.reason);
+        assert_eq!(all[0].source, AuditSource::System);
+
+        let window = ApprovalAuditFilter {
+            since: Some(100),
+            until: Some(300),
+            channel: Some("slack".to_string()),
+            ..Default::default()
+        };
+        let ids: Vec<String> = store
+            .list_approval_audit(&window, 10)
+            .await
+            .expect("list window")
+            .into_iter()
+            .map(|r| r.tool_call_id)
+            .collect();
+        assert_eq!(ids, vec!["tc2", "tc1"]);
+        assert_eq!(
+            store
+                .list_approval_audit(&ApprovalAuditFilter::default(), 1)
+                .await
+                .expect("list limit")
+                .len(),
+            1
+        );
+    }
 }
@@ -17,6 +17,17 @@ pub enum ChannelTarget {
         channel: String,
         thread_ts: Option<String>,
     },
+    WhatsApp {
+        to: String,
+    },
+    Matrix {
+        room_id: String,
+        thread_id: Option<String>,
+    },
+    RocketChat {
+        room_id: String,
+        thread_id: Option<String>,
+    },
 }
 
 impl ChannelTarget {
@@ -55,6 +66,29 @@ impl ChannelTarget {
                 let thread_ts = obj.get("thread_ts").and_then(value_as_string);
                 Ok(Self::Slack { channel, thread_ts })
             }
+            "whatsapp" => {
+                let to = obj
+                    .get("to")
+                    .and_then(value_as_string)
+                    .ok_or_else(|| anyhow!("missing required field: target.to"))?;
+                Ok(Self::WhatsApp { to })
+            }
+            "matrix" => {
+                let room_id = obj
+                    .get("room_id")
+                    .and_then(value_as_string)
+                    .ok_or_else(|| anyhow!("missing required field: target.room_id"))?;
+                let thread_id = obj.get("thread_id").and_then(value_as_string);
+                Ok(Self::Matrix { room_id, thread_id })
+            }
+            "rocketchat" => {
+                let room_id = obj
+                    .get("room_id")
+                    .and_then(value_as_string)
+                    .ok_or_else(|| anyhow!("missing required field: target.room_id"))?;
+                let thread_id = obj.get("thread_id").and_then(value_as_string);
+                Ok(Self::RocketChat { room_id, thread_id })
+            }
             other => Err(anyhow!("unsupported channel target: {other}")),
         }
     }
@@ -83,6 +117,15 @@ impl ChannelTarget {
                 }
                 None => format!("slack:channel:{channel}"),
             },
+            Self::WhatsApp { to } => format!("whatsapp:chat:{to}"),
+            Self::Matrix { room_id, thread_id } => match thread_id {
+                Some(thread_id) => format!("matrix:room:{room_id}:thread:{thread_id}"),
+                None => format!("matrix:room:{room_id}"),
+            },
+            Self::RocketChat { room_id, thread_id } => match thread_id {
+                Some(thread_id) => format!("rocketchat:room:{room_id}:thread:{thread_id}"),
+                None => format!("rocketchat:room:{room_id}"),
+            },
         }
     }
 
@@ -91,6 +134,10 @@ impl ChannelTarget {
             Self::Telegram { chat_id, .. } => chat_id.clone().into(),
             Self::Discord { channel_id, .. } => channel_id.clone().into(),
             Self::Slack { channel, .. } => channel.clone().into(),
+            Self::WhatsApp { to } => to.clone().into(),
+            Self::Matrix { room_id, .. } | Self::RocketChat { room_id, .. } => {
+                room_id.clone().into()
+            }
         }
     }
 
@@ -127,6 +174,18 @@ impl ChannelTarget {
                     id: channel.clone(),
                 },
             },
+            Self::WhatsApp { .. } => ChatType::Direct,
+            Self::Matrix { room_id, thread_id } | Self::RocketChat { room_id, thread_id } => {
+                match thread_id {
+                    Some(thread_id) => ChatType::Thread {
+                        group_id: room_id.clone(),
+                        thread_id: thread_id.clone(),
+                    },
+                    None => ChatType::Group {
+                        id: room_id.clone(),
+                    },
+                }
+            }
         }
     }
 
@@ -149,6 +208,15 @@ impl ChannelTarget {
                 "channel": channel,
                 "thread_ts": thread_ts,
             }),
+            Self::WhatsApp { to } => serde_json::json!({
+                "to": to,
+            }),
+            Self::Matrix { room_id, thread_id } | Self::RocketChat { room_id, thread_id } => {
+                serde_json::json!({
+                    "room_id": room_id,
+                    "thread_id": thread_id,
+                })
+            }
         }
     }
 
@@ -171,6 +239,15 @@ impl ChannelTarget {
                 channel: channel.clone(),
                 thread_ts: thread_id,
             },
+            Self::WhatsApp { .. } => self.clone(),
+            Self::Matrix { room_id, .. } => Self::Matrix {
+                room_id: room_id.clone(),
+                thread_id,
+            },
+            Self::RocketChat { room_id, .. } => Self::RocketChat {
+                room_id: room_id.clone(),
+                thread_id,
+            },
         }
     }
 
@@ -178,10 +255,13 @@ impl ChannelTarget {
         match self {
             Self::Telegram { thread_id, .. }
             | Self::Discord { thread_id, .. }
+            | Self::Matrix { thread_id, .. }
+            | Self::RocketChat { thread_id, .. }
             | Self::Slack {
                 thread_ts: thread_id,
                 ..
             } => thread_id.clone(),
+            Self::WhatsApp { .. } => None,
         }
     }
 }
@@ -233,6 +313,21 @@ pub fn target_key_from_inbound(message: &InboundMessage) -> String {
                 None => format!("slack:channel:{channel}"),
             }
         }
+        "matrix" | "rocketchat" => {
+            let room_id = message
+                .metadata
+                .get("room_id")
+                .and_then(value_as_string)
+                .or_else(|| fallback_group_id(&message.chat_type))
+                .unwrap_or_else(|| message.peer_id.0.clone());
+            let thread_id = message.metadata.get("thread_id").and_then(value_as_string);
+            match thread_id {
+                Some(thread_id) => {
+                    format!("{}:room:{room_id}:thread:{thread_id}", message.channel.0)
+                }
+                None => format!("{}:room:{room_id}", message.channel.0),
+            }
+        }
         _ => {
             let chat =
                 fallback_group_id(&message.chat_type).unwrap_or_else(|| message.peer_id.0.clone());
@@ -271,6 +366,14 @@ pub fn target_key_from_channel_chat(
             ChatType::Group { id } => format!("slack:channel:{id}"),
             ChatType::Direct => format!("slack:channel:{}", peer.0),
         },
+        "matrix" | "rocketchat" => match chat_type {
+            ChatType::Thread {
+                group_id,
+                thread_id,
+            } => format!("{}:room:{group_id}:thread:{thread_id}", channel.0),
+            ChatType::Group { id } => format!("{}:room:{id}", channel.0),
+            ChatType::Direct => format!("{}:room:{}", channel.0, peer.0),
+        },
         _ => match chat_type {
             ChatType::Thread {
                 group_id,
@@ -325,8 +428,10 @@ fn value_as_string(value: &serde_json::Value) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::{ChannelTarget, render_title_template};
-    use crate::types::ChatType;
+    use super::{
+        ChannelTarget, render_title_template, target_key_from_channel_chat, target_key_from_inbound,
+    };
+    use crate::types::{ChatType, InboundMessage};
 
     #[test]
     fn with_thread_id_sets_slack_thread() {
@@ -350,6 +455,35 @@ mod tests {
         assert!(target.thread_id().is_none());
     }
 
+    #[test]
+    fn matrix_target_keys_match_inbound_room_threads() {
+        let target = ChannelTarget::parse(
+            "matrix",
+            &serde_json::json!({"room_id": "!ops:example.org", "thread_id": "$root"}),
+        )
+        .expect("matrix target");
+        assert_eq!(
+            target.target_key(),
+            "matrix:room:!ops:example.org:thread:$root"
+        );
+
+        let inbound = InboundMessage {
+            channel: "matrix".into(),
+            peer_id: "@dana:example.org".into(),
+            chat_type: target.chat_type(),
+            text: "hi".to_string(),
+            media: Vec::new(),
+            metadata: target.metadata(),
+            timestamp: chrono::Utc::now(),
+            control: None,
+        };
+        assert_eq!(target_key_from_inbound(&inbound), target.target_key());
+        assert_eq!(
+            target_key_from_channel_chat(&inbound.channel, &inbound.chat_type, &inbound.peer_id),
+            target.target_key()
+        );
+    }
+
     #[test]
     fn render_title_template_formats_chat_placeholders() {
         let title = render_title_template(
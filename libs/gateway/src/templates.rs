@@ -0,0 +1,200 @@
+//! Outbound message templates.
+//!
+//! [`MessageTemplates`] replaces the dispatcher's built-in text for run
+//! lifecycle messages. Templates are set under `[gateway.templates]` and per
+//! channel under `[channels.<name>.templates]`, where they win field by
+//! field. An unset template keeps the built-in behavior: no message for
+//! `run_started` and `run_completed`, the default text for `tool_proposed`
+//! and `run_failed`.
+//!
+//! Templates use `{name}` placeholders; see [`TemplateVars`]. Unknown
+//! placeholders are left as written.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageTemplates {
+    /// Posted when a run starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_started: Option<String>,
+    /// Replaces the summary of tool calls that run without approval.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_proposed: Option<String>,
+    /// Posted after a run's final reply.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_completed: Option<String>,
+    /// Replaces the run failure notice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_failed: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageEvent {
+    RunStarted,
+    ToolProposed,
+    RunCompleted,
+    RunFailed,
+}
+
+/// Values for a template's placeholders.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateVars<'a> {
+    /// `{session_title}`
+    pub session_title: &'a str,
+    /// `{session_id}`
+    pub session_id: &'a str,
+    /// `{run_id}`
+    pub run_id: &'a str,
+    /// `{channel}`
+    pub channel: &'a str,
+    /// `{duration}`: time since the run started, e.g. `2m 05s`.
+    pub duration: Duration,
+    /// `{summary}`: the first line of the message that started the run, the
+    /// proposed tool names, the final reply, or the error.
+    pub summary: &'a str,
+    /// `{tool_count}`
+    pub tool_count: usize,
+    /// `{tools}`: each proposed tool call with its argument preview.
+    pub tools: &'a str,
+}
+
+impl MessageTemplates {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// These templates, falling back to `defaults` for unset ones.
+    pub fn or(&self, defaults: &MessageTemplates) -> MessageTemplates {
+        MessageTemplates {
+            run_started: self.run_started.clone().or(defaults.run_started.clone()),
+            tool_proposed: self
+                .tool_proposed
+                .clone()
+                .or(defaults.tool_proposed.clone()),
+            run_completed: self
+                .run_completed
+                .clone()
+                .or(defaults.run_completed.clone()),
+            run_failed: self.run_failed.clone().or(defaults.run_failed.clone()),
+        }
+    }
+
+    pub fn get(&self, event: MessageEvent) -> Option<&str> {
+        match event {
+            MessageEvent::RunStarted => self.run_started.as_deref(),
+            MessageEvent::ToolProposed => self.tool_proposed.as_deref(),
+            MessageEvent::RunCompleted => self.run_completed.as_deref(),
+            MessageEvent::RunFailed => self.run_failed.as_deref(),
+        }
+        .filter(|template| !template.trim().is_empty())
+    }
+
+    /// The message for `event`, or `None` when no template is set.
+    pub fn render(&self, event: MessageEvent, vars: &TemplateVars<'_>) -> Option<String> {
+        self.get(event)
+            .map(|template| render_template(template, vars))
+    }
+}
+
+/// Substitute placeholders in one pass, so values containing `{...}` are
+/// never expanded again.
+pub fn render_template(template: &str, vars: &TemplateVars<'_>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let (before, from_brace) = rest.split_at(start);
+        out.push_str(before);
+
+        let value = from_brace.find('}').and_then(|end| {
+            let name = from_brace.get(1..end)?;
+            let value = match name {
+                "session_title" => vars.session_title.to_string(),
+                "session_id" => vars.session_id.to_string(),
+                "run_id" => vars.run_id.to_string(),
+                "channel" => vars.channel.to_string(),
+                "duration" => format_duration(vars.duration),
+                "summary" => vars.summary.to_string(),
+                "tool_count" => vars.tool_count.to_string(),
+                "tools" => vars.tools.trim_end().to_string(),
+                _ => return None,
+            };
+            Some((value, end + 1))
+        });
+
+        match value {
+            Some((value, consumed)) => {
+                out.push_str(&value);
+                rest = from_brace.get(consumed..).unwrap_or_default();
+            }
+            None => {
+                out.push('{');
+                rest = from_brace.get(1..).unwrap_or_default();
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `45s`, `2m 05s`, `1h 02m`.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match (seconds / 3600, seconds % 3600 / 60, seconds % 60) {
+        (0, 0, secs) => format!("{secs}s"),
+        (0, mins, secs) => format!("{mins}m {secs:02}s"),
+        (hours, mins, _) => format!("{hours}h {mins:02}m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_placeholders_once() {
+        let vars = TemplateVars {
+            session_title: "slack / U1",
+            run_id: "r-1",
+            duration: Duration::from_secs(125),
+            summary: "literal {run_id}",
+            ..TemplateVars::default()
+        };
+        assert_eq!(
+            render_template(
+                "✅ {session_title} done in {duration}: {summary} ({run_id}) {unknown} {",
+                &vars
+            ),
+            "✅ slack / U1 done in 2m 05s: literal {run_id} (r-1) {unknown} {"
+        );
+    }
+
+    #[test]
+    fn channel_templates_fall_back_to_defaults() {
+        let defaults = MessageTemplates {
+            run_started: Some("started".to_string()),
+            run_failed: Some("failed".to_string()),
+            ..MessageTemplates::default()
+        };
+        let channel = MessageTemplates {
+            run_failed: Some("channel failed".to_string()),
+            run_completed: Some("  ".to_string()),
+            ..MessageTemplates::default()
+        };
+
+        let merged = channel.or(&defaults);
+        assert_eq!(merged.get(MessageEvent::RunStarted), Some("started"));
+        assert_eq!(merged.get(MessageEvent::RunFailed), Some("channel failed"));
+        assert_eq!(merged.get(MessageEvent::RunCompleted), None);
+        assert_eq!(merged.get(MessageEvent::ToolProposed), None);
+    }
+
+    #[test]
+    fn durations_are_compact() {
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(60)), "1m 00s");
+        assert_eq!(format_duration(Duration::from_secs(3_725)), "1h 02m");
+    }
+}
@@ -0,0 +1,404 @@
+//! Chat renderers for finished tool calls.
+//!
+//! [`ToolResultRenderers`] maps tool names to renderers that turn a tool
+//! result into readable chat markdown: `kubectl get` output becomes a table, a
+//! `terraform plan` is summarized into counts and affected resources, and file
+//! edits are shown as a diff. The dispatcher tries these first and falls back
+//! to [`render_generic_result`] when no renderer applies.
+
+use std::collections::HashMap;
+
+use stakpak_shared::utils::{strip_tool_name, truncate_chars_with_ellipsis};
+
+use crate::config::ToolResultDisplay;
+
+const MAX_RESULT_CHARS: usize = 1500;
+const MAX_TABLE_ROWS: usize = 30;
+const MAX_PLAN_RESOURCES: usize = 15;
+const MAX_DIFF_LINES: usize = 40;
+
+/// A finished tool call, as seen by a renderer.
+#[derive(Debug, Clone, Copy)]
+pub struct ToolResultView<'a> {
+    pub tool_name: &'a str,
+    /// Arguments the tool was called with, `Null` when unknown.
+    pub args: &'a serde_json::Value,
+    pub result: &'a str,
+    pub is_error: bool,
+}
+
+impl ToolResultView<'_> {
+    fn arg_str(&self, key: &str) -> Option<&str> {
+        self.args.get(key).and_then(|value| value.as_str())
+    }
+}
+
+/// Renders a successful tool result, or returns `None` to fall back to the
+/// generic renderer.
+pub type ToolResultRenderer = fn(&ToolResultView<'_>) -> Option<String>;
+
+/// Registry of per-tool renderers, keyed by bare tool name.
+#[derive(Clone)]
+pub struct ToolResultRenderers {
+    renderers: HashMap<String, ToolResultRenderer>,
+}
+
+impl Default for ToolResultRenderers {
+    fn default() -> Self {
+        let mut renderers = Self::empty();
+        for tool in ["run_command", "run_remote_command"] {
+            renderers.register(tool, render_command_result);
+        }
+        renderers.register("str_replace", render_str_replace_result);
+        renderers
+    }
+}
+
+impl ToolResultRenderers {
+    /// A registry without built-in renderers.
+    pub fn empty() -> Self {
+        Self {
+            renderers: HashMap::new(),
+        }
+    }
+
+    /// Register `renderer` for `tool_name`, replacing any existing one.
+    pub fn register(&mut self, tool_name: &str, renderer: ToolResultRenderer) {
+        self.renderers.insert(tool_name.to_string(), renderer);
+    }
+
+    /// Render `view` for chat according to `display`. Failed calls always use
+    /// the generic renderer so the error text is shown unchanged.
+    pub fn render(&self, display: ToolResultDisplay, view: &ToolResultView<'_>) -> Option<String> {
+        if display == ToolResultDisplay::Off {
+            return None;
+        }
+        if !view.is_error
+            && let Some(renderer) = self.renderers.get(strip_tool_name(view.tool_name))
+            && let Some(text) = renderer(view)
+        {
+            return Some(text);
+        }
+        (display == ToolResultDisplay::All).then(|| render_generic_result(view))
+    }
+}
+
+/// Fallback rendering: the tool name and its (truncated) output in a code block.
+pub fn render_generic_result(view: &ToolResultView<'_>) -> String {
+    let icon = if view.is_error { "⚠️" } else { "✅" };
+    let result = strip_ansi(view.result);
+    let result = result.trim();
+    let name = strip_tool_name(view.tool_name);
+    if result.is_empty() {
+        return format!("{icon} **{name}**");
+    }
+    format!(
+        "{icon} **{name}**\n```\n{}\n```",
+        truncate_chars_with_ellipsis(result, MAX_RESULT_CHARS)
+    )
+}
+
+fn render_command_result(view: &ToolResultView<'_>) -> Option<String> {
+    let command = view.arg_str("command")?;
+    let output = strip_ansi(view.result);
+    if is_subcommand(command, &["kubectl", "get"]) {
+        render_kubectl_table(command, &output)
+    } else if is_subcommand(command, &["terraform", "plan"])
+        || is_subcommand(command, &["tofu", "plan"])
+    {
+        render_terraform_plan(&output)
+    } else {
+        None
+    }
+}
+
+/// Whether `command` runs `words` in sequence, e.g. `kubectl -n prod get pods`
+/// for `["kubectl", "get"]`; flags between the words are skipped.
+fn is_subcommand(command: &str, words: &[&str]) -> bool {
+    let mut tokens = command.split_whitespace();
+    let Some((program, rest)) = words.split_first() else {
+        return false;
+    };
+    if !tokens.any(|token| token.rsplit('/').next() == Some(*program)) {
+        return false;
+    }
+    rest.iter().all(|word| tokens.any(|token| token == *word))
+}
+
+/// Render column-aligned `kubectl get` output as a markdown table.
+fn render_kubectl_table(command: &str, output: &str) -> Option<String> {
+    let mut lines = output.lines().filter(|line| !line.trim().is_empty());
+    let header = lines.next()?;
+    let starts = column_starts(header);
+    let columns = split_columns(header, &starts);
+    // Table output has at least two upper-case headers; anything else (-o yaml,
+    // "No resources found", ...) is left to the generic renderer.
+    if columns.len() < 2
+        || columns
+            .iter()
+            .any(|column| column.chars().any(char::is_lowercase))
+    {
+        return None;
+    }
+
+    let rows: Vec<Vec<String>> = lines.map(|line| split_columns(line, &starts)).collect();
+    let mut out = format!(
+        "☸️ `{}`\n\n",
+        truncate_chars_with_ellipsis(command.trim(), 120)
+    );
+    out.push_str(&table_row(&columns));
+    out.push_str(&table_row(&vec!["---".to_string(); columns.len()]));
+    for row in rows.iter().take(MAX_TABLE_ROWS) {
+        out.push_str(&table_row(row));
+    }
+    if rows.len() > MAX_TABLE_ROWS {
+        out.push_str(&format!(
+            "\n_…and {} more row(s)_\n",
+            rows.len() - MAX_TABLE_ROWS
+        ));
+    }
+    Some(out.trim_end().to_string())
+}
+
+/// Character offsets where header columns start: the first character and any
+/// character following two or more spaces.
+fn column_starts(header: &str) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut spaces = 0;
+    for (index, ch) in header.chars().enumerate() {
+        if ch == ' ' {
+            spaces += 1;
+            continue;
+        }
+        if starts.is_empty() || spaces >= 2 {
+            starts.push(index);
+        }
+        spaces = 0;
+    }
+    starts
+}
+
+fn split_columns(line: &str, starts: &[usize]) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(index, start)| {
+            let end = starts.get(index + 1).copied().unwrap_or(chars.len());
+            chars
+                .iter()
+                .skip(*start)
+                .take(end.saturating_sub(*start))
+                .collect::<String>()
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+fn table_row(cells: &[String]) -> String {
+    let cells: Vec<String> = cells.iter().map(|cell| cell.replace('|', "\\|")).collect();
+    format!("| {} |\n", cells.join(" | "))
+}
+
+/// Summarize `terraform plan` output into its change counts and resources,
+/// with the plan body as truncated detail.
+fn render_terraform_plan(output: &str) -> Option<String> {
+    let summary = output.lines().map(str::trim).find_map(|line| {
+        if let Some(counts) = line.strip_prefix("Plan:") {
+            Some(counts.trim().trim_end_matches('.').to_string())
+        } else if line.starts_with("No changes.") {
+            Some("no changes".to_string())
+        } else {
+            None
+        }
+    })?;
+
+    let resources: Vec<&str> = output
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| line.strip_prefix("# "))
+        .filter(|line| line.contains(" will be ") || line.contains(" must be "))
+        .collect();
+
+    let mut out = format!("📋 **terraform plan**: {}\n", summary);
+    for resource in resources.iter().take(MAX_PLAN_RESOURCES) {
+        match resource.split_once(' ') {
+            Some((address, action)) => out.push_str(&format!("• `{}` {}\n", address, action)),
+            None => out.push_str(&format!("• `{}`\n", resource)),
+        }
+    }
+    if resources.len() > MAX_PLAN_RESOURCES {
+        out.push_str(&format!(
+            "_…and {} more resource(s)_\n",
+            resources.len() - MAX_PLAN_RESOURCES
+        ));
+    }
+    if !resources.is_empty() {
+        out.push_str(&format!(
+            "\nDetails:\n```\n{}\n```",
+            truncate_chars_with_ellipsis(output.trim(), MAX_RESULT_CHARS)
+        ));
+    }
+    Some(out.trim_end().to_string())
+}
+
+/// Show a successful `str_replace` as a diff of the replaced text.
+fn render_str_replace_result(view: &ToolResultView<'_>) -> Option<String> {
+    let path = view.arg_str("path")?;
+    let old = view.arg_str("old_str").unwrap_or_default();
+    let new = view.arg_str("new_str").unwrap_or_default();
+
+    let removed = old.lines().map(|line| format!("- {}", line));
+    let added = new.lines().map(|line| format!("+ {}", line));
+    let lines: Vec<String> = removed.chain(added).collect();
+
+    let mut out = format!("✏️ `{}`\n```diff\n", path);
+    for line in lines.iter().take(MAX_DIFF_LINES) {
+        out.push_str(line);
+        out.push('\n');
+    }
+    if lines.len() > MAX_DIFF_LINES {
+        out.push_str(&format!(
+            "… {} more line(s)\n",
+            lines.len() - MAX_DIFF_LINES
+        ));
+    }
+    out.push_str("```");
+    Some(out)
+}
+
+/// Remove ANSI escape sequences (colors from `terraform`, `kubectl`, ...).
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\u{1b}' {
+            out.push(ch);
+            continue;
+        }
+        if chars.next() == Some('[') {
+            for next in chars.by_ref() {
+                if next.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view<'a>(
+        tool_name: &'a str,
+        args: &'a serde_json::Value,
+        result: &'a str,
+    ) -> ToolResultView<'a> {
+        ToolResultView {
+            tool_name,
+            args,
+            result,
+            is_error: false,
+        }
+    }
+
+    #[test]
+    fn kubectl_get_renders_markdown_table() {
+        let args = serde_json::json!({"command": "kubectl -n prod get pods"});
+        let output = "NAME                     READY   STATUS             RESTARTS   AGE\n\
+                      api-7d9f8b6c5d-x2k4p     1/1     Running            0          3d\n\
+                      worker-5c6d7e8f9-abcde   0/1     CrashLoopBackOff   42         1h\n";
+
+        let rendered = ToolResultRenderers::default()
+            .render(
+                ToolResultDisplay::Rendered,
+                &view("stakpak__run_command", &args, output),
+            )
+            .expect("rendered table");
+
+        assert!(rendered.contains("| NAME | READY | STATUS | RESTARTS | AGE |"));
+        assert!(rendered.contains("| --- | --- | --- | --- | --- |"));
+        assert!(rendered.contains("| worker-5c6d7e8f9-abcde | 0/1 | CrashLoopBackOff | 42 | 1h |"));
+    }
+
+    #[test]
+    fn terraform_plan_renders_counts_and_resources() {
+        let args = serde_json::json!({"command": "cd infra && terraform plan -no-color"});
+        let output = "Terraform will perform the following actions:\n\n  \
+                      # aws_instance.web will be created\n  + resource \"aws_instance\" \"web\" {}\n\n  \
+                      # aws_s3_bucket.logs will be updated in-place\n\n\
+                      \u{1b}[1mPlan:\u{1b}[0m 1 to add, 1 to change, 0 to destroy.\n";
+
+        let rendered = ToolResultRenderers::default()
+            .render(
+                ToolResultDisplay::Rendered,
+                &view("run_command", &args, output),
+            )
+            .expect("rendered plan");
+
+        assert!(rendered.starts_with("📋 **terraform plan**: 1 to add, 1 to change, 0 to destroy"));
+        assert!(rendered.contains("• `aws_instance.web` will be created"));
+        assert!(rendered.contains("• `aws_s3_bucket.logs` will be updated in-place"));
+        assert!(rendered.contains("Details:\n```"));
+    }
+
+    #[test]
+    fn str_replace_renders_diff() {
+        let args = serde_json::json!({
+            "path": "/etc/app.conf",
+            "old_str": "workers = 2",
+            "new_str": "workers = 4\nqueue = 100",
+        });
+
+        let rendered = ToolResultRenderers::default()
+            .render(
+                ToolResultDisplay::Rendered,
+                &view("str_replace", &args, "ok"),
+            )
+            .expect("rendered diff");
+
+        assert_eq!(
+            rendered,
+            "✏️ `/etc/app.conf`\n```diff\n- workers = 2\n+ workers = 4\n+ queue = 100\n```"
+        );
+    }
+
+    #[test]
+    fn unmatched_results_follow_display_setting() {
+        let renderers = ToolResultRenderers::default();
+        let args = serde_json::json!({"command": "df -h"});
+        let plain = view("run_command", &args, "Filesystem  Size\n/dev/sda1   50G");
+
+        assert_eq!(renderers.render(ToolResultDisplay::Rendered, &plain), None);
+        assert_eq!(renderers.render(ToolResultDisplay::Off, &plain), None);
+        assert_eq!(
+            renderers.render(ToolResultDisplay::All, &plain).as_deref(),
+            Some("✅ **run_command**\n```\nFilesystem  Size\n/dev/sda1   50G\n```")
+        );
+
+        let kubectl = serde_json::json!({"command": "kubectl get pods"});
+        let failed = ToolResultView {
+            is_error: true,
+            ..view("run_command", &kubectl, "COMMAND_FAILED")
+        };
+        assert_eq!(
+            renderers.render(ToolResultDisplay::All, &failed).as_deref(),
+            Some("⚠️ **run_command**\n```\nCOMMAND_FAILED\n```")
+        );
+        let yaml = view("run_command", &kubectl, "apiVersion: v1\nkind: List");
+        assert_eq!(renderers.render(ToolResultDisplay::Rendered, &yaml), None);
+    }
+
+    #[test]
+    fn is_subcommand_skips_flags_and_paths() {
+        assert!(is_subcommand(
+            "/usr/local/bin/kubectl --context prod get nodes",
+            &["kubectl", "get"]
+        ));
+        assert!(!is_subcommand("echo kubectlget", &["kubectl", "get"]));
+        assert!(!is_subcommand("terraform apply", &["terraform", "plan"]));
+    }
+}
@@ -69,6 +69,40 @@ pub struct InboundMessage {
     pub media: Vec<MediaAttachment>,
     pub metadata: serde_json::Value,
     pub timestamp: DateTime<Utc>,
+    /// Set when the message is a command to the gateway rather than a
+    /// message for the agent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control: Option<InboundControl>,
+}
+
+/// Commands a channel can send on behalf of a user.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InboundControl {
+    /// Cancel the active run of the conversation, e.g. after a cancel
+    /// reaction on one of the bot's messages.
+    Cancel,
+}
+
+impl InboundMessage {
+    /// The `/name` command the text starts with and the rest of the text,
+    /// also accepting Telegram's `/name@bot` form.
+    pub fn command(&self) -> Option<(&str, &str)> {
+        let rest = self.text.trim().strip_prefix('/')?;
+        let (head, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let name = match head.split_once('@') {
+            Some((name, bot)) if !bot.is_empty() => name,
+            Some(_) => return None,
+            None => head,
+        };
+        (!name.is_empty()).then_some((name, args.trim()))
+    }
+
+    /// Whether the message asks to cancel the active run, either as a
+    /// [`InboundControl::Cancel`] or as a `/cancel` command.
+    pub fn is_cancel(&self) -> bool {
+        self.control == Some(InboundControl::Cancel) || self.command() == Some(("cancel", ""))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,7 +127,7 @@ pub struct DeliveryContext {
 mod tests {
     use serde_json::json;
 
-    use super::ChatType;
+    use super::{ChatType, InboundControl, InboundMessage};
 
     #[test]
     fn chat_type_direct_round_trip() {
@@ -159,4 +193,48 @@ mod tests {
 
         assert_eq!(deserialized, chat_type);
     }
+
+    #[test]
+    fn inbound_cancel_from_control_or_command() {
+        let inbound: InboundMessage = match serde_json::from_value(json!({
+            "channel": "telegram",
+            "peer_id": "u1",
+            "chat_type": {"kind": "direct"},
+            "text": "/cancel",
+            "media": [],
+            "metadata": {},
+            "timestamp": "2026-01-01T00:00:00Z"
+        })) {
+            Ok(value) => value,
+            Err(error) => panic!("deserialization failed: {error}"),
+        };
+        assert_eq!(inbound.control, None);
+        assert!(inbound.is_cancel());
+
+        let with_text = |text: &str| InboundMessage {
+            text: text.to_string(),
+            ..inbound.clone()
+        };
+        assert!(with_text(" /cancel@stakpak_bot ").is_cancel());
+        assert!(!with_text("/cancel the deploy").is_cancel());
+        assert!(!with_text("/cancelled").is_cancel());
+        assert!(!with_text("please cancel").is_cancel());
+        assert_eq!(
+            with_text("/link@stakpak_bot  k3x9 ").command(),
+            Some(("link", "k3x9"))
+        );
+        assert_eq!(with_text("/link@ k3x9").command(), None);
+
+        let reaction = InboundMessage {
+            control: Some(InboundControl::Cancel),
+            ..with_text("")
+        };
+        assert!(reaction.is_cancel());
+        assert_eq!(
+            serde_json::to_value(&reaction)
+                .ok()
+                .and_then(|value| value.get("control").cloned()),
+            Some(json!("cancel"))
+        );
+    }
 }
@@ -41,6 +41,8 @@ pub enum McpServerEntry {
         env: Option<HashMap<String, String>>,
         #[serde(default, skip_serializing_if = "std::ops::Not::not")]
         disabled: bool,
+        #[serde(default, skip_serializing_if = "McpServerApproval::is_empty")]
+        approval: McpServerApproval,
     },
     UrlBased {
         url: String,
@@ -48,10 +50,151 @@ pub enum McpServerEntry {
         headers: Option<HashMap<String, String>>,
         #[serde(default, skip_serializing_if = "std::ops::Not::not")]
         disabled: bool,
+        #[serde(default, skip_serializing_if = "McpServerApproval::is_empty")]
+        approval: McpServerApproval,
     },
 }
 
+/// How calls to an external server's tools are approved, on top of the
+/// session's own approval settings.
+///
+/// ```toml
+/// [mcpServers.postgres.approval]
+/// default = "ask"
+/// tools = { query = "approve", drop_table = "deny" }
+/// ```
+///
+/// Denied tools are hidden from the agent and refused by the proxy.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct McpServerApproval {
+    /// Action for this server's tools without a `tools` entry. Unset leaves
+    /// them to the session's approval settings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<McpApprovalAction>,
+    /// Per-tool actions, by the tool's name on the server (no prefix).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tools: BTreeMap<String, McpApprovalAction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpApprovalAction {
+    /// Run without asking.
+    Approve,
+    /// Ask the user first.
+    Ask,
+    /// Never run.
+    Deny,
+}
+
+impl McpServerApproval {
+    pub fn is_empty(&self) -> bool {
+        self.default.is_none() && self.tools.is_empty()
+    }
+
+    /// The action for `tool` (its name on the server), if this policy sets
+    /// one.
+    pub fn action_for(&self, tool: &str) -> Option<McpApprovalAction> {
+        self.tools.get(tool).copied().or(self.default)
+    }
+}
+
+/// Server names stakpak uses for its own tools; config entries with these
+/// names are ignored.
+pub const RESERVED_SERVER_NAMES: &[&str] = &["stakpak", "paks", "aap"];
+
+/// Separator between the server name and the tool name in the tools the
+/// proxy exposes (`postgres__query`).
+pub const TOOL_NAME_SEPARATOR: &str = "__";
+
+/// Per-server approval policies, keyed by server name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct McpApprovalRules {
+    servers: BTreeMap<String, McpServerApproval>,
+}
+
+impl McpApprovalRules {
+    pub fn new(servers: BTreeMap<String, McpServerApproval>) -> Self {
+        Self {
+            servers: servers
+                .into_iter()
+                .filter(|(_, approval)| !approval.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Policies of the enabled, non-reserved servers in `config`.
+    pub fn from_config(config: &McpConfigFile) -> Self {
+        Self::new(
+            config
+                .servers
+                .iter()
+                .filter(|(name, entry)| {
+                    !entry.is_disabled() && !RESERVED_SERVER_NAMES.contains(&name.as_str())
+                })
+                .map(|(name, entry)| (name.clone(), entry.approval().clone()))
+                .collect(),
+        )
+    }
+
+    /// Policies from the MCP config file in the standard locations; empty
+    /// when there is none or it does not parse.
+    pub fn load() -> Self {
+        find_config_file()
+            .and_then(|path| load_config(Path::new(&path)))
+            .map(|config| Self::from_config(&config))
+            .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty()
+    }
+
+    /// The action for a prefixed tool name (`postgres__query`), if its
+    /// server's policy sets one.
+    pub fn action_for(&self, prefixed_tool_name: &str) -> Option<McpApprovalAction> {
+        let (server, tool) = prefixed_tool_name.split_once(TOOL_NAME_SEPARATOR)?;
+        let tool = tool.strip_suffix("()").unwrap_or(tool);
+        self.servers.get(server)?.action_for(tool)
+    }
+
+    pub fn is_denied(&self, prefixed_tool_name: &str) -> bool {
+        self.action_for(prefixed_tool_name) == Some(McpApprovalAction::Deny)
+    }
+
+    /// Rules keyed by prefixed tool name (`postgres__query`) and by server
+    /// (`postgres__*`), mapped to `approve`, `ask` or `deny`.
+    pub fn scope_rules<T: Clone>(&self, approve: T, ask: T, deny: T) -> HashMap<String, T> {
+        let action = |action: McpApprovalAction| match action {
+            McpApprovalAction::Approve => approve.clone(),
+            McpApprovalAction::Ask => ask.clone(),
+            McpApprovalAction::Deny => deny.clone(),
+        };
+
+        let mut rules = HashMap::new();
+        for (server, approval) in &self.servers {
+            if let Some(default) = approval.default {
+                rules.insert(format!("{server}{TOOL_NAME_SEPARATOR}*"), action(default));
+            }
+            for (tool, tool_action) in &approval.tools {
+                rules.insert(
+                    format!("{server}{TOOL_NAME_SEPARATOR}{tool}"),
+                    action(*tool_action),
+                );
+            }
+        }
+        rules
+    }
+}
+
 impl McpServerEntry {
+    pub fn approval(&self) -> &McpServerApproval {
+        match self {
+            McpServerEntry::CommandBased { approval, .. } => approval,
+            McpServerEntry::UrlBased { approval, .. } => approval,
+        }
+    }
+
     pub fn is_disabled(&self) -> bool {
         match self {
             McpServerEntry::CommandBased { disabled, .. } => *disabled,
@@ -66,6 +209,13 @@ impl McpServerEntry {
         }
     }
 
+    pub fn set_approval(&mut self, approval: McpServerApproval) {
+        match self {
+            McpServerEntry::CommandBased { approval: a, .. } => *a = approval,
+            McpServerEntry::UrlBased { approval: a, .. } => *a = approval,
+        }
+    }
+
     pub fn entry_type(&self) -> &'static str {
         match self {
             McpServerEntry::CommandBased { .. } => "stdio",
@@ -236,7 +386,7 @@ pub fn add_server(
     name: &str,
     entry: McpServerEntry,
 ) -> Result<(), String> {
-    if name == "stakpak" || name == "paks" || name == "aap" {
+    if RESERVED_SERVER_NAMES.contains(&name) {
         return Err(format!("Cannot add server with reserved name '{name}'."));
     }
 
@@ -251,7 +401,7 @@ pub fn add_server(
 
 /// Remove a server entry. Fails if name not found.
 pub fn remove_server(config: &mut McpConfigFile, name: &str) -> Result<McpServerEntry, String> {
-    if name == "stakpak" || name == "paks" || name == "aap" {
+    if RESERVED_SERVER_NAMES.contains(&name) {
         return Err(format!("Cannot remove internal server '{name}'."));
     }
 
@@ -267,7 +417,7 @@ pub fn set_server_disabled(
     name: &str,
     disabled: bool,
 ) -> Result<(), String> {
-    if name == "stakpak" || name == "paks" || name == "aap" {
+    if RESERVED_SERVER_NAMES.contains(&name) {
         return Err(format!("Cannot modify internal server '{name}'."));
     }
 
@@ -300,6 +450,7 @@ mod tests {
                 url: "https://api.githubcopilot.com/mcp".to_string(),
                 headers: None,
                 disabled: false,
+                approval: McpServerApproval::default(),
             },
         );
 
@@ -337,6 +488,7 @@ disabled = true
             args: vec!["-y".into(), "server".into()],
             env: None,
             disabled: false,
+            approval: McpServerApproval::default(),
         };
         add_server(&mut config, "test", entry).unwrap();
         assert!(config.servers.contains_key("test"));
@@ -346,6 +498,7 @@ disabled = true
             url: "https://example.com".into(),
             headers: None,
             disabled: false,
+            approval: McpServerApproval::default(),
         };
         assert!(add_server(&mut config, "test", entry2).is_err());
 
@@ -361,6 +514,7 @@ disabled = true
             args: vec![],
             env: None,
             disabled: false,
+            approval: McpServerApproval::default(),
         };
         add_server(&mut config, "test", entry).unwrap();
         assert!(!config.servers["test"].is_disabled());
@@ -395,6 +549,7 @@ disabled = true
             args: vec!["-y".into(), "@upstash/context7-mcp".into()],
             env: None,
             disabled: false,
+            approval: McpServerApproval::default(),
         };
         assert_eq!(cmd.entry_type(), "stdio");
         assert_eq!(cmd.summary(), "npx -y @upstash/context7-mcp");
@@ -403,6 +558,7 @@ disabled = true
             url: "https://example.com/mcp".into(),
             headers: None,
             disabled: false,
+            approval: McpServerApproval::default(),
         };
         assert_eq!(url.entry_type(), "http");
         assert_eq!(url.summary(), "https://example.com/mcp");
@@ -415,6 +571,7 @@ disabled = true
             args: vec!["with".into(), "many".into(), "arguments".into()],
             env: None,
             disabled: false,
+            approval: McpServerApproval::default(),
         };
 
         assert_eq!(entry.summary_truncated(100), entry.summary());
@@ -507,4 +664,54 @@ disabled = true
 
         let _ = fs::remove_file(path);
     }
+
+    #[test]
+    fn test_server_approval_policies() {
+        let toml_str = r#"
+[mcpServers.postgres]
+command = "pg-mcp"
+args = []
+
+[mcpServers.postgres.approval]
+default = "ask"
+tools = { query = "approve", drop_table = "deny" }
+
+[mcpServers.grafana]
+url = "https://grafana.example.com/mcp"
+
+[mcpServers.old]
+command = "old-mcp"
+args = []
+disabled = true
+approval = { default = "approve" }
+"#;
+        let config: McpConfigFile = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            config.servers["postgres"].approval().default,
+            Some(McpApprovalAction::Ask)
+        );
+        assert!(config.servers["grafana"].approval().is_empty());
+
+        let rules = McpApprovalRules::from_config(&config);
+        assert_eq!(
+            rules.action_for("postgres__query"),
+            Some(McpApprovalAction::Approve)
+        );
+        assert_eq!(
+            rules.action_for("postgres__vacuum()"),
+            Some(McpApprovalAction::Ask)
+        );
+        assert!(rules.is_denied("postgres__drop_table"));
+        assert_eq!(rules.action_for("grafana__search"), None);
+        assert_eq!(rules.action_for("old__anything"), None);
+        assert_eq!(rules.action_for("query"), None);
+
+        let scoped = rules.scope_rules("approve", "ask", "deny");
+        assert_eq!(scoped.len(), 3);
+        assert_eq!(scoped["postgres__*"], "ask");
+        assert_eq!(scoped["postgres__drop_table"], "deny");
+
+        let json = serde_json::to_value(&config.servers["grafana"]).unwrap();
+        assert!(json.get("approval").is_none());
+    }
 }
@@ -5,7 +5,9 @@ use rmcp::model::{
 };
 use rmcp::service::{NotificationContext, Peer, RunningService};
 use rmcp::{RoleClient, RoleServer};
-use stakpak_mcp_config::{McpConfigFile, McpServerEntry, load_config, load_config_from_str};
+use stakpak_mcp_config::{
+    McpApprovalRules, McpConfigFile, McpServerEntry, load_config, load_config_from_str,
+};
 use stakpak_shared::cert_utils::CertificateChain;
 use std::collections::HashMap;
 use std::ops::Deref;
@@ -155,10 +157,14 @@ pub enum ServerConfig {
 #[derive(Debug, Clone, Default)]
 pub struct ClientPoolConfig {
     pub servers: HashMap<String, ServerConfig>,
+    /// Per-server approval policies; the proxy hides and refuses denied
+    /// tools.
+    pub approvals: McpApprovalRules,
 }
 
 impl From<McpConfigFile> for ClientPoolConfig {
     fn from(config: McpConfigFile) -> Self {
+        let approvals = McpApprovalRules::from_config(&config);
         let mut servers = HashMap::new();
 
         for (name, entry) in config.servers {
@@ -196,7 +202,7 @@ impl From<McpConfigFile> for ClientPoolConfig {
             servers.insert(name, server_config);
         }
 
-        Self { servers }
+        Self { servers, approvals }
     }
 }
 
@@ -206,7 +212,15 @@ impl ClientPoolConfig {
     }
 
     pub fn with_servers(servers: HashMap<String, ServerConfig>) -> Self {
-        Self { servers }
+        Self {
+            servers,
+            approvals: McpApprovalRules::default(),
+        }
+    }
+
+    pub fn with_approvals(mut self, approvals: McpApprovalRules) -> Self {
+        self.approvals = approvals;
+        self
     }
 
     pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
@@ -19,9 +19,11 @@ use rmcp::ServiceExt;
 use rmcp::transport::StreamableHttpClientTransport;
 use rmcp::transport::TokioChildProcess;
 use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
+use stakpak_mcp_config::McpApprovalRules;
 use stakpak_shared::cert_utils::CertificateChain;
 use stakpak_shared::paths::stakpak_home_dir;
 use stakpak_shared::secret_manager::SecretManager;
+use stakpak_shared::secrets::redaction::{self, Redactor};
 use stakpak_shared::utils::{LargeOutputLimits, handle_large_output_with_limits};
 use std::collections::HashMap;
 use std::future::Future;
@@ -127,6 +129,31 @@ fn restore_secrets_in_json_value(
     }
 }
 
+/// Recursively swap output-redaction placeholders such as
+/// `[REDACTED:jwt:1]` back for their secrets, so a tool call that quotes
+/// text the user saw redacted still runs with the real value.
+fn restore_placeholders_in_json_value(value: &mut serde_json::Value, redactor: &Redactor) {
+    match value {
+        serde_json::Value::String(s) => {
+            let restored = redactor.restore(s);
+            if restored != *s {
+                *s = restored;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (_k, v) in map.iter_mut() {
+                restore_placeholders_in_json_value(v, redactor);
+            }
+        }
+        serde_json::Value::Array(arr) => {
+            for v in arr.iter_mut() {
+                restore_placeholders_in_json_value(v, redactor);
+            }
+        }
+        _ => {}
+    }
+}
+
 const PROXY_LARGE_OUTPUT_MAX_LINES: usize = 300;
 const PROXY_LARGE_OUTPUT_MAX_BYTES: usize = 64 * 1024;
 
@@ -188,6 +215,8 @@ pub struct ProxyServer {
     clients_initialized: Arc<Mutex<bool>>,
     // Secret manager for redacting secrets in tool responses
     secret_manager: SecretManager,
+    // Per-server approval policies; denied tools are hidden and refused
+    approvals: Arc<McpApprovalRules>,
 }
 
 impl ProxyServer {
@@ -195,13 +224,15 @@ impl ProxyServer {
         Self {
             pool: Arc::new(ClientPool::new()),
             request_tracking: Arc::new(Mutex::new(HashMap::new())),
+            approvals: Arc::new(config.approvals.clone()),
             client_config: Arc::new(Mutex::new(Some(config))),
             clients_initialized: Arc::new(Mutex::new(false)),
             secret_manager: SecretManager::new(redact_secrets, privacy_mode),
         }
     }
 
-    /// Set the configuration for upstream clients
+    /// Set the configuration for upstream clients. Approval policies stay
+    /// the ones passed to [`ProxyServer::new`].
     pub async fn set_client_config(&self, config: ClientPoolConfig) {
         let mut stored_config = self.client_config.lock().await;
         *stored_config = Some(config);
@@ -333,6 +364,15 @@ impl ProxyServer {
         let mut tool_params = params.clone();
         tool_params.name = tool_name.to_string().into();
 
+        // Placeholders from the process-wide output redactor first; their
+        // values never reach the session map.
+        if let Some(arguments) = &mut tool_params.arguments {
+            let redactor = redaction::global();
+            for (_key, value) in arguments.iter_mut() {
+                restore_placeholders_in_json_value(value, redactor);
+            }
+        }
+
         // Load the redaction map once, then walk the entire JSON value tree.
         let redaction_map = self.secret_manager.load_session_redaction_map();
         if !redaction_map.is_empty()
@@ -626,6 +666,7 @@ impl ServerHandler for ProxyServer {
         let tools = self
             .aggregate_from_clients("list tools", |name, peer| {
                 let params = params.clone();
+                let approvals = self.approvals.clone();
                 async move {
                     peer.list_tools(params)
                         .await
@@ -638,6 +679,7 @@ impl ServerHandler for ProxyServer {
                                     tool.name = format!("{}__{}", name, tool.name).into();
                                     tool
                                 })
+                                .filter(|tool| !approvals.is_denied(&tool.name))
                                 .collect()
                         })
                         .map_err(|e| (name, e))
@@ -659,6 +701,15 @@ impl ServerHandler for ProxyServer {
     ) -> Result<CallToolResult, ErrorData> {
         // Parse the client name from the tool name (format: client_name__tool_name)
         let (client_name, tool_name) = Self::parse_tool_name(&params.name)?;
+        if self.approvals.is_denied(&params.name) {
+            return Err(ErrorData::invalid_request(
+                format!(
+                    "Tool {} on server {} is denied by its approval policy",
+                    tool_name, client_name
+                ),
+                None,
+            ));
+        }
 
         // Get a cloned peer for the client (releases lock immediately)
         let client_peer = self
@@ -1074,6 +1125,29 @@ mod tests {
         assert_eq!(artifact, output);
     }
 
+    #[test]
+    fn test_restore_output_placeholders_in_nested_arguments() {
+        let redactor = Redactor::new(&Default::default());
+        let redacted = redactor.redact("DATABASE_URL=postgres://app:s3cr3t@db:5432/app");
+        let placeholder = "[REDACTED:connection_string:1]";
+        assert!(redacted.contains(placeholder));
+
+        let mut value = json!({
+            "command": format!("psql {redacted}"),
+            "env": [placeholder, "[REDACTED:jwt:9]"],
+            "timeout": 30,
+        });
+        restore_placeholders_in_json_value(&mut value, &redactor);
+        assert_eq!(
+            value,
+            json!({
+                "command": "psql DATABASE_URL=postgres://app:s3cr3t@db:5432/app",
+                "env": ["s3cr3t", "[REDACTED:jwt:9]"],
+                "timeout": 30,
+            })
+        );
+    }
+
     // ---------------------------------------------------------------
     // restore_secrets_in_json_value — basic string restoration
     // ---------------------------------------------------------------
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use rmcp::{
     ServiceExt,
+    handler::server::router::tool::{ToolRoute, ToolRouter},
     transport::{
         stdio,
         streamable_http_server::{StreamableHttpService, session::local::LocalSessionManager},
@@ -20,6 +21,7 @@ use stakpak_shared::task_manager::{TaskManager, TaskManagerHandle};
 pub mod integrations;
 pub mod local_tools;
 pub mod remote_tools;
+pub mod sse;
 pub mod subagent_tools;
 pub mod tool_container;
 
@@ -126,6 +128,8 @@ pub struct SubagentConfig {
     pub profile_name: Option<String>,
     pub config_path: Option<String>,
     pub model: Option<String>,
+    /// Local model endpoint of an offline parent, passed on as `--local-endpoint`
+    pub local_endpoint: Option<String>,
 }
 
 pub struct MCPServerConfig {
@@ -144,6 +148,48 @@ pub struct MCPServerConfig {
     /// instead of creating its own. This allows external code (e.g., the TUI) to
     /// query task status directly.
     pub task_manager_handle: Option<Arc<TaskManagerHandle>>,
+    /// Tools the host serves on top of the tool mode's, such as the CLI's
+    /// discovery and plan tools for `stakpak mcp serve`.
+    pub extra_tools: Vec<ToolRoute<ToolContainer>>,
+    /// Which HTTP transport `start_server` mounts.
+    pub http_transport: HttpTransport,
+    /// Bearer token every HTTP request must present. `None` leaves the
+    /// endpoint open, which is only safe behind mTLS or on loopback.
+    pub auth_token: Option<String>,
+}
+
+/// HTTP transport served by `start_server`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HttpTransport {
+    /// Streamable HTTP on `/mcp`.
+    #[default]
+    StreamableHttp,
+    /// Legacy HTTP+SSE: `GET /sse` plus `POST /message?sessionId=…`.
+    Sse,
+}
+
+async fn require_bearer(
+    axum::extract::State(token): axum::extract::State<Arc<str>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let presented = request
+        .headers()
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    if presented == Some(&*token) {
+        next.run(request).await
+    } else {
+        (
+            axum::http::StatusCode::UNAUTHORIZED,
+            "Missing or invalid bearer token",
+        )
+            .into_response()
+    }
 }
 
 /// Create graceful shutdown handler
@@ -226,6 +272,12 @@ fn build_tool_container(
     task_manager_handle: Arc<TaskManagerHandle>,
 ) -> Result<ToolContainer> {
     let skill_directories = config.skill_directories.clone();
+    let with_extra_tools = |mut tool_router: ToolRouter<ToolContainer>| {
+        for route in &config.extra_tools {
+            tool_router.add_route(route.clone());
+        }
+        tool_router
+    };
     let tool_container = match config.tool_mode {
         ToolMode::LocalOnly => {
             let mut tool_router = ToolContainer::tool_router_local();
@@ -238,7 +290,7 @@ fn build_tool_container(
                 None,
                 config.enabled_tools.clone(),
                 task_manager_handle.clone(),
-                tool_router,
+                with_extra_tools(tool_router),
                 skill_directories,
                 config.subagent_config.clone(),
             )
@@ -257,7 +309,7 @@ fn build_tool_container(
                 config.client.clone(),
                 config.enabled_tools.clone(),
                 task_manager_handle.clone(),
-                tool_router,
+                with_extra_tools(tool_router),
                 skill_directories,
                 config.subagent_config.clone(),
             )
@@ -278,7 +330,7 @@ fn build_tool_container(
                 config.client.clone(),
                 config.enabled_tools.clone(),
                 task_manager_handle.clone(),
-                tool_router,
+                with_extra_tools(tool_router),
                 skill_directories,
                 config.subagent_config.clone(),
             )
@@ -317,13 +369,24 @@ async fn start_server_internal(
 
     let tool_container = build_tool_container(&config, task_manager_handle.clone())?;
 
-    let service = StreamableHttpService::new(
-        move || Ok(tool_container.to_owned()),
-        LocalSessionManager::default().into(),
-        Default::default(),
-    );
+    let mut router = match config.http_transport {
+        HttpTransport::StreamableHttp => {
+            let service = StreamableHttpService::new(
+                move || Ok(tool_container.to_owned()),
+                LocalSessionManager::default().into(),
+                Default::default(),
+            );
+            axum::Router::new().nest_service("/mcp", service)
+        }
+        HttpTransport::Sse => sse::router(tool_container),
+    };
 
-    let router = axum::Router::new().nest_service("/mcp", service);
+    if let Some(token) = config.auth_token.as_deref() {
+        router = router.layer(axum::middleware::from_fn_with_state(
+            Arc::<str>::from(token),
+            require_bearer,
+        ));
+    }
 
     let tls_config = if let Some(pre_built) = config.server_tls_config {
         Some(pre_built)
@@ -412,3 +475,39 @@ pub async fn start_server_stdio(
 
     wait_result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, http::StatusCode};
+    use tower::ServiceExt as _;
+
+    fn guarded_router() -> axum::Router {
+        axum::Router::new()
+            .route("/mcp", axum::routing::get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::<str>::from("secret"),
+                require_bearer,
+            ))
+    }
+
+    async fn status(authorization: Option<&str>) -> StatusCode {
+        let mut request = Request::get("/mcp");
+        if let Some(value) = authorization {
+            request = request.header("authorization", value);
+        }
+        guarded_router()
+            .oneshot(request.body(Body::empty()).expect("request"))
+            .await
+            .expect("response")
+            .status()
+    }
+
+    #[tokio::test]
+    async fn bearer_token_is_required() {
+        assert_eq!(status(None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(Some("Bearer wrong")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(Some("secret")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(Some("Bearer secret")).await, StatusCode::OK);
+    }
+}
@@ -386,7 +386,7 @@ Use the get_all_tasks tool to monitor task progress, or the cancel_task tool to
     )]
     pub async fn run_command_task(
         &self,
-        _ctx: RequestContext<RoleServer>,
+        ctx: RequestContext<RoleServer>,
         Parameters(RunCommandRequest {
             command,
             description,
@@ -394,6 +394,8 @@ Use the get_all_tasks tool to monitor task progress, or the cancel_task tool to
         }): Parameters<RunCommandRequest>,
     ) -> Result<CallToolResult, McpError> {
         let timeout_duration = timeout.map(std::time::Duration::from_secs);
+        let mut child_env = self.task_child_env_defaults(None);
+        child_env.extend(self.get_command_env(&ctx));
 
         let result = self
             .get_task_manager()
@@ -403,7 +405,7 @@ Use the get_all_tasks tool to monitor task progress, or the cancel_task tool to
                     description,
                     timeout: timeout_duration,
                     remote_connection: None,
-                    child_env: self.task_child_env_defaults(None),
+                    child_env,
                 },
             )
             .await;
@@ -1339,6 +1341,10 @@ SAFETY NOTES:
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
         self.apply_local_command_env(&mut cmd);
+        cmd.envs(self.get_command_env(ctx));
+        if let Some(cwd) = self.get_command_cwd(ctx) {
+            cmd.current_dir(cwd);
+        }
         #[cfg(unix)]
         {
             cmd.env("DEBIAN_FRONTEND", "noninteractive")
@@ -3382,6 +3388,7 @@ mod tests {
                 profile_name: profile_name.map(str::to_string),
                 config_path: None,
                 model: None,
+                local_endpoint: None,
             },
         )
         .expect("tool container should be constructed")
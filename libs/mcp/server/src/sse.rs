@@ -0,0 +1,233 @@
+//! Legacy HTTP+SSE transport (MCP protocol revision 2024-11-05).
+//!
+//! A client opens `GET /sse`, receives an `endpoint` event naming the URL to
+//! POST its JSON-RPC messages to, and then reads every server message as a
+//! `message` event on the same stream. rmcp only ships the streamable HTTP
+//! server, so the session plumbing lives here.
+
+use crate::tool_container::ToolContainer;
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::{get, post},
+};
+use futures::{SinkExt, StreamExt, channel::mpsc, stream};
+use rmcp::{ServiceExt, model::ClientJsonRpcMessage};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+pub const SSE_PATH: &str = "/sse";
+pub const MESSAGE_PATH: &str = "/message";
+
+const CHANNEL_CAPACITY: usize = 64;
+
+type Sessions = Arc<Mutex<HashMap<String, mpsc::Sender<ClientJsonRpcMessage>>>>;
+
+#[derive(Clone)]
+struct SseState {
+    tool_container: ToolContainer,
+    sessions: Sessions,
+}
+
+#[derive(Deserialize)]
+struct MessageQuery {
+    #[serde(rename = "sessionId")]
+    session_id: String,
+}
+
+/// Removes the session once the client's event stream is dropped.
+struct SessionGuard {
+    session_id: String,
+    sessions: Sessions,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.remove(&self.session_id);
+        }
+    }
+}
+
+pub(crate) fn router(tool_container: ToolContainer) -> Router {
+    let state = SseState {
+        tool_container,
+        sessions: Arc::default(),
+    };
+
+    Router::new()
+        .route(SSE_PATH, get(open_session))
+        .route(MESSAGE_PATH, post(post_message))
+        .with_state(state)
+}
+
+async fn open_session(State(state): State<SseState>) -> Response {
+    let session_id = uuid::Uuid::new_v4().simple().to_string();
+    let (client_tx, client_rx) = mpsc::channel::<ClientJsonRpcMessage>(CHANNEL_CAPACITY);
+    let (server_tx, server_rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+    match state.sessions.lock() {
+        Ok(mut sessions) => {
+            sessions.insert(session_id.clone(), client_tx);
+        }
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+
+    // `serve` waits for the initialize handshake, which only arrives once the
+    // client has read the endpoint event below, so it has to run detached.
+    let tool_container = state.tool_container.clone();
+    let sessions = state.sessions.clone();
+    let serving_session = session_id.clone();
+    tokio::spawn(async move {
+        match tool_container.serve((server_tx, client_rx)).await {
+            Ok(running) => {
+                let _ = running.waiting().await;
+            }
+            Err(e) => tracing::warn!("SSE session {} failed to start: {}", serving_session, e),
+        }
+        if let Ok(mut sessions) = sessions.lock() {
+            sessions.remove(&serving_session);
+        }
+    });
+
+    let guard = SessionGuard {
+        session_id: session_id.clone(),
+        sessions: state.sessions.clone(),
+    };
+    let endpoint = Event::default()
+        .event("endpoint")
+        .data(format!("{MESSAGE_PATH}?sessionId={session_id}"));
+    let messages = server_rx.map(move |message| {
+        let _guard = &guard;
+        let data = serde_json::to_string(&message).unwrap_or_default();
+        Ok::<_, Infallible>(Event::default().event("message").data(data))
+    });
+
+    Sse::new(stream::once(async move { Ok(endpoint) }).chain(messages))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+async fn post_message(
+    State(state): State<SseState>,
+    Query(query): Query<MessageQuery>,
+    Json(message): Json<ClientJsonRpcMessage>,
+) -> StatusCode {
+    let sender = match state.sessions.lock() {
+        Ok(sessions) => sessions.get(&query.session_id).cloned(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    match sender {
+        Some(mut sender) => match sender.send(message).await {
+            Ok(()) => StatusCode::ACCEPTED,
+            Err(_) => StatusCode::GONE,
+        },
+        None => StatusCode::NOT_FOUND,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EnabledToolsConfig, SubagentConfig};
+    use axum::{body::Body, http::Request};
+    use rmcp::handler::server::router::tool::ToolRouter;
+    use stakpak_shared::task_manager::TaskManager;
+    use tower::ServiceExt as _;
+
+    fn test_router() -> Router {
+        let task_manager = TaskManager::new();
+        let tool_container = ToolContainer::new(
+            None,
+            EnabledToolsConfig { slack: false },
+            task_manager.handle(),
+            ToolRouter::new(),
+            Vec::new(),
+            SubagentConfig::default(),
+        )
+        .expect("tool container");
+        router(tool_container)
+    }
+
+    #[tokio::test]
+    async fn sse_stream_opens_with_endpoint_event() {
+        let response = test_router()
+            .oneshot(Request::get(SSE_PATH).body(Body::empty()).expect("request"))
+            .await
+            .expect("response");
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut body = response.into_body().into_data_stream();
+        let first = body.next().await.expect("first chunk").expect("bytes");
+        let first = String::from_utf8_lossy(&first);
+        assert!(first.contains("event: endpoint"), "{first}");
+        assert!(first.contains("data: /message?sessionId="), "{first}");
+    }
+
+    #[tokio::test]
+    async fn initialize_is_answered_on_the_event_stream() {
+        let router = test_router();
+        let response = router
+            .clone()
+            .oneshot(Request::get(SSE_PATH).body(Body::empty()).expect("request"))
+            .await
+            .expect("response");
+        let mut body = response.into_body().into_data_stream();
+        let first = body.next().await.expect("first chunk").expect("bytes");
+        let endpoint = String::from_utf8_lossy(&first)
+            .lines()
+            .find_map(|line| line.strip_prefix("data: ").map(str::to_string))
+            .expect("endpoint data");
+
+        let initialize = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": { "name": "test", "version": "0" }
+            }
+        });
+        let response = router
+            .oneshot(
+                Request::post(endpoint)
+                    .header("content-type", "application/json")
+                    .body(Body::from(initialize.to_string()))
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let reply = body.next().await.expect("reply chunk").expect("bytes");
+        let reply = String::from_utf8_lossy(&reply);
+        assert!(reply.contains("event: message"), "{reply}");
+        assert!(reply.contains("\"id\":1"), "{reply}");
+        assert!(reply.contains("serverInfo"), "{reply}");
+    }
+
+    #[tokio::test]
+    async fn posting_to_unknown_session_is_not_found() {
+        let body = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+        let response = test_router()
+            .oneshot(
+                Request::post(format!("{MESSAGE_PATH}?sessionId=missing"))
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
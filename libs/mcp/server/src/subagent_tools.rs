@@ -387,6 +387,10 @@ NOTES:
         // Build the stakpak CLI command for resuming
         let mut command = format!("{} -a --output json -c {}", current_exe, checkpoint_id);
 
+        if let Some(endpoint) = self.subagent_config.local_endpoint.as_deref() {
+            command.push_str(&format!(" --local-endpoint {}", shell_quote_arg(endpoint)));
+        }
+
         if approve_all.unwrap_or(false) {
             command.push_str(" --approve-all");
         }
@@ -509,6 +513,9 @@ NOTES:
         if let Some(path) = config_path.filter(|p| !p.is_empty()) {
             args.extend(["--config".to_string(), path.to_string()]);
         }
+        if let Some(endpoint) = self.subagent_config.local_endpoint.as_deref() {
+            args.extend(["--local-endpoint".to_string(), endpoint.to_string()]);
+        }
 
         // --pause-on-approval only when NOT in sandbox mode
         if !enable_sandbox {
@@ -771,6 +778,39 @@ mod tests {
         assert!(command.contains("-t 'stakpak__view; touch /tmp/tool_pwn'"));
     }
 
+    #[test]
+    fn dynamic_subagent_command_inherits_local_endpoint() {
+        let task_manager = TaskManager::new();
+        let container = ToolContainer::new(
+            None,
+            EnabledToolsConfig::default(),
+            task_manager.handle(),
+            ToolContainer::tool_router_subagent(),
+            Vec::new(),
+            SubagentConfig {
+                local_endpoint: Some("http://localhost:11434/v1".to_string()),
+                ..SubagentConfig::default()
+            },
+        )
+        .expect("tool container should be constructed");
+
+        let command = container
+            .build_dynamic_subagent_command(
+                "do the thing",
+                None,
+                &["stakpak__view".to_string()],
+                Some("local/llama3"),
+                1,
+                false,
+                None,
+                None,
+                None,
+            )
+            .expect("subagent command should be built");
+
+        assert!(command.contains("--local-endpoint http://localhost:11434/v1"));
+    }
+
     #[test]
     fn caller_override_wins_verbatim() {
         let resolved = resolve_subagent_model(
@@ -7,6 +7,7 @@ use rmcp::{
 use stakpak_api::AgentProvider;
 use stakpak_shared::remote_connection::RemoteConnectionManager;
 use stakpak_shared::task_manager::TaskManagerHandle;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -85,6 +86,29 @@ impl ToolContainer {
             .get("session_id")
             .and_then(|s| s.as_str().map(|s| s.to_string()))
     }
+
+    /// Working directory the caller asked local commands to run in. Ignored
+    /// unless it names an existing directory.
+    pub fn get_command_cwd(&self, ctx: &RequestContext<RoleServer>) -> Option<PathBuf> {
+        ctx.meta
+            .get("cwd")
+            .and_then(|cwd| cwd.as_str())
+            .map(PathBuf::from)
+            .filter(|cwd| cwd.is_dir())
+    }
+
+    /// Extra environment the caller asked local commands to run with.
+    pub fn get_command_env(&self, ctx: &RequestContext<RoleServer>) -> HashMap<String, String> {
+        ctx.meta
+            .get("env")
+            .and_then(|env| env.as_object())
+            .map(|env| {
+                env.iter()
+                    .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 }
 
 #[tool_handler]
@@ -1,14 +1,42 @@
-use crate::context::project::{ContextFile, ContextPriority};
+use crate::context::{
+    project::{ContextFile, ContextPriority},
+    tokenizer::{TokenEstimator, Tokenizer},
+};
+use std::sync::Arc;
 
 const DEFAULT_HEAD_RATIO: f64 = 0.7;
 const DEFAULT_TAIL_RATIO: f64 = 0.2;
-const MIN_FILE_ALLOCATION_CHARS: usize = 64;
+const MIN_FILE_ALLOCATION_TOKENS: usize = 16;
+/// Largest share of a model's context window the user context block may
+/// take, whatever `total_context_max_tokens` says.
+const MAX_CONTEXT_WINDOW_SHARE: f64 = 0.25;
 
+/// Token limits for session context, counted with `tokenizer` (by default
+/// a [`TokenEstimator`], so limits are approximate).
+///
+/// Sections are filled in a fixed order: the environment block, the machine
+/// profile, workspace memories, then project files from Critical to CallerSupplied priority,
+/// most relevant to the prompt first and in discovery order among equally
+/// relevant files. Each is capped by its own limit and by what is left of
+/// `total_context_max_tokens`, so when the total runs out, trimming always
+/// starts from the last caller-supplied file and works back towards the
+/// environment block.
 #[derive(Debug, Clone)]
 pub struct ContextBudget {
-    pub system_prompt_max_chars: usize,
-    pub per_file_max_chars: usize,
-    pub total_context_max_chars: usize,
+    pub tokenizer: Arc<dyn Tokenizer>,
+    pub system_prompt_max_tokens: usize,
+    pub per_file_max_tokens: usize,
+    /// Cap for all project and caller-supplied files together.
+    pub project_files_max_tokens: usize,
+    /// Cap for the `<local_context>` block; large directory trees are cut.
+    pub environment_max_tokens: usize,
+    pub machine_profile_max_tokens: usize,
+    /// Cap for the `<memories>` block; least relevant memories are cut.
+    pub memories_max_tokens: usize,
+    pub total_context_max_tokens: usize,
+    /// Share of the model's context window that conversation history may
+    /// fill before older messages are trimmed.
+    pub history_max_ratio: f32,
     pub head_ratio: f64,
     pub tail_ratio: f64,
 }
@@ -16,52 +44,97 @@ pub struct ContextBudget {
 impl Default for ContextBudget {
     fn default() -> Self {
         Self {
-            system_prompt_max_chars: 32_000,
-            per_file_max_chars: 20_000,
-            total_context_max_chars: 100_000,
+            tokenizer: Arc::new(TokenEstimator::default()),
+            system_prompt_max_tokens: 8_000,
+            per_file_max_tokens: 5_000,
+            project_files_max_tokens: 20_000,
+            environment_max_tokens: 2_000,
+            machine_profile_max_tokens: 3_000,
+            memories_max_tokens: 1_500,
+            total_context_max_tokens: 25_000,
+            history_max_ratio: 0.8,
             head_ratio: DEFAULT_HEAD_RATIO,
             tail_ratio: DEFAULT_TAIL_RATIO,
         }
     }
 }
 
-pub fn truncate_with_marker(content: &str, max_chars: usize, name: &str) -> (String, bool) {
+impl ContextBudget {
+    /// This budget estimated for `model`'s tokenizer family, with the total capped
+    /// to a quarter of its context window.
+    pub fn for_model(&self, model: &stakai::Model) -> Self {
+        let window_share = (model.limit.context as f64 * MAX_CONTEXT_WINDOW_SHARE) as usize;
+        Self {
+            tokenizer: Arc::new(TokenEstimator::for_model(model)),
+            total_context_max_tokens: match window_share {
+                0 => self.total_context_max_tokens,
+                share => self.total_context_max_tokens.min(share),
+            },
+            ..self.clone()
+        }
+    }
+
+    pub fn count(&self, text: &str) -> usize {
+        self.tokenizer.count(text)
+    }
+
+    /// [`truncate_with_marker`] with this budget's tokenizer and ratios.
+    pub fn truncate(&self, content: &str, max_tokens: usize, name: &str) -> (String, bool) {
+        truncate_with_marker_and_ratio(
+            content,
+            max_tokens,
+            name,
+            self.head_ratio,
+            self.tail_ratio,
+            self.tokenizer.as_ref(),
+        )
+    }
+}
+
+pub fn truncate_with_marker(
+    content: &str,
+    max_tokens: usize,
+    name: &str,
+    tokenizer: &dyn Tokenizer,
+) -> (String, bool) {
     truncate_with_marker_and_ratio(
         content,
-        max_chars,
+        max_tokens,
         name,
         DEFAULT_HEAD_RATIO,
         DEFAULT_TAIL_RATIO,
+        tokenizer,
     )
 }
 
+/// Cap each file at `per_file_max_tokens`, then keep files in priority
+/// order while they fit in `project_files_max_tokens` (or the total, if
+/// smaller). The first file that does not fit is cut to what is left;
+/// lower-priority files after it are dropped.
 pub fn apply_budget(files: &mut Vec<ContextFile>, budget: &ContextBudget) {
     for file in files.iter_mut() {
-        let (content, truncated) = truncate_with_marker_and_ratio(
-            &file.content,
-            budget.per_file_max_chars,
-            &file.name,
-            budget.head_ratio,
-            budget.tail_ratio,
-        );
+        let (content, truncated) =
+            budget.truncate(&file.content, budget.per_file_max_tokens, &file.name);
         file.content = content;
         file.truncated |= truncated;
     }
 
     let mut prioritized = prioritized_files(files);
-    let mut remaining = budget.total_context_max_chars;
+    let mut remaining = budget
+        .project_files_max_tokens
+        .min(budget.total_context_max_tokens);
     let mut kept = Vec::new();
 
     for mut file in prioritized.drain(..) {
-        let file_chars = file.content.chars().count();
-        if file_chars <= remaining {
-            remaining -= file_chars;
+        let file_tokens = budget.count(&file.content);
+        if file_tokens <= remaining {
+            remaining -= file_tokens;
             kept.push(file);
             continue;
         }
 
         let should_keep_as_truncated =
-            file.priority == ContextPriority::Critical || remaining >= MIN_FILE_ALLOCATION_CHARS;
+            file.priority == ContextPriority::Critical || remaining >= MIN_FILE_ALLOCATION_TOKENS;
 
         if !should_keep_as_truncated {
             continue;
@@ -71,13 +144,7 @@ pub fn apply_budget(files: &mut Vec<ContextFile>, budget: &ContextBudget) {
             continue;
         }
 
-        let (content, truncated) = truncate_with_marker_and_ratio(
-            &file.content,
-            remaining,
-            &file.name,
-            budget.head_ratio,
-            budget.tail_ratio,
-        );
+        let (content, truncated) = budget.truncate(&file.content, remaining, &file.name);
         file.content = content;
         file.truncated |= truncated;
         remaining = 0;
@@ -96,11 +163,16 @@ fn prioritized_files(files: &[ContextFile]) -> Vec<ContextFile> {
         ContextPriority::Normal,
         ContextPriority::CallerSupplied,
     ] {
+        let start = prioritized.len();
         for file in files {
             if file.priority == priority {
                 prioritized.push(file.clone());
             }
         }
+        // Stable, so equally relevant files keep discovery order.
+        if let Some(group) = prioritized.get_mut(start..) {
+            group.sort_by(|a, b| b.relevance.total_cmp(&a.relevance));
+        }
     }
 
     prioritized
@@ -108,67 +180,112 @@ fn prioritized_files(files: &[ContextFile]) -> Vec<ContextFile> {
 
 fn truncate_with_marker_and_ratio(
     content: &str,
-    max_chars: usize,
+    max_tokens: usize,
     name: &str,
     head_ratio: f64,
     tail_ratio: f64,
+    tokenizer: &dyn Tokenizer,
 ) -> (String, bool) {
-    if max_chars == 0 {
+    if max_tokens == 0 {
         return (String::new(), !content.is_empty());
     }
 
-    let chars: Vec<char> = content.chars().collect();
-    if chars.len() <= max_chars {
+    if tokenizer.count(content) <= max_tokens {
         return (content.to_string(), false);
     }
 
+    let chars: Vec<char> = content.chars().collect();
     let marker = format!("\n[... truncated {name}; read file for full content ...]\n");
-    let marker_len = marker.chars().count();
+    let marker_tokens = tokenizer.count(&marker);
 
-    if marker_len >= max_chars {
-        let truncated: String = chars.into_iter().take(max_chars).collect();
-        return (truncated, true);
+    if marker_tokens >= max_tokens {
+        let head_len = longest_fit(
+            chars.len(),
+            max_tokens,
+            |len| chars.iter().take(len).collect(),
+            tokenizer,
+        );
+        return (chars.iter().take(head_len).collect(), true);
     }
 
-    let available = max_chars - marker_len;
-    let mut head_count = ((available as f64) * head_ratio).floor() as usize;
-    let mut tail_count = ((available as f64) * tail_ratio).floor() as usize;
+    let available = max_tokens - marker_tokens;
+    let head_share = (((available as f64) * head_ratio).floor() as usize).min(available);
+    let tail_tokens =
+        (((available as f64) * tail_ratio).floor() as usize).min(available - head_share);
+    let head_tokens = available - tail_tokens;
 
-    if head_count + tail_count > available {
-        tail_count = tail_count.min(available.saturating_sub(head_count));
-    }
+    let tail_len = longest_fit(
+        chars.len(),
+        tail_tokens,
+        |len| chars.iter().skip(chars.len() - len).collect(),
+        tokenizer,
+    );
+    let head_len = longest_fit(
+        chars.len() - tail_len,
+        head_tokens,
+        |len| chars.iter().take(len).collect(),
+        tokenizer,
+    );
 
-    let used = head_count + tail_count;
-    if used < available {
-        head_count += available - used;
-    }
-
-    let head: String = chars.iter().take(head_count).collect();
-    let tail: String = chars
-        .iter()
-        .rev()
-        .take(tail_count)
-        .copied()
-        .collect::<Vec<char>>()
-        .into_iter()
-        .rev()
-        .collect();
+    let head: String = chars.iter().take(head_len).collect();
+    let tail: String = chars.iter().skip(chars.len() - tail_len).collect();
 
     (format!("{head}{marker}{tail}"), true)
 }
 
+/// Largest `len <= max_len` whose `slice(len)` fits in `max_tokens`.
+fn longest_fit(
+    max_len: usize,
+    max_tokens: usize,
+    slice: impl Fn(usize) -> String,
+    tokenizer: &dyn Tokenizer,
+) -> usize {
+    let (mut low, mut high) = (0, max_len);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if tokenizer.count(&slice(mid)) <= max_tokens {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    low
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn truncates_with_marker() {
-        let content = "x".repeat(1_000);
-        let (truncated, changed) = truncate_with_marker(&content, 120, "AGENTS.md");
+        let tokenizer = TokenEstimator::default();
+        let content = "let x = 1;\n".repeat(1_000);
+        let (truncated, changed) = truncate_with_marker(&content, 120, "AGENTS.md", &tokenizer);
 
         assert!(changed);
         assert!(truncated.contains("truncated AGENTS.md"));
-        assert!(truncated.chars().count() <= 120);
+        assert!(tokenizer.count(&truncated) <= 120);
+        assert!(truncated.starts_with("let x = 1;\n"));
+        assert!(truncated.ends_with("let x = 1;\n"));
+    }
+
+    #[test]
+    fn for_model_selects_estimator_and_caps_total_to_context_window() {
+        let budget = ContextBudget::default();
+        let mut model = stakai::Model::custom("gpt-4", "openai");
+        model.limit.context = 8_192;
+        let code = "let registry = ProbeRegistry::from_config(&configuration)?;\n".repeat(20);
+
+        let scoped = budget.for_model(&model);
+
+        assert_eq!(scoped.total_context_max_tokens, 2_048);
+        assert_ne!(scoped.count(&code), budget.count(&code));
+        assert_eq!(
+            budget
+                .for_model(&stakai::Model::custom("claude-sonnet-4-5", "anthropic"))
+                .total_context_max_tokens,
+            25_000
+        );
     }
 
     #[test]
@@ -191,9 +308,15 @@ mod tests {
         apply_budget(
             &mut files,
             &ContextBudget {
-                system_prompt_max_chars: 1_000,
-                per_file_max_chars: 1_000,
-                total_context_max_chars: 300,
+                tokenizer: Arc::new(TokenEstimator::default()),
+                system_prompt_max_tokens: 1_000,
+                per_file_max_tokens: 1_000,
+                project_files_max_tokens: 1_000,
+                environment_max_tokens: 1_000,
+                machine_profile_max_tokens: 1_000,
+                memories_max_tokens: 1_000,
+                total_context_max_tokens: 150,
+                history_max_ratio: 0.8,
                 head_ratio: 0.7,
                 tail_ratio: 0.2,
             },
@@ -202,4 +325,52 @@ mod tests {
         assert!(!files.is_empty());
         assert_eq!(files[0].name, "AGENTS.md");
     }
+
+    #[test]
+    fn budget_trims_lowest_priority_files_first_in_discovery_order() {
+        let file = |name: &str, priority| {
+            ContextFile::new(name, format!("/tmp/{name}"), "word ".repeat(100), priority)
+        };
+        let mut files = vec![
+            file("caller-a", ContextPriority::CallerSupplied),
+            file("notes-a", ContextPriority::Normal),
+            file("notes-b", ContextPriority::Normal),
+            file("AGENTS.md", ContextPriority::Critical),
+        ];
+
+        apply_budget(
+            &mut files,
+            &ContextBudget {
+                project_files_max_tokens: 250,
+                ..Default::default()
+            },
+        );
+
+        let kept: Vec<(&str, bool)> = files
+            .iter()
+            .map(|file| (file.name.as_str(), file.truncated))
+            .collect();
+        assert_eq!(
+            kept,
+            vec![("AGENTS.md", false), ("notes-a", false), ("notes-b", true)]
+        );
+
+        let mut files = vec![
+            file("notes-a", ContextPriority::Normal),
+            file("notes-b", ContextPriority::Normal),
+        ];
+        files[1].relevance = 0.4;
+        apply_budget(
+            &mut files,
+            &ContextBudget {
+                project_files_max_tokens: 150,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            files[0].name, "notes-b",
+            "more relevant files are kept first"
+        );
+        assert!(!files[0].truncated && files[1].truncated);
+    }
 }
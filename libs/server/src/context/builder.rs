@@ -1,9 +1,11 @@
 use crate::context::{
     ContextBudget,
-    budget::{apply_budget, truncate_with_marker},
+    budget::apply_budget,
     environment::EnvironmentContext,
+    memory::Memory,
     project::{ContextFile, ProjectContext},
 };
+use stakpak_shared::discovery::DiscoveryReport;
 
 #[derive(Debug, Clone, Default)]
 pub struct SessionContext {
@@ -15,6 +17,8 @@ pub struct SessionContext {
 pub struct SessionContextBuilder {
     environment: Option<EnvironmentContext>,
     project: Option<ProjectContext>,
+    machine_profile: Option<DiscoveryReport>,
+    memories: Vec<Memory>,
     base_system_prompt: Option<String>,
     tool_summaries: Vec<String>,
     budget: ContextBudget,
@@ -35,6 +39,20 @@ impl SessionContextBuilder {
         self
     }
 
+    /// Discovery results to include as a `<machine_profile>` block, capped
+    /// by [`ContextBudget::machine_profile_max_tokens`].
+    pub fn machine_profile(mut self, report: DiscoveryReport) -> Self {
+        self.machine_profile = Some(report).filter(|report| !report.is_empty());
+        self
+    }
+
+    /// Workspace memories to include as a `<memories>` block, most relevant
+    /// first, capped by [`ContextBudget::memories_max_tokens`].
+    pub fn memories(mut self, memories: Vec<Memory>) -> Self {
+        self.memories = memories;
+        self
+    }
+
     pub fn base_system_prompt(mut self, prompt: impl Into<String>) -> Self {
         self.base_system_prompt = Some(prompt.into());
         self
@@ -87,22 +105,61 @@ impl SessionContextBuilder {
         }
 
         let combined = sections.join("\n\n");
-        let (truncated, _) = truncate_with_marker(
+        let (truncated, _) = self.budget.truncate(
             &combined,
-            self.budget.system_prompt_max_chars,
+            self.budget.system_prompt_max_tokens,
             "system prompt",
         );
         truncated
     }
 
+    /// Sections in [`ContextBudget`] fill order, each taking its share of
+    /// the total before the next one is sized.
     fn build_user_context_block(&self) -> Option<String> {
         let mut sections = Vec::new();
+        let mut budget = self.budget.clone();
 
         if let Some(environment) = &self.environment {
-            sections.push(format!(
-                "<local_context>\n{}\n</local_context>",
-                environment.to_local_context_block()
-            ));
+            let block = take_section(
+                &mut budget,
+                &environment.to_local_context_block(),
+                self.budget.environment_max_tokens,
+                "local context",
+            );
+            if !block.is_empty() {
+                sections.push(format!("<local_context>\n{}\n</local_context>", block));
+            }
+        }
+
+        if let Some(profile) = &self.machine_profile {
+            let markdown = take_section(
+                &mut budget,
+                profile.to_markdown().trim(),
+                self.budget.machine_profile_max_tokens,
+                "machine profile",
+            );
+            if !markdown.is_empty() {
+                sections.push(format!(
+                    "<machine_profile>\n{}\n</machine_profile>",
+                    markdown
+                ));
+            }
+        }
+
+        if !self.memories.is_empty() {
+            let lines: Vec<String> = self.memories.iter().map(Memory::to_line).collect();
+            let block = take_section(
+                &mut budget,
+                &lines.join("\n"),
+                self.budget.memories_max_tokens,
+                "memories",
+            );
+            if !block.is_empty() {
+                sections.push(format!(
+                    "<memories>\nFacts saved in earlier sessions in this workspace. They may be out of date: verify before relying on them, and forget_memory the wrong ones.\n{}\n</memories>",
+                    block
+                ));
+            }
         }
 
         let mut files = self
@@ -112,7 +169,7 @@ impl SessionContextBuilder {
             .unwrap_or_default();
 
         if !files.is_empty() {
-            apply_budget(&mut files, &self.budget);
+            apply_budget(&mut files, &budget);
             for file in files {
                 sections.push(format_context_file(&file));
             }
@@ -126,6 +183,25 @@ impl SessionContextBuilder {
     }
 }
 
+/// `content` cut to `max_tokens` and what is left of the total, which is
+/// reduced by what the section uses.
+fn take_section(
+    budget: &mut ContextBudget,
+    content: &str,
+    max_tokens: usize,
+    name: &str,
+) -> String {
+    let (section, _) = budget.truncate(
+        content,
+        max_tokens.min(budget.total_context_max_tokens),
+        name,
+    );
+    budget.total_context_max_tokens = budget
+        .total_context_max_tokens
+        .saturating_sub(budget.count(&section));
+    section
+}
+
 fn format_context_file(file: &ContextFile) -> String {
     if file.name.eq_ignore_ascii_case("AGENTS.md") {
         return format!(
@@ -229,6 +305,177 @@ mod tests {
         assert!(context.user_context_block.is_none());
     }
 
+    #[test]
+    fn machine_profile_follows_local_context_and_skips_empty_reports() {
+        let report = DiscoveryReport::new(
+            None,
+            "toolchain,systemd",
+            vec![
+                stakpak_shared::discovery::ProbeResult {
+                    id: "toolchain".to_string(),
+                    name: "Toolchain".to_string(),
+                    output: "- Languages: rust 1.79.0\n".to_string(),
+                    timed_out: false,
+                    duration_ms: 40,
+                },
+                stakpak_shared::discovery::ProbeResult {
+                    id: "systemd".to_string(),
+                    name: "Systemd".to_string(),
+                    output: String::new(),
+                    timed_out: false,
+                    duration_ms: 3,
+                },
+            ],
+        );
+
+        let context = SessionContextBuilder::new()
+            .environment(test_environment())
+            .machine_profile(report.clone())
+            .build();
+        let block = context.user_context_block.unwrap_or_default();
+        assert!(block.contains(
+            "</local_context>\n\n<machine_profile>\n## Toolchain\n\n- Languages: rust 1.79.0\n</machine_profile>"
+        ));
+        assert!(!block.contains("Systemd"));
+
+        let empty = DiscoveryReport::new(None, "systemd", report.results[1..].to_vec());
+        let context = SessionContextBuilder::new().machine_profile(empty).build();
+        assert!(context.user_context_block.is_none());
+    }
+
+    #[test]
+    fn machine_profile_is_capped_and_counts_toward_total_budget() {
+        let report = DiscoveryReport::new(
+            None,
+            "git-repos",
+            vec![stakpak_shared::discovery::ProbeResult {
+                id: "git-repos".to_string(),
+                name: "Git Repositories".to_string(),
+                output: "- ~/src/app (rust, main)\n".repeat(200),
+                timed_out: false,
+                duration_ms: 900,
+            }],
+        );
+        let project = ProjectContext {
+            files: vec![ContextFile::new(
+                "APPS.md",
+                "/workspace/APPS.md",
+                "a".repeat(1_000),
+                ContextPriority::High,
+            )],
+        };
+        let budget = ContextBudget {
+            machine_profile_max_tokens: 120,
+            total_context_max_tokens: 200,
+            ..Default::default()
+        };
+
+        let context = SessionContextBuilder::new()
+            .machine_profile(report)
+            .project(project)
+            .budget(budget.clone())
+            .build();
+        let block = context.user_context_block.unwrap_or_default();
+
+        let profile = block
+            .split("<machine_profile>\n")
+            .nth(1)
+            .and_then(|rest| rest.split("\n</machine_profile>").next())
+            .unwrap_or_default();
+        let profile_tokens = budget.count(profile);
+        assert!((110..=120).contains(&profile_tokens), "{profile_tokens}");
+        assert!(profile.contains("truncated machine profile"));
+        let apps_md = block
+            .split("/workspace/APPS.md)\n\n")
+            .nth(1)
+            .and_then(|rest| rest.split("\n</apps_md>").next())
+            .unwrap_or_default();
+        assert!(
+            budget.count(apps_md) <= 200 - profile_tokens,
+            "project files get what the profile left"
+        );
+    }
+
+    #[test]
+    fn environment_block_is_capped_and_filled_first() {
+        let mut environment = test_environment();
+        environment.directory_tree = "├── src/module.rs\n".repeat(2_000);
+        let project = ProjectContext {
+            files: vec![ContextFile::new(
+                "AGENTS.md",
+                "/tmp/AGENTS.md",
+                "Follow project conventions",
+                ContextPriority::Critical,
+            )],
+        };
+        let budget = ContextBudget {
+            environment_max_tokens: 300,
+            ..Default::default()
+        };
+
+        let context = SessionContextBuilder::new()
+            .environment(environment)
+            .project(project)
+            .budget(budget.clone())
+            .build();
+        let block = context.user_context_block.unwrap_or_default();
+
+        let local_context = block
+            .split("<local_context>\n")
+            .nth(1)
+            .and_then(|rest| rest.split("\n</local_context>").next())
+            .unwrap_or_default();
+        assert!(budget.count(local_context) <= 300);
+        assert!(local_context.starts_with("# System Details"));
+        assert!(local_context.contains("truncated local context"));
+        assert!(block.contains("Follow project conventions"));
+    }
+
+    #[test]
+    fn memories_follow_machine_profile_and_precede_project_files() {
+        let now = chrono::Utc::now();
+        let memory = |id: &str, fact: &str, tags: &[&str]| Memory {
+            id: id.to_string(),
+            fact: fact.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            created_at: now,
+            updated_at: now,
+            session_id: None,
+        };
+        let project = ProjectContext {
+            files: vec![ContextFile::new(
+                "AGENTS.md",
+                "/tmp/AGENTS.md",
+                "Follow project conventions",
+                ContextPriority::Critical,
+            )],
+        };
+
+        let context = SessionContextBuilder::new()
+            .environment(test_environment())
+            .memories(vec![
+                memory(
+                    "a1b2c3d4",
+                    "Production AWS account is 123456789012",
+                    &["aws"],
+                ),
+                memory("e5f6a7b8", "Bastion host is bastion.prod.acme.io", &[]),
+            ])
+            .project(project)
+            .build();
+        let block = context.user_context_block.unwrap_or_default();
+
+        let memories = block.find("<memories>").unwrap_or(usize::MAX);
+        assert!(block.find("</local_context>").unwrap_or_default() < memories);
+        assert!(memories < block.find("<agents_md>").unwrap_or_default());
+        assert!(block.contains(
+            "- [a1b2c3d4] Production AWS account is 123456789012 (tags: aws)\n- [e5f6a7b8] Bastion host is bastion.prod.acme.io\n</memories>"
+        ));
+
+        let context = SessionContextBuilder::new().memories(Vec::new()).build();
+        assert!(context.user_context_block.is_none());
+    }
+
     #[test]
     fn apps_md_formatted_with_apps_md_tag() {
         let project = ProjectContext {
@@ -295,17 +542,17 @@ mod tests {
     #[test]
     fn system_prompt_truncated_by_budget() {
         let budget = ContextBudget {
-            system_prompt_max_chars: 50,
+            system_prompt_max_tokens: 50,
             ..Default::default()
         };
 
         let context = SessionContextBuilder::new()
             .base_system_prompt("A".repeat(1_000))
-            .budget(budget)
+            .budget(budget.clone())
             .build();
 
         assert!(
-            context.system_prompt.chars().count() <= 50,
+            budget.count(&context.system_prompt) <= 50,
             "system prompt should be truncated to budget"
         );
     }
@@ -0,0 +1,659 @@
+//! Summarizing compaction of long conversation history.
+//!
+//! When a session's history outgrows its share of the context window
+//! ([`ContextBudget::history_max_ratio`]), the older turns are summarized
+//! with the session's model and replaced by one `<conversation_summary>`
+//! message. The latest turns stay verbatim, and so do tool results they
+//! still refer to — a file that was viewed and is now being edited, a
+//! background task that is being polled — so the agent does not have to
+//! fetch them again.
+
+use crate::context::{ContextBudget, budget::truncate_with_marker};
+use async_trait::async_trait;
+use serde_json::Value;
+use stakai::{ContentPart, GenerateRequest, Inference, Message, MessageContent, Model, Role};
+use stakpak_agent_core::{AgentError, CompactionEngine, CompactionResult};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+pub const SUMMARY_TAG: &str = "conversation_summary";
+
+/// User turns kept verbatim after compaction.
+const KEEP_RECENT_TURNS: usize = 2;
+
+/// Assistant steps kept verbatim when a single turn has to be compacted.
+const KEEP_RECENT_STEPS: usize = 4;
+
+/// Share of the history limit the verbatim tail may keep.
+const KEPT_HISTORY_SHARE: f64 = 0.5;
+
+/// Share of the model's context window given to the transcript sent to the
+/// summarizer.
+const TRANSCRIPT_WINDOW_SHARE: f64 = 0.5;
+
+/// Tokens of each tool result that go into the transcript.
+const TRANSCRIPT_TOOL_RESULT_MAX_TOKENS: usize = 1_000;
+
+const PRESERVED_RESULTS_MAX_TOKENS: usize = 8_000;
+const PRESERVED_RESULT_MAX_TOKENS: usize = 2_000;
+
+/// Shortest argument or result value that counts as a reference.
+const MIN_REFERENCE_CHARS: usize = 4;
+
+const SUMMARY_MAX_OUTPUT_TOKENS: u64 = 4_096;
+
+/// Characters of each user message listed when the summarizer fails.
+const FALLBACK_REQUEST_CHARS: usize = 300;
+
+/// Per-message overhead of role markers and separators.
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+const IMAGE_TOKENS: usize = 1_000;
+
+const SUMMARY_PROMPT: &str = "You are compacting the history of an agent session so the agent can \
+continue it within its context window. Summarize the conversation you are given for that agent. \
+Keep the user's requests and constraints, decisions made, facts discovered (hosts, paths, IDs, \
+versions, error messages), what was changed and how, and what remains to be done. Be concise \
+and concrete, write in the third person, and do not add anything that is not in the conversation.";
+
+/// Turns a conversation transcript into a summary.
+#[async_trait]
+pub trait Summarizer: Send + Sync {
+    async fn summarize(&self, transcript: &str, model: &Model) -> Result<String, String>;
+}
+
+/// Summarizes with the session's model.
+#[derive(Clone)]
+pub struct InferenceSummarizer {
+    inference: Arc<Inference>,
+}
+
+impl InferenceSummarizer {
+    pub fn new(inference: Arc<Inference>) -> Self {
+        Self { inference }
+    }
+}
+
+#[async_trait]
+impl Summarizer for InferenceSummarizer {
+    async fn summarize(&self, transcript: &str, model: &Model) -> Result<String, String> {
+        let mut request = GenerateRequest::new(
+            model.clone(),
+            vec![
+                Message::new(Role::System, SUMMARY_PROMPT),
+                Message::new(Role::User, transcript.to_string()),
+            ],
+        );
+        let max_tokens = match model.limit.output {
+            0 => SUMMARY_MAX_OUTPUT_TOKENS,
+            output => output.min(SUMMARY_MAX_OUTPUT_TOKENS),
+        };
+        request.options.max_tokens = Some(max_tokens as u32);
+
+        let response = self
+            .inference
+            .generate(&request)
+            .await
+            .map_err(|error| error.to_string())?;
+        let summary = response.text().trim().to_string();
+        if summary.is_empty() {
+            return Err("Summarizer returned an empty summary".to_string());
+        }
+        Ok(summary)
+    }
+}
+
+/// [`CompactionEngine`] that summarizes older turns, counting tokens with
+/// the budget's tokenizer.
+pub struct SummarizingCompactionEngine {
+    summarizer: Arc<dyn Summarizer>,
+    budget: ContextBudget,
+}
+
+impl SummarizingCompactionEngine {
+    pub fn new(summarizer: Arc<dyn Summarizer>, budget: ContextBudget) -> Self {
+        Self { summarizer, budget }
+    }
+
+    /// Tokens history may take in `model`'s context window, after reserving
+    /// its output.
+    pub fn history_limit(&self, model: &Model) -> usize {
+        let window = model.limit.context.saturating_sub(model.limit.output);
+        (window as f64 * f64::from(self.budget.history_max_ratio)) as usize
+    }
+
+    pub fn count_messages(&self, messages: &[Message]) -> usize {
+        messages
+            .iter()
+            .map(|message| self.count_message(message))
+            .sum()
+    }
+
+    fn count_message(&self, message: &Message) -> usize {
+        let content = match &message.content {
+            MessageContent::Text(text) => self.budget.count(text),
+            MessageContent::Parts(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    ContentPart::Text { text, .. } => self.budget.count(text),
+                    ContentPart::Image { .. } => IMAGE_TOKENS,
+                    ContentPart::ToolCall {
+                        name, arguments, ..
+                    } => self.budget.count(name) + self.budget.count(&arguments.to_string()),
+                    ContentPart::ToolResult { content, .. } => {
+                        self.budget.count(&value_text(content))
+                    }
+                })
+                .sum(),
+        };
+        content + MESSAGE_OVERHEAD_TOKENS
+    }
+
+    /// Where the verbatim tail starts in `body`: the start of the latest
+    /// turns, moved later while the tail alone is over its share of
+    /// `limit`. `None` when there is nothing before it to summarize.
+    fn cut_index(&self, body: &[Message], limit: usize) -> Option<usize> {
+        // Cutting before a user or assistant message never separates a tool
+        // call from its result.
+        let candidates: Vec<usize> = (1..body.len())
+            .filter(|&index| matches!(body[index].role, Role::User | Role::Assistant))
+            .collect();
+        let turns: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&index| body[index].role == Role::User)
+            .collect();
+        let preferred = if turns.len() >= KEEP_RECENT_TURNS {
+            turns[turns.len() - KEEP_RECENT_TURNS]
+        } else {
+            let steps: Vec<usize> = candidates
+                .iter()
+                .copied()
+                .filter(|&index| body[index].role == Role::Assistant)
+                .collect();
+            *steps.get(steps.len().checked_sub(KEEP_RECENT_STEPS)?)?
+        };
+
+        let kept_limit = (limit as f64 * KEPT_HISTORY_SHARE) as usize;
+        let mut cut = preferred;
+        for &candidate in candidates.iter().filter(|&&index| index >= preferred) {
+            cut = candidate;
+            if self.count_messages(&body[candidate..]) <= kept_limit {
+                break;
+            }
+        }
+
+        // A lone summary from an earlier compaction has nothing to add.
+        if cut == 1 && is_summary(&body[0]) {
+            return None;
+        }
+        Some(cut)
+    }
+
+    /// Transcript of `older` for the summarizer, cut to fit its window.
+    fn transcript(
+        &self,
+        older: &[Message],
+        calls: &HashMap<String, ToolCallInfo>,
+        model: &Model,
+    ) -> String {
+        let mut lines = Vec::new();
+        for message in older {
+            let speaker = match message.role {
+                Role::System => "System",
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+                Role::Tool => "Tool",
+            };
+            for part in message.parts() {
+                match part {
+                    ContentPart::Text { text, .. } if !text.trim().is_empty() => {
+                        lines.push(format!("{speaker}: {}", text.trim()));
+                    }
+                    ContentPart::ToolCall {
+                        name, arguments, ..
+                    } => lines.push(format!("Assistant called {name} {arguments}")),
+                    ContentPart::ToolResult {
+                        tool_call_id,
+                        content,
+                        ..
+                    } => {
+                        let name = calls
+                            .get(&tool_call_id)
+                            .map_or("tool", |call| call.name.as_str());
+                        let (content, _) = self.budget.truncate(
+                            &value_text(&content),
+                            TRANSCRIPT_TOOL_RESULT_MAX_TOKENS,
+                            "tool result",
+                        );
+                        lines.push(format!("Result of {name}: {content}"));
+                    }
+                    ContentPart::Image { .. } => lines.push(format!("{speaker}: [image]")),
+                    ContentPart::Text { .. } => {}
+                }
+            }
+        }
+
+        let max_tokens = (model.limit.context as f64 * TRANSCRIPT_WINDOW_SHARE) as usize;
+        let (transcript, _) = self
+            .budget
+            .truncate(&lines.join("\n\n"), max_tokens, "conversation");
+        transcript
+    }
+
+    /// Results of calls in `older` that `kept` still refers to: by call id,
+    /// by an argument both calls share (a path viewed then edited), or by a
+    /// value from the result that a later call uses (a task id). Latest
+    /// first, within [`PRESERVED_RESULTS_MAX_TOKENS`].
+    fn preserved_results(
+        &self,
+        older: &[Message],
+        kept: &[Message],
+        calls: &HashMap<String, ToolCallInfo>,
+    ) -> Vec<String> {
+        let mut kept_text = String::new();
+        let mut kept_values = HashSet::new();
+        for part in kept.iter().flat_map(Message::parts) {
+            match part {
+                ContentPart::Text { text, .. } => {
+                    kept_text.push_str(&text);
+                    kept_text.push('\n');
+                }
+                ContentPart::ToolCall { arguments, .. } => {
+                    collect_strings(&arguments, &mut kept_values);
+                }
+                _ => {}
+            }
+        }
+
+        let mut seen_calls = HashSet::new();
+        let mut remaining = PRESERVED_RESULTS_MAX_TOKENS;
+        let mut preserved = Vec::new();
+        let results = older
+            .iter()
+            .flat_map(Message::parts)
+            .filter_map(|part| match part {
+                ContentPart::ToolResult {
+                    tool_call_id,
+                    content,
+                    ..
+                } => Some((tool_call_id, content)),
+                _ => None,
+            });
+        let results: Vec<(String, Value)> = results.collect();
+
+        for (tool_call_id, content) in results.into_iter().rev() {
+            let Some(call) = calls.get(&tool_call_id) else {
+                continue;
+            };
+            let content = value_text(&content);
+            let referenced = kept_text.contains(&tool_call_id)
+                || kept_values
+                    .iter()
+                    .any(|value| call.values.contains(value) || content.contains(value.as_str()));
+            // Only the latest result of a repeated call is worth keeping.
+            if !referenced || !seen_calls.insert((call.name.clone(), call.arguments.clone())) {
+                continue;
+            }
+
+            let (content, _) = truncate_with_marker(
+                &content,
+                PRESERVED_RESULT_MAX_TOKENS.min(remaining),
+                "tool result",
+                self.budget.tokenizer.as_ref(),
+            );
+            let block = format!(
+                "<tool_result tool=\"{}\" arguments=\"{}\">\n{}\n</tool_result>",
+                call.name,
+                call.arguments.replace('"', "&quot;"),
+                content
+            );
+            let tokens = self.budget.count(&block);
+            if tokens > remaining {
+                break;
+            }
+            remaining -= tokens;
+            preserved.push(block);
+        }
+
+        preserved.reverse();
+        preserved
+    }
+}
+
+#[async_trait]
+impl CompactionEngine for SummarizingCompactionEngine {
+    fn should_compact(&self, messages: &[Message], model: &Model) -> bool {
+        self.count_messages(messages) > self.history_limit(model)
+    }
+
+    async fn compact(
+        &self,
+        messages: Vec<Message>,
+        model: &Model,
+    ) -> Result<CompactionResult, AgentError> {
+        let tokens_before = self.count_messages(&messages);
+        let unchanged = |messages: Vec<Message>| CompactionResult {
+            messages,
+            tokens_before,
+            tokens_after: tokens_before,
+            truncated: false,
+        };
+
+        let system_len = messages
+            .iter()
+            .take_while(|message| message.role == Role::System)
+            .count();
+        let (head, body) = messages.split_at(system_len);
+        let Some(cut) = self.cut_index(body, self.history_limit(model)) else {
+            return Ok(unchanged(messages));
+        };
+        let (older, kept) = body.split_at(cut);
+
+        let calls = tool_calls(older);
+        let preserved = self.preserved_results(older, kept, &calls);
+        let transcript = self.transcript(older, &calls, model);
+        let (summary, truncated) = match self.summarizer.summarize(&transcript, model).await {
+            Ok(summary) => (summary, false),
+            Err(error) => {
+                tracing::warn!("Conversation summary failed, keeping user requests only: {error}");
+                (fallback_summary(older), true)
+            }
+        };
+
+        let mut block = format!(
+            "<{SUMMARY_TAG}>\nEarlier turns of this conversation were compacted into this summary.\n\n{summary}\n</{SUMMARY_TAG}>"
+        );
+        if !preserved.is_empty() {
+            block.push_str(&format!(
+                "\n\n<preserved_tool_results>\nResults from the compacted turns that the conversation still refers to.\n\n{}\n</preserved_tool_results>",
+                preserved.join("\n\n")
+            ));
+        }
+
+        let mut compacted = head.to_vec();
+        compacted.push(Message::new(Role::User, block));
+        compacted.extend_from_slice(kept);
+        let tokens_after = self.count_messages(&compacted);
+        if tokens_after >= tokens_before {
+            return Ok(unchanged(messages));
+        }
+
+        Ok(CompactionResult {
+            messages: compacted,
+            tokens_before,
+            tokens_after,
+            truncated,
+        })
+    }
+}
+
+struct ToolCallInfo {
+    name: String,
+    arguments: String,
+    /// String values in the arguments, for matching later references.
+    values: HashSet<String>,
+}
+
+fn tool_calls(messages: &[Message]) -> HashMap<String, ToolCallInfo> {
+    let mut calls = HashMap::new();
+    for part in messages.iter().flat_map(Message::parts) {
+        if let ContentPart::ToolCall {
+            id,
+            name,
+            arguments,
+            ..
+        } = part
+        {
+            let mut values = HashSet::new();
+            collect_strings(&arguments, &mut values);
+            calls.insert(
+                id,
+                ToolCallInfo {
+                    name,
+                    arguments: arguments.to_string(),
+                    values,
+                },
+            );
+        }
+    }
+    calls
+}
+
+fn collect_strings(value: &Value, strings: &mut HashSet<String>) {
+    match value {
+        Value::String(text) if text.chars().count() >= MIN_REFERENCE_CHARS => {
+            strings.insert(text.clone());
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, strings)),
+        Value::Object(map) => map.values().for_each(|item| collect_strings(item, strings)),
+        _ => {}
+    }
+}
+
+fn value_text(value: &Value) -> String {
+    value
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| value.to_string())
+}
+
+fn is_summary(message: &Message) -> bool {
+    message.role == Role::User
+        && message
+            .text()
+            .is_some_and(|text| text.starts_with(&format!("<{SUMMARY_TAG}>")))
+}
+
+/// The user's requests from `older`, when no model summary is available.
+fn fallback_summary(older: &[Message]) -> String {
+    let requests: Vec<String> = older
+        .iter()
+        .filter(|message| message.role == Role::User)
+        .filter_map(Message::text)
+        .map(|text| {
+            let text = text.trim();
+            let mut request: String = text.chars().take(FALLBACK_REQUEST_CHARS).collect();
+            if text.chars().count() > FALLBACK_REQUEST_CHARS {
+                request.push_str("...");
+            }
+            format!("- {}", request.replace('\n', " "))
+        })
+        .collect();
+    format!(
+        "The summary could not be generated; the assistant's work in these turns was dropped. The user asked:\n{}",
+        requests.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingSummarizer {
+        transcripts: Mutex<Vec<String>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl Summarizer for RecordingSummarizer {
+        async fn summarize(&self, transcript: &str, _model: &Model) -> Result<String, String> {
+            self.transcripts.lock().await.push(transcript.to_string());
+            if self.fail {
+                return Err("provider unavailable".to_string());
+            }
+            Ok("The user is fixing invoice rounding in billing.".to_string())
+        }
+    }
+
+    fn json_of(messages: &[Message]) -> Value {
+        serde_json::to_value(messages).expect("serialize messages")
+    }
+
+    fn model(context: u64) -> Model {
+        let mut model = Model::custom("claude-sonnet-4-5", "anthropic");
+        model.limit = stakai::ModelLimit::new(context, 0);
+        model
+    }
+
+    fn call(id: &str, name: &str, arguments: Value) -> Message {
+        Message::new(
+            Role::Assistant,
+            vec![ContentPart::ToolCall {
+                id: id.to_string(),
+                name: name.to_string(),
+                arguments,
+                provider_options: None,
+                metadata: None,
+            }],
+        )
+    }
+
+    fn result(id: &str, content: &str) -> Message {
+        Message::new(
+            Role::Tool,
+            vec![ContentPart::ToolResult {
+                tool_call_id: id.to_string(),
+                content: json!(content),
+                provider_options: None,
+            }],
+        )
+    }
+
+    fn session() -> Vec<Message> {
+        vec![
+            Message::new(Role::System, "You are an agent."),
+            Message::new(Role::User, "Why are invoices rounded wrong?"),
+            call("c1", "view", json!({"path": "src/billing/round.rs"})),
+            result(
+                "c1",
+                &"fn round(amount: f64) -> f64 { amount.floor() }\n".repeat(40),
+            ),
+            call("c2", "view", json!({"path": "src/search/index.rs"})),
+            result("c2", &"fn index() {}\n".repeat(40)),
+            call(
+                "c3",
+                "run_command_task",
+                json!({"command": "cargo test billing"}),
+            ),
+            result("c3", "Started task task-7f3a"),
+            Message::new(
+                Role::Assistant,
+                "round uses floor instead of banker's rounding.",
+            ),
+            Message::new(Role::User, "Fix it."),
+            call(
+                "c4",
+                "str_replace",
+                json!({"path": "src/billing/round.rs", "old": "floor"}),
+            ),
+            result("c4", "ok"),
+            Message::new(Role::User, "Is the test done?"),
+            call("c5", "get_task_details", json!({"task_id": "task-7f3a"})),
+        ]
+    }
+
+    #[tokio::test]
+    async fn summarizes_older_turns_and_keeps_referenced_tool_results() {
+        let summarizer = Arc::new(RecordingSummarizer::default());
+        let engine = SummarizingCompactionEngine::new(summarizer.clone(), ContextBudget::default());
+        let messages = session();
+        let model = model(10_000);
+
+        let compacted = engine
+            .compact(messages.clone(), &model)
+            .await
+            .expect("compact");
+
+        assert!(!compacted.truncated);
+        assert!(compacted.tokens_after < compacted.tokens_before);
+        assert_eq!(compacted.messages[0].role, Role::System);
+        assert_eq!(json_of(&compacted.messages[2..]), json_of(&messages[9..]));
+
+        let block = compacted.messages[1].text().unwrap_or_default();
+        assert!(block.starts_with(
+            "<conversation_summary>\nEarlier turns of this conversation were compacted into this summary.\n\nThe user is fixing invoice rounding in billing.\n</conversation_summary>"
+        ));
+        assert!(
+            block.contains("<tool_result tool=\"view\""),
+            "viewed file is being edited"
+        );
+        assert!(block.contains("amount.floor()"));
+        assert!(
+            block.contains("Started task task-7f3a"),
+            "task is being polled"
+        );
+        assert!(
+            !block.contains("fn index()"),
+            "unrelated results are left out"
+        );
+
+        let transcripts = summarizer.transcripts.lock().await;
+        assert!(transcripts[0].starts_with("User: Why are invoices rounded wrong?"));
+        assert!(
+            transcripts[0].contains("Assistant called view {\"path\":\"src/search/index.rs\"}")
+        );
+        assert!(!transcripts[0].contains("Fix it."));
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_user_requests_when_summarizer_fails() {
+        let engine = SummarizingCompactionEngine::new(
+            Arc::new(RecordingSummarizer {
+                fail: true,
+                ..Default::default()
+            }),
+            ContextBudget::default(),
+        );
+
+        let compacted = engine
+            .compact(session(), &model(10_000))
+            .await
+            .expect("compact");
+
+        assert!(compacted.truncated);
+        let block = compacted.messages[1].text().unwrap_or_default();
+        assert!(block.contains(
+            "The user asked:\n- Why are invoices rounded wrong?\n</conversation_summary>"
+        ));
+    }
+
+    #[tokio::test]
+    async fn compacts_only_history_over_the_limit_with_something_to_summarize() {
+        let summarizer = Arc::new(RecordingSummarizer::default());
+        let engine = SummarizingCompactionEngine::new(summarizer.clone(), ContextBudget::default());
+        let messages = session();
+        let tokens = engine.count_messages(&messages) as u64;
+
+        assert!(!engine.should_compact(&messages, &model(tokens * 2)));
+        assert!(engine.should_compact(&messages, &model(tokens)));
+
+        let short = vec![
+            Message::new(Role::User, "Hello"),
+            Message::new(Role::Assistant, "Hi"),
+        ];
+        let compacted = engine
+            .compact(short.clone(), &model(10))
+            .await
+            .expect("compact");
+        assert_eq!(json_of(&compacted.messages), json_of(&short));
+        assert_eq!(compacted.tokens_after, compacted.tokens_before);
+
+        let once = engine
+            .compact(messages, &model(10_000))
+            .await
+            .expect("compact");
+        let again = engine
+            .compact(once.messages[..3].to_vec(), &model(10))
+            .await
+            .expect("compact again");
+        assert_eq!(
+            again.messages.len(),
+            3,
+            "a lone summary is not resummarized"
+        );
+        assert_eq!(summarizer.transcripts.lock().await.len(), 1);
+    }
+}
@@ -1,6 +1,10 @@
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use stakpak_shared::utils::{LocalFileSystemProvider, generate_directory_tree};
+use stakpak_shared::context_rules::{ContextRules, ContextRulesConfig};
+use stakpak_shared::utils::{
+    DirectoryEntry, FileSystemProvider, LocalFileSystemProvider, generate_directory_tree,
+};
 use std::env;
 use std::path::Path;
 use std::process::Command;
@@ -24,9 +28,39 @@ pub struct EnvironmentContext {
     pub git: Option<GitContext>,
 }
 
+/// Local directory listings without the entries context rules exclude.
+struct RulesFileSystemProvider<'a> {
+    rules: &'a ContextRules,
+}
+
+#[async_trait]
+impl FileSystemProvider for RulesFileSystemProvider<'_> {
+    type Error = std::io::Error;
+
+    async fn list_directory(&self, path: &str) -> Result<Vec<DirectoryEntry>, Self::Error> {
+        let mut entries = LocalFileSystemProvider.list_directory(path).await?;
+        entries.retain(|entry| {
+            !self
+                .rules
+                .is_excluded(Path::new(&entry.path), entry.is_directory)
+        });
+        Ok(entries)
+    }
+}
+
 impl EnvironmentContext {
+    /// Snapshot the environment, honouring `.stakpakignore` files in the
+    /// directory tree.
     pub async fn snapshot(working_directory: &str) -> Self {
-        let provider = LocalFileSystemProvider;
+        let rules =
+            ContextRules::load(Path::new(working_directory), &ContextRulesConfig::default());
+        Self::snapshot_with_rules(working_directory, &rules).await
+    }
+
+    /// Snapshot the environment, leaving what `rules` exclude out of the
+    /// directory tree.
+    pub async fn snapshot_with_rules(working_directory: &str, rules: &ContextRules) -> Self {
+        let provider = RulesFileSystemProvider { rules };
         let directory_tree = generate_directory_tree(&provider, working_directory, "", 1, 0)
             .await
             .ok()
@@ -243,6 +277,37 @@ mod tests {
         assert!(block.contains("# Current Working Directory"));
     }
 
+    #[tokio::test]
+    async fn snapshot_leaves_ignored_entries_out_of_the_directory_tree() {
+        let temp = tempfile::TempDir::new().expect("temp dir");
+        std::fs::create_dir_all(temp.path().join(".git")).expect("create git dir");
+        for dir in ["src", "vendor", "dist"] {
+            std::fs::create_dir_all(temp.path().join(dir)).expect("create dir");
+        }
+        std::fs::write(temp.path().join("Cargo.lock"), "").expect("write lockfile");
+        std::fs::write(temp.path().join("Cargo.toml"), "").expect("write manifest");
+        std::fs::write(temp.path().join(".stakpakignore"), "vendor/\n*.lock\n")
+            .expect("write ignore");
+        let cwd = temp.path().to_string_lossy().to_string();
+
+        let tree = EnvironmentContext::snapshot(&cwd).await.directory_tree;
+        assert!(tree.contains("src") && tree.contains("dist") && tree.contains("Cargo.toml"));
+        assert!(!tree.contains("vendor") && !tree.contains("Cargo.lock"));
+
+        let rules = ContextRules::load(
+            temp.path(),
+            &ContextRulesConfig {
+                include: vec!["Cargo.lock".to_string()],
+                exclude: vec!["dist/".to_string()],
+            },
+        );
+        let tree = EnvironmentContext::snapshot_with_rules(&cwd, &rules)
+            .await
+            .directory_tree;
+        assert!(tree.contains("Cargo.lock"));
+        assert!(!tree.contains("dist") && !tree.contains("vendor"));
+    }
+
     #[tokio::test]
     async fn snapshot_populates_all_fields() {
         let temp = tempfile::TempDir::new().expect("temp dir");
@@ -0,0 +1,24 @@
+use stakpak_shared::discovery::{DiscoveryCache, DiscoveryReport};
+use std::path::Path;
+use std::time::Duration;
+
+/// Supplies the machine profile — discovery probe results such as git
+/// repositories, cloud accounts and listening ports — for new sessions.
+pub trait MachineProfileSource: Send + Sync {
+    /// Latest profile for a session working in `cwd`, if one is available
+    /// without blocking on a scan.
+    fn machine_profile(&self, cwd: &Path) -> Option<DiscoveryReport>;
+}
+
+/// Discovery results the CLI cached for the session's working directory in
+/// `~/.stakpak/cache/discovery/`, while younger than `max_age`.
+#[derive(Debug, Clone)]
+pub struct CachedMachineProfile {
+    pub max_age: Duration,
+}
+
+impl MachineProfileSource for CachedMachineProfile {
+    fn machine_profile(&self, cwd: &Path) -> Option<DiscoveryReport> {
+        DiscoveryCache::for_dir(cwd).load(self.max_age)
+    }
+}
@@ -0,0 +1,574 @@
+//! Facts the agent keeps across sessions in the same workspace.
+//!
+//! The agent saves short, distilled facts — host names, account IDs, how a
+//! past incident was resolved — with the `save_memory` tool. New sessions in
+//! the workspace get the ones most relevant to their first prompt in a
+//! `<memories>` block, and `recall_memory` / `forget_memory` search and
+//! prune them mid-session.
+//!
+//! A workspace is the repository holding the session's working directory,
+//! or the directory itself outside a repository. Each workspace's memories
+//! are one JSON file under `~/.stakpak/server/memory/`.
+
+use crate::context::ranking::similarities;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+pub const SAVE_MEMORY_TOOL: &str = "save_memory";
+pub const RECALL_MEMORY_TOOL: &str = "recall_memory";
+pub const FORGET_MEMORY_TOOL: &str = "forget_memory";
+
+/// Bumped when the file layout changes; older files are ignored.
+const STORE_VERSION: u32 = 1;
+
+/// Least recently updated memories are dropped past this many.
+const MAX_MEMORIES_PER_WORKSPACE: usize = 500;
+
+const MAX_FACT_CHARS: usize = 2_000;
+
+/// Memories `recall_memory` returns when the call does not say.
+const DEFAULT_RECALL_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Memory {
+    pub id: String,
+    pub fact: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Session that last saved the fact.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<Uuid>,
+}
+
+impl Memory {
+    /// `- [id] fact (tags: a, b)`, the form memories take in context and
+    /// tool results.
+    pub fn to_line(&self) -> String {
+        let mut line = format!("- [{}] {}", self.id, self.fact);
+        if !self.tags.is_empty() {
+            line.push_str(&format!(" (tags: {})", self.tags.join(", ")));
+        }
+        line
+    }
+
+    fn document(&self) -> String {
+        format!("{}\n{}", self.fact, self.tags.join(" "))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MemoryFile {
+    version: u32,
+    workspace: PathBuf,
+    memories: Vec<Memory>,
+}
+
+/// Local memory store, one file per workspace.
+#[derive(Debug, Clone)]
+pub struct MemoryStore {
+    root: PathBuf,
+    /// Serializes read-modify-write cycles within this process.
+    lock: Arc<Mutex<()>>,
+}
+
+impl MemoryStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    pub fn default_local() -> Self {
+        let root = std::env::var("HOME")
+            .map(|home| {
+                PathBuf::from(home)
+                    .join(".stakpak")
+                    .join("server")
+                    .join("memory")
+            })
+            .unwrap_or_else(|_| PathBuf::from(".stakpak").join("server").join("memory"));
+
+        Self::new(root)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Workspace for a session working in `cwd`: the enclosing repository
+    /// root, or `cwd` itself.
+    pub fn workspace(cwd: &Path) -> PathBuf {
+        let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+        cwd.ancestors()
+            .find(|dir| dir.join(".git").exists())
+            .unwrap_or(&cwd)
+            .to_path_buf()
+    }
+
+    /// All memories of `workspace`, most recently updated first.
+    pub async fn list(&self, workspace: &Path) -> Result<Vec<Memory>, String> {
+        let mut memories = self.read(workspace).await?.memories;
+        memories.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        Ok(memories)
+    }
+
+    /// Save `fact`. Saving a fact that is already stored, ignoring case and
+    /// surrounding whitespace, refreshes it and merges the tags instead of
+    /// adding a duplicate.
+    pub async fn save(
+        &self,
+        workspace: &Path,
+        fact: &str,
+        tags: &[String],
+        session_id: Option<Uuid>,
+    ) -> Result<Memory, String> {
+        let fact = fact.trim();
+        if fact.is_empty() {
+            return Err("Memory fact is empty".to_string());
+        }
+        if fact.chars().count() > MAX_FACT_CHARS {
+            return Err(format!(
+                "Memory fact is longer than {MAX_FACT_CHARS} characters; save a shorter summary"
+            ));
+        }
+        let tags: Vec<String> = tags
+            .iter()
+            .map(|tag| tag.trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        let _guard = self.lock.lock().await;
+        let mut file = self.read(workspace).await?;
+        let now = Utc::now();
+        let saved = match file
+            .memories
+            .iter_mut()
+            .find(|memory| memory.fact.to_lowercase() == fact.to_lowercase())
+        {
+            Some(memory) => {
+                for tag in tags {
+                    if !memory.tags.contains(&tag) {
+                        memory.tags.push(tag);
+                    }
+                }
+                memory.updated_at = now;
+                memory.session_id = session_id.or(memory.session_id);
+                memory.clone()
+            }
+            None => {
+                let mut unique_tags = Vec::new();
+                for tag in tags {
+                    if !unique_tags.contains(&tag) {
+                        unique_tags.push(tag);
+                    }
+                }
+                let memory = Memory {
+                    id: new_id(),
+                    fact: fact.to_string(),
+                    tags: unique_tags,
+                    created_at: now,
+                    updated_at: now,
+                    session_id,
+                };
+                file.memories.push(memory.clone());
+                memory
+            }
+        };
+
+        if file.memories.len() > MAX_MEMORIES_PER_WORKSPACE {
+            file.memories
+                .sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+            file.memories.truncate(MAX_MEMORIES_PER_WORKSPACE);
+        }
+        self.write(workspace, file).await?;
+        Ok(saved)
+    }
+
+    /// Delete memory `id`; `false` when there is none.
+    pub async fn forget(&self, workspace: &Path, id: &str) -> Result<bool, String> {
+        let _guard = self.lock.lock().await;
+        let mut file = self.read(workspace).await?;
+        let before = file.memories.len();
+        file.memories.retain(|memory| memory.id != id.trim());
+        if file.memories.len() == before {
+            return Ok(false);
+        }
+        self.write(workspace, file).await?;
+        Ok(true)
+    }
+
+    /// Up to `limit` memories, the ones most similar to `query` first and
+    /// the most recently updated among equals. Without searchable terms in
+    /// `query`, the most recent.
+    pub async fn recall(
+        &self,
+        workspace: &Path,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Memory>, String> {
+        let memories = self.list(workspace).await?;
+        let documents: Vec<String> = memories.iter().map(Memory::document).collect();
+        let Some(scores) = similarities(&documents, query) else {
+            return Ok(memories.into_iter().take(limit).collect());
+        };
+
+        let mut scored: Vec<(f32, Memory)> = scores.into_iter().zip(memories).collect();
+        // Stable, so ties stay most recent first.
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        Ok(scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, memory)| memory)
+            .collect())
+    }
+
+    fn path(&self, workspace: &Path) -> PathBuf {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        workspace.hash(&mut hasher);
+        self.root.join(format!("{:016x}.json", hasher.finish()))
+    }
+
+    async fn read(&self, workspace: &Path) -> Result<MemoryFile, String> {
+        let path = self.path(workspace);
+        let payload = match tokio::fs::read(&path).await {
+            Ok(payload) => payload,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(MemoryFile {
+                    version: STORE_VERSION,
+                    workspace: workspace.to_path_buf(),
+                    memories: Vec::new(),
+                });
+            }
+            Err(error) => {
+                return Err(format!(
+                    "Failed to read memories from {}: {}",
+                    path.display(),
+                    error
+                ));
+            }
+        };
+        let file: MemoryFile = serde_json::from_slice(&payload).map_err(|error| {
+            format!("Failed to parse memories in {}: {}", path.display(), error)
+        })?;
+        if file.version != STORE_VERSION || file.workspace != workspace {
+            return Ok(MemoryFile {
+                version: STORE_VERSION,
+                workspace: workspace.to_path_buf(),
+                memories: Vec::new(),
+            });
+        }
+        Ok(file)
+    }
+
+    async fn write(&self, workspace: &Path, file: MemoryFile) -> Result<(), String> {
+        let path = self.path(workspace);
+        tokio::fs::create_dir_all(&self.root)
+            .await
+            .map_err(|error| {
+                format!(
+                    "Failed to create memory directory {}: {}",
+                    self.root.display(),
+                    error
+                )
+            })?;
+        let payload = serde_json::to_vec_pretty(&file)
+            .map_err(|error| format!("Failed to serialize memories: {error}"))?;
+        let temp_path = path.with_extension("tmp");
+        tokio::fs::write(&temp_path, payload)
+            .await
+            .map_err(|error| format!("Failed to write memories: {error}"))?;
+        tokio::fs::rename(&temp_path, &path)
+            .await
+            .map_err(|error| format!("Failed to write memories to {}: {}", path.display(), error))
+    }
+}
+
+fn new_id() -> String {
+    let id = Uuid::new_v4().simple().to_string();
+    id.chars().take(8).collect()
+}
+
+/// Memory tools for one session, bound to its workspace.
+#[derive(Debug, Clone)]
+pub struct MemoryTools {
+    store: MemoryStore,
+    workspace: PathBuf,
+    session_id: Option<Uuid>,
+}
+
+impl MemoryTools {
+    pub fn new(store: MemoryStore, workspace: PathBuf, session_id: Option<Uuid>) -> Self {
+        Self {
+            store,
+            workspace,
+            session_id,
+        }
+    }
+
+    /// Tool definitions to add to the session's tools.
+    pub fn definitions() -> Vec<stakai::Tool> {
+        vec![
+            stakai::Tool::function(
+                SAVE_MEMORY_TOOL,
+                "Save a short, durable fact about this workspace for future sessions: host \
+                 names, account or project IDs, where things run, how an incident was \
+                 resolved. One fact per call; never save secrets.",
+            )
+            .parameters(json!({
+                "type": "object",
+                "properties": {
+                    "fact": {
+                        "type": "string",
+                        "description": "The fact, as one self-contained sentence"
+                    },
+                    "tags": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Optional tags, e.g. [\"aws\", \"incident\"]"
+                    }
+                },
+                "required": ["fact"]
+            })),
+            stakai::Tool::function(
+                RECALL_MEMORY_TOOL,
+                "Search facts saved in earlier sessions in this workspace.",
+            )
+            .parameters(json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "What to look for; empty for the most recent facts"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": format!("Maximum facts to return (default {DEFAULT_RECALL_LIMIT})")
+                    }
+                }
+            })),
+            stakai::Tool::function(
+                FORGET_MEMORY_TOOL,
+                "Delete a saved fact that is wrong or out of date, by the id shown in brackets.",
+            )
+            .parameters(json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Memory id" }
+                },
+                "required": ["id"]
+            })),
+        ]
+    }
+
+    pub fn handles(name: &str) -> bool {
+        matches!(
+            name,
+            SAVE_MEMORY_TOOL | RECALL_MEMORY_TOOL | FORGET_MEMORY_TOOL
+        )
+    }
+
+    /// Run memory tool `name`; `Err` carries a message for the agent.
+    pub async fn call(&self, name: &str, arguments: &Value) -> Result<String, String> {
+        let text = |key: &str| {
+            arguments
+                .get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        };
+        match name {
+            SAVE_MEMORY_TOOL => {
+                let tags: Vec<String> = arguments
+                    .get("tags")
+                    .and_then(Value::as_array)
+                    .map(|tags| {
+                        tags.iter()
+                            .filter_map(|tag| tag.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let memory = self
+                    .store
+                    .save(&self.workspace, text("fact"), &tags, self.session_id)
+                    .await?;
+                Ok(format!("Saved memory {}", memory.to_line()))
+            }
+            RECALL_MEMORY_TOOL => {
+                let limit = arguments
+                    .get("limit")
+                    .and_then(Value::as_u64)
+                    .map_or(DEFAULT_RECALL_LIMIT, |limit| limit.clamp(1, 100) as usize);
+                let memories = self
+                    .store
+                    .recall(&self.workspace, text("query"), limit)
+                    .await?;
+                if memories.is_empty() {
+                    return Ok("No memories saved for this workspace".to_string());
+                }
+                Ok(memories
+                    .iter()
+                    .map(Memory::to_line)
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }
+            FORGET_MEMORY_TOOL => {
+                let id = text("id");
+                if self.store.forget(&self.workspace, id).await? {
+                    Ok(format!("Forgot memory {}", id.trim()))
+                } else {
+                    Err(format!("No memory with id {:?}", id.trim()))
+                }
+            }
+            other => Err(format!("Unknown memory tool {other}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> (tempfile::TempDir, MemoryStore) {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let store = MemoryStore::new(dir.path().join("memory"));
+        (dir, store)
+    }
+
+    #[tokio::test]
+    async fn save_dedupes_facts_and_recall_ranks_by_query() {
+        let (_dir, store) = store();
+        let workspace = Path::new("/work/infra");
+        let tags = |tags: &[&str]| -> Vec<String> { tags.iter().map(|t| t.to_string()).collect() };
+
+        let bastion = store
+            .save(
+                workspace,
+                "Bastion host is bastion.prod.acme.io",
+                &tags(&["ssh"]),
+                None,
+            )
+            .await
+            .expect("save bastion");
+        store
+            .save(
+                workspace,
+                "Production AWS account is 123456789012",
+                &tags(&["AWS"]),
+                None,
+            )
+            .await
+            .expect("save account");
+        let again = store
+            .save(
+                workspace,
+                "  bastion host is bastion.prod.acme.io ",
+                &tags(&["prod", "ssh"]),
+                None,
+            )
+            .await
+            .expect("save duplicate");
+
+        assert_eq!(again.id, bastion.id);
+        assert_eq!(again.tags, vec!["ssh", "prod"]);
+        assert_eq!(store.list(workspace).await.expect("list").len(), 2);
+
+        let recalled = store
+            .recall(workspace, "which aws account is production?", 1)
+            .await
+            .expect("recall");
+        assert_eq!(recalled[0].fact, "Production AWS account is 123456789012");
+        assert_eq!(recalled[0].tags, vec!["aws"]);
+
+        let recent = store.recall(workspace, "", 5).await.expect("recall recent");
+        assert_eq!(recent[0].id, bastion.id, "most recently updated first");
+        assert!(
+            store
+                .list(Path::new("/work/other"))
+                .await
+                .expect("list other")
+                .is_empty(),
+            "memories are per workspace"
+        );
+    }
+
+    #[tokio::test]
+    async fn tools_save_recall_and_forget() {
+        let (_dir, store) = store();
+        let session = Uuid::new_v4();
+        let tools = MemoryTools::new(store.clone(), PathBuf::from("/work/app"), Some(session));
+
+        let saved = tools
+            .call(
+                SAVE_MEMORY_TOOL,
+                &json!({"fact": "Incident 42: disk full on db-1, fixed by rotating WAL", "tags": ["incident"]}),
+            )
+            .await
+            .expect("save");
+        assert!(saved.starts_with("Saved memory - ["));
+        let memory = &store.list(Path::new("/work/app")).await.expect("list")[0];
+        assert_eq!(memory.session_id, Some(session));
+
+        let recalled = tools
+            .call(RECALL_MEMORY_TOOL, &json!({"query": "db-1 disk"}))
+            .await
+            .expect("recall");
+        assert_eq!(recalled, memory.to_line());
+
+        assert!(
+            tools
+                .call(SAVE_MEMORY_TOOL, &json!({"fact": " "}))
+                .await
+                .is_err()
+        );
+        assert!(
+            tools
+                .call(FORGET_MEMORY_TOOL, &json!({"id": "missing"}))
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            tools
+                .call(FORGET_MEMORY_TOOL, &json!({"id": memory.id}))
+                .await
+                .expect("forget"),
+            format!("Forgot memory {}", memory.id)
+        );
+        assert_eq!(
+            tools
+                .call(RECALL_MEMORY_TOOL, &json!({}))
+                .await
+                .expect("recall empty"),
+            "No memories saved for this workspace"
+        );
+        assert!(
+            MemoryTools::definitions()
+                .iter()
+                .all(|tool| MemoryTools::handles(&tool.function.name))
+        );
+    }
+
+    #[test]
+    fn workspace_is_the_enclosing_repository() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let nested = dir.path().join("services/api");
+        std::fs::create_dir_all(&nested).expect("create nested");
+        assert_eq!(
+            MemoryStore::workspace(&nested),
+            nested.canonicalize().expect("canonicalize")
+        );
+
+        std::fs::create_dir(dir.path().join(".git")).expect("create .git");
+        assert_eq!(
+            MemoryStore::workspace(&nested),
+            dir.path().canonicalize().expect("canonicalize")
+        );
+    }
+}
@@ -1,9 +1,18 @@
 pub mod budget;
 pub mod builder;
+pub mod compaction;
 pub mod environment;
+pub mod machine;
+pub mod memory;
 pub mod project;
+pub mod ranking;
+pub mod tokenizer;
 
 pub use budget::ContextBudget;
 pub use builder::{SessionContext, SessionContextBuilder};
+pub use compaction::{InferenceSummarizer, Summarizer, SummarizingCompactionEngine};
 pub use environment::{EnvironmentContext, GitContext};
+pub use machine::{CachedMachineProfile, MachineProfileSource};
+pub use memory::{Memory, MemoryStore, MemoryTools};
 pub use project::{ContextFile, ContextPriority, ProjectContext};
+pub use tokenizer::{TokenEstimator, Tokenizer, TokenizerFamily};
@@ -1,3 +1,6 @@
+use crate::context::ranking;
+use stakpak_shared::context_rules::{ContextRules, ContextRulesConfig};
+use stakpak_shared::project_scope::ProjectScope;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -24,6 +27,9 @@ pub struct ContextFile {
     pub original_size: usize,
     pub truncated: bool,
     pub priority: ContextPriority,
+    /// Similarity to the run's prompt, from 0 to 1, set by
+    /// [`ProjectContext::rank_for_prompt`]; orders files within a priority.
+    pub relevance: f32,
 }
 
 impl ContextFile {
@@ -41,6 +47,7 @@ impl ContextFile {
             content,
             truncated: false,
             priority,
+            relevance: 0.0,
         }
     }
 }
@@ -51,17 +58,35 @@ pub struct ProjectContext {
 }
 
 impl ProjectContext {
+    /// Discover project files, honouring `.stakpakignore` files.
     pub fn discover(start_dir: &Path) -> Self {
+        Self::discover_with_rules(
+            start_dir,
+            &ContextRules::load(start_dir, &ContextRulesConfig::default()),
+        )
+    }
+
+    /// Discover project files, leaving out the ones `rules` exclude.
+    pub fn discover_with_rules(start_dir: &Path, rules: &ContextRules) -> Self {
         let mut files = Vec::new();
 
-        if let Some(file) = discover_agents_md(start_dir) {
+        if let Some(file) = discover_agents_md(start_dir, rules) {
             files.push(file);
         }
 
-        if let Some(file) = discover_apps_md(start_dir) {
+        if let Some(file) = discover_apps_md(start_dir, rules) {
             files.push(file);
         }
 
+        let scope = ProjectScope::resolve(start_dir);
+        if scope.is_scoped() {
+            files.extend(discover_scoped_agents_md(&scope, &files));
+            files.push(scope_context_file(&scope));
+        }
+
+        files.retain(|file| {
+            file.path.is_empty() || !rules.is_excluded(Path::new(&file.path), false)
+        });
         Self { files }
     }
 
@@ -69,10 +94,17 @@ impl ProjectContext {
         self.files.extend(caller_files);
         self
     }
+
+    /// Score files against `prompt`, promoting the ones it is about; see
+    /// [`crate::context::ranking`].
+    pub fn rank_for_prompt(mut self, prompt: &str) -> Self {
+        ranking::rank(&mut self.files, prompt);
+        self
+    }
 }
 
-fn discover_agents_md(start_dir: &Path) -> Option<ContextFile> {
-    let discovered = discover_nearest_file(start_dir, &["AGENTS.md", "agents.md"])?;
+fn discover_agents_md(start_dir: &Path, rules: &ContextRules) -> Option<ContextFile> {
+    let discovered = discover_nearest_file(start_dir, &["AGENTS.md", "agents.md"], rules)?;
 
     Some(ContextFile::new(
         "AGENTS.md",
@@ -82,13 +114,61 @@ fn discover_agents_md(start_dir: &Path) -> Option<ContextFile> {
     ))
 }
 
+/// AGENTS.md at the top of each scoped directory, beyond the nearest one.
+fn discover_scoped_agents_md(scope: &ProjectScope, discovered: &[ContextFile]) -> Vec<ContextFile> {
+    scope
+        .dirs
+        .iter()
+        .filter_map(|dir| {
+            let candidate = ["AGENTS.md", "agents.md"]
+                .iter()
+                .map(|name| dir.join(name))
+                .find(|path| path.is_file())?;
+            let path = canonical_or_original(&candidate).display().to_string();
+            if discovered.iter().any(|file| file.path == path) {
+                return None;
+            }
+            let content = fs::read_to_string(&candidate).ok()?;
+            Some(ContextFile::new(
+                "AGENTS.md",
+                path,
+                content,
+                ContextPriority::Normal,
+            ))
+        })
+        .collect()
+}
+
+/// Tell the agent which slice of the repository the session is scoped to.
+fn scope_context_file(scope: &ProjectScope) -> ContextFile {
+    let mut content = String::from(
+        "This repository is scoped. Limit searches, edits and summaries to these \
+         directories unless the user asks about something outside them:\n",
+    );
+    for dir in &scope.dirs {
+        content.push_str(&format!("- {}\n", dir.display()));
+    }
+    if let Some(owner) = &scope.owner {
+        content.push_str(&format!(
+            "\nIncludes directories CODEOWNERS assigns to {}.\n",
+            owner
+        ));
+    }
+    let path = scope
+        .config_path
+        .as_deref()
+        .map(|path| path.display().to_string())
+        .unwrap_or_default();
+    ContextFile::new("project_scope", path, content, ContextPriority::High)
+}
+
 /// Discover APPS.md with a global fallback at `~/.stakpak/APPS.md`.
 ///
 /// Unlike AGENTS.md (which is always project-specific), APPS.md can describe
 /// globally-managed applications and infrastructure, so a user-level fallback
 /// is supported when no project-local file is found.
-fn discover_apps_md(start_dir: &Path) -> Option<ContextFile> {
-    if let Some(discovered) = discover_nearest_file(start_dir, &["APPS.md", "apps.md"]) {
+fn discover_apps_md(start_dir: &Path, rules: &ContextRules) -> Option<ContextFile> {
+    if let Some(discovered) = discover_nearest_file(start_dir, &["APPS.md", "apps.md"], rules) {
         return Some(ContextFile::new(
             "APPS.md",
             discovered.path.display().to_string(),
@@ -116,13 +196,19 @@ struct DiscoveredFile {
     content: String,
 }
 
-fn discover_nearest_file(start_dir: &Path, file_names: &[&str]) -> Option<DiscoveredFile> {
+/// Nearest of `file_names` in `start_dir` or its ancestors that `rules` do
+/// not exclude.
+fn discover_nearest_file(
+    start_dir: &Path,
+    file_names: &[&str],
+    rules: &ContextRules,
+) -> Option<DiscoveredFile> {
     let mut current = start_dir.to_path_buf();
 
     for _ in 0..=MAX_TRAVERSAL_DEPTH {
         for file_name in file_names {
             let candidate = current.join(file_name);
-            if !candidate.exists() {
+            if !candidate.exists() || rules.is_excluded(&candidate, false) {
                 continue;
             }
 
@@ -256,6 +342,90 @@ mod tests {
         assert!(agents.is_none(), "empty dir should not have AGENTS.md");
     }
 
+    #[test]
+    fn scope_file_adds_scope_and_scoped_agents_md() {
+        let temp = tempfile::TempDir::new().expect("temp dir");
+        let billing = temp.path().join("services").join("billing");
+        std::fs::create_dir_all(temp.path().join(".git")).expect("create git dir");
+        std::fs::create_dir_all(temp.path().join(".stakpak")).expect("create stakpak dir");
+        std::fs::create_dir_all(&billing).expect("create billing");
+        std::fs::write(temp.path().join("AGENTS.md"), "root").expect("write root agents");
+        std::fs::write(billing.join("AGENTS.md"), "billing rules").expect("write billing agents");
+        std::fs::write(
+            temp.path().join(".stakpak").join("scope.toml"),
+            "paths = [\"services/billing\"]\n",
+        )
+        .expect("write scope");
+
+        let context = ProjectContext::discover(temp.path());
+        let agents: Vec<&str> = context
+            .files
+            .iter()
+            .filter(|file| file.name == "AGENTS.md")
+            .map(|file| file.content.as_str())
+            .collect();
+        assert_eq!(agents, vec!["root", "billing rules"]);
+
+        let scope = context
+            .files
+            .iter()
+            .find(|file| file.name == "project_scope")
+            .expect("scope context");
+        assert!(scope.path.ends_with("scope.toml"));
+        assert!(scope.content.contains("billing"));
+    }
+
+    #[test]
+    fn context_rules_skip_excluded_files_for_the_next_nearest() {
+        let temp = tempfile::TempDir::new().expect("temp dir");
+        let vendored = temp.path().join("vendor").join("lib");
+        let billing = temp.path().join("services").join("billing");
+        std::fs::create_dir_all(temp.path().join(".git")).expect("create git dir");
+        std::fs::create_dir_all(temp.path().join(".stakpak")).expect("create stakpak dir");
+        std::fs::create_dir_all(&vendored).expect("create vendored");
+        std::fs::create_dir_all(&billing).expect("create billing");
+        std::fs::write(temp.path().join("AGENTS.md"), "root").expect("write root agents");
+        std::fs::write(vendored.join("AGENTS.md"), "upstream").expect("write vendored agents");
+        std::fs::write(billing.join("AGENTS.md"), "billing rules").expect("write billing agents");
+        std::fs::write(
+            temp.path().join(".stakpakignore"),
+            "vendor/
+",
+        )
+        .expect("write ignore");
+        std::fs::write(
+            temp.path().join(".stakpak").join("scope.toml"),
+            "paths = [\"services/billing\"]\n",
+        )
+        .expect("write scope");
+
+        let agents = |context: &ProjectContext| -> Vec<String> {
+            context
+                .files
+                .iter()
+                .filter(|file| file.name == "AGENTS.md")
+                .map(|file| file.content.clone())
+                .collect()
+        };
+
+        assert_eq!(
+            agents(&ProjectContext::discover(&vendored)),
+            vec!["root", "billing rules"]
+        );
+
+        let rules = ContextRules::load(
+            temp.path(),
+            &ContextRulesConfig {
+                include: Vec::new(),
+                exclude: vec!["services/billing/AGENTS.md".to_string()],
+            },
+        );
+        assert_eq!(
+            agents(&ProjectContext::discover_with_rules(temp.path(), &rules)),
+            vec!["root"]
+        );
+    }
+
     #[test]
     fn context_file_tracks_original_size() {
         let content = "x".repeat(500);
@@ -0,0 +1,237 @@
+//! Relevance of project context files to the prompt that starts a run.
+//!
+//! Files are scored with TF-IDF cosine similarity between the prompt and a
+//! short summary of each file: its name, path, markdown headings and
+//! opening text. Scores order files within a priority, and a file that
+//! matches the prompt well moves up one priority, so a scoped AGENTS.md or
+//! a caller-supplied note about what the user is asking for is kept ahead
+//! of unrelated ones when the budget runs short.
+
+use crate::context::project::{ContextFile, ContextPriority};
+use std::collections::HashMap;
+
+/// Characters of file content, after the headings, that go into a summary.
+const SUMMARY_MAX_CHARS: usize = 4_000;
+
+/// Relevance at which a file is promoted one priority.
+const PROMOTE_THRESHOLD: f32 = 0.2;
+
+const STOPWORDS: &[&str] = &[
+    "a", "about", "all", "an", "and", "any", "are", "as", "at", "be", "but", "by", "can", "do",
+    "does", "for", "from", "how", "if", "in", "into", "is", "it", "its", "me", "my", "no", "not",
+    "of", "on", "or", "our", "please", "should", "so", "that", "the", "their", "then", "there",
+    "these", "this", "to", "up", "us", "was", "we", "what", "when", "where", "which", "why",
+    "will", "with", "you", "your",
+];
+
+/// Score `files` against `prompt`, setting [`ContextFile::relevance`] and
+/// promoting well-matching files one priority (never to Critical). An empty
+/// prompt leaves every file as it was.
+pub fn rank(files: &mut [ContextFile], prompt: &str) {
+    let summaries: Vec<String> = files.iter().map(summary).collect();
+    let Some(scores) = similarities(&summaries, prompt) else {
+        return;
+    };
+
+    for (file, relevance) in files.iter_mut().zip(scores) {
+        file.relevance = relevance;
+        if file.relevance >= PROMOTE_THRESHOLD {
+            file.priority = match file.priority {
+                ContextPriority::CallerSupplied => ContextPriority::Normal,
+                ContextPriority::Normal | ContextPriority::High => ContextPriority::High,
+                ContextPriority::Critical => ContextPriority::Critical,
+            };
+        }
+    }
+}
+
+/// TF-IDF cosine similarity of each document to `query`, from 0 to 1;
+/// `None` when the query has no searchable terms.
+pub(crate) fn similarities(documents: &[String], query: &str) -> Option<Vec<f32>> {
+    let query = term_frequencies(&terms(query));
+    if query.is_empty() {
+        return None;
+    }
+
+    let documents: Vec<HashMap<String, usize>> = documents
+        .iter()
+        .map(|document| term_frequencies(&terms(document)))
+        .collect();
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for document in &documents {
+        for term in document.keys() {
+            *document_frequency.entry(term.as_str()).or_default() += 1;
+        }
+    }
+    let idf = |term: &str| {
+        let df = document_frequency.get(term).copied().unwrap_or(0);
+        ((1.0 + documents.len() as f32) / (1.0 + df as f32)).ln() + 1.0
+    };
+    let weights = |frequencies: &HashMap<String, usize>| -> HashMap<String, f32> {
+        frequencies
+            .iter()
+            .map(|(term, tf)| (term.clone(), (1.0 + (*tf as f32).ln()) * idf(term)))
+            .collect()
+    };
+
+    let query = weights(&query);
+    let query_norm = norm(&query);
+    let scores = documents
+        .iter()
+        .map(|document| {
+            let document = weights(document);
+            let dot: f32 = query
+                .iter()
+                .filter_map(|(term, weight)| document.get(term).map(|other| weight * other))
+                .sum();
+            let denominator = query_norm * norm(&document);
+            if denominator > 0.0 {
+                dot / denominator
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    Some(scores)
+}
+
+/// Name, path, headings, then the start of the content.
+fn summary(file: &ContextFile) -> String {
+    let mut summary = format!("{}\n{}\n", file.name, file.path);
+    for heading in file
+        .content
+        .lines()
+        .filter(|line| line.trim_start().starts_with('#'))
+    {
+        summary.push_str(heading.trim_start_matches(['#', ' ']));
+        summary.push('\n');
+    }
+    summary.extend(file.content.chars().take(SUMMARY_MAX_CHARS));
+    summary
+}
+
+/// Lowercased words, with identifiers split at `_`, `-`, `.` and camelCase
+/// boundaries, plurals folded and stopwords dropped.
+fn terms(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let mut start = 0;
+        let chars: Vec<(usize, char)> = word.char_indices().collect();
+        for window in chars.windows(2) {
+            if let [(_, previous), (index, current)] = *window
+                && previous.is_lowercase()
+                && current.is_uppercase()
+            {
+                push_term(&mut terms, word.get(start..index).unwrap_or_default());
+                start = index;
+            }
+        }
+        push_term(&mut terms, word.get(start..).unwrap_or_default());
+    }
+    terms
+}
+
+fn push_term(terms: &mut Vec<String>, word: &str) {
+    let mut term = word.to_lowercase();
+    if term.chars().count() < 2 || STOPWORDS.contains(&term.as_str()) {
+        return;
+    }
+    if term.len() > 3 && term.ends_with('s') && !term.ends_with("ss") {
+        term.pop();
+    }
+    terms.push(term);
+}
+
+fn term_frequencies(terms: &[String]) -> HashMap<String, usize> {
+    let mut frequencies = HashMap::new();
+    for term in terms {
+        *frequencies.entry(term.clone()).or_default() += 1;
+    }
+    frequencies
+}
+
+fn norm(vector: &HashMap<String, f32>) -> f32 {
+    vector
+        .values()
+        .map(|weight| weight * weight)
+        .sum::<f32>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_identifiers_and_drops_stopwords() {
+        assert_eq!(
+            terms("Fix the InvoiceRounding in billing_service/invoices.rs"),
+            vec![
+                "fix", "invoice", "rounding", "billing", "service", "invoice", "rs"
+            ]
+        );
+    }
+
+    #[test]
+    fn ranks_matching_files_and_promotes_them_one_priority() {
+        let mut files = vec![
+            ContextFile::new(
+                "AGENTS.md",
+                "/repo/AGENTS.md",
+                "# Conventions\nRun cargo test before committing.",
+                ContextPriority::Critical,
+            ),
+            ContextFile::new(
+                "AGENTS.md",
+                "/repo/services/search/AGENTS.md",
+                "# Search service\nElasticsearch indexes are rebuilt nightly.",
+                ContextPriority::Normal,
+            ),
+            ContextFile::new(
+                "AGENTS.md",
+                "/repo/services/billing/AGENTS.md",
+                "# Billing service\nInvoices are rounded to the cent with banker's rounding.",
+                ContextPriority::Normal,
+            ),
+            ContextFile::new(
+                "oncall.md",
+                "caller://oncall.md",
+                "Billing invoice incidents go to #billing-oncall.",
+                ContextPriority::CallerSupplied,
+            ),
+        ];
+
+        rank(&mut files, "Why are billing invoices rounding wrong?");
+
+        let ranked: Vec<(&str, ContextPriority)> = files
+            .iter()
+            .map(|file| (file.path.as_str(), file.priority))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("/repo/AGENTS.md", ContextPriority::Critical),
+                ("/repo/services/search/AGENTS.md", ContextPriority::Normal),
+                ("/repo/services/billing/AGENTS.md", ContextPriority::High),
+                ("caller://oncall.md", ContextPriority::Normal),
+            ]
+        );
+        assert_eq!(files[1].relevance, 0.0);
+        assert!(files[2].relevance > files[3].relevance);
+    }
+
+    #[test]
+    fn empty_prompt_leaves_files_unranked() {
+        let mut files = vec![ContextFile::new(
+            "notes",
+            "/repo/notes",
+            "billing",
+            ContextPriority::CallerSupplied,
+        )];
+
+        rank(&mut files, "  the  ");
+
+        assert_eq!(files[0].priority, ContextPriority::CallerSupplied);
+        assert_eq!(files[0].relevance, 0.0);
+    }
+}
@@ -0,0 +1,249 @@
+//! Token counts for context budgeting.
+//!
+//! [`TokenEstimator`] does not load any vocabulary: it estimates from
+//! character classes, shaped after each family's pre-tokenization (words
+//! with their leading space, digit groups, punctuation runs, indentation).
+//! Counts are approximate and lean high on code, so budgets err on the safe
+//! side. An exact BPE counter can be plugged in through [`Tokenizer`].
+
+use std::fmt::Debug;
+
+/// Counts tokens for context budgeting.
+pub trait Tokenizer: Send + Sync + Debug {
+    fn count(&self, text: &str) -> usize;
+}
+
+/// Tokenizer families used by the supported providers, which set the
+/// estimator's rates. Code and large repositories are not undercounted the
+/// way a flat characters-per-token ratio undercounts them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizerFamily {
+    /// GPT-4 and GPT-3.5, rates approximating `cl100k_base`
+    Cl100k,
+    /// GPT-4o, GPT-4.1, GPT-5 and the o-series, rates approximating `o200k_base`
+    O200k,
+    /// Claude models
+    Claude,
+    /// Gemini models, with digits counted one per token
+    Gemini,
+    /// Unknown models; errs on the high side
+    Generic,
+}
+
+impl TokenizerFamily {
+    /// Family for a configured model, from its id and provider.
+    pub fn for_model(model_id: &str, provider: &str) -> Self {
+        let id = model_id.to_ascii_lowercase();
+        let id = id.rsplit('/').next().unwrap_or(&id);
+        if id.starts_with("claude") {
+            Self::Claude
+        } else if id.starts_with("gemini") || id.starts_with("gemma") {
+            Self::Gemini
+        } else if id.starts_with("gpt-4o")
+            || id.starts_with("gpt-4.1")
+            || id.starts_with("gpt-5")
+            || id.starts_with("chatgpt")
+            || id.starts_with("codex")
+            || (id.starts_with('o') && id.chars().nth(1).is_some_and(|c| c.is_ascii_digit()))
+        {
+            Self::O200k
+        } else if id.starts_with("gpt-") {
+            Self::Cl100k
+        } else {
+            match provider.to_ascii_lowercase().as_str() {
+                "anthropic" => Self::Claude,
+                "google" | "gemini" => Self::Gemini,
+                "openai" => Self::O200k,
+                _ => Self::Generic,
+            }
+        }
+    }
+
+    /// Letters a word's first token covers, and letters per token after it.
+    fn word_rates(self) -> (usize, f64) {
+        match self {
+            Self::Cl100k => (6, 4.0),
+            Self::O200k => (7, 4.5),
+            Self::Claude => (5, 3.5),
+            Self::Gemini => (6, 4.0),
+            Self::Generic => (5, 3.0),
+        }
+    }
+
+    /// Longest digit run merged into one token.
+    fn digits_per_token(self) -> usize {
+        match self {
+            Self::Gemini => 1,
+            _ => 3,
+        }
+    }
+
+    /// CJK and other non-Latin characters per token.
+    fn wide_chars_per_token(self) -> f64 {
+        match self {
+            Self::O200k => 1.4,
+            Self::Gemini => 1.2,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Heuristic token estimate for one [`TokenizerFamily`]; see the module docs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenEstimator {
+    family: TokenizerFamily,
+}
+
+impl TokenEstimator {
+    pub fn new(family: TokenizerFamily) -> Self {
+        Self { family }
+    }
+
+    pub fn for_model(model: &stakai::Model) -> Self {
+        Self::new(TokenizerFamily::for_model(&model.id, &model.provider))
+    }
+
+    pub fn family(&self) -> TokenizerFamily {
+        self.family
+    }
+}
+
+impl Default for TokenEstimator {
+    fn default() -> Self {
+        Self::new(TokenizerFamily::Generic)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Word,
+    Digit,
+    Space,
+    Newline,
+    Punct,
+    Wide,
+}
+
+fn char_class(c: char) -> CharClass {
+    match c {
+        '\n' | '\r' => CharClass::Newline,
+        c if c.is_whitespace() => CharClass::Space,
+        c if c.is_ascii_digit() => CharClass::Digit,
+        c if c.is_ascii_alphabetic() || c == '_' => CharClass::Word,
+        c if c.is_ascii() => CharClass::Punct,
+        c if is_wide(c) || !c.is_alphabetic() => CharClass::Wide,
+        _ => CharClass::Word,
+    }
+}
+
+/// CJK scripts, which the vocabularies encode at about a token per character.
+fn is_wide(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30ff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{ac00}'..='\u{d7af}'
+            | '\u{f900}'..='\u{faff}'
+    )
+}
+
+impl Tokenizer for TokenEstimator {
+    fn count(&self, text: &str) -> usize {
+        let (word_head, word_rate) = self.family.word_rates();
+        let mut tokens = 0;
+        let mut chars = text.chars().peekable();
+
+        while let Some(first) = chars.next() {
+            let class = char_class(first);
+            let mut len = 1;
+            while chars.peek().is_some_and(|&c| char_class(c) == class) {
+                chars.next();
+                len += 1;
+            }
+            let next = chars.peek().map(|&c| char_class(c));
+
+            tokens += match class {
+                CharClass::Word if len <= word_head => 1,
+                CharClass::Word => 1 + ((len - word_head) as f64 / word_rate).ceil() as usize,
+                CharClass::Digit => len.div_ceil(self.family.digits_per_token()),
+                // A single space is merged into the word or symbol after it.
+                CharClass::Space if len == 1 && next.is_some() => 0,
+                CharClass::Space | CharClass::Newline => len.div_ceil(8),
+                CharClass::Punct => len.div_ceil(2),
+                CharClass::Wide => {
+                    (len as f64 / self.family.wide_chars_per_token()).ceil() as usize
+                }
+            };
+        }
+
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(family: TokenizerFamily, text: &str) -> usize {
+        TokenEstimator::new(family).count(text)
+    }
+
+    #[test]
+    fn selects_family_from_model_id_then_provider() {
+        let cases = [
+            (
+                "claude-sonnet-4-5-20250929",
+                "anthropic",
+                TokenizerFamily::Claude,
+            ),
+            (
+                "anthropic/claude-opus-4-1",
+                "stakpak",
+                TokenizerFamily::Claude,
+            ),
+            ("gpt-4o-mini", "openai", TokenizerFamily::O200k),
+            ("o3", "openai", TokenizerFamily::O200k),
+            ("gpt-4-turbo", "openai", TokenizerFamily::Cl100k),
+            ("gemini-2.5-pro", "google", TokenizerFamily::Gemini),
+            ("my-finetune", "openai", TokenizerFamily::O200k),
+            ("llama3.1:70b", "ollama", TokenizerFamily::Generic),
+        ];
+        for (id, provider, family) in cases {
+            assert_eq!(TokenizerFamily::for_model(id, provider), family, "{id}");
+        }
+    }
+
+    #[test]
+    fn counts_words_digits_and_indentation() {
+        let family = TokenizerFamily::Cl100k;
+        assert_eq!(count(family, ""), 0);
+        assert_eq!(
+            count(family, "The quick brown fox jumps over the lazy dog."),
+            10
+        );
+        assert_eq!(count(family, "1234567"), 3);
+        assert_eq!(count(family, "truncate_with_marker"), 5);
+        assert_eq!(count(family, "fn main() {\n        run();\n}"), 11);
+        assert_eq!(count(TokenizerFamily::Gemini, "1234567"), 7);
+        assert_eq!(count(family, "配置文件"), 4);
+        assert_eq!(count(TokenizerFamily::O200k, "配置文件"), 3);
+    }
+
+    #[test]
+    fn code_counts_exceed_a_chars_per_token_estimate() {
+        let code = "if (x[i] != y[i]) { return -1; } // 0x7f3a\n".repeat(50);
+        let chars_estimate = code.chars().count() / 4;
+        for family in [
+            TokenizerFamily::Cl100k,
+            TokenizerFamily::O200k,
+            TokenizerFamily::Claude,
+        ] {
+            assert!(count(family, &code) > chars_estimate, "{family:?}");
+        }
+        assert!(
+            count(TokenizerFamily::Claude, &code) >= count(TokenizerFamily::O200k, &code),
+            "Claude's vocabulary is smaller than o200k's"
+        );
+    }
+}
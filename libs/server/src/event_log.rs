@@ -1,12 +1,14 @@
+use crate::hibernation::{HibernatedEvents, HibernationStats, HibernationStore};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use stakpak_agent_core::AgentEvent;
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     sync::{
-        Arc, Mutex,
-        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicBool, AtomicU64, Ordering},
     },
+    time::{Duration, Instant},
 };
 use tokio::sync::{RwLock, broadcast};
 use uuid::Uuid;
@@ -38,30 +40,69 @@ struct SessionEventBuffer {
     next_id: AtomicU64,
     ring: Mutex<VecDeque<EventEnvelope>>,
     tx: broadcast::Sender<EventEnvelope>,
+    last_activity: Mutex<Instant>,
+    /// Set once the buffer has been hibernated and removed from the log;
+    /// callers still holding it must look the session up again.
+    retired: AtomicBool,
 }
 
 impl SessionEventBuffer {
     fn new(capacity: usize) -> Self {
+        Self::restore(
+            capacity,
+            HibernatedEvents {
+                next_id: 1,
+                events: Vec::new(),
+            },
+        )
+    }
+
+    fn restore(capacity: usize, state: HibernatedEvents) -> Self {
         let (tx, _rx) = broadcast::channel(capacity.max(1) * 2);
+        let mut ring = VecDeque::with_capacity(capacity.max(1));
+        ring.extend(state.events);
+        while ring.len() > capacity.max(1) {
+            let _ = ring.pop_front();
+        }
         Self {
-            next_id: AtomicU64::new(1),
-            ring: Mutex::new(VecDeque::with_capacity(capacity.max(1))),
+            next_id: AtomicU64::new(state.next_id.max(1)),
+            ring: Mutex::new(ring),
             tx,
+            last_activity: Mutex::new(Instant::now()),
+            retired: AtomicBool::new(false),
         }
     }
 
-    fn lock_ring(&self) -> std::sync::MutexGuard<'_, VecDeque<EventEnvelope>> {
+    fn lock_ring(&self) -> MutexGuard<'_, VecDeque<EventEnvelope>> {
         match self.ring.lock() {
             Ok(guard) => guard,
             Err(poisoned) => poisoned.into_inner(),
         }
     }
+
+    fn touch(&self) {
+        let mut last_activity = match self.last_activity.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *last_activity = Instant::now();
+    }
+
+    /// Idle long enough to hibernate, with nobody streaming its events.
+    fn is_idle(&self, idle_after: Duration) -> bool {
+        let last_activity = match self.last_activity.lock() {
+            Ok(guard) => *guard,
+            Err(poisoned) => *poisoned.into_inner(),
+        };
+        self.tx.receiver_count() == 0 && last_activity.elapsed() >= idle_after
+    }
 }
 
 #[derive(Clone)]
 pub struct EventLog {
     capacity: usize,
     sessions: Arc<RwLock<HashMap<Uuid, Arc<SessionEventBuffer>>>>,
+    hibernation: Option<Arc<HibernationStore>>,
 }
 
 impl EventLog {
@@ -69,28 +110,50 @@ impl EventLog {
         Self {
             capacity: capacity.max(1),
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            hibernation: None,
         }
     }
 
+    /// Allow idle sessions to be hibernated into `store` (see
+    /// [`EventLog::hibernate_idle`]). Hibernated sessions are woken from the
+    /// store transparently on their next publish or subscribe.
+    pub fn with_hibernation(mut self, store: Arc<HibernationStore>) -> Self {
+        self.hibernation = Some(store);
+        self
+    }
+
+    pub fn hibernation_stats(&self) -> Option<HibernationStats> {
+        self.hibernation.as_ref().map(|store| store.stats())
+    }
+
+    /// Number of sessions whose events are currently held in memory.
+    pub async fn resident_sessions(&self) -> usize {
+        self.sessions.read().await.len()
+    }
+
     pub async fn publish(
         &self,
         session_id: Uuid,
         run_id: Option<Uuid>,
         event: AgentEvent,
     ) -> EventEnvelope {
-        let buffer = self.session_buffer(session_id).await;
-        let event_id = buffer.next_id.fetch_add(1, Ordering::SeqCst);
+        loop {
+            let buffer = self.session_buffer(session_id).await;
+            let mut ring = buffer.lock_ring();
+            if buffer.retired.load(Ordering::SeqCst) {
+                continue;
+            }
+            buffer.touch();
 
-        let envelope = EventEnvelope {
-            id: event_id,
-            session_id,
-            run_id,
-            timestamp: Utc::now(),
-            event,
-        };
+            let event_id = buffer.next_id.fetch_add(1, Ordering::SeqCst);
+            let envelope = EventEnvelope {
+                id: event_id,
+                session_id,
+                run_id,
+                timestamp: Utc::now(),
+                event,
+            };
 
-        {
-            let mut ring = buffer.lock_ring();
             ring.push_back(envelope.clone());
 
             while ring.len() > self.capacity {
@@ -98,19 +161,22 @@ impl EventLog {
             }
 
             let _ = buffer.tx.send(envelope.clone());
-        }
 
-        envelope
+            return envelope;
+        }
     }
 
     pub async fn subscribe(&self, session_id: Uuid, after_id: Option<u64>) -> EventSubscription {
-        let buffer = self.session_buffer(session_id).await;
-
-        let (replay, gap_detected, live) = {
+        let (replay, gap_detected, live) = loop {
+            let buffer = self.session_buffer(session_id).await;
             let ring = buffer.lock_ring();
+            if buffer.retired.load(Ordering::SeqCst) {
+                continue;
+            }
+            buffer.touch();
             let live = buffer.tx.subscribe();
 
-            match after_id {
+            break match after_id {
                 None => (Vec::new(), None, live),
                 Some(requested_after_id) => {
                     let oldest = ring.front().map(|event| event.id);
@@ -142,7 +208,7 @@ impl EventLog {
 
                     (replay, gap, live)
                 }
-            }
+            };
         };
 
         EventSubscription {
@@ -162,6 +228,67 @@ impl EventLog {
         Some((oldest, newest))
     }
 
+    /// Hibernate every session idle for at least `idle_after`, except those
+    /// in `keep`. Returns the hibernated session ids.
+    pub async fn hibernate_idle(&self, idle_after: Duration, keep: &HashSet<Uuid>) -> Vec<Uuid> {
+        let Some(store) = self.hibernation.as_ref() else {
+            return Vec::new();
+        };
+
+        let candidates: Vec<(Uuid, Arc<SessionEventBuffer>)> = {
+            let guard = self.sessions.read().await;
+            guard
+                .iter()
+                .filter(|(session_id, buffer)| {
+                    !keep.contains(session_id) && buffer.is_idle(idle_after)
+                })
+                .map(|(session_id, buffer)| (*session_id, buffer.clone()))
+                .collect()
+        };
+
+        let mut hibernated = Vec::new();
+        for (session_id, buffer) in candidates {
+            let state = {
+                let ring = buffer.lock_ring();
+                HibernatedEvents {
+                    next_id: buffer.next_id.load(Ordering::SeqCst),
+                    events: ring.iter().cloned().collect(),
+                }
+            };
+
+            if let Err(error) = store.save(session_id, &state).await {
+                tracing::warn!(session_id = %session_id, error = %error, "Failed to hibernate idle session");
+                continue;
+            }
+
+            // Only drop the buffer if nothing touched it while it was saved.
+            let removed = {
+                let mut guard = self.sessions.write().await;
+                let _ring = buffer.lock_ring();
+                let unchanged = buffer.next_id.load(Ordering::SeqCst) == state.next_id
+                    && buffer.is_idle(idle_after)
+                    && guard
+                        .get(&session_id)
+                        .is_some_and(|current| Arc::ptr_eq(current, &buffer));
+                if unchanged {
+                    buffer.retired.store(true, Ordering::SeqCst);
+                    guard.remove(&session_id);
+                }
+                unchanged
+            };
+
+            if removed {
+                store.record_hibernated();
+                tracing::debug!(session_id = %session_id, events = state.events.len(), "Hibernated idle session");
+                hibernated.push(session_id);
+            } else {
+                store.discard(session_id).await;
+            }
+        }
+
+        hibernated
+    }
+
     async fn session_buffer(&self, session_id: Uuid) -> Arc<SessionEventBuffer> {
         {
             let guard = self.sessions.read().await;
@@ -170,11 +297,42 @@ impl EventLog {
             }
         }
 
-        let mut guard = self.sessions.write().await;
-        guard
-            .entry(session_id)
-            .or_insert_with(|| Arc::new(SessionEventBuffer::new(self.capacity)))
-            .clone()
+        let restored = match self.hibernation.as_ref() {
+            Some(store) => match store.load(session_id).await {
+                Ok(restored) => restored,
+                Err(error) => {
+                    tracing::warn!(session_id = %session_id, error = %error, "Failed to wake hibernated session; starting with an empty event log");
+                    None
+                }
+            },
+            None => None,
+        };
+        let waking = restored.is_some();
+
+        let (buffer, inserted) = {
+            let mut guard = self.sessions.write().await;
+            match guard.get(&session_id) {
+                Some(existing) => (existing.clone(), false),
+                None => {
+                    let buffer = Arc::new(match restored {
+                        Some(state) => SessionEventBuffer::restore(self.capacity, state),
+                        None => SessionEventBuffer::new(self.capacity),
+                    });
+                    guard.insert(session_id, buffer.clone());
+                    (buffer, true)
+                }
+            }
+        };
+
+        if inserted
+            && waking
+            && let Some(store) = self.hibernation.as_ref()
+        {
+            store.mark_woken(session_id).await;
+            tracing::debug!(session_id = %session_id, "Woke hibernated session");
+        }
+
+        buffer
     }
 }
 
@@ -290,6 +448,68 @@ mod tests {
         assert_eq!(subscription.replay.len(), 4);
     }
 
+    fn hibernating_log(root: &std::path::Path) -> EventLog {
+        EventLog::new(8).with_hibernation(Arc::new(HibernationStore::new(root)))
+    }
+
+    #[tokio::test]
+    async fn hibernated_session_wakes_with_ids_and_replay_intact() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let log = hibernating_log(root.path());
+        let session_id = Uuid::new_v4();
+        let run_id = Uuid::new_v4();
+
+        for turn in 0..3 {
+            let _ = log
+                .publish(session_id, Some(run_id), turn_completed(run_id, turn))
+                .await;
+        }
+
+        let hibernated = log.hibernate_idle(Duration::ZERO, &HashSet::new()).await;
+        assert_eq!(hibernated, vec![session_id]);
+        assert_eq!(log.resident_sessions().await, 0);
+
+        let next = log
+            .publish(session_id, Some(run_id), turn_completed(run_id, 3))
+            .await;
+        assert_eq!(next.id, 4);
+
+        let subscription = log.subscribe(session_id, Some(0)).await;
+        let ids: Vec<u64> = subscription.replay.iter().map(|event| event.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+        assert_eq!(
+            log.hibernation_stats(),
+            Some(HibernationStats {
+                hibernated: 1,
+                woken: 1
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn hibernation_skips_streaming_and_kept_sessions() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let log = hibernating_log(root.path());
+        let streaming = Uuid::new_v4();
+        let kept = Uuid::new_v4();
+        let run_id = Uuid::new_v4();
+
+        let _ = log
+            .publish(streaming, Some(run_id), run_started(run_id))
+            .await;
+        let _ = log.publish(kept, Some(run_id), run_started(run_id)).await;
+        let _subscription = log.subscribe(streaming, None).await;
+
+        let keep = HashSet::from([kept]);
+        assert!(log.hibernate_idle(Duration::ZERO, &keep).await.is_empty());
+        assert!(
+            log.hibernate_idle(Duration::from_secs(3600), &HashSet::new())
+                .await
+                .is_empty()
+        );
+        assert_eq!(log.resident_sessions().await, 2);
+    }
+
     #[tokio::test]
     async fn replay_is_session_scoped() {
         let log = EventLog::new(8);
@@ -0,0 +1,203 @@
+//! Idle session hibernation.
+//!
+//! The replay ring each session keeps in the [`EventLog`](crate::EventLog)
+//! lives in memory for the life of the server. Once a session has been idle
+//! for the configured period (no run in flight, no pending tool approvals, no
+//! connected event stream), its ring is gzip-compressed to disk and dropped
+//! from memory. The next publish or subscribe for that session reads it back,
+//! so event ids and replay cursors carry on as if nothing happened.
+
+use crate::event_log::EventEnvelope;
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+use uuid::Uuid;
+
+/// Event ring state written to disk for a hibernated session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HibernatedEvents {
+    pub next_id: u64,
+    pub events: Vec<EventEnvelope>,
+}
+
+/// Hibernate/wake counters since server start.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct HibernationStats {
+    pub hibernated: u64,
+    pub woken: u64,
+}
+
+#[derive(Debug)]
+pub struct HibernationStore {
+    root: PathBuf,
+    hibernated: AtomicU64,
+    woken: AtomicU64,
+}
+
+impl HibernationStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let _ = std::fs::create_dir_all(&root);
+        Self {
+            root,
+            hibernated: AtomicU64::new(0),
+            woken: AtomicU64::new(0),
+        }
+    }
+
+    pub fn default_local() -> Self {
+        let root = std::env::var("HOME")
+            .map(|home| {
+                PathBuf::from(home)
+                    .join(".stakpak")
+                    .join("server")
+                    .join("hibernated")
+            })
+            .unwrap_or_else(|_| PathBuf::from(".stakpak").join("server").join("hibernated"));
+
+        Self::new(root)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn stats(&self) -> HibernationStats {
+        HibernationStats {
+            hibernated: self.hibernated.load(Ordering::Relaxed),
+            woken: self.woken.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Compress and write `state` for `session_id`, replacing any earlier one.
+    pub async fn save(&self, session_id: Uuid, state: &HibernatedEvents) -> Result<(), String> {
+        let json = serde_json::to_vec(state)
+            .map_err(|error| format!("Failed to serialize hibernated session: {error}"))?;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        let payload = encoder
+            .write_all(&json)
+            .and_then(|()| encoder.finish())
+            .map_err(|error| format!("Failed to compress hibernated session: {error}"))?;
+
+        let path = self.path(session_id);
+        let temp_path = path.with_extension("tmp");
+
+        tokio::fs::write(&temp_path, payload)
+            .await
+            .map_err(|error| {
+                format!(
+                    "Failed to write hibernated session {}: {}",
+                    temp_path.display(),
+                    error
+                )
+            })?;
+
+        tokio::fs::rename(&temp_path, &path)
+            .await
+            .map_err(|error| {
+                format!(
+                    "Failed to finalize hibernated session {}: {}",
+                    path.display(),
+                    error
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// Count a session whose saved state replaced its in-memory ring.
+    pub fn record_hibernated(&self) {
+        self.hibernated.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Read the hibernated state for `session_id`, if any. The file is left in
+    /// place until [`HibernationStore::mark_woken`] confirms it was restored.
+    pub async fn load(&self, session_id: Uuid) -> Result<Option<HibernatedEvents>, String> {
+        let path = self.path(session_id);
+        let payload = match tokio::fs::read(&path).await {
+            Ok(payload) => payload,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => {
+                return Err(format!(
+                    "Failed to read hibernated session {}: {}",
+                    path.display(),
+                    error
+                ));
+            }
+        };
+
+        let mut json = Vec::new();
+        GzDecoder::new(payload.as_slice())
+            .read_to_end(&mut json)
+            .map_err(|error| format!("Failed to decompress hibernated session: {error}"))?;
+        serde_json::from_slice(&json)
+            .map(Some)
+            .map_err(|error| format!("Failed to deserialize hibernated session: {error}"))
+    }
+
+    /// Remove the hibernated state after it has been restored into memory.
+    pub async fn mark_woken(&self, session_id: Uuid) {
+        self.discard(session_id).await;
+        self.woken.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Remove hibernated state without counting a wake, e.g. when the
+    /// session became active again before hibernation completed.
+    pub async fn discard(&self, session_id: Uuid) {
+        let _ = tokio::fs::remove_file(self.path(session_id)).await;
+    }
+
+    fn path(&self, session_id: Uuid) -> PathBuf {
+        self.root.join(format!("{session_id}.events.gz"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use stakpak_agent_core::AgentEvent;
+
+    #[tokio::test]
+    async fn save_load_discard_roundtrip_counts_hibernate_and_wake() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let store = HibernationStore::new(root.path());
+        let session_id = Uuid::new_v4();
+        let run_id = Uuid::new_v4();
+
+        let state = HibernatedEvents {
+            next_id: 8,
+            events: vec![EventEnvelope {
+                id: 7,
+                session_id,
+                run_id: Some(run_id),
+                timestamp: Utc::now(),
+                event: AgentEvent::RunStarted { run_id },
+            }],
+        };
+
+        store.save(session_id, &state).await.expect("save");
+        let loaded = store
+            .load(session_id)
+            .await
+            .expect("load")
+            .expect("hibernated state");
+        assert_eq!(loaded.next_id, 8);
+        assert_eq!(loaded.events.len(), 1);
+        assert_eq!(loaded.events[0].id, 7);
+
+        store.mark_woken(session_id).await;
+        assert!(store.load(session_id).await.expect("load").is_none());
+        assert_eq!(
+            store.stats(),
+            HibernationStats {
+                hibernated: 0,
+                woken: 1
+            }
+        );
+    }
+}
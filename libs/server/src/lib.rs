@@ -3,6 +3,7 @@ pub mod checkpoint_store;
 pub mod context;
 pub mod error;
 pub mod event_log;
+pub mod hibernation;
 pub mod idempotency;
 pub mod message_bridge;
 pub mod openapi;
@@ -12,18 +13,22 @@ pub mod session_actor;
 pub mod session_manager;
 pub mod state;
 pub mod types;
+pub mod usage;
 
 pub use auth::AuthConfig;
 pub use checkpoint_store::CheckpointStore;
 pub use context::{
-    ContextBudget, ContextFile, ContextPriority, EnvironmentContext, GitContext, ProjectContext,
-    SessionContext, SessionContextBuilder,
+    CachedMachineProfile, ContextBudget, ContextFile, ContextPriority, EnvironmentContext,
+    GitContext, InferenceSummarizer, MachineProfileSource, Memory, MemoryStore, MemoryTools,
+    ProjectContext, SessionContext, SessionContextBuilder, Summarizer, SummarizingCompactionEngine,
+    TokenEstimator, Tokenizer, TokenizerFamily,
 };
 pub use error::SessionManagerError;
 pub use event_log::{EventEnvelope, EventLog, EventSubscription, GapDetected};
+pub use hibernation::{HibernatedEvents, HibernationStats, HibernationStore};
 pub use idempotency::{IdempotencyRequest, IdempotencyStore, LookupResult, StoredResponse};
 pub use openapi::generate_openapi;
-pub use routes::{protected_router, public_router, router};
+pub use routes::{protected_router, public_router, resolve_tool_approval_override, router};
 pub use sandbox::{
     PersistentSandbox, SandboxConfig, SandboxHealth, SandboxMode, SandboxUserMapping,
     SandboxedMcpServer,
@@ -31,7 +36,9 @@ pub use sandbox::{
 pub use session_actor::{build_checkpoint_envelope, build_run_context, spawn_session_actor};
 pub use session_manager::SessionManager;
 pub use stakpak_agent_core::{
-    SAFE_AUTOPILOT_TOOLS, ToolApprovalAction, ToolApprovalPolicy, strip_tool_prefix,
+    SAFE_AUTOPILOT_TOOLS, ToolApprovalAction, ToolApprovalMatch, ToolApprovalPolicy,
+    strip_tool_prefix,
 };
 pub use state::AppState;
 pub use types::{AutoApproveOverride, RunConfig, RunOverrides, SessionHandle, SessionRuntimeState};
+pub use usage::{RunUsage, SessionUsage, UsageLedger, UsageTotals};
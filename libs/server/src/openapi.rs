@@ -13,6 +13,15 @@ pub struct HealthResponseDoc {
     pub status: String,
     pub version: String,
     pub uptime_seconds: u64,
+    pub hibernation: Option<HibernationStatusDoc>,
+}
+
+/// Present when idle session hibernation is enabled.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HibernationStatusDoc {
+    pub resident_sessions: usize,
+    pub hibernated_total: u64,
+    pub woken_total: u64,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -45,6 +54,7 @@ pub struct SessionDoc {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub run_status: RunStatusDoc,
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -63,6 +73,7 @@ pub struct SessionDetailResponseDoc {
 pub struct CreateSessionBodyDoc {
     pub title: String,
     pub cwd: Option<String>,
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -206,6 +217,7 @@ pub struct RunOverridesDoc {
     pub auto_approve: Option<AutoApproveOverrideDoc>,
     pub system_prompt: Option<String>,
     pub max_turns: Option<usize>,
+    pub env: Option<std::collections::BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -247,6 +259,37 @@ pub struct PendingToolsResponseDoc {
     pub tool_calls: Vec<ProposedToolCallDoc>,
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UsageTotalsDoc {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RunUsageDoc {
+    pub run_id: Uuid,
+    /// Model the run started on, as `provider/id`
+    pub model: String,
+    pub turns: usize,
+    pub usage: UsageTotalsDoc,
+    /// Estimated USD cost; null when the model's pricing is unknown
+    pub estimated_cost: Option<f64>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SessionUsageResponseDoc {
+    pub session_id: Uuid,
+    pub usage: UsageTotalsDoc,
+    pub estimated_cost: Option<f64>,
+    /// Runs in this server process, oldest first
+    pub runs: Vec<RunUsageDoc>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum DecisionActionDoc {
@@ -388,7 +431,8 @@ fn openapi_doc() {}
         ("limit" = Option<u32>, Query, description = "page size"),
         ("offset" = Option<u32>, Query, description = "page offset"),
         ("search" = Option<String>, Query, description = "title query"),
-        ("status" = Option<String>, Query, description = "ACTIVE or DELETED")
+        ("status" = Option<String>, Query, description = "ACTIVE or DELETED"),
+        ("tag" = Option<String>, Query, description = "only sessions created with this tag since the server started")
     ),
     responses(
         (status = 200, body = SessionsResponseDoc),
@@ -404,6 +448,7 @@ fn list_sessions_doc() {}
     request_body = CreateSessionBodyDoc,
     responses(
         (status = 201, body = SessionDoc),
+        (status = 400, body = ErrorResponseDoc),
         (status = 401, body = ErrorResponseDoc),
         (status = 409, body = ErrorResponseDoc)
     )
@@ -510,6 +555,19 @@ fn events_doc() {}
 )]
 fn pending_tools_doc() {}
 
+#[utoipa::path(
+    get,
+    path = "/v1/sessions/{id}/usage",
+    security(("bearer_auth" = [])),
+    params(("id" = Uuid, Path, description = "session id")),
+    responses(
+        (status = 200, body = SessionUsageResponseDoc),
+        (status = 401, body = ErrorResponseDoc),
+        (status = 404, body = ErrorResponseDoc)
+    )
+)]
+fn session_usage_doc() {}
+
 #[utoipa::path(
     post,
     path = "/v1/sessions/{id}/tools/{tool_call_id}/decision",
@@ -636,6 +694,7 @@ impl Modify for SecurityAddon {
         get_messages_doc,
         events_doc,
         pending_tools_doc,
+        session_usage_doc,
         tool_decision_doc,
         tool_decisions_doc,
         tool_resolve_doc,
@@ -647,6 +706,7 @@ impl Modify for SecurityAddon {
     components(
         schemas(
             HealthResponseDoc,
+            HibernationStatusDoc,
             ErrorResponseDoc,
             RunStateDoc,
             RunStatusDoc,
@@ -675,6 +735,9 @@ impl Modify for SecurityAddon {
             SessionMessagesResponseDoc,
             ProposedToolCallDoc,
             PendingToolsResponseDoc,
+            UsageTotalsDoc,
+            RunUsageDoc,
+            SessionUsageResponseDoc,
             DecisionActionDoc,
             DecisionInputDoc,
             ToolDecisionRequestDoc,
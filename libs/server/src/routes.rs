@@ -34,6 +34,16 @@ struct HealthResponse {
     uptime_seconds: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     sandbox: Option<SandboxStatusResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hibernation: Option<HibernationStatusResponse>,
+}
+
+#[derive(Debug, Serialize)]
+struct HibernationStatusResponse {
+    /// Sessions whose event logs are currently held in memory.
+    resident_sessions: usize,
+    hibernated_total: u64,
+    woken_total: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -77,6 +87,8 @@ struct SessionDto {
     created_at: chrono::DateTime<chrono::Utc>,
     updated_at: chrono::DateTime<chrono::Utc>,
     run_status: RunStatusDto,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -90,6 +102,8 @@ struct CreateSessionBody {
     title: String,
     #[serde(default)]
     cwd: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -141,6 +155,8 @@ struct ListSessionsParams {
     search: Option<String>,
     #[serde(default)]
     status: Option<String>,
+    #[serde(default)]
+    tag: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -250,6 +266,9 @@ const DEFAULT_MAX_TURNS: usize = 64;
 const MIN_MAX_TURNS: usize = 1;
 const MAX_MAX_TURNS: usize = 256;
 const MAX_SYSTEM_PROMPT_CHARS: usize = 32 * 1024;
+const MAX_COMMAND_ENV_VARS: usize = 128;
+const MAX_SESSION_TAGS: usize = 16;
+const MAX_SESSION_TAG_CHARS: usize = 128;
 
 pub fn router(state: AppState, auth: AuthConfig) -> Router {
     public_router()
@@ -296,6 +315,7 @@ pub fn protected_router(auth: AuthConfig) -> Router<AppState> {
             "/v1/sessions/{id}/tools/resolve",
             post(tool_decisions_handler),
         )
+        .route("/v1/sessions/{id}/usage", get(session_usage_handler))
         .route("/v1/sessions/{id}/cancel", post(cancel_handler))
         .route("/v1/sessions/{id}/model", post(model_switch_handler))
         .route("/v1/models", get(models_handler))
@@ -316,11 +336,21 @@ async fn health_handler(State(state): State<AppState>) -> Json<HealthResponse> {
         }
     });
 
+    let hibernation = match state.events.hibernation_stats() {
+        Some(stats) if state.hibernate_after.is_some() => Some(HibernationStatusResponse {
+            resident_sessions: state.events.resident_sessions().await,
+            hibernated_total: stats.hibernated,
+            woken_total: stats.woken,
+        }),
+        _ => None,
+    };
+
     Json(HealthResponse {
         status: "ok",
         version: env!("CARGO_PKG_VERSION"),
         uptime_seconds: state.uptime_seconds(),
         sandbox,
+        hibernation,
     })
 }
 
@@ -332,6 +362,10 @@ async fn list_sessions_handler(
     State(state): State<AppState>,
     Query(params): Query<ListSessionsParams>,
 ) -> Result<Json<SessionsResponse>, Response> {
+    if let Some(tag) = params.tag.as_deref() {
+        return list_tagged_sessions(&state, tag).await.map(Json);
+    }
+
     let mut query = ListSessionsQuery::new();
 
     if let Some(limit) = params.limit {
@@ -363,6 +397,7 @@ async fn list_sessions_handler(
             created_at: summary.created_at,
             updated_at: summary.updated_at,
             run_status: map_run_status(run_status),
+            tags: state.session_tags(summary.id).await,
         });
     }
 
@@ -372,6 +407,48 @@ async fn list_sessions_handler(
     }))
 }
 
+/// Sessions created with `tag`, newest first. Tags live in memory, so only
+/// sessions created since the server started are found.
+async fn list_tagged_sessions(state: &AppState, tag: &str) -> Result<SessionsResponse, Response> {
+    let mut sessions = Vec::new();
+    for session_id in state.sessions_tagged(tag).await {
+        let session = match state.session_store.get_session(session_id).await {
+            Ok(session) => session,
+            Err(stakpak_api::StorageError::NotFound(_)) => continue,
+            Err(error) => return Err(storage_error(error)),
+        };
+        let run_status = state.run_manager.state(session_id).await;
+        sessions.push(SessionDto {
+            id: session.id,
+            title: session.title,
+            cwd: session.cwd,
+            created_at: session.created_at,
+            updated_at: session.updated_at,
+            run_status: map_run_status(run_status),
+            tags: state.session_tags(session_id).await,
+        });
+    }
+    sessions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    Ok(SessionsResponse {
+        total: sessions.len(),
+        sessions,
+    })
+}
+
+fn validate_session_tags(tags: &[String]) -> Result<(), &'static str> {
+    if tags.len() > MAX_SESSION_TAGS {
+        return Err("too many tags");
+    }
+    if tags
+        .iter()
+        .any(|tag| tag.trim().is_empty() || tag.chars().count() > MAX_SESSION_TAG_CHARS)
+    {
+        return Err("tags must be non-empty and at most 128 characters");
+    }
+    Ok(())
+}
+
 async fn create_session_handler(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -390,10 +467,15 @@ async fn create_session_handler(
         return Ok(replayed);
     }
 
+    if let Err(message) = validate_session_tags(&body.tags) {
+        return Err(api_error(StatusCode::BAD_REQUEST, "invalid_tags", message));
+    }
+
     let mut request = StorageCreateSessionRequest::new(body.title, Vec::new());
     if let Some(cwd) = body.cwd {
         request = request.with_cwd(cwd);
     }
+    let tags = body.tags;
 
     let created = state
         .session_store
@@ -406,6 +488,7 @@ async fn create_session_handler(
         .get_session(created.session_id)
         .await
         .map_err(storage_error)?;
+    state.set_session_tags(session.id, tags.clone()).await;
 
     let payload = SessionDto {
         id: session.id,
@@ -414,6 +497,7 @@ async fn create_session_handler(
         created_at: session.created_at,
         updated_at: session.updated_at,
         run_status: map_run_status(SessionRuntimeState::Idle),
+        tags,
     };
 
     save_idempotency_response(&state, idempotency.request, StatusCode::CREATED, &payload).await;
@@ -441,6 +525,7 @@ async fn get_session_handler(
             created_at: session.created_at,
             updated_at: session.updated_at,
             run_status: map_run_status(run_status),
+            tags: state.session_tags(session_id).await,
         },
         config: runtime_config(&state),
     }))
@@ -475,6 +560,7 @@ async fn update_session_handler(
             created_at: session.created_at,
             updated_at: session.updated_at,
             run_status: map_run_status(run_status),
+            tags: state.session_tags(session_id).await,
         },
         config: runtime_config(&state),
     }))
@@ -493,6 +579,8 @@ async fn delete_session_handler(
         .delete_session(session_id)
         .await
         .map_err(storage_error)?;
+    state.set_session_tags(session_id, Vec::new()).await;
+    state.usage.remove(session_id).await;
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -580,6 +668,9 @@ async fn sessions_message_handler(
                 tool_approval_policy,
                 system_prompt: system_prompt_override,
                 max_turns,
+                command_env: overrides
+                    .and_then(|value| value.env.clone())
+                    .unwrap_or_default(),
             };
 
             let caller_context = map_caller_context_inputs(request.context.as_deref());
@@ -741,6 +832,28 @@ async fn pending_tools_handler(
     }))
 }
 
+async fn session_usage_handler(
+    State(state): State<AppState>,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<crate::SessionUsage>, Response> {
+    if let Some(usage) = state.usage.session(session_id).await {
+        return Ok(Json(usage));
+    }
+
+    // Sessions without runs in this process have no usage yet.
+    state
+        .session_store
+        .get_session(session_id)
+        .await
+        .map_err(storage_error)?;
+    Ok(Json(crate::SessionUsage {
+        session_id,
+        usage: Default::default(),
+        estimated_cost: None,
+        runs: Vec::new(),
+    }))
+}
+
 async fn tool_decision_handler(
     State(state): State<AppState>,
     Path((session_id, tool_call_id)): Path<(Uuid, String)>,
@@ -888,9 +1001,17 @@ async fn model_switch_handler(
 
     state
         .run_manager
-        .send_command(session_id, request.run_id, AgentCommand::SwitchModel(model))
+        .send_command(
+            session_id,
+            request.run_id,
+            AgentCommand::SwitchModel(model.clone()),
+        )
         .await
         .map_err(run_manager_error)?;
+    state
+        .usage
+        .switch_model(session_id, request.run_id, &model)
+        .await;
 
     let payload = ModelSwitchResponse {
         accepted: true,
@@ -937,7 +1058,9 @@ fn runtime_config(state: &AppState) -> ConfigResponse {
     }
 }
 
-fn resolve_tool_approval_override(
+/// Tool approval policy for a run: the run's `auto_approve` override when it
+/// has one, otherwise the server default.
+pub fn resolve_tool_approval_override(
     override_value: Option<&AutoApproveOverride>,
     default: &stakpak_agent_core::ToolApprovalPolicy,
 ) -> stakpak_agent_core::ToolApprovalPolicy {
@@ -1098,6 +1221,26 @@ fn validate_session_message_request(request: &SessionMessageRequest) -> Option<R
                 "system_prompt exceeds maximum length",
             ));
         }
+
+        if let Some(env) = overrides.env.as_ref() {
+            if env.len() > MAX_COMMAND_ENV_VARS {
+                return Some(api_error(
+                    StatusCode::BAD_REQUEST,
+                    "invalid_overrides",
+                    "env has too many variables",
+                ));
+            }
+
+            if env.iter().any(|(key, value)| {
+                key.is_empty() || key.contains(['=', '\0']) || value.contains('\0')
+            }) {
+                return Some(api_error(
+                    StatusCode::BAD_REQUEST,
+                    "invalid_overrides",
+                    "env keys must be non-empty and must not contain '=' or NUL",
+                ));
+            }
+        }
     }
 
     None
@@ -1680,6 +1823,82 @@ mod tests {
         assert!(parsed.get("run_id").is_some());
     }
 
+    #[tokio::test]
+    async fn sessions_list_filters_by_tag() {
+        let app = match test_state().await {
+            Ok(state) => router(state.clone(), AuthConfig::token("secret")),
+            Err(error) => panic!("failed to create app state: {error}"),
+        };
+
+        let mut tagged_id = None;
+        for payload in [
+            json!({"title":"tagged","tags":["schedule:backup"]}),
+            json!({"title":"untagged"}),
+        ] {
+            let request = match Request::builder()
+                .method("POST")
+                .uri("/v1/sessions")
+                .header(AUTHORIZATION, "Bearer secret")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(payload.to_string()))
+            {
+                Ok(request) => request,
+                Err(error) => panic!("failed to build create request: {error}"),
+            };
+            let response = match app.clone().oneshot(request).await {
+                Ok(response) => response,
+                Err(error) => panic!("create request should succeed: {error}"),
+            };
+            assert_eq!(response.status(), StatusCode::CREATED);
+            let body = match to_bytes(response.into_body(), 1024 * 1024).await {
+                Ok(body) => body,
+                Err(error) => panic!("failed to read create response body: {error}"),
+            };
+            let created: serde_json::Value = match serde_json::from_slice(&body) {
+                Ok(value) => value,
+                Err(error) => panic!("invalid create response json: {error}"),
+            };
+            if created.get("tags").is_some() {
+                tagged_id = created.get("id").cloned();
+            }
+        }
+
+        let request = match Request::builder()
+            .method("GET")
+            .uri("/v1/sessions?tag=schedule:backup")
+            .header(AUTHORIZATION, "Bearer secret")
+            .body(Body::empty())
+        {
+            Ok(request) => request,
+            Err(error) => panic!("failed to build list request: {error}"),
+        };
+        let response = match app.oneshot(request).await {
+            Ok(response) => response,
+            Err(error) => panic!("list request should succeed: {error}"),
+        };
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = match to_bytes(response.into_body(), 1024 * 1024).await {
+            Ok(body) => body,
+            Err(error) => panic!("failed to read list response body: {error}"),
+        };
+        let listed: serde_json::Value = match serde_json::from_slice(&body) {
+            Ok(value) => value,
+            Err(error) => panic!("invalid list response json: {error}"),
+        };
+
+        assert_eq!(listed["total"], json!(1));
+        assert_eq!(listed["sessions"][0].get("id"), tagged_id.as_ref());
+        assert_eq!(listed["sessions"][0]["tags"], json!(["schedule:backup"]));
+    }
+
+    #[test]
+    fn validate_session_tags_rejects_blank_and_oversized_tags() {
+        assert!(validate_session_tags(&["schedule:backup".to_string()]).is_ok());
+        assert!(validate_session_tags(&[" ".to_string()]).is_err());
+        assert!(validate_session_tags(&["x".repeat(MAX_SESSION_TAG_CHARS + 1)]).is_err());
+        assert!(validate_session_tags(&vec!["t".to_string(); MAX_SESSION_TAGS + 1]).is_err());
+    }
+
     #[tokio::test]
     async fn sessions_messages_rejects_non_user_role_input() {
         let app = match test_state().await {
@@ -2597,6 +2816,103 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn usage_endpoint_returns_recorded_run_usage() {
+        let state = match test_state().await {
+            Ok(state) => state,
+            Err(error) => panic!("failed to create app state: {error}"),
+        };
+
+        let app = router(state.clone(), AuthConfig::token("secret"));
+
+        let create_request = match Request::builder()
+            .method("POST")
+            .uri("/v1/sessions")
+            .header(AUTHORIZATION, "Bearer secret")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(json!({"title":"usage"}).to_string()))
+        {
+            Ok(request) => request,
+            Err(error) => panic!("failed to build create request: {error}"),
+        };
+
+        let create_response = match app.clone().oneshot(create_request).await {
+            Ok(response) => response,
+            Err(error) => panic!("create request should succeed: {error}"),
+        };
+
+        let create_body = match to_bytes(create_response.into_body(), 1024 * 1024).await {
+            Ok(body) => body,
+            Err(error) => panic!("failed to read create body: {error}"),
+        };
+
+        let create_json: serde_json::Value = match serde_json::from_slice(&create_body) {
+            Ok(value) => value,
+            Err(error) => panic!("invalid create json: {error}"),
+        };
+
+        let session_uuid = match create_json
+            .get("id")
+            .and_then(|value| value.as_str())
+            .map(Uuid::parse_str)
+        {
+            Some(Ok(value)) => value,
+            _ => panic!("missing or invalid session id"),
+        };
+
+        let get_usage = |app: Router| async move {
+            let request = match Request::builder()
+                .method("GET")
+                .uri(format!("/v1/sessions/{session_uuid}/usage"))
+                .header(AUTHORIZATION, "Bearer secret")
+                .body(Body::empty())
+            {
+                Ok(request) => request,
+                Err(error) => panic!("failed to build usage request: {error}"),
+            };
+            let response = match app.oneshot(request).await {
+                Ok(response) => response,
+                Err(error) => panic!("usage request should succeed: {error}"),
+            };
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = match to_bytes(response.into_body(), 1024 * 1024).await {
+                Ok(body) => body,
+                Err(error) => panic!("failed to read usage body: {error}"),
+            };
+            match serde_json::from_slice::<serde_json::Value>(&body) {
+                Ok(value) => value,
+                Err(error) => panic!("invalid usage json: {error}"),
+            }
+        };
+
+        let empty = get_usage(app.clone()).await;
+        assert_eq!(empty.pointer("/usage/total_tokens"), Some(&json!(0)));
+        assert_eq!(empty.get("runs"), Some(&json!([])));
+
+        let run_id = Uuid::new_v4();
+        state
+            .usage
+            .start_run(
+                session_uuid,
+                run_id,
+                &stakai::Model::custom("llama3", "ollama"),
+            )
+            .await;
+        state
+            .usage
+            .record(session_uuid, run_id, &stakai::Usage::new(1200, 34))
+            .await;
+
+        let usage = get_usage(app).await;
+        assert_eq!(usage.pointer("/usage/total_tokens"), Some(&json!(1234)));
+        assert_eq!(usage.pointer("/runs/0/run_id"), Some(&json!(run_id)));
+        assert_eq!(
+            usage.pointer("/runs/0/model"),
+            Some(&json!("ollama/llama3"))
+        );
+        assert_eq!(usage.get("estimated_cost"), Some(&serde_json::Value::Null));
+    }
+
     #[tokio::test]
     async fn model_switch_rejects_run_mismatch() {
         let state = match test_state().await {
@@ -3009,6 +3325,38 @@ mod tests {
         assert!(validate_session_message_request(&request_max).is_none());
     }
 
+    #[test]
+    fn validate_session_message_request_rejects_invalid_env_override() {
+        let request = SessionMessageRequest {
+            message: stakai::Message::new(stakai::Role::User, "hello"),
+            r#type: SessionMessageType::Message,
+            run_id: None,
+            model: None,
+            sandbox: None,
+            context: None,
+            overrides: Some(RunOverrides {
+                env: Some(std::collections::BTreeMap::from([(
+                    "BAD=KEY".to_string(),
+                    "value".to_string(),
+                )])),
+                ..RunOverrides::default()
+            }),
+        };
+        assert!(validate_session_message_request(&request).is_some());
+
+        let request_valid = SessionMessageRequest {
+            overrides: Some(RunOverrides {
+                env: Some(std::collections::BTreeMap::from([(
+                    "APP_ENV".to_string(),
+                    "ci".to_string(),
+                )])),
+                ..RunOverrides::default()
+            }),
+            ..request
+        };
+        assert!(validate_session_message_request(&request_valid).is_none());
+    }
+
     #[test]
     fn validate_session_message_request_rejects_oversized_system_prompt_override() {
         let request = SessionMessageRequest {
@@ -1,5 +1,8 @@
 use crate::{
-    context::{ContextFile, EnvironmentContext, ProjectContext, SessionContextBuilder},
+    context::{
+        ContextFile, EnvironmentContext, InferenceSummarizer, MemoryStore, MemoryTools,
+        ProjectContext, SessionContextBuilder, SummarizingCompactionEngine,
+    },
     message_bridge,
     sandbox::{SandboxConfig, SandboxMode, SandboxedMcpServer},
     state::AppState,
@@ -14,19 +17,22 @@ use serde_json::json;
 use stakai::{ContentPart, Message, MessageContent, Role};
 use stakpak_agent_core::{
     AgentCommand, AgentConfig, AgentEvent, AgentHook, AgentRunContext, BudgetAwareContextReducer,
-    CheckpointEnvelopeV1, CompactionConfig, PassthroughCompactionEngine, ProposedToolCall,
-    RetryConfig, ToolExecutionResult, ToolExecutor, run_agent,
+    CheckpointEnvelopeV1, CompactionConfig, ProposedToolCall, RetryConfig, ToolExecutionResult,
+    ToolExecutor, run_agent,
 };
 use stakpak_api::CreateCheckpointRequest;
 use stakpak_mcp_client::McpClient;
+use stakpak_shared::context_rules::ContextRules;
 use stakpak_shared::utils::sanitize_text_output;
-use std::{path::Path, sync::Arc};
+use std::{collections::BTreeMap, path::Path, sync::Arc};
 use tokio::sync::{Mutex, mpsc};
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 const CHECKPOINT_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
 pub(crate) const ACTIVE_MODEL_METADATA_KEY: &str = "active_model";
+/// Memories recalled into the context of a new session, before the budget.
+const RECALLED_MEMORIES: usize = 20;
 
 pub fn build_run_context(session_id: Uuid, run_id: Uuid) -> AgentRunContext {
     AgentRunContext { run_id, session_id }
@@ -56,6 +62,10 @@ pub fn spawn_session_actor(
 
     let state_for_task = state.clone();
     tokio::spawn(async move {
+        state_for_task
+            .usage
+            .start_run(session_id, run_id, &run_config.model)
+            .await;
         let actor_result = run_session_actor(
             state_for_task.clone(),
             session_id,
@@ -69,6 +79,7 @@ pub fn spawn_session_actor(
         )
         .await;
 
+        state_for_task.usage.finish_run(session_id, run_id).await;
         let finish_result = actor_result.map(|_| ());
         let _ = state_for_task
             .run_manager
@@ -127,52 +138,93 @@ async fn run_session_actor(
     // shut it down at the end. Persistent sandboxes are not owned by the session.
     let mut ephemeral_sandbox: Option<SandboxedMcpServer> = None;
 
-    let (run_tools, tool_executor): (Vec<stakai::Tool>, Box<dyn ToolExecutor + Send + Sync>) =
-        if let Some(ref sandbox_cfg) = sandbox_config {
-            if let Some(ref persistent) = state.persistent_sandbox {
-                // Persistent mode: reuse the pre-spawned sandbox
-                tracing::info!(session_id = %session_id, "Using persistent sandbox for session");
-                (
-                    persistent.tools().await,
-                    Box::new(SandboxedToolExecutor {
-                        mcp_client: persistent.client().await,
-                    }),
-                )
-            } else if sandbox_cfg.mode == SandboxMode::Persistent {
-                // Persistent mode was configured but the sandbox is not available.
-                // This should not happen because the server hard-fails on startup
-                // if the persistent sandbox cannot be spawned. Fail explicitly rather
-                // than silently falling back to ephemeral mode.
-                return Err(format!(
-                    "Sandbox mode is 'persistent' but no persistent sandbox is available for session {session_id}. \
+    // Host paths mean nothing inside a sandbox container, so only the
+    // environment is forwarded to sandboxed tool calls.
+    let sandbox_scope = ToolCallScope {
+        cwd: None,
+        env: run_config.command_env.clone(),
+    };
+
+    let (mut run_tools, mut tool_executor): (
+        Vec<stakai::Tool>,
+        Box<dyn ToolExecutor + Send + Sync>,
+    ) = if let Some(ref sandbox_cfg) = sandbox_config {
+        if let Some(ref persistent) = state.persistent_sandbox {
+            // Persistent mode: reuse the pre-spawned sandbox
+            tracing::info!(session_id = %session_id, "Using persistent sandbox for session");
+            (
+                persistent.tools().await,
+                Box::new(SandboxedToolExecutor {
+                    mcp_client: persistent.client().await,
+                    scope: sandbox_scope,
+                }),
+            )
+        } else if sandbox_cfg.mode == SandboxMode::Persistent {
+            // Persistent mode was configured but the sandbox is not available.
+            // This should not happen because the server hard-fails on startup
+            // if the persistent sandbox cannot be spawned. Fail explicitly rather
+            // than silently falling back to ephemeral mode.
+            return Err(format!(
+                "Sandbox mode is 'persistent' but no persistent sandbox is available for session {session_id}. \
                      This indicates the server started without a healthy sandbox. Restart the autopilot to fix."
-                ));
-            } else {
-                // Ephemeral mode: spawn a new sandbox for this session
-                tracing::info!(session_id = %session_id, image = %sandbox_cfg.image, "Spawning ephemeral sandbox container for session");
-                let sandbox = SandboxedMcpServer::spawn(sandbox_cfg).await.map_err(|e| {
-                    format!("Failed to start sandbox for session {session_id}: {e}")
-                })?;
-                let tools = sandbox.tools.clone();
-                let client = sandbox.client.clone();
-                ephemeral_sandbox = Some(sandbox);
-                (
-                    tools,
-                    Box::new(SandboxedToolExecutor { mcp_client: client }),
-                )
-            }
+            ));
         } else {
+            // Ephemeral mode: spawn a new sandbox for this session
+            tracing::info!(session_id = %session_id, image = %sandbox_cfg.image, "Spawning ephemeral sandbox container for session");
+            let sandbox = SandboxedMcpServer::spawn(sandbox_cfg)
+                .await
+                .map_err(|e| format!("Failed to start sandbox for session {session_id}: {e}"))?;
+            let tools = sandbox.tools.clone();
+            let client = sandbox.client.clone();
+            ephemeral_sandbox = Some(sandbox);
             (
-                state.current_mcp_tools().await,
-                Box::new(ServerToolExecutor {
-                    state: state.clone(),
+                tools,
+                Box::new(SandboxedToolExecutor {
+                    mcp_client: client,
+                    scope: sandbox_scope,
                 }),
             )
-        };
+        }
+    } else {
+        (
+            state.current_mcp_tools().await,
+            Box::new(ServerToolExecutor {
+                state: state.clone(),
+                scope: ToolCallScope {
+                    cwd: explicit_session_cwd(&state, session_id).await,
+                    env: run_config.command_env.clone(),
+                },
+            }),
+        )
+    };
 
     let is_new_session = is_new_session_history(&initial_messages);
     let session_cwd = resolve_session_cwd(&state, session_id).await;
-    let environment = EnvironmentContext::snapshot(&session_cwd).await;
+    let context_rules = ContextRules::load(Path::new(&session_cwd), &state.context_rules);
+    let environment = EnvironmentContext::snapshot_with_rules(&session_cwd, &context_rules).await;
+
+    // Memories live on the host, keyed by the session's workspace, whether
+    // or not the other tools run in a sandbox.
+    let mut memories = Vec::new();
+    if let Some(store) = &state.memory {
+        let workspace = MemoryStore::workspace(Path::new(&session_cwd));
+        memories = store
+            .recall(
+                &workspace,
+                &user_message.text().unwrap_or_default(),
+                RECALLED_MEMORIES,
+            )
+            .await
+            .unwrap_or_else(|error| {
+                tracing::warn!(session_id = %session_id, "Failed to recall memories: {error}");
+                Vec::new()
+            });
+        run_tools.extend(MemoryTools::definitions());
+        tool_executor = Box::new(MemoryToolExecutor {
+            memory: MemoryTools::new(store.clone(), workspace, Some(session_id)),
+            inner: tool_executor,
+        });
+    }
 
     // Combine caller context with pre-loaded remote skills context from AppState.
     // Explicit caller context should force per-turn injection, even on resumed
@@ -181,10 +233,17 @@ async fn run_session_actor(
     let mut all_caller_context = caller_context;
     all_caller_context.extend(state.current_skills().await);
 
-    let project =
-        ProjectContext::discover(Path::new(&session_cwd)).with_caller_context(all_caller_context);
+    let project = ProjectContext::discover_with_rules(Path::new(&session_cwd), &context_rules)
+        .with_caller_context(all_caller_context)
+        .rank_for_prompt(&user_message.text().unwrap_or_default());
+
+    let machine_profile = state
+        .machine_profile
+        .as_ref()
+        .and_then(|source| source.machine_profile(Path::new(&session_cwd)));
 
-    let session_context = SessionContextBuilder::new()
+    let context_budget = state.context_budget.for_model(&run_config.model);
+    let mut context_builder = SessionContextBuilder::new()
         .base_system_prompt(
             run_config
                 .system_prompt
@@ -194,9 +253,13 @@ async fn run_session_actor(
         )
         .environment(environment)
         .project(project)
+        .memories(memories)
         .tools(&run_tools)
-        .budget(state.context_budget.clone())
-        .build();
+        .budget(context_budget.clone());
+    if let Some(profile) = machine_profile {
+        context_builder = context_builder.machine_profile(profile);
+    }
+    let session_context = context_builder.build();
 
     if (is_new_session || has_runtime_caller_context)
         && let Some(context_block) = session_context.user_context_block.as_deref()
@@ -268,8 +331,11 @@ async fn run_session_actor(
         checkpoint_runtime: checkpoint_runtime.clone(),
     })];
 
-    let compactor = PassthroughCompactionEngine;
-    let context_reducer = BudgetAwareContextReducer::new(5, 0.8);
+    let compactor = SummarizingCompactionEngine::new(
+        Arc::new(InferenceSummarizer::new(run_config.inference.clone())),
+        context_budget.clone(),
+    );
+    let context_reducer = BudgetAwareContextReducer::new(5, context_budget.history_max_ratio);
     let run_context = build_run_context(session_id, run_id);
 
     let run_result = run_agent(
@@ -328,12 +394,20 @@ fn is_new_session_history(messages: &[Message]) -> bool {
         .any(|message| matches!(message.role, Role::User | Role::Assistant | Role::Tool))
 }
 
+/// The cwd the API caller set on the session, if any.
+async fn explicit_session_cwd(state: &AppState, session_id: Uuid) -> Option<String> {
+    state
+        .session_store
+        .get_session(session_id)
+        .await
+        .ok()
+        .and_then(|session| session.cwd)
+        .filter(|cwd| !cwd.trim().is_empty())
+}
+
 async fn resolve_session_cwd(state: &AppState, session_id: Uuid) -> String {
     // 1. Session-specific cwd (set by API caller)
-    if let Ok(session) = state.session_store.get_session(session_id).await
-        && let Some(cwd) = session.cwd
-        && !cwd.trim().is_empty()
-    {
+    if let Some(cwd) = explicit_session_cwd(state, session_id).await {
         return cwd;
     }
 
@@ -381,6 +455,9 @@ async fn handle_core_event(state: &AppState, session_id: Uuid, run_id: Uuid, eve
                 .set_pending_tools(session_id, run_id, tool_calls.clone())
                 .await;
         }
+        AgentEvent::UsageReport { usage, .. } => {
+            state.usage.record(session_id, run_id, usage).await;
+        }
         AgentEvent::TurnCompleted { .. }
         | AgentEvent::RunCompleted { .. }
         | AgentEvent::RunError { .. } => {
@@ -392,9 +469,38 @@ async fn handle_core_event(state: &AppState, session_id: Uuid, run_id: Uuid, eve
     state.events.publish(session_id, Some(run_id), event).await;
 }
 
+/// Working directory and extra environment forwarded to the MCP server as
+/// call metadata, so commands run where and how the run asked for.
+#[derive(Clone, Default)]
+struct ToolCallScope {
+    cwd: Option<String>,
+    env: BTreeMap<String, String>,
+}
+
+impl ToolCallScope {
+    fn extend_metadata(&self, metadata: &mut serde_json::Map<String, serde_json::Value>) {
+        if let Some(cwd) = &self.cwd {
+            metadata.insert("cwd".to_string(), serde_json::Value::String(cwd.clone()));
+        }
+
+        if !self.env.is_empty() {
+            metadata.insert(
+                "env".to_string(),
+                serde_json::Value::Object(
+                    self.env
+                        .iter()
+                        .map(|(key, value)| (key.clone(), serde_json::Value::String(value.clone())))
+                        .collect(),
+                ),
+            );
+        }
+    }
+}
+
 #[derive(Clone)]
 struct ServerToolExecutor {
     state: AppState,
+    scope: ToolCallScope,
 }
 
 #[async_trait]
@@ -405,7 +511,52 @@ impl ToolExecutor for ServerToolExecutor {
         tool_call: &ProposedToolCall,
         cancel: &CancellationToken,
     ) -> Result<ToolExecutionResult, stakpak_agent_core::AgentError> {
-        Ok(execute_mcp_tool_call(&self.state, run.session_id, run.run_id, tool_call, cancel).await)
+        Ok(execute_mcp_tool_call(
+            &self.state,
+            run.session_id,
+            run.run_id,
+            tool_call,
+            &self.scope,
+            cancel,
+        )
+        .await)
+    }
+}
+
+/// Tool executor that answers memory tools from the memory store and hands
+/// every other call to `inner`.
+struct MemoryToolExecutor {
+    memory: MemoryTools,
+    inner: Box<dyn ToolExecutor + Send + Sync>,
+}
+
+#[async_trait]
+impl ToolExecutor for MemoryToolExecutor {
+    async fn execute_tool_call(
+        &self,
+        run: &AgentRunContext,
+        tool_call: &ProposedToolCall,
+        cancel: &CancellationToken,
+    ) -> Result<ToolExecutionResult, stakpak_agent_core::AgentError> {
+        if !MemoryTools::handles(&tool_call.name) {
+            return self.inner.execute_tool_call(run, tool_call, cancel).await;
+        }
+        Ok(
+            match self
+                .memory
+                .call(&tool_call.name, &tool_call.arguments)
+                .await
+            {
+                Ok(result) => ToolExecutionResult::Completed {
+                    result,
+                    is_error: false,
+                },
+                Err(result) => ToolExecutionResult::Completed {
+                    result,
+                    is_error: true,
+                },
+            },
+        )
     }
 }
 
@@ -413,6 +564,7 @@ impl ToolExecutor for ServerToolExecutor {
 #[derive(Clone)]
 struct SandboxedToolExecutor {
     mcp_client: Arc<McpClient>,
+    scope: ToolCallScope,
 }
 
 #[async_trait]
@@ -428,6 +580,7 @@ impl ToolExecutor for SandboxedToolExecutor {
             run.session_id,
             run.run_id,
             tool_call,
+            &self.scope,
             cancel,
         )
         .await)
@@ -582,6 +735,7 @@ async fn execute_mcp_tool_call(
     session_id: Uuid,
     run_id: Uuid,
     tool_call: &ProposedToolCall,
+    scope: &ToolCallScope,
     cancel: &CancellationToken,
 ) -> ToolExecutionResult {
     let Some(mcp_client) = state.mcp_client.as_ref() else {
@@ -591,7 +745,8 @@ async fn execute_mcp_tool_call(
         };
     };
 
-    execute_mcp_tool_call_with_client(mcp_client, session_id, run_id, tool_call, cancel).await
+    execute_mcp_tool_call_with_client(mcp_client, session_id, run_id, tool_call, scope, cancel)
+        .await
 }
 
 async fn execute_mcp_tool_call_with_client(
@@ -599,9 +754,10 @@ async fn execute_mcp_tool_call_with_client(
     session_id: Uuid,
     run_id: Uuid,
     tool_call: &ProposedToolCall,
+    scope: &ToolCallScope,
     cancel: &CancellationToken,
 ) -> ToolExecutionResult {
-    let metadata = Some(serde_json::Map::from_iter([
+    let mut metadata = serde_json::Map::from_iter([
         (
             "session_id".to_string(),
             serde_json::Value::String(session_id.to_string()),
@@ -614,7 +770,9 @@ async fn execute_mcp_tool_call_with_client(
             "tool_call_id".to_string(),
             serde_json::Value::String(tool_call.id.clone()),
         ),
-    ]));
+    ]);
+    scope.extend_metadata(&mut metadata);
+    let metadata = Some(metadata);
 
     let arguments = match &tool_call.arguments {
         serde_json::Value::Object(map) => Some(map.clone()),
@@ -800,6 +958,22 @@ mod tests {
         assert_eq!(envelope.run_id, Some(run_id));
     }
 
+    #[test]
+    fn tool_call_scope_adds_cwd_and_env_only_when_set() {
+        let mut metadata = serde_json::Map::new();
+        ToolCallScope::default().extend_metadata(&mut metadata);
+        assert!(metadata.is_empty());
+
+        let scope = ToolCallScope {
+            cwd: Some("/srv/app".to_string()),
+            env: BTreeMap::from([("APP_ENV".to_string(), "ci".to_string())]),
+        };
+        scope.extend_metadata(&mut metadata);
+
+        assert_eq!(metadata.get("cwd"), Some(&json!("/srv/app")));
+        assert_eq!(metadata.get("env"), Some(&json!({"APP_ENV": "ci"})));
+    }
+
     #[test]
     fn render_call_tool_result_sanitizes_text_blocks() {
         let result = CallToolResult::success(vec![Content::text("ok\u{0007}done")]);
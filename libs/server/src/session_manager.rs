@@ -1,7 +1,11 @@
 use crate::error::SessionManagerError;
 use crate::types::{SessionHandle, SessionRuntimeState};
 use stakpak_agent_core::AgentCommand;
-use std::{collections::HashMap, future::Future, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    sync::Arc,
+};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
@@ -41,6 +45,25 @@ impl SessionManager {
             .collect()
     }
 
+    /// Sessions with a run starting or in flight.
+    pub async fn active_sessions(&self) -> HashSet<Uuid> {
+        let guard = self.states.read().await;
+        guard
+            .iter()
+            .filter(|(_, state)| state.is_active())
+            .map(|(session_id, _)| *session_id)
+            .collect()
+    }
+
+    /// Drop the bookkeeping entry for an idle session. Idle is also the state
+    /// reported for unknown sessions, so this only frees memory.
+    pub async fn evict_idle(&self, session_id: Uuid) {
+        let mut guard = self.states.write().await;
+        if matches!(guard.get(&session_id), Some(SessionRuntimeState::Idle)) {
+            guard.remove(&session_id);
+        }
+    }
+
     pub async fn start_run<F, Fut>(
         &self,
         session_id: Uuid,
@@ -371,6 +394,37 @@ mod tests {
         assert_eq!(running_runs[0], (running_session_id, running_run_id));
     }
 
+    #[tokio::test]
+    async fn evict_idle_keeps_active_and_failed_sessions() {
+        let manager = SessionManager::new();
+        let running_session_id = Uuid::new_v4();
+        let failed_session_id = Uuid::new_v4();
+
+        let (handle, _rx) = make_handle();
+        let result = manager
+            .start_run(running_session_id, move |_run_id| async move { Ok(handle) })
+            .await;
+        assert!(result.is_ok());
+        let result = manager
+            .start_run(failed_session_id, |_run_id| async move {
+                Err("boom".to_string())
+            })
+            .await;
+        assert!(result.is_err());
+
+        manager.evict_idle(running_session_id).await;
+        manager.evict_idle(failed_session_id).await;
+
+        assert_eq!(
+            manager.active_sessions().await,
+            HashSet::from([running_session_id])
+        );
+        assert!(matches!(
+            manager.state(failed_session_id).await,
+            SessionRuntimeState::Failed { .. }
+        ));
+    }
+
     #[tokio::test]
     async fn startup_failure_transitions_to_failed_state() {
         let manager = SessionManager::new();
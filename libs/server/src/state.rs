@@ -1,17 +1,24 @@
 use crate::{
     checkpoint_store::CheckpointStore,
     context::ContextBudget,
-    context::ContextFile,
+    context::{ContextFile, MachineProfileSource, MemoryStore},
     event_log::EventLog,
     idempotency::IdempotencyStore,
     sandbox::{PersistentSandbox, SandboxConfig, SandboxMode},
     session_manager::SessionManager,
+    usage::UsageLedger,
 };
 use stakpak_agent_core::{ProposedToolCall, ToolApprovalPolicy};
 use stakpak_api::SessionStorage;
 use stakpak_mcp_client::McpClient;
-use std::{collections::HashMap, sync::Arc, time::Instant};
+use stakpak_shared::context_rules::ContextRulesConfig;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::{RwLock, broadcast};
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -51,7 +58,23 @@ pub struct AppState {
     /// Cached remote skill context files (currently fetched from the remote
     /// skills endpoint contract) and injected into new sessions as baseline context.
     pub skills_context: Arc<RwLock<Vec<ContextFile>>>,
+    /// Include/exclude patterns applied, with `.stakpakignore` files, to
+    /// project context and the directory tree of new sessions.
+    pub context_rules: ContextRulesConfig,
+    /// Machine profile added to the context of new sessions. Off when `None`.
+    pub machine_profile: Option<Arc<dyn MachineProfileSource>>,
+    /// Workspace memories recalled into new sessions and the memory tools
+    /// offered to them. Off when `None`.
+    pub memory: Option<MemoryStore>,
+    /// Token usage and estimated cost of runs in this process.
+    pub usage: UsageLedger,
+    /// Hibernate sessions idle for this long. Requires an event log built
+    /// with [`EventLog::with_hibernation`].
+    pub hibernate_after: Option<Duration>,
     pending_tools: Arc<RwLock<HashMap<Uuid, PendingToolApprovals>>>,
+    /// Tags given when a session was created. Kept in memory only: they let
+    /// callers find sessions they started that are alive in this process.
+    session_tags: Arc<RwLock<HashMap<Uuid, Vec<String>>>>,
 }
 
 impl AppState {
@@ -86,7 +109,13 @@ impl AppState {
             context_budget: ContextBudget::default(),
             project_dir: None,
             skills_context: Arc::new(RwLock::new(Vec::new())),
+            context_rules: ContextRulesConfig::default(),
+            machine_profile: None,
+            memory: None,
+            usage: UsageLedger::new(),
+            hibernate_after: None,
             pending_tools: Arc::new(RwLock::new(HashMap::new())),
+            session_tags: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -134,6 +163,11 @@ impl AppState {
         self
     }
 
+    pub fn with_context_rules(mut self, rules: ContextRulesConfig) -> Self {
+        self.context_rules = rules;
+        self
+    }
+
     pub fn with_project_dir(mut self, dir: Option<String>) -> Self {
         self.project_dir = dir.filter(|value| !value.trim().is_empty());
         self
@@ -144,11 +178,65 @@ impl AppState {
         self
     }
 
+    /// Add a machine profile from `source` to the context of new sessions.
+    pub fn with_machine_profile(mut self, source: Arc<dyn MachineProfileSource>) -> Self {
+        self.machine_profile = Some(source);
+        self
+    }
+
+    /// Recall memories from `store` into new sessions and give them the
+    /// memory tools.
+    pub fn with_memory(mut self, store: MemoryStore) -> Self {
+        self.memory = Some(store);
+        self
+    }
+
     pub fn with_checkpoint_store(mut self, checkpoint_store: Arc<CheckpointStore>) -> Self {
         self.checkpoint_store = checkpoint_store;
         self
     }
 
+    pub fn with_hibernation(mut self, idle_after: Option<Duration>) -> Self {
+        self.hibernate_after = idle_after.filter(|after| !after.is_zero());
+        self
+    }
+
+    /// Hibernate sessions that have been idle for [`AppState::hibernate_after`]
+    /// and have no run in flight or tool calls awaiting approval.
+    pub async fn hibernate_idle_sessions(&self) -> usize {
+        let Some(idle_after) = self.hibernate_after else {
+            return 0;
+        };
+
+        let mut keep: HashSet<Uuid> = self.run_manager.active_sessions().await;
+        keep.extend(self.pending_tools.read().await.keys().copied());
+
+        let hibernated = self.events.hibernate_idle(idle_after, &keep).await;
+        for session_id in &hibernated {
+            self.run_manager.evict_idle(*session_id).await;
+        }
+        hibernated.len()
+    }
+
+    /// Periodically run [`AppState::hibernate_idle_sessions`]. Returns `None`
+    /// when hibernation is not configured.
+    pub fn spawn_hibernation_sweeper(&self) -> Option<JoinHandle<()>> {
+        let idle_after = self.hibernate_after?;
+        let period = idle_after.clamp(Duration::from_secs(1), Duration::from_secs(60));
+        let state = self.clone();
+        Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                let hibernated = state.hibernate_idle_sessions().await;
+                if hibernated > 0 {
+                    tracing::info!(count = hibernated, "Hibernated idle sessions");
+                }
+            }
+        }))
+    }
+
     pub async fn current_skills(&self) -> Vec<ContextFile> {
         self.skills_context.read().await.clone()
     }
@@ -232,6 +320,30 @@ impl AppState {
         guard.get(&session_id).cloned()
     }
 
+    pub async fn set_session_tags(&self, session_id: Uuid, tags: Vec<String>) {
+        let mut guard = self.session_tags.write().await;
+        if tags.is_empty() {
+            guard.remove(&session_id);
+        } else {
+            guard.insert(session_id, tags);
+        }
+    }
+
+    pub async fn session_tags(&self, session_id: Uuid) -> Vec<String> {
+        let guard = self.session_tags.read().await;
+        guard.get(&session_id).cloned().unwrap_or_default()
+    }
+
+    /// Sessions created with `tag`, in no particular order.
+    pub async fn sessions_tagged(&self, tag: &str) -> Vec<Uuid> {
+        let guard = self.session_tags.read().await;
+        guard
+            .iter()
+            .filter(|(_, tags)| tags.iter().any(|candidate| candidate == tag))
+            .map(|(session_id, _)| *session_id)
+            .collect()
+    }
+
     fn find_model(&self, requested: &str) -> Option<stakai::Model> {
         if let Some((provider, id)) = requested.split_once('/') {
             return self
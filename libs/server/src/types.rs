@@ -1,6 +1,6 @@
 use stakpak_agent_core::ToolApprovalPolicy;
 pub use stakpak_shared::models::overrides::{AutoApproveOverride, RunOverrides};
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
@@ -33,6 +33,8 @@ pub struct RunConfig {
     pub tool_approval_policy: ToolApprovalPolicy,
     pub system_prompt: Option<String>,
     pub max_turns: usize,
+    /// Extra environment variables applied to commands run by tools.
+    pub command_env: BTreeMap<String, String>,
 }
 
 impl std::fmt::Debug for RunConfig {
@@ -42,6 +44,7 @@ impl std::fmt::Debug for RunConfig {
             .field("tool_approval_policy", &self.tool_approval_policy)
             .field("system_prompt", &self.system_prompt)
             .field("max_turns", &self.max_turns)
+            .field("command_env", &self.command_env.keys().collect::<Vec<_>>())
             .field("inference", &"<opaque>")
             .finish()
     }
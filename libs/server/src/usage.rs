@@ -0,0 +1,274 @@
+//! Token usage and estimated cost per run and per session.
+//!
+//! Usage is summed from the usage each turn reports. Costs are estimated
+//! from the list prices of the run's model, turn by turn so a model switch
+//! mid-run is priced correctly; turns on models without known pricing add
+//! tokens but no cost. Like session tags, usage is kept in memory for the
+//! life of the process.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use stakai::{Model, ModelCost, Usage};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Runs listed per session; older runs still count toward the totals.
+const MAX_RUNS_PER_SESSION: usize = 200;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    /// Prompt tokens read from the provider's cache.
+    pub cache_read_tokens: u64,
+    /// Prompt tokens written to the provider's cache.
+    pub cache_write_tokens: u64,
+}
+
+impl UsageTotals {
+    fn add_usage(&mut self, usage: &Usage) {
+        let details = usage.input_token_details.as_ref();
+        self.prompt_tokens += u64::from(usage.prompt_tokens);
+        self.completion_tokens += u64::from(usage.completion_tokens);
+        self.total_tokens += u64::from(usage.total_tokens);
+        self.cache_read_tokens += u64::from(details.and_then(|d| d.cache_read).unwrap_or(0));
+        self.cache_write_tokens += u64::from(details.and_then(|d| d.cache_write).unwrap_or(0));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunUsage {
+    pub run_id: Uuid,
+    /// Model the run started on, as `provider/id`.
+    pub model: String,
+    pub turns: usize,
+    pub usage: UsageTotals,
+    /// USD, for the turns on models with known pricing. `None` when no turn
+    /// was priced.
+    pub estimated_cost: Option<f64>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionUsage {
+    pub session_id: Uuid,
+    pub usage: UsageTotals,
+    pub estimated_cost: Option<f64>,
+    /// Most recent runs, oldest first.
+    pub runs: Vec<RunUsage>,
+}
+
+#[derive(Debug)]
+struct TrackedRun {
+    usage: RunUsage,
+    /// Pricing of the model the run is on now.
+    cost: Option<ModelCost>,
+}
+
+#[derive(Debug, Default)]
+struct TrackedSession {
+    usage: UsageTotals,
+    estimated_cost: Option<f64>,
+    runs: Vec<TrackedRun>,
+}
+
+/// Usage of every session this process has run.
+#[derive(Debug, Clone, Default)]
+pub struct UsageLedger {
+    sessions: Arc<RwLock<HashMap<Uuid, TrackedSession>>>,
+}
+
+impl UsageLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn start_run(&self, session_id: Uuid, run_id: Uuid, model: &Model) {
+        let mut guard = self.sessions.write().await;
+        let session = guard.entry(session_id).or_default();
+        session.runs.push(TrackedRun {
+            usage: RunUsage {
+                run_id,
+                model: format!("{}/{}", model.provider, model.id),
+                turns: 0,
+                usage: UsageTotals::default(),
+                estimated_cost: None,
+                started_at: Utc::now(),
+                finished_at: None,
+            },
+            cost: model.cost.clone(),
+        });
+        if session.runs.len() > MAX_RUNS_PER_SESSION {
+            let excess = session.runs.len() - MAX_RUNS_PER_SESSION;
+            session.runs.drain(..excess);
+        }
+    }
+
+    /// Price the rest of the run at `model`'s rates.
+    pub async fn switch_model(&self, session_id: Uuid, run_id: Uuid, model: &Model) {
+        let mut guard = self.sessions.write().await;
+        if let Some(run) = find_run(&mut guard, session_id, run_id) {
+            run.cost = model.cost.clone();
+        }
+    }
+
+    /// Add one turn's usage to its run and session.
+    pub async fn record(&self, session_id: Uuid, run_id: Uuid, usage: &Usage) {
+        let mut guard = self.sessions.write().await;
+        let Some(session) = guard.get_mut(&session_id) else {
+            return;
+        };
+        let Some(run) = session
+            .runs
+            .iter_mut()
+            .rev()
+            .find(|run| run.usage.run_id == run_id)
+        else {
+            return;
+        };
+
+        run.usage.turns += 1;
+        run.usage.usage.add_usage(usage);
+        session.usage.add_usage(usage);
+        if let Some(cost) = run.cost.as_ref().map(|cost| estimate_cost(cost, usage)) {
+            run.usage.estimated_cost = Some(run.usage.estimated_cost.unwrap_or(0.0) + cost);
+            session.estimated_cost = Some(session.estimated_cost.unwrap_or(0.0) + cost);
+        }
+    }
+
+    pub async fn finish_run(&self, session_id: Uuid, run_id: Uuid) {
+        let mut guard = self.sessions.write().await;
+        if let Some(run) = find_run(&mut guard, session_id, run_id) {
+            run.usage.finished_at = Some(Utc::now());
+        }
+    }
+
+    pub async fn session(&self, session_id: Uuid) -> Option<SessionUsage> {
+        let guard = self.sessions.read().await;
+        guard.get(&session_id).map(|session| SessionUsage {
+            session_id,
+            usage: session.usage,
+            estimated_cost: session.estimated_cost,
+            runs: session.runs.iter().map(|run| run.usage.clone()).collect(),
+        })
+    }
+
+    pub async fn remove(&self, session_id: Uuid) {
+        self.sessions.write().await.remove(&session_id);
+    }
+}
+
+fn find_run(
+    sessions: &mut HashMap<Uuid, TrackedSession>,
+    session_id: Uuid,
+    run_id: Uuid,
+) -> Option<&mut TrackedRun> {
+    sessions
+        .get_mut(&session_id)?
+        .runs
+        .iter_mut()
+        .rev()
+        .find(|run| run.usage.run_id == run_id)
+}
+
+/// USD cost of one turn. Cached prompt tokens are priced at the cache rates
+/// when the provider reports them.
+fn estimate_cost(cost: &ModelCost, usage: &Usage) -> f64 {
+    let prompt = u64::from(usage.prompt_tokens);
+    let completion = u64::from(usage.completion_tokens);
+    let Some(details) = usage.input_token_details.as_ref() else {
+        return cost.calculate(prompt, completion);
+    };
+    let cache_read = u64::from(details.cache_read.unwrap_or(0));
+    let cache_write = u64::from(details.cache_write.unwrap_or(0));
+    let no_cache = details.no_cache.map_or_else(
+        || prompt.saturating_sub(cache_read + cache_write),
+        u64::from,
+    );
+    cost.calculate_with_cache(no_cache, completion, cache_read, cache_write)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn priced_model(id: &str, input: f64, output: f64) -> Model {
+        let mut model = Model::custom(id, "anthropic");
+        model.cost = Some(ModelCost::with_cache(
+            input,
+            output,
+            input / 10.0,
+            input * 1.25,
+        ));
+        model
+    }
+
+    #[tokio::test]
+    async fn sums_turns_per_run_and_session_at_the_current_model_price() {
+        let ledger = UsageLedger::new();
+        let session_id = Uuid::new_v4();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+        ledger
+            .start_run(
+                session_id,
+                first,
+                &priced_model("claude-sonnet-4-5", 3.0, 15.0),
+            )
+            .await;
+        ledger
+            .record(session_id, first, &Usage::new(1_000_000, 100_000))
+            .await;
+        ledger
+            .switch_model(
+                session_id,
+                first,
+                &priced_model("claude-haiku-4-5", 1.0, 5.0),
+            )
+            .await;
+        let mut cached = Usage::new(1_000_000, 0);
+        cached.input_token_details = Some(stakai::InputTokenDetails {
+            total: Some(1_000_000),
+            no_cache: Some(200_000),
+            cache_read: Some(800_000),
+            cache_write: None,
+        });
+        ledger.record(session_id, first, &cached).await;
+        ledger.finish_run(session_id, first).await;
+
+        ledger
+            .start_run(session_id, second, &Model::custom("llama3", "ollama"))
+            .await;
+        ledger
+            .record(session_id, second, &Usage::new(500, 50))
+            .await;
+        ledger
+            .record(Uuid::new_v4(), second, &Usage::new(1, 1))
+            .await;
+
+        let usage = ledger.session(session_id).await.expect("session usage");
+        assert_eq!(usage.runs.len(), 2);
+
+        let run = &usage.runs[0];
+        assert_eq!(run.model, "anthropic/claude-sonnet-4-5");
+        assert_eq!(run.turns, 2);
+        assert_eq!(run.usage.prompt_tokens, 2_000_000);
+        assert_eq!(run.usage.cache_read_tokens, 800_000);
+        // 3.0 + 1.5 on Sonnet, then 0.2 + 0.08 on Haiku.
+        let cost = run.estimated_cost.expect("priced run");
+        assert!((cost - 4.78).abs() < 1e-9, "{cost}");
+        assert!(run.finished_at.is_some());
+
+        assert_eq!(usage.runs[1].estimated_cost, None);
+        assert_eq!(usage.runs[1].usage.total_tokens, 550);
+        assert_eq!(usage.usage.total_tokens, 2_100_000 + 550);
+        assert_eq!(usage.estimated_cost, run.estimated_cost);
+
+        ledger.remove(session_id).await;
+        assert!(ledger.session(session_id).await.is_none());
+    }
+}
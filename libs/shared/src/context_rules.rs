@@ -0,0 +1,226 @@
+//! Which files may go into agent context.
+//!
+//! A `.stakpakignore` in the working directory or any ancestor up to the
+//! repository root lists paths, in gitignore syntax, that are left out of
+//! project context and the directory tree the agent starts with:
+//!
+//! ```text
+//! vendor/
+//! *.lock
+//! src/generated/
+//! !src/generated/README.md
+//! ```
+//!
+//! Explicit `include`/`exclude` patterns from configuration use the same
+//! syntax, relative to the repository root (or the working directory outside
+//! a repository). `exclude` adds to the ignore files; `include` wins over
+//! both.
+
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Ignore file, in gitignore syntax, read from each directory it applies to.
+pub const IGNORE_FILE: &str = ".stakpakignore";
+
+/// Include/exclude patterns from configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextRulesConfig {
+    /// Paths kept in context even when excluded or ignored
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Paths left out of context, on top of `.stakpakignore`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+impl ContextRulesConfig {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+}
+
+/// Compiled rules for one working directory.
+#[derive(Debug, Clone)]
+pub struct ContextRules {
+    root: PathBuf,
+    include: Gitignore,
+    exclude: Gitignore,
+    /// `.stakpakignore` matchers, nearest directory first.
+    ignore_files: Vec<Gitignore>,
+}
+
+impl Default for ContextRules {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::new(),
+            include: Gitignore::empty(),
+            exclude: Gitignore::empty(),
+            ignore_files: Vec::new(),
+        }
+    }
+}
+
+impl ContextRules {
+    /// Rules for `cwd`: every `.stakpakignore` from the repository root down
+    /// to `cwd`, plus `config`. Invalid patterns are skipped with a warning.
+    pub fn load(cwd: &Path, config: &ContextRulesConfig) -> Self {
+        let cwd = canonical_or_original(cwd);
+        let root = cwd
+            .ancestors()
+            .find(|dir| dir.join(".git").exists())
+            .unwrap_or(&cwd)
+            .to_path_buf();
+
+        let ignore_files = cwd
+            .ancestors()
+            .take_while(|dir| dir.starts_with(&root))
+            .filter_map(|dir| {
+                let path = dir.join(IGNORE_FILE);
+                if !path.is_file() {
+                    return None;
+                }
+                let mut builder = GitignoreBuilder::new(dir);
+                if let Some(error) = builder.add(&path) {
+                    tracing::warn!("Invalid pattern in {}: {}", path.display(), error);
+                }
+                builder.build().ok().filter(|matcher| !matcher.is_empty())
+            })
+            .collect();
+
+        Self {
+            include: compile(&root, &config.include),
+            exclude: compile(&root, &config.exclude),
+            ignore_files,
+            root,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty() && self.ignore_files.is_empty()
+    }
+
+    /// Whether `path`, or a directory it is in, is kept out of context.
+    /// Paths outside the repository are only matched against `.stakpakignore`
+    /// files above them, so global files such as `~/.stakpak/APPS.md` stay.
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let path = if path.starts_with(&self.root) {
+            path.to_path_buf()
+        } else {
+            canonical_or_original(path)
+        };
+
+        if path.starts_with(&self.root) && path != self.root {
+            if self
+                .include
+                .matched_path_or_any_parents(&path, is_dir)
+                .is_ignore()
+            {
+                return false;
+            }
+            if self
+                .exclude
+                .matched_path_or_any_parents(&path, is_dir)
+                .is_ignore()
+            {
+                return true;
+            }
+        }
+
+        for matcher in &self.ignore_files {
+            if !path.starts_with(matcher.path()) || path == matcher.path() {
+                continue;
+            }
+            match matcher.matched_path_or_any_parents(&path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+}
+
+fn compile(root: &Path, patterns: &[String]) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    for pattern in patterns {
+        if let Err(error) = builder.add_line(None, pattern) {
+            tracing::warn!("Invalid context pattern {:?}: {}", pattern, error);
+        }
+    }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+fn canonical_or_original(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join(".git")).expect("create .git");
+        dir
+    }
+
+    fn root(dir: &tempfile::TempDir) -> PathBuf {
+        dir.path().canonicalize().expect("canonicalize")
+    }
+
+    #[test]
+    fn ignore_files_apply_from_repo_root_down_to_cwd() {
+        let dir = repo();
+        let root = root(&dir);
+        let service = root.join("services/api");
+        std::fs::create_dir_all(&service).expect("create service");
+        std::fs::write(root.join(IGNORE_FILE), "vendor/\n*.lock\n").expect("write root ignore");
+        std::fs::write(service.join(IGNORE_FILE), "gen/\n!keep.lock\n")
+            .expect("write service ignore");
+
+        let rules = ContextRules::load(&service, &ContextRulesConfig::default());
+
+        assert!(rules.is_excluded(&root.join("vendor"), true));
+        assert!(rules.is_excluded(&service.join("vendor/lib.go"), false));
+        assert!(rules.is_excluded(&service.join("Cargo.lock"), false));
+        assert!(rules.is_excluded(&service.join("gen/api.rs"), false));
+        assert!(!rules.is_excluded(&service.join("keep.lock"), false));
+        assert!(!rules.is_excluded(&service.join("src/main.rs"), false));
+        assert!(!rules.is_excluded(&root.join("gen/api.rs"), false));
+    }
+
+    #[test]
+    fn config_excludes_apply_and_includes_win() {
+        let dir = repo();
+        let root = root(&dir);
+        std::fs::write(root.join(IGNORE_FILE), "docs/\n").expect("write ignore");
+
+        let rules = ContextRules::load(
+            &root,
+            &ContextRulesConfig {
+                include: vec!["docs/AGENTS.md".to_string()],
+                exclude: vec!["dist/".to_string(), "*.min.js".to_string()],
+            },
+        );
+
+        assert!(rules.is_excluded(&root.join("dist"), true));
+        assert!(rules.is_excluded(&root.join("web/app.min.js"), false));
+        assert!(rules.is_excluded(&root.join("docs/guide.md"), false));
+        assert!(!rules.is_excluded(&root.join("docs/AGENTS.md"), false));
+        assert!(!rules.is_excluded(&root, true));
+        assert!(!rules.is_excluded(Path::new("/etc/hosts"), false));
+    }
+
+    #[test]
+    fn no_rules_excludes_nothing() {
+        let dir = repo();
+        let rules = ContextRules::load(dir.path(), &ContextRulesConfig::default());
+        assert!(rules.is_empty());
+        assert!(!rules.is_excluded(&dir.path().join("vendor"), true));
+    }
+}
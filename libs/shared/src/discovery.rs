@@ -0,0 +1,227 @@
+//! Discovery results, shared by the CLI that runs the probes and the server
+//! that can add them to session context.
+//!
+//! A [`DiscoveryReport`] is cached per working directory in
+//! `~/.stakpak/cache/discovery/`, so a session started shortly after another
+//! one reuses its results instead of scanning the home directory again.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Cache directory, relative to the Stakpak home directory.
+const CACHE_DIR: &str = "cache/discovery";
+
+/// Bumped when the report layout changes; older caches are ignored.
+const CACHE_VERSION: u32 = 1;
+
+/// Output of one discovery probe.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProbeResult {
+    /// Probe id, e.g. `git-repos`
+    pub id: String,
+    /// Section title, e.g. `Git Repositories`
+    pub name: String,
+    /// Markdown bullet list; empty when the probe found nothing
+    pub output: String,
+    #[serde(default)]
+    pub timed_out: bool,
+    #[serde(default)]
+    pub duration_ms: u64,
+}
+
+impl ProbeResult {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+/// Results of one discovery run, in report order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscoveryReport {
+    #[serde(default)]
+    version: u32,
+    /// Unix time, in seconds, when the probes finished
+    pub generated_at: u64,
+    /// Working directory the probes ran in
+    pub cwd: Option<PathBuf>,
+    /// Fingerprint of the probe selection that produced this report; a
+    /// cached report is only reused for the same selection.
+    pub selection: String,
+    pub results: Vec<ProbeResult>,
+}
+
+impl DiscoveryReport {
+    pub fn new(
+        cwd: Option<PathBuf>,
+        selection: impl Into<String>,
+        results: Vec<ProbeResult>,
+    ) -> Self {
+        Self {
+            version: CACHE_VERSION,
+            generated_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            cwd,
+            selection: selection.into(),
+            results,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    /// One `## name` section per probe that found something, the form the
+    /// agent reads inside `<discovery_results>`.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::with_capacity(4096);
+        for result in &self.results {
+            if result.output.trim().is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "## {}\n", result.name);
+            let _ = writeln!(out, "{}", result.output.trim());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Whether no probe found anything.
+    pub fn is_empty(&self) -> bool {
+        self.results
+            .iter()
+            .all(|result| result.output.trim().is_empty())
+    }
+
+    pub fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH + Duration::from_secs(self.generated_at))
+            .unwrap_or_default()
+    }
+}
+
+/// On-disk cache of the latest [`DiscoveryReport`] for one working directory.
+#[derive(Debug, Clone)]
+pub struct DiscoveryCache {
+    path: PathBuf,
+}
+
+impl DiscoveryCache {
+    /// Cache for discovery run in `cwd`, under `~/.stakpak/cache/discovery/`.
+    pub fn for_dir(cwd: &Path) -> Self {
+        Self::in_dir(&crate::paths::stakpak_home_dir().join(CACHE_DIR), cwd)
+    }
+
+    /// [`DiscoveryCache::for_dir`] with the cache kept in `cache_dir`.
+    pub fn in_dir(cache_dir: &Path, cwd: &Path) -> Self {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        cwd.hash(&mut hasher);
+        Self {
+            path: cache_dir.join(format!("{:016x}.json", hasher.finish())),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The cached report when it is younger than `max_age`.
+    pub fn load(&self, max_age: Duration) -> Option<DiscoveryReport> {
+        let content = std::fs::read(&self.path).ok()?;
+        let report: DiscoveryReport = serde_json::from_slice(&content).ok()?;
+        (report.version == CACHE_VERSION && report.age() < max_age).then_some(report)
+    }
+
+    /// Write through a temporary file so a concurrent reader never sees half
+    /// a report.
+    pub fn store(&self, report: &DiscoveryReport) {
+        let Some(dir) = self.path.parent() else {
+            return;
+        };
+        let Ok(content) = serde_json::to_vec(report) else {
+            return;
+        };
+        let temp = self
+            .path
+            .with_extension(format!("json.{}.tmp", std::process::id()));
+        let written = std::fs::create_dir_all(dir)
+            .and_then(|()| std::fs::write(&temp, content))
+            .and_then(|()| std::fs::rename(&temp, &self.path));
+        if let Err(error) = written {
+            tracing::debug!(
+                "Failed to cache discovery results {}: {}",
+                self.path.display(),
+                error
+            );
+            let _ = std::fs::remove_file(&temp);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, name: &str, output: &str) -> ProbeResult {
+        ProbeResult {
+            id: id.to_string(),
+            name: name.to_string(),
+            output: output.to_string(),
+            timed_out: false,
+            duration_ms: 12,
+        }
+    }
+
+    #[test]
+    fn markdown_skips_empty_sections_and_json_keeps_them() {
+        let report = DiscoveryReport::new(
+            Some(PathBuf::from("/work/app")),
+            "toolchain,systemd",
+            vec![
+                result("toolchain", "Toolchain", "- Languages: rust 1.79.0\n"),
+                result("systemd", "Systemd", "  \n"),
+            ],
+        );
+
+        assert_eq!(
+            report.to_markdown(),
+            "## Toolchain\n\n- Languages: rust 1.79.0\n\n"
+        );
+        let json = report.to_json();
+        assert_eq!(json["cwd"], "/work/app");
+        assert_eq!(json["results"][1]["id"], "systemd");
+        assert_eq!(
+            report.results[0].to_json()["output"],
+            "- Languages: rust 1.79.0\n"
+        );
+        assert!(!report.is_empty());
+    }
+
+    #[test]
+    fn cache_round_trips_and_expires() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cache = DiscoveryCache::in_dir(dir.path(), Path::new("/work/app"));
+        assert_ne!(
+            cache.path(),
+            DiscoveryCache::in_dir(dir.path(), Path::new("/work/other")).path()
+        );
+        assert_eq!(cache.load(Duration::from_secs(600)), None);
+
+        let mut report = DiscoveryReport::new(
+            None,
+            "crontabs",
+            vec![result("crontabs", "Crontabs", "- User: 2 entries\n")],
+        );
+        cache.store(&report);
+        assert_eq!(cache.load(Duration::from_secs(600)), Some(report.clone()));
+
+        report.generated_at = report.generated_at.saturating_sub(3600);
+        cache.store(&report);
+        assert_eq!(cache.load(Duration::from_secs(600)), None);
+        assert!(cache.load(Duration::from_secs(7200)).is_some());
+    }
+}
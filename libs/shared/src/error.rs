@@ -0,0 +1,400 @@
+//! User-facing error taxonomy.
+//!
+//! Failures that reach a person — CLI exit, TUI error banner, autopilot run
+//! record, gateway reply — are reported as a [`StakpakError`]: an
+//! [`ErrorKind`] with a stable code, a remediation hint and a CLI exit code,
+//! plus the underlying message. Kinds are assigned where the error is
+//! raised — from an HTTP status, a provider error variant, the step that
+//! failed — never guessed from the message text. A bare string converts to
+//! [`ErrorKind::Internal`]. The only text matching left is in
+//! [`StakpakError::from_record`], for run records written before the record
+//! carried a code.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// What went wrong, from the user's point of view.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Missing, invalid or expired credentials
+    Auth,
+    /// The API, agent server or a provider could not be reached
+    Network,
+    /// Invalid or missing configuration
+    Config,
+    /// A tool, check script or MCP server failed
+    ToolExecution,
+    /// The model provider rejected or failed the request
+    Model,
+    /// Too many requests, or a usage limit was reached
+    RateLimit,
+    /// Anything else
+    #[default]
+    Internal,
+}
+
+impl ErrorKind {
+    pub const ALL: [ErrorKind; 7] = [
+        ErrorKind::Auth,
+        ErrorKind::Network,
+        ErrorKind::Config,
+        ErrorKind::ToolExecution,
+        ErrorKind::Model,
+        ErrorKind::RateLimit,
+        ErrorKind::Internal,
+    ];
+
+    /// Stable machine-readable code, e.g. `rate_limit`.
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorKind::Auth => "auth",
+            ErrorKind::Network => "network",
+            ErrorKind::Config => "config",
+            ErrorKind::ToolExecution => "tool_execution",
+            ErrorKind::Model => "model",
+            ErrorKind::RateLimit => "rate_limit",
+            ErrorKind::Internal => "internal",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.code() == code)
+    }
+
+    /// What the user can do about it.
+    pub fn hint(self) -> &'static str {
+        match self {
+            ErrorKind::Auth => "Check your credentials, or log in again with `stakpak auth login`.",
+            ErrorKind::Network => {
+                "Check your network connection and that the endpoint is reachable, then retry."
+            }
+            ErrorKind::Config => {
+                "Fix the setting in ~/.stakpak/config.toml or the flag you passed."
+            }
+            ErrorKind::ToolExecution => {
+                "Check the tool's output above; run the command by hand to reproduce it."
+            }
+            ErrorKind::Model => {
+                "Try again, pick another model with `--model`, or shorten the conversation."
+            }
+            ErrorKind::RateLimit => "Wait a moment and retry, or check your plan's usage limits.",
+            ErrorKind::Internal => {
+                "Retry; if it keeps happening, report it at https://github.com/stakpak/agent/issues."
+            }
+        }
+    }
+
+    /// Process exit code for a CLI command that fails with this kind. `1`
+    /// stays the generic failure; `2` belongs to usage errors and `10` to
+    /// paused runs.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::Internal => 1,
+            ErrorKind::Config => 3,
+            ErrorKind::Auth => 4,
+            ErrorKind::Network => 5,
+            ErrorKind::RateLimit => 6,
+            ErrorKind::Model => 7,
+            ErrorKind::ToolExecution => 8,
+        }
+    }
+}
+
+impl ErrorKind {
+    /// The kind a `stakpak` process failed with, from its exit code.
+    pub fn from_exit_code(code: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.exit_code() == code)
+    }
+}
+
+impl From<&stakai::Error> for ErrorKind {
+    fn from(error: &stakai::Error) -> Self {
+        match error {
+            stakai::Error::HttpError(error) => match error.status().map(|status| status.as_u16()) {
+                Some(401 | 403) => ErrorKind::Auth,
+                Some(429) => ErrorKind::RateLimit,
+                Some(_) => ErrorKind::Model,
+                None => ErrorKind::Network,
+            },
+            stakai::Error::NetworkError(_) => ErrorKind::Network,
+            stakai::Error::MissingApiKey(_) => ErrorKind::Auth,
+            stakai::Error::RateLimitExceeded(_) => ErrorKind::RateLimit,
+            stakai::Error::ProviderNotFound(_)
+            | stakai::Error::UnknownProvider(_)
+            | stakai::Error::InvalidModel(_)
+            | stakai::Error::ConfigError(_) => ErrorKind::Config,
+            stakai::Error::JsonError(_)
+            | stakai::Error::InvalidResponse(_)
+            | stakai::Error::ProviderError(_)
+            | stakai::Error::StreamError(_) => ErrorKind::Model,
+            stakai::Error::Other(_) => ErrorKind::Internal,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// A failure reported to the user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[error("{message}")]
+pub struct StakpakError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl StakpakError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn auth(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Auth, message)
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Network, message)
+    }
+
+    pub fn config(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Config, message)
+    }
+
+    pub fn tool_execution(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::ToolExecution, message)
+    }
+
+    pub fn model(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Model, message)
+    }
+
+    pub fn rate_limit(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::RateLimit, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Internal, message)
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    pub fn hint(&self) -> &'static str {
+        self.kind.hint()
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        self.kind.exit_code()
+    }
+
+    /// The message with its code, then the hint on its own line.
+    pub fn render(&self) -> String {
+        format!("{} [{}]\nHint: {}", self.message, self.code(), self.hint())
+    }
+
+    /// Single-line form for stored records: `[code] message`.
+    pub fn to_record(&self) -> String {
+        format!("[{}] {}", self.code(), self.message)
+    }
+
+    /// Parse [`Self::to_record`] output. Records stored before errors
+    /// carried a code are sorted into a kind by what they mention.
+    pub fn from_record(record: &str) -> Self {
+        record
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once("] "))
+            .and_then(|(code, message)| {
+                ErrorKind::from_code(code).map(|kind| Self::new(kind, message))
+            })
+            .unwrap_or_else(|| Self::new(classify_legacy_record(record), record))
+    }
+}
+
+impl From<String> for StakpakError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for StakpakError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<stakai::Error> for StakpakError {
+    fn from(error: stakai::Error) -> Self {
+        Self::new(ErrorKind::from(&error), error.to_string())
+    }
+}
+
+fn classify_legacy_record(record: &str) -> ErrorKind {
+    LEGACY_CLASSIFIERS
+        .iter()
+        .find(|(_, regex)| regex.is_match(record))
+        .map_or(ErrorKind::Internal, |(kind, _)| *kind)
+}
+
+/// Checked in order; the first match wins, so the more specific kinds come
+/// first (a rate-limited model call is `rate_limit`, not `model`).
+static LEGACY_CLASSIFIERS: LazyLock<Vec<(ErrorKind, Regex)>> = LazyLock::new(|| {
+    [
+        (
+            ErrorKind::RateLimit,
+            r"(?i)\b429\b|rate.?limit|too many requests|quota|exceeded api limit|usage limit|FREE_PLAN",
+        ),
+        (
+            ErrorKind::Auth,
+            r"(?i)\b40[13]\b|unauthori[sz]ed|forbidden|authenticat|invalid api key|api key (?:is )?(?:missing|invalid|not set)|(?:token|credentials?) (?:expired|missing|invalid)|(?:expired|invalid|missing) (?:token|credentials?)|not logged in",
+        ),
+        (
+            ErrorKind::ToolExecution,
+            r"(?i)\btool\b|check script|command failed|exit(?:ed with)? (?:code|status)|\bmcp\b",
+        ),
+        (
+            ErrorKind::Network,
+            r"(?i)connection (?:refused|reset|closed|failed)|timed? ?out|dns|resolve host|unreachable|error sending request|network|broken pipe|connect(?:ion)? error",
+        ),
+        (
+            ErrorKind::Config,
+            r"(?i)config|profile|missing field|unknown field|invalid value|toml|not configured",
+        ),
+        (
+            ErrorKind::Model,
+            r"(?i)\bmodel\b|context (?:length|window)|max(?:imum)? tokens|overloaded|\b5(?:00|02|03|29)\b|provider",
+        ),
+    ]
+    .into_iter()
+    .map(|(kind, pattern)| match Regex::new(pattern) {
+        Ok(regex) => (kind, regex),
+        Err(error) => panic!("invalid error classifier for {kind}: {error}"),
+    })
+    .collect()
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_records_are_sorted_into_kinds() {
+        let cases = [
+            ("server returned 429: slow down", ErrorKind::RateLimit),
+            ("Exceeded API limit", ErrorKind::RateLimit),
+            ("server returned 401: bad key", ErrorKind::Auth),
+            ("Invalid API key provided", ErrorKind::Auth),
+            ("Check script timed out", ErrorKind::ToolExecution),
+            (
+                "MCP server exited before it was ready",
+                ErrorKind::ToolExecution,
+            ),
+            (
+                "error sending request: connection refused",
+                ErrorKind::Network,
+            ),
+            (
+                "Profile 'prod' not found in configuration",
+                ErrorKind::Config,
+            ),
+            ("model is overloaded", ErrorKind::Model),
+            ("context length exceeded", ErrorKind::Model),
+            ("something odd happened", ErrorKind::Internal),
+        ];
+        for (message, kind) in cases {
+            assert_eq!(StakpakError::from_record(message).kind, kind, "{message}");
+        }
+    }
+
+    #[test]
+    fn test_provider_errors_keep_their_kind() {
+        let cases = [
+            (
+                stakai::Error::MissingApiKey("anthropic".into()),
+                ErrorKind::Auth,
+            ),
+            (
+                stakai::Error::RateLimitExceeded("slow down".into()),
+                ErrorKind::RateLimit,
+            ),
+            (
+                stakai::Error::InvalidModel("gpt-x".into()),
+                ErrorKind::Config,
+            ),
+            (
+                stakai::Error::NetworkError("fetch failed".into()),
+                ErrorKind::Network,
+            ),
+            (
+                stakai::Error::provider_error("overloaded"),
+                ErrorKind::Model,
+            ),
+        ];
+        for (error, kind) in cases {
+            let error = StakpakError::from(error);
+            assert_eq!(error.kind, kind, "{}", error.message);
+        }
+    }
+
+    #[test]
+    fn test_codes_are_stable_and_unique() {
+        let codes: Vec<&str> = ErrorKind::ALL.iter().map(|kind| kind.code()).collect();
+        assert_eq!(
+            codes,
+            [
+                "auth",
+                "network",
+                "config",
+                "tool_execution",
+                "model",
+                "rate_limit",
+                "internal"
+            ]
+        );
+        for kind in ErrorKind::ALL {
+            assert_eq!(ErrorKind::from_code(kind.code()), Some(kind));
+            assert_eq!(ErrorKind::from_exit_code(kind.exit_code()), Some(kind));
+            assert_eq!(
+                serde_json::to_value(kind).expect("serialize"),
+                serde_json::Value::String(kind.code().to_string())
+            );
+        }
+
+        let mut exit_codes: Vec<i32> = ErrorKind::ALL.iter().map(|kind| kind.exit_code()).collect();
+        exit_codes.sort_unstable();
+        exit_codes.dedup();
+        assert_eq!(exit_codes.len(), ErrorKind::ALL.len());
+        assert!(!exit_codes.contains(&0) && !exit_codes.contains(&2) && !exit_codes.contains(&10));
+    }
+
+    #[test]
+    fn test_record_round_trips_and_untyped_strings_are_internal() {
+        let error = StakpakError::tool_execution("Check script error: exit 3");
+        assert_eq!(
+            error.to_record(),
+            "[tool_execution] Check script error: exit 3"
+        );
+        assert_eq!(StakpakError::from_record(&error.to_record()), error);
+
+        let legacy = StakpakError::from_record("connection refused by api.stakpak.dev");
+        assert_eq!(legacy.kind, ErrorKind::Network);
+        assert_eq!(legacy.message, "connection refused by api.stakpak.dev");
+
+        let untyped = StakpakError::from("server returned 401: bad key");
+        assert_eq!(untyped.kind, ErrorKind::Internal);
+
+        let rendered = StakpakError::auth("token expired").render();
+        assert_eq!(
+            rendered,
+            format!("token expired [auth]\nHint: {}", ErrorKind::Auth.hint())
+        );
+    }
+}
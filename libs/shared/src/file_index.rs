@@ -0,0 +1,803 @@
+//! Persistent index of the files under a directory.
+//!
+//! Discovery probes, `@` file mentions and project context all need to know
+//! what lives under a directory. Instead of each of them walking it on every
+//! start, an index is built once under a size and time budget, cached in
+//! `~/.stakpak/cache/file-index/`, and kept fresh from filesystem
+//! notifications once [`FileIndex::watch`] is called.
+//!
+//! Entries are paths relative to the indexed root; directories end in `/`.
+
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::OsStr;
+use std::path::{MAIN_SEPARATOR, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock, RwLock, Weak};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Cache directory, relative to the Stakpak home directory.
+const CACHE_DIR: &str = "cache/file-index";
+
+/// Bumped when the cache layout changes; older caches are rebuilt.
+const CACHE_VERSION: u32 = 1;
+
+/// Filesystem events arriving this close together are applied as one batch.
+const WATCH_BATCH: Duration = Duration::from_millis(200);
+
+const WALK_THREADS: usize = 2;
+
+/// Directories a home scan never enters: caches, toolchains and dependency
+/// trees that do not hold the user's own projects.
+const HOME_SKIP_DIRS: &[&str] = &[
+    "node_modules",
+    "vendor",
+    "target",
+    ".terraform",
+    "venv",
+    ".venv",
+    "__pycache__",
+    ".cache",
+    ".Trash",
+    "Library",
+    ".local",
+    ".cargo",
+    ".rustup",
+    ".npm",
+    ".nvm",
+    ".pyenv",
+    ".gradle",
+    ".m2",
+    ".docker",
+    ".kube",
+    ".aws",
+];
+
+static OPENED: LazyLock<Mutex<HashMap<PathBuf, Arc<OnceLock<FileIndex>>>>> =
+    LazyLock::new(Default::default);
+
+#[derive(Debug, Clone)]
+pub struct FileIndexOptions {
+    /// Names the cache file, so one root can be indexed several ways.
+    pub kind: &'static str,
+    pub respect_gitignore: bool,
+    /// Directory names left out of the index, wherever they appear.
+    pub skip_dirs: &'static [&'static str],
+    /// Directory names listed in the index but not entered.
+    pub opaque_dirs: &'static [&'static str],
+    pub max_depth: Option<usize>,
+    /// Stop indexing after this many entries; the index is then partial.
+    pub max_entries: usize,
+    /// Stop a build after this long; the index is then partial.
+    pub max_build_time: Duration,
+    /// A cached index older than this is rebuilt when opened.
+    pub max_age: Duration,
+}
+
+impl FileIndexOptions {
+    /// A working tree: everything git does not ignore, hidden files included,
+    /// `.git` left out.
+    pub fn project() -> Self {
+        Self {
+            kind: "project",
+            respect_gitignore: true,
+            skip_dirs: &[".git"],
+            opaque_dirs: &[],
+            max_depth: None,
+            max_entries: 200_000,
+            max_build_time: Duration::from_secs(5),
+            max_age: Duration::from_secs(10 * 60),
+        }
+    }
+
+    /// A home directory scan for repositories and project roots. Gitignore is
+    /// not honoured, since dotfile repositories often ignore everything, and
+    /// `.git` directories are listed without being entered.
+    pub fn home() -> Self {
+        Self {
+            kind: "home",
+            respect_gitignore: false,
+            skip_dirs: HOME_SKIP_DIRS,
+            opaque_dirs: &[".git"],
+            max_depth: Some(6),
+            max_entries: 300_000,
+            max_build_time: Duration::from_secs(5),
+            max_age: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    root: PathBuf,
+    /// Unix timestamp of the walk the entries came from.
+    built_at: u64,
+    /// False when a budget stopped the walk early.
+    complete: bool,
+    entries: BTreeSet<String>,
+}
+
+struct Inner {
+    root: PathBuf,
+    options: FileIndexOptions,
+    cache_path: Option<PathBuf>,
+    snapshot: RwLock<Snapshot>,
+    /// Bumped on every change, so readers can skip copying unchanged entries.
+    generation: AtomicU64,
+    /// Entries came from the cache and may be missing recent changes.
+    from_cache: AtomicBool,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+/// Shared handle to an index; clones see the same entries.
+#[derive(Clone)]
+pub struct FileIndex {
+    inner: Arc<Inner>,
+}
+
+impl std::fmt::Debug for FileIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileIndex")
+            .field("root", &self.inner.root)
+            .field("kind", &self.inner.options.kind)
+            .field("entries", &self.len())
+            .field("complete", &self.is_complete())
+            .finish()
+    }
+}
+
+enum Change {
+    /// Directories whose children may have changed.
+    Dirs(Vec<PathBuf>),
+    /// The watcher dropped events; only a full rebuild is reliable.
+    Rescan,
+}
+
+impl FileIndex {
+    /// The process-wide index of `root`, loaded from the cache when it is
+    /// younger than `options.max_age` and built (and cached) otherwise.
+    /// Callers asking for the same root and kind share one index.
+    pub fn open(root: &Path, options: &FileIndexOptions) -> Self {
+        Self::open_in(
+            &crate::paths::stakpak_home_dir().join(CACHE_DIR),
+            root,
+            options,
+        )
+    }
+
+    /// [`FileIndex::open`] with the cache kept in `cache_dir`.
+    pub fn open_in(cache_dir: &Path, root: &Path, options: &FileIndexOptions) -> Self {
+        let cache_path = cache_file(cache_dir, root, options);
+        let slot = match OPENED.lock() {
+            Ok(mut opened) => opened.entry(cache_path.clone()).or_default().clone(),
+            Err(_) => Arc::new(OnceLock::new()),
+        };
+        slot.get_or_init(|| Self::load_or_build(root, options, cache_path))
+            .clone()
+    }
+
+    /// Walk `root` now, without reading or writing the cache.
+    pub fn build(root: &Path, options: &FileIndexOptions) -> Self {
+        Self::new(root, options, None, build_snapshot(root, options), false)
+    }
+
+    fn load_or_build(root: &Path, options: &FileIndexOptions, cache_path: PathBuf) -> Self {
+        let cached = read_snapshot(&cache_path).filter(|snapshot| {
+            snapshot.version == CACHE_VERSION
+                && snapshot.root == root
+                && age(snapshot.built_at) < options.max_age
+        });
+        match cached {
+            Some(snapshot) => Self::new(root, options, Some(cache_path), snapshot, true),
+            None => {
+                let snapshot = build_snapshot(root, options);
+                write_snapshot(&cache_path, &snapshot);
+                Self::new(root, options, Some(cache_path), snapshot, false)
+            }
+        }
+    }
+
+    fn new(
+        root: &Path,
+        options: &FileIndexOptions,
+        cache_path: Option<PathBuf>,
+        snapshot: Snapshot,
+        from_cache: bool,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                root: root.to_path_buf(),
+                options: options.clone(),
+                cache_path,
+                snapshot: RwLock::new(snapshot),
+                generation: AtomicU64::new(0),
+                from_cache: AtomicBool::new(from_cache),
+                watcher: Mutex::new(None),
+            }),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.inner.root
+    }
+
+    /// Absolute path of a relative path from [`FileIndex::files`] or
+    /// [`FileIndex::dirs`]; the empty path is the root itself.
+    pub fn absolute(&self, relative: &Path) -> PathBuf {
+        if relative.as_os_str().is_empty() {
+            self.inner.root.clone()
+        } else {
+            self.inner.root.join(relative)
+        }
+    }
+
+    /// False when a size or time budget cut the index short.
+    pub fn is_complete(&self) -> bool {
+        self.inner
+            .snapshot
+            .read()
+            .map(|snapshot| snapshot.complete)
+            .unwrap_or(false)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner
+            .snapshot
+            .read()
+            .map(|snapshot| snapshot.entries.len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Changes whenever the entries do.
+    pub fn generation(&self) -> u64 {
+        self.inner.generation.load(Ordering::Acquire)
+    }
+
+    /// All entries in path order.
+    pub fn entries(&self) -> Vec<String> {
+        self.inner
+            .snapshot
+            .read()
+            .map(|snapshot| snapshot.entries.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Relative paths of files at most `max_depth` components deep.
+    pub fn files(&self, max_depth: usize) -> Vec<PathBuf> {
+        self.select(max_depth, false)
+    }
+
+    /// Relative paths of directories at most `max_depth` components deep.
+    pub fn dirs(&self, max_depth: usize) -> Vec<PathBuf> {
+        self.select(max_depth, true)
+    }
+
+    fn select(&self, max_depth: usize, dirs: bool) -> Vec<PathBuf> {
+        let Ok(snapshot) = self.inner.snapshot.read() else {
+            return Vec::new();
+        };
+        snapshot
+            .entries
+            .iter()
+            .filter(|entry| entry.ends_with('/') == dirs)
+            .map(|entry| PathBuf::from(entry.trim_end_matches('/')))
+            .filter(|path| path.components().count() <= max_depth)
+            .collect()
+    }
+
+    /// Keep the index fresh from filesystem notifications until it is
+    /// dropped. An index loaded from the cache is rebuilt once in the
+    /// background first, to pick up changes made while nothing watched it.
+    /// Partial indexes are not watched: a tree too large to index is too
+    /// large to watch. Calling this again is a no-op.
+    pub fn watch(&self) -> Result<(), String> {
+        let mut slot = self
+            .inner
+            .watcher
+            .lock()
+            .map_err(|_| "file index watcher lock poisoned".to_string())?;
+        if slot.is_some() {
+            return Ok(());
+        }
+        if !self.is_complete() {
+            return Err(format!(
+                "{} is too large to watch; it is re-indexed when the cache expires",
+                self.inner.root.display()
+            ));
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+            if let Some(change) = result.ok().as_ref().and_then(Change::from_event) {
+                let _ = tx.send(change);
+            }
+        })
+        .map_err(|e| format!("Failed to create file index watcher: {}", e))?;
+        watcher
+            .watch(&self.inner.root, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {}", self.inner.root.display(), e))?;
+        *slot = Some(watcher);
+
+        let revalidate = self.inner.from_cache.swap(false, Ordering::AcqRel);
+        let inner = Arc::downgrade(&self.inner);
+        std::thread::Builder::new()
+            .name("file-index-watch".to_string())
+            .spawn(move || watch_loop(inner, rx, revalidate))
+            .map_err(|e| format!("Failed to start file index watcher: {}", e))?;
+        Ok(())
+    }
+}
+
+impl Change {
+    fn from_event(event: &Event) -> Option<Self> {
+        if event.need_rescan() {
+            return Some(Change::Rescan);
+        }
+        match event.kind {
+            EventKind::Create(_)
+            | EventKind::Remove(_)
+            | EventKind::Modify(ModifyKind::Name(_))
+            | EventKind::Any => Some(Change::Dirs(
+                event
+                    .paths
+                    .iter()
+                    .filter_map(|path| path.parent())
+                    .map(Path::to_path_buf)
+                    .collect(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn watch_loop(inner: Weak<Inner>, rx: Receiver<Change>, revalidate: bool) {
+    if revalidate && let Some(inner) = inner.upgrade() {
+        inner.rebuild();
+    }
+    while let Ok(first) = rx.recv() {
+        let mut batch = vec![first];
+        let deadline = Instant::now() + WATCH_BATCH;
+        loop {
+            match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(change) => batch.push(change),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        let Some(inner) = inner.upgrade() else {
+            return;
+        };
+        inner.apply(batch);
+    }
+}
+
+impl Inner {
+    fn apply(&self, batch: Vec<Change>) {
+        let mut dirs = Vec::new();
+        for change in batch {
+            match change {
+                Change::Dirs(changed) => dirs.extend(changed),
+                Change::Rescan => {
+                    self.rebuild();
+                    return;
+                }
+            }
+        }
+        // Parents first, so a removed directory is gone before its children
+        // are looked at.
+        dirs.sort_by_cached_key(|dir| (dir.components().count(), dir.clone()));
+        dirs.dedup();
+        let mut changed = false;
+        for dir in dirs {
+            changed |= self.refresh_dir(&dir);
+        }
+        if changed {
+            self.generation.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+
+    fn rebuild(&self) {
+        let snapshot = build_snapshot(&self.root, &self.options);
+        if let Some(cache_path) = &self.cache_path {
+            write_snapshot(cache_path, &snapshot);
+        }
+        if let Ok(mut current) = self.snapshot.write() {
+            *current = snapshot;
+        }
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Re-list the children of an indexed directory, dropping vanished ones
+    /// and walking new subdirectories. Returns whether anything changed.
+    fn refresh_dir(&self, dir: &Path) -> bool {
+        let Some(relative) = dir.strip_prefix(&self.root).ok().and_then(Path::to_str) else {
+            return false;
+        };
+        let relative_path = Path::new(relative);
+        if relative_path.components().any(|component| {
+            let name = component.as_os_str();
+            is_named(name, self.options.skip_dirs) || is_named(name, self.options.opaque_dirs)
+        }) {
+            return false;
+        }
+        let depth = relative_path.components().count();
+        if self.options.max_depth.is_some_and(|max| depth >= max) {
+            return false;
+        }
+        let prefix = if relative.is_empty() {
+            String::new()
+        } else {
+            format!("{}{}", relative, MAIN_SEPARATOR)
+        };
+
+        let current = {
+            let Ok(snapshot) = self.snapshot.read() else {
+                return false;
+            };
+            // Ignored directories are not indexed, and their events skipped.
+            if !relative.is_empty() && !snapshot.entries.contains(&format!("{}/", relative)) {
+                return false;
+            }
+            children(&snapshot.entries, &prefix)
+        };
+        let deadline = Instant::now() + self.options.max_build_time;
+        let listed: BTreeSet<String> = walk(
+            &self.root,
+            dir,
+            Some(1),
+            &self.options,
+            usize::MAX,
+            deadline,
+        )
+        .entries
+        .into_iter()
+        .collect();
+
+        let removed: Vec<&String> = current.iter().filter(|e| !listed.contains(*e)).collect();
+        let mut added: Vec<String> = Vec::new();
+        let mut truncated = false;
+        for entry in listed.iter().filter(|e| !current.contains(*e)) {
+            added.push(entry.clone());
+            let Some(subdir) = entry.strip_suffix('/') else {
+                continue;
+            };
+            let subdir = Path::new(subdir);
+            if subdir
+                .file_name()
+                .is_some_and(|name| is_named(name, self.options.opaque_dirs))
+            {
+                continue;
+            }
+            let walked = walk(
+                &self.root,
+                &self.root.join(subdir),
+                self.options
+                    .max_depth
+                    .map(|max| max.saturating_sub(depth + 1)),
+                &self.options,
+                self.options.max_entries,
+                deadline,
+            );
+            truncated |= walked.truncated;
+            added.extend(walked.entries);
+        }
+        if removed.is_empty() && added.is_empty() {
+            return false;
+        }
+
+        let Ok(mut snapshot) = self.snapshot.write() else {
+            return false;
+        };
+        for entry in removed {
+            snapshot.entries.remove(entry);
+            if let Some(subdir) = entry.strip_suffix('/') {
+                let nested = format!("{}{}", subdir, MAIN_SEPARATOR);
+                let descendants: Vec<String> = snapshot
+                    .entries
+                    .range(nested.clone()..)
+                    .take_while(|e| e.starts_with(&nested))
+                    .cloned()
+                    .collect();
+                for descendant in descendants {
+                    snapshot.entries.remove(&descendant);
+                }
+            }
+        }
+        for entry in added {
+            if snapshot.entries.len() >= self.options.max_entries {
+                truncated = true;
+                break;
+            }
+            snapshot.entries.insert(entry);
+        }
+        if truncated {
+            snapshot.complete = false;
+        }
+        true
+    }
+}
+
+/// Direct children of the directory whose entries start with `prefix`.
+fn children(entries: &BTreeSet<String>, prefix: &str) -> BTreeSet<String> {
+    entries
+        .range(prefix.to_string()..)
+        .take_while(|entry| entry.starts_with(prefix))
+        .filter(|entry| {
+            entry.strip_prefix(prefix).is_some_and(|rest| {
+                !rest.is_empty() && !rest.trim_end_matches('/').contains(MAIN_SEPARATOR)
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+fn build_snapshot(root: &Path, options: &FileIndexOptions) -> Snapshot {
+    let walked = walk(
+        root,
+        root,
+        options.max_depth,
+        options,
+        options.max_entries,
+        Instant::now() + options.max_build_time,
+    );
+    Snapshot {
+        version: CACHE_VERSION,
+        root: root.to_path_buf(),
+        built_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        complete: !walked.truncated,
+        entries: walked.entries.into_iter().collect(),
+    }
+}
+
+struct Walked {
+    entries: Vec<String>,
+    truncated: bool,
+}
+
+/// Walk `start` (inside `root`) collecting entries relative to `root`, until
+/// `limit` entries or `deadline`.
+fn walk(
+    root: &Path,
+    start: &Path,
+    max_depth: Option<usize>,
+    options: &FileIndexOptions,
+    limit: usize,
+    deadline: Instant,
+) -> Walked {
+    let skip_dirs = options.skip_dirs;
+    let walker = ignore::WalkBuilder::new(start)
+        .threads(WALK_THREADS)
+        .hidden(false)
+        .git_ignore(options.respect_gitignore)
+        .git_global(options.respect_gitignore)
+        .git_exclude(options.respect_gitignore)
+        .require_git(false)
+        .max_depth(max_depth)
+        .filter_entry(move |entry| entry.depth() == 0 || !is_named(entry.file_name(), skip_dirs))
+        .build_parallel();
+
+    let found = Mutex::new(Vec::new());
+    let truncated = AtomicBool::new(false);
+    walker.run(|| {
+        let (found, truncated) = (&found, &truncated);
+        Box::new(move |result| {
+            let Ok(entry) = result else {
+                return ignore::WalkState::Continue;
+            };
+            let Some(file_type) = entry.file_type() else {
+                return ignore::WalkState::Continue;
+            };
+            if entry.depth() == 0 || !(file_type.is_file() || file_type.is_dir()) {
+                return ignore::WalkState::Continue;
+            }
+            let Some(relative) = entry.path().strip_prefix(root).ok().and_then(Path::to_str) else {
+                return ignore::WalkState::Continue;
+            };
+            let Ok(mut found) = found.lock() else {
+                return ignore::WalkState::Quit;
+            };
+            if found.len() >= limit || Instant::now() >= deadline {
+                truncated.store(true, Ordering::Relaxed);
+                return ignore::WalkState::Quit;
+            }
+            if file_type.is_dir() {
+                found.push(format!("{}/", relative));
+                if is_named(entry.file_name(), options.opaque_dirs) {
+                    return ignore::WalkState::Skip;
+                }
+            } else {
+                found.push(relative.to_string());
+            }
+            ignore::WalkState::Continue
+        })
+    });
+
+    Walked {
+        entries: found.into_inner().unwrap_or_default(),
+        truncated: truncated.into_inner(),
+    }
+}
+
+fn is_named(name: &OsStr, names: &[&str]) -> bool {
+    names.iter().any(|candidate| name == *candidate)
+}
+
+fn cache_file(cache_dir: &Path, root: &Path, options: &FileIndexOptions) -> PathBuf {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    root.hash(&mut hasher);
+    cache_dir.join(format!("{}-{:016x}.json", options.kind, hasher.finish()))
+}
+
+fn age(built_at: u64) -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH + Duration::from_secs(built_at))
+        .unwrap_or_default()
+}
+
+fn read_snapshot(path: &Path) -> Option<Snapshot> {
+    let content = std::fs::read(path).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+/// Write through a temporary file so a concurrent reader never sees half a
+/// cache.
+fn write_snapshot(path: &Path, snapshot: &Snapshot) {
+    let Some(dir) = path.parent() else {
+        return;
+    };
+    let Ok(content) = serde_json::to_vec(snapshot) else {
+        return;
+    };
+    let temp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    let written = std::fs::create_dir_all(dir)
+        .and_then(|()| std::fs::write(&temp, content))
+        .and_then(|()| std::fs::rename(&temp, path));
+    if let Err(error) = written {
+        tracing::debug!("Failed to cache file index {}: {}", path.display(), error);
+        let _ = std::fs::remove_file(&temp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, relative: &str) {
+        let path = root.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create dir");
+        }
+        std::fs::write(path, "x").expect("write file");
+    }
+
+    fn tree() -> tempfile::TempDir {
+        let temp = tempfile::TempDir::new().expect("temp dir");
+        std::fs::create_dir_all(temp.path().join(".git/objects")).expect("git dir");
+        std::fs::write(temp.path().join(".gitignore"), "target/\n*.log\n").expect("gitignore");
+        for file in [
+            "src/main.rs",
+            "src/lib/util.rs",
+            ".env",
+            "debug.log",
+            "target/debug/app",
+        ] {
+            write(temp.path(), file);
+        }
+        temp
+    }
+
+    fn wait_for(index: &FileIndex, condition: impl Fn(&[String]) -> bool) -> Vec<String> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let entries = index.entries();
+            if condition(&entries) || Instant::now() >= deadline {
+                return entries;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    fn project_index_honours_gitignore_and_leaves_out_git() {
+        let temp = tree();
+        let index = FileIndex::build(temp.path(), &FileIndexOptions::project());
+
+        assert!(index.is_complete());
+        assert_eq!(
+            index.entries(),
+            vec![
+                ".env",
+                ".gitignore",
+                "src/",
+                "src/lib/",
+                "src/lib/util.rs",
+                "src/main.rs"
+            ]
+        );
+        assert_eq!(
+            index.files(1),
+            vec![PathBuf::from(".env"), PathBuf::from(".gitignore")]
+        );
+        assert_eq!(
+            index.dirs(2),
+            vec![PathBuf::from("src"), PathBuf::from("src/lib")]
+        );
+    }
+
+    #[test]
+    fn home_index_lists_git_dirs_without_entering_them_and_respects_budgets() {
+        let temp = tree();
+        write(temp.path(), "node_modules/pkg/index.js");
+        let index = FileIndex::build(temp.path(), &FileIndexOptions::home());
+
+        let entries = index.entries();
+        assert!(entries.contains(&".git/".to_string()));
+        assert!(entries.contains(&"debug.log".to_string()));
+        assert!(!entries.iter().any(|e| e.starts_with("target")));
+        assert!(!entries.iter().any(|e| e.starts_with(".git/objects")));
+        assert!(!entries.iter().any(|e| e.starts_with("node_modules")));
+
+        let small = FileIndexOptions {
+            max_entries: 3,
+            ..FileIndexOptions::project()
+        };
+        let partial = FileIndex::build(temp.path(), &small);
+        assert_eq!(partial.len(), 3);
+        assert!(!partial.is_complete());
+        assert!(partial.watch().is_err());
+    }
+
+    #[test]
+    fn cached_index_is_reused_until_it_expires() {
+        let temp = tree();
+        let cache = tempfile::TempDir::new().expect("cache dir");
+        let options = FileIndexOptions::project();
+        let cache_path = cache_file(cache.path(), temp.path(), &options);
+
+        let first = FileIndex::load_or_build(temp.path(), &options, cache_path.clone());
+        assert!(cache_path.is_file());
+        write(temp.path(), "src/new.rs");
+
+        let cached = FileIndex::load_or_build(temp.path(), &options, cache_path.clone());
+        assert_eq!(cached.entries(), first.entries());
+
+        let expired = FileIndexOptions {
+            max_age: Duration::ZERO,
+            ..options
+        };
+        let rebuilt = FileIndex::load_or_build(temp.path(), &expired, cache_path);
+        assert!(rebuilt.entries().contains(&"src/new.rs".to_string()));
+    }
+
+    #[test]
+    fn watched_index_follows_creates_and_removes() {
+        let temp = tree();
+        let index = FileIndex::build(temp.path(), &FileIndexOptions::project());
+        index.watch().expect("watch");
+
+        write(temp.path(), "docs/guide/intro.md");
+        write(temp.path(), "build.log");
+        let entries = wait_for(&index, |e| e.iter().any(|e| e == "docs/guide/intro.md"));
+        assert!(entries.contains(&"docs/".to_string()));
+        assert!(entries.contains(&"docs/guide/".to_string()));
+        assert!(entries.contains(&"docs/guide/intro.md".to_string()));
+        assert!(!entries.contains(&"build.log".to_string()));
+
+        std::fs::remove_dir_all(temp.path().join("src")).expect("remove src");
+        let entries = wait_for(&index, |e| !e.iter().any(|e| e.starts_with("src")));
+        assert!(!entries.iter().any(|e| e.starts_with("src")));
+        assert!(index.generation() > 0);
+    }
+}
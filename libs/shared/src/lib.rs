@@ -1,7 +1,11 @@
 pub mod auth_manager;
 pub mod cert_utils;
 pub mod container;
+pub mod context_rules;
+pub mod discovery;
+pub mod error;
 pub mod file_backup_manager;
+pub mod file_index;
 pub mod file_watcher;
 pub mod helper;
 pub mod hooks;
@@ -10,6 +14,8 @@ pub mod local_store;
 pub mod models;
 pub mod oauth;
 pub mod paths;
+pub mod pending_interactions;
+pub mod project_scope;
 pub mod remote_connection;
 pub mod remote_store;
 pub mod secret_manager;
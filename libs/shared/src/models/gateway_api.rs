@@ -0,0 +1,395 @@
+//! Versioned wire types for the gateway notification API (`POST /v1/gateway/send`).
+//!
+//! Shared by the gateway server and the autopilot notification client so
+//! both sides build and parse the same payload. Requests carry
+//! `api_version`; a request without one is the unversioned payload autopilot
+//! sent before versioning and is treated as version 1. Versions newer than
+//! [`GATEWAY_API_VERSION`] are rejected with `unsupported_api_version` rather
+//! than half-understood.
+
+use serde::{Deserialize, Serialize};
+
+/// Current `/v1/gateway/send` payload version.
+pub const GATEWAY_API_VERSION: u32 = 1;
+
+pub const MAX_INTERACTIVE_PROMPT_BYTES: usize = 32 * 1024;
+pub const MAX_INTERACTIVE_CALLER_CONTEXT_ITEMS: usize = 50;
+pub const MAX_INTERACTIVE_CALLER_CONTEXT_NAME_BYTES: usize = 256;
+pub const MAX_INTERACTIVE_CALLER_CONTEXT_PRIORITY_BYTES: usize = 32;
+pub const MAX_INTERACTIVE_CALLER_CONTEXT_ITEM_BYTES: usize = 10 * 1024;
+pub const MAX_INTERACTIVE_CALLER_CONTEXT_TOTAL_BYTES: usize = 100 * 1024;
+
+fn legacy_api_version() -> u32 {
+    1
+}
+
+/// Body of `POST /v1/gateway/send`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GatewaySendRequest {
+    #[serde(default = "legacy_api_version")]
+    pub api_version: u32,
+    pub channel: String,
+    /// Channel-specific target, e.g. `{"channel": "#ops"}` for Slack.
+    pub target: serde_json::Value,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<NotificationContext>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interactive: Option<InteractiveOptions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<ApprovalOptions>,
+}
+
+/// Context stored with a delivery so replies in the same chat can see it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationContext {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_output: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_url: Option<String>,
+    /// Fields this version does not know about, kept as sent.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CallerContextInput {
+    pub name: String,
+    pub content: String,
+    #[serde(default)]
+    pub priority: Option<String>,
+}
+
+/// Start an agent session in the target chat after delivering the message.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct InteractiveOptions {
+    pub prompt: String,
+    #[serde(default)]
+    pub caller_context: Vec<CallerContextInput>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub sandbox: Option<bool>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+/// Post allow/deny buttons for the tool calls a session started elsewhere
+/// (e.g. a paused autopilot run) is waiting on.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ApprovalOptions {
+    pub session_id: String,
+}
+
+/// Successful `POST /v1/gateway/send` response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GatewaySendResponse {
+    #[serde(default = "legacy_api_version")]
+    pub api_version: u32,
+    pub delivered: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+}
+
+/// Machine-readable gateway API error codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatewayErrorCode {
+    Unauthorized,
+    UnsupportedApiVersion,
+    InvalidRequest,
+    InvalidTarget,
+    ChannelNotFound,
+    InteractiveAuthRequired,
+    ApprovalAuthRequired,
+    InvalidInteractivePrompt,
+    InvalidInteractiveTimeout,
+    InvalidInteractiveCallerContext,
+    DeliveryFailed,
+    DispatcherUnavailable,
+    SessionCreateFailed,
+    SessionPersistFailed,
+    SessionLookupFailed,
+    SessionNotFound,
+    InteractiveStartFailed,
+    ApprovalFailed,
+    CursorLookupFailed,
+    ReplayFailed,
+    MappingNotFound,
+    MappingUpdateFailed,
+    /// The mapped session has a run in progress.
+    RunActive,
+    /// A code added by a newer gateway.
+    #[serde(other)]
+    Unknown,
+}
+
+impl std::fmt::Display for GatewayErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let code = serde_json::to_value(self)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_owned))
+            .unwrap_or_else(|| "unknown".to_string());
+        f.write_str(&code)
+    }
+}
+
+/// Error body returned by the gateway API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("{error}: {message}")]
+pub struct GatewayApiError {
+    pub error: GatewayErrorCode,
+    pub message: String,
+}
+
+impl GatewayApiError {
+    pub fn new(error: GatewayErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            error,
+            message: message.into(),
+        }
+    }
+}
+
+impl GatewaySendRequest {
+    /// Plain notification at the current API version.
+    pub fn new(
+        channel: impl Into<String>,
+        target: serde_json::Value,
+        text: impl Into<String>,
+    ) -> Self {
+        Self {
+            api_version: GATEWAY_API_VERSION,
+            channel: channel.into(),
+            target,
+            text: text.into(),
+            context: None,
+            interactive: None,
+            approval: None,
+        }
+    }
+
+    /// Check the payload against the schema for its `api_version`.
+    ///
+    /// Channel-specific target parsing and auth are left to the gateway.
+    pub fn validate(&self) -> Result<(), GatewayApiError> {
+        if self.api_version == 0 || self.api_version > GATEWAY_API_VERSION {
+            return Err(GatewayApiError::new(
+                GatewayErrorCode::UnsupportedApiVersion,
+                format!(
+                    "api_version {} is not supported (supported: 1-{})",
+                    self.api_version, GATEWAY_API_VERSION
+                ),
+            ));
+        }
+        if self.channel.trim().is_empty() {
+            return Err(GatewayApiError::new(
+                GatewayErrorCode::InvalidRequest,
+                "channel must not be empty",
+            ));
+        }
+        if !self.target.is_object() {
+            return Err(GatewayApiError::new(
+                GatewayErrorCode::InvalidTarget,
+                "target must be a JSON object",
+            ));
+        }
+        if self.interactive.is_some() && self.approval.is_some() {
+            return Err(GatewayApiError::new(
+                GatewayErrorCode::InvalidRequest,
+                "interactive and approval cannot be combined",
+            ));
+        }
+        if let Some(approval) = &self.approval
+            && approval.session_id.trim().is_empty()
+        {
+            return Err(GatewayApiError::new(
+                GatewayErrorCode::InvalidRequest,
+                "approval.session_id must not be empty",
+            ));
+        }
+        if let Some(interactive) = &self.interactive {
+            interactive.validate()?;
+        }
+        Ok(())
+    }
+}
+
+impl InteractiveOptions {
+    /// Enforce prompt and caller-context size limits.
+    pub fn validate(&self) -> Result<(), GatewayApiError> {
+        let caller_context_error = |message: String| {
+            GatewayApiError::new(GatewayErrorCode::InvalidInteractiveCallerContext, message)
+        };
+
+        if self.prompt.trim().is_empty() {
+            return Err(GatewayApiError::new(
+                GatewayErrorCode::InvalidInteractivePrompt,
+                "interactive.prompt must not be empty",
+            ));
+        }
+
+        if self.prompt.len() > MAX_INTERACTIVE_PROMPT_BYTES {
+            return Err(GatewayApiError::new(
+                GatewayErrorCode::InvalidInteractivePrompt,
+                format!(
+                    "interactive.prompt exceeds {} bytes",
+                    MAX_INTERACTIVE_PROMPT_BYTES
+                ),
+            ));
+        }
+
+        if self.timeout == Some(0) {
+            return Err(GatewayApiError::new(
+                GatewayErrorCode::InvalidInteractiveTimeout,
+                "interactive.timeout must be greater than 0",
+            ));
+        }
+
+        if self.caller_context.len() > MAX_INTERACTIVE_CALLER_CONTEXT_ITEMS {
+            return Err(caller_context_error(format!(
+                "interactive.caller_context supports up to {} items",
+                MAX_INTERACTIVE_CALLER_CONTEXT_ITEMS
+            )));
+        }
+
+        let mut total_payload_bytes = self.prompt.len();
+
+        for item in &self.caller_context {
+            if item.name.trim().is_empty() {
+                return Err(caller_context_error(
+                    "interactive.caller_context item name must not be empty".to_string(),
+                ));
+            }
+
+            let name_bytes = item.name.len();
+            if name_bytes > MAX_INTERACTIVE_CALLER_CONTEXT_NAME_BYTES {
+                return Err(caller_context_error(format!(
+                    "interactive.caller_context item name '{}' exceeds {} bytes",
+                    item.name, MAX_INTERACTIVE_CALLER_CONTEXT_NAME_BYTES
+                )));
+            }
+
+            let priority_bytes = item.priority.as_ref().map_or(0, String::len);
+            if priority_bytes > MAX_INTERACTIVE_CALLER_CONTEXT_PRIORITY_BYTES {
+                return Err(caller_context_error(format!(
+                    "interactive.caller_context item '{}' priority exceeds {} bytes",
+                    item.name, MAX_INTERACTIVE_CALLER_CONTEXT_PRIORITY_BYTES
+                )));
+            }
+
+            let content_bytes = item.content.len();
+            if content_bytes > MAX_INTERACTIVE_CALLER_CONTEXT_ITEM_BYTES {
+                return Err(caller_context_error(format!(
+                    "interactive.caller_context item '{}' exceeds {} bytes",
+                    item.name, MAX_INTERACTIVE_CALLER_CONTEXT_ITEM_BYTES
+                )));
+            }
+
+            total_payload_bytes = total_payload_bytes
+                .saturating_add(name_bytes)
+                .saturating_add(priority_bytes)
+                .saturating_add(content_bytes);
+            if total_payload_bytes > MAX_INTERACTIVE_CALLER_CONTEXT_TOTAL_BYTES {
+                return Err(caller_context_error(format!(
+                    "interactive payload exceeds {} bytes",
+                    MAX_INTERACTIVE_CALLER_CONTEXT_TOTAL_BYTES
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unversioned_legacy_payload_parses_as_version_one() {
+        let request: GatewaySendRequest = serde_json::from_value(serde_json::json!({
+            "channel": "slack",
+            "target": {"channel": "#ops"},
+            "text": "disk at 91%",
+            "context": {"schedule": "disk-check", "status": "failed", "host": "web-1"},
+            "approval": null,
+        }))
+        .expect("legacy payload should parse");
+
+        assert_eq!(request.api_version, 1);
+        assert_eq!(request.approval, None);
+        let context = request.context.as_ref().expect("context");
+        assert_eq!(context.schedule.as_deref(), Some("disk-check"));
+        assert_eq!(context.extra.get("host"), Some(&serde_json::json!("web-1")));
+        assert_eq!(request.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_rejects_unknown_versions_and_bad_shapes() {
+        let mut request =
+            GatewaySendRequest::new("slack", serde_json::json!({"channel": "#ops"}), "hi");
+        assert_eq!(request.validate(), Ok(()));
+
+        request.api_version = GATEWAY_API_VERSION + 1;
+        assert_eq!(
+            request.validate().map_err(|e| e.error),
+            Err(GatewayErrorCode::UnsupportedApiVersion)
+        );
+
+        request.api_version = GATEWAY_API_VERSION;
+        request.target = serde_json::json!("#ops");
+        assert_eq!(
+            request.validate().map_err(|e| e.error),
+            Err(GatewayErrorCode::InvalidTarget)
+        );
+
+        request.target = serde_json::json!({"channel": "#ops"});
+        request.approval = Some(ApprovalOptions {
+            session_id: "session-1".to_string(),
+        });
+        request.interactive = Some(InteractiveOptions {
+            prompt: "run".to_string(),
+            caller_context: Vec::new(),
+            model: None,
+            sandbox: None,
+            timeout: None,
+            title: None,
+        });
+        assert_eq!(
+            request.validate().map_err(|e| e.error),
+            Err(GatewayErrorCode::InvalidRequest)
+        );
+    }
+
+    #[test]
+    fn error_codes_round_trip_and_tolerate_new_codes() {
+        let error = GatewayApiError::new(
+            GatewayErrorCode::ChannelNotFound,
+            "Channel 'x' is not connected",
+        );
+        let json = serde_json::to_value(&error).expect("serialize");
+        assert_eq!(json["error"], "channel_not_found");
+        assert_eq!(
+            error.to_string(),
+            "channel_not_found: Channel 'x' is not connected"
+        );
+
+        let newer: GatewayApiError = serde_json::from_value(serde_json::json!({
+            "error": "rate_limited",
+            "message": "slow down",
+        }))
+        .expect("unknown code should parse");
+        assert_eq!(newer.error, GatewayErrorCode::Unknown);
+    }
+}
@@ -604,7 +604,7 @@ pub struct TaskPauseInfo {
 }
 
 pub use crate::models::tools::ask_user::{
-    AskUserAnswer, AskUserOption, AskUserQuestion, AskUserRequest, AskUserResult,
+    AskUserAnswer, AskUserInputType, AskUserOption, AskUserQuestion, AskUserRequest, AskUserResult,
 };
 
 /// Chat completion request
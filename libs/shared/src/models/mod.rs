@@ -3,6 +3,7 @@ pub mod auth;
 pub mod billing;
 pub mod context;
 pub mod error;
+pub mod gateway_api;
 pub mod indexing;
 pub mod integrations;
 pub mod llm;
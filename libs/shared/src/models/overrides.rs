@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(untagged)]
@@ -20,6 +21,9 @@ pub struct RunOverrides {
     pub system_prompt: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_turns: Option<usize>,
+    /// Extra environment variables for commands the agent runs in this run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
 }
 
 impl RunOverrides {
@@ -28,12 +32,14 @@ impl RunOverrides {
             && self.auto_approve.is_none()
             && self.system_prompt.is_none()
             && self.max_turns.is_none()
+            && self.env.is_none()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::{AutoApproveOverride, RunOverrides};
+    use std::collections::BTreeMap;
 
     #[test]
     fn run_overrides_is_empty_only_when_all_fields_absent() {
@@ -51,6 +57,12 @@ mod tests {
             ..RunOverrides::default()
         };
         assert!(!with_allowlist.is_empty());
+
+        let with_env = RunOverrides {
+            env: Some(BTreeMap::from([("APP_ENV".to_string(), "ci".to_string())])),
+            ..RunOverrides::default()
+        };
+        assert!(!with_env.is_empty());
     }
 
     #[test]
@@ -63,6 +75,7 @@ mod tests {
             ])),
             system_prompt: Some("hello".to_string()),
             max_turns: Some(24),
+            env: Some(BTreeMap::from([("APP_ENV".to_string(), "ci".to_string())])),
         };
 
         let encoded = serde_json::to_string(&overrides).expect("serialize overrides");
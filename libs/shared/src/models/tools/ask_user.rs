@@ -39,6 +39,122 @@ pub struct AskUserQuestion {
         description = "When true, user can select/deselect multiple options (checkbox list). Default: false (single-select radio behavior)."
     )]
     pub multi_select: bool,
+    /// Kind of value the custom input accepts. Plain free text when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Kind of value the custom text input accepts, validated before the user can confirm it: text (optional regex `pattern` and `pattern_hint`), number (optional `min`/`max`, `integer`), secret (masked while typing, redacted in the answer), or path (tab completion, optional `must_exist`). Omit for plain free text."
+    )]
+    pub input_type: Option<AskUserInputType>,
+}
+
+/// Kind of value a question's custom input accepts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, schemars::JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AskUserInputType {
+    /// Free text, optionally required to match a regex.
+    Text {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[schemars(description = "Regex the whole answer must match")]
+        pattern: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[schemars(
+            description = "Shown when the answer doesn't match `pattern`, e.g. \"a lowercase DNS name\""
+        )]
+        pattern_hint: Option<String>,
+    },
+    /// A number, optionally bounded.
+    Number {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[schemars(description = "Smallest accepted value (inclusive)")]
+        min: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[schemars(description = "Largest accepted value (inclusive)")]
+        max: Option<f64>,
+        #[serde(default)]
+        #[schemars(description = "Only accept whole numbers")]
+        integer: bool,
+    },
+    /// A secret (password, token). Masked while typing and redacted before
+    /// the answer reaches the LLM.
+    Secret,
+    /// A filesystem path, with tab completion.
+    Path {
+        #[serde(default)]
+        #[schemars(description = "Only accept paths that exist")]
+        must_exist: bool,
+    },
+}
+
+impl AskUserInputType {
+    /// Check a custom answer, returning a message to show the user when it
+    /// isn't acceptable.
+    pub fn validate(&self, input: &str) -> Result<(), String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err("Answer can't be empty".to_string());
+        }
+        match self {
+            AskUserInputType::Text {
+                pattern: Some(pattern),
+                pattern_hint,
+            } => {
+                // Anchor so the pattern has to match the whole answer
+                let regex = regex::Regex::new(&format!("^(?:{})$", pattern))
+                    .map_err(|e| format!("Invalid pattern from the agent: {}", e))?;
+                if regex.is_match(input) {
+                    Ok(())
+                } else {
+                    Err(match pattern_hint {
+                        Some(hint) => format!("Expected {}", hint),
+                        None => format!("Must match {}", pattern),
+                    })
+                }
+            }
+            AskUserInputType::Text { pattern: None, .. } | AskUserInputType::Secret => Ok(()),
+            AskUserInputType::Number { min, max, integer } => {
+                let value: f64 = input
+                    .parse()
+                    .ok()
+                    .filter(|value: &f64| value.is_finite())
+                    .ok_or_else(|| "Enter a number".to_string())?;
+                if *integer && value.fract() != 0.0 {
+                    return Err("Enter a whole number".to_string());
+                }
+                match (min, max) {
+                    (Some(min), Some(max)) if value < *min || value > *max => {
+                        Err(format!("Enter a number from {} to {}", min, max))
+                    }
+                    (Some(min), _) if value < *min => Err(format!("Enter at least {}", min)),
+                    (_, Some(max)) if value > *max => Err(format!("Enter at most {}", max)),
+                    _ => Ok(()),
+                }
+            }
+            AskUserInputType::Path { must_exist } => {
+                if *must_exist && !expand_home(input).exists() {
+                    Err(format!("{} doesn't exist", input))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    pub fn is_secret(&self) -> bool {
+        matches!(self, AskUserInputType::Secret)
+    }
+
+    pub fn is_path(&self) -> bool {
+        matches!(self, AskUserInputType::Path { .. })
+    }
+}
+
+/// Expand a leading `~/` to the home directory.
+pub fn expand_home(path: &str) -> std::path::PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ if path == "~" => dirs::home_dir().unwrap_or_else(|| path.into()),
+        _ => path.into(),
+    }
 }
 
 /// A predefined answer option for a question.
@@ -157,6 +273,7 @@ mod tests {
             ],
             allow_custom: true,
             multi_select: false,
+            input_type: None,
         };
 
         let json = serde_json::to_string(&question).unwrap();
@@ -320,6 +437,7 @@ mod tests {
             ],
             allow_custom: true,
             multi_select: false,
+            input_type: None,
         };
 
         let json = serde_json::to_string(&question).unwrap();
@@ -339,6 +457,7 @@ mod tests {
             options: vec![],
             allow_custom: true,
             multi_select: false,
+            input_type: None,
         };
 
         let q2 = q1.clone();
@@ -378,6 +497,7 @@ mod tests {
                 }],
                 allow_custom: false,
                 multi_select: false,
+                input_type: None,
             }],
         };
 
@@ -433,6 +553,7 @@ mod tests {
             ],
             allow_custom: false,
             multi_select: true,
+            input_type: None,
         };
 
         let json = serde_json::to_string(&question).unwrap();
@@ -543,4 +664,112 @@ mod tests {
         assert_eq!(parsed.value, "custom_value");
         assert_eq!(parsed.label, "Display Label");
     }
+
+    #[test]
+    fn test_input_type_deserialization() {
+        let json = r#"{
+            "label": "Port",
+            "question": "Which port?",
+            "options": [],
+            "input_type": {"type": "number", "min": 1, "max": 65535, "integer": true}
+        }"#;
+        let question: AskUserQuestion = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            question.input_type,
+            Some(AskUserInputType::Number {
+                min: Some(1.0),
+                max: Some(65535.0),
+                integer: true,
+            })
+        );
+
+        let secret: AskUserInputType = serde_json::from_str(r#"{"type": "secret"}"#).unwrap();
+        assert!(secret.is_secret());
+        let path: AskUserInputType = serde_json::from_str(r#"{"type": "path"}"#).unwrap();
+        assert_eq!(path, AskUserInputType::Path { must_exist: false });
+    }
+
+    #[test]
+    fn test_input_type_omitted_when_absent() {
+        let json = r#"{"label": "Env", "question": "Which env?", "options": []}"#;
+        let question: AskUserQuestion = serde_json::from_str(json).unwrap();
+        assert_eq!(question.input_type, None);
+        assert!(
+            !serde_json::to_string(&question)
+                .unwrap()
+                .contains("input_type")
+        );
+    }
+
+    #[test]
+    fn test_number_validation() {
+        let port = AskUserInputType::Number {
+            min: Some(1.0),
+            max: Some(65535.0),
+            integer: true,
+        };
+        assert!(port.validate("8080").is_ok());
+        assert!(port.validate(" 443 ").is_ok());
+        assert_eq!(port.validate("abc"), Err("Enter a number".to_string()));
+        assert_eq!(
+            port.validate("80.5"),
+            Err("Enter a whole number".to_string())
+        );
+        assert_eq!(
+            port.validate("70000"),
+            Err("Enter a number from 1 to 65535".to_string())
+        );
+        assert_eq!(port.validate(""), Err("Answer can't be empty".to_string()));
+
+        let ratio = AskUserInputType::Number {
+            min: Some(0.0),
+            max: None,
+            integer: false,
+        };
+        assert!(ratio.validate("0.25").is_ok());
+        assert_eq!(ratio.validate("-1"), Err("Enter at least 0".to_string()));
+        assert!(ratio.validate("NaN").is_err());
+    }
+
+    #[test]
+    fn test_text_pattern_validation() {
+        let name = AskUserInputType::Text {
+            pattern: Some("[a-z][a-z0-9-]*".to_string()),
+            pattern_hint: Some("a lowercase DNS name".to_string()),
+        };
+        assert!(name.validate("api-v2").is_ok());
+        // The pattern must match the whole answer
+        assert_eq!(
+            name.validate("api v2"),
+            Err("Expected a lowercase DNS name".to_string())
+        );
+
+        let no_hint = AskUserInputType::Text {
+            pattern: Some("\\d+".to_string()),
+            pattern_hint: None,
+        };
+        assert_eq!(no_hint.validate("x"), Err("Must match \\d+".to_string()));
+
+        let broken = AskUserInputType::Text {
+            pattern: Some("(".to_string()),
+            pattern_hint: None,
+        };
+        assert!(broken.validate("x").is_err());
+    }
+
+    #[test]
+    fn test_path_validation() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let existing = dir.path().to_string_lossy().to_string();
+        let missing = dir.path().join("missing").to_string_lossy().to_string();
+
+        let must_exist = AskUserInputType::Path { must_exist: true };
+        assert!(must_exist.validate(&existing).is_ok());
+        assert!(must_exist.validate(&missing).is_err());
+        assert!(
+            AskUserInputType::Path { must_exist: false }
+                .validate(&missing)
+                .is_ok()
+        );
+    }
 }
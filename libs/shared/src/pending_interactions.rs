@@ -0,0 +1,296 @@
+//! Tracking for approval and ask_user prompts that are waiting on a human.
+//!
+//! Both the TUI and the gateway keep interactions open until someone answers.
+//! This module decides *when* to remind: each tracked interaction moves through
+//! escalating [`ReminderStage`]s as it ages, and every stage fires at most once.
+//! Callers own the actual side effect (terminal bell, channel re-ping, ...).
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Default delay before the TUI rings the terminal bell.
+pub const DEFAULT_BELL_AFTER: Duration = Duration::from_secs(2 * 60);
+/// Default delay before a channel prompt is re-pinged.
+pub const DEFAULT_REPING_AFTER: Duration = Duration::from_secs(10 * 60);
+/// Default delay before a prompt is escalated to a secondary channel.
+pub const DEFAULT_ESCALATE_AFTER: Duration = Duration::from_secs(30 * 60);
+
+/// What kind of human input an interaction is waiting for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InteractionKind {
+    Approval,
+    AskUser,
+}
+
+/// Escalating reminder stages, in firing order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ReminderStage {
+    /// Local nudge, e.g. the terminal bell.
+    Bell,
+    /// Re-send the prompt on the channel it was originally sent to.
+    Reping,
+    /// Notify a secondary channel that the prompt is still unanswered.
+    Escalate,
+}
+
+impl ReminderStage {
+    const ALL: [ReminderStage; 3] = [
+        ReminderStage::Bell,
+        ReminderStage::Reping,
+        ReminderStage::Escalate,
+    ];
+}
+
+/// Delays for each reminder stage. `None` disables a stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReminderPolicy {
+    pub bell_after: Option<Duration>,
+    pub reping_after: Option<Duration>,
+    pub escalate_after: Option<Duration>,
+}
+
+impl ReminderPolicy {
+    /// Policy with every stage disabled.
+    pub fn disabled() -> Self {
+        Self {
+            bell_after: None,
+            reping_after: None,
+            escalate_after: None,
+        }
+    }
+
+    /// Bell-only policy used by the TUI.
+    pub fn terminal() -> Self {
+        Self {
+            bell_after: Some(DEFAULT_BELL_AFTER),
+            ..Self::disabled()
+        }
+    }
+
+    fn delay(&self, stage: ReminderStage) -> Option<Duration> {
+        match stage {
+            ReminderStage::Bell => self.bell_after,
+            ReminderStage::Reping => self.reping_after,
+            ReminderStage::Escalate => self.escalate_after,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        ReminderStage::ALL
+            .iter()
+            .any(|stage| self.delay(*stage).is_some())
+    }
+}
+
+/// A reminder that became due on the latest [`PendingInteractionTracker::due`] call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DueReminder<K> {
+    pub key: K,
+    pub kind: InteractionKind,
+    pub stage: ReminderStage,
+    /// How long the interaction has been waiting.
+    pub waiting: Duration,
+}
+
+#[derive(Debug, Clone)]
+struct PendingInteraction {
+    kind: InteractionKind,
+    since: Instant,
+    fired: Vec<ReminderStage>,
+}
+
+/// Tracks open interactions by key and reports reminders as they come due.
+///
+/// Time is passed in explicitly so callers can drive the tracker from their own
+/// tick loop and tests can control the clock.
+#[derive(Debug, Clone)]
+pub struct PendingInteractionTracker<K> {
+    policy: ReminderPolicy,
+    pending: HashMap<K, PendingInteraction>,
+}
+
+impl<K: Eq + Hash + Clone> PendingInteractionTracker<K> {
+    pub fn new(policy: ReminderPolicy) -> Self {
+        Self {
+            policy,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn policy(&self) -> &ReminderPolicy {
+        &self.policy
+    }
+
+    /// Start tracking an interaction. Re-tracking an open key keeps its
+    /// original start time so reminders are not reset by repeated calls.
+    pub fn track(&mut self, key: K, kind: InteractionKind, now: Instant) {
+        self.pending.entry(key).or_insert(PendingInteraction {
+            kind,
+            since: now,
+            fired: Vec::new(),
+        });
+    }
+
+    /// Stop tracking an interaction. Returns whether it was being tracked.
+    pub fn resolve(&mut self, key: &K) -> bool {
+        self.pending.remove(key).is_some()
+    }
+
+    /// Drop every interaction whose key no longer satisfies `keep`. Useful when
+    /// the caller's own state is the source of truth for what is still open.
+    pub fn retain(&mut self, mut keep: impl FnMut(&K) -> bool) {
+        self.pending.retain(|key, _| keep(key));
+    }
+
+    pub fn is_tracked(&self, key: &K) -> bool {
+        self.pending.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Return every reminder stage that has come due since the last call and
+    /// mark it as fired.
+    pub fn due(&mut self, now: Instant) -> Vec<DueReminder<K>> {
+        let mut due = Vec::new();
+        for (key, interaction) in &mut self.pending {
+            let waiting = now.saturating_duration_since(interaction.since);
+            for stage in ReminderStage::ALL {
+                let Some(delay) = self.policy.delay(stage) else {
+                    continue;
+                };
+                if waiting < delay || interaction.fired.contains(&stage) {
+                    continue;
+                }
+                interaction.fired.push(stage);
+                due.push(DueReminder {
+                    key: key.clone(),
+                    kind: interaction.kind,
+                    stage,
+                    waiting,
+                });
+            }
+        }
+        due.sort_by_key(|reminder| reminder.stage);
+        due
+    }
+}
+
+/// Short human-readable form of a waiting duration, e.g. `12m` or `45s`.
+pub fn format_waiting(waiting: Duration) -> String {
+    let secs = waiting.as_secs();
+    if secs >= 3600 {
+        format!("{}h{}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> ReminderPolicy {
+        ReminderPolicy {
+            bell_after: Some(Duration::from_secs(120)),
+            reping_after: Some(Duration::from_secs(600)),
+            escalate_after: Some(Duration::from_secs(1800)),
+        }
+    }
+
+    #[test]
+    fn stages_fire_once_in_order() {
+        let start = Instant::now();
+        let mut tracker = PendingInteractionTracker::new(policy());
+        tracker.track("a", InteractionKind::Approval, start);
+
+        assert!(tracker.due(start + Duration::from_secs(60)).is_empty());
+
+        let due = tracker.due(start + Duration::from_secs(121));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].stage, ReminderStage::Bell);
+        assert!(tracker.due(start + Duration::from_secs(200)).is_empty());
+
+        let due = tracker.due(start + Duration::from_secs(601));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].stage, ReminderStage::Reping);
+
+        let due = tracker.due(start + Duration::from_secs(1801));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].stage, ReminderStage::Escalate);
+        assert!(tracker.due(start + Duration::from_secs(5000)).is_empty());
+    }
+
+    #[test]
+    fn late_tick_fires_all_overdue_stages() {
+        let start = Instant::now();
+        let mut tracker = PendingInteractionTracker::new(policy());
+        tracker.track(1u32, InteractionKind::AskUser, start);
+
+        let stages: Vec<_> = tracker
+            .due(start + Duration::from_secs(3600))
+            .into_iter()
+            .map(|reminder| reminder.stage)
+            .collect();
+        assert_eq!(
+            stages,
+            vec![
+                ReminderStage::Bell,
+                ReminderStage::Reping,
+                ReminderStage::Escalate
+            ]
+        );
+    }
+
+    #[test]
+    fn resolved_interactions_stop_reminding() {
+        let start = Instant::now();
+        let mut tracker = PendingInteractionTracker::new(policy());
+        tracker.track("a", InteractionKind::Approval, start);
+        assert!(tracker.resolve(&"a"));
+        assert!(!tracker.resolve(&"a"));
+        assert!(tracker.due(start + Duration::from_secs(3600)).is_empty());
+    }
+
+    #[test]
+    fn retracking_keeps_original_start() {
+        let start = Instant::now();
+        let mut tracker = PendingInteractionTracker::new(policy());
+        tracker.track("a", InteractionKind::Approval, start);
+        tracker.track(
+            "a",
+            InteractionKind::Approval,
+            start + Duration::from_secs(100),
+        );
+
+        let due = tracker.due(start + Duration::from_secs(121));
+        assert_eq!(due.len(), 1);
+    }
+
+    #[test]
+    fn disabled_stages_are_skipped() {
+        let start = Instant::now();
+        let mut tracker = PendingInteractionTracker::new(ReminderPolicy::terminal());
+        tracker.track("a", InteractionKind::Approval, start);
+
+        let due = tracker.due(start + Duration::from_secs(3600));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].stage, ReminderStage::Bell);
+        assert!(!ReminderPolicy::disabled().is_enabled());
+    }
+
+    #[test]
+    fn format_waiting_is_compact() {
+        assert_eq!(format_waiting(Duration::from_secs(45)), "45s");
+        assert_eq!(format_waiting(Duration::from_secs(600)), "10m");
+        assert_eq!(format_waiting(Duration::from_secs(3720)), "1h2m");
+    }
+}
@@ -0,0 +1,299 @@
+//! Monorepo scoping for project context and file mentions.
+//!
+//! A `.stakpak/scope.toml` in the working directory or any ancestor up to the
+//! repository root narrows what the agent looks at to a slice of the tree:
+//!
+//! ```toml
+//! # Directories, relative to the directory holding `.stakpak/`
+//! paths = ["services/billing", "libs/money"]
+//! # Directories this CODEOWNERS owner owns
+//! owner = "@acme/billing"
+//! ```
+//!
+//! Without a scope file the working directory is the scope, as before.
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Scope file, relative to the directory it scopes.
+pub const SCOPE_FILE: &str = ".stakpak/scope.toml";
+
+/// CODEOWNERS locations GitHub checks, in its order.
+const CODEOWNERS_FILES: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+#[derive(Debug, Default, Deserialize)]
+struct ScopeConfig {
+    #[serde(default)]
+    paths: Vec<String>,
+    #[serde(default)]
+    owner: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectScope {
+    /// The scope file that defined this scope; `None` when unscoped.
+    pub config_path: Option<PathBuf>,
+    /// CODEOWNERS owner whose directories were included.
+    pub owner: Option<String>,
+    /// Absolute, existing directories, none nested in another.
+    pub dirs: Vec<PathBuf>,
+}
+
+impl ProjectScope {
+    /// Resolve the scope for `cwd` from the nearest scope file, stopping at
+    /// the repository root. A scope file that names no existing directory
+    /// leaves the session unscoped.
+    pub fn resolve(cwd: &Path) -> Self {
+        let repo_root = find_repo_root(cwd);
+        let Some(config_path) = find_scope_file(cwd, repo_root.as_deref()) else {
+            return Self::unscoped();
+        };
+        let Some(config) = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| toml::from_str::<ScopeConfig>(&content).ok())
+        else {
+            return Self::unscoped();
+        };
+        let base = config_path
+            .parent()
+            .and_then(Path::parent)
+            .unwrap_or(cwd)
+            .to_path_buf();
+
+        let mut dirs: Vec<PathBuf> = config
+            .paths
+            .iter()
+            .map(|path| base.join(path.trim_matches('/')))
+            .collect();
+        let owner = config
+            .owner
+            .map(|owner| owner.trim().to_string())
+            .filter(|owner| !owner.is_empty());
+        if let Some(owner) = &owner {
+            let codeowners_root = repo_root.as_deref().unwrap_or(&base);
+            dirs.extend(
+                owned_dirs(codeowners_root, owner)
+                    .into_iter()
+                    .map(|dir| codeowners_root.join(dir)),
+            );
+        }
+
+        let dirs = normalize_dirs(dirs);
+        if dirs.is_empty() {
+            return Self::unscoped();
+        }
+        Self {
+            config_path: Some(config_path),
+            owner,
+            dirs,
+        }
+    }
+
+    fn unscoped() -> Self {
+        Self {
+            config_path: None,
+            owner: None,
+            dirs: Vec::new(),
+        }
+    }
+
+    pub fn is_scoped(&self) -> bool {
+        !self.dirs.is_empty()
+    }
+
+    /// Directories to walk for files under `cwd`: the scoped directories
+    /// inside it, or `cwd` itself when it is unscoped, inside a scoped
+    /// directory, or outside all of them. Returned paths are under `cwd` as
+    /// given, even when it is not canonical.
+    pub fn search_roots(&self, cwd: &Path) -> Vec<PathBuf> {
+        let canonical_cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+        if self.dirs.iter().any(|dir| canonical_cwd.starts_with(dir)) {
+            return vec![cwd.to_path_buf()];
+        }
+        let inside: Vec<PathBuf> = self
+            .dirs
+            .iter()
+            .filter_map(|dir| dir.strip_prefix(&canonical_cwd).ok())
+            .map(|relative| cwd.join(relative))
+            .collect();
+        if inside.is_empty() {
+            vec![cwd.to_path_buf()]
+        } else {
+            inside
+        }
+    }
+}
+
+/// Nearest ancestor of `start` (inclusive) holding `.git`.
+fn find_repo_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+fn find_scope_file(cwd: &Path, repo_root: Option<&Path>) -> Option<PathBuf> {
+    for dir in cwd.ancestors() {
+        let candidate = dir.join(SCOPE_FILE);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if repo_root.is_none_or(|root| dir == root) {
+            break;
+        }
+    }
+    None
+}
+
+/// Directories, relative to `repo_root`, that CODEOWNERS assigns to `owner`.
+///
+/// Only directory-shaped patterns count (`/services/billing/`, `apps/web/**`);
+/// globs such as `*.sql` cannot be walked as a slice and are skipped. Later
+/// rules that hand a subdirectory to someone else are not subtracted.
+fn owned_dirs(repo_root: &Path, owner: &str) -> Vec<PathBuf> {
+    let Some(content) = CODEOWNERS_FILES
+        .iter()
+        .find_map(|file| std::fs::read_to_string(repo_root.join(file)).ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut dirs = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut tokens = line.split_whitespace();
+        let Some(pattern) = tokens.next() else {
+            continue;
+        };
+        if !tokens.any(|candidate| candidate.eq_ignore_ascii_case(owner)) {
+            continue;
+        }
+        let dir = pattern
+            .trim_start_matches('/')
+            .trim_end_matches("/**")
+            .trim_end_matches("/*")
+            .trim_end_matches('/');
+        if dir.contains(['*', '?', '[']) || dir == "**" {
+            continue;
+        }
+        let dir = PathBuf::from(dir);
+        if repo_root.join(&dir).is_dir() {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Keep existing directories, dropping duplicates and ones nested in another.
+fn normalize_dirs(dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = dirs
+        .into_iter()
+        .filter(|dir| dir.is_dir())
+        .map(|dir| dir.canonicalize().unwrap_or(dir))
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+    let mut kept: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        if !kept.iter().any(|parent| dir.starts_with(parent)) {
+            kept.push(dir);
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo() -> tempfile::TempDir {
+        let temp = tempfile::TempDir::new().expect("temp dir");
+        for dir in [
+            ".git",
+            "services/billing/src",
+            "services/search",
+            "libs/money",
+            ".stakpak",
+        ] {
+            std::fs::create_dir_all(temp.path().join(dir)).expect("create dir");
+        }
+        temp
+    }
+
+    fn canonical(path: PathBuf) -> PathBuf {
+        path.canonicalize().expect("canonicalize")
+    }
+
+    #[test]
+    fn unscoped_without_scope_file() {
+        let temp = repo();
+        let scope = ProjectScope::resolve(&temp.path().join("services/billing"));
+        assert!(!scope.is_scoped());
+        assert_eq!(
+            scope.search_roots(temp.path()),
+            vec![temp.path().to_path_buf()]
+        );
+    }
+
+    #[test]
+    fn scope_file_paths_are_resolved_from_a_subdirectory() {
+        let temp = repo();
+        std::fs::write(
+            temp.path().join(SCOPE_FILE),
+            "paths = [\"services/billing\", \"services/billing/src\", \"libs/money\", \"missing\"]\n",
+        )
+        .expect("write scope");
+        let root = canonical(temp.path().to_path_buf());
+
+        let scope = ProjectScope::resolve(&root.join("services/search"));
+        assert_eq!(
+            scope.dirs,
+            vec![root.join("libs/money"), root.join("services/billing")]
+        );
+        assert_eq!(
+            scope.search_roots(&root.join("services")),
+            vec![root.join("services/billing")]
+        );
+        assert_eq!(
+            scope.search_roots(&root.join("services/billing/src")),
+            vec![root.join("services/billing/src")]
+        );
+    }
+
+    #[test]
+    fn codeowners_owner_selects_owned_directories() {
+        let temp = repo();
+        std::fs::create_dir_all(temp.path().join(".github")).expect("github dir");
+        std::fs::write(
+            temp.path().join(".github/CODEOWNERS"),
+            "# ownership\n\
+             *.sql                @acme/dba @acme/billing\n\
+             /services/billing/   @acme/billing\n\
+             libs/money/**        @alice @acme/Billing # shared\n\
+             /services/search/    @acme/search\n",
+        )
+        .expect("write codeowners");
+        std::fs::write(temp.path().join(SCOPE_FILE), "owner = \"@acme/billing\"\n")
+            .expect("write scope");
+        let root = canonical(temp.path().to_path_buf());
+
+        let scope = ProjectScope::resolve(&root);
+        assert_eq!(scope.owner.as_deref(), Some("@acme/billing"));
+        assert_eq!(
+            scope.dirs,
+            vec![root.join("libs/money"), root.join("services/billing")]
+        );
+    }
+
+    #[test]
+    fn scope_file_outside_the_repository_is_ignored() {
+        let outer = tempfile::TempDir::new().expect("temp dir");
+        std::fs::create_dir_all(outer.path().join(".stakpak")).expect("stakpak dir");
+        std::fs::create_dir_all(outer.path().join("repo/.git")).expect("repo");
+        std::fs::create_dir_all(outer.path().join("repo/app")).expect("app");
+        std::fs::write(outer.path().join(SCOPE_FILE), "paths = [\"repo/app\"]\n")
+            .expect("write scope");
+
+        assert!(!ProjectScope::resolve(&outer.path().join("repo")).is_scoped());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod gitleaks;
+pub mod redaction;
 use crate::helper::generate_simple_id;
 /// Re-export the gitleaks initialization function for external access
 pub use gitleaks::initialize_gitleaks_config;
@@ -238,6 +238,12 @@ pub fn sanitize_text_output(text: &str) -> String {
         .collect()
 }
 
+/// [`sanitize_text_output`], then redact secrets with the process-wide
+/// redactor. Use this for text shown to users or stored outside the session.
+pub fn redact_text_output(text: &str) -> String {
+    crate::secrets::redaction::redact_output(&sanitize_text_output(text))
+}
+
 /// Truncate a string by character count and append `...` when truncated.
 ///
 /// Uses char iteration (not byte slicing) so it is UTF-8 safe.
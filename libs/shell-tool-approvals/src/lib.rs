@@ -5,7 +5,9 @@
 mod matcher;
 mod parse;
 mod resolver;
+mod rules;
 
 pub use matcher::matches_pattern;
 pub use parse::{ParseError, ParsedCommand, parse, parse_with_status};
-pub use resolver::resolve_hierarchical_policy;
+pub use resolver::{explain_hierarchical_policy, resolve_hierarchical_policy};
+pub use rules::{ApprovalRules, RuleAction, RuleMatch, scope_key, scope_key_pattern};
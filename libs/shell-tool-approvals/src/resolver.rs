@@ -20,6 +20,19 @@ pub fn resolve_hierarchical_policy<T: Clone + Ord>(
     rules: &HashMap<String, T>,
     default: T,
 ) -> Result<Option<T>, ParseError> {
+    explain_hierarchical_policy(command_str, primary_scope, fallback_scopes, rules, default)
+        .map(|resolved| resolved.map(|(_, action)| action))
+}
+
+/// Like [`resolve_hierarchical_policy`], but also returns the key of the
+/// rule that decided the action, or `None` when `default` applied.
+pub fn explain_hierarchical_policy<T: Clone + Ord>(
+    command_str: &str,
+    primary_scope: &str,
+    fallback_scopes: &[&str],
+    rules: &HashMap<String, T>,
+    default: T,
+) -> Result<Option<(Option<String>, T)>, ParseError> {
     let parsed_commands = parse_with_status(command_str)?;
     if parsed_commands.is_empty() {
         return Ok(None);
@@ -29,26 +42,29 @@ pub fn resolve_hierarchical_policy<T: Clone + Ord>(
         .chain(fallback_scopes.iter().copied())
         .collect();
 
-    let action = parsed_commands
+    let resolved = parsed_commands
         .iter()
         .map(|cmd| {
             scope_chain
                 .iter()
                 .find_map(|scope| resolve_command_in_scope(cmd, scope, rules))
-                .unwrap_or_else(|| default.clone())
+                .map(|(key, action)| (Some(key), action))
+                .unwrap_or_else(|| (None, default.clone()))
         })
-        .max();
+        .max_by(|a, b| a.1.cmp(&b.1));
 
-    Ok(action)
+    Ok(resolved)
 }
 
 fn resolve_command_in_scope<T: Clone + Ord>(
     cmd: &ParsedCommand,
     scope: &str,
     rules: &HashMap<String, T>,
-) -> Option<T> {
+) -> Option<(String, T)> {
     let Some(name) = &cmd.name else {
-        return rules.get(scope).cloned();
+        return rules
+            .get(scope)
+            .map(|action| (scope.to_string(), action.clone()));
     };
 
     let arg_prefix = format!("{scope}::{name}::");
@@ -62,19 +78,23 @@ fn resolve_command_in_scope<T: Clone + Ord>(
             .iter()
             .zip(cmd.args.iter())
             .all(|(segment, arg)| matches_pattern(segment, arg));
-        matched.then_some(action.clone())
+        matched.then_some((key, action))
     });
 
-    if let Some(action) = arg_match.max() {
-        return Some(action);
+    // Ties between equally restrictive rules are broken by key so the
+    // reported rule does not depend on map iteration order.
+    if let Some((key, action)) = arg_match.max_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(b.0))) {
+        return Some((key.clone(), action.clone()));
     }
 
     let command_key = format!("{scope}::{name}");
     if let Some(action) = rules.get(&command_key).cloned() {
-        return Some(action);
+        return Some((command_key, action));
     }
 
-    rules.get(scope).cloned()
+    rules
+        .get(scope)
+        .map(|action| (scope.to_string(), action.clone()))
 }
 
 #[cfg(test)]
@@ -99,6 +119,39 @@ mod tests {
         assert_eq!(resolved, Ok(Some(Action::Approve)));
     }
 
+    #[test]
+    fn explain_reports_the_deciding_rule() {
+        let mut rules = HashMap::new();
+        rules.insert("run_command".to_string(), Action::Ask);
+        rules.insert("run_command::git::status".to_string(), Action::Approve);
+        rules.insert("run_command::rm".to_string(), Action::Deny);
+
+        let explained = explain_hierarchical_policy(
+            "git status && rm -rf build",
+            "run_command",
+            &[],
+            &rules,
+            Action::Ask,
+        );
+        assert_eq!(
+            explained,
+            Ok(Some((Some("run_command::rm".to_string()), Action::Deny)))
+        );
+
+        let explained =
+            explain_hierarchical_policy("git status", "run_command", &[], &rules, Action::Ask);
+        assert_eq!(
+            explained,
+            Ok(Some((
+                Some("run_command::git::status".to_string()),
+                Action::Approve
+            )))
+        );
+
+        let explained = explain_hierarchical_policy("ls", "other_scope", &[], &rules, Action::Ask);
+        assert_eq!(explained, Ok(Some((None, Action::Ask))));
+    }
+
     #[test]
     fn arg_single_segment_matches_args_first() {
         let mut rules = HashMap::new();
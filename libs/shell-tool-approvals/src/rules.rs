@@ -0,0 +1,258 @@
+//! Approval rules written as command lines instead of scope keys.
+//!
+//! `kubectl get *` becomes the scope key `run_command::kubectl::get`, so the
+//! rules resolve through [`crate::explain_hierarchical_policy`] like every
+//! other shell rule: the command name is literal, each following word is
+//! matched against the argument in the same position with
+//! [`crate::matches_pattern`] (`re:` regex, glob or exact), and a pattern
+//! also matches commands with more arguments than it lists. A trailing `*`
+//! is therefore optional. A pattern without spaces names a tool (`view`,
+//! `create`); write `terraform *` for a bare shell command.
+
+use crate::explain_hierarchical_policy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Scope shell command patterns are keyed under.
+const SHELL_SCOPE: &str = "run_command";
+
+/// Patterns grouped by what happens to a matching tool call.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApprovalRules {
+    /// Run without asking.
+    pub allow: Vec<String>,
+    /// Always ask, even when the surrounding policy would approve.
+    pub require_approval: Vec<String>,
+    /// Reject without asking.
+    pub deny: Vec<String>,
+}
+
+impl ApprovalRules {
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.require_approval.is_empty() && self.deny.is_empty()
+    }
+
+    /// The rule that settles a call to `tool_name` (without its MCP prefix),
+    /// or `None` when no rule applies. `command` is the script of a shell
+    /// tool call; a script is allowed only when every command in it matches
+    /// an allow rule, while one denied or held command settles all of it.
+    /// Scripts that fail to parse match no command rule.
+    pub fn evaluate(&self, tool_name: &str, command: Option<&str>) -> Option<RuleMatch> {
+        let rules = self.scope_rules(Level::Allow, Level::RequireApproval, Level::Deny);
+        if rules.is_empty() {
+            return None;
+        }
+
+        let command_match = command
+            .and_then(|command| {
+                explain_hierarchical_policy(command, SHELL_SCOPE, &[], &rules, Level::Unmatched)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|(key, level)| Some((key?, level)));
+        let (key, level) = command_match.or_else(|| {
+            rules
+                .get_key_value(tool_name)
+                .map(|(key, level)| (key.clone(), *level))
+        })?;
+
+        let action = match level {
+            Level::Allow => RuleAction::Allow,
+            Level::RequireApproval => RuleAction::RequireApproval,
+            Level::Deny => RuleAction::Deny,
+            Level::Unmatched => return None,
+        };
+        Some(RuleMatch {
+            action,
+            pattern: scope_key_pattern(&key),
+        })
+    }
+
+    /// Scope-key rules for every pattern, mapped to `allow`, `ask` or `deny`.
+    /// A key listed in more than one group keeps the most restrictive action.
+    pub fn scope_rules<T: Clone + Ord>(&self, allow: T, ask: T, deny: T) -> HashMap<String, T> {
+        let mut rules: HashMap<String, T> = HashMap::new();
+        for (patterns, action) in [
+            (&self.allow, allow),
+            (&self.require_approval, ask),
+            (&self.deny, deny),
+        ] {
+            for key in patterns.iter().filter_map(|pattern| scope_key(pattern)) {
+                match rules.get_mut(&key) {
+                    Some(existing) if *existing >= action => {}
+                    Some(existing) => *existing = action.clone(),
+                    None => {
+                        rules.insert(key, action.clone());
+                    }
+                }
+            }
+        }
+        rules
+    }
+}
+
+/// What a matching rule does with a tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAction {
+    Allow,
+    RequireApproval,
+    Deny,
+}
+
+/// The rule that settled a tool call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleMatch {
+    pub action: RuleAction,
+    /// The pattern as a command line (`kubectl get`) or tool name.
+    pub pattern: String,
+}
+
+/// Commands no rule matches sort between allowed and held ones, so they
+/// outweigh an allow elsewhere in the script but not a deny.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Level {
+    Allow,
+    Unmatched,
+    RequireApproval,
+    Deny,
+}
+
+/// Scope key for a rule pattern: `kubectl get *` → `run_command::kubectl::get`,
+/// `view` → `view`. `None` for blank patterns, a lone `*`
+/// and words containing `::`.
+pub fn scope_key(pattern: &str) -> Option<String> {
+    let mut words: Vec<&str> = pattern.split_whitespace().collect();
+    if words.iter().any(|word| word.contains("::")) {
+        return None;
+    }
+    match words.as_slice() {
+        [] | ["*"] => None,
+        [tool] => Some((*tool).to_string()),
+        _ => {
+            while words.len() > 1 && words.last() == Some(&"*") {
+                words.pop();
+            }
+            let mut key = SHELL_SCOPE.to_string();
+            for word in words {
+                key.push_str("::");
+                key.push_str(word);
+            }
+            Some(key)
+        }
+    }
+}
+
+/// The command line a scope key stands for, for display:
+/// `run_command::kubectl::get` → `kubectl get`. Other keys are returned as is.
+pub fn scope_key_pattern(key: &str) -> String {
+    key.strip_prefix(SHELL_SCOPE)
+        .and_then(|rest| rest.strip_prefix("::"))
+        .map(|command| command.split("::").collect::<Vec<_>>().join(" "))
+        .unwrap_or_else(|| key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    enum Action {
+        Approve,
+        Ask,
+        Deny,
+    }
+
+    #[test]
+    fn scope_key_turns_command_lines_into_keys() {
+        assert_eq!(
+            scope_key("kubectl get *").as_deref(),
+            Some("run_command::kubectl::get")
+        );
+        assert_eq!(
+            scope_key("  aws s3   ls ").as_deref(),
+            Some("run_command::aws::s3::ls")
+        );
+        assert_eq!(
+            scope_key("terraform *").as_deref(),
+            Some("run_command::terraform")
+        );
+        assert_eq!(
+            scope_key("kubectl * secrets").as_deref(),
+            Some("run_command::kubectl::*::secrets")
+        );
+        assert_eq!(scope_key("view").as_deref(), Some("view"));
+        assert_eq!(scope_key(" "), None);
+        assert_eq!(scope_key("*"), None);
+        assert_eq!(scope_key("run_command::rm"), None);
+        assert_eq!(
+            scope_key_pattern("run_command::kubectl::get"),
+            "kubectl get"
+        );
+        assert_eq!(scope_key_pattern("view"), "view");
+    }
+
+    #[test]
+    fn scope_rules_resolve_like_shell_rules() {
+        let rules = ApprovalRules {
+            allow: vec!["kubectl get *".to_string(), "aws s3 ls".to_string()],
+            require_approval: vec!["terraform apply".to_string()],
+            deny: vec!["rm -rf *".to_string(), "aws s3 ls".to_string()],
+        }
+        .scope_rules(Action::Approve, Action::Ask, Action::Deny);
+
+        // Listed as both allow and deny: deny wins
+        assert_eq!(rules.get("run_command::aws::s3::ls"), Some(&Action::Deny));
+
+        let explain = |command: &str| {
+            explain_hierarchical_policy(command, "run_command", &[], &rules, Action::Ask)
+                .expect("parse")
+                .expect("command")
+        };
+        assert_eq!(
+            explain("kubectl get pods -n prod"),
+            (
+                Some("run_command::kubectl::get".to_string()),
+                Action::Approve
+            )
+        );
+        assert_eq!(explain("kubectl get pods && rm -rf /tmp/x").1, Action::Deny);
+        assert_eq!(explain("terraform apply -auto-approve").1, Action::Ask);
+        assert_eq!(explain("kubectl delete pod x"), (None, Action::Ask));
+    }
+
+    #[test]
+    fn evaluate_needs_every_command_allowed() {
+        let rules = ApprovalRules {
+            allow: vec!["kubectl get *".to_string(), "view".to_string()],
+            require_approval: vec!["terraform apply".to_string()],
+            deny: vec!["rm -rf *".to_string()],
+        };
+        let evaluate = |command: &str| {
+            rules
+                .evaluate("run_command", Some(command))
+                .map(|rule| (rule.action, rule.pattern))
+        };
+
+        assert_eq!(
+            evaluate("kubectl get pods"),
+            Some((RuleAction::Allow, "kubectl get".to_string()))
+        );
+        assert_eq!(evaluate("ls && kubectl get pods"), None);
+        assert_eq!(evaluate("kubectl get pods; ls"), None);
+        assert_eq!(
+            evaluate("ls && terraform apply"),
+            Some((RuleAction::RequireApproval, "terraform apply".to_string()))
+        );
+        assert_eq!(
+            evaluate("kubectl get pods && rm -rf /tmp/x").map(|(action, _)| action),
+            Some(RuleAction::Deny)
+        );
+        assert_eq!(
+            rules.evaluate("view", None).map(|rule| rule.action),
+            Some(RuleAction::Allow)
+        );
+        assert_eq!(rules.evaluate("create", None), None);
+        assert_eq!(ApprovalRules::default().evaluate("view", None), None);
+    }
+}
@@ -53,10 +53,16 @@ pub struct AppState {
     pub message_revert_state: MessageRevertState,
     pub plan_mode_state: PlanModeState,
     pub plan_review_state: PlanReviewState,
+    pub plan_pane_state: PlanPaneState,
+    pub tabs_state: TabsState,
+    pub notification_state: NotificationState,
+    pub input_history_state: InputHistoryState,
+    pub confirm_state: ConfirmState,
     pub ask_user_state: AskUserState,
     pub tool_approval_popup_state: AutoApprovePopupState,
     pub approval_settings_persistence_state: ApprovalSettingsPersistenceModal,
     pub background_tasks_state: BackgroundTasksState,
+    pub discovery_state: DiscoveryState,
 }
 
 pub struct AppStateOptions<'a> {
@@ -266,10 +272,16 @@ impl AppState {
                 running_background_tasks: 0,
                 task_manager_handle,
             },
+            discovery_state: DiscoveryState::default(),
 
             // Plan mode/review initialization
             plan_mode_state: PlanModeState::default(),
             plan_review_state: PlanReviewState::default(),
+            plan_pane_state: PlanPaneState::default(),
+            tabs_state: TabsState::default(),
+            notification_state: NotificationState::default(),
+            input_history_state: InputHistoryState::default(),
+            confirm_state: ConfirmState::default(),
             // Ask User inline block initialization
             ask_user_state: AskUserState {
                 is_focused: true,
@@ -1,15 +1,17 @@
 use ratatui::style::Color;
 use stakai::Model;
 use stakpak_api::models::ListRuleBook;
+use stakpak_shared::error::StakpakError;
 use stakpak_shared::models::{
     integrations::openai::{ToolCall, ToolCallResult, ToolCallResultProgress, ToolCallStreamInfo},
     llm::LLMTokenUsage,
 };
 use uuid::Uuid;
 
-use crate::app::{ExistingPlanPrompt, LoadingOperation, SessionInfo};
+use crate::app::{ExistingPlanPrompt, LoadingOperation, SessionInfo, TranscriptFormat};
 use crate::services::banner::BannerStyle;
 use crate::services::board_tasks::FetchTasksResult;
+use crate::services::session_templates::SessionTemplate;
 
 #[derive(Debug)]
 pub enum InputEvent {
@@ -31,6 +33,8 @@ pub enum InputEvent {
     GetStatus(String),
     BillingInfoLoaded(stakpak_shared::models::billing::BillingResponse),
     Error(String),
+    /// An error whose kind is known, shown with its code and remediation hint
+    Failure(StakpakError),
     SetSessions(Vec<SessionInfo>),
     SetBannerMessage(String, BannerStyle),
     InputBackspace,
@@ -54,6 +58,8 @@ pub enum InputEvent {
     CursorRight,
     ToggleCursorVisible,
     Resized(u16, u16),
+    /// Terminal gained (true) or lost (false) focus
+    FocusChanged(bool),
     ShowConfirmationDialog(ToolCall),
     HasUserMessage,
     Tab,
@@ -71,6 +77,8 @@ pub enum InputEvent {
     InputDeleteWord,
     InputCursorStart,
     InputCursorEnd,
+    /// Edit the prompt or plan review comment in the external editor
+    ComposeInEditor,
     InputCursorPrevWord,
     InputCursorNextWord,
     ToggleAutoApprove,
@@ -140,6 +148,8 @@ pub enum InputEvent {
     StreamUsage(LLMTokenUsage),
     RequestTotalUsage,
     TotalUsage(LLMTokenUsage),
+    /// Transcript written by `/export` (path of the file)
+    TranscriptExported(String),
 
     // Model events
     StreamModel(Model),
@@ -158,6 +168,17 @@ pub enum InputEvent {
     ToggleSidePanel,
     SidePanelNextSection,
     SidePanelToggleSection,
+    /// Show or hide the split-pane plan sidebar
+    TogglePlanPane,
+
+    // Prompt history search (Ctrl+R)
+    ShowHistorySearch,
+
+    // Session tabs
+    NextTab,
+    PrevTab,
+    /// Switch to the tab at this index (Alt+1..9)
+    GoToTab(usize),
 
     // Mouse events
     MouseClick(u16, u16),
@@ -226,6 +247,13 @@ pub enum InputEvent {
 
     // Background task status
     RunningBackgroundTasksCount(usize),
+    /// Startup environment discovery progress: probes completed out of total.
+    DiscoveryProgress {
+        completed: usize,
+        total: usize,
+    },
+    /// Startup environment discovery finished; its results are in the session context.
+    DiscoveryFinished,
     // Approval settings persistence modal events
     ShowApprovalSettingsPersistenceModal,
     ApprovalSettingsPersistenceNavigate(i32),
@@ -254,6 +282,7 @@ impl InputEvent {
                 | InputEvent::StreamToolResult(_)
                 | InputEvent::HasUserMessage
                 | InputEvent::Error(_)
+                | InputEvent::Failure(_)
                 | InputEvent::RunToolCall(_)
                 | InputEvent::ToolResult(_)
                 | InputEvent::MessageToolCalls(_)
@@ -269,6 +298,7 @@ impl InputEvent {
                 | InputEvent::GetStatus(_)
                 | InputEvent::BillingInfoLoaded(_)
                 | InputEvent::TotalUsage(_)
+                | InputEvent::TranscriptExported(_)
                 | InputEvent::ProfileSwitchProgress(_)
                 | InputEvent::ProfileSwitchComplete(_)
                 | InputEvent::ProfileSwitchFailed(_)
@@ -278,6 +308,8 @@ impl InputEvent {
                 | InputEvent::RulebooksLoaded(_)
                 | InputEvent::CurrentRulebooksLoaded(_)
                 | InputEvent::RunningBackgroundTasksCount(_)
+                | InputEvent::DiscoveryProgress { .. }
+                | InputEvent::DiscoveryFinished
         )
     }
 }
@@ -319,4 +351,14 @@ pub enum OutputEvent {
     AskUserResponse(ToolCallResult),
     /// Save auto-approve settings to the profile config (tool names set to Auto)
     SaveAutoApproveToProfile(Vec<String>),
+    /// Apply a session template (model + system prompt addendum) to the current session.
+    ApplyTemplate(SessionTemplate),
+    /// Stop startup environment discovery (/skip_discovery).
+    CancelDiscovery,
+    /// Write the session transcript to a file (/export, Ctrl+S).
+    ExportTranscript(TranscriptFormat),
+    /// Make the tab with this id active, parking the current session's state.
+    SwitchTab(u64),
+    /// Drop the parked state of a closed tab.
+    CloseTab(u64),
 }
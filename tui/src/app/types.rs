@@ -19,6 +19,9 @@ use stakpak_shared::models::integrations::openai::{
     ContentPart, TaskPauseInfo, ToolCall, ToolCallResult,
 };
 use stakpak_shared::models::llm::LLMTokenUsage;
+use stakpak_shared::pending_interactions::{
+    InteractionKind, PendingInteractionTracker, ReminderPolicy,
+};
 use stakpak_shared::secret_manager::SecretManager;
 use stakpak_shared::task_manager::TaskManagerHandle;
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -281,6 +284,8 @@ pub struct MessagesScrollingState {
     /// When scroll is locked, this stores how many lines from the end we want to show at top of viewport
     /// This allows us to maintain relative position even as total_lines changes
     pub scroll_lines_from_end: Option<usize>,
+    /// Autosaved scroll position to apply once a restored session's history is loaded
+    pub restore_lines_from_end: Option<usize>,
     pub content_changed_while_scrolled_up: bool,
     pub message_lines_cache: Option<MessageLinesCache>,
     pub collapsed_message_lines_cache: Option<MessageLinesCache>,
@@ -317,6 +322,7 @@ impl Default for MessagesScrollingState {
             stay_at_bottom: true,
             block_stay_at_bottom_frames: 0,
             scroll_lines_from_end: None,
+            restore_lines_from_end: None,
             content_changed_while_scrolled_up: false,
             message_lines_cache: None,
             collapsed_message_lines_cache: None,
@@ -351,6 +357,7 @@ pub struct SidePanelState {
     pub board_agent_id: Option<String>,
     pub editor_command: String,
     pub pending_editor_open: Option<String>,
+    pub pending_compose: Option<crate::services::editor::ComposeTarget>,
     pub billing_info: Option<stakpak_shared::models::billing::BillingResponse>,
 }
 
@@ -380,6 +387,7 @@ impl Default for SidePanelState {
             board_agent_id: None,
             editor_command: "nano".to_string(),
             pending_editor_open: None,
+            pending_compose: None,
             billing_info: None,
         }
     }
@@ -405,6 +413,8 @@ pub struct QuitIntentState {
 pub struct TerminalUiState {
     pub mouse_capture_enabled: bool,
     pub terminal_size: ratatui::layout::Size,
+    /// Last focus report from the terminal; true until one arrives
+    pub is_focused: bool,
 }
 
 impl Default for TerminalUiState {
@@ -415,6 +425,7 @@ impl Default for TerminalUiState {
                 width: 0,
                 height: 0,
             },
+            is_focused: true,
         }
     }
 }
@@ -458,6 +469,14 @@ pub struct UserMessageQueueState {
 pub struct MessageRevertState {
     pub user_message_count: usize,
     pub pending_revert_index: Option<usize>,
+    /// Last revert to a message, while it can still be undone
+    pub undo: Option<crate::services::confirm::RevertUndo>,
+}
+
+/// Confirmation dialog for cancelling a run, `/clear` and dismissing answers
+#[derive(Default)]
+pub struct ConfirmState {
+    pub pending: Option<crate::services::confirm::ConfirmAction>,
 }
 
 #[derive(Default)]
@@ -527,6 +546,17 @@ pub struct ToolCallState {
     pub subagent_pause_info: HashMap<String, TaskPauseInfo>,
 }
 
+/// Environment discovery started in the background with the session.
+#[derive(Debug, Default)]
+pub struct DiscoveryState {
+    /// `(completed, total)` probes while discovery is running.
+    pub progress: Option<(usize, usize)>,
+    /// Discovery finished or was skipped; late progress updates are ignored.
+    pub done: bool,
+    /// Send the init prompt once discovery ends (`stakpak init`).
+    pub init_prompt_pending: bool,
+}
+
 pub struct BackgroundTasksState {
     pub running_background_tasks: usize,
     pub task_manager_handle: Option<Arc<TaskManagerHandle>>,
@@ -545,6 +575,8 @@ pub struct DialogApprovalState {
     pub message_rejected_tools: Vec<ToolCall>,
     pub toggle_approved_message: bool,
     pub show_shortcuts: bool,
+    /// Inactivity reminders for open approval / ask_user prompts
+    pub reminders: PendingInteractionTracker<InteractionKind>,
 }
 
 impl Default for DialogApprovalState {
@@ -561,6 +593,7 @@ impl Default for DialogApprovalState {
             message_rejected_tools: Vec::new(),
             toggle_approved_message: true,
             show_shortcuts: false,
+            reminders: PendingInteractionTracker::new(ReminderPolicy::terminal()),
         }
     }
 }
@@ -657,6 +690,62 @@ pub struct PlanModeState {
     pub existing_prompt: Option<ExistingPlanPrompt>,
 }
 
+/// Split-pane plan sidebar (`F2` / `/split`)
+#[derive(Default)]
+pub struct PlanPaneState {
+    /// Whether the chat shares the screen with the plan pane
+    pub is_shown: bool,
+    /// Plan title from the front matter
+    pub title: Option<String>,
+    /// Headings and checklist items of plan.md
+    pub items: Vec<crate::services::plan_pane::PlanPaneItem>,
+    /// Hash of the last-read plan.md; None when there is no plan file
+    pub content_hash: Option<String>,
+}
+
+/// Open session tabs (`Ctrl+Tab` / `/tab`)
+pub struct TabsState {
+    pub tabs: Vec<crate::services::tabs::SessionTab>,
+    /// Index of the tab whose state is in `AppState`
+    pub active: usize,
+    /// Id given to the next tab opened
+    pub next_id: u64,
+}
+
+/// Prompt history recall and the `Ctrl+R` search popup
+#[derive(Default)]
+pub struct InputHistoryState {
+    pub history: crate::services::input_history::InputHistory,
+    pub is_searching: bool,
+    pub query: String,
+    /// Indices into the history entries, best match first
+    pub matches: Vec<usize>,
+    pub selected: usize,
+}
+
+/// Last attention sample, for [`crate::services::notifications::observe`]
+#[derive(Default)]
+pub struct NotificationState {
+    pub last: crate::services::notifications::Attention,
+    /// Set while the agent works; cleared once its finish is reported
+    pub armed: bool,
+    /// When the agent went idle after working
+    pub idle_since: Option<std::time::Instant>,
+}
+
+impl Default for TabsState {
+    fn default() -> Self {
+        Self {
+            tabs: vec![crate::services::tabs::SessionTab {
+                id: 0,
+                parked: None,
+            }],
+            active: 0,
+            next_id: 1,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct PlanReviewState {
     /// Whether the plan review overlay is visible
@@ -683,6 +772,27 @@ pub struct PlanReviewState {
     pub modal_kind: Option<crate::services::plan_review::CommentModalKind>,
     /// Confirmation dialog currently shown (approve, feedback, delete)
     pub confirm: Option<crate::services::plan_review::ConfirmAction>,
+    /// Changes since the previously reviewed revision, if there is one
+    pub diff: Option<crate::services::plan_diff::PlanDiff>,
+    /// Whether the viewer highlights those changes
+    pub show_diff: bool,
+    /// Line where visual selection started; the selection runs to the cursor
+    pub selection_start: Option<usize>,
+    /// Text searched for with `/`; empty when no search is active
+    pub search_query: String,
+    /// Whether the search prompt is taking input
+    pub search_editing: bool,
+    /// Cursor line when the search prompt opened, restored on cancel
+    pub search_origin: usize,
+    /// Where the gutter was last drawn, for mouse clicks
+    pub gutter_area: ratatui::layout::Rect,
+    /// Where the plan text was last drawn, for mouse clicks
+    pub plan_area: ratatui::layout::Rect,
+    /// Logical line on each drawn row of the plan area; `None` for removed
+    /// lines and padding
+    pub visible_rows: Vec<Option<usize>>,
+    /// Syntax-highlighted spans for each line inside a fenced code block
+    pub code_highlights: Vec<Option<Vec<ratatui::text::Span<'static>>>>,
 }
 
 #[derive(Default)]
@@ -765,6 +875,31 @@ pub enum LoadingOperation {
     CheckpointResume,
 }
 
+/// File format written by `/export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    Markdown,
+    Json,
+}
+
+impl TranscriptFormat {
+    /// Parse the `/export` argument (`md`, `markdown` or `json`)
+    pub fn parse(arg: &str) -> Option<Self> {
+        match arg.trim().to_lowercase().as_str() {
+            "" | "md" | "markdown" => Some(TranscriptFormat::Markdown),
+            "json" => Some(TranscriptFormat::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Markdown => "md",
+            TranscriptFormat::Json => "json",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ToolCallStatus {
     Approved,
@@ -33,6 +33,9 @@ pub fn map_crossterm_event_to_input_event(event: Event) -> Option<InputEvent> {
                     }
                 }
                 KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Some(InputEvent::ShowHistorySearch)
+                }
+                KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::ALT) => {
                     Some(InputEvent::RetryLastToolCall)
                 }
                 KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -52,7 +55,7 @@ pub fn map_crossterm_event_to_input_event(event: Event) -> Option<InputEvent> {
                     Some(InputEvent::ShowFileChangesPopup)
                 }
                 KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                    Some(InputEvent::InputCursorEnd)
+                    Some(InputEvent::ComposeInEditor)
                 }
                 KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                     Some(InputEvent::FileChangesRevertFile)
@@ -112,6 +115,9 @@ pub fn map_crossterm_event_to_input_event(event: Event) -> Option<InputEvent> {
                         Some(InputEvent::InputChanged('h'))
                     }
                 }
+                KeyCode::Char(c @ '1'..='9') if key.modifiers.contains(KeyModifiers::ALT) => {
+                    Some(InputEvent::GoToTab(c as usize - '1' as usize))
+                }
                 KeyCode::Char(c) => Some(InputEvent::InputChanged(c)),
                 KeyCode::Backspace => {
                     if key.modifiers.contains(KeyModifiers::CONTROL) {
@@ -164,9 +170,27 @@ pub fn map_crossterm_event_to_input_event(event: Event) -> Option<InputEvent> {
                 }
                 KeyCode::Home => Some(InputEvent::InputCursorStart),
                 KeyCode::End => Some(InputEvent::InputCursorEnd),
+                KeyCode::PageUp if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Some(InputEvent::PrevTab)
+                }
+                KeyCode::PageDown if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Some(InputEvent::NextTab)
+                }
                 KeyCode::PageUp => Some(InputEvent::PageUp),
                 KeyCode::PageDown => Some(InputEvent::PageDown),
+                KeyCode::Tab if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    if key.modifiers.contains(KeyModifiers::SHIFT) {
+                        Some(InputEvent::PrevTab)
+                    } else {
+                        Some(InputEvent::NextTab)
+                    }
+                }
+                KeyCode::BackTab if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Some(InputEvent::PrevTab)
+                }
                 KeyCode::Tab => Some(InputEvent::Tab),
+                KeyCode::F(1) => Some(InputEvent::ShowShortcuts),
+                KeyCode::F(2) => Some(InputEvent::TogglePlanPane),
                 _ => None,
             }
         }
@@ -187,6 +211,53 @@ pub fn map_crossterm_event_to_input_event(event: Event) -> Option<InputEvent> {
         },
         Event::Resize(w, h) => Some(InputEvent::Resized(w, h)),
         Event::Paste(p) => Some(InputEvent::HandlePaste(p)),
-        _ => None,
+        Event::FocusGained => Some(InputEvent::FocusChanged(true)),
+        Event::FocusLost => Some(InputEvent::FocusChanged(false)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Option<InputEvent> {
+        map_crossterm_event_to_input_event(Event::Key(KeyEvent::new(code, modifiers)))
+    }
+
+    #[test]
+    fn alt_digit_goes_to_that_tab() {
+        assert!(matches!(
+            key(KeyCode::Char('1'), KeyModifiers::ALT),
+            Some(InputEvent::GoToTab(0))
+        ));
+        assert!(matches!(
+            key(KeyCode::Char('9'), KeyModifiers::ALT),
+            Some(InputEvent::GoToTab(8))
+        ));
+        assert!(matches!(
+            key(KeyCode::Char('0'), KeyModifiers::ALT),
+            Some(InputEvent::InputChanged('0'))
+        ));
+        assert!(matches!(
+            key(KeyCode::Char('2'), KeyModifiers::NONE),
+            Some(InputEvent::InputChanged('2'))
+        ));
+    }
+
+    #[test]
+    fn ctrl_page_keys_cycle_tabs() {
+        assert!(matches!(
+            key(KeyCode::PageDown, KeyModifiers::CONTROL),
+            Some(InputEvent::NextTab)
+        ));
+        assert!(matches!(
+            key(KeyCode::PageUp, KeyModifiers::CONTROL),
+            Some(InputEvent::PrevTab)
+        ));
+        assert!(matches!(
+            key(KeyCode::PageDown, KeyModifiers::NONE),
+            Some(InputEvent::PageDown)
+        ));
     }
 }
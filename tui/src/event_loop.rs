@@ -3,6 +3,7 @@
 //! Contains the main TUI event loop and related helper functions.
 
 use crate::app::{AppState, AppStateOptions, InputEvent, OutputEvent};
+use crate::services::autosave::{Autosaver, SessionSnapshot};
 use crate::services::banner::BannerMessage;
 use crate::services::detect_term::ThemeColors;
 use crate::services::handlers::tool::{
@@ -11,7 +12,8 @@ use crate::services::handlers::tool::{
 use crate::services::message::Message;
 use crate::view::view;
 use crossterm::event::{
-    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+    DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+    EnableFocusChange, EnableMouseCapture,
 };
 use crossterm::{execute, terminal::EnterAlternateScreen};
 use ratatui::{Terminal, backend::CrosstermBackend};
@@ -60,6 +62,7 @@ pub async fn run_tui(
     recent_models: Vec<String>,
     banner_message: Option<BannerMessage>,
     task_manager_handle: Arc<TaskManagerHandle>,
+    restore_snapshot: Option<SessionSnapshot>,
 ) -> io::Result<()> {
     let _guard = TerminalGuard;
 
@@ -75,8 +78,10 @@ pub async fn run_tui(
         std::io::stdout(),
         EnterAlternateScreen,
         EnableBracketedPaste,
-        EnableMouseCapture
+        EnableMouseCapture,
+        EnableFocusChange
     )?;
+    crate::terminal::enable_keyboard_enhancement();
 
     let mut terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
 
@@ -107,6 +112,11 @@ pub async fn run_tui(
 
     state.banner_state.message = banner_message;
 
+    if let Some(path) = crate::services::input_history::InputHistory::default_path() {
+        state.input_history_state.history =
+            crate::services::input_history::InputHistory::load(path);
+    }
+
     // Mouse capture is always enabled
     state.terminal_ui_state.mouse_capture_enabled = true;
 
@@ -133,23 +143,20 @@ pub async fn run_tui(
     );
     state.messages_scrolling_state.messages.extend(welcome_msg);
 
+    // Re-apply draft input, overlays and scroll from `--restore-last`
+    if let Some(snapshot) = restore_snapshot {
+        crate::services::autosave::apply(&mut state, snapshot);
+    }
+    let mut autosaver = Autosaver::default();
+
     // Trigger initial board tasks refresh if agent ID is configured
     if state.side_panel_state.board_agent_id.is_some() {
         let _ = internal_tx.try_send(InputEvent::RefreshBoardTasks);
     }
 
-    // When started via `stakpak init`, add init prompt as user message and send to backend
-    if send_init_prompt_on_start
-        && let Some(prompt) = state.configuration_state.init_prompt_content.clone()
-        && !prompt.trim().is_empty()
-    {
-        state
-            .messages_scrolling_state
-            .messages
-            .push(Message::user(prompt.clone(), None));
-        crate::services::message::invalidate_message_lines_cache(&mut state);
-        let _ = output_tx.try_send(OutputEvent::UserMessage(prompt, None, Vec::new(), None));
-    }
+    // When started via `stakpak init`, send the init prompt once startup discovery
+    // finishes (or is skipped) so the agent sees the discovery results.
+    state.discovery_state.init_prompt_pending = send_init_prompt_on_start;
 
     let internal_tx_thread = internal_tx.clone();
     // Create atomic pause flag for input thread
@@ -225,6 +232,7 @@ pub async fn run_tui(
                        } else {
                            term_size.width
                        };
+                       let main_area_width = crate::services::plan_pane::chat_width(&state, main_area_width);
                        let term_rect = ratatui::layout::Rect::new(0, 0, main_area_width, term_size.height);
                        let margin_height: u16 = 2;
                        let dropdown_showing = state.input_state.show_helper_dropdown
@@ -236,7 +244,8 @@ pub async fn run_tui(
                        // The approval bar will be visible, so input and dropdown are hidden
                        let approval_bar_height = state.dialog_approval_state.approval_bar.calculate_height(term_rect.width).max(7); // Use expected height
 
-                       let banner_h = crate::services::banner::banner_height(&state);
+                       let banner_h = crate::services::banner::banner_height(&state)
+                            + crate::services::tabs::tab_bar_height(&state);
                         let outer_chunks = ratatui::layout::Layout::default()
                              .direction(ratatui::layout::Direction::Vertical)
                              .constraints([
@@ -405,6 +414,7 @@ pub async fn run_tui(
                        } else {
                            term_size.width
                        };
+                       let main_area_width = crate::services::plan_pane::chat_width(&state, main_area_width);
                        let term_rect = ratatui::layout::Rect::new(0, 0, main_area_width, term_size.height);
                        let input_height = 3;
                        let margin_height = 2;
@@ -417,7 +427,8 @@ pub async fn run_tui(
                             0
                         };
                          let hint_height = if dropdown_showing { 0 } else { margin_height };
-                        let banner_h = crate::services::banner::banner_height(&state);
+                        let banner_h = crate::services::banner::banner_height(&state)
+                            + crate::services::tabs::tab_bar_height(&state);
                         let outer_chunks = ratatui::layout::Layout::default()
                             .direction(ratatui::layout::Direction::Vertical)
                             .constraints([
@@ -499,6 +510,7 @@ pub async fn run_tui(
                     } else {
                         term_size.width
                     };
+                    let main_area_width = crate::services::plan_pane::chat_width(&state, main_area_width);
                     let term_rect = ratatui::layout::Rect::new(0, 0, main_area_width, term_size.height);
                     let input_height = 3;
                     let margin_height = 2;
@@ -511,7 +523,8 @@ pub async fn run_tui(
                         0
                     };
                     let hint_height = if dropdown_showing { 0 } else { margin_height };
-                    let banner_h = crate::services::banner::banner_height(&state);
+                    let banner_h = crate::services::banner::banner_height(&state)
+                            + crate::services::tabs::tab_bar_height(&state);
                     let outer_chunks = ratatui::layout::Layout::default()
                         .direction(ratatui::layout::Direction::Vertical)
                         .constraints([
@@ -623,6 +636,14 @@ pub async fn run_tui(
                              input_paused.store(false, Ordering::Relaxed);
                          }
 
+                        // Handle pending compose-in-editor request
+                        if let Some(target) = state.side_panel_state.pending_compose.take() {
+                            input_paused.store(true, Ordering::Relaxed);
+                            std::thread::sleep(Duration::from_millis(10));
+                            compose_with_editor(&mut terminal, &mut state, target);
+                            input_paused.store(false, Ordering::Relaxed);
+                        }
+
                         state.update_session_empty_status();
                     }
                 }
@@ -638,6 +659,10 @@ pub async fn run_tui(
                    // Update shell cursor blink (toggles every ~5 ticks = 500ms)
                    crate::services::shell_popup::update_cursor_blink(&mut state);
                    state.poll_file_search_results();
+                   crate::services::plan_pane::refresh(
+                       &mut state.plan_pane_state,
+                       std::path::Path::new(".stakpak/session"),
+                   );
 
                    // Poll plan file and handle status transitions
                    if let Some((old_status, new_status)) = state.poll_plan_file() {
@@ -672,6 +697,22 @@ pub async fn run_tui(
                    // Auto-scroll during drag selection when mouse is at viewport edges
                    crate::services::handlers::tick_selection_auto_scroll(&mut state);
 
+                   // Ring the terminal bell when a prompt has been waiting too long
+                   if crate::services::handlers::tick_interaction_reminders(&mut state) {
+                       use std::io::Write;
+                       let mut stdout = std::io::stdout();
+                       let _ = stdout.write_all(b"\x07");
+                       let _ = stdout.flush();
+                   }
+
+                   // Tell an unfocused user the agent paused, asked or finished
+                   crate::services::notifications::tick(&mut state);
+
+                   crate::services::confirm::drop_stale(&mut state);
+                   crate::services::confirm::expire_undo(&mut state);
+
+                   autosaver.tick(&state);
+
                    terminal.draw(|f| view(f, &mut state))?;
                }
            }
@@ -688,17 +729,98 @@ pub async fn run_tui(
         terminal.draw(|f| view(f, &mut state))?;
     }
 
+    // Quitting closes the undo window of a revert to a message
+    crate::services::confirm::commit_revert(&mut state);
+    autosaver.save(&state);
     let _ = shutdown_tx.send(());
+    crate::terminal::disable_keyboard_enhancement();
     crossterm::terminal::disable_raw_mode()?;
     execute!(
         std::io::stdout(),
         crossterm::terminal::LeaveAlternateScreen,
         DisableBracketedPaste,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableFocusChange
     )?;
     Ok(())
 }
 
+/// Edit the prompt or plan review comment in the external editor, replacing
+/// it with what was saved.
+fn compose_with_editor<B: ratatui::backend::Backend>(
+    terminal: &mut ratatui::Terminal<B>,
+    state: &mut AppState,
+    target: crate::services::editor::ComposeTarget,
+) {
+    use crate::services::editor::ComposeTarget;
+
+    let (initial, suffix) = match target {
+        ComposeTarget::Input => (state.input().to_string(), ".md".to_string()),
+        ComposeTarget::PlanComment => (
+            state.plan_review_state.comment_input.clone(),
+            ".md".to_string(),
+        ),
+        ComposeTarget::ToolCall => {
+            let Some((path, content)) = state
+                .dialog_approval_state
+                .approval_bar
+                .selected_action()
+                .and_then(|action| action.editable_content())
+            else {
+                return;
+            };
+            // Keep the file's extension so the editor highlights it
+            let suffix = std::path::Path::new(&path)
+                .extension()
+                .map(|ext| format!(".{}", ext.to_string_lossy()))
+                .unwrap_or_default();
+            (content, suffix)
+        }
+    };
+
+    // Disable mouse capture while the editor owns the terminal
+    let was_mouse_capture_enabled = state.terminal_ui_state.mouse_capture_enabled;
+    if was_mouse_capture_enabled {
+        let _ = execute!(std::io::stdout(), DisableMouseCapture);
+        state.terminal_ui_state.mouse_capture_enabled = false;
+    }
+
+    match crate::services::editor::compose_in_editor(
+        terminal,
+        &state.side_panel_state.editor_command,
+        &initial,
+        &suffix,
+    ) {
+        Ok(text) => match target {
+            ComposeTarget::Input => {
+                state.set_input(&text);
+                state.set_cursor_position(text.len());
+            }
+            ComposeTarget::PlanComment => state.plan_review_state.comment_input = text,
+            ComposeTarget::ToolCall => {
+                // The editor round-trip drops trailing newlines; file content keeps its own
+                let text = if initial.ends_with('\n') {
+                    format!("{}\n", text)
+                } else {
+                    text
+                };
+                crate::services::handlers::tool::apply_approval_edit(state, &text);
+            }
+        },
+        Err(error) => {
+            state.messages_scrolling_state.messages.push(Message::info(
+                format!("Failed to open editor: {}", error),
+                Some(ratatui::style::Style::default().fg(ThemeColors::red())),
+            ));
+        }
+    }
+
+    if was_mouse_capture_enabled {
+        let _ = execute!(std::io::stdout(), EnableMouseCapture);
+        state.terminal_ui_state.mouse_capture_enabled = true;
+    }
+}
+
 pub fn emergency_clear_and_redraw<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     state: &mut AppState,
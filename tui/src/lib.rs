@@ -11,6 +11,7 @@ mod terminal;
 mod view;
 pub use app::{
     AppState, ExistingPlanPrompt, InputEvent, LoadingOperation, OutputEvent, SessionInfo,
+    TranscriptFormat,
 };
 pub use event_loop::{RulebookConfig, run_tui};
 pub use ratatui::style::Color;
@@ -0,0 +1,100 @@
+//! Accessibility mode for the TUI.
+//!
+//! When enabled, status is never signaled by color alone (result headers
+//! carry `[OK]`/`[ERROR]` markers), theme colors switch to a high-contrast
+//! palette, spinner and cursor-blink animations stop, and mouse click
+//! targets are enlarged.
+//!
+//! The flag is process-wide, like the terminal theme, because color and
+//! marker helpers are called from render code that has no access to
+//! `AppState`. It is set at startup from config and toggled at runtime with
+//! the `/accessibility` command.
+
+use crate::services::detect_term::ThemeColors;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ACCESSIBILITY_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Extra cells added on each side of a click target in accessibility mode.
+pub const HIT_AREA_PADDING: u16 = 2;
+
+/// Whether accessibility mode is active.
+pub fn is_enabled() -> bool {
+    ACCESSIBILITY_MODE.load(Ordering::Relaxed)
+}
+
+/// Turn accessibility mode on or off.
+pub fn set_enabled(enabled: bool) {
+    ACCESSIBILITY_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// Flip accessibility mode and return the new state.
+pub fn toggle() -> bool {
+    !ACCESSIBILITY_MODE.fetch_xor(true, Ordering::Relaxed)
+}
+
+/// Header marker for a status dot of the given color.
+///
+/// Result headers only encode success/failure through the dot color, so in
+/// accessibility mode the color is mapped to a textual marker instead.
+pub fn status_marker(dot: Color) -> &'static str {
+    status_marker_for(dot, is_enabled())
+}
+
+fn status_marker_for(dot: Color, enabled: bool) -> &'static str {
+    if !enabled {
+        "● "
+    } else if dot == ThemeColors::dot_success() || dot == ThemeColors::success() {
+        "[OK] "
+    } else if dot == ThemeColors::dot_error() || dot == ThemeColors::danger() {
+        "[ERROR] "
+    } else {
+        "● "
+    }
+}
+
+/// Grow a click target by [`HIT_AREA_PADDING`] on each side and to the full
+/// height of `bounds`, clamped to `bounds`, when accessibility mode is active.
+pub fn hit_area(target: Rect, bounds: Rect) -> Rect {
+    hit_area_for(target, bounds, is_enabled())
+}
+
+fn hit_area_for(target: Rect, bounds: Rect, enabled: bool) -> Rect {
+    if !enabled {
+        return target;
+    }
+    let x = target.x.saturating_sub(HIT_AREA_PADDING).max(bounds.x);
+    let right = target
+        .right()
+        .saturating_add(HIT_AREA_PADDING)
+        .min(bounds.right());
+    Rect::new(x, bounds.y, right.saturating_sub(x), bounds.height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_marker_is_textual_only_when_enabled() {
+        assert_eq!(status_marker_for(ThemeColors::dot_success(), false), "● ");
+        assert_eq!(status_marker_for(ThemeColors::dot_success(), true), "[OK] ");
+        assert_eq!(status_marker_for(ThemeColors::danger(), true), "[ERROR] ");
+        assert_eq!(status_marker_for(ThemeColors::magenta(), true), "● ");
+    }
+
+    #[test]
+    fn hit_area_grows_within_bounds() {
+        let bounds = Rect::new(0, 5, 40, 3);
+        let target = Rect::new(10, 6, 4, 1);
+
+        assert_eq!(hit_area_for(target, bounds, false), target);
+        assert_eq!(hit_area_for(target, bounds, true), Rect::new(8, 5, 8, 3));
+        assert_eq!(
+            hit_area_for(Rect::new(1, 6, 4, 1), bounds, true).x,
+            bounds.x
+        );
+    }
+}
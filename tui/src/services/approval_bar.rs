@@ -13,6 +13,8 @@
 //! - All tools start as Approved (✓) by default
 //! - Space toggles between Approved (✓) and Rejected (✗)
 //! - Left/Right arrows navigate between tabs
+//! - `e` opens the proposed content of a `str_replace`/`create` call in the
+//!   editor, so it can be approved with changes
 //! - Enter confirms all decisions and executes
 
 use crate::services::detect_term::ThemeColors;
@@ -22,6 +24,7 @@ use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Clear, Paragraph};
 use stakpak_shared::models::integrations::openai::ToolCall;
+use stakpak_shared::secrets::redaction::{self, redact_output};
 use stakpak_shared::utils::strip_tool_name;
 
 /// Approval status for a tool call
@@ -38,6 +41,8 @@ pub struct ApprovalAction {
     pub status: ApprovalStatus,
     /// Display label (e.g., "Run Command", "Create", "Str Replace")
     pub label: String,
+    /// Whether the user changed the proposed content before approving
+    pub edited: bool,
 }
 
 impl ApprovalAction {
@@ -56,10 +61,11 @@ impl ApprovalAction {
                 .as_ref()
                 .and_then(|a| a.get("enable_sandbox").and_then(|v| v.as_bool()))
                 .unwrap_or(false);
+            let desc = redact_output(desc);
             if is_sandbox {
                 format!("{} [sandboxed]", desc)
             } else {
-                desc.to_string()
+                desc
             }
         } else {
             // Convert snake_case to Title Case (e.g., "get_pak_content" -> "Get Pak Content")
@@ -81,9 +87,55 @@ impl ApprovalAction {
             // Default to Approved - user can reject with Space
             status: ApprovalStatus::Approved,
             label,
+            edited: false,
         }
     }
 
+    /// Content the user can edit before approving: the replacement text of
+    /// a `str_replace` or the file text of a `create`. None for other tools.
+    /// Secrets come back as redaction placeholders, since the content is
+    /// shown in an editor.
+    pub fn editable_content(&self) -> Option<(String, String)> {
+        let field = editable_field(&self.tool_call)?;
+        let args: serde_json::Value =
+            serde_json::from_str(&self.tool_call.function.arguments).ok()?;
+        let content = redact_output(args.get(field)?.as_str()?);
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        Some((path, content))
+    }
+
+    /// Replace the editable content with `content`, swapping redaction
+    /// placeholders back for their secrets. Returns false when the tool has
+    /// nothing to edit or the content is unchanged.
+    pub fn apply_edit(&mut self, content: &str) -> bool {
+        let content = &redaction::global().restore(content);
+        let Some(field) = editable_field(&self.tool_call) else {
+            return false;
+        };
+        let Ok(mut args) =
+            serde_json::from_str::<serde_json::Value>(&self.tool_call.function.arguments)
+        else {
+            return false;
+        };
+        let Some(slot) = args.get_mut(field) else {
+            return false;
+        };
+        if slot.as_str() == Some(content) {
+            return false;
+        }
+        *slot = serde_json::Value::String(content.to_string());
+        self.tool_call.function.arguments = args.to_string();
+        if !self.edited {
+            self.edited = true;
+            self.label.push_str(" (edited)");
+        }
+        true
+    }
+
     /// Toggle between Approved and Rejected
     pub fn toggle(&mut self) {
         self.status = match self.status {
@@ -93,6 +145,15 @@ impl ApprovalAction {
     }
 }
 
+/// Argument holding the proposed content of a file-modifying tool call
+fn editable_field(tool_call: &ToolCall) -> Option<&'static str> {
+    match strip_tool_name(&tool_call.function.name) {
+        "str_replace" => Some("new_str"),
+        "create" => Some("file_text"),
+        _ => None,
+    }
+}
+
 /// The inline approval bar state
 #[derive(Debug)]
 pub struct ApprovalBar {
@@ -189,6 +250,13 @@ impl ApprovalBar {
         self.selected_action().map(|a| &a.tool_call)
     }
 
+    /// Replace the proposed content of the selected action. Returns the
+    /// updated tool call when something changed.
+    pub fn edit_selected(&mut self, content: &str) -> Option<ToolCall> {
+        let action = self.actions.get_mut(self.selected_index)?;
+        action.apply_edit(content).then(|| action.tool_call.clone())
+    }
+
     /// Get the currently selected index
     pub fn selected_index(&self) -> usize {
         self.selected_index
@@ -485,7 +553,7 @@ impl ApprovalBar {
         let footer_y = current_y;
         if footer_y < area.y + area.height.saturating_sub(1) {
             // Build footer controls with same style as approval popup
-            let footer_controls = vec![
+            let mut footer_controls = vec![
                 Span::styled("space", Style::default().fg(ThemeColors::accent())),
                 Span::styled(" toggle", Style::default().fg(ThemeColors::muted())),
                 Span::raw("  "),
@@ -498,6 +566,16 @@ impl ApprovalBar {
                 Span::styled("esc", Style::default().fg(ThemeColors::accent())),
                 Span::styled(" reject all", Style::default().fg(ThemeColors::muted())),
             ];
+            if self
+                .selected_action()
+                .is_some_and(|action| editable_field(&action.tool_call).is_some())
+            {
+                footer_controls.extend([
+                    Span::raw("  "),
+                    Span::styled("e", Style::default().fg(ThemeColors::accent())),
+                    Span::styled(" edit", Style::default().fg(ThemeColors::muted())),
+                ]);
+            }
 
             let footer_content_width: usize = footer_controls
                 .iter()
@@ -592,6 +670,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_editable_content_is_redacted_and_edits_keep_secrets() {
+        let secret = "postgres://app:s3cr3t@db:5432/app";
+        let mut action = ApprovalAction::new(make_tool_call(
+            "create",
+            &serde_json::json!({ "path": ".env", "file_text": format!("DB={secret}\n") })
+                .to_string(),
+        ));
+
+        let (path, content) = action.editable_content().expect("editable");
+        assert_eq!(path, ".env");
+        assert!(!content.contains("s3cr3t"), "{content}");
+
+        // Saving the editor unchanged is not an edit
+        assert!(!action.apply_edit(&content));
+
+        assert!(action.apply_edit(&format!("{content}DEBUG=1\n")));
+        let args: serde_json::Value =
+            serde_json::from_str(&action.tool_call.function.arguments).expect("json");
+        assert_eq!(args["file_text"], format!("DB={secret}\nDEBUG=1\n"));
+    }
+
     #[test]
     fn test_approval_bar_basics() {
         let mut bar = ApprovalBar::new();
@@ -690,6 +790,41 @@ mod tests {
         assert_eq!(bar_action.label, "Create");
     }
 
+    #[test]
+    fn test_edit_selected_replaces_proposed_content() {
+        let mut bar = ApprovalBar::new();
+        bar.add_action(make_tool_call(
+            "str_replace",
+            r#"{"path": "src/main.rs", "old_str": "a", "new_str": "b"}"#,
+        ));
+        bar.add_action(make_tool_call(
+            "run_command",
+            r#"{"command": "npm install"}"#,
+        ));
+
+        let action = bar.selected_action().unwrap();
+        assert_eq!(
+            action.editable_content(),
+            Some(("src/main.rs".to_string(), "b".to_string()))
+        );
+        // Unchanged content is not an edit
+        assert!(bar.edit_selected("b").is_none());
+
+        let edited = bar.edit_selected("c").unwrap();
+        let args: serde_json::Value = serde_json::from_str(&edited.function.arguments).unwrap();
+        assert_eq!(args["new_str"], "c");
+        assert_eq!(args["old_str"], "a");
+        assert_eq!(bar.selected_action().unwrap().label, "Str Replace (edited)");
+        assert_eq!(
+            bar.get_approved()[0].function.arguments,
+            edited.function.arguments
+        );
+
+        bar.select_next();
+        assert!(bar.selected_action().unwrap().editable_content().is_none());
+        assert!(bar.edit_selected("rm -rf /").is_none());
+    }
+
     #[test]
     fn test_calculate_height_single_row() {
         let mut bar = ApprovalBar::new();
@@ -1,8 +1,10 @@
 use crate::app::InputEvent;
 use crate::constants::AUTO_APPROVE_CONFIG_PATH;
 use serde::{Deserialize, Serialize};
+use stakpak_mcp_config::{McpApprovalAction, McpApprovalRules};
 use stakpak_shared::models::integrations::openai::ToolCall;
 use stakpak_shared::utils::{backward_compatibility_mapping, strip_tool_name};
+use stakpak_shell_tool_approvals::{ApprovalRules, RuleAction};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -34,6 +36,10 @@ pub struct AutoApproveConfig {
     pub default_policy: AutoApprovePolicy,
     pub tools: HashMap<String, AutoApprovePolicy>,
     pub command_patterns: CommandPatterns,
+    /// Command-line patterns (`kubectl get *`) checked before `tools`; the
+    /// same rules `[gateway] approval_rules` takes.
+    #[serde(default)]
+    pub rules: ApprovalRules,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
@@ -109,6 +115,7 @@ impl Default for AutoApproveConfig {
             default_policy: AutoApprovePolicy::Prompt,
             tools,
             command_patterns: CommandPatterns::default(),
+            rules: ApprovalRules::default(),
         }
     }
 }
@@ -118,6 +125,8 @@ pub struct AutoApproveManager {
     original_config: AutoApproveConfig,
     pub config_path: PathBuf,
     input_tx: Option<mpsc::Sender<InputEvent>>,
+    /// Approval policies of external MCP servers, checked before `config`.
+    mcp_approvals: McpApprovalRules,
 }
 
 impl AutoApproveManager {
@@ -150,6 +159,7 @@ impl AutoApproveManager {
                     config: config.clone(),
                     config_path,
                     input_tx: input_tx.clone(),
+                    mcp_approvals: McpApprovalRules::load(),
                 }
             }
         }
@@ -176,6 +186,7 @@ impl AutoApproveManager {
             config: config.clone(),
             config_path,
             input_tx,
+            mcp_approvals: McpApprovalRules::load(),
         })
     }
 
@@ -253,9 +264,25 @@ impl AutoApproveManager {
     }
 
     pub fn get_policy_for_tool(&self, tool_call: &ToolCall) -> AutoApprovePolicy {
+        if let Some(action) = self.mcp_approvals.action_for(&tool_call.function.name) {
+            return mcp_approval_policy(action);
+        }
+
         let binding = tool_call.function.name.clone();
         let tool_name = strip_tool_name(&binding);
 
+        let command = SHELL_TOOLS
+            .contains(&tool_name)
+            .then(|| shell_command(tool_call))
+            .flatten();
+        if let Some(rule) = self.config.rules.evaluate(tool_name, command.as_deref()) {
+            return match rule.action {
+                RuleAction::Allow => AutoApprovePolicy::Auto,
+                RuleAction::RequireApproval => AutoApprovePolicy::Prompt,
+                RuleAction::Deny => AutoApprovePolicy::Never,
+            };
+        }
+
         // For shell commands, resolve hierarchical scope keys
         if SHELL_TOOLS.contains(&tool_name)
             && let Some(action) =
@@ -274,6 +301,10 @@ impl AutoApproveManager {
     }
 
     pub fn get_policy_for_tool_name(&self, tool_name: &str) -> AutoApprovePolicy {
+        if let Some(action) = self.mcp_approvals.action_for(tool_name) {
+            return mcp_approval_policy(action);
+        }
+
         // Check if there's a specific policy for this tool
         if let Some(policy) = self.config.tools.get(strip_tool_name(tool_name)) {
             return policy.clone();
@@ -458,6 +489,20 @@ impl AutoApproveManager {
     }
 }
 
+fn mcp_approval_policy(action: McpApprovalAction) -> AutoApprovePolicy {
+    match action {
+        McpApprovalAction::Approve => AutoApprovePolicy::Auto,
+        McpApprovalAction::Ask => AutoApprovePolicy::Prompt,
+        McpApprovalAction::Deny => AutoApprovePolicy::Never,
+    }
+}
+
+/// The `command` argument of a shell tool call.
+fn shell_command(tool_call: &ToolCall) -> Option<String> {
+    let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments).ok()?;
+    args.get("command")?.as_str().map(str::to_string)
+}
+
 /// Resolve hierarchical shell scope for a tool call.
 ///
 /// Parses the shell command string from the tool call arguments, then resolves
@@ -908,6 +953,7 @@ mod tests {
             config: AutoApproveConfig::default(),
             config_path: PathBuf::from(AUTO_APPROVE_CONFIG_PATH),
             input_tx: None,
+            mcp_approvals: McpApprovalRules::default(),
         };
         let tc = make_run_command_tool_call("stakpak ak search --tree");
 
@@ -921,12 +967,44 @@ mod tests {
             config: AutoApproveConfig::default(),
             config_path: PathBuf::from(AUTO_APPROVE_CONFIG_PATH),
             input_tx: None,
+            mcp_approvals: McpApprovalRules::default(),
         };
         let tc = make_run_command_tool_call("stakpak ak write notes.md");
 
         assert!(manager.should_auto_approve(&tc));
     }
 
+    #[test]
+    fn mcp_server_policies_win_over_bare_tool_policies() {
+        let manager = AutoApproveManager {
+            original_config: AutoApproveConfig::default(),
+            config: AutoApproveConfig::default(),
+            config_path: PathBuf::from(AUTO_APPROVE_CONFIG_PATH),
+            input_tx: None,
+            mcp_approvals: McpApprovalRules::new(
+                [(
+                    "postgres".to_string(),
+                    stakpak_mcp_config::McpServerApproval {
+                        default: Some(McpApprovalAction::Ask),
+                        tools: [("query".to_string(), McpApprovalAction::Approve)].into(),
+                    },
+                )]
+                .into(),
+            ),
+        };
+
+        assert!(manager.should_auto_approve(&make_tool_call("postgres__query", "")));
+        // `view` is auto-approved for built-in tools, not for this server
+        assert_eq!(
+            manager.get_policy_for_tool_name("postgres__view"),
+            AutoApprovePolicy::Prompt
+        );
+        assert_eq!(
+            manager.get_policy_for_tool_name("stakpak__view"),
+            AutoApprovePolicy::Auto
+        );
+    }
+
     #[test]
     fn should_not_auto_approve_stakpak_update_with_fresh_defaults() {
         let manager = AutoApproveManager {
@@ -934,6 +1012,7 @@ mod tests {
             config: AutoApproveConfig::default(),
             config_path: PathBuf::from(AUTO_APPROVE_CONFIG_PATH),
             input_tx: None,
+            mcp_approvals: McpApprovalRules::default(),
         };
         let tc = make_run_command_tool_call("stakpak update");
 
@@ -947,6 +1026,7 @@ mod tests {
             config: AutoApproveConfig::default(),
             config_path: PathBuf::from(AUTO_APPROVE_CONFIG_PATH),
             input_tx: None,
+            mcp_approvals: McpApprovalRules::default(),
         };
         let tc = make_run_command_tool_call("stakpak browser ak visit example.com");
 
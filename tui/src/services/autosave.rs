@@ -0,0 +1,267 @@
+//! Crash-safe session autosave.
+//!
+//! While a session is open the TUI periodically writes a small snapshot of
+//! the state that is lost when the terminal dies: the draft input, the
+//! message scroll position, which overlays are open and any plan review
+//! comments that have not been sent yet. The snapshot lives in
+//! `~/.stakpak/tui-autosave.json` and is only rewritten when it changes.
+//!
+//! `stakpak --restore-last` resumes the snapshot's session on the server and
+//! hands the snapshot back to the TUI through [`apply`].
+
+use crate::app::AppState;
+use crate::services::plan_comments::PlanComments;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How often the snapshot is refreshed.
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// TUI state needed to pick a session back up after a crash.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// Server-side session to re-bind to.
+    pub session_id: String,
+    /// Unsent text in the input box.
+    #[serde(default)]
+    pub draft: String,
+    /// Lines between the top of the viewport and the end of the transcript.
+    /// `None` when the view was following the latest message.
+    #[serde(default)]
+    pub scroll_lines_from_end: Option<usize>,
+    #[serde(default)]
+    pub side_panel_open: bool,
+    /// Set when the plan review overlay was open.
+    #[serde(default)]
+    pub plan_review: Option<PlanReviewSnapshot>,
+}
+
+/// Open plan review overlay, including comments not yet sent to the agent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlanReviewSnapshot {
+    #[serde(default)]
+    pub cursor_line: usize,
+    #[serde(default)]
+    pub scroll: usize,
+    #[serde(default)]
+    pub comments: Option<SavedComments>,
+    /// Text of a comment being composed on `cursor_line`.
+    #[serde(default)]
+    pub comment_draft: Option<String>,
+}
+
+/// Serialized [`PlanComments`], compared by their JSON form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SavedComments(pub PlanComments);
+
+impl PartialEq for SavedComments {
+    fn eq(&self, other: &Self) -> bool {
+        serde_json::to_value(&self.0).ok() == serde_json::to_value(&other.0).ok()
+    }
+}
+
+impl SessionSnapshot {
+    /// Capture the current state, or `None` before the session has an id.
+    pub fn capture(state: &AppState) -> Option<Self> {
+        let session_id = state.side_panel_state.session_id.clone();
+        if session_id.is_empty() {
+            return None;
+        }
+
+        let scrolling = &state.messages_scrolling_state;
+        let scroll_lines_from_end = if scrolling.stay_at_bottom {
+            None
+        } else {
+            scrolling
+                .assembled_lines_cache
+                .as_ref()
+                .map(|(_, lines, _)| lines.len().saturating_sub(scrolling.scroll))
+        };
+
+        let review = &state.plan_review_state;
+        let plan_review = review.is_visible.then(|| PlanReviewSnapshot {
+            cursor_line: review.cursor_line,
+            scroll: review.scroll,
+            comments: review.comments.clone().map(SavedComments),
+            comment_draft: review
+                .show_comment_modal
+                .then(|| review.comment_input.clone()),
+        });
+
+        Some(Self {
+            session_id,
+            draft: state.input().to_string(),
+            scroll_lines_from_end,
+            side_panel_open: state.side_panel_state.is_shown,
+            plan_review,
+        })
+    }
+}
+
+/// Default snapshot location, `~/.stakpak/tui-autosave.json`.
+pub fn autosave_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".stakpak").join("tui-autosave.json"))
+}
+
+/// Load the last snapshot from the default location.
+pub fn load_last() -> Result<SessionSnapshot, String> {
+    let path = autosave_path().ok_or("Could not determine home directory")?;
+    load_from(&path)
+}
+
+fn load_from(path: &Path) -> Result<SessionSnapshot, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|_| format!("No autosaved session found at {}", path.display()))?;
+    serde_json::from_str(&content).map_err(|e| {
+        format!(
+            "Failed to parse autosaved session {}: {}",
+            path.display(),
+            e
+        )
+    })
+}
+
+/// Write `snapshot` to `path` through a temporary file so a crash mid-write
+/// never leaves a truncated snapshot behind.
+fn save_to(path: &Path, snapshot: &SessionSnapshot) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(snapshot).map_err(std::io::Error::other)?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Restore a snapshot into a freshly created [`AppState`].
+///
+/// The scroll position is applied once the resumed history has been loaded
+/// and the session id arrives.
+pub fn apply(state: &mut AppState, snapshot: SessionSnapshot) {
+    if !snapshot.draft.is_empty() {
+        state.set_input(&snapshot.draft);
+        state.set_cursor_position(snapshot.draft.len());
+    }
+    state.messages_scrolling_state.restore_lines_from_end = snapshot.scroll_lines_from_end;
+    state.side_panel_state.is_shown = snapshot.side_panel_open;
+
+    let Some(review) = snapshot.plan_review else {
+        return;
+    };
+    crate::services::plan_review::open_plan_review(state);
+    if !state.plan_review_state.is_visible {
+        return;
+    }
+    let last_line = state.plan_review_state.lines.len().saturating_sub(1);
+    state.plan_review_state.cursor_line = review.cursor_line.min(last_line);
+    state.plan_review_state.scroll = review.scroll.min(last_line);
+    if let Some(SavedComments(comments)) = review.comments {
+        let body = crate::services::plan::extract_plan_body(&state.plan_review_state.content);
+        state.plan_review_state.resolved_anchors =
+            crate::services::plan_comments::resolve_anchors(body, &comments.comments);
+        state.plan_review_state.comments = Some(comments);
+    }
+    if let Some(draft) = review.comment_draft {
+        crate::services::plan_review::open_comment_modal(state);
+        if state.plan_review_state.show_comment_modal {
+            state.plan_review_state.comment_input = draft;
+        }
+    }
+}
+
+/// Periodically persists [`SessionSnapshot`]s from the event loop.
+pub struct Autosaver {
+    path: Option<PathBuf>,
+    last_saved_at: Instant,
+    last_snapshot: Option<SessionSnapshot>,
+}
+
+impl Default for Autosaver {
+    fn default() -> Self {
+        Self::new(autosave_path())
+    }
+}
+
+impl Autosaver {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            last_saved_at: Instant::now(),
+            last_snapshot: None,
+        }
+    }
+
+    /// Save if [`AUTOSAVE_INTERVAL`] has passed since the last save.
+    pub fn tick(&mut self, state: &AppState) {
+        if self.last_saved_at.elapsed() >= AUTOSAVE_INTERVAL {
+            self.save(state);
+        }
+    }
+
+    /// Save now, skipping the write when nothing changed.
+    pub fn save(&mut self, state: &AppState) {
+        self.last_saved_at = Instant::now();
+        let Some(path) = &self.path else {
+            return;
+        };
+        let Some(snapshot) = SessionSnapshot::capture(state) else {
+            return;
+        };
+        if self.last_snapshot.as_ref() == Some(&snapshot) {
+            return;
+        }
+        match save_to(path, &snapshot) {
+            Ok(()) => self.last_snapshot = Some(snapshot),
+            Err(e) => log::warn!("Failed to autosave session to {}: {}", path.display(), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> SessionSnapshot {
+        SessionSnapshot {
+            session_id: "session-1".to_string(),
+            draft: "half-written question".to_string(),
+            scroll_lines_from_end: Some(40),
+            side_panel_open: true,
+            plan_review: Some(PlanReviewSnapshot {
+                cursor_line: 3,
+                scroll: 0,
+                comments: None,
+                comment_draft: Some("tighten this step".to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trips_through_disk() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("nested").join("tui-autosave.json");
+
+        save_to(&path, &snapshot()).expect("save snapshot");
+
+        assert_eq!(load_from(&path).expect("load snapshot"), snapshot());
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn load_reports_missing_snapshot() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let err = load_from(&dir.path().join("missing.json")).expect_err("missing");
+        assert!(err.starts_with("No autosaved session found"));
+    }
+
+    #[test]
+    fn snapshot_tolerates_missing_optional_fields() {
+        let parsed: SessionSnapshot =
+            serde_json::from_str(r#"{"session_id":"session-1"}"#).expect("parse");
+        assert_eq!(parsed.session_id, "session-1");
+        assert!(parsed.draft.is_empty());
+        assert_eq!(parsed.plan_review, None);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::app::AppState;
+use crate::services::accessibility;
 use crate::services::detect_term::ThemeColors;
 use ratatui::{
     Frame,
@@ -36,6 +37,17 @@ impl BannerStyle {
             BannerStyle::Success => ThemeColors::success(),
         }
     }
+
+    /// Textual marker shown in accessibility mode so the style is not
+    /// conveyed by the border color alone.
+    pub fn label(&self) -> &'static str {
+        match self {
+            BannerStyle::Warning => "[WARNING]",
+            BannerStyle::Error => "[ERROR]",
+            BannerStyle::Info => "[INFO]",
+            BannerStyle::Success => "[OK]",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -169,6 +181,17 @@ pub fn render_banner(f: &mut Frame, area: Rect, state: &mut AppState) {
     let mut char_x: u16 = 2;
     let mut byte_offset: usize = 0;
 
+    if accessibility::is_enabled() {
+        let label = format!("{} ", msg.style.label());
+        char_x += label.chars().count() as u16;
+        spans.push(Span::styled(
+            label,
+            Style::default()
+                .fg(msg.style.color())
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
     if commands.is_empty() {
         spans.push(Span::raw(msg.text.clone()));
     } else {
@@ -186,7 +209,7 @@ pub fn render_banner(f: &mut Frame, area: Rect, state: &mut AppState) {
                 cmd_width,
                 1,
             );
-            per_cmd_regions.push((cmd.clone(), cmd_rect));
+            per_cmd_regions.push((cmd.clone(), accessibility::hit_area(cmd_rect, area)));
 
             let styled = Span::styled(
                 cmd.clone(),
@@ -231,7 +254,11 @@ pub fn render_banner(f: &mut Frame, area: Rect, state: &mut AppState) {
 
     // Dismiss click target covers the " x " label plus some padding around it
     // for a more forgiving click area (5 chars wide).
-    let dismiss_width: u16 = 5;
+    let dismiss_width: u16 = if accessibility::is_enabled() {
+        5 + 2 * accessibility::HIT_AREA_PADDING
+    } else {
+        5
+    };
     let dismiss_x = area.x + area.width.saturating_sub(2 + dismiss_width); // border(1) + padding(1) + target
     let dismiss_y = area.y; // cover the full banner height for easier clicking
     state.banner_state.dismiss_region = Some(Rect::new(
@@ -11,6 +11,7 @@ use regex::Regex;
 use stakpak_shared::models::integrations::openai::{
     ToolCall, ToolCallResult, ToolCallResultStatus, ToolCallStreamInfo,
 };
+use stakpak_shared::secrets::redaction::redact_output;
 use stakpak_shared::utils::strip_tool_name;
 use std::sync::OnceLock;
 use unicode_width::UnicodeWidthStr;
@@ -61,10 +62,11 @@ pub fn strip_all_ansi(text: &str) -> String {
     }
 }
 // Add this function to preprocess text and handle carriage returns
+// Secrets are redacted here so no terminal or tool output reaches the screen unredacted
 pub fn preprocess_terminal_output(text: &str) -> String {
     let mut lines: Vec<String> = Vec::new();
     let mut current_line = String::new();
-    let text = strip_all_ansi(text);
+    let text = redact_output(&strip_all_ansi(text));
     let text = text.replace("\r\n", "\n");
     for ch in text.chars() {
         match ch {
@@ -1190,13 +1192,14 @@ pub fn render_result_block(tool_call_result: &ToolCallResult, width: usize) -> V
     // Header line with border - handle multi-line command arguments
     let title_with_args = format!("{} ({})", title, command_args);
 
-    // Calculate available width for the title and arguments
-    let available_width = inner_width - 2; // Account for borders and spacing
     let dot_color = if is_collapsed {
         ThemeColors::magenta()
     } else {
         ThemeColors::dot_success()
     };
+    let marker = crate::services::accessibility::status_marker(dot_color);
+    // Calculate available width for the title and arguments
+    let available_width = inner_width.saturating_sub(marker.chars().count()); // Account for the status marker
     let title_color = if is_collapsed {
         ThemeColors::title()
     } else {
@@ -1218,7 +1221,7 @@ pub fn render_result_block(tool_call_result: &ToolCallResult, width: usize) -> V
         }
 
         header_spans.push(Span::styled(
-            "● ",
+            marker,
             Style::default().fg(dot_color).add_modifier(Modifier::BOLD),
         ));
         header_spans.push(Span::styled(
@@ -1233,7 +1236,7 @@ pub fn render_result_block(tool_call_result: &ToolCallResult, width: usize) -> V
         ));
 
         if !is_collapsed {
-            let header_content_width = 2 + title_with_args.len();
+            let header_content_width = marker.chars().count() + title_with_args.len();
             let header_padding = inner_width.saturating_sub(header_content_width);
             header_spans.push(Span::from(" ".repeat(header_padding)));
             header_spans.push(Span::styled(
@@ -1256,7 +1259,7 @@ pub fn render_result_block(tool_call_result: &ToolCallResult, width: usize) -> V
         }
 
         header_spans.push(Span::styled(
-            "● ",
+            marker,
             Style::default().fg(dot_color).add_modifier(Modifier::BOLD),
         ));
         header_spans.push(Span::styled(
@@ -1267,7 +1270,7 @@ pub fn render_result_block(tool_call_result: &ToolCallResult, width: usize) -> V
         ));
 
         if !is_collapsed {
-            let title_content_width = 2 + title.len();
+            let title_content_width = marker.chars().count() + title.len();
             let title_padding = inner_width.saturating_sub(title_content_width);
             header_spans.push(Span::from(" ".repeat(title_padding)));
             header_spans.push(Span::styled(
@@ -1548,8 +1551,9 @@ fn render_styled_header_with_dot(
 
     // Use actual terminal width if provided, otherwise fall back to 100
     let max_line_width: usize = terminal_width.unwrap_or(100);
-    // First line prefix: "● " (2) + title + " (" (2)
-    let first_line_prefix_len: usize = 2 + title.chars().count() + 2;
+    let marker = crate::services::accessibility::status_marker(colors.dot);
+    // First line prefix: marker ("● " or "[OK] ") + title + " (" (2)
+    let first_line_prefix_len: usize = marker.chars().count() + title.chars().count() + 2;
     // Continuation line prefix: just some indentation (2 spaces)
     let continuation_indent = "  ";
     let continuation_prefix_len: usize = continuation_indent.len();
@@ -1566,7 +1570,7 @@ fn render_styled_header_with_dot(
         // Single line - command fits on one line with title
         let mut spans = vec![
             Span::styled(
-                "● ",
+                marker,
                 Style::default().fg(colors.dot).add_modifier(Modifier::BOLD),
             ),
             Span::styled(
@@ -1589,7 +1593,7 @@ fn render_styled_header_with_dot(
         // First line: title + start of command
         result_lines.push(Line::from(vec![
             Span::styled(
-                "● ",
+                marker,
                 Style::default().fg(colors.dot).add_modifier(Modifier::BOLD),
             ),
             Span::styled(
@@ -2320,6 +2324,22 @@ pub fn render_run_command_block(
     owned_lines
 }
 
+/// Custom answer as shown on screen: secrets are masked with one dot per character
+fn mask_secret_answer(
+    question: &stakpak_shared::models::integrations::openai::AskUserQuestion,
+    answer: &str,
+) -> String {
+    let is_secret = question
+        .input_type
+        .as_ref()
+        .is_some_and(|input_type| input_type.is_secret());
+    if is_secret {
+        "•".repeat(answer.chars().count())
+    } else {
+        answer.to_string()
+    }
+}
+
 /// Render an ask_user tool block inline, similar to render_run_command_block.
 /// Shows a bordered block with tab bar, question content or review, and help text.
 pub fn render_ask_user_block(
@@ -2487,7 +2507,7 @@ pub fn render_ask_user_block(
                             .collect()
                     } else {
                         let display = if answer.is_custom {
-                            answer.answer.clone()
+                            mask_secret_answer(q, &answer.answer)
                         } else {
                             q.options
                                 .iter()
@@ -2778,8 +2798,10 @@ pub fn render_ask_user_block(
                     let prefix_width = bracket_display_width + 1; // bracket + space
                     // Reserve 1 char for cursor on last line
                     let available_text_width = max_content_width.saturating_sub(prefix_width + 1);
-                    let wrapped_input =
-                        wrap_text_by_word(custom_input, available_text_width.max(1));
+                    let wrapped_input = wrap_text_by_word(
+                        &mask_secret_answer(q, custom_input),
+                        available_text_width.max(1),
+                    );
                     let total_lines = wrapped_input.len();
 
                     for (line_idx, input_line) in wrapped_input.iter().enumerate() {
@@ -2847,7 +2869,10 @@ pub fn render_ask_user_block(
                 let bracket_display_width = calculate_display_width(&bracket);
                 let prefix_width = bracket_display_width + 1;
                 let available_text_width = max_content_width.saturating_sub(prefix_width);
-                let wrapped_answer = wrap_text_by_word(&answer.answer, available_text_width.max(1));
+                let wrapped_answer = wrap_text_by_word(
+                    &mask_secret_answer(q, &answer.answer),
+                    available_text_width.max(1),
+                );
 
                 for (line_idx, answer_line) in wrapped_answer.iter().enumerate() {
                     let is_first = line_idx == 0;
@@ -2900,6 +2925,29 @@ pub fn render_ask_user_block(
                     Span::styled(" │", Style::default().fg(border_color)),
                 ]));
             }
+
+            // Inline validation error for typed inputs, shown while typing
+            let validation_error = q
+                .input_type
+                .as_ref()
+                .filter(|_| is_selected && !custom_input.is_empty())
+                .and_then(|input_type| input_type.validate(custom_input).err());
+            if let Some(error) = validation_error {
+                let indent = " ".repeat(calculate_display_width("[›] "));
+                let error_width = max_content_width.saturating_sub(indent.len() + 2);
+                for error_line in wrap_text_by_word(&error, error_width.max(1)) {
+                    let text = format!("{}⚠ {}", indent, error_line);
+                    let text_padding =
+                        max_content_width.saturating_sub(calculate_display_width(&text));
+                    formatted_lines.push(Line::from(vec![
+                        Span::styled("│", Style::default().fg(border_color)),
+                        Span::from(" "),
+                        Span::styled(text, Style::default().fg(ThemeColors::danger())),
+                        Span::from(" ".repeat(text_padding)),
+                        Span::styled(" │", Style::default().fg(border_color)),
+                    ]));
+                }
+            }
         }
 
         // Empty line after options
@@ -2940,7 +2988,7 @@ pub fn render_ask_user_block(
                 .unwrap_or(false);
 
             if is_custom_selected {
-                vec![
+                let mut spans = vec![
                     Span::styled("Type", Style::default().fg(ThemeColors::dark_gray())),
                     Span::styled(" your answer", Style::default().fg(ThemeColors::cyan())),
                     Span::raw(" · "),
@@ -2949,7 +2997,19 @@ pub fn render_ask_user_block(
                     Span::raw(" · "),
                     Span::styled("↑", Style::default().fg(ThemeColors::dark_gray())),
                     Span::styled(" back", Style::default().fg(ThemeColors::cyan())),
-                ]
+                ];
+                let is_path = questions
+                    .get(current_tab)
+                    .and_then(|q| q.input_type.as_ref())
+                    .is_some_and(|input_type| input_type.is_path());
+                if is_path {
+                    spans.extend([
+                        Span::raw(" · "),
+                        Span::styled("Tab", Style::default().fg(ThemeColors::dark_gray())),
+                        Span::styled(" complete", Style::default().fg(ThemeColors::cyan())),
+                    ]);
+                }
+                spans
             } else {
                 let is_multi = questions
                     .get(current_tab)
@@ -2964,9 +3024,12 @@ pub fn render_ask_user_block(
                         Span::styled("Enter", Style::default().fg(ThemeColors::dark_gray())),
                         Span::styled(" next", Style::default().fg(ThemeColors::cyan())),
                         Span::raw(" · "),
-                        Span::styled("↑/↓", Style::default().fg(ThemeColors::dark_gray())),
+                        Span::styled("↑/↓/1-9", Style::default().fg(ThemeColors::dark_gray())),
                         Span::styled(" options", Style::default().fg(ThemeColors::cyan())),
                         Span::raw(" · "),
+                        Span::styled("a", Style::default().fg(ThemeColors::dark_gray())),
+                        Span::styled(" all", Style::default().fg(ThemeColors::cyan())),
+                        Span::raw(" · "),
                         Span::styled("←/→", Style::default().fg(ThemeColors::dark_gray())),
                         Span::styled(" questions", Style::default().fg(ThemeColors::cyan())),
                         Span::raw(" · "),
@@ -2981,7 +3044,7 @@ pub fn render_ask_user_block(
                         Span::styled("Enter", Style::default().fg(ThemeColors::dark_gray())),
                         Span::styled(" next", Style::default().fg(ThemeColors::cyan())),
                         Span::raw(" · "),
-                        Span::styled("↑/↓", Style::default().fg(ThemeColors::dark_gray())),
+                        Span::styled("↑/↓/1-9", Style::default().fg(ThemeColors::dark_gray())),
                         Span::styled(" options", Style::default().fg(ThemeColors::cyan())),
                         Span::raw(" · "),
                         Span::styled("←/→", Style::default().fg(ThemeColors::dark_gray())),
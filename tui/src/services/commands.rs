@@ -8,17 +8,18 @@
 //!
 //! All commands are defined here and executed through a unified executor.
 
-use crate::app::{AppState, HelperCommand};
+use crate::app::{AppState, HelperCommand, TranscriptFormat};
 use crate::constants::SUMMARIZE_PROMPT_BASE;
 use crate::services::auto_approve::AutoApprovePolicy;
 use crate::services::detect_term::ThemeColors;
 use crate::services::helper_block::{
-    push_clear_message, push_error_message, push_help_message, push_issue_message,
-    push_status_message, push_styled_message, push_support_message, push_usage_message,
-    render_system_message, welcome_messages,
+    push_error_message, push_help_message, push_issue_message, push_status_message,
+    push_styled_message, push_support_message, push_usage_message, render_system_message,
+    welcome_messages,
 };
 use crate::services::layout::centered_rect;
 use crate::services::message::{Message, MessageContent};
+use crate::services::session_templates::SessionTemplate;
 use crate::{InputEvent, OutputEvent};
 use ratatui::{
     Frame,
@@ -59,6 +60,7 @@ pub enum CommandAction {
     ShowUsage,
     SwitchModel,
     PlanMode,
+    ExportTranscript,
 }
 
 impl CommandAction {
@@ -74,6 +76,7 @@ impl CommandAction {
             CommandAction::ShowUsage => Some("/usage"),
             CommandAction::SwitchModel => Some("/model"),
             CommandAction::PlanMode => Some("/plan"),
+            CommandAction::ExportTranscript => Some("/export"),
             // These don't have slash commands, handled separately
             CommandAction::OpenProfileSwitcher
             | CommandAction::OpenRulebookSwitcher
@@ -127,7 +130,7 @@ pub fn get_all_commands() -> Vec<Command> {
         Command::new(
             "Shortcuts",
             "Show all keyboard shortcuts",
-            "Ctrl+S",
+            "F1",
             CommandAction::OpenShortcuts,
         ),
         Command::new(
@@ -190,6 +193,12 @@ pub fn get_all_commands() -> Vec<Command> {
             "/plan",
             CommandAction::PlanMode,
         ),
+        Command::new(
+            "Export Transcript",
+            "Save the session transcript to a markdown file",
+            "Ctrl+S",
+            CommandAction::ExportTranscript,
+        ),
     ]
 }
 
@@ -242,6 +251,23 @@ pub fn commands_to_helper_commands() -> Vec<HelperCommand> {
             description: "Show token usage for this session".into(),
             source: CommandSource::BuiltIn,
         },
+        HelperCommand {
+            command: "/split".into(),
+            description: "Show or hide the plan task pane next to the chat (F2)".into(),
+            source: CommandSource::BuiltIn,
+        },
+        HelperCommand {
+            command: "/tab".into(),
+            description: "Open or switch session tabs: /tab [new|close|next|prev|<n>] (Alt+1..9)"
+                .into(),
+            source: CommandSource::BuiltIn,
+        },
+        HelperCommand {
+            command: "/export".into(),
+            description: "Save the session transcript with tool calls and results: /export [md|json]"
+                .into(),
+            source: CommandSource::BuiltIn,
+        },
         HelperCommand {
             command: "/issue".into(),
             description: "Report an issue or bug".into(),
@@ -272,6 +298,22 @@ pub fn commands_to_helper_commands() -> Vec<HelperCommand> {
             description: "Toggle mouse capture on/off".into(),
             source: CommandSource::BuiltIn,
         },
+        HelperCommand {
+            command: "/accessibility".into(),
+            description: "Toggle accessibility mode (text status markers, high contrast, no animations)".into(),
+            source: CommandSource::BuiltIn,
+        },
+        HelperCommand {
+            command: "/theme".into(),
+            description: "Switch color scheme: /theme [auto|dark|light|solarized|high-contrast|custom]"
+                .into(),
+            source: CommandSource::BuiltIn,
+        },
+        HelperCommand {
+            command: "/privacy".into(),
+            description: "Toggle screen-share mode (mask secrets, paths and hostnames already on screen)".into(),
+            source: CommandSource::BuiltIn,
+        },
         HelperCommand {
             command: "/profiles".into(),
             description: "Switch to a different profile".into(),
@@ -292,11 +334,21 @@ pub fn commands_to_helper_commands() -> Vec<HelperCommand> {
             description: "Enter plan mode: /plan [optional prompt]".into(),
             source: CommandSource::BuiltIn,
         },
+        HelperCommand {
+            command: "/template".into(),
+            description: "Apply a session template: /template <name>".into(),
+            source: CommandSource::BuiltIn,
+        },
         HelperCommand {
             command: "/init".into(),
             description: "Analyze your infrastructure setup".into(),
             source: CommandSource::BuiltIn,
         },
+        HelperCommand {
+            command: "/skip_discovery".into(),
+            description: "Stop startup environment discovery".into(),
+            source: CommandSource::BuiltIn,
+        },
     ]
 }
 
@@ -341,9 +393,7 @@ pub fn execute_command(command_id: CommandId<'_>, ctx: CommandContext) -> Result
             Ok(())
         }
         "/clear" => {
-            push_clear_message(ctx.state);
-            ctx.state.input_state.text_area.set_text("");
-            ctx.state.input_state.show_helper_dropdown = false;
+            crate::services::confirm::request_clear(ctx.state);
             Ok(())
         }
         "/status" => {
@@ -397,6 +447,34 @@ pub fn execute_command(command_id: CommandId<'_>, ctx: CommandContext) -> Result
             ctx.state.input_state.show_helper_dropdown = false;
             Ok(())
         }
+        "/split" => {
+            crate::services::plan_pane::toggle(ctx.state);
+            ctx.state.input_state.text_area.set_text("");
+            ctx.state.input_state.show_helper_dropdown = false;
+            Ok(())
+        }
+        "/tab" => {
+            let input = ctx.state.input().trim().to_string();
+            let arg = input.strip_prefix("/tab").unwrap_or_default().to_string();
+            ctx.state.input_state.text_area.set_text("");
+            ctx.state.input_state.show_helper_dropdown = false;
+            crate::services::tabs::run_command(ctx.state, &arg, ctx.output_tx)
+        }
+        "/export" => {
+            let input = ctx.state.input().trim().to_string();
+            let arg = input.strip_prefix("/export").unwrap_or_default();
+            ctx.state.input_state.text_area.set_text("");
+            ctx.state.input_state.show_helper_dropdown = false;
+
+            let format = TranscriptFormat::parse(arg).ok_or_else(|| {
+                format!(
+                    "Unknown transcript format '{}'. Use /export md or /export json.",
+                    arg.trim()
+                )
+            })?;
+            export_transcript(ctx.state, ctx.output_tx, format);
+            Ok(())
+        }
         "/issue" => {
             push_issue_message(ctx.state);
             ctx.state.input_state.text_area.set_text("");
@@ -449,6 +527,31 @@ pub fn execute_command(command_id: CommandId<'_>, ctx: CommandContext) -> Result
             let _ = ctx.input_tx.try_send(InputEvent::ToggleMouseCapture);
             Ok(())
         }
+        "/accessibility" => {
+            ctx.state.input_state.text_area.set_text("");
+            ctx.state.input_state.show_helper_dropdown = false;
+            toggle_accessibility_mode(ctx.state);
+            Ok(())
+        }
+        "/theme" => {
+            let input = ctx.state.input().trim().to_string();
+            let arg = input.strip_prefix("/theme").unwrap_or_default().trim();
+            ctx.state.input_state.text_area.set_text("");
+            ctx.state.input_state.show_helper_dropdown = false;
+            if arg.is_empty() {
+                show_color_schemes(ctx.state);
+                return Ok(());
+            }
+            let name = crate::services::theme::SchemeName::parse(arg)?;
+            switch_color_scheme(ctx.state, name);
+            Ok(())
+        }
+        "/privacy" => {
+            ctx.state.input_state.text_area.set_text("");
+            ctx.state.input_state.show_helper_dropdown = false;
+            toggle_screen_share_mode(ctx.state);
+            Ok(())
+        }
         "/editor" => {
             // Parse path argument if provided: /editor <path>
             let input = ctx.state.input().trim().to_string();
@@ -497,6 +600,22 @@ pub fn execute_command(command_id: CommandId<'_>, ctx: CommandContext) -> Result
             }
             Ok(())
         }
+        "/template" => {
+            let input = ctx.state.input().trim().to_string();
+            let name = input
+                .strip_prefix("/template")
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string);
+            ctx.state.input_state.text_area.set_text("");
+            ctx.state.input_state.show_helper_dropdown = false;
+
+            match name {
+                Some(name) => apply_session_template(ctx.state, ctx.output_tx, &name),
+                None => list_session_templates(ctx.state),
+            }
+            Ok(())
+        }
         "/shortcuts" => {
             ctx.state.input_state.text_area.set_text("");
             ctx.state.input_state.show_helper_dropdown = false;
@@ -545,6 +664,32 @@ pub fn execute_command(command_id: CommandId<'_>, ctx: CommandContext) -> Result
             Ok(())
         }
 
+        "/skip_discovery" => {
+            ctx.state.input_state.text_area.set_text("");
+            ctx.state.input_state.show_helper_dropdown = false;
+            if ctx.state.discovery_state.progress.is_none() {
+                push_styled_message(
+                    ctx.state,
+                    " Environment discovery is not running.",
+                    ThemeColors::dark_gray(),
+                    "",
+                    ThemeColors::dark_gray(),
+                );
+                return Ok(());
+            }
+
+            let _ = ctx.output_tx.try_send(OutputEvent::CancelDiscovery);
+            push_styled_message(
+                ctx.state,
+                " Environment discovery skipped.",
+                ThemeColors::yellow(),
+                "⚠ ",
+                ThemeColors::yellow(),
+            );
+            crate::services::handlers::handle_discovery_finished(ctx.state, ctx.output_tx);
+            Ok(())
+        }
+
         "/init" => {
             //  init prompt is always available (embedded at compile time)
             let prompt = match ctx.state.configuration_state.init_prompt_content.as_deref() {
@@ -762,12 +907,18 @@ pub fn resume_session(state: &mut AppState, output_tx: &Sender<OutputEvent>) {
     state.plan_review_state.is_visible = false;
     state.plan_review_state.content.clear();
     state.plan_review_state.lines.clear();
+    state.plan_review_state.code_highlights.clear();
     state.plan_review_state.comments = None;
     state.plan_review_state.resolved_anchors.clear();
     state.plan_review_state.show_comment_modal = false;
     state.plan_review_state.comment_input.clear();
     state.plan_review_state.selected_comment = None;
     state.plan_review_state.modal_kind = None;
+    state.plan_review_state.diff = None;
+    state.plan_review_state.show_diff = false;
+    state.plan_review_state.selection_start = None;
+    state.plan_review_state.search_query.clear();
+    state.plan_review_state.search_editing = false;
 }
 
 pub fn new_session(state: &mut AppState, output_tx: &Sender<OutputEvent>) {
@@ -911,6 +1062,97 @@ fn collect_recent_user_inputs(state: &AppState, limit: usize) -> Vec<String> {
     entries
 }
 
+/// Flip screen-share mode and drop every render cache, so text already on
+/// screen is masked (or unmasked) immediately, even mid-stream.
+pub fn toggle_screen_share_mode(state: &mut AppState) {
+    let enabled = crate::services::screen_share::toggle();
+    let scrolling = &mut state.messages_scrolling_state;
+    scrolling.per_message_cache.clear();
+    scrolling.assembled_lines_cache = None;
+    scrolling.visible_lines_cache = None;
+    scrolling.message_lines_cache = None;
+    let message = if enabled {
+        " Screen-share mode on: secrets, paths and hostnames are masked."
+    } else {
+        " Screen-share mode off."
+    };
+    push_styled_message(state, message, ThemeColors::cyan(), "", ThemeColors::cyan());
+}
+
+/// Ask the session loop to write the transcript; it answers with
+/// `InputEvent::TranscriptExported` once the file is written
+pub fn export_transcript(
+    state: &mut AppState,
+    output_tx: &Sender<OutputEvent>,
+    format: TranscriptFormat,
+) {
+    push_styled_message(
+        state,
+        &format!(
+            " Exporting transcript ({})...",
+            format.extension().to_uppercase()
+        ),
+        ThemeColors::dark_gray(),
+        "",
+        ThemeColors::dark_gray(),
+    );
+    let _ = output_tx.try_send(OutputEvent::ExportTranscript(format));
+}
+
+/// Flip accessibility mode for this session and re-render cached messages
+/// so their colors and status markers follow the new mode.
+pub fn toggle_accessibility_mode(state: &mut AppState) {
+    let enabled = crate::services::accessibility::toggle();
+    state.messages_scrolling_state.per_message_cache.clear();
+    crate::services::message::invalidate_message_lines_cache(state);
+    let message = if enabled {
+        " Accessibility mode on: text status markers, high contrast, no animations."
+    } else {
+        " Accessibility mode off."
+    };
+    push_styled_message(state, message, ThemeColors::cyan(), "", ThemeColors::cyan());
+}
+
+/// List the color schemes, marking the active one.
+pub fn show_color_schemes(state: &mut AppState) {
+    let active = crate::services::theme::active().name;
+    let schemes = crate::services::theme::SchemeName::ALL
+        .iter()
+        .map(|name| {
+            if *name == active {
+                format!("{} (active)", name.as_str())
+            } else {
+                name.as_str().to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let message = format!(" Color schemes: {}. Use /theme <name> to switch.", schemes);
+    push_styled_message(
+        state,
+        &message,
+        ThemeColors::cyan(),
+        "",
+        ThemeColors::cyan(),
+    );
+}
+
+/// Switch the color scheme for this session and re-render cached messages
+/// in the new colors. `[settings.theme.colors]` overrides still apply.
+pub fn switch_color_scheme(state: &mut AppState, name: crate::services::theme::SchemeName) {
+    crate::services::theme::switch(name);
+    state.messages_scrolling_state.per_message_cache.clear();
+    crate::services::message::invalidate_message_lines_cache(state);
+    let message = format!(" Color scheme: {}", name.as_str());
+    push_styled_message(
+        state,
+        &message,
+        ThemeColors::cyan(),
+        "",
+        ThemeColors::cyan(),
+    );
+}
+
 pub fn list_auto_approved_tools(state: &mut AppState) {
     let config = state.configuration_state.auto_approve_manager.get_config();
     let mut auto_approved_tools: Vec<_> = config
@@ -959,6 +1201,68 @@ pub fn list_auto_approved_tools(state: &mut AppState) {
     }
 }
 
+/// Show the available session templates.
+fn list_session_templates(state: &mut AppState) {
+    let templates = SessionTemplate::list();
+    if templates.is_empty() {
+        push_styled_message(
+            state,
+            "No session templates found. Add <name>.md files to .stakpak/templates/ or ~/.stakpak/templates/.",
+            ThemeColors::cyan(),
+            "",
+            ThemeColors::cyan(),
+        );
+        return;
+    }
+
+    state
+        .messages_scrolling_state
+        .messages
+        .push(Message::plain_text(""));
+    push_styled_message(
+        state,
+        "Session templates (apply with /template <name>):",
+        ThemeColors::cyan(),
+        "",
+        ThemeColors::cyan(),
+    );
+    for template in templates {
+        let line = match &template.description {
+            Some(description) => format!("  {} - {}", template.name, description),
+            None => format!("  {}", template.name),
+        };
+        push_styled_message(state, &line, ThemeColors::cyan(), "", ThemeColors::cyan());
+    }
+}
+
+/// Load a template, apply its approval policy locally and hand the rest
+/// (model, system prompt addendum) to the session.
+fn apply_session_template(state: &mut AppState, output_tx: &Sender<OutputEvent>, name: &str) {
+    let template = match SessionTemplate::load(name) {
+        Ok(template) => template,
+        Err(e) => {
+            push_error_message(state, &e, None);
+            return;
+        }
+    };
+
+    for tool_name in template.auto_approve.iter().flatten() {
+        let _ = state
+            .configuration_state
+            .auto_approve_manager
+            .update_tool_policy(tool_name, AutoApprovePolicy::Auto);
+    }
+
+    push_styled_message(
+        state,
+        &format!(" Applied template '{}'", template.name),
+        ThemeColors::green(),
+        "✓",
+        ThemeColors::green(),
+    );
+    let _ = output_tx.try_send(OutputEvent::ApplyTemplate(template));
+}
+
 // ========== Command Palette Rendering ==========
 // NOTE: render_command_palette is preserved for reference but no longer used.
 // The unified popup in shortcuts_popup.rs now handles command palette rendering.
@@ -0,0 +1,244 @@
+//! Confirmation and undo for destructive actions outside plan review.
+//!
+//! Cancelling a running turn (`Esc`), clearing the conversation (`/clear`)
+//! and dismissing an `ask_user` prompt that already has answers each ask
+//! first, with the same y/Enter and n/Esc modal plan review uses. Reverting
+//! to a message can't be confirmed up front without getting in the way, so
+//! it takes effect in the view at once and keeps an undo (`Ctrl+Z`) open
+//! for [`UNDO_WINDOW`]; the files and the backend history are only reverted
+//! when the window closes or the next message is sent.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+use std::time::{Duration, Instant};
+
+use crate::app::AppState;
+use crate::services::changeset::TodoItem;
+use crate::services::detect_term::ThemeColors;
+use crate::services::message::{Message, MessageContent, invalidate_message_lines_cache};
+use crate::services::toast::Toast;
+
+/// How long a revert to a message can be undone
+pub const UNDO_WINDOW: Duration = Duration::from_secs(5);
+
+/// Confirmation dialog variants for destructive actions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmAction {
+    /// Stop the turn that is thinking, streaming or running a tool.
+    CancelRun,
+    /// Clear the conversation from the screen.
+    ClearHistory,
+    /// Cancel the ask_user prompt, discarding N answers.
+    DismissAskUser { answered: usize },
+}
+
+/// A revert to a message that can still be undone.
+#[derive(Debug, Clone)]
+pub struct RevertUndo {
+    /// Messages removed from the view, in order
+    pub removed: Vec<Message>,
+    /// User message index the files are reverted from
+    pub target_index: usize,
+    pub user_message_count: usize,
+    pub pending_revert_index: Option<usize>,
+    pub todos: Vec<TodoItem>,
+    pub expires_at: Instant,
+}
+
+pub fn run_is_active(state: &AppState) -> bool {
+    state.loading_state.is_loading || state.tool_call_state.is_streaming
+}
+
+fn has_conversation(state: &AppState) -> bool {
+    state
+        .messages_scrolling_state
+        .messages
+        .iter()
+        .any(|m| matches!(m.content, MessageContent::UserMessage(_)))
+}
+
+/// `/clear`: ask first when there is a conversation to lose.
+pub fn request_clear(state: &mut AppState) {
+    state.input_state.text_area.set_text("");
+    state.input_state.show_helper_dropdown = false;
+    if has_conversation(state) {
+        state.confirm_state.pending = Some(ConfirmAction::ClearHistory);
+    } else {
+        crate::services::helper_block::push_clear_message(state);
+    }
+}
+
+/// Answers given so far in the open ask_user prompt, counting typed text.
+pub fn unsaved_ask_user_answers(state: &AppState) -> usize {
+    let typed = usize::from(!state.ask_user_state.custom_input.trim().is_empty());
+    state.ask_user_state.answers.len() + typed
+}
+
+/// Drop a confirmation whose subject went away on its own, e.g. the run
+/// finished while the dialog was open. Called from the spinner tick along
+/// with [`expire_undo`].
+pub fn drop_stale(state: &mut AppState) {
+    let stale = match state.confirm_state.pending {
+        Some(ConfirmAction::CancelRun) => !run_is_active(state),
+        Some(ConfirmAction::DismissAskUser { .. }) => !state.ask_user_state.is_visible,
+        Some(ConfirmAction::ClearHistory) | None => false,
+    };
+    if stale {
+        state.confirm_state.pending = None;
+    }
+}
+
+/// Remove the messages from index `from` on and keep them for undo. Any
+/// earlier revert still in its window is applied first.
+pub fn stash_revert(state: &mut AppState, from: usize, target_index: usize) {
+    commit_revert(state);
+    let messages = &mut state.messages_scrolling_state.messages;
+    let removed = messages.split_off(from.min(messages.len()));
+    let revert_state = &mut state.message_revert_state;
+    revert_state.undo = Some(RevertUndo {
+        removed,
+        target_index,
+        user_message_count: revert_state.user_message_count,
+        pending_revert_index: revert_state.pending_revert_index,
+        todos: std::mem::take(&mut state.side_panel_state.todos),
+        expires_at: Instant::now() + UNDO_WINDOW,
+    });
+    revert_state.pending_revert_index = Some(target_index);
+    revert_state.user_message_count = target_index.saturating_sub(1);
+    invalidate_message_lines_cache(state);
+
+    let mut toast = Toast::info("Reverted to message · ctrl+z to undo");
+    toast.duration = UNDO_WINDOW;
+    state.toast = Some(toast);
+}
+
+/// Put back the messages of the last revert. Returns false when its undo
+/// window has closed.
+pub fn undo_revert(state: &mut AppState) -> bool {
+    let Some(undo) = state.message_revert_state.undo.take() else {
+        return false;
+    };
+    state.messages_scrolling_state.messages.extend(undo.removed);
+    state.message_revert_state.user_message_count = undo.user_message_count;
+    state.message_revert_state.pending_revert_index = undo.pending_revert_index;
+    state.side_panel_state.todos = undo.todos;
+    invalidate_message_lines_cache(state);
+    state.toast = Some(Toast::success("Revert undone"));
+    true
+}
+
+/// Apply the file side of a pending revert and close its undo window.
+pub fn commit_revert(state: &mut AppState) {
+    let Some(undo) = state.message_revert_state.undo.take() else {
+        return;
+    };
+    let revert_result = state
+        .side_panel_state
+        .changeset
+        .revert_from_user_message(undo.target_index, &state.side_panel_state.session_id);
+    match revert_result {
+        Ok((files_reverted, files_deleted)) if files_reverted > 0 || files_deleted > 0 => {
+            state.toast = Some(Toast::success(format!(
+                "Reverted {} file(s), deleted {} created file(s)",
+                files_reverted, files_deleted
+            )));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            log::warn!("Revert failed: {}", e);
+            state.toast = Some(Toast::error("Reverted messages (file revert failed)"));
+        }
+    }
+}
+
+/// Commit a revert whose undo window has passed.
+pub fn expire_undo(state: &mut AppState) {
+    if state
+        .message_revert_state
+        .undo
+        .as_ref()
+        .is_some_and(|undo| Instant::now() >= undo.expires_at)
+    {
+        commit_revert(state);
+    }
+}
+
+/// Render the confirmation dialog, centered over the whole screen.
+pub fn render_confirm_modal(f: &mut Frame, state: &AppState) {
+    let Some(ref action) = state.confirm_state.pending else {
+        return;
+    };
+
+    let (title, message, confirm_label, color) = match action {
+        ConfirmAction::CancelRun => (
+            " Cancel Run ",
+            "Stop the agent's current turn?".to_string(),
+            "stop",
+            ThemeColors::yellow(),
+        ),
+        ConfirmAction::ClearHistory => (
+            " Clear Conversation ",
+            "Clear all messages from the screen?".to_string(),
+            "clear",
+            ThemeColors::red(),
+        ),
+        ConfirmAction::DismissAskUser { answered } => (
+            " Discard Answers ",
+            format!(
+                "Cancel the question and discard {} answer{}?",
+                answered,
+                if *answered == 1 { "" } else { "s" }
+            ),
+            "discard",
+            ThemeColors::red(),
+        ),
+    };
+
+    let area = f.area();
+    let lines: Vec<Line<'_>> = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            message,
+            Style::default().fg(ThemeColors::text()),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", Style::default().fg(ThemeColors::green())),
+            Span::styled("/", Style::default().fg(ThemeColors::dark_gray())),
+            Span::styled("Enter", Style::default().fg(ThemeColors::green())),
+            Span::styled(
+                format!("={}  ", confirm_label),
+                Style::default().fg(ThemeColors::dark_gray()),
+            ),
+            Span::styled("n", Style::default().fg(ThemeColors::red())),
+            Span::styled("/", Style::default().fg(ThemeColors::dark_gray())),
+            Span::styled("Esc", Style::default().fg(ThemeColors::red())),
+            Span::styled("=keep", Style::default().fg(ThemeColors::dark_gray())),
+        ]),
+    ];
+
+    let modal_width = 52u16.min(area.width.saturating_sub(4));
+    let modal_height = (lines.len() as u16 + 2)
+        .min(area.height.saturating_sub(4))
+        .max(4);
+    let x = area.x + area.width.saturating_sub(modal_width) / 2;
+    let y = area.y + area.height.saturating_sub(modal_height) / 2;
+    let modal_area = Rect::new(x, y, modal_width, modal_height);
+
+    f.render_widget(Clear, modal_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(color))
+        .title(Span::styled(
+            title,
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
@@ -7,7 +7,17 @@ use std::process::Command;
 // ============================================================================
 
 // Re-export shared theme detection so existing imports continue to work
-pub use stakpak_shared::terminal_theme::{Theme, current_theme, init_theme, is_light_mode};
+pub use stakpak_shared::terminal_theme::{Theme, current_theme, init_theme};
+
+use crate::services::theme::ColorRole;
+
+/// Whether colors should suit a light background: the `dark` and `light`
+/// color schemes fix the background, other schemes follow detection.
+pub fn is_light_mode() -> bool {
+    crate::services::theme::active()
+        .light_background()
+        .unwrap_or_else(stakpak_shared::terminal_theme::is_light_mode)
+}
 
 // ============================================================================
 // Themed Colors - The main interface for getting theme-aware colors
@@ -16,11 +26,28 @@ pub use stakpak_shared::terminal_theme::{Theme, current_theme, init_theme, is_li
 /// Get theme-aware colors. This is the primary interface for color selection.
 pub struct ThemeColors;
 
+/// Color the active color scheme assigns to `role`, if any.
+fn scheme_color(role: ColorRole) -> Option<Color> {
+    crate::services::theme::color(role)
+}
+
+/// High-contrast replacement used while accessibility mode is on: `dark` on
+/// dark backgrounds, `light` on light ones.
+fn high_contrast(dark: Color, light: Color) -> Option<Color> {
+    crate::services::accessibility::is_enabled().then(|| if is_light_mode() { light } else { dark })
+}
+
 impl ThemeColors {
     // --- Text Colors ---
 
     /// Primary text color - should be readable on terminal background
     pub fn text() -> Color {
+        if let Some(color) = high_contrast(Color::White, Color::Black) {
+            return color;
+        }
+        if let Some(color) = scheme_color(ColorRole::Text) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(235) // Very dark gray for light backgrounds
         } else if should_use_rgb_colors() {
@@ -32,6 +59,12 @@ impl ThemeColors {
 
     /// Muted/secondary text color - for less important text
     pub fn muted() -> Color {
+        if let Some(color) = high_contrast(Color::Gray, Color::Indexed(236)) {
+            return color;
+        }
+        if let Some(color) = scheme_color(ColorRole::Muted) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(242) // Medium gray for light backgrounds
         } else {
@@ -41,6 +74,12 @@ impl ThemeColors {
 
     /// Assistant message text color
     pub fn assistant_text() -> Color {
+        if let Some(color) = high_contrast(Color::White, Color::Black) {
+            return color;
+        }
+        if let Some(color) = scheme_color(ColorRole::AssistantText) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(238) // Dark gray, readable on white
         } else if should_use_rgb_colors() {
@@ -54,6 +93,9 @@ impl ThemeColors {
 
     /// Primary accent color (for highlights, borders, interactive elements)
     pub fn accent() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Accent) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(30) // Darker cyan/teal for light backgrounds
         } else {
@@ -63,6 +105,9 @@ impl ThemeColors {
 
     /// Secondary accent color
     pub fn accent_secondary() -> Color {
+        if let Some(color) = scheme_color(ColorRole::AccentSecondary) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(25) // Dark blue for light backgrounds
         } else {
@@ -74,6 +119,9 @@ impl ThemeColors {
 
     /// Success color (green)
     pub fn success() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Success) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(28) // Darker green for light backgrounds
         } else {
@@ -83,6 +131,9 @@ impl ThemeColors {
 
     /// Warning color (yellow/orange)
     pub fn warning() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Warning) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(172) // Darker orange for light backgrounds
         } else {
@@ -92,6 +143,9 @@ impl ThemeColors {
 
     /// Error/danger color (red)
     pub fn danger() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Danger) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(160) // Darker red for light backgrounds
         } else {
@@ -103,6 +157,12 @@ impl ThemeColors {
 
     /// Border color for boxes and panels
     pub fn border() -> Color {
+        if let Some(color) = high_contrast(Color::Gray, Color::Indexed(236)) {
+            return color;
+        }
+        if let Some(color) = scheme_color(ColorRole::Border) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(245) // Medium gray for light backgrounds
         } else {
@@ -112,6 +172,9 @@ impl ThemeColors {
 
     /// Title color in popups and sections
     pub fn title() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Title) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(166) // Darker yellow/orange for light backgrounds
         } else {
@@ -121,6 +184,9 @@ impl ThemeColors {
 
     /// Highlight background color (e.g., selected item)
     pub fn highlight_bg() -> Color {
+        if let Some(color) = scheme_color(ColorRole::HighlightBg) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(117) // Light blue for light backgrounds
         } else {
@@ -130,12 +196,40 @@ impl ThemeColors {
 
     /// Highlight foreground color (text on highlight)
     pub fn highlight_fg() -> Color {
+        if let Some(color) = scheme_color(ColorRole::HighlightFg) {
+            return color;
+        }
         // Black text on highlight works well for both themes
         Color::Black
     }
 
+    /// Background of selected text
+    pub fn selection_bg() -> Color {
+        if let Some(color) = high_contrast(Color::White, Color::Black) {
+            return color;
+        }
+        if let Some(color) = scheme_color(ColorRole::SelectionBg) {
+            return color;
+        }
+        Color::Blue
+    }
+
+    /// Foreground of selected text
+    pub fn selection_fg() -> Color {
+        if let Some(color) = high_contrast(Color::Black, Color::White) {
+            return color;
+        }
+        if let Some(color) = scheme_color(ColorRole::SelectionFg) {
+            return color;
+        }
+        Color::White
+    }
+
     /// Input cursor color
     pub fn cursor() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Cursor) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(30) // Darker cyan for light backgrounds
         } else {
@@ -145,6 +239,9 @@ impl ThemeColors {
 
     /// Code block background
     pub fn code_bg() -> Color {
+        if let Some(color) = scheme_color(ColorRole::CodeBg) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(254) // Very light gray for light backgrounds
         } else if should_use_rgb_colors() {
@@ -156,6 +253,9 @@ impl ThemeColors {
 
     /// Magenta accent (for user messages, special highlights)
     pub fn magenta() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Magenta) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(127) // Darker magenta for light backgrounds
         } else {
@@ -165,6 +265,9 @@ impl ThemeColors {
 
     /// Light magenta for backgrounds
     pub fn magenta_dim() -> Color {
+        if let Some(color) = scheme_color(ColorRole::MagentaDim) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(225) // Very light magenta for light backgrounds
         } else if should_use_rgb_colors() {
@@ -179,6 +282,12 @@ impl ThemeColors {
     /// Primary title color - for headers, labels, and prominent text
     /// Replaces direct use of Color::White which is invisible on light backgrounds
     pub fn title_primary() -> Color {
+        if let Some(color) = high_contrast(Color::White, Color::Black) {
+            return color;
+        }
+        if let Some(color) = scheme_color(ColorRole::TitlePrimary) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(235) // Very dark gray for light backgrounds
         } else {
@@ -188,6 +297,9 @@ impl ThemeColors {
 
     /// Success dot/indicator color - for status dots and small indicators
     pub fn dot_success() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Success) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(28) // Darker green for light backgrounds
         } else {
@@ -197,6 +309,9 @@ impl ThemeColors {
 
     /// Error dot/indicator color - for error status dots and small indicators
     pub fn dot_error() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Danger) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(160) // Darker red for light backgrounds
         } else {
@@ -206,6 +321,9 @@ impl ThemeColors {
 
     /// Unselected/inactive background color - for non-highlighted items
     pub fn unselected_bg() -> Color {
+        if let Some(color) = scheme_color(ColorRole::UnselectedBg) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(252) // Light gray for light mode
         } else {
@@ -215,6 +333,9 @@ impl ThemeColors {
 
     /// Theme-aware red color (for text/icons, not backgrounds)
     pub fn red() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Red) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(160) // Darker red for light backgrounds
         } else if should_use_rgb_colors() {
@@ -226,6 +347,9 @@ impl ThemeColors {
 
     /// Theme-aware green color (for text/icons, not backgrounds)
     pub fn green() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Green) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(28) // Darker green for light backgrounds
         } else if should_use_rgb_colors() {
@@ -237,6 +361,12 @@ impl ThemeColors {
 
     /// Theme-aware dark gray color
     pub fn dark_gray() -> Color {
+        if let Some(color) = high_contrast(Color::Gray, Color::Indexed(236)) {
+            return color;
+        }
+        if let Some(color) = scheme_color(ColorRole::DarkGray) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(245) // Medium gray for light backgrounds
         } else if should_use_rgb_colors() {
@@ -248,6 +378,9 @@ impl ThemeColors {
 
     /// Theme-aware orange color
     pub fn orange() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Orange) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(166) // Darker orange for light backgrounds
         } else {
@@ -262,6 +395,9 @@ impl ThemeColors {
 
     /// Theme-aware yellow color
     pub fn yellow() -> Color {
+        if let Some(color) = scheme_color(ColorRole::Yellow) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(136) // Darker yellow/gold for light backgrounds
         } else {
@@ -272,6 +408,9 @@ impl ThemeColors {
     /// Background color for dropdown menus and overlays
     /// Light mode needs an explicit bg for contrast; dark mode uses terminal default
     pub fn dropdown_bg() -> Color {
+        if let Some(color) = scheme_color(ColorRole::DropdownBg) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(255) // Near-white for light mode
         } else {
@@ -281,6 +420,12 @@ impl ThemeColors {
 
     /// Text color for dropdown menus (contrasts with dropdown_bg)
     pub fn dropdown_text() -> Color {
+        if let Some(color) = high_contrast(Color::White, Color::Black) {
+            return color;
+        }
+        if let Some(color) = scheme_color(ColorRole::DropdownText) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(235) // Very dark gray for light mode
         } else {
@@ -290,6 +435,12 @@ impl ThemeColors {
 
     /// Muted/secondary text color for dropdowns
     pub fn dropdown_muted() -> Color {
+        if let Some(color) = high_contrast(Color::Gray, Color::Indexed(236)) {
+            return color;
+        }
+        if let Some(color) = scheme_color(ColorRole::DropdownMuted) {
+            return color;
+        }
         if is_light_mode() {
             Color::Indexed(245) // Medium gray for light mode
         } else {
@@ -68,6 +68,7 @@ pub fn open_in_editor<B: ratatui::backend::Backend>(
     line_number: Option<usize>,
 ) -> Result<(), String> {
     // Suspend TUI and show cursor for the editor
+    crate::terminal::disable_keyboard_enhancement();
     execute!(stdout(), LeaveAlternateScreen, Show)
         .map_err(|e| format!("Failed to leave alternate screen: {}", e))?;
     disable_raw_mode().map_err(|e| format!("Failed to disable raw mode: {}", e))?;
@@ -98,11 +99,53 @@ pub fn open_in_editor<B: ratatui::backend::Backend>(
     }
 }
 
+/// Text an external editor compose session is editing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComposeTarget {
+    /// The main prompt input
+    Input,
+    /// The comment being written in plan review
+    PlanComment,
+    /// The proposed content of the file edit selected in the approval bar
+    ToolCall,
+}
+
+/// Edit `initial` in an external editor and return the saved text
+///
+/// The text round-trips through a temporary file ending in `suffix` (so the
+/// editor picks the right syntax) that is removed afterwards. Trailing
+/// newlines added by the editor are dropped.
+pub fn compose_in_editor<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    editor: &str,
+    initial: &str,
+    suffix: &str,
+) -> Result<String, String> {
+    let file = tempfile::Builder::new()
+        .prefix("stakpak-compose-")
+        .suffix(suffix)
+        .tempfile()
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    std::fs::write(file.path(), initial)
+        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+
+    open_in_editor(terminal, editor, &file.path().to_string_lossy(), None)?;
+
+    let text = std::fs::read_to_string(file.path())
+        .map_err(|e| format!("Failed to read temp file: {}", e))?;
+    Ok(strip_trailing_newlines(&text).to_string())
+}
+
+fn strip_trailing_newlines(text: &str) -> &str {
+    text.trim_end_matches(['\n', '\r'])
+}
+
 /// Restore TUI state after external editor closes
 fn restore_tui<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>) -> Result<(), String> {
     execute!(stdout(), EnterAlternateScreen, Hide)
         .map_err(|e| format!("Failed to enter alternate screen: {}", e))?;
     enable_raw_mode().map_err(|e| format!("Failed to enable raw mode: {}", e))?;
+    crate::terminal::enable_keyboard_enhancement();
     terminal
         .clear()
         .map_err(|e| format!("Failed to clear terminal: {}", e))?;
@@ -129,4 +172,13 @@ mod tests {
         // 'nonexistent_editor_xyz' should not be available
         assert!(!is_editor_available("nonexistent_editor_xyz"));
     }
+
+    #[test]
+    fn test_strip_trailing_newlines_keeps_inner_paragraphs() {
+        assert_eq!(
+            strip_trailing_newlines("First paragraph.\n\nSecond.\r\n\n"),
+            "First paragraph.\n\nSecond."
+        );
+        assert_eq!(strip_trailing_newlines(""), "");
+    }
 }
@@ -6,7 +6,8 @@ use stakpak_shared::models::integrations::openai::ToolCall;
 use stakpak_shared::utils::strip_tool_name;
 use unicode_width::UnicodeWidthStr;
 
-use crate::services::detect_term::AdaptiveColors;
+use crate::services::detect_term::{AdaptiveColors, ThemeColors};
+use crate::services::syntax_highlighter;
 
 /// Extract the starting line number from a diff result string.
 /// Parses the hunk header like "@@ -21 +21 @@" or "@@ -21,3 +21,3 @@" and returns the old line number.
@@ -41,6 +42,35 @@ pub fn find_starting_line_in_file(file_path: &str, old_str: &str) -> Option<usiz
     Some(line_number)
 }
 
+/// Spans for the text of one diff row. Unwrapped lines take their syntax
+/// colors from `highlighted` when it holds the same text; wrapped lines (and
+/// files without a known syntax) stay plain.
+fn content_spans(
+    content_line: &str,
+    highlighted: Option<&Vec<Span<'static>>>,
+    wrapped: bool,
+    bg: Option<Color>,
+) -> Vec<Span<'static>> {
+    let with_bg = |style: Style| match bg {
+        Some(bg) => style.bg(bg),
+        None => style,
+    };
+    if !wrapped && let Some(spans) = highlighted {
+        let spans: Vec<Span<'static>> = spans
+            .iter()
+            .map(|span| Span::styled(span.content.replace('\t', "    "), with_bg(span.style)))
+            .collect();
+        let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+        if text == content_line {
+            return spans;
+        }
+    }
+    vec![Span::styled(
+        content_line.to_string(),
+        with_bg(Style::default().fg(AdaptiveColors::text())),
+    )]
+}
+
 pub fn render_file_diff_block(
     tool_call: &ToolCall,
     terminal_width: usize,
@@ -54,15 +84,37 @@ pub fn render_file_diff_block(
 /// This is used as a fallback when the file has already been modified (e.g., on session resume).
 /// The `starting_line` parameter allows specifying the starting line number offset
 /// (e.g., if the old_str starts at line 21 in the actual file, pass Some(21)).
+///
+/// With a `language` (a file extension or syntax name the highlighter knows),
+/// the text of each line that fits on one row is syntax colored on top of the
+/// add/remove background.
 pub fn preview_diff_from_strings(
     old_str: &str,
     new_str: &str,
     terminal_width: usize,
     starting_line: Option<usize>,
+    language: Option<&str>,
 ) -> (Vec<Line<'static>>, usize, usize, usize, usize) {
     // Create a line-by-line diff directly from the strings
     let diff = TextDiff::from_lines(old_str, new_str);
 
+    // Highlight each side as a whole so multi-line constructs carry over
+    let language = language.filter(|lang| syntax_highlighter::is_known_language(lang));
+    let highlight = |slices: &[&str]| -> Vec<Vec<Span<'static>>> {
+        match language {
+            Some(lang) => {
+                let lines: Vec<&str> = slices
+                    .iter()
+                    .map(|line| line.trim_end_matches(['\n', '\r']))
+                    .collect();
+                syntax_highlighter::highlight_code_lines(&lines, Some(lang))
+            }
+            None => Vec::new(),
+        }
+    };
+    let old_highlights = highlight(diff.old_slices());
+    let new_highlights = highlight(diff.new_slices());
+
     let mut lines = Vec::new();
     let mut deletions = 0;
     let mut insertions = 0;
@@ -145,8 +197,8 @@ pub fn preview_diff_from_strings(
                     let wrapped_content = wrap_content(&line_content, terminal_width, prefix_width);
 
                     for (i, content_line) in wrapped_content.iter().enumerate() {
-                        if i == 0 {
-                            lines.push(Line::from(vec![
+                        let mut line_spans = if i == 0 {
+                            vec![
                                 Span::styled(
                                     format!("{:>4} ", old_line_num),
                                     Style::default().fg(AdaptiveColors::dark_gray()),
@@ -156,13 +208,9 @@ pub fn preview_diff_from_strings(
                                     Style::default().fg(AdaptiveColors::dark_gray()),
                                 ),
                                 Span::styled("  ", Style::default()),
-                                Span::styled(
-                                    content_line.clone(),
-                                    Style::default().fg(AdaptiveColors::text()),
-                                ),
-                            ]));
+                            ]
                         } else {
-                            lines.push(Line::from(vec![
+                            vec![
                                 Span::styled(
                                     "     ",
                                     Style::default().fg(AdaptiveColors::dark_gray()),
@@ -172,12 +220,15 @@ pub fn preview_diff_from_strings(
                                     Style::default().fg(AdaptiveColors::dark_gray()),
                                 ),
                                 Span::styled("  ", Style::default()),
-                                Span::styled(
-                                    content_line.clone(),
-                                    Style::default().fg(AdaptiveColors::text()),
-                                ),
-                            ]));
-                        }
+                            ]
+                        };
+                        line_spans.extend(content_spans(
+                            content_line,
+                            old_highlights.get(old_range.start + idx),
+                            wrapped_content.len() > 1,
+                            None,
+                        ));
+                        lines.push(Line::from(line_spans));
                     }
                 }
             }
@@ -233,11 +284,11 @@ pub fn preview_diff_from_strings(
                             ));
                         }
 
-                        line_spans.push(Span::styled(
-                            content_line.clone(),
-                            Style::default()
-                                .fg(AdaptiveColors::text())
-                                .bg(AdaptiveColors::dark_red()),
+                        line_spans.extend(content_spans(
+                            content_line,
+                            old_highlights.get(old_range.start + idx),
+                            wrapped_content.len() > 1,
+                            Some(AdaptiveColors::dark_red()),
                         ));
 
                         let current_width =
@@ -307,11 +358,11 @@ pub fn preview_diff_from_strings(
                             ));
                         }
 
-                        line_spans.push(Span::styled(
-                            content_line.clone(),
-                            Style::default()
-                                .fg(AdaptiveColors::text())
-                                .bg(AdaptiveColors::dark_green()),
+                        line_spans.extend(content_spans(
+                            content_line,
+                            new_highlights.get(new_range.start + idx),
+                            wrapped_content.len() > 1,
+                            Some(AdaptiveColors::dark_green()),
                         ));
 
                         let current_width =
@@ -382,11 +433,11 @@ pub fn preview_diff_from_strings(
                             ));
                         }
 
-                        line_spans.push(Span::styled(
-                            content_line.clone(),
-                            Style::default()
-                                .fg(AdaptiveColors::text())
-                                .bg(AdaptiveColors::dark_red()),
+                        line_spans.extend(content_spans(
+                            content_line,
+                            old_highlights.get(old_range.start + idx),
+                            wrapped_content.len() > 1,
+                            Some(AdaptiveColors::dark_red()),
                         ));
 
                         let current_width =
@@ -451,11 +502,11 @@ pub fn preview_diff_from_strings(
                             ));
                         }
 
-                        line_spans.push(Span::styled(
-                            content_line.clone(),
-                            Style::default()
-                                .fg(AdaptiveColors::text())
-                                .bg(AdaptiveColors::dark_green()),
+                        line_spans.extend(content_spans(
+                            content_line,
+                            new_highlights.get(new_range.start + idx),
+                            wrapped_content.len() > 1,
+                            Some(AdaptiveColors::dark_green()),
                         ));
 
                         let current_width =
@@ -509,6 +560,11 @@ pub fn render_file_diff_block_from_args(
         args.get("new_str").and_then(|v| v.as_str()).unwrap_or("")
     };
     let path = args.get("path").and_then(|v| v.as_str()).unwrap_or("");
+    let file_path = std::path::Path::new(path);
+    let language = file_path
+        .extension()
+        .or_else(|| file_path.file_name())
+        .and_then(|name| name.to_str());
 
     // If both old and new are empty, no diff to show
     if old_str.is_empty() && new_str.is_empty() {
@@ -524,7 +580,7 @@ pub fn render_file_diff_block_from_args(
 
     // Generate diff directly from the strings
     let (diff_lines, deletions, insertions, first_change_index, last_change_index) =
-        preview_diff_from_strings(old_str, new_str, terminal_width, starting_line);
+        preview_diff_from_strings(old_str, new_str, terminal_width, starting_line, language);
 
     if deletions == 0 && insertions == 0 {
         return (vec![], vec![]);
@@ -546,9 +602,12 @@ pub fn render_file_diff_block_from_args(
         ),
         Span::styled(
             format!(" +{}", insertions),
-            Style::default().fg(Color::Green),
+            Style::default().fg(ThemeColors::green()),
+        ),
+        Span::styled(
+            format!(" -{}", deletions),
+            Style::default().fg(ThemeColors::red()),
         ),
-        Span::styled(format!(" -{}", deletions), Style::default().fg(Color::Red)),
     ]));
 
     // Extract only the changed range (from first change to last change)
@@ -574,7 +633,7 @@ pub fn render_file_diff_block_from_args(
                 "... truncated ({} more lines) . ctrl+t to review",
                 remaining_count
             ),
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(ThemeColors::yellow()),
         )]);
 
         truncated_diff_lines = change_lines;
@@ -589,3 +648,49 @@ pub fn render_file_diff_block_from_args(
 
     (truncated_diff_lines, full_diff_lines)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_text(line: &Line) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn test_preview_diff_colors_known_languages() {
+        let (lines, deletions, insertions, _, _) = preview_diff_from_strings(
+            "let x = 1;\n",
+            "let x = \"two\";\n",
+            80,
+            Some(10),
+            Some("rs"),
+        );
+        assert_eq!((deletions, insertions), (1, 1));
+        let added = lines
+            .iter()
+            .find(|line| row_text(line).contains("\"two\""))
+            .unwrap();
+        assert!(row_text(added).starts_with("       10  + "));
+        let text_colors: std::collections::HashSet<_> = added
+            .spans
+            .iter()
+            .skip(3)
+            .map(|span| span.style.fg)
+            .collect();
+        assert!(text_colors.len() > 1);
+        // The add background still covers the highlighted text
+        assert!(
+            added
+                .spans
+                .iter()
+                .all(|span| span.style.bg == Some(AdaptiveColors::dark_green()))
+        );
+
+        let (plain, ..) = preview_diff_from_strings("a\n", "b\n", 80, None, Some("no-such-lang"));
+        assert!(plain.iter().all(|line| line.spans.len() <= 5));
+    }
+}
@@ -1,16 +1,16 @@
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
 use std::path::Path;
-use std::sync::Arc;
 
-use ignore::WalkBuilder;
-use ignore::overrides::OverrideBuilder;
 use nucleo_matcher::{
     Matcher, Utf32Str,
     pattern::{AtomKind, CaseMatching, Normalization, Pattern},
 };
 use tokio::sync::mpsc;
 
+use stakpak_shared::file_index::{FileIndex, FileIndexOptions};
+use stakpak_shared::project_scope::ProjectScope;
+
 use crate::AppState;
 use crate::app::{FileSearchResult, HelperCommand};
 
@@ -83,6 +83,10 @@ pub struct FileSearch {
     max_matches: usize,
     // Cache for loaded files to avoid reloading
     last_directory: Option<String>,
+    // Indexes of the directories searched, with the prefix their entries are listed under
+    indexes: Vec<(String, FileIndex)>,
+    // Index generations `file_suggestions` was copied at
+    index_generations: Vec<u64>,
 }
 
 impl Default for FileSearch {
@@ -95,74 +99,77 @@ impl Default for FileSearch {
             debounced_filter: DebouncedFilter::new(120), // 120ms debounce
             max_matches: 50,                             // Default to 50 matches for performance
             last_directory: None,
+            indexes: Vec::new(),
+            index_generations: Vec::new(),
         }
     }
 }
 
 impl FileSearch {
-    /// Load all files and directories from current directory using parallel walking with ignore crate
+    /// Load all files and directories under the current directory from the shared file index,
+    /// which is built once and kept fresh by a filesystem watcher.
+    /// In a scoped repository (`.stakpak/scope.toml`) only the scoped directories are indexed.
     pub fn scan_directory(&mut self, dir: &Path) {
         let dir_str = dir.to_string_lossy().to_string();
 
-        // Only reload if directory changed or files are empty
-        if self.last_directory.as_ref() == Some(&dir_str) && !self.file_suggestions.is_empty() {
+        // Same directory: only copy entries again if the index changed
+        if self.last_directory.as_ref() == Some(&dir_str) && !self.indexes.is_empty() {
+            self.refresh_from_indexes();
             return;
         }
 
         self.file_suggestions.clear();
+        self.index_generations.clear();
         self.last_directory = Some(dir_str);
 
-        // Build overrides to exclude .git directory
-        let mut overrides_builder = OverrideBuilder::new(dir);
-        overrides_builder.add("!.git/").ok();
-        let overrides = match overrides_builder.build() {
-            Ok(o) => o,
-            Err(_) => match OverrideBuilder::new(dir).build() {
-                Ok(o) => o,
-                Err(_) => return,
-            },
-        };
-
-        // Use ignore crate for fast parallel directory walking
-        let walker = WalkBuilder::new(dir)
-            .threads(2) // Use 2 threads for optimal performance
-            .hidden(false) // Don't skip hidden files
-            .git_ignore(true) // Respect .gitignore files
-            .git_global(true) // Respect global gitignore
-            .git_exclude(true) // Respect .git/info/exclude
-            .overrides(overrides) // Exclude .git directory
-            .require_git(false) // Don't require git to be present
-            .build_parallel();
-
-        let file_suggestions = Arc::new(std::sync::Mutex::new(Vec::new()));
-        let file_suggestions_clone = file_suggestions.clone();
-
-        walker.run(|| {
-            let file_suggestions = file_suggestions_clone.clone();
-            Box::new(move |entry_result| {
-                if let Ok(entry) = entry_result
-                    && let Some(ft) = entry.file_type()
-                    && (ft.is_file() || ft.is_dir())
-                    && let Ok(rel_path) = entry.path().strip_prefix(dir)
-                    && let Some(path_str) = rel_path.to_str()
-                    && !path_str.is_empty() // Skip the root directory itself
-                    && let Ok(mut files) = file_suggestions.lock()
-                {
-                    // Append "/" to directories to distinguish them
-                    let entry_str = if ft.is_dir() {
-                        format!("{}/", path_str)
-                    } else {
-                        path_str.to_string()
-                    };
-                    files.push(entry_str);
+        self.indexes = ProjectScope::resolve(dir)
+            .search_roots(dir)
+            .into_iter()
+            .map(|root| {
+                // Scoped roots below `dir` are listed relative to `dir`
+                let prefix = root
+                    .strip_prefix(dir)
+                    .ok()
+                    .and_then(Path::to_str)
+                    .filter(|relative| !relative.is_empty())
+                    .map(|relative| format!("{}/", relative))
+                    .unwrap_or_default();
+                let index = FileIndex::open(&root, &FileIndexOptions::project());
+                if let Err(error) = index.watch() {
+                    log::debug!("File mentions will not follow changes: {}", error);
                 }
-                ignore::WalkState::Continue
+                (prefix, index)
             })
-        });
+            .collect();
+        self.refresh_from_indexes();
+    }
 
-        if let Ok(files) = file_suggestions.lock() {
-            self.file_suggestions = files.clone();
+    fn refresh_from_indexes(&mut self) {
+        let generations: Vec<u64> = self
+            .indexes
+            .iter()
+            .map(|(_, index)| index.generation())
+            .collect();
+        if generations == self.index_generations && !self.file_suggestions.is_empty() {
+            return;
+        }
+        self.index_generations = generations;
+
+        let mut file_suggestions = Vec::new();
+        for (prefix, index) in &self.indexes {
+            if prefix.is_empty() {
+                file_suggestions.extend(index.entries());
+            } else {
+                file_suggestions.push(prefix.clone());
+                file_suggestions.extend(
+                    index
+                        .entries()
+                        .into_iter()
+                        .map(|entry| format!("{}{}", prefix, entry)),
+                );
+            }
         }
+        self.file_suggestions = file_suggestions;
     }
 
     /// Filter files based on current input using fuzzy matching with parallel processing
@@ -245,11 +252,13 @@ impl FileSearch {
     pub fn clear_caches(&mut self) {
         self.file_suggestions.clear();
         self.last_directory = None;
+        self.indexes.clear();
+        self.index_generations.clear();
     }
 
-    /// Force reload files from directory (useful when files are created/deleted)
+    /// Pick up files created or deleted since the last call. The index follows the
+    /// filesystem, so this only copies entries when something changed.
     pub fn force_reload_files(&mut self, dir: &Path) {
-        self.clear_caches();
         self.scan_directory(dir);
     }
 }
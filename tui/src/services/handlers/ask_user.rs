@@ -7,6 +7,7 @@ use crate::app::{AppState, OutputEvent};
 use stakpak_shared::models::integrations::openai::{
     AskUserAnswer, AskUserQuestion, AskUserResult, ToolCall, ToolCallResult, ToolCallResultStatus,
 };
+use stakpak_shared::models::tools::ask_user::expand_home;
 use tokio::sync::mpsc::Sender;
 
 /// Get the total number of options for a question (including custom if allowed)
@@ -257,6 +258,15 @@ pub fn handle_ask_user_prev_option(state: &mut AppState) -> bool {
     }
 }
 
+/// Whether a custom answer passes the question's input type (if any).
+/// The inline block shows the validation error while the user types.
+fn is_valid_custom_answer(question: &AskUserQuestion, input: &str) -> bool {
+    question
+        .input_type
+        .as_ref()
+        .is_none_or(|input_type| input_type.validate(input).is_ok())
+}
+
 /// Toggle/select the current option WITHOUT advancing to the next question.
 /// This is triggered by Space. It selects in single-select or toggles in multi-select.
 pub fn handle_ask_user_select_option(state: &mut AppState, output_tx: &Sender<OutputEvent>) {
@@ -293,29 +303,7 @@ pub fn handle_ask_user_select_option(state: &mut AppState, output_tx: &Sender<Ou
                 selections.push(opt_value);
             }
 
-            // Build the answer from current selections
-            let selected = state
-                .ask_user_state
-                .multi_selections
-                .get(&question_label)
-                .cloned()
-                .unwrap_or_default();
-
-            let answer_json = serde_json::to_string(&selected).unwrap_or_else(|_| "[]".to_string());
-
-            let answer = AskUserAnswer {
-                question_label: question_label.clone(),
-                answer: answer_json,
-                is_custom: false,
-                selected_values: selected,
-            };
-
-            if answer.selected_values.is_empty() {
-                // No selections — remove the answer so "required" validation works
-                state.ask_user_state.answers.remove(&question_label);
-            } else {
-                state.ask_user_state.answers.insert(question_label, answer);
-            }
+            sync_multi_select_answer(state, question_label);
         }
         refresh_ask_user_block(state);
         return;
@@ -325,8 +313,10 @@ pub fn handle_ask_user_select_option(state: &mut AppState, output_tx: &Sender<Ou
 
     // Check if custom input is selected
     if current_q.allow_custom && state.ask_user_state.selected_option == current_q.options.len() {
-        // Custom input selected - save the custom answer if not empty (no advance)
-        if !state.ask_user_state.custom_input.is_empty() {
+        // Custom input selected - save the custom answer if not empty and valid (no advance)
+        if !state.ask_user_state.custom_input.is_empty()
+            && is_valid_custom_answer(current_q, &state.ask_user_state.custom_input)
+        {
             let answer = AskUserAnswer {
                 question_label: question_label.clone(),
                 answer: state.ask_user_state.custom_input.clone(),
@@ -358,6 +348,102 @@ pub fn handle_ask_user_select_option(state: &mut AppState, output_tx: &Sender<Ou
     refresh_ask_user_block(state);
 }
 
+/// Rebuild a multi-select question's answer from its current selections
+fn sync_multi_select_answer(state: &mut AppState, question_label: String) {
+    let selected = state
+        .ask_user_state
+        .multi_selections
+        .get(&question_label)
+        .cloned()
+        .unwrap_or_default();
+
+    let answer_json = serde_json::to_string(&selected).unwrap_or_else(|_| "[]".to_string());
+
+    let answer = AskUserAnswer {
+        question_label: question_label.clone(),
+        answer: answer_json,
+        is_custom: false,
+        selected_values: selected,
+    };
+
+    if answer.selected_values.is_empty() {
+        // No selections — remove the answer so "required" validation works
+        state.ask_user_state.answers.remove(&question_label);
+    } else {
+        state.ask_user_state.answers.insert(question_label, answer);
+    }
+}
+
+/// Quick-select by number key: jump to option `number` (1-based, as shown in
+/// the brackets) and select or toggle it, like moving there and pressing
+/// Space. The custom slot is only focused, so the user can start typing.
+pub fn handle_ask_user_quick_select(
+    state: &mut AppState,
+    number: usize,
+    output_tx: &Sender<OutputEvent>,
+) {
+    if !state.ask_user_state.is_visible {
+        return;
+    }
+
+    let Some(current_q) = state
+        .ask_user_state
+        .questions
+        .get(state.ask_user_state.current_tab)
+    else {
+        return;
+    };
+    let Some(index) = number.checked_sub(1) else {
+        return;
+    };
+    if index >= get_total_options(current_q) {
+        return;
+    }
+
+    let is_custom_slot = index == current_q.options.len();
+    state.ask_user_state.selected_option = index;
+    if is_custom_slot {
+        refresh_ask_user_block(state);
+    } else {
+        handle_ask_user_select_option(state, output_tx);
+    }
+}
+
+/// Select every option of a multi-select question, or clear them all when
+/// they are already selected
+pub fn handle_ask_user_select_all(state: &mut AppState) {
+    if !state.ask_user_state.is_visible {
+        return;
+    }
+
+    let Some(current_q) = state
+        .ask_user_state
+        .questions
+        .get(state.ask_user_state.current_tab)
+    else {
+        return;
+    };
+    if !current_q.multi_select {
+        return;
+    }
+
+    let question_label = current_q.label.clone();
+    let all_values: Vec<String> = current_q.options.iter().map(|o| o.value.clone()).collect();
+    let selections = state
+        .ask_user_state
+        .multi_selections
+        .entry(question_label.clone())
+        .or_default();
+    if all_values.iter().all(|value| selections.contains(value)) {
+        selections.clear();
+    } else {
+        *selections = all_values;
+    }
+
+    sync_multi_select_answer(state, question_label);
+    refresh_ask_user_block(state);
+}
+
 /// Confirm the current question and advance to the next one.
 /// This is triggered by Enter. It ONLY advances — it never selects or toggles.
 /// Use Space to select/toggle options. On the submit tab, Enter submits.
@@ -380,6 +466,11 @@ pub fn handle_ask_user_confirm_question(state: &mut AppState, output_tx: &Sender
         && state.ask_user_state.selected_option == current_q.options.len()
         && !state.ask_user_state.custom_input.is_empty()
     {
+        // Stay on the question until the answer is valid; the error is shown inline
+        if !is_valid_custom_answer(current_q, &state.ask_user_state.custom_input) {
+            refresh_ask_user_block(state);
+            return;
+        }
         let answer = AskUserAnswer {
             question_label: current_q.label.clone(),
             answer: state.ask_user_state.custom_input.clone(),
@@ -408,11 +499,20 @@ pub fn handle_ask_user_custom_input_paste(state: &mut AppState, text: &str) {
 
     let current_q = &state.ask_user_state.questions[state.ask_user_state.current_tab];
     if current_q.allow_custom && state.ask_user_state.selected_option == current_q.options.len() {
-        let redacted = state
-            .configuration_state
-            .secret_manager
-            .redact_and_store_secrets(text, None);
-        state.ask_user_state.custom_input.push_str(&redacted);
+        // Secret answers are masked on screen and redacted whole on submit
+        let is_secret = current_q
+            .input_type
+            .as_ref()
+            .is_some_and(|input_type| input_type.is_secret());
+        let text = if is_secret {
+            text.to_string()
+        } else {
+            state
+                .configuration_state
+                .secret_manager
+                .redact_and_store_secrets(text, None)
+        };
+        state.ask_user_state.custom_input.push_str(&text);
         refresh_ask_user_block(state);
     }
 }
@@ -482,12 +582,21 @@ pub fn handle_ask_user_submit(state: &mut AppState, output_tx: &Sender<OutputEve
         .ask_user_state
         .questions
         .iter()
-        .filter_map(|q| state.ask_user_state.answers.get(&q.label).cloned())
-        .map(|mut answer| {
-            answer.answer = state
-                .configuration_state
-                .secret_manager
-                .redact_and_store_secrets(&answer.answer, None);
+        .filter_map(|q| {
+            let answer = state.ask_user_state.answers.get(&q.label).cloned()?;
+            Some((q, answer))
+        })
+        .map(|(q, mut answer)| {
+            let secret_manager = &state.configuration_state.secret_manager;
+            let is_secret = q
+                .input_type
+                .as_ref()
+                .is_some_and(|input_type| input_type.is_secret());
+            answer.answer = if is_secret && answer.is_custom {
+                secret_manager.redact_and_store_password(&answer.answer, &answer.answer)
+            } else {
+                secret_manager.redact_and_store_secrets(&answer.answer, None)
+            };
             answer
         })
         .collect();
@@ -585,12 +694,89 @@ pub fn is_custom_input_selected(state: &AppState) -> bool {
     current_q.allow_custom && state.ask_user_state.selected_option == current_q.options.len()
 }
 
+/// Check if the custom input is selected on a question that asks for a path
+pub fn is_path_input_selected(state: &AppState) -> bool {
+    is_custom_input_selected(state)
+        && state
+            .ask_user_state
+            .questions
+            .get(state.ask_user_state.current_tab)
+            .and_then(|q| q.input_type.as_ref())
+            .is_some_and(|input_type| input_type.is_path())
+}
+
+/// Tab-complete the path typed into the custom input
+pub fn handle_ask_user_complete_path(state: &mut AppState) {
+    if !is_path_input_selected(state) {
+        return;
+    }
+    if let Some(completed) = complete_path(&state.ask_user_state.custom_input) {
+        state.ask_user_state.custom_input = completed;
+        refresh_ask_user_block(state);
+    }
+}
+
+/// Complete the last component of `input` against the filesystem.
+///
+/// A single match is completed fully (with a trailing `/` for directories);
+/// several matches are completed up to their longest common prefix. Hidden
+/// entries only match when the typed component starts with a dot.
+fn complete_path(input: &str) -> Option<String> {
+    let (dir_part, prefix) = match input.rfind('/') {
+        Some(idx) => input.split_at(idx + 1),
+        None => ("", input),
+    };
+    let dir = if dir_part.is_empty() {
+        std::path::PathBuf::from(".")
+    } else {
+        expand_home(dir_part)
+    };
+    let matches: Vec<(String, bool)> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let visible = prefix.starts_with('.') || !name.starts_with('.');
+            (visible && name.starts_with(prefix)).then(|| (name, entry.path().is_dir()))
+        })
+        .collect();
+
+    match matches.as_slice() {
+        [] => None,
+        [(name, is_dir)] => Some(format!(
+            "{}{}{}",
+            dir_part,
+            name,
+            if *is_dir { "/" } else { "" }
+        )),
+        _ => {
+            let common = matches
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .reduce(common_prefix)?;
+            (common.len() > prefix.len()).then(|| format!("{}{}", dir_part, common))
+        }
+    }
+}
+
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .chars()
+        .zip(b.chars())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum();
+    a.get(..len).unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::app::AppStateOptions;
     use stakai::Model;
-    use stakpak_shared::models::integrations::openai::{AskUserOption, FunctionCall};
+    use stakpak_shared::models::integrations::openai::{
+        AskUserInputType, AskUserOption, FunctionCall,
+    };
     use tokio::sync::mpsc;
 
     /// Helper to create a minimal AppState for testing
@@ -635,6 +821,7 @@ mod tests {
                 ],
                 allow_custom: true,
                 multi_select: false,
+                input_type: None,
             },
             AskUserQuestion {
                 label: "Confirm".to_string(),
@@ -655,6 +842,7 @@ mod tests {
                 ],
                 allow_custom: false,
                 multi_select: false,
+                input_type: None,
             },
         ]
     }
@@ -1127,6 +1315,7 @@ mod tests {
             ],
             allow_custom: false,
             multi_select: true,
+            input_type: None,
         }]
     }
 
@@ -1210,6 +1399,7 @@ mod tests {
             }],
             allow_custom: false,
             multi_select: true,
+            input_type: None,
         }];
         let tool_call = create_test_tool_call();
         let (output_tx, _output_rx) = mpsc::channel(10);
@@ -1289,6 +1479,7 @@ mod tests {
             ],
             allow_custom: true, // should be ignored for multi-select
             multi_select: true,
+            input_type: None,
         }];
         let tool_call = create_test_tool_call();
 
@@ -1304,4 +1495,159 @@ mod tests {
         handle_ask_user_next_option(&mut state);
         assert_eq!(state.ask_user_state.selected_option, 1); // stuck at 1, no custom slot
     }
+
+    #[tokio::test]
+    async fn test_quick_select_by_number() {
+        let mut state = create_test_state();
+        let (output_tx, _output_rx) = mpsc::channel(10);
+        handle_show_ask_user_popup(&mut state, create_test_tool_call(), create_test_questions());
+
+        handle_ask_user_quick_select(&mut state, 2, &output_tx);
+        assert_eq!(state.ask_user_state.selected_option, 1);
+        assert_eq!(state.ask_user_state.answers["Environment"].answer, "prod");
+        // Quick select doesn't advance
+        assert_eq!(state.ask_user_state.current_tab, 0);
+
+        // Out of range numbers are ignored
+        handle_ask_user_quick_select(&mut state, 9, &output_tx);
+        handle_ask_user_quick_select(&mut state, 0, &output_tx);
+        assert_eq!(state.ask_user_state.selected_option, 1);
+
+        // The custom slot is only focused
+        handle_ask_user_quick_select(&mut state, 3, &output_tx);
+        assert!(is_custom_input_selected(&state));
+        assert_eq!(state.ask_user_state.answers["Environment"].answer, "prod");
+    }
+
+    #[tokio::test]
+    async fn test_quick_select_toggles_and_select_all() {
+        let mut state = create_test_state();
+        let (output_tx, _output_rx) = mpsc::channel(10);
+        handle_show_ask_user_popup(
+            &mut state,
+            create_test_tool_call(),
+            create_multi_select_questions(),
+        );
+
+        handle_ask_user_quick_select(&mut state, 1, &output_tx);
+        assert_eq!(
+            state.ask_user_state.answers["Scope"].selected_values,
+            vec!["repo:web".to_string()]
+        );
+
+        handle_ask_user_select_all(&mut state);
+        assert_eq!(
+            state.ask_user_state.answers["Scope"].selected_values.len(),
+            3
+        );
+
+        // Pressing it again with everything selected clears the selection
+        handle_ask_user_select_all(&mut state);
+        assert!(!state.ask_user_state.answers.contains_key("Scope"));
+    }
+
+    fn create_typed_question(input_type: AskUserInputType) -> AskUserQuestion {
+        AskUserQuestion {
+            label: "Value".to_string(),
+            question: "Enter a value".to_string(),
+            options: vec![],
+            allow_custom: true,
+            multi_select: false,
+            input_type: Some(input_type),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_typed_answer_is_not_confirmed() {
+        let mut state = create_test_state();
+        let (output_tx, _output_rx) = mpsc::channel(10);
+        let question = create_typed_question(AskUserInputType::Number {
+            min: Some(1.0),
+            max: Some(100.0),
+            integer: true,
+        });
+        handle_show_ask_user_popup(&mut state, create_test_tool_call(), vec![question]);
+        assert!(is_custom_input_selected(&state));
+
+        handle_ask_user_custom_input_paste(&mut state, "500");
+        handle_ask_user_confirm_question(&mut state, &output_tx);
+        assert!(state.ask_user_state.answers.is_empty());
+        assert_eq!(state.ask_user_state.current_tab, 0);
+
+        handle_ask_user_custom_input_delete(&mut state);
+        handle_ask_user_custom_input_paste(&mut state, "42");
+        handle_ask_user_confirm_question(&mut state, &output_tx);
+        assert_eq!(state.ask_user_state.answers["Value"].answer, "42");
+        assert_eq!(state.ask_user_state.current_tab, 1);
+    }
+
+    #[test]
+    fn test_secret_answers_are_masked_and_errors_shown_inline() {
+        let question = create_typed_question(AskUserInputType::Secret);
+        let answers = std::collections::HashMap::new();
+        let rendered = crate::services::bash_block::render_ask_user_block(
+            &[question],
+            &answers,
+            0,
+            0,
+            "hunter2",
+            80,
+            true,
+        );
+        let text: String = rendered
+            .iter()
+            .flat_map(|line| line.spans.iter().map(|span| span.content.to_string()))
+            .collect();
+        assert!(!text.contains("hunter2"));
+        assert!(text.contains("•••••••"));
+
+        let question = create_typed_question(AskUserInputType::Number {
+            min: None,
+            max: None,
+            integer: false,
+        });
+        let rendered = crate::services::bash_block::render_ask_user_block(
+            &[question],
+            &answers,
+            0,
+            0,
+            "abc",
+            80,
+            true,
+        );
+        let text: String = rendered
+            .iter()
+            .flat_map(|line| line.spans.iter().map(|span| span.content.to_string()))
+            .collect();
+        assert!(text.contains("⚠ Enter a number"));
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("deploy")).unwrap();
+        std::fs::write(dir.path().join("data.txt"), "").unwrap();
+        std::fs::write(dir.path().join("data.csv"), "").unwrap();
+        std::fs::write(dir.path().join(".env"), "").unwrap();
+        let base = format!("{}/", dir.path().display());
+
+        // Single match: completed fully, directories get a trailing slash
+        assert_eq!(
+            complete_path(&format!("{}de", base)),
+            Some(format!("{}deploy/", base))
+        );
+        // Several matches: completed to their common prefix
+        assert_eq!(
+            complete_path(&format!("{}da", base)),
+            Some(format!("{}data.", base))
+        );
+        // Nothing more to add
+        assert_eq!(complete_path(&format!("{}d", base)), None);
+        // Hidden entries need a leading dot
+        assert_eq!(
+            complete_path(&format!("{}.e", base)),
+            Some(format!("{}.env", base))
+        );
+        assert_eq!(complete_path(&format!("{}missing/x", base)), None);
+    }
 }
@@ -14,6 +14,7 @@ use crate::services::text_selection::SelectionState;
 use ratatui::layout::Size;
 use ratatui::style::Color;
 use stakpak_shared::models::integrations::openai::ToolCall;
+use stakpak_shared::pending_interactions::{InteractionKind, ReminderStage};
 use stakpak_shared::utils::strip_tool_name;
 use tokio::sync::mpsc::Sender;
 use uuid::Uuid;
@@ -129,6 +130,26 @@ pub fn handle_esc_event(
         return;
     }
 
+    // Esc with nothing else to close would stop a running turn, so ask first
+    if crate::services::confirm::run_is_active(state)
+        && !state.dialog_approval_state.is_dialog_open
+        && !state.shell_popup_state.is_expanded
+        && !state.input_state.show_helper_dropdown
+    {
+        state.confirm_state.pending = Some(crate::services::confirm::ConfirmAction::CancelRun);
+        return;
+    }
+
+    cancel_or_reject(state, input_tx, output_tx, cancel_tx);
+}
+
+/// Reject the open tool call dialog, or cancel the running turn
+pub fn cancel_or_reject(
+    state: &mut AppState,
+    input_tx: &Sender<InputEvent>,
+    output_tx: &Sender<OutputEvent>,
+    cancel_tx: Option<tokio::sync::broadcast::Sender<()>>,
+) {
     // Common handling for rejection
     state.dialog_approval_state.message_tool_calls = None;
     state
@@ -536,19 +557,23 @@ pub fn handle_show_confirmation_dialog(
     }
 
     // Check if this tool call is already approved (after popup interaction or auto-approved)
-    if is_auto_approved
-        || state
-            .dialog_approval_state
-            .message_approved_tools
-            .iter()
-            .any(|tool| tool.id == tool_call.id)
-    {
+    let approved_version = state
+        .dialog_approval_state
+        .message_approved_tools
+        .iter()
+        .find(|tool| tool.id == tool_call.id)
+        .cloned();
+    if is_auto_approved || approved_version.is_some() {
         // Remove from approved list to avoid processing it again
         state
             .dialog_approval_state
             .message_approved_tools
             .retain(|tool| tool.id != tool_call.id);
 
+        // Run what the user approved, which carries any edits made in the
+        // approval bar
+        let tool_call = approved_version.unwrap_or(tool_call);
+
         // Update run_command block to Running state before execution starts
         update_run_command_to_running(state, &tool_call);
 
@@ -655,3 +680,29 @@ pub fn handle_toggle_dialog_focus(state: &mut AppState) {
         );
     }
 }
+
+/// Sync open approval / ask_user prompts into the reminder tracker and report
+/// whether the terminal bell should ring. Called from the spinner tick.
+pub fn tick_interaction_reminders(state: &mut AppState) -> bool {
+    let now = std::time::Instant::now();
+    let approval_open = state.dialog_approval_state.approval_bar.is_visible()
+        || state.dialog_approval_state.is_dialog_open;
+    let ask_user_open = state.ask_user_state.is_visible;
+
+    let reminders = &mut state.dialog_approval_state.reminders;
+    for (kind, open) in [
+        (InteractionKind::Approval, approval_open),
+        (InteractionKind::AskUser, ask_user_open),
+    ] {
+        if open {
+            reminders.track(kind, kind, now);
+        } else {
+            reminders.resolve(&kind);
+        }
+    }
+
+    reminders
+        .due(now)
+        .iter()
+        .any(|reminder| reminder.stage == ReminderStage::Bell)
+}
@@ -12,7 +12,7 @@ use crate::services::commands::{CommandContext, execute_command};
 use crate::services::detect_term::ThemeColors;
 use crate::services::file_search::handle_file_selection;
 use crate::services::helper_block::{
-    push_clear_message, push_error_message, push_styled_message, render_system_message,
+    push_error_message, push_styled_message, render_system_message,
 };
 use crate::services::message::{BubbleColors, Message, MessageContent};
 use ratatui::style::{Color, Style};
@@ -419,7 +419,7 @@ fn handle_input_submitted(
     }
 
     if state.input().trim() == "clear" {
-        push_clear_message(state);
+        crate::services::confirm::request_clear(state);
         return;
     }
 
@@ -538,7 +538,11 @@ fn handle_input_submitted(
         let command_word = input.split_once(' ').map(|(cmd, _)| cmd).unwrap_or(&input);
 
         let command_with_args: Option<&str> = match command_word {
-            "/editor" | "/toggle_auto_approve" if input.contains(' ') => Some(command_word),
+            "/editor" | "/toggle_auto_approve" | "/template" | "/export" | "/theme" | "/tab"
+                if input.contains(' ') =>
+            {
+                Some(command_word)
+            }
             _ => None,
         };
 
@@ -771,6 +775,7 @@ fn handle_input_submitted(
             .configuration_state
             .secret_manager
             .redact_and_store_secrets(&final_input, None);
+        crate::services::input_history::record(state, &final_input);
 
         // Keep placeholders in text for LLM context
         let user_message_text = final_input.clone();
@@ -855,6 +860,7 @@ fn handle_input_submitted(
                     ));
             } else {
                 // Take pending revert index if set (will be None on normal messages)
+                crate::services::confirm::commit_revert(state);
                 let revert_index = state.message_revert_state.pending_revert_index.take();
 
                 if let Err(e) = output_tx.try_send(OutputEvent::UserMessage(
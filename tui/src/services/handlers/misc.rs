@@ -2,7 +2,7 @@
 //!
 //! Handles miscellaneous events that don't fit into other categories.
 
-use crate::app::{AppState, InputEvent};
+use crate::app::{AppState, InputEvent, OutputEvent};
 use crate::services::bash_block::render_bash_block_rejected;
 use crate::services::board_tasks::{
     FetchTasksResult, extract_board_agent_id_from_messages, fetch_tasks_as_todo_items,
@@ -15,10 +15,20 @@ use crate::services::message::Message;
 use crate::services::message::get_wrapped_collapsed_message_lines_cached;
 use ratatui::layout::Size;
 use stakai::Model;
+use stakpak_shared::error::{ErrorKind, StakpakError};
 use uuid::Uuid;
 
 /// Handle error event
 pub fn handle_error(state: &mut AppState, err: String) {
+    report_error(state, err, ErrorKind::Internal);
+}
+
+/// Handle an error whose kind is known
+pub fn handle_failure(state: &mut AppState, error: StakpakError) {
+    report_error(state, error.message, error.kind);
+}
+
+fn report_error(state: &mut AppState, err: String, kind: ErrorKind) {
     if err.contains("FREE_PLAN") {
         push_error_message(state, "Free plan limit reached.", None);
         push_error_message(
@@ -47,7 +57,9 @@ pub fn handle_error(state: &mut AppState, err: String) {
         return;
     }
     let mut error_message = handle_errors(err);
-    if error_message.contains("RETRY_ATTEMPT") || error_message.contains("MAX_RETRY_REACHED") {
+    let retrying =
+        error_message.contains("RETRY_ATTEMPT") || error_message.contains("MAX_RETRY_REACHED");
+    if retrying {
         if error_message.contains("RETRY_ATTEMPT") {
             let retry_attempt = error_message.split("RETRY_ATTEMPT_").last().unwrap_or("1");
             error_message = format!(
@@ -62,6 +74,14 @@ pub fn handle_error(state: &mut AppState, err: String) {
     }
 
     push_error_message(state, &error_message, None);
+    // Known kinds get their code and remediation hint under the message
+    if !retrying && kind != ErrorKind::Internal {
+        push_error_message(
+            state,
+            &format!("Hint ({}): {}", kind.code(), kind.hint()),
+            Some(true),
+        );
+    }
 }
 
 /// Handle resized event
@@ -80,7 +100,9 @@ pub fn handle_resized(state: &mut AppState, width: u16, height: u16) {
 
 /// Handle toggle cursor visible event
 pub fn handle_toggle_cursor_visible(state: &mut AppState) {
-    state.input_state.cursor_visible = !state.input_state.cursor_visible;
+    // The input cursor stays solid in accessibility mode.
+    state.input_state.cursor_visible =
+        crate::services::accessibility::is_enabled() || !state.input_state.cursor_visible;
 }
 
 /// Handle toggle auto approve event
@@ -248,12 +270,20 @@ fn handle_collapsed_messages_tab(
 }
 
 /// Handle Ctrl+S event
-pub fn handle_ctrl_s(state: &mut AppState, input_tx: &tokio::sync::mpsc::Sender<InputEvent>) {
+pub fn handle_ctrl_s(
+    state: &mut AppState,
+    input_tx: &tokio::sync::mpsc::Sender<InputEvent>,
+    output_tx: &tokio::sync::mpsc::Sender<OutputEvent>,
+) {
     if state.rulebook_switcher_state.show_rulebook_switcher {
         let _ = input_tx.try_send(InputEvent::RulebookSwitcherSelectAll);
         return;
     }
-    let _ = input_tx.try_send(InputEvent::ShowShortcuts);
+    crate::services::commands::export_transcript(
+        state,
+        output_tx,
+        crate::app::TranscriptFormat::Markdown,
+    );
 }
 
 /// Handle attempt quit event
@@ -347,6 +377,49 @@ pub fn handle_set_banner_message(
     state.banner_state.message = Some(crate::services::banner::BannerMessage::new(text, style));
 }
 
+/// Handle startup discovery progress event
+pub fn handle_discovery_progress(state: &mut AppState, completed: usize, total: usize) {
+    if !state.discovery_state.done {
+        state.discovery_state.progress = Some((completed, total));
+    }
+}
+
+/// Handle startup discovery finishing (or being skipped)
+pub fn handle_discovery_finished(
+    state: &mut AppState,
+    output_tx: &tokio::sync::mpsc::Sender<crate::app::OutputEvent>,
+) {
+    state.discovery_state.progress = None;
+    state.discovery_state.done = true;
+    if std::mem::take(&mut state.discovery_state.init_prompt_pending) {
+        send_init_prompt(state, output_tx);
+    }
+}
+
+/// Add the init prompt as a user message and send it to the backend
+pub fn send_init_prompt(
+    state: &mut AppState,
+    output_tx: &tokio::sync::mpsc::Sender<crate::app::OutputEvent>,
+) {
+    let Some(prompt) = state.configuration_state.init_prompt_content.clone() else {
+        return;
+    };
+    if prompt.trim().is_empty() {
+        return;
+    }
+    state
+        .messages_scrolling_state
+        .messages
+        .push(Message::user(prompt.clone(), None));
+    crate::services::message::invalidate_message_lines_cache(state);
+    let _ = output_tx.try_send(crate::app::OutputEvent::UserMessage(
+        prompt,
+        None,
+        Vec::new(),
+        None,
+    ));
+}
+
 /// Handle start loading operation event
 pub fn handle_start_loading_operation(
     state: &mut AppState,
@@ -19,9 +19,15 @@ pub mod tool;
 pub use input::find_image_file_by_name;
 // Re-export tick_selection_auto_scroll for use in event_loop spinner tick
 pub use text_selection::tick_selection_auto_scroll;
+// Re-export tick_interaction_reminders for use in event_loop spinner tick
+pub use dialog::tick_interaction_reminders;
+// Re-export handle_discovery_finished for use by /skip_discovery
+pub use misc::handle_discovery_finished;
 
 use crate::app::{AppState, InputEvent, OutputEvent, PendingUserMessage};
+use crate::services::confirm::{self, ConfirmAction};
 use crate::services::handlers::banner::handle_banner_mouse_click;
+use crate::services::input_history;
 use ratatui::layout::Size;
 use tokio::sync::mpsc::Sender;
 
@@ -66,7 +72,9 @@ fn flush_pending_user_messages_if_idle(
         user_message_text,
     } = pending_message;
 
-    // Take pending revert index if set (will be None on normal messages)
+    // Take pending revert index if set (will be None on normal messages),
+    // applying its file revert first if it could still be undone
+    confirm::commit_revert(state);
     let revert_index = state.message_revert_state.pending_revert_index.take();
 
     // Dismiss the onboarding banner once the user sends their first message.
@@ -133,6 +141,12 @@ pub fn update(
     shell_tx: &Sender<InputEvent>,
     terminal_size: Size,
 ) {
+    // Focus reports only feed notifications, so no popup or block may eat them
+    if let InputEvent::FocusChanged(focused) = event {
+        state.terminal_ui_state.is_focused = focused;
+        return;
+    }
+
     // Block all input during profile switch EXCEPT profile switch events and Quit
     if state.is_input_blocked() {
         match event {
@@ -166,6 +180,39 @@ pub fn update(
     // when a popup (model switcher, file changes, plan review, etc.) is open.
     let skip_popup_interception = event.is_backend_event();
 
+    // Confirmation dialog is open — y/Enter confirms, n/Esc keeps things as they are
+    if let Some(action) = state.confirm_state.pending.clone()
+        && !skip_popup_interception
+    {
+        match event {
+            InputEvent::HandleEsc | InputEvent::InputChanged('n') => {
+                state.confirm_state.pending = None;
+                return;
+            }
+            InputEvent::InputSubmitted | InputEvent::InputChanged('y') => {
+                state.confirm_state.pending = None;
+                match action {
+                    ConfirmAction::CancelRun => {
+                        dialog::cancel_or_reject(state, input_tx, output_tx, cancel_tx);
+                    }
+                    ConfirmAction::ClearHistory => {
+                        crate::services::helper_block::push_clear_message(state);
+                    }
+                    ConfirmAction::DismissAskUser { .. } => {
+                        ask_user::handle_ask_user_cancel(state, output_tx);
+                    }
+                }
+                return;
+            }
+            InputEvent::AttemptQuit | InputEvent::Quit => {
+                // Allow quit through
+            }
+            _ => {
+                return; // Consume everything else
+            }
+        }
+    }
+
     // Intercept keys for Message Action Popup
     if state.message_interaction_state.show_message_action_popup && !skip_popup_interception {
         match event {
@@ -200,6 +247,27 @@ pub fn update(
         }
     }
 
+    // Intercept keys for the prompt history search
+    if state.input_history_state.is_searching && !skip_popup_interception {
+        match event {
+            InputEvent::HandleEsc => input_history::close_search(state),
+            InputEvent::Up | InputEvent::ScrollUp => input_history::search_navigate(state, -1),
+            InputEvent::Down | InputEvent::ScrollDown | InputEvent::ShowHistorySearch => {
+                input_history::search_navigate(state, 1)
+            }
+            InputEvent::InputSubmitted => input_history::accept_search(state),
+            InputEvent::InputChanged(c) => input_history::search_input(state, c),
+            InputEvent::InputBackspace => input_history::search_backspace(state),
+            InputEvent::HandlePaste(text) => {
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    input_history::search_input(state, c);
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+
     // Intercept keys for Auto-Approve Popup
     if state.tool_approval_popup_state.is_visible && !skip_popup_interception {
         match event {
@@ -340,6 +408,11 @@ pub fn update(
                     crate::services::plan_review::submit_comment(state);
                     return;
                 }
+                InputEvent::ComposeInEditor => {
+                    state.side_panel_state.pending_compose =
+                        Some(crate::services::editor::ComposeTarget::PlanComment);
+                    return;
+                }
                 InputEvent::AttemptQuit | InputEvent::Quit => {
                     // Allow quit through
                 }
@@ -367,8 +440,42 @@ pub fn update(
                     return; // Consume everything else
                 }
             }
+        } else if state.plan_review_state.search_editing {
+            // Search prompt is open — typing refines the search
+            match event {
+                InputEvent::HandleEsc => {
+                    crate::services::plan_review::cancel_search(state);
+                    return;
+                }
+                InputEvent::InputChanged(c) => {
+                    crate::services::plan_review::search_input_char(state, c);
+                    return;
+                }
+                InputEvent::InputBackspace => {
+                    crate::services::plan_review::search_input_backspace(state);
+                    return;
+                }
+                InputEvent::InputSubmitted | InputEvent::InputChangedNewline => {
+                    crate::services::plan_review::submit_search(state);
+                    return;
+                }
+                InputEvent::AttemptQuit | InputEvent::Quit => {
+                    // Allow quit through
+                }
+                _ => {
+                    return; // Consume everything else
+                }
+            }
         } else {
             match event {
+                InputEvent::HandleEsc if state.plan_review_state.selection_start.is_some() => {
+                    crate::services::plan_review::clear_selection(state);
+                    return;
+                }
+                InputEvent::HandleEsc if !state.plan_review_state.search_query.is_empty() => {
+                    crate::services::plan_review::clear_search(state);
+                    return;
+                }
                 InputEvent::HandleEsc
                 | InputEvent::PlanReviewClose
                 | InputEvent::TogglePlanReview => {
@@ -396,7 +503,7 @@ pub fn update(
                     return;
                 }
                 InputEvent::InputChanged('r') => {
-                    // 'r' key is no longer bound (replies removed)
+                    crate::services::plan_review::toggle_resolve(state);
                     return;
                 }
                 InputEvent::InputChanged('x') => {
@@ -407,6 +514,42 @@ pub fn update(
                     crate::services::plan_review::open_delete_confirm(state);
                     return;
                 }
+                InputEvent::InputChanged('v') => {
+                    crate::services::plan_review::toggle_selection(state);
+                    return;
+                }
+                InputEvent::InputChanged('D') => {
+                    crate::services::plan_review::toggle_diff(state);
+                    return;
+                }
+                InputEvent::InputChanged('/') => {
+                    crate::services::plan_review::open_search(state);
+                    return;
+                }
+                InputEvent::MouseClick(col, row) | InputEvent::MouseDragStart(col, row) => {
+                    crate::services::plan_review::handle_mouse_click(state, col, row);
+                    return;
+                }
+                InputEvent::MouseDrag(col, row) => {
+                    crate::services::plan_review::handle_mouse_drag(state, col, row);
+                    return;
+                }
+                InputEvent::InputChanged('n') => {
+                    crate::services::plan_review::next_match(state);
+                    return;
+                }
+                InputEvent::InputChanged('N') => {
+                    crate::services::plan_review::prev_match(state);
+                    return;
+                }
+                InputEvent::InputChanged(']') => {
+                    crate::services::plan_review::next_hunk(state);
+                    return;
+                }
+                InputEvent::InputChanged('[') => {
+                    crate::services::plan_review::prev_hunk(state);
+                    return;
+                }
                 InputEvent::Tab | InputEvent::PlanReviewNextComment => {
                     crate::services::plan_review::next_comment(state);
                     return;
@@ -428,7 +571,7 @@ pub fn update(
                     return;
                 }
                 InputEvent::PlanReviewResolve => {
-                    // Resolve removed — no-op
+                    crate::services::plan_review::toggle_resolve(state);
                     return;
                 }
                 InputEvent::InputSubmitted
@@ -513,10 +656,16 @@ pub fn update(
     // Mouse wheel (ScrollUp/ScrollDown) scrolls the viewport.
     // ←/→ switch question tabs (except in custom input).
     // Enter selects/toggles. Esc cancels.
+    // 1-9 quick-select the numbered option, a selects all (multi-select).
     if state.ask_user_state.is_visible && !skip_popup_interception {
         match event {
             InputEvent::HandleEsc | InputEvent::AskUserCancel => {
-                ask_user::handle_ask_user_cancel(state, output_tx);
+                let answered = confirm::unsaved_ask_user_answers(state);
+                if answered > 0 {
+                    state.confirm_state.pending = Some(ConfirmAction::DismissAskUser { answered });
+                } else {
+                    ask_user::handle_ask_user_cancel(state, output_tx);
+                }
                 return;
             }
             InputEvent::ShowAskUserPopup(tool_call, questions) => {
@@ -582,6 +731,12 @@ pub fn update(
                 if c == ' ' && !is_custom {
                     // Space toggles/selects the current option (same as AskUserSelectOption)
                     ask_user::handle_ask_user_select_option(state, output_tx);
+                } else if let Some(number) = c.to_digit(10).filter(|_| !is_custom) {
+                    // 1-9 jump to and select/toggle the numbered option
+                    ask_user::handle_ask_user_quick_select(state, number as usize, output_tx);
+                } else if c == 'a' && !is_custom {
+                    // a selects (or clears) all options in multi-select
+                    ask_user::handle_ask_user_select_all(state);
                 } else if is_custom {
                     ask_user::handle_ask_user_custom_input_changed(state, c);
                 }
@@ -622,6 +777,12 @@ pub fn update(
                 return;
             }
 
+            // --- Tab: complete paths typed into a path input ---
+            InputEvent::Tab if ask_user::is_path_input_selected(state) => {
+                ask_user::handle_ask_user_complete_path(state);
+                return;
+            }
+
             // --- Scroll events always pass through (mouse wheel scrolls viewport) ---
             InputEvent::ScrollUp
             | InputEvent::ScrollDown
@@ -752,7 +913,7 @@ pub fn update(
     }
 
     // Intercept keys for Approval Bar (inline approval)
-    // Controls: ←→ navigate, Space toggle, Enter confirm all, Esc reject all
+    // Controls: ←→ navigate, Space toggle, e edit, Enter confirm all, Esc reject all
     // Don't intercept if collapsed messages popup is showing
     if state.dialog_approval_state.approval_bar.is_visible()
         && !state.messages_scrolling_state.show_collapsed_messages
@@ -857,6 +1018,11 @@ pub fn update(
                 tool::handle_approval_bar_toggle_selected(state, input_tx);
                 return;
             }
+            InputEvent::InputChanged('e') | InputEvent::ComposeInEditor => {
+                // e / Ctrl+E: edit the proposed content of a file change
+                tool::request_approval_edit(state);
+                return;
+            }
             InputEvent::CursorLeft => {
                 // Left arrow: select previous tab and update message display
                 tool::handle_approval_bar_prev_action(state, input_tx);
@@ -1077,6 +1243,10 @@ pub fn update(
         InputEvent::InputCursorEnd => {
             input::handle_input_cursor_end(state);
         }
+        InputEvent::ComposeInEditor => {
+            state.side_panel_state.pending_compose =
+                Some(crate::services::editor::ComposeTarget::Input);
+        }
         InputEvent::InputCursorPrevWord => {
             input::handle_input_cursor_prev_word(state);
         }
@@ -1092,10 +1262,17 @@ pub fn update(
 
         // Navigation handlers
         InputEvent::Up => {
-            navigation::handle_up_navigation(state);
+            if !(navigation::arrows_reach_input(state) && input_history::recall_older(state)) {
+                navigation::handle_up_navigation(state);
+            }
         }
         InputEvent::Down => {
-            navigation::handle_down_navigation(state, message_area_height, message_area_width);
+            if !(navigation::arrows_reach_input(state) && input_history::recall_newer(state)) {
+                navigation::handle_down_navigation(state, message_area_height, message_area_width);
+            }
+        }
+        InputEvent::ShowHistorySearch => {
+            input_history::open_search(state);
         }
         InputEvent::ScrollUp => {
             navigation::handle_up_navigation(state);
@@ -1356,6 +1533,24 @@ pub fn update(
         InputEvent::ToggleSidePanel => {
             popup::handle_toggle_side_panel(state, input_tx);
         }
+        InputEvent::TogglePlanPane => {
+            crate::services::plan_pane::toggle(state);
+        }
+        InputEvent::NextTab => {
+            if let Err(e) = crate::services::tabs::cycle(state, 1, output_tx) {
+                crate::services::helper_block::push_error_message(state, &e, None);
+            }
+        }
+        InputEvent::PrevTab => {
+            if let Err(e) = crate::services::tabs::cycle(state, -1, output_tx) {
+                crate::services::helper_block::push_error_message(state, &e, None);
+            }
+        }
+        InputEvent::GoToTab(index) => {
+            if let Err(e) = crate::services::tabs::switch_to(state, index, output_tx) {
+                crate::services::helper_block::push_error_message(state, &e, None);
+            }
+        }
         InputEvent::SidePanelNextSection => {
             popup::handle_side_panel_next_section(state);
         }
@@ -1367,6 +1562,15 @@ pub fn update(
         }
         InputEvent::SetSessionId(session_id) => {
             state.side_panel_state.session_id = session_id;
+            // The resumed history arrives before the session id, so an
+            // autosaved scroll position can be applied now
+            if let Some(lines_from_end) =
+                state.messages_scrolling_state.restore_lines_from_end.take()
+            {
+                state.messages_scrolling_state.stay_at_bottom = false;
+                state.messages_scrolling_state.scroll_lines_from_end = Some(lines_from_end);
+                state.messages_scrolling_state.block_stay_at_bottom_frames = 10;
+            }
         }
 
         // Message handlers
@@ -1389,11 +1593,23 @@ pub fn update(
         InputEvent::TotalUsage(usage) => {
             message::handle_total_usage(state, usage);
         }
+        InputEvent::TranscriptExported(path) => {
+            crate::services::helper_block::push_styled_message(
+                state,
+                &format!(" Transcript saved to {}", path),
+                crate::services::detect_term::ThemeColors::green(),
+                "✓",
+                crate::services::detect_term::ThemeColors::green(),
+            );
+        }
 
         // Misc handlers
         InputEvent::Error(err) => {
             misc::handle_error(state, err);
         }
+        InputEvent::Failure(error) => {
+            misc::handle_failure(state, error);
+        }
         InputEvent::Resized(width, height) => {
             misc::handle_resized(state, width, height);
         }
@@ -1410,7 +1626,7 @@ pub fn update(
             misc::handle_tab(state, message_area_height, message_area_width);
         }
         InputEvent::HandleCtrlS => {
-            misc::handle_ctrl_s(state, input_tx);
+            misc::handle_ctrl_s(state, input_tx, output_tx);
         }
         InputEvent::Quit => {
             // Quit is handled in event loop
@@ -1439,7 +1655,8 @@ pub fn update(
             }
         }
         InputEvent::FileChangesRevertAll => {
-            // Handled in file changes popup context above
+            // Ctrl+Z outside the file changes popup undoes a recent revert to a message
+            confirm::undo_revert(state);
         }
         InputEvent::FileChangesOpenEditor => {
             // Handled in file changes popup context above
@@ -1525,9 +1742,9 @@ pub fn update(
                 push_styled_message(
                     state,
                     " Plan mode activated - what are we working on today?",
-                    ratatui::style::Color::Cyan,
+                    crate::services::detect_term::ThemeColors::cyan(),
                     "⚙ ",
-                    ratatui::style::Color::Cyan,
+                    crate::services::detect_term::ThemeColors::cyan(),
                 );
             }
         }
@@ -1601,11 +1818,19 @@ pub fn update(
         InputEvent::RunningBackgroundTasksCount(count) => {
             state.background_tasks_state.running_background_tasks = count;
         }
+        InputEvent::DiscoveryProgress { completed, total } => {
+            misc::handle_discovery_progress(state, completed, total);
+        }
+        InputEvent::DiscoveryFinished => {
+            misc::handle_discovery_finished(state, output_tx);
+        }
         // Policy persistence modal events are handled in the intercept block above
         InputEvent::ShowApprovalSettingsPersistenceModal
         | InputEvent::ApprovalSettingsPersistenceNavigate(_)
         | InputEvent::ApprovalSettingsPersistenceConfirm
         | InputEvent::ApprovalSettingsPersistenceCancel => {}
+        // Handled before any interception at the top of `update`
+        InputEvent::FocusChanged(_) => {}
     }
 
     flush_pending_user_messages_if_idle(state, input_tx, output_tx);
@@ -1616,7 +1841,7 @@ pub fn update(
 mod tests {
     use super::*;
     use crate::app::{AppStateOptions, LoadingOperation};
-    use crate::services::message::MessageContent;
+    use crate::services::message::{Message, MessageContent};
     use ratatui::layout::Size;
     use stakai::Model;
     use stakpak_shared::models::integrations::openai::{
@@ -1667,6 +1892,20 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn only_typed_errors_get_a_hint() {
+        let mut state = build_state();
+        misc::handle_error(&mut state, "server returned 429: slow down".to_string());
+        assert_eq!(state.messages_scrolling_state.messages.len(), 1);
+
+        let mut state = build_state();
+        misc::handle_failure(
+            &mut state,
+            stakpak_shared::error::StakpakError::rate_limit("slow down"),
+        );
+        assert_eq!(state.messages_scrolling_state.messages.len(), 2);
+    }
+
     #[tokio::test]
     async fn flush_pending_messages_merges_queue_into_single_user_message() {
         let mut state = build_state();
@@ -1718,6 +1957,32 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn discovery_finished_sends_pending_init_prompt_and_ignores_late_progress() {
+        let mut state = build_state();
+        state.configuration_state.init_prompt_content = Some("Analyze this setup".to_string());
+        state.discovery_state.init_prompt_pending = true;
+        let (output_tx, mut output_rx) = mpsc::channel(8);
+
+        misc::handle_discovery_progress(&mut state, 1, 5);
+        assert_eq!(state.discovery_state.progress, Some((1, 5)));
+        assert!(output_rx.try_recv().is_err());
+
+        handle_discovery_finished(&mut state, &output_tx);
+        match output_rx.recv().await {
+            Some(OutputEvent::UserMessage(text, None, image_parts, None)) => {
+                assert_eq!(text, "Analyze this setup");
+                assert!(image_parts.is_empty());
+            }
+            other => panic!("unexpected output event: {:?}", other),
+        }
+
+        misc::handle_discovery_progress(&mut state, 4, 5);
+        assert_eq!(state.discovery_state.progress, None);
+        handle_discovery_finished(&mut state, &output_tx);
+        assert!(output_rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn flush_pending_messages_does_not_run_when_busy() {
         let mut state = build_state();
@@ -1912,6 +2177,7 @@ mod tests {
             ],
             allow_custom: false,
             multi_select: false,
+            input_type: None,
         }];
         let tool_call = ToolCall {
             id: "tc_1".to_string(),
@@ -2000,6 +2266,7 @@ mod tests {
                 }],
                 allow_custom: false,
                 multi_select: false,
+                input_type: None,
             },
             AskUserQuestion {
                 label: "Q2".to_string(),
@@ -2012,6 +2279,7 @@ mod tests {
                 }],
                 allow_custom: false,
                 multi_select: false,
+                input_type: None,
             },
         ];
         let tool_call = ToolCall {
@@ -2089,6 +2357,7 @@ mod tests {
             ],
             allow_custom: false,
             multi_select: false,
+            input_type: None,
         }];
         let tool_call = ToolCall {
             id: "tc_3".to_string(),
@@ -2199,4 +2468,76 @@ mod tests {
             "Down should navigate even when scrolled up"
         );
     }
+
+    #[tokio::test]
+    async fn esc_during_run_asks_before_cancelling() {
+        let mut state = build_state();
+        let (input_tx, _input_rx) = mpsc::channel(8);
+        let (output_tx, _output_rx) = mpsc::channel(8);
+        let (shell_tx, _shell_rx) = mpsc::channel(8);
+        let (cancel_tx, mut cancel_rx) = tokio::sync::broadcast::channel(1);
+        state.loading_state.is_loading = true;
+
+        let send = |state: &mut AppState, event| {
+            update(
+                state,
+                event,
+                10,
+                80,
+                &input_tx,
+                &output_tx,
+                Some(cancel_tx.clone()),
+                &shell_tx,
+                Size::new(80, 24),
+            )
+        };
+
+        send(&mut state, InputEvent::HandleEsc);
+        assert_eq!(state.confirm_state.pending, Some(ConfirmAction::CancelRun));
+        assert!(cancel_rx.try_recv().is_err(), "Esc alone must not cancel");
+
+        send(&mut state, InputEvent::InputChanged('n'));
+        assert!(state.confirm_state.pending.is_none());
+        assert!(cancel_rx.try_recv().is_err(), "n keeps the run going");
+
+        send(&mut state, InputEvent::HandleEsc);
+        send(&mut state, InputEvent::InputSubmitted);
+        assert!(state.confirm_state.pending.is_none());
+        assert!(cancel_rx.try_recv().is_ok(), "Enter confirms the cancel");
+    }
+
+    #[tokio::test]
+    async fn revert_to_message_can_be_undone() {
+        let mut state = build_state();
+        let (input_tx, _input_rx) = mpsc::channel(8);
+        let (output_tx, _output_rx) = mpsc::channel(8);
+        let (shell_tx, _shell_rx) = mpsc::channel(8);
+        state.messages_scrolling_state.messages = vec![
+            Message::user("first", None),
+            Message::user("second", None),
+            Message::info("reply", None),
+        ];
+        state.message_revert_state.user_message_count = 2;
+
+        confirm::stash_revert(&mut state, 1, 2);
+        assert_eq!(state.messages_scrolling_state.messages.len(), 1);
+        assert_eq!(state.message_revert_state.pending_revert_index, Some(2));
+        assert_eq!(state.message_revert_state.user_message_count, 1);
+
+        update(
+            &mut state,
+            InputEvent::FileChangesRevertAll,
+            10,
+            80,
+            &input_tx,
+            &output_tx,
+            None,
+            &shell_tx,
+            Size::new(80, 24),
+        );
+        assert_eq!(state.messages_scrolling_state.messages.len(), 3);
+        assert_eq!(state.message_revert_state.pending_revert_index, None);
+        assert_eq!(state.message_revert_state.user_message_count, 2);
+        assert!(state.message_revert_state.undo.is_none());
+    }
 }
@@ -110,6 +110,17 @@ pub fn handle_dropdown_down(state: &mut AppState) {
 }
 
 /// Handles upward navigation with approval popup check
+/// Whether plain Up/Down belong to the input (prompt history) rather than a
+/// popup, dropdown or focused dialog.
+pub fn arrows_reach_input(state: &AppState) -> bool {
+    !state.profile_switcher_state.show_profile_switcher
+        && !state.shortcuts_panel_state.is_visible
+        && !state.rulebook_switcher_state.show_rulebook_switcher
+        && !state.input_state.show_helper_dropdown
+        && (!state.dialog_approval_state.is_dialog_open
+            || !state.dialog_approval_state.dialog_focused)
+}
+
 pub fn handle_up_navigation(state: &mut AppState) {
     if state.profile_switcher_state.show_profile_switcher {
         if state.profile_switcher_state.selected_index > 0 {
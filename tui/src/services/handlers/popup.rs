@@ -1521,57 +1521,15 @@ pub fn handle_message_action_popup_execute(state: &mut AppState) {
                     .map(|(_, _, _, _, _, user_idx)| *user_idx);
 
                 if let Some(target_idx) = target_user_idx {
-                    // Revert file changes for edits at or after target_idx
-                    // (the clicked message and everything after it)
-                    let revert_result = state
-                        .side_panel_state
-                        .changeset
-                        .revert_from_user_message(target_idx, &state.side_panel_state.session_id);
-
-                    // Find the TUI message index and truncate
-                    if let Some(msg_idx) = state
+                    // Remove the clicked message and everything after it. The
+                    // files are reverted once the undo window closes.
+                    let msg_idx = state
                         .messages_scrolling_state
                         .messages
                         .iter()
                         .position(|m| m.id == target_id)
-                    {
-                        // Truncate messages - remove target message and everything after
-                        state.messages_scrolling_state.messages.truncate(msg_idx);
-                    }
-
-                    // Store pending revert for backend sync
-                    state.message_revert_state.pending_revert_index = Some(target_idx);
-
-                    // Update user_message_count to match the new state
-                    // We removed the clicked message (target_idx) and everything after,
-                    // so we now have (target_idx - 1) user messages remaining
-                    state.message_revert_state.user_message_count = target_idx.saturating_sub(1);
-
-                    // Clear todos
-                    state.side_panel_state.todos.clear();
-
-                    // Invalidate message cache
-                    invalidate_message_lines_cache(state);
-
-                    // Show appropriate toast
-                    match revert_result {
-                        Ok((files_reverted, files_deleted)) => {
-                            let message = if files_reverted > 0 || files_deleted > 0 {
-                                format!(
-                                    "Reverted {} file(s), deleted {} created file(s)",
-                                    files_reverted, files_deleted
-                                )
-                            } else {
-                                "Reverted to message".to_string()
-                            };
-                            state.toast = Some(Toast::success(&message));
-                        }
-                        Err(e) => {
-                            log::warn!("Revert failed: {}", e);
-                            state.toast =
-                                Some(Toast::success("Reverted messages (file revert failed)"));
-                        }
-                    }
+                        .unwrap_or(state.messages_scrolling_state.messages.len());
+                    crate::services::confirm::stash_revert(state, msg_idx, target_idx);
                 } else {
                     state.toast = Some(Toast::error("Could not find message index"));
                 }
@@ -27,6 +27,18 @@ fn is_in_input_area(state: &AppState, col: u16, row: u16) -> bool {
         && row < input_area.y + input_area.height
 }
 
+/// Width of the chat column, left of the side panel (32 chars wide) and the
+/// plan pane when they are shown.
+fn chat_area_width(state: &AppState) -> u16 {
+    let width = state.terminal_ui_state.terminal_size.width;
+    let main_area_width = if state.side_panel_state.is_shown {
+        width.saturating_sub(32 + 1)
+    } else {
+        width
+    };
+    crate::services::plan_pane::chat_width(state, main_area_width)
+}
+
 /// Convert terminal column to content-relative column within the message area.
 /// The message content is rendered at `message_area_x`, so we subtract that offset
 /// to get a 0-based column within the rendered line content.
@@ -84,20 +96,12 @@ pub fn handle_drag_start(state: &mut AppState, col: u16, row: u16) {
         return;
     }
 
-    // Also check if side panel is shown and click is in side panel area
-    if state.side_panel_state.is_shown {
-        // Side panel is on the right, typically 32 chars wide
-        let side_panel_width = 32u16;
-        let main_area_width = state
-            .terminal_ui_state
-            .terminal_size
-            .width
-            .saturating_sub(side_panel_width + 1);
-        if col >= main_area_width {
-            // Click is in side panel, don't start selection
-            state.message_interaction_state.selection = SelectionState::default();
-            return;
-        }
+    // Also check if side panel or plan pane is shown and click is in their area
+    let chat_width = chat_area_width(state);
+    if col >= chat_width {
+        // Click is in side panel or plan pane, don't start selection
+        state.message_interaction_state.selection = SelectionState::default();
+        return;
     }
 
     // Convert screen row to absolute line index (row_in_message_area already calculated above)
@@ -221,18 +225,8 @@ pub fn handle_drag(state: &mut AppState, col: u16, row: u16) {
     // Convert screen row to absolute line index
     let absolute_line = state.messages_scrolling_state.scroll + clamped_row;
 
-    // Clamp col to main area if side panel is visible, then convert to content-relative
-    let clamped_col = if state.side_panel_state.is_shown {
-        let side_panel_width = 32u16;
-        let main_area_width = state
-            .terminal_ui_state
-            .terminal_size
-            .width
-            .saturating_sub(side_panel_width + 1);
-        col.min(main_area_width.saturating_sub(1))
-    } else {
-        col
-    };
+    // Clamp col to the chat area, then convert to content-relative
+    let clamped_col = col.min(chat_area_width(state).saturating_sub(1));
     let rel_col = content_col(state, clamped_col);
 
     state.message_interaction_state.selection.end_line = Some(absolute_line);
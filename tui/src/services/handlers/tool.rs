@@ -6,6 +6,7 @@ use crate::app::{AppState, InputEvent, OutputEvent, ToolCallStatus};
 use crate::services::commands::{CommandAction, CommandContext, execute_command, filter_commands};
 use crate::services::helper_block::push_error_message;
 use crate::services::message::{Message, invalidate_message_lines_cache};
+use crate::services::toast::Toast;
 use stakpak_shared::models::integrations::openai::{
     ProgressType, ToolCall, ToolCallResult, ToolCallResultProgress, ToolCallResultStatus,
     ToolCallStreamInfo,
@@ -987,6 +988,42 @@ pub fn handle_approval_bar_collapse(state: &mut AppState) {
     state.dialog_approval_state.approval_bar.clear();
 }
 
+/// Ask the event loop to open the selected file change in the editor (`e` /
+/// Ctrl+E in the approval bar).
+pub fn request_approval_edit(state: &mut AppState) {
+    let editable = state
+        .dialog_approval_state
+        .approval_bar
+        .selected_action()
+        .is_some_and(|action| action.editable_content().is_some());
+    if editable {
+        state.side_panel_state.pending_compose =
+            Some(crate::services::editor::ComposeTarget::ToolCall);
+    } else {
+        state.toast = Some(Toast::error("Only file edits can be changed"));
+    }
+}
+
+/// Use the content saved in the editor for the selected tool call, so the
+/// edited version is what runs once approved.
+pub fn apply_approval_edit(state: &mut AppState, content: &str) {
+    let Some(edited) = state
+        .dialog_approval_state
+        .approval_bar
+        .edit_selected(content)
+    else {
+        state.toast = Some(Toast::info("No changes"));
+        return;
+    };
+    if let Some(tool_calls) = state.dialog_approval_state.message_tool_calls.as_mut() {
+        for tool_call in tool_calls.iter_mut().filter(|tc| tc.id == edited.id) {
+            *tool_call = edited.clone();
+        }
+    }
+    update_pending_tool_display(state);
+    state.toast = Some(Toast::success("Edit applied, review the diff"));
+}
+
 /// Update the pending tool display in messages area based on selected tab
 fn update_pending_tool_display(state: &mut AppState) {
     // Remove any existing pending tool block
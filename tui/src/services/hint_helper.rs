@@ -52,9 +52,9 @@ pub fn render_hint_or_shortcuts(f: &mut Frame, state: &AppState, area: Rect) {
 
     if state.dialog_approval_state.show_shortcuts && state.input().is_empty() {
         let shortcuts = vec![
-            Line::from("$ shell . / commands . ctrl+s shortcuts"),
+            Line::from("$ shell . / commands . f1 shortcuts"),
             Line::from(format!(
-                "{} shell mode . ↵ submit . ctrl+c quit . ctrl+f profile . ctrl+k rulebooks . ctrl+s shortcuts",
+                "{} shell mode . ↵ submit . ctrl+c quit . ctrl+f profile . ctrl+k rulebooks . f1 shortcuts",
                 SHELL_PROMPT_PREFIX.trim()
             )),
         ];
@@ -87,14 +87,18 @@ pub fn render_hint_or_shortcuts(f: &mut Frame, state: &AppState, area: Rect) {
             };
 
             // Helper text for the right side
-            let helper_text = "$ shell | / commands | ctrl+s shortcuts";
+            let helper_text = "$ shell | / commands | f1 shortcuts";
 
             // Left side: loader if loading, otherwise empty
             let mut left_spans = Vec::new();
             if state.loading_state.is_loading {
                 let spinner_chars = ["▄▀", "▐▌", "▀▄", "▐▌"];
-                let spinner =
-                    spinner_chars[state.loading_state.spinner_frame % spinner_chars.len()];
+                // Accessibility mode shows a static marker instead of animating.
+                let spinner = if crate::services::accessibility::is_enabled() {
+                    "[BUSY]"
+                } else {
+                    spinner_chars[state.loading_state.spinner_frame % spinner_chars.len()]
+                };
                 let spinner_text =
                     if state.loading_state.loading_type == crate::app::LoadingType::Sessions {
                         "Loading sessions..."
@@ -115,6 +119,15 @@ pub fn render_hint_or_shortcuts(f: &mut Frame, state: &AppState, area: Rect) {
                         Style::default().fg(ThemeColors::dark_gray()),
                     ));
                 }
+            } else if let Some((completed, total)) = state.discovery_state.progress {
+                left_spans.push(Span::styled(
+                    format!("Discovering environment {}/{}", completed, total),
+                    Style::default().fg(ThemeColors::orange()),
+                ));
+                left_spans.push(Span::styled(
+                    " - /skip_discovery",
+                    Style::default().fg(ThemeColors::dark_gray()),
+                ));
             }
 
             // Right side: helper text (always on right), plus profile info if side panel hidden
@@ -0,0 +1,476 @@
+//! Prompt history shared across sessions.
+//!
+//! Every prompt sent to the agent is appended to `~/.stakpak/history`, one
+//! JSON string per line so multi-line prompts survive. With an empty input,
+//! `Up` / `Down` step through past prompts; `Ctrl+R` opens a fuzzy search
+//! over all of them, seeded with whatever is already typed.
+//!
+//! Secrets are redacted before a prompt is written, and prompts longer than
+//! [`MAX_ENTRY_LEN`] (usually large pastes) are not recorded.
+
+use nucleo_matcher::{
+    Matcher, Utf32Str,
+    pattern::{AtomKind, CaseMatching, Normalization, Pattern},
+};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::app::AppState;
+use crate::services::detect_term::ThemeColors;
+
+/// Prompts kept in memory and on disk
+const MAX_ENTRIES: usize = 1000;
+/// Longest prompt, in bytes, that is recorded
+pub const MAX_ENTRY_LEN: usize = 8 * 1024;
+/// Rows of matches shown in the search popup
+const VISIBLE_ROWS: usize = 10;
+
+/// Past prompts, oldest first, and the file they persist to.
+#[derive(Debug, Default)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    /// None keeps history in memory only (tests, unwritable home)
+    path: Option<PathBuf>,
+    /// Index of the entry shown in the input while stepping with Up/Down
+    browsing: Option<usize>,
+}
+
+impl InputHistory {
+    /// `~/.stakpak/history`
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".stakpak").join("history"))
+    }
+
+    /// Read the history file, keeping the newest copy of each prompt. A file
+    /// that grew past [`MAX_ENTRIES`] is rewritten without the oldest lines.
+    pub fn load(path: PathBuf) -> Self {
+        let lines: Vec<String> = std::fs::read_to_string(&path)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<String>(line).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let line_count = lines.len();
+
+        let mut history = Self {
+            entries: Vec::new(),
+            path: Some(path),
+            browsing: None,
+        };
+        for entry in lines {
+            history.insert(entry);
+        }
+        if line_count > MAX_ENTRIES
+            && let Err(e) = history.rewrite()
+        {
+            log::warn!("Failed to compact prompt history: {}", e);
+        }
+        history
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Add a prompt as the newest entry and append it to the history file.
+    pub fn push(&mut self, prompt: &str) {
+        self.browsing = None;
+        if prompt.trim().is_empty() || prompt.len() > MAX_ENTRY_LEN {
+            return;
+        }
+        if self.entries.last().map(String::as_str) == Some(prompt) {
+            return;
+        }
+        self.insert(prompt.to_string());
+        if let Err(e) = self.append(prompt) {
+            log::warn!("Failed to save prompt history: {}", e);
+        }
+    }
+
+    fn insert(&mut self, entry: String) {
+        self.entries.retain(|existing| *existing != entry);
+        self.entries.push(entry);
+        if self.entries.len() > MAX_ENTRIES {
+            let excess = self.entries.len() - MAX_ENTRIES;
+            self.entries.drain(..excess);
+        }
+    }
+
+    fn append(&self, prompt: &str) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut file = open_private(path, false)?;
+        writeln!(file, "{}", serde_json::to_string(prompt)?)
+    }
+
+    fn rewrite(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut content = String::new();
+        for entry in &self.entries {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        open_private(path, true)?.write_all(content.as_bytes())
+    }
+
+    /// Entry before the one being shown, given the current input. Starts from
+    /// the newest entry when the input is empty; any other text means the
+    /// user is editing, so there is nothing to recall.
+    pub fn older(&mut self, input: &str) -> Option<&str> {
+        let next = match self.browsing {
+            Some(index) if self.entries.get(index).map(String::as_str) == Some(input) => {
+                index.checked_sub(1)?
+            }
+            _ if input.is_empty() => self.entries.len().checked_sub(1)?,
+            _ => return None,
+        };
+        self.browsing = Some(next);
+        self.entries.get(next).map(String::as_str)
+    }
+
+    /// Entry after the one being shown; `Some("")` when stepping past the
+    /// newest one, None when not browsing.
+    pub fn newer(&mut self, input: &str) -> Option<&str> {
+        let index = self.browsing?;
+        if self.entries.get(index).map(String::as_str) != Some(input) {
+            self.browsing = None;
+            return None;
+        }
+        match self.entries.get(index + 1) {
+            Some(entry) => {
+                self.browsing = Some(index + 1);
+                Some(entry)
+            }
+            None => {
+                self.browsing = None;
+                Some("")
+            }
+        }
+    }
+
+    /// Indices of entries matching `query`, best match first; newest first
+    /// for an empty query and among equal scores.
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        let newest_first = (0..self.entries.len()).rev();
+        if query.trim().is_empty() {
+            return newest_first.collect();
+        }
+        let pattern = Pattern::new(
+            query,
+            CaseMatching::Smart,
+            Normalization::Smart,
+            AtomKind::Fuzzy,
+        );
+        let mut matcher = Matcher::new(nucleo_matcher::Config::DEFAULT);
+        let mut buf = Vec::new();
+        let mut scored: Vec<(u32, usize)> = newest_first
+            .filter_map(|index| {
+                let haystack = Utf32Str::new(&self.entries[index], &mut buf);
+                pattern
+                    .score(haystack, &mut matcher)
+                    .map(|score| (score, index))
+            })
+            .collect();
+        // Stable sort keeps newer entries ahead on ties
+        scored.sort_by(|a, b| b.0.cmp(&a.0));
+        scored.into_iter().map(|(_, index)| index).collect()
+    }
+}
+
+fn open_private(path: &Path, truncate: bool) -> std::io::Result<std::fs::File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    if truncate {
+        options.write(true).truncate(true);
+    } else {
+        options.append(true);
+    }
+    options.create(true);
+    // Prompts can mention hosts and paths, so keep the file owner-only
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Record a submitted prompt, redacting secrets even when session redaction
+/// is off.
+pub fn record(state: &mut AppState, prompt: &str) {
+    let redacted = stakpak_shared::secrets::redact_secrets(prompt, None, &HashMap::new(), false)
+        .redacted_string;
+    state.input_history_state.history.push(&redacted);
+}
+
+fn show_in_input(state: &mut AppState, text: &str) {
+    state.input_state.text_area.set_text(text);
+    state.input_state.text_area.set_cursor(text.len());
+}
+
+/// Put the previous prompt in the input. Returns false when there is nothing
+/// to recall, so `Up` can scroll the messages instead.
+pub fn recall_older(state: &mut AppState) -> bool {
+    let input = state.input().to_string();
+    let Some(entry) = state.input_history_state.history.older(&input) else {
+        return false;
+    };
+    let entry = entry.to_string();
+    show_in_input(state, &entry);
+    true
+}
+
+/// Put the next prompt (or an empty input) in the input. Returns false when
+/// not browsing history.
+pub fn recall_newer(state: &mut AppState) -> bool {
+    let input = state.input().to_string();
+    let Some(entry) = state.input_history_state.history.newer(&input) else {
+        return false;
+    };
+    let entry = entry.to_string();
+    show_in_input(state, &entry);
+    true
+}
+
+/// Open the `Ctrl+R` search, seeded with the current input.
+pub fn open_search(state: &mut AppState) {
+    let query = state.input().to_string();
+    let search = &mut state.input_history_state;
+    search.is_searching = true;
+    search.query = query;
+    refresh_matches(state);
+}
+
+fn refresh_matches(state: &mut AppState) {
+    let search = &mut state.input_history_state;
+    search.matches = search.history.search(&search.query);
+    search.selected = 0;
+}
+
+pub fn close_search(state: &mut AppState) {
+    let search = &mut state.input_history_state;
+    search.is_searching = false;
+    search.query.clear();
+    search.matches.clear();
+    search.selected = 0;
+}
+
+pub fn search_input(state: &mut AppState, c: char) {
+    state.input_history_state.query.push(c);
+    refresh_matches(state);
+}
+
+pub fn search_backspace(state: &mut AppState) {
+    state.input_history_state.query.pop();
+    refresh_matches(state);
+}
+
+/// Move the selection; positive steps go to older matches.
+pub fn search_navigate(state: &mut AppState, step: isize) {
+    let search = &mut state.input_history_state;
+    if search.matches.is_empty() {
+        return;
+    }
+    let len = search.matches.len() as isize;
+    search.selected = (search.selected as isize + step).rem_euclid(len) as usize;
+}
+
+/// Replace the input with the selected prompt and close the search.
+pub fn accept_search(state: &mut AppState) {
+    let search = &state.input_history_state;
+    let entry = search
+        .matches
+        .get(search.selected)
+        .and_then(|&index| search.history.entries().get(index))
+        .cloned();
+    close_search(state);
+    if let Some(entry) = entry {
+        show_in_input(state, &entry);
+    }
+}
+
+/// One-line preview of a prompt: whitespace collapsed, cut to `max` chars.
+fn preview(entry: &str, max: usize) -> String {
+    let flat = entry.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= max {
+        flat
+    } else {
+        let mut out: String = flat.chars().take(max.saturating_sub(1)).collect();
+        out.push('…');
+        out
+    }
+}
+
+pub fn render_history_search(f: &mut Frame, state: &AppState) {
+    let search = &state.input_history_state;
+    let terminal_area = f.area();
+    let width = (terminal_area.width * 80 / 100)
+        .max(60)
+        .min(terminal_area.width);
+    // borders(2) + title(1) + query(1) + spacing(1) + rows + footer(1)
+    let height = ((VISIBLE_ROWS + 6) as u16).min(terminal_area.height);
+    let area = Rect::new(
+        terminal_area.width.saturating_sub(width) / 2,
+        terminal_area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    );
+
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(ThemeColors::cyan()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let row_width = (inner.width as usize).saturating_sub(4);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            " Prompt History",
+            Style::default()
+                .fg(ThemeColors::yellow())
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(vec![
+            Span::raw(" "),
+            Span::styled(">", Style::default().fg(ThemeColors::magenta())),
+            Span::raw(" "),
+            Span::raw(search.query.clone()),
+            Span::styled("|", Style::default().fg(ThemeColors::cyan())),
+        ]),
+        Line::from(""),
+    ];
+
+    if search.matches.is_empty() {
+        let message = if search.history.entries().is_empty() {
+            "  No prompts yet"
+        } else {
+            "  No matching prompts"
+        };
+        lines.push(Line::from(Span::styled(
+            message,
+            Style::default().fg(ThemeColors::dark_gray()),
+        )));
+    }
+
+    // Keep the selection in view
+    let first = search.selected.saturating_sub(VISIBLE_ROWS - 1);
+    for (offset, &index) in search
+        .matches
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(VISIBLE_ROWS)
+    {
+        let Some(entry) = search.history.entries().get(index) else {
+            continue;
+        };
+        let text = preview(entry, row_width);
+        if offset == search.selected {
+            let padding = row_width.saturating_sub(text.chars().count());
+            lines.push(Line::from(Span::styled(
+                format!("  {}{}  ", text, " ".repeat(padding)),
+                Style::default()
+                    .bg(ThemeColors::highlight_bg())
+                    .fg(ThemeColors::highlight_fg()),
+            )));
+        } else {
+            lines.push(Line::from(format!("  {}", text)));
+        }
+    }
+
+    let footer_row = inner.height.saturating_sub(1) as usize;
+    while lines.len() < footer_row {
+        lines.push(Line::from(""));
+    }
+    lines.truncate(footer_row);
+    lines.push(Line::from(Span::styled(
+        format!(
+            " {}/{}  ↑/↓ or ctrl+r select · enter insert · esc cancel",
+            search.matches.len(),
+            search.history.entries().len()
+        ),
+        Style::default().fg(ThemeColors::dark_gray()),
+    )));
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(entries: &[&str]) -> InputHistory {
+        let mut history = InputHistory::default();
+        for entry in entries {
+            history.push(entry);
+        }
+        history
+    }
+
+    #[test]
+    fn test_up_down_steps_through_prompts_from_empty_input() {
+        let mut history = history(&["deploy staging", "check logs", "deploy staging"]);
+        // The repeat moved to the end instead of being stored twice
+        assert_eq!(history.entries(), ["check logs", "deploy staging"]);
+
+        assert_eq!(history.older("draft"), None);
+        assert_eq!(history.older(""), Some("deploy staging"));
+        assert_eq!(history.older("deploy staging"), Some("check logs"));
+        assert_eq!(history.older("check logs"), None);
+        assert_eq!(history.newer("check logs"), Some("deploy staging"));
+        assert_eq!(history.newer("deploy staging"), Some(""));
+        assert_eq!(history.newer(""), None);
+
+        // Editing a recalled prompt stops browsing
+        assert_eq!(history.older(""), Some("deploy staging"));
+        assert_eq!(history.newer("deploy staging now"), None);
+    }
+
+    #[test]
+    fn test_search_ranks_fuzzy_matches_newest_first_on_ties() {
+        let history = history(&[
+            "restart nginx",
+            "deploy api",
+            "tail nginx logs",
+            "restart api",
+        ]);
+        let found: Vec<&str> = history
+            .search("rst")
+            .into_iter()
+            .map(|index| history.entries()[index].as_str())
+            .collect();
+        assert_eq!(found, ["restart api", "restart nginx"]);
+        assert_eq!(history.search("").first(), Some(&3));
+        assert!(history.search("kubectl").is_empty());
+    }
+
+    #[test]
+    fn test_history_file_round_trip_keeps_multiline_prompts() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join(".stakpak").join("history");
+
+        let mut history = InputHistory::load(path.clone());
+        history.push("first\nsecond line");
+        history.push("check logs");
+        history.push(&"x".repeat(MAX_ENTRY_LEN + 1));
+
+        let reloaded = InputHistory::load(path);
+        assert_eq!(reloaded.entries(), ["first\nsecond line", "check logs"]);
+    }
+}
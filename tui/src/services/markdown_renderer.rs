@@ -100,8 +100,11 @@ impl MarkdownStyle {
     pub fn adaptive() -> Self {
         let is_light = crate::services::detect_term::is_light_mode();
         let is_rgb_supported = crate::services::detect_term::should_use_rgb_colors();
+        let scheme = crate::services::theme::active();
 
-        if is_light {
+        let style = if scheme.name == crate::services::theme::SchemeName::HighContrast {
+            Self::high_contrast_theme()
+        } else if is_light {
             // Light theme with dark colors for good contrast
             Self::light_theme()
         } else if is_rgb_supported {
@@ -110,7 +113,53 @@ impl MarkdownStyle {
         } else {
             // Use high-contrast colors for unsupported terminals (works on both light and dark)
             Self::high_contrast_theme()
-        }
+        };
+        style.with_scheme_colors(|role| scheme.color(role, is_light))
+    }
+
+    /// Recolor headings, links, callouts and body text with the color
+    /// scheme's roles. Elements whose role the scheme leaves to the default
+    /// palette keep their color.
+    fn with_scheme_colors(
+        mut self,
+        color: impl Fn(crate::services::theme::ColorRole) -> Option<Color>,
+    ) -> Self {
+        use crate::services::theme::ColorRole;
+
+        let recolor = |style: &mut Style, role: ColorRole| {
+            if let Some(fg) = color(role) {
+                *style = style.fg(fg);
+            }
+        };
+        recolor(&mut self.h1_style, ColorRole::AccentSecondary);
+        recolor(&mut self.h2_style, ColorRole::Accent);
+        recolor(&mut self.h3_style, ColorRole::Success);
+        recolor(&mut self.h4_style, ColorRole::Magenta);
+        recolor(&mut self.h5_style, ColorRole::Warning);
+        recolor(&mut self.h6_style, ColorRole::Danger);
+        recolor(&mut self.code_style, ColorRole::Orange);
+        recolor(&mut self.link_style, ColorRole::AccentSecondary);
+        recolor(&mut self.quote_style, ColorRole::Muted);
+        recolor(&mut self.list_bullet_style, ColorRole::Muted);
+        recolor(&mut self.task_open_style, ColorRole::Warning);
+        recolor(&mut self.task_complete_style, ColorRole::Success);
+        recolor(&mut self.important_style, ColorRole::Danger);
+        recolor(&mut self.note_style, ColorRole::AccentSecondary);
+        recolor(&mut self.tip_style, ColorRole::Success);
+        recolor(&mut self.warning_style, ColorRole::Warning);
+        recolor(&mut self.caution_style, ColorRole::Danger);
+        recolor(&mut self.text_style, ColorRole::Text);
+        recolor(&mut self.italic_style, ColorRole::Text);
+        recolor(&mut self.table_cell_style, ColorRole::Text);
+        recolor(&mut self.bold_style, ColorRole::TitlePrimary);
+        recolor(&mut self.bold_italic_style, ColorRole::TitlePrimary);
+        recolor(&mut self.table_header_style, ColorRole::TitlePrimary);
+        recolor(&mut self.separator_style, ColorRole::Border);
+        if let Some(bg) = color(ColorRole::CodeBg) {
+            self.code_style = self.code_style.bg(bg);
+            self.code_block_style = self.code_block_style.bg(bg);
+        }
+        self
     }
 
     /// Light theme optimized for light terminal backgrounds
@@ -1289,7 +1338,7 @@ impl MarkdownRenderer {
                 let lines: Vec<&str> = content.lines().take(200).collect();
 
                 // Try to use syntax highlighting if available
-                if let Ok(highlighted_lines) = self.try_syntax_highlighting(&content, &language) {
+                if let Ok(highlighted_lines) = self.try_syntax_highlighting(&lines, &language) {
                     code_lines.extend(highlighted_lines);
                 } else {
                     // Fallback to simple styling
@@ -1707,42 +1756,18 @@ impl MarkdownRenderer {
         result_lines
     }
 
-    // Try to apply syntax highlighting if the feature is available
+    // Highlight a fenced code block for its language tag
     fn try_syntax_highlighting(
         &self,
-        content: &str,
+        lines: &[&str],
         language: &Option<String>,
     ) -> Result<Vec<Line<'static>>, Box<dyn std::error::Error>> {
-        // This is a placeholder - you can implement syntax highlighting here
-        // For now, we'll use a simple fallback
-
-        // If you have the syntax_highlighter module, uncomment this:
-        let extension = language
-            .as_ref()
-            .map(|lang| match lang.to_lowercase().as_str() {
-                "rust" | "rs" => "rs",
-                "javascript" | "js" => "js",
-                "typescript" | "ts" => "ts",
-                "python" | "py" => "py",
-                "php" => "php",
-                "bash" | "sh" | "shell" => "sh",
-                "go" => "go",
-                "java" => "java",
-                "cpp" | "c++" => "cpp",
-                "c" => "c",
-                "html" => "html",
-                "css" => "css",
-                "sql" => "sql",
-                "yaml" | "yml" => "yml",
-                "toml" => "toml",
-                "json" => "json",
-                "markdown" | "md" => "md",
-                _ => "txt",
-            });
-
-        Ok(syntax_highlighter::apply_syntax_highlighting(
-            content, extension,
-        ))
+        Ok(
+            syntax_highlighter::highlight_code_lines(lines, language.as_deref())
+                .into_iter()
+                .map(Line::from)
+                .collect(),
+        )
     }
 }
 
@@ -1913,6 +1938,33 @@ mod tests {
         assert!(matches!(style.code_style.fg, Some(_)));
     }
 
+    #[test]
+    fn test_scheme_colors_recolor_only_their_roles() {
+        use crate::services::theme::{ColorRole, ColorScheme, ThemeConfig};
+
+        let scheme = ColorScheme::from_config(&ThemeConfig {
+            scheme: Some("custom".to_string()),
+            colors: [("accent".to_string(), "#112233".to_string())].into(),
+        })
+        .expect("valid theme");
+        let base = MarkdownStyle::dark_theme();
+        let style =
+            MarkdownStyle::dark_theme().with_scheme_colors(|role| scheme.color(role, false));
+        assert_eq!(style.h2_style.fg, Some(Color::Rgb(0x11, 0x22, 0x33)));
+        assert!(style.h2_style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(style.h1_style, base.h1_style);
+        assert_eq!(style.code_block_style, base.code_block_style);
+
+        let solarized = ColorScheme::new(crate::services::theme::SchemeName::Solarized);
+        let style =
+            MarkdownStyle::dark_theme().with_scheme_colors(|role| solarized.color(role, false));
+        assert_eq!(style.h6_style.fg, solarized.color(ColorRole::Danger, false));
+        assert_eq!(
+            style.code_block_style.bg,
+            solarized.color(ColorRole::CodeBg, false)
+        );
+    }
+
     #[test]
     fn test_dark_theme_creation() {
         // Test that dark theme can be created
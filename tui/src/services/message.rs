@@ -17,6 +17,7 @@ use stakpak_shared::models::integrations::openai::FunctionCall;
 use stakpak_shared::models::integrations::openai::{
     ToolCall, ToolCallResult, ToolCallResultStatus, ToolCallStreamInfo,
 };
+use stakpak_shared::secrets::redaction::redact_output;
 use stakpak_shared::utils::strip_tool_name;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
@@ -1290,9 +1291,85 @@ pub fn get_wrapped_message_lines_cached(state: &mut AppState, width: usize) -> V
 
 /// Render a single message to lines.
 /// This is extracted to allow per-message caching.
+fn redact_tool_call(tool_call: &ToolCall) -> ToolCall {
+    let mut tool_call = tool_call.clone();
+    tool_call.function.arguments = redact_output(&tool_call.function.arguments);
+    tool_call
+}
+
+fn redact_tool_call_result(result: &ToolCallResult) -> ToolCallResult {
+    ToolCallResult {
+        call: redact_tool_call(&result.call),
+        result: redact_output(&result.result),
+        status: result.status.clone(),
+    }
+}
+
+/// `msg` with secrets swapped for redaction placeholders in everything the
+/// renderers show: message text, tool call arguments and tool results.
+fn redact_message(msg: &Message) -> Message {
+    let content = match &msg.content {
+        MessageContent::Plain(text, style) => MessageContent::Plain(redact_output(text), *style),
+        MessageContent::AssistantMD(text, style) => {
+            MessageContent::AssistantMD(redact_output(text), *style)
+        }
+        MessageContent::Markdown(text) => MessageContent::Markdown(redact_output(text)),
+        MessageContent::PlainText(text) => MessageContent::PlainText(redact_output(text)),
+        MessageContent::UserMessage(text) => MessageContent::UserMessage(redact_output(text)),
+        MessageContent::RenderEscapedTextBlock(text) => {
+            MessageContent::RenderEscapedTextBlock(redact_output(text))
+        }
+        MessageContent::RenderPendingBorderBlock(tool_call, auto_approved) => {
+            MessageContent::RenderPendingBorderBlock(redact_tool_call(tool_call), *auto_approved)
+        }
+        MessageContent::RenderPendingBorderBlockWithStallWarning(
+            tool_call,
+            auto_approved,
+            warning,
+        ) => MessageContent::RenderPendingBorderBlockWithStallWarning(
+            redact_tool_call(tool_call),
+            *auto_approved,
+            warning.clone(),
+        ),
+        MessageContent::RenderCollapsedMessage(tool_call) => {
+            MessageContent::RenderCollapsedMessage(redact_tool_call(tool_call))
+        }
+        MessageContent::RenderSubagentResumePendingBlock(tool_call, auto_approved, pause) => {
+            MessageContent::RenderSubagentResumePendingBlock(
+                redact_tool_call(tool_call),
+                *auto_approved,
+                pause.clone(),
+            )
+        }
+        MessageContent::RenderResultBorderBlock(result) => {
+            MessageContent::RenderResultBorderBlock(redact_tool_call_result(result))
+        }
+        MessageContent::RenderCommandCollapsedResult(result) => {
+            MessageContent::RenderCommandCollapsedResult(redact_tool_call_result(result))
+        }
+        MessageContent::RenderFullContentMessage(result) => {
+            MessageContent::RenderFullContentMessage(redact_tool_call_result(result))
+        }
+        MessageContent::RenderRunCommandBlock(command, result, state) => {
+            MessageContent::RenderRunCommandBlock(
+                redact_output(command),
+                result.as_deref().map(redact_output),
+                state.clone(),
+            )
+        }
+        other => other.clone(),
+    };
+    Message {
+        id: msg.id,
+        content,
+        is_collapsed: msg.is_collapsed,
+    }
+}
+
 fn render_single_message(msg: &Message, width: usize) -> Vec<Line<'static>> {
     use crate::services::message_pattern::spans_to_string;
 
+    let msg = &redact_message(msg);
     // Render the message using the internal function
     let raw_lines = render_single_message_internal(msg, width);
 
@@ -1311,7 +1388,7 @@ fn render_single_message(msg: &Message, width: usize) -> Vec<Line<'static>> {
         if line_text.trim() == "SPACING_MARKER" {
             processed.push(Line::from(""));
         } else {
-            processed.push(line);
+            processed.push(crate::services::screen_share::mask_line(line));
         }
     }
 
@@ -1967,6 +2044,7 @@ fn get_wrapped_message_lines_internal(
     let mut checkpoint_id_removed = false;
 
     for msg in filtered_messages {
+        let msg = &redact_message(msg);
         match &msg.content {
             MessageContent::AssistantMD(text, style) => {
                 let mut cleaned = text.to_string();
@@ -2800,6 +2878,42 @@ pub fn get_command_type_name(tool_call: &ToolCall) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_rendered_messages_are_redacted() {
+        let secret = "postgres://app:s3cr3t@db:5432/app";
+        let rendered_text = |content: MessageContent| {
+            let msg = Message {
+                id: Uuid::new_v4(),
+                content,
+                is_collapsed: None,
+            };
+            render_single_message(&msg, 120)
+                .iter()
+                .map(crate::services::message_pattern::spans_to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let tool_call = ToolCall {
+            id: "call_1".to_string(),
+            r#type: "function".to_string(),
+            function: FunctionCall {
+                name: "run_command".to_string(),
+                arguments: serde_json::json!({ "command": format!("psql {secret}") }).to_string(),
+            },
+            metadata: None,
+        };
+
+        for content in [
+            MessageContent::UserMessage(format!("connect to {secret}")),
+            MessageContent::AssistantMD(format!("Use `{secret}`"), Style::default()),
+            MessageContent::RenderPendingBorderBlock(tool_call.clone(), false),
+            MessageContent::RenderCollapsedMessage(tool_call),
+        ] {
+            let text = rendered_text(content);
+            assert!(!text.contains("s3cr3t"), "{text}");
+        }
+    }
+
     #[test]
     fn test_various_formats() {
         // Test cases based on your examples
@@ -1,12 +1,15 @@
+pub mod accessibility;
 pub mod approval_bar;
 pub mod auto_approve;
 pub mod auto_approve_popup;
+pub mod autosave;
 pub mod banner;
 pub mod bash_block;
 pub mod board_tasks;
 pub mod changeset;
 pub mod clipboard_paste;
 pub mod commands;
+pub mod confirm;
 pub mod custom_commands;
 pub mod detect_term;
 pub mod editor;
@@ -18,26 +21,34 @@ pub mod helper_block;
 pub mod helper_dropdown;
 pub mod hint_helper;
 pub mod image_upload;
+pub mod input_history;
 pub mod layout;
 pub mod markdown_renderer;
 pub mod message;
 pub mod message_action_popup;
 pub mod message_pattern;
 pub mod model_switcher;
+pub mod notifications;
 pub mod placeholder_prompts;
 pub mod plan;
 pub mod plan_comments;
+pub mod plan_diff;
+pub mod plan_pane;
 pub mod plan_review;
 pub mod policy_persistence_popup;
 pub mod profile_switcher;
 pub mod rulebook_switcher;
+pub mod screen_share;
+pub mod session_templates;
 pub mod shell_mode;
 pub mod shell_popup;
 pub mod shortcuts_popup;
 pub mod side_panel;
 pub mod syntax_highlighter;
+pub mod tabs;
 pub mod text_selection;
 pub mod textarea;
+pub mod theme;
 pub mod toast;
 pub mod todo_extractor;
 pub mod update;
@@ -0,0 +1,394 @@
+//! Attention notifications for long-running sessions.
+//!
+//! When the agent stops to wait for the user — a tool needs approval, a
+//! subagent paused, `ask_user` opened a question — or finishes a turn while
+//! the terminal is not focused, the TUI rings the bell and can also raise a
+//! desktop notification:
+//!
+//! ```toml
+//! [settings.notifications]
+//! bell = true        # default true
+//! desktop = true     # default false
+//! method = "auto"    # auto, osc777, notify-send or osascript
+//! ```
+//!
+//! Focus comes from the terminal's focus reporting; terminals that never
+//! report it count as focused, so they only get the existing two-minute
+//! reminder bell. `auto` uses osascript on macOS, notify-send when it is on
+//! `PATH` and the OSC 777 escape (understood by rxvt, foot, WezTerm and
+//! others) everywhere else.
+
+use crate::app::{AppState, NotificationState};
+use serde::{Deserialize, Serialize};
+use stakpak_shared::utils::strip_tool_name;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+/// How long the agent has to stay idle before a finished turn is reported,
+/// so the gap between a tool result and the next request doesn't count
+const FINISHED_SETTLE: Duration = Duration::from_millis(1500);
+
+/// `[settings.notifications]` as written in config.toml.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    /// Ring the terminal bell; defaults to true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bell: Option<bool>,
+    /// Raise a desktop notification; defaults to false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desktop: Option<bool>,
+    /// Desktop notification method; `auto` when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+}
+
+/// How desktop notifications are delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DesktopMethod {
+    Auto,
+    Osc777,
+    NotifySend,
+    Osascript,
+}
+
+impl std::str::FromStr for DesktopMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "osc777" | "osc" => Ok(Self::Osc777),
+            "notify-send" | "notify_send" => Ok(Self::NotifySend),
+            "osascript" => Ok(Self::Osascript),
+            other => Err(format!(
+                "unknown notification method '{}' (expected auto, osc777, notify-send or osascript)",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Settings {
+    bell: bool,
+    desktop: bool,
+    method: DesktopMethod,
+}
+
+static SETTINGS: RwLock<Settings> = RwLock::new(Settings {
+    bell: true,
+    desktop: false,
+    method: DesktopMethod::Auto,
+});
+
+/// Apply the notification settings from config. Called once at startup.
+pub fn init(config: &NotificationConfig) -> Result<(), String> {
+    let method = match config.method.as_deref() {
+        Some(method) => method.parse()?,
+        None => DesktopMethod::Auto,
+    };
+    *SETTINGS.write().unwrap_or_else(PoisonError::into_inner) = Settings {
+        bell: config.bell.unwrap_or(true),
+        desktop: config.desktop.unwrap_or(false),
+        method,
+    };
+    Ok(())
+}
+
+fn settings() -> Settings {
+    *SETTINGS.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// What the session is waiting on, sampled every tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Attention {
+    /// A tool call (or paused subagent) waits for approval
+    pub approval: bool,
+    /// `ask_user` is showing a question
+    pub question: bool,
+    /// The agent is thinking, streaming or running tools
+    pub busy: bool,
+}
+
+impl Attention {
+    pub fn of(state: &AppState) -> Self {
+        Self {
+            approval: state.dialog_approval_state.approval_bar.is_visible()
+                || state.dialog_approval_state.is_dialog_open,
+            question: state.ask_user_state.is_visible,
+            busy: state.loading_state.is_loading || state.tool_call_state.is_streaming,
+        }
+    }
+
+    fn waiting_on_user(self) -> bool {
+        self.approval || self.question
+    }
+}
+
+/// Moments worth telling an absent user about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyEvent {
+    Approval,
+    Question,
+    Finished,
+}
+
+/// Compare `now` with the last sample and report what just happened, if
+/// anything. Prompts are reported when they open; a finished turn once the
+/// agent has stayed idle for [`FINISHED_SETTLE`] with no prompt open.
+pub fn observe(
+    tracker: &mut NotificationState,
+    attention: Attention,
+    now: Instant,
+) -> Option<NotifyEvent> {
+    let last = std::mem::replace(&mut tracker.last, attention);
+
+    if attention.approval && !last.approval {
+        tracker.idle_since = None;
+        return Some(NotifyEvent::Approval);
+    }
+    if attention.question && !last.question {
+        tracker.idle_since = None;
+        return Some(NotifyEvent::Question);
+    }
+
+    if attention.busy || attention.waiting_on_user() {
+        // Work resumed (or a prompt is up), so a later idle is a new turn
+        if attention.busy {
+            tracker.armed = true;
+        }
+        tracker.idle_since = None;
+        return None;
+    }
+
+    if !tracker.armed {
+        return None;
+    }
+    let idle_since = *tracker.idle_since.get_or_insert(now);
+    if now.duration_since(idle_since) >= FINISHED_SETTLE {
+        tracker.armed = false;
+        tracker.idle_since = None;
+        return Some(NotifyEvent::Finished);
+    }
+    None
+}
+
+/// Sample the session and notify an unfocused user. Called from the spinner
+/// tick.
+pub fn tick(state: &mut AppState) {
+    let attention = Attention::of(state);
+    let Some(event) = observe(&mut state.notification_state, attention, Instant::now()) else {
+        return;
+    };
+    if state.terminal_ui_state.is_focused {
+        return;
+    }
+    let (title, body) = describe(state, event);
+    notify(&title, &body);
+}
+
+fn describe(state: &AppState, event: NotifyEvent) -> (String, String) {
+    match event {
+        NotifyEvent::Approval => {
+            let tool = state
+                .dialog_approval_state
+                .approval_bar
+                .actions()
+                .first()
+                .map(|action| strip_tool_name(&action.tool_call.function.name).to_string())
+                .or_else(|| {
+                    state
+                        .dialog_approval_state
+                        .dialog_command
+                        .as_ref()
+                        .map(|tool_call| strip_tool_name(&tool_call.function.name).to_string())
+                });
+            match tool.as_deref() {
+                Some("resume_subagent_task") => (
+                    "Stakpak: subagent paused".to_string(),
+                    "A subagent is waiting for approval".to_string(),
+                ),
+                Some(tool) => (
+                    "Stakpak: approval needed".to_string(),
+                    format!("{} is waiting for approval", tool),
+                ),
+                None => (
+                    "Stakpak: approval needed".to_string(),
+                    "A tool call is waiting for approval".to_string(),
+                ),
+            }
+        }
+        NotifyEvent::Question => (
+            "Stakpak: question".to_string(),
+            state
+                .ask_user_state
+                .questions
+                .first()
+                .map(|question| question.question.clone())
+                .unwrap_or_else(|| "The agent has a question for you".to_string()),
+        ),
+        NotifyEvent::Finished => (
+            "Stakpak: done".to_string(),
+            "The agent finished and is waiting for your next message".to_string(),
+        ),
+    }
+}
+
+/// Ring the bell and raise a desktop notification, as configured.
+pub fn notify(title: &str, body: &str) {
+    let settings = settings();
+    let mut stdout = std::io::stdout();
+    if settings.bell {
+        let _ = stdout.write_all(b"\x07");
+    }
+    if settings.desktop {
+        match resolve_method(settings.method) {
+            DesktopMethod::Osc777 | DesktopMethod::Auto => {
+                let _ = stdout.write_all(osc777(title, body).as_bytes());
+            }
+            DesktopMethod::NotifySend => {
+                spawn_detached(Command::new("notify-send").args(["--", title, body]))
+            }
+            DesktopMethod::Osascript => spawn_detached(
+                Command::new("osascript")
+                    .arg("-e")
+                    .arg(osascript_command(title, body)),
+            ),
+        }
+    }
+    let _ = stdout.flush();
+}
+
+fn resolve_method(method: DesktopMethod) -> DesktopMethod {
+    if method != DesktopMethod::Auto {
+        return method;
+    }
+    if cfg!(target_os = "macos") {
+        DesktopMethod::Osascript
+    } else if on_path("notify-send") {
+        DesktopMethod::NotifySend
+    } else {
+        DesktopMethod::Osc777
+    }
+}
+
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+/// Run a notifier without letting its output reach the TUI, reaping it off
+/// the render thread.
+fn spawn_detached(command: &mut Command) {
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Ok(mut child) = child {
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+    }
+}
+
+/// Drop characters that would end or break the escape sequence early.
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_control())
+        .map(|c| if c == ';' { ',' } else { c })
+        .collect()
+}
+
+fn osc777(title: &str, body: &str) -> String {
+    format!("\x1b]777;notify;{};{}\x07", sanitize(title), sanitize(body))
+}
+
+fn osascript_command(title: &str, body: &str) -> String {
+    let quote = |text: &str| {
+        text.chars()
+            .filter(|c| !c.is_control())
+            .collect::<String>()
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+    };
+    format!(
+        "display notification \"{}\" with title \"{}\"",
+        quote(body),
+        quote(title)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attention(approval: bool, question: bool, busy: bool) -> Attention {
+        Attention {
+            approval,
+            question,
+            busy,
+        }
+    }
+
+    #[test]
+    fn test_observe_reports_prompts_once_and_settled_finish() {
+        let mut tracker = NotificationState::default();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert_eq!(
+            observe(&mut tracker, attention(false, false, true), at(0)),
+            None
+        );
+        assert_eq!(
+            observe(&mut tracker, attention(true, false, false), at(100)),
+            Some(NotifyEvent::Approval)
+        );
+        // Still open: no repeat
+        assert_eq!(
+            observe(&mut tracker, attention(true, false, false), at(200)),
+            None
+        );
+
+        assert_eq!(
+            observe(&mut tracker, attention(false, false, true), at(300)),
+            None
+        );
+        // Idle, but not for long enough yet
+        assert_eq!(
+            observe(&mut tracker, attention(false, false, false), at(400)),
+            None
+        );
+        assert_eq!(
+            observe(&mut tracker, attention(false, false, false), at(2000)),
+            Some(NotifyEvent::Finished)
+        );
+        // Reported once per turn
+        assert_eq!(
+            observe(&mut tracker, attention(false, false, false), at(5000)),
+            None
+        );
+
+        assert_eq!(
+            observe(&mut tracker, attention(false, true, false), at(5100)),
+            Some(NotifyEvent::Question)
+        );
+    }
+
+    #[test]
+    fn test_escape_sequences_cannot_be_broken_by_content() {
+        assert_eq!(
+            osc777("Stakpak", "run\x07; rm\n"),
+            "\x1b]777;notify;Stakpak;run, rm\x07"
+        );
+        assert_eq!(
+            osascript_command("a \"b\"", "c\\d"),
+            "display notification \"c\\\\d\" with title \"a \\\"b\\\"\""
+        );
+        assert!("osc777".parse::<DesktopMethod>().is_ok());
+        assert!("growl".parse::<DesktopMethod>().is_err());
+    }
+}
@@ -13,6 +13,12 @@ use std::path::{Path, PathBuf};
 /// Plan file path relative to session directory.
 pub const PLAN_FILENAME: &str = "plan.md";
 
+/// Directory within the session directory holding reviewed plan revisions.
+pub const PLAN_HISTORY_DIR: &str = "plan_history";
+
+/// Revisions kept in the plan history; older ones are deleted.
+const MAX_PLAN_REVISIONS: usize = 20;
+
 // ─── PlanStatus ──────────────────────────────────────────────────────────────
 
 /// Status of the plan as set in the YAML front matter.
@@ -110,6 +116,37 @@ pub fn extract_plan_body(content: &str) -> &str {
     }
 }
 
+/// Rewrite the `status:` line of the front matter, keeping the rest of the
+/// file as written.
+///
+/// Returns `None` if the content has no valid front matter.
+pub fn set_plan_status(content: &str, status: PlanStatus) -> Option<String> {
+    parse_plan_front_matter(content)?;
+
+    // Front matter starts at the first `---` and the status line is the
+    // only one rewritten; a `---` rule in the body is left alone.
+    let mut in_front_matter = false;
+    let mut replaced = false;
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            if !replaced && line.trim_end() == "---" {
+                in_front_matter = true;
+            } else if in_front_matter && !replaced && line.trim_start().starts_with("status:") {
+                replaced = true;
+                return format!("status: {}", status);
+            }
+            line.to_string()
+        })
+        .collect();
+
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
 // ─── File I/O ────────────────────────────────────────────────────────────────
 
 /// Build the full path to plan.md given a session directory.
@@ -147,7 +184,9 @@ pub fn compute_plan_hash(content: &str) -> String {
 /// Uses the `created` field from YAML front matter for the suffix. Falls back
 /// to file modification time, then current time if neither is available.
 ///
-/// Renames `plan.md` → `plan.<YYYYMMDD_HHMMSS>.md`.
+/// Renames `plan.md` → `plan.<YYYYMMDD_HHMMSS>.md`, and its review comments
+/// and revision history with the same suffix so they don't carry over to
+/// the next plan.
 /// Returns the archive path on success, or `None` if no plan file exists.
 pub fn archive_plan_file(session_dir: &Path) -> Option<PathBuf> {
     let plan_path = plan_file_path(session_dir);
@@ -170,9 +209,87 @@ pub fn archive_plan_file(session_dir: &Path) -> Option<PathBuf> {
 
     let archive_path = session_dir.join(format!("plan.{ts}.md"));
     std::fs::rename(&plan_path, &archive_path).ok()?;
+
+    let comments_path = crate::services::plan_comments::comments_file_path(session_dir);
+    if comments_path.exists() {
+        let _ = std::fs::rename(
+            &comments_path,
+            session_dir.join(format!("plan_comments.{ts}.json")),
+        );
+    }
+    let history_dir = session_dir.join(PLAN_HISTORY_DIR);
+    if history_dir.exists() {
+        let _ = std::fs::rename(
+            &history_dir,
+            session_dir.join(format!("{PLAN_HISTORY_DIR}.{ts}")),
+        );
+    }
     Some(archive_path)
 }
 
+// ─── History ─────────────────────────────────────────────────────────────────
+
+/// Stored revisions, oldest first. Revisions are named `rev_<NNNN>.md`.
+fn plan_revisions(session_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(session_dir.join(PLAN_HISTORY_DIR)) else {
+        return Vec::new();
+    };
+    let mut revisions: Vec<(u32, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let number = revision_number(&entry.path())?;
+            Some((number, entry.path()))
+        })
+        .collect();
+    revisions.sort_by_key(|(number, _)| *number);
+    revisions.into_iter().map(|(_, path)| path).collect()
+}
+
+fn revision_number(path: &Path) -> Option<u32> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix("rev_")?
+        .strip_suffix(".md")?
+        .parse()
+        .ok()
+}
+
+/// Record `content` as the newest plan revision unless it already is.
+///
+/// Called when a plan is opened for review, so the history holds the
+/// revisions the user actually reviewed.
+pub fn record_plan_revision(session_dir: &Path, content: &str) -> std::io::Result<()> {
+    let revisions = plan_revisions(session_dir);
+    if let Some(latest) = revisions.last()
+        && std::fs::read_to_string(latest).is_ok_and(|saved| saved == content)
+    {
+        return Ok(());
+    }
+
+    let next = revisions
+        .last()
+        .and_then(|path| revision_number(path))
+        .map_or(1, |number| number + 1);
+    let history_dir = session_dir.join(PLAN_HISTORY_DIR);
+    std::fs::create_dir_all(&history_dir)?;
+    std::fs::write(history_dir.join(format!("rev_{next:04}.md")), content)?;
+
+    let excess = (revisions.len() + 1).saturating_sub(MAX_PLAN_REVISIONS);
+    for old in revisions.iter().take(excess) {
+        let _ = std::fs::remove_file(old);
+    }
+    Ok(())
+}
+
+/// The newest stored revision that differs from `content`.
+pub fn previous_plan_revision(session_dir: &Path, content: &str) -> Option<String> {
+    plan_revisions(session_dir)
+        .iter()
+        .rev()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .find(|saved| saved != content)
+}
+
 // ─── Tests ───────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -279,6 +396,22 @@ Some content.
         assert!(body.contains("OAuth-based authentication"));
     }
 
+    #[test]
+    fn test_set_plan_status_rewrites_only_the_status_line() {
+        let content = format!("{}\n---\nstatus: not front matter\n", VALID_FRONT_MATTER);
+        let updated = set_plan_status(&content, PlanStatus::Approved).unwrap();
+
+        assert_eq!(
+            parse_plan_front_matter(&updated).unwrap().status,
+            PlanStatus::Approved
+        );
+        assert_eq!(
+            updated,
+            content.replacen("status: pending_review", "status: approved", 1)
+        );
+        assert!(set_plan_status("# No front matter", PlanStatus::Approved).is_none());
+    }
+
     #[test]
     fn test_extract_plan_body_no_front_matter() {
         let content = "# Just content\nHello";
@@ -383,6 +516,54 @@ Some content.
         assert!(!plan_file_exists(tmp.path()));
     }
 
+    #[test]
+    fn test_archive_plan_file_archives_comments() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join(PLAN_FILENAME), VALID_FRONT_MATTER).unwrap();
+        std::fs::write(tmp.path().join("plan_comments.json"), "{}").unwrap();
+        assert!(archive_plan_file(tmp.path()).is_some());
+        assert!(!tmp.path().join("plan_comments.json").exists());
+        assert!(
+            tmp.path()
+                .join("plan_comments.20260207_153000.json")
+                .exists()
+        );
+    }
+
+    #[test]
+    fn test_record_plan_revision_skips_unchanged_and_finds_previous() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(previous_plan_revision(tmp.path(), "v1").is_none());
+
+        record_plan_revision(tmp.path(), "v1").unwrap();
+        record_plan_revision(tmp.path(), "v1").unwrap();
+        assert_eq!(plan_revisions(tmp.path()).len(), 1);
+        assert!(previous_plan_revision(tmp.path(), "v1").is_none());
+
+        record_plan_revision(tmp.path(), "v2").unwrap();
+        assert_eq!(
+            previous_plan_revision(tmp.path(), "v2").as_deref(),
+            Some("v1")
+        );
+        // An unreviewed edit still diffs against the last reviewed revision.
+        assert_eq!(
+            previous_plan_revision(tmp.path(), "v3").as_deref(),
+            Some("v2")
+        );
+        assert!(tmp.path().join("plan_history/rev_0002.md").exists());
+    }
+
+    #[test]
+    fn test_record_plan_revision_keeps_newest_revisions() {
+        let tmp = tempfile::tempdir().unwrap();
+        for rev in 0..MAX_PLAN_REVISIONS + 3 {
+            record_plan_revision(tmp.path(), &format!("v{rev}")).unwrap();
+        }
+        let revisions = plan_revisions(tmp.path());
+        assert_eq!(revisions.len(), MAX_PLAN_REVISIONS);
+        assert!(revisions[0].ends_with("rev_0004.md"));
+    }
+
     #[test]
     fn test_archive_plan_file_no_created_field() {
         let tmp = tempfile::tempdir().unwrap();
@@ -1,11 +1,17 @@
 //! Comment data model for plan review comments.
 //!
-//! Comments are kept in-memory during the review session and discarded
-//! when the review is closed. They are formatted into feedback text
-//! before being sent to the agent.
+//! Comments are saved to `plan_comments.json` next to plan.md on every
+//! change and reloaded when the review is reopened. When the plan has been
+//! revised since, anchors are re-attached to the new text. Comments are
+//! formatted into feedback text before being sent to the agent.
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Name of the comments file within the session directory.
+pub const COMMENTS_FILENAME: &str = "plan_comments.json";
 
 // ─── Types ───────────────────────────────────────────────────────────────────
 
@@ -31,6 +37,50 @@ pub struct CommentAnchor {
     pub anchor_type: AnchorType,
     /// The text content at the anchor point (heading text or line text).
     pub text: String,
+    /// 0-indexed line the anchor pointed at when the comment was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// Headings enclosing the anchor, outermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parent_headings: Vec<String>,
+    /// Every selected line, first to last, when the comment covers a range.
+    /// The first line is `text`; empty for single-line anchors.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub selection: Vec<String>,
+}
+
+impl CommentAnchor {
+    /// Number of plan lines the anchor covers.
+    pub fn line_span(&self) -> usize {
+        self.selection.len().max(1)
+    }
+}
+
+/// Headings enclosing `line_number` in `plan_content`, outermost first.
+///
+/// Stored on new anchors so they can be re-attached by section after the
+/// plan is rewritten.
+pub fn parent_headings(plan_content: &str, line_number: usize) -> Vec<String> {
+    let lines: Vec<&str> = plan_content.lines().collect();
+    heading_context(&lines)
+        .get(line_number)
+        .map(|parents| parents.iter().map(|heading| heading.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// A reply in a comment's thread.
+///
+/// The agent replies by appending to `replies` in the comments file, so
+/// everything but the author and text is optional.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentReply {
+    /// Reply ID, e.g. `cmt_01.1`. Filled in on load when missing.
+    #[serde(default)]
+    pub id: String,
+    pub author: CommentAuthor,
+    pub text: String,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
 }
 
 /// A top-level comment on the plan.
@@ -44,6 +94,9 @@ pub struct PlanComment {
     pub created_at: DateTime<Utc>,
     #[serde(default)]
     pub resolved: bool,
+    /// Thread of replies, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replies: Vec<CommentReply>,
 }
 
 /// Root container for all comments on a plan.
@@ -89,10 +142,44 @@ pub fn add_comment(
         text,
         created_at: Utc::now(),
         resolved: false,
+        replies: Vec::new(),
     });
     id
 }
 
+/// Append a reply to a comment's thread. Returns the reply ID, or `None`
+/// if the comment doesn't exist.
+pub fn add_reply(
+    plan_comments: &mut PlanComments,
+    comment_id: &str,
+    author: CommentAuthor,
+    text: String,
+) -> Option<String> {
+    let comment = plan_comments
+        .comments
+        .iter_mut()
+        .find(|c| c.id == comment_id)?;
+    let id = format!("{}.{}", comment.id, comment.replies.len() + 1);
+    comment.replies.push(CommentReply {
+        id: id.clone(),
+        author,
+        text,
+        created_at: Utc::now(),
+    });
+    Some(id)
+}
+
+/// Give replies added without an ID (by the agent) their thread position.
+pub fn number_replies(plan_comments: &mut PlanComments) {
+    for comment in &mut plan_comments.comments {
+        for (index, reply) in comment.replies.iter_mut().enumerate() {
+            if reply.id.is_empty() {
+                reply.id = format!("{}.{}", comment.id, index + 1);
+            }
+        }
+    }
+}
+
 /// Mark a comment as resolved.
 ///
 /// Returns `true` if the comment was found and updated, `false` otherwise.
@@ -109,11 +196,33 @@ pub fn resolve_comment(plan_comments: &mut PlanComments, comment_id: &str) -> bo
     }
 }
 
+/// Resolve the given comments, or reopen them all if they are all resolved
+/// already. Returns the new resolved state, or `None` if none were found.
+pub fn toggle_resolved(plan_comments: &mut PlanComments, comment_ids: &[String]) -> Option<bool> {
+    let mut targets: Vec<&mut PlanComment> = plan_comments
+        .comments
+        .iter_mut()
+        .filter(|c| comment_ids.contains(&c.id))
+        .collect();
+    if targets.is_empty() {
+        return None;
+    }
+    let resolved = targets.iter().any(|c| !c.resolved);
+    for comment in &mut targets {
+        comment.resolved = resolved;
+    }
+    Some(resolved)
+}
+
 // ─── Anchor Matching ─────────────────────────────────────────────────────────
 
 /// Minimum normalized similarity for a fuzzy match to be accepted.
 const FUZZY_MATCH_THRESHOLD: f64 = 0.7;
 
+/// Share of the fuzzy score taken from the enclosing headings when the
+/// anchor recorded them; the rest comes from the line text itself.
+const HEADING_CONTEXT_WEIGHT: f64 = 0.25;
+
 /// Quality of an anchor-to-line match.
 #[derive(Debug, Clone, PartialEq)]
 pub enum MatchQuality {
@@ -132,6 +241,17 @@ pub struct ResolvedAnchor {
     pub line_number: usize,
     /// How well the anchor matched.
     pub match_quality: MatchQuality,
+    /// Line the comment was created on, when it now resolves somewhere else.
+    pub moved_from: Option<usize>,
+    /// Lines covered starting at `line_number`; more than 1 for ranges.
+    pub span: usize,
+}
+
+impl ResolvedAnchor {
+    /// Whether the anchor covers `line`.
+    pub fn covers(&self, line: usize) -> bool {
+        (self.line_number..self.line_number + self.span).contains(&line)
+    }
 }
 
 /// Compute normalized Levenshtein similarity between two strings.
@@ -176,11 +296,82 @@ pub fn levenshtein_similarity(a: &str, b: &str) -> f64 {
     1.0 - (distance as f64 / max_len as f64)
 }
 
+/// Dice similarity between the word sets of two strings.
+///
+/// Returns a value in `[0.0, 1.0]`. Unlike [`levenshtein_similarity`] it is
+/// insensitive to word order and to words being inserted or removed around
+/// the ones that survived an edit.
+pub fn token_similarity(a: &str, b: &str) -> f64 {
+    let tokens = |text: &str| -> HashSet<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let a = tokens(a);
+    let b = tokens(b);
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(&b).count();
+    2.0 * shared as f64 / (a.len() + b.len()) as f64
+}
+
+/// Similarity of two lines of text: the better of edit-distance and word overlap.
+fn text_similarity(a: &str, b: &str) -> f64 {
+    levenshtein_similarity(a, b).max(token_similarity(a, b))
+}
+
+/// For each line, the headings enclosing it, outermost first.
+///
+/// A heading's own entry lists only headings of a higher level.
+fn heading_context<'a>(lines: &[&'a str]) -> Vec<Vec<&'a str>> {
+    let mut stack: Vec<(usize, &'a str)> = Vec::new();
+    lines
+        .iter()
+        .map(|line| {
+            let trimmed = line.trim();
+            let level = trimmed.chars().take_while(|c| *c == '#').count();
+            if level > 0 {
+                stack.retain(|(parent_level, _)| *parent_level < level);
+            }
+            let parents = stack.iter().map(|(_, heading)| *heading).collect();
+            if level > 0 {
+                stack.push((level, trimmed));
+            }
+            parents
+        })
+        .collect()
+}
+
+/// How well a candidate's enclosing headings match the ones recorded on the anchor.
+fn heading_context_similarity(anchor_parents: &[String], candidate_parents: &[&str]) -> f64 {
+    if anchor_parents.is_empty() {
+        return 1.0;
+    }
+    let total: f64 = anchor_parents
+        .iter()
+        .map(|parent| {
+            candidate_parents
+                .iter()
+                .map(|candidate| text_similarity(parent, candidate))
+                .fold(0.0, f64::max)
+        })
+        .sum();
+    total / anchor_parents.len() as f64
+}
+
 /// Resolve comment anchors to line numbers in the plan content.
 ///
 /// For each comment, tries:
-/// 1. Exact match against candidate lines
-/// 2. Best fuzzy match above [`FUZZY_MATCH_THRESHOLD`]
+/// 1. Exact match against candidate lines; duplicates are disambiguated by
+///    enclosing headings, then by distance from the original line
+/// 2. Best fuzzy match above [`FUZZY_MATCH_THRESHOLD`], scored on the line
+///    text (edit distance or word overlap, whichever is higher) and, when the
+///    anchor recorded them, its enclosing headings
 /// 3. Falls back to Orphaned (line_number = 0)
 ///
 /// Results are sorted by line_number.
@@ -189,11 +380,24 @@ pub fn resolve_anchors(
     comments: &[PlanComment],
 ) -> Vec<(String, ResolvedAnchor)> {
     let lines: Vec<&str> = plan_content.lines().collect();
+    let context = heading_context(&lines);
 
     let mut results: Vec<(String, ResolvedAnchor)> = comments
         .iter()
         .map(|comment| {
             let anchor_text = comment.anchor.text.trim();
+            let parents = &comment.anchor.parent_headings;
+            let original_line = comment.anchor.line;
+            let span = comment.anchor.line_span();
+            let resolved = |line_number: usize, match_quality: MatchQuality| ResolvedAnchor {
+                line_number,
+                match_quality,
+                moved_from: original_line.filter(|line| *line != line_number),
+                span: span.min(lines.len().saturating_sub(line_number)).max(1),
+            };
+            // Closer to where the comment was made wins between equal scores.
+            let distance =
+                |line_number: usize| original_line.map_or(0, |line| line.abs_diff(line_number));
 
             // Determine which lines are candidates based on anchor type
             let candidates: Vec<(usize, &str)> = lines
@@ -213,37 +417,52 @@ pub fn resolve_anchors(
                 .collect();
 
             // 1. Exact match
-            for &(line_num, line) in &candidates {
-                if line.trim() == anchor_text {
-                    return (
-                        comment.id.clone(),
-                        ResolvedAnchor {
-                            line_number: line_num,
-                            match_quality: MatchQuality::Exact,
-                        },
-                    );
-                }
+            let exact = candidates
+                .iter()
+                .filter(|(_, line)| line.trim() == anchor_text)
+                .map(|&(line_num, _)| {
+                    let context_score = heading_context_similarity(parents, &context[line_num]);
+                    (line_num, context_score)
+                })
+                .min_by(|(a_line, a_score), (b_line, b_score)| {
+                    b_score
+                        .total_cmp(a_score)
+                        .then_with(|| distance(*a_line).cmp(&distance(*b_line)))
+                });
+            if let Some((line_num, _)) = exact {
+                return (comment.id.clone(), resolved(line_num, MatchQuality::Exact));
             }
 
             // 2. Fuzzy match — find best above threshold
-            let mut best_score = 0.0_f64;
-            let mut best_line = 0;
+            let mut best: Option<(usize, f64)> = None;
 
             for &(line_num, line) in &candidates {
-                let score = levenshtein_similarity(anchor_text, line.trim());
-                if score > best_score {
-                    best_score = score;
-                    best_line = line_num;
+                let text_score = text_similarity(anchor_text, line.trim());
+                let score = if parents.is_empty() {
+                    text_score
+                } else {
+                    let context_score = heading_context_similarity(parents, &context[line_num]);
+                    (1.0 - HEADING_CONTEXT_WEIGHT) * text_score
+                        + HEADING_CONTEXT_WEIGHT * context_score
+                };
+                let better = match best {
+                    None => true,
+                    Some((best_line, best_score)) => {
+                        score > best_score
+                            || (score == best_score && distance(line_num) < distance(best_line))
+                    }
+                };
+                if better {
+                    best = Some((line_num, score));
                 }
             }
 
-            if best_score >= FUZZY_MATCH_THRESHOLD {
+            if let Some((line_num, score)) = best
+                && score >= FUZZY_MATCH_THRESHOLD
+            {
                 return (
                     comment.id.clone(),
-                    ResolvedAnchor {
-                        line_number: best_line,
-                        match_quality: MatchQuality::Fuzzy(best_score),
-                    },
+                    resolved(line_num, MatchQuality::Fuzzy(score)),
                 );
             }
 
@@ -253,6 +472,8 @@ pub fn resolve_anchors(
                 ResolvedAnchor {
                     line_number: 0,
                     match_quality: MatchQuality::Orphaned,
+                    moved_from: None,
+                    span: 1,
                 },
             )
         })
@@ -262,6 +483,72 @@ pub fn resolve_anchors(
     results
 }
 
+/// Carry comments over to a revised plan.
+///
+/// When `plan_content` no longer matches `plan_hash`, every comment that
+/// still resolves is re-anchored to the text and section of the line it
+/// resolves to, so it keeps matching through later revisions. Orphaned
+/// comments keep their old anchor. Returns `true` if anything changed.
+pub fn sync_to_plan(plan_comments: &mut PlanComments, plan_content: &str) -> bool {
+    let hash = crate::services::plan::compute_plan_hash(plan_content);
+    if plan_comments.plan_hash == hash {
+        return false;
+    }
+
+    let body = crate::services::plan::extract_plan_body(plan_content);
+    let lines: Vec<&str> = body.lines().collect();
+    for (id, resolved) in resolve_anchors(body, &plan_comments.comments) {
+        if resolved.match_quality == MatchQuality::Orphaned {
+            continue;
+        }
+        let Some(text) = lines.get(resolved.line_number) else {
+            continue;
+        };
+        if let Some(comment) = plan_comments.comments.iter_mut().find(|c| c.id == id) {
+            comment.anchor.text = text.trim().to_string();
+            comment.anchor.parent_headings = parent_headings(body, resolved.line_number);
+            if !comment.anchor.selection.is_empty() {
+                comment.anchor.selection = lines
+                    .iter()
+                    .skip(resolved.line_number)
+                    .take(resolved.span)
+                    .map(|line| line.trim().to_string())
+                    .collect();
+            }
+        }
+    }
+    plan_comments.plan_hash = hash;
+    true
+}
+
+// ─── Persistence ─────────────────────────────────────────────────────────────
+
+/// Build the full path to the comments file given a session directory.
+pub fn comments_file_path(session_dir: &Path) -> PathBuf {
+    session_dir.join(COMMENTS_FILENAME)
+}
+
+/// Read saved comments from the session directory.
+///
+/// Returns `None` if the file doesn't exist or can't be parsed.
+pub fn load_comments(session_dir: &Path) -> Option<PlanComments> {
+    let content = std::fs::read_to_string(comments_file_path(session_dir)).ok()?;
+    let mut plan_comments: PlanComments = serde_json::from_str(&content).ok()?;
+    number_replies(&mut plan_comments);
+    Some(plan_comments)
+}
+
+/// Write comments to the session directory through a temporary file, so a
+/// crash mid-write never leaves a truncated file behind.
+pub fn save_comments(session_dir: &Path, plan_comments: &PlanComments) -> std::io::Result<()> {
+    std::fs::create_dir_all(session_dir)?;
+    let json = serde_json::to_string_pretty(plan_comments).map_err(std::io::Error::other)?;
+    let path = comments_file_path(session_dir);
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
+}
+
 // ─── Tests ───────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -273,6 +560,9 @@ mod tests {
         CommentAnchor {
             anchor_type: AnchorType::Heading,
             text: "## Overview".to_string(),
+            line: None,
+            parent_headings: Vec::new(),
+            selection: Vec::new(),
         }
     }
 
@@ -310,6 +600,9 @@ mod tests {
             CommentAnchor {
                 anchor_type: AnchorType::Line,
                 text: "Use RDS PostgreSQL".to_string(),
+                line: None,
+                parent_headings: Vec::new(),
+                selection: Vec::new(),
             },
             CommentAuthor::Agent,
             "Should we consider Aurora?".to_string(),
@@ -371,8 +664,61 @@ mod tests {
         assert_eq!(comment.anchor.text, "## Overview");
     }
 
+    // ── Replies ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_add_reply_threads_under_comment() {
+        let mut pc = sample_plan_comments();
+        let id = add_comment(&mut pc, sample_anchor(), CommentAuthor::User, "Q".into());
+        assert_eq!(
+            add_reply(&mut pc, &id, CommentAuthor::Agent, "A".into()).as_deref(),
+            Some("cmt_01.1")
+        );
+        assert_eq!(
+            add_reply(&mut pc, &id, CommentAuthor::User, "Thanks".into()).as_deref(),
+            Some("cmt_01.2")
+        );
+        assert!(add_reply(&mut pc, "cmt_99", CommentAuthor::Agent, "?".into()).is_none());
+        assert_eq!(pc.comments[0].replies[0].author, CommentAuthor::Agent);
+    }
+
+    #[test]
+    fn test_load_accepts_minimal_agent_replies() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut pc = sample_plan_comments();
+        add_comment(&mut pc, sample_anchor(), CommentAuthor::User, "Q".into());
+        let mut json = serde_json::to_value(&pc).unwrap();
+        json["comments"][0]["replies"] =
+            serde_json::json!([{"author": "agent", "text": "Answered in Step 2."}]);
+        std::fs::write(
+            tmp.path().join(COMMENTS_FILENAME),
+            serde_json::to_string(&json).unwrap(),
+        )
+        .unwrap();
+
+        let loaded = load_comments(tmp.path()).unwrap();
+        let reply = &loaded.comments[0].replies[0];
+        assert_eq!(reply.id, "cmt_01.1");
+        assert_eq!(reply.text, "Answered in Step 2.");
+    }
+
     // ── Resolve ──────────────────────────────────────────────────────────
 
+    #[test]
+    fn test_toggle_resolved_resolves_then_reopens() {
+        let mut pc = sample_plan_comments();
+        let first = add_comment(&mut pc, sample_anchor(), CommentAuthor::User, "A".into());
+        let second = add_comment(&mut pc, sample_anchor(), CommentAuthor::User, "B".into());
+        resolve_comment(&mut pc, &first);
+
+        let ids = vec![first, second];
+        assert_eq!(toggle_resolved(&mut pc, &ids), Some(true));
+        assert!(pc.comments.iter().all(|c| c.resolved));
+        assert_eq!(toggle_resolved(&mut pc, &ids), Some(false));
+        assert!(pc.comments.iter().all(|c| !c.resolved));
+        assert_eq!(toggle_resolved(&mut pc, &["cmt_99".to_string()]), None);
+    }
+
     #[test]
     fn test_resolve_comment() {
         let mut pc = sample_plan_comments();
@@ -429,6 +775,7 @@ mod tests {
                 text: "A".to_string(),
                 created_at: Utc::now(),
                 resolved: false,
+                replies: Vec::new(),
             },
             PlanComment {
                 id: "cmt_05".to_string(),
@@ -437,6 +784,7 @@ mod tests {
                 text: "B".to_string(),
                 created_at: Utc::now(),
                 resolved: false,
+                replies: Vec::new(),
             },
         ];
         assert_eq!(next_comment_id(&comments), "cmt_06");
@@ -489,6 +837,17 @@ mod tests {
         assert!((levenshtein_similarity("  hello  ", "hello") - 1.0).abs() < f64::EPSILON);
     }
 
+    // ── Token Similarity ─────────────────────────────────────────────────
+
+    #[test]
+    fn test_token_similarity_ignores_word_order_and_punctuation() {
+        let score = token_similarity("Use PostgreSQL on RDS.", "On RDS, use PostgreSQL");
+        assert!((score - 1.0).abs() < f64::EPSILON);
+        assert!((token_similarity("", "") - 1.0).abs() < f64::EPSILON);
+        assert_eq!(token_similarity("alpha", ""), 0.0);
+        assert_eq!(token_similarity("alpha beta", "gamma delta"), 0.0);
+    }
+
     // ── Anchor Resolution ────────────────────────────────────────────────
 
     const SAMPLE_PLAN: &str = "\
@@ -517,11 +876,15 @@ Build login, logout, and refresh token endpoints.
             anchor: CommentAnchor {
                 anchor_type,
                 text: text.to_string(),
+                line: None,
+                parent_headings: Vec::new(),
+                selection: Vec::new(),
             },
             author: CommentAuthor::User,
             text: "Some feedback".to_string(),
             created_at: Utc::now(),
             resolved: false,
+            replies: Vec::new(),
         }
     }
 
@@ -643,9 +1006,210 @@ Build login, logout, and refresh token endpoints.
         assert_eq!(resolved[0].1.match_quality, MatchQuality::Orphaned);
     }
 
+    #[test]
+    fn test_parent_headings() {
+        // Line 8 is "Use PostgreSQL on RDS." under "## Step 1: Set up database"
+        assert_eq!(
+            parent_headings(SAMPLE_PLAN, 8),
+            vec![
+                "# Deploy Auth Service".to_string(),
+                "## Step 1: Set up database".to_string()
+            ]
+        );
+        // A heading's parents exclude itself and its siblings
+        assert_eq!(
+            parent_headings(SAMPLE_PLAN, 10),
+            vec!["# Deploy Auth Service".to_string()]
+        );
+        assert!(parent_headings(SAMPLE_PLAN, 0).is_empty());
+        assert!(parent_headings(SAMPLE_PLAN, 999).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_rewritten_line_by_tokens() {
+        // Reworded enough that edit distance alone drops below the threshold
+        let plan = SAMPLE_PLAN.replace("Use PostgreSQL on RDS.", "On RDS we will use PostgreSQL.");
+        let comments = vec![make_comment(
+            "cmt_01",
+            AnchorType::Line,
+            "Use PostgreSQL on RDS.",
+        )];
+        assert!(
+            levenshtein_similarity("Use PostgreSQL on RDS.", "On RDS we will use PostgreSQL.")
+                < FUZZY_MATCH_THRESHOLD
+        );
+
+        let resolved = resolve_anchors(&plan, &comments);
+        assert!(matches!(
+            resolved[0].1.match_quality,
+            MatchQuality::Fuzzy(_)
+        ));
+        assert_eq!(resolved[0].1.line_number, 8);
+    }
+
+    #[test]
+    fn test_resolve_uses_parent_headings_to_pick_section() {
+        let plan = "\
+# Rollout
+
+## Staging
+
+Run the smoke tests.
+
+## Production
+
+Run the smoke tests.
+";
+        let mut comment = make_comment("cmt_01", AnchorType::Line, "Run the smoke tests.");
+        comment.anchor.parent_headings = vec!["# Rollout".to_string(), "## Production".to_string()];
+        let resolved = resolve_anchors(plan, &[comment.clone()]);
+        assert_eq!(resolved[0].1.match_quality, MatchQuality::Exact);
+        assert_eq!(resolved[0].1.line_number, 8);
+
+        // After a rewrite both lines change; the section still decides
+        let rewritten = plan.replace("Run the smoke tests.", "Run all smoke tests.");
+        let resolved = resolve_anchors(&rewritten, &[comment]);
+        assert!(matches!(
+            resolved[0].1.match_quality,
+            MatchQuality::Fuzzy(_)
+        ));
+        assert_eq!(resolved[0].1.line_number, 8);
+    }
+
+    #[test]
+    fn test_resolve_reports_moved_from() {
+        let mut comment = make_comment("cmt_01", AnchorType::Heading, "## Overview");
+        comment.anchor.line = Some(2);
+        let resolved = resolve_anchors(SAMPLE_PLAN, &[comment.clone()]);
+        assert_eq!(resolved[0].1.moved_from, None);
+
+        let plan = SAMPLE_PLAN.replace("## Overview", "## Context\n\nBackground.\n\n## Overview");
+        let resolved = resolve_anchors(&plan, &[comment]);
+        assert_eq!(resolved[0].1.line_number, 6);
+        assert_eq!(resolved[0].1.moved_from, Some(2));
+    }
+
     #[test]
     fn test_resolve_no_comments() {
         let resolved = resolve_anchors(SAMPLE_PLAN, &[]);
         assert!(resolved.is_empty());
     }
+
+    // ── Persistence ──────────────────────────────────────────────────────
+
+    #[test]
+    fn test_save_and_load_comments() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(load_comments(tmp.path()).is_none());
+
+        let mut pc = sample_plan_comments();
+        add_comment(
+            &mut pc,
+            sample_anchor(),
+            CommentAuthor::User,
+            "Keep me".to_string(),
+        );
+        save_comments(tmp.path(), &pc).unwrap();
+
+        let loaded = load_comments(tmp.path()).unwrap();
+        assert_eq!(loaded.plan_hash, pc.plan_hash);
+        assert_eq!(loaded.comments.len(), 1);
+        assert_eq!(loaded.comments[0].text, "Keep me");
+        assert!(!tmp.path().join("plan_comments.json.tmp").exists());
+    }
+
+    #[test]
+    fn test_sync_to_plan_carries_comments_across_revisions() {
+        let mut pc = PlanComments {
+            plan_file: "plan.md".to_string(),
+            plan_hash: compute_plan_hash(SAMPLE_PLAN),
+            comments: vec![
+                make_comment("cmt_01", AnchorType::Line, "Use PostgreSQL on RDS."),
+                make_comment(
+                    "cmt_02",
+                    AnchorType::Line,
+                    "Create users and sessions tables.",
+                ),
+            ],
+        };
+        assert!(!sync_to_plan(&mut pc, SAMPLE_PLAN));
+
+        let first = SAMPLE_PLAN
+            .replace(
+                "Use PostgreSQL on RDS.",
+                "Use PostgreSQL on RDS (Multi-AZ).",
+            )
+            .replace("Create users and sessions tables.", "Store blobs in S3.");
+        assert!(sync_to_plan(&mut pc, &first));
+        assert_eq!(pc.plan_hash, compute_plan_hash(&first));
+        assert_eq!(
+            pc.comments[0].anchor.text,
+            "Use PostgreSQL on RDS (Multi-AZ)."
+        );
+        assert_eq!(
+            pc.comments[0].anchor.parent_headings,
+            vec!["# Deploy Auth Service", "## Step 1: Set up database"]
+        );
+        // Orphaned comments keep their anchor.
+        assert_eq!(
+            pc.comments[1].anchor.text,
+            "Create users and sessions tables."
+        );
+
+        // Too far from the original text, but close to the first revision.
+        let second = first.replace(
+            "Use PostgreSQL on RDS (Multi-AZ).",
+            "Use Aurora PostgreSQL on RDS (Multi-AZ).",
+        );
+        assert!(sync_to_plan(&mut pc, &second));
+        let resolved = resolve_anchors(&second, &pc.comments[..1]);
+        assert_ne!(resolved[0].1.match_quality, MatchQuality::Orphaned);
+        assert_eq!(
+            pc.comments[0].anchor.text,
+            "Use Aurora PostgreSQL on RDS (Multi-AZ)."
+        );
+    }
+
+    #[test]
+    fn test_range_anchor_spans_and_follows_revisions() {
+        let mut comment = make_comment("cmt_01", AnchorType::Heading, "## Step 1: Set up database");
+        comment.anchor.line = Some(6);
+        comment.anchor.selection = vec![
+            "## Step 1: Set up database".to_string(),
+            String::new(),
+            "Use PostgreSQL on RDS.".to_string(),
+        ];
+        let mut pc = PlanComments {
+            plan_file: "plan.md".to_string(),
+            plan_hash: compute_plan_hash(SAMPLE_PLAN),
+            comments: vec![comment],
+        };
+
+        let revised = SAMPLE_PLAN
+            .replace("## Overview", "## Overview\n\nShort summary.")
+            .replace("Use PostgreSQL on RDS.", "Use Aurora PostgreSQL.");
+        let resolved = resolve_anchors(&revised, &pc.comments);
+        let anchor = &resolved[0].1;
+        assert_eq!((anchor.line_number, anchor.span), (8, 3));
+        assert!(anchor.covers(10));
+        assert!(!anchor.covers(11));
+
+        assert!(sync_to_plan(&mut pc, &revised));
+        assert_eq!(
+            pc.comments[0].anchor.selection,
+            vec!["## Step 1: Set up database", "", "Use Aurora PostgreSQL."]
+        );
+    }
+
+    #[test]
+    fn test_range_span_is_clamped_to_plan_end() {
+        let mut comment = make_comment(
+            "cmt_01",
+            AnchorType::Line,
+            "Build login, logout, and refresh token endpoints.",
+        );
+        comment.anchor.selection = vec![comment.anchor.text.clone(), "Deploy.".to_string()];
+        let resolved = resolve_anchors(SAMPLE_PLAN, &[comment]);
+        assert_eq!(resolved[0].1.span, 1);
+    }
 }
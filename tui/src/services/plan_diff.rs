@@ -0,0 +1,173 @@
+//! Line diff between two revisions of a plan, for the plan review diff view.
+//!
+//! The diff is expressed against the current plan's lines so the viewer can
+//! keep its cursor, comments and anchors on the current text: current lines
+//! are flagged as added, and removed lines are attached to the current line
+//! they used to precede.
+
+use similar::{DiffTag, TextDiff};
+use std::collections::BTreeMap;
+
+/// Changes from a previous plan body to the current one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlanDiff {
+    /// Whether each current line was added (or rewritten) in this revision.
+    pub added: Vec<bool>,
+    /// Removed lines, keyed by the current line they preceded. Lines removed
+    /// from the end of the plan are keyed by the current line count.
+    pub removed: BTreeMap<usize, Vec<String>>,
+}
+
+impl PlanDiff {
+    /// Diff two plan bodies (front matter already stripped).
+    pub fn compute(previous: &str, current: &str) -> Self {
+        let old_lines: Vec<&str> = previous.lines().collect();
+        let mut diff = Self {
+            added: vec![false; current.lines().count()],
+            removed: BTreeMap::new(),
+        };
+
+        // A missing final newline would make the last line differ
+        let (previous, current) = (with_final_newline(previous), with_final_newline(current));
+        for op in TextDiff::from_lines(&previous, &current).ops() {
+            let old_range = op.old_range();
+            let new_range = op.new_range();
+            if matches!(op.tag(), DiffTag::Delete | DiffTag::Replace) {
+                diff.removed.entry(new_range.start).or_default().extend(
+                    old_lines
+                        .get(old_range)
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|line| line.to_string()),
+                );
+            }
+            if matches!(op.tag(), DiffTag::Insert | DiffTag::Replace) {
+                for added in diff.added.get_mut(new_range).unwrap_or_default() {
+                    *added = true;
+                }
+            }
+        }
+        diff
+    }
+
+    /// Whether the revisions are identical.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && !self.added.contains(&true)
+    }
+
+    pub fn added_count(&self) -> usize {
+        self.added.iter().filter(|added| **added).count()
+    }
+
+    pub fn removed_count(&self) -> usize {
+        self.removed.values().map(Vec::len).sum()
+    }
+
+    /// Current lines where a run of changes starts, in order.
+    ///
+    /// A hunk starts at its first added line, or at the line its removed
+    /// lines preceded. Removals at the very end map to the last line.
+    pub fn hunk_starts(&self) -> Vec<usize> {
+        let last_line = self.added.len().saturating_sub(1);
+        let mut starts: Vec<usize> = Vec::new();
+        for (line, added) in self.added.iter().enumerate() {
+            let previous_added = line > 0 && self.added.get(line - 1) == Some(&true);
+            if (*added && !previous_added) || self.removed.contains_key(&line) {
+                starts.push(line);
+            }
+        }
+        if self.removed.contains_key(&self.added.len()) {
+            starts.push(last_line);
+        }
+        starts.dedup();
+        starts
+    }
+}
+
+fn with_final_newline(text: &str) -> String {
+    let mut text = text.replace("\r\n", "\n");
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREVIOUS: &str = "\
+# Plan
+
+## Step 1
+
+Use PostgreSQL on RDS.
+
+## Step 2
+
+Build endpoints.
+";
+
+    #[test]
+    fn test_identical_revisions_have_no_changes() {
+        let diff = PlanDiff::compute(PREVIOUS, PREVIOUS);
+        assert!(diff.is_empty());
+        assert!(diff.hunk_starts().is_empty());
+    }
+
+    #[test]
+    fn test_added_and_removed_lines_map_to_current_lines() {
+        let current = "\
+# Plan
+
+## Step 1
+
+Use Aurora PostgreSQL.
+Enable Multi-AZ.
+
+## Step 2
+
+Build endpoints.
+";
+        let diff = PlanDiff::compute(PREVIOUS, current);
+        assert_eq!(
+            diff.added,
+            vec![
+                false, false, false, false, true, true, false, false, false, false
+            ]
+        );
+        assert_eq!(
+            diff.removed.get(&4),
+            Some(&vec!["Use PostgreSQL on RDS.".to_string()])
+        );
+        assert_eq!(diff.added_count(), 2);
+        assert_eq!(diff.removed_count(), 1);
+        assert_eq!(diff.hunk_starts(), vec![4]);
+    }
+
+    #[test]
+    fn test_trailing_removal_starts_hunk_on_last_line() {
+        let current = "\
+# Plan
+
+## Step 1
+
+Use PostgreSQL on RDS.
+";
+        let diff = PlanDiff::compute(PREVIOUS, current);
+        assert_eq!(diff.added_count(), 0);
+        assert_eq!(diff.removed.get(&5).map(Vec::len), Some(4));
+        assert_eq!(diff.hunk_starts(), vec![4]);
+    }
+
+    #[test]
+    fn test_separate_hunks_are_listed_in_order() {
+        let current = PREVIOUS
+            .replace("## Step 1", "## Step 1: Database")
+            .replace("Build endpoints.", "Build login endpoints.");
+        let diff = PlanDiff::compute(PREVIOUS, &current);
+        assert_eq!(diff.hunk_starts(), vec![2, 8]);
+    }
+}
@@ -0,0 +1,367 @@
+//! Split-pane plan sidebar.
+//!
+//! When toggled with `F2` or `/split`, the chat shares the screen with a
+//! right-hand pane listing the task checklist of `.stakpak/session/plan.md`
+//! (`- [ ]`, `- [/]`, `- [x]` items, grouped under their headings). The file
+//! is re-read on every spinner tick while the pane is shown, so statuses the
+//! agent ticks off appear without opening the plan review overlay.
+
+use crate::app::{AppState, PlanPaneState};
+use crate::services::changeset::{TodoItem, TodoItemType, TodoStatus};
+use crate::services::detect_term::ThemeColors;
+use crate::services::todo_extractor::parse_todo_line;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use std::path::Path;
+
+/// Narrowest pane worth drawing; on smaller terminals the chat keeps the
+/// whole width.
+pub const MIN_PANE_WIDTH: u16 = 28;
+
+/// Width the pane takes from a main area `main_width` columns wide, or None
+/// when it is hidden or the chat would get too narrow.
+pub fn pane_width(state: &AppState, main_width: u16) -> Option<u16> {
+    let width = (main_width * 35 / 100).max(MIN_PANE_WIDTH);
+    (state.plan_pane_state.is_shown && main_width >= width + MIN_PANE_WIDTH).then_some(width)
+}
+
+/// Width left for the chat, including the margin before the pane divider.
+pub fn chat_width(state: &AppState, main_width: u16) -> u16 {
+    match pane_width(state, main_width) {
+        Some(width) => main_width.saturating_sub(width + 1),
+        None => main_width,
+    }
+}
+
+/// A row of the task list.
+#[derive(Debug, Clone)]
+pub enum PlanPaneItem {
+    /// Heading of a section that holds tasks
+    Heading(String),
+    Task(TodoItem),
+}
+
+/// Show or hide the pane, loading the plan right away when it opens.
+pub fn toggle(state: &mut AppState) {
+    let pane = &mut state.plan_pane_state;
+    pane.is_shown = !pane.is_shown;
+    if pane.is_shown {
+        pane.content_hash = None;
+        refresh(pane, Path::new(".stakpak/session"));
+    }
+}
+
+/// Re-read `plan.md` when the pane is shown. Returns whether the task list
+/// changed.
+pub fn refresh(pane: &mut PlanPaneState, session_dir: &Path) -> bool {
+    if !pane.is_shown {
+        return false;
+    }
+    let path = crate::services::plan::plan_file_path(session_dir);
+    let Ok(content) = std::fs::read_to_string(path) else {
+        let had_plan = pane.content_hash.is_some();
+        *pane = PlanPaneState {
+            is_shown: true,
+            ..PlanPaneState::default()
+        };
+        return had_plan;
+    };
+
+    let hash = crate::services::plan::compute_plan_hash(&content);
+    if pane.content_hash.as_deref() == Some(&hash) {
+        return false;
+    }
+    pane.content_hash = Some(hash);
+    pane.title = crate::services::plan::parse_plan_front_matter(&content)
+        .map(|meta| meta.title)
+        .filter(|title| !title.is_empty());
+    pane.items = parse_plan_tasks(crate::services::plan::extract_plan_body(&content));
+    true
+}
+
+/// Checklist items of a plan body, each section's heading listed before its
+/// first task. Indented items become checklist entries under the item above.
+pub fn parse_plan_tasks(body: &str) -> Vec<PlanPaneItem> {
+    let mut items = Vec::new();
+    let mut pending_heading: Option<String> = None;
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if let Some(heading) = trimmed.strip_prefix('#') {
+            let heading = heading.trim_start_matches('#').trim();
+            pending_heading = (!heading.is_empty()).then(|| heading.to_string());
+            continue;
+        }
+        let Some(mut task) = parse_todo_line(line) else {
+            continue;
+        };
+        if line.len() - trimmed.len() >= 2 {
+            task.item_type = TodoItemType::ChecklistItem;
+        }
+        if let Some(heading) = pending_heading.take() {
+            items.push(PlanPaneItem::Heading(heading));
+        }
+        items.push(PlanPaneItem::Task(task));
+    }
+    items
+}
+
+/// Completed and total task counts.
+pub fn progress(items: &[PlanPaneItem]) -> (usize, usize) {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            PlanPaneItem::Task(task) => Some(task.status == TodoStatus::Done),
+            PlanPaneItem::Heading(_) => None,
+        })
+        .fold((0, 0), |(done, total), is_done| {
+            (done + usize::from(is_done), total + 1)
+        })
+}
+
+pub fn render_plan_pane(f: &mut Frame, state: &AppState, area: Rect) {
+    f.render_widget(ratatui::widgets::Clear, area);
+    let pane = &state.plan_pane_state;
+
+    let (done, total) = progress(&pane.items);
+    let title = if total > 0 {
+        format!(" Plan · {}/{} done ", done, total)
+    } else {
+        " Plan ".to_string()
+    };
+    let block = Block::default()
+        .borders(Borders::LEFT)
+        .border_style(Style::default().fg(ThemeColors::border()))
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(ThemeColors::title_primary())
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let width = inner.width.saturating_sub(2) as usize;
+    let mut lines: Vec<Line> = Vec::new();
+    if let Some(title) = &pane.title {
+        for text in crate::services::side_panel::wrap_text(title, width) {
+            lines.push(Line::from(Span::styled(
+                format!(" {}", text),
+                Style::default().fg(ThemeColors::cyan()),
+            )));
+        }
+        lines.push(Line::from(""));
+    }
+
+    if pane.content_hash.is_none() {
+        lines.push(placeholder(" No plan.md in this session yet"));
+    } else if pane.items.is_empty() {
+        lines.push(placeholder(" No checklist items in plan.md"));
+    }
+
+    for item in &pane.items {
+        match item {
+            PlanPaneItem::Heading(heading) => {
+                if !lines.is_empty() {
+                    lines.push(Line::from(""));
+                }
+                for text in crate::services::side_panel::wrap_text(heading, width) {
+                    lines.push(Line::from(Span::styled(
+                        format!(" {}", text),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )));
+                }
+            }
+            PlanPaneItem::Task(task) => push_task_lines(&mut lines, task, width),
+        }
+    }
+
+    // Keep the rows following the agent visible: start at the first open task
+    // when the list is taller than the pane
+    let height = inner.height as usize;
+    let first_open = pane.items.iter().position(
+        |item| matches!(item, PlanPaneItem::Task(task) if task.status != TodoStatus::Done),
+    );
+    let scroll = match first_open {
+        Some(_) if lines.len() > height => {
+            let open_row = first_open_row(&lines).unwrap_or_default();
+            open_row
+                .saturating_sub(2)
+                .min(lines.len().saturating_sub(height))
+        }
+        _ => 0,
+    };
+
+    let paragraph = Paragraph::new(lines).scroll((scroll as u16, 0));
+    f.render_widget(
+        paragraph,
+        Rect {
+            x: inner.x.saturating_add(1),
+            width: inner.width.saturating_sub(1),
+            ..inner
+        },
+    );
+}
+
+fn placeholder(text: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        text.to_string(),
+        Style::default()
+            .fg(ThemeColors::dark_gray())
+            .add_modifier(Modifier::ITALIC),
+    ))
+}
+
+/// Marker drawn before an open task, found again when picking the scroll
+/// offset
+const IN_PROGRESS_SYMBOL: &str = "◐";
+const PENDING_SYMBOL: &str = "○";
+
+fn push_task_lines(lines: &mut Vec<Line<'static>>, task: &TodoItem, width: usize) {
+    let (symbol, symbol_color, text_style) = match task.status {
+        TodoStatus::Done => (
+            "✓",
+            ThemeColors::green(),
+            Style::default().fg(ThemeColors::dark_gray()),
+        ),
+        TodoStatus::InProgress => (
+            IN_PROGRESS_SYMBOL,
+            ThemeColors::yellow(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        TodoStatus::Pending => (PENDING_SYMBOL, ThemeColors::dark_gray(), Style::default()),
+    };
+    let indent = if task.item_type == TodoItemType::ChecklistItem {
+        "   "
+    } else {
+        " "
+    };
+    let text_width = width.saturating_sub(indent.len() + 2);
+    for (i, text) in crate::services::side_panel::wrap_text(&task.text, text_width)
+        .into_iter()
+        .enumerate()
+    {
+        let prefix = if i == 0 {
+            Span::styled(
+                format!("{}{} ", indent, symbol),
+                Style::default().fg(symbol_color),
+            )
+        } else {
+            Span::raw(format!("{}  ", indent))
+        };
+        lines.push(Line::from(vec![prefix, Span::styled(text, text_style)]));
+    }
+}
+
+fn first_open_row(lines: &[Line]) -> Option<usize> {
+    lines.iter().position(|line| {
+        line.spans.first().is_some_and(|span| {
+            let marker = span.content.trim();
+            marker == IN_PROGRESS_SYMBOL || marker == PENDING_SYMBOL
+        })
+    })
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = "\
+---
+title: Migrate to Aurora
+status: approved
+version: 2
+---
+
+# Plan
+
+Some context that is not a task.
+
+## Step 1: Database
+
+- [x] Snapshot RDS
+- [/] Restore into Aurora
+  - [ ] Verify row counts
+
+## Notes
+
+Nothing to do here.
+
+## Step 2: Cutover
+
+- [ ] Switch DNS
+";
+
+    #[test]
+    fn test_parse_plan_tasks_groups_under_headings() {
+        let items = parse_plan_tasks(crate::services::plan::extract_plan_body(PLAN));
+        let rendered: Vec<String> = items
+            .iter()
+            .map(|item| match item {
+                PlanPaneItem::Heading(heading) => format!("# {}", heading),
+                PlanPaneItem::Task(task) => format!(
+                    "{} {}{}",
+                    task.status.symbol(),
+                    if task.item_type == TodoItemType::ChecklistItem {
+                        "└ "
+                    } else {
+                        ""
+                    },
+                    task.text
+                ),
+            })
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "# Step 1: Database",
+                "[x] Snapshot RDS",
+                "[/] Restore into Aurora",
+                "[ ] └ Verify row counts",
+                "# Step 2: Cutover",
+                "[ ] Switch DNS",
+            ]
+        );
+        assert_eq!(progress(&items), (1, 4));
+    }
+
+    #[test]
+    fn test_refresh_follows_plan_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut pane = PlanPaneState {
+            is_shown: true,
+            ..PlanPaneState::default()
+        };
+
+        assert!(!refresh(&mut pane, dir.path()));
+        assert!(pane.items.is_empty());
+
+        let plan_path = crate::services::plan::plan_file_path(dir.path());
+        std::fs::write(&plan_path, PLAN).unwrap();
+        assert!(refresh(&mut pane, dir.path()));
+        assert_eq!(pane.title.as_deref(), Some("Migrate to Aurora"));
+        assert_eq!(progress(&pane.items), (1, 4));
+
+        // Unchanged file: nothing to redo
+        assert!(!refresh(&mut pane, dir.path()));
+
+        std::fs::write(
+            &plan_path,
+            PLAN.replace("- [ ] Switch DNS", "- [x] Switch DNS"),
+        )
+        .unwrap();
+        assert!(refresh(&mut pane, dir.path()));
+        assert_eq!(progress(&pane.items), (2, 4));
+
+        std::fs::remove_file(&plan_path).unwrap();
+        assert!(refresh(&mut pane, dir.path()));
+        assert!(pane.items.is_empty());
+        assert!(pane.is_shown);
+    }
+}
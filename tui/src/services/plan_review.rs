@@ -9,7 +9,7 @@
 use crate::app::AppState;
 use crate::services::detect_term::ThemeColors;
 use crate::services::plan_comments::{
-    AnchorType, CommentAnchor, CommentAuthor, MatchQuality, PlanComments,
+    AnchorType, CommentAnchor, CommentAuthor, MatchQuality, PlanComment, PlanComments,
 };
 use ratatui::{
     Frame,
@@ -18,7 +18,7 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Open the plan review overlay, loading content and comments from disk.
 pub fn open_plan_review(state: &mut AppState) {
@@ -44,21 +44,62 @@ pub fn open_plan_review(state: &mut AppState) {
     // Extract body (skip front matter)
     let body = crate::services::plan::extract_plan_body(&content);
 
+    // Diff against the revision reviewed last, then record this one
+    state.plan_review_state.diff =
+        crate::services::plan::previous_plan_revision(session_dir, &content)
+            .map(|previous| {
+                crate::services::plan_diff::PlanDiff::compute(
+                    crate::services::plan::extract_plan_body(&previous),
+                    body,
+                )
+            })
+            .filter(|diff| !diff.is_empty());
+    state.plan_review_state.show_diff = false;
+    if let Err(e) = crate::services::plan::record_plan_revision(session_dir, &content) {
+        log::warn!("Failed to record plan revision: {}", e);
+    }
+
     state.plan_review_state.content = content.clone();
     state.plan_review_state.lines = body.lines().map(String::from).collect();
+    state.plan_review_state.code_highlights = highlight_code_blocks(&state.plan_review_state.lines);
     state.plan_review_state.scroll = 0;
     state.plan_review_state.cursor_line = 0;
     state.plan_review_state.show_comment_modal = false;
     state.plan_review_state.comment_input.clear();
     state.plan_review_state.selected_comment = None;
-
-    // Start with empty comments (in-memory only, no persistence)
-    state.plan_review_state.resolved_anchors.clear();
-    state.plan_review_state.comments = None;
+    state.plan_review_state.selection_start = None;
+    state.plan_review_state.search_query.clear();
+    state.plan_review_state.search_editing = false;
+
+    // Reload saved comments, re-anchoring them if the plan was revised
+    let comments = crate::services::plan_comments::load_comments(session_dir).map(|mut pc| {
+        if crate::services::plan_comments::sync_to_plan(&mut pc, &content)
+            && let Err(e) = crate::services::plan_comments::save_comments(session_dir, &pc)
+        {
+            log::warn!("Failed to save plan comments: {}", e);
+        }
+        pc
+    });
+    state.plan_review_state.resolved_anchors = comments
+        .as_ref()
+        .map(|pc| crate::services::plan_comments::resolve_anchors(body, &pc.comments))
+        .unwrap_or_default();
+    state.plan_review_state.comments = comments;
 
     state.plan_review_state.is_visible = true;
 }
 
+/// Save the review's comments next to plan.md.
+fn persist_comments(state: &AppState) {
+    let Some(ref pc) = state.plan_review_state.comments else {
+        return;
+    };
+    let session_dir = std::path::Path::new(".stakpak/session");
+    if let Err(e) = crate::services::plan_comments::save_comments(session_dir, pc) {
+        log::warn!("Failed to save plan comments: {}", e);
+    }
+}
+
 /// Close the plan review overlay.
 pub fn close_plan_review(state: &mut AppState) {
     state.plan_review_state.is_visible = false;
@@ -146,6 +187,281 @@ fn ensure_cursor_visible(state: &mut AppState) {
     // Upper bound will be clamped during render when we know viewport height
 }
 
+/// Toggle highlighting of changes since the previously reviewed revision.
+pub fn toggle_diff(state: &mut AppState) {
+    if state.plan_review_state.diff.is_some() {
+        state.plan_review_state.show_diff = !state.plan_review_state.show_diff;
+    }
+}
+
+/// Jump to the next changed hunk, turning the diff view on.
+pub fn next_hunk(state: &mut AppState) {
+    let Some(starts) = hunk_starts(state) else {
+        return;
+    };
+    let cursor = state.plan_review_state.cursor_line;
+    if let Some(&next) = starts.iter().find(|&&ln| ln > cursor).or(starts.first()) {
+        state.plan_review_state.cursor_line = next;
+        ensure_cursor_visible(state);
+    }
+}
+
+/// Jump to the previous changed hunk, turning the diff view on.
+pub fn prev_hunk(state: &mut AppState) {
+    let Some(starts) = hunk_starts(state) else {
+        return;
+    };
+    let cursor = state.plan_review_state.cursor_line;
+    if let Some(&prev) = starts
+        .iter()
+        .rev()
+        .find(|&&ln| ln < cursor)
+        .or(starts.last())
+    {
+        state.plan_review_state.cursor_line = prev;
+        ensure_cursor_visible(state);
+    }
+}
+
+/// Start or cancel a visual selection at the cursor line.
+pub fn toggle_selection(state: &mut AppState) {
+    let review = &mut state.plan_review_state;
+    review.selection_start = match review.selection_start {
+        Some(_) => None,
+        None => Some(review.cursor_line),
+    };
+}
+
+/// Cancel the visual selection.
+pub fn clear_selection(state: &mut AppState) {
+    state.plan_review_state.selection_start = None;
+}
+
+/// First and last selected lines, inclusive.
+fn selected_range(state: &AppState) -> Option<(usize, usize)> {
+    let start = state.plan_review_state.selection_start?;
+    let cursor = state.plan_review_state.cursor_line;
+    Some((start.min(cursor), start.max(cursor)))
+}
+
+fn is_selected(state: &AppState, line: usize) -> bool {
+    selected_range(state).is_some_and(|(first, last)| (first..=last).contains(&line))
+}
+
+/// Open the search prompt, remembering where the cursor was.
+pub fn open_search(state: &mut AppState) {
+    let review = &mut state.plan_review_state;
+    review.search_editing = true;
+    review.search_query.clear();
+    review.search_origin = review.cursor_line;
+}
+
+/// Add a character to the search and jump to the first match from where
+/// the search started.
+pub fn search_input_char(state: &mut AppState, c: char) {
+    state.plan_review_state.search_query.push(c);
+    jump_to_first_match(state);
+}
+
+pub fn search_input_backspace(state: &mut AppState) {
+    state.plan_review_state.search_query.pop();
+    jump_to_first_match(state);
+}
+
+/// Close the search prompt, keeping the matches highlighted.
+pub fn submit_search(state: &mut AppState) {
+    state.plan_review_state.search_editing = false;
+}
+
+/// Close the search prompt and return the cursor to where it was.
+pub fn cancel_search(state: &mut AppState) {
+    state.plan_review_state.search_editing = false;
+    state.plan_review_state.search_query.clear();
+    state.plan_review_state.cursor_line = state.plan_review_state.search_origin;
+    ensure_cursor_visible(state);
+}
+
+/// Stop highlighting the last search.
+pub fn clear_search(state: &mut AppState) {
+    state.plan_review_state.search_query.clear();
+}
+
+/// Jump to the next line matching the search, wrapping around.
+pub fn next_match(state: &mut AppState) {
+    let matches = search_matches(state);
+    let cursor = state.plan_review_state.cursor_line;
+    if let Some(&next) = matches.iter().find(|&&ln| ln > cursor).or(matches.first()) {
+        state.plan_review_state.cursor_line = next;
+        ensure_cursor_visible(state);
+    }
+}
+
+/// Jump to the previous line matching the search, wrapping around.
+pub fn prev_match(state: &mut AppState) {
+    let matches = search_matches(state);
+    let cursor = state.plan_review_state.cursor_line;
+    if let Some(&prev) = matches
+        .iter()
+        .rev()
+        .find(|&&ln| ln < cursor)
+        .or(matches.last())
+    {
+        state.plan_review_state.cursor_line = prev;
+        ensure_cursor_visible(state);
+    }
+}
+
+fn jump_to_first_match(state: &mut AppState) {
+    let origin = state.plan_review_state.search_origin;
+    let matches = search_matches(state);
+    state.plan_review_state.cursor_line = matches
+        .iter()
+        .find(|&&ln| ln >= origin)
+        .or(matches.first())
+        .copied()
+        .unwrap_or(origin);
+    ensure_cursor_visible(state);
+}
+
+/// "2/5" when the cursor is on a match, otherwise the number of matches.
+fn match_status(state: &AppState) -> String {
+    let matches = search_matches(state);
+    let cursor = state.plan_review_state.cursor_line;
+    match matches.iter().position(|&ln| ln == cursor) {
+        _ if matches.is_empty() => "no matches".to_string(),
+        Some(i) => format!("{}/{}", i + 1, matches.len()),
+        None if matches.len() == 1 => "1 match".to_string(),
+        None => format!("{} matches", matches.len()),
+    }
+}
+
+fn search_matches(state: &AppState) -> Vec<usize> {
+    matching_lines(
+        &state.plan_review_state.lines,
+        &state.plan_review_state.search_query,
+    )
+}
+
+/// Lines containing `query`, ignoring ASCII case.
+fn matching_lines(lines: &[String], query: &str) -> Vec<usize> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let query = query.to_ascii_lowercase();
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.to_ascii_lowercase().contains(&query))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Split spans so every occurrence of `query` (ignoring ASCII case) is
+/// highlighted. Occurrences split across two spans are not found.
+fn highlight_matches<'a>(spans: Vec<Span<'a>>, query: &str) -> Vec<Span<'a>> {
+    if query.is_empty() {
+        return spans;
+    }
+    let query = query.to_ascii_lowercase();
+    let highlight = Style::default()
+        .fg(ThemeColors::highlight_fg())
+        .bg(ThemeColors::yellow());
+    let mut result = Vec::with_capacity(spans.len());
+    for span in spans {
+        // ASCII lowercasing keeps byte offsets, so they index the original text
+        let lowered = span.content.to_ascii_lowercase();
+        let mut last = 0;
+        for (start, found) in lowered.match_indices(&query) {
+            let end = start + found.len();
+            if let Some(before) = span.content.get(last..start)
+                && !before.is_empty()
+            {
+                result.push(Span::styled(before.to_string(), span.style));
+            }
+            if let Some(matched) = span.content.get(start..end) {
+                result.push(Span::styled(
+                    matched.to_string(),
+                    span.style.patch(highlight),
+                ));
+            }
+            last = end;
+        }
+        if last == 0 {
+            result.push(span);
+        } else if let Some(after) = span.content.get(last..)
+            && !after.is_empty()
+        {
+            result.push(Span::styled(after.to_string(), span.style));
+        }
+    }
+    result
+}
+
+/// Move the cursor to the clicked line. Clicking a gutter badge brings that
+/// line's comment thread up in the comment panel the same way.
+pub fn handle_mouse_click(state: &mut AppState, col: u16, row: u16) {
+    let Some(line) = line_at(&state.plan_review_state, col, row) else {
+        return;
+    };
+    state.plan_review_state.selection_start = None;
+    state.plan_review_state.cursor_line = line;
+}
+
+/// Dragging over the plan selects the lines between the click and the
+/// mouse, like `v`.
+pub fn handle_mouse_drag(state: &mut AppState, col: u16, row: u16) {
+    let Some(line) = line_at(&state.plan_review_state, col, row) else {
+        return;
+    };
+    let review = &mut state.plan_review_state;
+    if review.selection_start.is_none() && line != review.cursor_line {
+        review.selection_start = Some(review.cursor_line);
+    }
+    review.cursor_line = line;
+}
+
+/// Plan line drawn at a screen position in the gutter or plan area.
+fn line_at(review: &crate::app::PlanReviewState, col: u16, row: u16) -> Option<usize> {
+    let in_area = |area: Rect| {
+        col >= area.x && col < area.x + area.width && row >= area.y && row < area.y + area.height
+    };
+    if !in_area(review.gutter_area) && !in_area(review.plan_area) {
+        return None;
+    }
+    let offset = usize::from(row.checked_sub(review.plan_area.y)?);
+    review.visible_rows.get(offset).copied().flatten()
+}
+
+/// Hunk start lines, or `None` when there is nothing to diff against.
+fn hunk_starts(state: &mut AppState) -> Option<Vec<usize>> {
+    let starts = state.plan_review_state.diff.as_ref()?.hunk_starts();
+    state.plan_review_state.show_diff = true;
+    Some(starts)
+}
+
+/// Resolve the comments on the cursor line, or reopen them if they are all
+/// resolved already.
+pub fn toggle_resolve(state: &mut AppState) {
+    let comment_ids = comment_ids_on_line(state, state.plan_review_state.cursor_line);
+    let Some(ref mut pc) = state.plan_review_state.comments else {
+        return;
+    };
+    if crate::services::plan_comments::toggle_resolved(pc, &comment_ids).is_some() {
+        persist_comments(state);
+    }
+}
+
+/// IDs of the comments whose anchor covers `line`.
+fn comment_ids_on_line(state: &AppState, line: usize) -> Vec<String> {
+    state
+        .plan_review_state
+        .resolved_anchors
+        .iter()
+        .filter(|(_, a)| a.covers(line) && a.match_quality != MatchQuality::Orphaned)
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
 /// Get sorted unique line numbers that have comments.
 fn commented_line_numbers(state: &AppState) -> Vec<usize> {
     let mut lines: Vec<usize> = state
@@ -171,6 +487,17 @@ fn comment_counts_by_line(state: &AppState) -> HashMap<usize, usize> {
     counts
 }
 
+/// Lines inside a range comment, other than the line its badge is on.
+fn range_continuation_lines(state: &AppState) -> HashSet<usize> {
+    state
+        .plan_review_state
+        .resolved_anchors
+        .iter()
+        .filter(|(_, a)| a.match_quality != MatchQuality::Orphaned)
+        .flat_map(|(_, a)| a.line_number + 1..a.line_number + a.span)
+        .collect()
+}
+
 /// A visual row produced by soft-wrapping a logical line.
 struct VisualRow {
     /// Index of the logical line this row belongs to.
@@ -179,72 +506,106 @@ struct VisualRow {
     is_first: bool,
     /// The text content for this visual row.
     text: String,
+    /// A line removed since the previous revision, shown just above
+    /// `logical_line` in the diff view. Never holds the cursor.
+    removed: bool,
 }
 
 /// Pre-wrap all logical lines into visual rows for a given width.
 ///
 /// Wraps at word boundaries (spaces) when possible, falling back to
-/// hard breaks only for words longer than the available width.
-fn build_visual_rows(lines: &[String], width: usize) -> (Vec<VisualRow>, Vec<usize>) {
+/// hard breaks only for words longer than the available width. With a
+/// `diff`, removed lines are interleaved where they used to be.
+fn build_visual_rows(
+    lines: &[String],
+    width: usize,
+    diff: Option<&crate::services::plan_diff::PlanDiff>,
+) -> (Vec<VisualRow>, Vec<usize>) {
     let mut rows: Vec<VisualRow> = Vec::new();
     // first_visual_row[i] = index into `rows` where logical line i starts
     let mut first_visual_row: Vec<usize> = Vec::with_capacity(lines.len());
 
     let w = width.max(1);
+    let removed_before = |line: usize| {
+        diff.and_then(|diff| diff.removed.get(&line))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    };
 
     for (logical_idx, line) in lines.iter().enumerate() {
+        for removed in removed_before(logical_idx) {
+            push_wrapped_rows(&mut rows, logical_idx, removed, w, true);
+        }
         first_visual_row.push(rows.len());
+        push_wrapped_rows(&mut rows, logical_idx, line, w, false);
+    }
 
-        if line.is_empty() {
-            rows.push(VisualRow {
-                logical_line: logical_idx,
-                is_first: true,
-                text: String::new(),
-            });
-            continue;
-        }
+    let last_line = lines.len().saturating_sub(1);
+    for removed in removed_before(lines.len()) {
+        push_wrapped_rows(&mut rows, last_line, removed, w, true);
+    }
 
-        let mut remaining = line.as_str();
-        let mut is_first = true;
-
-        while !remaining.is_empty() {
-            // Count characters, not bytes — handles multi-byte UTF-8 (e.g. →, emoji)
-            let char_count = remaining.chars().count();
-            if char_count <= w {
-                // Fits on one row
-                rows.push(VisualRow {
-                    logical_line: logical_idx,
-                    is_first,
-                    text: remaining.to_string(),
-                });
-                break;
-            }
+    (rows, first_visual_row)
+}
 
-            // Find the byte offset of the w-th character
-            let byte_limit = remaining
-                .char_indices()
-                .nth(w)
-                .map(|(idx, _)| idx)
-                .unwrap_or(remaining.len());
+/// Soft-wrap one line into `rows`.
+fn push_wrapped_rows(
+    rows: &mut Vec<VisualRow>,
+    logical_idx: usize,
+    line: &str,
+    w: usize,
+    removed: bool,
+) {
+    if line.is_empty() {
+        rows.push(VisualRow {
+            logical_line: logical_idx,
+            is_first: true,
+            text: String::new(),
+            removed,
+        });
+        return;
+    }
 
-            // Find the last space at or before that byte offset
-            let break_at = remaining[..byte_limit]
-                .rfind(' ')
-                .map(|pos| pos + 1) // include the space on this row
-                .unwrap_or(byte_limit); // no space found — hard break at width
+    let mut remaining = line;
+    let mut is_first = true;
 
-            let (chunk, rest) = remaining.split_at(break_at);
+    while !remaining.is_empty() {
+        // Count characters, not bytes — handles multi-byte UTF-8 (e.g. →, emoji)
+        let char_count = remaining.chars().count();
+        if char_count <= w {
+            // Fits on one row
             rows.push(VisualRow {
                 logical_line: logical_idx,
                 is_first,
-                text: chunk.to_string(),
+                text: remaining.to_string(),
+                removed,
             });
-            is_first = false;
-            remaining = rest;
+            break;
         }
-    }
 
-    (rows, first_visual_row)
+        // Find the byte offset of the w-th character
+        let byte_limit = remaining
+            .char_indices()
+            .nth(w)
+            .map(|(idx, _)| idx)
+            .unwrap_or(remaining.len());
+
+        // Find the last space at or before that byte offset
+        let break_at = remaining[..byte_limit]
+            .rfind(' ')
+            .map(|pos| pos + 1) // include the space on this row
+            .unwrap_or(byte_limit); // no space found — hard break at width
+
+        let (chunk, rest) = remaining.split_at(break_at);
+        rows.push(VisualRow {
+            logical_line: logical_idx,
+            is_first,
+            text: chunk.to_string(),
+            removed,
+        });
+        is_first = false;
+        remaining = rest;
+    }
 }
 
 /// Render the full-screen plan review overlay.
@@ -256,7 +617,7 @@ pub fn render_plan_review(f: &mut Frame, state: &mut AppState, area: Rect) {
         .borders(Borders::ALL)
         .border_style(Style::default().fg(ThemeColors::dark_gray()))
         .title(Span::styled(
-            " Plan Review ",
+            review_title(state),
             Style::default()
                 .fg(ThemeColors::cyan())
                 .add_modifier(Modifier::BOLD),
@@ -301,8 +662,16 @@ pub fn render_plan_review(f: &mut Frame, state: &mut AppState, area: Rect) {
     let visible_height = plan_area.height as usize;
 
     // Build visual rows (soft-wrapped) for the plan width
-    let (visual_rows, first_visual_row) =
-        build_visual_rows(&state.plan_review_state.lines, plan_area.width as usize);
+    let diff = state
+        .plan_review_state
+        .diff
+        .as_ref()
+        .filter(|_| state.plan_review_state.show_diff);
+    let (visual_rows, first_visual_row) = build_visual_rows(
+        &state.plan_review_state.lines,
+        plan_area.width as usize,
+        diff,
+    );
 
     // Convert logical cursor/scroll to visual row space
     let cursor_visual = if state.plan_review_state.cursor_line < first_visual_row.len() {
@@ -332,6 +701,16 @@ pub fn render_plan_review(f: &mut Frame, state: &mut AppState, area: Rect) {
         state.plan_review_state.scroll = row.logical_line;
     }
 
+    // Remember what was drawn where so mouse clicks can find their line
+    state.plan_review_state.gutter_area = gutter_area;
+    state.plan_review_state.plan_area = plan_area;
+    state.plan_review_state.visible_rows = visual_rows
+        .iter()
+        .skip(scroll_visual)
+        .take(visible_height)
+        .map(|row| (!row.removed).then_some(row.logical_line))
+        .collect();
+
     let comment_counts = comment_counts_by_line(state);
 
     // Render gutter
@@ -359,7 +738,7 @@ pub fn render_plan_review(f: &mut Frame, state: &mut AppState, area: Rect) {
     render_comment_panel(f, state, comment_area);
 
     // Render key hints
-    render_key_hints(f, hints_area);
+    render_key_hints(f, state, hints_area);
 
     // Render comment modal on top (if open)
     render_comment_modal(f, state, area);
@@ -367,6 +746,33 @@ pub fn render_plan_review(f: &mut Frame, state: &mut AppState, area: Rect) {
     // Render confirmation dialog on top (if open)
     render_confirm_modal(f, state, area);
 }
+/// Overlay title, with the selection or change counts when there are any.
+fn review_title(state: &AppState) -> String {
+    if let Some((first, last)) = selected_range(state) {
+        return format!(" Plan Review — selecting lines {}–{} ", first + 1, last + 1);
+    }
+    match &state.plan_review_state.diff {
+        Some(diff) if state.plan_review_state.show_diff => format!(
+            " Plan Review — changes since last review (+{} -{}) ",
+            diff.added_count(),
+            diff.removed_count()
+        ),
+        _ => " Plan Review ".to_string(),
+    }
+}
+
+/// Whether `line` was added since the previous revision, in the diff view.
+fn is_added_line(state: &AppState, line: usize) -> bool {
+    state.plan_review_state.show_diff
+        && state
+            .plan_review_state
+            .diff
+            .as_ref()
+            .and_then(|diff| diff.added.get(line))
+            .copied()
+            .unwrap_or(false)
+}
+
 /// Render the left gutter with comment count badges.
 fn render_gutter(
     f: &mut Frame,
@@ -377,6 +783,7 @@ fn render_gutter(
     visual_rows: &[VisualRow],
     scroll_visual: usize,
 ) {
+    let range_lines = range_continuation_lines(state);
     let mut lines: Vec<Line<'_>> = Vec::with_capacity(visible_height);
 
     for i in 0..visible_height {
@@ -390,6 +797,15 @@ fn render_gutter(
         let logical = vrow.logical_line;
         let is_cursor = logical == state.plan_review_state.cursor_line;
 
+        if vrow.removed {
+            let marker = if vrow.is_first { "   - " } else { "     " };
+            lines.push(Line::from(Span::styled(
+                marker,
+                Style::default().fg(ThemeColors::red()),
+            )));
+            continue;
+        }
+
         // Only show gutter content on the first visual row of a logical line
         if !vrow.is_first {
             if is_cursor {
@@ -419,6 +835,21 @@ fn render_gutter(
                 "   > ",
                 Style::default().fg(ThemeColors::cyan()),
             )));
+        } else if is_selected(state, logical) {
+            lines.push(Line::from(Span::styled(
+                "   ┃ ",
+                Style::default().fg(ThemeColors::cyan()),
+            )));
+        } else if range_lines.contains(&logical) {
+            lines.push(Line::from(Span::styled(
+                "   │ ",
+                Style::default().fg(ThemeColors::yellow()),
+            )));
+        } else if is_added_line(state, logical) {
+            lines.push(Line::from(Span::styled(
+                "   + ",
+                Style::default().fg(ThemeColors::green()),
+            )));
         } else {
             lines.push(Line::from("     "));
         }
@@ -459,6 +890,17 @@ fn render_plan_content(
 
         let vrow = &visual_rows[vrow_idx];
         let logical = vrow.logical_line;
+
+        if vrow.removed {
+            lines.push(Line::from(Span::styled(
+                vrow.text.clone(),
+                Style::default()
+                    .fg(ThemeColors::red())
+                    .add_modifier(Modifier::CROSSED_OUT),
+            )));
+            continue;
+        }
+
         let is_cursor = logical == state.plan_review_state.cursor_line;
         let in_code_block = code_block_map.get(logical).copied().unwrap_or(false);
 
@@ -477,23 +919,57 @@ fn render_plan_content(
             format!("  {}", vrow.text)
         };
 
-        // Build styled spans for this row
-        let styled_spans =
-            style_plan_line(&display_text, original_trimmed, in_code_block, &md_style);
+        // Build styled spans for this row; code is syntax-highlighted and
+        // added lines are tinted green
+        let highlighted = state
+            .plan_review_state
+            .code_highlights
+            .get(logical)
+            .and_then(Option::as_ref);
+        let mut styled_spans = match highlighted {
+            Some(spans) => {
+                // Characters of this logical line drawn on earlier rows
+                let row_start: usize = visual_rows
+                    .get(..vrow_idx)
+                    .unwrap_or_default()
+                    .iter()
+                    .rev()
+                    .take_while(|row| row.logical_line == logical && !row.removed)
+                    .map(|row| row.text.chars().count())
+                    .sum();
+                let mut row_spans = slice_spans(spans, row_start, vrow.text.chars().count());
+                if !vrow.is_first {
+                    row_spans.insert(0, Span::raw("  "));
+                }
+                row_spans
+            }
+            None => style_plan_line(&display_text, original_trimmed, in_code_block, &md_style),
+        };
+        if is_added_line(state, logical) {
+            for span in &mut styled_spans {
+                span.style = span.style.fg(ThemeColors::green());
+            }
+        }
 
-        // Apply cursor highlight as background overlay on each span
-        let final_spans = if is_cursor {
-            styled_spans
+        // Apply cursor or selection highlight as background overlay on each span
+        let highlight = if is_cursor {
+            Some(ThemeColors::dark_gray())
+        } else if is_selected(state, logical) {
+            Some(ThemeColors::highlight_bg())
+        } else {
+            None
+        };
+        let final_spans = match highlight {
+            Some(bg) => styled_spans
                 .into_iter()
                 .map(|span| {
-                    let mut s = span.style;
-                    s = s.bg(ThemeColors::dark_gray());
+                    let s = span.style.bg(bg);
                     Span::styled(span.content, s)
                 })
-                .collect()
-        } else {
-            styled_spans
+                .collect(),
+            None => styled_spans,
         };
+        let final_spans = highlight_matches(final_spans, &state.plan_review_state.search_query);
 
         lines.push(Line::from(final_spans));
     }
@@ -502,6 +978,63 @@ fn render_plan_content(
     f.render_widget(paragraph, area);
 }
 
+/// Highlight the lines of each fenced code block for its language tag.
+///
+/// Fence lines and lines outside code blocks get `None`. An unclosed block
+/// runs to the end of the plan.
+fn highlight_code_blocks(lines: &[String]) -> Vec<Option<Vec<Span<'static>>>> {
+    let mut highlights = vec![None; lines.len()];
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(language) = lines[i].trim().strip_prefix("```") else {
+            i += 1;
+            continue;
+        };
+        let language = Some(language.trim()).filter(|lang| !lang.is_empty());
+        let start = i + 1;
+        let end = lines
+            .iter()
+            .skip(start)
+            .position(|line| line.trim().starts_with("```"))
+            .map_or(lines.len(), |offset| start + offset);
+        let block: Vec<&str> = lines
+            .iter()
+            .take(end)
+            .skip(start)
+            .map(String::as_str)
+            .collect();
+        let highlighted =
+            crate::services::syntax_highlighter::highlight_code_lines(&block, language);
+        for (slot, spans) in highlights.iter_mut().skip(start).zip(highlighted) {
+            *slot = Some(spans);
+        }
+        i = end + 1;
+    }
+    highlights
+}
+
+/// The part of highlighted `spans` covering `len` characters from `start`.
+fn slice_spans(spans: &[Span<'static>], start: usize, len: usize) -> Vec<Span<'static>> {
+    let mut result = Vec::new();
+    let mut offset = 0;
+    for span in spans {
+        let span_len = span.content.chars().count();
+        let from = start.max(offset);
+        let to = (start + len).min(offset + span_len);
+        if from < to {
+            let text: String = span
+                .content
+                .chars()
+                .skip(from - offset)
+                .take(to - from)
+                .collect();
+            result.push(Span::styled(text, span.style));
+        }
+        offset += span_len;
+    }
+    result
+}
+
 /// Build a map of logical_line_index → is_inside_code_block.
 ///
 /// Scans lines up to `max_line` (inclusive) tracking fenced code block toggles.
@@ -810,12 +1343,12 @@ fn parse_link_at(text: &str, start: usize) -> Option<(&str, &str, usize)> {
 fn render_comment_panel(f: &mut Frame, state: &AppState, area: Rect) {
     let cursor_line = state.plan_review_state.cursor_line;
 
-    // Find comments anchored to this line
+    // Find comments whose anchor covers this line
     let comment_ids: Vec<&str> = state
         .plan_review_state
         .resolved_anchors
         .iter()
-        .filter(|(_, a)| a.line_number == cursor_line && a.match_quality != MatchQuality::Orphaned)
+        .filter(|(_, a)| a.covers(cursor_line) && a.match_quality != MatchQuality::Orphaned)
         .map(|(id, _)| id.as_str())
         .collect();
 
@@ -859,12 +1392,21 @@ fn render_comment_panel(f: &mut Frame, state: &AppState, area: Rect) {
                 .resolved_anchors
                 .iter()
                 .find(|(id, _)| id == cid)
-                .map(|(_, a)| match &a.match_quality {
-                    MatchQuality::Exact => "",
-                    MatchQuality::Fuzzy(_) => " ~shifted",
-                    MatchQuality::Orphaned => " ⚠orphaned",
+                .map(|(_, a)| {
+                    let range = if a.span > 1 {
+                        format!(" lines {}–{}", a.line_number + 1, a.line_number + a.span)
+                    } else {
+                        String::new()
+                    };
+                    let quality = match (&a.match_quality, a.moved_from) {
+                        (MatchQuality::Orphaned, _) => " ⚠orphaned".to_string(),
+                        (_, Some(from)) => format!(" moved from line {}", from + 1),
+                        (MatchQuality::Exact, None) => String::new(),
+                        (MatchQuality::Fuzzy(_), None) => " ~shifted".to_string(),
+                    };
+                    range + &quality
                 })
-                .unwrap_or("");
+                .unwrap_or_default();
 
             lines.push(Line::from(vec![
                 Span::styled(
@@ -877,10 +1419,7 @@ fn render_comment_panel(f: &mut Frame, state: &AppState, area: Rect) {
                         })
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(
-                    match_info.to_string(),
-                    Style::default().fg(ThemeColors::dark_gray()),
-                ),
+                Span::styled(match_info, Style::default().fg(ThemeColors::dark_gray())),
             ]));
 
             // Comment text
@@ -894,6 +1433,28 @@ fn render_comment_panel(f: &mut Frame, state: &AppState, area: Rect) {
                 text_style,
             )));
 
+            // Reply thread, indented under the comment
+            for reply in &comment.replies {
+                let (label, color) = match reply.author {
+                    CommentAuthor::User => ("You", ThemeColors::yellow()),
+                    CommentAuthor::Agent => ("Agent", ThemeColors::cyan()),
+                };
+                lines.push(Line::from(vec![
+                    Span::styled("   ↳ ", Style::default().fg(ThemeColors::dark_gray())),
+                    Span::styled(
+                        format!("{label}: "),
+                        Style::default()
+                            .fg(if comment.resolved {
+                                ThemeColors::dark_gray()
+                            } else {
+                                color
+                            })
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(reply.text.clone(), text_style),
+                ]));
+            }
+
             lines.push(Line::from("")); // spacing
         }
     }
@@ -990,9 +1551,9 @@ fn render_confirm_modal(f: &mut Frame, state: &AppState, area: Rect) {
 
 /// Format all unresolved comments into a structured feedback message.
 ///
-/// Each comment includes its anchor text (the exact heading or line it's attached to)
-/// so the agent knows which part of the plan to revise.
-/// Skips resolved comments.
+/// Each comment includes its anchor text (the exact heading or line it's attached to,
+/// or every line of a selected range) so the agent knows which part of the plan to
+/// revise. Skips resolved comments.
 pub fn format_feedback_message(comments: &PlanComments, _plan_content: &str) -> Option<String> {
     // Only unresolved comments
     let unresolved: Vec<_> = comments.comments.iter().filter(|c| !c.resolved).collect();
@@ -1004,21 +1565,38 @@ pub fn format_feedback_message(comments: &PlanComments, _plan_content: &str) ->
     let mut output =
         String::from("I've reviewed the plan and have feedback on specific sections:\n\n");
 
-    // Group comments by anchor text so we don't repeat the same anchor header
-    let mut grouped: Vec<(&str, Vec<&str>)> = Vec::new();
+    // Group comments by anchor so we don't repeat the same anchor header
+    let mut grouped: Vec<(&CommentAnchor, Vec<&PlanComment>)> = Vec::new();
     for comment in &unresolved {
-        let anchor_text = comment.anchor.text.as_str();
-        if let Some(group) = grouped.iter_mut().find(|(a, _)| *a == anchor_text) {
-            group.1.push(comment.text.as_str());
+        let anchor = &comment.anchor;
+        if let Some(group) = grouped
+            .iter_mut()
+            .find(|(a, _)| a.text == anchor.text && a.selection == anchor.selection)
+        {
+            group.1.push(comment);
         } else {
-            grouped.push((anchor_text, vec![comment.text.as_str()]));
+            grouped.push((anchor, vec![comment]));
         }
     }
 
-    for (anchor_text, texts) in &grouped {
-        output.push_str(&format!("> On: `{}`\n", anchor_text));
-        for text in texts {
-            output.push_str(&format!("- {}\n", text));
+    for (anchor, comments) in &grouped {
+        if anchor.selection.len() > 1 {
+            output.push_str("> On:\n");
+            for line in &anchor.selection {
+                output.push_str(&format!("> {}\n", line));
+            }
+        } else {
+            output.push_str(&format!("> On: `{}`\n", anchor.text));
+        }
+        for comment in comments {
+            output.push_str(&format!("- [{}] {}\n", comment.id, comment.text));
+            for reply in &comment.replies {
+                let label = match reply.author {
+                    CommentAuthor::User => "I replied",
+                    CommentAuthor::Agent => "You replied",
+                };
+                output.push_str(&format!("  - {}: {}\n", label, reply.text));
+            }
         }
         output.push('\n');
     }
@@ -1120,11 +1698,15 @@ Build login and refresh endpoints.
             anchor: CommentAnchor {
                 anchor_type,
                 text: anchor_text.to_string(),
+                line: None,
+                parent_headings: Vec::new(),
+                selection: Vec::new(),
             },
             author: CommentAuthor::User,
             text: text.to_string(),
             created_at: Utc::now(),
             resolved,
+            replies: Vec::new(),
         }
     }
 
@@ -1264,6 +1846,57 @@ Build login and refresh endpoints.
         assert!(msg.contains("Open issue"));
     }
 
+    #[test]
+    fn test_format_feedback_includes_ids_and_reply_threads() {
+        let mut pc = make_plan_comments(vec![make_plan_comment(
+            "cmt_01",
+            AnchorType::Heading,
+            "## Step 1: Database",
+            "Why not Aurora?",
+            false,
+        )]);
+        crate::services::plan_comments::add_reply(
+            &mut pc,
+            "cmt_01",
+            CommentAuthor::Agent,
+            "RDS is cheaper at this size.".to_string(),
+        );
+        crate::services::plan_comments::add_reply(
+            &mut pc,
+            "cmt_01",
+            CommentAuthor::User,
+            "Traffic will triple next quarter.".to_string(),
+        );
+
+        let msg = format_feedback_message(&pc, TEST_PLAN).unwrap();
+        assert!(msg.contains(
+            "- [cmt_01] Why not Aurora?\n  - You replied: RDS is cheaper at this size.\n  - I replied: Traffic will triple next quarter.\n"
+        ));
+    }
+
+    #[test]
+    fn test_format_feedback_quotes_full_selection() {
+        let mut comment = make_plan_comment(
+            "cmt_01",
+            AnchorType::Heading,
+            "## Step 1: Database",
+            "Split this step",
+            false,
+        );
+        comment.anchor.selection = vec![
+            "## Step 1: Database".to_string(),
+            String::new(),
+            "Use PostgreSQL on RDS.".to_string(),
+        ];
+        let pc = make_plan_comments(vec![comment]);
+
+        let msg = format_feedback_message(&pc, TEST_PLAN).unwrap();
+        assert!(msg.contains(
+            "> On:\n> ## Step 1: Database\n> \n> Use PostgreSQL on RDS.\n- [cmt_01] Split this step\n"
+        ));
+        assert!(!msg.contains("> On: `"));
+    }
+
     // ─── Inline Markdown Rendering Tests ─────────────────────────────────────
 
     fn md_style() -> crate::services::markdown_renderer::MarkdownStyle {
@@ -1409,6 +2042,107 @@ Build login and refresh endpoints.
         assert!(text.contains("🔥"));
     }
 
+    #[test]
+    fn test_line_at_maps_clicks_to_drawn_lines() {
+        let review = crate::app::PlanReviewState {
+            gutter_area: Rect::new(1, 2, 5, 4),
+            plan_area: Rect::new(6, 2, 40, 4),
+            visible_rows: vec![Some(10), Some(10), None, Some(11)],
+            ..Default::default()
+        };
+        assert_eq!(line_at(&review, 2, 2), Some(10));
+        assert_eq!(line_at(&review, 20, 3), Some(10));
+        assert_eq!(line_at(&review, 20, 4), None);
+        assert_eq!(line_at(&review, 45, 5), Some(11));
+        assert_eq!(line_at(&review, 46, 5), None);
+        assert_eq!(line_at(&review, 20, 6), None);
+        assert_eq!(line_at(&review, 0, 2), None);
+    }
+
+    #[test]
+    fn test_highlight_code_blocks_covers_only_code_lines() {
+        let lines: Vec<String> = ["Intro", "```rust", "fn main() {}", "```", "After", "```"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let highlights = highlight_code_blocks(&lines);
+        let covered: Vec<bool> = highlights.iter().map(Option::is_some).collect();
+        assert_eq!(covered, vec![false, false, true, false, false, false]);
+        let text: String = highlights[2]
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(text, "fn main() {}");
+    }
+
+    #[test]
+    fn test_slice_spans_cuts_across_span_boundaries() {
+        let red = Style::default().fg(ThemeColors::red());
+        let spans = vec![Span::styled("let ", red), Span::raw("value = 1;")];
+        let sliced = slice_spans(&spans, 2, 5);
+        let texts: Vec<&str> = sliced.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(texts, vec!["t ", "val"]);
+        assert_eq!(sliced[0].style, red);
+    }
+
+    #[test]
+    fn test_matching_lines_ignores_case() {
+        let lines: Vec<String> = TEST_PLAN.lines().map(String::from).collect();
+        let matches = matching_lines(&lines, "postgres");
+        assert!(!matches.is_empty());
+        assert!(
+            matches
+                .iter()
+                .all(|&ln| lines[ln].to_lowercase().contains("postgres"))
+        );
+        assert!(matching_lines(&lines, "").is_empty());
+        assert!(matching_lines(&lines, "no such text").is_empty());
+    }
+
+    #[test]
+    fn test_highlight_matches_splits_spans() {
+        let base = Style::default().add_modifier(Modifier::BOLD);
+        let spans = vec![Span::styled("Use RDS and rds", base), Span::raw(" later")];
+        let result = highlight_matches(spans, "RdS");
+        let texts: Vec<&str> = result.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(texts, vec!["Use ", "RDS", " and ", "rds", " later"]);
+        assert_eq!(result[1].style.bg, Some(ThemeColors::yellow()));
+        assert!(result[1].style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(result[4].style.bg, None);
+    }
+
+    #[test]
+    fn test_build_visual_rows_interleaves_removed_lines() {
+        let previous = "# Plan\nOld step.\nKeep.\nGone at end.";
+        let current = "# Plan\nNew step.\nKeep.";
+        let diff = crate::services::plan_diff::PlanDiff::compute(previous, current);
+        let lines: Vec<String> = current.lines().map(String::from).collect();
+
+        let (rows, first_visual_row) = build_visual_rows(&lines, 80, Some(&diff));
+        let summary: Vec<(usize, bool, &str)> = rows
+            .iter()
+            .map(|row| (row.logical_line, row.removed, row.text.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, false, "# Plan"),
+                (1, true, "Old step."),
+                (1, false, "New step."),
+                (2, false, "Keep."),
+                (2, true, "Gone at end."),
+            ]
+        );
+        // The cursor lands on the current line, not the removed one above it.
+        assert_eq!(first_visual_row, vec![0, 2, 3]);
+
+        let (plain, _) = build_visual_rows(&lines, 80, None);
+        assert!(plain.iter().all(|row| !row.removed));
+        assert_eq!(plain.len(), 3);
+    }
+
     #[test]
     fn test_build_code_block_map_basic() {
         let lines: Vec<String> = vec![
@@ -1544,21 +2278,67 @@ Build login and refresh endpoints.
 }
 
 /// Render the bottom key hints bar.
-fn render_key_hints(f: &mut Frame, area: Rect) {
-    let hints = Line::from(vec![
+fn render_key_hints(f: &mut Frame, state: &AppState, area: Rect) {
+    if state.plan_review_state.search_editing {
+        let prompt = Line::from(vec![
+            Span::styled(" /", Style::default().fg(ThemeColors::cyan())),
+            Span::styled(
+                state.plan_review_state.search_query.clone(),
+                Style::default().fg(ThemeColors::text()),
+            ),
+            Span::styled("█ ", Style::default().fg(ThemeColors::cursor())),
+            Span::styled(
+                format!("{} ", match_status(state)),
+                Style::default().fg(ThemeColors::yellow()),
+            ),
+            Span::styled("Enter", Style::default().fg(ThemeColors::green())),
+            Span::styled("=done ", Style::default().fg(ThemeColors::dark_gray())),
+            Span::styled("Esc", Style::default().fg(ThemeColors::red())),
+            Span::styled("=cancel", Style::default().fg(ThemeColors::dark_gray())),
+        ]);
+        f.render_widget(Paragraph::new(prompt), area);
+        return;
+    }
+
+    let mut hints = vec![
         Span::styled(" c", Style::default().fg(ThemeColors::cyan())),
         Span::styled("=comment ", Style::default().fg(ThemeColors::dark_gray())),
         Span::styled("d", Style::default().fg(ThemeColors::red())),
         Span::styled("=delete ", Style::default().fg(ThemeColors::dark_gray())),
+        Span::styled("r", Style::default().fg(ThemeColors::green())),
+        Span::styled("=resolve ", Style::default().fg(ThemeColors::dark_gray())),
+        Span::styled("v", Style::default().fg(ThemeColors::cyan())),
+        Span::styled("=select ", Style::default().fg(ThemeColors::dark_gray())),
         Span::styled("Enter", Style::default().fg(ThemeColors::green())),
         Span::styled("=submit ", Style::default().fg(ThemeColors::dark_gray())),
         Span::styled("Tab", Style::default().fg(ThemeColors::cyan())),
         Span::styled("=next ", Style::default().fg(ThemeColors::dark_gray())),
+        Span::styled("/", Style::default().fg(ThemeColors::cyan())),
+        Span::styled("=search ", Style::default().fg(ThemeColors::dark_gray())),
+    ];
+    if !state.plan_review_state.search_query.is_empty() {
+        hints.extend([
+            Span::styled("n/N", Style::default().fg(ThemeColors::cyan())),
+            Span::styled(
+                format!("={} ", match_status(state)),
+                Style::default().fg(ThemeColors::yellow()),
+            ),
+        ]);
+    }
+    if state.plan_review_state.diff.is_some() {
+        hints.extend([
+            Span::styled("D", Style::default().fg(ThemeColors::cyan())),
+            Span::styled("=diff ", Style::default().fg(ThemeColors::dark_gray())),
+            Span::styled("]/[", Style::default().fg(ThemeColors::cyan())),
+            Span::styled("=change ", Style::default().fg(ThemeColors::dark_gray())),
+        ]);
+    }
+    hints.extend([
         Span::styled("Esc", Style::default().fg(ThemeColors::red())),
         Span::styled("=close", Style::default().fg(ThemeColors::dark_gray())),
     ]);
 
-    let paragraph = Paragraph::new(hints);
+    let paragraph = Paragraph::new(Line::from(hints));
     f.render_widget(paragraph, area);
 }
 
@@ -1567,8 +2347,14 @@ fn render_key_hints(f: &mut Frame, area: Rect) {
 /// The kind of comment action the modal is for.
 #[derive(Debug, Clone, PartialEq)]
 pub enum CommentModalKind {
-    /// New top-level comment anchored to a line.
-    NewComment { anchor_text: String },
+    /// New top-level comment anchored to a line or a selected range.
+    NewComment {
+        anchor_text: String,
+        /// 0-indexed plan body line the comment is anchored to.
+        line: usize,
+        /// Every selected line when commenting on a range, else empty.
+        selection: Vec<String>,
+    },
 }
 
 /// Confirmation dialog variants for destructive/important actions.
@@ -1585,33 +2371,54 @@ pub enum ConfirmAction {
     },
 }
 
-/// Open the comment modal for a new comment on the current cursor line.
+/// Open the comment modal for a new comment on the selection, or on the
+/// current cursor line when nothing is selected.
 pub fn open_comment_modal(state: &mut AppState) {
     if state.plan_review_state.lines.is_empty() {
         return;
     }
 
     let cursor = state.plan_review_state.cursor_line;
-    let anchor_text = state
+    let (first, last) = selected_range(state).unwrap_or((cursor, cursor));
+    let mut selected: Vec<(usize, String)> = state
         .plan_review_state
         .lines
-        .get(cursor)
-        .map(|s| s.trim().to_string())
-        .unwrap_or_default();
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(last + 1 - first)
+        .map(|(i, s)| (i, s.trim().to_string()))
+        .collect();
 
-    if anchor_text.is_empty() {
-        return; // Don't comment on blank lines
+    // Blank lines at either end of a selection aren't part of the anchor
+    while selected.last().is_some_and(|(_, s)| s.is_empty()) {
+        selected.pop();
     }
+    let leading_blank = selected.iter().take_while(|(_, s)| s.is_empty()).count();
+    selected.drain(..leading_blank);
+
+    let Some((line, anchor_text)) = selected.first().cloned() else {
+        return; // Don't comment on blank lines
+    };
+    let selection = if selected.len() > 1 {
+        selected.into_iter().map(|(_, s)| s).collect()
+    } else {
+        Vec::new()
+    };
 
     state.plan_review_state.comment_input.clear();
     state.plan_review_state.show_comment_modal = true;
     state.plan_review_state.selected_comment = None; // new comment, not a reply
-    state.plan_review_state.modal_kind = Some(CommentModalKind::NewComment { anchor_text });
+    state.plan_review_state.modal_kind = Some(CommentModalKind::NewComment {
+        anchor_text,
+        line,
+        selection,
+    });
 }
 
 /// Submit the comment from the modal.
 ///
-/// Adds comment to in-memory state and refreshes resolved anchors.
+/// Adds the comment, refreshes resolved anchors and saves the comments.
 pub fn submit_comment(state: &mut AppState) {
     let text = state.plan_review_state.comment_input.trim().to_string();
     if text.is_empty() {
@@ -1630,17 +2437,25 @@ pub fn submit_comment(state: &mut AppState) {
         });
 
     match &state.plan_review_state.modal_kind {
-        Some(CommentModalKind::NewComment { anchor_text }) => {
+        Some(CommentModalKind::NewComment {
+            anchor_text,
+            line,
+            selection,
+        }) => {
             let anchor_type = if anchor_text.starts_with('#') {
                 AnchorType::Heading
             } else {
                 AnchorType::Line
             };
+            let body = crate::services::plan::extract_plan_body(&state.plan_review_state.content);
             crate::services::plan_comments::add_comment(
                 &mut pc,
                 CommentAnchor {
                     anchor_type,
                     text: anchor_text.clone(),
+                    line: Some(*line),
+                    parent_headings: crate::services::plan_comments::parent_headings(body, *line),
+                    selection: selection.clone(),
                 },
                 CommentAuthor::User,
                 text,
@@ -1655,6 +2470,8 @@ pub fn submit_comment(state: &mut AppState) {
         crate::services::plan_comments::resolve_anchors(body, &pc.comments);
 
     state.plan_review_state.comments = Some(pc);
+    state.plan_review_state.selection_start = None;
+    persist_comments(state);
     close_comment_modal(state);
 }
 
@@ -1681,13 +2498,7 @@ pub fn open_delete_confirm(state: &mut AppState) {
     let cursor = state.plan_review_state.cursor_line;
 
     // Collect all comment IDs anchored to this line
-    let comment_ids: Vec<String> = state
-        .plan_review_state
-        .resolved_anchors
-        .iter()
-        .filter(|(_, a)| a.line_number == cursor && a.match_quality != MatchQuality::Orphaned)
-        .map(|(id, _)| id.clone())
-        .collect();
+    let comment_ids = comment_ids_on_line(state, cursor);
 
     if comment_ids.is_empty() {
         return; // No comments on this line
@@ -1727,6 +2538,7 @@ pub fn execute_confirm(
             let body = crate::services::plan::extract_plan_body(&state.plan_review_state.content);
             state.plan_review_state.resolved_anchors =
                 crate::services::plan_comments::resolve_anchors(body, &pc.comments);
+            persist_comments(state);
         }
     }
 }
@@ -1775,13 +2587,22 @@ pub fn render_comment_modal(f: &mut Frame, state: &AppState, area: Rect) {
     let title = " Add Comment ";
 
     // Anchor preview
-    if let Some(CommentModalKind::NewComment { anchor_text }) = &state.plan_review_state.modal_kind
+    if let Some(CommentModalKind::NewComment {
+        anchor_text,
+        selection,
+        ..
+    }) = &state.plan_review_state.modal_kind
     {
         let max_chars = (modal_width as usize).saturating_sub(17);
         let display: String = anchor_text.chars().take(max_chars).collect();
+        let more = match selection.len() {
+            0 | 1 => String::new(),
+            n => format!(" (+{} more lines)", n - 1),
+        };
         lines.push(Line::from(vec![
             Span::styled("On: ", Style::default().fg(ThemeColors::muted())),
             Span::styled(display, Style::default().fg(ThemeColors::text())),
+            Span::styled(more, Style::default().fg(ThemeColors::muted())),
         ]));
         lines.push(Line::from("")); // padding below anchor
     }
@@ -1837,6 +2658,8 @@ pub fn render_comment_modal(f: &mut Frame, state: &AppState, area: Rect) {
         Span::styled("=submit  ", Style::default().fg(ThemeColors::dark_gray())),
         Span::styled("Ctrl+J", Style::default().fg(ThemeColors::cyan())),
         Span::styled("=newline  ", Style::default().fg(ThemeColors::dark_gray())),
+        Span::styled("Ctrl+E", Style::default().fg(ThemeColors::cyan())),
+        Span::styled("=editor  ", Style::default().fg(ThemeColors::dark_gray())),
         Span::styled("Esc", Style::default().fg(ThemeColors::red())),
         Span::styled("=cancel", Style::default().fg(ThemeColors::dark_gray())),
     ]));
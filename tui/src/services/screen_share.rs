@@ -0,0 +1,176 @@
+//! Redacted screen-share mode for the TUI.
+//!
+//! `redact_secrets` only covers output produced after it is enabled. This
+//! mode masks what is already on screen: when toggled with `/privacy`, every
+//! transcript line is re-rendered with secrets, IP addresses, file paths and
+//! hostnames replaced by mask characters of the same width, so the layout
+//! does not shift and nothing sensitive leaks onto a shared screen.
+//!
+//! Like accessibility mode the flag is process-wide, because masking happens
+//! in render code that has no access to `AppState`. The underlying messages
+//! are never modified; turning the mode off re-renders them unmasked.
+
+use ratatui::text::{Line, Span};
+use regex::Regex;
+use stakpak_shared::secrets::gitleaks::detect_secrets;
+use std::ops::Range;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static SCREEN_SHARE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Character drawn in place of each masked character.
+const MASK_CHAR: char = '•';
+
+/// Whether screen-share mode is active.
+pub fn is_enabled() -> bool {
+    SCREEN_SHARE_MODE.load(Ordering::Relaxed)
+}
+
+/// Flip screen-share mode and return the new state.
+pub fn toggle() -> bool {
+    !SCREEN_SHARE_MODE.fetch_xor(true, Ordering::Relaxed)
+}
+
+/// Mask sensitive text in a rendered line while screen-share mode is active.
+pub fn mask_line(line: Line<'static>) -> Line<'static> {
+    if is_enabled() {
+        mask_line_always(line)
+    } else {
+        line
+    }
+}
+
+fn mask_line_always(mut line: Line<'static>) -> Line<'static> {
+    let text: String = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    let ranges = sensitive_ranges(&text);
+    if ranges.is_empty() {
+        return line;
+    }
+
+    // Ranges are byte offsets into the joined line, so a match that spans
+    // several styled spans is masked in each of them.
+    let mut offset = 0;
+    line.spans = std::mem::take(&mut line.spans)
+        .into_iter()
+        .map(|span| {
+            let start = offset;
+            offset += span.content.len();
+            let end = offset;
+            if !ranges
+                .iter()
+                .any(|range| range.start < end && start < range.end)
+            {
+                return span;
+            }
+
+            let masked: String = span
+                .content
+                .char_indices()
+                .map(|(index, c)| {
+                    let position = start + index;
+                    if !c.is_whitespace() && ranges.iter().any(|range| range.contains(&position)) {
+                        MASK_CHAR
+                    } else {
+                        c
+                    }
+                })
+                .collect();
+            Span::styled(masked, span.style)
+        })
+        .collect();
+    line
+}
+
+/// Byte ranges of `text` that must not be shown while screen sharing.
+fn sensitive_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = detect_secrets(text, None, true)
+        .into_iter()
+        .map(|secret| secret.start_pos..secret.end_pos)
+        .collect();
+
+    for pattern in patterns() {
+        ranges.extend(
+            pattern
+                .captures_iter(text)
+                .filter_map(|captures| captures.get(1))
+                .map(|found| found.range()),
+        );
+    }
+
+    ranges
+}
+
+/// Path, hostname and address patterns. Each masks its first capture group,
+/// so a leading boundary character stays visible.
+fn patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            // URLs and user@host: everything after the scheme or the `@`.
+            r"(?:[a-zA-Z][a-zA-Z0-9+.-]*://|@)([A-Za-z0-9._~%:/?#\[\]@!$&'*+,;=-]+)",
+            // Unix paths with at least two segments, and ~/ paths.
+            r"(?:^|[^A-Za-z0-9_.:/~-])((?:~|\.{1,2})?(?:/[A-Za-z0-9._@+-]+){2,}/?|~/[A-Za-z0-9._@+/-]+)",
+            // Windows paths.
+            r"(?:^|[^A-Za-z0-9])([A-Za-z]:\\[^\s]+)",
+            // IPv4 addresses, private ones included.
+            r"(?:^|[^0-9.])((?:\d{1,3}\.){3}\d{1,3})(?:[^0-9.]|$)",
+            // Fully qualified hostnames with three or more labels.
+            r"(?:^|[^A-Za-z0-9_.-])((?:[A-Za-z0-9](?:[A-Za-z0-9-]{0,61}[A-Za-z0-9])?\.){2,}[A-Za-z]{2,63})(?:[^A-Za-z0-9_-]|$)",
+        ]
+        .iter()
+        .filter_map(|pattern| Regex::new(pattern).ok())
+        .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Color, Style};
+
+    fn masked(text: &str) -> String {
+        mask_line_always(Line::from(text.to_string()))
+            .spans
+            .iter()
+            .map(|span| span.content.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn masks_paths_hosts_and_addresses_keeping_width() {
+        let text = "edit /home/alice/infra/main.tf on db1.prod.example.com (10.0.4.12)";
+        let result = masked(text);
+
+        assert_eq!(result.chars().count(), text.chars().count());
+        assert!(result.starts_with("edit •"));
+        assert!(!result.contains("alice"));
+        assert!(!result.contains("example"));
+        assert!(!result.contains("10.0.4.12"));
+        assert!(result.contains(" on "));
+    }
+
+    #[test]
+    fn leaves_ordinary_text_and_commands_alone() {
+        for text in ["run /privacy to toggle", "read Cargo.toml and/or main.rs"] {
+            assert_eq!(masked(text), text);
+        }
+    }
+
+    #[test]
+    fn masks_across_styled_spans() {
+        let line = Line::from(vec![
+            Span::styled("cat /etc/", Style::default().fg(Color::Green)),
+            Span::styled("ssh/config", Style::default().fg(Color::Blue)),
+        ]);
+        let result = mask_line_always(line);
+
+        assert_eq!(result.spans[0].content, "cat •••••");
+        assert_eq!(result.spans[0].style.fg, Some(Color::Green));
+        assert_eq!(result.spans[1].content, "••••••••••");
+    }
+}
@@ -0,0 +1,314 @@
+//! Session Templates
+//!
+//! Named presets for recurring task types, loaded from markdown files in:
+//! - `.stakpak/templates/`   (project-local, higher priority on name clash)
+//! - `~/.stakpak/templates/` (global)
+//!
+//! Each `<name>.md` file defines the `<name>` template, selectable with
+//! `stakpak --template <name>` or `/template <name>` in the TUI. The file body
+//! is appended to the system prompt. YAML front matter configures the rest:
+//! ```markdown
+//! ---
+//! description: Triage a production incident
+//! model: claude-opus-4-5
+//! context_files:
+//!   - docs/runbooks/incidents.md
+//! allowed_tools: [view, run_command, search_docs]
+//! auto_approve: [view]
+//! ---
+//!
+//! You are triaging a live incident. Start by collecting recent errors...
+//! ```
+//!
+//! Relative `context_files` paths are resolved against the current working
+//! directory, so project templates can reference files in the repository.
+//! `allowed_tools` only takes effect when a session starts with `--template`.
+
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Context files larger than this are rejected rather than silently truncated.
+const MAX_CONTEXT_FILE_BYTES: u64 = 256 * 1024;
+
+/// A parsed session template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionTemplate {
+    pub name: String,
+    pub description: Option<String>,
+    /// Model to use instead of the profile default.
+    pub model: Option<String>,
+    /// Text appended to the system prompt.
+    pub system_prompt: String,
+    /// Files whose contents are attached to the system prompt.
+    pub context_files: Vec<String>,
+    /// Restrict the session to these tools.
+    pub allowed_tools: Option<Vec<String>>,
+    /// Tools that run without asking for approval.
+    pub auto_approve: Option<Vec<String>>,
+    /// File the template was loaded from.
+    pub source: PathBuf,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateFrontMatter {
+    description: Option<String>,
+    model: Option<String>,
+    #[serde(default)]
+    context_files: Vec<String>,
+    allowed_tools: Option<Vec<String>>,
+    auto_approve: Option<Vec<String>>,
+}
+
+impl SessionTemplate {
+    /// Load a template by name from the project-local and global directories.
+    pub fn load(name: &str) -> Result<Self, String> {
+        Self::load_from_dirs(name, &template_directories())
+    }
+
+    /// All available templates, project-local first, sorted by name.
+    pub fn list() -> Vec<Self> {
+        Self::list_from_dirs(&template_directories())
+    }
+
+    fn load_from_dirs(name: &str, dirs: &[PathBuf]) -> Result<Self, String> {
+        if !is_valid_template_name(name) {
+            return Err(format!("Invalid template name '{}'", name));
+        }
+
+        for dir in dirs {
+            let path = dir.join(format!("{}.md", name));
+            if path.is_file() {
+                return Self::parse_file(name, &path);
+            }
+        }
+
+        let available: Vec<String> = Self::list_from_dirs(dirs)
+            .into_iter()
+            .map(|template| template.name)
+            .collect();
+        if available.is_empty() {
+            Err(format!(
+                "Template '{}' not found (no templates in .stakpak/templates or ~/.stakpak/templates)",
+                name
+            ))
+        } else {
+            Err(format!(
+                "Template '{}' not found. Available templates: {}",
+                name,
+                available.join(", ")
+            ))
+        }
+    }
+
+    fn list_from_dirs(dirs: &[PathBuf]) -> Vec<Self> {
+        let mut templates = Vec::new();
+        let mut seen_names: HashSet<String> = HashSet::new();
+
+        for dir in dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            let mut dir_templates: Vec<Self> = entries
+                .flatten()
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    if path.extension().and_then(|e| e.to_str()) != Some("md") {
+                        return None;
+                    }
+                    let name = path.file_stem()?.to_str()?.to_string();
+                    if !is_valid_template_name(&name) || seen_names.contains(&name) {
+                        return None;
+                    }
+                    match Self::parse_file(&name, &path) {
+                        Ok(template) => Some(template),
+                        Err(e) => {
+                            log::warn!("Skipping template {}: {}", path.display(), e);
+                            None
+                        }
+                    }
+                })
+                .collect();
+            dir_templates.sort_by(|a, b| a.name.cmp(&b.name));
+            seen_names.extend(dir_templates.iter().map(|t| t.name.clone()));
+            templates.extend(dir_templates);
+        }
+
+        templates
+    }
+
+    fn parse_file(name: &str, path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read template '{}': {}", path.display(), e))?;
+        Self::parse(name, &content, path)
+    }
+
+    fn parse(name: &str, content: &str, source: &Path) -> Result<Self, String> {
+        let (front_matter, body) = split_front_matter(content.trim());
+        let front_matter: TemplateFrontMatter = match front_matter {
+            Some(yaml) if !yaml.trim().is_empty() => serde_yaml::from_str(yaml)
+                .map_err(|e| format!("Invalid front matter in template '{}': {}", name, e))?,
+            _ => TemplateFrontMatter::default(),
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            description: front_matter.description,
+            model: front_matter.model,
+            system_prompt: body.trim().to_string(),
+            context_files: front_matter.context_files,
+            allowed_tools: front_matter.allowed_tools,
+            auto_approve: front_matter.auto_approve,
+            source: source.to_path_buf(),
+        })
+    }
+
+    /// Build the system prompt text for this template: the template body
+    /// followed by each context file. Fails if a context file is missing or
+    /// too large so a template never starts with silently missing context.
+    pub fn system_prompt_addendum(&self) -> Result<String, String> {
+        let mut sections = Vec::new();
+        if !self.system_prompt.is_empty() {
+            sections.push(self.system_prompt.clone());
+        }
+
+        for file in &self.context_files {
+            let path = Path::new(file);
+            let size = std::fs::metadata(path)
+                .map_err(|e| format!("Template context file '{}': {}", file, e))?
+                .len();
+            if size > MAX_CONTEXT_FILE_BYTES {
+                return Err(format!(
+                    "Template context file '{}' is too large ({} KiB, max {} KiB)",
+                    file,
+                    size / 1024,
+                    MAX_CONTEXT_FILE_BYTES / 1024
+                ));
+            }
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| format!("Template context file '{}': {}", file, e))?;
+            sections.push(format!(
+                "<context_file path=\"{}\">\n{}\n</context_file>",
+                file,
+                content.trim_end()
+            ));
+        }
+
+        Ok(sections.join("\n\n"))
+    }
+}
+
+/// Split `---`-delimited YAML front matter from the body.
+fn split_front_matter(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content.strip_prefix("---") else {
+        return (None, content);
+    };
+    match rest.split_once("\n---") {
+        Some((front_matter, body)) => (Some(front_matter), body),
+        None => (None, content),
+    }
+}
+
+/// Allowed: `a-z`, `A-Z`, `0-9`, `-`, `_`
+fn is_valid_template_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn template_directories() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from(".stakpak/templates")];
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".stakpak/templates"));
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("stakpak_test_templates_{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("create test dir");
+        dir
+    }
+
+    #[test]
+    fn test_parse_template_with_front_matter() {
+        let content = "---\ndescription: Triage incidents\nmodel: claude-opus-4-5\nauto_approve: [view]\n---\n\nCollect recent errors first.";
+        let template = SessionTemplate::parse("triage", content, Path::new("triage.md"))
+            .expect("template should parse");
+
+        assert_eq!(template.description.as_deref(), Some("Triage incidents"));
+        assert_eq!(template.model.as_deref(), Some("claude-opus-4-5"));
+        assert_eq!(template.auto_approve, Some(vec!["view".to_string()]));
+        assert_eq!(template.allowed_tools, None);
+        assert_eq!(template.system_prompt, "Collect recent errors first.");
+    }
+
+    #[test]
+    fn test_parse_template_rejects_unknown_fields() {
+        let content = "---\nmodle: typo\n---\nBody";
+        assert!(SessionTemplate::parse("bad", content, Path::new("bad.md")).is_err());
+
+        let plain = SessionTemplate::parse("plain", "Just a prompt", Path::new("plain.md"))
+            .expect("plain template should parse");
+        assert_eq!(plain.system_prompt, "Just a prompt");
+        assert!(plain.description.is_none());
+    }
+
+    #[test]
+    fn test_project_templates_override_global() {
+        let project = test_dir("project");
+        let global = test_dir("global");
+        std::fs::write(project.join("deploy.md"), "Project deploy").expect("write");
+        std::fs::write(global.join("deploy.md"), "Global deploy").expect("write");
+        std::fs::write(global.join("audit.md"), "Global audit").expect("write");
+        let dirs = vec![project.clone(), global.clone()];
+
+        let deploy = SessionTemplate::load_from_dirs("deploy", &dirs).expect("load deploy");
+        assert_eq!(deploy.system_prompt, "Project deploy");
+
+        let names: Vec<String> = SessionTemplate::list_from_dirs(&dirs)
+            .into_iter()
+            .map(|template| template.name)
+            .collect();
+        assert_eq!(names, vec!["deploy".to_string(), "audit".to_string()]);
+
+        let missing = SessionTemplate::load_from_dirs("nope", &dirs).expect_err("missing");
+        assert!(missing.contains("deploy, audit"));
+        assert!(SessionTemplate::load_from_dirs("../etc", &dirs).is_err());
+
+        let _ = std::fs::remove_dir_all(&project);
+        let _ = std::fs::remove_dir_all(&global);
+    }
+
+    #[test]
+    fn test_system_prompt_addendum_attaches_context_files() {
+        let dir = test_dir("context");
+        let runbook = dir.join("runbook.md");
+        std::fs::write(&runbook, "Restart the API first.\n").expect("write");
+        let runbook = runbook.display().to_string();
+
+        let mut template =
+            SessionTemplate::parse("triage", "Follow the runbook.", Path::new("triage.md"))
+                .expect("template should parse");
+        template.context_files = vec![runbook.clone()];
+
+        let addendum = template.system_prompt_addendum().expect("addendum");
+        assert!(addendum.starts_with("Follow the runbook.\n\n"));
+        assert!(addendum.contains(&format!(
+            "<context_file path=\"{}\">\nRestart the API first.\n</context_file>",
+            runbook
+        )));
+
+        template.context_files = vec![dir.join("missing.md").display().to_string()];
+        assert!(template.system_prompt_addendum().is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
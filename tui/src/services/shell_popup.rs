@@ -217,6 +217,11 @@ pub fn render_shell_popup(f: &mut Frame, state: &mut AppState, area: Rect) {
 
 /// Update cursor blink state (call this from event loop tick)
 pub fn update_cursor_blink(state: &mut AppState) {
+    // No blinking in accessibility mode; keep the cursor solid.
+    if crate::services::accessibility::is_enabled() {
+        state.shell_popup_state.cursor_visible = true;
+        return;
+    }
     state.shell_popup_state.cursor_blink_timer =
         state.shell_popup_state.cursor_blink_timer.wrapping_add(1);
 
@@ -47,7 +47,8 @@ pub fn get_all_shortcuts() -> Vec<Shortcut> {
         Shortcut::new("Esc", "Close dialogs/popups", "Navigation"),
         // Text Input
         Shortcut::new("Ctrl+A", "Move cursor to start of line", "Text Input"),
-        Shortcut::new("Ctrl+E", "Move cursor to end of line", "Text Input"),
+        Shortcut::new("End", "Move cursor to end of line", "Text Input"),
+        Shortcut::new("Ctrl+E", "Compose in external editor", "Text Input"),
         Shortcut::new("Ctrl+F", "Move cursor right", "Text Input"),
         Shortcut::new("Ctrl+B", "Move cursor left", "Text Input"),
         Shortcut::new("Alt+F", "Move cursor to next word", "Text Input"),
@@ -57,20 +58,49 @@ pub fn get_all_shortcuts() -> Vec<Shortcut> {
         Shortcut::new("Ctrl+H", "Delete previous character", "Text Input"),
         Shortcut::new("Ctrl+J", "Insert newline", "Text Input"),
         Shortcut::new("Enter", "Submit input", "Text Input"),
+        Shortcut::new(
+            "Up/Down",
+            "Recall previous prompts (empty input)",
+            "Text Input",
+        ),
+        Shortcut::new("Ctrl+R", "Search prompt history", "Text Input"),
         Shortcut::new("Backspace", "Delete previous character", "Text Input"),
         // Tool Management
         Shortcut::new("Ctrl+O", "Toggle auto-approve mode", "Tool Management"),
         Shortcut::new("Ctrl+Y", "Toggle side panel", "Tool Management"),
-        Shortcut::new("Ctrl+R", "Retry last tool call", "Tool Management"),
+        Shortcut::new("Alt+R", "Retry last tool call", "Tool Management"),
+        Shortcut::new(
+            "Ctrl+Z",
+            "Undo a revert to a message (5s)",
+            "Tool Management",
+        ),
+        Shortcut::new(
+            "e",
+            "Edit a file change before approving",
+            "Tool Management",
+        ),
         // UI Controls
         Shortcut::new("Ctrl+C", "Quit (double press)", "UI Controls"),
         Shortcut::new("Ctrl+T", "Toggle collapsed messages", "UI Controls"),
         Shortcut::new("Ctrl+L", "Toggle mouse capture", "UI Controls"),
         Shortcut::new("Ctrl+F", "Show profile switcher", "UI Controls"),
         Shortcut::new("Ctrl+P", "Show command palette", "UI Controls"),
-        Shortcut::new("Ctrl+S", "Show shortcuts (this popup)", "UI Controls"),
+        Shortcut::new("F1", "Show shortcuts (this popup)", "UI Controls"),
+        Shortcut::new("F2", "Toggle plan task pane", "UI Controls"),
+        Shortcut::new(
+            "Ctrl+Tab",
+            "Next session tab (Shift: previous)",
+            "UI Controls",
+        ),
+        Shortcut::new(
+            "Ctrl+PgDn",
+            "Next session tab (PgUp: previous)",
+            "UI Controls",
+        ),
+        Shortcut::new("Alt+1..9", "Go to session tab", "UI Controls"),
         Shortcut::new("Ctrl+G", "Show file changes", "UI Controls"),
         Shortcut::new("Ctrl+X", "Copy session ID", "UI Controls"),
+        Shortcut::new("Ctrl+S", "Export transcript (markdown)", "UI Controls"),
         // Commands
         Shortcut::new("/help", "Show help information", "Commands"),
         Shortcut::new("/clear", "Clear screen", "Commands"),
@@ -84,6 +114,9 @@ pub fn get_all_shortcuts() -> Vec<Shortcut> {
             "Commands",
         ),
         Shortcut::new("/usage", "Show token usage for this session", "Commands"),
+        Shortcut::new("/split", "Toggle plan task pane", "Commands"),
+        Shortcut::new("/tab", "Session tabs: /tab [new|close|<n>]", "Commands"),
+        Shortcut::new("/export", "Save transcript: /export [md|json]", "Commands"),
         Shortcut::new(
             "/list_approved_tools",
             "List auto-approved tools",
@@ -95,6 +128,9 @@ pub fn get_all_shortcuts() -> Vec<Shortcut> {
             "Commands",
         ),
         Shortcut::new("/mouse_capture", "Toggle mouse capture", "Commands"),
+        Shortcut::new("/accessibility", "Toggle accessibility mode", "Commands"),
+        Shortcut::new("/theme", "Switch color scheme: /theme [name]", "Commands"),
+        Shortcut::new("/privacy", "Toggle screen-share masking", "Commands"),
         Shortcut::new("/profiles", "Switch profile", "Commands"),
         Shortcut::new("/quit", "Quit application", "Commands"),
         // File Search
@@ -104,6 +140,11 @@ pub fn get_all_shortcuts() -> Vec<Shortcut> {
         // Mouse
         Shortcut::new("Scroll Up/Down", "Scroll messages", "Mouse"),
         Shortcut::new("Click", "Interact with UI elements", "Mouse"),
+        Shortcut::new(
+            "Click/Drag",
+            "Move cursor, select lines in plan review",
+            "Mouse",
+        ),
     ]
 }
 
@@ -794,7 +794,7 @@ fn render_footer_section(f: &mut Frame, state: &AppState, area: Rect) {
     let separator_width = area.width.saturating_sub(4) as usize;
     lines.push(Line::from(vec![Span::styled(
         format!("{}{}", LEFT_PADDING, "─".repeat(separator_width)),
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(ThemeColors::dark_gray()),
     )]));
 
     // Session ID line with copy shortcut
@@ -822,16 +822,19 @@ fn render_footer_section(f: &mut Frame, state: &AppState, area: Rect) {
     let session_line = if recently_copied {
         Line::from(vec![
             Span::styled(LEFT_PADDING, Style::default()),
-            Span::styled("Session ", Style::default().fg(Color::DarkGray)),
-            Span::styled(session_display, Style::default().fg(Color::Green)),
-            Span::styled(suffix, Style::default().fg(Color::Green)),
+            Span::styled("Session ", Style::default().fg(ThemeColors::dark_gray())),
+            Span::styled(session_display, Style::default().fg(ThemeColors::green())),
+            Span::styled(suffix, Style::default().fg(ThemeColors::green())),
         ])
     } else {
         Line::from(vec![
             Span::styled(LEFT_PADDING, Style::default()),
-            Span::styled("Session ", Style::default().fg(Color::DarkGray)),
-            Span::styled(session_display, Style::default().fg(Color::White)),
-            Span::styled(suffix, Style::default().fg(Color::DarkGray)),
+            Span::styled("Session ", Style::default().fg(ThemeColors::dark_gray())),
+            Span::styled(
+                session_display,
+                Style::default().fg(ThemeColors::title_primary()),
+            ),
+            Span::styled(suffix, Style::default().fg(ThemeColors::dark_gray())),
         ])
     };
     lines.push(session_line);
@@ -957,7 +960,7 @@ fn truncate_string(s: &str, max_width: usize) -> String {
 }
 
 /// Wrap text to fit within a given width, returning multiple lines
-fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
+pub(crate) fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
     // Handle edge cases - always return at least the original text
     if text.is_empty() {
         return vec![String::new()];
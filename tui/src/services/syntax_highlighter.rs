@@ -1,63 +1,193 @@
 use ratatui::style::{Color, Style};
-use ratatui::text::{Line, Span};
+use ratatui::text::Span;
+use std::sync::OnceLock;
 use syntect::easy::HighlightLines;
-use syntect::highlighting::{Color as SyntectColor, ThemeSet};
-use syntect::parsing::SyntaxSet;
-use syntect::util::LinesWithEndings;
+use syntect::highlighting::{Color as SyntectColor, Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
 
-use crate::services::detect_term::{ThemeColors, is_light_mode, should_use_rgb_colors};
+use crate::services::detect_term::{is_light_mode, should_use_rgb_colors};
+
+// Loading the bundled syntaxes and themes takes tens of milliseconds, so do it once
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+    THEME_SET.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Theme matching the terminal background
+///
+/// base16-ocean.light has darker colors suitable for light backgrounds
+/// base16-ocean.dark has lighter colors suitable for dark backgrounds
+/// The solarized color scheme gets the matching bundled Solarized theme.
+fn theme() -> Option<&'static Theme> {
+    let solarized =
+        crate::services::theme::active().name == crate::services::theme::SchemeName::Solarized;
+    let theme_name = match (solarized, is_light_mode()) {
+        (true, true) => "Solarized (light)",
+        (true, false) => "Solarized (dark)",
+        (false, true) => "base16-ocean.light",
+        (false, false) => "base16-ocean.dark",
+    };
+    theme_set().themes.get(theme_name)
+}
+
+/// ANSI colors a non-RGB terminal can show, with their usual RGB values
+const ANSI_PALETTE: [(Color, (u8, u8, u8)); 8] = [
+    (Color::Red, (205, 49, 49)),
+    (Color::Green, (13, 188, 121)),
+    (Color::Yellow, (229, 229, 16)),
+    (Color::Blue, (36, 114, 200)),
+    (Color::Magenta, (188, 63, 188)),
+    (Color::Cyan, (17, 168, 205)),
+    (Color::Gray, (170, 170, 170)),
+    (Color::DarkGray, (102, 102, 102)),
+];
+
+/// Closest ANSI color to a theme color, so token kinds stay distinguishable
+/// on terminals without true color
+fn nearest_ansi_color(color: SyntectColor) -> Color {
+    let distance = |(r, g, b): (u8, u8, u8)| {
+        let dr = i32::from(color.r) - i32::from(r);
+        let dg = i32::from(color.g) - i32::from(g);
+        let db = i32::from(color.b) - i32::from(b);
+        dr * dr + dg * dg + db * db
+    };
+    ANSI_PALETTE
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map(|(ansi, _)| *ansi)
+        .unwrap_or(Color::Reset)
+}
 
 fn syntect_color_to_ratatui_color(syntect_color: SyntectColor) -> Color {
     if should_use_rgb_colors() {
         Color::Rgb(syntect_color.r, syntect_color.g, syntect_color.b)
     } else {
-        // For non-RGB terminals, use a theme-aware cyan color
-        ThemeColors::cyan()
+        nearest_ansi_color(syntect_color)
     }
 }
 
-//  apply_syntax_highlighting -> takes string and optional extension and returns highlighted ratatui lines
-pub fn apply_syntax_highlighting(text: &str, extension: Option<&str>) -> Vec<Line<'static>> {
-    let syntax_set = SyntaxSet::load_defaults_newlines();
-    let theme_set = ThemeSet::load_defaults();
+/// Syntax for a code fence language tag (`rust`, `py`, `bash`, ...)
+///
+/// Tries the tag as a syntax name or file extension, then a few common
+/// aliases the bundled syntaxes don't know. Unknown or missing tags get
+/// plain text.
+fn syntax_for_language(language: Option<&str>) -> &'static SyntaxReference {
+    let syntax_set = syntax_set();
+    language
+        .map(|lang| lang.trim().to_lowercase())
+        .and_then(|lang| {
+            let alias = match lang.as_str() {
+                "shell" | "zsh" | "console" => "sh",
+                "typescript" | "ts" | "tsx" | "jsx" => "js",
+                "yml" => "yaml",
+                "c++" => "cpp",
+                "golang" => "go",
+                other => other,
+            };
+            syntax_set.find_syntax_by_token(alias)
+        })
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text())
+}
 
-    // Select theme based on terminal background color
-    // base16-ocean.light has darker colors suitable for light backgrounds
-    // base16-ocean.dark has lighter colors suitable for dark backgrounds
-    let theme_name = if is_light_mode() {
-        "base16-ocean.light"
-    } else {
-        "base16-ocean.dark"
+/// Whether `language` resolves to a syntax other than plain text
+pub fn is_known_language(language: &str) -> bool {
+    !std::ptr::eq(
+        syntax_for_language(Some(language)),
+        syntax_set().find_syntax_plain_text(),
+    )
+}
+
+/// Highlight `lines` of one code block in order, so constructs spanning
+/// lines (block comments, strings) carry over. Returns the spans of each
+/// line, without line endings.
+pub fn highlight_code_lines(lines: &[&str], language: Option<&str>) -> Vec<Vec<Span<'static>>> {
+    let Some(theme) = theme() else {
+        return lines
+            .iter()
+            .map(|line| vec![Span::raw(line.to_string())])
+            .collect();
     };
-    let theme = &theme_set.themes[theme_name];
+    let mut highlighter = HighlightLines::new(syntax_for_language(language), theme);
+    lines
+        .iter()
+        .map(|line| {
+            let with_ending = format!("{}\n", line);
+            highlight_line(&mut highlighter, &with_ending)
+        })
+        .collect()
+}
+
+fn highlight_line(highlighter: &mut HighlightLines<'_>, line: &str) -> Vec<Span<'static>> {
+    let ranges = highlighter
+        .highlight_line(line, syntax_set())
+        .unwrap_or_else(|_| vec![(syntect::highlighting::Style::default(), line)]);
+    ranges
+        .into_iter()
+        .filter_map(|(style, text)| {
+            let text = text.trim_end_matches(['\n', '\r']);
+            // Use only foreground color for better compatibility
+            (!text.is_empty()).then(|| {
+                Span::styled(
+                    text.to_string(),
+                    Style::default().fg(syntect_color_to_ratatui_color(style.foreground)),
+                )
+            })
+        })
+        .collect()
+}
 
-    // add default extensions if none
-    let extension = extension.or(Some("js"));
-    let syntax = extension
-        .and_then(|ext| syntax_set.find_syntax_by_extension(ext))
-        .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut highlighter = HighlightLines::new(syntax, theme);
-    let mut lines = Vec::new();
+    #[test]
+    fn test_known_languages_get_more_than_one_color() {
+        let lines = highlight_code_lines(&["fn main() { let x = \"hi\"; }"], Some("rust"));
+        assert_eq!(lines.len(), 1);
+        let text: String = lines[0].iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "fn main() { let x = \"hi\"; }");
+        let colors: std::collections::HashSet<_> = lines[0].iter().map(|s| s.style.fg).collect();
+        assert!(colors.len() > 1);
+    }
 
-    for line in LinesWithEndings::from(text) {
-        let ranges = highlighter
-            .highlight_line(line, &syntax_set)
-            .unwrap_or_else(|_| vec![(syntect::highlighting::Style::default(), line)]);
-        let mut spans = Vec::new();
+    #[test]
+    fn test_syntax_for_language_resolves_aliases() {
+        assert_eq!(syntax_for_language(Some("python")).name, "Python");
+        assert_eq!(
+            syntax_for_language(Some("BASH")).name,
+            "Bourne Again Shell (bash)"
+        );
+        assert_eq!(
+            syntax_for_language(Some("shell")).name,
+            "Bourne Again Shell (bash)"
+        );
+        assert_eq!(syntax_for_language(Some("no-such-lang")).name, "Plain Text");
+        assert_eq!(syntax_for_language(None).name, "Plain Text");
+        assert!(is_known_language("rs"));
+        assert!(!is_known_language("no-such-lang"));
+    }
 
-        for (style, text) in ranges {
-            let color = syntect_color_to_ratatui_color(style.foreground);
-            // Use only foreground color for better compatibility
-            let styled_span = Span::styled(
-                text.to_string(),
-                Style::default().fg(color), // Only foreground color, no background
-            );
-            spans.push(styled_span);
-        }
-
-        lines.push(Line::from(spans));
+    #[test]
+    fn test_block_comment_state_carries_across_lines() {
+        let lines = highlight_code_lines(&["/* start", "still comment */ let x = 1;"], Some("rs"));
+        let comment_color = lines[0][0].style.fg;
+        assert!(lines[1][0].content.starts_with("still comment"));
+        assert_eq!(lines[1][0].style.fg, comment_color);
     }
 
-    lines
+    #[test]
+    fn test_nearest_ansi_color() {
+        let red = SyntectColor {
+            r: 220,
+            g: 40,
+            b: 40,
+            a: 255,
+        };
+        assert_eq!(nearest_ansi_color(red), Color::Red);
+    }
 }
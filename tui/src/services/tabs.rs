@@ -0,0 +1,419 @@
+//! Multi-session tabs.
+//!
+//! Each tab is its own agent session. Only the active tab lives in
+//! `AppState`; the others are parked as a [`ParkedTab`] holding everything
+//! that belongs to one conversation (messages, scroll position, approval and
+//! ask_user prompts, file change popup, plan review, usage, input draft).
+//! Switching swaps the active state with the parked one and tells the backend
+//! with `OutputEvent::SwitchTab`, which does the same with its own
+//! per-session state. Background tabs are paused: switching is refused while
+//! the agent is working or waiting on a prompt.
+
+use crate::app::{
+    AppState, AskUserState, DialogApprovalState, FileChangesPopupState, MessageInteractionState,
+    MessageRevertState, MessagesScrollingState, OutputEvent, PlanModeState, PlanReviewState,
+    SessionToolCallsState, UsageTrackingState, UserMessageQueueState,
+};
+use crate::services::changeset::{Changeset, TodoItem};
+use crate::services::detect_term::ThemeColors;
+use crate::services::message::{Message, MessageContent};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use tokio::sync::mpsc::Sender;
+
+/// Widest a tab label gets before its title is cut
+const MAX_TITLE_CHARS: usize = 24;
+
+/// Title of a tab whose session has no user message yet
+const UNTITLED: &str = "New session";
+
+pub struct SessionTab {
+    /// Stable id shared with the backend, unlike the position in the bar
+    pub id: u64,
+    /// State of a background tab; None for the active one, whose state is
+    /// in `AppState`
+    pub parked: Option<Box<ParkedTab>>,
+}
+
+/// Everything that belongs to one conversation.
+pub struct ParkedTab {
+    pub messages_scrolling_state: MessagesScrollingState,
+    pub input: String,
+    pub dialog_approval_state: DialogApprovalState,
+    pub session_tool_calls_state: SessionToolCallsState,
+    pub usage_tracking_state: UsageTrackingState,
+    pub plan_mode_state: PlanModeState,
+    pub plan_review_state: PlanReviewState,
+    pub ask_user_state: AskUserState,
+    pub message_revert_state: MessageRevertState,
+    pub user_message_queue_state: UserMessageQueueState,
+    pub message_interaction_state: MessageInteractionState,
+    pub file_changes_popup_state: FileChangesPopupState,
+    pub session_id: String,
+    pub changeset: Changeset,
+    pub todos: Vec<TodoItem>,
+    pub task_progress: Option<crate::services::board_tasks::TaskProgress>,
+    pub session_start_time: std::time::Instant,
+}
+
+impl ParkedTab {
+    /// State of a tab for a session that has not started yet.
+    pub fn fresh(messages: Vec<Message>) -> Self {
+        Self {
+            messages_scrolling_state: MessagesScrollingState {
+                messages,
+                scroll_to_bottom: true,
+                ..Default::default()
+            },
+            input: String::new(),
+            dialog_approval_state: DialogApprovalState::default(),
+            session_tool_calls_state: SessionToolCallsState::default(),
+            usage_tracking_state: UsageTrackingState::default(),
+            plan_mode_state: PlanModeState::default(),
+            plan_review_state: PlanReviewState::default(),
+            ask_user_state: AskUserState {
+                is_focused: true,
+                ..Default::default()
+            },
+            message_revert_state: MessageRevertState::default(),
+            user_message_queue_state: UserMessageQueueState::default(),
+            message_interaction_state: MessageInteractionState::default(),
+            file_changes_popup_state: FileChangesPopupState::default(),
+            session_id: String::new(),
+            changeset: Changeset::default(),
+            todos: Vec::new(),
+            task_progress: None,
+            session_start_time: std::time::Instant::now(),
+        }
+    }
+}
+
+/// Swap the conversation shown in `state` with `tab`.
+pub fn exchange(state: &mut AppState, tab: &mut ParkedTab) {
+    use std::mem::swap;
+
+    swap(
+        &mut state.messages_scrolling_state,
+        &mut tab.messages_scrolling_state,
+    );
+    let draft = state.input().to_string();
+    state.set_input(&tab.input);
+    state.set_cursor_position(tab.input.len());
+    tab.input = draft;
+    swap(
+        &mut state.dialog_approval_state,
+        &mut tab.dialog_approval_state,
+    );
+    swap(
+        &mut state.session_tool_calls_state,
+        &mut tab.session_tool_calls_state,
+    );
+    swap(
+        &mut state.usage_tracking_state,
+        &mut tab.usage_tracking_state,
+    );
+    swap(&mut state.plan_mode_state, &mut tab.plan_mode_state);
+    swap(&mut state.plan_review_state, &mut tab.plan_review_state);
+    swap(&mut state.ask_user_state, &mut tab.ask_user_state);
+    swap(
+        &mut state.message_revert_state,
+        &mut tab.message_revert_state,
+    );
+    swap(
+        &mut state.user_message_queue_state,
+        &mut tab.user_message_queue_state,
+    );
+    swap(
+        &mut state.message_interaction_state,
+        &mut tab.message_interaction_state,
+    );
+    swap(
+        &mut state.file_changes_popup_state,
+        &mut tab.file_changes_popup_state,
+    );
+
+    let panel = &mut state.side_panel_state;
+    swap(&mut panel.session_id, &mut tab.session_id);
+    swap(&mut panel.changeset, &mut tab.changeset);
+    swap(&mut panel.todos, &mut tab.todos);
+    swap(&mut panel.task_progress, &mut tab.task_progress);
+    swap(&mut panel.session_start_time, &mut tab.session_start_time);
+}
+
+/// Why the active tab can't be left right now, if it can't.
+fn switch_blocker(state: &AppState) -> Option<&'static str> {
+    if state.loading_state.is_loading || state.tool_call_state.is_streaming {
+        Some("Wait for the agent to finish (or press Esc) before switching tabs.")
+    } else if state.dialog_approval_state.approval_bar.is_visible()
+        || state.dialog_approval_state.is_dialog_open
+    {
+        Some("Approve or reject the pending tool call before switching tabs.")
+    } else if state.ask_user_state.is_visible {
+        Some("Answer the agent's question before switching tabs.")
+    } else if state.shell_popup_state.is_visible {
+        Some("Close the shell before switching tabs.")
+    } else {
+        None
+    }
+}
+
+/// Close popups that are not tied to a conversation, so they don't show
+/// data of the tab being left.
+fn close_global_popups(state: &mut AppState) {
+    state.input_state.show_helper_dropdown = false;
+    state.command_palette_state.is_visible = false;
+    state.shortcuts_panel_state.is_visible = false;
+    state.model_switcher_state.is_visible = false;
+    state.profile_switcher_state.show_profile_switcher = false;
+    state.rulebook_switcher_state.show_rulebook_switcher = false;
+}
+
+fn redraw(state: &mut AppState) {
+    state.messages_scrolling_state.per_message_cache.clear();
+    crate::services::message::invalidate_message_lines_cache(state);
+    state.messages_scrolling_state.scroll_to_bottom = state.messages_scrolling_state.stay_at_bottom;
+}
+
+/// Make the tab at `index` active.
+pub fn switch_to(
+    state: &mut AppState,
+    index: usize,
+    output_tx: &Sender<OutputEvent>,
+) -> Result<(), String> {
+    let count = state.tabs_state.tabs.len();
+    if index >= count {
+        return Err(format!("No tab {}. There are {} open.", index + 1, count));
+    }
+    let active = state.tabs_state.active;
+    if index == active {
+        return Ok(());
+    }
+    if let Some(reason) = switch_blocker(state) {
+        return Err(reason.to_string());
+    }
+    // The undo window doesn't travel with the tab
+    crate::services::confirm::commit_revert(state);
+
+    let Some(mut parked) = state.tabs_state.tabs[index].parked.take() else {
+        return Err("Tab state is missing".to_string());
+    };
+    exchange(state, &mut parked);
+    state.tabs_state.tabs[active].parked = Some(parked);
+    state.tabs_state.active = index;
+
+    close_global_popups(state);
+    redraw(state);
+    let _ = output_tx.try_send(OutputEvent::SwitchTab(state.tabs_state.tabs[index].id));
+    Ok(())
+}
+
+/// Move to the next tab, wrapping around; `step` of -1 goes back.
+pub fn cycle(
+    state: &mut AppState,
+    step: isize,
+    output_tx: &Sender<OutputEvent>,
+) -> Result<(), String> {
+    let count = state.tabs_state.tabs.len();
+    if count < 2 {
+        return Err("Only one tab is open. Use /tab new to open another.".to_string());
+    }
+    let index = (state.tabs_state.active as isize + step).rem_euclid(count as isize) as usize;
+    switch_to(state, index, output_tx)
+}
+
+/// Open a tab with a new session and switch to it.
+pub fn open_tab(state: &mut AppState, output_tx: &Sender<OutputEvent>) -> Result<(), String> {
+    if let Some(reason) = switch_blocker(state) {
+        return Err(reason.to_string());
+    }
+    let welcome = crate::services::helper_block::welcome_messages(
+        state.configuration_state.latest_version.clone(),
+        state,
+    );
+    let id = state.tabs_state.next_id;
+    state.tabs_state.next_id += 1;
+    state.tabs_state.tabs.push(SessionTab {
+        id,
+        parked: Some(Box::new(ParkedTab::fresh(welcome))),
+    });
+    let index = state.tabs_state.tabs.len() - 1;
+    switch_to(state, index, output_tx)
+}
+
+/// Close the active tab, dropping its session from memory, and show the
+/// one before it. The session itself stays resumable with `/resume`.
+pub fn close_tab(state: &mut AppState, output_tx: &Sender<OutputEvent>) -> Result<(), String> {
+    let count = state.tabs_state.tabs.len();
+    if count < 2 {
+        return Err("Can't close the last tab.".to_string());
+    }
+    if let Some(reason) = switch_blocker(state) {
+        return Err(reason.to_string());
+    }
+    let closing = state.tabs_state.active;
+    let closing_id = state.tabs_state.tabs[closing].id;
+    let target = if closing == 0 { 1 } else { closing - 1 };
+    switch_to(state, target, output_tx)?;
+
+    state.tabs_state.tabs.remove(closing);
+    if closing < state.tabs_state.active {
+        state.tabs_state.active -= 1;
+    }
+    let _ = output_tx.try_send(OutputEvent::CloseTab(closing_id));
+    Ok(())
+}
+
+/// Handle `/tab [new|close|next|prev|<n>]`; a bare `/tab` opens a new tab.
+pub fn run_command(
+    state: &mut AppState,
+    arg: &str,
+    output_tx: &Sender<OutputEvent>,
+) -> Result<(), String> {
+    match arg.trim().to_lowercase().as_str() {
+        "" | "new" => open_tab(state, output_tx),
+        "close" => close_tab(state, output_tx),
+        "next" => cycle(state, 1, output_tx),
+        "prev" => cycle(state, -1, output_tx),
+        other => match other.parse::<usize>() {
+            Ok(number) if number > 0 => switch_to(state, number - 1, output_tx),
+            _ => Err(format!(
+                "Unknown tab command '{}'. Use /tab new, close, next, prev or a tab number.",
+                other
+            )),
+        },
+    }
+}
+
+/// Title of a conversation: its first user message, cut to fit a tab.
+pub fn tab_title(messages: &[Message]) -> String {
+    let Some(text) = messages.iter().find_map(|message| match &message.content {
+        MessageContent::UserMessage(text) => Some(text.trim()),
+        _ => None,
+    }) else {
+        return UNTITLED.to_string();
+    };
+    let first_line = text.lines().next().unwrap_or_default();
+    if first_line.chars().count() > MAX_TITLE_CHARS {
+        let cut: String = first_line.chars().take(MAX_TITLE_CHARS - 1).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        first_line.to_string()
+    }
+}
+
+/// Rows the tab bar takes; it only shows once a second tab is open.
+pub fn tab_bar_height(state: &AppState) -> u16 {
+    u16::from(state.tabs_state.tabs.len() > 1)
+}
+
+pub fn render_tab_bar(f: &mut Frame, state: &AppState, area: Rect) {
+    if area.height == 0 {
+        return;
+    }
+    let tabs = &state.tabs_state;
+    let mut spans = Vec::new();
+    for (index, tab) in tabs.tabs.iter().enumerate() {
+        let is_active = index == tabs.active;
+        let messages = match &tab.parked {
+            Some(parked) => &parked.messages_scrolling_state.messages,
+            None => &state.messages_scrolling_state.messages,
+        };
+        let label = format!(" {} {} ", index + 1, tab_title(messages));
+        let style = if is_active {
+            Style::default()
+                .fg(ThemeColors::selection_fg())
+                .bg(ThemeColors::selection_bg())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(ThemeColors::dark_gray())
+        };
+        spans.push(Span::styled(label, style));
+        spans.push(Span::raw(" "));
+    }
+    spans.push(Span::styled(
+        "Alt+1..9 or Ctrl+PgUp/PgDn to switch",
+        Style::default().fg(ThemeColors::dark_gray()),
+    ));
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn user_message(text: &str) -> Message {
+        Message {
+            id: Uuid::new_v4(),
+            content: MessageContent::UserMessage(text.to_string()),
+            is_collapsed: None,
+        }
+    }
+
+    #[test]
+    fn test_tab_title_uses_first_user_message() {
+        assert_eq!(tab_title(&[]), UNTITLED);
+        assert_eq!(
+            tab_title(&[user_message("fix the ingress\nand more")]),
+            "fix the ingress"
+        );
+        let title = tab_title(&[user_message("migrate every database in staging to aurora")]);
+        assert_eq!(title, "migrate every database…");
+    }
+
+    fn create_test_state() -> AppState {
+        AppState::new(crate::app::AppStateOptions {
+            task_manager_handle: None,
+            latest_version: None,
+            redact_secrets: false,
+            privacy_mode: false,
+            is_git_repo: false,
+            auto_approve_tools: None,
+            allowed_tools: None,
+            input_tx: None,
+            model: stakai::Model::default(),
+            editor_command: None,
+            auth_display_info: (None, None, None),
+            board_agent_id: None,
+            init_prompt_content: None,
+            recent_models: Vec::new(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_exchange_swaps_conversation_state() {
+        let mut parked = ParkedTab::fresh(vec![user_message("second tab")]);
+        parked.input = "draft two".to_string();
+        parked.session_id = "session-two".to_string();
+
+        let messages = vec![user_message("first tab")];
+        let mut first = ParkedTab::fresh(messages);
+        first.input = "draft one".to_string();
+        first.session_id = "session-one".to_string();
+
+        let mut state = create_test_state();
+        exchange(&mut state, &mut first);
+        assert_eq!(state.input(), "draft one");
+
+        exchange(&mut state, &mut parked);
+        assert_eq!(state.input(), "draft two");
+        assert_eq!(state.side_panel_state.session_id, "session-two");
+        assert_eq!(
+            tab_title(&state.messages_scrolling_state.messages),
+            "second tab"
+        );
+        assert_eq!(parked.input, "draft one");
+        assert_eq!(parked.session_id, "session-one");
+        assert_eq!(
+            tab_title(&parked.messages_scrolling_state.messages),
+            "first tab"
+        );
+    }
+}
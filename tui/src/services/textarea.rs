@@ -4,7 +4,6 @@ use crossterm::event::KeyEvent;
 use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::Color;
 use ratatui::style::Style;
 use ratatui::widgets::StatefulWidgetRef;
 use ratatui::widgets::WidgetRef;
@@ -1284,8 +1283,10 @@ impl TextArea {
                         self.text[overlap_start..overlap_end].to_string()
                     };
                     let x_off = self.text[line_range.start..overlap_start].width() as u16;
-                    // Selection style: inverted colors (white text on blue background)
-                    let selection_style = Style::default().fg(Color::White).bg(Color::Blue);
+                    // Selection style: inverted colors (white text on blue background by default)
+                    let selection_style = Style::default()
+                        .fg(ThemeColors::selection_fg())
+                        .bg(ThemeColors::selection_bg());
                     buf.set_string(content_x + x_off, y, &selected_text, selection_style);
                 }
             }
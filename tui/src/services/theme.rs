@@ -0,0 +1,514 @@
+//! Color schemes for the TUI.
+//!
+//! Every [`ThemeColors`](crate::services::detect_term::ThemeColors) role is
+//! looked up in the active scheme before falling back to the built-in palette
+//! for the terminal background, and `MarkdownStyle` derives its heading, link
+//! and callout colors from the same roles. The scheme comes from
+//! `[settings.theme]` in config.toml or `--theme`, and `/theme` switches it for
+//! the rest of the session:
+//!
+//! ```toml
+//! [settings.theme]
+//! scheme = "solarized"   # auto, dark, light, solarized, high-contrast or custom
+//!
+//! [settings.theme.colors]
+//! accent = "#d33682"     # hex, a color name or a 256-color index
+//! border = "darkgray"
+//! ```
+//!
+//! `colors` override single roles on top of whichever scheme is active;
+//! `custom` is the detected palette with only those overrides. Like
+//! accessibility mode, the scheme is process-wide because color helpers are
+//! called from render code that has no access to `AppState`.
+
+use crate::services::detect_term::should_use_rgb_colors;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::{PoisonError, RwLock};
+
+/// `[settings.theme]` as written in config.toml.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Scheme name; auto-detected palette when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
+    /// Role name to color, applied on top of the scheme
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub colors: BTreeMap<String, String>,
+}
+
+/// Built-in color schemes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemeName {
+    /// Palette for the detected terminal background
+    #[default]
+    Auto,
+    /// Dark-background palette regardless of detection
+    Dark,
+    /// Light-background palette regardless of detection
+    Light,
+    Solarized,
+    HighContrast,
+    /// Detected palette with the `colors` overrides only
+    Custom,
+}
+
+impl SchemeName {
+    pub const ALL: [SchemeName; 6] = [
+        SchemeName::Auto,
+        SchemeName::Dark,
+        SchemeName::Light,
+        SchemeName::Solarized,
+        SchemeName::HighContrast,
+        SchemeName::Custom,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SchemeName::Auto => "auto",
+            SchemeName::Dark => "dark",
+            SchemeName::Light => "light",
+            SchemeName::Solarized => "solarized",
+            SchemeName::HighContrast => "high-contrast",
+            SchemeName::Custom => "custom",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let normalized = name.trim().to_lowercase().replace('_', "-");
+        match normalized.as_str() {
+            "" | "auto" | "default" => Ok(SchemeName::Auto),
+            "dark" => Ok(SchemeName::Dark),
+            "light" => Ok(SchemeName::Light),
+            "solarized" => Ok(SchemeName::Solarized),
+            "high-contrast" | "highcontrast" => Ok(SchemeName::HighContrast),
+            "custom" => Ok(SchemeName::Custom),
+            _ => Err(format!(
+                "Unknown color scheme '{}'. Use one of: {}",
+                name.trim(),
+                scheme_names()
+            )),
+        }
+    }
+}
+
+/// Comma-separated scheme names, for messages and help text.
+pub fn scheme_names() -> String {
+    SchemeName::ALL
+        .iter()
+        .map(|name| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A color slot of the UI, named as in `[settings.theme.colors]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorRole {
+    Text,
+    Muted,
+    AssistantText,
+    Accent,
+    AccentSecondary,
+    Success,
+    Warning,
+    Danger,
+    Border,
+    Title,
+    TitlePrimary,
+    HighlightBg,
+    HighlightFg,
+    SelectionBg,
+    SelectionFg,
+    Cursor,
+    CodeBg,
+    Magenta,
+    MagentaDim,
+    UnselectedBg,
+    Red,
+    Green,
+    Yellow,
+    Orange,
+    DarkGray,
+    DropdownBg,
+    DropdownText,
+    DropdownMuted,
+}
+
+impl ColorRole {
+    pub const ALL: [ColorRole; 28] = [
+        ColorRole::Text,
+        ColorRole::Muted,
+        ColorRole::AssistantText,
+        ColorRole::Accent,
+        ColorRole::AccentSecondary,
+        ColorRole::Success,
+        ColorRole::Warning,
+        ColorRole::Danger,
+        ColorRole::Border,
+        ColorRole::Title,
+        ColorRole::TitlePrimary,
+        ColorRole::HighlightBg,
+        ColorRole::HighlightFg,
+        ColorRole::SelectionBg,
+        ColorRole::SelectionFg,
+        ColorRole::Cursor,
+        ColorRole::CodeBg,
+        ColorRole::Magenta,
+        ColorRole::MagentaDim,
+        ColorRole::UnselectedBg,
+        ColorRole::Red,
+        ColorRole::Green,
+        ColorRole::Yellow,
+        ColorRole::Orange,
+        ColorRole::DarkGray,
+        ColorRole::DropdownBg,
+        ColorRole::DropdownText,
+        ColorRole::DropdownMuted,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorRole::Text => "text",
+            ColorRole::Muted => "muted",
+            ColorRole::AssistantText => "assistant_text",
+            ColorRole::Accent => "accent",
+            ColorRole::AccentSecondary => "accent_secondary",
+            ColorRole::Success => "success",
+            ColorRole::Warning => "warning",
+            ColorRole::Danger => "danger",
+            ColorRole::Border => "border",
+            ColorRole::Title => "title",
+            ColorRole::TitlePrimary => "title_primary",
+            ColorRole::HighlightBg => "highlight_bg",
+            ColorRole::HighlightFg => "highlight_fg",
+            ColorRole::SelectionBg => "selection_bg",
+            ColorRole::SelectionFg => "selection_fg",
+            ColorRole::Cursor => "cursor",
+            ColorRole::CodeBg => "code_bg",
+            ColorRole::Magenta => "magenta",
+            ColorRole::MagentaDim => "magenta_dim",
+            ColorRole::UnselectedBg => "unselected_bg",
+            ColorRole::Red => "red",
+            ColorRole::Green => "green",
+            ColorRole::Yellow => "yellow",
+            ColorRole::Orange => "orange",
+            ColorRole::DarkGray => "dark_gray",
+            ColorRole::DropdownBg => "dropdown_bg",
+            ColorRole::DropdownText => "dropdown_text",
+            ColorRole::DropdownMuted => "dropdown_muted",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        let normalized = name.trim().to_lowercase().replace('-', "_");
+        Self::ALL.into_iter().find(|role| role.name() == normalized)
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The active scheme plus the per-role overrides from config.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorScheme {
+    pub name: SchemeName,
+    overrides: [Option<Color>; ColorRole::ALL.len()],
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self::new(SchemeName::Auto)
+    }
+}
+
+impl ColorScheme {
+    pub const fn new(name: SchemeName) -> Self {
+        Self {
+            name,
+            overrides: [None; ColorRole::ALL.len()],
+        }
+    }
+
+    /// Scheme described by `[settings.theme]`. Unknown scheme or role names
+    /// and unparsable colors are errors, so typos don't go unnoticed.
+    pub fn from_config(config: &ThemeConfig) -> Result<Self, String> {
+        let name = SchemeName::parse(config.scheme.as_deref().unwrap_or_default())?;
+        let mut scheme = Self::new(name);
+        for (role_name, value) in &config.colors {
+            let role = ColorRole::from_name(role_name).ok_or_else(|| {
+                format!(
+                    "Unknown theme color '{}'. Use one of: {}",
+                    role_name,
+                    ColorRole::ALL
+                        .iter()
+                        .map(|role| role.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })?;
+            let color = Color::from_str(value.trim()).map_err(|_| {
+                format!(
+                    "Invalid color '{}' for '{}'. Use #rrggbb, a color name or 0-255",
+                    value, role_name
+                )
+            })?;
+            scheme.overrides[role.index()] = Some(color);
+        }
+        Ok(scheme)
+    }
+
+    /// Same overrides on top of another built-in scheme.
+    pub fn with_name(self, name: SchemeName) -> Self {
+        Self { name, ..self }
+    }
+
+    /// Background the scheme is drawn for, when it fixes one.
+    pub fn light_background(&self) -> Option<bool> {
+        match self.name {
+            SchemeName::Dark => Some(false),
+            SchemeName::Light => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Color for `role` on a light or dark background, or None to use the
+    /// default palette.
+    pub fn color(&self, role: ColorRole, light: bool) -> Option<Color> {
+        self.overrides[role.index()].or_else(|| match self.name {
+            SchemeName::Solarized => Some(solarized(role, light)),
+            SchemeName::HighContrast => high_contrast(role, light),
+            SchemeName::Auto | SchemeName::Dark | SchemeName::Light | SchemeName::Custom => None,
+        })
+    }
+}
+
+static ACTIVE_SCHEME: RwLock<ColorScheme> = RwLock::new(ColorScheme::new(SchemeName::Auto));
+
+/// Copy of the active scheme, so no lock is held while colors are resolved.
+pub fn active() -> ColorScheme {
+    *ACTIVE_SCHEME.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub fn set_active(scheme: ColorScheme) {
+    *ACTIVE_SCHEME
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = scheme;
+}
+
+/// Activate the scheme from config. Called once at startup.
+pub fn init(config: &ThemeConfig) -> Result<(), String> {
+    set_active(ColorScheme::from_config(config)?);
+    Ok(())
+}
+
+/// Switch to another built-in scheme, keeping the configured overrides.
+pub fn switch(name: SchemeName) {
+    set_active(active().with_name(name));
+}
+
+/// Color the active scheme assigns to `role`.
+pub fn color(role: ColorRole) -> Option<Color> {
+    active().color(role, crate::services::detect_term::is_light_mode())
+}
+
+/// Solarized color with the ANSI color a solarized terminal palette maps it
+/// to, for terminals without true color.
+fn solarized(role: ColorRole, light: bool) -> Color {
+    const BASE03: (u8, u8, u8) = (0x00, 0x2b, 0x36);
+    const BASE02: (u8, u8, u8) = (0x07, 0x36, 0x42);
+    const BASE01: (u8, u8, u8) = (0x58, 0x6e, 0x75);
+    const BASE00: (u8, u8, u8) = (0x65, 0x7b, 0x83);
+    const BASE0: (u8, u8, u8) = (0x83, 0x94, 0x96);
+    const BASE1: (u8, u8, u8) = (0x93, 0xa1, 0xa1);
+    const BASE2: (u8, u8, u8) = (0xee, 0xe8, 0xd5);
+    const BASE3: (u8, u8, u8) = (0xfd, 0xf6, 0xe3);
+    const YELLOW: (u8, u8, u8) = (0xb5, 0x89, 0x00);
+    const ORANGE: (u8, u8, u8) = (0xcb, 0x4b, 0x16);
+    const RED: (u8, u8, u8) = (0xdc, 0x32, 0x2f);
+    const MAGENTA: (u8, u8, u8) = (0xd3, 0x36, 0x82);
+    const BLUE: (u8, u8, u8) = (0x26, 0x8b, 0xd2);
+    const CYAN: (u8, u8, u8) = (0x2a, 0xa1, 0x98);
+    const GREEN: (u8, u8, u8) = (0x85, 0x99, 0x00);
+
+    // Content tones swap between the dark and light variants; accents stay
+    let (body, emphasis, subtle, surface, background) = if light {
+        (BASE00, BASE01, BASE1, BASE2, BASE3)
+    } else {
+        (BASE0, BASE1, BASE01, BASE02, BASE03)
+    };
+    let (rgb, ansi) = match role {
+        ColorRole::Text | ColorRole::AssistantText | ColorRole::DropdownText => {
+            (body, Color::Reset)
+        }
+        ColorRole::TitlePrimary | ColorRole::SelectionFg => (emphasis, Color::Reset),
+        ColorRole::Muted | ColorRole::Border | ColorRole::DarkGray | ColorRole::DropdownMuted => {
+            (subtle, Color::DarkGray)
+        }
+        ColorRole::CodeBg
+        | ColorRole::MagentaDim
+        | ColorRole::UnselectedBg
+        | ColorRole::SelectionBg => (surface, Color::Reset),
+        ColorRole::DropdownBg if light => (background, Color::Reset),
+        ColorRole::DropdownBg => return Color::Reset,
+        ColorRole::HighlightFg => (background, Color::Black),
+        ColorRole::Accent | ColorRole::Cursor | ColorRole::HighlightBg => (CYAN, Color::Cyan),
+        ColorRole::AccentSecondary => (BLUE, Color::Blue),
+        ColorRole::Success | ColorRole::Green => (GREEN, Color::Green),
+        ColorRole::Warning | ColorRole::Yellow | ColorRole::Title => (YELLOW, Color::Yellow),
+        ColorRole::Danger | ColorRole::Red => (RED, Color::Red),
+        ColorRole::Orange => (ORANGE, Color::LightRed),
+        ColorRole::Magenta => (MAGENTA, Color::Magenta),
+    };
+    if should_use_rgb_colors() {
+        Color::Rgb(rgb.0, rgb.1, rgb.2)
+    } else {
+        ansi
+    }
+}
+
+/// Maximum-contrast palette: pure black or white text and saturated
+/// accents. Surfaces keep the terminal default.
+fn high_contrast(role: ColorRole, light: bool) -> Option<Color> {
+    let pick = |dark: Color, light_bg: Color| Some(if light { light_bg } else { dark });
+    match role {
+        ColorRole::Text
+        | ColorRole::AssistantText
+        | ColorRole::TitlePrimary
+        | ColorRole::DropdownText
+        | ColorRole::Cursor
+        | ColorRole::HighlightBg
+        | ColorRole::SelectionBg => pick(Color::White, Color::Black),
+        ColorRole::HighlightFg | ColorRole::SelectionFg => pick(Color::Black, Color::White),
+        ColorRole::Muted | ColorRole::Border | ColorRole::DarkGray | ColorRole::DropdownMuted => {
+            pick(Color::Gray, Color::Indexed(236))
+        }
+        ColorRole::Accent => pick(Color::LightCyan, Color::Indexed(24)),
+        ColorRole::AccentSecondary => pick(Color::LightBlue, Color::Blue),
+        ColorRole::Success | ColorRole::Green => pick(Color::LightGreen, Color::Indexed(22)),
+        ColorRole::Warning | ColorRole::Yellow | ColorRole::Title => {
+            pick(Color::LightYellow, Color::Indexed(94))
+        }
+        ColorRole::Danger | ColorRole::Red => pick(Color::LightRed, Color::Indexed(124)),
+        ColorRole::Orange => pick(Color::Indexed(214), Color::Indexed(130)),
+        ColorRole::Magenta => pick(Color::LightMagenta, Color::Indexed(90)),
+        ColorRole::DropdownBg => pick(Color::Black, Color::White),
+        ColorRole::CodeBg | ColorRole::MagentaDim | ColorRole::UnselectedBg => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(scheme: Option<&str>, colors: &[(&str, &str)]) -> ThemeConfig {
+        ThemeConfig {
+            scheme: scheme.map(str::to_string),
+            colors: colors
+                .iter()
+                .map(|(role, color)| (role.to_string(), color.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_scheme_names_round_trip() {
+        for name in SchemeName::ALL {
+            assert_eq!(SchemeName::parse(name.as_str()), Ok(name));
+        }
+        assert_eq!(
+            SchemeName::parse("High_Contrast"),
+            Ok(SchemeName::HighContrast)
+        );
+        assert_eq!(SchemeName::parse(""), Ok(SchemeName::Auto));
+        assert!(
+            SchemeName::parse("monokai")
+                .unwrap_err()
+                .contains("solarized")
+        );
+    }
+
+    #[test]
+    fn test_overrides_win_over_the_scheme_palette() {
+        let scheme = ColorScheme::from_config(&config(
+            Some("solarized"),
+            &[
+                ("accent", "#ff8800"),
+                ("Border", "dark-gray"),
+                ("cursor", "33"),
+            ],
+        ))
+        .unwrap();
+        assert_eq!(scheme.name, SchemeName::Solarized);
+        assert_eq!(
+            scheme.color(ColorRole::Accent, false),
+            Some(Color::Rgb(0xff, 0x88, 0x00))
+        );
+        assert_eq!(scheme.color(ColorRole::Border, true), Some(Color::DarkGray));
+        assert_eq!(
+            scheme.color(ColorRole::Cursor, false),
+            Some(Color::Indexed(33))
+        );
+        // Roles without an override come from the palette
+        assert!(scheme.color(ColorRole::Danger, false).is_some());
+
+        // Overrides survive switching schemes
+        let custom = scheme.with_name(SchemeName::Custom);
+        assert_eq!(
+            custom.color(ColorRole::Accent, false),
+            Some(Color::Rgb(0xff, 0x88, 0x00))
+        );
+        assert_eq!(custom.color(ColorRole::Danger, false), None);
+    }
+
+    #[test]
+    fn test_invalid_config_is_reported() {
+        let err = ColorScheme::from_config(&config(None, &[("acent", "red")])).unwrap_err();
+        assert!(err.contains("Unknown theme color 'acent'"));
+        let err = ColorScheme::from_config(&config(None, &[("accent", "#12345")])).unwrap_err();
+        assert!(err.contains("Invalid color '#12345' for 'accent'"));
+        assert!(ColorScheme::from_config(&config(Some("neon"), &[])).is_err());
+    }
+
+    #[test]
+    fn test_builtin_palettes() {
+        let auto = ColorScheme::default();
+        assert!(
+            ColorRole::ALL
+                .iter()
+                .all(|role| auto.color(*role, false).is_none())
+        );
+        assert_eq!(auto.light_background(), None);
+        assert_eq!(
+            ColorScheme::new(SchemeName::Light).light_background(),
+            Some(true)
+        );
+        assert_eq!(
+            ColorScheme::new(SchemeName::Dark).light_background(),
+            Some(false)
+        );
+
+        let high_contrast = ColorScheme::new(SchemeName::HighContrast);
+        assert_eq!(
+            high_contrast.color(ColorRole::Text, false),
+            Some(Color::White)
+        );
+        assert_eq!(
+            high_contrast.color(ColorRole::Text, true),
+            Some(Color::Black)
+        );
+
+        // Solarized swaps content tones between its dark and light variants
+        let solarized = ColorScheme::new(SchemeName::Solarized);
+        assert_ne!(
+            solarized.color(ColorRole::CodeBg, false),
+            solarized.color(ColorRole::CodeBg, true)
+        );
+        assert_eq!(
+            solarized.color(ColorRole::Accent, false),
+            solarized.color(ColorRole::Accent, true)
+        );
+    }
+}
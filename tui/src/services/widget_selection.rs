@@ -28,7 +28,8 @@
 //! }
 //! ```
 
-use ratatui::style::{Color, Style};
+use crate::services::detect_term::ThemeColors;
+use ratatui::style::Style;
 
 /// Generic selection state for any widget
 #[derive(Debug, Clone, Copy, Default)]
@@ -129,7 +130,9 @@ impl WidgetSelection {
 
     /// Get the selection style for highlighting
     pub fn highlight_style() -> Style {
-        Style::default().fg(Color::White).bg(Color::Blue)
+        Style::default()
+            .fg(ThemeColors::selection_fg())
+            .bg(ThemeColors::selection_bg())
     }
 }
 
@@ -1,7 +1,46 @@
+use crossterm::event::{
+    KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the terminal answered the keyboard enhancement query; asked once.
+static KEYBOARD_ENHANCEMENT_SUPPORTED: OnceLock<bool> = OnceLock::new();
+
+/// Whether keyboard enhancement flags are pushed and must be popped again.
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
+/// Ask the terminal to report keys like `Ctrl+Tab` and `Alt+1` unambiguously.
+///
+/// Only terminals that implement the kitty keyboard protocol support this;
+/// elsewhere it does nothing and those keys may not arrive at all.
+pub fn enable_keyboard_enhancement() {
+    let supported = *KEYBOARD_ENHANCEMENT_SUPPORTED
+        .get_or_init(|| crossterm::terminal::supports_keyboard_enhancement().unwrap_or(false));
+    if supported
+        && crossterm::execute!(
+            std::io::stdout(),
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )
+        .is_ok()
+    {
+        KEYBOARD_ENHANCED.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Undo [`enable_keyboard_enhancement`], before handing the terminal to
+/// another program or exiting.
+pub fn disable_keyboard_enhancement() {
+    if KEYBOARD_ENHANCED.swap(false, Ordering::SeqCst) {
+        let _ = crossterm::execute!(std::io::stdout(), PopKeyboardEnhancementFlags);
+    }
+}
+
 pub struct TerminalGuard;
 
 impl Drop for TerminalGuard {
     fn drop(&mut self) {
+        disable_keyboard_enhancement();
         let _ = crossterm::terminal::disable_raw_mode();
         let _ = crossterm::execute!(std::io::stdout(), crossterm::terminal::LeaveAlternateScreen);
     }
@@ -9,8 +9,10 @@ use crate::services::message::{
 use crate::services::message_pattern::spans_to_string;
 
 use crate::services::banner;
+use crate::services::plan_pane;
 use crate::services::shell_popup;
 use crate::services::side_panel;
+use crate::services::tabs;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -20,16 +22,23 @@ use ratatui::{
 };
 
 pub fn view(f: &mut Frame, state: &mut AppState) {
-    // Full-width banner at the top (height=0 when no active message)
+    // Tab bar (height=0 with a single tab), then a full-width banner
+    // (height=0 when no active message)
+    let tab_bar_h = tabs::tab_bar_height(state);
     let banner_h = banner::banner_height(state);
     let vertical_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(banner_h), Constraint::Min(1)])
+        .constraints([
+            Constraint::Length(tab_bar_h),
+            Constraint::Length(banner_h),
+            Constraint::Min(1),
+        ])
         .split(f.area());
 
-    let banner_area = vertical_chunks[0];
-    let screen_area = vertical_chunks[1];
+    let banner_area = vertical_chunks[1];
+    let screen_area = vertical_chunks[2];
 
+    tabs::render_tab_bar(f, state, vertical_chunks[0]);
     banner::render_banner(f, banner_area, state);
 
     // Store banner area for click detection (None when banner is hidden)
@@ -66,6 +75,21 @@ pub fn view(f: &mut Frame, state: &mut AppState) {
         side_panel::render_side_panel(f, state, panel_area);
     }
 
+    // Split pane: chat on the left, plan tasks on the right
+    let main_area = if let Some(pane_width) = plan_pane::pane_width(state, main_area.width) {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(1), Constraint::Length(pane_width)])
+            .split(main_area);
+        plan_pane::render_plan_pane(f, state, chunks[1]);
+        Rect {
+            width: chunks[0].width.saturating_sub(1),
+            ..chunks[0]
+        }
+    } else {
+        main_area
+    };
+
     // Calculate the required height for the input area based on content
     // Subtract 2 for borders (matching render_multiline_input's content_area.width)
     let input_area_width = main_area.width.saturating_sub(2) as usize;
@@ -289,6 +313,11 @@ pub fn view(f: &mut Frame, state: &mut AppState) {
         crate::services::message_action_popup::render_message_action_popup(f, state);
     }
 
+    // Render prompt history search
+    if state.input_history_state.is_searching {
+        crate::services::input_history::render_history_search(f, state);
+    }
+
     // Render model switcher
     if state.model_switcher_state.is_visible {
         crate::services::model_switcher::render_model_switcher_popup(f, state);
@@ -309,6 +338,11 @@ pub fn view(f: &mut Frame, state: &mut AppState) {
         crate::services::profile_switcher::render_profile_switch_overlay(f, state);
     }
 
+    // Render confirmation dialog for cancelling a run, /clear or discarding answers
+    if state.confirm_state.pending.is_some() {
+        crate::services::confirm::render_confirm_modal(f, state);
+    }
+
     // Render toast notification (highest z-index, always on top)
     render_toast(f, state);
 